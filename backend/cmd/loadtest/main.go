@@ -0,0 +1,374 @@
+// Command loadtest drives concurrent HTTP load at a backend seeded with
+// `omp seed --demo` and compares latency and throughput with a recorded
+// baseline, so performance regressions show up before a release.
+//
+//	loadtest [--duration 30s] [--concurrency 16] [--scenarios search,library,stream] [--baseline file] [--record]
+//
+// Each scenario runs on its own for --duration: search queries
+// /api/v1/search, library pages through /api/v1/library with varying sorts,
+// and stream issues a playback URL and reads the first 256 KiB of the track
+// from object storage. With --record the results replace the baseline;
+// otherwise a scenario whose p95 latency rises, or whose throughput falls, by
+// more than --tolerance fails the run, as does one with more than 1% errors.
+package main
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"math/rand"
+	"net/http"
+	"net/url"
+	"os"
+	"slices"
+	"strings"
+	"sync"
+	"time"
+)
+
+// maxErrorRate is the share of failed requests that fails a run even
+// without a baseline.
+const maxErrorRate = 0.01
+
+// streamRange is how much of each track the stream scenario reads, about
+// what a player buffers before it starts.
+const streamRange = "bytes=0-262143"
+
+// searchTerms match words `omp seed --demo` builds names from.
+var searchTerms = []string{"summer", "echo light", "velvet", "the ghost", "midnight harbor", "rain", "paper", "silver shadow"}
+
+var librarySorts = []string{"added_at", "title", "artist", "album", "duration"}
+
+// Result is one scenario's measurements.
+type Result struct {
+	Requests int     `json:"requests"`
+	Errors   int     `json:"errors"`
+	RPS      float64 `json:"rps"`
+	P50Ms    float64 `json:"p50Ms"`
+	P95Ms    float64 `json:"p95Ms"`
+	P99Ms    float64 `json:"p99Ms"`
+}
+
+// Baseline is the file --record writes and later runs compare against.
+type Baseline struct {
+	Recorded    string            `json:"recorded,omitempty"`
+	Concurrency int               `json:"concurrency,omitempty"`
+	Duration    string            `json:"duration,omitempty"`
+	Scenarios   map[string]Result `json:"scenarios"`
+}
+
+type target struct {
+	server   string
+	token    string
+	client   *http.Client
+	trackIDs []int64
+	total    int
+}
+
+func main() {
+	if err := run(os.Args[1:], os.Stdout); err != nil {
+		fmt.Fprintf(os.Stderr, "loadtest: %v\n", err)
+		os.Exit(1)
+	}
+}
+
+func run(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("loadtest", flag.ContinueOnError)
+	server := flags.String("server", envDefault("OMP_SERVER_URL", "http://localhost:8080"), "backend root URL, without /api/v1")
+	email := flags.String("email", "demo1@example.com", "user to log in as")
+	password := flags.String("password", "demo-password", "password of that user")
+	duration := flags.Duration("duration", 30*time.Second, "how long each scenario runs")
+	concurrency := flags.Int("concurrency", 16, "concurrent clients per scenario")
+	scenarios := flags.String("scenarios", "search,library,stream", "comma-separated scenarios to run")
+	baselinePath := flags.String("baseline", "", "baseline file to compare with, or to write with --record")
+	record := flags.Bool("record", false, "write the results to --baseline instead of comparing")
+	tolerance := flags.Float64("tolerance", 0.25, "allowed p95 latency rise and throughput drop, as a fraction of the baseline")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if *duration <= 0 || *concurrency <= 0 || *tolerance < 0 {
+		return errors.New("--duration and --concurrency must be positive, and --tolerance cannot be negative")
+	}
+	if *record && *baselinePath == "" {
+		return errors.New("--record needs --baseline")
+	}
+	names := strings.Split(*scenarios, ",")
+	for _, name := range names {
+		if !slices.Contains([]string{"search", "library", "stream"}, name) {
+			return fmt.Errorf("unknown scenario %q", name)
+		}
+	}
+
+	ctx := context.Background()
+	t := &target{
+		server: strings.TrimRight(*server, "/"),
+		client: &http.Client{
+			Timeout:   30 * time.Second,
+			Transport: &http.Transport{MaxIdleConnsPerHost: *concurrency},
+		},
+	}
+	if err := t.login(ctx, *email, *password); err != nil {
+		return err
+	}
+	if err := t.loadTracks(ctx); err != nil {
+		return err
+	}
+
+	results := map[string]Result{}
+	fmt.Fprintf(out, "%-8s %9s %7s %9s %9s %9s %9s\n", "scenario", "requests", "errors", "req/s", "p50 ms", "p95 ms", "p99 ms")
+	for _, name := range names {
+		result := measure(ctx, *concurrency, *duration, t.scenario(name))
+		results[name] = result
+		fmt.Fprintf(out, "%-8s %9d %7d %9.1f %9.1f %9.1f %9.1f\n",
+			name, result.Requests, result.Errors, result.RPS, result.P50Ms, result.P95Ms, result.P99Ms)
+	}
+
+	if *record {
+		baseline := Baseline{
+			Recorded:    time.Now().UTC().Format(time.DateOnly),
+			Concurrency: *concurrency,
+			Duration:    duration.String(),
+			Scenarios:   results,
+		}
+		data, err := json.MarshalIndent(baseline, "", "  ")
+		if err != nil {
+			return err
+		}
+		if err := os.WriteFile(*baselinePath, append(data, '\n'), 0o644); err != nil {
+			return err
+		}
+		fmt.Fprintf(out, "recorded baseline in %s\n", *baselinePath)
+		return nil
+	}
+
+	var baseline Baseline
+	if *baselinePath != "" {
+		data, err := os.ReadFile(*baselinePath)
+		if err != nil {
+			return fmt.Errorf("read baseline: %w", err)
+		}
+		if err := json.Unmarshal(data, &baseline); err != nil {
+			return fmt.Errorf("parse baseline %s: %w", *baselinePath, err)
+		}
+	}
+	problems := compare(baseline, results, *tolerance)
+	for _, problem := range problems {
+		fmt.Fprintf(out, "REGRESSION %s\n", problem)
+	}
+	if len(problems) > 0 {
+		return fmt.Errorf("%d performance regressions", len(problems))
+	}
+	return nil
+}
+
+// measure runs op from concurrency goroutines until duration passes.
+func measure(ctx context.Context, concurrency int, duration time.Duration, op func(context.Context, *rand.Rand) error) Result {
+	ctx, cancel := context.WithTimeout(ctx, duration)
+	defer cancel()
+	var mu sync.Mutex
+	var latencies []time.Duration
+	errs := 0
+	var wg sync.WaitGroup
+	start := time.Now()
+	for worker := range concurrency {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			rng := rand.New(rand.NewSource(int64(worker)))
+			var own []time.Duration
+			failed := 0
+			for ctx.Err() == nil {
+				began := time.Now()
+				err := op(ctx, rng)
+				if ctx.Err() != nil {
+					// Requests cut off by the deadline are not the server's.
+					break
+				}
+				if err != nil {
+					failed++
+				}
+				own = append(own, time.Since(began))
+			}
+			mu.Lock()
+			latencies = append(latencies, own...)
+			errs += failed
+			mu.Unlock()
+		}()
+	}
+	wg.Wait()
+	return summarize(latencies, errs, time.Since(start))
+}
+
+func summarize(latencies []time.Duration, errs int, elapsed time.Duration) Result {
+	slices.Sort(latencies)
+	result := Result{Requests: len(latencies), Errors: errs}
+	if elapsed > 0 {
+		result.RPS = float64(len(latencies)) / elapsed.Seconds()
+	}
+	result.P50Ms = percentileMs(latencies, 50)
+	result.P95Ms = percentileMs(latencies, 95)
+	result.P99Ms = percentileMs(latencies, 99)
+	return result
+}
+
+// percentileMs is the nearest-rank percentile p of sorted, in milliseconds.
+func percentileMs(sorted []time.Duration, p int) float64 {
+	if len(sorted) == 0 {
+		return 0
+	}
+	rank := (len(sorted)*p + 99) / 100
+	return float64(sorted[max(rank, 1)-1]) / float64(time.Millisecond)
+}
+
+// compare lists the ways results fall short of the baseline. Scenarios the
+// baseline has not recorded are only checked for errors.
+func compare(baseline Baseline, results map[string]Result, tolerance float64) []string {
+	var problems []string
+	names := make([]string, 0, len(results))
+	for name := range results {
+		names = append(names, name)
+	}
+	slices.Sort(names)
+	for _, name := range names {
+		got := results[name]
+		if got.Requests == 0 || float64(got.Errors) > maxErrorRate*float64(got.Requests) {
+			problems = append(problems, fmt.Sprintf("%s: %d of %d requests failed", name, got.Errors, got.Requests))
+			continue
+		}
+		want, ok := baseline.Scenarios[name]
+		if !ok {
+			continue
+		}
+		if want.P95Ms > 0 && got.P95Ms > want.P95Ms*(1+tolerance) {
+			problems = append(problems, fmt.Sprintf("%s: p95 %.1f ms, baseline %.1f ms", name, got.P95Ms, want.P95Ms))
+		}
+		if got.RPS < want.RPS*(1-tolerance) {
+			problems = append(problems, fmt.Sprintf("%s: %.1f req/s, baseline %.1f req/s", name, got.RPS, want.RPS))
+		}
+	}
+	return problems
+}
+
+func (t *target) scenario(name string) func(context.Context, *rand.Rand) error {
+	switch name {
+	case "search":
+		return func(ctx context.Context, rng *rand.Rand) error {
+			query := url.Values{"q": {searchTerms[rng.Intn(len(searchTerms))]}, "limit": {"20"}}
+			return t.do(ctx, http.MethodGet, "/api/v1/search?"+query.Encode(), nil, nil)
+		}
+	case "library":
+		return func(ctx context.Context, rng *rand.Rand) error {
+			query := url.Values{
+				"limit":  {"50"},
+				"offset": {fmt.Sprint(rng.Intn(max(t.total-49, 1)))},
+				"sort":   {librarySorts[rng.Intn(len(librarySorts))]},
+			}
+			return t.do(ctx, http.MethodGet, "/api/v1/library?"+query.Encode(), nil, nil)
+		}
+	default:
+		return func(ctx context.Context, rng *rand.Rand) error {
+			trackID := t.trackIDs[rng.Intn(len(t.trackIDs))]
+			var resp struct {
+				URLs []struct {
+					URL string `json:"url"`
+				} `json:"urls"`
+			}
+			if err := t.do(ctx, http.MethodPost, "/api/v1/playback/urls", map[string]any{"trackIds": []int64{trackID}}, &resp); err != nil {
+				return err
+			}
+			if len(resp.URLs) == 0 {
+				return fmt.Errorf("no playback URL for track %d", trackID)
+			}
+			req, err := http.NewRequestWithContext(ctx, http.MethodGet, resp.URLs[0].URL, nil)
+			if err != nil {
+				return err
+			}
+			req.Header.Set("Range", streamRange)
+			return t.send(req, nil)
+		}
+	}
+}
+
+func (t *target) login(ctx context.Context, email, password string) error {
+	var resp struct {
+		AccessToken string `json:"accessToken"`
+	}
+	if err := t.do(ctx, http.MethodPost, "/api/v1/auth/login", map[string]string{"email": email, "password": password}, &resp); err != nil {
+		return fmt.Errorf("log in as %s (seed the server with `omp seed --demo`): %w", email, err)
+	}
+	t.token = resp.AccessToken
+	return nil
+}
+
+// loadTracks collects the library's track IDs for the stream scenario.
+func (t *target) loadTracks(ctx context.Context) error {
+	var resp struct {
+		Tracks []struct {
+			ID int64 `json:"id"`
+		} `json:"tracks"`
+		Total int `json:"total"`
+	}
+	if err := t.do(ctx, http.MethodGet, "/api/v1/library?limit=100&fields=id", nil, &resp); err != nil {
+		return fmt.Errorf("list library: %w", err)
+	}
+	if len(resp.Tracks) == 0 {
+		return errors.New("the library is empty; seed the server with `omp seed --demo`")
+	}
+	for _, track := range resp.Tracks {
+		t.trackIDs = append(t.trackIDs, track.ID)
+	}
+	t.total = resp.Total
+	return nil
+}
+
+func (t *target) do(ctx context.Context, method, path string, body, decoded any) error {
+	var reader io.Reader
+	if body != nil {
+		payload, err := json.Marshal(body)
+		if err != nil {
+			return err
+		}
+		reader = bytes.NewReader(payload)
+	}
+	req, err := http.NewRequestWithContext(ctx, method, t.server+path, reader)
+	if err != nil {
+		return err
+	}
+	if body != nil {
+		req.Header.Set("Content-Type", "application/json")
+	}
+	if t.token != "" {
+		req.Header.Set("Authorization", "Bearer "+t.token)
+	}
+	return t.send(req, decoded)
+}
+
+// send reads the whole response, so connections are reused and the timing
+// covers the body, and decodes it into decoded when given.
+func (t *target) send(req *http.Request, decoded any) error {
+	resp, err := t.client.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode < 200 || resp.StatusCode > 299 {
+		_, _ = io.Copy(io.Discard, resp.Body)
+		return fmt.Errorf("%s %s: %s", req.Method, req.URL.Path, resp.Status)
+	}
+	if decoded == nil {
+		_, err = io.Copy(io.Discard, resp.Body)
+		return err
+	}
+	return json.NewDecoder(resp.Body).Decode(decoded)
+}
+
+func envDefault(key, fallback string) string {
+	if value := os.Getenv(key); value != "" {
+		return value
+	}
+	return fallback
+}
@@ -0,0 +1,38 @@
+package main
+
+import (
+	"strings"
+	"testing"
+	"time"
+)
+
+func TestSummarizeUsesNearestRankPercentiles(t *testing.T) {
+	latencies := make([]time.Duration, 0, 100)
+	for ms := 100; ms >= 1; ms-- {
+		latencies = append(latencies, time.Duration(ms)*time.Millisecond)
+	}
+	got := summarize(latencies, 2, 4*time.Second)
+	want := Result{Requests: 100, Errors: 2, RPS: 25, P50Ms: 50, P95Ms: 95, P99Ms: 99}
+	if got != want {
+		t.Fatalf("summarize = %+v, want %+v", got, want)
+	}
+	if got := summarize(nil, 0, time.Second); got.P95Ms != 0 || got.Requests != 0 {
+		t.Fatalf("summarize(nil) = %+v", got)
+	}
+}
+
+func TestCompareFlagsRegressionsBeyondTolerance(t *testing.T) {
+	baseline := Baseline{Scenarios: map[string]Result{
+		"search":  {RPS: 400, P95Ms: 20},
+		"library": {RPS: 300, P95Ms: 30},
+	}}
+	problems := compare(baseline, map[string]Result{
+		"search":  {Requests: 1000, RPS: 380, P95Ms: 24},
+		"library": {Requests: 1000, RPS: 200, P95Ms: 45},
+		"stream":  {Requests: 1000, Errors: 50, RPS: 90, P95Ms: 80},
+	}, 0.25)
+	report := strings.Join(problems, "\n")
+	if len(problems) != 3 || !strings.Contains(report, "library: p95 45.0 ms") || !strings.Contains(report, "library: 200.0 req/s") || !strings.Contains(report, "stream: 50 of 1000") {
+		t.Fatalf("compare = %q", problems)
+	}
+}
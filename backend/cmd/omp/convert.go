@@ -0,0 +1,107 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/formatpolicy"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+const convertUsage = "usage: omp convert [--dry-run] [--policy opus:160] [--keep-lossless=true] [--batch 100]"
+
+func runConvert(args []string, out io.Writer) error {
+	cfg := config.Load()
+	flags := flag.NewFlagSet("convert", flag.ContinueOnError)
+	dryRun := flags.Bool("dry-run", false, "print the estimate without queuing conversions")
+	spec := flags.String("policy", cfg.FormatPolicy, "format policy to estimate, as format or format:kbps (defaults to $FORMAT_POLICY)")
+	keepLossless := flags.Bool("keep-lossless", cfg.FormatPolicyKeepLossless, "leave lossless files as they are")
+	batch := flags.Int("batch", 100, "tracks per conversion job (at most 1000)")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if flags.NArg() != 0 {
+		return errors.New(convertUsage)
+	}
+	if *batch <= 0 || *batch > 1000 {
+		return errors.New("--batch must be between 1 and 1000")
+	}
+	policy, err := formatpolicy.Parse(*spec, *keepLossless)
+	if err != nil {
+		return err
+	}
+	if !policy.Enabled() {
+		return errors.New("no format policy: set FORMAT_POLICY or pass --policy")
+	}
+	if !*dryRun && (*spec != cfg.FormatPolicy || *keepLossless != cfg.FormatPolicyKeepLossless) {
+		// The server converts to its own policy whatever queued the job.
+		return errors.New("--policy and --keep-lossless differing from the server's FORMAT_POLICY only work with --dry-run")
+	}
+
+	database, err := openDatabase()
+	if err != nil {
+		return err
+	}
+	defer database.Close()
+	ctx := context.Background()
+	audio, err := db.NewTrackRepository(database).ListStoredAudio(ctx)
+	if err != nil {
+		return err
+	}
+	estimate := policy.Estimate(audio)
+	writeEstimate(out, policy, len(audio), estimate)
+	if *dryRun || estimate.Tracks == 0 {
+		return nil
+	}
+
+	queue := formatpolicy.NewQueue(jobs.NewStore(database))
+	queued := 0
+	for start := 0; start < len(estimate.TrackIDs); start += *batch {
+		ids := estimate.TrackIDs[start:min(start+*batch, len(estimate.TrackIDs))]
+		_, err := queue.Enqueue(ctx, ids, time.Time{})
+		if errors.Is(err, jobs.ErrDuplicate) {
+			continue
+		}
+		if err != nil {
+			return fmt.Errorf("queue conversion of tracks %d to %d: %w", ids[0], ids[len(ids)-1], err)
+		}
+		queued++
+	}
+	fmt.Fprintf(out, "queued %d conversion jobs; follow them under /api/v1/jobs\n", queued)
+	return nil
+}
+
+func writeEstimate(out io.Writer, policy formatpolicy.Policy, stored int, estimate formatpolicy.Estimate) {
+	fmt.Fprintf(out, "%d of %d stored tracks break format policy %s\n", estimate.Tracks, stored, policy)
+	if estimate.Tracks == 0 {
+		return
+	}
+	fmt.Fprintf(out, "  now:       %s\n", formatBytes(estimate.CurrentBytes))
+	fmt.Fprintf(out, "  converted: about %s\n", formatBytes(estimate.EstimatedBytes))
+	if saved := estimate.SavedBytes(); saved >= 0 {
+		fmt.Fprintf(out, "  saves:     about %s\n", formatBytes(saved))
+	} else {
+		fmt.Fprintf(out, "  costs:     about %s more\n", formatBytes(-saved))
+	}
+}
+
+// formatBytes writes n in the largest binary unit it reaches, such as
+// "4.2 GiB".
+func formatBytes(n int64) string {
+	const unit = 1024
+	if n < unit {
+		return fmt.Sprintf("%d B", n)
+	}
+	value, exp := float64(n)/unit, 0
+	for value >= unit && exp < 4 {
+		value /= unit
+		exp++
+	}
+	return fmt.Sprintf("%.1f %ciB", value, "KMGTP"[exp])
+}
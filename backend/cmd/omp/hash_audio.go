@@ -0,0 +1,61 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/maintenance"
+)
+
+const hashAudioUsage = "usage: omp hash-audio [--dry-run] [--batch 100]"
+
+func runHashAudio(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("hash-audio", flag.ContinueOnError)
+	dryRun := flags.Bool("dry-run", false, "count the unhashed tracks without queuing jobs")
+	batch := flags.Int("batch", 100, "tracks per backfill job (at most 1000)")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if flags.NArg() != 0 {
+		return errors.New(hashAudioUsage)
+	}
+	if *batch <= 0 || *batch > 1000 {
+		return errors.New("--batch must be between 1 and 1000")
+	}
+
+	database, err := openDatabase()
+	if err != nil {
+		return err
+	}
+	defer database.Close()
+	ctx := context.Background()
+	ids, err := db.NewTrackRepository(database).ListUnhashedAudio(ctx)
+	if err != nil {
+		return err
+	}
+	fmt.Fprintf(out, "%d stored tracks have no audio hash\n", len(ids))
+	if *dryRun || len(ids) == 0 {
+		return nil
+	}
+
+	queue := maintenance.NewQueue(jobs.NewStore(database))
+	queued := 0
+	for start := 0; start < len(ids); start += *batch {
+		batchIDs := ids[start:min(start+*batch, len(ids))]
+		_, err := queue.EnqueueAudioHashes(ctx, batchIDs)
+		if errors.Is(err, jobs.ErrDuplicate) {
+			continue
+		}
+		if err != nil {
+			return fmt.Errorf("queue hashing of tracks %d to %d: %w", batchIDs[0], batchIDs[len(batchIDs)-1], err)
+		}
+		queued++
+	}
+	fmt.Fprintf(out, "queued %d audio hash jobs; follow them under /api/v1/jobs\n", queued)
+	return nil
+}
@@ -0,0 +1,285 @@
+// Command omp is the operator CLI for a running Open Music Player backend. It
+// drives maintenance endpoints over HTTP with an access token, so it needs no
+// database or MusicBrainz configuration of its own.
+//
+//	omp enrich [--all] [--stale 90d] [--limit 50] [track-id ...]
+//
+// enrich re-checks MusicBrainz-linked tracks whose match is older than --stale
+// and prints a change report. Without --all it processes a single batch; with
+// --all it keeps requesting batches until no stale tracks remain.
+//
+//	omp doctor [--timeout 10s]
+//
+// doctor is the exception: it loads the server's own environment and checks
+// the configuration, database and schema version, Redis, object storage, the
+// download directory, ffmpeg and yt-dlp, and the external APIs the server
+// calls, then prints a report with a fix for each problem. It exits non-zero
+// when any check fails.
+//
+//	omp tenant create [--max-tracks N] [--max-storage-bytes N] <slug> <name>
+//	omp tenant assign [--admin] <slug> <email>
+//
+// tenant also works on the database directly, with the server's environment.
+// create sets up a household with its own library, storage prefix and
+// optional quotas; assign moves an existing user into it, refusing users
+// whose library already holds tracks outside the tenant.
+//
+//	omp convert [--dry-run] [--policy opus:160] [--keep-lossless=true] [--batch 100]
+//
+// convert works on the database too. It lists the stored tracks that break
+// the server's FORMAT_POLICY and estimates the space converting them saves,
+// then queues background jobs that convert them. With --dry-run it only
+// prints the estimate, for the server's policy or another given by --policy.
+//
+//	omp hash-audio [--dry-run] [--batch 100]
+//
+// hash-audio lists the stored tracks whose audio has not been hashed, such as
+// those stored before downloads were, and queues background jobs that hash
+// them so AUDIO_HASH_MATCH can match later downloads against them.
+//
+//	omp seed --demo [--users 3] [--tracks 200] [--plays 300] [--seed 1]
+//
+// seed fills the database and object storage with a fake library for UI
+// development and load testing: a catalog of generated albums whose audio is
+// silence, and demo users (demo1@example.com onwards) with libraries,
+// favorites, playlists and months of play history. The same --seed gives the
+// same catalog, and running it again reuses the tracks and skips existing
+// users.
+//
+//	omp migrate [--yes] [--backup FILE] [--down-to N] [--dry-run]
+//	omp migrate --status
+//	omp migrate --verify
+//
+// migrate brings the schema to this build's version without starting the
+// server, first asking for a backup unless --backup takes one with pg_dump or
+// --yes skips it. With --down-to it instead undoes the schema changes newer
+// than an older release, before going back to it. Afterwards, or alone with
+// --verify, it checks that no rows were lost and that invariants the
+// constraints do not enforce still hold, and exits non-zero when any fails.
+// --status lists the pending migrations and --dry-run adds their SQL; both
+// exit non-zero when a newer release has migrated the database, which the
+// server refuses to start against.
+package main
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/doctor"
+)
+
+type reverifyRequest struct {
+	TrackIDs       []int64 `json:"trackIds,omitempty"`
+	StaleAfterDays int     `json:"staleAfterDays"`
+	Limit          int     `json:"limit"`
+}
+
+type reverifyResponse struct {
+	Tracks  []reverifyTrack `json:"tracks"`
+	Summary reverifySummary `json:"summary"`
+}
+
+type reverifyTrack struct {
+	TrackID int64         `json:"trackId"`
+	Title   string        `json:"title"`
+	Status  string        `json:"status"`
+	Reason  string        `json:"reason,omitempty"`
+	Changes []fieldChange `json:"changes,omitempty"`
+}
+
+type fieldChange struct {
+	Field  string `json:"field"`
+	Before string `json:"before"`
+	After  string `json:"after"`
+}
+
+type reverifySummary struct {
+	Selected  int `json:"selected"`
+	Updated   int `json:"updated"`
+	Unchanged int `json:"unchanged"`
+	Skipped   int `json:"skipped"`
+	Errors    int `json:"errors"`
+}
+
+func main() {
+	if err := run(os.Args[1:], os.Stdout, &http.Client{}); err != nil {
+		fmt.Fprintf(os.Stderr, "omp: %v\n", err)
+		os.Exit(1)
+	}
+}
+
+func run(args []string, out io.Writer, client *http.Client) error {
+	if len(args) == 0 {
+		return errors.New("usage: omp enrich [--all] [--stale 90d] [--limit 50] [track-id ...] | omp doctor [--timeout 10s] | omp tenant create|assign ... | omp convert [--dry-run] | omp hash-audio [--dry-run] | omp seed --demo | omp migrate [--status|--verify]")
+	}
+	switch args[0] {
+	case "enrich":
+		return runEnrich(args[1:], out, client)
+	case "doctor":
+		return runDoctor(args[1:], out)
+	case "tenant":
+		return runTenant(args[1:], out)
+	case "convert":
+		return runConvert(args[1:], out)
+	case "hash-audio":
+		return runHashAudio(args[1:], out)
+	case "seed":
+		return runSeed(args[1:], out)
+	case "migrate":
+		return runMigrate(args[1:], out)
+	default:
+		return fmt.Errorf("unknown command %q", args[0])
+	}
+}
+
+func runEnrich(args []string, out io.Writer, client *http.Client) error {
+	flags := flag.NewFlagSet("enrich", flag.ContinueOnError)
+	all := flags.Bool("all", false, "keep re-verifying batches until no stale tracks remain")
+	stale := flags.String("stale", "90d", "re-check tracks whose match is older than this (e.g. 90d, 720h)")
+	limit := flags.Int("limit", 50, "tracks per batch (at most 200)")
+	server := flags.String("server", envDefault("OMP_SERVER_URL", "http://localhost:8080"), "backend root URL, without /api/v1")
+	token := flags.String("token", os.Getenv("OMP_TOKEN"), "access token (defaults to $OMP_TOKEN)")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if *token == "" {
+		return errors.New("an access token is required (--token or OMP_TOKEN)")
+	}
+	if *limit <= 0 || *limit > 200 {
+		return errors.New("--limit must be between 1 and 200")
+	}
+	staleDays, err := parseStaleDays(*stale)
+	if err != nil {
+		return err
+	}
+	req := reverifyRequest{StaleAfterDays: staleDays, Limit: *limit}
+	for _, arg := range flags.Args() {
+		id, err := strconv.ParseInt(arg, 10, 64)
+		if err != nil || id <= 0 {
+			return fmt.Errorf("invalid track id %q", arg)
+		}
+		req.TrackIDs = append(req.TrackIDs, id)
+	}
+	if *all && len(req.TrackIDs) > 0 {
+		return errors.New("--all cannot be combined with track ids")
+	}
+
+	endpoint := strings.TrimRight(*server, "/") + "/api/v1/maintenance/reverify"
+	var total reverifySummary
+	for {
+		resp, err := postReverify(client, endpoint, *token, req)
+		if err != nil {
+			return err
+		}
+		writeReport(out, resp.Tracks)
+		total.Selected += resp.Summary.Selected
+		total.Updated += resp.Summary.Updated
+		total.Unchanged += resp.Summary.Unchanged
+		total.Skipped += resp.Summary.Skipped
+		total.Errors += resp.Summary.Errors
+		// Checked tracks leave the stale set whether or not the check failed,
+		// so a short batch means the backlog is drained. Skipped tracks stay,
+		// so a batch that checked nothing would repeat forever.
+		checked := resp.Summary.Updated + resp.Summary.Unchanged + resp.Summary.Errors
+		if !*all || resp.Summary.Selected < *limit || checked == 0 {
+			break
+		}
+	}
+	fmt.Fprintf(out, "re-verified %d tracks: %d updated, %d unchanged, %d skipped, %d failed\n",
+		total.Selected, total.Updated, total.Unchanged, total.Skipped, total.Errors)
+	return nil
+}
+
+func runDoctor(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("doctor", flag.ContinueOnError)
+	timeout := flags.Duration("timeout", doctor.DefaultTimeout, "time limit for each check")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	d := doctor.New(config.Load())
+	defer d.Close()
+	results := doctor.Run(context.Background(), d.Checks(), *timeout)
+	doctor.WriteReport(out, results)
+	if !doctor.Passed(results) {
+		return errors.New("doctor found problems")
+	}
+	return nil
+}
+
+// parseStaleDays accepts a day count such as "90d" or a Go duration such as
+// "720h", rounded down to whole days with a minimum of one.
+func parseStaleDays(value string) (int, error) {
+	if days, ok := strings.CutSuffix(value, "d"); ok {
+		n, err := strconv.Atoi(days)
+		if err != nil || n <= 0 {
+			return 0, fmt.Errorf("invalid --stale %q", value)
+		}
+		return n, nil
+	}
+	d, err := time.ParseDuration(value)
+	if err != nil || d <= 0 {
+		return 0, fmt.Errorf("invalid --stale %q", value)
+	}
+	return max(1, int(d/(24*time.Hour))), nil
+}
+
+func postReverify(client *http.Client, endpoint, token string, body reverifyRequest) (*reverifyResponse, error) {
+	payload, err := json.Marshal(body)
+	if err != nil {
+		return nil, err
+	}
+	req, err := http.NewRequest(http.MethodPost, endpoint, bytes.NewReader(payload))
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Set("Authorization", "Bearer "+token)
+	req.Header.Set("Content-Type", "application/json")
+	resp, err := client.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("reverify request: %w", err)
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		detail, _ := io.ReadAll(io.LimitReader(resp.Body, 4096))
+		return nil, fmt.Errorf("reverify request: %s: %s", resp.Status, strings.TrimSpace(string(detail)))
+	}
+	var decoded reverifyResponse
+	if err := json.NewDecoder(resp.Body).Decode(&decoded); err != nil {
+		return nil, fmt.Errorf("decode reverify response: %w", err)
+	}
+	return &decoded, nil
+}
+
+// writeReport prints the tracks that changed or failed; unchanged and skipped
+// tracks only count toward the summary.
+func writeReport(out io.Writer, tracks []reverifyTrack) {
+	for _, track := range tracks {
+		switch track.Status {
+		case "updated":
+			fmt.Fprintf(out, "track %d %q: updated\n", track.TrackID, track.Title)
+			for _, change := range track.Changes {
+				fmt.Fprintf(out, "  %s: %q -> %q\n", change.Field, change.Before, change.After)
+			}
+		case "failed":
+			fmt.Fprintf(out, "track %d %q: failed: %s\n", track.TrackID, track.Title, track.Reason)
+		}
+	}
+}
+
+func envDefault(key, fallback string) string {
+	if value := os.Getenv(key); value != "" {
+		return value
+	}
+	return fallback
+}
@@ -0,0 +1,187 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"math/rand"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"strconv"
+	"strings"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestParseStaleDays(t *testing.T) {
+	for value, want := range map[string]int{"90d": 90, "720h": 30, "1h": 1} {
+		got, err := parseStaleDays(value)
+		if err != nil || got != want {
+			t.Fatalf("parseStaleDays(%q) = %d, %v; want %d", value, got, err, want)
+		}
+	}
+	for _, value := range []string{"", "0d", "-5d", "soon"} {
+		if _, err := parseStaleDays(value); err == nil {
+			t.Fatalf("parseStaleDays(%q) accepted an invalid value", value)
+		}
+	}
+}
+
+func TestEnrichAllRequestsBatchesUntilDrained(t *testing.T) {
+	var requests []reverifyRequest
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/api/v1/maintenance/reverify" || r.Header.Get("Authorization") != "Bearer secret" {
+			http.Error(w, "unexpected request", http.StatusBadRequest)
+			return
+		}
+		var req reverifyRequest
+		_ = json.NewDecoder(r.Body).Decode(&req)
+		requests = append(requests, req)
+		resp := reverifyResponse{Summary: reverifySummary{Selected: 2, Unchanged: 2}}
+		if len(requests) == 2 {
+			resp = reverifyResponse{
+				Tracks: []reverifyTrack{{
+					TrackID: 7,
+					Title:   "Song",
+					Status:  "updated",
+					Changes: []fieldChange{{Field: "title", Before: "Song", After: "Song (Remastered)"}},
+				}},
+				Summary: reverifySummary{Selected: 1, Updated: 1},
+			}
+		}
+		_ = json.NewEncoder(w).Encode(resp)
+	}))
+	defer server.Close()
+
+	var out strings.Builder
+	err := run([]string{"enrich", "--all", "--stale", "30d", "--limit", "2", "--server", server.URL, "--token", "secret"}, &out, server.Client())
+	if err != nil {
+		t.Fatalf("run: %v", err)
+	}
+	if len(requests) != 2 || requests[0].StaleAfterDays != 30 || requests[0].Limit != 2 {
+		t.Fatalf("requests = %+v, want two 30-day batches of 2", requests)
+	}
+	report := out.String()
+	for _, want := range []string{
+		`track 7 "Song": updated`,
+		`title: "Song" -> "Song (Remastered)"`,
+		"re-verified 3 tracks: 1 updated, 2 unchanged, 0 skipped, 0 failed",
+	} {
+		if !strings.Contains(report, want) {
+			t.Fatalf("report missing %q:\n%s", want, report)
+		}
+	}
+}
+
+func TestTenantCreateValidatesBeforeConnecting(t *testing.T) {
+	for _, args := range [][]string{
+		{"tenant"},
+		{"tenant", "create", "smiths"},
+		{"tenant", "create", "The Smiths", "The Smiths"},
+		{"tenant", "create", "--max-tracks", "-1", "smiths", "The Smiths"},
+		{"tenant", "assign", "smiths"},
+	} {
+		err := run(args, &strings.Builder{}, nil)
+		if err == nil || strings.Contains(err.Error(), "cannot connect") {
+			t.Errorf("run(%q) = %v, want a usage error", args, err)
+		}
+	}
+}
+
+func TestConvertValidatesBeforeConnecting(t *testing.T) {
+	t.Setenv("FORMAT_POLICY", "opus:160")
+	for _, args := range [][]string{
+		{"convert", "extra"},
+		{"convert", "--batch", "0"},
+		{"convert", "--policy", "ogg"},
+		{"convert", "--policy", "original"},
+		{"convert", "--policy", "mp3:320"},
+	} {
+		err := run(args, &strings.Builder{}, nil)
+		if err == nil || strings.Contains(err.Error(), "cannot connect") {
+			t.Errorf("run(%q) = %v, want a usage error", args, err)
+		}
+	}
+}
+
+func TestFormatBytes(t *testing.T) {
+	for n, want := range map[int64]string{512: "512 B", 1536: "1.5 KiB", 5 << 30: "5.0 GiB"} {
+		if got := formatBytes(n); got != want {
+			t.Errorf("formatBytes(%d) = %q, want %q", n, got, want)
+		}
+	}
+}
+
+func TestSeedValidatesBeforeConnecting(t *testing.T) {
+	for _, args := range [][]string{
+		{"seed"},
+		{"seed", "--demo", "extra"},
+		{"seed", "--demo", "--tracks", "0"},
+		{"seed", "--demo", "--password", "short"},
+	} {
+		err := run(args, &strings.Builder{}, nil)
+		if err == nil || strings.Contains(err.Error(), "cannot connect") {
+			t.Errorf("run(%q) = %v, want a usage error", args, err)
+		}
+	}
+}
+
+func TestGenerateDemoTracksIsRepeatable(t *testing.T) {
+	first := generateDemoTracks(rand.New(rand.NewSource(7)), 40)
+	second := generateDemoTracks(rand.New(rand.NewSource(7)), 40)
+	if len(first) != 40 || !reflect.DeepEqual(first, second) {
+		t.Fatalf("generateDemoTracks gave %d tracks, repeatable %v", len(first), reflect.DeepEqual(first, second))
+	}
+	for _, track := range first {
+		if track.Artist == "" || track.Album == "" || track.Title == "" || track.DurationMs%(demoDurationStep*1000) != 0 {
+			t.Fatalf("generated track %+v", track)
+		}
+	}
+
+	wav := silentWAV(3)
+	if len(wav) != 44+3*demoSampleRate || string(wav[:4]) != "RIFF" || string(wav[36:40]) != "data" || wav[len(wav)-1] != 0x80 {
+		t.Fatalf("silentWAV(3) = %d bytes, header %q", len(wav), wav[:44])
+	}
+}
+
+func TestMigrateValidatesBeforeConnecting(t *testing.T) {
+	for _, args := range [][]string{
+		{"migrate", "extra"},
+		{"migrate", "--verify", "--yes"},
+		{"migrate", "--verify", "--down-to", "50"},
+		{"migrate", "--down-to", "3"},
+		{"migrate", "--down-to", strconv.Itoa(db.SchemaVersion)},
+		{"migrate", "--status", "--dry-run"},
+		{"migrate", "--dry-run", "--yes"},
+	} {
+		err := run(args, &strings.Builder{}, nil)
+		if err == nil || strings.Contains(err.Error(), "cannot connect") {
+			t.Errorf("run(%q) = %v, want a usage error", args, err)
+		}
+	}
+}
+
+func TestPrintMigrationPlan(t *testing.T) {
+	var out strings.Builder
+	if err := printMigrationPlan(&out, db.SchemaVersion-2, db.SchemaVersion, false); err != nil {
+		t.Fatal(err)
+	}
+	pending := fmt.Sprintf("pending migrations:\n  %3d  ", db.SchemaVersion-1)
+	if !strings.Contains(out.String(), pending) || strings.Count(out.String(), "\n  ") != 2 || strings.Contains(out.String(), "CREATE") {
+		t.Fatalf("status output = %q", out.String())
+	}
+
+	out.Reset()
+	if err := printMigrationPlan(&out, db.SchemaVersion, db.SchemaVersion-1, true); err != nil {
+		t.Fatal(err)
+	}
+	undo := fmt.Sprintf("migrations to undo, newest first:\n  %3d  ", db.SchemaVersion)
+	if !strings.Contains(out.String(), undo) || !strings.Contains(out.String(), "DROP") || !strings.Contains(out.String(), "nothing was changed") {
+		t.Fatalf("dry-run output = %q", out.String())
+	}
+
+	if err := printMigrationPlan(&strings.Builder{}, db.SchemaVersion+1, db.SchemaVersion, false); err == nil {
+		t.Fatal("printMigrationPlan accepted a database newer than the build")
+	}
+}
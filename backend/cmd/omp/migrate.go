@@ -0,0 +1,228 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"slices"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/doctor"
+)
+
+const migrateUsage = "usage: omp migrate [--yes] [--backup FILE] [--down-to N] [--dry-run] | omp migrate --status | omp migrate --verify"
+
+func runMigrate(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("migrate", flag.ContinueOnError)
+	verify := flags.Bool("verify", false, "only run the data checks, changing nothing")
+	status := flags.Bool("status", false, "only show the schema versions and the pending migrations")
+	dryRun := flags.Bool("dry-run", false, "show the migrations and their SQL without running them")
+	downTo := flags.Int("down-to", 0, "undo schema changes newer than this version, before going back to its release")
+	backup := flags.String("backup", "", "pg_dump the database to this file before changing the schema")
+	yes := flags.Bool("yes", false, "change the schema without a backup and without asking")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	changes := *downTo != 0 || *backup != "" || *yes
+	if flags.NArg() != 0 || *verify && (changes || *status || *dryRun) || *status && (changes || *dryRun) || *dryRun && (*backup != "" || *yes) {
+		return errors.New(migrateUsage)
+	}
+	if *downTo != 0 && (*downTo < db.OldestReversibleVersion || *downTo >= db.SchemaVersion) {
+		return fmt.Errorf("--down-to must be between %d and %d", db.OldestReversibleVersion, db.SchemaVersion-1)
+	}
+
+	database, err := openDatabase()
+	if err != nil {
+		return err
+	}
+	defer database.Close()
+	ctx := context.Background()
+	version, err := database.MigratedVersion(ctx)
+	if err != nil {
+		return fmt.Errorf("read schema version: %w", err)
+	}
+
+	target := db.SchemaVersion
+	if *downTo != 0 {
+		target = *downTo
+	}
+	if *status || *dryRun {
+		return printMigrationPlan(out, version, target, *dryRun)
+	}
+	if *verify || version == target {
+		if !*verify {
+			fmt.Fprintf(out, "schema is already at version %d\n", version)
+		}
+		return reportDataChecks(ctx, out, database, target, nil)
+	}
+	if version > db.SchemaVersion {
+		return fmt.Errorf("schema is at version %d, newer than this build's %d; use the omp of the release that migrated it", version, db.SchemaVersion)
+	}
+
+	fmt.Fprintf(out, "schema is at version %d and will be migrated to %d\n", version, target)
+	if *backup != "" {
+		if err := dumpDatabase(ctx, *backup); err != nil {
+			return err
+		}
+		fmt.Fprintf(out, "backed up the database to %s; restore it with pg_restore --clean --dbname <database> %s\n", *backup, *backup)
+	} else if !*yes {
+		fmt.Fprintln(out, "Take a backup first: pg_dump --format=custom --file omp-backup.dump <database>, or rerun with --backup FILE.")
+		fmt.Fprint(out, "Continue without a backup? [y/N] ")
+		answer, _ := bufio.NewReader(os.Stdin).ReadString('\n')
+		if answer = strings.ToLower(strings.TrimSpace(answer)); answer != "y" && answer != "yes" {
+			return errors.New("migration cancelled")
+		}
+	}
+
+	before, err := database.RowCounts(ctx)
+	if err != nil {
+		return err
+	}
+	if *downTo != 0 {
+		err = database.MigrateDown(ctx, target)
+	} else {
+		err = database.Migrate()
+	}
+	if err != nil {
+		return fmt.Errorf("migrate: %w", err)
+	}
+	fmt.Fprintf(out, "migrated the schema to version %d\n", target)
+	return reportDataChecks(ctx, out, database, target, before)
+}
+
+// printMigrationPlan shows the schema versions and the migrations moving to
+// target applies or undoes, with their SQL when withSQL is set.
+func printMigrationPlan(out io.Writer, version, target int, withSQL bool) error {
+	if version == 0 {
+		fmt.Fprintln(out, "database schema: not recorded (new, or migrated by a release that did not record it)")
+	} else {
+		fmt.Fprintf(out, "database schema: version %d\n", version)
+	}
+	fmt.Fprintf(out, "this build:      version %d\n", db.SchemaVersion)
+	if version > db.SchemaVersion {
+		return fmt.Errorf("the database was migrated by a newer release, so this build's server refuses to start; run that release, or first its `omp migrate --down-to %d`", db.SchemaVersion)
+	}
+
+	undo := target < version
+	from := version
+	if undo {
+		from = target
+	}
+	migrations, err := db.PendingMigrations(from)
+	if err != nil {
+		return err
+	}
+	if undo {
+		migrations = slices.DeleteFunc(migrations, func(m db.Migration) bool { return m.Version > version })
+		slices.Reverse(migrations)
+	}
+	switch {
+	case len(migrations) == 0:
+		fmt.Fprintln(out, "no pending migrations")
+		return nil
+	case undo:
+		fmt.Fprintln(out, "migrations to undo, newest first:")
+	default:
+		fmt.Fprintln(out, "pending migrations:")
+	}
+	for _, m := range migrations {
+		fmt.Fprintf(out, "  %3d  %s\n", m.Version, m.Name)
+	}
+	if !withSQL {
+		return nil
+	}
+
+	for _, m := range migrations {
+		script, err := db.MigrationScript(m.Version)
+		if undo {
+			script, err = db.DownMigrationScript(m.Version)
+		}
+		if err != nil {
+			return err
+		}
+		fmt.Fprintf(out, "\n-- %d %s\n%s", m.Version, m.Name, script)
+	}
+	if !undo {
+		fmt.Fprintln(out, "\n-- The server's startup migration applies the equivalent of these, skipping what is already in place.")
+	}
+	fmt.Fprintln(out, "dry run: nothing was changed")
+	return nil
+}
+
+// dumpDatabase writes a pg_dump archive of the server's database to file.
+func dumpDatabase(ctx context.Context, file string) error {
+	if _, err := exec.LookPath("pg_dump"); err != nil {
+		return errors.New("--backup needs pg_dump on PATH; install the PostgreSQL client tools or back up another way")
+	}
+	cfg := config.Load()
+	cmd := exec.CommandContext(ctx, "pg_dump", "--format=custom", "--file", file)
+	cmd.Env = append(os.Environ(),
+		"PGHOST="+cfg.DBHost, "PGPORT="+cfg.DBPort, "PGUSER="+cfg.DBUser,
+		"PGPASSWORD="+cfg.DBPassword, "PGDATABASE="+cfg.DBName)
+	if output, err := cmd.CombinedOutput(); err != nil {
+		return fmt.Errorf("pg_dump: %w: %s", err, strings.TrimSpace(string(output)))
+	}
+	return nil
+}
+
+// reportDataChecks prints the schema version, the row counts compared with
+// before when given, and each of db.DataChecks, failing when any check does.
+func reportDataChecks(ctx context.Context, out io.Writer, database *db.DB, wantVersion int, before map[string]int64) error {
+	const restore = "restore the backup taken before migrating"
+	var results []doctor.Result
+
+	version, err := database.MigratedVersion(ctx)
+	switch {
+	case err != nil:
+		results = append(results, doctor.Result{Name: "schema-version", Status: doctor.StatusFail, Detail: err.Error(), Fix: "check that DB_USER can read DB_NAME"})
+	case version != wantVersion:
+		results = append(results, doctor.Result{Name: "schema-version", Status: doctor.StatusFail,
+			Detail: fmt.Sprintf("schema is at version %d, expected %d", version, wantVersion), Fix: "run omp migrate"})
+	default:
+		results = append(results, doctor.Result{Name: "schema-version", Status: doctor.StatusOK, Detail: fmt.Sprintf("at version %d", version)})
+	}
+
+	after, err := database.RowCounts(ctx)
+	if err != nil {
+		results = append(results, doctor.Result{Name: "row-counts", Status: doctor.StatusFail, Detail: err.Error(), Fix: restore})
+	} else if shrunk := db.ShrunkTables(before, after); len(shrunk) > 0 {
+		var lost []string
+		for _, table := range shrunk {
+			lost = append(lost, fmt.Sprintf("%s %d -> %d", table, before[table], after[table]))
+		}
+		results = append(results, doctor.Result{Name: "row-counts", Status: doctor.StatusFail, Detail: "rows lost: " + strings.Join(lost, ", "), Fix: restore})
+	} else {
+		var counts []string
+		for _, table := range db.CountedTables {
+			counts = append(counts, fmt.Sprintf("%s %d", table, after[table]))
+		}
+		results = append(results, doctor.Result{Name: "row-counts", Status: doctor.StatusOK, Detail: strings.Join(counts, ", ")})
+	}
+
+	for _, check := range db.DataChecks {
+		result := doctor.Result{Name: check.Name, Status: doctor.StatusOK, Detail: "no rows break it"}
+		count, err := database.CountViolations(ctx, check)
+		switch {
+		case err != nil:
+			result = doctor.Result{Name: check.Name, Status: doctor.StatusFail, Detail: err.Error(), Fix: restore}
+		case count > 0 && check.Warn:
+			result = doctor.Result{Name: check.Name, Status: doctor.StatusWarn, Detail: fmt.Sprintf("%d rows break it", count), Fix: check.Fix}
+		case count > 0:
+			result = doctor.Result{Name: check.Name, Status: doctor.StatusFail, Detail: fmt.Sprintf("%d rows break it", count), Fix: check.Fix}
+		}
+		results = append(results, result)
+	}
+
+	doctor.WriteReport(out, results)
+	if !doctor.Passed(results) {
+		return errors.New("data checks found problems")
+	}
+	return nil
+}
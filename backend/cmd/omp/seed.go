@@ -0,0 +1,268 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"database/sql"
+	"encoding/binary"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"math/rand"
+	"time"
+
+	"github.com/google/uuid"
+	"golang.org/x/crypto/bcrypt"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+const seedUsage = "usage: omp seed --demo [--users 3] [--tracks 200] [--plays 300] [--seed 1] [--password demo-password]"
+
+// demoSampleRate is low enough that a five-minute silent WAV stays under
+// 2.5 MB while remaining a file every player decodes.
+const demoSampleRate = 8000
+
+// demoDurationStep groups track lengths so tracks share a handful of stored
+// silent files instead of one each.
+const demoDurationStep = 15
+
+var (
+	demoArtistFirst = []string{"Velvet", "Paper", "Northern", "Glass", "Hollow", "Electric", "Quiet", "Golden", "Midnight", "Lunar", "Copper", "Wild"}
+	demoArtistLast  = []string{"Harbor", "Foxes", "Lanterns", "Static", "Orchard", "Tides", "Parade", "Engines", "Satellites", "Choir", "Rivers", "Atlas"}
+	demoTitleWords  = []string{"Summer", "Echo", "Fading", "Light", "Rain", "Heart", "City", "Signal", "Weather", "Window", "Ocean", "Morning", "Ghost", "Highway", "Silver", "Shadow", "Paper", "Fire", "Garden", "Distance"}
+	demoAlbumWords  = []string{"Sessions", "Stories", "Years", "Rooms", "Maps", "Songs", "Hours", "Letters"}
+	demoPlaylists   = []string{"Morning Coffee", "Late Night Drive", "Deep Focus", "Workout", "Rainy Day", "Road Trip", "Sunday Slow"}
+)
+
+// demoTrack is one generated track, before it is stored.
+type demoTrack struct {
+	Artist     string
+	Album      string
+	Title      string
+	DurationMs int
+}
+
+// generateDemoTracks invents n tracks grouped into albums of six to twelve by
+// artists with one to three albums each. The same rng seed gives the same
+// catalog.
+func generateDemoTracks(rng *rand.Rand, n int) []demoTrack {
+	tracks := make([]demoTrack, 0, n)
+	for len(tracks) < n {
+		artist := demoArtistFirst[rng.Intn(len(demoArtistFirst))] + " " + demoArtistLast[rng.Intn(len(demoArtistLast))]
+		for albums := 1 + rng.Intn(3); albums > 0 && len(tracks) < n; albums-- {
+			album := demoTitleWords[rng.Intn(len(demoTitleWords))] + " " + demoAlbumWords[rng.Intn(len(demoAlbumWords))]
+			for i := 6 + rng.Intn(7); i > 0 && len(tracks) < n; i-- {
+				title := demoTitleWords[rng.Intn(len(demoTitleWords))] + " " + demoTitleWords[rng.Intn(len(demoTitleWords))]
+				if rng.Intn(4) == 0 {
+					title = "The " + title
+				}
+				seconds := 120 + demoDurationStep*rng.Intn(15)
+				tracks = append(tracks, demoTrack{Artist: artist, Album: album, Title: title, DurationMs: seconds * 1000})
+			}
+		}
+	}
+	return tracks
+}
+
+// silentWAV returns a mono 8-bit PCM WAV file of the given length holding
+// nothing but silence.
+func silentWAV(seconds int) []byte {
+	dataSize := seconds * demoSampleRate
+	wav := make([]byte, 0, 44+dataSize)
+	wav = append(wav, "RIFF"...)
+	wav = binary.LittleEndian.AppendUint32(wav, uint32(36+dataSize))
+	wav = append(wav, "WAVEfmt "...)
+	wav = binary.LittleEndian.AppendUint32(wav, 16)             // fmt chunk size
+	wav = binary.LittleEndian.AppendUint16(wav, 1)              // PCM
+	wav = binary.LittleEndian.AppendUint16(wav, 1)              // channels
+	wav = binary.LittleEndian.AppendUint32(wav, demoSampleRate) // sample rate
+	wav = binary.LittleEndian.AppendUint32(wav, demoSampleRate) // bytes per second
+	wav = binary.LittleEndian.AppendUint16(wav, 1)              // bytes per sample
+	wav = binary.LittleEndian.AppendUint16(wav, 8)              // bits per sample
+	wav = append(wav, "data"...)
+	wav = binary.LittleEndian.AppendUint32(wav, uint32(dataSize))
+	// Unsigned 8-bit samples are silent at their midpoint, not at zero.
+	return append(wav, bytes.Repeat([]byte{0x80}, dataSize)...)
+}
+
+func runSeed(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("seed", flag.ContinueOnError)
+	demo := flags.Bool("demo", false, "generate a fake library for development and load testing")
+	users := flags.Int("users", 3, "demo users to create, demo1@example.com onwards")
+	trackCount := flags.Int("tracks", 200, "tracks in the shared catalog")
+	plays := flags.Int("plays", 300, "plays in each user's history, spread over the last 90 days")
+	seed := flags.Int64("seed", 1, "random seed; the same seed generates the same catalog")
+	password := flags.String("password", "demo-password", "password for every demo user")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if !*demo || flags.NArg() != 0 {
+		return errors.New(seedUsage)
+	}
+	if *users < 0 || *trackCount <= 0 || *plays < 0 {
+		return errors.New("--tracks must be positive, and --users and --plays cannot be negative")
+	}
+	if len(*password) < 8 {
+		return errors.New("--password must be at least 8 characters")
+	}
+
+	cfg := config.Load()
+	store, err := storage.New(&storage.Config{
+		Endpoint:  cfg.MinioEndpoint,
+		Region:    cfg.S3Region,
+		AccessKey: cfg.MinioAccessKey,
+		SecretKey: cfg.MinioSecretKey,
+		Bucket:    cfg.MinioBucket,
+		UseSSL:    cfg.MinioUseSSL,
+	})
+	if err != nil {
+		return fmt.Errorf("object storage: %w", err)
+	}
+	database, err := openDatabase()
+	if err != nil {
+		return err
+	}
+	defer database.Close()
+
+	ctx := context.Background()
+	rng := rand.New(rand.NewSource(*seed))
+	trackIDs, err := seedDemoTracks(ctx, out, database, store, generateDemoTracks(rng, *trackCount))
+	if err != nil {
+		return err
+	}
+	history := db.PlaylistHistoryPolicy{
+		MaxAge:       time.Duration(cfg.PlaylistHistoryRetentionDays) * 24 * time.Hour,
+		MaxRevisions: cfg.PlaylistHistoryMaxRevisions,
+	}
+	hash, err := bcrypt.GenerateFromPassword([]byte(*password), auth.BcryptCost)
+	if err != nil {
+		return err
+	}
+	for i := 1; i <= *users; i++ {
+		if err := seedDemoUser(ctx, out, database, history, rng, i, string(hash), trackIDs, *plays); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// seedDemoTracks uploads one silent file per track length and stores the
+// catalog, returning the track IDs. Tracks already stored by an earlier run
+// are reused.
+func seedDemoTracks(ctx context.Context, out io.Writer, database *db.DB, store *storage.Client, tracks []demoTrack) ([]int64, error) {
+	uploaded := map[int]int64{}
+	repo := db.NewTrackRepository(database)
+	ids := make([]int64, 0, len(tracks))
+	created := 0
+	for _, t := range tracks {
+		seconds := t.DurationMs / 1000
+		key := fmt.Sprintf("demo/silence-%ds.wav", seconds)
+		size, ok := uploaded[seconds]
+		if !ok {
+			audio := silentWAV(seconds)
+			if err := store.PutObject(ctx, key, bytes.NewReader(audio), int64(len(audio)), "audio/wav"); err != nil {
+				return nil, fmt.Errorf("upload %s: %w", key, err)
+			}
+			size = int64(len(audio))
+			uploaded[seconds] = size
+		}
+		track, isNew, err := repo.CreateTrackFromMetadata(ctx, t.Artist, t.Title, t.Album, t.DurationMs,
+			db.WithSource("", "demo"),
+			db.WithStorage(key, size),
+			db.WithAudioQuality("pcm_u8", demoSampleRate*8/1000, demoSampleRate, 1, "audio/wav"))
+		if err != nil {
+			return nil, fmt.Errorf("track %q by %s: %w", t.Title, t.Artist, err)
+		}
+		if isNew {
+			created++
+		}
+		ids = append(ids, track.ID)
+	}
+	fmt.Fprintf(out, "catalog: %d tracks (%d new), %d silent files under demo/\n", len(ids), created, len(uploaded))
+	return ids, nil
+}
+
+// seedDemoUser creates demo user n with a library drawn from the catalog, a
+// few favorites and playlists, and a play history that favors some tracks
+// over others. A user left by an earlier run is skipped.
+func seedDemoUser(ctx context.Context, out io.Writer, database *db.DB, history db.PlaylistHistoryPolicy, rng *rand.Rand, n int, passwordHash string, catalog []int64, plays int) error {
+	now := time.Now()
+	user := &db.User{
+		ID:           uuid.New(),
+		Email:        fmt.Sprintf("demo%d@example.com", n),
+		Username:     fmt.Sprintf("demo%d", n),
+		PasswordHash: passwordHash,
+		CreatedAt:    now,
+		UpdatedAt:    now,
+	}
+
+	// The user, library, favorites and playlists land together, so a failed
+	// run leaves no half-seeded user behind for the next one to skip.
+	owned := make([]int64, 0, len(catalog))
+	names := rng.Perm(len(demoPlaylists))[:2+rng.Intn(3)]
+	err := database.WithTx(ctx, history, func(tx *db.Tx) error {
+		if err := tx.CreateUser(ctx, user); err != nil {
+			return err
+		}
+		for _, i := range rng.Perm(len(catalog))[:max(1, len(catalog)*(50+rng.Intn(40))/100)] {
+			if _, err := tx.AddTrackToLibrary(ctx, user.ID, catalog[i]); err != nil {
+				return fmt.Errorf("library: %w", err)
+			}
+			owned = append(owned, catalog[i])
+		}
+		for _, trackID := range owned[:len(owned)/10] {
+			if err := tx.AddFavorite(ctx, user.ID, trackID, "", ""); err != nil {
+				return fmt.Errorf("favorites: %w", err)
+			}
+		}
+		for _, i := range names {
+			playlist := &db.Playlist{
+				UserID:      user.ID,
+				Name:        demoPlaylists[i],
+				Description: sql.NullString{String: "Generated by omp seed --demo", Valid: true},
+				IsPublic:    rng.Intn(3) == 0,
+			}
+			if err := tx.CreatePlaylist(ctx, playlist); err != nil {
+				return fmt.Errorf("playlist %q: %w", playlist.Name, err)
+			}
+			var trackIDs []int64
+			for _, j := range rng.Perm(len(owned))[:min(len(owned), 10+rng.Intn(16))] {
+				trackIDs = append(trackIDs, owned[j])
+			}
+			if _, err := tx.AddTracksToPlaylist(ctx, playlist.ID, trackIDs); err != nil {
+				return fmt.Errorf("playlist %q: %w", playlist.Name, err)
+			}
+		}
+		return nil
+	})
+	if errors.Is(err, db.ErrEmailExists) {
+		fmt.Fprintf(out, "%s: already exists, skipped\n", user.Email)
+		return nil
+	} else if err != nil {
+		return fmt.Errorf("%s: %w", user.Email, err)
+	}
+
+	// Squaring the draw keeps returning to the front of the shuffled library,
+	// so top-tracks and recommendations have favorites to find.
+	events := make([]db.ClientPlayEvent, plays)
+	for i := range events {
+		pick := rng.Float64()
+		events[i] = db.ClientPlayEvent{
+			EventID:    uuid.New(),
+			Kind:       db.PlayEventKindPlay,
+			TrackID:    owned[int(pick*pick*float64(len(owned)))],
+			OccurredAt: now.Add(-time.Duration(rng.Int63n(int64(90 * 24 * time.Hour)))),
+		}
+	}
+	if _, err := db.NewPlayEventRepository(database).RecordClientEvents(ctx, user.ID, events); err != nil {
+		return fmt.Errorf("%s plays: %w", user.Email, err)
+	}
+	fmt.Fprintf(out, "%s: %d library tracks, %d favorites, %d playlists, %d plays\n",
+		user.Email, len(owned), len(owned)/10, len(names), plays)
+	return nil
+}
@@ -0,0 +1,120 @@
+package main
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"regexp"
+
+	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const tenantUsage = "usage: omp tenant create [--max-tracks N] [--max-storage-bytes N] <slug> <name> | omp tenant assign [--admin] <slug> <email>"
+
+var tenantSlugPattern = regexp.MustCompile(`^[a-z0-9][a-z0-9-]{0,63}$`)
+
+func runTenant(args []string, out io.Writer) error {
+	if len(args) == 0 {
+		return errors.New(tenantUsage)
+	}
+	switch args[0] {
+	case "create":
+		return runTenantCreate(args[1:], out)
+	case "assign":
+		return runTenantAssign(args[1:], out)
+	default:
+		return fmt.Errorf("unknown tenant command %q", args[0])
+	}
+}
+
+func runTenantCreate(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("tenant create", flag.ContinueOnError)
+	maxTracks := flags.Int64("max-tracks", 0, "most tracks the tenant may store (0 for no limit)")
+	maxBytes := flags.Int64("max-storage-bytes", 0, "most bytes of audio the tenant may store (0 for no limit)")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if flags.NArg() != 2 {
+		return errors.New(tenantUsage)
+	}
+	slug := flags.Arg(0)
+	if !tenantSlugPattern.MatchString(slug) {
+		return fmt.Errorf("invalid slug %q: use lowercase letters, digits and dashes", slug)
+	}
+	if *maxTracks < 0 || *maxBytes < 0 {
+		return errors.New("quotas cannot be negative")
+	}
+	tenant := &db.Tenant{
+		Slug:            slug,
+		Name:            flags.Arg(1),
+		MaxTracks:       sql.NullInt64{Int64: *maxTracks, Valid: *maxTracks > 0},
+		MaxStorageBytes: sql.NullInt64{Int64: *maxBytes, Valid: *maxBytes > 0},
+	}
+
+	database, err := openDatabase()
+	if err != nil {
+		return err
+	}
+	defer database.Close()
+	if err := db.NewTenantRepository(database).Create(context.Background(), tenant); err != nil {
+		return err
+	}
+	fmt.Fprintf(out, "created tenant %s (%s), storing audio under %s/\n", tenant.Slug, tenant.Name, tenant.StoragePrefix)
+	return nil
+}
+
+func runTenantAssign(args []string, out io.Writer) error {
+	flags := flag.NewFlagSet("tenant assign", flag.ContinueOnError)
+	admin := flags.Bool("admin", false, "make the user an admin of the tenant")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if flags.NArg() != 2 {
+		return errors.New(tenantUsage)
+	}
+
+	database, err := openDatabase()
+	if err != nil {
+		return err
+	}
+	defer database.Close()
+	ctx := context.Background()
+	tenants := db.NewTenantRepository(database)
+	tenant, err := tenants.GetBySlug(ctx, flags.Arg(0))
+	if err != nil {
+		return fmt.Errorf("tenant %q: %w", flags.Arg(0), err)
+	}
+	userID, err := tenants.AssignUser(ctx, tenant.ID, flags.Arg(1), *admin)
+	if errors.Is(err, db.ErrTenantLibraryConflict) {
+		return fmt.Errorf("%s: %w; remove those tracks from their library first", flags.Arg(1), err)
+	}
+	if err != nil {
+		return fmt.Errorf("%s: %w", flags.Arg(1), err)
+	}
+	role := "member"
+	if *admin {
+		role = "admin"
+	}
+	fmt.Fprintf(out, "assigned %s (%s) to tenant %s as %s\n", flags.Arg(1), userID, tenant.Slug, role)
+	return nil
+}
+
+// openDatabase connects with the server's own environment, as doctor does.
+func openDatabase() (*db.DB, error) {
+	cfg := config.Load()
+	database, err := db.NewWithConfig(db.ConnConfig{
+		Host:     cfg.DBHost,
+		Port:     cfg.DBPort,
+		User:     cfg.DBUser,
+		Password: cfg.DBPassword,
+		Name:     cfg.DBName,
+	}, db.PoolConfig{MaxOpenConns: 2, ConnectTimeout: cfg.DBConnectTimeout})
+	if err != nil {
+		return nil, fmt.Errorf("cannot connect to %s:%s/%s: %w", cfg.DBHost, cfg.DBPort, cfg.DBName, err)
+	}
+	return database, nil
+}
@@ -2,37 +2,64 @@ package main
 
 import (
 	"context"
+	"errors"
+	"flag"
 	"fmt"
 	"net/http"
 	"os"
 	"os/signal"
+	"strconv"
 	"sync"
 	"syscall"
 	"time"
 
 	"github.com/redis/go-redis/v9"
 
+	"github.com/openmusicplayer/backend/internal/activitypub"
 	"github.com/openmusicplayer/backend/internal/aiassist"
 	"github.com/openmusicplayer/backend/internal/analyzer"
 	"github.com/openmusicplayer/backend/internal/api"
+	"github.com/openmusicplayer/backend/internal/artistinfo"
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/cache"
+	"github.com/openmusicplayer/backend/internal/certs"
+	"github.com/openmusicplayer/backend/internal/chapters"
 	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/dailymix"
 	"github.com/openmusicplayer/backend/internal/db"
 	"github.com/openmusicplayer/backend/internal/discovery"
+	"github.com/openmusicplayer/backend/internal/diskspace"
+	"github.com/openmusicplayer/backend/internal/doctor"
 	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/egress"
+	"github.com/openmusicplayer/backend/internal/fetcher"
+	"github.com/openmusicplayer/backend/internal/formatpolicy"
 	"github.com/openmusicplayer/backend/internal/health"
+	"github.com/openmusicplayer/backend/internal/images"
+	"github.com/openmusicplayer/backend/internal/importers"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+	"github.com/openmusicplayer/backend/internal/libraryimport"
 	"github.com/openmusicplayer/backend/internal/logger"
+	"github.com/openmusicplayer/backend/internal/maintenance"
 	"github.com/openmusicplayer/backend/internal/matcher"
 	"github.com/openmusicplayer/backend/internal/metrics"
 	"github.com/openmusicplayer/backend/internal/middleware"
 	"github.com/openmusicplayer/backend/internal/musicbrainz"
+	"github.com/openmusicplayer/backend/internal/notifications"
+	"github.com/openmusicplayer/backend/internal/playhistory"
 	"github.com/openmusicplayer/backend/internal/playlistimport"
 	"github.com/openmusicplayer/backend/internal/processor"
+	"github.com/openmusicplayer/backend/internal/push"
 	"github.com/openmusicplayer/backend/internal/queue"
 	"github.com/openmusicplayer/backend/internal/research"
 	"github.com/openmusicplayer/backend/internal/search"
+	"github.com/openmusicplayer/backend/internal/sourceauth"
+	"github.com/openmusicplayer/backend/internal/sponsorblock"
 	"github.com/openmusicplayer/backend/internal/storage"
+	"github.com/openmusicplayer/backend/internal/tools"
+	"github.com/openmusicplayer/backend/internal/tracing"
+	"github.com/openmusicplayer/backend/internal/webui"
 	"github.com/openmusicplayer/backend/internal/websocket"
 )
 
@@ -45,6 +72,12 @@ const (
 	startupAnalyzerRepairWorkers = 4
 	startupAnalyzerRepairTimeout = 15 * time.Second
 	startupAnalyzerRetryInterval = 30 * time.Second
+
+	// searchIndexBatchSize bounds each pass of the track search indexer.
+	searchIndexBatchSize = 500
+
+	// playRollupBatchSize bounds each statement of the play rollup pass.
+	playRollupBatchSize = 5000
 )
 
 type analyzerInfoClient interface {
@@ -113,8 +146,9 @@ func newSourceQualityJudge(cfg *config.Config) discovery.SourceQualityJudge {
 
 // newAgentToolsHandler wires the private research gateway independently from
 // ordinary discovery. A missing service token returns nil, so no gateway route
-// is registered and deterministic discovery remains untouched.
-func newAgentToolsHandler(cfg *config.Config, search *discovery.Service) *discovery.AgentToolsHandler {
+// is registered and deterministic discovery remains untouched. Its web
+// fetches go through the discovery proxy.
+func newAgentToolsHandler(cfg *config.Config, search *discovery.Service, proxies *egress.Proxies) *discovery.AgentToolsHandler {
 	if cfg == nil {
 		return nil
 	}
@@ -122,6 +156,7 @@ func newAgentToolsHandler(cfg *config.Config, search *discovery.Service) *discov
 		ServiceToken:    cfg.AgentServiceToken,
 		FirecrawlAPIKey: cfg.FirecrawlAPIKey,
 		Search:          search,
+		HTTPClient:      &http.Client{Transport: proxies.Transport(egress.Discovery)},
 	})
 }
 
@@ -360,33 +395,159 @@ func main() {
 	})
 
 	cfg := config.Load()
+	flag.BoolVar(&cfg.WebUIEnabled, "with-webui", cfg.WebUIEnabled, "serve the embedded web client on paths outside the API (WEBUI_ENABLED)")
+	flag.Parse()
 	if err := validateResearchStartup(cfg); err != nil {
 		log.Error(ctx, "Invalid research rollout configuration", nil, err)
 		os.Exit(1)
 	}
+	proxies, err := egress.Parse(cfg.ExternalProxies)
+	if err != nil {
+		log.Error(ctx, "Invalid external proxy configuration", nil, err)
+		os.Exit(1)
+	}
+	for _, provider := range proxies.Configured() {
+		log.Info(ctx, "Routing external requests through a proxy", map[string]interface{}{
+			"provider": provider,
+			"proxy":    proxies.Redacted(provider),
+		})
+	}
+
+	// Managed yt-dlp and ffmpeg builds go ahead of PATH before anything looks
+	// them up, so downloads, discovery, and imports all run the same binaries.
+	toolManager := tools.NewManager(tools.Config{
+		Dir:            cfg.ToolsDir,
+		AutoDownload:   cfg.ToolsAutoDownload,
+		YTDLPVersion:   cfg.YTDLPVersion,
+		FFmpegVersion:  cfg.FFmpegVersion,
+		UpdateInterval: cfg.ToolsUpdateCheckInterval,
+		AutoUpdate:     cfg.YTDLPAutoUpdate,
+		Logger:         log,
+	})
+	if err := toolManager.PrependToPath(); err != nil {
+		log.Error(ctx, "Failed to prepare managed tools directory", map[string]interface{}{
+			"dir": cfg.ToolsDir,
+		}, err)
+	} else if cfg.ToolsAutoDownload {
+		ensureCtx, cancelEnsure := context.WithTimeout(ctx, 10*time.Minute)
+		if err := toolManager.Ensure(ensureCtx); err != nil {
+			log.Error(ctx, "Failed to install external tools", map[string]interface{}{
+				"dir": cfg.ToolsDir,
+			}, err)
+		}
+		cancelEnsure()
+	}
+
+	// Surface local problems (missing ffmpeg or yt-dlp, an unwritable download
+	// directory) up front rather than on the first download. They are not fatal:
+	// playback works without them. `omp doctor` runs the full set of checks.
+	for _, result := range doctor.Run(ctx, doctor.New(cfg).StartupChecks(), 0) {
+		if result.Status != doctor.StatusOK {
+			log.Warn(ctx, "Startup self-check: "+result.Name, map[string]interface{}{
+				"status": string(result.Status),
+				"detail": result.Detail,
+				"fix":    result.Fix,
+			})
+		}
+	}
+
+	// Install the tracer before any client is built so outbound MusicBrainz,
+	// database, and worker spans share request trace context.
+	shutdownTracing, err := tracing.Setup(ctx, tracing.Config{
+		Enabled:       cfg.TracingEnabled,
+		ServiceName:   cfg.TracingServiceName,
+		Endpoint:      cfg.TracingEndpoint,
+		Headers:       cfg.TracingHeaders,
+		SampleRatio:   cfg.TracingSampleRatio,
+		FlushInterval: cfg.TracingFlushInterval,
+	})
+	if err != nil {
+		log.Error(ctx, "Failed to initialize tracing", nil, err)
+		os.Exit(1)
+	}
+	log.Info(ctx, "Initialized tracing", map[string]interface{}{
+		"tracing_enabled": cfg.TracingEnabled && cfg.TracingEndpoint != "",
+		"service_name":    cfg.TracingServiceName,
+		"sample_ratio":    cfg.TracingSampleRatio,
+	})
 
 	// Initialize metrics before the research handlers so their aggregate,
 	// allowlisted lifecycle observer is available from startup.
 	appMetrics := metrics.New()
 
 	// Initialize database
-	database, err := db.New(cfg.DBHost, cfg.DBPort, cfg.DBUser, cfg.DBPassword, cfg.DBName)
+	poolConfig := db.PoolConfig{
+		MaxOpenConns:       cfg.DBMaxOpenConns,
+		MaxIdleConns:       cfg.DBMaxIdleConns,
+		ConnMaxLifetime:    cfg.DBConnMaxLifetime,
+		ConnMaxIdleTime:    cfg.DBConnMaxIdleTime,
+		ConnectTimeout:     cfg.DBConnectTimeout,
+		StatementTimeout:   cfg.DBStatementTimeout,
+		SlowQueryThreshold: cfg.DBSlowQueryThreshold,
+		Logger:             log,
+	}
+	if cfg.DBReplicaHost != "" {
+		poolConfig.Replica = &db.ConnConfig{
+			Host:     cfg.DBReplicaHost,
+			Port:     cfg.DBReplicaPort,
+			User:     cfg.DBReplicaUser,
+			Password: cfg.DBReplicaPassword,
+			Name:     cfg.DBReplicaName,
+		}
+	}
+	database, err := db.NewWithConfig(db.ConnConfig{
+		Host:     cfg.DBHost,
+		Port:     cfg.DBPort,
+		User:     cfg.DBUser,
+		Password: cfg.DBPassword,
+		Name:     cfg.DBName,
+	}, poolConfig)
 	if err != nil {
 		log.Error(ctx, "Failed to connect to database", nil, err)
 		os.Exit(1)
 	}
 	defer database.Close()
 	log.Info(ctx, "Connected to database", map[string]interface{}{
-		"host": cfg.DBHost,
-		"port": cfg.DBPort,
-		"name": cfg.DBName,
+		"host":           cfg.DBHost,
+		"port":           cfg.DBPort,
+		"name":           cfg.DBName,
+		"max_open_conns": cfg.DBMaxOpenConns,
+		"max_idle_conns": cfg.DBMaxIdleConns,
+		"read_replica":   database.HasReplica(),
+	})
+	appMetrics.AddCollector(func(m *metrics.Metrics) {
+		for role, stats := range database.PoolStats() {
+			prefix := "db_pool_" + role + "_"
+			m.SetGauge(prefix+"max_open_connections", float64(stats.MaxOpenConnections))
+			m.SetGauge(prefix+"open_connections", float64(stats.OpenConnections))
+			m.SetGauge(prefix+"in_use", float64(stats.InUse))
+			m.SetGauge(prefix+"idle", float64(stats.Idle))
+			m.SetGauge(prefix+"wait_count", float64(stats.WaitCount))
+			m.SetGauge(prefix+"wait_duration_seconds", stats.WaitDuration.Seconds())
+			m.SetGauge(prefix+"max_idle_closed", float64(stats.MaxIdleClosed))
+			m.SetGauge(prefix+"max_lifetime_closed", float64(stats.MaxLifetimeClosed))
+		}
 	})
 
-	if err := database.Migrate(); err != nil {
+	if err := database.Migrate(); errors.Is(err, db.ErrSchemaTooNew) {
+		// Running anyway would quietly undo the newer release's schema.
+		log.Error(ctx, "Refusing to start: the database was migrated by a newer release", nil, err)
+		os.Exit(1)
+	} else if err != nil {
 		log.Error(ctx, "Failed to run migrations", nil, err)
 		os.Exit(1)
 	}
 	log.Info(ctx, "Database migrations completed", nil)
+	if cfg.SortArticles != nil {
+		if err := database.SetSortArticles(cfg.SortArticles); err != nil {
+			log.Error(ctx, "Invalid SORT_ARTICLES", nil, err)
+			os.Exit(1)
+		}
+	}
+	if err := database.SetSortCollation(ctx, cfg.SortCollation); err != nil {
+		log.Error(ctx, "Invalid SORT_COLLATION", nil, err)
+		os.Exit(1)
+	}
 
 	// Initialize optional Redis cache/queue support.
 	var redisCache *cache.Cache
@@ -410,24 +571,53 @@ func main() {
 	userRepo := db.NewUserRepository(database)
 	tokenRepo := db.NewTokenRepository(database)
 	trackRepo := db.NewTrackRepository(database)
+	if cfg.MetadataFieldPrecedence != nil {
+		trackRepo.SetFieldPrecedence(cfg.MetadataFieldPrecedence)
+	}
 	libraryRepo := db.NewLibraryRepository(database)
 	analysisRepo := db.NewAnalysisRepository(database)
 	playlistRepo := db.NewPlaylistRepository(database)
+	playlistRepo.SetHistoryPolicy(db.PlaylistHistoryPolicy{
+		MaxAge:       time.Duration(cfg.PlaylistHistoryRetentionDays) * 24 * time.Hour,
+		MaxRevisions: cfg.PlaylistHistoryMaxRevisions,
+	})
+	noteRepo := db.NewNoteRepository(database)
+	listenLaterRepo := db.NewListenLaterRepository(database)
+	dailyMixRepo := db.NewDailyMixRepository(database)
 	playlistSourceRepo := db.NewPlaylistSourceRepository(database)
 	playlistImportRepo := playlistimport.NewImportRepository(database)
 	trackSourceRepo := playlistimport.NewTrackSourceRepository(database)
 	mixPlanRepo := db.NewMixPlanRepository(database)
 	playEventRepo := db.NewPlayEventRepository(database)
+	playEventRepo.SetRetentionPolicy(db.PlayRetentionPolicy{
+		RawDays:   cfg.PlayEventRetentionDays,
+		DailyDays: cfg.PlayDailyRollupRetentionDays,
+	})
+	deviceProfileRepo := db.NewDeviceProfileRepository(database)
+	tenantRepo := db.NewTenantRepository(database)
 	sourceSelectionRepo := db.NewSourceSelectionRepository(database)
+	idempotencyRepo := db.NewIdempotencyRepository(database)
 
 	// Initialize services
 	authService := auth.NewService(userRepo, tokenRepo, cfg.JWTSecret)
 	authHandlers := auth.NewHandlers(authService)
 	searchHandlers := search.NewHandlers(trackRepo)
-	mbClient := musicbrainz.NewClient(redisCache)
+	mbClient := musicbrainz.NewClient(redisCache, musicbrainz.WithTransport(proxies.Transport(egress.MusicBrainz)))
 	mbHandlers := musicbrainz.NewHandlers(mbClient)
+	artistBios := artistinfo.NewBioService(db.NewArtistBioRepository(database), artistinfo.NewWikipediaClient())
+	var similarSource artistinfo.SimilarSource = artistinfo.NewListenBrainzClient("")
+	if cfg.LastFMAPIKey != "" {
+		similarSource = artistinfo.NewLastFMClient("", cfg.LastFMAPIKey)
+	}
+	similarArtists := artistinfo.NewSimilarService(db.NewSimilarArtistRepository(database), similarSource)
+	skipMarkerRepo := db.NewSkipMarkerRepository(database)
+	skipSegmentRepo := db.NewSkipSegmentRepository(database)
+	radioHandlers := api.NewRadioHandlers(similarArtists, libraryRepo).WithSkipMarkers(skipMarkerRepo).WithListeningHours(playEventRepo)
 	sourceQualityJudge := newSourceQualityJudge(cfg)
 	discoveryService := discovery.NewDefaultServiceWithCatalogAndSourceQualityJudge(mbClient, sourceQualityJudge)
+	if proxy := proxies.URL(egress.Discovery); proxy != nil {
+		discoveryService.UseProxy(proxy.String())
+	}
 	researchRuntime, err := newResearchRuntime(cfg, database, discoveryService, appMetrics)
 	if err != nil {
 		log.Error(ctx, "Failed to initialize durable research", nil, err)
@@ -447,7 +637,7 @@ func main() {
 			"research_worker_enabled": cfg.ResearchWorkerEnabled,
 		})
 	}
-	agentToolsHandler := newAgentToolsHandler(cfg, discoveryService)
+	agentToolsHandler := newAgentToolsHandler(cfg, discoveryService, proxies)
 	// AI assist is grounded against discovery/resolution; a nil client (unset or
 	// disabled config) degrades to the disabled envelope without breaking search.
 	assistClient := aiassist.NewClient(aiassist.Config{
@@ -472,12 +662,14 @@ func main() {
 		"agent_tools_enabled": agentToolsHandler != nil,
 		"firecrawl_enabled":   agentToolsHandler != nil && cfg.FirecrawlAPIKey != "",
 	})
-	libraryHandlers := api.NewLibraryHandlers(trackRepo, libraryRepo)
 	analysisHandlers := api.NewAnalysisHandlers(analysisRepo, libraryRepo)
 	playlistHandlers := api.NewPlaylistHandlers(playlistRepo, trackRepo)
+	playlistFolderHandlers := api.NewPlaylistFolderHandlers(db.NewPlaylistFolderRepository(database))
 	mixPlanHandlers := api.NewMixPlanHandlers(mixPlanRepo)
 	playlistMixHandlers := api.NewPlaylistMixHandlers(playlistRepo, mixPlanRepo, cfg.EnablePlaylistMix)
 	playEventHandlers := api.NewPlayEventHandlers(playEventRepo, trackRepo)
+	homeHandlers := api.NewHomeHandlers(playEventRepo, libraryRepo, listenLaterRepo, dailyMixRepo, redisCache).WithListeningHours(playEventRepo)
+	historyImportHandlers := api.NewHistoryImportHandlers(playhistory.NewImporter(libraryRepo, playEventRepo))
 
 	// Initialize storage client
 	storageClient, err := storage.New(&storage.Config{
@@ -498,11 +690,48 @@ func main() {
 		"public_endpoint": cfg.MinioPublicEndpoint,
 		"bucket":          cfg.MinioBucket,
 	})
+	libraryHandlers := api.NewLibraryHandlers(trackRepo, libraryRepo).WithObjectStorage(storageClient)
 
 	// Initialize playback URL handlers. Normal audio bytes are served by object
 	// storage/CDN through short-lived signed URLs; the backend does not register a
 	// byte-proxy streaming route in the normal playback path.
-	playbackHandlers := api.NewPlaybackHandlers(trackRepo, libraryRepo, storageClient)
+	playbackHandlers := api.NewPlaybackHandlers(trackRepo, libraryRepo, storageClient).WithSkipMarkers(skipMarkerRepo).WithSkipSegments(skipSegmentRepo)
+
+	// Guest mode opens the designated shelf playlists to visitors without an
+	// account, through the same signed URLs.
+	var guestHandlers *api.GuestHandlers
+	if cfg.GuestMode {
+		var shelf []int64
+		for _, value := range cfg.GuestShelfPlaylists {
+			id, err := strconv.ParseInt(value, 10, 64)
+			if err != nil || id <= 0 {
+				log.Error(ctx, "Invalid GUEST_SHELF_PLAYLISTS playlist ID", map[string]interface{}{"value": value}, err)
+				os.Exit(1)
+			}
+			shelf = append(shelf, id)
+		}
+		if len(shelf) == 0 {
+			log.Error(ctx, "GUEST_MODE needs GUEST_SHELF_PLAYLISTS to name the playlists guests may see", nil, nil)
+			os.Exit(1)
+		}
+		guestHandlers = api.NewGuestHandlers(shelf, playlistRepo, storageClient, cfg.GuestRateLimit)
+	}
+
+	// Cover art is rendered into sized variants on ingest and served the same
+	// way as audio, through signed object storage URLs.
+	artworkRepo := db.NewArtworkRepository(database)
+	artworkService := images.NewService(storageClient, artworkRepo)
+	artworkHandlers := api.NewArtworkHandlers(artworkRepo, libraryRepo, storageClient)
+	lyricsRepo := db.NewLyricsRepository(database)
+	chapterRepo := db.NewChapterRepository(database)
+	lyricsHandlers := api.NewLyricsHandlers(lyricsRepo, libraryRepo)
+	trackMetadataHandlers := api.NewTrackMetadataHandlers(trackRepo, libraryRepo)
+	skipMarkerHandlers := api.NewSkipMarkerHandlers(skipMarkerRepo, libraryRepo, trackRepo)
+	userSettingsRepo := db.NewUserSettingsRepository(database)
+	userSettingsHandlers := api.NewUserSettingsHandlers(userSettingsRepo, playlistRepo)
+	apiKeyHandlers := api.NewAPIKeyHandlers(db.NewAPIKeyRepository(database))
+	libraryHealthHandlers := api.NewLibraryHealthHandlers(libraryRepo)
+	libraryInboxHandlers := api.NewLibraryInboxHandlers(libraryRepo, trackRepo, storageClient)
 
 	// Initialize WebSocket hub and handler
 	wsHub := websocket.NewHub()
@@ -546,6 +775,78 @@ func main() {
 	})
 
 	// Initialize job processor with matching integration
+	downloaders := processor.ProxiedDownloaders(proxies.URL(egress.Downloads))
+	if err := processor.ExternalDownloaders(downloaders, cfg.ExternalDownloaders, proxies.Env(egress.Downloads)); err != nil {
+		log.Error(ctx, "Invalid external downloader configuration", nil, err)
+		os.Exit(1)
+	}
+	if cfg.DirectDownloadAllowPrivate {
+		// Direct file URLs may point at hosts on the server's own network.
+		downloaders.RegisterSourceType(download.SourceTypeDirect, fetcher.NewHTTP(&http.Client{Timeout: 10 * time.Minute, Transport: proxies.Transport(egress.Downloads)}, 0))
+	}
+	importRemotes, err := importers.ParseRemotes(cfg.RemoteImportSources)
+	if err != nil {
+		log.Error(ctx, "Invalid remote import configuration", nil, err)
+		os.Exit(1)
+	}
+	downloaders.RegisterSourceType(download.SourceTypeRemote, importers.NewDownloader(importRemotes, 0))
+	var beets *importers.Beets
+	if cfg.BeetsConfig != "" {
+		beets, err = importers.LoadBeets(cfg.BeetsConfig, cfg.BeetsExecutable)
+		if err != nil {
+			log.Error(ctx, "Invalid beets configuration", nil, err)
+			os.Exit(1)
+		}
+	}
+	var skipSegmentSource processor.SkipSegmentSource
+	if cfg.SponsorBlockEnabled {
+		skipSegmentSource = sponsorblock.NewClient(cfg.SponsorBlockURL, cfg.SponsorBlockCategories)
+	}
+	formatPolicy, err := formatpolicy.Parse(cfg.FormatPolicy, cfg.FormatPolicyKeepLossless)
+	if err != nil {
+		log.Error(ctx, "Invalid format policy", nil, err)
+		os.Exit(1)
+	}
+	audioHashMatch, err := processor.ParseAudioHashMatch(cfg.AudioHashMatch)
+	if err != nil {
+		log.Error(ctx, "Invalid audio hash match", nil, err)
+		os.Exit(1)
+	}
+	var sourceCredentials processor.SourceCredentials
+	var credentialHandlers *api.SourceCredentialHandlers
+	if cfg.SourceCredentialsKey != "" {
+		key, err := sourceauth.ParseKey(cfg.SourceCredentialsKey)
+		if err != nil {
+			log.Error(ctx, "Invalid source credentials key", nil, err)
+			os.Exit(1)
+		}
+		sealer, err := sourceauth.NewSealer(key)
+		if err != nil {
+			log.Error(ctx, "Invalid source credentials key", nil, err)
+			os.Exit(1)
+		}
+		credentialService := sourceauth.NewService(db.NewSourceCredentialRepository(database), sealer)
+		sourceCredentials = credentialService
+		credentialHandlers = api.NewSourceCredentialHandlers(credentialService)
+	}
+	jobStore := jobs.NewStore(database)
+	var formatEnforcer processor.FormatEnforcer
+	if formatPolicy.Enabled() {
+		formatEnforcer = formatpolicy.NewEnforcer(formatPolicy, formatpolicy.NewQueue(jobStore))
+	}
+	migrationSources, err := libraryimport.ParseSources(cfg.MigrationSources)
+	if err != nil {
+		log.Error(ctx, "Invalid library migration configuration", nil, err)
+		os.Exit(1)
+	}
+	var trackQuality processor.TrackQualityStore
+	if cfg.QualityScoringEnabled {
+		trackQuality = db.NewTrackQualityRepository(database)
+	}
+	var moodFeatures processor.MoodFeatureStore
+	if cfg.MoodAnalysisEnabled {
+		moodFeatures = trackRepo
+	}
 	jobProcessor := processor.New(&processor.ProcessorConfig{
 		Matcher:                 matcherService,
 		TrackRepo:               trackRepo,
@@ -559,6 +860,26 @@ func main() {
 		AnalysisConcurrency:     cfg.AnalyzerConcurrency,
 		RequireAnalyzerIdentity: serviceAnalyzerClient != nil,
 		Storage:                 storageClient,
+		Artwork:                 artworkService,
+		FolderArtworkNames:      cfg.ArtworkFolderFilenames,
+		Lyrics:                  lyricsRepo,
+		Chapters:                chapterRepo,
+		SkipSegmentSource:       skipSegmentSource,
+		SkipSegments:            skipSegmentRepo,
+		TrimSkipSegments:        cfg.SponsorBlockTrim,
+		UserSettings:            userSettingsRepo,
+		SilenceTrims:            db.NewSilenceTrimRepository(database),
+		LoudnessNormalizations:  db.NewLoudnessNormalizationRepository(database),
+		LoudnessTargetLUFS:      cfg.LoudnessTargetLUFS,
+		KeepLoudnessOriginals:   cfg.LoudnessKeepOriginals,
+		TrackQuality:            trackQuality,
+		MoodFeatures:            moodFeatures,
+		FormatEnforcer:          formatEnforcer,
+		Downloaders:             downloaders,
+		Tenants:                 tenantRepo,
+		AudioHashMatch:          audioHashMatch,
+		SourceCredentials:       sourceCredentials,
+		DB:                      database,
 	})
 	stopAnalyzerMaintenance := func() {}
 	if serviceAnalyzerClient != nil {
@@ -596,12 +917,330 @@ func main() {
 		}()
 	}
 	maintenanceHandlers := api.NewMaintenanceHandlers(trackRepo, jobProcessor)
+	maintenanceJobHandlers := api.NewMaintenanceJobHandlers(maintenance.NewQueue(jobStore))
+	var remoteImportHandlers *api.RemoteImportHandlers
+	if len(importRemotes) > 0 {
+		remoteImportHandlers = api.NewRemoteImportHandlers(importers.NewQueue(jobStore, importRemotes))
+	}
+	var beetsImportHandlers *api.BeetsImportHandlers
+	if beets != nil {
+		beetsImportHandlers = api.NewBeetsImportHandlers(importers.NewBeetsQueue(jobStore))
+	}
+	var migrationHandlers *api.LibraryMigrationHandlers
+	if len(migrationSources) > 0 {
+		migrationHandlers = api.NewLibraryMigrationHandlers(libraryimport.NewQueue(jobStore, migrationSources))
+	}
+	var exportHandlers *api.LibraryExportHandlers
+	if cfg.ExportDir != "" {
+		exportHandlers = api.NewLibraryExportHandlers(libraryexport.NewQueue(jobStore), deviceProfileRepo)
+	}
+	transcodeQueue := libraryexport.NewTranscodeQueue(jobStore, cfg.TranscodeMaxActive)
+	deviceProfileHandlers := api.NewDeviceProfileHandlers(deviceProfileRepo, trackRepo, libraryRepo, storageClient, transcodeQueue)
+	playbackClientRepo := db.NewPlaybackClientRepository(database)
+	eqPresetRepo := db.NewEQPresetRepository(database)
+	playbackClientHandlers := api.NewPlaybackClientHandlers(playbackClientRepo).WithEQPresets(eqPresetRepo)
+	eqPresetHandlers := api.NewEQPresetHandlers(eqPresetRepo)
+	playbackHandlers.WithClients(playbackClientRepo, transcodeQueue)
+	chapterHandlers := api.NewChapterHandlers(chapterRepo, libraryRepo, chapters.NewQueue(jobStore))
+	trackTechnicalHandlers := api.NewTrackTechnicalHandlers(libraryRepo, trackRepo, jobProcessor)
+	noteHandlers := api.NewNoteHandlers(noteRepo, libraryRepo, playlistRepo)
+	listenLaterHandlers := api.NewListenLaterHandlers(listenLaterRepo, trackRepo)
+	eventHandlers := api.NewEventHandlers(db.NewEventRepository(database))
+	activityHandlers := api.NewActivityHandlers(db.NewActivityRepository(database))
+	followHandlers := api.NewFollowHandlers(db.NewFollowRepository(database), playlistRepo)
+	var fcm *push.FCM
+	if cfg.PushFCMCredentialsFile != "" {
+		if fcm, err = push.LoadFCM(cfg.PushFCMCredentialsFile); err != nil {
+			log.Error(ctx, "Failed to load FCM credentials", nil, err)
+			os.Exit(1)
+		}
+	}
+	pushDeviceRepo := db.NewPushDeviceRepository(database)
+	pushDeviceHandlers := api.NewPushDeviceHandlers(pushDeviceRepo, fcm != nil)
+	notificationRepo := db.NewNotificationRepository(database)
+	notificationService := notifications.NewService(notificationRepo, wsHub, push.NewGateway(pushDeviceRepo, fetcher.PublicClient(nil), fcm))
+	notificationHandlers := api.NewNotificationHandlers(notificationRepo)
+	giftHandlers := api.NewGiftHandlers(db.NewGiftRepository(database), notificationService)
+	var activityPubService *activitypub.Service
+	var activityPubHandlers *api.ActivityPubHandlers
+	if cfg.ActivityPubEnabled {
+		base, err := activitypub.ParseBaseURL(cfg.ActivityPubBaseURL)
+		if err != nil {
+			log.Error(ctx, "Invalid ActivityPub base URL", nil, err)
+			os.Exit(1)
+		}
+		activityPubService = activitypub.NewService(db.NewActivityPubRepository(database), base, fetcher.PublicClient(nil))
+		activityPubHandlers = api.NewActivityPubHandlers(activityPubService)
+	}
+	continueHandlers := api.NewContinueHandlers(playEventRepo, playlistRepo, trackRepo)
+	yearInReviewHandlers := api.NewYearInReviewHandlers(playEventRepo, db.NewYearReviewCardRepository(database), userRepo)
+	shuffleHandlers := api.NewShuffleHandlers(libraryRepo, playlistRepo)
+	tenantHandlers := api.NewTenantHandlers(tenantRepo)
+
+	// Expired idempotency keys are reclaimed on reuse; this prune only keeps
+	// the table from growing with keys that are never retried.
+	idempotencyPruneCtx, stopIdempotencyPrune := context.WithCancel(context.Background())
+	go func() {
+		ticker := time.NewTicker(time.Hour)
+		defer ticker.Stop()
+		for {
+			if deleted, err := idempotencyRepo.DeleteExpired(idempotencyPruneCtx, api.IdempotencyKeyTTL); err != nil {
+				log.Error(ctx, "Failed to prune expired idempotency keys", nil, err)
+			} else if deleted > 0 {
+				log.Info(ctx, "Pruned expired idempotency keys", map[string]interface{}{"deleted": deleted})
+			}
+			select {
+			case <-idempotencyPruneCtx.Done():
+				return
+			case <-ticker.C:
+			}
+		}
+	}()
+
+	// Plays are counted into daily rollups before raw events past retention
+	// are deleted, so stats never lose plays; the first pass after an upgrade
+	// works through the whole play history in batches.
+	playRollupCtx, stopPlayRollup := context.WithCancel(context.Background())
+	go func() {
+		ticker := time.NewTicker(time.Hour)
+		defer ticker.Stop()
+		steps := []struct {
+			name string
+			run  func(context.Context, int) (int, error)
+		}{
+			{"rolled_up", playEventRepo.RollUpPlays},
+			{"compacted", playEventRepo.CompactDailyRollups},
+			{"purged", playEventRepo.PurgeRawPlays},
+		}
+		for {
+			fields := map[string]interface{}{}
+			for _, step := range steps {
+				total := 0
+				for {
+					n, err := step.run(playRollupCtx, playRollupBatchSize)
+					total += n
+					if err != nil {
+						if playRollupCtx.Err() == nil {
+							log.Error(ctx, "Failed to maintain play rollups", map[string]interface{}{"step": step.name}, err)
+						}
+						break
+					}
+					if n < playRollupBatchSize {
+						break
+					}
+				}
+				if total > 0 {
+					fields[step.name] = total
+				}
+			}
+			if len(fields) > 0 {
+				log.Info(ctx, "Maintained play rollups", fields)
+			}
+			select {
+			case <-playRollupCtx.Done():
+				return
+			case <-ticker.C:
+			}
+		}
+	}()
+
+	// Cached playlist and library album totals are refreshed by the writes
+	// that change them; this pass fixes any that drifted, and fills them in
+	// on the first start after an upgrade.
+	aggregateRepairCtx, stopAggregateRepair := context.WithCancel(context.Background())
+	go func() {
+		ticker := time.NewTicker(24 * time.Hour)
+		defer ticker.Stop()
+		for {
+			if repair, err := db.RepairAggregates(aggregateRepairCtx, database); err != nil {
+				if aggregateRepairCtx.Err() == nil {
+					log.Error(ctx, "Failed to repair cached aggregates", nil, err)
+				}
+			} else if repair.Playlists > 0 || repair.LibraryOwners > 0 {
+				log.Info(ctx, "Repaired cached aggregates", map[string]interface{}{
+					"playlists":      repair.Playlists,
+					"library_owners": repair.LibraryOwners,
+				})
+			}
+			select {
+			case <-aggregateRepairCtx.Done():
+				return
+			case <-ticker.C:
+			}
+		}
+	}()
+
+	// Popular and newly added tracks are converted overnight to the settings
+	// most device profiles share, so their first sync finds a copy waiting.
+	transcodeWarmer := libraryexport.NewTranscodeWarmer(jobStore, libraryexport.WarmConfig{
+		Profiles: deviceProfileRepo,
+		Plays:    playEventRepo,
+		Tracks:   trackRepo,
+		Objects:  storageClient,
+		Budget:   cfg.TranscodeWarmBudget,
+	})
+	transcodeWarmCtx, stopTranscodeWarm := context.WithCancel(context.Background())
+	go func() {
+		if cfg.TranscodeWarmBudget == 0 {
+			return
+		}
+		ticker := time.NewTicker(time.Hour)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-transcodeWarmCtx.Done():
+				return
+			case now := <-ticker.C:
+				if now.Hour() != cfg.TranscodeWarmHour {
+					continue
+				}
+			}
+			job, err := transcodeWarmer.Warm(transcodeWarmCtx)
+			if err != nil {
+				if transcodeWarmCtx.Err() == nil {
+					log.Error(ctx, "Failed to queue transcode warming", nil, err)
+				}
+			} else if job != nil {
+				log.Info(ctx, "Queued transcode warming", map[string]interface{}{"job_id": job.ID.String()})
+			}
+		}
+	}()
+
+	// Tracks stored before search normalization, and tracks whose title,
+	// artist, or album changed, wait here for their folded search text.
+	searchIndexCtx, stopSearchIndex := context.WithCancel(context.Background())
+	go func() {
+		ticker := time.NewTicker(time.Minute)
+		defer ticker.Stop()
+		for {
+			for {
+				indexed, err := trackRepo.IndexSearchText(searchIndexCtx, searchIndexBatchSize)
+				if err != nil {
+					if searchIndexCtx.Err() == nil {
+						log.Error(ctx, "Failed to index track search text", nil, err)
+					}
+					break
+				}
+				if indexed < searchIndexBatchSize {
+					break
+				}
+			}
+			select {
+			case <-searchIndexCtx.Done():
+				return
+			case <-ticker.C:
+			}
+		}
+	}()
+
+	// Downloads are refused while the disk they are written to is nearly full,
+	// so jobs never leave half-written files behind.
+	diskNotifiers := []diskspace.Notifier{diskspace.NotifierFunc(func(_ context.Context, event diskspace.Event) error {
+		fields := map[string]interface{}{
+			"path":           event.Status.Path,
+			"free_bytes":     event.Status.FreeBytes,
+			"min_free_bytes": event.Status.MinFreeBytes,
+		}
+		if event.Type == diskspace.EventLow {
+			log.Warn(ctx, "Free disk space is low; refusing new downloads", fields)
+		} else {
+			log.Info(ctx, "Free disk space recovered; accepting new downloads", fields)
+		}
+		return nil
+	})}
+	if cfg.DiskAlertWebhookURL != "" {
+		diskNotifiers = append(diskNotifiers, diskspace.NewWebhook(cfg.DiskAlertWebhookURL))
+	}
+	diskMonitor := diskspace.NewMonitor(diskspace.Config{
+		Path:         cfg.DiskMonitorPath,
+		MinFreeBytes: uint64(cfg.DiskMinFreeMB) << 20,
+		Notifiers:    diskNotifiers,
+	})
+	diskMonitorCtx, stopDiskMonitor := context.WithCancel(context.Background())
+	go diskMonitor.Run(diskMonitorCtx)
+	appMetrics.AddCollector(func(m *metrics.Metrics) {
+		status := diskMonitor.Status()
+		m.SetGauge("disk_free_bytes", float64(status.FreeBytes))
+		m.SetGauge("disk_total_bytes", float64(status.TotalBytes))
+		m.SetGauge("disk_min_free_bytes", float64(status.MinFreeBytes))
+		low := 0.0
+		if status.Low {
+			low = 1
+		}
+		m.SetGauge("disk_space_low", low)
+	})
+	activityPubCtx, stopActivityPub := context.WithCancel(context.Background())
+	if activityPubService != nil {
+		go activityPubService.Run(activityPubCtx)
+	}
+	notificationsCtx, stopNotifications := context.WithCancel(context.Background())
+	go notificationService.Run(notificationsCtx)
+	dailyMixCtx, stopDailyMixes := context.WithCancel(context.Background())
+	if cfg.DailyMixCount > 0 {
+		go dailymix.New(dailyMixRepo, cfg.DailyMixCount, cfg.DailyMixSize, cfg.DailyMixHour).Run(dailyMixCtx)
+	}
+	toolUpdatesCtx, stopToolUpdates := context.WithCancel(context.Background())
+	go toolManager.Run(toolUpdatesCtx)
+	appMetrics.AddCollector(func(m *metrics.Metrics) {
+		available := 0.0
+		if toolManager.Status().UpdateAvailable {
+			available = 1
+		}
+		m.SetGauge("tools_ytdlp_update_available", available)
+	})
+
+	// Background jobs, such as bulk fix-it jobs queued from the library
+	// health report, run one at a time.
+	jobWorker := jobs.NewWorker(jobStore, jobs.WorkerConfig{})
+	maintenance.NewRunner(libraryRepo, trackRepo, jobProcessor).GuardRedownloads(diskMonitor).Register(jobWorker)
+	maintenance.NewAudioHashRunner(trackRepo, jobProcessor).Register(jobWorker)
+	formatpolicy.NewRunner(formatPolicy, trackRepo, jobProcessor).Register(jobWorker)
+	importers.NewRunner(importRemotes, trackSourceRepo, libraryRepo, jobProcessor).GuardDownloads(diskMonitor).Register(jobWorker)
+	if beets != nil {
+		importers.NewBeetsRunner(beets, trackSourceRepo, libraryRepo, jobProcessor).GuardDownloads(diskMonitor).Register(jobWorker)
+	}
+	libraryimport.NewRunner(migrationSources, libraryimport.RunnerConfig{
+		Tracks:          trackRepo,
+		Library:         libraryRepo,
+		Plays:           playEventRepo,
+		Playlists:       playlistRepo,
+		PlaylistSources: playlistSourceRepo,
+	}).Register(jobWorker)
+	if cfg.ExportDir != "" {
+		libraryexport.NewRunner(cfg.ExportDir, libraryexport.RunnerConfig{
+			Tracks:    trackRepo,
+			Library:   libraryRepo,
+			Playlists: playlistRepo,
+			Artwork:   artworkRepo,
+			Objects:   storageClient,
+			Tagger:    libraryexport.NewFFmpeg(),
+			Notes:     noteRepo,
+		}).Register(jobWorker)
+	}
+	libraryexport.NewTranscodeRunner(libraryexport.TranscodeConfig{
+		Tracks:  trackRepo,
+		Artwork: artworkRepo,
+		Objects: storageClient,
+		Tagger:  libraryexport.NewFFmpeg(),
+	}).Register(jobWorker)
+	chapters.NewRunner(chapters.RunnerConfig{
+		Chapters: chapterRepo,
+		Tracks:   trackRepo,
+		Library:  libraryRepo,
+		Objects:  storageClient,
+		Importer: jobProcessor,
+		Cutter:   chapters.NewFFmpeg(),
+	}).Register(jobWorker)
+	jobWorkerCtx, stopJobWorker := context.WithCancel(context.Background())
+	go jobWorker.Run(jobWorkerCtx)
 
 	// Initialize Redis-backed download and playback queue services only when enabled.
 	var downloadService *download.Service
 	var downloadHandlers *api.DownloadHandlers
+	var quickAddHandlers *api.QuickAddHandlers
 	var queueHandlers *queue.Handlers
 	var playlistImportHandlers *api.PlaylistImportHandlers
+	playlistImportJobs := api.NewPlaylistImportJobSource(playlistImportRepo, nil)
 
 	if cfg.RedisEnabled {
 		sourceSelectionLifecycle := db.NewSourceSelectionDownloadLifecycle(database)
@@ -609,6 +1248,9 @@ func main() {
 		downloadService, err = download.NewService(&download.ServiceConfig{
 			RedisURL:    cfg.RedisURL,
 			WorkerCount: cfg.WorkerCount,
+			DeadLetters: api.NewDownloadDeadLetters(jobStore),
+			Admission:   diskMonitor,
+			Notifier:    notificationService,
 		}, jobProcessor.Process, sourceSelectionLifecycle)
 		if err != nil {
 			log.Error(ctx, "Failed to initialize download service", nil, err)
@@ -633,6 +1275,7 @@ func main() {
 			"workers": cfg.WorkerCount,
 		})
 		downloadHandlers = api.NewDownloadHandlers(downloadService, sourceSelectionIngestion)
+		quickAddHandlers = api.NewQuickAddHandlers(downloadService, sourceSelectionIngestion, userSettingsRepo)
 		ytdlpEnumerator := playlistimport.NewYTDLPEnumerator()
 		playlistImportService := playlistimport.NewService(playlistimport.Config{
 			Store:          playlistImportRepo,
@@ -640,6 +1283,7 @@ func main() {
 			Tracks:         trackSourceRepo,
 			Library:        libraryRepo,
 			Downloader:     downloadService,
+			Canceler:       downloadService,
 			Selections:     sourceSelectionRepo,
 			Ingestion:      sourceSelectionIngestion,
 			Enumerator:     ytdlpEnumerator,
@@ -647,9 +1291,34 @@ func main() {
 			SourceBindings: playlistSourceRepo,
 		})
 		playlistImportHandlers = api.NewPlaylistImportHandlers(playlistImportService)
+		playlistImportJobs = api.NewPlaylistImportJobSource(playlistImportRepo, playlistImportService)
+
+		queueHandlers = queue.NewHandlersWithSourceSelections(queueService, downloadService, analysisRepo, sourceSelectionRepo, database).WithPlaybackSettings(userSettingsRepo)
+	}
 
-		queueHandlers = queue.NewHandlersWithSourceSelections(queueService, downloadService, analysisRepo, sourceSelectionRepo, database)
+	// Downloads keep their Redis queue but report and pause through the jobs API.
+	jobSources := []jobs.Source{jobStore, playlistImportJobs}
+	queueControls := []jobs.QueueControl{jobWorker}
+	retriers := []jobs.Retrier{jobWorker}
+	if downloadService != nil {
+		downloadJobs := api.NewDownloadJobSource(downloadService)
+		jobSources = append(jobSources, downloadJobs)
+		queueControls = append(queueControls, api.NewDownloadQueueControl(downloadService))
+		retriers = append(retriers, downloadJobs)
 	}
+	jobHandlers := api.NewJobHandlers(jobs.NewDirectory(jobSources...))
+	jobQueueHandlers := api.NewJobQueueHandlers(jobs.NewControls(queueControls...), jobs.NewDeadLetters(jobStore, retriers...), cfg.JobControlToken)
+	appMetrics.AddCollector(func(m *metrics.Metrics) {
+		countCtx, cancel := context.WithTimeout(context.Background(), 2*time.Second)
+		defer cancel()
+		counts, err := jobStore.DeadLetterCounts(countCtx)
+		if err != nil {
+			return
+		}
+		for _, category := range jobs.FailureCategories {
+			m.SetGauge("job_dead_letters_"+category, float64(counts[category]))
+		}
+	})
 
 	var redisClient *redis.Client
 	if redisCache != nil {
@@ -663,11 +1332,24 @@ func main() {
 		StorageCheck: func(ctx context.Context) error {
 			return storageClient.Ping(ctx)
 		},
-		Version: version,
-		Timeout: 5 * time.Second,
+		DiskCheck: diskMonitor.Check,
+		Version:   version,
+		Timeout:   5 * time.Second,
 	})
 	healthHandler := health.NewHandler(healthChecker)
 
+	// The embedded web client is served by the API itself, so an install
+	// needs no separate web server for it.
+	var webUI http.Handler
+	if cfg.WebUIEnabled {
+		webUIHandler, err := webui.New()
+		if err != nil {
+			log.Error(ctx, "Failed to load the embedded web client", nil, err)
+			os.Exit(1)
+		}
+		webUI = webUIHandler
+	}
+
 	// Create router with all handlers
 	router := api.NewRouterWithConfig(&api.RouterConfig{
 		AuthHandlers:            authHandlers,
@@ -675,34 +1357,90 @@ func main() {
 		SearchHandlers:          searchHandlers,
 		MBClient:                mbClient,
 		MBHandlers:              mbHandlers,
+		ArtistBios:              artistBios,
+		SimilarArtists:          similarArtists,
 		WSHandler:               wsHandler,
 		MatcherHandlers:         matcherHandlers,
 		LibraryHandlers:         libraryHandlers,
+		LibraryHealthHandlers:   libraryHealthHandlers,
+		LibraryInboxHandlers:    libraryInboxHandlers,
 		AnalysisHandlers:        analysisHandlers,
 		PlaybackHandlers:        playbackHandlers,
+		PlaybackClientHandlers:  playbackClientHandlers,
+		ArtworkHandlers:         artworkHandlers,
+		LyricsHandlers:          lyricsHandlers,
+		TrackMetadataHandlers:   trackMetadataHandlers,
+		SkipMarkerHandlers:      skipMarkerHandlers,
+		ChapterHandlers:         chapterHandlers,
+		TrackTechnicalHandlers:  trackTechnicalHandlers,
+		NoteHandlers:            noteHandlers,
+		ListenLaterHandlers:     listenLaterHandlers,
+		EventHandlers:           eventHandlers,
+		ActivityHandlers:        activityHandlers,
+		FollowHandlers:          followHandlers,
+		ActivityPubHandlers:     activityPubHandlers,
+		GiftHandlers:            giftHandlers,
+		NotificationHandlers:    notificationHandlers,
+		PushDeviceHandlers:      pushDeviceHandlers,
+		EQPresetHandlers:        eqPresetHandlers,
+		ContinueHandlers:        continueHandlers,
+		YearInReviewHandlers:    yearInReviewHandlers,
+		ShuffleHandlers:         shuffleHandlers,
+		UserSettingsHandlers:    userSettingsHandlers,
+		APIKeyHandlers:          apiKeyHandlers,
+		CredentialHandlers:      credentialHandlers,
+		QuickAddHandlers:        quickAddHandlers,
 		QueueHandlers:           queueHandlers,
 		DiscoveryHandlers:       discoveryHandlers,
 		AgentToolsHandler:       agentToolsHandler,
 		PlaylistHandlers:        playlistHandlers,
 		PlaylistImportHandlers:  playlistImportHandlers,
 		PlaylistMixHandlers:     playlistMixHandlers,
+		PlaylistFolderHandlers:  playlistFolderHandlers,
 		MixPlanHandlers:         mixPlanHandlers,
 		DownloadHandlers:        downloadHandlers,
 		SourceSelectionHandlers: sourceSelectionHandlers,
 		MaintenanceHandlers:     maintenanceHandlers,
+		MaintenanceJobHandlers:  maintenanceJobHandlers,
+		RemoteImportHandlers:    remoteImportHandlers,
+		BeetsImportHandlers:     beetsImportHandlers,
+		MigrationHandlers:       migrationHandlers,
+		HistoryImportHandlers:   historyImportHandlers,
+		ExportHandlers:          exportHandlers,
+		DeviceProfileHandlers:   deviceProfileHandlers,
+		GuestHandlers:           guestHandlers,
+		StreamLimiter:           api.NewStreamLimiter(cfg.StreamConcurrency),
+		TenantHandlers:          tenantHandlers,
+		RadioHandlers:           radioHandlers,
+		JobHandlers:             jobHandlers,
+		JobQueueHandlers:        jobQueueHandlers,
 		PlayEventHandlers:       playEventHandlers,
+		HomeHandlers:            homeHandlers,
 		ResearchHandlers:        researchRuntime.handlers,
+		IdempotencyStore:        idempotencyRepo,
 		HealthHandler:           healthHandler,
 		Metrics:                 appMetrics,
 		CORSAllowedOrigins:      cfg.CORSAllowedOrigins,
+		WebUI:                   webUI,
 	})
 
+	proxy, err := middleware.NewProxy(cfg.TrustedProxies, cfg.BasePath)
+	if err != nil {
+		log.Error(ctx, "Invalid TRUSTED_PROXIES or BASE_PATH", nil, err)
+		os.Exit(1)
+	}
+
 	// Apply middleware chain
+	// Forwarding headers and the base path are resolved before anything
+	// reads the client address or the path. Request and trace IDs are
+	// assigned next so recovery and access logs carry them.
 	handler := middleware.Chain(
 		router,
-		middleware.Recoverer(log),
-		middleware.Logging(log),
+		proxy.Middleware,
 		middleware.RequestID,
+		tracing.Middleware,
+		middleware.Recoverer(log),
+		middleware.Logging(log.WithComponent("http")),
 		metrics.MetricsMiddleware(appMetrics),
 	)
 
@@ -715,6 +1453,62 @@ func main() {
 		Handler: handler,
 	}
 
+	// Built-in TLS serves the API on TLS_ADDR. The plain listener then
+	// answers ACME HTTP-01 challenges and redirects everything else there.
+	certConfig := certs.Config{
+		Domains:      cfg.ACMEDomains,
+		Email:        cfg.ACMEEmail,
+		CacheDir:     cfg.ACMECacheDir,
+		DirectoryURL: cfg.ACMEDirectoryURL,
+		CertFile:     cfg.TLSCertFile,
+		KeyFile:      cfg.TLSKeyFile,
+	}
+	var tlsServer *http.Server
+	var certManager *certs.Manager
+	if certConfig.Enabled() {
+		certManager, err = certs.New(certConfig)
+		if err != nil {
+			log.Error(ctx, "Invalid TLS settings", nil, err)
+			os.Exit(1)
+		}
+		tlsServer = &http.Server{
+			Addr:      cfg.TLSAddr,
+			Handler:   handler,
+			TLSConfig: certManager.TLSConfig(),
+		}
+		if cfg.TLSRedirectHTTP {
+			server.Handler = certManager.HTTPHandler(certs.RedirectHTTPS(cfg.TLSAddr))
+		} else {
+			server.Handler = certManager.HTTPHandler(handler)
+		}
+	}
+
+	// Certificates are checked daily, obtaining any not yet issued and
+	// picking up replaced certificate files. The first pass waits for the
+	// listeners to come up so challenges can be answered.
+	certRenewCtx, stopCertRenew := context.WithCancel(context.Background())
+	go func() {
+		if certManager == nil {
+			return
+		}
+		next := time.After(time.Minute)
+		for {
+			select {
+			case <-certRenewCtx.Done():
+				return
+			case <-next:
+			}
+			if expires, err := certManager.Renew(certRenewCtx); err != nil {
+				if certRenewCtx.Err() == nil {
+					log.Error(ctx, "Failed to renew TLS certificates", nil, err)
+				}
+			} else {
+				log.Info(ctx, "TLS certificates checked", map[string]interface{}{"expires_at": expires})
+			}
+			next = time.After(24 * time.Hour)
+		}
+	}()
+
 	// Graceful shutdown handling
 	shutdownComplete := make(chan struct{})
 	go func() {
@@ -727,11 +1521,29 @@ func main() {
 			"signal": sig.String(),
 		})
 		stopAnalyzerMaintenance()
+		stopIdempotencyPrune()
+		stopPlayRollup()
+		stopAggregateRepair()
+		stopTranscodeWarm()
+		stopSearchIndex()
+		stopJobWorker()
+		stopDiskMonitor()
+		stopToolUpdates()
+		stopActivityPub()
+		stopNotifications()
+		stopDailyMixes()
+		stopCertRenew()
 
 		// Stop accepting new requests
 		shutdownCtx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
 		defer cancel()
 
+		if tlsServer != nil {
+			if err := tlsServer.Shutdown(shutdownCtx); err != nil {
+				log.Error(ctx, "HTTPS server shutdown error", nil, err)
+				_ = tlsServer.Close()
+			}
+		}
 		if err := server.Shutdown(shutdownCtx); err != nil {
 			log.Error(ctx, "HTTP server shutdown error", nil, err)
 			_ = server.Close()
@@ -752,10 +1564,24 @@ func main() {
 		if err := jobProcessor.Shutdown(shutdownCtx); err != nil {
 			log.Error(ctx, "Analysis worker shutdown error", nil, err)
 		}
+		if err := shutdownTracing(shutdownCtx); err != nil {
+			log.Error(ctx, "Trace exporter shutdown error", nil, err)
+		}
 
 		log.Info(ctx, "Server shutdown complete", nil)
 	}()
 
+	if tlsServer != nil {
+		log.Info(ctx, "HTTPS server starting", map[string]interface{}{
+			"addr": cfg.TLSAddr,
+		})
+		go func() {
+			if err := tlsServer.ListenAndServeTLS("", ""); err != nil && err != http.ErrServerClosed {
+				log.Error(ctx, "HTTPS server failed to start", nil, err)
+				os.Exit(1)
+			}
+		}()
+	}
 	log.Info(ctx, "Server starting", map[string]interface{}{
 		"addr": cfg.ServerAddr,
 	})
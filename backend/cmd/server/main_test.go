@@ -37,10 +37,10 @@ func TestNewSourceQualityJudgeEnabledConfigConstructsOllamaJudge(t *testing.T) {
 
 func TestNewAgentToolsHandlerRequiresServiceToken(t *testing.T) {
 	search := discovery.NewService(discovery.ServiceConfig{})
-	if handler := newAgentToolsHandler(&config.Config{FirecrawlAPIKey: "configured"}, search); handler != nil {
+	if handler := newAgentToolsHandler(&config.Config{FirecrawlAPIKey: "configured"}, search, nil); handler != nil {
 		t.Fatal("agent tools must be absent without service token")
 	}
-	if handler := newAgentToolsHandler(&config.Config{AgentServiceToken: "service-token"}, search); handler == nil {
+	if handler := newAgentToolsHandler(&config.Config{AgentServiceToken: "service-token"}, search, nil); handler == nil {
 		t.Fatal("agent tools should be created with service token even without Firecrawl")
 	}
 }
@@ -0,0 +1,157 @@
+// Package activitypub gives users who opt in an ActivityPub actor, so
+// accounts on Mastodon and other fediverse servers can follow them and see
+// what they choose to publish: now-playing posts, loved tracks and public
+// playlists. It serves WebFinger, the actor, outbox and followers documents
+// and an inbox for follows, signs deliveries with HTTP signatures and checks
+// the signatures of what arrives. It is experimental and off unless
+// ACTIVITYPUB_ENABLED is set.
+package activitypub
+
+import (
+	"errors"
+	"fmt"
+	"net/url"
+	"regexp"
+	"strconv"
+	"strings"
+)
+
+// ContentType is the media type of ActivityPub documents.
+const ContentType = "application/activity+json"
+
+// Public is the collection addressing a post to everyone.
+const Public = "https://www.w3.org/ns/activitystreams#Public"
+
+var activityStreamsContext = []any{"https://www.w3.org/ns/activitystreams", "https://w3id.org/security/v1"}
+
+// ErrInvalidHandle is returned for a handle that is not 1 to 30 lowercase
+// letters, digits and underscores.
+var ErrInvalidHandle = errors.New("handle must be 1 to 30 lowercase letters, digits or underscores")
+
+var handlePattern = regexp.MustCompile(`^[a-z0-9_]{1,30}$`)
+
+// ValidHandle reports whether handle can name an actor.
+func ValidHandle(handle string) bool {
+	return handlePattern.MatchString(handle)
+}
+
+// ParseBaseURL reads ACTIVITYPUB_BASE_URL: the public origin remote servers
+// reach this one at, such as https://music.example.com.
+func ParseBaseURL(raw string) (*url.URL, error) {
+	base, err := url.Parse(strings.TrimSpace(raw))
+	if err != nil {
+		return nil, fmt.Errorf("invalid base URL: %w", err)
+	}
+	if (base.Scheme != "https" && base.Scheme != "http") || base.Host == "" || strings.Trim(base.Path, "/") != "" {
+		return nil, fmt.Errorf("base URL %q must be an origin such as https://music.example.com", raw)
+	}
+	return &url.URL{Scheme: base.Scheme, Host: base.Host}, nil
+}
+
+// urls builds the IRIs of one server's actors.
+type urls struct {
+	base *url.URL
+}
+
+func (u urls) actor(handle string) string {
+	return u.base.String() + "/ap/users/" + handle
+}
+
+func (u urls) inbox(handle string) string {
+	return u.actor(handle) + "/inbox"
+}
+
+func (u urls) outbox(handle string) string {
+	return u.actor(handle) + "/outbox"
+}
+
+func (u urls) followers(handle string) string {
+	return u.actor(handle) + "/followers"
+}
+
+func (u urls) key(handle string) string {
+	return u.actor(handle) + "#main-key"
+}
+
+func (u urls) post(handle string, id int64) string {
+	return u.actor(handle) + "/posts/" + strconv.FormatInt(id, 10)
+}
+
+// domain is the host handles are qualified with, as in handle@domain.
+func (u urls) domain() string {
+	return u.base.Host
+}
+
+// PublicKey is the key of an actor, as actor documents publish it.
+type PublicKey struct {
+	ID           string `json:"id"`
+	Owner        string `json:"owner"`
+	PublicKeyPEM string `json:"publicKeyPem"`
+}
+
+// Actor is the subset of an actor document this package writes and reads.
+type Actor struct {
+	Context           any        `json:"@context,omitempty"`
+	ID                string     `json:"id"`
+	Type              string     `json:"type"`
+	PreferredUsername string     `json:"preferredUsername"`
+	Name              string     `json:"name,omitempty"`
+	Summary           string     `json:"summary,omitempty"`
+	Inbox             string     `json:"inbox"`
+	Outbox            string     `json:"outbox,omitempty"`
+	Followers         string     `json:"followers,omitempty"`
+	Endpoints         *Endpoints `json:"endpoints,omitempty"`
+	PublicKey         *PublicKey `json:"publicKey,omitempty"`
+}
+
+// Endpoints carries a remote actor's shared inbox, which takes one delivery
+// for every follower on its server.
+type Endpoints struct {
+	SharedInbox string `json:"sharedInbox,omitempty"`
+}
+
+// Note is a published post.
+type Note struct {
+	Context      any      `json:"@context,omitempty"`
+	ID           string   `json:"id"`
+	Type         string   `json:"type"`
+	AttributedTo string   `json:"attributedTo"`
+	Content      string   `json:"content"`
+	Published    string   `json:"published"`
+	To           []string `json:"to"`
+	Cc           []string `json:"cc,omitempty"`
+}
+
+// Activity is an activity sent or received. Object is an IRI or an embedded
+// object.
+type Activity struct {
+	Context any      `json:"@context,omitempty"`
+	ID      string   `json:"id"`
+	Type    string   `json:"type"`
+	Actor   string   `json:"actor"`
+	Object  any      `json:"object"`
+	To      []string `json:"to,omitempty"`
+	Cc      []string `json:"cc,omitempty"`
+}
+
+// OrderedCollection is an outbox or followers collection.
+type OrderedCollection struct {
+	Context      any    `json:"@context,omitempty"`
+	ID           string `json:"id"`
+	Type         string `json:"type"`
+	TotalItems   int    `json:"totalItems"`
+	OrderedItems []any  `json:"orderedItems,omitempty"`
+}
+
+// WebFinger is the JRD answering a WebFinger query for an actor.
+type WebFinger struct {
+	Subject string          `json:"subject"`
+	Aliases []string        `json:"aliases,omitempty"`
+	Links   []WebFingerLink `json:"links"`
+}
+
+type WebFingerLink struct {
+	Rel  string `json:"rel"`
+	Type string `json:"type,omitempty"`
+	Href string `json:"href"`
+}
@@ -0,0 +1,111 @@
+package activitypub
+
+import (
+	"context"
+	"html"
+	"log"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// PublishInterval is how often Run looks for something new to publish.
+const PublishInterval = time.Minute
+
+// changeBatch is how many events one pass reads per actor.
+const changeBatch = 100
+
+// Run publishes every PublishInterval until ctx is done.
+func (s *Service) Run(ctx context.Context) {
+	ticker := time.NewTicker(PublishInterval)
+	defer ticker.Stop()
+	for {
+		if err := s.PublishAll(ctx); err != nil {
+			log.Printf("Failed to publish ActivityPub posts: %v", err)
+		}
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// PublishAll makes posts of what each actor's user did since the last pass
+// and delivers them to the actor's followers. What a user chose not to
+// publish is skipped over, never held back for later.
+func (s *Service) PublishAll(ctx context.Context) error {
+	actors, err := s.store.List(ctx)
+	if err != nil {
+		return err
+	}
+	for i := range actors {
+		if err := s.publish(ctx, &actors[i]); err != nil {
+			log.Printf("Failed to publish ActivityPub posts of %s: %v", actors[i].Handle, err)
+		}
+	}
+	return nil
+}
+
+func (s *Service) publish(ctx context.Context, actor *db.ActivityPubActor) error {
+	changes, err := s.store.Changes(ctx, actor.UserID, actor.LastEventID, changeBatch)
+	if err != nil {
+		return err
+	}
+	play, err := s.store.LatestPlay(ctx, actor.UserID, actor.LastPlayID)
+	if err != nil {
+		return err
+	}
+
+	var posts []db.ActivityPubPost
+	lastEventID, lastPlayID := actor.LastEventID, actor.LastPlayID
+	for _, change := range changes {
+		lastEventID = change.EventID
+		switch {
+		case change.Action == "favorited" && actor.PublishLoved && change.TrackTitle != "":
+			posts = append(posts, db.ActivityPubPost{
+				Kind:    db.ActivityPubPostLoved,
+				Content: "<p>Loved " + trackContent(change.TrackTitle, change.TrackArtist) + "</p>",
+			})
+		case change.Action != "favorited" && actor.PublishPlaylists && change.PlaylistPublic:
+			posts = append(posts, db.ActivityPubPost{
+				Kind:       db.ActivityPubPostPlaylist,
+				Content:    "<p>Shared the playlist <em>" + html.EscapeString(change.PlaylistName) + "</em></p>",
+				PlaylistID: change.PlaylistID,
+			})
+		}
+	}
+	if play != nil {
+		lastPlayID = play.ID
+		if actor.PublishListening {
+			posts = append(posts, db.ActivityPubPost{
+				Kind:    db.ActivityPubPostListening,
+				Content: "<p>Listening to " + trackContent(play.TrackTitle, play.TrackArtist) + "</p>",
+			})
+		}
+	}
+	if lastEventID == actor.LastEventID && lastPlayID == actor.LastPlayID {
+		return nil
+	}
+
+	stored, err := s.store.Publish(ctx, actor.UserID, posts, lastEventID, lastPlayID)
+	if err != nil || len(stored) == 0 {
+		return err
+	}
+	followers, err := s.store.ListFollowers(ctx, actor.UserID)
+	if err != nil {
+		return err
+	}
+	for _, post := range stored {
+		s.deliverAll(actor, followers, s.create(actor, post))
+	}
+	return nil
+}
+
+func trackContent(title, artist string) string {
+	content := "<em>" + html.EscapeString(title) + "</em>"
+	if artist != "" {
+		content += " by " + html.EscapeString(artist)
+	}
+	return content
+}
@@ -0,0 +1,433 @@
+package activitypub
+
+import (
+	"bytes"
+	"context"
+	"crypto/rsa"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+	"net/url"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// ErrNotFound is returned for a handle, resource or post this server has no
+// actor or post for.
+var ErrNotFound = errors.New("not found")
+
+// ErrInvalidActivity is returned for an inbox delivery that is not an
+// activity from the actor that signed it.
+var ErrInvalidActivity = errors.New("invalid activity")
+
+const (
+	// maxDocumentBytes bounds remote actor documents and inbox deliveries.
+	maxDocumentBytes = 1 << 20
+	// requestTimeout bounds each request to a remote server.
+	requestTimeout = 15 * time.Second
+	// outboxPosts is how many of the latest posts the outbox lists.
+	outboxPosts = 20
+)
+
+// Store keeps actors, followers and posts. db.ActivityPubRepository
+// satisfies this interface.
+type Store interface {
+	GetByHandle(ctx context.Context, handle string) (*db.ActivityPubActor, error)
+	GetByUser(ctx context.Context, userID uuid.UUID) (*db.ActivityPubActor, error)
+	List(ctx context.Context) ([]db.ActivityPubActor, error)
+	Put(ctx context.Context, actor *db.ActivityPubActor) error
+	Delete(ctx context.Context, userID uuid.UUID) error
+	AddFollower(ctx context.Context, userID uuid.UUID, follower db.ActivityPubFollower) error
+	RemoveFollower(ctx context.Context, userID uuid.UUID, actorIRI string) error
+	ListFollowers(ctx context.Context, userID uuid.UUID) ([]db.ActivityPubFollower, error)
+	ListPosts(ctx context.Context, userID uuid.UUID, limit int) ([]db.ActivityPubPost, int, error)
+	GetPost(ctx context.Context, userID uuid.UUID, id int64) (*db.ActivityPubPost, error)
+	Changes(ctx context.Context, userID uuid.UUID, after int64, limit int) ([]db.ActivityPubChange, error)
+	LatestPlay(ctx context.Context, userID uuid.UUID, after int64) (*db.ActivityPubPlay, error)
+	Publish(ctx context.Context, userID uuid.UUID, posts []db.ActivityPubPost, lastEventID, lastPlayID int64) ([]db.ActivityPubPost, error)
+}
+
+// Settings are what a user chose to publish under which handle.
+type Settings struct {
+	Handle           string
+	PublishListening bool
+	PublishLoved     bool
+	PublishPlaylists bool
+}
+
+// Status is a user's actor as they see it.
+type Status struct {
+	Actor     *db.ActivityPubActor
+	Address   string
+	ActorURL  string
+	Followers int
+}
+
+type Service struct {
+	store  Store
+	urls   urls
+	client *http.Client
+	now    func() time.Time
+}
+
+// NewService serves actors at base. client fetches remote actors and
+// delivers to their inboxes; it should refuse private addresses, as
+// fetcher.PublicClient does, since remote documents name the URLs.
+func NewService(store Store, base *url.URL, client *http.Client) *Service {
+	return &Service{store: store, urls: urls{base: base}, client: client, now: time.Now}
+}
+
+// Enable gives the user an actor with settings, or updates the one they
+// have.
+func (s *Service) Enable(ctx context.Context, userID uuid.UUID, settings Settings) (*Status, error) {
+	if !ValidHandle(settings.Handle) {
+		return nil, ErrInvalidHandle
+	}
+	actor, err := s.store.GetByUser(ctx, userID)
+	if errors.Is(err, db.ErrActivityPubActorNotFound) {
+		actor = &db.ActivityPubActor{UserID: userID}
+		if actor.PublicKeyPEM, actor.PrivateKeyPEM, err = GenerateKey(); err != nil {
+			return nil, fmt.Errorf("generate key: %w", err)
+		}
+	} else if err != nil {
+		return nil, err
+	}
+	actor.Handle = settings.Handle
+	actor.PublishListening = settings.PublishListening
+	actor.PublishLoved = settings.PublishLoved
+	actor.PublishPlaylists = settings.PublishPlaylists
+	if err := s.store.Put(ctx, actor); err != nil {
+		return nil, err
+	}
+	return s.Status(ctx, userID)
+}
+
+// Status returns the user's actor, or db.ErrActivityPubActorNotFound.
+func (s *Service) Status(ctx context.Context, userID uuid.UUID) (*Status, error) {
+	actor, err := s.store.GetByUser(ctx, userID)
+	if err != nil {
+		return nil, err
+	}
+	followers, err := s.store.ListFollowers(ctx, userID)
+	if err != nil {
+		return nil, err
+	}
+	return &Status{
+		Actor:     actor,
+		Address:   actor.Handle + "@" + s.urls.domain(),
+		ActorURL:  s.urls.actor(actor.Handle),
+		Followers: len(followers),
+	}, nil
+}
+
+// Disable removes the user's actor, its followers and posts, and tells the
+// followers' servers the actor is gone.
+func (s *Service) Disable(ctx context.Context, userID uuid.UUID) error {
+	actor, err := s.store.GetByUser(ctx, userID)
+	if err != nil {
+		return err
+	}
+	followers, err := s.store.ListFollowers(ctx, userID)
+	if err != nil {
+		return err
+	}
+	if err := s.store.Delete(ctx, userID); err != nil {
+		return err
+	}
+	actorIRI := s.urls.actor(actor.Handle)
+	s.deliverAll(actor, followers, Activity{
+		Context: activityStreamsContext,
+		ID:      actorIRI + "#delete",
+		Type:    "Delete",
+		Actor:   actorIRI,
+		Object:  actorIRI,
+		To:      []string{Public},
+	})
+	return nil
+}
+
+// WebFinger answers a query for acct:handle@domain or an actor URL.
+func (s *Service) WebFinger(ctx context.Context, resource string) (*WebFinger, error) {
+	handle, ok := strings.CutPrefix(resource, "acct:")
+	if ok {
+		var domain string
+		handle, domain, ok = strings.Cut(handle, "@")
+		if !ok || !strings.EqualFold(domain, s.urls.domain()) {
+			return nil, ErrNotFound
+		}
+	} else if handle, ok = strings.CutPrefix(resource, s.urls.actor("")); !ok {
+		return nil, ErrNotFound
+	}
+	actor, err := s.actor(ctx, strings.ToLower(handle))
+	if err != nil {
+		return nil, err
+	}
+	actorIRI := s.urls.actor(actor.Handle)
+	return &WebFinger{
+		Subject: "acct:" + actor.Handle + "@" + s.urls.domain(),
+		Aliases: []string{actorIRI},
+		Links:   []WebFingerLink{{Rel: "self", Type: ContentType, Href: actorIRI}},
+	}, nil
+}
+
+// Actor returns the actor document of handle.
+func (s *Service) Actor(ctx context.Context, handle string) (*Actor, error) {
+	actor, err := s.actor(ctx, handle)
+	if err != nil {
+		return nil, err
+	}
+	actorIRI := s.urls.actor(actor.Handle)
+	return &Actor{
+		Context:           activityStreamsContext,
+		ID:                actorIRI,
+		Type:              "Person",
+		PreferredUsername: actor.Handle,
+		Name:              actor.Handle,
+		Summary:           "Listening on Open Music Player.",
+		Inbox:             s.urls.inbox(actor.Handle),
+		Outbox:            s.urls.outbox(actor.Handle),
+		Followers:         s.urls.followers(actor.Handle),
+		PublicKey:         &PublicKey{ID: s.urls.key(actor.Handle), Owner: actorIRI, PublicKeyPEM: actor.PublicKeyPEM},
+	}, nil
+}
+
+// Followers returns the followers collection of handle. It gives only the
+// count; who follows is not published.
+func (s *Service) Followers(ctx context.Context, handle string) (*OrderedCollection, error) {
+	actor, err := s.actor(ctx, handle)
+	if err != nil {
+		return nil, err
+	}
+	followers, err := s.store.ListFollowers(ctx, actor.UserID)
+	if err != nil {
+		return nil, err
+	}
+	return &OrderedCollection{Context: activityStreamsContext, ID: s.urls.followers(actor.Handle), Type: "OrderedCollection", TotalItems: len(followers)}, nil
+}
+
+// Outbox returns the outbox of handle with its latest posts.
+func (s *Service) Outbox(ctx context.Context, handle string) (*OrderedCollection, error) {
+	actor, err := s.actor(ctx, handle)
+	if err != nil {
+		return nil, err
+	}
+	posts, total, err := s.store.ListPosts(ctx, actor.UserID, outboxPosts)
+	if err != nil {
+		return nil, err
+	}
+	outbox := &OrderedCollection{Context: activityStreamsContext, ID: s.urls.outbox(actor.Handle), Type: "OrderedCollection", TotalItems: total}
+	for _, post := range posts {
+		create := s.create(actor, post)
+		create.Context = nil
+		outbox.OrderedItems = append(outbox.OrderedItems, create)
+	}
+	return outbox, nil
+}
+
+// Post returns one post of handle.
+func (s *Service) Post(ctx context.Context, handle string, id int64) (*Note, error) {
+	actor, err := s.actor(ctx, handle)
+	if err != nil {
+		return nil, err
+	}
+	post, err := s.store.GetPost(ctx, actor.UserID, id)
+	if errors.Is(err, db.ErrActivityPubPostNotFound) {
+		return nil, ErrNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	note := s.note(actor, *post)
+	note.Context = activityStreamsContext
+	return &note, nil
+}
+
+func (s *Service) actor(ctx context.Context, handle string) (*db.ActivityPubActor, error) {
+	actor, err := s.store.GetByHandle(ctx, handle)
+	if errors.Is(err, db.ErrActivityPubActorNotFound) {
+		return nil, ErrNotFound
+	}
+	return actor, err
+}
+
+func (s *Service) note(actor *db.ActivityPubActor, post db.ActivityPubPost) Note {
+	return Note{
+		ID:           s.urls.post(actor.Handle, post.ID),
+		Type:         "Note",
+		AttributedTo: s.urls.actor(actor.Handle),
+		Content:      post.Content,
+		Published:    post.PublishedAt.UTC().Format(time.RFC3339),
+		To:           []string{Public},
+		Cc:           []string{s.urls.followers(actor.Handle)},
+	}
+}
+
+func (s *Service) create(actor *db.ActivityPubActor, post db.ActivityPubPost) Activity {
+	note := s.note(actor, post)
+	return Activity{
+		Context: activityStreamsContext,
+		ID:      note.ID + "/activity",
+		Type:    "Create",
+		Actor:   note.AttributedTo,
+		Object:  note,
+		To:      note.To,
+		Cc:      note.Cc,
+	}
+}
+
+// HandleInbox takes a delivery to the inbox of handle: follows, undone
+// follows and deleted accounts. Other activities are accepted and ignored.
+func (s *Service) HandleInbox(ctx context.Context, handle string, req *http.Request, body []byte) error {
+	actor, err := s.actor(ctx, handle)
+	if err != nil {
+		return err
+	}
+	signer, err := Verify(ctx, req, body, s.now(), s.fetchKey)
+	if err != nil {
+		return err
+	}
+	var activity Activity
+	if err := json.Unmarshal(body, &activity); err != nil {
+		return fmt.Errorf("%w: %v", ErrInvalidActivity, err)
+	}
+	if activity.Actor != signer {
+		return fmt.Errorf("%w: signed by %s, not its actor %s", ErrInvalidActivity, signer, activity.Actor)
+	}
+	switch activity.Type {
+	case "Follow":
+		if object, _ := activity.Object.(string); object != s.urls.actor(actor.Handle) {
+			return fmt.Errorf("%w: follow of %v", ErrInvalidActivity, activity.Object)
+		}
+		remote, err := s.fetchActor(ctx, signer)
+		if err != nil {
+			return fmt.Errorf("%w: fetch follower: %v", ErrInvalidActivity, err)
+		}
+		follower := db.ActivityPubFollower{ActorIRI: remote.ID, Inbox: remote.Inbox}
+		if remote.Endpoints != nil {
+			follower.SharedInbox = remote.Endpoints.SharedInbox
+		}
+		if err := s.store.AddFollower(ctx, actor.UserID, follower); err != nil {
+			return err
+		}
+		actorIRI := s.urls.actor(actor.Handle)
+		s.deliverAll(actor, []db.ActivityPubFollower{{Inbox: follower.Inbox}}, Activity{
+			Context: activityStreamsContext,
+			ID:      actorIRI + "#accept-" + uuid.NewString(),
+			Type:    "Accept",
+			Actor:   actorIRI,
+			Object:  activity,
+		})
+	case "Undo":
+		if object, ok := activity.Object.(map[string]any); ok && object["type"] != "Follow" {
+			return nil
+		}
+		return s.store.RemoveFollower(ctx, actor.UserID, signer)
+	case "Delete":
+		if object, _ := activity.Object.(string); object == signer {
+			return s.store.RemoveFollower(ctx, actor.UserID, signer)
+		}
+	}
+	return nil
+}
+
+// fetchKey is the KeyFetcher of inbox deliveries: it loads the actor
+// document keyID belongs to.
+func (s *Service) fetchKey(ctx context.Context, keyID string) (*rsa.PublicKey, string, error) {
+	actorIRI, _, _ := strings.Cut(keyID, "#")
+	remote, err := s.fetchActor(ctx, actorIRI)
+	if err != nil {
+		return nil, "", err
+	}
+	if remote.PublicKey == nil || remote.PublicKey.ID != keyID {
+		return nil, "", fmt.Errorf("actor %s does not publish key %s", actorIRI, keyID)
+	}
+	key, err := parsePublicKey(remote.PublicKey.PublicKeyPEM)
+	if err != nil {
+		return nil, "", err
+	}
+	return key, remote.ID, nil
+}
+
+func (s *Service) fetchActor(ctx context.Context, iri string) (*Actor, error) {
+	ctx, cancel := context.WithTimeout(ctx, requestTimeout)
+	defer cancel()
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, iri, nil)
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Set("Accept", ContentType)
+	resp, err := s.client.Do(req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("GET %s: %s", iri, resp.Status)
+	}
+	var remote Actor
+	if err := json.NewDecoder(io.LimitReader(resp.Body, maxDocumentBytes)).Decode(&remote); err != nil {
+		return nil, fmt.Errorf("decode actor %s: %w", iri, err)
+	}
+	if remote.ID != iri || remote.Inbox == "" {
+		return nil, fmt.Errorf("%s is not an actor document", iri)
+	}
+	return &remote, nil
+}
+
+// deliverAll posts activity to every follower's inbox in the background,
+// once per shared inbox.
+func (s *Service) deliverAll(actor *db.ActivityPubActor, followers []db.ActivityPubFollower, activity Activity) {
+	seen := make(map[string]bool)
+	for _, follower := range followers {
+		inbox := follower.SharedInbox
+		if inbox == "" {
+			inbox = follower.Inbox
+		}
+		if seen[inbox] {
+			continue
+		}
+		seen[inbox] = true
+		go func(inbox string) {
+			ctx, cancel := context.WithTimeout(context.Background(), requestTimeout)
+			defer cancel()
+			if err := s.deliver(ctx, actor, inbox, activity); err != nil {
+				log.Printf("Failed to deliver %s of %s to %s: %v", activity.Type, actor.Handle, inbox, err)
+			}
+		}(inbox)
+	}
+}
+
+func (s *Service) deliver(ctx context.Context, actor *db.ActivityPubActor, inbox string, activity Activity) error {
+	body, err := json.Marshal(activity)
+	if err != nil {
+		return err
+	}
+	key, err := parsePrivateKey(actor.PrivateKeyPEM)
+	if err != nil {
+		return err
+	}
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, inbox, bytes.NewReader(body))
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Content-Type", ContentType)
+	if err := Sign(req, body, s.urls.key(actor.Handle), key, s.now()); err != nil {
+		return err
+	}
+	resp, err := s.client.Do(req)
+	if err != nil {
+		return err
+	}
+	resp.Body.Close()
+	if resp.StatusCode >= 300 {
+		return fmt.Errorf("POST %s: %s", inbox, resp.Status)
+	}
+	return nil
+}
@@ -0,0 +1,276 @@
+package activitypub
+
+import (
+	"context"
+	"crypto/rsa"
+	"encoding/json"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeStore struct {
+	mu        sync.Mutex
+	actor     *db.ActivityPubActor
+	followers []db.ActivityPubFollower
+	posts     []db.ActivityPubPost
+	changes   []db.ActivityPubChange
+	play      *db.ActivityPubPlay
+}
+
+func (f *fakeStore) GetByHandle(ctx context.Context, handle string) (*db.ActivityPubActor, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	if f.actor == nil || f.actor.Handle != handle {
+		return nil, db.ErrActivityPubActorNotFound
+	}
+	actor := *f.actor
+	return &actor, nil
+}
+
+func (f *fakeStore) GetByUser(ctx context.Context, userID uuid.UUID) (*db.ActivityPubActor, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	if f.actor == nil || f.actor.UserID != userID {
+		return nil, db.ErrActivityPubActorNotFound
+	}
+	actor := *f.actor
+	return &actor, nil
+}
+
+func (f *fakeStore) List(ctx context.Context) ([]db.ActivityPubActor, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	if f.actor == nil {
+		return nil, nil
+	}
+	return []db.ActivityPubActor{*f.actor}, nil
+}
+
+func (f *fakeStore) Put(ctx context.Context, actor *db.ActivityPubActor) error {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	stored := *actor
+	f.actor = &stored
+	return nil
+}
+
+func (f *fakeStore) Delete(ctx context.Context, userID uuid.UUID) error {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	f.actor, f.followers, f.posts = nil, nil, nil
+	return nil
+}
+
+func (f *fakeStore) AddFollower(ctx context.Context, userID uuid.UUID, follower db.ActivityPubFollower) error {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	f.followers = append(f.followers, follower)
+	return nil
+}
+
+func (f *fakeStore) RemoveFollower(ctx context.Context, userID uuid.UUID, actorIRI string) error {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	kept := f.followers[:0]
+	for _, follower := range f.followers {
+		if follower.ActorIRI != actorIRI {
+			kept = append(kept, follower)
+		}
+	}
+	f.followers = kept
+	return nil
+}
+
+func (f *fakeStore) ListFollowers(ctx context.Context, userID uuid.UUID) ([]db.ActivityPubFollower, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	return append([]db.ActivityPubFollower(nil), f.followers...), nil
+}
+
+func (f *fakeStore) ListPosts(ctx context.Context, userID uuid.UUID, limit int) ([]db.ActivityPubPost, int, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	return append([]db.ActivityPubPost(nil), f.posts...), len(f.posts), nil
+}
+
+func (f *fakeStore) GetPost(ctx context.Context, userID uuid.UUID, id int64) (*db.ActivityPubPost, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	for _, post := range f.posts {
+		if post.ID == id {
+			return &post, nil
+		}
+	}
+	return nil, db.ErrActivityPubPostNotFound
+}
+
+func (f *fakeStore) Changes(ctx context.Context, userID uuid.UUID, after int64, limit int) ([]db.ActivityPubChange, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	var changes []db.ActivityPubChange
+	for _, change := range f.changes {
+		if change.EventID > after {
+			changes = append(changes, change)
+		}
+	}
+	return changes, nil
+}
+
+func (f *fakeStore) LatestPlay(ctx context.Context, userID uuid.UUID, after int64) (*db.ActivityPubPlay, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	if f.play == nil || f.play.ID <= after {
+		return nil, nil
+	}
+	return f.play, nil
+}
+
+func (f *fakeStore) Publish(ctx context.Context, userID uuid.UUID, posts []db.ActivityPubPost, lastEventID, lastPlayID int64) ([]db.ActivityPubPost, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	for i := range posts {
+		posts[i].ID = int64(len(f.posts) + 1)
+		posts[i].UserID = userID
+		posts[i].PublishedAt = time.Now()
+		f.posts = append(f.posts, posts[i])
+	}
+	f.actor.LastEventID, f.actor.LastPlayID = lastEventID, lastPlayID
+	return posts, nil
+}
+
+// remoteServer is a fediverse server with one actor, alice, whose inbox
+// passes on what it is sent once the signature checks out against key.
+func remoteServer(t *testing.T, key func() *rsa.PublicKey) (*httptest.Server, *rsa.PrivateKey, chan Activity) {
+	t.Helper()
+	publicPEM, privatePEM, err := GenerateKey()
+	if err != nil {
+		t.Fatalf("GenerateKey() = %v", err)
+	}
+	private, err := parsePrivateKey(privatePEM)
+	if err != nil {
+		t.Fatalf("parsePrivateKey() = %v", err)
+	}
+	received := make(chan Activity, 4)
+	mux := http.NewServeMux()
+	srv := httptest.NewServer(mux)
+	t.Cleanup(srv.Close)
+	mux.HandleFunc("GET /users/alice", func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", ContentType)
+		json.NewEncoder(w).Encode(Actor{
+			ID:                srv.URL + "/users/alice",
+			Type:              "Person",
+			PreferredUsername: "alice",
+			Inbox:             srv.URL + "/inbox",
+			PublicKey:         &PublicKey{ID: srv.URL + "/users/alice#main-key", Owner: srv.URL + "/users/alice", PublicKeyPEM: publicPEM},
+		})
+	})
+	mux.HandleFunc("POST /inbox", func(w http.ResponseWriter, r *http.Request) {
+		body, _ := io.ReadAll(r.Body)
+		if _, err := Verify(r.Context(), r, body, time.Now(), func(ctx context.Context, keyID string) (*rsa.PublicKey, string, error) {
+			return key(), keyID, nil
+		}); err != nil {
+			t.Errorf("delivery signature: %v", err)
+		}
+		var activity Activity
+		if err := json.Unmarshal(body, &activity); err != nil {
+			t.Errorf("delivery body: %v", err)
+		}
+		received <- activity
+		w.WriteHeader(http.StatusAccepted)
+	})
+	return srv, private, received
+}
+
+func TestServiceAcceptsFollowersAndDeliversPosts(t *testing.T) {
+	ctx := context.Background()
+	store := &fakeStore{}
+	userID := uuid.New()
+	srv, remoteKey, received := remoteServer(t, func() *rsa.PublicKey {
+		actor, _ := store.GetByUser(ctx, userID)
+		key, err := parsePublicKey(actor.PublicKeyPEM)
+		if err != nil {
+			t.Errorf("parsePublicKey() = %v", err)
+		}
+		return key
+	})
+	base, _ := url.Parse("https://music.example.com")
+	s := NewService(store, base, srv.Client())
+	expect := func(want string) Activity {
+		t.Helper()
+		select {
+		case activity := <-received:
+			if activity.Type != want {
+				t.Fatalf("delivered %s, want %s", activity.Type, want)
+			}
+			return activity
+		case <-time.After(5 * time.Second):
+			t.Fatalf("no %s delivered", want)
+		}
+		return Activity{}
+	}
+	inbox := func(activity map[string]any) error {
+		body, _ := json.Marshal(activity)
+		req := httptest.NewRequest(http.MethodPost, "https://music.example.com/ap/users/bob/inbox", strings.NewReader(string(body)))
+		if err := Sign(req, body, srv.URL+"/users/alice#main-key", remoteKey, time.Now()); err != nil {
+			t.Fatalf("Sign() = %v", err)
+		}
+		return s.HandleInbox(ctx, "bob", req, body)
+	}
+
+	if _, err := s.Enable(ctx, userID, Settings{Handle: "Bob"}); err != ErrInvalidHandle {
+		t.Fatalf("Enable() with an uppercase handle = %v", err)
+	}
+	status, err := s.Enable(ctx, userID, Settings{Handle: "bob", PublishLoved: true, PublishPlaylists: true})
+	if err != nil || status.Address != "bob@music.example.com" {
+		t.Fatalf("Enable() = %+v, %v", status, err)
+	}
+	finger, err := s.WebFinger(ctx, "acct:bob@music.example.com")
+	if err != nil || finger.Links[0].Href != "https://music.example.com/ap/users/bob" {
+		t.Fatalf("WebFinger() = %+v, %v", finger, err)
+	}
+
+	alice := srv.URL + "/users/alice"
+	if err := inbox(map[string]any{"id": alice + "/follow", "type": "Follow", "actor": alice, "object": status.ActorURL}); err != nil {
+		t.Fatalf("Follow = %v", err)
+	}
+	if accept := expect("Accept"); accept.Actor != status.ActorURL {
+		t.Errorf("Accept from %s", accept.Actor)
+	}
+	if err := inbox(map[string]any{"id": alice + "/follow", "type": "Follow", "actor": srv.URL + "/users/mallory", "object": status.ActorURL}); err == nil {
+		t.Error("Follow signed by another actor was accepted")
+	}
+
+	store.changes = []db.ActivityPubChange{
+		{EventID: 5, Action: "favorited", TrackTitle: "Heroes & Villains", TrackArtist: "The Beach Boys"},
+		{EventID: 6, Action: "playlist_created", PlaylistID: 3, PlaylistName: "Private"},
+	}
+	store.play = &db.ActivityPubPlay{ID: 9, TrackTitle: "Kid A", TrackArtist: "Radiohead"}
+	if err := s.PublishAll(ctx); err != nil {
+		t.Fatalf("PublishAll() = %v", err)
+	}
+	create := expect("Create")
+	note, _ := create.Object.(map[string]any)
+	if content := note["content"]; content != "<p>Loved <em>Heroes &amp; Villains</em> by The Beach Boys</p>" {
+		t.Errorf("posted %v", content)
+	}
+	if len(store.posts) != 1 || store.actor.LastEventID != 6 || store.actor.LastPlayID != 9 {
+		t.Errorf("posts %+v, read up to event %d and play %d", store.posts, store.actor.LastEventID, store.actor.LastPlayID)
+	}
+
+	if err := inbox(map[string]any{"id": alice + "/undo", "type": "Undo", "actor": alice, "object": map[string]any{"type": "Follow"}}); err != nil {
+		t.Fatalf("Undo = %v", err)
+	}
+	if followers, _ := store.ListFollowers(ctx, userID); len(followers) != 0 {
+		t.Errorf("followers after Undo = %+v", followers)
+	}
+}
@@ -0,0 +1,207 @@
+package activitypub
+
+import (
+	"context"
+	"crypto"
+	"crypto/rand"
+	"crypto/rsa"
+	"crypto/sha256"
+	"crypto/x509"
+	"encoding/base64"
+	"encoding/pem"
+	"errors"
+	"fmt"
+	"net/http"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+// keyBits is the size of actor keys; Mastodon and most other servers expect
+// 2048-bit RSA.
+const keyBits = 2048
+
+// maxClockSkew bounds how far a signed request's Date may be from now, so a
+// captured request cannot be replayed much later.
+const maxClockSkew = time.Hour
+
+// signedHeaders are the headers outgoing requests sign; incoming requests
+// must sign at least these.
+var signedHeaders = []string{"(request-target)", "host", "date", "digest"}
+
+// ErrInvalidSignature is returned for a request whose HTTP signature is
+// missing, malformed, stale or wrong.
+var ErrInvalidSignature = errors.New("invalid HTTP signature")
+
+// GenerateKey returns a new actor key pair as PEM: the PKIX public key and
+// the PKCS#8 private key.
+func GenerateKey() (publicPEM, privatePEM string, err error) {
+	key, err := rsa.GenerateKey(rand.Reader, keyBits)
+	if err != nil {
+		return "", "", err
+	}
+	public, err := x509.MarshalPKIXPublicKey(&key.PublicKey)
+	if err != nil {
+		return "", "", err
+	}
+	private, err := x509.MarshalPKCS8PrivateKey(key)
+	if err != nil {
+		return "", "", err
+	}
+	return string(pem.EncodeToMemory(&pem.Block{Type: "PUBLIC KEY", Bytes: public})),
+		string(pem.EncodeToMemory(&pem.Block{Type: "PRIVATE KEY", Bytes: private})), nil
+}
+
+func parsePrivateKey(privatePEM string) (*rsa.PrivateKey, error) {
+	block, _ := pem.Decode([]byte(privatePEM))
+	if block == nil {
+		return nil, errors.New("private key is not PEM")
+	}
+	key, err := x509.ParsePKCS8PrivateKey(block.Bytes)
+	if err != nil {
+		return nil, err
+	}
+	rsaKey, ok := key.(*rsa.PrivateKey)
+	if !ok {
+		return nil, errors.New("private key is not RSA")
+	}
+	return rsaKey, nil
+}
+
+// parsePublicKey reads a PKIX or PKCS#1 RSA public key, the two forms actor
+// documents use.
+func parsePublicKey(publicPEM string) (*rsa.PublicKey, error) {
+	block, _ := pem.Decode([]byte(publicPEM))
+	if block == nil {
+		return nil, errors.New("public key is not PEM")
+	}
+	if block.Type == "RSA PUBLIC KEY" {
+		return x509.ParsePKCS1PublicKey(block.Bytes)
+	}
+	key, err := x509.ParsePKIXPublicKey(block.Bytes)
+	if err != nil {
+		return nil, err
+	}
+	rsaKey, ok := key.(*rsa.PublicKey)
+	if !ok {
+		return nil, errors.New("public key is not RSA")
+	}
+	return rsaKey, nil
+}
+
+func digest(body []byte) string {
+	sum := sha256.Sum256(body)
+	return "SHA-256=" + base64.StdEncoding.EncodeToString(sum[:])
+}
+
+// signingString is what a signature covers: each header on its own line,
+// with the pseudo-header (request-target) standing for the method and
+// target, the path and query the sender addressed.
+func signingString(req *http.Request, target string, headers []string) (string, error) {
+	lines := make([]string, 0, len(headers))
+	for _, name := range headers {
+		var value string
+		switch name {
+		case "(request-target)":
+			value = strings.ToLower(req.Method) + " " + target
+		case "host":
+			value = req.Host
+			if value == "" {
+				value = req.URL.Host
+			}
+		default:
+			values := req.Header.Values(name)
+			if len(values) == 0 {
+				return "", fmt.Errorf("%w: signed header %s is missing", ErrInvalidSignature, name)
+			}
+			value = strings.Join(values, ", ")
+		}
+		lines = append(lines, name+": "+value)
+	}
+	return strings.Join(lines, "\n"), nil
+}
+
+// Sign signs req, which will carry body, with the actor key keyID names, as
+// draft-cavage-http-signatures describes and Mastodon expects.
+func Sign(req *http.Request, body []byte, keyID string, key *rsa.PrivateKey, now time.Time) error {
+	req.Header.Set("Date", now.UTC().Format(http.TimeFormat))
+	req.Header.Set("Digest", digest(body))
+	str, err := signingString(req, req.URL.RequestURI(), signedHeaders)
+	if err != nil {
+		return err
+	}
+	hash := sha256.Sum256([]byte(str))
+	sig, err := rsa.SignPKCS1v15(rand.Reader, key, crypto.SHA256, hash[:])
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Signature", fmt.Sprintf(`keyId="%s",algorithm="rsa-sha256",headers="%s",signature="%s"`,
+		keyID, strings.Join(signedHeaders, " "), base64.StdEncoding.EncodeToString(sig)))
+	return nil
+}
+
+// KeyFetcher returns the public key keyID names and the actor that owns it.
+type KeyFetcher func(ctx context.Context, keyID string) (key *rsa.PublicKey, owner string, err error)
+
+// Verify checks the signature of req, whose body was read into body, and
+// returns the actor that signed it. The sender signed the path it addressed,
+// so the base path middleware.Proxy stripped is put back first.
+func Verify(ctx context.Context, req *http.Request, body []byte, now time.Time, fetch KeyFetcher) (string, error) {
+	params := parseSignature(req.Header.Get("Signature"))
+	keyID, headerList, signature := params["keyId"], params["headers"], params["signature"]
+	if keyID == "" || signature == "" {
+		return "", fmt.Errorf("%w: no keyId or signature", ErrInvalidSignature)
+	}
+	if algorithm := params["algorithm"]; algorithm != "" && algorithm != "rsa-sha256" && algorithm != "hs2019" {
+		return "", fmt.Errorf("%w: unsupported algorithm %s", ErrInvalidSignature, algorithm)
+	}
+	headers := strings.Fields(strings.ToLower(headerList))
+	for _, required := range signedHeaders {
+		if !slices.Contains(headers, required) {
+			return "", fmt.Errorf("%w: %s is not signed", ErrInvalidSignature, required)
+		}
+	}
+	date, err := http.ParseTime(req.Header.Get("Date"))
+	if err != nil || date.Sub(now).Abs() > maxClockSkew {
+		return "", fmt.Errorf("%w: date is missing or too far from now", ErrInvalidSignature)
+	}
+	if req.Header.Get("Digest") != digest(body) {
+		return "", fmt.Errorf("%w: digest does not match the body", ErrInvalidSignature)
+	}
+	target := middleware.Path(req, req.URL.EscapedPath())
+	if req.URL.RawQuery != "" {
+		target += "?" + req.URL.RawQuery
+	}
+	str, err := signingString(req, target, headers)
+	if err != nil {
+		return "", err
+	}
+	sig, err := base64.StdEncoding.DecodeString(signature)
+	if err != nil {
+		return "", fmt.Errorf("%w: signature is not base64", ErrInvalidSignature)
+	}
+	key, owner, err := fetch(ctx, keyID)
+	if err != nil {
+		return "", fmt.Errorf("%w: fetch key %s: %v", ErrInvalidSignature, keyID, err)
+	}
+	hash := sha256.Sum256([]byte(str))
+	if err := rsa.VerifyPKCS1v15(key, crypto.SHA256, hash[:], sig); err != nil {
+		return "", fmt.Errorf("%w: signature does not match key %s", ErrInvalidSignature, keyID)
+	}
+	return owner, nil
+}
+
+// parseSignature splits a Signature header into its quoted parameters.
+func parseSignature(header string) map[string]string {
+	params := make(map[string]string)
+	for _, part := range strings.Split(header, ",") {
+		name, value, ok := strings.Cut(strings.TrimSpace(part), "=")
+		if !ok {
+			continue
+		}
+		params[name] = strings.Trim(value, `"`)
+	}
+	return params
+}
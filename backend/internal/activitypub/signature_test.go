@@ -0,0 +1,95 @@
+package activitypub
+
+import (
+	"context"
+	"crypto/rsa"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+func TestSignAndVerify(t *testing.T) {
+	publicPEM, privatePEM, err := GenerateKey()
+	if err != nil {
+		t.Fatalf("GenerateKey() = %v", err)
+	}
+	private, err := parsePrivateKey(privatePEM)
+	if err != nil {
+		t.Fatalf("parsePrivateKey() = %v", err)
+	}
+	public, err := parsePublicKey(publicPEM)
+	if err != nil {
+		t.Fatalf("parsePublicKey() = %v", err)
+	}
+	const keyID = "https://remote.example/users/alice#main-key"
+	fetch := func(ctx context.Context, id string) (*rsa.PublicKey, string, error) {
+		if id != keyID {
+			t.Fatalf("fetched key %s", id)
+		}
+		return public, "https://remote.example/users/alice", nil
+	}
+	now := time.Date(2026, 3, 1, 12, 0, 0, 0, time.UTC)
+	newRequest := func(body string) *http.Request {
+		req := httptest.NewRequest(http.MethodPost, "https://music.example.com/ap/users/bob/inbox", strings.NewReader(body))
+		if err := Sign(req, []byte(body), keyID, private, now); err != nil {
+			t.Fatalf("Sign() = %v", err)
+		}
+		return req
+	}
+
+	body := `{"type":"Follow"}`
+	owner, err := Verify(context.Background(), newRequest(body), []byte(body), now.Add(time.Minute), fetch)
+	if err != nil || owner != "https://remote.example/users/alice" {
+		t.Fatalf("Verify() = %q, %v", owner, err)
+	}
+
+	if _, err := Verify(context.Background(), newRequest(body), []byte(`{"type":"Delete"}`), now, fetch); !errors.Is(err, ErrInvalidSignature) {
+		t.Errorf("Verify() of a changed body = %v, want ErrInvalidSignature", err)
+	}
+	if _, err := Verify(context.Background(), newRequest(body), []byte(body), now.Add(2*maxClockSkew), fetch); !errors.Is(err, ErrInvalidSignature) {
+		t.Errorf("Verify() of a stale request = %v, want ErrInvalidSignature", err)
+	}
+	req := newRequest(body)
+	req.URL.Path = "/ap/users/carol/inbox"
+	if _, err := Verify(context.Background(), req, []byte(body), now, fetch); !errors.Is(err, ErrInvalidSignature) {
+		t.Errorf("Verify() for another inbox = %v, want ErrInvalidSignature", err)
+	}
+}
+
+func TestVerifyUnderBasePath(t *testing.T) {
+	publicPEM, privatePEM, err := GenerateKey()
+	if err != nil {
+		t.Fatalf("GenerateKey() = %v", err)
+	}
+	private, _ := parsePrivateKey(privatePEM)
+	public, _ := parsePublicKey(publicPEM)
+	fetch := func(context.Context, string) (*rsa.PublicKey, string, error) {
+		return public, "https://remote.example/users/alice", nil
+	}
+	proxy, err := middleware.NewProxy(nil, "/music")
+	if err != nil {
+		t.Fatalf("NewProxy() = %v", err)
+	}
+	now := time.Date(2026, 3, 1, 12, 0, 0, 0, time.UTC)
+	body := `{"type":"Follow"}`
+	req := httptest.NewRequest(http.MethodPost, "https://music.example.com/music/ap/users/bob/inbox?x=1", strings.NewReader(body))
+	if err := Sign(req, []byte(body), "https://remote.example/users/alice#main-key", private, now); err != nil {
+		t.Fatalf("Sign() = %v", err)
+	}
+
+	var verifyErr error
+	proxy.Middleware(http.HandlerFunc(func(_ http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/ap/users/bob/inbox" {
+			t.Fatalf("path after the proxy = %q", r.URL.Path)
+		}
+		_, verifyErr = Verify(r.Context(), r, []byte(body), now, fetch)
+	})).ServeHTTP(httptest.NewRecorder(), req)
+	if verifyErr != nil {
+		t.Fatalf("Verify() under a base path = %v", verifyErr)
+	}
+}
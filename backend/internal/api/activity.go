@@ -0,0 +1,120 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+type activityFeed interface {
+	List(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.Activity, int, error)
+}
+
+// ActivityHandlers serve the caller's "what's new" feed: tracks added to
+// their library, changes to their playlists, downloads that finished or
+// failed, and the public playlists and shared listening of users they
+// follow. Clients that need every change in order sync from the change
+// journal instead.
+type ActivityHandlers struct {
+	activity activityFeed
+}
+
+func NewActivityHandlers(activity activityFeed) *ActivityHandlers {
+	return &ActivityHandlers{activity: activity}
+}
+
+// ActivityUser is the followed user an activity is about.
+type ActivityUser struct {
+	ID       uuid.UUID `json:"id"`
+	Username string    `json:"username"`
+}
+
+type ActivityPlaylist struct {
+	ID   int64  `json:"id"`
+	Name string `json:"name"`
+}
+
+// ActivityDownload is the job behind a download activity. A failed job is
+// explained the way the job endpoints explain it, not with the raw error.
+type ActivityDownload struct {
+	JobID           uuid.UUID `json:"jobId"`
+	Title           string    `json:"title,omitempty"`
+	Artist          string    `json:"artist,omitempty"`
+	Error           string    `json:"error,omitempty"`
+	FailureCategory string    `json:"failureCategory,omitempty"`
+	Remediation     string    `json:"remediation,omitempty"`
+}
+
+// ActivityResponse is one feed entry. TrackIDs lists up to ten of the
+// TrackCount tracks it is about. User is set when the entry is another
+// user's activity rather than the caller's.
+type ActivityResponse struct {
+	Kind       string            `json:"kind"`
+	OccurredAt time.Time         `json:"occurredAt"`
+	TrackCount int               `json:"trackCount"`
+	TrackIDs   []int64           `json:"trackIds"`
+	User       *ActivityUser     `json:"user,omitempty"`
+	Playlist   *ActivityPlaylist `json:"playlist,omitempty"`
+	Download   *ActivityDownload `json:"download,omitempty"`
+}
+
+type ActivityListResponse struct {
+	Items  []ActivityResponse `json:"items"`
+	Total  int                `json:"total"`
+	Limit  int                `json:"limit"`
+	Offset int                `json:"offset"`
+}
+
+// ListActivity handles GET /api/v1/activity?limit=&offset=.
+func (h *ActivityHandlers) ListActivity(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	limit, offset := query.Limit(50), query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
+	activity, total, err := h.activity.List(r.Context(), userCtx.UserID, limit, offset)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list activity")
+		return
+	}
+	resp := ActivityListResponse{Items: make([]ActivityResponse, 0, len(activity)), Total: total, Limit: limit, Offset: offset}
+	for _, a := range activity {
+		resp.Items = append(resp.Items, newActivityResponse(a))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+func newActivityResponse(a db.Activity) ActivityResponse {
+	resp := ActivityResponse{Kind: a.Kind, OccurredAt: a.OccurredAt, TrackCount: a.TrackCount, TrackIDs: a.TrackIDs}
+	if resp.TrackIDs == nil {
+		resp.TrackIDs = []int64{}
+	}
+	if a.UserID.Valid {
+		resp.User = &ActivityUser{ID: a.UserID.UUID, Username: a.Username}
+	}
+	if a.PlaylistID.Valid {
+		resp.Playlist = &ActivityPlaylist{ID: a.PlaylistID.Int64, Name: a.PlaylistName}
+	}
+	if a.JobID.Valid {
+		resp.Download = &ActivityDownload{JobID: a.JobID.UUID, Title: a.Title, Artist: a.Artist}
+		if a.Kind == db.ActivityDownloadFailed {
+			job := download.DownloadJob{Status: download.StatusFailed, Error: a.Error}
+			failure := job.Failure()
+			resp.Download.Error = failure.Message
+			resp.Download.FailureCategory = failure.Category
+			resp.Download.Remediation = failure.Remediation
+		}
+	}
+	return resp
+}
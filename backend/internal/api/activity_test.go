@@ -0,0 +1,60 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+type fakeActivityFeed struct {
+	limit, offset int
+}
+
+func (f *fakeActivityFeed) List(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.Activity, int, error) {
+	f.limit, f.offset = limit, offset
+	now := time.Now()
+	return []db.Activity{
+		{Kind: db.ActivityDownloadFailed, OccurredAt: now, JobID: uuid.NullUUID{UUID: uuid.New(), Valid: true}, Title: "Song", Error: "yt-dlp failed: exit status 1: ERROR: [youtube] abc: Sign in to confirm your age"},
+		{Kind: db.EventPlaylistTracksAdded, OccurredAt: now.Add(-time.Minute), PlaylistID: sql.NullInt64{Int64: 7, Valid: true}, PlaylistName: "Road Trip", TrackCount: 2, TrackIDs: []int64{3, 4}},
+		{Kind: db.ActivityTracksAdded, OccurredAt: now.Add(-time.Hour), TrackCount: 12, TrackIDs: []int64{1, 2}},
+	}, 23, nil
+}
+
+func TestListActivitySummarizesTheFeed(t *testing.T) {
+	feed := &fakeActivityFeed{}
+	h := NewActivityHandlers(feed)
+	rec := httptest.NewRecorder()
+	h.ListActivity(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/activity?limit=3&offset=6", nil), uuid.New()))
+	body := rec.Body.String()
+	if rec.Code != http.StatusOK || feed.limit != 3 || feed.offset != 6 || !strings.Contains(body, `"total":23`) {
+		t.Fatalf("list = %d %s (limit %d, offset %d)", rec.Code, body, feed.limit, feed.offset)
+	}
+	for _, want := range []string{
+		`"failureCategory":"` + download.FailureAgeRestricted + `"`,
+		`"playlist":{"id":7,"name":"Road Trip"}`,
+		`"kind":"tracks_added","occurredAt":`,
+		`"trackCount":12,"trackIds":[1,2]`,
+	} {
+		if !strings.Contains(body, want) {
+			t.Errorf("response lacks %s: %s", want, body)
+		}
+	}
+	if strings.Contains(body, "yt-dlp") {
+		t.Errorf("response leaks the raw download error: %s", body)
+	}
+
+	rec = httptest.NewRecorder()
+	h.ListActivity(rec, httptest.NewRequest(http.MethodGet, "/api/v1/activity", nil))
+	if rec.Code != http.StatusUnauthorized {
+		t.Fatalf("anonymous list = %d", rec.Code)
+	}
+}
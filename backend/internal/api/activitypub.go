@@ -0,0 +1,239 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"io"
+	"log"
+	"net/http"
+	"strconv"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/activitypub"
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// maxInboxBodyBytes bounds an activity delivered to an inbox.
+const maxInboxBodyBytes = 1 << 20
+
+// fediverseService is what the handlers need of activitypub.Service.
+type fediverseService interface {
+	Enable(ctx context.Context, userID uuid.UUID, settings activitypub.Settings) (*activitypub.Status, error)
+	Status(ctx context.Context, userID uuid.UUID) (*activitypub.Status, error)
+	Disable(ctx context.Context, userID uuid.UUID) error
+	WebFinger(ctx context.Context, resource string) (*activitypub.WebFinger, error)
+	Actor(ctx context.Context, handle string) (*activitypub.Actor, error)
+	Followers(ctx context.Context, handle string) (*activitypub.OrderedCollection, error)
+	Outbox(ctx context.Context, handle string) (*activitypub.OrderedCollection, error)
+	Post(ctx context.Context, handle string, id int64) (*activitypub.Note, error)
+	HandleInbox(ctx context.Context, handle string, req *http.Request, body []byte) error
+}
+
+// ActivityPubHandlers serve the experimental fediverse actors: the
+// WebFinger and ActivityPub documents remote servers read, the inbox they
+// deliver follows to, and the settings a user publishes with.
+type ActivityPubHandlers struct {
+	service fediverseService
+}
+
+func NewActivityPubHandlers(service fediverseService) *ActivityPubHandlers {
+	return &ActivityPubHandlers{service: service}
+}
+
+// FediverseRequest is the handle a user publishes under and what they
+// publish.
+type FediverseRequest struct {
+	Handle           string `json:"handle"`
+	PublishListening bool   `json:"publishListening"`
+	PublishLoved     bool   `json:"publishLoved"`
+	PublishPlaylists bool   `json:"publishPlaylists"`
+}
+
+// FediverseResponse is the caller's actor. Address is what fediverse users
+// search for to follow it.
+type FediverseResponse struct {
+	Handle           string `json:"handle"`
+	Address          string `json:"address"`
+	ActorURL         string `json:"actorUrl"`
+	PublishListening bool   `json:"publishListening"`
+	PublishLoved     bool   `json:"publishLoved"`
+	PublishPlaylists bool   `json:"publishPlaylists"`
+	Followers        int    `json:"followers"`
+}
+
+func fediverseResponse(status *activitypub.Status) FediverseResponse {
+	return FediverseResponse{
+		Handle:           status.Actor.Handle,
+		Address:          status.Address,
+		ActorURL:         status.ActorURL,
+		PublishListening: status.Actor.PublishListening,
+		PublishLoved:     status.Actor.PublishLoved,
+		PublishPlaylists: status.Actor.PublishPlaylists,
+		Followers:        status.Followers,
+	}
+}
+
+// GetFediverse handles GET /api/v1/me/fediverse.
+func (h *ActivityPubHandlers) GetFediverse(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	status, err := h.service.Status(r.Context(), userCtx.UserID)
+	if errors.Is(err, db.ErrActivityPubActorNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "fediverse publishing is not enabled")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load fediverse settings")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, fediverseResponse(status))
+}
+
+// PutFediverse handles PUT /api/v1/me/fediverse: it turns publishing on, or
+// changes the handle and what is published.
+func (h *ActivityPubHandlers) PutFediverse(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req FediverseRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	status, err := h.service.Enable(r.Context(), userCtx.UserID, activitypub.Settings{
+		Handle:           req.Handle,
+		PublishListening: req.PublishListening,
+		PublishLoved:     req.PublishLoved,
+		PublishPlaylists: req.PublishPlaylists,
+	})
+	switch {
+	case errors.Is(err, activitypub.ErrInvalidHandle):
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+	case errors.Is(err, db.ErrActivityPubHandleTaken):
+		writeLibraryError(w, http.StatusConflict, "CONFLICT", "handle is already in use")
+	case err != nil:
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to save fediverse settings")
+	default:
+		writeLibraryJSON(w, http.StatusOK, fediverseResponse(status))
+	}
+}
+
+// DeleteFediverse handles DELETE /api/v1/me/fediverse: it removes the
+// caller's actor, followers and posts.
+func (h *ActivityPubHandlers) DeleteFediverse(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	err := h.service.Disable(r.Context(), userCtx.UserID)
+	if errors.Is(err, db.ErrActivityPubActorNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "fediverse publishing is not enabled")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to disable fediverse publishing")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// WebFinger handles GET /.well-known/webfinger.
+func (h *ActivityPubHandlers) WebFinger(w http.ResponseWriter, r *http.Request) {
+	finger, err := h.service.WebFinger(r.Context(), r.URL.Query().Get("resource"))
+	if h.writeFederationError(w, err) {
+		return
+	}
+	w.Header().Set("Content-Type", "application/jrd+json")
+	writeActivityJSON(w, finger)
+}
+
+// Actor handles GET /ap/users/{handle}.
+func (h *ActivityPubHandlers) Actor(w http.ResponseWriter, r *http.Request) {
+	actor, err := h.service.Actor(r.Context(), r.PathValue("handle"))
+	if h.writeFederationError(w, err) {
+		return
+	}
+	writeActivityJSON(w, actor)
+}
+
+// Outbox handles GET /ap/users/{handle}/outbox.
+func (h *ActivityPubHandlers) Outbox(w http.ResponseWriter, r *http.Request) {
+	outbox, err := h.service.Outbox(r.Context(), r.PathValue("handle"))
+	if h.writeFederationError(w, err) {
+		return
+	}
+	writeActivityJSON(w, outbox)
+}
+
+// Followers handles GET /ap/users/{handle}/followers.
+func (h *ActivityPubHandlers) Followers(w http.ResponseWriter, r *http.Request) {
+	followers, err := h.service.Followers(r.Context(), r.PathValue("handle"))
+	if h.writeFederationError(w, err) {
+		return
+	}
+	writeActivityJSON(w, followers)
+}
+
+// Post handles GET /ap/users/{handle}/posts/{id}.
+func (h *ActivityPubHandlers) Post(w http.ResponseWriter, r *http.Request) {
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "post not found")
+		return
+	}
+	note, err := h.service.Post(r.Context(), r.PathValue("handle"), id)
+	if h.writeFederationError(w, err) {
+		return
+	}
+	writeActivityJSON(w, note)
+}
+
+// Inbox handles POST /ap/users/{handle}/inbox. Deliveries must carry a valid
+// HTTP signature of the activity's actor.
+func (h *ActivityPubHandlers) Inbox(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(http.MaxBytesReader(w, r.Body, maxInboxBodyBytes))
+	if err != nil {
+		writeLibraryError(w, http.StatusRequestEntityTooLarge, "REQUEST_TOO_LARGE", "activity is too large")
+		return
+	}
+	if h.writeFederationError(w, h.service.HandleInbox(r.Context(), r.PathValue("handle"), r, body)) {
+		return
+	}
+	w.WriteHeader(http.StatusAccepted)
+}
+
+// writeFederationError writes the response for err, if any, and reports
+// whether it did.
+func (h *ActivityPubHandlers) writeFederationError(w http.ResponseWriter, err error) bool {
+	switch {
+	case err == nil:
+		return false
+	case errors.Is(err, activitypub.ErrNotFound):
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "actor not found")
+	case errors.Is(err, activitypub.ErrInvalidSignature):
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", err.Error())
+	case errors.Is(err, activitypub.ErrInvalidActivity):
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+	default:
+		log.Printf("ActivityPub request failed: %v", err)
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to handle ActivityPub request")
+	}
+	return true
+}
+
+func writeActivityJSON(w http.ResponseWriter, data any) {
+	if w.Header().Get("Content-Type") == "" {
+		w.Header().Set("Content-Type", activitypub.ContentType)
+	}
+	w.WriteHeader(http.StatusOK)
+	json.NewEncoder(w).Encode(data)
+}
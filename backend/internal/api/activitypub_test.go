@@ -0,0 +1,109 @@
+package api
+
+import (
+	"context"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/activitypub"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeFediverse struct {
+	actor *db.ActivityPubActor
+}
+
+func (f *fakeFediverse) Enable(ctx context.Context, userID uuid.UUID, settings activitypub.Settings) (*activitypub.Status, error) {
+	if !activitypub.ValidHandle(settings.Handle) {
+		return nil, activitypub.ErrInvalidHandle
+	}
+	if settings.Handle == "taken" {
+		return nil, db.ErrActivityPubHandleTaken
+	}
+	f.actor = &db.ActivityPubActor{UserID: userID, Handle: settings.Handle, PublishLoved: settings.PublishLoved}
+	return f.Status(ctx, userID)
+}
+
+func (f *fakeFediverse) Status(ctx context.Context, userID uuid.UUID) (*activitypub.Status, error) {
+	if f.actor == nil {
+		return nil, db.ErrActivityPubActorNotFound
+	}
+	return &activitypub.Status{Actor: f.actor, Address: f.actor.Handle + "@music.example.com"}, nil
+}
+
+func (f *fakeFediverse) Disable(ctx context.Context, userID uuid.UUID) error {
+	return nil
+}
+
+func (f *fakeFediverse) WebFinger(ctx context.Context, resource string) (*activitypub.WebFinger, error) {
+	return nil, activitypub.ErrNotFound
+}
+
+func (f *fakeFediverse) Actor(ctx context.Context, handle string) (*activitypub.Actor, error) {
+	if f.actor == nil || f.actor.Handle != handle {
+		return nil, activitypub.ErrNotFound
+	}
+	return &activitypub.Actor{ID: "https://music.example.com/ap/users/" + handle, Type: "Person"}, nil
+}
+
+func (f *fakeFediverse) Followers(ctx context.Context, handle string) (*activitypub.OrderedCollection, error) {
+	return nil, activitypub.ErrNotFound
+}
+
+func (f *fakeFediverse) Outbox(ctx context.Context, handle string) (*activitypub.OrderedCollection, error) {
+	return nil, activitypub.ErrNotFound
+}
+
+func (f *fakeFediverse) Post(ctx context.Context, handle string, id int64) (*activitypub.Note, error) {
+	return nil, activitypub.ErrNotFound
+}
+
+func (f *fakeFediverse) HandleInbox(ctx context.Context, handle string, req *http.Request, body []byte) error {
+	return fmt.Errorf("%w: no Signature header", activitypub.ErrInvalidSignature)
+}
+
+func TestActivityPubHandlers(t *testing.T) {
+	userID := uuid.New()
+	h := NewActivityPubHandlers(&fakeFediverse{})
+	put := func(body string) *httptest.ResponseRecorder {
+		rec := httptest.NewRecorder()
+		h.PutFediverse(rec, withUser(httptest.NewRequest(http.MethodPut, "/api/v1/me/fediverse", strings.NewReader(body)), userID))
+		return rec
+	}
+
+	rec := httptest.NewRecorder()
+	h.GetFediverse(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/fediverse", nil), userID))
+	if rec.Code != http.StatusNotFound {
+		t.Fatalf("get before enabling = %d", rec.Code)
+	}
+	if rec := put(`{"handle":"Not Valid"}`); rec.Code != http.StatusBadRequest {
+		t.Errorf("invalid handle = %d", rec.Code)
+	}
+	if rec := put(`{"handle":"taken"}`); rec.Code != http.StatusConflict {
+		t.Errorf("taken handle = %d", rec.Code)
+	}
+	if rec := put(`{"handle":"bob","publishLoved":true}`); rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"address":"bob@music.example.com"`) {
+		t.Fatalf("enable = %d %s", rec.Code, rec.Body.String())
+	}
+
+	req := httptest.NewRequest(http.MethodGet, "/ap/users/bob", nil)
+	req.SetPathValue("handle", "bob")
+	rec = httptest.NewRecorder()
+	h.Actor(rec, req)
+	if rec.Code != http.StatusOK || rec.Header().Get("Content-Type") != activitypub.ContentType {
+		t.Errorf("actor = %d %s", rec.Code, rec.Header().Get("Content-Type"))
+	}
+
+	req = httptest.NewRequest(http.MethodPost, "/ap/users/bob/inbox", strings.NewReader(`{"type":"Follow"}`))
+	req.SetPathValue("handle", "bob")
+	rec = httptest.NewRecorder()
+	h.Inbox(rec, req)
+	if rec.Code != http.StatusUnauthorized {
+		t.Errorf("unsigned delivery = %d", rec.Code)
+	}
+}
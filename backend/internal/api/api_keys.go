@@ -0,0 +1,155 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	maxAPIKeyNameLength = 100
+	maxAPIKeysPerUser   = 20
+)
+
+type apiKeyRepository interface {
+	auth.APIKeyAuthenticator
+	List(ctx context.Context, userID uuid.UUID) ([]db.APIKey, error)
+	Create(ctx context.Context, key *db.APIKey) error
+	Delete(ctx context.Context, userID, id uuid.UUID) error
+}
+
+// APIKeyHandlers let users issue and revoke the API keys browser extensions
+// and bookmarklets quick-add downloads with. A key opens no other route.
+type APIKeyHandlers struct {
+	keys apiKeyRepository
+}
+
+func NewAPIKeyHandlers(keys apiKeyRepository) *APIKeyHandlers {
+	return &APIKeyHandlers{keys: keys}
+}
+
+type CreateAPIKeyRequest struct {
+	Name string `json:"name"`
+}
+
+// APIKeyResponse describes a key. Key is only set in the response creating
+// it; afterwards the prefix is all that identifies it.
+type APIKeyResponse struct {
+	ID         uuid.UUID  `json:"id"`
+	Name       string     `json:"name"`
+	Prefix     string     `json:"prefix"`
+	Key        string     `json:"key,omitempty"`
+	CreatedAt  time.Time  `json:"createdAt"`
+	LastUsedAt *time.Time `json:"lastUsedAt,omitempty"`
+}
+
+type APIKeysResponse struct {
+	Keys []APIKeyResponse `json:"keys"`
+}
+
+// Authenticate admits requests carrying one of a user's API keys.
+func (h *APIKeyHandlers) Authenticate(next http.HandlerFunc) http.HandlerFunc {
+	return auth.APIKeyMiddleware(h.keys)(next).ServeHTTP
+}
+
+// ListKeys handles GET /api/v1/me/api-keys.
+func (h *APIKeyHandlers) ListKeys(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	keys, err := h.keys.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list API keys")
+		return
+	}
+	resp := APIKeysResponse{Keys: make([]APIKeyResponse, 0, len(keys))}
+	for _, key := range keys {
+		resp.Keys = append(resp.Keys, newAPIKeyResponse(key))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// CreateKey handles POST /api/v1/me/api-keys. The response carries the key,
+// which cannot be read again.
+func (h *APIKeyHandlers) CreateKey(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req CreateAPIKeyRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	name := strings.TrimSpace(req.Name)
+	if name == "" || utf8.RuneCountInString(name) > maxAPIKeyNameLength {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name is required and must be at most 100 characters")
+		return
+	}
+	existing, err := h.keys.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list API keys")
+		return
+	}
+	if len(existing) >= maxAPIKeysPerUser {
+		writeLibraryError(w, http.StatusConflict, "API_KEY_LIMIT", "revoke an API key before creating another")
+		return
+	}
+
+	secret, hash, prefix, err := auth.NewAPIKey()
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to generate API key")
+		return
+	}
+	key := db.APIKey{ID: uuid.New(), UserID: userCtx.UserID, Name: name, KeyPrefix: prefix, KeyHash: hash}
+	if err := h.keys.Create(r.Context(), &key); err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to create API key")
+		return
+	}
+	resp := newAPIKeyResponse(key)
+	resp.Key = secret
+	writeLibraryJSON(w, http.StatusCreated, resp)
+}
+
+// DeleteKey handles DELETE /api/v1/me/api-keys/{id}.
+func (h *APIKeyHandlers) DeleteKey(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := uuid.Parse(r.PathValue("id"))
+	if err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid API key ID")
+		return
+	}
+	err = h.keys.Delete(r.Context(), userCtx.UserID, id)
+	if errors.Is(err, db.ErrAPIKeyNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "API_KEY_NOT_FOUND", "API key not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to revoke API key")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func newAPIKeyResponse(key db.APIKey) APIKeyResponse {
+	resp := APIKeyResponse{ID: key.ID, Name: key.Name, Prefix: key.KeyPrefix, CreatedAt: key.CreatedAt}
+	if key.LastUsedAt.Valid {
+		resp.LastUsedAt = &key.LastUsedAt.Time
+	}
+	return resp
+}
@@ -0,0 +1,134 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// artworkURLTTL is longer than the playback TTL: artwork URLs end up in image
+// caches and list views that outlive a single playback session.
+const artworkURLTTL = time.Hour
+
+type artworkStore interface {
+	GetForTrack(ctx context.Context, trackID int64) (*db.Artwork, error)
+}
+
+type artworkURLSigner interface {
+	PresignGetObject(ctx context.Context, key string, expires time.Duration) (string, error)
+}
+
+// ArtworkHandlers serves processed track artwork.
+type ArtworkHandlers struct {
+	artwork     artworkStore
+	libraryRepo playbackLibraryRepository
+	storage     artworkURLSigner
+	now         func() time.Time
+}
+
+func NewArtworkHandlers(artwork artworkStore, libraryRepo playbackLibraryRepository, storageClient artworkURLSigner) *ArtworkHandlers {
+	return &ArtworkHandlers{
+		artwork:     artwork,
+		libraryRepo: libraryRepo,
+		storage:     storageClient,
+		now:         time.Now,
+	}
+}
+
+type ArtworkVariantResponse struct {
+	Name      string `json:"name"`
+	URL       string `json:"url"`
+	Width     int    `json:"width"`
+	Height    int    `json:"height"`
+	SizeBytes int64  `json:"sizeBytes"`
+}
+
+type ArtworkResponse struct {
+	TrackID       int64                    `json:"trackId"`
+	Width         int                      `json:"width"`
+	Height        int                      `json:"height"`
+	Blurhash      string                   `json:"blurhash"`
+	DominantColor string                   `json:"dominantColor"`
+	Palette       []string                 `json:"palette"`
+	Variants      []ArtworkVariantResponse `json:"variants"`
+	ExpiresAt     time.Time                `json:"expiresAt"`
+}
+
+// GetTrackArtwork handles GET /api/v1/tracks/{track_id}/artwork. It returns
+// the placeholder, palette, and signed variant URLs; with ?size=<variant> it
+// redirects to that variant instead.
+func (h *ArtworkHandlers) GetTrackArtwork(w http.ResponseWriter, r *http.Request) {
+	if h == nil || h.artwork == nil || h.libraryRepo == nil || h.storage == nil {
+		writeLibraryError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "artwork is unavailable")
+		return
+	}
+	_, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	query := newQueryParams(r)
+	size := query.Enum("size", "", "thumb", "small", "medium", "large")
+	if !query.Valid(w, r) {
+		return
+	}
+
+	artwork, err := h.artwork.GetForTrack(r.Context(), trackID)
+	if err != nil {
+		if errors.Is(err, db.ErrArtworkNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "ARTWORK_NOT_FOUND", "track has no processed artwork")
+			return
+		}
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load artwork")
+		return
+	}
+
+	if size != "" {
+		for _, variant := range artwork.Variants {
+			if variant.Name != size {
+				continue
+			}
+			url, err := h.storage.PresignGetObject(r.Context(), variant.StorageKey, artworkURLTTL)
+			if err != nil {
+				writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to issue artwork URL")
+				return
+			}
+			w.Header().Set("Cache-Control", "private, max-age=300")
+			http.Redirect(w, r, url, http.StatusFound)
+			return
+		}
+		writeLibraryError(w, http.StatusNotFound, "ARTWORK_NOT_FOUND", "artwork size not available")
+		return
+	}
+
+	resp := ArtworkResponse{
+		TrackID:       trackID,
+		Width:         artwork.Width,
+		Height:        artwork.Height,
+		Blurhash:      artwork.Blurhash,
+		DominantColor: artwork.DominantColor,
+		Palette:       artwork.Palette,
+		Variants:      make([]ArtworkVariantResponse, 0, len(artwork.Variants)),
+		ExpiresAt:     h.now().Add(artworkURLTTL).UTC(),
+	}
+	if resp.Palette == nil {
+		resp.Palette = []string{}
+	}
+	for _, variant := range artwork.Variants {
+		url, err := h.storage.PresignGetObject(r.Context(), variant.StorageKey, artworkURLTTL)
+		if err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to issue artwork URL")
+			return
+		}
+		resp.Variants = append(resp.Variants, ArtworkVariantResponse{
+			Name:      variant.Name,
+			URL:       url,
+			Width:     variant.Width,
+			Height:    variant.Height,
+			SizeBytes: variant.SizeBytes,
+		})
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
@@ -0,0 +1,122 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeArtworkStore struct {
+	byTrack map[int64]*db.Artwork
+}
+
+func (f *fakeArtworkStore) GetForTrack(ctx context.Context, trackID int64) (*db.Artwork, error) {
+	artwork, ok := f.byTrack[trackID]
+	if !ok {
+		return nil, db.ErrArtworkNotFound
+	}
+	return artwork, nil
+}
+
+func newArtworkTestHandlers() (*ArtworkHandlers, *fakePlaybackStorage) {
+	signer := &fakePlaybackStorage{}
+	h := NewArtworkHandlers(
+		&fakeArtworkStore{byTrack: map[int64]*db.Artwork{
+			7: {
+				ID:            1,
+				Width:         1000,
+				Height:        1000,
+				Blurhash:      "LEHV6nWB2yk8pyo0adR*.7kCMdnj",
+				DominantColor: "#dc141e",
+				Palette:       []string{"#dc141e", "#0a28c8"},
+				Variants: []db.ArtworkVariant{
+					{Name: "thumb", StorageKey: "artwork/abc/thumb.jpg", Width: 128, Height: 128, SizeBytes: 4000},
+					{Name: "large", StorageKey: "artwork/abc/large.jpg", Width: 1000, Height: 1000, SizeBytes: 90000},
+				},
+			},
+		}},
+		&fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true, 8: true}},
+		signer,
+	)
+	h.now = func() time.Time { return time.Date(2026, 1, 2, 3, 4, 5, 0, time.UTC) }
+	return h, signer
+}
+
+func artworkRequest(h *ArtworkHandlers, trackID, query string) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/tracks/"+trackID+"/artwork"+query, nil)
+	req.SetPathValue("track_id", trackID)
+	rec := httptest.NewRecorder()
+	h.GetTrackArtwork(rec, withUser(req, uuid.New()))
+	return rec
+}
+
+func TestGetTrackArtworkReturnsPlaceholderPaletteAndSignedVariants(t *testing.T) {
+	h, signer := newArtworkTestHandlers()
+	rec := artworkRequest(h, "7", "")
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp ArtworkResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Blurhash == "" || resp.DominantColor != "#dc141e" || len(resp.Palette) != 2 {
+		t.Fatalf("artwork = %#v", resp)
+	}
+	if len(resp.Variants) != 2 || resp.Variants[0].Name != "thumb" || resp.Variants[0].URL == "" {
+		t.Fatalf("variants = %#v", resp.Variants)
+	}
+	if signer.lastTTL != artworkURLTTL || !resp.ExpiresAt.Equal(h.now().Add(artworkURLTTL)) {
+		t.Fatalf("ttl = %v, expiresAt = %v", signer.lastTTL, resp.ExpiresAt)
+	}
+}
+
+func TestGetTrackArtworkSizeRedirects(t *testing.T) {
+	h, signer := newArtworkTestHandlers()
+	rec := artworkRequest(h, "7", "?size=large")
+	if rec.Code != http.StatusFound {
+		t.Fatalf("status = %d, want 302 (body=%s)", rec.Code, rec.Body.String())
+	}
+	if len(signer.presignKeys) != 1 || signer.presignKeys[0] != "artwork/abc/large.jpg" {
+		t.Fatalf("signed keys = %v, want only the large variant", signer.presignKeys)
+	}
+	if rec.Header().Get("Location") == "" {
+		t.Fatal("redirect has no Location")
+	}
+
+	if rec := artworkRequest(h, "7", "?size=medium"); rec.Code != http.StatusNotFound {
+		t.Fatalf("missing size status = %d, want 404", rec.Code)
+	}
+	if rec := artworkRequest(h, "7", "?size=huge"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("unknown size status = %d, want 400", rec.Code)
+	}
+}
+
+func TestGetTrackArtworkNotFound(t *testing.T) {
+	h, _ := newArtworkTestHandlers()
+	for _, tc := range []struct {
+		trackID string
+		code    string
+	}{
+		{"9", "TRACK_NOT_FOUND"},
+		{"8", "ARTWORK_NOT_FOUND"},
+	} {
+		rec := artworkRequest(h, tc.trackID, "")
+		if rec.Code != http.StatusNotFound {
+			t.Fatalf("track %s status = %d, want 404", tc.trackID, rec.Code)
+		}
+		var problem struct {
+			Code string `json:"code"`
+		}
+		if err := json.Unmarshal(rec.Body.Bytes(), &problem); err != nil || problem.Code != tc.code {
+			t.Fatalf("track %s code = %q (%v), want %s", tc.trackID, problem.Code, err, tc.code)
+		}
+	}
+}
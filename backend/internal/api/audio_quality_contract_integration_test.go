@@ -206,6 +206,7 @@ func TestIngestAndBackfillExposeStoredObjectFFprobeFactsThroughLibraryAPI(t *tes
 		contentTypes: make(map[string]string),
 	}
 	jobProcessor := processor.New(&processor.ProcessorConfig{
+		DB:          database,
 		TrackRepo:   trackRepo,
 		LibraryRepo: libraryRepo,
 		Storage:     objectStore,
@@ -326,6 +327,7 @@ func TestIngestQualityContractAgainstRealMinIO(t *testing.T) {
 	key := "tracks/fixture/" + jobID + ".wav"
 	t.Cleanup(func() { _ = minioClient.DeleteObject(context.Background(), key) })
 	jobProcessor := processor.New(&processor.ProcessorConfig{
+		DB:          database,
 		TrackRepo:   trackRepo,
 		LibraryRepo: libraryRepo,
 		Storage:     minioClient,
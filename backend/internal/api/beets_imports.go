@@ -0,0 +1,66 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/importers"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type beetsImportQueue interface {
+	Enqueue(ctx context.Context, userID uuid.UUID, payload importers.BeetsImportPayload) (*jobs.Job, error)
+}
+
+// BeetsImportHandlers queue imports from the beets library the operator
+// configured, so beets users can adopt the server without re-tagging.
+type BeetsImportHandlers struct {
+	queue beetsImportQueue
+}
+
+func NewBeetsImportHandlers(queue beetsImportQueue) *BeetsImportHandlers {
+	return &BeetsImportHandlers{queue: queue}
+}
+
+type BeetsImportRequest struct {
+	Mode  string   `json:"mode"`
+	Query []string `json:"query"`
+	Path  string   `json:"path"`
+}
+
+// CreateImport handles POST /api/v1/imports/beets.
+func (h *BeetsImportHandlers) CreateImport(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req BeetsImportRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	job, err := h.queue.Enqueue(r.Context(), userCtx.UserID, importers.BeetsImportPayload{
+		Mode:  req.Mode,
+		Query: req.Query,
+		Path:  req.Path,
+	})
+	switch {
+	case errors.Is(err, importers.ErrInvalidBeetsImport):
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	case errors.Is(err, jobs.ErrDuplicate):
+		writeMaintenanceError(w, http.StatusConflict, "JOB_ACTIVE", "a beets import is already queued or running")
+		return
+	case err != nil:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue beets import")
+		return
+	}
+	w.Header().Set("Location", middleware.Path(r, "/api/v1/jobs/"+job.ID.String()))
+	writeMaintenanceJSON(w, http.StatusAccepted, jobResponse(job.Summary()))
+}
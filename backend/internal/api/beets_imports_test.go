@@ -0,0 +1,64 @@
+package api
+
+import (
+	"context"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/importers"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+type fakeBeetsImportQueue struct {
+	payloads []importers.BeetsImportPayload
+}
+
+func (f *fakeBeetsImportQueue) Enqueue(ctx context.Context, userID uuid.UUID, payload importers.BeetsImportPayload) (*jobs.Job, error) {
+	switch {
+	case payload.Mode == "folder" && len(payload.Query) > 0:
+		return nil, fmt.Errorf("%w: query applies to library imports", importers.ErrInvalidBeetsImport)
+	case len(f.payloads) > 0:
+		return nil, jobs.ErrDuplicate
+	}
+	f.payloads = append(f.payloads, payload)
+	return &jobs.Job{ID: uuid.New(), Kind: "beets_import", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued, MaxAttempts: 3}, nil
+}
+
+func TestBeetsImportsQueueJobs(t *testing.T) {
+	queue := &fakeBeetsImportQueue{}
+	h := NewBeetsImportHandlers(queue)
+	userID := uuid.New()
+	create := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/imports/beets", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.CreateImport(rec, withUser(req, userID))
+		return rec
+	}
+
+	rec := create(`{"query":["artist:Low","year:1994"]}`)
+	if rec.Code != http.StatusAccepted || !strings.Contains(rec.Body.String(), `"kind":"beets_import"`) || !strings.HasPrefix(rec.Header().Get("Location"), "/api/v1/jobs/") {
+		t.Fatalf("create = %d %s", rec.Code, rec.Body.String())
+	}
+	if got := queue.payloads[0]; got.Mode != "" || len(got.Query) != 2 || got.Query[1] != "year:1994" {
+		t.Fatalf("payload = %+v", got)
+	}
+
+	tests := []struct {
+		body string
+		want int
+	}{
+		{`{"mode":"library"}`, http.StatusConflict},
+		{`{"mode":"folder","query":["artist:Low"]}`, http.StatusBadRequest},
+		{`{"mode":"folder","directory":"/etc"}`, http.StatusBadRequest},
+	}
+	for _, tt := range tests {
+		if rec := create(tt.body); rec.Code != tt.want {
+			t.Errorf("%s: status = %d, want %d", tt.body, rec.Code, tt.want)
+		}
+	}
+}
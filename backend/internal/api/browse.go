@@ -1,11 +1,21 @@
 package api
 
 import (
+	"context"
 	"encoding/json"
 	"errors"
+	"log"
 	"net/http"
 	"regexp"
+	"sync"
+	"time"
 
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/matcher"
 	"github.com/openmusicplayer/backend/internal/musicbrainz"
 )
 
@@ -15,6 +25,144 @@ var uuidRegex = regexp.MustCompile(`^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f
 // BrowseHandlers contains handlers for browse/discovery endpoints
 type BrowseHandlers struct {
 	mbClient *musicbrainz.Client
+	// related finds covers, remixes, and samples in the caller's library;
+	// nil leaves them out of track details.
+	related relatedLibraryFinder
+	// settings holds the caller's display preferences; nil shows names as
+	// MusicBrainz lists them.
+	settings userSettingsReader
+	// library and plays mark artist and album details against the caller's
+	// library and listening; nil leaves those parts empty.
+	library libraryCatalog
+	plays   entityPlayRanker
+	// bios supplies Wikipedia biographies; nil falls back to MusicBrainz
+	// annotations.
+	bios ArtistBioProvider
+	// similar fills "fans also like" on artist details; nil leaves it empty.
+	similar SimilarArtistProvider
+}
+
+// ArtistBioProvider finds an artist's biography in the language of a locale.
+// It returns nil when the artist has none.
+type ArtistBioProvider interface {
+	ArtistBio(ctx context.Context, artistID uuid.UUID, wikidataID, locale string) (*db.ArtistBio, error)
+}
+
+type libraryCatalog interface {
+	ArtistLibraryAlbums(ctx context.Context, userID, artistMBID uuid.UUID) ([]db.LibraryAlbum, error)
+	LibraryRecordings(ctx context.Context, userID uuid.UUID, recordingIDs []uuid.UUID) (map[uuid.UUID]int64, error)
+}
+
+type entityPlayRanker interface {
+	TopTracksForArtist(ctx context.Context, userID, artistMBID uuid.UUID, limit int) ([]db.TopTrack, error)
+	TopTracksForRelease(ctx context.Context, userID, releaseMBID uuid.UUID, limit int) ([]db.TopTrack, error)
+}
+
+// SimilarArtistProvider lists the artists listeners of an artist also play,
+// most similar first.
+type SimilarArtistProvider interface {
+	SimilarArtists(ctx context.Context, artistID uuid.UUID, limit int) ([]db.SimilarArtist, error)
+}
+
+// fansAlsoLikeLimit is how many similar artists artist details list.
+const fansAlsoLikeLimit = 12
+
+// entityTopTrackLimit is how many most-played tracks artist and album details
+// list.
+const entityTopTrackLimit = 10
+
+type relatedLibraryFinder interface {
+	RelatedLibraryTracks(ctx context.Context, userID, recordingID uuid.UUID, rels []db.TrackRelation) ([]db.RelatedLibraryTrack, error)
+}
+
+// TrackDetailResponse is a MusicBrainz recording with the library tracks
+// related to it.
+type TrackDetailResponse struct {
+	*musicbrainz.Track
+	OriginalNames
+	RelatedInLibrary []RelatedLibraryTrackResponse `json:"relatedInLibrary"`
+}
+
+// localizedTrack is a recording whose names follow the caller's display
+// settings.
+type localizedTrack struct {
+	*musicbrainz.Track
+	OriginalNames
+}
+
+// AlbumDetailResponse is a MusicBrainz release whose names follow the
+// caller's display settings, with its tracks marked against the caller's
+// library.
+type AlbumDetailResponse struct {
+	*musicbrainz.Release
+	OriginalNames
+	Bio             *BioResponse     `json:"bio,omitempty"`
+	TracksInLibrary int              `json:"tracksInLibrary"`
+	TopTracks       []EntityTopTrack `json:"topTracks"`
+}
+
+// ArtistDetailResponse is a MusicBrainz artist with their discography marked
+// against the caller's library and the tracks the caller plays most.
+type ArtistDetailResponse struct {
+	*musicbrainz.Artist
+	Bio               *BioResponse            `json:"bio,omitempty"`
+	Releases          []DiscographyEntry      `json:"releases"`
+	ReleasesInLibrary int                     `json:"releasesInLibrary"`
+	ReleasesMissing   int                     `json:"releasesMissing"`
+	TopTracks         []EntityTopTrack        `json:"topTracks"`
+	FansAlsoLike      []SimilarArtistResponse `json:"fansAlsoLike"`
+}
+
+// SimilarArtistResponse is an artist listeners of another also play. Score
+// is the source's similarity; its scale depends on the source.
+type SimilarArtistResponse struct {
+	ID    string  `json:"id"`
+	Name  string  `json:"name"`
+	Score float64 `json:"score"`
+}
+
+// DiscographyEntry is a release group of the artist. LibraryTracks counts
+// the caller's library tracks from an album of the same title, which last
+// LibraryDurationMs and take LibrarySizeBytes of storage.
+type DiscographyEntry struct {
+	musicbrainz.Release
+	InLibrary         bool  `json:"inLibrary"`
+	LibraryTracks     int   `json:"libraryTracks,omitempty"`
+	LibraryDurationMs int64 `json:"libraryDurationMs,omitempty"`
+	LibrarySizeBytes  int64 `json:"librarySizeBytes,omitempty"`
+}
+
+// BioResponse is a short biography with what its attribution needs: the
+// source, the page to link, and the license the text is reused under.
+type BioResponse struct {
+	Text     string `json:"text"`
+	Source   string `json:"source"`
+	URL      string `json:"url,omitempty"`
+	Title    string `json:"title,omitempty"`
+	Language string `json:"language,omitempty"`
+	License  string `json:"license,omitempty"`
+}
+
+// EntityTopTrack is a library track the caller has played, with their play
+// count.
+type EntityTopTrack struct {
+	TrackID      int64     `json:"trackId"`
+	Title        string    `json:"title"`
+	Album        string    `json:"album,omitempty"`
+	PlayCount    int       `json:"playCount"`
+	LastPlayedAt time.Time `json:"lastPlayedAt"`
+}
+
+// RelatedLibraryTrackResponse is a library track related to the recording.
+// Relation reads from the library track's side: "remix_of" means it is a
+// remix of the recording, "cover_of" that it covers the same work.
+type RelatedLibraryTrackResponse struct {
+	TrackID       int64  `json:"trackId"`
+	Title         string `json:"title"`
+	Artist        string `json:"artist,omitempty"`
+	Album         string `json:"album,omitempty"`
+	MBRecordingID string `json:"mbRecordingId,omitempty"`
+	Relation      string `json:"relation"`
 }
 
 // NewBrowseHandlers creates a new BrowseHandlers instance
@@ -41,18 +189,90 @@ func (h *BrowseHandlers) GetArtist(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
+	userCtx := auth.GetUserFromContext(r.Context())
+	artistID := uuid.MustParse(mbID)
+	var albums []db.LibraryAlbum
+	var topTracks []db.TopTrack
+	var wg sync.WaitGroup
+	if userCtx != nil && h.library != nil {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			var err error
+			if albums, err = h.library.ArtistLibraryAlbums(r.Context(), userCtx.UserID, artistID); err != nil {
+				log.Printf("Library albums for artist %s failed: %v", mbID, err)
+			}
+		}()
+	}
+	if userCtx != nil && h.plays != nil {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			var err error
+			if topTracks, err = h.plays.TopTracksForArtist(r.Context(), userCtx.UserID, artistID, entityTopTrackLimit); err != nil {
+				log.Printf("Top tracks for artist %s failed: %v", mbID, err)
+			}
+		}()
+	}
+	var similar []db.SimilarArtist
+	if h.similar != nil {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			var err error
+			if similar, err = h.similar.SimilarArtists(r.Context(), artistID, fansAlsoLikeLimit); err != nil {
+				log.Printf("Similar artists for %s failed: %v", mbID, err)
+			}
+		}()
+	}
 	artist, err := h.mbClient.GetArtist(r.Context(), mbID)
+	var bio *BioResponse
+	if err == nil {
+		bio = h.artistBio(r.Context(), artistID, artist)
+	}
+	wg.Wait()
 	if err != nil {
-		if errors.Is(err, musicbrainz.ErrNotFound) {
-			writeErrorResponse(w, http.StatusNotFound, "NOT_FOUND", "artist not found")
-			return
-		}
-		writeErrorResponse(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to fetch artist")
+		writeBrowseLookupError(w, r, err, "artist")
 		return
 	}
 
+	// Release groups have no library rows of their own, so they match the
+	// caller's albums by normalized title.
+	libraryAlbums := make(map[string]db.LibraryAlbum, len(albums))
+	for _, album := range albums {
+		key := db.NormalizeString(album.Album)
+		total := libraryAlbums[key]
+		total.Tracks += album.Tracks
+		total.DurationMs += album.DurationMs
+		total.SizeBytes += album.SizeBytes
+		libraryAlbums[key] = total
+	}
+	resp := ArtistDetailResponse{
+		Artist:       artist,
+		Bio:          bio,
+		Releases:     make([]DiscographyEntry, 0, len(artist.Releases)),
+		TopTracks:    entityTopTracks(topTracks),
+		FansAlsoLike: similarArtistResponses(similar),
+	}
+	for _, release := range artist.Releases {
+		album := libraryAlbums[db.NormalizeString(release.Title)]
+		entry := DiscographyEntry{
+			Release:           release,
+			LibraryTracks:     album.Tracks,
+			LibraryDurationMs: album.DurationMs,
+			LibrarySizeBytes:  album.SizeBytes,
+		}
+		entry.InLibrary = entry.LibraryTracks > 0
+		if entry.InLibrary {
+			resp.ReleasesInLibrary++
+		} else {
+			resp.ReleasesMissing++
+		}
+		resp.Releases = append(resp.Releases, entry)
+	}
+
 	w.Header().Set("Content-Type", "application/json")
-	json.NewEncoder(w).Encode(artist)
+	json.NewEncoder(w).Encode(resp)
 }
 
 // GetAlbum handles GET /api/v1/albums/{mb_id}
@@ -68,18 +288,118 @@ func (h *BrowseHandlers) GetAlbum(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
+	userCtx := auth.GetUserFromContext(r.Context())
+	var topTracks []db.TopTrack
+	var wg sync.WaitGroup
+	if userCtx != nil && h.plays != nil {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			var err error
+			if topTracks, err = h.plays.TopTracksForRelease(r.Context(), userCtx.UserID, uuid.MustParse(mbID), entityTopTrackLimit); err != nil {
+				log.Printf("Top tracks for album %s failed: %v", mbID, err)
+			}
+		}()
+	}
 	release, err := h.mbClient.GetRelease(r.Context(), mbID)
+	if err == nil && userCtx != nil && h.library != nil {
+		markLibraryRecordings(r.Context(), h.library, userCtx.UserID, release)
+	}
+	wg.Wait()
 	if err != nil {
-		if errors.Is(err, musicbrainz.ErrNotFound) {
-			writeErrorResponse(w, http.StatusNotFound, "NOT_FOUND", "album not found")
-			return
-		}
-		writeErrorResponse(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to fetch album")
+		writeBrowseLookupError(w, r, err, "album")
 		return
 	}
 
+	names := localizeNames(displaySettings(r.Context(), h.settings), &release.Title, &release.Artist, matcher.ReleaseAliases(release))
+	resp := AlbumDetailResponse{
+		Release:       release,
+		OriginalNames: names,
+		Bio:           annotationBio(release.Annotation, "https://musicbrainz.org/release/"+mbID),
+		TopTracks:     entityTopTracks(topTracks),
+	}
+	for _, track := range release.Tracks {
+		if track.InLibrary {
+			resp.TracksInLibrary++
+		}
+	}
+
 	w.Header().Set("Content-Type", "application/json")
-	json.NewEncoder(w).Encode(release)
+	json.NewEncoder(w).Encode(resp)
+}
+
+// markLibraryRecordings flags the release's tracks the caller keeps in their
+// library. The release itself loaded; library matches are a best-effort extra.
+func markLibraryRecordings(ctx context.Context, library libraryCatalog, userID uuid.UUID, release *musicbrainz.Release) {
+	ids := make([]uuid.UUID, 0, len(release.Tracks))
+	for _, track := range release.Tracks {
+		if id, err := uuid.Parse(track.ID); err == nil {
+			ids = append(ids, id)
+		}
+	}
+	found, err := library.LibraryRecordings(ctx, userID, ids)
+	if err != nil {
+		log.Printf("Library recordings for album %s failed: %v", release.ID, err)
+		return
+	}
+	for i := range release.Tracks {
+		if id, err := uuid.Parse(release.Tracks[i].ID); err == nil {
+			_, release.Tracks[i].InLibrary = found[id]
+		}
+	}
+}
+
+// artistBio prefers the artist's Wikipedia article, in the caller's metadata
+// language, over their MusicBrainz annotation.
+func (h *BrowseHandlers) artistBio(ctx context.Context, artistID uuid.UUID, artist *musicbrainz.Artist) *BioResponse {
+	if h.bios != nil && artist.WikidataID != "" {
+		bio, err := h.bios.ArtistBio(ctx, artistID, artist.WikidataID, displaySettings(ctx, h.settings).MetadataLocale)
+		if err != nil {
+			log.Printf("Bio for artist %s failed: %v", artist.ID, err)
+		}
+		if bio != nil {
+			return &BioResponse{
+				Text:     bio.Extract,
+				Source:   bio.Source,
+				URL:      bio.URL,
+				Title:    bio.Title,
+				Language: bio.ContentLanguage,
+				License:  bio.License,
+			}
+		}
+	}
+	return annotationBio(artist.Annotation, "https://musicbrainz.org/artist/"+artist.ID)
+}
+
+// annotationBio turns a MusicBrainz annotation into a bio, or nil when the
+// entity has none.
+func annotationBio(annotation, pageURL string) *BioResponse {
+	if annotation == "" {
+		return nil
+	}
+	return &BioResponse{Text: annotation, Source: "musicbrainz", URL: pageURL}
+}
+
+func similarArtistResponses(artists []db.SimilarArtist) []SimilarArtistResponse {
+	out := make([]SimilarArtistResponse, 0, len(artists))
+	for _, a := range artists {
+		out = append(out, SimilarArtistResponse{ID: a.MBArtistID.String(), Name: a.Name, Score: a.Score})
+	}
+	return out
+}
+
+func entityTopTracks(tracks []db.TopTrack) []EntityTopTrack {
+	out := make([]EntityTopTrack, 0, len(tracks))
+	for _, t := range tracks {
+		out = append(out, EntityTopTrack{
+			TrackID:      t.ID,
+			Title:        t.Title,
+			Album:        t.Album.String,
+			PlayCount:    t.PlayCount,
+			LastPlayedAt: t.LastPlayedAt,
+		})
+	}
+	return out
 }
 
 // GetTrack handles GET /api/v1/tracks/{mb_id}
@@ -97,23 +417,54 @@ func (h *BrowseHandlers) GetTrack(w http.ResponseWriter, r *http.Request) {
 
 	track, err := h.mbClient.GetRecording(r.Context(), mbID)
 	if err != nil {
-		if errors.Is(err, musicbrainz.ErrNotFound) {
-			writeErrorResponse(w, http.StatusNotFound, "NOT_FOUND", "track not found")
-			return
-		}
-		writeErrorResponse(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to fetch track")
+		writeBrowseLookupError(w, r, err, "track")
 		return
 	}
 
+	names := localizeNames(displaySettings(r.Context(), h.settings), &track.Title, &track.Artist, matcher.TrackAliases(track))
+
 	w.Header().Set("Content-Type", "application/json")
-	json.NewEncoder(w).Encode(track)
+	userCtx := auth.GetUserFromContext(r.Context())
+	if h.related == nil || userCtx == nil {
+		json.NewEncoder(w).Encode(localizedTrack{Track: track, OriginalNames: names})
+		return
+	}
+	resp := TrackDetailResponse{Track: track, OriginalNames: names, RelatedInLibrary: []RelatedLibraryTrackResponse{}}
+	related, err := h.related.RelatedLibraryTracks(r.Context(), userCtx.UserID, uuid.MustParse(mbID), matcher.TrackRelations(track.Relations))
+	if err != nil {
+		// The recording itself loaded; library matches are a best-effort extra.
+		log.Printf("Related library tracks for %s failed: %v", mbID, err)
+	}
+	for _, rt := range related {
+		item := RelatedLibraryTrackResponse{
+			TrackID:  rt.TrackID,
+			Title:    rt.Title,
+			Artist:   rt.Artist.String,
+			Album:    rt.Album.String,
+			Relation: rt.Relation,
+		}
+		if rt.MBRecordingID != nil {
+			item.MBRecordingID = rt.MBRecordingID.String()
+		}
+		resp.RelatedInLibrary = append(resp.RelatedInLibrary, item)
+	}
+	json.NewEncoder(w).Encode(resp)
+}
+
+// writeBrowseLookupError maps MusicBrainz lookup failures: unknown MBIDs are
+// 404s, upstream failures keep their 502/504 problem type, anything else is 500.
+func writeBrowseLookupError(w http.ResponseWriter, r *http.Request, err error, resource string) {
+	var appErr *apperrors.AppError
+	switch {
+	case errors.Is(err, musicbrainz.ErrNotFound):
+		writeErrorResponse(w, http.StatusNotFound, "NOT_FOUND", resource+" not found")
+	case errors.As(err, &appErr):
+		apperrors.WriteProblem(w, r, appErr.Problem())
+	default:
+		writeErrorResponse(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to fetch "+resource)
+	}
 }
 
 func writeErrorResponse(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
@@ -0,0 +1,178 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+	"github.com/openmusicplayer/backend/internal/testutil"
+)
+
+type fakeRelatedLibraryFinder struct {
+	recordingID uuid.UUID
+	rels        []db.TrackRelation
+}
+
+func (f *fakeRelatedLibraryFinder) RelatedLibraryTracks(_ context.Context, _ uuid.UUID, recordingID uuid.UUID, rels []db.TrackRelation) ([]db.RelatedLibraryTrack, error) {
+	f.recordingID = recordingID
+	f.rels = rels
+	return []db.RelatedLibraryTrack{
+		{TrackID: 9, Title: "Song (Club Mix)", Artist: sql.NullString{String: "DJ", Valid: true}, Relation: musicbrainz.RelationRemixOf},
+	}, nil
+}
+
+func TestGetTrackIncludesRelatedLibraryTracks(t *testing.T) {
+	const recordingID = "8a0e5c5d-0f36-4b7a-8a0e-3d3c1a9b2f41"
+	mb := testutil.NewMusicBrainzServer(t)
+	mb.RespondJSON("/recording/"+recordingID, []byte(`{
+		"id": "`+recordingID+`",
+		"title": "Song",
+		"relations": [
+			{"type": "performance", "direction": "forward", "target-type": "work", "attributes": [], "work": {"id": "4a9b8e0f-3f3a-3c52-b4a8-91b3d6a6b0e7", "title": "Song"}}
+		]
+	}`))
+	finder := &fakeRelatedLibraryFinder{}
+	h := NewBrowseHandlers(musicbrainz.NewClient(nil, musicbrainz.WithBaseURL(mb.URL())))
+	h.related = finder
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/tracks/"+recordingID, nil)
+	req.SetPathValue("mb_id", recordingID)
+	rec := httptest.NewRecorder()
+	h.GetTrack(rec, withUser(req, uuid.New()))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp TrackDetailResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Track == nil || resp.Title != "Song" {
+		t.Fatalf("track = %+v", resp.Track)
+	}
+	if len(resp.RelatedInLibrary) != 1 || resp.RelatedInLibrary[0].TrackID != 9 || resp.RelatedInLibrary[0].Relation != musicbrainz.RelationRemixOf {
+		t.Fatalf("relatedInLibrary = %+v", resp.RelatedInLibrary)
+	}
+	if finder.recordingID.String() != recordingID || len(finder.rels) != 1 || finder.rels[0].Relation != musicbrainz.RelationPerformanceOf {
+		t.Fatalf("finder called with %s %+v", finder.recordingID, finder.rels)
+	}
+}
+
+type fakeEntityLibrary struct {
+	albums []db.LibraryAlbum
+	plays  []db.TopTrack
+}
+
+func (f *fakeEntityLibrary) ArtistLibraryAlbums(context.Context, uuid.UUID, uuid.UUID) ([]db.LibraryAlbum, error) {
+	return f.albums, nil
+}
+
+func (f *fakeEntityLibrary) LibraryRecordings(context.Context, uuid.UUID, []uuid.UUID) (map[uuid.UUID]int64, error) {
+	return nil, nil
+}
+
+func (f *fakeEntityLibrary) TopTracksForArtist(context.Context, uuid.UUID, uuid.UUID, int) ([]db.TopTrack, error) {
+	return f.plays, nil
+}
+
+func (f *fakeEntityLibrary) TopTracksForRelease(context.Context, uuid.UUID, uuid.UUID, int) ([]db.TopTrack, error) {
+	return f.plays, nil
+}
+
+func TestGetArtistMarksDiscographyAgainstLibrary(t *testing.T) {
+	const artistID = "a74b1b7f-71a5-4011-9441-d0b5e4122711"
+	mb := testutil.NewMusicBrainzServer(t)
+	mb.RespondJSON("/artist/"+artistID, []byte(`{
+		"id": "`+artistID+`",
+		"name": "Radiohead",
+		"annotation": "English rock band.",
+		"release-groups": [
+			{"id": "b1392450-e666-3926-a536-22c65f834433", "title": "OK Computer", "primary-type": "Album"},
+			{"id": "3ed9e1d1-9a2e-3e1f-8c6b-2c07e8b7f7a9", "title": "In Rainbows", "primary-type": "Album"}
+		]
+	}`))
+	library := &fakeEntityLibrary{
+		albums: []db.LibraryAlbum{{Album: "ok computer", Tracks: 3, DurationMs: 720000, SizeBytes: 24000000}},
+		plays:  []db.TopTrack{{Track: db.Track{ID: 4, Title: "Airbag"}, PlayCount: 7}},
+	}
+	h := NewBrowseHandlers(musicbrainz.NewClient(nil, musicbrainz.WithBaseURL(mb.URL())))
+	h.library = library
+	h.plays = library
+	h.similar = &fakeSimilarArtists{artists: []db.SimilarArtist{{MBArtistID: uuid.New(), Name: "Thom Yorke", Score: 0.8}}}
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/artists/"+artistID, nil)
+	req.SetPathValue("mb_id", artistID)
+	rec := httptest.NewRecorder()
+	h.GetArtist(rec, withUser(req, uuid.New()))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp ArtistDetailResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if len(resp.Releases) != 2 || !resp.Releases[0].InLibrary || resp.Releases[0].LibraryTracks != 3 || resp.Releases[1].InLibrary {
+		t.Fatalf("releases = %+v", resp.Releases)
+	}
+	if resp.Releases[0].LibraryDurationMs != 720000 || resp.Releases[0].LibrarySizeBytes != 24000000 {
+		t.Fatalf("library totals = %d ms, %d bytes", resp.Releases[0].LibraryDurationMs, resp.Releases[0].LibrarySizeBytes)
+	}
+	if resp.ReleasesInLibrary != 1 || resp.ReleasesMissing != 1 {
+		t.Fatalf("present %d, missing %d; want 1 and 1", resp.ReleasesInLibrary, resp.ReleasesMissing)
+	}
+	if resp.Bio == nil || resp.Bio.Source != "musicbrainz" || resp.Bio.Text != "English rock band." {
+		t.Fatalf("bio = %+v", resp.Bio)
+	}
+	if len(resp.TopTracks) != 1 || resp.TopTracks[0].TrackID != 4 || resp.TopTracks[0].PlayCount != 7 {
+		t.Fatalf("topTracks = %+v", resp.TopTracks)
+	}
+	if len(resp.FansAlsoLike) != 1 || resp.FansAlsoLike[0].Name != "Thom Yorke" {
+		t.Fatalf("fansAlsoLike = %+v", resp.FansAlsoLike)
+	}
+}
+
+type fakeArtistBios struct {
+	wikidataID string
+}
+
+func (f *fakeArtistBios) ArtistBio(_ context.Context, _ uuid.UUID, wikidataID, _ string) (*db.ArtistBio, error) {
+	f.wikidataID = wikidataID
+	return &db.ArtistBio{Extract: "Radiohead are an English rock band.", Source: db.ArtistBioSourceWikipedia, URL: "https://en.wikipedia.org/wiki/Radiohead", ContentLanguage: "en", License: "CC BY-SA 4.0"}, nil
+}
+
+func TestGetArtistPrefersWikipediaBio(t *testing.T) {
+	const artistID = "a74b1b7f-71a5-4011-9441-d0b5e4122711"
+	mb := testutil.NewMusicBrainzServer(t)
+	mb.RespondJSON("/artist/"+artistID, []byte(`{
+		"id": "`+artistID+`",
+		"name": "Radiohead",
+		"annotation": "English rock band.",
+		"relations": [
+			{"type": "wikidata", "target-type": "url", "url": {"resource": "https://www.wikidata.org/wiki/Q44190"}}
+		]
+	}`))
+	bios := &fakeArtistBios{}
+	h := NewBrowseHandlers(musicbrainz.NewClient(nil, musicbrainz.WithBaseURL(mb.URL())))
+	h.bios = bios
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/artists/"+artistID, nil)
+	req.SetPathValue("mb_id", artistID)
+	rec := httptest.NewRecorder()
+	h.GetArtist(rec, withUser(req, uuid.New()))
+	var resp ArtistDetailResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (body=%s)", err, rec.Body.String())
+	}
+	if bios.wikidataID != "Q44190" {
+		t.Fatalf("bio looked up for %q, want Q44190", bios.wikidataID)
+	}
+	if resp.Bio == nil || resp.Bio.Source != "wikipedia" || resp.Bio.License != "CC BY-SA 4.0" || resp.Bio.URL != "https://en.wikipedia.org/wiki/Radiohead" {
+		t.Fatalf("bio = %+v", resp.Bio)
+	}
+}
@@ -0,0 +1,108 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type chapterLister interface {
+	ListForTrack(ctx context.Context, trackID int64) ([]db.Chapter, error)
+}
+
+type chapterSplitQueue interface {
+	Enqueue(ctx context.Context, userID uuid.UUID, trackID int64) (*jobs.Job, error)
+}
+
+// ChapterHandlers list the tracklist of long mixes in the caller's library
+// and queue splitting a mix into one track per chapter.
+type ChapterHandlers struct {
+	chapters    chapterLister
+	libraryRepo playbackLibraryRepository
+	splits      chapterSplitQueue
+}
+
+func NewChapterHandlers(chapters chapterLister, libraryRepo playbackLibraryRepository, splits chapterSplitQueue) *ChapterHandlers {
+	return &ChapterHandlers{chapters: chapters, libraryRepo: libraryRepo, splits: splits}
+}
+
+// ChapterResponse is one chapter of a mix. SplitTrackID is the track cut
+// from it, once the mix has been split.
+type ChapterResponse struct {
+	Position     int    `json:"position"`
+	Title        string `json:"title"`
+	Artist       string `json:"artist,omitempty"`
+	StartMs      int    `json:"startMs"`
+	EndMs        int    `json:"endMs"`
+	Source       string `json:"source"`
+	SplitTrackID *int64 `json:"splitTrackId,omitempty"`
+}
+
+type ChaptersResponse struct {
+	TrackID  int64             `json:"trackId"`
+	Chapters []ChapterResponse `json:"chapters"`
+}
+
+// ListChapters handles GET /api/v1/tracks/{track_id}/chapters.
+func (h *ChapterHandlers) ListChapters(w http.ResponseWriter, r *http.Request) {
+	_, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	chapters, err := h.chapters.ListForTrack(r.Context(), trackID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load chapters")
+		return
+	}
+	resp := ChaptersResponse{TrackID: trackID, Chapters: make([]ChapterResponse, 0, len(chapters))}
+	for _, c := range chapters {
+		chapter := ChapterResponse{
+			Position: c.Position,
+			Title:    c.Title,
+			Artist:   c.Artist.String,
+			StartMs:  c.StartMs,
+			EndMs:    c.EndMs,
+			Source:   c.Source,
+		}
+		if c.SplitTrackID.Valid {
+			chapter.SplitTrackID = &c.SplitTrackID.Int64
+		}
+		resp.Chapters = append(resp.Chapters, chapter)
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// SplitChapters handles POST /api/v1/tracks/{track_id}/chapters/split.
+func (h *ChapterHandlers) SplitChapters(w http.ResponseWriter, r *http.Request) {
+	userID, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	chapters, err := h.chapters.ListForTrack(r.Context(), trackID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load chapters")
+		return
+	}
+	if len(chapters) == 0 {
+		writeLibraryError(w, http.StatusConflict, "NO_CHAPTERS", "track has no chapters to split")
+		return
+	}
+	job, err := h.splits.Enqueue(r.Context(), userID, trackID)
+	switch {
+	case errors.Is(err, jobs.ErrDuplicate):
+		writeLibraryError(w, http.StatusConflict, "JOB_ACTIVE", "a split of this track is already queued or running")
+		return
+	case err != nil:
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue chapter split")
+		return
+	}
+	w.Header().Set("Location", middleware.Path(r, "/api/v1/jobs/"+job.ID.String()))
+	writeLibraryJSON(w, http.StatusAccepted, jobResponse(job.Summary()))
+}
+
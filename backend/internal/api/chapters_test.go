@@ -0,0 +1,64 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+type fakeChapterLister map[int64][]db.Chapter
+
+func (f fakeChapterLister) ListForTrack(_ context.Context, trackID int64) ([]db.Chapter, error) {
+	return f[trackID], nil
+}
+
+type fakeChapterSplitQueue struct {
+	trackIDs []int64
+}
+
+func (f *fakeChapterSplitQueue) Enqueue(_ context.Context, userID uuid.UUID, trackID int64) (*jobs.Job, error) {
+	f.trackIDs = append(f.trackIDs, trackID)
+	return &jobs.Job{ID: uuid.New(), Kind: "chapter_split", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued, MaxAttempts: 3}, nil
+}
+
+func TestChaptersListAndSplit(t *testing.T) {
+	queue := &fakeChapterSplitQueue{}
+	h := NewChapterHandlers(fakeChapterLister{7: {
+		{Position: 1, Title: "Ratio", Artist: sql.NullString{String: "Floating Points", Valid: true}, StartMs: 0, EndMs: 750000, Source: db.ChapterSourceDescription, SplitTrackID: sql.NullInt64{Int64: 40, Valid: true}},
+		{Position: 2, Title: "Baby", StartMs: 750000, EndMs: 900000, Source: db.ChapterSourceDescription},
+	}}, &fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true, 8: true}}, queue)
+	request := func(handler http.HandlerFunc, method, trackID string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(method, "/api/v1/tracks/"+trackID+"/chapters", nil)
+		req.SetPathValue("track_id", trackID)
+		rec := httptest.NewRecorder()
+		handler(rec, withUser(req, uuid.New()))
+		return rec
+	}
+
+	rec := request(h.ListChapters, http.MethodGet, "7")
+	var resp ChaptersResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (status %d)", err, rec.Code)
+	}
+	if len(resp.Chapters) != 2 || resp.Chapters[0].Artist != "Floating Points" || *resp.Chapters[0].SplitTrackID != 40 || resp.Chapters[1].SplitTrackID != nil {
+		t.Fatalf("chapters = %+v", resp.Chapters)
+	}
+
+	if rec := request(h.SplitChapters, http.MethodPost, "7"); rec.Code != http.StatusAccepted || len(queue.trackIDs) != 1 {
+		t.Fatalf("split = %d %s", rec.Code, rec.Body.String())
+	}
+	if rec := request(h.SplitChapters, http.MethodPost, "8"); rec.Code != http.StatusConflict {
+		t.Fatalf("split without chapters = %d, want 409", rec.Code)
+	}
+	if rec := request(h.SplitChapters, http.MethodPost, "9"); rec.Code != http.StatusNotFound {
+		t.Fatalf("split outside the library = %d, want 404", rec.Code)
+	}
+}
@@ -0,0 +1,79 @@
+package api
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"net/http"
+
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+// resourceETag quotes a repository version token as a strong ETag. Used for
+// single resources whose ETag is also accepted in If-Match.
+func resourceETag(version string) string {
+	return `"` + version + `"`
+}
+
+// listETag derives an ETag for a list response. Every page, filter, and sort
+// is its own representation, so the query string is folded in with the
+// repository version.
+func listETag(kind, version string, r *http.Request) string {
+	sum := sha256.Sum256([]byte(kind + "\x00" + version + "\x00" + r.URL.RawQuery))
+	return `"` + hex.EncodeToString(sum[:16]) + `"`
+}
+
+// writeNotModified sets the ETag for the response and, when the client's
+// If-None-Match already holds it, answers 304 and returns true so the handler
+// can skip loading the representation.
+func writeNotModified(w http.ResponseWriter, r *http.Request, etag string) bool {
+	w.Header().Set("ETag", etag)
+	w.Header().Set("Cache-Control", "private, no-cache")
+	if middleware.IfNoneMatch(r.Header.Get("If-None-Match"), etag) {
+		w.WriteHeader(http.StatusNotModified)
+		return true
+	}
+	return false
+}
+
+// requireIfMatch returns a precondition for the repository that accepts only
+// the version the client last saw. It writes 428 and returns nil when the
+// request carries no If-Match header.
+func requireIfMatch(w http.ResponseWriter, r *http.Request) func(version string) bool {
+	header := r.Header.Get("If-Match")
+	if header == "" {
+		apperrors.WriteProblem(w, r, apperrors.NewProblem(http.StatusPreconditionRequired, apperrors.CodePreconditionRequired,
+			"If-Match header with the playlist ETag is required"))
+		return nil
+	}
+	return func(version string) bool {
+		return middleware.IfMatch(header, resourceETag(version))
+	}
+}
+
+// ifMatch returns a precondition accepting only the version in the request's
+// If-Match header, or nil when there is none so the last write wins as it
+// did before clients sent one.
+func ifMatch(r *http.Request) func(version string) bool {
+	header := r.Header.Get("If-Match")
+	if header == "" {
+		return nil
+	}
+	return func(version string) bool {
+		return middleware.IfMatch(header, resourceETag(version))
+	}
+}
+
+// writeVersionConflict answers an edit made against a stale copy with 409,
+// the resource as the server now holds it under details.current, and its
+// ETag, so the client can reapply the edit and retry with that ETag.
+func writeVersionConflict(w http.ResponseWriter, r *http.Request, message, version string, current any) {
+	w.Header().Set("ETag", resourceETag(version))
+	p := apperrors.NewProblem(http.StatusConflict, apperrors.CodeVersionConflict, message)
+	p.Details = map[string]any{"current": current}
+	apperrors.WriteProblem(w, r, p)
+}
+
+func writePreconditionFailed(w http.ResponseWriter, r *http.Request, message string) {
+	apperrors.WriteProblem(w, r, apperrors.NewProblem(http.StatusPreconditionFailed, apperrors.CodePreconditionFailed, message))
+}
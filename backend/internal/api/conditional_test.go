@@ -0,0 +1,77 @@
+package api
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+)
+
+func TestListETagVariesByQuery(t *testing.T) {
+	page1 := httptest.NewRequest(http.MethodGet, "/api/v1/library?limit=20&offset=0", nil)
+	page2 := httptest.NewRequest(http.MethodGet, "/api/v1/library?limit=20&offset=20", nil)
+
+	if listETag("library", "3.1700000000", page1) == listETag("library", "3.1700000000", page2) {
+		t.Fatal("different pages must not share an ETag")
+	}
+	if listETag("library", "3.1700000000", page1) == listETag("library", "4.1700000001", page1) {
+		t.Fatal("a new version must change the ETag")
+	}
+	if listETag("library", "1", page1) == listETag("playlists", "1", page1) {
+		t.Fatal("different lists must not share an ETag")
+	}
+}
+
+func TestWriteNotModified(t *testing.T) {
+	etag := resourceETag("1700000000.0")
+
+	r := httptest.NewRequest(http.MethodGet, "/api/v1/playlists/1", nil)
+	r.Header.Set("If-None-Match", `"other", W/`+etag)
+	w := httptest.NewRecorder()
+	if !writeNotModified(w, r, etag) {
+		t.Fatal("matching If-None-Match must short-circuit")
+	}
+	if w.Code != http.StatusNotModified || w.Header().Get("ETag") != etag {
+		t.Fatalf("status = %d, ETag = %q", w.Code, w.Header().Get("ETag"))
+	}
+
+	r = httptest.NewRequest(http.MethodGet, "/api/v1/playlists/1", nil)
+	r.Header.Set("If-None-Match", `"stale"`)
+	w = httptest.NewRecorder()
+	if writeNotModified(w, r, etag) {
+		t.Fatal("stale If-None-Match must not short-circuit")
+	}
+	if w.Header().Get("ETag") != etag {
+		t.Fatalf("ETag = %q, want %q", w.Header().Get("ETag"), etag)
+	}
+}
+
+func TestRequireIfMatch(t *testing.T) {
+	r := httptest.NewRequest(http.MethodPost, "/api/v1/playlists/1/tracks/reorder", nil)
+	w := httptest.NewRecorder()
+	if requireIfMatch(w, r) != nil {
+		t.Fatal("missing If-Match must not yield a precondition")
+	}
+	if w.Code != http.StatusPreconditionRequired {
+		t.Fatalf("status = %d, want 428", w.Code)
+	}
+	if got := w.Header().Get("Content-Type"); got != apperrors.ProblemContentType {
+		t.Fatalf("Content-Type = %q", got)
+	}
+
+	r.Header.Set("If-Match", resourceETag("v1"))
+	w = httptest.NewRecorder()
+	precondition := requireIfMatch(w, r)
+	if precondition == nil {
+		t.Fatal("If-Match must yield a precondition")
+	}
+	if !precondition("v1") || precondition("v2") {
+		t.Fatal("precondition must accept only the version the client holds")
+	}
+
+	r.Header.Set("If-Match", "W/"+resourceETag("v1"))
+	if requireIfMatch(httptest.NewRecorder(), r)("v1") {
+		t.Fatal("weak validators must not satisfy If-Match")
+	}
+}
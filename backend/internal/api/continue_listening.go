@@ -0,0 +1,155 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"strconv"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/listening"
+)
+
+type continueListeningPlayStore interface {
+	ListeningEvents(ctx context.Context, userID uuid.UUID, since time.Time) ([]db.ListeningEvent, error)
+}
+
+// continueListeningPlaylists loads the playlists behind playlist sessions.
+// db.PlaylistRepository satisfies this interface.
+type continueListeningPlaylists interface {
+	GetManyWithTracks(ctx context.Context, ids []int64) (map[int64]*db.PlaylistWithTracks, error)
+}
+
+// ContinueHandlers serve what the caller was in the middle of: the
+// playlists, albums, artists, and radio stations they listened to lately,
+// each once, with the track to pick up at. See package listening for how
+// sessions are rebuilt from plays and skips.
+type ContinueHandlers struct {
+	plays     continueListeningPlayStore
+	playlists continueListeningPlaylists
+	tracks    playEventTrackRepository
+	now       func() time.Time
+}
+
+func NewContinueHandlers(plays continueListeningPlayStore, playlists continueListeningPlaylists, tracks playEventTrackRepository) *ContinueHandlers {
+	return &ContinueHandlers{plays: plays, playlists: playlists, tracks: tracks, now: time.Now}
+}
+
+// ContinueListeningItem is a context to continue. Position and TrackCount
+// are set for playlists, whose order is known; Heard counts the distinct
+// tracks played in the last session.
+type ContinueListeningItem struct {
+	ContextType  string                 `json:"contextType"`
+	ContextID    string                 `json:"contextId"`
+	Name         string                 `json:"name,omitempty"`
+	Track        PlayEventTrackResponse `json:"track"`
+	Position     int                    `json:"position,omitempty"`
+	TrackCount   int                    `json:"trackCount,omitempty"`
+	Heard        int                    `json:"heard"`
+	StartedAt    time.Time              `json:"startedAt"`
+	LastPlayedAt time.Time              `json:"lastPlayedAt"`
+}
+
+type ContinueListeningResponse struct {
+	Items []ContinueListeningItem `json:"items"`
+}
+
+// GetContinueListening handles GET /api/v1/me/continue-listening.
+// Query params: limit (default 10, max 50).
+func (h *ContinueHandlers) GetContinueListening(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	limit := query.Int("limit", 10, 1, 50)
+	if !query.Valid(w, r) {
+		return
+	}
+
+	now := h.now()
+	events, err := h.plays.ListeningEvents(r.Context(), userCtx.UserID, now.Add(-listening.MaxAge))
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load listening history")
+		return
+	}
+	sessions := listening.Latest(listening.Sessions(events), now)
+
+	var playlistIDs []int64
+	for _, s := range sessions {
+		if s.ContextType == "playlist" {
+			if id, err := strconv.ParseInt(s.ContextID, 10, 64); err == nil {
+				playlistIDs = append(playlistIDs, id)
+			}
+		}
+	}
+	playlists := map[int64]*db.PlaylistWithTracks{}
+	if len(playlistIDs) > 0 {
+		if playlists, err = h.playlists.GetManyWithTracks(r.Context(), playlistIDs); err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load playlists")
+			return
+		}
+	}
+
+	var points []listening.Point
+	names := map[string]string{}
+	counts := map[string]int{}
+	for _, s := range sessions {
+		var order []int64
+		if s.ContextType == "playlist" {
+			id, _ := strconv.ParseInt(s.ContextID, 10, 64)
+			playlist := playlists[id]
+			// Playlists deleted or made private since are not continued.
+			if playlist == nil || (playlist.UserID != userCtx.UserID && !playlist.IsPublic) {
+				continue
+			}
+			for _, t := range playlist.Tracks {
+				order = append(order, t.ID)
+			}
+			key := listening.Key(s.ContextType, s.ContextID)
+			names[key], counts[key] = playlist.Name, playlist.TrackCount
+		}
+		if point, ok := listening.Resume(s, order); ok {
+			points = append(points, point)
+		}
+	}
+	points = listening.Dedupe(points)
+
+	trackIDs := make([]int64, 0, len(points))
+	for _, p := range points {
+		trackIDs = append(trackIDs, p.TrackID)
+	}
+	tracks, err := h.tracks.GetByIDsForUser(r.Context(), userCtx.UserID, trackIDs)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load tracks")
+		return
+	}
+
+	resp := ContinueListeningResponse{Items: make([]ContinueListeningItem, 0, len(points))}
+	for _, p := range points {
+		if len(resp.Items) == limit {
+			break
+		}
+		track, ok := tracks[p.TrackID]
+		if !ok {
+			continue
+		}
+		key := listening.Key(p.Session.ContextType, p.Session.ContextID)
+		resp.Items = append(resp.Items, ContinueListeningItem{
+			ContextType:  p.Session.ContextType,
+			ContextID:    p.Session.ContextID,
+			Name:         names[key],
+			Track:        trackToPlayEventResponse(*track),
+			Position:     p.Position,
+			TrackCount:   counts[key],
+			Heard:        p.Heard,
+			StartedAt:    p.Session.StartedAt(),
+			LastPlayedAt: p.Session.EndedAt(),
+		})
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
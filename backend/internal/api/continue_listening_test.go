@@ -0,0 +1,89 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeListeningEvents []db.ListeningEvent
+
+func (f fakeListeningEvents) ListeningEvents(_ context.Context, _ uuid.UUID, since time.Time) ([]db.ListeningEvent, error) {
+	var events []db.ListeningEvent
+	for _, e := range f {
+		if !e.At.Before(since) {
+			events = append(events, e)
+		}
+	}
+	return events, nil
+}
+
+type fakeContinuePlaylists map[int64]*db.PlaylistWithTracks
+
+func (f fakeContinuePlaylists) GetManyWithTracks(_ context.Context, ids []int64) (map[int64]*db.PlaylistWithTracks, error) {
+	found := map[int64]*db.PlaylistWithTracks{}
+	for _, id := range ids {
+		if playlist, ok := f[id]; ok {
+			found[id] = playlist
+		}
+	}
+	return found, nil
+}
+
+func TestContinueListeningResumesEachContextOnce(t *testing.T) {
+	userID := uuid.New()
+	now := time.Date(2026, 3, 2, 9, 0, 0, 0, time.UTC)
+	at := func(minutes int) time.Time { return now.Add(-12 * time.Hour).Add(time.Duration(minutes) * time.Minute) }
+	events := fakeListeningEvents{
+		{TrackID: 9, At: now.AddDate(0, 0, -20), ContextType: "album", ContextID: "old"},
+		{TrackID: 1, At: at(0), ContextType: "playlist", ContextID: "7"},
+		{TrackID: 2, At: at(4), Skipped: true, ContextType: "playlist", ContextID: "7"},
+		{TrackID: 4, At: at(6), ContextType: "playlist", ContextID: "8"},
+		{TrackID: 5, At: at(10), ContextType: "album", ContextID: "a"},
+		{TrackID: 6, At: at(14), ContextType: "album", ContextID: "a"},
+		{TrackID: 6, At: at(20), ContextType: "artist", ContextID: "x"},
+	}
+	playlists := fakeContinuePlaylists{
+		7: {Playlist: db.Playlist{ID: 7, UserID: userID, Name: "Road trip"}, Tracks: []db.Track{{ID: 1}, {ID: 2}, {ID: 3}}, TrackCount: 3},
+		8: {Playlist: db.Playlist{ID: 8, UserID: uuid.New(), Name: "Someone else's"}, Tracks: []db.Track{{ID: 4}}, TrackCount: 1},
+	}
+	tracks := &fakePlayTrackRepo{tracks: map[int64]*db.Track{3: newTrack(3, "Third"), 6: newTrack(6, "Sixth"), 9: newTrack(9, "Ninth")}}
+	h := NewContinueHandlers(events, playlists, tracks)
+	h.now = func() time.Time { return now }
+
+	get := func(query string) []ContinueListeningItem {
+		t.Helper()
+		rec := httptest.NewRecorder()
+		h.GetContinueListening(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/continue-listening"+query, nil), userID))
+		if rec.Code != http.StatusOK {
+			t.Fatalf("GET %s = %d: %s", query, rec.Code, rec.Body.String())
+		}
+		var resp ContinueListeningResponse
+		if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+			t.Fatalf("decode: %v", err)
+		}
+		return resp.Items
+	}
+
+	items := get("")
+	if len(items) != 2 {
+		t.Fatalf("items = %+v, want the artist and playlist 7", items)
+	}
+	if got := items[0]; got.ContextType != "artist" || got.Track.ID != 6 || got.Heard != 1 || !got.LastPlayedAt.Equal(at(20)) {
+		t.Fatalf("first item = %+v", got)
+	}
+	if got := items[1]; got.ContextID != "7" || got.Name != "Road trip" || got.Track.ID != 3 || got.Position != 3 || got.TrackCount != 3 || !got.StartedAt.Equal(at(0)) {
+		t.Fatalf("playlist item = %+v", got)
+	}
+
+	if items := get("?limit=1"); len(items) != 1 || items[0].ContextType != "artist" {
+		t.Fatalf("limited items = %+v", items)
+	}
+}
@@ -0,0 +1,390 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"path"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+)
+
+const (
+	maxDeviceProfileNameLength = 100
+	maxSyncManifestTracks      = 200
+
+	syncUnavailableCodeConverting = "converting"
+
+	// transcodeBusyRetryAfter is how long clients refused a conversion wait
+	// before asking again.
+	transcodeBusyRetryAfter = 30 * time.Second
+)
+
+type deviceProfileRepository interface {
+	List(ctx context.Context, userID uuid.UUID) ([]db.DeviceProfile, error)
+	Get(ctx context.Context, userID uuid.UUID, id int64) (*db.DeviceProfile, error)
+	Create(ctx context.Context, profile *db.DeviceProfile) error
+	Update(ctx context.Context, profile *db.DeviceProfile) error
+	Delete(ctx context.Context, userID uuid.UUID, id int64) error
+}
+
+type transcodeQueue interface {
+	Enqueue(ctx context.Context, userID uuid.UUID, profile libraryexport.Profile, trackIDs []int64) (*jobs.Job, error)
+}
+
+// DeviceProfileHandlers manage a user's device profiles and issue offline
+// sync manifests that deliver tracks in a profile's format. Copies a profile
+// converts are made by background jobs and kept in object storage, so a
+// manifest lists the tracks still converting for the client to ask again.
+type DeviceProfileHandlers struct {
+	profiles    deviceProfileRepository
+	trackRepo   playbackTrackRepository
+	libraryRepo playbackLibraryRepository
+	storage     playbackURLStorage
+	transcodes  transcodeQueue
+	now         func() time.Time
+}
+
+func NewDeviceProfileHandlers(profiles deviceProfileRepository, trackRepo playbackTrackRepository, libraryRepo playbackLibraryRepository, storageClient playbackURLStorage, transcodes transcodeQueue) *DeviceProfileHandlers {
+	return &DeviceProfileHandlers{
+		profiles:    profiles,
+		trackRepo:   trackRepo,
+		libraryRepo: libraryRepo,
+		storage:     storageClient,
+		transcodes:  transcodes,
+		now:         time.Now,
+	}
+}
+
+// DeviceProfileRequest creates or replaces a profile. Format is original,
+// mp3, aac, opus, or flac; BitrateKbps defaults per lossy format, and
+// MaxArtworkBytes of 0 keeps cover art of any size.
+type DeviceProfileRequest struct {
+	Name            string `json:"name"`
+	Format          string `json:"format"`
+	BitrateKbps     int    `json:"bitrateKbps,omitempty"`
+	MaxArtworkBytes int64  `json:"maxArtworkBytes,omitempty"`
+}
+
+type DeviceProfileResponse struct {
+	ID              int64     `json:"id"`
+	Name            string    `json:"name"`
+	Format          string    `json:"format"`
+	BitrateKbps     int       `json:"bitrateKbps,omitempty"`
+	MaxArtworkBytes int64     `json:"maxArtworkBytes,omitempty"`
+	CreatedAt       time.Time `json:"createdAt"`
+	UpdatedAt       time.Time `json:"updatedAt"`
+}
+
+type DeviceProfileListResponse struct {
+	Profiles []DeviceProfileResponse `json:"profiles"`
+}
+
+type SyncManifestRequest struct {
+	TrackIDs   []int64 `json:"trackIds"`
+	TTLSeconds int     `json:"ttlSeconds,omitempty"`
+}
+
+// SyncManifestResponse lists signed URLs of the tracks ready in the
+// profile's format. Tracks still converting are listed as unavailable with
+// code "converting" and JobID names the job converting them, when one was
+// queued by this request.
+type SyncManifestResponse struct {
+	Profile     DeviceProfileResponse     `json:"profile"`
+	Items       []SyncManifestItem        `json:"items"`
+	Unavailable []PlaybackUnavailableItem `json:"unavailable,omitempty"`
+	JobID       string                    `json:"jobId,omitempty"`
+}
+
+type SyncManifestItem struct {
+	TrackID     int64     `json:"trackId"`
+	URL         string    `json:"url"`
+	ExpiresAt   time.Time `json:"expiresAt"`
+	ContentType string    `json:"contentType"`
+	Extension   string    `json:"extension"`
+	SizeBytes   int64     `json:"sizeBytes"`
+}
+
+// ListProfiles handles GET /api/v1/device-profiles.
+func (h *DeviceProfileHandlers) ListProfiles(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	profiles, err := h.profiles.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list device profiles")
+		return
+	}
+	resp := DeviceProfileListResponse{Profiles: make([]DeviceProfileResponse, 0, len(profiles))}
+	for _, profile := range profiles {
+		resp.Profiles = append(resp.Profiles, newDeviceProfileResponse(profile))
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
+// CreateProfile handles POST /api/v1/device-profiles.
+func (h *DeviceProfileHandlers) CreateProfile(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	profile, ok := decodeDeviceProfile(w, r)
+	if !ok {
+		return
+	}
+	profile.UserID = userCtx.UserID
+	if err := h.profiles.Create(r.Context(), profile); err != nil {
+		writeDeviceProfileError(w, err, "failed to create device profile")
+		return
+	}
+	writeMaintenanceJSON(w, http.StatusCreated, newDeviceProfileResponse(*profile))
+}
+
+// UpdateProfile handles PUT /api/v1/device-profiles/{id}.
+func (h *DeviceProfileHandlers) UpdateProfile(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid device profile ID")
+		return
+	}
+	profile, ok := decodeDeviceProfile(w, r)
+	if !ok {
+		return
+	}
+	profile.ID, profile.UserID = id, userCtx.UserID
+	if err := h.profiles.Update(r.Context(), profile); err != nil {
+		writeDeviceProfileError(w, err, "failed to update device profile")
+		return
+	}
+	writeMaintenanceJSON(w, http.StatusOK, newDeviceProfileResponse(*profile))
+}
+
+// DeleteProfile handles DELETE /api/v1/device-profiles/{id}.
+func (h *DeviceProfileHandlers) DeleteProfile(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid device profile ID")
+		return
+	}
+	if err := h.profiles.Delete(r.Context(), userCtx.UserID, id); err != nil {
+		writeDeviceProfileError(w, err, "failed to delete device profile")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// CreateSyncManifest handles POST /api/v1/device-profiles/{id}/sync-manifest.
+// Tracks the profile converts that have no stored copy yet are queued for
+// conversion.
+func (h *DeviceProfileHandlers) CreateSyncManifest(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid device profile ID")
+		return
+	}
+	var req SyncManifestRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	trackIDs, err := validateAndDedupeTrackIDs(req.TrackIDs)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	}
+	if len(trackIDs) == 0 || len(trackIDs) > maxSyncManifestTracks {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds must name 1 to "+strconv.Itoa(maxSyncManifestTracks)+" tracks")
+		return
+	}
+	stored, err := h.profiles.Get(r.Context(), userCtx.UserID, id)
+	if err != nil {
+		writeDeviceProfileError(w, err, "failed to load device profile")
+		return
+	}
+	profile := exportProfile(stored)
+
+	ttl := clampPlaybackTTL(req.TTLSeconds)
+	resp := SyncManifestResponse{
+		Profile: newDeviceProfileResponse(*stored),
+		Items:   make([]SyncManifestItem, 0, len(trackIDs)),
+	}
+	inLibrary, err := h.libraryRepo.TracksInLibrary(r.Context(), userCtx.UserID, trackIDs)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify library ownership")
+		return
+	}
+	for _, trackID := range trackIDs {
+		if !inLibrary[trackID] {
+			writeMaintenanceError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+	}
+	tracks, err := h.trackRepo.GetByIDs(r.Context(), trackIDs)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+		return
+	}
+
+	var converting []int64
+	for _, trackID := range trackIDs {
+		track, ok := tracks[trackID]
+		if !ok {
+			writeMaintenanceError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+
+		key := strings.TrimSpace(track.StorageKey.String)
+		if key == "" {
+			resp.Unavailable = append(resp.Unavailable, PlaybackUnavailableItem{
+				TrackID: trackID,
+				Code:    playbackUnavailableCodeAudioUnavailable,
+				Message: "track has no stored audio object",
+			})
+			continue
+		}
+		if !profile.IsOriginal() {
+			key = libraryexport.TranscodeKey(profile, key)
+		}
+		objInfo, err := h.storage.StatObject(r.Context(), key)
+		if err != nil {
+			if r.Context().Err() != nil {
+				return
+			}
+			item := PlaybackUnavailableItem{
+				TrackID: trackID,
+				Code:    playbackUnavailableCodeArtifactMissing,
+				Message: "stored audio object is unavailable",
+			}
+			if !profile.IsOriginal() {
+				item.Code, item.Message = syncUnavailableCodeConverting, "track is being converted for this profile"
+				converting = append(converting, trackID)
+			}
+			resp.Unavailable = append(resp.Unavailable, item)
+			continue
+		}
+		url, err := h.storage.PresignGetObject(r.Context(), key, ttl)
+		if err != nil {
+			if r.Context().Err() != nil {
+				return
+			}
+			writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to issue sync URL")
+			return
+		}
+		resp.Items = append(resp.Items, SyncManifestItem{
+			TrackID:     trackID,
+			URL:         url,
+			ExpiresAt:   h.now().Add(ttl).UTC(),
+			ContentType: playbackContentType(key, objInfo.ContentType),
+			Extension:   strings.TrimPrefix(path.Ext(key), "."),
+			SizeBytes:   objInfo.Size,
+		})
+	}
+
+	if len(converting) > 0 {
+		// While a conversion for this profile runs, tracks it does not cover
+		// are queued by a later request once it finishes.
+		job, err := h.transcodes.Enqueue(r.Context(), userCtx.UserID, profile, converting)
+		if errors.Is(err, libraryexport.ErrTranscodeBusy) {
+			w.Header().Set("Retry-After", strconv.Itoa(int(transcodeBusyRetryAfter.Seconds())))
+			writeMaintenanceError(w, http.StatusTooManyRequests, "TRANSCODE_BUSY", "the server is converting as many tracks as it admits; try again shortly")
+			return
+		}
+		if err != nil && !errors.Is(err, jobs.ErrDuplicate) {
+			writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue conversion")
+			return
+		}
+		if err == nil {
+			resp.JobID = job.ID.String()
+		}
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
+// decodeDeviceProfile reads and checks a DeviceProfileRequest, writing the
+// error response when it is invalid.
+func decodeDeviceProfile(w http.ResponseWriter, r *http.Request) (*db.DeviceProfile, bool) {
+	var req DeviceProfileRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return nil, false
+	}
+	name := strings.TrimSpace(req.Name)
+	if name == "" || utf8.RuneCountInString(name) > maxDeviceProfileNameLength {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name must be 1 to "+strconv.Itoa(maxDeviceProfileNameLength)+" characters")
+		return nil, false
+	}
+	profile, err := libraryexport.Profile{
+		Format:          req.Format,
+		BitrateKbps:     req.BitrateKbps,
+		MaxArtworkBytes: req.MaxArtworkBytes,
+	}.Normalize()
+	if err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return nil, false
+	}
+	return &db.DeviceProfile{
+		Name:            name,
+		Format:          profile.Format,
+		BitrateKbps:     profile.BitrateKbps,
+		MaxArtworkBytes: profile.MaxArtworkBytes,
+	}, true
+}
+
+// exportProfile is the conversion settings of a stored profile.
+func exportProfile(profile *db.DeviceProfile) libraryexport.Profile {
+	return libraryexport.Profile{
+		Format:          profile.Format,
+		BitrateKbps:     profile.BitrateKbps,
+		MaxArtworkBytes: profile.MaxArtworkBytes,
+	}
+}
+
+func newDeviceProfileResponse(profile db.DeviceProfile) DeviceProfileResponse {
+	return DeviceProfileResponse{
+		ID:              profile.ID,
+		Name:            profile.Name,
+		Format:          profile.Format,
+		BitrateKbps:     profile.BitrateKbps,
+		MaxArtworkBytes: profile.MaxArtworkBytes,
+		CreatedAt:       profile.CreatedAt,
+		UpdatedAt:       profile.UpdatedAt,
+	}
+}
+
+func writeDeviceProfileError(w http.ResponseWriter, err error, message string) {
+	switch {
+	case errors.Is(err, db.ErrDeviceProfileNotFound):
+		writeMaintenanceError(w, http.StatusNotFound, "PROFILE_NOT_FOUND", "device profile not found")
+	case errors.Is(err, db.ErrDeviceProfileNameTaken):
+		writeMaintenanceError(w, http.StatusConflict, "PROFILE_NAME_TAKEN", "a device profile with this name already exists")
+	default:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", message)
+	}
+}
@@ -0,0 +1,206 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+type fakeDeviceProfiles struct {
+	profiles map[int64]*db.DeviceProfile
+	nextID   int64
+}
+
+func (f *fakeDeviceProfiles) List(_ context.Context, userID uuid.UUID) ([]db.DeviceProfile, error) {
+	var out []db.DeviceProfile
+	for _, p := range f.profiles {
+		if p.UserID == userID {
+			out = append(out, *p)
+		}
+	}
+	return out, nil
+}
+
+func (f *fakeDeviceProfiles) Get(_ context.Context, userID uuid.UUID, id int64) (*db.DeviceProfile, error) {
+	p, ok := f.profiles[id]
+	if !ok || p.UserID != userID {
+		return nil, db.ErrDeviceProfileNotFound
+	}
+	return p, nil
+}
+
+func (f *fakeDeviceProfiles) Create(_ context.Context, profile *db.DeviceProfile) error {
+	for _, p := range f.profiles {
+		if p.UserID == profile.UserID && p.Name == profile.Name {
+			return db.ErrDeviceProfileNameTaken
+		}
+	}
+	f.nextID++
+	profile.ID = f.nextID
+	f.profiles[profile.ID] = profile
+	return nil
+}
+
+func (f *fakeDeviceProfiles) Update(ctx context.Context, profile *db.DeviceProfile) error {
+	if _, err := f.Get(ctx, profile.UserID, profile.ID); err != nil {
+		return err
+	}
+	f.profiles[profile.ID] = profile
+	return nil
+}
+
+func (f *fakeDeviceProfiles) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	if _, err := f.Get(ctx, userID, id); err != nil {
+		return err
+	}
+	delete(f.profiles, id)
+	return nil
+}
+
+type fakeTranscodeQueue struct {
+	queued [][]int64
+	active bool
+	busy   bool
+}
+
+func (f *fakeTranscodeQueue) Enqueue(_ context.Context, userID uuid.UUID, profile libraryexport.Profile, trackIDs []int64) (*jobs.Job, error) {
+	if f.active {
+		return nil, jobs.ErrDuplicate
+	}
+	if f.busy {
+		return nil, libraryexport.ErrTranscodeBusy
+	}
+	f.queued = append(f.queued, trackIDs)
+	return &jobs.Job{ID: uuid.New(), Kind: "offline_transcode", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued}, nil
+}
+
+func TestDeviceProfilesCRUD(t *testing.T) {
+	h := NewDeviceProfileHandlers(&fakeDeviceProfiles{profiles: map[int64]*db.DeviceProfile{}}, nil, nil, nil, nil)
+	userID := uuid.New()
+	send := func(method, id, body string, handler http.HandlerFunc) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(method, "/api/v1/device-profiles", strings.NewReader(body))
+		req.SetPathValue("id", id)
+		rec := httptest.NewRecorder()
+		handler(rec, withUser(req, userID))
+		return rec
+	}
+
+	rec := send(http.MethodPost, "", `{"name":" Car USB ","format":"mp3","maxArtworkBytes":512000}`, h.CreateProfile)
+	if rec.Code != http.StatusCreated {
+		t.Fatalf("create = %d %s", rec.Code, rec.Body.String())
+	}
+	var created DeviceProfileResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &created); err != nil {
+		t.Fatal(err)
+	}
+	if created.Name != "Car USB" || created.Format != "mp3" || created.BitrateKbps != 320 || created.MaxArtworkBytes != 512000 {
+		t.Fatalf("created = %+v", created)
+	}
+
+	tests := []struct {
+		method  string
+		id      string
+		body    string
+		handler http.HandlerFunc
+		want    int
+	}{
+		{http.MethodPost, "", `{"name":"Car USB","format":"flac"}`, h.CreateProfile, http.StatusConflict},
+		{http.MethodPost, "", `{"name":"DAP","format":"wma"}`, h.CreateProfile, http.StatusBadRequest},
+		{http.MethodPost, "", `{"name":"DAP","format":"flac","bitrateKbps":320}`, h.CreateProfile, http.StatusBadRequest},
+		{http.MethodPost, "", `{"name":"  ","format":"original"}`, h.CreateProfile, http.StatusBadRequest},
+		{http.MethodPut, "1", `{"name":"Car USB","format":"aac","bitrateKbps":192}`, h.UpdateProfile, http.StatusOK},
+		{http.MethodPut, "2", `{"name":"Other","format":"aac"}`, h.UpdateProfile, http.StatusNotFound},
+		{http.MethodGet, "", "", h.ListProfiles, http.StatusOK},
+		{http.MethodDelete, "1", "", h.DeleteProfile, http.StatusNoContent},
+		{http.MethodDelete, "1", "", h.DeleteProfile, http.StatusNotFound},
+	}
+	for _, tt := range tests {
+		if rec := send(tt.method, tt.id, tt.body, tt.handler); rec.Code != tt.want {
+			t.Errorf("%s %s %s: status = %d, want %d (%s)", tt.method, tt.id, tt.body, rec.Code, tt.want, rec.Body.String())
+		}
+	}
+}
+
+func TestSyncManifestQueuesConversions(t *testing.T) {
+	userID := uuid.New()
+	profile := &db.DeviceProfile{ID: 7, UserID: userID, Name: "Car USB", Format: "mp3", BitrateKbps: 320}
+	profiles := &fakeDeviceProfiles{profiles: map[int64]*db.DeviceProfile{7: profile}}
+	trackRepo := &fakePlaybackTrackRepo{tracks: map[int64]*db.Track{
+		1: {ID: 1, StorageKey: sql.NullString{String: "tracks/a.flac", Valid: true}},
+		2: {ID: 2, StorageKey: sql.NullString{String: "tracks/b.flac", Valid: true}},
+		3: {ID: 3},
+	}}
+	libraryRepo := &fakePlaybackLibraryRepo{allowed: map[int64]bool{1: true, 2: true, 3: true}}
+	converted := libraryexport.TranscodeKey(libraryexport.Profile{Format: "mp3", BitrateKbps: 320}, "tracks/a.flac")
+	objects := &fakePlaybackStorage{info: map[string]*storage.ObjectInfo{converted: {Size: 4096, ContentType: "audio/mpeg"}}}
+	queue := &fakeTranscodeQueue{}
+	h := NewDeviceProfileHandlers(profiles, trackRepo, libraryRepo, objects, queue)
+	manifest := func(id, body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/device-profiles/"+id+"/sync-manifest", strings.NewReader(body))
+		req.SetPathValue("id", id)
+		rec := httptest.NewRecorder()
+		h.CreateSyncManifest(rec, withUser(req, userID))
+		return rec
+	}
+
+	rec := manifest("7", `{"trackIds":[1,2,3]}`)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("manifest = %d %s", rec.Code, rec.Body.String())
+	}
+	var resp SyncManifestResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatal(err)
+	}
+	if len(resp.Items) != 1 || resp.Items[0].TrackID != 1 || resp.Items[0].Extension != "mp3" || resp.Items[0].ContentType != "audio/mpeg" || !strings.Contains(resp.Items[0].URL, converted) {
+		t.Fatalf("items = %+v", resp.Items)
+	}
+	if len(resp.Unavailable) != 2 || resp.Unavailable[0].Code != "converting" || resp.Unavailable[1].Code != "audio_unavailable" {
+		t.Fatalf("unavailable = %+v", resp.Unavailable)
+	}
+	if resp.JobID == "" || len(queue.queued) != 1 || len(queue.queued[0]) != 1 || queue.queued[0][0] != 2 {
+		t.Fatalf("job %q queued %v", resp.JobID, queue.queued)
+	}
+	if trackRepo.calls != 1 || libraryRepo.calls != 1 {
+		t.Fatalf("track/library lookups for 3 tracks = %d/%d, want one batch each", trackRepo.calls, libraryRepo.calls)
+	}
+
+	// A conversion already running for the profile is left to finish.
+	queue.active = true
+	if rec := manifest("7", `{"trackIds":[2]}`); rec.Code != http.StatusOK || strings.Contains(rec.Body.String(), "jobId") {
+		t.Fatalf("manifest while converting = %d %s", rec.Code, rec.Body.String())
+	}
+
+	// Past the server's transcode admission limit the client is told to wait.
+	queue.active, queue.busy = false, true
+	rec = manifest("7", `{"trackIds":[2]}`)
+	if rec.Code != http.StatusTooManyRequests || rec.Header().Get("Retry-After") != "30" || !strings.Contains(rec.Body.String(), "TRANSCODE_BUSY") {
+		t.Fatalf("manifest while busy = %d, Retry-After %q, %s", rec.Code, rec.Header().Get("Retry-After"), rec.Body.String())
+	}
+
+	tests := []struct {
+		id   string
+		body string
+		want int
+	}{
+		{"8", `{"trackIds":[1]}`, http.StatusNotFound},
+		{"7", `{"trackIds":[4]}`, http.StatusNotFound},
+		{"7", `{"trackIds":[]}`, http.StatusBadRequest},
+		{"x", `{"trackIds":[1]}`, http.StatusBadRequest},
+	}
+	for _, tt := range tests {
+		if rec := manifest(tt.id, tt.body); rec.Code != tt.want {
+			t.Errorf("%s %s: status = %d, want %d", tt.id, tt.body, rec.Code, tt.want)
+		}
+	}
+}
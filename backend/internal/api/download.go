@@ -4,10 +4,13 @@ import (
 	"context"
 	"crypto/sha256"
 	"encoding/json"
+	"errors"
 	"fmt"
 	"io"
 	"net/http"
+	"net/textproto"
 	"net/url"
+	"path"
 	"strings"
 
 	"github.com/google/uuid"
@@ -15,10 +18,25 @@ import (
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
 	"github.com/openmusicplayer/backend/internal/download"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/fetcher"
 )
 
 const maxCreateDownloadBodyBytes = 16 * 1024
 
+const (
+	maxDirectRequestHeaders     = 8
+	maxDirectRequestHeaderBytes = 4096
+)
+
+// forbiddenRequestHeaders are managed by the HTTP client and cannot be
+// supplied for a direct download.
+var forbiddenRequestHeaders = map[string]bool{
+	"Host": true, "Content-Length": true, "Transfer-Encoding": true, "Connection": true,
+	"Keep-Alive": true, "Te": true, "Trailer": true, "Upgrade": true, "Proxy-Authorization": true,
+	"Proxy-Connection": true, "Accept-Encoding": true, "Range": true,
+}
+
 type trustedDownloadIngestion interface {
 	CreateTrustedDownload(context.Context, uuid.UUID, string, download.SourceCandidate, string) (*db.SourceSelectionDownload, error)
 	EnqueueTrustedDownload(context.Context, *db.SourceSelectionDownload, db.SourceSelectionDownloadEnqueuer) (*download.DownloadJob, error)
@@ -28,6 +46,7 @@ type downloadService interface {
 	db.SourceSelectionDownloadEnqueuer
 	GetJob(context.Context, string) (*download.DownloadJob, error)
 	GetUserJobs(context.Context, string) ([]*download.DownloadJob, error)
+	Admit(context.Context) error
 }
 
 type DownloadHandlers struct {
@@ -46,11 +65,14 @@ func NewDownloadHandlers(downloadService downloadService, ingestion ...trustedDo
 	}
 }
 
-// CreateDownloadRequest represents the request body for creating a download
+// CreateDownloadRequest represents the request body for creating a download.
+// A URL naming an audio file, or any URL with source_type "direct", is fetched
+// as-is; Headers are sent with that fetch and require https.
 type CreateDownloadRequest struct {
-	URL          string       `json:"url"`
-	SourceType   string       `json:"source_type"`
-	PageMetadata PageMetadata `json:"page_metadata,omitempty"`
+	URL          string            `json:"url"`
+	SourceType   string            `json:"source_type"`
+	PageMetadata PageMetadata      `json:"page_metadata,omitempty"`
+	Headers      map[string]string `json:"headers,omitempty"`
 }
 
 // PageMetadata contains metadata extracted from the source page
@@ -66,24 +88,22 @@ type CreateDownloadResponse struct {
 	SourceDecisionID string `json:"sourceDecisionId"`
 }
 
-// DownloadErrorResponse represents an error response
-type DownloadErrorResponse struct {
-	Code    string `json:"code"`
-	Message string `json:"message"`
-}
-
-// GetJobResponse represents a job status response
+// GetJobResponse represents a job status response. A failed job's Error is
+// a short explanation, with its FailureCategory and what the user can do
+// about it in Remediation; the tool output behind it stays in the server log.
 type GetJobResponse struct {
-	JobID       string  `json:"job_id"`
-	Status      string  `json:"status"`
-	Progress    int     `json:"progress"`
-	Error       string  `json:"error,omitempty"`
-	URL         string  `json:"url"`
-	SourceType  string  `json:"source_type"`
-	TrackID     *int64  `json:"track_id,omitempty"`
-	CreatedAt   string  `json:"created_at"`
-	StartedAt   *string `json:"started_at,omitempty"`
-	CompletedAt *string `json:"completed_at,omitempty"`
+	JobID           string  `json:"job_id"`
+	Status          string  `json:"status"`
+	Progress        int     `json:"progress"`
+	Error           string  `json:"error,omitempty"`
+	FailureCategory string  `json:"failure_category,omitempty"`
+	Remediation     string  `json:"remediation,omitempty"`
+	URL             string  `json:"url"`
+	SourceType      string  `json:"source_type"`
+	TrackID         *int64  `json:"track_id,omitempty"`
+	CreatedAt       string  `json:"created_at"`
+	StartedAt       *string `json:"started_at,omitempty"`
+	CompletedAt     *string `json:"completed_at,omitempty"`
 }
 
 // CreateDownload handles POST /api/v1/downloads
@@ -108,6 +128,11 @@ func (h *DownloadHandlers) CreateDownload(w http.ResponseWriter, r *http.Request
 		writeDownloadError(w, http.StatusServiceUnavailable, "DOWNLOAD_UNAVAILABLE", "download processing is unavailable")
 		return
 	}
+	// Refused downloads are turned away before an audit row is written for them.
+	if err := h.downloadService.Admit(r.Context()); err != nil {
+		writeInsufficientStorage(w)
+		return
+	}
 	persisted, err := h.ingestion.CreateTrustedDownload(r.Context(), userCtx.UserID, db.SourceSelectionOriginDirectURL, candidate, "server-normalized authenticated direct/share URL")
 	if err != nil {
 		writeDownloadError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to persist trusted download")
@@ -115,6 +140,10 @@ func (h *DownloadHandlers) CreateDownload(w http.ResponseWriter, r *http.Request
 	}
 	job, err := h.ingestion.EnqueueTrustedDownload(r.Context(), persisted, h.downloadService)
 	if err != nil {
+		if errors.Is(err, download.ErrAdmissionRefused) {
+			writeInsufficientStorage(w)
+			return
+		}
 		writeDownloadError(w, http.StatusInternalServerError, "DOWNLOAD_ENQUEUE_FAILED", "failed to enqueue trusted download")
 		return
 	}
@@ -124,6 +153,10 @@ func (h *DownloadHandlers) CreateDownload(w http.ResponseWriter, r *http.Request
 	})
 }
 
+func writeInsufficientStorage(w http.ResponseWriter) {
+	writeDownloadError(w, http.StatusInsufficientStorage, "INSUFFICIENT_STORAGE", "new downloads are paused until disk space is freed")
+}
+
 func decodeCreateDownloadRequest(w http.ResponseWriter, r *http.Request, req *CreateDownloadRequest) error {
 	r.Body = http.MaxBytesReader(w, r.Body, maxCreateDownloadBodyBytes)
 	decoder := json.NewDecoder(r.Body)
@@ -150,9 +183,6 @@ func normalizedDirectCandidate(req CreateDownloadRequest) (download.SourceCandid
 		return download.SourceCandidate{}, fmt.Errorf("url must be an absolute http(s) URL")
 	}
 	parsed.Scheme = strings.ToLower(parsed.Scheme)
-	if parsed.Scheme != "https" {
-		return download.SourceCandidate{}, fmt.Errorf("url must use https")
-	}
 	parsed.Host = strings.ToLower(parsed.Host)
 	parsed.Fragment = ""
 	host := parsed.Hostname()
@@ -162,17 +192,76 @@ func normalizedDirectCandidate(req CreateDownloadRequest) (download.SourceCandid
 		provider = "youtube"
 	case host == "soundcloud.com" || strings.HasSuffix(host, ".soundcloud.com"):
 		provider = "soundcloud"
+	case fetcher.IsDirectAudioURL(parsed) || strings.EqualFold(strings.TrimSpace(req.SourceType), download.SourceTypeDirect):
+		provider = download.SourceTypeDirect
+	case parsed.Scheme != "https":
+		return download.SourceCandidate{}, fmt.Errorf("url must use https")
 	default:
 		return download.SourceCandidate{}, fmt.Errorf("unsupported source URL")
 	}
+	// Plain http is only for direct files, which are often served from
+	// personal hosts without TLS; credentials never travel over it.
+	if parsed.Scheme != "https" && (provider != download.SourceTypeDirect || len(req.Headers) > 0) {
+		return download.SourceCandidate{}, fmt.Errorf("url must use https")
+	}
+	headers, err := directRequestHeaders(req.Headers)
+	if err != nil {
+		return download.SourceCandidate{}, err
+	}
+	if len(headers) > 0 && provider != download.SourceTypeDirect {
+		return download.SourceCandidate{}, fmt.Errorf("headers are only supported for direct file URLs")
+	}
 	normalized := parsed.String()
 	digest := sha256.Sum256([]byte(normalized))
 	sourceID := fmt.Sprintf("%x", digest[:16])
 	title := strings.TrimSpace(req.PageMetadata.Title)
-	if title == "" {
+	if title == "" && provider == download.SourceTypeDirect {
+		name := path.Base(parsed.Path)
+		title = strings.TrimSpace(strings.TrimSuffix(name, path.Ext(name)))
+	}
+	if title == "" || title == "." || title == "/" {
 		title = "Shared " + provider + " source"
 	}
-	return download.SourceCandidate{CandidateID: provider + ":" + sourceID, Provider: provider, SourceID: sourceID, SourceURL: normalized, Title: title, ThumbnailURL: strings.TrimSpace(req.PageMetadata.Thumbnail), Metadata: map[string]interface{}{"trustedIngestion": true, "origin": db.SourceSelectionOriginDirectURL}}, nil
+	metadata := map[string]interface{}{"trustedIngestion": true, "origin": db.SourceSelectionOriginDirectURL}
+	if len(headers) > 0 {
+		metadata[download.MetadataRequestHeaders] = headers
+	}
+	return download.SourceCandidate{CandidateID: provider + ":" + sourceID, Provider: provider, SourceID: sourceID, SourceURL: normalized, Title: title, ThumbnailURL: strings.TrimSpace(req.PageMetadata.Thumbnail), Metadata: metadata}, nil
+}
+
+// directRequestHeaders canonicalizes user-supplied headers, rejecting names
+// the HTTP client manages and values that could split a request.
+func directRequestHeaders(raw map[string]string) (map[string]string, error) {
+	if len(raw) == 0 {
+		return nil, nil
+	}
+	if len(raw) > maxDirectRequestHeaders {
+		return nil, fmt.Errorf("at most %d headers are allowed", maxDirectRequestHeaders)
+	}
+	headers := make(map[string]string, len(raw))
+	for name, value := range raw {
+		canonical := textproto.CanonicalMIMEHeaderKey(strings.TrimSpace(name))
+		if !validHeaderName(canonical) || forbiddenRequestHeaders[canonical] {
+			return nil, fmt.Errorf("header %q is not allowed", name)
+		}
+		if len(value) > maxDirectRequestHeaderBytes || strings.ContainsAny(value, "\r\n\x00") {
+			return nil, fmt.Errorf("header %q has an invalid value", name)
+		}
+		headers[canonical] = strings.TrimSpace(value)
+	}
+	return headers, nil
+}
+
+func validHeaderName(name string) bool {
+	if name == "" || len(name) > 128 {
+		return false
+	}
+	for _, r := range name {
+		if !(r >= 'a' && r <= 'z' || r >= 'A' && r <= 'Z' || r >= '0' && r <= '9' || strings.ContainsRune("!#$%&'*+-.^_`|~", r)) {
+			return false
+		}
+	}
+	return true
 }
 
 // GetJob handles GET /api/v1/downloads/{job_id}
@@ -211,6 +300,7 @@ func (h *DownloadHandlers) GetJob(w http.ResponseWriter, r *http.Request) {
 		TrackID:    job.TrackID,
 		CreatedAt:  job.CreatedAt.Format("2006-01-02T15:04:05Z"),
 	}
+	explainJobFailure(&resp, job)
 
 	if job.StartedAt != nil {
 		startedAt := job.StartedAt.Format("2006-01-02T15:04:05Z")
@@ -250,6 +340,7 @@ func (h *DownloadHandlers) GetUserJobs(w http.ResponseWriter, r *http.Request) {
 			TrackID:    job.TrackID,
 			CreatedAt:  job.CreatedAt.Format("2006-01-02T15:04:05Z"),
 		}
+		explainJobFailure(&resp, job)
 		if job.StartedAt != nil {
 			startedAt := job.StartedAt.Format("2006-01-02T15:04:05Z")
 			resp.StartedAt = &startedAt
@@ -266,6 +357,15 @@ func (h *DownloadHandlers) GetUserJobs(w http.ResponseWriter, r *http.Request) {
 	})
 }
 
+// explainJobFailure replaces a failed job's raw error with its explanation.
+func explainJobFailure(resp *GetJobResponse, job *download.DownloadJob) {
+	if failure := job.Failure(); failure != nil {
+		resp.Error = failure.Message
+		resp.FailureCategory = failure.Category
+		resp.Remediation = failure.Remediation
+	}
+}
+
 func writeDownloadJSON(w http.ResponseWriter, status int, data interface{}) {
 	w.Header().Set("Content-Type", "application/json")
 	w.WriteHeader(status)
@@ -273,10 +373,5 @@ func writeDownloadJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writeDownloadError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(DownloadErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
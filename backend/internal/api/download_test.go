@@ -62,6 +62,52 @@ func TestCreateDownloadCreatesTrustedDecisionAndKeepsResponseFields(t *testing.T
 	}
 }
 
+func TestCreateDownloadAcceptsDirectFileURLsWithHeaders(t *testing.T) {
+	ingestion := &fakeDirectIngestion{}
+	handler := NewDownloadHandlers(fakeDirectDownloadService{}, ingestion)
+	rec := httptest.NewRecorder()
+	handler.CreateDownload(rec, authenticatedDownloadRequest(`{"url":"https://files.example.test/music/Some%20Song.flac?token=x","headers":{"authorization":"Bearer secret"}}`))
+	if rec.Code != http.StatusCreated {
+		t.Fatalf("status = %d body=%s", rec.Code, rec.Body.String())
+	}
+	candidate := ingestion.created.Candidate
+	if candidate.Provider != download.SourceTypeDirect || candidate.Title != "Some Song" {
+		t.Fatalf("candidate = %+v", candidate)
+	}
+	job := &download.DownloadJob{Metadata: candidate.Metadata}
+	if got := job.RequestHeaders()["Authorization"]; got != "Bearer secret" {
+		t.Fatalf("request headers = %v", job.RequestHeaders())
+	}
+	if got := job.Redacted().RequestHeaders()["Authorization"]; got != "[redacted]" {
+		t.Fatalf("redacted header = %q", got)
+	}
+
+	ingestion = &fakeDirectIngestion{}
+	handler = NewDownloadHandlers(fakeDirectDownloadService{}, ingestion)
+	rec = httptest.NewRecorder()
+	handler.CreateDownload(rec, authenticatedDownloadRequest(`{"url":"http://nas.example.test/stream?id=7","source_type":"direct"}`))
+	if rec.Code != http.StatusCreated || ingestion.created.Candidate.Provider != download.SourceTypeDirect {
+		t.Fatalf("plain http direct status = %d body=%s", rec.Code, rec.Body.String())
+	}
+}
+
+func TestCreateDownloadRejectsUnsafeDirectHeaders(t *testing.T) {
+	for name, body := range map[string]string{
+		"http with credentials": `{"url":"http://files.example.test/a.mp3","headers":{"Authorization":"Basic x"}}`,
+		"managed header":        `{"url":"https://files.example.test/a.mp3","headers":{"Host":"internal"}}`,
+		"header injection":      `{"url":"https://files.example.test/a.mp3","headers":{"X-Token":"a\r\nHost: internal"}}`,
+		"non-direct source":     `{"url":"https://www.youtube.com/watch?v=x","headers":{"Cookie":"a=b"}}`,
+	} {
+		t.Run(name, func(t *testing.T) {
+			rec := httptest.NewRecorder()
+			NewDownloadHandlers(nil).CreateDownload(rec, authenticatedDownloadRequest(body))
+			if rec.Code != http.StatusBadRequest || !bytes.Contains(rec.Body.Bytes(), []byte("INVALID_URL")) {
+				t.Fatalf("status = %d body=%s", rec.Code, rec.Body.String())
+			}
+		})
+	}
+}
+
 func TestCreateDownloadRejectsUnknownAndOversizedFields(t *testing.T) {
 	handler := NewDownloadHandlers(nil)
 	for name, body := range map[string]string{
@@ -88,12 +134,24 @@ func TestCreateDownloadEnqueueFailureKeepsTrustedAudit(t *testing.T) {
 	}
 }
 
+func TestCreateDownloadRefusedAdmissionSkipsTrustedAudit(t *testing.T) {
+	ingestion := &fakeDirectIngestion{}
+	handler := NewDownloadHandlers(fakeDirectDownloadService{admitErr: download.ErrAdmissionRefused}, ingestion)
+	rec := httptest.NewRecorder()
+	handler.CreateDownload(rec, authenticatedDownloadRequest(`{"url":"https://soundcloud.com/artist/track"}`))
+	if rec.Code != http.StatusInsufficientStorage || ingestion.created != nil || ingestion.enqueueCalled {
+		t.Fatalf("status/audit = %d/%+v/%v", rec.Code, ingestion.created, ingestion.enqueueCalled)
+	}
+}
+
 func authenticatedDownloadRequest(body string) *http.Request {
 	req := httptest.NewRequest(http.MethodPost, "/api/v1/downloads", bytes.NewBufferString(body))
 	return req.WithContext(context.WithValue(req.Context(), auth.UserContextKey, &auth.UserContext{UserID: uuid.MustParse("11111111-1111-1111-1111-111111111111")}))
 }
 
-type fakeDirectDownloadService struct{}
+type fakeDirectDownloadService struct {
+	admitErr error
+}
 
 func (fakeDirectDownloadService) EnqueueSourceCandidateWithID(_ context.Context, id, userID string, candidate download.SourceCandidate, _ *string) (*download.DownloadJob, error) {
 	return &download.DownloadJob{ID: id, UserID: userID, Status: download.StatusQueued, URL: candidate.SourceURL}, nil
@@ -104,6 +162,9 @@ func (fakeDirectDownloadService) GetJob(context.Context, string) (*download.Down
 func (fakeDirectDownloadService) GetUserJobs(context.Context, string) ([]*download.DownloadJob, error) {
 	return nil, nil
 }
+func (f fakeDirectDownloadService) Admit(context.Context) error {
+	return f.admitErr
+}
 
 type fakeDirectIngestion struct {
 	created       *db.SourceSelectionDownload
@@ -0,0 +1,251 @@
+package api
+
+import (
+	"cmp"
+	"context"
+	"errors"
+	"net/http"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	maxEQPresetNameLength = 100
+	maxEQBands            = 31
+	minEQFrequencyHz      = 20
+	maxEQFrequencyHz      = 20000
+	// maxEQGainDB bounds band gains and the preamp in both directions.
+	maxEQGainDB = 24
+	minEQQ      = 0.1
+	maxEQQ      = 10
+)
+
+type eqPresetRepository interface {
+	List(ctx context.Context, userID uuid.UUID) ([]db.EQPreset, error)
+	Create(ctx context.Context, preset *db.EQPreset) error
+	Update(ctx context.Context, preset *db.EQPreset) error
+	Delete(ctx context.Context, userID uuid.UUID, id int64) error
+	SetClientPreset(ctx context.Context, userID uuid.UUID, clientID int64, presetID *int64) error
+}
+
+// EQPresetHandlers keep a user's equalizer presets on the server, so every
+// player applies the same EQ. Players learn theirs in the playback client
+// handshake.
+type EQPresetHandlers struct {
+	presets eqPresetRepository
+}
+
+func NewEQPresetHandlers(presets eqPresetRepository) *EQPresetHandlers {
+	return &EQPresetHandlers{presets: presets}
+}
+
+// EQPresetRequest creates or replaces a preset. Bands are peaking filters
+// at 20 to 20000 Hz with gains of at most 24 dB either way; Q of 0 leaves
+// the width to the player. Default makes the preset the one players apply
+// unless they override it.
+type EQPresetRequest struct {
+	Name     string      `json:"name"`
+	PreampDB float64     `json:"preampDb,omitempty"`
+	Bands    []db.EQBand `json:"bands"`
+	Default  bool        `json:"default,omitempty"`
+}
+
+type EQPresetResponse struct {
+	ID        int64       `json:"id"`
+	Name      string      `json:"name"`
+	PreampDB  float64     `json:"preampDb"`
+	Bands     []db.EQBand `json:"bands"`
+	Default   bool        `json:"default"`
+	CreatedAt time.Time   `json:"createdAt"`
+	UpdatedAt time.Time   `json:"updatedAt"`
+}
+
+type EQPresetListResponse struct {
+	Presets []EQPresetResponse `json:"presets"`
+}
+
+// ClientEQPresetRequest overrides the default preset on one player. A null
+// PresetID goes back to the default.
+type ClientEQPresetRequest struct {
+	PresetID *int64 `json:"presetId"`
+}
+
+// ListEQPresets handles GET /api/v1/eq-presets.
+func (h *EQPresetHandlers) ListEQPresets(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	presets, err := h.presets.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list EQ presets")
+		return
+	}
+	resp := EQPresetListResponse{Presets: make([]EQPresetResponse, 0, len(presets))}
+	for _, preset := range presets {
+		resp.Presets = append(resp.Presets, newEQPresetResponse(preset))
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// CreateEQPreset handles POST /api/v1/eq-presets.
+func (h *EQPresetHandlers) CreateEQPreset(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	preset, ok := decodeEQPreset(w, r)
+	if !ok {
+		return
+	}
+	preset.UserID = userCtx.UserID
+	if err := h.presets.Create(r.Context(), preset); err != nil {
+		writeEQPresetError(w, err, "failed to create EQ preset")
+		return
+	}
+	writePlaybackJSON(w, http.StatusCreated, newEQPresetResponse(*preset))
+}
+
+// UpdateEQPreset handles PUT /api/v1/eq-presets/{id}.
+func (h *EQPresetHandlers) UpdateEQPreset(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid EQ preset ID")
+		return
+	}
+	preset, ok := decodeEQPreset(w, r)
+	if !ok {
+		return
+	}
+	preset.ID, preset.UserID = id, userCtx.UserID
+	if err := h.presets.Update(r.Context(), preset); err != nil {
+		writeEQPresetError(w, err, "failed to update EQ preset")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, newEQPresetResponse(*preset))
+}
+
+// DeleteEQPreset handles DELETE /api/v1/eq-presets/{id}.
+func (h *EQPresetHandlers) DeleteEQPreset(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid EQ preset ID")
+		return
+	}
+	if err := h.presets.Delete(r.Context(), userCtx.UserID, id); err != nil {
+		writeEQPresetError(w, err, "failed to delete EQ preset")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// SetClientEQPreset handles PUT /api/v1/playback/clients/{id}/eq-preset.
+func (h *EQPresetHandlers) SetClientEQPreset(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	clientID, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid client ID")
+		return
+	}
+	var req ClientEQPresetRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if err := h.presets.SetClientPreset(r.Context(), userCtx.UserID, clientID, req.PresetID); err != nil {
+		writeEQPresetError(w, err, "failed to set the client's EQ preset")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// decodeEQPreset reads and checks an EQPresetRequest, writing the error
+// response when it is invalid. Bands come back ordered by frequency.
+func decodeEQPreset(w http.ResponseWriter, r *http.Request) (*db.EQPreset, bool) {
+	var req EQPresetRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return nil, false
+	}
+	name := strings.TrimSpace(req.Name)
+	if name == "" || utf8.RuneCountInString(name) > maxEQPresetNameLength {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name must be 1 to "+strconv.Itoa(maxEQPresetNameLength)+" characters")
+		return nil, false
+	}
+	if err := validateEQBands(req.PreampDB, req.Bands); err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return nil, false
+	}
+	slices.SortStableFunc(req.Bands, func(a, b db.EQBand) int { return cmp.Compare(a.FrequencyHz, b.FrequencyHz) })
+	return &db.EQPreset{Name: name, PreampDB: req.PreampDB, Bands: req.Bands, IsDefault: req.Default}, true
+}
+
+func validateEQBands(preampDB float64, bands []db.EQBand) error {
+	if preampDB < -maxEQGainDB || preampDB > maxEQGainDB {
+		return errors.New("preampDb must be between -24 and 24")
+	}
+	if len(bands) == 0 || len(bands) > maxEQBands {
+		return errors.New("bands must hold 1 to " + strconv.Itoa(maxEQBands) + " bands")
+	}
+	for _, band := range bands {
+		if band.FrequencyHz < minEQFrequencyHz || band.FrequencyHz > maxEQFrequencyHz {
+			return errors.New("band frequencyHz must be between 20 and 20000")
+		}
+		if band.GainDB < -maxEQGainDB || band.GainDB > maxEQGainDB {
+			return errors.New("band gainDb must be between -24 and 24")
+		}
+		if band.Q != 0 && (band.Q < minEQQ || band.Q > maxEQQ) {
+			return errors.New("band q must be 0 or between 0.1 and 10")
+		}
+	}
+	return nil
+}
+
+func newEQPresetResponse(preset db.EQPreset) EQPresetResponse {
+	return EQPresetResponse{
+		ID:        preset.ID,
+		Name:      preset.Name,
+		PreampDB:  preset.PreampDB,
+		Bands:     preset.Bands,
+		Default:   preset.IsDefault,
+		CreatedAt: preset.CreatedAt,
+		UpdatedAt: preset.UpdatedAt,
+	}
+}
+
+func writeEQPresetError(w http.ResponseWriter, err error, message string) {
+	switch {
+	case errors.Is(err, db.ErrEQPresetNotFound):
+		writePlaybackError(w, http.StatusNotFound, "PRESET_NOT_FOUND", "EQ preset not found")
+	case errors.Is(err, db.ErrEQPresetNameTaken):
+		writePlaybackError(w, http.StatusConflict, "PRESET_NAME_TAKEN", "an EQ preset with this name already exists")
+	case errors.Is(err, db.ErrPlaybackClientNotFound):
+		writePlaybackError(w, http.StatusNotFound, "CLIENT_NOT_FOUND", "client not found")
+	default:
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", message)
+	}
+}
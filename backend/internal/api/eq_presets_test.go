@@ -0,0 +1,118 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeEQPresets struct {
+	presets  []db.EQPreset
+	override map[int64]int64
+}
+
+func (f *fakeEQPresets) List(ctx context.Context, userID uuid.UUID) ([]db.EQPreset, error) {
+	return f.presets, nil
+}
+
+func (f *fakeEQPresets) Create(ctx context.Context, preset *db.EQPreset) error {
+	for _, p := range f.presets {
+		if p.Name == preset.Name {
+			return db.ErrEQPresetNameTaken
+		}
+	}
+	preset.ID = int64(len(f.presets) + 1)
+	f.presets = append(f.presets, *preset)
+	return nil
+}
+
+func (f *fakeEQPresets) Update(ctx context.Context, preset *db.EQPreset) error {
+	return db.ErrEQPresetNotFound
+}
+
+func (f *fakeEQPresets) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	return db.ErrEQPresetNotFound
+}
+
+func (f *fakeEQPresets) SetClientPreset(ctx context.Context, userID uuid.UUID, clientID int64, presetID *int64) error {
+	if presetID == nil {
+		delete(f.override, clientID)
+	} else {
+		f.override[clientID] = *presetID
+	}
+	return nil
+}
+
+func (f *fakeEQPresets) ForClient(ctx context.Context, userID uuid.UUID, clientID int64) (*db.EQPreset, error) {
+	for _, p := range f.presets {
+		if p.ID == f.override[clientID] || (f.override[clientID] == 0 && p.IsDefault) {
+			return &p, nil
+		}
+	}
+	return nil, nil
+}
+
+func TestEQPresetHandlers(t *testing.T) {
+	user := uuid.New()
+	presets := &fakeEQPresets{override: map[int64]int64{}}
+	h := NewEQPresetHandlers(presets)
+	create := func(body string) *httptest.ResponseRecorder {
+		rec := httptest.NewRecorder()
+		h.CreateEQPreset(rec, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/eq-presets", strings.NewReader(body)), user))
+		return rec
+	}
+
+	for _, body := range []string{
+		`{"name":"","bands":[{"frequencyHz":60}]}`,
+		`{"name":"Bass","bands":[]}`,
+		`{"name":"Bass","bands":[{"frequencyHz":10}]}`,
+		`{"name":"Bass","bands":[{"frequencyHz":60,"gainDb":30}]}`,
+		`{"name":"Bass","bands":[{"frequencyHz":60,"q":50}]}`,
+		`{"name":"Bass","preampDb":-40,"bands":[{"frequencyHz":60}]}`,
+	} {
+		if rec := create(body); rec.Code != http.StatusBadRequest {
+			t.Errorf("create %s = %d, want 400", body, rec.Code)
+		}
+	}
+	rec := create(`{"name":" Bass ","preampDb":-3,"bands":[{"frequencyHz":8000,"gainDb":-2},{"frequencyHz":60,"gainDb":6,"q":0.7}],"default":true}`)
+	var got EQPresetResponse
+	_ = json.Unmarshal(rec.Body.Bytes(), &got)
+	if rec.Code != http.StatusCreated || got.Name != "Bass" || !got.Default || len(got.Bands) != 2 || got.Bands[0].FrequencyHz != 60 {
+		t.Fatalf("create = %d %s; want the bands ordered by frequency", rec.Code, rec.Body.String())
+	}
+	if rec := create(`{"name":"Bass","bands":[{"frequencyHz":60}]}`); rec.Code != http.StatusConflict {
+		t.Errorf("create with a taken name = %d, want 409", rec.Code)
+	}
+
+	req := httptest.NewRequest(http.MethodPut, "/api/v1/playback/clients/4/eq-preset", strings.NewReader(`{"presetId":null}`))
+	req.SetPathValue("id", "4")
+	rec = httptest.NewRecorder()
+	h.SetClientEQPreset(rec, withUser(req, user))
+	if rec.Code != http.StatusNoContent {
+		t.Fatalf("reset client preset = %d %s", rec.Code, rec.Body.String())
+	}
+}
+
+func TestPlaybackClientHandshakeIncludesEQPreset(t *testing.T) {
+	user := uuid.New()
+	presets := &fakeEQPresets{
+		presets:  []db.EQPreset{{ID: 1, UserID: user, Name: "Flat", Bands: []db.EQBand{{FrequencyHz: 1000}}, IsDefault: true}},
+		override: map[int64]int64{},
+	}
+	h := NewPlaybackClientHandlers(&fakePlaybackClients{clients: map[int64]*db.PlaybackClient{}}).WithEQPresets(presets)
+
+	rec := httptest.NewRecorder()
+	h.RegisterClient(rec, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/playback/clients", strings.NewReader(`{"name":"Phone","codecs":["aac"]}`)), user))
+	var resp PlaybackClientResponse
+	_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+	if rec.Code != http.StatusOK || resp.EQ == nil || resp.EQ.Name != "Flat" || resp.EQPresetID != nil {
+		t.Fatalf("handshake = %d %s; want the default preset", rec.Code, rec.Body.String())
+	}
+}
@@ -0,0 +1,79 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"math"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type eventLog interface {
+	ListSince(ctx context.Context, userID uuid.UUID, after int64, limit int) ([]db.Event, error)
+}
+
+// EventHandlers serve the change journal: every change to the caller's
+// library, favorites, ratings and playlists, and to the catalog tracks in
+// their library, in the order the events log recorded them. A client syncs
+// by passing the cursor of its last page back as since.
+type EventHandlers struct {
+	events eventLog
+}
+
+func NewEventHandlers(events eventLog) *EventHandlers {
+	return &EventHandlers{events: events}
+}
+
+type EventResponse struct {
+	ID         int64           `json:"id"`
+	ActorID    *uuid.UUID      `json:"actorId,omitempty"`
+	EntityType string          `json:"entityType"`
+	EntityID   int64           `json:"entityId"`
+	Action     string          `json:"action"`
+	Payload    json.RawMessage `json:"payload"`
+	CreatedAt  time.Time       `json:"createdAt"`
+}
+
+// EventListResponse is one page of the journal. Cursor is the since to ask
+// for the next page with; HasMore reports whether that page has events yet.
+type EventListResponse struct {
+	Events  []EventResponse `json:"events"`
+	Cursor  int64           `json:"cursor"`
+	HasMore bool            `json:"hasMore"`
+}
+
+// ListEvents handles GET /api/v1/me/events?since=&limit=.
+func (h *EventHandlers) ListEvents(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	since := int64(query.Int("since", 0, 0, math.MaxInt))
+	limit := query.Limit(maxPageLimit)
+	if !query.Valid(w, r) {
+		return
+	}
+	// One event more than asked for tells whether another page follows.
+	events, err := h.events.ListSince(r.Context(), userCtx.UserID, since, limit+1)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list events")
+		return
+	}
+	resp := EventListResponse{Events: make([]EventResponse, 0, min(len(events), limit)), Cursor: since, HasMore: len(events) > limit}
+	for _, e := range events[:min(len(events), limit)] {
+		event := EventResponse{ID: e.ID, EntityType: e.EntityType, EntityID: e.EntityID, Action: e.Action, Payload: e.Payload, CreatedAt: e.CreatedAt}
+		if e.ActorID.Valid {
+			event.ActorID = &e.ActorID.UUID
+		}
+		resp.Events = append(resp.Events, event)
+		resp.Cursor = e.ID
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
@@ -0,0 +1,64 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeEventLog struct {
+	events []db.Event
+}
+
+func (f *fakeEventLog) ListSince(_ context.Context, userID uuid.UUID, after int64, limit int) ([]db.Event, error) {
+	var out []db.Event
+	for _, e := range f.events {
+		if e.ID > after && e.UserID.UUID == userID && len(out) < limit {
+			out = append(out, e)
+		}
+	}
+	return out, nil
+}
+
+func TestListEventsPagesWithACursor(t *testing.T) {
+	userID := uuid.New()
+	owner := uuid.NullUUID{UUID: userID, Valid: true}
+	log := &fakeEventLog{}
+	for id := int64(1); id <= 3; id++ {
+		log.events = append(log.events, db.Event{ID: id, UserID: owner, ActorID: owner, EntityType: db.EventEntityTrack, EntityID: 10 + id, Action: db.EventLibraryAdded, Payload: json.RawMessage(`{}`)})
+	}
+	log.events = append(log.events, db.Event{ID: 4, UserID: uuid.NullUUID{UUID: uuid.New(), Valid: true}, Action: db.EventFavorited, Payload: json.RawMessage(`{}`)})
+	h := NewEventHandlers(log)
+
+	list := func(query string) (*httptest.ResponseRecorder, EventListResponse) {
+		rec := httptest.NewRecorder()
+		h.ListEvents(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/events"+query, nil), userID))
+		var resp EventListResponse
+		_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+		return rec, resp
+	}
+
+	rec, page := list("?limit=2")
+	if rec.Code != http.StatusOK || len(page.Events) != 2 || page.Cursor != 2 || !page.HasMore {
+		t.Fatalf("first page = %d %+v; want events 1-2, cursor 2, more", rec.Code, page)
+	}
+	if page.Events[0].ActorID == nil || *page.Events[0].ActorID != userID || page.Events[0].EntityID != 11 {
+		t.Fatalf("first event = %+v", page.Events[0])
+	}
+	if _, page = list("?limit=2&since=2"); len(page.Events) != 1 || page.Cursor != 3 || page.HasMore {
+		t.Fatalf("second page = %+v; want event 3, cursor 3, no more", page)
+	}
+	// An empty page keeps the cursor where the client left it.
+	if _, page = list("?since=3"); len(page.Events) != 0 || page.Cursor != 3 || page.HasMore {
+		t.Fatalf("caught-up page = %+v", page)
+	}
+	if rec, _ = list("?since=-1"); rec.Code == http.StatusOK {
+		t.Fatalf("negative since = %d; want a validation error", rec.Code)
+	}
+}
@@ -0,0 +1,244 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type followStore interface {
+	Follow(ctx context.Context, followerID, followeeID uuid.UUID) (*db.FollowedUser, error)
+	Unfollow(ctx context.Context, followerID, followeeID uuid.UUID) error
+	IsFollowing(ctx context.Context, followerID, followeeID uuid.UUID) (bool, error)
+	ListFollowing(ctx context.Context, userID uuid.UUID) ([]db.FollowedUser, error)
+	ListFollowers(ctx context.Context, userID uuid.UUID) ([]db.FollowedUser, error)
+	Directory(ctx context.Context, userID uuid.UUID) ([]db.FollowedUser, error)
+}
+
+// followedPlaylistReader reads a followed user's playlists.
+// db.PlaylistRepository satisfies this interface.
+type followedPlaylistReader interface {
+	GetByUserID(ctx context.Context, userID uuid.UUID, params db.ListPlaylistsParams) ([]db.PlaylistWithTracks, int, error)
+	GetByIDWithTracks(ctx context.Context, id int64) (*db.PlaylistWithTracks, error)
+}
+
+// FollowHandlers let users of one instance follow each other. Following is
+// opt-in: only users whose settings accept followers can be followed, and
+// followers see their public playlists and, when they share it, their
+// listening in the activity feed.
+type FollowHandlers struct {
+	follows   followStore
+	playlists followedPlaylistReader
+}
+
+func NewFollowHandlers(follows followStore, playlists followedPlaylistReader) *FollowHandlers {
+	return &FollowHandlers{follows: follows, playlists: playlists}
+}
+
+// FollowUserResponse is another user as the caller sees them. FollowedAt is
+// when the follow began, for the caller's follows and followers.
+type FollowUserResponse struct {
+	ID             uuid.UUID  `json:"id"`
+	Username       string     `json:"username"`
+	ShareListening bool       `json:"shareListening"`
+	Following      bool       `json:"following"`
+	FollowedAt     *time.Time `json:"followedAt,omitempty"`
+}
+
+type FollowUserListResponse struct {
+	Items []FollowUserResponse `json:"items"`
+}
+
+// ListUsers handles GET /api/v1/users: the other users of the caller's
+// instance who accept followers.
+func (h *FollowHandlers) ListUsers(w http.ResponseWriter, r *http.Request) {
+	h.listUsers(w, r, h.follows.Directory)
+}
+
+// ListFollowing handles GET /api/v1/me/following.
+func (h *FollowHandlers) ListFollowing(w http.ResponseWriter, r *http.Request) {
+	h.listUsers(w, r, h.follows.ListFollowing)
+}
+
+// ListFollowers handles GET /api/v1/me/followers.
+func (h *FollowHandlers) ListFollowers(w http.ResponseWriter, r *http.Request) {
+	h.listUsers(w, r, h.follows.ListFollowers)
+}
+
+func (h *FollowHandlers) listUsers(w http.ResponseWriter, r *http.Request, list func(context.Context, uuid.UUID) ([]db.FollowedUser, error)) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	users, err := list(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list users")
+		return
+	}
+	resp := FollowUserListResponse{Items: make([]FollowUserResponse, 0, len(users))}
+	for _, u := range users {
+		resp.Items = append(resp.Items, newFollowUserResponse(u))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// Follow handles PUT /api/v1/users/{user_id}/follow. Following a user again
+// returns the existing follow.
+func (h *FollowHandlers) Follow(w http.ResponseWriter, r *http.Request) {
+	userCtx, userID, ok := followPathUser(w, r)
+	if !ok {
+		return
+	}
+	followee, err := h.follows.Follow(r.Context(), userCtx.UserID, userID)
+	switch {
+	case errors.Is(err, db.ErrFollowSelf):
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "you cannot follow yourself")
+		return
+	case errors.Is(err, db.ErrUserNotFound):
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "user not found")
+		return
+	case errors.Is(err, db.ErrFollowNotAccepted):
+		writeLibraryError(w, http.StatusForbidden, "FOLLOW_NOT_ACCEPTED", "user does not accept followers")
+		return
+	case err != nil:
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to follow user")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, newFollowUserResponse(*followee))
+}
+
+// Unfollow handles DELETE /api/v1/users/{user_id}/follow.
+func (h *FollowHandlers) Unfollow(w http.ResponseWriter, r *http.Request) {
+	userCtx, userID, ok := followPathUser(w, r)
+	if !ok {
+		return
+	}
+	h.endFollow(w, r, userCtx.UserID, userID)
+}
+
+// RemoveFollower handles DELETE /api/v1/me/followers/{user_id}.
+func (h *FollowHandlers) RemoveFollower(w http.ResponseWriter, r *http.Request) {
+	userCtx, userID, ok := followPathUser(w, r)
+	if !ok {
+		return
+	}
+	h.endFollow(w, r, userID, userCtx.UserID)
+}
+
+func (h *FollowHandlers) endFollow(w http.ResponseWriter, r *http.Request, followerID, followeeID uuid.UUID) {
+	if err := h.follows.Unfollow(r.Context(), followerID, followeeID); err != nil {
+		if errors.Is(err, db.ErrFollowNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "follow not found")
+			return
+		}
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to remove follow")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// ListUserPlaylists handles GET /api/v1/users/{user_id}/playlists: the
+// public playlists of a user the caller follows.
+func (h *FollowHandlers) ListUserPlaylists(w http.ResponseWriter, r *http.Request) {
+	userCtx, userID, ok := followPathUser(w, r)
+	if !ok {
+		return
+	}
+	query := newQueryParams(r)
+	params := db.ListPlaylistsParams{
+		Query:      query.Text("q", maxQueryTextRunes),
+		Sort:       query.Enum("sort", "", "updated_at", "name", "track_count"),
+		Order:      query.Enum("order", "", "asc", "desc"),
+		Limit:      query.Limit(20),
+		Offset:     query.Offset(),
+		PublicOnly: true,
+	}
+	if !query.Valid(w, r) {
+		return
+	}
+	if !h.requireFollowing(w, r, userCtx.UserID, userID) {
+		return
+	}
+	playlists, total, err := h.playlists.GetByUserID(r.Context(), userID, params)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list playlists")
+		return
+	}
+	responses := make([]PlaylistResponse, 0, len(playlists))
+	for _, p := range playlists {
+		responses = append(responses, newPlaylistResponse(&p))
+	}
+	writeLibraryJSON(w, http.StatusOK, PaginatedPlaylistResponse{Data: responses, Total: total, Limit: params.Limit, Offset: params.Offset})
+}
+
+// GetUserPlaylist handles GET /api/v1/users/{user_id}/playlists/{id}. Only
+// public playlists of a followed user are found.
+func (h *FollowHandlers) GetUserPlaylist(w http.ResponseWriter, r *http.Request) {
+	userCtx, userID, ok := followPathUser(w, r)
+	if !ok {
+		return
+	}
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+	if !h.requireFollowing(w, r, userCtx.UserID, userID) {
+		return
+	}
+	playlist, err := h.playlists.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil && !errors.Is(err, db.ErrPlaylistNotFound) {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get playlist")
+		return
+	}
+	if playlist == nil || playlist.UserID != userID || !playlist.IsPublic {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, newPlaylistWithTracksResponse(playlist, mapTrackResponses(playlist.Tracks)))
+}
+
+// requireFollowing answers 403 unless followerID follows followeeID.
+func (h *FollowHandlers) requireFollowing(w http.ResponseWriter, r *http.Request, followerID, followeeID uuid.UUID) bool {
+	following, err := h.follows.IsFollowing(r.Context(), followerID, followeeID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to check follow")
+		return false
+	}
+	if !following {
+		writeLibraryError(w, http.StatusForbidden, "FORBIDDEN", "follow this user to see their playlists")
+		return false
+	}
+	return true
+}
+
+// followPathUser reads the caller and the user_id path value, answering the
+// request itself when either is missing or invalid.
+func followPathUser(w http.ResponseWriter, r *http.Request) (*auth.UserContext, uuid.UUID, bool) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return nil, uuid.Nil, false
+	}
+	userID, err := uuid.Parse(r.PathValue("user_id"))
+	if err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid user_id")
+		return nil, uuid.Nil, false
+	}
+	return userCtx, userID, true
+}
+
+func newFollowUserResponse(u db.FollowedUser) FollowUserResponse {
+	resp := FollowUserResponse{ID: u.UserID, Username: u.Username, ShareListening: u.ShareListening, Following: u.Following}
+	if !u.FollowedAt.IsZero() {
+		resp.FollowedAt = &u.FollowedAt
+	}
+	return resp
+}
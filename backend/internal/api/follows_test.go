@@ -0,0 +1,130 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeFollows struct {
+	accepting map[uuid.UUID]bool
+	follows   map[[2]uuid.UUID]bool
+}
+
+func (f *fakeFollows) Follow(ctx context.Context, followerID, followeeID uuid.UUID) (*db.FollowedUser, error) {
+	if followerID == followeeID {
+		return nil, db.ErrFollowSelf
+	}
+	accepts, ok := f.accepting[followeeID]
+	if !ok {
+		return nil, db.ErrUserNotFound
+	}
+	if !accepts {
+		return nil, db.ErrFollowNotAccepted
+	}
+	f.follows[[2]uuid.UUID{followerID, followeeID}] = true
+	return &db.FollowedUser{UserID: followeeID, Username: "friend", Following: true, FollowedAt: time.Now()}, nil
+}
+
+func (f *fakeFollows) Unfollow(ctx context.Context, followerID, followeeID uuid.UUID) error {
+	key := [2]uuid.UUID{followerID, followeeID}
+	if !f.follows[key] {
+		return db.ErrFollowNotFound
+	}
+	delete(f.follows, key)
+	return nil
+}
+
+func (f *fakeFollows) IsFollowing(ctx context.Context, followerID, followeeID uuid.UUID) (bool, error) {
+	return f.follows[[2]uuid.UUID{followerID, followeeID}], nil
+}
+
+func (f *fakeFollows) ListFollowing(ctx context.Context, userID uuid.UUID) ([]db.FollowedUser, error) {
+	return nil, nil
+}
+
+func (f *fakeFollows) ListFollowers(ctx context.Context, userID uuid.UUID) ([]db.FollowedUser, error) {
+	return nil, nil
+}
+
+func (f *fakeFollows) Directory(ctx context.Context, userID uuid.UUID) ([]db.FollowedUser, error) {
+	return nil, nil
+}
+
+type fakeFollowedPlaylists struct {
+	playlists []db.PlaylistWithTracks
+	params    db.ListPlaylistsParams
+}
+
+func (f *fakeFollowedPlaylists) GetByUserID(ctx context.Context, userID uuid.UUID, params db.ListPlaylistsParams) ([]db.PlaylistWithTracks, int, error) {
+	f.params = params
+	return f.playlists, len(f.playlists), nil
+}
+
+func (f *fakeFollowedPlaylists) GetByIDWithTracks(ctx context.Context, id int64) (*db.PlaylistWithTracks, error) {
+	for i := range f.playlists {
+		if f.playlists[i].ID == id {
+			return &f.playlists[i], nil
+		}
+	}
+	return nil, db.ErrPlaylistNotFound
+}
+
+func TestFollowHandlersShowPublicPlaylistsToFollowers(t *testing.T) {
+	me, friend, private := uuid.New(), uuid.New(), uuid.New()
+	follows := &fakeFollows{accepting: map[uuid.UUID]bool{friend: true, private: false}, follows: map[[2]uuid.UUID]bool{}}
+	playlists := &fakeFollowedPlaylists{playlists: []db.PlaylistWithTracks{
+		{Playlist: db.Playlist{ID: 1, UserID: friend, Name: "Shared", IsPublic: true}},
+		{Playlist: db.Playlist{ID: 2, UserID: friend, Name: "Secret"}},
+	}}
+	h := NewFollowHandlers(follows, playlists)
+	call := func(handler http.HandlerFunc, method, target string, pathValues ...string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(method, target, nil)
+		for i := 0; i+1 < len(pathValues); i += 2 {
+			req.SetPathValue(pathValues[i], pathValues[i+1])
+		}
+		rec := httptest.NewRecorder()
+		handler(rec, withUser(req, me))
+		return rec
+	}
+	follow := func(userID uuid.UUID) *httptest.ResponseRecorder {
+		return call(h.Follow, http.MethodPut, "/api/v1/users/"+userID.String()+"/follow", "user_id", userID.String())
+	}
+
+	if rec := call(h.ListUserPlaylists, http.MethodGet, "/api/v1/users/x/playlists", "user_id", friend.String()); rec.Code != http.StatusForbidden {
+		t.Fatalf("playlists before following = %d", rec.Code)
+	}
+	for userID, want := range map[uuid.UUID]int{private: http.StatusForbidden, uuid.New(): http.StatusNotFound, me: http.StatusBadRequest} {
+		if rec := follow(userID); rec.Code != want {
+			t.Errorf("follow = %d %s, want %d", rec.Code, rec.Body.String(), want)
+		}
+	}
+	if rec := follow(friend); rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"following":true`) {
+		t.Fatalf("follow = %d %s", rec.Code, rec.Body.String())
+	}
+
+	rec := call(h.ListUserPlaylists, http.MethodGet, "/api/v1/users/x/playlists", "user_id", friend.String())
+	if rec.Code != http.StatusOK || !playlists.params.PublicOnly {
+		t.Fatalf("playlists = %d %s, params %+v", rec.Code, rec.Body.String(), playlists.params)
+	}
+	if rec := call(h.GetUserPlaylist, http.MethodGet, "/api/v1/users/x/playlists/1", "user_id", friend.String(), "id", "1"); rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"name":"Shared"`) {
+		t.Fatalf("public playlist = %d %s", rec.Code, rec.Body.String())
+	}
+	if rec := call(h.GetUserPlaylist, http.MethodGet, "/api/v1/users/x/playlists/2", "user_id", friend.String(), "id", "2"); rec.Code != http.StatusNotFound {
+		t.Fatalf("private playlist = %d", rec.Code)
+	}
+
+	if rec := call(h.Unfollow, http.MethodDelete, "/api/v1/users/x/follow", "user_id", friend.String()); rec.Code != http.StatusNoContent {
+		t.Fatalf("unfollow = %d", rec.Code)
+	}
+	if rec := call(h.RemoveFollower, http.MethodDelete, "/api/v1/me/followers/x", "user_id", friend.String()); rec.Code != http.StatusNotFound {
+		t.Fatalf("remove a user who does not follow = %d", rec.Code)
+	}
+}
@@ -0,0 +1,229 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"log"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// maxGiftMessageLength is the longest note a gift may carry, in runes.
+const maxGiftMessageLength = 500
+
+type giftStore interface {
+	Send(ctx context.Context, gift *db.Gift) error
+	List(ctx context.Context, recipientID uuid.UUID, status string, limit, offset int) ([]db.Gift, int, error)
+	Accept(ctx context.Context, recipientID uuid.UUID, id int64) ([]int64, error)
+	Dismiss(ctx context.Context, recipientID uuid.UUID, id int64) error
+}
+
+type giftNotifier interface {
+	GiftReceived(ctx context.Context, gift *db.Gift) error
+}
+
+// GiftHandlers let users of one instance send each other a track or a
+// playlist. Gifts wait in the recipient's gift inbox until they add them to
+// their library or dismiss them.
+type GiftHandlers struct {
+	gifts    giftStore
+	notifier giftNotifier
+}
+
+// NewGiftHandlers creates the handlers. notifier, when not nil, tells
+// recipients about gifts as they are sent.
+func NewGiftHandlers(gifts giftStore, notifier giftNotifier) *GiftHandlers {
+	return &GiftHandlers{gifts: gifts, notifier: notifier}
+}
+
+// SendGiftRequest names exactly one of a track and a playlist.
+type SendGiftRequest struct {
+	TrackID    int64  `json:"trackId,omitempty"`
+	PlaylistID int64  `json:"playlistId,omitempty"`
+	Message    string `json:"message,omitempty"`
+}
+
+type GiftResponse struct {
+	ID          int64         `json:"id"`
+	From        ActivityUser  `json:"from"`
+	Track       *GiftTrack    `json:"track,omitempty"`
+	Playlist    *GiftPlaylist `json:"playlist,omitempty"`
+	Message     string        `json:"message,omitempty"`
+	Status      string        `json:"status"`
+	CreatedAt   time.Time     `json:"createdAt"`
+	RespondedAt *time.Time    `json:"respondedAt,omitempty"`
+}
+
+type GiftTrack struct {
+	ID     int64  `json:"id"`
+	Title  string `json:"title"`
+	Artist string `json:"artist"`
+}
+
+type GiftPlaylist struct {
+	ID         int64  `json:"id"`
+	Name       string `json:"name"`
+	TrackCount int    `json:"trackCount"`
+}
+
+type GiftListResponse struct {
+	Items  []GiftResponse `json:"items"`
+	Total  int            `json:"total"`
+	Limit  int            `json:"limit"`
+	Offset int            `json:"offset"`
+}
+
+// AcceptGiftResponse lists the tracks accepting added to the library;
+// tracks already there are left out.
+type AcceptGiftResponse struct {
+	AddedTrackIDs []int64 `json:"addedTrackIds"`
+}
+
+func newGiftResponse(g db.Gift) GiftResponse {
+	resp := GiftResponse{
+		ID:          g.ID,
+		From:        ActivityUser{ID: g.SenderID, Username: g.SenderUsername},
+		Message:     g.Message,
+		Status:      g.Status,
+		CreatedAt:   g.CreatedAt,
+		RespondedAt: g.RespondedAt,
+	}
+	if g.TrackID != 0 {
+		resp.Track = &GiftTrack{ID: g.TrackID, Title: g.TrackTitle, Artist: g.TrackArtist}
+	} else {
+		resp.Playlist = &GiftPlaylist{ID: g.PlaylistID, Name: g.PlaylistName, TrackCount: g.PlaylistTracks}
+	}
+	return resp
+}
+
+// SendGift handles POST /api/v1/users/{user_id}/gifts.
+func (h *GiftHandlers) SendGift(w http.ResponseWriter, r *http.Request) {
+	userCtx, userID, ok := followPathUser(w, r)
+	if !ok {
+		return
+	}
+	var req SendGiftRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	req.Message = strings.TrimSpace(req.Message)
+	if req.TrackID < 0 || req.PlaylistID < 0 || (req.TrackID > 0) == (req.PlaylistID > 0) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "give exactly one of trackId and playlistId")
+		return
+	}
+	if utf8.RuneCountInString(req.Message) > maxGiftMessageLength {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "message must be at most 500 characters")
+		return
+	}
+	gift := &db.Gift{SenderID: userCtx.UserID, RecipientID: userID, TrackID: req.TrackID, PlaylistID: req.PlaylistID, Message: req.Message}
+	switch err := h.gifts.Send(r.Context(), gift); {
+	case errors.Is(err, db.ErrGiftSelf):
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "you cannot send gifts to yourself")
+	case errors.Is(err, db.ErrUserNotFound):
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "user not found")
+	case errors.Is(err, db.ErrTrackNotFound):
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "track not found")
+	case errors.Is(err, db.ErrPlaylistNotFound):
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
+	case errors.Is(err, db.ErrGiftLimit):
+		writeLibraryError(w, http.StatusTooManyRequests, "GIFT_LIMIT", "this user has too many of your gifts waiting")
+	case err != nil:
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to send gift")
+	default:
+		if h.notifier != nil {
+			if err := h.notifier.GiftReceived(r.Context(), gift); err != nil {
+				log.Printf("Failed to notify %s of gift %d: %v", gift.RecipientID, gift.ID, err)
+			}
+		}
+		writeLibraryJSON(w, http.StatusCreated, newGiftResponse(*gift))
+	}
+}
+
+// ListGifts handles GET /api/v1/me/gifts: the gifts sent to the caller,
+// pending ones unless status says otherwise.
+func (h *GiftHandlers) ListGifts(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	status := query.Enum("status", db.GiftPending, db.GiftPending, db.GiftAccepted, db.GiftDismissed, "all")
+	limit, offset := query.Limit(50), query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
+	if status == "all" {
+		status = ""
+	}
+	gifts, total, err := h.gifts.List(r.Context(), userCtx.UserID, status, limit, offset)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list gifts")
+		return
+	}
+	resp := GiftListResponse{Items: make([]GiftResponse, 0, len(gifts)), Total: total, Limit: limit, Offset: offset}
+	for _, g := range gifts {
+		resp.Items = append(resp.Items, newGiftResponse(g))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// AcceptGift handles POST /api/v1/me/gifts/{id}/accept: the gift's track,
+// or the playlist's tracks, join the caller's library.
+func (h *GiftHandlers) AcceptGift(w http.ResponseWriter, r *http.Request) {
+	userCtx, id, ok := giftPathID(w, r)
+	if !ok {
+		return
+	}
+	added, err := h.gifts.Accept(r.Context(), userCtx.UserID, id)
+	if errors.Is(err, db.ErrGiftNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "gift not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to accept gift")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, AcceptGiftResponse{AddedTrackIDs: added})
+}
+
+// DismissGift handles DELETE /api/v1/me/gifts/{id}.
+func (h *GiftHandlers) DismissGift(w http.ResponseWriter, r *http.Request) {
+	userCtx, id, ok := giftPathID(w, r)
+	if !ok {
+		return
+	}
+	err := h.gifts.Dismiss(r.Context(), userCtx.UserID, id)
+	if errors.Is(err, db.ErrGiftNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "gift not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to dismiss gift")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func giftPathID(w http.ResponseWriter, r *http.Request) (*auth.UserContext, int64, bool) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return nil, 0, false
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil || id <= 0 {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid gift ID")
+		return nil, 0, false
+	}
+	return userCtx, id, true
+}
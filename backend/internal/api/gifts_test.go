@@ -0,0 +1,124 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeGifts struct {
+	sent   []db.Gift
+	status string
+}
+
+func (f *fakeGifts) Send(ctx context.Context, gift *db.Gift) error {
+	if gift.SenderID == gift.RecipientID {
+		return db.ErrGiftSelf
+	}
+	if gift.TrackID == 404 {
+		return db.ErrTrackNotFound
+	}
+	gift.ID = int64(len(f.sent) + 1)
+	gift.SenderUsername = "sender"
+	gift.TrackTitle = "Gifted"
+	gift.Status = db.GiftPending
+	gift.CreatedAt = time.Now()
+	f.sent = append(f.sent, *gift)
+	return nil
+}
+
+func (f *fakeGifts) List(ctx context.Context, recipientID uuid.UUID, status string, limit, offset int) ([]db.Gift, int, error) {
+	f.status = status
+	return f.sent, len(f.sent), nil
+}
+
+func (f *fakeGifts) Accept(ctx context.Context, recipientID uuid.UUID, id int64) ([]int64, error) {
+	for _, gift := range f.sent {
+		if gift.ID == id && gift.RecipientID == recipientID {
+			return []int64{gift.TrackID}, nil
+		}
+	}
+	return nil, db.ErrGiftNotFound
+}
+
+func (f *fakeGifts) Dismiss(ctx context.Context, recipientID uuid.UUID, id int64) error {
+	return db.ErrGiftNotFound
+}
+
+type recordingGiftNotifier struct {
+	gifts []int64
+}
+
+func (n *recordingGiftNotifier) GiftReceived(ctx context.Context, gift *db.Gift) error {
+	n.gifts = append(n.gifts, gift.ID)
+	return nil
+}
+
+func TestGiftHandlers(t *testing.T) {
+	sender, recipient := uuid.New(), uuid.New()
+	gifts, notifier := &fakeGifts{}, &recordingGiftNotifier{}
+	h := NewGiftHandlers(gifts, notifier)
+	send := func(to uuid.UUID, body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/users/x/gifts", strings.NewReader(body))
+		req.SetPathValue("user_id", to.String())
+		rec := httptest.NewRecorder()
+		h.SendGift(rec, withUser(req, sender))
+		return rec
+	}
+
+	for body, want := range map[string]int{
+		`{"message":"hi"}`:             http.StatusBadRequest,
+		`{"trackId":1,"playlistId":2}`: http.StatusBadRequest,
+		`{"trackId":-1}`:               http.StatusBadRequest,
+		`{"trackId":404}`:              http.StatusNotFound,
+	} {
+		if rec := send(recipient, body); rec.Code != want {
+			t.Errorf("send %s = %d, want %d", body, rec.Code, want)
+		}
+	}
+	if rec := send(recipient, `{"trackId":1,"message":"` + strings.Repeat("x", 501) + `"}`); rec.Code != http.StatusBadRequest {
+		t.Errorf("send with a long message = %d", rec.Code)
+	}
+	if rec := send(sender, `{"trackId":1}`); rec.Code != http.StatusBadRequest {
+		t.Errorf("send to self = %d", rec.Code)
+	}
+	rec := send(recipient, `{"trackId":7,"message":" listen to this "}`)
+	if rec.Code != http.StatusCreated || !strings.Contains(rec.Body.String(), `"message":"listen to this"`) || !strings.Contains(rec.Body.String(), `"track":{"id":7`) {
+		t.Fatalf("send = %d %s", rec.Code, rec.Body.String())
+	}
+	if len(notifier.gifts) != 1 {
+		t.Fatalf("notified about %v; want only the gift that was sent", notifier.gifts)
+	}
+
+	rec = httptest.NewRecorder()
+	h.ListGifts(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/gifts", nil), recipient))
+	if rec.Code != http.StatusOK || gifts.status != db.GiftPending {
+		t.Fatalf("list = %d, status filter %q", rec.Code, gifts.status)
+	}
+	rec = httptest.NewRecorder()
+	h.ListGifts(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/gifts?status=all", nil), recipient))
+	if rec.Code != http.StatusOK || gifts.status != "" {
+		t.Fatalf("list all = %d, status filter %q", rec.Code, gifts.status)
+	}
+
+	accept := func(userID uuid.UUID) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/me/gifts/1/accept", nil)
+		req.SetPathValue("id", "1")
+		rec := httptest.NewRecorder()
+		h.AcceptGift(rec, withUser(req, userID))
+		return rec
+	}
+	if rec := accept(sender); rec.Code != http.StatusNotFound {
+		t.Errorf("accept by the sender = %d", rec.Code)
+	}
+	if rec := accept(recipient); rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"addedTrackIds":[7]`) {
+		t.Errorf("accept = %d %s", rec.Code, rec.Body.String())
+	}
+}
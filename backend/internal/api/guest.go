@@ -0,0 +1,260 @@
+package api
+
+import (
+	"context"
+	"net"
+	"net/http"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	// guestURLTTL keeps guest stream URLs short-lived, so they are of little
+	// use beyond the listening session they were issued for.
+	guestURLTTL     = 5 * time.Minute
+	guestRateWindow = time.Minute
+	// maxGuestClients bounds the rate limit windows held at once.
+	maxGuestClients = 10000
+)
+
+type guestPlaylistRepository interface {
+	GetManyWithTracks(ctx context.Context, ids []int64) (map[int64]*db.PlaylistWithTracks, error)
+}
+
+// GuestHandlers serve the public shelf to visitors without an account: the
+// playlists the operator listed in GUEST_SHELF_PLAYLISTS, browsable and
+// streamable but never changed. Guests have no user, so every other route
+// stays closed to them, and each client is rate limited.
+type GuestHandlers struct {
+	shelf     []int64
+	playlists guestPlaylistRepository
+	storage   playbackURLStorage
+	limiter   *guestLimiter
+	now       func() time.Time
+}
+
+func NewGuestHandlers(shelf []int64, playlists guestPlaylistRepository, storageClient playbackURLStorage, requestsPerMinute int) *GuestHandlers {
+	return &GuestHandlers{
+		shelf:     shelf,
+		playlists: playlists,
+		storage:   storageClient,
+		limiter:   &guestLimiter{limit: requestsPerMinute, windows: map[string]guestWindow{}},
+		now:       time.Now,
+	}
+}
+
+type GuestShelfResponse struct {
+	Playlists []GuestPlaylist `json:"playlists"`
+}
+
+type GuestPlaylist struct {
+	ID          int64        `json:"id"`
+	Name        string       `json:"name"`
+	Description string       `json:"description,omitempty"`
+	Tracks      []GuestTrack `json:"tracks"`
+}
+
+type GuestTrack struct {
+	ID         int64  `json:"id"`
+	Title      string `json:"title"`
+	Artist     string `json:"artist,omitempty"`
+	Album      string `json:"album,omitempty"`
+	DurationMs int    `json:"durationMs,omitempty"`
+}
+
+// RateLimit rejects guest requests past the per-client limit with 429.
+func (h *GuestHandlers) RateLimit(next http.HandlerFunc) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		if ok, retryAfter := h.limiter.allow(guestClient(r), h.now()); !ok {
+			w.Header().Set("Retry-After", strconv.Itoa(int(retryAfter.Seconds())+1))
+			writePlaybackError(w, http.StatusTooManyRequests, "RATE_LIMITED", "too many guest requests; try again shortly")
+			return
+		}
+		next(w, r)
+	}
+}
+
+// GetShelf handles GET /api/v1/guest/shelf.
+func (h *GuestHandlers) GetShelf(w http.ResponseWriter, r *http.Request) {
+	shelf, err := h.loadShelf(r.Context())
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load shelf")
+		return
+	}
+	resp := GuestShelfResponse{Playlists: make([]GuestPlaylist, 0, len(shelf))}
+	for _, playlist := range shelf {
+		item := GuestPlaylist{
+			ID:          playlist.ID,
+			Name:        playlist.Name,
+			Description: playlist.Description.String,
+			Tracks:      make([]GuestTrack, 0, len(playlist.Tracks)),
+		}
+		for _, track := range playlist.Tracks {
+			item.Tracks = append(item.Tracks, GuestTrack{
+				ID:         track.ID,
+				Title:      track.Title,
+				Artist:     track.Artist.String,
+				Album:      track.Album.String,
+				DurationMs: int(track.DurationMs.Int32),
+			})
+		}
+		resp.Playlists = append(resp.Playlists, item)
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// CreatePlaybackURLs handles POST /api/v1/guest/playback/urls. Only tracks
+// on the shelf are issued URLs.
+func (h *GuestHandlers) CreatePlaybackURLs(w http.ResponseWriter, r *http.Request) {
+	var req struct {
+		TrackIDs []int64 `json:"trackIds"`
+	}
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid JSON request body")
+		return
+	}
+	trackIDs, err := validateAndDedupeTrackIDs(req.TrackIDs)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "INVALID_REQUEST", err.Error())
+		return
+	}
+	if len(trackIDs) == 0 || len(trackIDs) > maxPlaybackURLBatch {
+		writePlaybackError(w, http.StatusBadRequest, "INVALID_REQUEST", "trackIds must name 1 to "+strconv.Itoa(maxPlaybackURLBatch)+" tracks")
+		return
+	}
+	shelf, err := h.loadShelf(r.Context())
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load shelf")
+		return
+	}
+	onShelf := map[int64]db.Track{}
+	for _, playlist := range shelf {
+		for _, track := range playlist.Tracks {
+			onShelf[track.ID] = track
+		}
+	}
+
+	expiresAt := h.now().Add(guestURLTTL).UTC()
+	resp := PlaybackURLResponse{URLs: make([]PlaybackURLItem, 0, len(trackIDs))}
+	for _, trackID := range trackIDs {
+		track, ok := onShelf[trackID]
+		if !ok {
+			writePlaybackError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		storageKey := strings.TrimSpace(track.StorageKey.String)
+		if storageKey == "" {
+			resp.Unavailable = append(resp.Unavailable, PlaybackUnavailableItem{
+				TrackID: trackID,
+				Code:    playbackUnavailableCodeAudioUnavailable,
+				Message: "track has no stored audio object",
+			})
+			continue
+		}
+		objInfo, err := h.storage.StatObject(r.Context(), storageKey)
+		if err != nil {
+			if r.Context().Err() != nil {
+				return
+			}
+			resp.Unavailable = append(resp.Unavailable, PlaybackUnavailableItem{
+				TrackID: trackID,
+				Code:    playbackUnavailableCodeArtifactMissing,
+				Message: "stored audio object is unavailable",
+			})
+			continue
+		}
+		url, err := h.storage.PresignGetObject(r.Context(), storageKey, guestURLTTL)
+		if err != nil {
+			if r.Context().Err() != nil {
+				return
+			}
+			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to issue playback URL")
+			return
+		}
+		resp.URLs = append(resp.URLs, PlaybackURLItem{
+			TrackID:     trackID,
+			URL:         url,
+			ExpiresAt:   expiresAt,
+			ContentType: playbackContentType(storageKey, objInfo.ContentType),
+			SizeBytes:   objInfo.Size,
+		})
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// loadShelf loads the shelf playlists in one query, in configured order,
+// leaving out ones that have since been deleted.
+func (h *GuestHandlers) loadShelf(ctx context.Context) ([]*db.PlaylistWithTracks, error) {
+	playlists, err := h.playlists.GetManyWithTracks(ctx, h.shelf)
+	if err != nil {
+		return nil, err
+	}
+	shelf := make([]*db.PlaylistWithTracks, 0, len(h.shelf))
+	for _, id := range h.shelf {
+		if playlist, ok := playlists[id]; ok {
+			shelf = append(shelf, playlist)
+		}
+	}
+	return shelf, nil
+}
+
+// guestClient identifies a guest by the address the request came from.
+// middleware.Proxy only takes it from X-Forwarded-For for requests sent by a
+// trusted proxy, since guests could set that header freely.
+func guestClient(r *http.Request) string {
+	host, _, err := net.SplitHostPort(r.RemoteAddr)
+	if err != nil {
+		return r.RemoteAddr
+	}
+	return host
+}
+
+// guestLimiter counts requests per client in fixed windows.
+type guestLimiter struct {
+	limit int
+
+	mu      sync.Mutex
+	windows map[string]guestWindow
+}
+
+type guestWindow struct {
+	started time.Time
+	count   int
+}
+
+// allow counts one request from client, reporting whether it is within the
+// limit and, when it is not, how long until the client's window resets.
+func (l *guestLimiter) allow(client string, now time.Time) (bool, time.Duration) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	window, ok := l.windows[client]
+	if !ok || !now.Before(window.started.Add(guestRateWindow)) {
+		if !ok && len(l.windows) >= maxGuestClients {
+			l.sweepLocked(now)
+		}
+		window = guestWindow{started: now}
+	}
+	if window.count >= l.limit {
+		return false, window.started.Add(guestRateWindow).Sub(now)
+	}
+	window.count++
+	l.windows[client] = window
+	return true, 0
+}
+
+// sweepLocked drops expired windows, or every window when none has expired,
+// so a flood of clients cannot grow the map without bound.
+func (l *guestLimiter) sweepLocked(now time.Time) {
+	for client, window := range l.windows {
+		if !now.Before(window.started.Add(guestRateWindow)) {
+			delete(l.windows, client)
+		}
+	}
+	if len(l.windows) >= maxGuestClients {
+		clear(l.windows)
+	}
+}
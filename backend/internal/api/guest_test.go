@@ -0,0 +1,129 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+type fakeGuestPlaylists map[int64]*db.PlaylistWithTracks
+
+func (f fakeGuestPlaylists) GetManyWithTracks(_ context.Context, ids []int64) (map[int64]*db.PlaylistWithTracks, error) {
+	playlists := make(map[int64]*db.PlaylistWithTracks, len(ids))
+	for _, id := range ids {
+		if playlist, ok := f[id]; ok {
+			playlists[id] = playlist
+		}
+	}
+	return playlists, nil
+}
+
+func newGuestTestHandlers(limit int) (*GuestHandlers, *fakePlaybackStorage) {
+	playlists := fakeGuestPlaylists{
+		1: {Playlist: db.Playlist{ID: 1, Name: "Staff picks"}, Tracks: []db.Track{
+			{ID: 10, Title: "Starfire", Artist: sql.NullString{String: "Low", Valid: true}, StorageKey: sql.NullString{String: "tracks/a.mp3", Valid: true}},
+			{ID: 11, Title: "Lullaby"},
+		}},
+		2: {Playlist: db.Playlist{ID: 2, Name: "Private"}, Tracks: []db.Track{{ID: 20, Title: "Secret"}}},
+	}
+	objects := &fakePlaybackStorage{info: map[string]*storage.ObjectInfo{"tracks/a.mp3": {Size: 1024, ContentType: "audio/mpeg"}}}
+	// Playlist 3 was deleted after the operator listed it.
+	return NewGuestHandlers([]int64{1, 3}, playlists, objects, limit), objects
+}
+
+func TestGuestShelfListsOnlyDesignatedPlaylists(t *testing.T) {
+	h, _ := newGuestTestHandlers(60)
+	rec := httptest.NewRecorder()
+	h.GetShelf(rec, httptest.NewRequest(http.MethodGet, "/api/v1/guest/shelf", nil))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("shelf = %d %s", rec.Code, rec.Body.String())
+	}
+	var resp GuestShelfResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatal(err)
+	}
+	if len(resp.Playlists) != 1 || resp.Playlists[0].Name != "Staff picks" || len(resp.Playlists[0].Tracks) != 2 || resp.Playlists[0].Tracks[0].Artist != "Low" {
+		t.Fatalf("shelf = %+v", resp)
+	}
+	if strings.Contains(rec.Body.String(), "storage") || strings.Contains(rec.Body.String(), "tracks/a.mp3") {
+		t.Fatalf("shelf leaks storage details: %s", rec.Body.String())
+	}
+}
+
+func TestGuestPlaybackURLsAreLimitedToTheShelf(t *testing.T) {
+	h, objects := newGuestTestHandlers(60)
+	issue := func(body string) *httptest.ResponseRecorder {
+		rec := httptest.NewRecorder()
+		h.CreatePlaybackURLs(rec, httptest.NewRequest(http.MethodPost, "/api/v1/guest/playback/urls", strings.NewReader(body)))
+		return rec
+	}
+
+	rec := issue(`{"trackIds":[10,11]}`)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("urls = %d %s", rec.Code, rec.Body.String())
+	}
+	var resp PlaybackURLResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatal(err)
+	}
+	if len(resp.URLs) != 1 || resp.URLs[0].TrackID != 10 || len(resp.Unavailable) != 1 || resp.Unavailable[0].TrackID != 11 {
+		t.Fatalf("urls = %+v", resp)
+	}
+	if objects.lastTTL != guestURLTTL {
+		t.Fatalf("URL TTL = %v, want %v", objects.lastTTL, guestURLTTL)
+	}
+
+	tests := []struct {
+		body string
+		want int
+	}{
+		{`{"trackIds":[20]}`, http.StatusNotFound},
+		{`{"trackIds":[]}`, http.StatusBadRequest},
+		{`{"trackIds":[10],"ttlSeconds":1800}`, http.StatusBadRequest},
+	}
+	for _, tt := range tests {
+		if rec := issue(tt.body); rec.Code != tt.want {
+			t.Errorf("%s: status = %d, want %d", tt.body, rec.Code, tt.want)
+		}
+	}
+}
+
+func TestGuestRateLimitIsPerClient(t *testing.T) {
+	h, _ := newGuestTestHandlers(2)
+	now := time.Date(2024, 5, 1, 12, 0, 0, 0, time.UTC)
+	h.now = func() time.Time { return now }
+	handler := h.RateLimit(h.GetShelf)
+	get := func(addr string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodGet, "/api/v1/guest/shelf", nil)
+		req.RemoteAddr = addr
+		req.Header.Set("X-Forwarded-For", "198.51.100.9")
+		rec := httptest.NewRecorder()
+		handler(rec, req)
+		return rec
+	}
+
+	for i := 0; i < 2; i++ {
+		if rec := get("203.0.113.5:4000"); rec.Code != http.StatusOK {
+			t.Fatalf("request %d = %d", i+1, rec.Code)
+		}
+	}
+	rec := get("203.0.113.5:4001")
+	if rec.Code != http.StatusTooManyRequests || rec.Header().Get("Retry-After") != "61" {
+		t.Fatalf("over limit = %d, Retry-After %q", rec.Code, rec.Header().Get("Retry-After"))
+	}
+	if rec := get("203.0.113.6:4000"); rec.Code != http.StatusOK {
+		t.Fatalf("another client = %d", rec.Code)
+	}
+	now = now.Add(guestRateWindow)
+	if rec := get("203.0.113.5:4000"); rec.Code != http.StatusOK {
+		t.Fatalf("next window = %d", rec.Code)
+	}
+}
@@ -0,0 +1,61 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"io"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/playhistory"
+)
+
+// maxHistoryImportBytes bounds an uploaded play history file. A Last.fm
+// export of several hundred thousand scrobbles fits.
+const maxHistoryImportBytes = 64 << 20
+
+type playHistoryImporter interface {
+	Import(ctx context.Context, userID uuid.UUID, format string, r io.Reader) (*playhistory.Result, error)
+}
+
+// HistoryImportHandlers backfill the caller's play history from listening
+// done before the server, such as a Last.fm scrobble export.
+type HistoryImportHandlers struct {
+	importer playHistoryImporter
+}
+
+func NewHistoryImportHandlers(importer playHistoryImporter) *HistoryImportHandlers {
+	return &HistoryImportHandlers{importer: importer}
+}
+
+// ImportPlayHistory handles POST /api/v1/imports/play-history?format=. The
+// request body is the file itself. Importing a file again records only
+// entries that were not recorded before.
+func (h *HistoryImportHandlers) ImportPlayHistory(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+
+	r.Body = http.MaxBytesReader(w, r.Body, maxHistoryImportBytes)
+	result, err := h.importer.Import(r.Context(), userCtx.UserID, r.URL.Query().Get("format"), r.Body)
+	var maxBytesError *http.MaxBytesError
+	switch {
+	case errors.As(err, &maxBytesError):
+		writeMaintenanceError(w, http.StatusRequestEntityTooLarge, "HISTORY_TOO_LARGE", "play history file is too large")
+		return
+	case errors.Is(err, playhistory.ErrUnknownFormat):
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "format must be one of: "+playhistory.FormatLastFMCSV+", "+playhistory.FormatYTDLPArchive)
+		return
+	case errors.Is(err, playhistory.ErrInvalidFile):
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	case err != nil:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to import play history")
+		return
+	}
+	writeMaintenanceJSON(w, http.StatusOK, result)
+}
@@ -0,0 +1,65 @@
+package api
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/playhistory"
+)
+
+type fakePlayHistoryImporter struct {
+	body string
+}
+
+func (f *fakePlayHistoryImporter) Import(ctx context.Context, userID uuid.UUID, format string, r io.Reader) (*playhistory.Result, error) {
+	data, err := io.ReadAll(r)
+	if err != nil {
+		return nil, err
+	}
+	switch format {
+	case playhistory.FormatLastFMCSV:
+	case playhistory.FormatYTDLPArchive:
+		return nil, fmt.Errorf("%w: line too long", playhistory.ErrInvalidFile)
+	default:
+		return nil, fmt.Errorf("%w: %q", playhistory.ErrUnknownFormat, format)
+	}
+	f.body = string(data)
+	return &playhistory.Result{Format: format, Entries: 1, Matched: 1, Recorded: 1}, nil
+}
+
+func TestImportPlayHistoryHTTP(t *testing.T) {
+	importer := &fakePlayHistoryImporter{}
+	h := NewHistoryImportHandlers(importer)
+	userID := uuid.New()
+	post := func(format, body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/imports/play-history?format="+format, strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.ImportPlayHistory(rec, withUser(req, userID))
+		return rec
+	}
+
+	rec := post(playhistory.FormatLastFMCSV, "Low,Things We Lost in the Fire,Sunflower,01 Feb 2021 10:00\n")
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"recorded":1`) || !strings.HasPrefix(importer.body, "Low,") {
+		t.Fatalf("import = %d %s", rec.Code, rec.Body.String())
+	}
+	if rec := post("itunes-xml", ""); rec.Code != http.StatusBadRequest {
+		t.Fatalf("unknown format = %d %s", rec.Code, rec.Body.String())
+	}
+	if rec := post(playhistory.FormatYTDLPArchive, "youtube x\n"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("invalid file = %d %s", rec.Code, rec.Body.String())
+	}
+
+	req := httptest.NewRequest(http.MethodPost, "/api/v1/imports/play-history?format=lastfm-csv", strings.NewReader(""))
+	rec = httptest.NewRecorder()
+	h.ImportPlayHistory(rec, req)
+	if rec.Code != http.StatusUnauthorized {
+		t.Fatalf("anonymous import = %d", rec.Code)
+	}
+}
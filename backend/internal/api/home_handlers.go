@@ -0,0 +1,365 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"sync"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/cache"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// homeCacheTTL bounds how stale a cached home payload can be. Plays and
+// library changes show up on the home screen within this window.
+const homeCacheTTL = 30 * time.Second
+
+// homeNewReleaseDays is the window for the new releases section.
+const homeNewReleaseDays = 30
+
+// homeSuggestionLimit caps the suggestions for the current moment.
+const homeSuggestionLimit = 6
+
+type homePlayStore interface {
+	RecentlyPlayed(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.RecentlyPlayedTrack, error)
+	TopTracks(ctx context.Context, userID uuid.UUID, days, limit int) ([]db.TopTrack, error)
+	RecentContexts(ctx context.Context, userID uuid.UUID, limit int) ([]db.RecentPlayContext, error)
+}
+
+type homeLibraryStore interface {
+	GetUserLibrary(ctx context.Context, userID uuid.UUID, opts db.LibraryQueryOptions) ([]db.LibraryTrack, int, error)
+	NewTracksFromLibraryArtists(ctx context.Context, userID uuid.UUID, days, limit int) ([]db.Track, error)
+}
+
+type homeListenLaterStore interface {
+	List(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.ListenLaterItem, int, error)
+}
+
+type homeDailyMixStore interface {
+	List(ctx context.Context, userID uuid.UUID) ([]db.DailyMix, error)
+}
+
+type homeCache interface {
+	Get(ctx context.Context, key string) (string, bool)
+	Set(ctx context.Context, key string, value string, ttl time.Duration) error
+}
+
+type HomeHandlers struct {
+	plays       homePlayStore
+	library     homeLibraryStore
+	listenLater homeListenLaterStore
+	dailyMixes  homeDailyMixStore
+	cache       homeCache
+	// hours and settings back the suggestions for the current moment; the
+	// section stays empty without them or while the caller has context
+	// suggestions off.
+	hours    listeningHoursStore
+	settings userSettingsReader
+}
+
+// NewHomeHandlers builds the home handlers. listenLater and dailyMixes may be
+// nil, leaving their sections empty. redisCache may be nil, in which case
+// every request is composed fresh.
+func NewHomeHandlers(plays homePlayStore, library homeLibraryStore, listenLater homeListenLaterStore, dailyMixes homeDailyMixStore, redisCache *cache.Cache) *HomeHandlers {
+	h := &HomeHandlers{plays: plays, library: library, listenLater: listenLater, dailyMixes: dailyMixes}
+	if redisCache != nil {
+		h.cache = redisCache
+	}
+	return h
+}
+
+// WithListeningHours suggests what the caller tends to play at the current
+// time of day and week, for callers who turned on context suggestions.
+func (h *HomeHandlers) WithListeningHours(hours listeningHoursStore) *HomeHandlers {
+	h.hours = hours
+	return h
+}
+
+// HomeTrackResponse is a track on the home screen. LastPlayedAt and AddedAt
+// replace the embedded response's lastPlayedAt so each section sets only the
+// timestamp that applies to it.
+type HomeTrackResponse struct {
+	PlayEventTrackResponse
+	LastPlayedAt *time.Time `json:"lastPlayedAt,omitempty"`
+	AddedAt      *time.Time `json:"addedAt,omitempty"`
+}
+
+// HomeContextResponse is a playlist, album, artist, or radio seed to resume.
+// Name is set for playlists.
+type HomeContextResponse struct {
+	ContextType  string            `json:"contextType"`
+	ContextID    string            `json:"contextId"`
+	Name         string            `json:"name,omitempty"`
+	LastPlayedAt time.Time         `json:"lastPlayedAt"`
+	Track        HomeTrackResponse `json:"track"`
+}
+
+// HomeDailyMixResponse is one of the user's daily mixes, a read-only
+// playlist fetched through the playlist endpoints. Genre is empty for a mix
+// of the whole library and mood when the mix spans moods.
+type HomeDailyMixResponse struct {
+	PlaylistID  int64     `json:"playlistId"`
+	Name        string    `json:"name"`
+	Description string    `json:"description,omitempty"`
+	Genre       string    `json:"genre,omitempty"`
+	Mood        string    `json:"mood,omitempty"`
+	TrackCount  int       `json:"trackCount"`
+	DurationMs  int64     `json:"durationMs"`
+	GeneratedAt time.Time `json:"generatedAt"`
+}
+
+// HomeSuggestionResponse is something to play now, from what the caller
+// tends to play at this time of day and week: the library shuffled within a
+// mood, or a station seeded by an artist. Explanation says why it fits.
+type HomeSuggestionResponse struct {
+	Kind        string `json:"kind"`
+	Mood        string `json:"mood,omitempty"`
+	ArtistID    string `json:"artistId,omitempty"`
+	Artist      string `json:"artist,omitempty"`
+	Explanation string `json:"explanation"`
+}
+
+// HomeResponse is the composed home screen. Degraded names sections that
+// failed to load and are returned empty.
+type HomeResponse struct {
+	RecentlyPlayed      []HomeTrackResponse       `json:"recentlyPlayed"`
+	RecentlyAdded       []HomeTrackResponse       `json:"recentlyAdded"`
+	MostPlayedThisMonth []HomeTrackResponse       `json:"mostPlayedThisMonth"`
+	ContinueListening   []HomeContextResponse     `json:"continueListening"`
+	NewReleases         []HomeTrackResponse       `json:"newReleases"`
+	ListenLater         []ListenLaterItemResponse `json:"listenLater"`
+	DailyMixes          []HomeDailyMixResponse    `json:"dailyMixes"`
+	SuggestedNow        []HomeSuggestionResponse  `json:"suggestedNow"`
+	Degraded            []string                  `json:"degraded,omitempty"`
+	GeneratedAt         time.Time                 `json:"generatedAt"`
+}
+
+// GetHome handles GET /api/v1/home. Sections are loaded in parallel; a section
+// that fails is returned empty and listed in degraded rather than failing the
+// whole screen. Complete payloads are cached per user for homeCacheTTL.
+// Query params: limit, tz (the caller's IANA time zone, default UTC, which
+// places the current moment for suggestedNow).
+func (h *HomeHandlers) GetHome(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	query := newQueryParams(r)
+	limit := query.Int("limit", 10, 1, 50)
+	loc := query.Location("tz")
+	if !query.Valid(w, r) {
+		return
+	}
+
+	cacheKey := fmt.Sprintf("home:%s:%d:%s", userCtx.UserID, limit, loc)
+	if h.cache != nil {
+		if cached, ok := h.cache.Get(r.Context(), cacheKey); ok {
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(http.StatusOK)
+			_, _ = w.Write([]byte(cached))
+			return
+		}
+	}
+
+	resp := h.compose(r.Context(), userCtx.UserID, limit, loc)
+
+	body, err := json.Marshal(resp)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to encode home")
+		return
+	}
+	if h.cache != nil && len(resp.Degraded) == 0 {
+		_ = h.cache.Set(r.Context(), cacheKey, string(body), homeCacheTTL)
+	}
+
+	w.Header().Set("Content-Type", "application/json")
+	w.WriteHeader(http.StatusOK)
+	_, _ = w.Write(body)
+}
+
+// homeSection loads one section of the home screen into the response.
+type homeSection struct {
+	name string
+	load func() error
+}
+
+func (h *HomeHandlers) compose(ctx context.Context, userID uuid.UUID, limit int, loc *time.Location) HomeResponse {
+	resp := HomeResponse{
+		RecentlyPlayed:      []HomeTrackResponse{},
+		RecentlyAdded:       []HomeTrackResponse{},
+		MostPlayedThisMonth: []HomeTrackResponse{},
+		ContinueListening:   []HomeContextResponse{},
+		NewReleases:         []HomeTrackResponse{},
+		ListenLater:         []ListenLaterItemResponse{},
+		DailyMixes:          []HomeDailyMixResponse{},
+		SuggestedNow:        []HomeSuggestionResponse{},
+		GeneratedAt:         time.Now().UTC(),
+	}
+
+	sections := []homeSection{
+		{"recentlyPlayed", func() error {
+			tracks, err := h.plays.RecentlyPlayed(ctx, userID, limit, 0)
+			if err != nil {
+				return err
+			}
+			for _, t := range tracks {
+				item := newHomeTrackResponse(t.Track)
+				item.LastPlayedAt = timePtr(t.LastPlayedAt)
+				resp.RecentlyPlayed = append(resp.RecentlyPlayed, item)
+			}
+			return nil
+		}},
+		{"recentlyAdded", func() error {
+			tracks, _, err := h.library.GetUserLibrary(ctx, userID, db.LibraryQueryOptions{
+				Limit: limit, SortBy: "added_at", SortOrder: "desc",
+			})
+			if err != nil {
+				return err
+			}
+			for _, t := range tracks {
+				track := t.Track
+				track.AnalysisStatus = t.AnalysisStatus
+				track.AnalysisSummary = t.AnalysisSummary
+				track.AnalysisUpdatedAt = t.AnalysisUpdatedAt
+				item := newHomeTrackResponse(track)
+				item.AddedAt = timePtr(t.AddedAt)
+				resp.RecentlyAdded = append(resp.RecentlyAdded, item)
+			}
+			return nil
+		}},
+		{"mostPlayedThisMonth", func() error {
+			tracks, err := h.plays.TopTracks(ctx, userID, 30, limit)
+			if err != nil {
+				return err
+			}
+			for _, t := range tracks {
+				item := newHomeTrackResponse(t.Track)
+				item.LastPlayedAt = timePtr(t.LastPlayedAt)
+				item.PlayCount = t.PlayCount
+				resp.MostPlayedThisMonth = append(resp.MostPlayedThisMonth, item)
+			}
+			return nil
+		}},
+		{"continueListening", func() error {
+			contexts, err := h.plays.RecentContexts(ctx, userID, limit)
+			if err != nil {
+				return err
+			}
+			for _, c := range contexts {
+				item := HomeContextResponse{
+					ContextType:  c.ContextType,
+					ContextID:    c.ContextID,
+					LastPlayedAt: c.LastPlayedAt,
+					Track:        newHomeTrackResponse(c.Track),
+				}
+				item.Track.LastPlayedAt = timePtr(c.LastPlayedAt)
+				if c.Name.Valid {
+					item.Name = c.Name.String
+				}
+				resp.ContinueListening = append(resp.ContinueListening, item)
+			}
+			return nil
+		}},
+		{"newReleases", func() error {
+			tracks, err := h.library.NewTracksFromLibraryArtists(ctx, userID, homeNewReleaseDays, limit)
+			if err != nil {
+				return err
+			}
+			for _, t := range tracks {
+				item := newHomeTrackResponse(t)
+				item.AddedAt = timePtr(t.CreatedAt)
+				resp.NewReleases = append(resp.NewReleases, item)
+			}
+			return nil
+		}},
+	}
+	if h.listenLater != nil {
+		sections = append(sections, homeSection{"listenLater", func() error {
+			items, _, err := h.listenLater.List(ctx, userID, limit, 0)
+			if err != nil {
+				return err
+			}
+			for _, item := range items {
+				resp.ListenLater = append(resp.ListenLater, newListenLaterItemResponse(item))
+			}
+			return nil
+		}})
+	}
+
+	if h.dailyMixes != nil {
+		sections = append(sections, homeSection{"dailyMixes", func() error {
+			mixes, err := h.dailyMixes.List(ctx, userID)
+			if err != nil {
+				return err
+			}
+			for _, m := range mixes {
+				resp.DailyMixes = append(resp.DailyMixes, HomeDailyMixResponse{
+					PlaylistID:  m.PlaylistID,
+					Name:        m.Name,
+					Description: m.Description,
+					Genre:       m.Genre,
+					Mood:        m.Mood,
+					TrackCount:  m.TrackCount,
+					DurationMs:  m.DurationMs,
+					GeneratedAt: m.GeneratedAt,
+				})
+			}
+			return nil
+		}})
+	}
+
+	if h.hours != nil && h.settings != nil {
+		sections = append(sections, homeSection{"suggestedNow", func() error {
+			settings, err := h.settings.Get(ctx, userID)
+			if err != nil {
+				return err
+			}
+			profile, ok, err := momentProfile(ctx, h.hours, settings, userID, time.Now().In(loc))
+			if err != nil || !ok {
+				return err
+			}
+			for _, s := range profile.Suggestions(homeSuggestionLimit) {
+				item := HomeSuggestionResponse{Kind: s.Kind, Mood: s.Mood, Artist: s.Artist, Explanation: s.Explanation}
+				if s.ArtistID != uuid.Nil {
+					item.ArtistID = s.ArtistID.String()
+				}
+				resp.SuggestedNow = append(resp.SuggestedNow, item)
+			}
+			return nil
+		}})
+	}
+
+	// Each loader writes only its own section, so they can run concurrently.
+	failed := make([]bool, len(sections))
+	var wg sync.WaitGroup
+	for i, section := range sections {
+		wg.Add(1)
+		go func(i int, load func() error) {
+			defer wg.Done()
+			failed[i] = load() != nil
+		}(i, section.load)
+	}
+	wg.Wait()
+
+	for i, section := range sections {
+		if failed[i] {
+			resp.Degraded = append(resp.Degraded, section.name)
+		}
+	}
+	return resp
+}
+
+func newHomeTrackResponse(t db.Track) HomeTrackResponse {
+	return HomeTrackResponse{PlayEventTrackResponse: trackToPlayEventResponse(t)}
+}
+
+func timePtr(t time.Time) *time.Time {
+	return &t
+}
@@ -0,0 +1,218 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeHomePlays struct {
+	recent   []db.RecentlyPlayedTrack
+	top      []db.TopTrack
+	contexts []db.RecentPlayContext
+	topErr   error
+}
+
+func (f *fakeHomePlays) RecentlyPlayed(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.RecentlyPlayedTrack, error) {
+	return f.recent, nil
+}
+
+func (f *fakeHomePlays) TopTracks(ctx context.Context, userID uuid.UUID, days, limit int) ([]db.TopTrack, error) {
+	return f.top, f.topErr
+}
+
+func (f *fakeHomePlays) RecentContexts(ctx context.Context, userID uuid.UUID, limit int) ([]db.RecentPlayContext, error) {
+	return f.contexts, nil
+}
+
+type fakeHomeLibrary struct {
+	added    []db.LibraryTrack
+	releases []db.Track
+	opts     db.LibraryQueryOptions
+}
+
+func (f *fakeHomeLibrary) GetUserLibrary(ctx context.Context, userID uuid.UUID, opts db.LibraryQueryOptions) ([]db.LibraryTrack, int, error) {
+	f.opts = opts
+	return f.added, len(f.added), nil
+}
+
+func (f *fakeHomeLibrary) NewTracksFromLibraryArtists(ctx context.Context, userID uuid.UUID, days, limit int) ([]db.Track, error) {
+	return f.releases, nil
+}
+
+type fakeHomeDailyMixes struct {
+	mixes []db.DailyMix
+}
+
+func (f *fakeHomeDailyMixes) List(ctx context.Context, userID uuid.UUID) ([]db.DailyMix, error) {
+	return f.mixes, nil
+}
+
+type fakeHomeCache struct {
+	entries map[string]string
+}
+
+func (f *fakeHomeCache) Get(ctx context.Context, key string) (string, bool) {
+	value, ok := f.entries[key]
+	return value, ok
+}
+
+func (f *fakeHomeCache) Set(ctx context.Context, key string, value string, ttl time.Duration) error {
+	f.entries[key] = value
+	return nil
+}
+
+func getHome(t *testing.T, h *HomeHandlers, userID uuid.UUID) HomeResponse {
+	t.Helper()
+	req := withUser(httptest.NewRequest(http.MethodGet, "/api/v1/home?limit=5", nil), userID)
+	rr := httptest.NewRecorder()
+	h.GetHome(rr, req)
+	if rr.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rr.Code, rr.Body.String())
+	}
+	var resp HomeResponse
+	if err := json.Unmarshal(rr.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	return resp
+}
+
+func TestGetHomeComposesSections(t *testing.T) {
+	now := time.Now().UTC().Truncate(time.Second)
+	plays := &fakeHomePlays{
+		recent: []db.RecentlyPlayedTrack{{Track: *newTrack(1, "Alpha"), LastPlayedAt: now}},
+		top:    []db.TopTrack{{Track: *newTrack(2, "Bravo"), PlayCount: 4, LastPlayedAt: now}},
+		contexts: []db.RecentPlayContext{{
+			ContextType:  "playlist",
+			ContextID:    "9",
+			Name:         sql.NullString{String: "Road Trip", Valid: true},
+			LastPlayedAt: now,
+			Track:        *newTrack(1, "Alpha"),
+		}},
+	}
+	library := &fakeHomeLibrary{
+		added:    []db.LibraryTrack{{Track: *newTrack(3, "Charlie"), AddedAt: now}},
+		releases: []db.Track{{ID: 4, Title: "Delta", CreatedAt: now}},
+	}
+	listenLater := &fakeListenLater{items: []db.ListenLaterItem{{ID: 7, Track: newTrack(5, "Echo"), AddedAt: now}}}
+	dailyMixes := &fakeHomeDailyMixes{mixes: []db.DailyMix{{Position: 1, PlaylistID: 11, Name: "Daily Mix 1", Genre: "Jazz", Mood: "calm", TrackCount: 50}}}
+	h := &HomeHandlers{plays: plays, library: library, listenLater: listenLater, dailyMixes: dailyMixes}
+
+	resp := getHome(t, h, uuid.New())
+	if len(resp.Degraded) != 0 {
+		t.Fatalf("degraded = %v, want none", resp.Degraded)
+	}
+	if len(resp.RecentlyPlayed) != 1 || resp.RecentlyPlayed[0].ID != 1 || resp.RecentlyPlayed[0].LastPlayedAt == nil {
+		t.Fatalf("recentlyPlayed = %#v", resp.RecentlyPlayed)
+	}
+	if len(resp.RecentlyAdded) != 1 || resp.RecentlyAdded[0].ID != 3 || resp.RecentlyAdded[0].AddedAt == nil || resp.RecentlyAdded[0].LastPlayedAt != nil {
+		t.Fatalf("recentlyAdded = %#v", resp.RecentlyAdded)
+	}
+	if library.opts.SortBy != "added_at" || library.opts.SortOrder != "desc" || library.opts.Limit != 5 {
+		t.Fatalf("library options = %#v, want newest 5 by added_at", library.opts)
+	}
+	if len(resp.MostPlayedThisMonth) != 1 || resp.MostPlayedThisMonth[0].PlayCount != 4 {
+		t.Fatalf("mostPlayedThisMonth = %#v", resp.MostPlayedThisMonth)
+	}
+	if len(resp.ContinueListening) != 1 || resp.ContinueListening[0].Name != "Road Trip" || resp.ContinueListening[0].ContextID != "9" {
+		t.Fatalf("continueListening = %#v", resp.ContinueListening)
+	}
+	if len(resp.NewReleases) != 1 || resp.NewReleases[0].ID != 4 {
+		t.Fatalf("newReleases = %#v", resp.NewReleases)
+	}
+	if len(resp.ListenLater) != 1 || resp.ListenLater[0].ID != 7 || resp.ListenLater[0].Track.ID != 5 {
+		t.Fatalf("listenLater = %#v", resp.ListenLater)
+	}
+	if len(resp.DailyMixes) != 1 || resp.DailyMixes[0].PlaylistID != 11 || resp.DailyMixes[0].Mood != "calm" || resp.DailyMixes[0].TrackCount != 50 {
+		t.Fatalf("dailyMixes = %#v", resp.DailyMixes)
+	}
+}
+
+func TestGetHomeDegradesFailedSections(t *testing.T) {
+	plays := &fakeHomePlays{
+		recent: []db.RecentlyPlayedTrack{{Track: *newTrack(1, "Alpha"), LastPlayedAt: time.Now()}},
+		topErr: errors.New("boom"),
+	}
+	cache := &fakeHomeCache{entries: map[string]string{}}
+	h := &HomeHandlers{plays: plays, library: &fakeHomeLibrary{}, cache: cache}
+
+	resp := getHome(t, h, uuid.New())
+	if len(resp.Degraded) != 1 || resp.Degraded[0] != "mostPlayedThisMonth" {
+		t.Fatalf("degraded = %v, want [mostPlayedThisMonth]", resp.Degraded)
+	}
+	if resp.MostPlayedThisMonth == nil || len(resp.MostPlayedThisMonth) != 0 || len(resp.RecentlyPlayed) != 1 {
+		t.Fatalf("sections = %#v, want empty failed section and intact others", resp)
+	}
+	if len(cache.entries) != 0 {
+		t.Fatal("degraded payloads must not be cached")
+	}
+}
+
+func TestGetHomeServesFromCache(t *testing.T) {
+	plays := &fakeHomePlays{recent: []db.RecentlyPlayedTrack{{Track: *newTrack(1, "Alpha"), LastPlayedAt: time.Now()}}}
+	cache := &fakeHomeCache{entries: map[string]string{}}
+	h := &HomeHandlers{plays: plays, library: &fakeHomeLibrary{}, cache: cache}
+	userID := uuid.New()
+
+	if first := getHome(t, h, userID); len(first.RecentlyPlayed) != 1 {
+		t.Fatalf("first recentlyPlayed = %#v", first.RecentlyPlayed)
+	}
+	if len(cache.entries) != 1 {
+		t.Fatalf("cache entries = %d, want 1", len(cache.entries))
+	}
+
+	plays.recent = nil
+	if cached := getHome(t, h, userID); len(cached.RecentlyPlayed) != 1 {
+		t.Fatalf("cached recentlyPlayed = %#v, want the cached payload", cached.RecentlyPlayed)
+	}
+	if other := getHome(t, h, uuid.New()); len(other.RecentlyPlayed) != 0 {
+		t.Fatalf("another user must not see the cached payload: %#v", other.RecentlyPlayed)
+	}
+}
+
+func TestGetHomeSuggestsForTheCurrentMoment(t *testing.T) {
+	berlin, err := time.LoadLocation("Europe/Berlin")
+	if err != nil {
+		t.Skipf("no time zone data: %v", err)
+	}
+	userID, favored := uuid.New(), uuid.New()
+	hours := hoursFavoringNow(berlin, uuid.New(), favored)
+	settings := &fakeUserSettingsStore{byUser: map[uuid.UUID]db.UserSettings{userID: {ContextSuggestions: true}}}
+	h := &HomeHandlers{plays: &fakeHomePlays{}, library: &fakeHomeLibrary{}, hours: hours, settings: settings}
+
+	serve := func(query string) *httptest.ResponseRecorder {
+		rec := httptest.NewRecorder()
+		h.GetHome(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/home"+query, nil), userID))
+		return rec
+	}
+	rec := serve("?tz=Europe/Berlin")
+	var resp HomeResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (status %d)", err, rec.Code)
+	}
+	if hours.zone != "Europe/Berlin" || len(resp.SuggestedNow) != 1 {
+		t.Fatalf("zone %q, suggestedNow = %#v; want one artist", hours.zone, resp.SuggestedNow)
+	}
+	if s := resp.SuggestedNow[0]; s.Kind != "artist" || s.ArtistID != favored.String() || !strings.HasPrefix(s.Explanation, "You often play Favored on ") {
+		t.Fatalf("suggestion = %#v", s)
+	}
+
+	settings.byUser[userID] = db.UserSettings{}
+	resp = getHome(t, h, userID)
+	if len(resp.SuggestedNow) != 0 || len(resp.Degraded) != 0 {
+		t.Fatalf("suggestedNow with the setting off = %#v, degraded %v", resp.SuggestedNow, resp.Degraded)
+	}
+	if rec := serve("?tz=Mars/Olympus"); rec.Code != http.StatusUnprocessableEntity {
+		t.Fatalf("?tz=Mars/Olympus = %d, want 422", rec.Code)
+	}
+}
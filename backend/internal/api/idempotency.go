@@ -0,0 +1,202 @@
+package api
+
+import (
+	"bytes"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+)
+
+const (
+	idempotencyKeyHeader      = "Idempotency-Key"
+	idempotentReplayedHeader  = "Idempotent-Replayed"
+	maxIdempotencyKeyLength   = 255
+	maxIdempotentRequestBytes = 1 << 20
+	maxIdempotentResponseSize = 1 << 20
+
+	// IdempotencyKeyTTL is how long a completed response is replayed for.
+	IdempotencyKeyTTL = 24 * time.Hour
+	// idempotencyLockTimeout reclaims keys whose original request died
+	// without completing or releasing them.
+	idempotencyLockTimeout = time.Minute
+)
+
+// IdempotencyStore persists Idempotency-Key reservations and the responses
+// they produced.
+type IdempotencyStore interface {
+	Reserve(ctx context.Context, userID uuid.UUID, key, requestHash string, ttl, lockTimeout time.Duration) (*db.IdempotencyRecord, bool, error)
+	Complete(ctx context.Context, userID uuid.UUID, key string, status int, contentType string, body []byte) error
+	Release(ctx context.Context, userID uuid.UUID, key string) error
+}
+
+// withIdempotency makes an authenticated mutation safe to retry. When the
+// client sends an Idempotency-Key, the first request runs and its response is
+// stored; retries with the same key and body replay that response instead of
+// creating duplicate jobs or playlist entries. Requests without the header are
+// unaffected, as is every route when no store is configured.
+func (r *Router) withIdempotency(next http.HandlerFunc) http.HandlerFunc {
+	if r.idempotencyStore == nil {
+		return next
+	}
+	return idempotent(r.idempotencyStore, next)
+}
+
+func idempotent(store IdempotencyStore, next http.HandlerFunc) http.HandlerFunc {
+	return func(w http.ResponseWriter, req *http.Request) {
+		key := req.Header.Get(idempotencyKeyHeader)
+		if key == "" {
+			next(w, req)
+			return
+		}
+		if !validIdempotencyKey(key) {
+			apperrors.WriteProblem(w, req, apperrors.NewProblem(http.StatusBadRequest, "INVALID_IDEMPOTENCY_KEY",
+				"Idempotency-Key must be 1-255 printable ASCII characters"))
+			return
+		}
+
+		user := auth.GetUserFromContext(req.Context())
+		if user == nil {
+			apperrors.WriteProblem(w, req, apperrors.NewProblem(http.StatusUnauthorized, apperrors.CodeUnauthorized, "not authenticated"))
+			return
+		}
+
+		body, err := io.ReadAll(io.LimitReader(req.Body, maxIdempotentRequestBytes+1))
+		if err != nil {
+			apperrors.WriteProblem(w, req, apperrors.NewProblem(http.StatusBadRequest, apperrors.CodeInvalidRequest, "failed to read request body"))
+			return
+		}
+		if len(body) > maxIdempotentRequestBytes {
+			apperrors.WriteProblem(w, req, apperrors.NewProblem(http.StatusRequestEntityTooLarge, "REQUEST_TOO_LARGE",
+				"request body is too large to use with Idempotency-Key"))
+			return
+		}
+		req.Body = io.NopCloser(bytes.NewReader(body))
+		hash := idempotencyRequestHash(req, body)
+
+		record, reserved, err := store.Reserve(req.Context(), user.UserID, key, hash, IdempotencyKeyTTL, idempotencyLockTimeout)
+		if err != nil {
+			log.Printf("Idempotency reserve failed: %v", err)
+			apperrors.WriteProblemError(w, req, err)
+			return
+		}
+		if !reserved {
+			replayIdempotentResponse(w, req, record, hash)
+			return
+		}
+
+		// Persist the outcome even if the client disconnects mid-request; that
+		// is exactly the retry this exists for.
+		storeCtx := context.WithoutCancel(req.Context())
+		recorder := &idempotencyRecorder{ResponseWriter: w}
+		completed := false
+		defer func() {
+			if !completed {
+				if err := store.Release(storeCtx, user.UserID, key); err != nil {
+					log.Printf("Idempotency release failed: %v", err)
+				}
+			}
+		}()
+
+		next(recorder, req)
+
+		status := recorder.statusCode()
+		if status >= http.StatusInternalServerError || recorder.overflow {
+			return
+		}
+		if err := store.Complete(storeCtx, user.UserID, key, status, recorder.Header().Get("Content-Type"), recorder.body.Bytes()); err != nil {
+			log.Printf("Idempotency complete failed: %v", err)
+			return
+		}
+		completed = true
+	}
+}
+
+func replayIdempotentResponse(w http.ResponseWriter, req *http.Request, record *db.IdempotencyRecord, hash string) {
+	switch {
+	case record.RequestHash != hash:
+		apperrors.WriteProblem(w, req, apperrors.NewProblem(http.StatusUnprocessableEntity, "IDEMPOTENCY_KEY_REUSED",
+			"Idempotency-Key was already used with a different request"))
+	case !record.Completed:
+		w.Header().Set("Retry-After", "1")
+		apperrors.WriteProblem(w, req, apperrors.NewProblem(http.StatusConflict, "IDEMPOTENCY_REQUEST_IN_PROGRESS",
+			"a request with this Idempotency-Key is still being processed"))
+	default:
+		if record.ContentType != "" {
+			w.Header().Set("Content-Type", record.ContentType)
+		}
+		w.Header().Set(idempotentReplayedHeader, "true")
+		w.WriteHeader(record.Status)
+		_, _ = w.Write(record.Body)
+	}
+}
+
+// idempotencyRequestHash fingerprints the request so a key reused for a
+// different mutation is rejected rather than answered with the wrong response.
+func idempotencyRequestHash(req *http.Request, body []byte) string {
+	h := sha256.New()
+	fmt.Fprintf(h, "%s %s?%s\n", req.Method, req.URL.Path, req.URL.RawQuery)
+	h.Write(body)
+	return hex.EncodeToString(h.Sum(nil))
+}
+
+func validIdempotencyKey(key string) bool {
+	if len(key) > maxIdempotencyKeyLength || strings.TrimSpace(key) == "" {
+		return false
+	}
+	for i := 0; i < len(key); i++ {
+		if key[i] < 0x20 || key[i] > 0x7e {
+			return false
+		}
+	}
+	return true
+}
+
+// idempotencyRecorder passes the response through while keeping a copy small
+// enough to store.
+type idempotencyRecorder struct {
+	http.ResponseWriter
+	status   int
+	body     bytes.Buffer
+	overflow bool
+}
+
+func (r *idempotencyRecorder) WriteHeader(status int) {
+	if r.status == 0 {
+		r.status = status
+	}
+	r.ResponseWriter.WriteHeader(status)
+}
+
+func (r *idempotencyRecorder) Write(p []byte) (int, error) {
+	if r.status == 0 {
+		r.status = http.StatusOK
+	}
+	if !r.overflow {
+		if r.body.Len()+len(p) > maxIdempotentResponseSize {
+			r.overflow = true
+			r.body.Reset()
+		} else {
+			r.body.Write(p)
+		}
+	}
+	return r.ResponseWriter.Write(p)
+}
+
+func (r *idempotencyRecorder) statusCode() int {
+	if r.status == 0 {
+		return http.StatusOK
+	}
+	return r.status
+}
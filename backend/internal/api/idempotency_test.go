@@ -0,0 +1,161 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+)
+
+type fakeIdempotencyStore struct {
+	mu      sync.Mutex
+	records map[string]*db.IdempotencyRecord
+}
+
+func newFakeIdempotencyStore() *fakeIdempotencyStore {
+	return &fakeIdempotencyStore{records: make(map[string]*db.IdempotencyRecord)}
+}
+
+func (s *fakeIdempotencyStore) Reserve(_ context.Context, userID uuid.UUID, key, requestHash string, _, _ time.Duration) (*db.IdempotencyRecord, bool, error) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	id := userID.String() + "/" + key
+	if record, ok := s.records[id]; ok {
+		copied := *record
+		return &copied, false, nil
+	}
+	s.records[id] = &db.IdempotencyRecord{UserID: userID, Key: key, RequestHash: requestHash}
+	return nil, true, nil
+}
+
+func (s *fakeIdempotencyStore) Complete(_ context.Context, userID uuid.UUID, key string, status int, contentType string, body []byte) error {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	record := s.records[userID.String()+"/"+key]
+	record.Status, record.ContentType, record.Body, record.Completed = status, contentType, body, true
+	return nil
+}
+
+func (s *fakeIdempotencyStore) Release(_ context.Context, userID uuid.UUID, key string) error {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	id := userID.String() + "/" + key
+	if record, ok := s.records[id]; ok && !record.Completed {
+		delete(s.records, id)
+	}
+	return nil
+}
+
+// countingHandler creates a "job" per call and echoes the request body.
+func countingHandler(calls *int, status int) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		*calls++
+		body, _ := io.ReadAll(r.Body)
+		w.Header().Set("Content-Type", "application/json")
+		w.WriteHeader(status)
+		json.NewEncoder(w).Encode(map[string]any{"job": *calls, "echo": string(body)})
+	}
+}
+
+func idempotentRequest(userID uuid.UUID, key, body string) *http.Request {
+	req := httptest.NewRequest(http.MethodPost, "/api/v1/downloads", strings.NewReader(body))
+	if key != "" {
+		req.Header.Set(idempotencyKeyHeader, key)
+	}
+	return withUser(req, userID)
+}
+
+func TestIdempotentReplaysStoredResponse(t *testing.T) {
+	store := newFakeIdempotencyStore()
+	calls := 0
+	handler := idempotent(store, countingHandler(&calls, http.StatusCreated))
+	user := uuid.New()
+
+	first := httptest.NewRecorder()
+	handler(first, idempotentRequest(user, "retry-1", `{"url":"a"}`))
+	second := httptest.NewRecorder()
+	handler(second, idempotentRequest(user, "retry-1", `{"url":"a"}`))
+
+	if calls != 1 {
+		t.Fatalf("handler ran %d times, want once", calls)
+	}
+	if second.Code != http.StatusCreated || second.Body.String() != first.Body.String() {
+		t.Fatalf("replay = %d %q, want %d %q", second.Code, second.Body.String(), first.Code, first.Body.String())
+	}
+	if second.Header().Get(idempotentReplayedHeader) != "true" || second.Header().Get("Content-Type") != "application/json" {
+		t.Fatalf("replay headers = %v", second.Header())
+	}
+	if first.Header().Get(idempotentReplayedHeader) != "" {
+		t.Fatal("original response must not be marked as replayed")
+	}
+
+	// Without the header every request executes.
+	handler(httptest.NewRecorder(), idempotentRequest(user, "", `{"url":"a"}`))
+	handler(httptest.NewRecorder(), idempotentRequest(user, "", `{"url":"a"}`))
+	if calls != 3 {
+		t.Fatalf("handler ran %d times, want requests without a key to run", calls)
+	}
+}
+
+func TestIdempotentRejectsReusedKeyAndInFlightRequest(t *testing.T) {
+	store := newFakeIdempotencyStore()
+	calls := 0
+	handler := idempotent(store, countingHandler(&calls, http.StatusCreated))
+	user := uuid.New()
+
+	handler(httptest.NewRecorder(), idempotentRequest(user, "k", `{"url":"a"}`))
+	reused := httptest.NewRecorder()
+	handler(reused, idempotentRequest(user, "k", `{"url":"b"}`))
+	assertProblemCode(t, reused, http.StatusUnprocessableEntity, "IDEMPOTENCY_KEY_REUSED")
+
+	store.records[user.String()+"/busy"] = &db.IdempotencyRecord{RequestHash: idempotencyRequestHash(
+		idempotentRequest(user, "busy", ""), []byte(`{"url":"c"}`))}
+	busy := httptest.NewRecorder()
+	handler(busy, idempotentRequest(user, "busy", `{"url":"c"}`))
+	assertProblemCode(t, busy, http.StatusConflict, "IDEMPOTENCY_REQUEST_IN_PROGRESS")
+	if busy.Header().Get("Retry-After") == "" {
+		t.Fatal("in-progress conflict should tell the client when to retry")
+	}
+
+	invalid := httptest.NewRecorder()
+	handler(invalid, idempotentRequest(user, "bad\x01key", `{}`))
+	assertProblemCode(t, invalid, http.StatusBadRequest, "INVALID_IDEMPOTENCY_KEY")
+
+	if calls != 1 {
+		t.Fatalf("handler ran %d times, want only the first request", calls)
+	}
+}
+
+func TestIdempotentReleasesKeyAfterServerError(t *testing.T) {
+	store := newFakeIdempotencyStore()
+	calls := 0
+	handler := idempotent(store, countingHandler(&calls, http.StatusServiceUnavailable))
+	user := uuid.New()
+
+	handler(httptest.NewRecorder(), idempotentRequest(user, "k", `{}`))
+	handler(httptest.NewRecorder(), idempotentRequest(user, "k", `{}`))
+	if calls != 2 {
+		t.Fatalf("handler ran %d times; a 5xx must not be replayed", calls)
+	}
+}
+
+func assertProblemCode(t *testing.T, rec *httptest.ResponseRecorder, status int, code string) {
+	t.Helper()
+	var problem apperrors.Problem
+	if err := json.NewDecoder(rec.Body).Decode(&problem); err != nil {
+		t.Fatalf("decode problem: %v", err)
+	}
+	if rec.Code != status || problem.Code != code || rec.Header().Get("Content-Type") != apperrors.ProblemContentType {
+		t.Fatalf("response = %d %s (%s), want %d %s", rec.Code, problem.Code, rec.Header().Get("Content-Type"), status, code)
+	}
+}
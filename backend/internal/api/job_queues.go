@@ -0,0 +1,298 @@
+package api
+
+import (
+	"context"
+	"crypto/subtle"
+	"encoding/json"
+	"errors"
+	"io"
+	"net/http"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// jobControlTokenHeader carries the operator token for queue controls.
+const jobControlTokenHeader = "X-OMP-Job-Control-Token"
+
+type jobQueueControls interface {
+	Queues(ctx context.Context) ([]jobs.QueueState, error)
+	Pause(ctx context.Context, name, reason string) (*jobs.QueueState, error)
+	Resume(ctx context.Context, name string) (*jobs.QueueState, error)
+}
+
+type jobDeadLetters interface {
+	List(ctx context.Context, filter jobs.DeadLetterFilter) ([]jobs.DeadLetter, error)
+	Counts(ctx context.Context) (map[string]int, error)
+	Retry(ctx context.Context, filter jobs.DeadLetterFilter) (*jobs.RetryOutcome, error)
+	Discard(ctx context.Context, filter jobs.DeadLetterFilter) (int64, error)
+}
+
+// JobQueueHandlers let an operator pause and resume job queues around
+// maintenance windows and triage jobs that failed for good. These act for
+// every user, so the routes take a service token rather than a user session.
+type JobQueueHandlers struct {
+	controls    jobQueueControls
+	deadLetters jobDeadLetters
+	token       string
+}
+
+// NewJobQueueHandlers returns nil without a token, leaving the routes
+// unregistered.
+func NewJobQueueHandlers(controls jobQueueControls, deadLetters jobDeadLetters, token string) *JobQueueHandlers {
+	token = strings.TrimSpace(token)
+	if token == "" || controls == nil || deadLetters == nil {
+		return nil
+	}
+	return &JobQueueHandlers{controls: controls, deadLetters: deadLetters, token: token}
+}
+
+type JobQueueResponse struct {
+	Name     string     `json:"name"`
+	Paused   bool       `json:"paused"`
+	Reason   string     `json:"reason,omitempty"`
+	PausedAt *time.Time `json:"pausedAt,omitempty"`
+}
+
+type JobQueueListResponse struct {
+	Queues []JobQueueResponse `json:"queues"`
+}
+
+type PauseJobQueueRequest struct {
+	Reason string `json:"reason"`
+}
+
+type DeadLetterResponse struct {
+	ID       int64           `json:"id"`
+	Kind     string          `json:"kind"`
+	JobID    string          `json:"jobId"`
+	UserID   string          `json:"userId,omitempty"`
+	Error    string          `json:"error"`
+	Category string          `json:"category"`
+	Attempts int             `json:"attempts"`
+	Context  json.RawMessage `json:"context,omitempty"`
+	FailedAt time.Time       `json:"failedAt"`
+}
+
+type DeadLetterListResponse struct {
+	DeadLetters []DeadLetterResponse `json:"deadLetters"`
+	// Counts holds the number of dead letters in each failure category.
+	Counts map[string]int `json:"counts"`
+}
+
+// DeadLetterSelection picks the dead letters a bulk action applies to. At
+// least one field must be set; set fields must all match.
+type DeadLetterSelection struct {
+	IDs      []int64 `json:"ids"`
+	Kind     string  `json:"kind"`
+	Category string  `json:"category"`
+}
+
+type DeadLetterRetryFailure struct {
+	ID    int64  `json:"id"`
+	Error string `json:"error"`
+}
+
+type DeadLetterRetryResponse struct {
+	Retried []int64                  `json:"retried"`
+	Failed  []DeadLetterRetryFailure `json:"failed"`
+}
+
+type DeadLetterDiscardResponse struct {
+	Discarded int64 `json:"discarded"`
+}
+
+// ListQueues handles GET /internal/jobs/v1/queues.
+func (h *JobQueueHandlers) ListQueues(w http.ResponseWriter, r *http.Request) {
+	if !h.authorized(w, r) {
+		return
+	}
+	queues, err := h.controls.Queues(r.Context())
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list job queues")
+		return
+	}
+	resp := JobQueueListResponse{Queues: make([]JobQueueResponse, 0, len(queues))}
+	for _, queue := range queues {
+		resp.Queues = append(resp.Queues, jobQueueResponse(queue))
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// PauseQueue handles POST /internal/jobs/v1/queues/{queue}/pause. Queued jobs
+// wait and running jobs finish.
+func (h *JobQueueHandlers) PauseQueue(w http.ResponseWriter, r *http.Request) {
+	if !h.authorized(w, r) {
+		return
+	}
+	var req PauseJobQueueRequest
+	if err := decodeStrictJSON(r, &req); err != nil && !errors.Is(err, io.EOF) {
+		writeMaintenanceError(w, http.StatusBadRequest, "BAD_REQUEST", "invalid request body")
+		return
+	}
+	state, err := h.controls.Pause(r.Context(), r.PathValue("queue"), strings.TrimSpace(req.Reason))
+	h.writeQueueState(w, state, err)
+}
+
+// ResumeQueue handles POST /internal/jobs/v1/queues/{queue}/resume.
+func (h *JobQueueHandlers) ResumeQueue(w http.ResponseWriter, r *http.Request) {
+	if !h.authorized(w, r) {
+		return
+	}
+	state, err := h.controls.Resume(r.Context(), r.PathValue("queue"))
+	h.writeQueueState(w, state, err)
+}
+
+// ListDeadLetters handles GET /internal/jobs/v1/dead-letters, optionally
+// filtered by kind and category.
+func (h *JobQueueHandlers) ListDeadLetters(w http.ResponseWriter, r *http.Request) {
+	if !h.authorized(w, r) {
+		return
+	}
+	query := r.URL.Query()
+	filter := jobs.DeadLetterFilter{Kind: query.Get("kind"), Category: query.Get("category")}
+	if !validFailureCategory(filter.Category) {
+		writeMaintenanceError(w, http.StatusBadRequest, "BAD_REQUEST", "unknown failure category")
+		return
+	}
+	if raw := query.Get("limit"); raw != "" {
+		limit, err := strconv.Atoi(raw)
+		if err != nil || limit <= 0 {
+			writeMaintenanceError(w, http.StatusBadRequest, "BAD_REQUEST", "limit must be a positive integer")
+			return
+		}
+		filter.Limit = limit
+	}
+	letters, err := h.deadLetters.List(r.Context(), filter)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list dead letters")
+		return
+	}
+	counts, err := h.deadLetters.Counts(r.Context())
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to count dead letters")
+		return
+	}
+	resp := DeadLetterListResponse{DeadLetters: make([]DeadLetterResponse, 0, len(letters)), Counts: map[string]int{}}
+	for _, category := range jobs.FailureCategories {
+		resp.Counts[category] = counts[category]
+	}
+	for _, letter := range letters {
+		resp.DeadLetters = append(resp.DeadLetters, deadLetterResponse(letter))
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// RetryDeadLetters handles POST /internal/jobs/v1/dead-letters/retry. Each
+// selected job is queued again with a fresh retry budget; those that cannot be
+// retried keep their dead letter and are reported as failed.
+func (h *JobQueueHandlers) RetryDeadLetters(w http.ResponseWriter, r *http.Request) {
+	if !h.authorized(w, r) {
+		return
+	}
+	filter, ok := decodeDeadLetterSelection(w, r)
+	if !ok {
+		return
+	}
+	outcome, err := h.deadLetters.Retry(r.Context(), filter)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to retry dead letters")
+		return
+	}
+	resp := DeadLetterRetryResponse{Retried: outcome.Retried, Failed: make([]DeadLetterRetryFailure, 0, len(outcome.Failed))}
+	if resp.Retried == nil {
+		resp.Retried = []int64{}
+	}
+	for id, message := range outcome.Failed {
+		resp.Failed = append(resp.Failed, DeadLetterRetryFailure{ID: id, Error: message})
+	}
+	slices.SortFunc(resp.Failed, func(a, b DeadLetterRetryFailure) int { return int(a.ID - b.ID) })
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// DiscardDeadLetters handles POST /internal/jobs/v1/dead-letters/discard. The
+// selected jobs stay failed; only their dead letters are removed.
+func (h *JobQueueHandlers) DiscardDeadLetters(w http.ResponseWriter, r *http.Request) {
+	if !h.authorized(w, r) {
+		return
+	}
+	filter, ok := decodeDeadLetterSelection(w, r)
+	if !ok {
+		return
+	}
+	discarded, err := h.deadLetters.Discard(r.Context(), filter)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to discard dead letters")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, DeadLetterDiscardResponse{Discarded: discarded})
+}
+
+func decodeDeadLetterSelection(w http.ResponseWriter, r *http.Request) (jobs.DeadLetterFilter, bool) {
+	var req DeadLetterSelection
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "BAD_REQUEST", "invalid request body")
+		return jobs.DeadLetterFilter{}, false
+	}
+	filter := jobs.DeadLetterFilter{IDs: req.IDs, Kind: strings.TrimSpace(req.Kind), Category: strings.TrimSpace(req.Category)}
+	if filter.Empty() {
+		writeMaintenanceError(w, http.StatusBadRequest, "BAD_REQUEST", "select dead letters by ids, kind, or category")
+		return jobs.DeadLetterFilter{}, false
+	}
+	if !validFailureCategory(filter.Category) {
+		writeMaintenanceError(w, http.StatusBadRequest, "BAD_REQUEST", "unknown failure category")
+		return jobs.DeadLetterFilter{}, false
+	}
+	return filter, true
+}
+
+func validFailureCategory(category string) bool {
+	return category == "" || slices.Contains(jobs.FailureCategories, category)
+}
+
+func (h *JobQueueHandlers) writeQueueState(w http.ResponseWriter, state *jobs.QueueState, err error) {
+	if errors.Is(err, jobs.ErrNotFound) {
+		writeMaintenanceError(w, http.StatusNotFound, "NOT_FOUND", "job queue not found")
+		return
+	}
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to update job queue")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, jobQueueResponse(*state))
+}
+
+func (h *JobQueueHandlers) authorized(w http.ResponseWriter, r *http.Request) bool {
+	token := r.Header.Get(jobControlTokenHeader)
+	if len(token) != len(h.token) || subtle.ConstantTimeCompare([]byte(token), []byte(h.token)) != 1 {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "job control token required")
+		return false
+	}
+	return true
+}
+
+func jobQueueResponse(state jobs.QueueState) JobQueueResponse {
+	return JobQueueResponse{
+		Name:     state.Name,
+		Paused:   state.Paused,
+		Reason:   state.Reason,
+		PausedAt: state.PausedAt,
+	}
+}
+
+func deadLetterResponse(letter jobs.DeadLetter) DeadLetterResponse {
+	return DeadLetterResponse{
+		ID:       letter.ID,
+		Kind:     letter.Kind,
+		JobID:    letter.JobID,
+		UserID:   letter.UserID,
+		Error:    letter.Error,
+		Category: letter.Category,
+		Attempts: letter.Attempts,
+		Context:  letter.Context,
+		FailedAt: letter.FailedAt,
+	}
+}
@@ -0,0 +1,228 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"slices"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+type fakeDownloadQueueService struct {
+	paused *download.PauseState
+}
+
+func (f *fakeDownloadQueueService) PauseQueue(ctx context.Context, reason string) (*download.PauseState, error) {
+	f.paused = &download.PauseState{Reason: reason, PausedAt: time.Now()}
+	return f.paused, nil
+}
+
+func (f *fakeDownloadQueueService) ResumeQueue(ctx context.Context) error {
+	f.paused = nil
+	return nil
+}
+
+func (f *fakeDownloadQueueService) QueuePaused(ctx context.Context) (*download.PauseState, error) {
+	return f.paused, nil
+}
+
+type fakeJobDeadLetters struct {
+	letters   []jobs.DeadLetter
+	retryable map[int64]bool
+	filters   []jobs.DeadLetterFilter
+}
+
+func (f *fakeJobDeadLetters) List(ctx context.Context, filter jobs.DeadLetterFilter) ([]jobs.DeadLetter, error) {
+	f.filters = append(f.filters, filter)
+	var letters []jobs.DeadLetter
+	for _, letter := range f.letters {
+		if filter.Category != "" && letter.Category != filter.Category {
+			continue
+		}
+		if filter.Kind != "" && letter.Kind != filter.Kind {
+			continue
+		}
+		if len(filter.IDs) > 0 && !slices.Contains(filter.IDs, letter.ID) {
+			continue
+		}
+		letters = append(letters, letter)
+	}
+	return letters, nil
+}
+
+func (f *fakeJobDeadLetters) Counts(ctx context.Context) (map[string]int, error) {
+	counts := map[string]int{}
+	for _, letter := range f.letters {
+		counts[letter.Category]++
+	}
+	return counts, nil
+}
+
+func (f *fakeJobDeadLetters) Retry(ctx context.Context, filter jobs.DeadLetterFilter) (*jobs.RetryOutcome, error) {
+	letters, _ := f.List(ctx, filter)
+	outcome := &jobs.RetryOutcome{Failed: map[int64]string{}}
+	for _, letter := range letters {
+		if f.retryable[letter.ID] {
+			outcome.Retried = append(outcome.Retried, letter.ID)
+		} else {
+			outcome.Failed[letter.ID] = jobs.ErrNotRetryable.Error()
+		}
+	}
+	return outcome, nil
+}
+
+func (f *fakeJobDeadLetters) Discard(ctx context.Context, filter jobs.DeadLetterFilter) (int64, error) {
+	letters, _ := f.List(ctx, filter)
+	return int64(len(letters)), nil
+}
+
+func newTestJobQueueHandlers() (*JobQueueHandlers, *fakeDownloadQueueService) {
+	h, downloads, _ := newTestJobQueueHandlersWithDeadLetters(&fakeJobDeadLetters{})
+	return h, downloads
+}
+
+func newTestJobQueueHandlersWithDeadLetters(deadLetters *fakeJobDeadLetters) (*JobQueueHandlers, *fakeDownloadQueueService, *fakeJobDeadLetters) {
+	downloads := &fakeDownloadQueueService{}
+	return NewJobQueueHandlers(jobs.NewControls(NewDownloadQueueControl(downloads)), deadLetters, "operator-token"), downloads, deadLetters
+}
+
+func TestJobQueueHandlersRequireToken(t *testing.T) {
+	if h := NewJobQueueHandlers(jobs.NewControls(), &fakeJobDeadLetters{}, " "); h != nil {
+		t.Fatal("NewJobQueueHandlers without a token should return nil")
+	}
+	h, _ := newTestJobQueueHandlers()
+	for _, token := range []string{"", "wrong-token"} {
+		req := httptest.NewRequest(http.MethodGet, "/internal/jobs/v1/queues", nil)
+		req.Header.Set(jobControlTokenHeader, token)
+		rec := httptest.NewRecorder()
+		h.ListQueues(rec, req)
+		if rec.Code != http.StatusUnauthorized {
+			t.Fatalf("token %q status = %d, want 401", token, rec.Code)
+		}
+	}
+}
+
+func TestJobQueueHandlersPauseAndResume(t *testing.T) {
+	h, downloads := newTestJobQueueHandlers()
+	post := func(queue, action, body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/internal/jobs/v1/queues/"+queue+"/"+action, strings.NewReader(body))
+		req.SetPathValue("queue", queue)
+		req.Header.Set(jobControlTokenHeader, "operator-token")
+		rec := httptest.NewRecorder()
+		if action == "pause" {
+			h.PauseQueue(rec, req)
+		} else {
+			h.ResumeQueue(rec, req)
+		}
+		return rec
+	}
+
+	rec := post("download", "pause", `{"reason":"storage migration"}`)
+	var paused JobQueueResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &paused); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusOK || !paused.Paused || paused.Reason != "storage migration" || paused.PausedAt == nil {
+		t.Fatalf("pause = %d %#v", rec.Code, paused)
+	}
+	if downloads.paused == nil {
+		t.Fatal("download queue was not paused")
+	}
+
+	req := httptest.NewRequest(http.MethodGet, "/internal/jobs/v1/queues", nil)
+	req.Header.Set(jobControlTokenHeader, "operator-token")
+	listRec := httptest.NewRecorder()
+	h.ListQueues(listRec, req)
+	var list JobQueueListResponse
+	if err := json.Unmarshal(listRec.Body.Bytes(), &list); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if len(list.Queues) != 1 || list.Queues[0].Name != "download" || !list.Queues[0].Paused {
+		t.Fatalf("queues = %#v", list.Queues)
+	}
+
+	rec = post("download", "resume", "")
+	var resumed JobQueueResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resumed); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusOK || resumed.Paused || downloads.paused != nil {
+		t.Fatalf("resume = %d %#v", rec.Code, resumed)
+	}
+
+	if rec := post("transcode", "pause", ""); rec.Code != http.StatusNotFound {
+		t.Fatalf("pause unknown queue status = %d, want 404", rec.Code)
+	}
+	if rec := post("download", "pause", `{"reason":1}`); rec.Code != http.StatusBadRequest {
+		t.Fatalf("pause with invalid body status = %d, want 400", rec.Code)
+	}
+}
+
+func TestJobQueueHandlersDeadLetters(t *testing.T) {
+	h, _, deadLetters := newTestJobQueueHandlersWithDeadLetters(&fakeJobDeadLetters{
+		letters: []jobs.DeadLetter{
+			{ID: 1, Kind: "download", JobID: "job-1", Error: "dial tcp: i/o timeout", Category: jobs.FailureNetwork, Attempts: 4},
+			{ID: 2, Kind: "download", JobID: "job-2", Error: "HTTP Error 403: Forbidden", Category: jobs.FailureProviderBlocked, Attempts: 4},
+			{ID: 3, Kind: "library_repair", JobID: "job-3", Error: "write: no space left on device", Category: jobs.FailureDiskFull, Attempts: 2},
+		},
+		retryable: map[int64]bool{1: true},
+	})
+	do := func(method, path, body string, handler http.HandlerFunc) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(method, path, strings.NewReader(body))
+		req.Header.Set(jobControlTokenHeader, "operator-token")
+		rec := httptest.NewRecorder()
+		handler(rec, req)
+		return rec
+	}
+
+	rec := do(http.MethodGet, "/internal/jobs/v1/dead-letters?kind=download&limit=10", "", h.ListDeadLetters)
+	var list DeadLetterListResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &list); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusOK || len(list.DeadLetters) != 2 || list.DeadLetters[0].JobID != "job-1" {
+		t.Fatalf("list = %d %#v", rec.Code, list.DeadLetters)
+	}
+	if deadLetters.filters[0].Limit != 10 {
+		t.Fatalf("limit = %d, want 10", deadLetters.filters[0].Limit)
+	}
+	wantCounts := map[string]int{jobs.FailureNetwork: 1, jobs.FailureProviderBlocked: 1, jobs.FailureDiskFull: 1, jobs.FailureOther: 0}
+	for category, want := range wantCounts {
+		if got, ok := list.Counts[category]; !ok || got != want {
+			t.Fatalf("counts[%s] = %d, want %d", category, got, want)
+		}
+	}
+	if rec := do(http.MethodGet, "/internal/jobs/v1/dead-letters?category=cosmic_rays", "", h.ListDeadLetters); rec.Code != http.StatusBadRequest {
+		t.Fatalf("unknown category status = %d, want 400", rec.Code)
+	}
+
+	rec = do(http.MethodPost, "/internal/jobs/v1/dead-letters/retry", `{"kind":"download"}`, h.RetryDeadLetters)
+	var retried DeadLetterRetryResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &retried); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusOK || !slices.Equal(retried.Retried, []int64{1}) || len(retried.Failed) != 1 || retried.Failed[0].ID != 2 {
+		t.Fatalf("retry = %d %#v", rec.Code, retried)
+	}
+
+	rec = do(http.MethodPost, "/internal/jobs/v1/dead-letters/discard", `{"category":"disk_full"}`, h.DiscardDeadLetters)
+	var discarded DeadLetterDiscardResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &discarded); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusOK || discarded.Discarded != 1 {
+		t.Fatalf("discard = %d %#v", rec.Code, discarded)
+	}
+
+	for _, body := range []string{"", "{}", `{"ids":[]}`} {
+		if rec := do(http.MethodPost, "/internal/jobs/v1/dead-letters/discard", body, h.DiscardDeadLetters); rec.Code != http.StatusBadRequest {
+			t.Fatalf("discard with selection %q status = %d, want 400", body, rec.Code)
+		}
+	}
+}
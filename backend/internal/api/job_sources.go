@@ -0,0 +1,371 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/playlistimport"
+)
+
+// Job kinds for work that runs outside the jobs framework.
+const (
+	jobKindDownload       = "download"
+	jobKindPlaylistImport = "playlist_import"
+)
+
+// playlistImportJobListLimit bounds how many recent imports the jobs API lists.
+const playlistImportJobListLimit = 50
+
+type downloadJobService interface {
+	GetJob(ctx context.Context, jobID string) (*download.DownloadJob, error)
+	GetUserJobs(ctx context.Context, userID string) ([]*download.DownloadJob, error)
+	CancelJob(ctx context.Context, jobID string) (*download.DownloadJob, error)
+	RetryDeadJob(ctx context.Context, jobID string) error
+}
+
+// DownloadJobSource reports Redis-backed download jobs through the jobs API.
+// A queued download is canceled at once; a running one is stopped by its
+// worker shortly after.
+type DownloadJobSource struct {
+	service downloadJobService
+}
+
+func NewDownloadJobSource(service downloadJobService) *DownloadJobSource {
+	return &DownloadJobSource{service: service}
+}
+
+func (s *DownloadJobSource) ListJobs(ctx context.Context, userID uuid.UUID) ([]jobs.Summary, error) {
+	downloads, err := s.service.GetUserJobs(ctx, userID.String())
+	if err != nil {
+		return nil, err
+	}
+	summaries := make([]jobs.Summary, 0, len(downloads))
+	for _, job := range downloads {
+		summaries = append(summaries, downloadJobSummary(job))
+	}
+	return summaries, nil
+}
+
+func (s *DownloadJobSource) GetJob(ctx context.Context, userID uuid.UUID, id string) (*jobs.Summary, error) {
+	job, err := s.ownedJob(ctx, userID, id)
+	if err != nil {
+		return nil, err
+	}
+	summary := downloadJobSummary(job)
+	return &summary, nil
+}
+
+func (s *DownloadJobSource) CancelJob(ctx context.Context, userID uuid.UUID, id string) (*jobs.Summary, error) {
+	if _, err := s.ownedJob(ctx, userID, id); err != nil {
+		return nil, err
+	}
+	job, err := s.service.CancelJob(ctx, id)
+	if errors.Is(err, download.ErrJobNotCancelable) {
+		return nil, jobs.ErrNotCancelable
+	}
+	if err != nil {
+		return nil, err
+	}
+	summary := downloadJobSummary(job)
+	return &summary, nil
+}
+
+// RetryDeadLetter implements jobs.Retrier for dead-lettered downloads.
+func (s *DownloadJobSource) RetryDeadLetter(ctx context.Context, letter jobs.DeadLetter) error {
+	if letter.Kind != jobKindDownload {
+		return jobs.ErrNotFound
+	}
+	err := s.service.RetryDeadJob(ctx, letter.JobID)
+	if errors.Is(err, download.ErrJobNotRetryable) || errors.Is(err, download.ErrJobNotFound) {
+		return jobs.ErrNotRetryable
+	}
+	return err
+}
+
+func (s *DownloadJobSource) ownedJob(ctx context.Context, userID uuid.UUID, id string) (*download.DownloadJob, error) {
+	job, err := s.service.GetJob(ctx, id)
+	if errors.Is(err, download.ErrJobNotFound) {
+		return nil, jobs.ErrNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	if job.UserID != userID.String() {
+		return nil, jobs.ErrNotFound
+	}
+	return job, nil
+}
+
+type deadLetterRecorder interface {
+	RecordDeadLetter(ctx context.Context, letter jobs.DeadLetter) error
+}
+
+// DownloadDeadLetters dead-letters downloads that fail for good, keeping the
+// whole job, with request headers redacted, as their error context.
+type DownloadDeadLetters struct {
+	recorder deadLetterRecorder
+}
+
+func NewDownloadDeadLetters(recorder deadLetterRecorder) *DownloadDeadLetters {
+	return &DownloadDeadLetters{recorder: recorder}
+}
+
+// DeadLetter implements download.DeadLetterQueue.
+func (d *DownloadDeadLetters) DeadLetter(ctx context.Context, job *download.DownloadJob, jobErr error) error {
+	detail, err := json.Marshal(job.Redacted())
+	if err != nil {
+		return err
+	}
+	letter := jobs.DeadLetter{
+		Kind:     jobKindDownload,
+		JobID:    job.ID,
+		Error:    jobErr.Error(),
+		Category: jobs.ClassifyFailure(jobErr.Error()),
+		Attempts: job.RetryCount + 1,
+		Context:  detail,
+	}
+	if userID, err := uuid.Parse(job.UserID); err == nil {
+		letter.UserID = userID.String()
+	}
+	return d.recorder.RecordDeadLetter(ctx, letter)
+}
+
+type downloadQueueService interface {
+	PauseQueue(ctx context.Context, reason string) (*download.PauseState, error)
+	ResumeQueue(ctx context.Context) error
+	QueuePaused(ctx context.Context) (*download.PauseState, error)
+}
+
+// DownloadQueueControl pauses and resumes the Redis download queue as the
+// "download" job queue.
+type DownloadQueueControl struct {
+	service downloadQueueService
+}
+
+func NewDownloadQueueControl(service downloadQueueService) *DownloadQueueControl {
+	return &DownloadQueueControl{service: service}
+}
+
+func (c *DownloadQueueControl) Queues(ctx context.Context) ([]jobs.QueueState, error) {
+	state, err := c.state(ctx)
+	if err != nil {
+		return nil, err
+	}
+	return []jobs.QueueState{*state}, nil
+}
+
+func (c *DownloadQueueControl) PauseQueue(ctx context.Context, name, reason string) (*jobs.QueueState, error) {
+	if name != jobKindDownload {
+		return nil, jobs.ErrNotFound
+	}
+	if _, err := c.service.PauseQueue(ctx, reason); err != nil {
+		return nil, err
+	}
+	return c.state(ctx)
+}
+
+func (c *DownloadQueueControl) ResumeQueue(ctx context.Context, name string) (*jobs.QueueState, error) {
+	if name != jobKindDownload {
+		return nil, jobs.ErrNotFound
+	}
+	if err := c.service.ResumeQueue(ctx); err != nil {
+		return nil, err
+	}
+	return c.state(ctx)
+}
+
+func (c *DownloadQueueControl) state(ctx context.Context) (*jobs.QueueState, error) {
+	paused, err := c.service.QueuePaused(ctx)
+	if err != nil {
+		return nil, err
+	}
+	state := &jobs.QueueState{Name: jobKindDownload}
+	if paused != nil {
+		state.Paused = true
+		state.Reason = paused.Reason
+		state.PausedAt = &paused.PausedAt
+	}
+	return state, nil
+}
+
+type downloadJobDetail struct {
+	Stage      string `json:"stage"`
+	SourceType string `json:"sourceType"`
+	Title      string `json:"title,omitempty"`
+	Artist     string `json:"artist,omitempty"`
+	TrackID    *int64 `json:"trackId,omitempty"`
+}
+
+func downloadJobSummary(job *download.DownloadJob) jobs.Summary {
+	status := jobs.StatusRunning
+	switch job.Status {
+	case download.StatusQueued:
+		status = jobs.StatusQueued
+	case download.StatusComplete:
+		status = jobs.StatusSucceeded
+	case download.StatusFailed:
+		status = jobs.StatusFailed
+	case download.StatusCanceled:
+		status = jobs.StatusCanceled
+	}
+	detail, _ := json.Marshal(downloadJobDetail{
+		Stage:      job.Status,
+		SourceType: job.SourceType,
+		Title:      job.Title,
+		Artist:     job.Artist,
+		TrackID:    job.TrackID,
+	})
+	summary := jobs.Summary{
+		ID:              job.ID,
+		Kind:            jobKindDownload,
+		Status:          status,
+		Attempts:        job.RetryCount + 1,
+		ProgressCurrent: job.Progress,
+		ProgressTotal:   100,
+		ProgressDetail:  detail,
+		Error:           job.Error,
+		Cancelable:      !job.IsTerminal(),
+		CreatedAt:       job.CreatedAt,
+		UpdatedAt:       job.UpdatedAt,
+		StartedAt:       job.StartedAt,
+		FinishedAt:      job.CompletedAt,
+	}
+	if status == jobs.StatusQueued {
+		summary.Attempts = job.RetryCount
+	}
+	if failure := job.Failure(); failure != nil {
+		summary.Error = failure.Message
+		summary.FailureCategory = failure.Category
+		summary.Remediation = failure.Remediation
+	}
+	return summary
+}
+
+type playlistImportJobStore interface {
+	GetJob(ctx context.Context, id uuid.UUID) (*playlistimport.ImportJob, error)
+	ListJobsForUser(ctx context.Context, userID uuid.UUID, limit int) ([]playlistimport.ImportJob, error)
+}
+
+type playlistImportCanceler interface {
+	CancelImport(ctx context.Context, userID uuid.UUID, id uuid.UUID) (*playlistimport.ImportJob, error)
+}
+
+// PlaylistImportJobSource reports playlist imports through the jobs API.
+// Canceling an import stops its remaining tracks and cancels their downloads;
+// without a canceler, imports cannot be canceled.
+type PlaylistImportJobSource struct {
+	store    playlistImportJobStore
+	canceler playlistImportCanceler
+}
+
+func NewPlaylistImportJobSource(store playlistImportJobStore, canceler playlistImportCanceler) *PlaylistImportJobSource {
+	return &PlaylistImportJobSource{store: store, canceler: canceler}
+}
+
+func (s *PlaylistImportJobSource) ListJobs(ctx context.Context, userID uuid.UUID) ([]jobs.Summary, error) {
+	imports, err := s.store.ListJobsForUser(ctx, userID, playlistImportJobListLimit)
+	if err != nil {
+		return nil, err
+	}
+	summaries := make([]jobs.Summary, 0, len(imports))
+	for i := range imports {
+		summaries = append(summaries, playlistImportJobSummary(&imports[i]))
+	}
+	return summaries, nil
+}
+
+func (s *PlaylistImportJobSource) GetJob(ctx context.Context, userID uuid.UUID, id string) (*jobs.Summary, error) {
+	job, err := s.ownedJob(ctx, userID, id)
+	if err != nil {
+		return nil, err
+	}
+	summary := playlistImportJobSummary(job)
+	return &summary, nil
+}
+
+func (s *PlaylistImportJobSource) CancelJob(ctx context.Context, userID uuid.UUID, id string) (*jobs.Summary, error) {
+	job, err := s.ownedJob(ctx, userID, id)
+	if err != nil {
+		return nil, err
+	}
+	if s.canceler == nil {
+		return nil, jobs.ErrNotCancelable
+	}
+	job, err = s.canceler.CancelImport(ctx, userID, job.ID)
+	if errors.Is(err, playlistimport.ErrNotCancelable) {
+		return nil, jobs.ErrNotCancelable
+	}
+	if errors.Is(err, playlistimport.ErrNotFound) || errors.Is(err, playlistimport.ErrForbidden) {
+		return nil, jobs.ErrNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	summary := playlistImportJobSummary(job)
+	return &summary, nil
+}
+
+func (s *PlaylistImportJobSource) ownedJob(ctx context.Context, userID uuid.UUID, id string) (*playlistimport.ImportJob, error) {
+	importID, err := uuid.Parse(id)
+	if err != nil {
+		return nil, jobs.ErrNotFound
+	}
+	job, err := s.store.GetJob(ctx, importID)
+	if errors.Is(err, playlistimport.ErrNotFound) {
+		return nil, jobs.ErrNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	if job.UserID != userID {
+		return nil, jobs.ErrNotFound
+	}
+	return job, nil
+}
+
+type playlistImportJobDetail struct {
+	Stage      string `json:"stage"`
+	PlaylistID int64  `json:"playlistId"`
+	Imported   int    `json:"imported"`
+	Queued     int    `json:"queued"`
+	Failed     int    `json:"failed"`
+	Skipped    int    `json:"skipped"`
+}
+
+func playlistImportJobSummary(job *playlistimport.ImportJob) jobs.Summary {
+	status := jobs.StatusRunning
+	switch job.Status {
+	case playlistimport.JobStatusComplete, playlistimport.JobStatusPartialFailure:
+		status = jobs.StatusSucceeded
+	case playlistimport.JobStatusFailed:
+		status = jobs.StatusFailed
+	case playlistimport.JobStatusCanceled:
+		status = jobs.StatusCanceled
+	}
+	detail, _ := json.Marshal(playlistImportJobDetail{
+		Stage:      job.Status,
+		PlaylistID: job.PlaylistID,
+		Imported:   job.ImportedItems,
+		Queued:     job.QueuedItems,
+		Failed:     job.FailedItems,
+		Skipped:    job.SkippedItems,
+	})
+	return jobs.Summary{
+		ID:              job.ID.String(),
+		Kind:            jobKindPlaylistImport,
+		Status:          status,
+		Attempts:        1,
+		ProgressCurrent: job.ImportedItems + job.QueuedItems + job.FailedItems + job.SkippedItems,
+		ProgressTotal:   job.TotalItems,
+		ProgressDetail:  detail,
+		Error:           job.Error.String,
+		Cancelable:      job.Status == playlistimport.JobStatusResolving || job.Status == playlistimport.JobStatusImporting,
+		CreatedAt:       job.CreatedAt,
+		UpdatedAt:       job.UpdatedAt,
+	}
+}
@@ -0,0 +1,151 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+type jobDirectory interface {
+	List(ctx context.Context, userID uuid.UUID) ([]jobs.Summary, error)
+	Get(ctx context.Context, userID uuid.UUID, id string) (*jobs.Summary, error)
+	Cancel(ctx context.Context, userID uuid.UUID, id string) (*jobs.Summary, error)
+}
+
+// JobHandlers report the status of every kind of background job a user owns
+// and let them cancel jobs that have not finished.
+type JobHandlers struct {
+	directory jobDirectory
+}
+
+func NewJobHandlers(directory jobDirectory) *JobHandlers {
+	return &JobHandlers{directory: directory}
+}
+
+type JobProgressResponse struct {
+	Current int             `json:"current"`
+	Total   int             `json:"total"`
+	Percent int             `json:"percent"`
+	Detail  json.RawMessage `json:"detail,omitempty"`
+}
+
+type JobResponse struct {
+	ID              string              `json:"id"`
+	Kind            string              `json:"kind"`
+	Status          string              `json:"status"`
+	Priority        int                 `json:"priority"`
+	Attempts        int                 `json:"attempts"`
+	MaxAttempts     int                 `json:"maxAttempts"`
+	Progress        JobProgressResponse `json:"progress"`
+	Error           string              `json:"error,omitempty"`
+	FailureCategory string              `json:"failureCategory,omitempty"`
+	Remediation     string              `json:"remediation,omitempty"`
+	Cancelable      bool                `json:"cancelable"`
+	CreatedAt       time.Time           `json:"createdAt"`
+	UpdatedAt       time.Time           `json:"updatedAt"`
+	StartedAt       *time.Time          `json:"startedAt,omitempty"`
+	FinishedAt      *time.Time          `json:"finishedAt,omitempty"`
+}
+
+type JobListResponse struct {
+	Jobs []JobResponse `json:"jobs"`
+}
+
+// ListJobs handles GET /api/v1/jobs.
+func (h *JobHandlers) ListJobs(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	summaries, err := h.directory.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list jobs")
+		return
+	}
+	resp := JobListResponse{Jobs: make([]JobResponse, 0, len(summaries))}
+	for _, summary := range summaries {
+		resp.Jobs = append(resp.Jobs, jobResponse(summary))
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// GetJob handles GET /api/v1/jobs/{id}.
+func (h *JobHandlers) GetJob(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	summary, err := h.directory.Get(r.Context(), userCtx.UserID, r.PathValue("id"))
+	if errors.Is(err, jobs.ErrNotFound) {
+		writeMaintenanceError(w, http.StatusNotFound, "NOT_FOUND", "job not found")
+		return
+	}
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load job")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, jobResponse(*summary))
+}
+
+// CancelJob handles POST /api/v1/jobs/{id}/cancel. A running job stops at its
+// next progress report, so the response may still show it running.
+func (h *JobHandlers) CancelJob(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	summary, err := h.directory.Cancel(r.Context(), userCtx.UserID, r.PathValue("id"))
+	switch {
+	case errors.Is(err, jobs.ErrNotFound):
+		writeMaintenanceError(w, http.StatusNotFound, "NOT_FOUND", "job not found")
+		return
+	case errors.Is(err, jobs.ErrNotCancelable):
+		writeMaintenanceError(w, http.StatusConflict, "NOT_CANCELABLE", "job has finished or can no longer be canceled")
+		return
+	case err != nil:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to cancel job")
+		return
+	}
+	writeMaintenanceJSON(w, http.StatusAccepted, jobResponse(*summary))
+}
+
+func jobResponse(summary jobs.Summary) JobResponse {
+	resp := JobResponse{
+		ID:          summary.ID,
+		Kind:        summary.Kind,
+		Status:      summary.Status,
+		Priority:    summary.Priority,
+		Attempts:    summary.Attempts,
+		MaxAttempts: summary.MaxAttempts,
+		Progress: JobProgressResponse{
+			Current: summary.ProgressCurrent,
+			Total:   summary.ProgressTotal,
+			Detail:  summary.ProgressDetail,
+		},
+		Error:           summary.Error,
+		FailureCategory: summary.FailureCategory,
+		Remediation:     summary.Remediation,
+		Cancelable:      summary.Cancelable,
+		CreatedAt:       summary.CreatedAt,
+		UpdatedAt:       summary.UpdatedAt,
+		StartedAt:       summary.StartedAt,
+		FinishedAt:      summary.FinishedAt,
+	}
+	switch {
+	case summary.ProgressTotal > 0:
+		resp.Progress.Percent = min(summary.ProgressCurrent*100/summary.ProgressTotal, 100)
+	case summary.Status == jobs.StatusSucceeded:
+		resp.Progress.Percent = 100
+	}
+	return resp
+}
@@ -0,0 +1,226 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/playlistimport"
+)
+
+type fakeDownloadJobService struct {
+	jobs map[string]*download.DownloadJob
+}
+
+func (f *fakeDownloadJobService) GetJob(ctx context.Context, jobID string) (*download.DownloadJob, error) {
+	job, ok := f.jobs[jobID]
+	if !ok {
+		return nil, download.ErrJobNotFound
+	}
+	return job, nil
+}
+
+func (f *fakeDownloadJobService) GetUserJobs(ctx context.Context, userID string) ([]*download.DownloadJob, error) {
+	var owned []*download.DownloadJob
+	for _, job := range f.jobs {
+		if job.UserID == userID {
+			owned = append(owned, job)
+		}
+	}
+	return owned, nil
+}
+
+func (f *fakeDownloadJobService) CancelJob(ctx context.Context, jobID string) (*download.DownloadJob, error) {
+	job := f.jobs[jobID]
+	if job.IsTerminal() {
+		return nil, download.ErrJobNotCancelable
+	}
+	if job.Status == download.StatusQueued {
+		job.Status = download.StatusCanceled
+	}
+	return job, nil
+}
+
+func (f *fakeDownloadJobService) RetryDeadJob(ctx context.Context, jobID string) error {
+	job, ok := f.jobs[jobID]
+	if !ok {
+		return download.ErrJobNotFound
+	}
+	if job.Status != download.StatusFailed {
+		return download.ErrJobNotRetryable
+	}
+	job.Status = download.StatusQueued
+	job.RetryCount = 0
+	return nil
+}
+
+type fakePlaylistImportJobStore struct {
+	jobs []playlistimport.ImportJob
+}
+
+func (f *fakePlaylistImportJobStore) GetJob(ctx context.Context, id uuid.UUID) (*playlistimport.ImportJob, error) {
+	for i := range f.jobs {
+		if f.jobs[i].ID == id {
+			return &f.jobs[i], nil
+		}
+	}
+	return nil, playlistimport.ErrNotFound
+}
+
+func (f *fakePlaylistImportJobStore) ListJobsForUser(ctx context.Context, userID uuid.UUID, limit int) ([]playlistimport.ImportJob, error) {
+	var owned []playlistimport.ImportJob
+	for _, job := range f.jobs {
+		if job.UserID == userID {
+			owned = append(owned, job)
+		}
+	}
+	return owned, nil
+}
+
+func (f *fakePlaylistImportJobStore) CancelImport(ctx context.Context, userID uuid.UUID, id uuid.UUID) (*playlistimport.ImportJob, error) {
+	job, err := f.GetJob(ctx, id)
+	if err != nil {
+		return nil, err
+	}
+	if job.Status != playlistimport.JobStatusImporting {
+		return nil, playlistimport.ErrNotCancelable
+	}
+	job.Status = playlistimport.JobStatusCanceled
+	return job, nil
+}
+
+func newTestJobHandlers(userID uuid.UUID) *JobHandlers {
+	now := time.Now()
+	downloads := &fakeDownloadJobService{jobs: map[string]*download.DownloadJob{
+		"dl-queued":  {ID: "dl-queued", UserID: userID.String(), Status: download.StatusQueued, CreatedAt: now.Add(-time.Minute)},
+		"dl-running": {ID: "dl-running", UserID: userID.String(), Status: download.StatusUploading, Progress: 80, CreatedAt: now.Add(-2 * time.Minute)},
+		"dl-other":   {ID: "dl-other", UserID: uuid.NewString(), Status: download.StatusQueued, CreatedAt: now},
+		"dl-done":    {ID: "dl-done", UserID: userID.String(), Status: download.StatusComplete, CreatedAt: now.Add(-3 * time.Minute)},
+	}}
+	imports := &fakePlaylistImportJobStore{jobs: []playlistimport.ImportJob{{
+		ID:            uuid.MustParse("00000000-0000-0000-0000-000000000001"),
+		UserID:        userID,
+		Status:        playlistimport.JobStatusPartialFailure,
+		TotalItems:    4,
+		ImportedItems: 3,
+		FailedItems:   1,
+		Error:         sql.NullString{String: "1 track failed", Valid: true},
+		CreatedAt:     now.Add(-time.Hour),
+	}, {
+		ID:         uuid.MustParse("00000000-0000-0000-0000-000000000002"),
+		UserID:     userID,
+		Status:     playlistimport.JobStatusImporting,
+		TotalItems: 500,
+		CreatedAt:  now.Add(-2 * time.Hour),
+	}}}
+	return NewJobHandlers(jobs.NewDirectory(NewDownloadJobSource(downloads), NewPlaylistImportJobSource(imports, imports)))
+}
+
+func TestJobsListMergesEveryKindNewestFirst(t *testing.T) {
+	userID := uuid.New()
+	h := newTestJobHandlers(userID)
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/jobs", nil)
+	rec := httptest.NewRecorder()
+	h.ListJobs(rec, withUser(req, userID))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp JobListResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if len(resp.Jobs) != 5 {
+		t.Fatalf("jobs = %#v, want the user's five jobs", resp.Jobs)
+	}
+	queued, running, done, imported, importing := resp.Jobs[0], resp.Jobs[1], resp.Jobs[2], resp.Jobs[3], resp.Jobs[4]
+	if queued.ID != "dl-queued" || queued.Kind != "download" || queued.Status != jobs.StatusQueued || !queued.Cancelable {
+		t.Fatalf("queued download = %#v", queued)
+	}
+	if running.Status != jobs.StatusRunning || running.Progress.Percent != 80 || !running.Cancelable {
+		t.Fatalf("running download = %#v", running)
+	}
+	if done.Status != jobs.StatusSucceeded || done.Cancelable {
+		t.Fatalf("finished download = %#v", done)
+	}
+	if imported.Kind != "playlist_import" || imported.Status != jobs.StatusSucceeded || imported.Progress.Current != 4 || imported.Progress.Percent != 100 || imported.Error != "1 track failed" || imported.Cancelable {
+		t.Fatalf("playlist import = %#v", imported)
+	}
+	if importing.Status != jobs.StatusRunning || !importing.Cancelable {
+		t.Fatalf("running playlist import = %#v", importing)
+	}
+}
+
+func TestJobsCancel(t *testing.T) {
+	userID := uuid.New()
+	h := newTestJobHandlers(userID)
+
+	cancel := func(id string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/jobs/"+id+"/cancel", nil)
+		req.SetPathValue("id", id)
+		rec := httptest.NewRecorder()
+		h.CancelJob(rec, withUser(req, userID))
+		return rec
+	}
+	rec := cancel("dl-queued")
+	var canceled JobResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &canceled); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusAccepted || canceled.Status != jobs.StatusCanceled || canceled.Cancelable {
+		t.Fatalf("cancel queued = %d %#v", rec.Code, canceled)
+	}
+	if rec := cancel("dl-running"); rec.Code != http.StatusAccepted {
+		t.Fatalf("cancel running download status = %d, want 202", rec.Code)
+	}
+	if rec := cancel("dl-done"); rec.Code != http.StatusConflict {
+		t.Fatalf("cancel finished download status = %d, want 409", rec.Code)
+	}
+	if rec := cancel("00000000-0000-0000-0000-000000000001"); rec.Code != http.StatusConflict {
+		t.Fatalf("cancel finished playlist import status = %d, want 409", rec.Code)
+	}
+	rec = cancel("00000000-0000-0000-0000-000000000002")
+	canceled = JobResponse{}
+	if err := json.Unmarshal(rec.Body.Bytes(), &canceled); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusAccepted || canceled.Status != jobs.StatusCanceled || canceled.Cancelable {
+		t.Fatalf("cancel running playlist import = %d %#v", rec.Code, canceled)
+	}
+	if rec := cancel("dl-other"); rec.Code != http.StatusNotFound {
+		t.Fatalf("cancel other user's job status = %d, want 404", rec.Code)
+	}
+	if rec := cancel("missing"); rec.Code != http.StatusNotFound {
+		t.Fatalf("cancel unknown job status = %d, want 404", rec.Code)
+	}
+}
+
+func TestJobsExplainDownloadFailures(t *testing.T) {
+	userID := uuid.New()
+	downloads := &fakeDownloadJobService{jobs: map[string]*download.DownloadJob{
+		"dl-failed": {ID: "dl-failed", UserID: userID.String(), Status: download.StatusFailed, FailureCategory: download.FailureAgeRestricted,
+			Error: "yt-dlp failed: exit status 1: [youtube] abc: Downloading webpage\nERROR: [youtube] abc: Sign in to confirm your age"},
+	}}
+	h := NewJobHandlers(jobs.NewDirectory(NewDownloadJobSource(downloads)))
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/jobs/dl-failed", nil)
+	req.SetPathValue("id", "dl-failed")
+	rec := httptest.NewRecorder()
+	h.GetJob(rec, withUser(req, userID))
+	var resp JobResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.FailureCategory != download.FailureAgeRestricted || resp.Remediation == "" || strings.Contains(resp.Error, "yt-dlp") {
+		t.Fatalf("failed download = %#v", resp)
+	}
+}
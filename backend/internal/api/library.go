@@ -1,8 +1,11 @@
 package api
 
 import (
+	"context"
 	"encoding/json"
 	"errors"
+	"io"
+	"log"
 	"net/http"
 	"strconv"
 	"strings"
@@ -12,12 +15,25 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/matcher"
 )
 
 type LibraryHandlers struct {
 	trackRepo   *db.TrackRepository
 	libraryRepo *db.LibraryRepository
+	// settings holds the caller's display preferences; nil lists names as
+	// stored.
+	settings userSettingsReader
+	// objects deletes the stored audio of tracks released by a removal; nil
+	// leaves it in place.
+	objects objectDeleter
+}
+
+// objectDeleter is the object storage surface needed to delete released
+// audio. storage.Client satisfies this interface.
+type objectDeleter interface {
+	DeleteObject(ctx context.Context, key string) error
 }
 
 func NewLibraryHandlers(trackRepo *db.TrackRepository, libraryRepo *db.LibraryRepository) *LibraryHandlers {
@@ -27,6 +43,20 @@ func NewLibraryHandlers(trackRepo *db.TrackRepository, libraryRepo *db.LibraryRe
 	}
 }
 
+// WithObjectStorage makes removals delete the stored audio of tracks that
+// nothing references any more.
+func (h *LibraryHandlers) WithObjectStorage(objects objectDeleter) *LibraryHandlers {
+	h.objects = objects
+	return h
+}
+
+// LikeTrackRequest is the optional body of a like. The context records where
+// the like happened and mirrors RecordPlayRequest's fields.
+type LikeTrackRequest struct {
+	ContextType string `json:"contextType,omitempty"`
+	ContextID   string `json:"contextId,omitempty"`
+}
+
 type LibraryTrackResponse struct {
 	ID                 int64                  `json:"id"`
 	Title              string                 `json:"title"`
@@ -58,11 +88,6 @@ type AddTrackResponse struct {
 	AddedAt string `json:"added_at"`
 }
 
-type LibraryErrorResponse struct {
-	Code    string `json:"code"`
-	Message string `json:"message"`
-}
-
 // FieldSelector tracks which fields to include in the response
 type FieldSelector struct {
 	fields map[string]bool
@@ -90,12 +115,22 @@ func (s *FieldSelector) Include(field string) bool {
 }
 
 // GetLibrary handles GET /api/v1/library
-// Query params: limit, offset, sort (added_at|title|artist|duration), order (asc|desc),
+// Query params: limit, offset, sort (added_at|title|artist|album|duration|release_date|quality), order (asc|desc),
 // q (full-text search), mb_verified (bool), liked (true -> only liked tracks),
 // genre (exact match; "Unknown" matches tracks with no genre),
-// artist (exact match, local artist listing), album (exact match, local album listing),
+// artist (exact match on the artist or any credited artist, local artist listing), album (exact match, local album listing),
+// released_from/released_to (inclusive YYYY, YYYY-MM, or YYYY-MM-DD bounds),
+// inbox (true -> only tracks awaiting review, false -> only reviewed tracks),
+// quality_max/dr_max (0-100, highest quality or dynamic range score of measured tracks),
+// upscale_suspected (bool, lossless files whose spectrum looks like a lossy source's),
+// mood (chill|high_energy|happy|sad|danceable, measured tracks in that mood bucket),
+// shuffle (true when the list is fetched to play shuffled),
 // fields (comma-separated field selection).
-// Available fields: id, title, artist, album, duration_ms, mb_verified, genre, added_at, cover_art_url, source_url, file_size_bytes, codec, bitrate_kbps, sample_rate_hz, channels, content_type, metadata_status, metadata_confidence, metadata_provenance, mb_recording_id, mb_suggestions, is_liked, analysis_status, analysis_summary, analysis_updated_at
+// Without inbox, searches and shuffle fetches leave out inbox tracks unless the
+// caller's settings let them in.
+// Titles and artists follow the caller's display settings; replaced names are
+// returned as original_title and original_artist.
+// Available fields: id, title, artist, artists, album, duration_ms, mb_verified, genre, release_date, added_at, cover_art_url, source_url, file_size_bytes, codec, bitrate_kbps, sample_rate_hz, channels, content_type, metadata_status, metadata_confidence, metadata_provenance, mb_recording_id, mb_suggestions, is_liked, liked_from, in_inbox, quality_score, dr_score, spectral_cutoff_hz, upscale_suspected, energy, valence, danceability, moods, analysis_status, analysis_summary, analysis_updated_at
 //
 // Note: liked/is_liked here are scoped to the caller's library — this endpoint
 // lists the library, optionally filtered to liked tracks. A standalone "Liked
@@ -108,61 +143,55 @@ func (h *LibraryHandlers) GetLibrary(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
+	query := newQueryParams(r)
 	opts := db.LibraryQueryOptions{
-		Limit:  parseIntParam(r, "limit", 50),
-		Offset: parseIntParam(r, "offset", 0),
+		Limit:        query.Limit(50),
+		Offset:       query.Offset(),
+		SortBy:       query.Enum("sort", "", "added_at", "title", "artist", "album", "duration", "release_date", "quality"),
+		SortOrder:    query.Enum("order", "", "asc", "desc"),
+		Search:       query.Text("q", maxQueryTextRunes),
+		MBVerified:   query.Bool("mb_verified"),
+		Genre:        query.Text("genre", maxQueryTextRunes),
+		Artist:       query.Text("artist", maxQueryTextRunes),
+		Album:        query.Text("album", maxQueryTextRunes),
+		ReleasedFrom: query.PartialDate("released_from"),
+		ReleasedTo:   query.PartialDate("released_to"),
+	}
+	// Liked Songs filter
+	if liked := query.Bool("liked"); liked != nil {
+		opts.Liked = *liked
+	}
+	opts.Inbox = query.Bool("inbox")
+	if qualityMax := query.Int("quality_max", -1, 0, 100); qualityMax >= 0 {
+		opts.QualityMax = &qualityMax
+	}
+	if drMax := query.Int("dr_max", -1, 0, 100); drMax >= 0 {
+		opts.DRMax = &drMax
+	}
+	opts.UpscaleSuspected = query.Bool("upscale_suspected")
+	opts.Mood = query.Enum("mood", "", db.MoodNames...)
+	shuffle := query.Bool("shuffle")
+	if !query.Valid(w, r) {
+		return
 	}
 
-	// Parse field selection
-	fields := NewFieldSelector(r.URL.Query().Get("fields"))
-
-	// Parse sort parameters
-	if sortBy := r.URL.Query().Get("sort"); sortBy != "" {
-		switch sortBy {
-		case "added_at", "title", "artist", "duration":
-			opts.SortBy = sortBy
-		default:
-			writeLibraryError(w, http.StatusBadRequest, "INVALID_SORT", "sort must be one of: added_at, title, artist, duration")
-			return
-		}
+	settings := displaySettings(r.Context(), h.settings)
+	kind := displaySettingsKind("library", settings)
+	if opts.Inbox == nil && (opts.Search != "" && !settings.InboxInSearch || shuffle != nil && *shuffle && !settings.InboxInShuffle) {
+		reviewed := false
+		opts.Inbox = &reviewed
+		kind += ":reviewed"
 	}
 
-	if sortOrder := r.URL.Query().Get("order"); sortOrder != "" {
-		switch sortOrder {
-		case "asc", "desc":
-			opts.SortOrder = sortOrder
-		default:
-			writeLibraryError(w, http.StatusBadRequest, "INVALID_ORDER", "order must be one of: asc, desc")
+	// Answer conditional GETs from the version alone, before loading the page.
+	if version, err := h.libraryRepo.LibraryVersion(r.Context(), userCtx.UserID); err == nil {
+		if writeNotModified(w, r, listETag(kind, version, r)) {
 			return
 		}
 	}
 
-	// Parse search query
-	if q := r.URL.Query().Get("q"); q != "" {
-		opts.Search = q
-	}
-
-	// Parse mb_verified filter
-	if mbVerified := r.URL.Query().Get("mb_verified"); mbVerified != "" {
-		val := mbVerified == "true"
-		opts.MBVerified = &val
-	}
-
-	// Parse liked filter (Liked Songs)
-	if r.URL.Query().Get("liked") == "true" {
-		opts.Liked = true
-	}
-
-	// Parse genre / artist / album exact-match filters (local browse pages).
-	if genre := r.URL.Query().Get("genre"); genre != "" {
-		opts.Genre = genre
-	}
-	if artist := r.URL.Query().Get("artist"); artist != "" {
-		opts.Artist = artist
-	}
-	if album := r.URL.Query().Get("album"); album != "" {
-		opts.Album = album
-	}
+	// Parse field selection
+	fields := NewFieldSelector(r.URL.Query().Get("fields"))
 
 	tracks, total, err := h.libraryRepo.GetUserLibrary(r.Context(), userCtx.UserID, opts)
 	if err != nil {
@@ -178,11 +207,19 @@ func (h *LibraryHandlers) GetLibrary(w http.ResponseWriter, r *http.Request) {
 		// Always include ID
 		track["id"] = t.ID
 
+		title, artist := t.Title, t.Artist.String
+		names := localizeNames(settings, &title, &artist, t.Aliases)
 		if fields.Include("title") {
-			track["title"] = t.Title
+			track["title"] = title
+			if names.OriginalTitle != "" {
+				track["original_title"] = names.OriginalTitle
+			}
 		}
 		if fields.Include("artist") && t.Artist.Valid {
-			track["artist"] = t.Artist.String
+			track["artist"] = artist
+			if names.OriginalArtist != "" {
+				track["original_artist"] = names.OriginalArtist
+			}
 		}
 		if fields.Include("album") && t.Album.Valid {
 			track["album"] = t.Album.String
@@ -200,6 +237,12 @@ func (h *LibraryHandlers) GetLibrary(w http.ResponseWriter, r *http.Request) {
 				track["genre"] = "Unknown"
 			}
 		}
+		if fields.Include("artists") && len(t.Artists) > 0 {
+			track["artists"] = t.Artists
+		}
+		if fields.Include("release_date") && !t.ReleaseDate.IsZero() {
+			track["release_date"] = t.ReleaseDate.String()
+		}
 		if fields.Include("added_at") {
 			track["added_at"] = t.AddedAt.Format("2006-01-02T15:04:05Z")
 		}
@@ -249,6 +292,42 @@ func (h *LibraryHandlers) GetLibrary(w http.ResponseWriter, r *http.Request) {
 		if fields.Include("is_liked") {
 			track["is_liked"] = t.IsLiked
 		}
+		if fields.Include("liked_from") && t.LikedContextType.Valid {
+			likedFrom := map[string]string{"type": t.LikedContextType.String}
+			if t.LikedContextID.Valid {
+				likedFrom["id"] = t.LikedContextID.String
+			}
+			track["liked_from"] = likedFrom
+		}
+		if fields.Include("in_inbox") {
+			track["in_inbox"] = t.InInbox
+		}
+		if fields.Include("quality_score") && t.QualityScore.Valid {
+			track["quality_score"] = int(t.QualityScore.Int32)
+		}
+		if fields.Include("dr_score") && t.DRScore.Valid {
+			track["dr_score"] = int(t.DRScore.Int32)
+		}
+		if fields.Include("spectral_cutoff_hz") && t.SpectralCutoffHz.Valid {
+			track["spectral_cutoff_hz"] = int(t.SpectralCutoffHz.Int32)
+		}
+		if fields.Include("upscale_suspected") && t.UpscaleSuspected.Valid {
+			track["upscale_suspected"] = t.UpscaleSuspected.Bool
+		}
+		if t.Mood != nil {
+			if fields.Include("energy") {
+				track["energy"] = t.Mood.Energy
+			}
+			if fields.Include("valence") {
+				track["valence"] = t.Mood.Valence
+			}
+			if fields.Include("danceability") {
+				track["danceability"] = t.Mood.Danceability
+			}
+			if fields.Include("moods") {
+				track["moods"] = t.Mood.Moods()
+			}
+		}
 		if fields.Include("analysis_status") && t.AnalysisStatus.Valid {
 			track["analysis_status"] = t.AnalysisStatus.String
 		}
@@ -302,7 +381,7 @@ func (h *LibraryHandlers) AddTrackToLibrary(w http.ResponseWriter, r *http.Reque
 	}
 
 	// Verify track exists
-	_, err = h.trackRepo.GetByID(r.Context(), trackID)
+	_, err = h.trackRepo.GetByIDForUser(r.Context(), userCtx.UserID, trackID)
 	if err != nil {
 		if errors.Is(err, db.ErrTrackNotFound) {
 			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
@@ -318,6 +397,10 @@ func (h *LibraryHandlers) AddTrackToLibrary(w http.ResponseWriter, r *http.Reque
 			writeLibraryError(w, http.StatusConflict, "ALREADY_IN_LIBRARY", "track already in library")
 			return
 		}
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
 		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to add track to library")
 		return
 	}
@@ -348,7 +431,7 @@ func (h *LibraryHandlers) RemoveTrackFromLibrary(w http.ResponseWriter, r *http.
 		return
 	}
 
-	err = h.libraryRepo.RemoveTrackFromLibrary(r.Context(), userCtx.UserID, trackID)
+	released, err := h.libraryRepo.ReleaseTrackFromLibrary(r.Context(), userCtx.UserID, trackID)
 	if err != nil {
 		if errors.Is(err, db.ErrTrackNotInLibrary) {
 			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_IN_LIBRARY", "track not in library")
@@ -357,10 +440,30 @@ func (h *LibraryHandlers) RemoveTrackFromLibrary(w http.ResponseWriter, r *http.
 		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to remove track from library")
 		return
 	}
+	deleteReleasedObjects(r.Context(), h.objects, released)
 
 	w.WriteHeader(http.StatusNoContent)
 }
 
+// deleteReleasedObjects deletes the stored audio of a track released from a
+// library, if any. The row is gone, so a failed delete only leaves an
+// unreferenced object.
+func deleteReleasedObjects(ctx context.Context, objects objectDeleter, released *db.ReleasedTrack) {
+	if released == nil || objects == nil {
+		return
+	}
+	if released.StorageKey != "" {
+		if err := objects.DeleteObject(ctx, released.StorageKey); err != nil {
+			log.Printf("Warning: failed to delete object %s of released track %d: %v", released.StorageKey, released.ID, err)
+		}
+	}
+	if released.OriginalStorageKey != "" {
+		if err := objects.DeleteObject(ctx, released.OriginalStorageKey); err != nil {
+			log.Printf("Warning: failed to delete original object %s of released track %d: %v", released.OriginalStorageKey, released.ID, err)
+		}
+	}
+}
+
 // parseTrackIDPath extracts and validates the {track_id} path value, writing an
 // error response and returning ok=false when it is missing or malformed.
 func parseTrackIDPath(w http.ResponseWriter, r *http.Request) (int64, bool) {
@@ -378,8 +481,9 @@ func parseTrackIDPath(w http.ResponseWriter, r *http.Request) (int64, bool) {
 }
 
 // LikeTrack handles POST /api/v1/library/tracks/{track_id}/like.
-// Idempotent: liking an already-liked track still returns 201. Liking does not
-// add the track to the library (favorites are independent of membership).
+// Idempotent: liking an already-liked track still returns 201 and keeps the
+// original "liked from" context. Liking does not add the track to the library
+// (favorites are independent of membership).
 func (h *LibraryHandlers) LikeTrack(w http.ResponseWriter, r *http.Request) {
 	userCtx := auth.GetUserFromContext(r.Context())
 	if userCtx == nil {
@@ -392,8 +496,19 @@ func (h *LibraryHandlers) LikeTrack(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
+	// The body is optional; an empty one likes without a context.
+	var req LikeTrackRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil && !errors.Is(err, io.EOF) {
+		writeLibraryError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid request body")
+		return
+	}
+	if msg := validatePlayContext(req.ContextType, req.ContextID); msg != "" {
+		writeLibraryError(w, http.StatusBadRequest, "INVALID_REQUEST", msg)
+		return
+	}
+
 	// Verify the track exists so an unknown track is a clean 404, not a silent like.
-	if _, err := h.trackRepo.GetByID(r.Context(), trackID); err != nil {
+	if _, err := h.trackRepo.GetByIDForUser(r.Context(), userCtx.UserID, trackID); err != nil {
 		if errors.Is(err, db.ErrTrackNotFound) {
 			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
 			return
@@ -402,7 +517,7 @@ func (h *LibraryHandlers) LikeTrack(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	if err := h.libraryRepo.AddFavorite(r.Context(), userCtx.UserID, trackID); err != nil {
+	if err := h.libraryRepo.AddFavorite(r.Context(), userCtx.UserID, trackID, req.ContextType, req.ContextID); err != nil {
 		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to like track")
 		return
 	}
@@ -436,15 +551,6 @@ func (h *LibraryHandlers) UnlikeTrack(w http.ResponseWriter, r *http.Request) {
 	w.WriteHeader(http.StatusNoContent)
 }
 
-func parseIntParam(r *http.Request, name string, defaultVal int) int {
-	if val := r.URL.Query().Get(name); val != "" {
-		if parsed, err := strconv.Atoi(val); err == nil && parsed >= 0 {
-			return parsed
-		}
-	}
-	return defaultVal
-}
-
 func writeLibraryJSON(w http.ResponseWriter, status int, data interface{}) {
 	w.Header().Set("Content-Type", "application/json")
 	w.WriteHeader(status)
@@ -452,12 +558,7 @@ func writeLibraryJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writeLibraryError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(LibraryErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
 
 // parseMBSuggestions extracts MB suggestions from metadata JSON
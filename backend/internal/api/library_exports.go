@@ -0,0 +1,88 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type libraryExportQueue interface {
+	Enqueue(ctx context.Context, userID uuid.UUID, payload libraryexport.LibraryExportPayload) (*jobs.Job, error)
+}
+
+type deviceProfileLookup interface {
+	Get(ctx context.Context, userID uuid.UUID, id int64) (*db.DeviceProfile, error)
+}
+
+// LibraryExportHandlers queue exports of tracks and playlists to a folder
+// below the export directory the operator configured, for copying to a USB
+// stick or a portable player.
+type LibraryExportHandlers struct {
+	queue    libraryExportQueue
+	profiles deviceProfileLookup
+}
+
+func NewLibraryExportHandlers(queue libraryExportQueue, profiles deviceProfileLookup) *LibraryExportHandlers {
+	return &LibraryExportHandlers{queue: queue, profiles: profiles}
+}
+
+type LibraryExportRequest struct {
+	Target      string  `json:"target"`
+	Template    string  `json:"template"`
+	TrackIDs    []int64 `json:"trackIds"`
+	PlaylistIDs []int64 `json:"playlistIds"`
+	// ProfileID names the device profile to write files for; omitted writes
+	// stored files as they are.
+	ProfileID *int64 `json:"profileId"`
+}
+
+// CreateExport handles POST /api/v1/exports.
+func (h *LibraryExportHandlers) CreateExport(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req LibraryExportRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	payload := libraryexport.LibraryExportPayload{
+		Target:      req.Target,
+		Template:    req.Template,
+		TrackIDs:    req.TrackIDs,
+		PlaylistIDs: req.PlaylistIDs,
+	}
+	if req.ProfileID != nil {
+		stored, err := h.profiles.Get(r.Context(), userCtx.UserID, *req.ProfileID)
+		if err != nil {
+			writeDeviceProfileError(w, err, "failed to load device profile")
+			return
+		}
+		profile := exportProfile(stored)
+		payload.Profile = &profile
+	}
+	job, err := h.queue.Enqueue(r.Context(), userCtx.UserID, payload)
+	switch {
+	case errors.Is(err, libraryexport.ErrInvalidExport):
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	case errors.Is(err, jobs.ErrDuplicate):
+		writeMaintenanceError(w, http.StatusConflict, "JOB_ACTIVE", "an export to this folder is already queued or running")
+		return
+	case err != nil:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue library export")
+		return
+	}
+	w.Header().Set("Location", middleware.Path(r, "/api/v1/jobs/"+job.ID.String()))
+	writeMaintenanceJSON(w, http.StatusAccepted, jobResponse(job.Summary()))
+}
@@ -0,0 +1,76 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+)
+
+type fakeLibraryExportQueue struct {
+	payloads []libraryexport.LibraryExportPayload
+}
+
+func (f *fakeLibraryExportQueue) Enqueue(ctx context.Context, userID uuid.UUID, payload libraryexport.LibraryExportPayload) (*jobs.Job, error) {
+	if len(f.payloads) > 0 && f.payloads[0].Target == payload.Target {
+		return nil, jobs.ErrDuplicate
+	}
+	if payload.Template != "" {
+		if _, err := libraryexport.ParsePathTemplate(payload.Template); err != nil {
+			return nil, err
+		}
+	}
+	f.payloads = append(f.payloads, payload)
+	return &jobs.Job{ID: uuid.New(), Kind: "library_export", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued, MaxAttempts: 3}, nil
+}
+
+func TestLibraryExportsQueueJobs(t *testing.T) {
+	queue := &fakeLibraryExportQueue{}
+	userID := uuid.New()
+	profiles := &fakeDeviceProfiles{profiles: map[int64]*db.DeviceProfile{
+		7: {ID: 7, UserID: userID, Name: "Car USB", Format: "opus", BitrateKbps: 160},
+	}}
+	h := NewLibraryExportHandlers(queue, profiles)
+	create := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/exports", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.CreateExport(rec, withUser(req, userID))
+		return rec
+	}
+
+	rec := create(`{"target":"car","trackIds":[3,4],"playlistIds":[9]}`)
+	if rec.Code != http.StatusAccepted || !strings.Contains(rec.Body.String(), `"kind":"library_export"`) || !strings.HasPrefix(rec.Header().Get("Location"), "/api/v1/jobs/") {
+		t.Fatalf("create = %d %s", rec.Code, rec.Body.String())
+	}
+	if got := queue.payloads[0]; got.Target != "car" || len(got.TrackIDs) != 2 || got.PlaylistIDs[0] != 9 || got.Profile != nil {
+		t.Fatalf("payload = %+v", got)
+	}
+	if rec := create(`{"target":"dap","trackIds":[3],"profileId":7}`); rec.Code != http.StatusAccepted {
+		t.Fatalf("create with profile = %d %s", rec.Code, rec.Body.String())
+	}
+	if got := queue.payloads[1].Profile; got == nil || got.Format != "opus" || got.BitrateKbps != 160 {
+		t.Fatalf("profile = %+v", got)
+	}
+
+	tests := []struct {
+		body string
+		want int
+	}{
+		{`{"target":"car","trackIds":[5]}`, http.StatusConflict},
+		{`{"target":"dap","template":"{artist}/{title}","trackIds":[5]}`, http.StatusBadRequest},
+		{`{"target":"dap","root":"/etc","trackIds":[5]}`, http.StatusBadRequest},
+		{`{"target":"usb","trackIds":[5],"profileId":8}`, http.StatusNotFound},
+	}
+	for _, tt := range tests {
+		if rec := create(tt.body); rec.Code != tt.want {
+			t.Errorf("%s: status = %d, want %d", tt.body, rec.Code, tt.want)
+		}
+	}
+}
@@ -0,0 +1,166 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type libraryHealthStore interface {
+	LibraryHealth(ctx context.Context, userID uuid.UUID) (db.LibraryHealthReport, error)
+	LibraryHealthTracks(ctx context.Context, userID uuid.UUID, issue string, limit, offset int) ([]db.LibraryHealthTrack, int, error)
+	UnmatchedImportItems(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.UnmatchedImportItem, int, error)
+}
+
+// LibraryHealthHandlers report problems in the caller's library so they can
+// be cleaned up one issue at a time.
+type LibraryHealthHandlers struct {
+	store libraryHealthStore
+}
+
+func NewLibraryHealthHandlers(store libraryHealthStore) *LibraryHealthHandlers {
+	return &LibraryHealthHandlers{store: store}
+}
+
+type LibraryHealthIssueResponse struct {
+	Issue string `json:"issue"`
+	Count int    `json:"count"`
+	// Href lists the affected tracks or import entries.
+	Href string `json:"href"`
+	// Action names the bulk fix-it job that repairs the issue, queued with
+	// POST /api/v1/maintenance/jobs.
+	Action string `json:"action,omitempty"`
+}
+
+type LibraryHealthResponse struct {
+	TotalTracks int                          `json:"totalTracks"`
+	Issues      []LibraryHealthIssueResponse `json:"issues"`
+}
+
+type LibraryHealthTrackResponse struct {
+	TrackID        int64  `json:"trackId"`
+	Title          string `json:"title"`
+	Artist         string `json:"artist,omitempty"`
+	Album          string `json:"album,omitempty"`
+	DurationMs     int    `json:"durationMs,omitempty"`
+	BitrateKbps    int    `json:"bitrateKbps,omitempty"`
+	DuplicateCount int    `json:"duplicateCount,omitempty"`
+}
+
+type UnmatchedImportItemResponse struct {
+	ID          int64     `json:"id"`
+	ImportJobID uuid.UUID `json:"importJobId"`
+	PlaylistID  int64     `json:"playlistId"`
+	Title       string    `json:"title,omitempty"`
+	Artist      string    `json:"artist,omitempty"`
+	SourceURL   string    `json:"sourceUrl,omitempty"`
+	Error       string    `json:"error,omitempty"`
+}
+
+// LibraryHealthDetailResponse lists one issue's tracks, or for
+// unmatched_import its playlist import entries.
+type LibraryHealthDetailResponse struct {
+	Issue  string                        `json:"issue"`
+	Tracks []LibraryHealthTrackResponse  `json:"tracks,omitempty"`
+	Items  []UnmatchedImportItemResponse `json:"items,omitempty"`
+	Total  int                           `json:"total"`
+	Limit  int                           `json:"limit"`
+	Offset int                           `json:"offset"`
+}
+
+// GetLibraryHealth handles GET /api/v1/library/health.
+func (h *LibraryHealthHandlers) GetLibraryHealth(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	report, err := h.store.LibraryHealth(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to build library health report")
+		return
+	}
+	resp := LibraryHealthResponse{TotalTracks: report.TotalTracks}
+	issues := append([]string{}, db.LibraryHealthTrackIssues...)
+	for _, issue := range append(issues, db.HealthIssueUnmatchedImport) {
+		resp.Issues = append(resp.Issues, LibraryHealthIssueResponse{
+			Issue:  issue,
+			Count:  report.Counts[issue],
+			Href:   middleware.Path(r, "/api/v1/library/health/"+issue),
+			Action: maintenanceActionFor(issue),
+		})
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// GetLibraryHealthIssue handles GET /api/v1/library/health/{issue}.
+// Query params: limit, offset.
+func (h *LibraryHealthHandlers) GetLibraryHealthIssue(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	resp := LibraryHealthDetailResponse{
+		Issue:  r.PathValue("issue"),
+		Limit:  query.Limit(50),
+		Offset: query.Offset(),
+	}
+	if !query.Valid(w, r) {
+		return
+	}
+
+	if resp.Issue == db.HealthIssueUnmatchedImport {
+		items, total, err := h.store.UnmatchedImportItems(r.Context(), userCtx.UserID, resp.Limit, resp.Offset)
+		if err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list unmatched imports")
+			return
+		}
+		resp.Total = total
+		resp.Items = make([]UnmatchedImportItemResponse, 0, len(items))
+		for _, item := range items {
+			resp.Items = append(resp.Items, UnmatchedImportItemResponse{
+				ID:          item.ID,
+				ImportJobID: item.ImportJobID,
+				PlaylistID:  item.PlaylistID,
+				Title:       item.Title,
+				Artist:      item.Artist,
+				SourceURL:   item.SourceURL,
+				Error:       item.Error.String,
+			})
+		}
+		writePlaybackJSON(w, http.StatusOK, resp)
+		return
+	}
+
+	tracks, total, err := h.store.LibraryHealthTracks(r.Context(), userCtx.UserID, resp.Issue, resp.Limit, resp.Offset)
+	if errors.Is(err, db.ErrUnknownHealthIssue) {
+		writeLibraryError(w, http.StatusNotFound, "UNKNOWN_ISSUE", "unknown library health issue")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list tracks")
+		return
+	}
+	resp.Total = total
+	resp.Tracks = make([]LibraryHealthTrackResponse, 0, len(tracks))
+	for _, track := range tracks {
+		resp.Tracks = append(resp.Tracks, LibraryHealthTrackResponse{
+			TrackID:        track.TrackID,
+			Title:          track.Title,
+			Artist:         track.Artist.String,
+			Album:          track.Album.String,
+			DurationMs:     int(track.DurationMs.Int32),
+			BitrateKbps:    int(track.BitrateKbps.Int32),
+			DuplicateCount: track.DuplicateCount,
+		})
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
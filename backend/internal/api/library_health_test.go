@@ -0,0 +1,83 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/maintenance"
+)
+
+type fakeLibraryHealthStore struct {
+	report db.LibraryHealthReport
+	tracks []db.LibraryHealthTrack
+}
+
+func (f *fakeLibraryHealthStore) LibraryHealth(ctx context.Context, userID uuid.UUID) (db.LibraryHealthReport, error) {
+	return f.report, nil
+}
+
+func (f *fakeLibraryHealthStore) LibraryHealthTracks(ctx context.Context, userID uuid.UUID, issue string, limit, offset int) ([]db.LibraryHealthTrack, int, error) {
+	if issue != db.HealthIssueLowBitrate {
+		return nil, 0, db.ErrUnknownHealthIssue
+	}
+	return f.tracks, len(f.tracks), nil
+}
+
+func (f *fakeLibraryHealthStore) UnmatchedImportItems(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.UnmatchedImportItem, int, error) {
+	return nil, 0, nil
+}
+
+func TestLibraryHealthReportsEveryIssueWithDrillDownLinks(t *testing.T) {
+	store := &fakeLibraryHealthStore{
+		report: db.LibraryHealthReport{TotalTracks: 12, Counts: map[string]int{db.HealthIssueLowBitrate: 3}},
+		tracks: []db.LibraryHealthTrack{{TrackID: 9, Title: "Demo"}},
+	}
+	h := NewLibraryHealthHandlers(store)
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/library/health", nil)
+	rec := httptest.NewRecorder()
+	h.GetLibraryHealth(rec, withUser(req, uuid.New()))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp LibraryHealthResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.TotalTracks != 12 || len(resp.Issues) != len(db.LibraryHealthTrackIssues)+1 {
+		t.Fatalf("report = %#v", resp)
+	}
+	for _, issue := range resp.Issues {
+		if issue.Issue == db.HealthIssueLowBitrate && (issue.Count != 3 || issue.Href != "/api/v1/library/health/low_bitrate") {
+			t.Fatalf("low_bitrate issue = %#v", issue)
+		}
+		if issue.Issue == db.HealthIssueCorruptFile && issue.Action != maintenance.ActionRedownloadCorrupt {
+			t.Fatalf("corrupt_file issue = %#v", issue)
+		}
+	}
+
+	drill := func(issue string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodGet, "/api/v1/library/health/"+issue, nil)
+		req.SetPathValue("issue", issue)
+		rec := httptest.NewRecorder()
+		h.GetLibraryHealthIssue(rec, withUser(req, uuid.New()))
+		return rec
+	}
+	rec = drill(db.HealthIssueLowBitrate)
+	var detail LibraryHealthDetailResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &detail); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if rec.Code != http.StatusOK || detail.Total != 1 || len(detail.Tracks) != 1 || detail.Tracks[0].TrackID != 9 {
+		t.Fatalf("drill-down = %d %#v", rec.Code, detail)
+	}
+	if rec := drill("haunted"); rec.Code != http.StatusNotFound {
+		t.Fatalf("unknown issue status = %d, want 404", rec.Code)
+	}
+}
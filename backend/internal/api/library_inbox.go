@@ -0,0 +1,282 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	// maxInboxBatch caps how many tracks one approve or discard names.
+	maxInboxBatch = 500
+	// maxInboxFieldLength bounds each corrected metadata field.
+	maxInboxFieldLength = 500
+)
+
+type libraryInboxRepository interface {
+	TracksInInbox(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]bool, error)
+	ApproveInboxTracks(ctx context.Context, userID uuid.UUID, trackIDs []int64) ([]int64, error)
+	ReleaseTrackFromLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*db.ReleasedTrack, error)
+}
+
+type inboxTrackEditor interface {
+	GetByIDs(ctx context.Context, ids []int64) (map[int64]*db.Track, error)
+	UpdateMetadata(ctx context.Context, trackID int64, update *db.MetadataUpdate, precondition func(version string) bool) error
+	MetadataVersion(ctx context.Context, trackID int64) (string, error)
+}
+
+// LibraryInboxHandlers review the tracks waiting in the caller's library
+// inbox: approving keeps them, fixing corrects their metadata first, and
+// discarding removes them. Inbox tracks are listed with
+// GET /api/v1/library?inbox=true.
+type LibraryInboxHandlers struct {
+	library libraryInboxRepository
+	tracks  inboxTrackEditor
+	// objects deletes the stored audio of discarded tracks nothing else
+	// references; nil leaves it in place.
+	objects objectDeleter
+}
+
+func NewLibraryInboxHandlers(library libraryInboxRepository, tracks inboxTrackEditor, objects objectDeleter) *LibraryInboxHandlers {
+	return &LibraryInboxHandlers{library: library, tracks: tracks, objects: objects}
+}
+
+// ApproveInboxRequest names the inbox tracks to keep. ConfirmMetadata locks
+// their current title, artist and album as user values, so later enrichment
+// leaves them alone.
+type ApproveInboxRequest struct {
+	TrackIDs        []int64 `json:"trackIds"`
+	ConfirmMetadata bool    `json:"confirmMetadata,omitempty"`
+}
+
+type DiscardInboxRequest struct {
+	TrackIDs []int64 `json:"trackIds"`
+}
+
+// FixInboxTrackRequest corrects an inbox track's metadata before it is kept.
+// Empty fields are left as they are.
+type FixInboxTrackRequest struct {
+	Title  string `json:"title,omitempty"`
+	Artist string `json:"artist,omitempty"`
+	Album  string `json:"album,omitempty"`
+}
+
+// InboxResponse lists the tracks a review applied to. Named tracks that were
+// not in the inbox are left out.
+type InboxResponse struct {
+	TrackIDs []int64 `json:"trackIds"`
+}
+
+// Approve handles POST /api/v1/library/inbox/approve.
+func (h *LibraryInboxHandlers) Approve(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	userID := userCtx.UserID
+	var req ApproveInboxRequest
+	if err := decodeStrictJSON(r, &req); err != nil || !validInboxBatch(req.TrackIDs) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds must name 1 to 500 tracks")
+		return
+	}
+
+	trackIDs := req.TrackIDs
+	if req.ConfirmMetadata {
+		var err error
+		if trackIDs, err = h.confirmMetadata(r.Context(), userID, trackIDs); err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to confirm metadata")
+			return
+		}
+	}
+	approved, err := h.library.ApproveInboxTracks(r.Context(), userID, trackIDs)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to approve inbox tracks")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, InboxResponse{TrackIDs: approved})
+}
+
+// FixTrack handles POST /api/v1/library/inbox/{track_id}/fix. The corrected
+// fields are locked as user edits and the track leaves the inbox.
+func (h *LibraryInboxHandlers) FixTrack(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	userID := userCtx.UserID
+	trackID, ok := parseTrackIDPath(w, r)
+	if !ok {
+		return
+	}
+	var req FixInboxTrackRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	update := &db.MetadataUpdate{
+		Title:  strings.TrimSpace(req.Title),
+		Artist: strings.TrimSpace(req.Artist),
+		Album:  strings.TrimSpace(req.Album),
+	}
+	if update.Title == "" && update.Artist == "" && update.Album == "" {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "title, artist or album is required")
+		return
+	}
+	for _, field := range []string{update.Title, update.Artist, update.Album} {
+		if utf8.RuneCountInString(field) > maxInboxFieldLength {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "metadata fields must be at most 500 characters")
+			return
+		}
+	}
+
+	inInbox, err := h.library.TracksInInbox(r.Context(), userID, []int64{trackID})
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify inbox membership")
+		return
+	}
+	if !inInbox[trackID] {
+		writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_IN_INBOX", "track not in inbox")
+		return
+	}
+	if err := h.tracks.UpdateMetadata(r.Context(), trackID, update, ifMatch(r)); err != nil {
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_IN_INBOX", "track not in inbox")
+			return
+		}
+		if errors.Is(err, db.ErrTrackVersionMismatch) {
+			h.writeTrackConflict(w, r, trackID)
+			return
+		}
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to update track metadata")
+		return
+	}
+	if version, err := h.tracks.MetadataVersion(r.Context(), trackID); err == nil {
+		w.Header().Set("ETag", resourceETag(version))
+	}
+	approved, err := h.library.ApproveInboxTracks(r.Context(), userID, []int64{trackID})
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to approve inbox track")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, InboxResponse{TrackIDs: approved})
+}
+
+// writeTrackConflict answers a fix sent with a stale If-Match with the track
+// as it now is; the track stays in the inbox.
+func (h *LibraryInboxHandlers) writeTrackConflict(w http.ResponseWriter, r *http.Request, trackID int64) {
+	version, err := h.tracks.MetadataVersion(r.Context(), trackID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+		return
+	}
+	tracks, err := h.tracks.GetByIDs(r.Context(), []int64{trackID})
+	if err != nil || tracks[trackID] == nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+		return
+	}
+	writeVersionConflict(w, r, "track was modified since it was fetched; reapply the fix to the current metadata and retry",
+		version, mapTrackResponses([]db.Track{*tracks[trackID]})[0])
+}
+
+// Discard handles POST /api/v1/library/inbox/discard. Discarded tracks leave
+// the library as DELETE /api/v1/library/tracks/{track_id} would remove them.
+func (h *LibraryInboxHandlers) Discard(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	userID := userCtx.UserID
+	var req DiscardInboxRequest
+	if err := decodeStrictJSON(r, &req); err != nil || !validInboxBatch(req.TrackIDs) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds must name 1 to 500 tracks")
+		return
+	}
+	inInbox, err := h.library.TracksInInbox(r.Context(), userID, req.TrackIDs)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify inbox membership")
+		return
+	}
+
+	discarded := []int64{}
+	for _, trackID := range req.TrackIDs {
+		if !inInbox[trackID] {
+			continue
+		}
+		// A track removed since the check is skipped.
+		released, err := h.library.ReleaseTrackFromLibrary(r.Context(), userID, trackID)
+		if errors.Is(err, db.ErrTrackNotInLibrary) {
+			continue
+		}
+		if err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to discard inbox tracks")
+			return
+		}
+		delete(inInbox, trackID)
+		discarded = append(discarded, trackID)
+		deleteReleasedObjects(r.Context(), h.objects, released)
+	}
+	writeLibraryJSON(w, http.StatusOK, InboxResponse{TrackIDs: discarded})
+}
+
+// confirmMetadata locks the current title, artist and album of the inbox
+// tracks among trackIDs and returns those tracks.
+func (h *LibraryInboxHandlers) confirmMetadata(ctx context.Context, userID uuid.UUID, trackIDs []int64) ([]int64, error) {
+	inInbox, err := h.library.TracksInInbox(ctx, userID, trackIDs)
+	if err != nil {
+		return nil, err
+	}
+	ids := make([]int64, 0, len(inInbox))
+	for _, id := range trackIDs {
+		if inInbox[id] {
+			ids = append(ids, id)
+			delete(inInbox, id)
+		}
+	}
+	if len(ids) == 0 {
+		return ids, nil
+	}
+	tracks, err := h.tracks.GetByIDs(ctx, ids)
+	if err != nil {
+		return nil, err
+	}
+	confirmed := make([]int64, 0, len(ids))
+	for _, id := range ids {
+		track := tracks[id]
+		if track == nil {
+			continue
+		}
+		update := &db.MetadataUpdate{Title: track.Title, Artist: track.Artist.String, Album: track.Album.String}
+		if err := h.tracks.UpdateMetadata(ctx, id, update, nil); err != nil {
+			if errors.Is(err, db.ErrTrackNotFound) {
+				continue
+			}
+			return nil, err
+		}
+		confirmed = append(confirmed, id)
+	}
+	return confirmed, nil
+}
+
+// validInboxBatch reports whether trackIDs names between 1 and maxInboxBatch
+// valid track IDs.
+func validInboxBatch(trackIDs []int64) bool {
+	if len(trackIDs) == 0 || len(trackIDs) > maxInboxBatch {
+		return false
+	}
+	for _, id := range trackIDs {
+		if id <= 0 {
+			return false
+		}
+	}
+	return true
+}
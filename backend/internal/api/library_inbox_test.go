@@ -0,0 +1,209 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeLibraryInbox struct {
+	inbox    map[int64]bool
+	released map[int64]*db.ReleasedTrack
+}
+
+func (f *fakeLibraryInbox) TracksInInbox(_ context.Context, _ uuid.UUID, trackIDs []int64) (map[int64]bool, error) {
+	found := map[int64]bool{}
+	for _, id := range trackIDs {
+		if f.inbox[id] {
+			found[id] = true
+		}
+	}
+	return found, nil
+}
+
+func (f *fakeLibraryInbox) ApproveInboxTracks(_ context.Context, _ uuid.UUID, trackIDs []int64) ([]int64, error) {
+	approved := []int64{}
+	for _, id := range trackIDs {
+		if f.inbox[id] {
+			delete(f.inbox, id)
+			approved = append(approved, id)
+		}
+	}
+	return approved, nil
+}
+
+func (f *fakeLibraryInbox) ReleaseTrackFromLibrary(_ context.Context, _ uuid.UUID, trackID int64) (*db.ReleasedTrack, error) {
+	delete(f.inbox, trackID)
+	return f.released[trackID], nil
+}
+
+type fakeInboxTracks struct {
+	tracks  map[int64]*db.Track
+	updates map[int64]db.MetadataUpdate
+}
+
+func (f *fakeInboxTracks) GetByIDs(_ context.Context, ids []int64) (map[int64]*db.Track, error) {
+	found := map[int64]*db.Track{}
+	for _, id := range ids {
+		if track, ok := f.tracks[id]; ok {
+			found[id] = track
+		}
+	}
+	return found, nil
+}
+
+func (f *fakeInboxTracks) UpdateMetadata(ctx context.Context, trackID int64, update *db.MetadataUpdate, precondition func(version string) bool) error {
+	version, err := f.MetadataVersion(ctx, trackID)
+	if err != nil {
+		return err
+	}
+	if precondition != nil && !precondition(version) {
+		return db.ErrTrackVersionMismatch
+	}
+	f.updates[trackID] = *update
+	if update.Title != "" {
+		f.tracks[trackID].Title = update.Title
+	}
+	return nil
+}
+
+// MetadataVersion derives the version from the title, the only field the
+// fixtures edit.
+func (f *fakeInboxTracks) MetadataVersion(_ context.Context, trackID int64) (string, error) {
+	track := f.tracks[trackID]
+	if track == nil {
+		return "", db.ErrTrackNotFound
+	}
+	return "v-" + track.Title, nil
+}
+
+type fakeObjects struct {
+	deleted []string
+}
+
+func (f *fakeObjects) DeleteObject(_ context.Context, key string) error {
+	f.deleted = append(f.deleted, key)
+	return nil
+}
+
+func newInboxFixture() (*LibraryInboxHandlers, *fakeLibraryInbox, *fakeInboxTracks, *fakeObjects) {
+	library := &fakeLibraryInbox{
+		inbox:    map[int64]bool{1: true, 2: true, 3: true},
+		released: map[int64]*db.ReleasedTrack{2: {ID: 2, StorageKey: "audio/2.mp3"}},
+	}
+	tracks := &fakeInboxTracks{
+		tracks: map[int64]*db.Track{
+			1: {ID: 1, Title: "First", Artist: sql.NullString{String: "Band", Valid: true}},
+			2: {ID: 2, Title: "Second"},
+			3: {ID: 3, Title: "Thrid"},
+		},
+		updates: map[int64]db.MetadataUpdate{},
+	}
+	objects := &fakeObjects{}
+	return NewLibraryInboxHandlers(library, tracks, objects), library, tracks, objects
+}
+
+func serveInbox(handler http.HandlerFunc, path, body, trackID string) (*httptest.ResponseRecorder, InboxResponse) {
+	req := httptest.NewRequest(http.MethodPost, path, strings.NewReader(body))
+	if trackID != "" {
+		req.SetPathValue("track_id", trackID)
+	}
+	rec := httptest.NewRecorder()
+	handler(rec, withUser(req, uuid.New()))
+	var resp InboxResponse
+	_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+	return rec, resp
+}
+
+func TestInboxApproveConfirmsMetadataOfInboxTracksOnly(t *testing.T) {
+	h, library, tracks, _ := newInboxFixture()
+
+	rec, resp := serveInbox(h.Approve, "/api/v1/library/inbox/approve", `{"trackIds":[1,9],"confirmMetadata":true}`, "")
+	if rec.Code != http.StatusOK || !reflect.DeepEqual(resp.TrackIDs, []int64{1}) {
+		t.Fatalf("approve = %d %#v", rec.Code, resp)
+	}
+	if got := tracks.updates[1]; got != (db.MetadataUpdate{Title: "First", Artist: "Band"}) || len(tracks.updates) != 1 {
+		t.Fatalf("metadata updates = %#v", tracks.updates)
+	}
+	if library.inbox[1] || !library.inbox[2] {
+		t.Fatalf("inbox after approve = %v", library.inbox)
+	}
+
+	for _, body := range []string{`{"trackIds":[]}`, `{"trackIds":[0]}`, `{"trackIds":[1],"extra":true}`} {
+		if rec, _ := serveInbox(h.Approve, "/api/v1/library/inbox/approve", body, ""); rec.Code != http.StatusBadRequest {
+			t.Fatalf("approve %s = %d, want 400", body, rec.Code)
+		}
+	}
+}
+
+func TestInboxFixUpdatesMetadataAndApproves(t *testing.T) {
+	h, library, tracks, _ := newInboxFixture()
+
+	rec, resp := serveInbox(h.FixTrack, "/api/v1/library/inbox/3/fix", `{"title":" Third "}`, "3")
+	if rec.Code != http.StatusOK || !reflect.DeepEqual(resp.TrackIDs, []int64{3}) {
+		t.Fatalf("fix = %d %#v", rec.Code, resp)
+	}
+	if got := tracks.updates[3]; got != (db.MetadataUpdate{Title: "Third"}) || library.inbox[3] {
+		t.Fatalf("update = %#v, still in inbox = %v", got, library.inbox[3])
+	}
+
+	if rec, _ := serveInbox(h.FixTrack, "/api/v1/library/inbox/3/fix", `{"title":"Again"}`, "3"); rec.Code != http.StatusNotFound {
+		t.Fatalf("fix of a reviewed track = %d, want 404", rec.Code)
+	}
+	if rec, _ := serveInbox(h.FixTrack, "/api/v1/library/inbox/1/fix", `{"title":"  "}`, "1"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("fix without fields = %d, want 400", rec.Code)
+	}
+}
+
+func TestInboxFixRefusesAStaleIfMatchWithTheCurrentTrack(t *testing.T) {
+	h, library, _, _ := newInboxFixture()
+	fix := func(ifMatch string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/library/inbox/3/fix", strings.NewReader(`{"title":"Third"}`))
+		req.SetPathValue("track_id", "3")
+		req.Header.Set("If-Match", ifMatch)
+		rec := httptest.NewRecorder()
+		h.FixTrack(rec, withUser(req, uuid.New()))
+		return rec
+	}
+
+	rec := fix(`"v-Thrd"`)
+	var problem struct {
+		Code    string `json:"code"`
+		Details struct {
+			Current TrackResponse `json:"current"`
+		} `json:"details"`
+	}
+	_ = json.Unmarshal(rec.Body.Bytes(), &problem)
+	if rec.Code != http.StatusConflict || problem.Code != "VERSION_CONFLICT" || problem.Details.Current.Title != "Thrid" || rec.Header().Get("ETag") != `"v-Thrid"` {
+		t.Fatalf("stale fix = %d %s, ETag %q", rec.Code, rec.Body.String(), rec.Header().Get("ETag"))
+	}
+	if !library.inbox[3] {
+		t.Fatal("a refused fix approved the track")
+	}
+
+	if rec := fix(`"v-Thrid"`); rec.Code != http.StatusOK || rec.Header().Get("ETag") != `"v-Third"` {
+		t.Fatalf("fix with the current ETag = %d, ETag %q", rec.Code, rec.Header().Get("ETag"))
+	}
+}
+
+func TestInboxDiscardReleasesTracksAndDeletesTheirAudio(t *testing.T) {
+	h, library, _, objects := newInboxFixture()
+	delete(library.inbox, 1)
+
+	rec, resp := serveInbox(h.Discard, "/api/v1/library/inbox/discard", `{"trackIds":[1,2,2]}`, "")
+	if rec.Code != http.StatusOK || !reflect.DeepEqual(resp.TrackIDs, []int64{2}) {
+		t.Fatalf("discard = %d %#v", rec.Code, resp)
+	}
+	if !reflect.DeepEqual(objects.deleted, []string{"audio/2.mp3"}) {
+		t.Fatalf("deleted objects = %v", objects.deleted)
+	}
+}
@@ -0,0 +1,82 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryimport"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type libraryMigrationQueue interface {
+	Sources() []libraryimport.SourceInfo
+	Enqueue(ctx context.Context, userID uuid.UUID, source, username, password string) (*jobs.Job, error)
+}
+
+// LibraryMigrationHandlers queue migrations of play counts, favorites,
+// ratings, and playlists from the Navidrome and Jellyfin servers the operator
+// configured. The jobs run in the background and report their progress
+// through the jobs API.
+type LibraryMigrationHandlers struct {
+	queue libraryMigrationQueue
+}
+
+func NewLibraryMigrationHandlers(queue libraryMigrationQueue) *LibraryMigrationHandlers {
+	return &LibraryMigrationHandlers{queue: queue}
+}
+
+// LibraryMigrationRequest names a source and the user's account on it. The
+// password is only used to sign in once and is never stored.
+type LibraryMigrationRequest struct {
+	Source   string `json:"source"`
+	Username string `json:"username"`
+	Password string `json:"password"`
+}
+
+type MigrationSourceListResponse struct {
+	Sources []libraryimport.SourceInfo `json:"sources"`
+}
+
+// ListSources handles GET /api/v1/imports/migrations/sources.
+func (h *LibraryMigrationHandlers) ListSources(w http.ResponseWriter, r *http.Request) {
+	writeMaintenanceJSON(w, http.StatusOK, MigrationSourceListResponse{Sources: h.queue.Sources()})
+}
+
+// CreateMigration handles POST /api/v1/imports/migrations.
+func (h *LibraryMigrationHandlers) CreateMigration(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req LibraryMigrationRequest
+	if err := decodeStrictJSON(r, &req); err != nil || req.Source == "" || req.Username == "" {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "source and username are required")
+		return
+	}
+	job, err := h.queue.Enqueue(r.Context(), userCtx.UserID, req.Source, req.Username, req.Password)
+	switch {
+	case errors.Is(err, libraryimport.ErrUnknownSource):
+		writeMaintenanceError(w, http.StatusNotFound, "SOURCE_NOT_FOUND", "no migration source named "+req.Source)
+		return
+	case errors.Is(err, libraryimport.ErrSignInFailed):
+		writeMaintenanceError(w, http.StatusForbidden, "SOURCE_SIGN_IN_FAILED", "the source rejected this username and password")
+		return
+	case errors.Is(err, libraryimport.ErrSourceUnavailable):
+		writeMaintenanceError(w, http.StatusBadGateway, "SOURCE_UNAVAILABLE", "could not reach "+req.Source+" to sign in")
+		return
+	case errors.Is(err, jobs.ErrDuplicate):
+		writeMaintenanceError(w, http.StatusConflict, "JOB_ACTIVE", "a migration from this source is already queued or running")
+		return
+	case err != nil:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue library migration")
+		return
+	}
+	w.Header().Set("Location", middleware.Path(r, "/api/v1/jobs/"+job.ID.String()))
+	writeMaintenanceJSON(w, http.StatusAccepted, jobResponse(job.Summary()))
+}
@@ -0,0 +1,82 @@
+package api
+
+import (
+	"context"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryimport"
+)
+
+type fakeMigrationQueue struct {
+	accounts []string
+}
+
+func (f *fakeMigrationQueue) Sources() []libraryimport.SourceInfo {
+	return []libraryimport.SourceInfo{{Name: "home", Protocol: "navidrome"}}
+}
+
+func (f *fakeMigrationQueue) Enqueue(ctx context.Context, userID uuid.UUID, source, username, password string) (*jobs.Job, error) {
+	switch {
+	case source != "home":
+		return nil, fmt.Errorf("%w: %q", libraryimport.ErrUnknownSource, source)
+	case password == "offline":
+		return nil, fmt.Errorf("%w: connection refused", libraryimport.ErrSourceUnavailable)
+	case password != "secret":
+		return nil, libraryimport.ErrSignInFailed
+	case len(f.accounts) > 0:
+		return nil, jobs.ErrDuplicate
+	}
+	f.accounts = append(f.accounts, username)
+	return &jobs.Job{ID: uuid.New(), Kind: "library_migration", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued, MaxAttempts: 3}, nil
+}
+
+func TestLibraryMigrationsQueueJobs(t *testing.T) {
+	queue := &fakeMigrationQueue{}
+	h := NewLibraryMigrationHandlers(queue)
+	userID := uuid.New()
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/imports/migrations/sources", nil)
+	rec := httptest.NewRecorder()
+	h.ListSources(rec, withUser(req, userID))
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `{"name":"home","protocol":"navidrome"}`) {
+		t.Fatalf("sources = %d %s", rec.Code, rec.Body.String())
+	}
+
+	create := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/imports/migrations", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.CreateMigration(rec, withUser(req, userID))
+		return rec
+	}
+	rec = create(`{"source":"home","username":"alice","password":"secret"}`)
+	if rec.Code != http.StatusAccepted || !strings.Contains(rec.Body.String(), `"kind":"library_migration"`) || !strings.HasPrefix(rec.Header().Get("Location"), "/api/v1/jobs/") {
+		t.Fatalf("create = %d %s", rec.Code, rec.Body.String())
+	}
+	if len(queue.accounts) != 1 || queue.accounts[0] != "alice" {
+		t.Fatalf("accounts = %v", queue.accounts)
+	}
+
+	tests := []struct {
+		body string
+		want int
+	}{
+		{`{"source":"home","username":"alice","password":"secret"}`, http.StatusConflict},
+		{`{"source":"home","username":"alice","password":"wrong"}`, http.StatusForbidden},
+		{`{"source":"home","username":"alice","password":"offline"}`, http.StatusBadGateway},
+		{`{"source":"work","username":"alice","password":"secret"}`, http.StatusNotFound},
+		{`{"source":"home"}`, http.StatusBadRequest},
+		{`{"source":"home","username":"alice","token":"x"}`, http.StatusBadRequest},
+	}
+	for _, tt := range tests {
+		if rec := create(tt.body); rec.Code != tt.want {
+			t.Errorf("%s: status = %d, want %d", tt.body, rec.Code, tt.want)
+		}
+	}
+}
@@ -10,6 +10,7 @@ import (
 	"github.com/google/uuid"
 
 	"github.com/openmusicplayer/backend/internal/auth"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 // authedLibraryRequest builds a GET /api/v1/library request carrying a valid
@@ -23,37 +24,73 @@ func authedLibraryRequest(rawQuery string) *http.Request {
 	return req.WithContext(ctx)
 }
 
-// TestGetLibraryRejectsUnknownSort confirms an unrecognized sort value is a 400
-// with INVALID_SORT before any repository access (nil repo is never touched).
+// TestGetLibraryRejectsUnknownSort confirms an unrecognized sort value is a 422
+// problem naming the sort field before any repository access (nil repo is
+// never touched).
 func TestGetLibraryRejectsUnknownSort(t *testing.T) {
 	h := NewLibraryHandlers(nil, nil)
 
 	rec := httptest.NewRecorder()
 	h.GetLibrary(rec, authedLibraryRequest("sort=bogus"))
 
-	if rec.Code != http.StatusBadRequest {
-		t.Fatalf("status = %d; want %d", rec.Code, http.StatusBadRequest)
+	if rec.Code != http.StatusUnprocessableEntity {
+		t.Fatalf("status = %d; want %d", rec.Code, http.StatusUnprocessableEntity)
 	}
-	var body LibraryErrorResponse
+	if ct := rec.Header().Get("Content-Type"); ct != apperrors.ProblemContentType {
+		t.Fatalf("Content-Type = %q; want problem+json", ct)
+	}
+	var body apperrors.Problem
 	if err := json.Unmarshal(rec.Body.Bytes(), &body); err != nil {
 		t.Fatalf("decode error body: %v", err)
 	}
-	if body.Code != "INVALID_SORT" {
-		t.Fatalf("code = %q; want INVALID_SORT", body.Code)
+	if body.Code != apperrors.CodeValidationError || len(body.Errors) != 1 || body.Errors[0].Field != "sort" {
+		t.Fatalf("problem = %+v; want one sort field error", body)
+	}
+}
+
+// TestGetLibraryReportsEveryInvalidParameter confirms garbage in several
+// parameters is reported together instead of silently defaulted.
+func TestGetLibraryReportsEveryInvalidParameter(t *testing.T) {
+	h := NewLibraryHandlers(nil, nil)
+
+	rec := httptest.NewRecorder()
+	h.GetLibrary(rec, authedLibraryRequest("limit=abc&offset=-1&order=sideways&liked=maybe"))
+
+	var body apperrors.Problem
+	if err := json.Unmarshal(rec.Body.Bytes(), &body); err != nil {
+		t.Fatalf("decode error body: %v", err)
+	}
+	fields := map[string]string{}
+	for _, fieldErr := range body.Errors {
+		fields[fieldErr.Field] = fieldErr.Code
+	}
+	want := map[string]string{
+		"limit":  apperrors.FieldInvalidInteger,
+		"offset": apperrors.FieldOutOfRange,
+		"order":  apperrors.FieldInvalidEnum,
+		"liked":  apperrors.FieldInvalidBoolean,
+	}
+	if rec.Code != http.StatusUnprocessableEntity || len(fields) != len(want) {
+		t.Fatalf("status %d errors %+v; want 422 with %v", rec.Code, body.Errors, want)
+	}
+	for field, code := range want {
+		if fields[field] != code {
+			t.Errorf("%s error code = %q; want %q", field, fields[field], code)
+		}
 	}
 }
 
 // TestGetLibraryAcceptsDurationSort confirms sort=duration passes validation. A
 // valid sort proceeds to the repository call; with a nil repo that panics, so a
-// recovered panic proves validation accepted the value (no 400 was written).
+// recovered panic proves validation accepted the value (no 422 was written).
 func TestGetLibraryAcceptsDurationSort(t *testing.T) {
 	h := NewLibraryHandlers(nil, nil)
 	rec := httptest.NewRecorder()
 
 	defer func() {
 		_ = recover() // expected: nil libraryRepo dereference after validation passes
-		if rec.Code == http.StatusBadRequest {
-			t.Fatalf("sort=duration was rejected with 400; want accepted")
+		if rec.Code == http.StatusUnprocessableEntity {
+			t.Fatalf("sort=duration was rejected with 422; want accepted")
 		}
 	}()
 
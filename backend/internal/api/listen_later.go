@@ -0,0 +1,247 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+type listenLaterStore interface {
+	AddTrack(ctx context.Context, userID uuid.UUID, track *db.Track) (*db.ListenLaterItem, error)
+	AddSource(ctx context.Context, userID uuid.UUID, source db.ListenLaterSource) (*db.ListenLaterItem, error)
+	Remove(ctx context.Context, userID uuid.UUID, itemID int64) error
+	Finish(ctx context.Context, userID uuid.UUID, trackID int64) ([]int64, error)
+	List(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.ListenLaterItem, int, error)
+}
+
+// ListenLaterHandlers keep the caller's listen later queue: tracks and
+// discovery results saved with one tap to hear soon, without filing them in a
+// playlist. Clients report tracks played to the end, which leave the queue.
+type ListenLaterHandlers struct {
+	items  listenLaterStore
+	tracks playEventTrackRepository
+}
+
+func NewListenLaterHandlers(items listenLaterStore, tracks playEventTrackRepository) *ListenLaterHandlers {
+	return &ListenLaterHandlers{items: items, tracks: tracks}
+}
+
+// ListenLaterSource is a discovery result, as search returns it, saved before
+// it is downloaded.
+type ListenLaterSource struct {
+	Provider     string `json:"provider"`
+	SourceURL    string `json:"sourceUrl"`
+	Title        string `json:"title"`
+	Artist       string `json:"artist,omitempty"`
+	DurationMs   int    `json:"durationMs,omitempty"`
+	ThumbnailURL string `json:"thumbnailUrl,omitempty"`
+}
+
+// AddListenLaterRequest saves either a track or a discovery result.
+type AddListenLaterRequest struct {
+	TrackID int64              `json:"trackId,omitempty"`
+	Source  *ListenLaterSource `json:"source,omitempty"`
+}
+
+// ListenLaterItemResponse is one queued entry; exactly one of Track and
+// Source is set.
+type ListenLaterItemResponse struct {
+	ID      int64                   `json:"id"`
+	Track   *PlayEventTrackResponse `json:"track,omitempty"`
+	Source  *ListenLaterSource      `json:"source,omitempty"`
+	AddedAt time.Time               `json:"addedAt"`
+}
+
+// FinishListenLaterRequest names a track the caller played to the end.
+type FinishListenLaterRequest struct {
+	TrackID int64 `json:"trackId"`
+}
+
+// FinishListenLaterResponse lists the entries a finished track took off the
+// queue: the track's own, and the discovery result it was downloaded from.
+type FinishListenLaterResponse struct {
+	ItemIDs []int64 `json:"itemIds"`
+}
+
+type ListenLaterListResponse struct {
+	Items  []ListenLaterItemResponse `json:"items"`
+	Total  int                       `json:"total"`
+	Limit  int                       `json:"limit"`
+	Offset int                       `json:"offset"`
+}
+
+// ListItems handles GET /api/v1/me/listen-later.
+func (h *ListenLaterHandlers) ListItems(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	limit, offset := query.Limit(50), query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
+	items, total, err := h.items.List(r.Context(), userCtx.UserID, limit, offset)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list listen later queue")
+		return
+	}
+	resp := ListenLaterListResponse{Items: make([]ListenLaterItemResponse, 0, len(items)), Total: total, Limit: limit, Offset: offset}
+	for _, item := range items {
+		resp.Items = append(resp.Items, newListenLaterItemResponse(item))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// AddItem handles POST /api/v1/me/listen-later. Saving an entry that is
+// already queued returns it in its current place.
+func (h *ListenLaterHandlers) AddItem(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req AddListenLaterRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if (req.TrackID != 0) == (req.Source != nil) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "exactly one of trackId and source is required")
+		return
+	}
+
+	var item *db.ListenLaterItem
+	if req.Source != nil {
+		source, msg := validateListenLaterSource(*req.Source)
+		if msg != "" {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", msg)
+			return
+		}
+		var err error
+		if item, err = h.items.AddSource(r.Context(), userCtx.UserID, source); err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to save to listen later")
+			return
+		}
+	} else {
+		if req.TrackID < 0 {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid track ID")
+			return
+		}
+		track, err := h.tracks.GetByIDForUser(r.Context(), userCtx.UserID, req.TrackID)
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		if err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify track")
+			return
+		}
+		if item, err = h.items.AddTrack(r.Context(), userCtx.UserID, track); err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to save to listen later")
+			return
+		}
+	}
+	writeLibraryJSON(w, http.StatusCreated, newListenLaterItemResponse(*item))
+}
+
+// RemoveItem handles DELETE /api/v1/me/listen-later/{item_id}.
+func (h *ListenLaterHandlers) RemoveItem(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	itemID, err := strconv.ParseInt(r.PathValue("item_id"), 10, 64)
+	if err != nil || itemID <= 0 {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid item ID")
+		return
+	}
+	err = h.items.Remove(r.Context(), userCtx.UserID, itemID)
+	if errors.Is(err, db.ErrListenLaterItemNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "ITEM_NOT_FOUND", "listen later item not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to remove from listen later")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// FinishTrack handles POST /api/v1/me/listen-later/finished. Clients call it
+// when a track plays to the end; it is not an error when nothing was queued.
+func (h *ListenLaterHandlers) FinishTrack(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req FinishListenLaterRequest
+	if err := decodeStrictJSON(r, &req); err != nil || req.TrackID <= 0 {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackId is required")
+		return
+	}
+	removed, err := h.items.Finish(r.Context(), userCtx.UserID, req.TrackID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to update listen later queue")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, FinishListenLaterResponse{ItemIDs: removed})
+}
+
+// validateListenLaterSource trims a saved discovery result and returns a
+// message describing the problem, or "" when it is acceptable.
+func validateListenLaterSource(req ListenLaterSource) (db.ListenLaterSource, string) {
+	source := db.ListenLaterSource{
+		Provider:     strings.TrimSpace(req.Provider),
+		SourceURL:    strings.TrimSpace(req.SourceURL),
+		Title:        strings.TrimSpace(req.Title),
+		Artist:       strings.TrimSpace(req.Artist),
+		DurationMs:   req.DurationMs,
+		ThumbnailURL: strings.TrimSpace(req.ThumbnailURL),
+	}
+	switch {
+	case source.Provider == "" || len(source.Provider) > 50:
+		return source, "source.provider is required and must be at most 50 characters"
+	case source.Title == "" || utf8.RuneCountInString(source.Title) > 500 || utf8.RuneCountInString(source.Artist) > 500:
+		return source, "source.title is required, and title and artist must be at most 500 characters"
+	case source.DurationMs < 0:
+		return source, "source.durationMs must not be negative"
+	case len(source.SourceURL) > 4096 || download.ValidateUserFacingURL(source.SourceURL) != nil:
+		return source, "source.sourceUrl must be an http or https URL"
+	case source.ThumbnailURL != "" && (len(source.ThumbnailURL) > 4096 || download.ValidateUserFacingURL(source.ThumbnailURL) != nil):
+		return source, "source.thumbnailUrl must be an http or https URL"
+	}
+	return source, ""
+}
+
+func newListenLaterItemResponse(item db.ListenLaterItem) ListenLaterItemResponse {
+	resp := ListenLaterItemResponse{ID: item.ID, AddedAt: item.AddedAt}
+	if item.Track != nil {
+		track := trackToPlayEventResponse(*item.Track)
+		resp.Track = &track
+	}
+	if item.Source != nil {
+		resp.Source = &ListenLaterSource{
+			Provider:     item.Source.Provider,
+			SourceURL:    item.Source.SourceURL,
+			Title:        item.Source.Title,
+			Artist:       item.Source.Artist,
+			DurationMs:   item.Source.DurationMs,
+			ThumbnailURL: item.Source.ThumbnailURL,
+		}
+	}
+	return resp
+}
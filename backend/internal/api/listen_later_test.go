@@ -0,0 +1,157 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeListenLater struct {
+	items []db.ListenLaterItem
+}
+
+func (f *fakeListenLater) AddTrack(_ context.Context, _ uuid.UUID, track *db.Track) (*db.ListenLaterItem, error) {
+	f.items = append(f.items, db.ListenLaterItem{ID: int64(len(f.items) + 1), Track: track, AddedAt: time.Now()})
+	return &f.items[len(f.items)-1], nil
+}
+
+func (f *fakeListenLater) AddSource(_ context.Context, _ uuid.UUID, source db.ListenLaterSource) (*db.ListenLaterItem, error) {
+	f.items = append(f.items, db.ListenLaterItem{ID: int64(len(f.items) + 1), Source: &source, AddedAt: time.Now()})
+	return &f.items[len(f.items)-1], nil
+}
+
+func (f *fakeListenLater) Remove(_ context.Context, _ uuid.UUID, itemID int64) error {
+	for i, item := range f.items {
+		if item.ID == itemID {
+			f.items = append(f.items[:i], f.items[i+1:]...)
+			return nil
+		}
+	}
+	return db.ErrListenLaterItemNotFound
+}
+
+func (f *fakeListenLater) Finish(_ context.Context, _ uuid.UUID, trackID int64) ([]int64, error) {
+	removed := []int64{}
+	kept := f.items[:0]
+	for _, item := range f.items {
+		if item.Track != nil && item.Track.ID == trackID {
+			removed = append(removed, item.ID)
+			continue
+		}
+		kept = append(kept, item)
+	}
+	f.items = kept
+	return removed, nil
+}
+
+func (f *fakeListenLater) List(_ context.Context, _ uuid.UUID, limit, offset int) ([]db.ListenLaterItem, int, error) {
+	return f.items, len(f.items), nil
+}
+
+func addListenLater(h *ListenLaterHandlers, body string) (*httptest.ResponseRecorder, ListenLaterItemResponse) {
+	req := httptest.NewRequest(http.MethodPost, "/api/v1/me/listen-later", strings.NewReader(body))
+	rec := httptest.NewRecorder()
+	h.AddItem(rec, withUser(req, uuid.New()))
+	var resp ListenLaterItemResponse
+	_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+	return rec, resp
+}
+
+func TestListenLaterAddsTracksAndDiscoveryResults(t *testing.T) {
+	items := &fakeListenLater{}
+	h := NewListenLaterHandlers(items, &fakePlayTrackRepo{tracks: map[int64]*db.Track{1: newTrack(1, "Alpha")}})
+
+	rec, resp := addListenLater(h, `{"trackId":1}`)
+	if rec.Code != http.StatusCreated || resp.Track == nil || resp.Track.Title != "Alpha" || resp.Source != nil {
+		t.Fatalf("add track = %d %s", rec.Code, rec.Body.String())
+	}
+	rec, resp = addListenLater(h, `{"source":{"provider":"youtube","sourceUrl":" https://example.test/watch?v=1 ","title":"Live at the Roxy","durationMs":180000}}`)
+	if rec.Code != http.StatusCreated || resp.Source == nil || resp.Source.SourceURL != "https://example.test/watch?v=1" || resp.Track != nil {
+		t.Fatalf("add source = %d %s", rec.Code, rec.Body.String())
+	}
+
+	for _, tc := range []struct {
+		body string
+		want int
+	}{
+		{`{"trackId":2}`, http.StatusNotFound},
+		{`{}`, http.StatusBadRequest},
+		{`{"trackId":1,"source":{"provider":"youtube"}}`, http.StatusBadRequest},
+		{`{"source":{"provider":"youtube","sourceUrl":"file:///etc/passwd","title":"x"}}`, http.StatusBadRequest},
+		{`{"source":{"provider":"youtube","sourceUrl":"https://example.test/2"}}`, http.StatusBadRequest},
+	} {
+		if rec, _ := addListenLater(h, tc.body); rec.Code != tc.want {
+			t.Errorf("add %s = %d, want %d", tc.body, rec.Code, tc.want)
+		}
+	}
+	if len(items.items) != 2 {
+		t.Fatalf("saved %d items, want 2", len(items.items))
+	}
+}
+
+func TestListenLaterListsAndRemovesItems(t *testing.T) {
+	items := &fakeListenLater{items: []db.ListenLaterItem{
+		{ID: 1, Track: newTrack(1, "Alpha")},
+		{ID: 2, Source: &db.ListenLaterSource{Provider: "youtube", SourceURL: "https://example.test/1", Title: "Bravo"}},
+	}}
+	h := NewListenLaterHandlers(items, &fakePlayTrackRepo{})
+	userID := uuid.New()
+
+	rec := httptest.NewRecorder()
+	h.ListItems(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/listen-later", nil), userID))
+	var list ListenLaterListResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &list); err != nil || rec.Code != http.StatusOK {
+		t.Fatalf("list = %d %s", rec.Code, rec.Body.String())
+	}
+	if list.Total != 2 || list.Items[0].Track.ID != 1 || list.Items[1].Source.Title != "Bravo" {
+		t.Fatalf("list = %#v", list)
+	}
+
+	remove := func(id string) int {
+		req := httptest.NewRequest(http.MethodDelete, "/api/v1/me/listen-later/"+id, nil)
+		req.SetPathValue("item_id", id)
+		rec := httptest.NewRecorder()
+		h.RemoveItem(rec, withUser(req, userID))
+		return rec.Code
+	}
+	if code := remove("1"); code != http.StatusNoContent || len(items.items) != 1 {
+		t.Fatalf("remove = %d, %d items left", code, len(items.items))
+	}
+	if code := remove("1"); code != http.StatusNotFound {
+		t.Fatalf("second remove = %d, want 404", code)
+	}
+	if code := remove("abc"); code != http.StatusBadRequest {
+		t.Fatalf("remove of a bad ID = %d, want 400", code)
+	}
+}
+
+func TestListenLaterFinishedTracksLeaveTheQueue(t *testing.T) {
+	items := &fakeListenLater{items: []db.ListenLaterItem{{ID: 3, Track: newTrack(1, "Alpha")}}}
+	h := NewListenLaterHandlers(items, &fakePlayTrackRepo{})
+
+	finish := func(body string) (int, FinishListenLaterResponse) {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/me/listen-later/finished", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.FinishTrack(rec, withUser(req, uuid.New()))
+		var resp FinishListenLaterResponse
+		_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+		return rec.Code, resp
+	}
+	if code, resp := finish(`{"trackId":1}`); code != http.StatusOK || len(resp.ItemIDs) != 1 || resp.ItemIDs[0] != 3 || len(items.items) != 0 {
+		t.Fatalf("finish = %d %#v, %d items left", code, resp, len(items.items))
+	}
+	if code, resp := finish(`{"trackId":1}`); code != http.StatusOK || len(resp.ItemIDs) != 0 {
+		t.Fatalf("finish of an unqueued track = %d %#v, want an empty 200", code, resp)
+	}
+	if code, _ := finish(`{}`); code != http.StatusBadRequest {
+		t.Fatalf("finish without a track = %d, want 400", code)
+	}
+}
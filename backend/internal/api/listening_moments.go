@@ -0,0 +1,32 @@
+package api
+
+import (
+	"context"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/listening"
+)
+
+// listeningHoursStore counts a user's plays by hour of the week.
+// db.PlayEventRepository satisfies this interface.
+type listeningHoursStore interface {
+	ListeningHours(ctx context.Context, userID uuid.UUID, since time.Time, zone string) ([]db.HourlyArtistPlays, error)
+}
+
+// momentProfile profiles what the user tends to play at the moment now falls
+// in, read in now's location. It reports false when there is no store, the
+// user has not turned on context suggestions, or their history is too thin.
+func momentProfile(ctx context.Context, hours listeningHoursStore, settings db.UserSettings, userID uuid.UUID, now time.Time) (listening.Profile, bool, error) {
+	if hours == nil || !settings.ContextSuggestions {
+		return listening.Profile{}, false, nil
+	}
+	plays, err := hours.ListeningHours(ctx, userID, now.Add(-listening.HistoryWindow), now.Location().String())
+	if err != nil {
+		return listening.Profile{}, false, err
+	}
+	profile, ok := listening.ProfileOf(plays, listening.MomentAt(now))
+	return profile, ok, nil
+}
@@ -0,0 +1,43 @@
+package api
+
+import (
+	"context"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/listening"
+)
+
+type fakeListeningHours struct {
+	plays []db.HourlyArtistPlays
+	zone  string
+}
+
+func (f *fakeListeningHours) ListeningHours(_ context.Context, _ uuid.UUID, _ time.Time, zone string) ([]db.HourlyArtistPlays, error) {
+	f.zone = zone
+	return f.plays, nil
+}
+
+// hoursFavoringNow spreads an everyday artist's plays over every hour of the
+// week, and adds plays of favored only in the moment it is now in loc.
+func hoursFavoringNow(loc *time.Location, everyday, favored uuid.UUID) *fakeListeningHours {
+	now := listening.MomentAt(time.Now().In(loc))
+	hours := &fakeListeningHours{}
+	for day := 0; day < 7; day++ {
+		for hour := 0; hour < 24; hour++ {
+			// 1 March 2026 was a Sunday.
+			at := time.Date(2026, 3, 1+day, hour, 0, 0, 0, loc)
+			hours.plays = append(hours.plays, db.HourlyArtistPlays{
+				Weekday: at.Weekday(), Hour: hour, ArtistID: uuid.NullUUID{UUID: everyday, Valid: true}, Artist: "Everyday", Plays: 2,
+			})
+			if listening.MomentAt(at) == now {
+				hours.plays = append(hours.plays, db.HourlyArtistPlays{
+					Weekday: at.Weekday(), Hour: hour, ArtistID: uuid.NullUUID{UUID: favored, Valid: true}, Artist: "Favored", Plays: 3,
+				})
+			}
+		}
+	}
+	return hours
+}
@@ -0,0 +1,61 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type lyricsStore interface {
+	GetForTrack(ctx context.Context, trackID int64) (*db.Lyrics, error)
+}
+
+// LyricsHandlers serves lyrics imported alongside library tracks.
+type LyricsHandlers struct {
+	lyrics      lyricsStore
+	libraryRepo playbackLibraryRepository
+}
+
+func NewLyricsHandlers(lyrics lyricsStore, libraryRepo playbackLibraryRepository) *LyricsHandlers {
+	return &LyricsHandlers{lyrics: lyrics, libraryRepo: libraryRepo}
+}
+
+type LyricsResponse struct {
+	TrackID   int64     `json:"trackId"`
+	Lyrics    string    `json:"lyrics"`
+	Synced    bool      `json:"synced"`
+	Source    string    `json:"source"`
+	UpdatedAt time.Time `json:"updatedAt"`
+}
+
+// GetTrackLyrics handles GET /api/v1/tracks/{track_id}/lyrics. Synced lyrics
+// are returned as LRC text with their timestamps.
+func (h *LyricsHandlers) GetTrackLyrics(w http.ResponseWriter, r *http.Request) {
+	if h == nil || h.lyrics == nil || h.libraryRepo == nil {
+		writeLibraryError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "lyrics are unavailable")
+		return
+	}
+	_, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	lyrics, err := h.lyrics.GetForTrack(r.Context(), trackID)
+	if err != nil {
+		if errors.Is(err, db.ErrLyricsNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "LYRICS_NOT_FOUND", "track has no lyrics")
+			return
+		}
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load lyrics")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, LyricsResponse{
+		TrackID:   trackID,
+		Lyrics:    lyrics.Text,
+		Synced:    lyrics.Synced,
+		Source:    lyrics.Source,
+		UpdatedAt: lyrics.UpdatedAt,
+	})
+}
@@ -0,0 +1,60 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeLyricsStore struct {
+	byTrack map[int64]*db.Lyrics
+}
+
+func (f *fakeLyricsStore) GetForTrack(ctx context.Context, trackID int64) (*db.Lyrics, error) {
+	lyrics, ok := f.byTrack[trackID]
+	if !ok {
+		return nil, db.ErrLyricsNotFound
+	}
+	return lyrics, nil
+}
+
+func lyricsRequest(h *LyricsHandlers, trackID string) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/tracks/"+trackID+"/lyrics", nil)
+	req.SetPathValue("track_id", trackID)
+	rec := httptest.NewRecorder()
+	h.GetTrackLyrics(rec, withUser(req, uuid.New()))
+	return rec
+}
+
+func TestGetTrackLyrics(t *testing.T) {
+	h := NewLyricsHandlers(
+		&fakeLyricsStore{byTrack: map[int64]*db.Lyrics{
+			7: {TrackID: 7, Text: "[00:01.00]Hello", Synced: true, Source: db.LyricsSourceLRC},
+		}},
+		&fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true, 8: true}},
+	)
+
+	rec := lyricsRequest(h, "7")
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp LyricsResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.TrackID != 7 || resp.Lyrics != "[00:01.00]Hello" || !resp.Synced || resp.Source != "lrc" {
+		t.Fatalf("lyrics = %#v", resp)
+	}
+
+	for trackID, want := range map[string]int{"8": http.StatusNotFound, "9": http.StatusNotFound, "x": http.StatusBadRequest} {
+		if rec := lyricsRequest(h, trackID); rec.Code != want {
+			t.Fatalf("track %s status = %d, want %d", trackID, rec.Code, want)
+		}
+	}
+}
@@ -10,11 +10,12 @@ import (
 	"time"
 
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/processor"
 )
 
 type maintenanceTrackStore interface {
-	GetByID(ctx context.Context, id int64) (*db.Track, error)
+	GetByIDs(ctx context.Context, ids []int64) (map[int64]*db.Track, error)
 	GetMaintenanceCandidates(ctx context.Context, includeMetadata, includeAnalysis bool, staleAfter time.Duration, limit int) ([]db.Track, error)
 	GetAudioQualityMaintenanceCandidates(ctx context.Context, limit int) ([]db.Track, error)
 }
@@ -187,6 +188,374 @@ func (h *MaintenanceHandlers) RepairTracks(w http.ResponseWriter, r *http.Reques
 	writeMaintenanceJSON(w, http.StatusOK, resp)
 }
 
+// maintenanceReverifyStore and metadataReverifier are the optional
+// capabilities behind MusicBrainz re-verification.
+type maintenanceReverifyStore interface {
+	GetMBReverifyCandidates(ctx context.Context, staleAfter time.Duration, limit int) ([]db.Track, error)
+}
+
+type metadataReverifier interface {
+	ReverifyMetadata(ctx context.Context, track *db.Track) (processor.MetadataReverifyResult, error)
+}
+
+type maintenanceReverifyRequest struct {
+	TrackIDs       []int64 `json:"trackIds"`
+	StaleAfterDays int     `json:"staleAfterDays"`
+	Limit          int     `json:"limit"`
+}
+
+type maintenanceReverifyResponse struct {
+	Tracks   []maintenanceTrackReverifyResult `json:"tracks"`
+	Summary  maintenanceReverifySummary       `json:"summary"`
+	Criteria maintenanceReverifyCriteria      `json:"criteria"`
+}
+
+type maintenanceTrackReverifyResult struct {
+	Title string `json:"title"`
+	processor.MetadataReverifyResult
+}
+
+type maintenanceReverifySummary struct {
+	Selected  int `json:"selected"`
+	Updated   int `json:"updated"`
+	Unchanged int `json:"unchanged"`
+	Skipped   int `json:"skipped"`
+	Errors    int `json:"errors"`
+}
+
+type maintenanceReverifyCriteria struct {
+	StaleAfterDays int     `json:"staleAfterDays"`
+	Limit          int     `json:"limit"`
+	TrackIDs       []int64 `json:"trackIds,omitempty"`
+}
+
+// ReverifyTracks handles POST /api/v1/maintenance/reverify. It re-checks
+// MusicBrainz-linked tracks not verified within staleAfterDays (default 90),
+// or the listed trackIds, and reports every field that changed.
+func (h *MaintenanceHandlers) ReverifyTracks(w http.ResponseWriter, r *http.Request) {
+	var (
+		store      maintenanceReverifyStore
+		reverifier metadataReverifier
+		ok         bool
+	)
+	if h != nil && h.tracks != nil && h.processor != nil {
+		store, ok = h.tracks.(maintenanceReverifyStore)
+		if ok {
+			reverifier, ok = h.processor.(metadataReverifier)
+		}
+	}
+	if !ok {
+		writeMaintenanceError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "metadata re-verification is unavailable")
+		return
+	}
+	var req maintenanceReverifyRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid reverify request JSON")
+		return
+	}
+	limit := req.Limit
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 200 {
+		limit = 200
+	}
+	staleDays := req.StaleAfterDays
+	if staleDays <= 0 {
+		staleDays = 90
+	}
+
+	var tracks []db.Track
+	var err error
+	if len(req.TrackIDs) == 0 {
+		tracks, err = store.GetMBReverifyCandidates(r.Context(), time.Duration(staleDays)*24*time.Hour, limit)
+	} else {
+		tracks, err = h.selectRepairTracks(r.Context(), req.TrackIDs, true, false, false, 0, limit)
+	}
+	if err != nil {
+		if errors.Is(err, errInvalidMaintenanceRequest) {
+			writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+			return
+		}
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeMaintenanceError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to select tracks to re-verify")
+		return
+	}
+
+	resp := maintenanceReverifyResponse{
+		Tracks: make([]maintenanceTrackReverifyResult, 0, len(tracks)),
+		Criteria: maintenanceReverifyCriteria{
+			StaleAfterDays: staleDays,
+			Limit:          limit,
+			TrackIDs:       req.TrackIDs,
+		},
+	}
+	resp.Summary.Selected = len(tracks)
+	for i := range tracks {
+		track := tracks[i]
+		result, err := reverifier.ReverifyMetadata(r.Context(), &track)
+		if err != nil {
+			log.Printf("Warning: MusicBrainz re-verification failed for track %d: %v", track.ID, err)
+			resp.Summary.Errors++
+		} else {
+			switch result.Status {
+			case "updated":
+				resp.Summary.Updated++
+			case "unchanged":
+				resp.Summary.Unchanged++
+			default:
+				resp.Summary.Skipped++
+			}
+		}
+		resp.Tracks = append(resp.Tracks, maintenanceTrackReverifyResult{Title: track.Title, MetadataReverifyResult: result})
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
+// maintenanceQualityStore and qualityScorer are the optional capabilities
+// behind quality scoring.
+type maintenanceQualityStore interface {
+	GetQualityScoreCandidates(ctx context.Context, limit int) ([]db.Track, error)
+}
+
+type qualityScorer interface {
+	ScoreQuality(ctx context.Context, track *db.Track) (*db.TrackQuality, error)
+}
+
+type maintenanceQualityScoreRequest struct {
+	TrackIDs []int64 `json:"trackIds"`
+	Limit    int     `json:"limit"`
+}
+
+type maintenanceQualityScoreResponse struct {
+	Tracks  []maintenanceTrackQualityScore `json:"tracks"`
+	Summary maintenanceQualityScoreSummary `json:"summary"`
+}
+
+type maintenanceTrackQualityScore struct {
+	TrackID          int64  `json:"trackId"`
+	Title            string `json:"title"`
+	Score            *int   `json:"score,omitempty"`
+	DRScore          *int32 `json:"drScore,omitempty"`
+	SpectralCutoffHz *int32 `json:"spectralCutoffHz,omitempty"`
+	UpscaleSuspected bool   `json:"upscaleSuspected"`
+	Error            string `json:"error,omitempty"`
+}
+
+type maintenanceQualityScoreSummary struct {
+	Selected         int `json:"selected"`
+	Scored           int `json:"scored"`
+	UpscaleSuspected int `json:"upscaleSuspected"`
+	Errors           int `json:"errors"`
+}
+
+// ScoreTrackQuality handles POST /api/v1/maintenance/quality-scores. It
+// measures the dynamic range and spectrum of stored tracks not scored yet,
+// or of the listed trackIds, and records their quality scores.
+func (h *MaintenanceHandlers) ScoreTrackQuality(w http.ResponseWriter, r *http.Request) {
+	var (
+		store  maintenanceQualityStore
+		scorer qualityScorer
+		ok     bool
+	)
+	if h != nil && h.tracks != nil && h.processor != nil {
+		store, ok = h.tracks.(maintenanceQualityStore)
+		if ok {
+			scorer, ok = h.processor.(qualityScorer)
+		}
+	}
+	if !ok {
+		writeMaintenanceError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "quality scoring is unavailable")
+		return
+	}
+	var req maintenanceQualityScoreRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid quality score request JSON")
+		return
+	}
+	limit := req.Limit
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 200 {
+		limit = 200
+	}
+
+	var tracks []db.Track
+	var err error
+	if len(req.TrackIDs) == 0 {
+		tracks, err = store.GetQualityScoreCandidates(r.Context(), limit)
+	} else {
+		tracks, err = h.selectRepairTracks(r.Context(), req.TrackIDs, true, false, false, 0, limit)
+	}
+	if err != nil {
+		if errors.Is(err, errInvalidMaintenanceRequest) {
+			writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+			return
+		}
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeMaintenanceError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to select tracks to score")
+		return
+	}
+
+	resp := maintenanceQualityScoreResponse{Tracks: make([]maintenanceTrackQualityScore, 0, len(tracks))}
+	resp.Summary.Selected = len(tracks)
+	for i := range tracks {
+		track := tracks[i]
+		item := maintenanceTrackQualityScore{TrackID: track.ID, Title: track.Title}
+		quality, err := scorer.ScoreQuality(r.Context(), &track)
+		if errors.Is(err, processor.ErrQualityScoringDisabled) {
+			writeMaintenanceError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "quality scoring is unavailable")
+			return
+		}
+		if err != nil {
+			log.Printf("Warning: quality scoring failed for track %d: %v", track.ID, err)
+			item.Error = err.Error()
+			resp.Summary.Errors++
+		} else {
+			item.Score = &quality.Score
+			if quality.DRScore.Valid {
+				item.DRScore = &quality.DRScore.Int32
+			}
+			if quality.SpectralCutoffHz.Valid {
+				item.SpectralCutoffHz = &quality.SpectralCutoffHz.Int32
+			}
+			item.UpscaleSuspected = quality.UpscaleSuspected
+			resp.Summary.Scored++
+			if quality.UpscaleSuspected {
+				resp.Summary.UpscaleSuspected++
+			}
+		}
+		resp.Tracks = append(resp.Tracks, item)
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
+// maintenanceMoodStore and moodMeasurer are the optional capabilities behind
+// mood analysis.
+type maintenanceMoodStore interface {
+	GetMoodFeatureCandidates(ctx context.Context, limit int) ([]db.Track, error)
+}
+
+type moodMeasurer interface {
+	MeasureMood(ctx context.Context, track *db.Track) (*db.MoodFeatures, error)
+}
+
+type maintenanceMoodFeaturesRequest struct {
+	TrackIDs []int64 `json:"trackIds"`
+	Limit    int     `json:"limit"`
+}
+
+type maintenanceMoodFeaturesResponse struct {
+	Tracks  []maintenanceTrackMoodFeatures `json:"tracks"`
+	Summary maintenanceMoodFeaturesSummary `json:"summary"`
+}
+
+type maintenanceTrackMoodFeatures struct {
+	TrackID      int64    `json:"trackId"`
+	Title        string   `json:"title"`
+	Energy       *float64 `json:"energy,omitempty"`
+	Valence      *float64 `json:"valence,omitempty"`
+	Danceability *float64 `json:"danceability,omitempty"`
+	Moods        []string `json:"moods,omitempty"`
+	Error        string   `json:"error,omitempty"`
+}
+
+type maintenanceMoodFeaturesSummary struct {
+	Selected int `json:"selected"`
+	Measured int `json:"measured"`
+	Unrated  int `json:"unrated"`
+	Errors   int `json:"errors"`
+}
+
+// MeasureTrackMoods handles POST /api/v1/maintenance/mood-features. It
+// measures the energy, valence and danceability of stored tracks not
+// measured yet, or of the listed trackIds. Tracks too quiet or short to rate
+// count as unrated.
+func (h *MaintenanceHandlers) MeasureTrackMoods(w http.ResponseWriter, r *http.Request) {
+	var (
+		store    maintenanceMoodStore
+		measurer moodMeasurer
+		ok       bool
+	)
+	if h != nil && h.tracks != nil && h.processor != nil {
+		store, ok = h.tracks.(maintenanceMoodStore)
+		if ok {
+			measurer, ok = h.processor.(moodMeasurer)
+		}
+	}
+	if !ok {
+		writeMaintenanceError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "mood analysis is unavailable")
+		return
+	}
+	var req maintenanceMoodFeaturesRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid mood features request JSON")
+		return
+	}
+	limit := req.Limit
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 200 {
+		limit = 200
+	}
+
+	var tracks []db.Track
+	var err error
+	if len(req.TrackIDs) == 0 {
+		tracks, err = store.GetMoodFeatureCandidates(r.Context(), limit)
+	} else {
+		tracks, err = h.selectRepairTracks(r.Context(), req.TrackIDs, true, false, false, 0, limit)
+	}
+	if err != nil {
+		if errors.Is(err, errInvalidMaintenanceRequest) {
+			writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+			return
+		}
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeMaintenanceError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to select tracks to measure")
+		return
+	}
+
+	resp := maintenanceMoodFeaturesResponse{Tracks: make([]maintenanceTrackMoodFeatures, 0, len(tracks))}
+	resp.Summary.Selected = len(tracks)
+	for i := range tracks {
+		track := tracks[i]
+		item := maintenanceTrackMoodFeatures{TrackID: track.ID, Title: track.Title}
+		features, err := measurer.MeasureMood(r.Context(), &track)
+		if errors.Is(err, processor.ErrMoodAnalysisDisabled) {
+			writeMaintenanceError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "mood analysis is unavailable")
+			return
+		}
+		switch {
+		case err != nil:
+			log.Printf("Warning: mood analysis failed for track %d: %v", track.ID, err)
+			item.Error = err.Error()
+			resp.Summary.Errors++
+		case features == nil:
+			resp.Summary.Unrated++
+		default:
+			item.Energy = &features.Energy
+			item.Valence = &features.Valence
+			item.Danceability = &features.Danceability
+			item.Moods = features.Moods()
+			resp.Summary.Measured++
+		}
+		resp.Tracks = append(resp.Tracks, item)
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
 var errInvalidMaintenanceRequest = errors.New("invalid maintenance repair request")
 
 func (h *MaintenanceHandlers) selectRepairTracks(ctx context.Context, ids []int64, includeMetadata, includeAnalysis, includeAudioQuality bool, staleAfter time.Duration, limit int) ([]db.Track, error) {
@@ -233,7 +602,7 @@ func (h *MaintenanceHandlers) selectRepairTracks(ctx context.Context, ids []int6
 		return tracks, nil
 	}
 	seen := make(map[int64]struct{}, len(ids))
-	tracks := make([]db.Track, 0, len(ids))
+	wanted := make([]int64, 0, min(len(ids), limit))
 	for _, id := range ids {
 		if id <= 0 {
 			return nil, errInvalidMaintenanceRequest
@@ -242,15 +611,23 @@ func (h *MaintenanceHandlers) selectRepairTracks(ctx context.Context, ids []int6
 			continue
 		}
 		seen[id] = struct{}{}
-		track, err := h.tracks.GetByID(ctx, id)
-		if err != nil {
-			return nil, err
-		}
-		tracks = append(tracks, *track)
-		if len(tracks) >= limit {
+		wanted = append(wanted, id)
+		if len(wanted) >= limit {
 			break
 		}
 	}
+	loaded, err := h.tracks.GetByIDs(ctx, wanted)
+	if err != nil {
+		return nil, err
+	}
+	tracks := make([]db.Track, 0, len(wanted))
+	for _, id := range wanted {
+		track, ok := loaded[id]
+		if !ok {
+			return nil, db.ErrTrackNotFound
+		}
+		tracks = append(tracks, *track)
+	}
 	return tracks, nil
 }
 
@@ -268,5 +645,5 @@ func writeMaintenanceJSON(w http.ResponseWriter, status int, data any) {
 }
 
 func writeMaintenanceError(w http.ResponseWriter, status int, code, message string) {
-	writeMaintenanceJSON(w, status, ErrorResponse{Code: code, Message: message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
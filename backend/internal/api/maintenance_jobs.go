@@ -0,0 +1,73 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/maintenance"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type maintenanceJobQueue interface {
+	Enqueue(ctx context.Context, userID uuid.UUID, action string) (*jobs.Job, error)
+}
+
+// MaintenanceJobHandlers queue the bulk fix-it actions offered by the library
+// health report. The jobs run in the background and report their progress
+// through the jobs API.
+type MaintenanceJobHandlers struct {
+	queue maintenanceJobQueue
+}
+
+func NewMaintenanceJobHandlers(queue maintenanceJobQueue) *MaintenanceJobHandlers {
+	return &MaintenanceJobHandlers{queue: queue}
+}
+
+type MaintenanceJobRequest struct {
+	Action string `json:"action"`
+}
+
+// CreateJob handles POST /api/v1/maintenance/jobs.
+func (h *MaintenanceJobHandlers) CreateJob(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req MaintenanceJobRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if _, ok := maintenance.ActionIssues[req.Action]; !ok {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "action must be one of: fetch_artwork, match_unverified, redownload_corrupt")
+		return
+	}
+	job, err := h.queue.Enqueue(r.Context(), userCtx.UserID, req.Action)
+	if errors.Is(err, jobs.ErrDuplicate) {
+		writeMaintenanceError(w, http.StatusConflict, "JOB_ACTIVE", "this action is already queued or running")
+		return
+	}
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue maintenance job")
+		return
+	}
+	w.Header().Set("Location", middleware.Path(r, "/api/v1/jobs/"+job.ID.String()))
+	writeMaintenanceJSON(w, http.StatusAccepted, jobResponse(job.Summary()))
+}
+
+// maintenanceActionFor returns the bulk fix-it action that repairs issue, if
+// there is one.
+func maintenanceActionFor(issue string) string {
+	for action, actionIssue := range maintenance.ActionIssues {
+		if actionIssue == issue {
+			return action
+		}
+	}
+	return ""
+}
@@ -0,0 +1,59 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+type fakeMaintenanceJobQueue struct {
+	active map[string]bool
+}
+
+func (f *fakeMaintenanceJobQueue) Enqueue(ctx context.Context, userID uuid.UUID, action string) (*jobs.Job, error) {
+	key := userID.String() + ":" + action
+	if f.active[key] {
+		return nil, jobs.ErrDuplicate
+	}
+	f.active[key] = true
+	return &jobs.Job{ID: uuid.New(), Kind: "library_repair", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued, MaxAttempts: 2}, nil
+}
+
+func TestMaintenanceJobsQueueOneJobPerAction(t *testing.T) {
+	h := NewMaintenanceJobHandlers(&fakeMaintenanceJobQueue{active: map[string]bool{}})
+	userID := uuid.New()
+
+	create := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/jobs", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.CreateJob(rec, withUser(req, userID))
+		return rec
+	}
+	rec := create(`{"action":"redownload_corrupt"}`)
+	if rec.Code != http.StatusAccepted {
+		t.Fatalf("status = %d, want 202 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var created JobResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &created); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if created.Kind != "library_repair" || created.Status != jobs.StatusQueued || !created.Cancelable || rec.Header().Get("Location") != "/api/v1/jobs/"+created.ID {
+		t.Fatalf("created = %#v, location %q", created, rec.Header().Get("Location"))
+	}
+	if rec := create(`{"action":"redownload_corrupt"}`); rec.Code != http.StatusConflict {
+		t.Fatalf("duplicate status = %d, want 409", rec.Code)
+	}
+	if rec := create(`{"action":"fetch_artwork"}`); rec.Code != http.StatusAccepted {
+		t.Fatalf("second action status = %d, want 202", rec.Code)
+	}
+	if rec := create(`{"action":"defragment"}`); rec.Code != http.StatusBadRequest {
+		t.Fatalf("unknown action status = %d, want 400", rec.Code)
+	}
+}
@@ -0,0 +1,81 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+type moodTrackStore struct {
+	qualitySelectionStore
+	unmeasured []db.Track
+}
+
+func (s *moodTrackStore) GetMoodFeatureCandidates(_ context.Context, limit int) ([]db.Track, error) {
+	return s.unmeasured[:min(limit, len(s.unmeasured))], nil
+}
+
+type moodProcessor struct {
+	reverifyProcessor
+	features map[int64]*db.MoodFeatures
+	err      error
+}
+
+func (p *moodProcessor) MeasureMood(_ context.Context, track *db.Track) (*db.MoodFeatures, error) {
+	if p.err != nil {
+		return nil, p.err
+	}
+	features, ok := p.features[track.ID]
+	if !ok {
+		return nil, processor.ErrNoStoredAudio
+	}
+	return features, nil
+}
+
+func TestMeasureTrackMoodsReportsMoods(t *testing.T) {
+	store := &moodTrackStore{unmeasured: []db.Track{{ID: 1, Title: "Ballad"}, {ID: 2}, {ID: 3}}}
+	h := NewMaintenanceHandlers(store, &moodProcessor{features: map[int64]*db.MoodFeatures{
+		1: {Energy: 0.2, Valence: 0.3, Danceability: 0.1},
+		2: nil,
+	}})
+
+	rec := httptest.NewRecorder()
+	h.MeasureTrackMoods(rec, httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/mood-features", strings.NewReader(`{}`)))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp maintenanceMoodFeaturesResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Summary != (maintenanceMoodFeaturesSummary{Selected: 3, Measured: 1, Unrated: 1, Errors: 1}) {
+		t.Fatalf("summary = %+v", resp.Summary)
+	}
+	first := resp.Tracks[0]
+	if first.Title != "Ballad" || first.Energy == nil || *first.Energy != 0.2 || strings.Join(first.Moods, ",") != "chill,sad" {
+		t.Fatalf("first track = %+v", first)
+	}
+	if resp.Tracks[1].Energy != nil || resp.Tracks[1].Error != "" || resp.Tracks[2].Error == "" {
+		t.Fatalf("tracks = %+v", resp.Tracks)
+	}
+}
+
+func TestMeasureTrackMoodsUnavailableWhenDisabled(t *testing.T) {
+	store := &moodTrackStore{unmeasured: []db.Track{{ID: 1}}}
+	for _, h := range []*MaintenanceHandlers{
+		NewMaintenanceHandlers(store, &reverifyProcessor{}),
+		NewMaintenanceHandlers(store, &moodProcessor{err: processor.ErrMoodAnalysisDisabled}),
+	} {
+		rec := httptest.NewRecorder()
+		h.MeasureTrackMoods(rec, httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/mood-features", strings.NewReader(`{}`)))
+		if rec.Code != http.StatusServiceUnavailable {
+			t.Fatalf("status = %d, want 503", rec.Code)
+		}
+	}
+}
@@ -0,0 +1,78 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+type qualityScoreTrackStore struct {
+	qualitySelectionStore
+	unscored []db.Track
+}
+
+func (s *qualityScoreTrackStore) GetQualityScoreCandidates(_ context.Context, limit int) ([]db.Track, error) {
+	return s.unscored[:min(limit, len(s.unscored))], nil
+}
+
+type qualityScoreProcessor struct {
+	reverifyProcessor
+	scores map[int64]*db.TrackQuality
+	err    error
+}
+
+func (p *qualityScoreProcessor) ScoreQuality(_ context.Context, track *db.Track) (*db.TrackQuality, error) {
+	if p.err != nil {
+		return nil, p.err
+	}
+	if quality := p.scores[track.ID]; quality != nil {
+		return quality, nil
+	}
+	return nil, processor.ErrNoStoredAudio
+}
+
+func TestScoreTrackQualityReportsUpscales(t *testing.T) {
+	store := &qualityScoreTrackStore{unscored: []db.Track{{ID: 1, Title: "Fake FLAC"}, {ID: 2}, {ID: 3}}}
+	h := NewMaintenanceHandlers(store, &qualityScoreProcessor{scores: map[int64]*db.TrackQuality{
+		1: {TrackID: 1, SpectralCutoffHz: sql.NullInt32{Int32: 16000, Valid: true}, UpscaleSuspected: true, Score: 36},
+		2: {TrackID: 2, DRScore: sql.NullInt32{Int32: 11, Valid: true}, Score: 100},
+	}})
+
+	rec := httptest.NewRecorder()
+	h.ScoreTrackQuality(rec, httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/quality-scores", strings.NewReader(`{}`)))
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp maintenanceQualityScoreResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Summary != (maintenanceQualityScoreSummary{Selected: 3, Scored: 2, UpscaleSuspected: 1, Errors: 1}) {
+		t.Fatalf("summary = %+v", resp.Summary)
+	}
+	first := resp.Tracks[0]
+	if first.Title != "Fake FLAC" || first.Score == nil || *first.Score != 36 || first.SpectralCutoffHz == nil || first.DRScore != nil || resp.Tracks[2].Error == "" {
+		t.Fatalf("tracks = %+v", resp.Tracks)
+	}
+}
+
+func TestScoreTrackQualityUnavailableWhenDisabled(t *testing.T) {
+	store := &qualityScoreTrackStore{unscored: []db.Track{{ID: 1}}}
+	for _, h := range []*MaintenanceHandlers{
+		NewMaintenanceHandlers(store, &reverifyProcessor{}),
+		NewMaintenanceHandlers(store, &qualityScoreProcessor{err: processor.ErrQualityScoringDisabled}),
+	} {
+		rec := httptest.NewRecorder()
+		h.ScoreTrackQuality(rec, httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/quality-scores", strings.NewReader(`{}`)))
+		if rec.Code != http.StatusServiceUnavailable {
+			t.Fatalf("status = %d, want 503", rec.Code)
+		}
+	}
+}
@@ -15,8 +15,8 @@ type qualitySelectionStore struct {
 	otherLimit   int
 }
 
-func (s *qualitySelectionStore) GetByID(context.Context, int64) (*db.Track, error) {
-	return nil, db.ErrTrackNotFound
+func (s *qualitySelectionStore) GetByIDs(context.Context, []int64) (map[int64]*db.Track, error) {
+	return map[int64]*db.Track{}, nil
 }
 
 func (s *qualitySelectionStore) GetAudioQualityMaintenanceCandidates(_ context.Context, limit int) ([]db.Track, error) {
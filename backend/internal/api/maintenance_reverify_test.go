@@ -0,0 +1,88 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+type reverifyTrackStore struct {
+	qualitySelectionStore
+	stale      []db.Track
+	staleAfter time.Duration
+}
+
+func (s *reverifyTrackStore) GetMBReverifyCandidates(_ context.Context, staleAfter time.Duration, limit int) ([]db.Track, error) {
+	s.staleAfter = staleAfter
+	return s.stale[:min(limit, len(s.stale))], nil
+}
+
+type reverifyProcessor struct {
+	results map[int64]processor.MetadataReverifyResult
+}
+
+func (p *reverifyProcessor) RepairMetadata(context.Context, *db.Track, processor.MetadataRepairOptions) (processor.MetadataRepairResult, error) {
+	return processor.MetadataRepairResult{}, nil
+}
+
+func (p *reverifyProcessor) RequestAnalysisRepair(context.Context, *db.Track, processor.AnalysisRepairOptions) (processor.AnalysisRepairResult, error) {
+	return processor.AnalysisRepairResult{}, nil
+}
+
+func (p *reverifyProcessor) RepairAudioQuality(context.Context, *db.Track) (processor.AudioQualityRepairResult, error) {
+	return processor.AudioQualityRepairResult{}, nil
+}
+
+func (p *reverifyProcessor) ReverifyMetadata(_ context.Context, track *db.Track) (processor.MetadataReverifyResult, error) {
+	result := p.results[track.ID]
+	if result.Status == "failed" {
+		return result, errors.New(result.Reason)
+	}
+	return result, nil
+}
+
+func TestReverifyTracksReportsChanges(t *testing.T) {
+	store := &reverifyTrackStore{stale: []db.Track{{ID: 1, Title: "Old"}, {ID: 2}, {ID: 3}}}
+	h := NewMaintenanceHandlers(store, &reverifyProcessor{results: map[int64]processor.MetadataReverifyResult{
+		1: {TrackID: 1, Status: "updated", Changes: []processor.MetadataFieldChange{{Field: "title", Before: "Old", After: "New"}}},
+		2: {TrackID: 2, Status: "unchanged"},
+		3: {TrackID: 3, Status: "failed", Reason: "musicbrainz unavailable"},
+	}})
+
+	req := httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/reverify", strings.NewReader(`{"staleAfterDays":90}`))
+	rec := httptest.NewRecorder()
+	h.ReverifyTracks(rec, req)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	if store.staleAfter != 90*24*time.Hour {
+		t.Fatalf("staleAfter = %v, want 90 days", store.staleAfter)
+	}
+	var resp maintenanceReverifyResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Summary != (maintenanceReverifySummary{Selected: 3, Updated: 1, Unchanged: 1, Errors: 1}) {
+		t.Fatalf("summary = %+v", resp.Summary)
+	}
+	if len(resp.Tracks) != 3 || resp.Tracks[0].Title != "Old" || len(resp.Tracks[0].Changes) != 1 || resp.Tracks[0].Changes[0].After != "New" {
+		t.Fatalf("tracks = %+v", resp.Tracks)
+	}
+}
+
+func TestReverifyTracksUnavailableWithoutReverifyStore(t *testing.T) {
+	h := NewMaintenanceHandlers(&qualitySelectionStore{}, &reverifyProcessor{})
+	rec := httptest.NewRecorder()
+	h.ReverifyTracks(rec, httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/reverify", strings.NewReader(`{}`)))
+	if rec.Code != http.StatusServiceUnavailable {
+		t.Fatalf("status = %d, want 503", rec.Code)
+	}
+}
@@ -15,6 +15,7 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 const (
@@ -110,7 +111,11 @@ func (h *MixPlanHandlers) ListMixPlans(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	limit, offset := parsePlaylistPagination(r)
+	query := newQueryParams(r)
+	limit, offset := query.Limit(20), query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
 	plans, total, err := h.store.GetByUserID(r.Context(), userCtx.UserID, limit, offset)
 	if err != nil {
 		writeMixPlanError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list mix plans")
@@ -486,7 +491,5 @@ func writeMixPlanJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writeMixPlanError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{Code: code, Message: message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
@@ -0,0 +1,230 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// maxNoteLength bounds a note's body in characters.
+const maxNoteLength = 2000
+
+type noteStore interface {
+	Get(ctx context.Context, userID uuid.UUID, targetType, targetID string) (*db.Note, error)
+	Set(ctx context.Context, userID uuid.UUID, targetType, targetID, body string) (*db.Note, error)
+	Delete(ctx context.Context, userID uuid.UUID, targetType, targetID string) error
+	List(ctx context.Context, userID uuid.UUID, opts db.NoteQueryOptions) ([]db.Note, int, error)
+}
+
+// notePlaylistReader checks a noted playlist belongs to the caller.
+// db.PlaylistRepository satisfies this interface.
+type notePlaylistReader interface {
+	GetByID(ctx context.Context, id int64) (*db.Playlist, error)
+}
+
+// NoteHandlers let users keep freeform notes on their tracks, albums and
+// playlists, such as where a rip came from. Notes are private to the user,
+// searched by library search and GET /api/v1/notes, and written to exports.
+type NoteHandlers struct {
+	notes     noteStore
+	library   playbackLibraryRepository
+	playlists notePlaylistReader
+}
+
+func NewNoteHandlers(notes noteStore, library playbackLibraryRepository, playlists notePlaylistReader) *NoteHandlers {
+	return &NoteHandlers{notes: notes, library: library, playlists: playlists}
+}
+
+type SetNoteRequest struct {
+	Body string `json:"body"`
+}
+
+// NoteResponse is a note on one target. TargetID is the track or playlist
+// ID, or the album's MusicBrainz release ID.
+type NoteResponse struct {
+	TargetType string    `json:"targetType"`
+	TargetID   string    `json:"targetId"`
+	Body       string    `json:"body"`
+	CreatedAt  time.Time `json:"createdAt"`
+	UpdatedAt  time.Time `json:"updatedAt"`
+}
+
+type NoteListResponse struct {
+	Notes  []NoteResponse `json:"notes"`
+	Total  int            `json:"total"`
+	Limit  int            `json:"limit"`
+	Offset int            `json:"offset"`
+}
+
+// ListNotes handles GET /api/v1/notes.
+// Query params: type (track|album|playlist), q (full-text search of note
+// bodies), limit, offset.
+func (h *NoteHandlers) ListNotes(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	opts := db.NoteQueryOptions{
+		TargetType: query.Enum("type", "", db.NoteTargetTrack, db.NoteTargetAlbum, db.NoteTargetPlaylist),
+		Search:     query.Text("q", maxQueryTextRunes),
+		Limit:      query.Limit(50),
+		Offset:     query.Offset(),
+	}
+	if !query.Valid(w, r) {
+		return
+	}
+	notes, total, err := h.notes.List(r.Context(), userCtx.UserID, opts)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list notes")
+		return
+	}
+	resp := NoteListResponse{Notes: make([]NoteResponse, 0, len(notes)), Total: total, Limit: opts.Limit, Offset: opts.Offset}
+	for _, note := range notes {
+		resp.Notes = append(resp.Notes, newNoteResponse(note))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// GetNote handles GET /api/v1/notes/{type}/{id}.
+func (h *NoteHandlers) GetNote(w http.ResponseWriter, r *http.Request) {
+	userID, targetType, targetID, ok := noteTarget(w, r)
+	if !ok {
+		return
+	}
+	note, err := h.notes.Get(r.Context(), userID, targetType, targetID)
+	if errors.Is(err, db.ErrNoteNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOTE_NOT_FOUND", "note not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load note")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, newNoteResponse(*note))
+}
+
+// SetNote handles PUT /api/v1/notes/{type}/{id}. Tracks must be in the
+// caller's library and playlists their own.
+func (h *NoteHandlers) SetNote(w http.ResponseWriter, r *http.Request) {
+	userID, targetType, targetID, ok := noteTarget(w, r)
+	if !ok {
+		return
+	}
+	var req SetNoteRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	body := strings.TrimSpace(req.Body)
+	if body == "" || utf8.RuneCountInString(body) > maxNoteLength {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "body is required and must be at most 2000 characters")
+		return
+	}
+	if !h.ownsTarget(w, r, userID, targetType, targetID) {
+		return
+	}
+	note, err := h.notes.Set(r.Context(), userID, targetType, targetID, body)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to save note")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, newNoteResponse(*note))
+}
+
+// DeleteNote handles DELETE /api/v1/notes/{type}/{id}.
+func (h *NoteHandlers) DeleteNote(w http.ResponseWriter, r *http.Request) {
+	userID, targetType, targetID, ok := noteTarget(w, r)
+	if !ok {
+		return
+	}
+	err := h.notes.Delete(r.Context(), userID, targetType, targetID)
+	if errors.Is(err, db.ErrNoteNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOTE_NOT_FOUND", "note not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to delete note")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// ownsTarget checks the caller may annotate the target, writing a 404 when
+// it is not theirs.
+func (h *NoteHandlers) ownsTarget(w http.ResponseWriter, r *http.Request, userID uuid.UUID, targetType, targetID string) bool {
+	switch targetType {
+	case db.NoteTargetTrack:
+		trackID, _ := strconv.ParseInt(targetID, 10, 64)
+		inLibrary, err := h.library.IsTrackInLibrary(r.Context(), userID, trackID)
+		if err != nil {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify library membership")
+			return false
+		}
+		if !inLibrary {
+			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return false
+		}
+	case db.NoteTargetPlaylist:
+		playlistID, _ := strconv.ParseInt(targetID, 10, 64)
+		playlist, err := h.playlists.GetByID(r.Context(), playlistID)
+		if err != nil && !errors.Is(err, db.ErrPlaylistNotFound) {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load playlist")
+			return false
+		}
+		if playlist == nil || playlist.UserID != userID {
+			writeLibraryError(w, http.StatusNotFound, "PLAYLIST_NOT_FOUND", "playlist not found")
+			return false
+		}
+	}
+	return true
+}
+
+// noteTarget reads and validates the note target from the path, writing the
+// error response when it is malformed. Album IDs come back in canonical form.
+func noteTarget(w http.ResponseWriter, r *http.Request) (uuid.UUID, string, string, bool) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return uuid.Nil, "", "", false
+	}
+	targetType, targetID := r.PathValue("type"), r.PathValue("id")
+	switch targetType {
+	case db.NoteTargetTrack, db.NoteTargetPlaylist:
+		if id, err := strconv.ParseInt(targetID, 10, 64); err != nil || id <= 0 {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid "+targetType+" ID")
+			return uuid.Nil, "", "", false
+		}
+	case db.NoteTargetAlbum:
+		releaseID, err := uuid.Parse(targetID)
+		if err != nil {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid MusicBrainz release ID")
+			return uuid.Nil, "", "", false
+		}
+		targetID = releaseID.String()
+	default:
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "notes are kept on tracks, albums and playlists")
+		return uuid.Nil, "", "", false
+	}
+	return userCtx.UserID, targetType, targetID, true
+}
+
+func newNoteResponse(note db.Note) NoteResponse {
+	return NoteResponse{
+		TargetType: note.TargetType,
+		TargetID:   note.TargetID,
+		Body:       note.Body,
+		CreatedAt:  note.CreatedAt,
+		UpdatedAt:  note.UpdatedAt,
+	}
+}
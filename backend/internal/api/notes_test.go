@@ -0,0 +1,132 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeNoteStore struct {
+	notes map[string]string
+}
+
+func (f *fakeNoteStore) Get(_ context.Context, _ uuid.UUID, targetType, targetID string) (*db.Note, error) {
+	body, ok := f.notes[targetType+"/"+targetID]
+	if !ok {
+		return nil, db.ErrNoteNotFound
+	}
+	return &db.Note{TargetType: targetType, TargetID: targetID, Body: body}, nil
+}
+
+func (f *fakeNoteStore) Set(_ context.Context, _ uuid.UUID, targetType, targetID, body string) (*db.Note, error) {
+	f.notes[targetType+"/"+targetID] = body
+	return &db.Note{TargetType: targetType, TargetID: targetID, Body: body}, nil
+}
+
+func (f *fakeNoteStore) Delete(_ context.Context, _ uuid.UUID, targetType, targetID string) error {
+	if _, ok := f.notes[targetType+"/"+targetID]; !ok {
+		return db.ErrNoteNotFound
+	}
+	delete(f.notes, targetType+"/"+targetID)
+	return nil
+}
+
+func (f *fakeNoteStore) List(_ context.Context, _ uuid.UUID, opts db.NoteQueryOptions) ([]db.Note, int, error) {
+	notes := []db.Note{}
+	for key, body := range f.notes {
+		targetType, targetID, _ := strings.Cut(key, "/")
+		if opts.TargetType == "" || opts.TargetType == targetType {
+			notes = append(notes, db.Note{TargetType: targetType, TargetID: targetID, Body: body})
+		}
+	}
+	return notes, len(notes), nil
+}
+
+func serveNote(handler http.HandlerFunc, method, targetType, targetID, body string, userID uuid.UUID) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(method, "/api/v1/notes/"+targetType+"/"+targetID, strings.NewReader(body))
+	req.SetPathValue("type", targetType)
+	req.SetPathValue("id", targetID)
+	rec := httptest.NewRecorder()
+	handler(rec, withUser(req, userID))
+	return rec
+}
+
+func TestNoteSetRequiresOwnedTargets(t *testing.T) {
+	userID := uuid.New()
+	store := &fakeNoteStore{notes: map[string]string{}}
+	library := &fakePlaybackLibraryRepo{allowed: map[int64]bool{1: true}}
+	h := NewNoteHandlers(store, library, fakeSettingsPlaylists{4: userID, 5: uuid.New()})
+
+	rec := serveNote(h.SetNote, http.MethodPut, "track", "1", `{"body":"  Ripped from vinyl  "}`, userID)
+	var resp NoteResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil || rec.Code != http.StatusOK {
+		t.Fatalf("set track note = %d %s", rec.Code, rec.Body.String())
+	}
+	if resp.Body != "Ripped from vinyl" || store.notes["track/1"] != "Ripped from vinyl" {
+		t.Fatalf("saved note = %#v, store = %v", resp, store.notes)
+	}
+
+	// Album IDs are stored in canonical form.
+	rec = serveNote(h.SetNote, http.MethodPut, "album", "A1B2C3D4-0000-4000-8000-000000000001", `{"body":"First pressing"}`, userID)
+	if rec.Code != http.StatusOK || store.notes["album/a1b2c3d4-0000-4000-8000-000000000001"] != "First pressing" {
+		t.Fatalf("set album note = %d, store = %v", rec.Code, store.notes)
+	}
+	if rec := serveNote(h.SetNote, http.MethodPut, "playlist", "4", `{"body":"Road trip"}`, userID); rec.Code != http.StatusOK {
+		t.Fatalf("set own playlist note = %d", rec.Code)
+	}
+
+	for _, tc := range []struct {
+		targetType, targetID, body string
+		want                       int
+	}{
+		{"track", "2", `{"body":"not mine"}`, http.StatusNotFound},
+		{"playlist", "5", `{"body":"not mine"}`, http.StatusNotFound},
+		{"artist", "1", `{"body":"no such target"}`, http.StatusNotFound},
+		{"track", "abc", `{"body":"bad id"}`, http.StatusBadRequest},
+		{"album", "not-a-uuid", `{"body":"bad id"}`, http.StatusBadRequest},
+		{"track", "1", `{"body":"   "}`, http.StatusBadRequest},
+		{"track", "1", `{"body":"` + strings.Repeat("é", maxNoteLength+1) + `"}`, http.StatusBadRequest},
+	} {
+		if rec := serveNote(h.SetNote, http.MethodPut, tc.targetType, tc.targetID, tc.body, userID); rec.Code != tc.want {
+			t.Errorf("set %s/%s = %d, want %d", tc.targetType, tc.targetID, rec.Code, tc.want)
+		}
+	}
+}
+
+func TestNoteGetListAndDelete(t *testing.T) {
+	userID := uuid.New()
+	store := &fakeNoteStore{notes: map[string]string{"track/1": "Ripped from vinyl", "playlist/4": "Road trip"}}
+	h := NewNoteHandlers(store, &fakePlaybackLibraryRepo{}, fakeSettingsPlaylists{})
+
+	if rec := serveNote(h.GetNote, http.MethodGet, "track", "1", "", userID); rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), "Ripped from vinyl") {
+		t.Fatalf("get = %d %s", rec.Code, rec.Body.String())
+	}
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/notes?type=playlist", nil)
+	rec := httptest.NewRecorder()
+	h.ListNotes(rec, withUser(req, userID))
+	var list NoteListResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &list); err != nil || rec.Code != http.StatusOK {
+		t.Fatalf("list = %d %s", rec.Code, rec.Body.String())
+	}
+	if list.Total != 1 || list.Notes[0].TargetID != "4" {
+		t.Fatalf("list = %#v, want only the playlist note", list)
+	}
+
+	if rec := serveNote(h.DeleteNote, http.MethodDelete, "track", "1", "", userID); rec.Code != http.StatusNoContent {
+		t.Fatalf("delete = %d", rec.Code)
+	}
+	if rec := serveNote(h.GetNote, http.MethodGet, "track", "1", "", userID); rec.Code != http.StatusNotFound {
+		t.Fatalf("get after delete = %d, want 404", rec.Code)
+	}
+	if rec := serveNote(h.DeleteNote, http.MethodDelete, "track", "1", "", userID); rec.Code != http.StatusNotFound {
+		t.Fatalf("second delete = %d, want 404", rec.Code)
+	}
+}
@@ -0,0 +1,166 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"slices"
+	"strconv"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/notifications"
+)
+
+type notificationStore interface {
+	List(ctx context.Context, userID uuid.UUID, unreadOnly bool, limit, offset int) ([]db.Notification, int, error)
+	UnreadCount(ctx context.Context, userID uuid.UUID) (int, error)
+	MarkRead(ctx context.Context, userID uuid.UUID, id int64) error
+	MarkAllRead(ctx context.Context, userID uuid.UUID) (int64, error)
+	Preferences(ctx context.Context, userID uuid.UUID) (map[string]bool, error)
+	SetPreferences(ctx context.Context, userID uuid.UUID, prefs map[string]bool) error
+}
+
+// NotificationHandlers serve the caller's notification inbox and which
+// notification types they receive.
+type NotificationHandlers struct {
+	notifications notificationStore
+}
+
+func NewNotificationHandlers(store notificationStore) *NotificationHandlers {
+	return &NotificationHandlers{notifications: store}
+}
+
+type NotificationListResponse struct {
+	Items  []notifications.Message `json:"items"`
+	Total  int                     `json:"total"`
+	Unread int                     `json:"unread"`
+	Limit  int                     `json:"limit"`
+	Offset int                     `json:"offset"`
+}
+
+type MarkAllReadResponse struct {
+	Marked int64 `json:"marked"`
+}
+
+// NotificationPreferences maps each notification type to whether the user
+// receives it. In a request, types left out keep their setting.
+type NotificationPreferences struct {
+	Preferences map[string]bool `json:"preferences"`
+}
+
+// ListNotifications handles GET /api/v1/notifications, newest first; with
+// unread=true only unread ones.
+func (h *NotificationHandlers) ListNotifications(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	unread := query.Bool("unread")
+	limit, offset := query.Limit(50), query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
+	items, total, err := h.notifications.List(r.Context(), userCtx.UserID, unread != nil && *unread, limit, offset)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list notifications")
+		return
+	}
+	unreadCount, err := h.notifications.UnreadCount(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list notifications")
+		return
+	}
+	resp := NotificationListResponse{Items: make([]notifications.Message, 0, len(items)), Total: total, Unread: unreadCount, Limit: limit, Offset: offset}
+	for _, n := range items {
+		resp.Items = append(resp.Items, notifications.NewMessage(n))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// MarkNotificationRead handles POST /api/v1/notifications/{id}/read.
+func (h *NotificationHandlers) MarkNotificationRead(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil || id <= 0 {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid notification ID")
+		return
+	}
+	err = h.notifications.MarkRead(r.Context(), userCtx.UserID, id)
+	if errors.Is(err, db.ErrNotificationNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "notification not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to mark notification read")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// MarkAllNotificationsRead handles POST /api/v1/notifications/read-all.
+func (h *NotificationHandlers) MarkAllNotificationsRead(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	marked, err := h.notifications.MarkAllRead(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to mark notifications read")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, MarkAllReadResponse{Marked: marked})
+}
+
+// GetNotificationPreferences handles GET /api/v1/notifications/preferences.
+func (h *NotificationHandlers) GetNotificationPreferences(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	h.writePreferences(w, r, userCtx.UserID)
+}
+
+// UpdateNotificationPreferences handles PUT /api/v1/notifications/preferences.
+func (h *NotificationHandlers) UpdateNotificationPreferences(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req NotificationPreferences
+	if err := decodeStrictJSON(r, &req); err != nil || len(req.Preferences) == 0 {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "preferences must name at least one notification type")
+		return
+	}
+	for kind := range req.Preferences {
+		if !slices.Contains(db.NotificationTypes, kind) {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "unknown notification type: "+kind)
+			return
+		}
+	}
+	if err := h.notifications.SetPreferences(r.Context(), userCtx.UserID, req.Preferences); err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to update notification preferences")
+		return
+	}
+	h.writePreferences(w, r, userCtx.UserID)
+}
+
+func (h *NotificationHandlers) writePreferences(w http.ResponseWriter, r *http.Request, userID uuid.UUID) {
+	prefs, err := h.notifications.Preferences(r.Context(), userID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load notification preferences")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, NotificationPreferences{Preferences: prefs})
+}
@@ -0,0 +1,105 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeNotifications struct {
+	items      []db.Notification
+	unreadOnly bool
+	prefs      map[string]bool
+}
+
+func (f *fakeNotifications) List(ctx context.Context, userID uuid.UUID, unreadOnly bool, limit, offset int) ([]db.Notification, int, error) {
+	f.unreadOnly = unreadOnly
+	return f.items, len(f.items), nil
+}
+
+func (f *fakeNotifications) UnreadCount(ctx context.Context, userID uuid.UUID) (int, error) {
+	return len(f.items), nil
+}
+
+func (f *fakeNotifications) MarkRead(ctx context.Context, userID uuid.UUID, id int64) error {
+	for _, n := range f.items {
+		if n.ID == id && n.UserID == userID {
+			return nil
+		}
+	}
+	return db.ErrNotificationNotFound
+}
+
+func (f *fakeNotifications) MarkAllRead(ctx context.Context, userID uuid.UUID) (int64, error) {
+	return int64(len(f.items)), nil
+}
+
+func (f *fakeNotifications) Preferences(ctx context.Context, userID uuid.UUID) (map[string]bool, error) {
+	return f.prefs, nil
+}
+
+func (f *fakeNotifications) SetPreferences(ctx context.Context, userID uuid.UUID, prefs map[string]bool) error {
+	for kind, enabled := range prefs {
+		f.prefs[kind] = enabled
+	}
+	return nil
+}
+
+func TestNotificationHandlers(t *testing.T) {
+	user := uuid.New()
+	store := &fakeNotifications{
+		items: []db.Notification{{ID: 1, UserID: user, Type: db.NotificationGiftReceived, Title: "alice sent you a track"}},
+		prefs: map[string]bool{db.NotificationGiftReceived: true, db.NotificationNewRelease: true},
+	}
+	h := NewNotificationHandlers(store)
+
+	rec := httptest.NewRecorder()
+	h.ListNotifications(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/notifications?unread=true", nil), user))
+	if rec.Code != http.StatusOK || !store.unreadOnly || !strings.Contains(rec.Body.String(), `"unread":1`) || !strings.Contains(rec.Body.String(), `"data":{}`) {
+		t.Fatalf("list = %d %s", rec.Code, rec.Body.String())
+	}
+	rec = httptest.NewRecorder()
+	h.ListNotifications(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/notifications?unread=maybe", nil), user))
+	if rec.Code != http.StatusBadRequest {
+		t.Errorf("list with a bad unread filter = %d", rec.Code)
+	}
+
+	markRead := func(userID uuid.UUID, id string) int {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/notifications/"+id+"/read", nil)
+		req.SetPathValue("id", id)
+		rec := httptest.NewRecorder()
+		h.MarkNotificationRead(rec, withUser(req, userID))
+		return rec.Code
+	}
+	if code := markRead(user, "1"); code != http.StatusNoContent {
+		t.Errorf("mark read = %d", code)
+	}
+	if code := markRead(uuid.New(), "1"); code != http.StatusNotFound {
+		t.Errorf("mark read of another user's notification = %d", code)
+	}
+	if code := markRead(user, "x"); code != http.StatusBadRequest {
+		t.Errorf("mark read with a bad ID = %d", code)
+	}
+
+	update := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPut, "/api/v1/notifications/preferences", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.UpdateNotificationPreferences(rec, withUser(req, user))
+		return rec
+	}
+	for _, body := range []string{`{}`, `{"preferences":{"spam":false}}`, `{"preferences":{"new_release":"no"}}`} {
+		if rec := update(body); rec.Code != http.StatusBadRequest {
+			t.Errorf("update %s = %d", body, rec.Code)
+		}
+	}
+	rec = update(`{"preferences":{"new_release":false}}`)
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"new_release":false`) || !strings.Contains(rec.Body.String(), `"gift_received":true`) {
+		t.Fatalf("update = %d %s", rec.Code, rec.Body.String())
+	}
+}
@@ -5,15 +5,19 @@ import (
 	"encoding/json"
 	"errors"
 	"net/http"
+	"strconv"
 	"time"
+	"unicode/utf8"
 
 	"github.com/google/uuid"
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
-// validPlayContextTypes is the exact allowed set for a play event's context_type.
+// validPlayContextTypes is the exact allowed set for a play event's (or a
+// like's) context_type. For "radio" the context ID names the seed.
 var validPlayContextTypes = map[string]bool{
 	"playlist": true,
 	"album":    true,
@@ -21,10 +25,41 @@ var validPlayContextTypes = map[string]bool{
 	"library":  true,
 	"queue":    true,
 	"search":   true,
+	"radio":    true,
+}
+
+// maxPlayContextIDLength bounds client-supplied context IDs such as an album
+// name or search query.
+const maxPlayContextIDLength = 512
+
+// validatePlayContext checks an optional context type/ID pair and returns a
+// message describing the problem, or "" when the pair is acceptable. A
+// playlist context ID must be the numeric playlist ID so per-playlist stats
+// line up.
+func validatePlayContext(contextType, contextID string) string {
+	if contextType == "" {
+		if contextID != "" {
+			return "contextId requires contextType"
+		}
+		return ""
+	}
+	if !validPlayContextTypes[contextType] {
+		return "contextType must be one of: playlist, album, artist, library, queue, search, radio"
+	}
+	if utf8.RuneCountInString(contextID) > maxPlayContextIDLength {
+		return "contextId must be at most " + strconv.Itoa(maxPlayContextIDLength) + " characters"
+	}
+	if contextType == "playlist" && contextID != "" {
+		if id, err := strconv.ParseInt(contextID, 10, 64); err != nil || id <= 0 {
+			return "contextId must be a playlist ID for playlist contexts"
+		}
+	}
+	return ""
 }
 
 type playEventTrackRepository interface {
-	GetByID(ctx context.Context, id int64) (*db.Track, error)
+	GetByIDForUser(ctx context.Context, userID uuid.UUID, id int64) (*db.Track, error)
+	GetByIDsForUser(ctx context.Context, userID uuid.UUID, ids []int64) (map[int64]*db.Track, error)
 }
 
 type playEventStore interface {
@@ -32,6 +67,10 @@ type playEventStore interface {
 	RecentlyPlayed(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.RecentlyPlayedTrack, error)
 	PlayHistory(ctx context.Context, userID uuid.UUID, limit, offset int) ([]db.PlayHistoryEvent, error)
 	TopTracks(ctx context.Context, userID uuid.UUID, days, limit int) ([]db.TopTrack, error)
+	ContextStats(ctx context.Context, userID uuid.UUID, contextType, contextID string, days, limit int) (*db.PlayContextStats, error)
+	RecordSkip(ctx context.Context, userID uuid.UUID, trackID int64, positionMs int, contextType, contextID string) error
+	SkipStats(ctx context.Context, userID uuid.UUID, days, limit int) (*db.SkipStats, error)
+	RecordClientEvents(ctx context.Context, userID uuid.UUID, events []db.ClientPlayEvent) ([]bool, error)
 }
 
 type PlayEventHandlers struct {
@@ -52,6 +91,64 @@ type RecordPlayRequest struct {
 	ContextID   string `json:"contextId,omitempty"`
 }
 
+// RecordSkipRequest reports a track the client moved past before it counted
+// as a play. PositionMs is how far into the track the skip happened.
+type RecordSkipRequest struct {
+	TrackID     int64  `json:"trackId"`
+	PositionMs  int    `json:"positionMs"`
+	ContextType string `json:"contextType,omitempty"`
+	ContextID   string `json:"contextId,omitempty"`
+}
+
+// Bounds for POST /api/v1/plays/batch. Events may be dated up to
+// maxPlayBatchEventAge back, for clients that were offline for a while, and
+// up to maxPlayBatchClockSkew ahead of the server clock.
+const (
+	maxPlayBatchEvents    = 500
+	maxPlayBatchBodyBytes = 1 << 20
+	maxPlayBatchEventAge  = 90 * 24 * time.Hour
+	maxPlayBatchClockSkew = 5 * time.Minute
+)
+
+const (
+	playBatchStatusRecorded  = "recorded"
+	playBatchStatusDuplicate = "duplicate"
+	playBatchStatusRejected  = "rejected"
+)
+
+// PlayBatchEventRequest is one play or skip recorded by a client, usually
+// while offline. EventID is generated by the client and makes resending the
+// event harmless.
+type PlayBatchEventRequest struct {
+	EventID     string    `json:"eventId"`
+	Type        string    `json:"type"`
+	TrackID     int64     `json:"trackId"`
+	OccurredAt  time.Time `json:"occurredAt"`
+	PositionMs  int       `json:"positionMs,omitempty"`
+	ContextType string    `json:"contextType,omitempty"`
+	ContextID   string    `json:"contextId,omitempty"`
+}
+
+type PlayBatchRequest struct {
+	Events []PlayBatchEventRequest `json:"events"`
+}
+
+// PlayBatchEventResult reports one event of a batch, in request order.
+// Status is "recorded", "duplicate" when the event ID was already recorded,
+// or "rejected" with the reason in Error.
+type PlayBatchEventResult struct {
+	EventID string `json:"eventId"`
+	Status  string `json:"status"`
+	Error   string `json:"error,omitempty"`
+}
+
+type PlayBatchResponse struct {
+	Recorded   int                    `json:"recorded"`
+	Duplicates int                    `json:"duplicates"`
+	Rejected   int                    `json:"rejected"`
+	Results    []PlayBatchEventResult `json:"results"`
+}
+
 type PlayEventTrackResponse struct {
 	ID                int64           `json:"id"`
 	Title             string          `json:"title"`
@@ -99,6 +196,38 @@ type TopTracksResponse struct {
 	Limit  int                      `json:"limit"`
 }
 
+// PlaylistPlayStatsResponse reports the caller's own plays started from a
+// playlist. Days is 0 for all-time stats.
+type PlaylistPlayStatsResponse struct {
+	PlaylistID   int64                    `json:"playlistId"`
+	PlayCount    int                      `json:"playCount"`
+	UniqueTracks int                      `json:"uniqueTracks"`
+	LastPlayedAt *time.Time               `json:"lastPlayedAt,omitempty"`
+	TopTracks    []PlayEventTrackResponse `json:"topTracks"`
+	Days         int                      `json:"days"`
+}
+
+// SkippedTrackResponse is a track from the skip stats. SkipRate is the share
+// of the track's listens in the window that were skips. LastPlayedAt replaces
+// the embedded field, which skip stats leave unset.
+type SkippedTrackResponse struct {
+	PlayEventTrackResponse
+	LastPlayedAt          *time.Time `json:"lastPlayedAt,omitempty"`
+	SkipCount             int        `json:"skipCount"`
+	SkipRate              float64    `json:"skipRate"`
+	AverageSkipPositionMs int        `json:"averageSkipPositionMs"`
+	LastSkippedAt         time.Time  `json:"lastSkippedAt"`
+}
+
+type SkipStatsResponse struct {
+	PlayCount int                    `json:"playCount"`
+	SkipCount int                    `json:"skipCount"`
+	SkipRate  float64                `json:"skipRate"`
+	Tracks    []SkippedTrackResponse `json:"tracks"`
+	Days      int                    `json:"days"`
+	Limit     int                    `json:"limit"`
+}
+
 // RecordPlay handles POST /api/v1/me/plays.
 func (h *PlayEventHandlers) RecordPlay(w http.ResponseWriter, r *http.Request) {
 	userCtx := auth.GetUserFromContext(r.Context())
@@ -118,15 +247,15 @@ func (h *PlayEventHandlers) RecordPlay(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	// context_type is optional, but when present it must be one of the known values.
-	if req.ContextType != "" && !validPlayContextTypes[req.ContextType] {
-		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "contextType must be one of: playlist, album, artist, library, queue, search")
+	// The context is optional, but when present it must be one of the known values.
+	if msg := validatePlayContext(req.ContextType, req.ContextID); msg != "" {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", msg)
 		return
 	}
 
 	// Verify the track exists so an unknown/foreign track is a clean 404 and no row
 	// is inserted.
-	if _, err := h.trackRepo.GetByID(r.Context(), req.TrackID); err != nil {
+	if _, err := h.trackRepo.GetByIDForUser(r.Context(), userCtx.UserID, req.TrackID); err != nil {
 		if errors.Is(err, db.ErrTrackNotFound) {
 			writePlayEventError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
 			return
@@ -146,6 +275,175 @@ func (h *PlayEventHandlers) RecordPlay(w http.ResponseWriter, r *http.Request) {
 	})
 }
 
+// RecordSkip handles POST /api/v1/me/plays/skips.
+func (h *PlayEventHandlers) RecordSkip(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	var req RecordSkipRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if req.TrackID <= 0 {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackId is required")
+		return
+	}
+	if req.PositionMs < 0 {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "positionMs must not be negative")
+		return
+	}
+	if msg := validatePlayContext(req.ContextType, req.ContextID); msg != "" {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", msg)
+		return
+	}
+
+	if _, err := h.trackRepo.GetByIDForUser(r.Context(), userCtx.UserID, req.TrackID); err != nil {
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writePlayEventError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify track")
+		return
+	}
+
+	if err := h.playEventRepo.RecordSkip(r.Context(), userCtx.UserID, req.TrackID, req.PositionMs, req.ContextType, req.ContextID); err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to record skip")
+		return
+	}
+
+	writePlayEventJSON(w, http.StatusCreated, map[string]interface{}{
+		"trackId": req.TrackID,
+		"skipped": true,
+	})
+}
+
+// RecordBatch handles POST /api/v1/plays/batch. Events that fail validation
+// are rejected one by one and the rest are recorded together, so one stale
+// or unknown track does not hold back a whole offline session.
+func (h *PlayEventHandlers) RecordBatch(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	r.Body = http.MaxBytesReader(w, r.Body, maxPlayBatchBodyBytes)
+	var req PlayBatchRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if len(req.Events) == 0 {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "events is required")
+		return
+	}
+	if len(req.Events) > maxPlayBatchEvents {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "at most "+strconv.Itoa(maxPlayBatchEvents)+" events per batch")
+		return
+	}
+
+	trackIDs := make([]int64, 0, len(req.Events))
+	for _, event := range req.Events {
+		trackIDs = append(trackIDs, event.TrackID)
+	}
+	knownTracks, err := h.trackRepo.GetByIDsForUser(r.Context(), userCtx.UserID, trackIDs)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify track")
+		return
+	}
+
+	now := time.Now()
+	results := make([]PlayBatchEventResult, len(req.Events))
+	accepted := make([]db.ClientPlayEvent, 0, len(req.Events))
+	acceptedIndex := make([]int, 0, len(req.Events))
+	for i, event := range req.Events {
+		results[i].EventID = event.EventID
+		validated, msg := validatePlayBatchEvent(event, now)
+		if msg == "" && knownTracks[event.TrackID] == nil {
+			msg = "track not found"
+		}
+		if msg != "" {
+			results[i].Status = playBatchStatusRejected
+			results[i].Error = msg
+			continue
+		}
+		accepted = append(accepted, validated)
+		acceptedIndex = append(acceptedIndex, i)
+	}
+
+	resp := PlayBatchResponse{Results: results}
+	if len(accepted) > 0 {
+		recorded, err := h.playEventRepo.RecordClientEvents(r.Context(), userCtx.UserID, accepted)
+		if err != nil {
+			writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to record events")
+			return
+		}
+		for j, i := range acceptedIndex {
+			if recorded[j] {
+				results[i].Status = playBatchStatusRecorded
+			} else {
+				results[i].Status = playBatchStatusDuplicate
+			}
+		}
+	}
+	for _, result := range results {
+		switch result.Status {
+		case playBatchStatusRecorded:
+			resp.Recorded++
+		case playBatchStatusDuplicate:
+			resp.Duplicates++
+		default:
+			resp.Rejected++
+		}
+	}
+
+	writePlayEventJSON(w, http.StatusOK, resp)
+}
+
+// validatePlayBatchEvent converts a batched event, returning a message
+// describing the problem, or "" when the event is acceptable. It does not
+// check that the track exists.
+func validatePlayBatchEvent(event PlayBatchEventRequest, now time.Time) (db.ClientPlayEvent, string) {
+	eventID, err := uuid.Parse(event.EventID)
+	if err != nil {
+		return db.ClientPlayEvent{}, "eventId must be a UUID"
+	}
+	if event.Type != db.PlayEventKindPlay && event.Type != db.PlayEventKindSkip {
+		return db.ClientPlayEvent{}, "type must be one of: play, skip"
+	}
+	if event.TrackID <= 0 {
+		return db.ClientPlayEvent{}, "trackId is required"
+	}
+	if event.OccurredAt.IsZero() {
+		return db.ClientPlayEvent{}, "occurredAt is required"
+	}
+	if event.OccurredAt.After(now.Add(maxPlayBatchClockSkew)) {
+		return db.ClientPlayEvent{}, "occurredAt must not be in the future"
+	}
+	if event.OccurredAt.Before(now.Add(-maxPlayBatchEventAge)) {
+		return db.ClientPlayEvent{}, "occurredAt is too old"
+	}
+	if event.PositionMs < 0 {
+		return db.ClientPlayEvent{}, "positionMs must not be negative"
+	}
+	if msg := validatePlayContext(event.ContextType, event.ContextID); msg != "" {
+		return db.ClientPlayEvent{}, msg
+	}
+	return db.ClientPlayEvent{
+		EventID:     eventID,
+		Kind:        event.Type,
+		TrackID:     event.TrackID,
+		OccurredAt:  event.OccurredAt,
+		PositionMs:  event.PositionMs,
+		ContextType: event.ContextType,
+		ContextID:   event.ContextID,
+	}, ""
+}
+
 // PlayHistory handles GET /api/v1/me/plays/history.
 func (h *PlayEventHandlers) PlayHistory(w http.ResponseWriter, r *http.Request) {
 	userCtx := auth.GetUserFromContext(r.Context())
@@ -154,8 +452,12 @@ func (h *PlayEventHandlers) PlayHistory(w http.ResponseWriter, r *http.Request)
 		return
 	}
 
-	limit := parseIntParam(r, "limit", 50)
-	offset := parseIntParam(r, "offset", 0)
+	query := newQueryParams(r)
+	limit := query.Limit(50)
+	offset := query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
 
 	events, err := h.playEventRepo.PlayHistory(r.Context(), userCtx.UserID, limit, offset)
 	if err != nil {
@@ -196,8 +498,12 @@ func (h *PlayEventHandlers) RecentlyPlayed(w http.ResponseWriter, r *http.Reques
 		return
 	}
 
-	limit := parseIntParam(r, "limit", 20)
-	offset := parseIntParam(r, "offset", 0)
+	query := newQueryParams(r)
+	limit := query.Limit(20)
+	offset := query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
 
 	tracks, err := h.playEventRepo.RecentlyPlayed(r.Context(), userCtx.UserID, limit, offset)
 	if err != nil {
@@ -227,8 +533,12 @@ func (h *PlayEventHandlers) TopTracks(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	days := parseIntParam(r, "days", 30)
-	limit := parseIntParam(r, "limit", 20)
+	query := newQueryParams(r)
+	days := query.Int("days", 30, 1, 3650)
+	limit := query.Limit(20)
+	if !query.Valid(w, r) {
+		return
+	}
 
 	tracks, err := h.playEventRepo.TopTracks(r.Context(), userCtx.UserID, days, limit)
 	if err != nil {
@@ -251,6 +561,108 @@ func (h *PlayEventHandlers) TopTracks(w http.ResponseWriter, r *http.Request) {
 	})
 }
 
+// PlaylistPlayStats handles GET /api/v1/me/plays/playlists/{id}. Stats cover
+// only the caller's plays, so any playlist the caller has played from can be
+// queried, including another user's public playlist.
+func (h *PlayEventHandlers) PlaylistPlayStats(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil || playlistID <= 0 {
+		writePlayEventError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	query := newQueryParams(r)
+	days := query.Int("days", 0, 0, 3650)
+	limit := query.Limit(10)
+	if !query.Valid(w, r) {
+		return
+	}
+
+	stats, err := h.playEventRepo.ContextStats(r.Context(), userCtx.UserID, "playlist", strconv.FormatInt(playlistID, 10), days, limit)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load playlist play stats")
+		return
+	}
+
+	response := PlaylistPlayStatsResponse{
+		PlaylistID:   playlistID,
+		PlayCount:    stats.PlayCount,
+		UniqueTracks: stats.UniqueTracks,
+		TopTracks:    make([]PlayEventTrackResponse, 0, len(stats.TopTracks)),
+		Days:         days,
+	}
+	if stats.LastPlayedAt.Valid {
+		response.LastPlayedAt = &stats.LastPlayedAt.Time
+	}
+	for _, t := range stats.TopTracks {
+		resp := trackToPlayEventResponse(t.Track)
+		resp.LastPlayedAt = t.LastPlayedAt
+		resp.PlayCount = t.PlayCount
+		response.TopTracks = append(response.TopTracks, resp)
+	}
+
+	writePlayEventJSON(w, http.StatusOK, response)
+}
+
+// SkipStats handles GET /api/v1/me/plays/skips.
+func (h *PlayEventHandlers) SkipStats(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	query := newQueryParams(r)
+	days := query.Int("days", 30, 1, 3650)
+	limit := query.Limit(20)
+	if !query.Valid(w, r) {
+		return
+	}
+
+	stats, err := h.playEventRepo.SkipStats(r.Context(), userCtx.UserID, days, limit)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load skip stats")
+		return
+	}
+
+	response := SkipStatsResponse{
+		PlayCount: stats.PlayCount,
+		SkipCount: stats.SkipCount,
+		SkipRate:  skipRate(stats.SkipCount, stats.PlayCount),
+		Tracks:    make([]SkippedTrackResponse, 0, len(stats.Tracks)),
+		Days:      days,
+		Limit:     limit,
+	}
+	for _, t := range stats.Tracks {
+		resp := SkippedTrackResponse{
+			PlayEventTrackResponse: trackToPlayEventResponse(t.Track),
+			SkipCount:              t.SkipCount,
+			SkipRate:               skipRate(t.SkipCount, t.PlayCount),
+			AverageSkipPositionMs:  t.AvgPositionMs,
+			LastSkippedAt:          t.LastSkippedAt,
+		}
+		resp.PlayCount = t.PlayCount
+		response.Tracks = append(response.Tracks, resp)
+	}
+
+	writePlayEventJSON(w, http.StatusOK, response)
+}
+
+// skipRate is the share of listens that were skips. Skips are recorded
+// instead of plays, so the two counts never overlap.
+func skipRate(skips, plays int) float64 {
+	if skips+plays == 0 {
+		return 0
+	}
+	return float64(skips) / float64(skips+plays)
+}
+
 func trackToPlayEventResponse(t db.Track) PlayEventTrackResponse {
 	resp := PlayEventTrackResponse{
 		ID:            t.ID,
@@ -308,10 +720,5 @@ func writePlayEventJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writePlayEventError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
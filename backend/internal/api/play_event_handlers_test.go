@@ -22,16 +22,28 @@ func withUser(req *http.Request, userID uuid.UUID) *http.Request {
 }
 
 type fakePlayTrackRepo struct {
-	tracks map[int64]*db.Track
+	tracks     map[int64]*db.Track
+	batchCalls int
 }
 
-func (f *fakePlayTrackRepo) GetByID(ctx context.Context, id int64) (*db.Track, error) {
+func (f *fakePlayTrackRepo) GetByIDForUser(ctx context.Context, userID uuid.UUID, id int64) (*db.Track, error) {
 	if t, ok := f.tracks[id]; ok {
 		return t, nil
 	}
 	return nil, db.ErrTrackNotFound
 }
 
+func (f *fakePlayTrackRepo) GetByIDsForUser(ctx context.Context, userID uuid.UUID, ids []int64) (map[int64]*db.Track, error) {
+	f.batchCalls++
+	tracks := make(map[int64]*db.Track, len(ids))
+	for _, id := range ids {
+		if t, ok := f.tracks[id]; ok {
+			tracks[id] = t
+		}
+	}
+	return tracks, nil
+}
+
 type recordedPlay struct {
 	userID      uuid.UUID
 	trackID     int64
@@ -40,10 +52,20 @@ type recordedPlay struct {
 }
 
 type fakePlayStore struct {
-	records []recordedPlay
-	recent  []db.RecentlyPlayedTrack
-	history []db.PlayHistoryEvent
-	top     []db.TopTrack
+	records      []recordedPlay
+	recent       []db.RecentlyPlayedTrack
+	history      []db.PlayHistoryEvent
+	top          []db.TopTrack
+	contextStats *db.PlayContextStats
+	statsQuery   recordedPlay
+	skips        []recordedSkip
+	skipStats    *db.SkipStats
+	clientEvents map[uuid.UUID]db.ClientPlayEvent
+}
+
+type recordedSkip struct {
+	recordedPlay
+	positionMs int
 }
 
 func (f *fakePlayStore) RecordPlay(ctx context.Context, userID uuid.UUID, trackID int64, contextType, contextID string) error {
@@ -63,6 +85,34 @@ func (f *fakePlayStore) TopTracks(ctx context.Context, userID uuid.UUID, days, l
 	return f.top, nil
 }
 
+func (f *fakePlayStore) ContextStats(ctx context.Context, userID uuid.UUID, contextType, contextID string, days, limit int) (*db.PlayContextStats, error) {
+	f.statsQuery = recordedPlay{userID: userID, contextType: contextType, contextID: contextID}
+	return f.contextStats, nil
+}
+
+func (f *fakePlayStore) RecordSkip(ctx context.Context, userID uuid.UUID, trackID int64, positionMs int, contextType, contextID string) error {
+	f.skips = append(f.skips, recordedSkip{recordedPlay{userID, trackID, contextType, contextID}, positionMs})
+	return nil
+}
+
+func (f *fakePlayStore) SkipStats(ctx context.Context, userID uuid.UUID, days, limit int) (*db.SkipStats, error) {
+	return f.skipStats, nil
+}
+
+func (f *fakePlayStore) RecordClientEvents(ctx context.Context, userID uuid.UUID, events []db.ClientPlayEvent) ([]bool, error) {
+	if f.clientEvents == nil {
+		f.clientEvents = map[uuid.UUID]db.ClientPlayEvent{}
+	}
+	recorded := make([]bool, len(events))
+	for i, event := range events {
+		if _, seen := f.clientEvents[event.EventID]; !seen {
+			f.clientEvents[event.EventID] = event
+			recorded[i] = true
+		}
+	}
+	return recorded, nil
+}
+
 func newTrack(id int64, title string) *db.Track {
 	return &db.Track{ID: id, Title: title}
 }
@@ -81,7 +131,9 @@ func TestRecordPlayValidation(t *testing.T) {
 		{"missing auth -> 401", false, `{"trackId":1}`, http.StatusUnauthorized},
 		{"invalid body -> 400", true, `{`, http.StatusBadRequest},
 		{"missing trackId -> 400", true, `{"contextType":"library"}`, http.StatusBadRequest},
-		{"invalid contextType -> 400", true, `{"trackId":1,"contextType":"radio"}`, http.StatusBadRequest},
+		{"invalid contextType -> 400", true, `{"trackId":1,"contextType":"podcast"}`, http.StatusBadRequest},
+		{"contextId without contextType -> 400", true, `{"trackId":1,"contextId":"9"}`, http.StatusBadRequest},
+		{"non-numeric playlist contextId -> 400", true, `{"trackId":1,"contextType":"playlist","contextId":"pl-9"}`, http.StatusBadRequest},
 		{"unknown track -> 404", true, `{"trackId":999,"contextType":"library"}`, http.StatusNotFound},
 	}
 
@@ -106,7 +158,7 @@ func TestRecordPlayValidation(t *testing.T) {
 }
 
 func TestRecordPlayValidContextTypesSet(t *testing.T) {
-	want := []string{"playlist", "album", "artist", "library", "queue", "search"}
+	want := []string{"playlist", "album", "artist", "library", "queue", "search", "radio"}
 	if len(validPlayContextTypes) != len(want) {
 		t.Fatalf("context type set size = %d, want %d", len(validPlayContextTypes), len(want))
 	}
@@ -124,7 +176,7 @@ func TestRecordPlaySuccessInsertsOne(t *testing.T) {
 
 	userID := uuid.New()
 	req := withUser(httptest.NewRequest(http.MethodPost, "/api/v1/me/plays",
-		strings.NewReader(`{"trackId":7,"contextType":"playlist","contextId":"pl-9"}`)), userID)
+		strings.NewReader(`{"trackId":7,"contextType":"playlist","contextId":"9"}`)), userID)
 	rr := httptest.NewRecorder()
 	h.RecordPlay(rr, req)
 
@@ -135,8 +187,8 @@ func TestRecordPlaySuccessInsertsOne(t *testing.T) {
 		t.Fatalf("recorded plays = %d, want exactly 1", len(store.records))
 	}
 	got := store.records[0]
-	if got.userID != userID || got.trackID != 7 || got.contextType != "playlist" || got.contextID != "pl-9" {
-		t.Fatalf("recorded play = %#v, want user %v track 7 playlist pl-9", got, userID)
+	if got.userID != userID || got.trackID != 7 || got.contextType != "playlist" || got.contextID != "9" {
+		t.Fatalf("recorded play = %#v, want user %v track 7 playlist 9", got, userID)
 	}
 }
 
@@ -228,6 +280,162 @@ func TestTopTracksHTTP(t *testing.T) {
 	}
 }
 
+func TestPlaylistPlayStatsHTTP(t *testing.T) {
+	now := time.Now()
+	store := &fakePlayStore{contextStats: &db.PlayContextStats{
+		PlayCount:    7,
+		UniqueTracks: 2,
+		LastPlayedAt: sql.NullTime{Time: now, Valid: true},
+		TopTracks: []db.TopTrack{
+			{Track: *newTrack(3, "Charlie"), PlayCount: 5, LastPlayedAt: now},
+			{Track: *newTrack(4, "Delta"), PlayCount: 2, LastPlayedAt: now.Add(-time.Hour)},
+		},
+	}}
+	h := NewPlayEventHandlers(store, &fakePlayTrackRepo{})
+
+	userID := uuid.New()
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/me/plays/playlists/42", nil)
+	req.SetPathValue("id", "42")
+	rr := httptest.NewRecorder()
+	h.PlaylistPlayStats(rr, withUser(req, userID))
+	if rr.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200 (body=%s)", rr.Code, rr.Body.String())
+	}
+	if store.statsQuery.userID != userID || store.statsQuery.contextType != "playlist" || store.statsQuery.contextID != "42" {
+		t.Fatalf("stats query = %#v, want caller's playlist 42", store.statsQuery)
+	}
+	var resp PlaylistPlayStatsResponse
+	if err := json.Unmarshal(rr.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.PlaylistID != 42 || resp.PlayCount != 7 || resp.UniqueTracks != 2 || resp.LastPlayedAt == nil || resp.Days != 0 {
+		t.Fatalf("stats = %#v, want playlist 42 with 7 plays of 2 tracks, all time", resp)
+	}
+	if len(resp.TopTracks) != 2 || resp.TopTracks[0].ID != 3 || resp.TopTracks[0].PlayCount != 5 {
+		t.Fatalf("top tracks = %#v, want track 3 first with 5 plays", resp.TopTracks)
+	}
+
+	req = httptest.NewRequest(http.MethodGet, "/api/v1/me/plays/playlists/abc", nil)
+	req.SetPathValue("id", "abc")
+	rr = httptest.NewRecorder()
+	h.PlaylistPlayStats(rr, withUser(req, userID))
+	if rr.Code != http.StatusBadRequest {
+		t.Fatalf("invalid playlist ID status = %d, want 400", rr.Code)
+	}
+}
+
 func sqlNullString(value string) sql.NullString {
 	return sql.NullString{String: value, Valid: value != ""}
 }
+
+func TestRecordSkipHTTP(t *testing.T) {
+	store := &fakePlayStore{}
+	tracks := &fakePlayTrackRepo{tracks: map[int64]*db.Track{7: newTrack(7, "Alpha")}}
+	h := NewPlayEventHandlers(store, tracks)
+	userID := uuid.New()
+
+	for _, body := range []string{`{"trackId":7,"positionMs":-1}`, `{"positionMs":1000}`, `{"trackId":7,"contextType":"bogus"}`} {
+		rr := httptest.NewRecorder()
+		h.RecordSkip(rr, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/me/plays/skips", strings.NewReader(body)), userID))
+		if rr.Code != http.StatusBadRequest {
+			t.Fatalf("body %s: status = %d, want 400", body, rr.Code)
+		}
+	}
+
+	rr := httptest.NewRecorder()
+	h.RecordSkip(rr, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/me/plays/skips",
+		strings.NewReader(`{"trackId":7,"positionMs":12500,"contextType":"radio","contextId":"seed"}`)), userID))
+	if rr.Code != http.StatusCreated {
+		t.Fatalf("status = %d, want 201 (body=%s)", rr.Code, rr.Body.String())
+	}
+	if len(store.skips) != 1 || store.skips[0].trackID != 7 || store.skips[0].positionMs != 12500 || store.skips[0].contextType != "radio" {
+		t.Fatalf("recorded skips = %#v", store.skips)
+	}
+	if len(store.records) != 0 {
+		t.Fatalf("a skip must not record a play: %#v", store.records)
+	}
+}
+
+func TestSkipStatsHTTP(t *testing.T) {
+	store := &fakePlayStore{skipStats: &db.SkipStats{
+		PlayCount: 6,
+		SkipCount: 2,
+		Tracks: []db.SkippedTrack{
+			{Track: *newTrack(3, "Charlie"), SkipCount: 2, PlayCount: 2, AvgPositionMs: 8000, LastSkippedAt: time.Now()},
+		},
+	}}
+	h := NewPlayEventHandlers(store, &fakePlayTrackRepo{})
+
+	rr := httptest.NewRecorder()
+	h.SkipStats(rr, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/plays/skips?days=7", nil), uuid.New()))
+	if rr.Code != http.StatusOK {
+		t.Fatalf("status = %d, want 200", rr.Code)
+	}
+	var resp SkipStatsResponse
+	if err := json.Unmarshal(rr.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Days != 7 || resp.SkipRate != 0.25 {
+		t.Fatalf("days = %d, skipRate = %v; want 7 and 0.25", resp.Days, resp.SkipRate)
+	}
+	if len(resp.Tracks) != 1 || resp.Tracks[0].SkipRate != 0.5 || resp.Tracks[0].PlayCount != 2 || resp.Tracks[0].AverageSkipPositionMs != 8000 {
+		t.Fatalf("tracks = %#v", resp.Tracks)
+	}
+	if strings.Contains(rr.Body.String(), "lastPlayedAt") {
+		t.Fatalf("skip stats must not report a zero lastPlayedAt: %s", rr.Body.String())
+	}
+}
+
+func TestRecordPlayBatchHTTP(t *testing.T) {
+	store := &fakePlayStore{}
+	tracks := &fakePlayTrackRepo{tracks: map[int64]*db.Track{7: newTrack(7, "Alpha")}}
+	h := NewPlayEventHandlers(store, tracks)
+	userID := uuid.New()
+
+	play, skip := uuid.New(), uuid.New()
+	at := time.Now().Add(-2 * time.Hour).UTC().Format(time.RFC3339)
+	old := time.Now().Add(-maxPlayBatchEventAge - time.Hour).UTC().Format(time.RFC3339)
+	body := `{"events":[
+		{"eventId":"` + play.String() + `","type":"play","trackId":7,"occurredAt":"` + at + `","contextType":"album","contextId":"x"},
+		{"eventId":"` + skip.String() + `","type":"skip","trackId":7,"occurredAt":"` + at + `","positionMs":9000},
+		{"eventId":"` + play.String() + `","type":"play","trackId":7,"occurredAt":"` + at + `"},
+		{"eventId":"` + uuid.NewString() + `","type":"play","trackId":99,"occurredAt":"` + at + `"},
+		{"eventId":"` + uuid.NewString() + `","type":"play","trackId":7,"occurredAt":"` + old + `"},
+		{"eventId":"not-a-uuid","type":"play","trackId":7,"occurredAt":"` + at + `"}
+	]}`
+
+	send := func() PlayBatchResponse {
+		t.Helper()
+		rr := httptest.NewRecorder()
+		h.RecordBatch(rr, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/plays/batch", strings.NewReader(body)), userID))
+		if rr.Code != http.StatusOK {
+			t.Fatalf("status = %d, want 200 (body=%s)", rr.Code, rr.Body.String())
+		}
+		var resp PlayBatchResponse
+		if err := json.Unmarshal(rr.Body.Bytes(), &resp); err != nil {
+			t.Fatalf("decode: %v", err)
+		}
+		return resp
+	}
+
+	resp := send()
+	if resp.Recorded != 2 || resp.Duplicates != 1 || resp.Rejected != 3 {
+		t.Fatalf("first batch = %+v", resp)
+	}
+	wantStatus := []string{"recorded", "recorded", "duplicate", "rejected", "rejected", "rejected"}
+	for i, result := range resp.Results {
+		if result.Status != wantStatus[i] {
+			t.Fatalf("result %d = %+v, want %s", i, result, wantStatus[i])
+		}
+	}
+	if got := store.clientEvents[skip]; got.Kind != db.PlayEventKindSkip || got.PositionMs != 9000 {
+		t.Fatalf("recorded skip = %+v", got)
+	}
+	if tracks.batchCalls != 1 {
+		t.Fatalf("track lookups for 6 events = %d, want one batch", tracks.batchCalls)
+	}
+
+	if resp := send(); resp.Recorded != 0 || resp.Duplicates != 3 {
+		t.Fatalf("resent batch = %+v, want every accepted event reported as a duplicate", resp)
+	}
+}
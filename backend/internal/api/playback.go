@@ -6,6 +6,7 @@ import (
 	"errors"
 	"io"
 	"net/http"
+	"strconv"
 	"strings"
 	"time"
 
@@ -13,6 +14,9 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
 	"github.com/openmusicplayer/backend/internal/storage"
 )
 
@@ -24,14 +28,42 @@ const (
 
 	playbackUnavailableCodeAudioUnavailable = "audio_unavailable"
 	playbackUnavailableCodeArtifactMissing  = "artifact_missing"
+	playbackUnavailableCodeUnsupported      = "unsupported_format"
 )
 
 type playbackTrackRepository interface {
-	GetByID(ctx context.Context, id int64) (*db.Track, error)
+	GetByIDs(ctx context.Context, ids []int64) (map[int64]*db.Track, error)
 }
 
 type playbackLibraryRepository interface {
 	IsTrackInLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (bool, error)
+	TracksInLibrary(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]bool, error)
+}
+
+// requireLibraryTrack reads the track_id path value and checks the track is
+// in the caller's library, writing the error response with writeError when
+// it is not.
+func requireLibraryTrack(w http.ResponseWriter, r *http.Request, library playbackLibraryRepository, writeError func(http.ResponseWriter, int, string, string)) (uuid.UUID, int64, bool) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return uuid.Nil, 0, false
+	}
+	trackID, err := strconv.ParseInt(r.PathValue("track_id"), 10, 64)
+	if err != nil || trackID <= 0 {
+		writeError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid track_id format")
+		return uuid.Nil, 0, false
+	}
+	inLibrary, err := library.IsTrackInLibrary(r.Context(), userCtx.UserID, trackID)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify library membership")
+		return uuid.Nil, 0, false
+	}
+	if !inLibrary {
+		writeError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+		return uuid.Nil, 0, false
+	}
+	return userCtx.UserID, trackID, true
 }
 
 type playbackURLStorage interface {
@@ -44,9 +76,17 @@ type PlaybackHandlers struct {
 	trackRepo   playbackTrackRepository
 	libraryRepo playbackLibraryRepository
 	storage     playbackURLStorage
+	skipMarkers skipMarkerSource
+	segments    skipSegmentLister
+	clients     playbackClientReader
+	transcodes  transcodeQueue
 	now         func() time.Time
 }
 
+type skipSegmentLister interface {
+	ForTracks(ctx context.Context, trackIDs []int64) (map[int64][]db.SkipSegment, error)
+}
+
 func NewPlaybackHandlers(trackRepo playbackTrackRepository, libraryRepo playbackLibraryRepository, storageClient playbackURLStorage) *PlaybackHandlers {
 	return &PlaybackHandlers{
 		trackRepo:   trackRepo,
@@ -56,14 +96,43 @@ func NewPlaybackHandlers(trackRepo playbackTrackRepository, libraryRepo playback
 	}
 }
 
+// WithSkipMarkers returns each URL with the intro and outro markers the
+// caller's playback should skip.
+func (h *PlaybackHandlers) WithSkipMarkers(markers skipMarkerSource) *PlaybackHandlers {
+	h.skipMarkers = markers
+	return h
+}
+
+// WithSkipSegments returns each URL with the non-music segments, such as
+// sponsor reads, found in the track's source video.
+func (h *PlaybackHandlers) WithSkipSegments(segments skipSegmentLister) *PlaybackHandlers {
+	h.segments = segments
+	return h
+}
+
+// WithClients issues URLs in a format the registered client named by a
+// request plays, queueing conversions of tracks it needs converted.
+func (h *PlaybackHandlers) WithClients(clients playbackClientReader, transcodes transcodeQueue) *PlaybackHandlers {
+	h.clients, h.transcodes = clients, transcodes
+	return h
+}
+
+// PlaybackURLRequest names the tracks to issue URLs for. ClientID, when set,
+// is a client registered under /api/v1/playback/clients whose capabilities
+// pick each track's format.
 type PlaybackURLRequest struct {
 	TrackIDs   []int64 `json:"trackIds"`
 	TTLSeconds int     `json:"ttlSeconds,omitempty"`
+	ClientID   int64   `json:"clientId,omitempty"`
 }
 
+// PlaybackURLResponse lists the issued URLs. Tracks being converted for the
+// client are listed as unavailable with code "converting", and JobID names
+// the job converting them when this request queued one.
 type PlaybackURLResponse struct {
 	URLs        []PlaybackURLItem         `json:"urls"`
 	Unavailable []PlaybackUnavailableItem `json:"unavailable,omitempty"`
+	JobID       string                    `json:"jobId,omitempty"`
 }
 
 type PlaybackURLItem struct {
@@ -78,15 +147,21 @@ type PlaybackURLItem struct {
 	Channels          int       `json:"channels,omitempty"`
 	ETag              string    `json:"etag,omitempty"`
 	StorageKeyVersion string    `json:"storageKeyVersion,omitempty"`
+	IntroEndMs        *int      `json:"introEndMs,omitempty"`
+	OutroStartMs      *int      `json:"outroStartMs,omitempty"`
+	Transcoded        bool      `json:"transcoded,omitempty"`
+	// SkipSegments are stretches inside the track playback skips over.
+	SkipSegments []PlaybackSkipSegment `json:"skipSegments,omitempty"`
 }
 
-type PlaybackUnavailableItem struct {
-	TrackID int64  `json:"trackId"`
-	Code    string `json:"code"`
-	Message string `json:"message"`
+type PlaybackSkipSegment struct {
+	Category string `json:"category"`
+	StartMs  int    `json:"startMs"`
+	EndMs    int    `json:"endMs"`
 }
 
-type playbackErrorResponse struct {
+type PlaybackUnavailableItem struct {
+	TrackID int64  `json:"trackId"`
 	Code    string `json:"code"`
 	Message string `json:"message"`
 }
@@ -130,30 +205,72 @@ func (h *PlaybackHandlers) CreatePlaybackURLs(w http.ResponseWriter, r *http.Req
 		return
 	}
 
+	var caps *libraryexport.Capabilities
+	if req.ClientID != 0 {
+		var client *db.PlaybackClient
+		if h.clients != nil {
+			client, err = h.clients.Get(r.Context(), userCtx.UserID, req.ClientID)
+		}
+		if err != nil && !errors.Is(err, db.ErrPlaybackClientNotFound) {
+			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load client")
+			return
+		}
+		if client == nil {
+			writePlaybackError(w, http.StatusBadRequest, "INVALID_REQUEST", "clientId names no registered client")
+			return
+		}
+		caps = &libraryexport.Capabilities{Codecs: client.Codecs, MaxBitrateKbps: client.MaxBitrateKbps}
+	}
+
 	ttl := clampPlaybackTTL(req.TTLSeconds)
 	expiresAt := h.now().Add(ttl).UTC()
 	resp := PlaybackURLResponse{
 		URLs: make([]PlaybackURLItem, 0, len(trackIDs)),
 	}
 
+	// Ownership and tracks are loaded for the whole batch at once, so a
+	// request costs two queries however many tracks it names.
+	inLibrary, err := h.libraryRepo.TracksInLibrary(r.Context(), userCtx.UserID, trackIDs)
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify library ownership")
+		return
+	}
 	for _, trackID := range trackIDs {
-		inLibrary, err := h.libraryRepo.IsTrackInLibrary(r.Context(), userCtx.UserID, trackID)
+		if !inLibrary[trackID] {
+			writePlaybackError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+	}
+	tracks, err := h.trackRepo.GetByIDs(r.Context(), trackIDs)
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+		return
+	}
+	var skipMarkers map[int64]db.SkipMarkers
+	if h.skipMarkers != nil {
+		skipMarkers, err = h.skipMarkers.ForUser(r.Context(), userCtx.UserID, trackIDs)
 		if err != nil {
-			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify library ownership")
+			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load skip markers")
 			return
 		}
-		if !inLibrary {
-			writePlaybackError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+	}
+	var skipSegments map[int64][]db.SkipSegment
+	if h.segments != nil {
+		skipSegments, err = h.segments.ForTracks(r.Context(), trackIDs)
+		if err != nil {
+			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load skip segments")
 			return
 		}
+	}
 
-		track, err := h.trackRepo.GetByID(r.Context(), trackID)
-		if err != nil {
-			if errors.Is(err, db.ErrTrackNotFound) {
-				writePlaybackError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
-				return
-			}
-			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+	// Every converted track gets the same profile: StreamProfile only
+	// converts to the best format the client plays.
+	var converting []int64
+	var conversion libraryexport.Profile
+	for _, trackID := range trackIDs {
+		track, ok := tracks[trackID]
+		if !ok {
+			writePlaybackError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
 			return
 		}
 
@@ -167,20 +284,41 @@ func (h *PlaybackHandlers) CreatePlaybackURLs(w http.ResponseWriter, r *http.Req
 			continue
 		}
 
-		objInfo, err := h.storage.StatObject(r.Context(), storageKey)
+		key, profile := storageKey, libraryexport.Profile{}
+		if caps != nil {
+			profile, ok = libraryexport.StreamProfile(*caps, track.Codec.String, int(track.BitrateKbps.Int32))
+			if !ok {
+				resp.Unavailable = append(resp.Unavailable, PlaybackUnavailableItem{
+					TrackID: trackID,
+					Code:    playbackUnavailableCodeUnsupported,
+					Message: "the server converts to no format this client plays",
+				})
+				continue
+			}
+			if !profile.IsOriginal() {
+				key, conversion = libraryexport.TranscodeKey(profile, storageKey), profile
+			}
+		}
+
+		objInfo, err := h.storage.StatObject(r.Context(), key)
 		if err != nil {
 			if r.Context().Err() != nil {
 				return
 			}
-			resp.Unavailable = append(resp.Unavailable, PlaybackUnavailableItem{
+			item := PlaybackUnavailableItem{
 				TrackID: trackID,
 				Code:    playbackUnavailableCodeArtifactMissing,
 				Message: "stored audio object is unavailable",
-			})
+			}
+			if !profile.IsOriginal() {
+				item.Code, item.Message = syncUnavailableCodeConverting, "track is being converted for this client"
+				converting = append(converting, trackID)
+			}
+			resp.Unavailable = append(resp.Unavailable, item)
 			continue
 		}
 
-		url, err := h.storage.PresignGetObject(r.Context(), storageKey, ttl)
+		url, err := h.storage.PresignGetObject(r.Context(), key, ttl)
 		if err != nil {
 			if r.Context().Err() != nil {
 				return
@@ -193,7 +331,7 @@ func (h *PlaybackHandlers) CreatePlaybackURLs(w http.ResponseWriter, r *http.Req
 			TrackID:     trackID,
 			URL:         url,
 			ExpiresAt:   expiresAt,
-			ContentType: playbackContentType(storageKey, objInfo.ContentType),
+			ContentType: playbackContentType(key, objInfo.ContentType),
 			SizeBytes:   objInfo.Size,
 			ETag:        objInfo.ETag,
 		}
@@ -215,9 +353,35 @@ func (h *PlaybackHandlers) CreatePlaybackURLs(w http.ResponseWriter, r *http.Req
 		if track.ContentType.Valid {
 			item.ContentType = track.ContentType.String
 		}
+		if !profile.IsOriginal() {
+			// The stored file's sample rate and channels survive
+			// conversion; its codec and bitrate do not.
+			item.Codec, item.BitrateKbps, item.ContentType, item.Transcoded = profile.Format, profile.BitrateKbps, profile.ContentType(), true
+		}
+		if markers, ok := skipMarkers[trackID]; ok {
+			item.IntroEndMs, item.OutroStartMs = skipMarkerMs(markers.IntroEndMs), skipMarkerMs(markers.OutroStartMs)
+		}
+		for _, segment := range skipSegments[trackID] {
+			item.SkipSegments = append(item.SkipSegments, PlaybackSkipSegment{Category: segment.Category, StartMs: segment.StartMs, EndMs: segment.EndMs})
+		}
 		resp.URLs = append(resp.URLs, item)
 	}
 
+	if len(converting) > 0 && h.transcodes != nil {
+		job, err := h.transcodes.Enqueue(r.Context(), userCtx.UserID, conversion, converting)
+		if errors.Is(err, libraryexport.ErrTranscodeBusy) {
+			w.Header().Set("Retry-After", strconv.Itoa(int(transcodeBusyRetryAfter.Seconds())))
+			writePlaybackError(w, http.StatusTooManyRequests, "TRANSCODE_BUSY", "the server is converting as many tracks as it admits; try again shortly")
+			return
+		}
+		if err != nil && !errors.Is(err, jobs.ErrDuplicate) {
+			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue conversion")
+			return
+		}
+		if err == nil {
+			resp.JobID = job.ID.String()
+		}
+	}
 	writePlaybackJSON(w, http.StatusOK, resp)
 }
 
@@ -285,5 +449,5 @@ func writePlaybackJSON(w http.ResponseWriter, status int, v interface{}) {
 }
 
 func writePlaybackError(w http.ResponseWriter, status int, code, message string) {
-	writePlaybackJSON(w, status, playbackErrorResponse{Code: code, Message: message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
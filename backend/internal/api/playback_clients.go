@@ -0,0 +1,213 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"regexp"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	maxPlaybackClientNameLength = 100
+	maxPlaybackClientCodecs     = 20
+	// minClientBitrateKbps is the lowest bitrate the server converts to.
+	minClientBitrateKbps = 32
+)
+
+// codecNamePattern accepts ffprobe codec names such as "mp3" or "pcm_s16le".
+var codecNamePattern = regexp.MustCompile(`^[a-z0-9_]{1,32}$`)
+
+type playbackClientRepository interface {
+	playbackClientReader
+	Register(ctx context.Context, client *db.PlaybackClient) error
+	List(ctx context.Context, userID uuid.UUID) ([]db.PlaybackClient, error)
+	Delete(ctx context.Context, userID uuid.UUID, id int64) error
+}
+
+type playbackClientReader interface {
+	Get(ctx context.Context, userID uuid.UUID, id int64) (*db.PlaybackClient, error)
+}
+
+type clientEQPresetResolver interface {
+	ForClient(ctx context.Context, userID uuid.UUID, clientID int64) (*db.EQPreset, error)
+}
+
+// PlaybackClientHandlers run the handshake in which a player declares the
+// codecs it decodes and the bitrate it wants. Playback URL requests naming
+// the returned client ID then come back in a format the player plays.
+type PlaybackClientHandlers struct {
+	clients   playbackClientRepository
+	eqPresets clientEQPresetResolver
+}
+
+func NewPlaybackClientHandlers(clients playbackClientRepository) *PlaybackClientHandlers {
+	return &PlaybackClientHandlers{clients: clients}
+}
+
+// WithEQPresets makes the handshake return the EQ preset the player applies.
+func (h *PlaybackClientHandlers) WithEQPresets(presets clientEQPresetResolver) *PlaybackClientHandlers {
+	h.eqPresets = presets
+	return h
+}
+
+// PlaybackClientRequest registers a client. MaxBitrateKbps of 0 sets no
+// limit.
+type PlaybackClientRequest struct {
+	Name           string   `json:"name"`
+	Codecs         []string `json:"codecs"`
+	MaxBitrateKbps int      `json:"maxBitrateKbps,omitempty"`
+}
+
+// PlaybackClientResponse describes a registered client. EQPresetID is set
+// when the client overrides the user's default EQ preset; the handshake
+// also returns EQ, the preset the client applies, when there is one.
+type PlaybackClientResponse struct {
+	ID             int64             `json:"id"`
+	Name           string            `json:"name"`
+	Codecs         []string          `json:"codecs"`
+	MaxBitrateKbps int               `json:"maxBitrateKbps,omitempty"`
+	EQPresetID     *int64            `json:"eqPresetId,omitempty"`
+	EQ             *EQPresetResponse `json:"eq,omitempty"`
+	CreatedAt      time.Time         `json:"createdAt"`
+	UpdatedAt      time.Time         `json:"updatedAt"`
+}
+
+type PlaybackClientListResponse struct {
+	Clients []PlaybackClientResponse `json:"clients"`
+}
+
+// RegisterClient handles POST /api/v1/playback/clients. Registering a name
+// again replaces that client's capabilities and keeps its ID and EQ preset.
+func (h *PlaybackClientHandlers) RegisterClient(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req PlaybackClientRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	name := strings.TrimSpace(req.Name)
+	if name == "" || utf8.RuneCountInString(name) > maxPlaybackClientNameLength {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name must be 1 to "+strconv.Itoa(maxPlaybackClientNameLength)+" characters")
+		return
+	}
+	codecs, err := normalizeCodecs(req.Codecs)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	}
+	if req.MaxBitrateKbps != 0 && req.MaxBitrateKbps < minClientBitrateKbps {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "maxBitrateKbps must be 0 or at least "+strconv.Itoa(minClientBitrateKbps))
+		return
+	}
+
+	client := &db.PlaybackClient{UserID: userCtx.UserID, Name: name, Codecs: codecs, MaxBitrateKbps: req.MaxBitrateKbps}
+	if err := h.clients.Register(r.Context(), client); err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to register client")
+		return
+	}
+	resp := newPlaybackClientResponse(*client)
+	if h.eqPresets != nil {
+		preset, err := h.eqPresets.ForClient(r.Context(), userCtx.UserID, client.ID)
+		if err != nil {
+			writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load EQ preset")
+			return
+		}
+		if preset != nil {
+			eq := newEQPresetResponse(*preset)
+			resp.EQ = &eq
+		}
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// ListClients handles GET /api/v1/playback/clients.
+func (h *PlaybackClientHandlers) ListClients(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	clients, err := h.clients.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list clients")
+		return
+	}
+	resp := PlaybackClientListResponse{Clients: make([]PlaybackClientResponse, 0, len(clients))}
+	for _, client := range clients {
+		resp.Clients = append(resp.Clients, newPlaybackClientResponse(client))
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// DeleteClient handles DELETE /api/v1/playback/clients/{id}.
+func (h *PlaybackClientHandlers) DeleteClient(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaybackError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid client ID")
+		return
+	}
+	err = h.clients.Delete(r.Context(), userCtx.UserID, id)
+	if errors.Is(err, db.ErrPlaybackClientNotFound) {
+		writePlaybackError(w, http.StatusNotFound, "CLIENT_NOT_FOUND", "client not found")
+		return
+	}
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to delete client")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// normalizeCodecs lowercases and dedupes declared codec names, keeping their
+// order.
+func normalizeCodecs(declared []string) ([]string, error) {
+	codecs := make([]string, 0, len(declared))
+	seen := map[string]bool{}
+	for _, codec := range declared {
+		codec = strings.ToLower(strings.TrimSpace(codec))
+		if !codecNamePattern.MatchString(codec) {
+			return nil, errors.New("codecs must be codec names such as mp3, aac, opus or flac")
+		}
+		if !seen[codec] {
+			seen[codec] = true
+			codecs = append(codecs, codec)
+		}
+	}
+	if len(codecs) == 0 || len(codecs) > maxPlaybackClientCodecs {
+		return nil, errors.New("codecs must name 1 to " + strconv.Itoa(maxPlaybackClientCodecs) + " codecs")
+	}
+	return codecs, nil
+}
+
+func newPlaybackClientResponse(client db.PlaybackClient) PlaybackClientResponse {
+	resp := PlaybackClientResponse{
+		ID:             client.ID,
+		Name:           client.Name,
+		Codecs:         client.Codecs,
+		MaxBitrateKbps: client.MaxBitrateKbps,
+		CreatedAt:      client.CreatedAt,
+		UpdatedAt:      client.UpdatedAt,
+	}
+	if client.EQPresetID.Valid {
+		resp.EQPresetID = &client.EQPresetID.Int64
+	}
+	return resp
+}
@@ -0,0 +1,136 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+type fakePlaybackClients struct {
+	clients map[int64]*db.PlaybackClient
+}
+
+func (f *fakePlaybackClients) Register(_ context.Context, client *db.PlaybackClient) error {
+	for _, c := range f.clients {
+		if c.UserID == client.UserID && c.Name == client.Name {
+			client.ID = c.ID
+		}
+	}
+	if client.ID == 0 {
+		client.ID = int64(len(f.clients) + 1)
+	}
+	f.clients[client.ID] = client
+	return nil
+}
+
+func (f *fakePlaybackClients) List(_ context.Context, userID uuid.UUID) ([]db.PlaybackClient, error) {
+	var out []db.PlaybackClient
+	for _, c := range f.clients {
+		if c.UserID == userID {
+			out = append(out, *c)
+		}
+	}
+	return out, nil
+}
+
+func (f *fakePlaybackClients) Get(_ context.Context, userID uuid.UUID, id int64) (*db.PlaybackClient, error) {
+	c, ok := f.clients[id]
+	if !ok || c.UserID != userID {
+		return nil, db.ErrPlaybackClientNotFound
+	}
+	return c, nil
+}
+
+func (f *fakePlaybackClients) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	if _, err := f.Get(ctx, userID, id); err != nil {
+		return err
+	}
+	delete(f.clients, id)
+	return nil
+}
+
+func TestRegisterPlaybackClientValidatesAndReregistersByName(t *testing.T) {
+	clients := &fakePlaybackClients{clients: map[int64]*db.PlaybackClient{}}
+	h := NewPlaybackClientHandlers(clients)
+	userID := uuid.New()
+	register := func(body string) (*httptest.ResponseRecorder, PlaybackClientResponse) {
+		rec := httptest.NewRecorder()
+		h.RegisterClient(rec, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/playback/clients", strings.NewReader(body)), userID))
+		var resp PlaybackClientResponse
+		_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+		return rec, resp
+	}
+
+	for _, body := range []string{
+		`{"name":"","codecs":["mp3"]}`,
+		`{"name":"Cast","codecs":[]}`,
+		`{"name":"Cast","codecs":["mp3;drop"]}`,
+		`{"name":"Cast","codecs":["mp3"],"maxBitrateKbps":16}`,
+	} {
+		if rec, _ := register(body); rec.Code != http.StatusBadRequest {
+			t.Fatalf("register %s = %d, want 400", body, rec.Code)
+		}
+	}
+	rec, first := register(`{"name":" Cast ","codecs":["MP3","aac","mp3"],"maxBitrateKbps":192}`)
+	if rec.Code != http.StatusOK || first.Name != "Cast" || strings.Join(first.Codecs, ",") != "mp3,aac" || first.MaxBitrateKbps != 192 {
+		t.Fatalf("register = %d %+v", rec.Code, first)
+	}
+	if _, again := register(`{"name":"Cast","codecs":["opus"]}`); again.ID != first.ID || again.MaxBitrateKbps != 0 {
+		t.Fatalf("re-register = %+v, want client %d replaced", again, first.ID)
+	}
+}
+
+func TestPlaybackURLsNegotiateFormatForRegisteredClient(t *testing.T) {
+	userID := uuid.MustParse("11111111-1111-1111-1111-111111111111")
+	tracks := map[int64]*db.Track{
+		1: {ID: 1, StorageKey: sql.NullString{String: "tracks/1.mp3", Valid: true}, Codec: sql.NullString{String: "mp3", Valid: true}, BitrateKbps: sql.NullInt32{Int32: 128, Valid: true}},
+		2: {ID: 2, StorageKey: sql.NullString{String: "tracks/2.flac", Valid: true}, Codec: sql.NullString{String: "flac", Valid: true}, BitrateKbps: sql.NullInt32{Int32: 900, Valid: true}},
+		3: {ID: 3, StorageKey: sql.NullString{String: "tracks/3.flac", Valid: true}, Codec: sql.NullString{String: "flac", Valid: true}, BitrateKbps: sql.NullInt32{Int32: 900, Valid: true}},
+	}
+	converted := libraryexport.TranscodeKey(libraryexport.Profile{Format: libraryexport.FormatAAC, BitrateKbps: 192}, "tracks/2.flac")
+	objects := &fakePlaybackStorage{info: map[string]*storage.ObjectInfo{
+		"tracks/1.mp3": {Size: 10, ContentType: "audio/mpeg"},
+		converted:      {Size: 20},
+	}}
+	transcodes := &fakeTranscodeQueue{}
+	clients := &fakePlaybackClients{clients: map[int64]*db.PlaybackClient{
+		7: {ID: 7, UserID: userID, Name: "Cast", Codecs: []string{"mp3", "aac"}, MaxBitrateKbps: 192},
+		8: {ID: 8, UserID: uuid.New(), Name: "Someone else's", Codecs: []string{"flac"}},
+	}}
+	h := NewPlaybackHandlers(&fakePlaybackTrackRepo{tracks: tracks}, &fakePlaybackLibraryRepo{allowed: map[int64]bool{1: true, 2: true, 3: true}}, objects).WithClients(clients, transcodes)
+
+	rec := playbackRequest(t, h.CreatePlaybackURLs, `{"trackIds":[1,2,3],"clientId":7}`)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, body %s", rec.Code, rec.Body.String())
+	}
+	var resp PlaybackURLResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatal(err)
+	}
+	if len(resp.URLs) != 2 || resp.URLs[0].Transcoded || resp.URLs[0].Codec != "mp3" {
+		t.Fatalf("urls = %+v, want track 1 as stored first", resp.URLs)
+	}
+	if got := resp.URLs[1]; !got.Transcoded || got.Codec != "aac" || got.BitrateKbps != 192 || got.ContentType != "audio/mp4" {
+		t.Fatalf("converted url = %+v", got)
+	}
+	if len(resp.Unavailable) != 1 || resp.Unavailable[0].TrackID != 3 || resp.Unavailable[0].Code != syncUnavailableCodeConverting || resp.JobID == "" {
+		t.Fatalf("unavailable = %+v, job %q", resp.Unavailable, resp.JobID)
+	}
+	if len(transcodes.queued) != 1 || len(transcodes.queued[0]) != 1 || transcodes.queued[0][0] != 3 {
+		t.Fatalf("queued = %v, want track 3", transcodes.queued)
+	}
+
+	if rec := playbackRequest(t, h.CreatePlaybackURLs, `{"trackIds":[1],"clientId":8}`); rec.Code != http.StatusBadRequest {
+		t.Fatalf("another user's client = %d, want 400", rec.Code)
+	}
+}
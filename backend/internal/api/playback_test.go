@@ -9,6 +9,7 @@ import (
 	"log"
 	"net/http"
 	"net/http/httptest"
+	"reflect"
 	"strconv"
 	"strings"
 	"testing"
@@ -27,30 +28,46 @@ type fakePlaybackTrackRepo struct {
 	calls  int
 }
 
-func (f *fakePlaybackTrackRepo) GetByID(ctx context.Context, id int64) (*db.Track, error) {
+func (f *fakePlaybackTrackRepo) GetByIDs(ctx context.Context, ids []int64) (map[int64]*db.Track, error) {
 	f.calls++
 	if f.err != nil {
 		return nil, f.err
 	}
-	track, ok := f.tracks[id]
-	if !ok {
-		return nil, db.ErrTrackNotFound
+	tracks := make(map[int64]*db.Track, len(ids))
+	for _, id := range ids {
+		if track, ok := f.tracks[id]; ok {
+			tracks[id] = track
+		}
 	}
-	return track, nil
+	return tracks, nil
 }
 
 type fakePlaybackLibraryRepo struct {
 	allowed map[int64]bool
 	err     error
+	calls   int
 }
 
 func (f *fakePlaybackLibraryRepo) IsTrackInLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (bool, error) {
+	f.calls++
 	if f.err != nil {
 		return false, f.err
 	}
 	return f.allowed[trackID], nil
 }
 
+func (f *fakePlaybackLibraryRepo) TracksInLibrary(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]bool, error) {
+	f.calls++
+	if f.err != nil {
+		return nil, f.err
+	}
+	inLibrary := make(map[int64]bool, len(trackIDs))
+	for _, id := range trackIDs {
+		inLibrary[id] = f.allowed[id]
+	}
+	return inLibrary, nil
+}
+
 type fakePlaybackStorage struct {
 	info        map[string]*storage.ObjectInfo
 	statErr     error
@@ -337,3 +354,49 @@ func TestPlaybackURLIssuanceReportsPresignFailureAsInternalError(t *testing.T) {
 		t.Fatalf("error response leaked signed URL: %s", rec.Body.String())
 	}
 }
+
+func TestPlaybackURLsCarrySkipMarkers(t *testing.T) {
+	track := &db.Track{ID: 42, StorageKey: sql.NullString{String: "audio/track-42.mp3", Valid: true}}
+	handler, _ := newPlaybackHandlerForTrack(track, true, &fakePlaybackStorage{info: map[string]*storage.ObjectInfo{
+		"audio/track-42.mp3": {Size: 100, ContentType: "audio/mpeg"},
+	}})
+	markers := &fakeSkipMarkerStore{}
+	markers.Set(context.Background(), 42, uuid.NullUUID{}, db.SkipMarkers{OutroStartMs: sql.NullInt32{Int32: 180000, Valid: true}})
+	handler.WithSkipMarkers(markers)
+
+	rec := playbackRequest(t, handler.CreatePlaybackURLs, `{"trackIds":[42]}`)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp PlaybackURLResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if len(resp.URLs) != 1 || resp.URLs[0].IntroEndMs != nil || resp.URLs[0].OutroStartMs == nil || *resp.URLs[0].OutroStartMs != 180000 {
+		t.Fatalf("urls = %+v", resp.URLs)
+	}
+}
+
+type fakeSkipSegmentLister map[int64][]db.SkipSegment
+
+func (f fakeSkipSegmentLister) ForTracks(context.Context, []int64) (map[int64][]db.SkipSegment, error) {
+	return f, nil
+}
+
+func TestPlaybackURLsCarrySkipSegments(t *testing.T) {
+	track := &db.Track{ID: 42, StorageKey: sql.NullString{String: "audio/track-42.mp3", Valid: true}}
+	handler, _ := newPlaybackHandlerForTrack(track, true, &fakePlaybackStorage{info: map[string]*storage.ObjectInfo{
+		"audio/track-42.mp3": {Size: 100, ContentType: "audio/mpeg"},
+	}})
+	handler.WithSkipSegments(fakeSkipSegmentLister{42: {{Category: "sponsor", StartMs: 60000, EndMs: 75000, Source: db.SkipSegmentSourceSponsorBlock}}})
+
+	rec := playbackRequest(t, handler.CreatePlaybackURLs, `{"trackIds":[42]}`)
+	var resp PlaybackURLResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (status %d)", err, rec.Code)
+	}
+	want := []PlaybackSkipSegment{{Category: "sponsor", StartMs: 60000, EndMs: 75000}}
+	if len(resp.URLs) != 1 || !reflect.DeepEqual(resp.URLs[0].SkipSegments, want) {
+		t.Fatalf("urls = %+v", resp.URLs)
+	}
+}
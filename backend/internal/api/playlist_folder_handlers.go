@@ -0,0 +1,433 @@
+package api
+
+import (
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"io"
+	"net/http"
+	"sort"
+	"strconv"
+	"time"
+	"unicode/utf8"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// PlaylistFolderHandlers serves playlist folders, pins, and the organized
+// playlist tree. Folders owned by other users are reported as not found.
+type PlaylistFolderHandlers struct {
+	folderRepo *db.PlaylistFolderRepository
+}
+
+func NewPlaylistFolderHandlers(folderRepo *db.PlaylistFolderRepository) *PlaylistFolderHandlers {
+	return &PlaylistFolderHandlers{folderRepo: folderRepo}
+}
+
+type CreatePlaylistFolderRequest struct {
+	Name     string `json:"name"`
+	ParentID *int64 `json:"parentId,omitempty"`
+}
+
+type RenamePlaylistFolderRequest struct {
+	Name string `json:"name"`
+}
+
+// MovePlaylistFolderRequest places a folder under ParentID (null or omitted
+// for the root) at Position (omitted to append).
+type MovePlaylistFolderRequest struct {
+	ParentID *int64 `json:"parentId"`
+	Position *int   `json:"position,omitempty"`
+}
+
+// MovePlaylistRequest places a playlist in FolderID (null or omitted for the
+// root) at Position (omitted to append).
+type MovePlaylistRequest struct {
+	FolderID *int64 `json:"folderId"`
+	Position *int   `json:"position,omitempty"`
+}
+
+type PinPlaylistRequest struct {
+	Position *int `json:"position,omitempty"`
+}
+
+type PlaylistFolderResponse struct {
+	ID        int64     `json:"id"`
+	Name      string    `json:"name"`
+	ParentID  *int64    `json:"parentId"`
+	Position  int       `json:"position"`
+	CreatedAt time.Time `json:"createdAt"`
+	UpdatedAt time.Time `json:"updatedAt"`
+}
+
+// PlaylistFolderNode is a folder with its subfolders and playlists, each in
+// display order.
+type PlaylistFolderNode struct {
+	PlaylistFolderResponse
+	Folders   []PlaylistFolderNode `json:"folders"`
+	Playlists []PlaylistResponse   `json:"playlists"`
+}
+
+// PlaylistTreeResponse is the user's whole playlist organization: pinned
+// playlists in pin order, then the root folders and playlists. Pinned
+// playlists also appear in their folder.
+type PlaylistTreeResponse struct {
+	Pinned    []PlaylistResponse   `json:"pinned"`
+	Folders   []PlaylistFolderNode `json:"folders"`
+	Playlists []PlaylistResponse   `json:"playlists"`
+}
+
+// GetTree handles GET /api/v1/playlist-tree
+func (h *PlaylistFolderHandlers) GetTree(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	h.writeTree(w, r, http.StatusOK)
+}
+
+// CreateFolder handles POST /api/v1/playlist-folders
+func (h *PlaylistFolderHandlers) CreateFolder(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	var req CreatePlaylistFolderRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if !validFolderName(w, req.Name) {
+		return
+	}
+
+	folder := &db.PlaylistFolder{
+		UserID:   userCtx.UserID,
+		ParentID: nullableID(req.ParentID),
+		Name:     req.Name,
+	}
+	if err := h.folderRepo.Create(r.Context(), folder); err != nil {
+		writeFolderError(w, err, "failed to create playlist folder")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusCreated, newPlaylistFolderResponse(*folder))
+}
+
+// RenameFolder handles PUT /api/v1/playlist-folders/{id}
+func (h *PlaylistFolderHandlers) RenameFolder(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	folderID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid folder ID")
+		return
+	}
+
+	folder, err := h.folderRepo.GetByID(r.Context(), folderID)
+	if err == nil && folder.UserID != userCtx.UserID {
+		err = db.ErrPlaylistFolderNotFound
+	}
+	if err != nil {
+		writeFolderError(w, err, "failed to get playlist folder")
+		return
+	}
+
+	var req RenamePlaylistFolderRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if !validFolderName(w, req.Name) {
+		return
+	}
+
+	folder.Name = req.Name
+	if err := h.folderRepo.Rename(r.Context(), folder); err != nil {
+		writeFolderError(w, err, "failed to rename playlist folder")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusOK, newPlaylistFolderResponse(*folder))
+}
+
+// MoveFolder handles POST /api/v1/playlist-folders/{id}/move
+func (h *PlaylistFolderHandlers) MoveFolder(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	folderID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid folder ID")
+		return
+	}
+
+	var req MovePlaylistFolderRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if !validTreePosition(w, req.Position) {
+		return
+	}
+
+	if err := h.folderRepo.Move(r.Context(), userCtx.UserID, folderID, nullableID(req.ParentID), req.Position); err != nil {
+		writeFolderError(w, err, "failed to move playlist folder")
+		return
+	}
+
+	h.writeTree(w, r, http.StatusOK)
+}
+
+// DeleteFolder handles DELETE /api/v1/playlist-folders/{id}. The folder's
+// contents move up to its parent.
+func (h *PlaylistFolderHandlers) DeleteFolder(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	folderID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid folder ID")
+		return
+	}
+
+	if err := h.folderRepo.Delete(r.Context(), userCtx.UserID, folderID); err != nil {
+		writeFolderError(w, err, "failed to delete playlist folder")
+		return
+	}
+
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// MovePlaylist handles POST /api/v1/playlists/{id}/move
+func (h *PlaylistFolderHandlers) MovePlaylist(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	var req MovePlaylistRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if !validTreePosition(w, req.Position) {
+		return
+	}
+
+	if err := h.folderRepo.MovePlaylist(r.Context(), userCtx.UserID, playlistID, nullableID(req.FolderID), req.Position); err != nil {
+		writeFolderError(w, err, "failed to move playlist")
+		return
+	}
+
+	h.writeTree(w, r, http.StatusOK)
+}
+
+// PinPlaylist handles PUT /api/v1/playlists/{id}/pin
+func (h *PlaylistFolderHandlers) PinPlaylist(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	// The body is optional; without one the playlist is pinned last.
+	var req PinPlaylistRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil && !errors.Is(err, io.EOF) {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if !validTreePosition(w, req.Position) {
+		return
+	}
+
+	if err := h.folderRepo.Pin(r.Context(), userCtx.UserID, playlistID, req.Position); err != nil {
+		writeFolderError(w, err, "failed to pin playlist")
+		return
+	}
+
+	h.writeTree(w, r, http.StatusOK)
+}
+
+// UnpinPlaylist handles DELETE /api/v1/playlists/{id}/pin
+func (h *PlaylistFolderHandlers) UnpinPlaylist(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	if err := h.folderRepo.Unpin(r.Context(), userCtx.UserID, playlistID); err != nil {
+		writeFolderError(w, err, "failed to unpin playlist")
+		return
+	}
+
+	h.writeTree(w, r, http.StatusOK)
+}
+
+func (h *PlaylistFolderHandlers) writeTree(w http.ResponseWriter, r *http.Request, status int) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	tree, err := h.folderRepo.Tree(r.Context(), userCtx.UserID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load playlist tree")
+		return
+	}
+	writePlaylistJSON(w, status, buildPlaylistTree(tree))
+}
+
+// buildPlaylistTree nests the repository's flat, ordered rows. Rows whose
+// folder is missing are placed at the root rather than dropped.
+func buildPlaylistTree(tree *db.PlaylistTree) PlaylistTreeResponse {
+	known := make(map[int64]bool, len(tree.Folders))
+	for _, f := range tree.Folders {
+		known[f.ID] = true
+	}
+	childFolders := make(map[int64][]db.PlaylistFolder)
+	var rootFolders []db.PlaylistFolder
+	for _, f := range tree.Folders {
+		if f.ParentID.Valid && known[f.ParentID.Int64] {
+			childFolders[f.ParentID.Int64] = append(childFolders[f.ParentID.Int64], f)
+		} else {
+			rootFolders = append(rootFolders, f)
+		}
+	}
+
+	folderPlaylists := make(map[int64][]PlaylistResponse)
+	resp := PlaylistTreeResponse{
+		Pinned:    []PlaylistResponse{},
+		Folders:   []PlaylistFolderNode{},
+		Playlists: []PlaylistResponse{},
+	}
+	var pinned []db.OrganizedPlaylist
+	for _, p := range tree.Playlists {
+		item := newPlaylistResponse(&p.PlaylistWithTracks)
+		if p.FolderID.Valid && known[p.FolderID.Int64] {
+			folderPlaylists[p.FolderID.Int64] = append(folderPlaylists[p.FolderID.Int64], item)
+		} else {
+			resp.Playlists = append(resp.Playlists, item)
+		}
+		if p.PinPosition.Valid {
+			pinned = append(pinned, p)
+		}
+	}
+	sort.SliceStable(pinned, func(i, j int) bool {
+		return pinned[i].PinPosition.Int32 < pinned[j].PinPosition.Int32
+	})
+	for _, p := range pinned {
+		resp.Pinned = append(resp.Pinned, newPlaylistResponse(&p.PlaylistWithTracks))
+	}
+
+	var build func(f db.PlaylistFolder) PlaylistFolderNode
+	build = func(f db.PlaylistFolder) PlaylistFolderNode {
+		node := PlaylistFolderNode{
+			PlaylistFolderResponse: newPlaylistFolderResponse(f),
+			Folders:                []PlaylistFolderNode{},
+			Playlists:              folderPlaylists[f.ID],
+		}
+		if node.Playlists == nil {
+			node.Playlists = []PlaylistResponse{}
+		}
+		for _, child := range childFolders[f.ID] {
+			node.Folders = append(node.Folders, build(child))
+		}
+		return node
+	}
+	for _, f := range rootFolders {
+		resp.Folders = append(resp.Folders, build(f))
+	}
+
+	return resp
+}
+
+func newPlaylistFolderResponse(f db.PlaylistFolder) PlaylistFolderResponse {
+	resp := PlaylistFolderResponse{
+		ID:        f.ID,
+		Name:      f.Name,
+		Position:  f.Position,
+		CreatedAt: f.CreatedAt,
+		UpdatedAt: f.UpdatedAt,
+	}
+	if f.ParentID.Valid {
+		parentID := f.ParentID.Int64
+		resp.ParentID = &parentID
+	}
+	return resp
+}
+
+func nullableID(id *int64) sql.NullInt64 {
+	if id == nil {
+		return sql.NullInt64{}
+	}
+	return sql.NullInt64{Int64: *id, Valid: true}
+}
+
+func validFolderName(w http.ResponseWriter, name string) bool {
+	if name == "" {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name is required")
+		return false
+	}
+	if utf8.RuneCountInString(name) > maxPlaylistNameLength {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name must be at most "+strconv.Itoa(maxPlaylistNameLength)+" characters")
+		return false
+	}
+	return true
+}
+
+func validTreePosition(w http.ResponseWriter, position *int) bool {
+	if position != nil && *position < 0 {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "position must be non-negative")
+		return false
+	}
+	return true
+}
+
+func writeFolderError(w http.ResponseWriter, err error, fallback string) {
+	switch {
+	case errors.Is(err, db.ErrPlaylistFolderNotFound):
+		writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist folder not found")
+	case errors.Is(err, db.ErrPlaylistNotFound):
+		writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
+	case errors.Is(err, db.ErrPlaylistNotOwned):
+		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
+	case errors.Is(err, db.ErrPlaylistFolderCycle):
+		writePlaylistError(w, http.StatusConflict, "CONFLICT", "a folder cannot be moved into itself or one of its subfolders")
+	case errors.Is(err, db.ErrPlaylistFolderTooDeep):
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "folders can be nested at most "+strconv.Itoa(db.MaxPlaylistFolderDepth)+" levels deep")
+	default:
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", fallback)
+	}
+}
@@ -0,0 +1,80 @@
+package api
+
+import (
+	"database/sql"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func organizedPlaylist(id int64, folder int64, pin int32) db.OrganizedPlaylist {
+	p := db.OrganizedPlaylist{}
+	p.ID = id
+	if folder != 0 {
+		p.FolderID = sql.NullInt64{Int64: folder, Valid: true}
+	}
+	if pin >= 0 {
+		p.PinPosition = sql.NullInt32{Int32: pin, Valid: true}
+	}
+	return p
+}
+
+func TestBuildPlaylistTreeNestsInOrder(t *testing.T) {
+	tree := &db.PlaylistTree{
+		Folders: []db.PlaylistFolder{
+			{ID: 1, Name: "Moods"},
+			{ID: 2, Name: "Work"},
+			{ID: 3, Name: "Calm", ParentID: sql.NullInt64{Int64: 1, Valid: true}},
+			{ID: 4, Name: "Orphan", ParentID: sql.NullInt64{Int64: 99, Valid: true}},
+		},
+		Playlists: []db.OrganizedPlaylist{
+			organizedPlaylist(10, 0, -1),
+			organizedPlaylist(11, 0, 1),
+			organizedPlaylist(12, 3, 0),
+			organizedPlaylist(13, 3, -1),
+			organizedPlaylist(14, 77, -1),
+		},
+	}
+
+	resp := buildPlaylistTree(tree)
+
+	if got := playlistIDs(resp.Pinned); !equalIDs(got, []int64{12, 11}) {
+		t.Fatalf("pinned = %v, want [12 11]", got)
+	}
+	if got := playlistIDs(resp.Playlists); !equalIDs(got, []int64{10, 11, 14}) {
+		t.Fatalf("root playlists = %v, want [10 11 14]", got)
+	}
+	if len(resp.Folders) != 3 || resp.Folders[0].ID != 1 || resp.Folders[1].ID != 2 || resp.Folders[2].ID != 4 {
+		t.Fatalf("root folders = %+v", resp.Folders)
+	}
+	moods := resp.Folders[0]
+	if len(moods.Folders) != 1 || moods.Folders[0].ID != 3 || *moods.Folders[0].ParentID != 1 {
+		t.Fatalf("Moods subfolders = %+v", moods.Folders)
+	}
+	if got := playlistIDs(moods.Folders[0].Playlists); !equalIDs(got, []int64{12, 13}) {
+		t.Fatalf("Calm playlists = %v, want [12 13]", got)
+	}
+	if resp.Folders[1].Playlists == nil || resp.Folders[1].Folders == nil {
+		t.Fatal("empty folders must serialize as [] rather than null")
+	}
+}
+
+func playlistIDs(ps []PlaylistResponse) []int64 {
+	ids := make([]int64, len(ps))
+	for i, p := range ps {
+		ids[i] = p.ID
+	}
+	return ids
+}
+
+func equalIDs(a, b []int64) bool {
+	if len(a) != len(b) {
+		return false
+	}
+	for i := range a {
+		if a[i] != b[i] {
+			return false
+		}
+	}
+	return true
+}
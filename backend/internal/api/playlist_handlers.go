@@ -4,16 +4,27 @@ import (
 	"database/sql"
 	"encoding/json"
 	"errors"
+	"io"
 	"net/http"
 	"strconv"
 	"time"
+	"unicode/utf8"
 
 	"github.com/google/uuid"
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
+// maxBulkTrackIDs caps the track IDs accepted by one bulk request; a large
+// album fits comfortably while a single request still runs in one short
+// transaction.
+const maxBulkTrackIDs = 1000
+
+// maxPlaylistNameLength matches the playlists.name column.
+const maxPlaylistNameLength = 255
+
 type PlaylistHandlers struct {
 	playlistRepo *db.PlaylistRepository
 	trackRepo    *db.TrackRepository
@@ -61,14 +72,65 @@ type ReorderTrackRequest struct {
 	NewPosition int   `json:"newPosition"`
 }
 
+// MoveTrackRangeRequest moves Count tracks starting at FromPosition so the
+// block starts at ToPosition in the resulting order.
+type MoveTrackRangeRequest struct {
+	FromPosition int `json:"fromPosition"`
+	Count        int `json:"count"`
+	ToPosition   int `json:"toPosition"`
+}
+
+type DeduplicateTracksResponse struct {
+	Removed  []int64          `json:"removed"`
+	Playlist PlaylistResponse `json:"playlist"`
+}
+
+type CopyPlaylistRequest struct {
+	Name string `json:"name,omitempty"`
+}
+
+type MergePlaylistsRequest struct {
+	SourcePlaylistIDs []int64 `json:"sourcePlaylistIds"`
+	DeleteSources     bool    `json:"deleteSources,omitempty"`
+}
+
+type RevertPlaylistRequest struct {
+	RevisionID int64 `json:"revisionId"`
+}
+
+// PlaylistRevisionResponse describes one recorded change. TracksAdded and
+// TracksRemoved are omitted for the oldest revision of a playlist that
+// predates history, since there is nothing to compare it with.
+type PlaylistRevisionResponse struct {
+	ID            int64     `json:"id"`
+	Action        string    `json:"action"`
+	Name          string    `json:"name"`
+	Description   string    `json:"description,omitempty"`
+	CoverURL      string    `json:"coverUrl,omitempty"`
+	IsPublic      bool      `json:"isPublic"`
+	TrackCount    int       `json:"trackCount"`
+	TracksAdded   *int      `json:"tracksAdded,omitempty"`
+	TracksRemoved *int      `json:"tracksRemoved,omitempty"`
+	CreatedAt     time.Time `json:"createdAt"`
+}
+
+type PaginatedPlaylistRevisionResponse struct {
+	Data   []PlaylistRevisionResponse `json:"data"`
+	Total  int                        `json:"total"`
+	Limit  int                        `json:"limit"`
+	Offset int                        `json:"offset"`
+}
+
 type PlaylistResponse struct {
 	ID          int64     `json:"id"`
 	Name        string    `json:"name"`
 	Description string    `json:"description,omitempty"`
 	CoverURL    string    `json:"coverUrl,omitempty"`
 	IsPublic    bool      `json:"isPublic"`
+	ReadOnly    bool      `json:"readOnly"`
 	TrackCount  int       `json:"trackCount"`
 	DurationMs  int64     `json:"durationMs"`
+	SizeBytes   int64     `json:"sizeBytes"`
 	CreatedAt   time.Time `json:"createdAt"`
 	UpdatedAt   time.Time `json:"updatedAt"`
 }
@@ -79,8 +141,10 @@ type PlaylistWithTracksResponse struct {
 	Description string          `json:"description,omitempty"`
 	CoverURL    string          `json:"coverUrl,omitempty"`
 	IsPublic    bool            `json:"isPublic"`
+	ReadOnly    bool            `json:"readOnly"`
 	TrackCount  int             `json:"trackCount"`
 	DurationMs  int64           `json:"durationMs"`
+	SizeBytes   int64           `json:"sizeBytes"`
 	CreatedAt   time.Time       `json:"createdAt"`
 	UpdatedAt   time.Time       `json:"updatedAt"`
 	Tracks      []TrackResponse `json:"tracks"`
@@ -121,14 +185,23 @@ func (h *PlaylistHandlers) ListPlaylists(w http.ResponseWriter, r *http.Request)
 		return
 	}
 
-	limit, offset := parsePlaylistPagination(r)
-
+	query := newQueryParams(r)
 	params := db.ListPlaylistsParams{
-		Query:  r.URL.Query().Get("q"),
-		Sort:   r.URL.Query().Get("sort"),
-		Order:  r.URL.Query().Get("order"),
-		Limit:  limit,
-		Offset: offset,
+		Query:  query.Text("q", maxQueryTextRunes),
+		Sort:   query.Enum("sort", "", "updated_at", "name", "track_count"),
+		Order:  query.Enum("order", "", "asc", "desc"),
+		Limit:  query.Limit(20),
+		Offset: query.Offset(),
+	}
+	if !query.Valid(w, r) {
+		return
+	}
+	limit, offset := params.Limit, params.Offset
+
+	if version, err := h.playlistRepo.ListVersion(r.Context(), userCtx.UserID); err == nil {
+		if writeNotModified(w, r, listETag("playlists", version, r)) {
+			return
+		}
 	}
 
 	playlists, total, err := h.playlistRepo.GetByUserID(r.Context(), userCtx.UserID, params)
@@ -139,7 +212,7 @@ func (h *PlaylistHandlers) ListPlaylists(w http.ResponseWriter, r *http.Request)
 
 	responses := make([]PlaylistResponse, 0, len(playlists))
 	for _, p := range playlists {
-		responses = append(responses, newPlaylistResponse(p.Playlist, p.TrackCount, p.DurationMs))
+		responses = append(responses, newPlaylistResponse(&p))
 	}
 
 	writePlaylistJSON(w, http.StatusOK, PaginatedPlaylistResponse{
@@ -182,7 +255,7 @@ func (h *PlaylistHandlers) CreatePlaylist(w http.ResponseWriter, r *http.Request
 		return
 	}
 
-	writePlaylistJSON(w, http.StatusCreated, newPlaylistResponse(*playlist, 0, 0))
+	writePlaylistJSON(w, http.StatusCreated, newPlaylistResponse(&db.PlaylistWithTracks{Playlist: *playlist}))
 }
 
 // GetPlaylist handles GET /api/v1/playlists/{id}
@@ -199,7 +272,7 @@ func (h *PlaylistHandlers) GetPlaylist(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	playlist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	owner, version, err := h.playlistRepo.Version(r.Context(), playlistID)
 	if err != nil {
 		if errors.Is(err, db.ErrPlaylistNotFound) {
 			writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
@@ -210,11 +283,25 @@ func (h *PlaylistHandlers) GetPlaylist(w http.ResponseWriter, r *http.Request) {
 	}
 
 	// Check ownership
-	if playlist.UserID != userCtx.UserID {
+	if owner != userCtx.UserID {
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to access this playlist")
 		return
 	}
 
+	if writeNotModified(w, r, resourceETag(version)) {
+		return
+	}
+
+	playlist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil {
+		if errors.Is(err, db.ErrPlaylistNotFound) {
+			writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
+			return
+		}
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get playlist")
+		return
+	}
+
 	writePlaylistJSON(w, http.StatusOK, newPlaylistWithTracksResponse(playlist, mapTrackResponses(playlist.Tracks)))
 }
 
@@ -247,6 +334,10 @@ func (h *PlaylistHandlers) UpdatePlaylist(w http.ResponseWriter, r *http.Request
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
 		return
 	}
+	if playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return
+	}
 
 	var req UpdatePlaylistRequest
 	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
@@ -264,10 +355,17 @@ func (h *PlaylistHandlers) UpdatePlaylist(w http.ResponseWriter, r *http.Request
 	playlist.CoverURL = sql.NullString{String: req.CoverURL, Valid: req.CoverURL != ""}
 	playlist.IsPublic = req.IsPublic
 
-	if err := h.playlistRepo.Update(r.Context(), playlist); err != nil {
+	if err := h.playlistRepo.Update(r.Context(), playlist, ifMatch(r)); err != nil {
+		if errors.Is(err, db.ErrPlaylistVersionMismatch) {
+			h.writePlaylistConflict(w, r, playlistID)
+			return
+		}
 		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to update playlist")
 		return
 	}
+	if _, version, err := h.playlistRepo.Version(r.Context(), playlistID); err == nil {
+		w.Header().Set("ETag", resourceETag(version))
+	}
 
 	// Get updated playlist with track count
 	updatedPlaylist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
@@ -276,7 +374,25 @@ func (h *PlaylistHandlers) UpdatePlaylist(w http.ResponseWriter, r *http.Request
 		return
 	}
 
-	writePlaylistJSON(w, http.StatusOK, newPlaylistResponse(updatedPlaylist.Playlist, updatedPlaylist.TrackCount, updatedPlaylist.DurationMs))
+	writePlaylistJSON(w, http.StatusOK, newPlaylistResponse(updatedPlaylist))
+}
+
+// writePlaylistConflict answers an update sent with a stale If-Match with the
+// playlist as GetPlaylist now returns it. The version is read first, so a
+// concurrent edit can only make it stale, never newer than the body.
+func (h *PlaylistHandlers) writePlaylistConflict(w http.ResponseWriter, r *http.Request, playlistID int64) {
+	_, version, err := h.playlistRepo.Version(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get playlist")
+		return
+	}
+	current, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get playlist")
+		return
+	}
+	writeVersionConflict(w, r, "playlist was modified since it was fetched; reapply the change to the current playlist and retry",
+		version, newPlaylistWithTracksResponse(current, mapTrackResponses(current.Tracks)))
 }
 
 // DeletePlaylist handles DELETE /api/v1/playlists/{id}
@@ -308,6 +424,10 @@ func (h *PlaylistHandlers) DeletePlaylist(w http.ResponseWriter, r *http.Request
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to delete this playlist")
 		return
 	}
+	if playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return
+	}
 
 	if err := h.playlistRepo.Delete(r.Context(), playlistID); err != nil {
 		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to delete playlist")
@@ -346,6 +466,10 @@ func (h *PlaylistHandlers) AddTracks(w http.ResponseWriter, r *http.Request) {
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
 		return
 	}
+	if playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return
+	}
 
 	var req AddTracksRequest
 	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
@@ -357,18 +481,20 @@ func (h *PlaylistHandlers) AddTracks(w http.ResponseWriter, r *http.Request) {
 		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds is required")
 		return
 	}
+	if len(req.TrackIDs) > maxBulkTrackIDs {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds must contain at most "+strconv.Itoa(maxBulkTrackIDs)+" entries")
+		return
+	}
 
 	// Verify tracks exist
-	for _, trackID := range req.TrackIDs {
-		_, err := h.trackRepo.GetByID(r.Context(), trackID)
-		if err != nil {
-			if errors.Is(err, db.ErrTrackNotFound) {
-				writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "track not found: "+strconv.FormatInt(trackID, 10))
-				return
-			}
-			writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify track")
-			return
-		}
+	missing, err := h.trackRepo.MissingIDs(r.Context(), userCtx.UserID, req.TrackIDs)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to verify track")
+		return
+	}
+	if len(missing) > 0 {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "track not found: "+strconv.FormatInt(missing[0], 10))
+		return
 	}
 
 	report, err := h.playlistRepo.AddTracks(r.Context(), playlistID, req.TrackIDs)
@@ -387,7 +513,7 @@ func (h *PlaylistHandlers) AddTracks(w http.ResponseWriter, r *http.Request) {
 	writePlaylistJSON(w, http.StatusOK, AddTracksResponse{
 		Added:    report.Added,
 		Skipped:  report.Skipped,
-		Playlist: newPlaylistResponse(updatedPlaylist.Playlist, updatedPlaylist.TrackCount, updatedPlaylist.DurationMs),
+		Playlist: newPlaylistResponse(updatedPlaylist),
 	})
 }
 
@@ -420,6 +546,10 @@ func (h *PlaylistHandlers) BatchRemoveTracks(w http.ResponseWriter, r *http.Requ
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
 		return
 	}
+	if playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return
+	}
 
 	var req BatchRemoveTracksRequest
 	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
@@ -431,6 +561,10 @@ func (h *PlaylistHandlers) BatchRemoveTracks(w http.ResponseWriter, r *http.Requ
 		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds is required")
 		return
 	}
+	if len(req.TrackIDs) > maxBulkTrackIDs {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trackIds must contain at most "+strconv.Itoa(maxBulkTrackIDs)+" entries")
+		return
+	}
 
 	if err := h.playlistRepo.RemoveTracks(r.Context(), playlistID, req.TrackIDs); err != nil {
 		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to remove tracks")
@@ -482,6 +616,10 @@ func (h *PlaylistHandlers) RemoveTrack(w http.ResponseWriter, r *http.Request) {
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
 		return
 	}
+	if playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return
+	}
 
 	if err := h.playlistRepo.RemoveTrack(r.Context(), playlistID, trackID); err != nil {
 		if errors.Is(err, db.ErrTrackNotInPlaylist) {
@@ -524,6 +662,10 @@ func (h *PlaylistHandlers) ReorderTracks(w http.ResponseWriter, r *http.Request)
 		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
 		return
 	}
+	if playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return
+	}
 
 	var req ReorderTrackRequest
 	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
@@ -541,7 +683,18 @@ func (h *PlaylistHandlers) ReorderTracks(w http.ResponseWriter, r *http.Request)
 		return
 	}
 
-	if err := h.playlistRepo.ReorderTrack(r.Context(), playlistID, req.TrackID, req.NewPosition); err != nil {
+	// Reorders are position-relative, so applying one computed against a
+	// stale track list silently scrambles another client's edit.
+	precondition := requireIfMatch(w, r)
+	if precondition == nil {
+		return
+	}
+
+	if err := h.playlistRepo.ReorderTrack(r.Context(), playlistID, req.TrackID, req.NewPosition, precondition); err != nil {
+		if errors.Is(err, db.ErrPlaylistVersionMismatch) {
+			writePreconditionFailed(w, r, "playlist was modified since it was fetched; reload and retry")
+			return
+		}
 		if errors.Is(err, db.ErrTrackNotInPlaylist) {
 			writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "track not in playlist")
 			return
@@ -550,6 +703,13 @@ func (h *PlaylistHandlers) ReorderTracks(w http.ResponseWriter, r *http.Request)
 		return
 	}
 
+	// Hand back the new version so the client can chain the next reorder. It
+	// is read before the body so a concurrent edit can only make it stale
+	// (a 412 later), never newer than what the client received.
+	if _, version, err := h.playlistRepo.Version(r.Context(), playlistID); err == nil {
+		w.Header().Set("ETag", resourceETag(version))
+	}
+
 	// Return updated playlist with tracks
 	updatedPlaylist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
 	if err != nil {
@@ -560,17 +720,360 @@ func (h *PlaylistHandlers) ReorderTracks(w http.ResponseWriter, r *http.Request)
 	writePlaylistJSON(w, http.StatusOK, newPlaylistWithTracksResponse(updatedPlaylist, mapTrackResponses(updatedPlaylist.Tracks)))
 }
 
+// MoveTrackRange handles POST /api/v1/playlists/{id}/tracks/move-range
+func (h *PlaylistHandlers) MoveTrackRange(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	if _, ok := h.writablePlaylist(w, r, playlistID, userCtx.UserID); !ok {
+		return
+	}
+
+	var req MoveTrackRangeRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+
+	if req.Count < 1 {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "count must be positive")
+		return
+	}
+	if req.FromPosition < 0 || req.ToPosition < 0 {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "positions must be non-negative")
+		return
+	}
+
+	// Like single-track reorders, range moves are position-relative.
+	precondition := requireIfMatch(w, r)
+	if precondition == nil {
+		return
+	}
+
+	if err := h.playlistRepo.MoveTrackRange(r.Context(), playlistID, req.FromPosition, req.Count, req.ToPosition, precondition); err != nil {
+		if errors.Is(err, db.ErrPlaylistVersionMismatch) {
+			writePreconditionFailed(w, r, "playlist was modified since it was fetched; reload and retry")
+			return
+		}
+		if errors.Is(err, db.ErrInvalidTrackRange) {
+			writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "range is outside the playlist")
+			return
+		}
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to move tracks")
+		return
+	}
+
+	if _, version, err := h.playlistRepo.Version(r.Context(), playlistID); err == nil {
+		w.Header().Set("ETag", resourceETag(version))
+	}
+
+	updatedPlaylist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get updated playlist")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusOK, newPlaylistWithTracksResponse(updatedPlaylist, mapTrackResponses(updatedPlaylist.Tracks)))
+}
+
+// DeduplicateTracks handles POST /api/v1/playlists/{id}/tracks/dedupe
+func (h *PlaylistHandlers) DeduplicateTracks(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	if _, ok := h.writablePlaylist(w, r, playlistID, userCtx.UserID); !ok {
+		return
+	}
+
+	removed, err := h.playlistRepo.DeduplicateTracks(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to deduplicate playlist")
+		return
+	}
+
+	updatedPlaylist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get updated playlist")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusOK, DeduplicateTracksResponse{
+		Removed:  removed,
+		Playlist: newPlaylistResponse(updatedPlaylist),
+	})
+}
+
+// CopyPlaylist handles POST /api/v1/playlists/{id}/copy
+func (h *PlaylistHandlers) CopyPlaylist(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	source, ok := h.ownedPlaylist(w, r, playlistID, userCtx.UserID)
+	if !ok {
+		return
+	}
+
+	// The body is optional; an empty one copies with the default name.
+	var req CopyPlaylistRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil && !errors.Is(err, io.EOF) {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+
+	name := req.Name
+	if name == "" {
+		name = copyName(source.Name)
+	}
+	if utf8.RuneCountInString(name) > maxPlaylistNameLength {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "name must be at most "+strconv.Itoa(maxPlaylistNameLength)+" characters")
+		return
+	}
+
+	// Copies start private; sharing is an explicit choice.
+	copied := &db.Playlist{
+		UserID:      userCtx.UserID,
+		Name:        name,
+		Description: source.Description,
+		CoverURL:    source.CoverURL,
+	}
+	if err := h.playlistRepo.Copy(r.Context(), playlistID, copied); err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to copy playlist")
+		return
+	}
+
+	created, err := h.playlistRepo.GetByIDWithTracks(r.Context(), copied.ID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get copied playlist")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusCreated, newPlaylistResponse(created))
+}
+
+// MergePlaylists handles POST /api/v1/playlists/{id}/merge
+func (h *PlaylistHandlers) MergePlaylists(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	var req MergePlaylistsRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+
+	if len(req.SourcePlaylistIDs) == 0 {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "sourcePlaylistIds is required")
+		return
+	}
+	seen := make(map[int64]bool, len(req.SourcePlaylistIDs))
+	for _, sourceID := range req.SourcePlaylistIDs {
+		if sourceID == playlistID {
+			writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "a playlist cannot be merged into itself")
+			return
+		}
+		if seen[sourceID] {
+			writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "duplicate source playlist: "+strconv.FormatInt(sourceID, 10))
+			return
+		}
+		seen[sourceID] = true
+	}
+
+	report, err := h.playlistRepo.Merge(r.Context(), userCtx.UserID, playlistID, req.SourcePlaylistIDs, req.DeleteSources)
+	if err != nil {
+		if errors.Is(err, db.ErrPlaylistNotFound) {
+			writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
+			return
+		}
+		if errors.Is(err, db.ErrPlaylistNotOwned) {
+			writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
+			return
+		}
+		if errors.Is(err, db.ErrPlaylistReadOnly) {
+			writePlaylistReadOnly(w)
+			return
+		}
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to merge playlists")
+		return
+	}
+
+	updatedPlaylist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get updated playlist")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusOK, AddTracksResponse{
+		Added:    report.Added,
+		Skipped:  report.Skipped,
+		Playlist: newPlaylistResponse(updatedPlaylist),
+	})
+}
+
+// PlaylistHistory handles GET /api/v1/playlists/{id}/history
+func (h *PlaylistHandlers) PlaylistHistory(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	query := newQueryParams(r)
+	limit := query.Limit(20)
+	offset := query.Offset()
+	if !query.Valid(w, r) {
+		return
+	}
+
+	if _, ok := h.ownedPlaylist(w, r, playlistID, userCtx.UserID); !ok {
+		return
+	}
+
+	revisions, total, err := h.playlistRepo.History(r.Context(), playlistID, limit, offset)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get playlist history")
+		return
+	}
+
+	responses := make([]PlaylistRevisionResponse, 0, len(revisions))
+	for _, rev := range revisions {
+		responses = append(responses, newPlaylistRevisionResponse(rev))
+	}
+
+	writePlaylistJSON(w, http.StatusOK, PaginatedPlaylistRevisionResponse{
+		Data:   responses,
+		Total:  total,
+		Limit:  limit,
+		Offset: offset,
+	})
+}
+
+// RevertPlaylist handles POST /api/v1/playlists/{id}/revert
+func (h *PlaylistHandlers) RevertPlaylist(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlaylistError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+
+	playlistID, err := parsePlaylistID(r)
+	if err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid playlist ID")
+		return
+	}
+
+	var req RevertPlaylistRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	if req.RevisionID <= 0 {
+		writePlaylistError(w, http.StatusBadRequest, "VALIDATION_ERROR", "revisionId is required")
+		return
+	}
+
+	if _, ok := h.writablePlaylist(w, r, playlistID, userCtx.UserID); !ok {
+		return
+	}
+
+	if err := h.playlistRepo.Revert(r.Context(), playlistID, req.RevisionID); err != nil {
+		if errors.Is(err, db.ErrPlaylistRevisionNotFound) {
+			writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist revision not found")
+			return
+		}
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to revert playlist")
+		return
+	}
+
+	updatedPlaylist, err := h.playlistRepo.GetByIDWithTracks(r.Context(), playlistID)
+	if err != nil {
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get updated playlist")
+		return
+	}
+
+	writePlaylistJSON(w, http.StatusOK, newPlaylistWithTracksResponse(updatedPlaylist, mapTrackResponses(updatedPlaylist.Tracks)))
+}
+
 // Helper functions
 
-// newPlaylistResponse builds a PlaylistResponse from a base playlist plus its
-// aggregate track count and duration.
-func newPlaylistResponse(p db.Playlist, trackCount int, durationMs int64) PlaylistResponse {
+func newPlaylistRevisionResponse(rev db.PlaylistRevision) PlaylistRevisionResponse {
+	resp := PlaylistRevisionResponse{
+		ID:         rev.ID,
+		Action:     rev.Action,
+		Name:       rev.Name,
+		IsPublic:   rev.IsPublic,
+		TrackCount: len(rev.TrackIDs),
+		CreatedAt:  rev.CreatedAt,
+	}
+	if rev.Description.Valid {
+		resp.Description = rev.Description.String
+	}
+	if rev.CoverURL.Valid {
+		resp.CoverURL = rev.CoverURL.String
+	}
+	if rev.TracksAdded.Valid {
+		added := int(rev.TracksAdded.Int32)
+		resp.TracksAdded = &added
+	}
+	if rev.TracksRemoved.Valid {
+		removed := int(rev.TracksRemoved.Int32)
+		resp.TracksRemoved = &removed
+	}
+	return resp
+}
+
+// newPlaylistResponse builds a PlaylistResponse from a playlist and its
+// aggregate track count, duration, and size, without its tracks.
+func newPlaylistResponse(p *db.PlaylistWithTracks) PlaylistResponse {
 	resp := PlaylistResponse{
 		ID:         p.ID,
 		Name:       p.Name,
 		IsPublic:   p.IsPublic,
-		TrackCount: trackCount,
-		DurationMs: durationMs,
+		ReadOnly:   p.ReadOnly,
+		TrackCount: p.TrackCount,
+		DurationMs: p.DurationMs,
+		SizeBytes:  p.SizeBytes,
 		CreatedAt:  p.CreatedAt,
 		UpdatedAt:  p.UpdatedAt,
 	}
@@ -590,8 +1093,10 @@ func newPlaylistWithTracksResponse(p *db.PlaylistWithTracks, tracks []TrackRespo
 		ID:         p.ID,
 		Name:       p.Name,
 		IsPublic:   p.IsPublic,
+		ReadOnly:   p.ReadOnly,
 		TrackCount: p.TrackCount,
 		DurationMs: p.DurationMs,
+		SizeBytes:  p.SizeBytes,
 		CreatedAt:  p.CreatedAt,
 		UpdatedAt:  p.UpdatedAt,
 		Tracks:     tracks,
@@ -657,6 +1162,53 @@ func mapTrackResponses(in []db.Track) []TrackResponse {
 	return tracks
 }
 
+// ownedPlaylist loads a playlist and checks it belongs to userID. It writes
+// the error response and returns false when either check fails.
+func (h *PlaylistHandlers) ownedPlaylist(w http.ResponseWriter, r *http.Request, playlistID int64, userID uuid.UUID) (*db.Playlist, bool) {
+	playlist, err := h.playlistRepo.GetByID(r.Context(), playlistID)
+	if err != nil {
+		if errors.Is(err, db.ErrPlaylistNotFound) {
+			writePlaylistError(w, http.StatusNotFound, "NOT_FOUND", "playlist not found")
+			return nil, false
+		}
+		writePlaylistError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to get playlist")
+		return nil, false
+	}
+
+	if playlist.UserID != userID {
+		writePlaylistError(w, http.StatusForbidden, "FORBIDDEN", "not authorized to modify this playlist")
+		return nil, false
+	}
+
+	return playlist, true
+}
+
+// writablePlaylist is ownedPlaylist for changes: it also refuses read-only
+// playlists.
+func (h *PlaylistHandlers) writablePlaylist(w http.ResponseWriter, r *http.Request, playlistID int64, userID uuid.UUID) (*db.Playlist, bool) {
+	playlist, ok := h.ownedPlaylist(w, r, playlistID, userID)
+	if ok && playlist.ReadOnly {
+		writePlaylistReadOnly(w)
+		return nil, false
+	}
+	return playlist, ok
+}
+
+func writePlaylistReadOnly(w http.ResponseWriter) {
+	writePlaylistError(w, http.StatusForbidden, "PLAYLIST_READ_ONLY", "playlist is read-only; copy it to make changes")
+}
+
+// copyName derives the default name for a copy, trimming the original so the
+// suffix still fits the column.
+func copyName(name string) string {
+	const suffix = " (copy)"
+	runes := []rune(name)
+	if limit := maxPlaylistNameLength - utf8.RuneCountInString(suffix); len(runes) > limit {
+		runes = runes[:limit]
+	}
+	return string(runes) + suffix
+}
+
 func parsePlaylistID(r *http.Request) (int64, error) {
 	idStr := r.PathValue("id")
 	if idStr == "" {
@@ -673,25 +1225,6 @@ func parseTrackID(r *http.Request) (int64, error) {
 	return strconv.ParseInt(idStr, 10, 64)
 }
 
-func parsePlaylistPagination(r *http.Request) (limit, offset int) {
-	limit = 20
-	offset = 0
-
-	if l := r.URL.Query().Get("limit"); l != "" {
-		if parsed, err := strconv.Atoi(l); err == nil && parsed > 0 {
-			limit = parsed
-		}
-	}
-
-	if o := r.URL.Query().Get("offset"); o != "" {
-		if parsed, err := strconv.Atoi(o); err == nil && parsed >= 0 {
-			offset = parsed
-		}
-	}
-
-	return limit, offset
-}
-
 func writePlaylistJSON(w http.ResponseWriter, status int, data interface{}) {
 	w.Header().Set("Content-Type", "application/json")
 	w.WriteHeader(status)
@@ -699,10 +1232,5 @@ func writePlaylistJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writePlaylistError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
@@ -11,6 +11,7 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/playlistimport"
 )
 
@@ -191,5 +192,5 @@ func writePlaylistImportJSON(w http.ResponseWriter, status int, data interface{}
 }
 
 func writePlaylistImportError(w http.ResponseWriter, status int, code, message string) {
-	writePlaylistImportJSON(w, status, ErrorResponse{Code: code, Message: message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
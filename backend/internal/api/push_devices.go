@@ -0,0 +1,181 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/push"
+)
+
+const (
+	// maxPushTokenLength bounds an FCM token or UnifiedPush endpoint URL.
+	maxPushTokenLength = 4096
+
+	// maxPushDeviceName is the longest device name, in runes.
+	maxPushDeviceName = 100
+)
+
+type pushDeviceStore interface {
+	Register(ctx context.Context, device *db.PushDevice) error
+	List(ctx context.Context, userID uuid.UUID) ([]db.PushDevice, error)
+	Delete(ctx context.Context, userID uuid.UUID, id int64) error
+}
+
+// PushDeviceHandlers let users register their phones for push
+// notifications, which reach them while the app has no WebSocket open.
+type PushDeviceHandlers struct {
+	devices    pushDeviceStore
+	fcmEnabled bool
+}
+
+// NewPushDeviceHandlers creates the handlers. FCM devices are refused
+// unless fcmEnabled, since the server could not reach them.
+func NewPushDeviceHandlers(devices pushDeviceStore, fcmEnabled bool) *PushDeviceHandlers {
+	return &PushDeviceHandlers{devices: devices, fcmEnabled: fcmEnabled}
+}
+
+// RegisterPushDeviceRequest registers a phone. Token is the UnifiedPush
+// endpoint URL or the FCM registration token; Types default to
+// push.DefaultTypes.
+type RegisterPushDeviceRequest struct {
+	Provider string   `json:"provider"`
+	Token    string   `json:"token"`
+	Name     string   `json:"name,omitempty"`
+	Types    []string `json:"types,omitempty"`
+}
+
+// PushDeviceResponse leaves out the token, which lets anyone holding it push
+// to the phone.
+type PushDeviceResponse struct {
+	ID        int64     `json:"id"`
+	Provider  string    `json:"provider"`
+	Name      string    `json:"name,omitempty"`
+	Types     []string  `json:"types"`
+	CreatedAt time.Time `json:"createdAt"`
+	UpdatedAt time.Time `json:"updatedAt"`
+}
+
+// PushDeviceListResponse lists the caller's devices and the providers the
+// server can deliver through.
+type PushDeviceListResponse struct {
+	Items     []PushDeviceResponse `json:"items"`
+	Providers []string             `json:"providers"`
+}
+
+func newPushDeviceResponse(d db.PushDevice) PushDeviceResponse {
+	return PushDeviceResponse{ID: d.ID, Provider: d.Provider, Name: d.Name, Types: d.Types, CreatedAt: d.CreatedAt, UpdatedAt: d.UpdatedAt}
+}
+
+// ListPushDevices handles GET /api/v1/push/devices.
+func (h *PushDeviceHandlers) ListPushDevices(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	devices, err := h.devices.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list push devices")
+		return
+	}
+	resp := PushDeviceListResponse{Items: make([]PushDeviceResponse, 0, len(devices)), Providers: []string{db.PushUnifiedPush}}
+	if h.fcmEnabled {
+		resp.Providers = append(resp.Providers, db.PushFCM)
+	}
+	for _, d := range devices {
+		resp.Items = append(resp.Items, newPushDeviceResponse(d))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// RegisterPushDevice handles POST /api/v1/push/devices. Registering a token
+// again replaces its name and types.
+func (h *PushDeviceHandlers) RegisterPushDevice(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req RegisterPushDeviceRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	req.Token, req.Name = strings.TrimSpace(req.Token), strings.TrimSpace(req.Name)
+	if msg := h.validatePushDevice(&req); msg != "" {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", msg)
+		return
+	}
+	device := &db.PushDevice{UserID: userCtx.UserID, Provider: req.Provider, Token: req.Token, Name: req.Name, Types: req.Types}
+	if err := h.devices.Register(r.Context(), device); err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to register push device")
+		return
+	}
+	writeLibraryJSON(w, http.StatusCreated, newPushDeviceResponse(*device))
+}
+
+// validatePushDevice checks req, filling in the default types, and returns
+// what is wrong with it.
+func (h *PushDeviceHandlers) validatePushDevice(req *RegisterPushDeviceRequest) string {
+	switch req.Provider {
+	case db.PushUnifiedPush:
+		if err := push.ValidEndpoint(req.Token); err != nil {
+			return err.Error()
+		}
+	case db.PushFCM:
+		if !h.fcmEnabled {
+			return "this server is not set up for FCM"
+		}
+	default:
+		return "provider must be unifiedpush or fcm"
+	}
+	if req.Token == "" || len(req.Token) > maxPushTokenLength {
+		return "token is required and must be at most 4096 bytes"
+	}
+	if utf8.RuneCountInString(req.Name) > maxPushDeviceName {
+		return "name must be at most 100 characters"
+	}
+	if req.Types == nil {
+		req.Types = push.DefaultTypes
+	}
+	for _, kind := range req.Types {
+		if !slices.Contains(db.NotificationTypes, kind) {
+			return "unknown notification type: " + kind
+		}
+	}
+	return ""
+}
+
+// DeletePushDevice handles DELETE /api/v1/push/devices/{id}.
+func (h *PushDeviceHandlers) DeletePushDevice(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	id, err := strconv.ParseInt(r.PathValue("id"), 10, 64)
+	if err != nil || id <= 0 {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid device ID")
+		return
+	}
+	err = h.devices.Delete(r.Context(), userCtx.UserID, id)
+	if errors.Is(err, db.ErrPushDeviceNotFound) {
+		writeLibraryError(w, http.StatusNotFound, "NOT_FOUND", "push device not found")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to delete push device")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
@@ -0,0 +1,77 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakePushDevices struct {
+	registered []db.PushDevice
+}
+
+func (f *fakePushDevices) Register(ctx context.Context, device *db.PushDevice) error {
+	device.ID = int64(len(f.registered) + 1)
+	device.CreatedAt, device.UpdatedAt = time.Now(), time.Now()
+	f.registered = append(f.registered, *device)
+	return nil
+}
+
+func (f *fakePushDevices) List(ctx context.Context, userID uuid.UUID) ([]db.PushDevice, error) {
+	return f.registered, nil
+}
+
+func (f *fakePushDevices) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	return db.ErrPushDeviceNotFound
+}
+
+func TestPushDeviceHandlers(t *testing.T) {
+	user := uuid.New()
+	devices := &fakePushDevices{}
+	h := NewPushDeviceHandlers(devices, false)
+	register := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/push/devices", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.RegisterPushDevice(rec, withUser(req, user))
+		return rec
+	}
+
+	for _, body := range []string{
+		`{"provider":"apns","token":"abc"}`,
+		`{"provider":"fcm","token":"abc"}`,
+		`{"provider":"unifiedpush","token":"http://push.example.test/up"}`,
+		`{"provider":"unifiedpush","token":"https://push.example.test/up","types":["spam"]}`,
+	} {
+		if rec := register(body); rec.Code != http.StatusBadRequest {
+			t.Errorf("register %s = %d", body, rec.Code)
+		}
+	}
+	rec := register(`{"provider":"unifiedpush","token":"https://push.example.test/up","name":" Pixel "}`)
+	if rec.Code != http.StatusCreated || strings.Contains(rec.Body.String(), "push.example.test") {
+		t.Fatalf("register = %d %s; want the token left out", rec.Code, rec.Body.String())
+	}
+	if got := devices.registered[0]; got.Name != "Pixel" || len(got.Types) != 2 {
+		t.Fatalf("registered %+v; want the default types", got)
+	}
+
+	rec = httptest.NewRecorder()
+	h.ListPushDevices(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/push/devices", nil), user))
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"providers":["unifiedpush"]`) {
+		t.Fatalf("list = %d %s", rec.Code, rec.Body.String())
+	}
+
+	req := httptest.NewRequest(http.MethodDelete, "/api/v1/push/devices/9", nil)
+	req.SetPathValue("id", "9")
+	rec = httptest.NewRecorder()
+	h.DeletePushDevice(rec, withUser(req, user))
+	if rec.Code != http.StatusNotFound {
+		t.Errorf("delete of a missing device = %d", rec.Code)
+	}
+}
@@ -0,0 +1,162 @@
+package api
+
+import (
+	"fmt"
+	"math"
+	"net/http"
+	"net/url"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+)
+
+// Shared query limits for list endpoints.
+const (
+	maxPageLimit      = 100
+	maxQueryTextRunes = 200
+)
+
+// queryParams reads typed query parameters and collects every violation so a
+// single 422 response lists all of them. Missing parameters take defaults;
+// present-but-garbage parameters are errors rather than silently ignored.
+type queryParams struct {
+	values url.Values
+	errs   []apperrors.FieldError
+}
+
+func newQueryParams(r *http.Request) *queryParams {
+	return &queryParams{values: r.URL.Query()}
+}
+
+func (q *queryParams) fail(field, code, detail string) {
+	q.errs = append(q.errs, apperrors.FieldError{Field: field, Code: code, Detail: detail})
+}
+
+// Int parses an integer in [minimum, maximum]. Values above maximum are
+// clamped, matching the repositories' page-size caps; values below minimum
+// are rejected.
+func (q *queryParams) Int(name string, defaultValue, minimum, maximum int) int {
+	raw := strings.TrimSpace(q.values.Get(name))
+	if raw == "" {
+		return defaultValue
+	}
+	parsed, err := strconv.Atoi(raw)
+	if err != nil {
+		q.fail(name, apperrors.FieldInvalidInteger, fmt.Sprintf("%s must be an integer", name))
+		return defaultValue
+	}
+	if parsed < minimum {
+		q.fail(name, apperrors.FieldOutOfRange, fmt.Sprintf("%s must be at least %d", name, minimum))
+		return defaultValue
+	}
+	if parsed > maximum {
+		return maximum
+	}
+	return parsed
+}
+
+// Limit parses a page size in [1, maxPageLimit].
+func (q *queryParams) Limit(defaultValue int) int {
+	return q.Int("limit", defaultValue, 1, maxPageLimit)
+}
+
+// Offset parses a non-negative page offset.
+func (q *queryParams) Offset() int {
+	return q.Int("offset", 0, 0, math.MaxInt32)
+}
+
+// Enum returns the parameter if it is one of allowed, else records an error.
+// Matching is case-insensitive; the canonical allowed spelling is returned.
+func (q *queryParams) Enum(name, defaultValue string, allowed ...string) string {
+	raw := strings.TrimSpace(q.values.Get(name))
+	if raw == "" {
+		return defaultValue
+	}
+	for _, candidate := range allowed {
+		if strings.EqualFold(raw, candidate) {
+			return candidate
+		}
+	}
+	q.fail(name, apperrors.FieldInvalidEnum, fmt.Sprintf("%s must be one of: %s", name, strings.Join(allowed, ", ")))
+	return defaultValue
+}
+
+// Bool parses true/false (and 1/0). It returns nil when absent.
+func (q *queryParams) Bool(name string) *bool {
+	raw := strings.TrimSpace(q.values.Get(name))
+	if raw == "" {
+		return nil
+	}
+	parsed, err := strconv.ParseBool(raw)
+	if err != nil {
+		q.fail(name, apperrors.FieldInvalidBoolean, fmt.Sprintf("%s must be true or false", name))
+		return nil
+	}
+	return &parsed
+}
+
+// UUID parses an optional UUID parameter.
+func (q *queryParams) UUID(name string) *uuid.UUID {
+	raw := strings.TrimSpace(q.values.Get(name))
+	if raw == "" {
+		return nil
+	}
+	parsed, err := uuid.Parse(raw)
+	if err != nil {
+		q.fail(name, apperrors.FieldInvalidUUID, fmt.Sprintf("%s must be a UUID", name))
+		return nil
+	}
+	return &parsed
+}
+
+// PartialDate parses an optional YYYY, YYYY-MM, or YYYY-MM-DD parameter. It
+// returns the zero date when absent.
+func (q *queryParams) PartialDate(name string) db.PartialDate {
+	parsed, err := db.ParsePartialDate(q.values.Get(name))
+	if err != nil {
+		q.fail(name, apperrors.FieldInvalidDate, fmt.Sprintf("%s must be YYYY, YYYY-MM, or YYYY-MM-DD", name))
+		return db.PartialDate{}
+	}
+	return parsed
+}
+
+// Text returns a trimmed free-text parameter capped at maxRunes characters.
+func (q *queryParams) Text(name string, maxRunes int) string {
+	raw := strings.TrimSpace(q.values.Get(name))
+	if utf8.RuneCountInString(raw) > maxRunes {
+		q.fail(name, apperrors.FieldTooLong, fmt.Sprintf("%s must be at most %d characters", name, maxRunes))
+		return ""
+	}
+	return raw
+}
+
+// Location parses an optional IANA time zone name such as Europe/Berlin. It
+// returns UTC when absent.
+func (q *queryParams) Location(name string) *time.Location {
+	raw := strings.TrimSpace(q.values.Get(name))
+	if raw == "" {
+		return time.UTC
+	}
+	loc, err := time.LoadLocation(raw)
+	if err != nil || raw == "Local" {
+		q.fail(name, apperrors.FieldInvalidZone, fmt.Sprintf("%s must be an IANA time zone such as Europe/Berlin", name))
+		return time.UTC
+	}
+	return loc
+}
+
+// Valid writes a 422 problem response listing every violation and returns
+// false when any parameter was invalid.
+func (q *queryParams) Valid(w http.ResponseWriter, r *http.Request) bool {
+	if len(q.errs) == 0 {
+		return true
+	}
+	apperrors.WriteProblem(w, r, apperrors.ValidationProblem(q.errs))
+	return false
+}
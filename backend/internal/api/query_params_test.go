@@ -0,0 +1,57 @@
+package api
+
+import (
+	"net/http/httptest"
+	"strings"
+	"testing"
+)
+
+func TestQueryParamsClampLimitAndDefaultMissingValues(t *testing.T) {
+	query := newQueryParams(httptest.NewRequest("GET", "/?limit=5000&sort=TITLE", nil))
+
+	if got := query.Limit(50); got != maxPageLimit {
+		t.Fatalf("Limit = %d; want clamp to %d", got, maxPageLimit)
+	}
+	if got := query.Offset(); got != 0 {
+		t.Fatalf("Offset = %d; want default 0", got)
+	}
+	if got := query.Enum("sort", "", "added_at", "title"); got != "title" {
+		t.Fatalf("Enum = %q; want canonical title", got)
+	}
+	if len(query.errs) != 0 {
+		t.Fatalf("errs = %+v; want none", query.errs)
+	}
+}
+
+func TestQueryParamsRejectMalformedUUIDAndLongText(t *testing.T) {
+	query := newQueryParams(httptest.NewRequest("GET", "/?user=not-a-uuid&q="+strings.Repeat("a", maxQueryTextRunes+1), nil))
+
+	if query.UUID("user") != nil {
+		t.Fatal("malformed UUID must not parse")
+	}
+	if query.Text("q", maxQueryTextRunes) != "" {
+		t.Fatal("overlong text must not pass through")
+	}
+
+	rec := httptest.NewRecorder()
+	if query.Valid(rec, httptest.NewRequest("GET", "/api/v1/library", nil)) {
+		t.Fatal("Valid = true; want false")
+	}
+	if rec.Code != 422 || !strings.Contains(rec.Body.String(), `"instance":"/api/v1/library"`) {
+		t.Fatalf("response = %d %s", rec.Code, rec.Body.String())
+	}
+}
+
+func TestQueryParamsPartialDate(t *testing.T) {
+	query := newQueryParams(httptest.NewRequest("GET", "/?from=1990&to=1999-13", nil))
+
+	if got := query.PartialDate("from"); got.String() != "1990" {
+		t.Fatalf("PartialDate(from) = %+v; want 1990", got)
+	}
+	if got := query.PartialDate("to"); !got.IsZero() {
+		t.Fatalf("PartialDate(to) = %+v; want zero for an invalid month", got)
+	}
+	if len(query.errs) != 1 || query.errs[0].Field != "to" {
+		t.Fatalf("errs = %+v; want one error for to", query.errs)
+	}
+}
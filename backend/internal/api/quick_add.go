@@ -0,0 +1,103 @@
+package api
+
+import (
+	"errors"
+	"log"
+	"net/http"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+// quickAddPath is served with open CORS, since extensions and bookmarklets
+// call it from whatever page the user is on.
+const quickAddPath = "/api/v1/quick-add"
+
+// QuickAddHandlers queue a download from just a URL for browser extensions
+// and bookmarklets, authenticated by an API key. The download goes through
+// the same trusted ingestion as POST /api/v1/downloads.
+type QuickAddHandlers struct {
+	downloads downloadService
+	ingestion trustedDownloadIngestion
+	settings  userSettingsReader
+}
+
+func NewQuickAddHandlers(downloads downloadService, ingestion trustedDownloadIngestion, settings userSettingsReader) *QuickAddHandlers {
+	return &QuickAddHandlers{downloads: downloads, ingestion: ingestion, settings: settings}
+}
+
+// QuickAddRequest names the page to download. Title is the page title, used
+// until the download is matched. Inbox false skips the caller's inbox
+// playlist.
+type QuickAddRequest struct {
+	URL   string `json:"url"`
+	Title string `json:"title,omitempty"`
+	Inbox *bool  `json:"inbox,omitempty"`
+}
+
+// QuickAddResponse identifies the queued job, followed under
+// /api/v1/downloads/{job_id}. InboxPlaylistID is the playlist the track
+// will be added to, if any.
+type QuickAddResponse struct {
+	JobID           string `json:"job_id"`
+	Status          string `json:"status"`
+	InboxPlaylistID int64  `json:"inboxPlaylistId,omitempty"`
+}
+
+// QuickAdd handles POST /api/v1/quick-add.
+func (h *QuickAddHandlers) QuickAdd(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeDownloadError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	r.Body = http.MaxBytesReader(w, r.Body, maxCreateDownloadBodyBytes)
+	var req QuickAddRequest
+	if err := decodeStrictJSON(r, &req); err != nil || len(req.URL) > 4096 || len(req.Title) > 500 {
+		writeDownloadError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid request body")
+		return
+	}
+	candidate, err := normalizedDirectCandidate(CreateDownloadRequest{URL: req.URL, PageMetadata: PageMetadata{Title: req.Title}})
+	if err != nil {
+		writeDownloadError(w, http.StatusBadRequest, "INVALID_URL", err.Error())
+		return
+	}
+	if h.ingestion == nil || h.downloads == nil {
+		writeDownloadError(w, http.StatusServiceUnavailable, "DOWNLOAD_UNAVAILABLE", "download processing is unavailable")
+		return
+	}
+	if err := h.downloads.Admit(r.Context()); err != nil {
+		writeInsufficientStorage(w)
+		return
+	}
+
+	var inbox int64
+	if req.Inbox == nil || *req.Inbox {
+		settings, err := h.settings.Get(r.Context(), userCtx.UserID)
+		if err != nil {
+			// The download matters more than where it is filed.
+			log.Printf("Loading inbox playlist for %s failed: %v", userCtx.UserID, err)
+		}
+		inbox = settings.InboxPlaylistID
+	}
+	if inbox != 0 {
+		candidate.Metadata[download.MetadataInboxPlaylistID] = inbox
+	}
+
+	persisted, err := h.ingestion.CreateTrustedDownload(r.Context(), userCtx.UserID, db.SourceSelectionOriginDirectURL, candidate, "server-normalized API-key quick-add URL")
+	if err != nil {
+		writeDownloadError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to persist trusted download")
+		return
+	}
+	job, err := h.ingestion.EnqueueTrustedDownload(r.Context(), persisted, h.downloads)
+	if err != nil {
+		if errors.Is(err, download.ErrAdmissionRefused) {
+			writeInsufficientStorage(w)
+			return
+		}
+		writeDownloadError(w, http.StatusInternalServerError, "DOWNLOAD_ENQUEUE_FAILED", "failed to enqueue trusted download")
+		return
+	}
+	writeDownloadJSON(w, http.StatusCreated, QuickAddResponse{JobID: job.ID, Status: job.Status, InboxPlaylistID: inbox})
+}
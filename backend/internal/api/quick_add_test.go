@@ -0,0 +1,109 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+type fakeAPIKeys struct {
+	byHash map[string]db.APIKey
+}
+
+func (f *fakeAPIKeys) Authenticate(_ context.Context, keyHash string) (*db.APIKey, error) {
+	if key, ok := f.byHash[keyHash]; ok {
+		return &key, nil
+	}
+	return nil, db.ErrAPIKeyNotFound
+}
+
+func (f *fakeAPIKeys) List(_ context.Context, userID uuid.UUID) ([]db.APIKey, error) {
+	keys := []db.APIKey{}
+	for _, key := range f.byHash {
+		if key.UserID == userID {
+			keys = append(keys, key)
+		}
+	}
+	return keys, nil
+}
+
+func (f *fakeAPIKeys) Create(_ context.Context, key *db.APIKey) error {
+	f.byHash[key.KeyHash] = *key
+	return nil
+}
+
+func (f *fakeAPIKeys) Delete(_ context.Context, userID, id uuid.UUID) error {
+	for hash, key := range f.byHash {
+		if key.ID == id && key.UserID == userID {
+			delete(f.byHash, hash)
+			return nil
+		}
+	}
+	return db.ErrAPIKeyNotFound
+}
+
+func TestQuickAddTakesAnAPIKeyFromAnyOrigin(t *testing.T) {
+	userID := uuid.New()
+	keys := &fakeAPIKeys{byHash: map[string]db.APIKey{}}
+	apiKeys := NewAPIKeyHandlers(keys)
+	created := httptest.NewRecorder()
+	apiKeys.CreateKey(created, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/me/api-keys", strings.NewReader(`{"name":"Firefox"}`)), userID))
+	var key APIKeyResponse
+	if err := json.Unmarshal(created.Body.Bytes(), &key); err != nil || created.Code != http.StatusCreated || !strings.HasPrefix(key.Key, auth.APIKeyPrefix) || !strings.HasPrefix(key.Key, key.Prefix) {
+		t.Fatalf("create key = %d %s", created.Code, created.Body.String())
+	}
+
+	ingestion := &fakeDirectIngestion{}
+	settings := &fakeUserSettingsStore{byUser: map[uuid.UUID]db.UserSettings{userID: {InboxPlaylistID: 12}}}
+	router := NewRouterWithConfig(&RouterConfig{
+		AuthHandlers:     auth.NewHandlers(nil),
+		APIKeyHandlers:   apiKeys,
+		QuickAddHandlers: NewQuickAddHandlers(fakeDirectDownloadService{}, ingestion, settings),
+	})
+	quickAdd := func(method, apiKey, body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(method, "/api/v1/quick-add", strings.NewReader(body))
+		req.Header.Set("Origin", "moz-extension://6f1c")
+		if apiKey != "" {
+			req.Header.Set("X-API-Key", apiKey)
+		}
+		rec := httptest.NewRecorder()
+		router.ServeHTTP(rec, req)
+		return rec
+	}
+
+	if rec := quickAdd(http.MethodOptions, "", ""); rec.Code != http.StatusNoContent || rec.Header().Get("Access-Control-Allow-Origin") != "*" {
+		t.Fatalf("preflight = %d %v", rec.Code, rec.Header())
+	}
+	if rec := quickAdd(http.MethodPost, "", `{"url":"https://soundcloud.com/artist/track"}`); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("without a key = %d, want 401", rec.Code)
+	}
+	if rec := quickAdd(http.MethodPost, auth.APIKeyPrefix+"0000", `{"url":"https://soundcloud.com/artist/track"}`); rec.Code != http.StatusUnauthorized {
+		t.Fatalf("unknown key = %d, want 401", rec.Code)
+	}
+
+	rec := quickAdd(http.MethodPost, key.Key, `{"url":"https://soundcloud.com/artist/track","title":"Track"}`)
+	var resp QuickAddResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil || rec.Code != http.StatusCreated {
+		t.Fatalf("quick add = %d %s", rec.Code, rec.Body.String())
+	}
+	if resp.JobID != "job-1" || resp.InboxPlaylistID != 12 || rec.Header().Get("Access-Control-Allow-Origin") != "*" {
+		t.Fatalf("quick add = %+v", resp)
+	}
+	if ingestion.created.Decision.UserID != userID || ingestion.created.Candidate.Metadata[download.MetadataInboxPlaylistID] != int64(12) {
+		t.Fatalf("queued download = %+v", ingestion.created.Candidate)
+	}
+
+	rec = quickAdd(http.MethodPost, key.Key, `{"url":"https://soundcloud.com/artist/other","inbox":false}`)
+	if rec.Code != http.StatusCreated || ingestion.created.Candidate.Metadata[download.MetadataInboxPlaylistID] != nil {
+		t.Fatalf("quick add without inbox = %d %+v", rec.Code, ingestion.created.Candidate)
+	}
+}
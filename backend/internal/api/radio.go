@@ -0,0 +1,271 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"log"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/shuffle"
+)
+
+// radioSimilarArtists is how many similar artists an artist station draws
+// from besides the seed.
+const radioSimilarArtists = 20
+
+// radioTracksPerArtist caps how many tracks one artist contributes, so a
+// station is not mostly the seed's own catalog.
+const radioTracksPerArtist = 5
+
+// radioMinPlayableMs is the least a track must play between its skip markers
+// to be queued; stations leave out tracks that are mostly skipped.
+const radioMinPlayableMs = 30000
+
+type radioLibrary interface {
+	LibraryTracksByArtists(ctx context.Context, userID uuid.UUID, artistIDs []uuid.UUID, perArtist int, includeInbox bool, mood string) ([]db.ArtistTrack, error)
+}
+
+// RadioHandlers builds artist stations from the caller's library.
+type RadioHandlers struct {
+	similar     SimilarArtistProvider
+	library     radioLibrary
+	skipMarkers skipMarkerSource
+	// settings decides whether tracks in the caller's library inbox are
+	// queued, and whether hours biases stations toward the current moment;
+	// nil leaves both off.
+	settings userSettingsReader
+	hours    listeningHoursStore
+}
+
+func NewRadioHandlers(similar SimilarArtistProvider, library radioLibrary) *RadioHandlers {
+	return &RadioHandlers{similar: similar, library: library}
+}
+
+// WithSkipMarkers has stations honor the caller's skip markers: queued tracks
+// carry them, and tracks left too short by them are not queued.
+func (h *RadioHandlers) WithSkipMarkers(markers skipMarkerSource) *RadioHandlers {
+	h.skipMarkers = markers
+	return h
+}
+
+// WithListeningHours brings up sooner the similar artists callers with
+// context suggestions on tend to play at the current time of day and week.
+func (h *RadioHandlers) WithListeningHours(hours listeningHoursStore) *RadioHandlers {
+	h.hours = hours
+	return h
+}
+
+// ArtistRadioResponse is a station seeded by an artist: library tracks by the
+// seed and the artists its listeners also play. Plays from it are recorded
+// with context type "radio" and the seed's MBID as context ID. Explanation
+// says how the current moment shaped the station, if it did.
+type ArtistRadioResponse struct {
+	SeedArtistID string                  `json:"seedArtistId"`
+	Artists      []SimilarArtistResponse `json:"artists"`
+	Tracks       []RadioTrackResponse    `json:"tracks"`
+	Explanation  string                  `json:"explanation,omitempty"`
+}
+
+// RadioTrackResponse is a library track queued by a station.
+type RadioTrackResponse struct {
+	ID           int64  `json:"id"`
+	Title        string `json:"title"`
+	Artist       string `json:"artist,omitempty"`
+	Album        string `json:"album,omitempty"`
+	DurationMs   int    `json:"durationMs,omitempty"`
+	ArtistID     string `json:"artistId"`
+	IntroEndMs   *int   `json:"introEndMs,omitempty"`
+	OutroStartMs *int   `json:"outroStartMs,omitempty"`
+}
+
+// ArtistRadio handles GET /api/v1/radio/artists/{mb_id}.
+// Query params: limit, shuffle (a shuffle mode; without it tracks are dealt
+// round-robin by artist, seed first), spacing (for artist_spread), mood (a
+// mood bucket such as chill, keeping the station to measured tracks in it),
+// tz (the caller's IANA time zone, default UTC, for context suggestions).
+func (h *RadioHandlers) ArtistRadio(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeErrorResponse(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+	seedID, err := uuid.Parse(r.PathValue("mb_id"))
+	if err != nil {
+		writeErrorResponse(w, http.StatusBadRequest, "INVALID_ID", "invalid MusicBrainz ID format")
+		return
+	}
+	query := newQueryParams(r)
+	limit := query.Limit(50)
+	mode := query.Enum("shuffle", "", shuffle.Modes...)
+	spacing := query.Int("spacing", shuffle.DefaultSpacing, 1, maxShuffleSpacing)
+	mood := query.Enum("mood", "", db.MoodNames...)
+	loc := query.Location("tz")
+	if !query.Valid(w, r) {
+		return
+	}
+
+	// Without similar artists the station still plays the seed.
+	similar, err := h.similar.SimilarArtists(r.Context(), seedID, radioSimilarArtists)
+	if err != nil {
+		log.Printf("Similar artists for radio seed %s failed: %v", seedID, err)
+	}
+	artistIDs := make([]uuid.UUID, 0, len(similar)+1)
+	artistIDs = append(artistIDs, seedID)
+	for _, a := range similar {
+		artistIDs = append(artistIDs, a.MBArtistID)
+	}
+	settings := displaySettings(r.Context(), h.settings)
+	var explanation string
+	if mode == "" {
+		artistIDs, explanation = h.favorMoment(r.Context(), userCtx.UserID, settings, artistIDs, time.Now().In(loc))
+	}
+	tracks, err := h.library.LibraryTracksByArtists(r.Context(), userCtx.UserID, artistIDs, radioTracksPerArtist, settings.InboxInShuffle, mood)
+	if err != nil {
+		apperrors.WriteCodeProblem(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to build station")
+		return
+	}
+	skipMarkers, err := h.userSkipMarkers(r.Context(), userCtx.UserID, tracks)
+	if err != nil {
+		apperrors.WriteCodeProblem(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to build station")
+		return
+	}
+	tracks = withoutMostlySkipped(tracks, skipMarkers)
+
+	resp := ArtistRadioResponse{
+		SeedArtistID: seedID.String(),
+		Artists:      similarArtistResponses(similar),
+		Tracks:       make([]RadioTrackResponse, 0, min(limit, len(tracks))),
+		Explanation:  explanation,
+	}
+	var ordered []db.ArtistTrack
+	if mode == "" {
+		ordered = spreadByArtist(tracks, artistIDs, limit)
+	} else {
+		ordered = shuffleArtistTracks(tracks, mode, spacing, limit)
+	}
+	for _, t := range ordered {
+		track := RadioTrackResponse{
+			ID:         t.ID,
+			Title:      t.Title,
+			Artist:     t.Artist.String,
+			Album:      t.Album.String,
+			DurationMs: int(t.DurationMs.Int32),
+			ArtistID:   t.MBArtistID.String(),
+		}
+		if markers, ok := skipMarkers[t.ID]; ok {
+			track.IntroEndMs, track.OutroStartMs = skipMarkerMs(markers.IntroEndMs), skipMarkerMs(markers.OutroStartMs)
+		}
+		resp.Tracks = append(resp.Tracks, track)
+	}
+	w.Header().Set("Content-Type", "application/json")
+	json.NewEncoder(w).Encode(resp)
+}
+
+// favorMoment moves the similar artists the user tends to play at now's
+// moment right after the seed, keeping their order otherwise, and explains
+// the change. Without context suggestions, or if the history fails to load,
+// the order stays as it is.
+func (h *RadioHandlers) favorMoment(ctx context.Context, userID uuid.UUID, settings db.UserSettings, artistIDs []uuid.UUID, now time.Time) ([]uuid.UUID, string) {
+	profile, ok, err := momentProfile(ctx, h.hours, settings, userID, now)
+	if err != nil {
+		log.Printf("Listening hours for radio of %s failed: %v", userID, err)
+	}
+	if !ok {
+		return artistIDs, ""
+	}
+	favored := make(map[uuid.UUID]bool, len(profile.Artists))
+	for _, a := range profile.Artists {
+		favored[a.ArtistID] = true
+	}
+	ordered := make([]uuid.UUID, 1, len(artistIDs))
+	ordered[0] = artistIDs[0]
+	var rest []uuid.UUID
+	for _, id := range artistIDs[1:] {
+		if favored[id] {
+			ordered = append(ordered, id)
+		} else {
+			rest = append(rest, id)
+		}
+	}
+	if len(ordered) == 1 {
+		return artistIDs, ""
+	}
+	return append(ordered, rest...), fmt.Sprintf("Artists you often play on %s come up sooner", profile.Moment)
+}
+
+func (h *RadioHandlers) userSkipMarkers(ctx context.Context, userID uuid.UUID, tracks []db.ArtistTrack) (map[int64]db.SkipMarkers, error) {
+	if h.skipMarkers == nil || len(tracks) == 0 {
+		return nil, nil
+	}
+	ids := make([]int64, len(tracks))
+	for i, t := range tracks {
+		ids[i] = t.ID
+	}
+	return h.skipMarkers.ForUser(ctx, userID, ids)
+}
+
+// withoutMostlySkipped drops tracks whose skip markers leave less than
+// radioMinPlayableMs to play. Tracks of unknown length are kept.
+func withoutMostlySkipped(tracks []db.ArtistTrack, skipMarkers map[int64]db.SkipMarkers) []db.ArtistTrack {
+	kept := make([]db.ArtistTrack, 0, len(tracks))
+	for _, t := range tracks {
+		markers, ok := skipMarkers[t.ID]
+		if ok && t.DurationMs.Valid {
+			end := t.DurationMs.Int32
+			if markers.OutroStartMs.Valid {
+				end = markers.OutroStartMs.Int32
+			}
+			if end-markers.IntroEndMs.Int32 < radioMinPlayableMs {
+				continue
+			}
+		}
+		kept = append(kept, t)
+	}
+	return kept
+}
+
+// shuffleArtistTracks orders a station's tracks by a shuffle mode and keeps
+// the first limit.
+func shuffleArtistTracks(tracks []db.ArtistTrack, mode string, spacing, limit int) []db.ArtistTrack {
+	byID := make(map[int64]db.ArtistTrack, len(tracks))
+	items := make([]shuffle.Item, len(tracks))
+	for i, t := range tracks {
+		byID[t.ID] = t
+		items[i] = shuffle.Item{ID: t.ID, Artist: t.MBArtistID.String(), Album: t.Album.String, Rating: t.Rating, LastPlayedAt: t.LastPlayedAt.Time}
+	}
+	items = shuffle.Order(items, mode, spacing, nil)
+	out := make([]db.ArtistTrack, 0, min(limit, len(items)))
+	for _, item := range items[:min(limit, len(items))] {
+		out = append(out, byID[item.ID])
+	}
+	return out
+}
+
+// spreadByArtist deals tracks round-robin across artists in the given order,
+// seed first and then by similarity, so neighbouring tracks come from
+// different artists and closer artists come up sooner.
+func spreadByArtist(tracks []db.ArtistTrack, order []uuid.UUID, limit int) []db.ArtistTrack {
+	byArtist := make(map[uuid.UUID][]db.ArtistTrack, len(order))
+	for _, t := range tracks {
+		byArtist[t.MBArtistID] = append(byArtist[t.MBArtistID], t)
+	}
+	var out []db.ArtistTrack
+	for dealt := true; dealt && len(out) < limit; {
+		dealt = false
+		for _, id := range order {
+			if queue := byArtist[id]; len(queue) > 0 && len(out) < limit {
+				out = append(out, queue[0])
+				byArtist[id] = queue[1:]
+				dealt = true
+			}
+		}
+	}
+	return out
+}
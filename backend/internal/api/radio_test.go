@@ -0,0 +1,196 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeSimilarArtists struct {
+	artists []db.SimilarArtist
+	err     error
+}
+
+func (f *fakeSimilarArtists) SimilarArtists(context.Context, uuid.UUID, int) ([]db.SimilarArtist, error) {
+	return f.artists, f.err
+}
+
+type fakeRadioLibrary struct {
+	tracks    []db.ArtistTrack
+	artistIDs []uuid.UUID
+	mood      string
+}
+
+func (f *fakeRadioLibrary) LibraryTracksByArtists(_ context.Context, _ uuid.UUID, artistIDs []uuid.UUID, _ int, _ bool, mood string) ([]db.ArtistTrack, error) {
+	f.artistIDs, f.mood = artistIDs, mood
+	var picked []db.ArtistTrack
+	for _, t := range f.tracks {
+		for _, id := range artistIDs {
+			if t.MBArtistID == id {
+				picked = append(picked, t)
+			}
+		}
+	}
+	return picked, nil
+}
+
+func TestArtistRadioSpreadsSeedAndSimilarArtists(t *testing.T) {
+	seed, similar := uuid.New(), uuid.New()
+	library := &fakeRadioLibrary{tracks: []db.ArtistTrack{
+		{ID: 1, Title: "Seed One", MBArtistID: seed},
+		{ID: 2, Title: "Seed Two", MBArtistID: seed},
+		{ID: 3, Title: "Similar One", MBArtistID: similar},
+		{ID: 4, Title: "Unrelated", MBArtistID: uuid.New()},
+	}}
+
+	for _, tt := range []struct {
+		name    string
+		similar *fakeSimilarArtists
+		want    []int64
+	}{
+		{"expanded", &fakeSimilarArtists{artists: []db.SimilarArtist{{MBArtistID: similar, Name: "Similar", Score: 0.9}}}, []int64{1, 3, 2}},
+		{"similar lookup failed", &fakeSimilarArtists{err: errors.New("listenbrainz unavailable")}, []int64{1, 2}},
+	} {
+		t.Run(tt.name, func(t *testing.T) {
+			h := NewRadioHandlers(tt.similar, library)
+			req := httptest.NewRequest(http.MethodGet, "/api/v1/radio/artists/"+seed.String(), nil)
+			req.SetPathValue("mb_id", seed.String())
+			rec := httptest.NewRecorder()
+			h.ArtistRadio(rec, withUser(req, uuid.New()))
+			if rec.Code != http.StatusOK {
+				t.Fatalf("status = %d (body=%s)", rec.Code, rec.Body.String())
+			}
+			var resp ArtistRadioResponse
+			if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+				t.Fatalf("decode: %v", err)
+			}
+			var got []int64
+			for _, track := range resp.Tracks {
+				got = append(got, track.ID)
+			}
+			if len(got) != len(tt.want) {
+				t.Fatalf("tracks = %v, want %v", got, tt.want)
+			}
+			for i := range got {
+				if got[i] != tt.want[i] {
+					t.Fatalf("tracks = %v, want %v", got, tt.want)
+				}
+			}
+			if library.artistIDs[0] != seed {
+				t.Fatalf("seed not queried first: %v", library.artistIDs)
+			}
+		})
+	}
+}
+
+func TestArtistRadioHonorsSkipMarkers(t *testing.T) {
+	seed, userID := uuid.New(), uuid.New()
+	minutes := func(n int32) sql.NullInt32 { return sql.NullInt32{Int32: n * 60000, Valid: true} }
+	library := &fakeRadioLibrary{tracks: []db.ArtistTrack{
+		{ID: 1, Title: "Long Intro", DurationMs: minutes(5), MBArtistID: seed},
+		{ID: 2, Title: "Mostly Talk", DurationMs: minutes(2), MBArtistID: seed},
+		{ID: 3, Title: "Plain", DurationMs: minutes(3), MBArtistID: seed},
+	}}
+	markers := &fakeSkipMarkerStore{}
+	markers.Set(context.Background(), 1, uuid.NullUUID{UUID: userID, Valid: true}, db.SkipMarkers{IntroEndMs: minutes(1)})
+	markers.Set(context.Background(), 2, uuid.NullUUID{}, db.SkipMarkers{IntroEndMs: sql.NullInt32{Int32: 100000, Valid: true}})
+
+	h := NewRadioHandlers(&fakeSimilarArtists{}, library).WithSkipMarkers(markers)
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/radio/artists/"+seed.String(), nil)
+	req.SetPathValue("mb_id", seed.String())
+	rec := httptest.NewRecorder()
+	h.ArtistRadio(rec, withUser(req, userID))
+	var resp ArtistRadioResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (status %d)", err, rec.Code)
+	}
+	// Track 2 has 20 seconds left after its intro and is not queued.
+	if len(resp.Tracks) != 2 || resp.Tracks[0].ID != 1 || resp.Tracks[1].ID != 3 {
+		t.Fatalf("tracks = %+v", resp.Tracks)
+	}
+	if resp.Tracks[0].IntroEndMs == nil || *resp.Tracks[0].IntroEndMs != 60000 || resp.Tracks[1].IntroEndMs != nil {
+		t.Fatalf("markers = %+v", resp.Tracks)
+	}
+}
+
+func TestArtistRadioFavorsArtistsOfTheCurrentMoment(t *testing.T) {
+	seed, other, favored := uuid.New(), uuid.New(), uuid.New()
+	userID := uuid.New()
+	library := &fakeRadioLibrary{tracks: []db.ArtistTrack{
+		{ID: 1, Title: "Seed", MBArtistID: seed},
+		{ID: 2, Title: "Other", MBArtistID: other},
+		{ID: 3, Title: "Favored", MBArtistID: favored},
+	}}
+	similar := &fakeSimilarArtists{artists: []db.SimilarArtist{{MBArtistID: other, Name: "Other"}, {MBArtistID: favored, Name: "Favored"}}}
+	settings := &fakeUserSettingsStore{byUser: map[uuid.UUID]db.UserSettings{userID: {ContextSuggestions: true}}}
+	h := NewRadioHandlers(similar, library).WithListeningHours(hoursFavoringNow(time.UTC, uuid.New(), favored))
+	h.settings = settings
+
+	serve := func() ArtistRadioResponse {
+		req := httptest.NewRequest(http.MethodGet, "/api/v1/radio/artists/"+seed.String(), nil)
+		req.SetPathValue("mb_id", seed.String())
+		rec := httptest.NewRecorder()
+		h.ArtistRadio(rec, withUser(req, userID))
+		var resp ArtistRadioResponse
+		if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+			t.Fatalf("decode: %v (status %d)", err, rec.Code)
+		}
+		return resp
+	}
+	resp := serve()
+	if len(resp.Tracks) != 3 || resp.Tracks[0].ID != 1 || resp.Tracks[1].ID != 3 || resp.Explanation == "" {
+		t.Fatalf("station = %+v, want the favored artist right after the seed, explained", resp)
+	}
+
+	settings.byUser[userID] = db.UserSettings{}
+	if resp := serve(); resp.Tracks[1].ID != 2 || resp.Explanation != "" {
+		t.Fatalf("station with the setting off = %+v, want similarity order", resp)
+	}
+}
+
+func TestArtistRadioShufflesBySelectedMode(t *testing.T) {
+	seed := uuid.New()
+	played := func(days int) sql.NullTime {
+		return sql.NullTime{Time: time.Date(2026, 3, 1, 0, 0, 0, 0, time.UTC).AddDate(0, 0, days), Valid: true}
+	}
+	library := &fakeRadioLibrary{tracks: []db.ArtistTrack{
+		{ID: 1, Title: "Yesterday", MBArtistID: seed, LastPlayedAt: played(-1)},
+		{ID: 2, Title: "Never", MBArtistID: seed},
+		{ID: 3, Title: "Last Month", MBArtistID: seed, LastPlayedAt: played(-30)},
+	}}
+	h := NewRadioHandlers(&fakeSimilarArtists{}, library)
+
+	serve := func(query string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodGet, "/api/v1/radio/artists/"+seed.String()+query, nil)
+		req.SetPathValue("mb_id", seed.String())
+		rec := httptest.NewRecorder()
+		h.ArtistRadio(rec, withUser(req, uuid.New()))
+		return rec
+	}
+	rec := serve("?shuffle=least_recent&limit=2")
+	var resp ArtistRadioResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (status %d)", err, rec.Code)
+	}
+	if len(resp.Tracks) != 2 || resp.Tracks[0].ID != 2 || resp.Tracks[1].ID != 3 {
+		t.Fatalf("tracks = %+v, want never played then least recently played", resp.Tracks)
+	}
+
+	if rec := serve("?mood=chill"); rec.Code != http.StatusOK || library.mood != "chill" {
+		t.Fatalf("?mood=chill = %d, library mood %q", rec.Code, library.mood)
+	}
+	for _, query := range []string{"?shuffle=alphabetical", "?shuffle=artist_spread&spacing=0", "?mood=grumpy"} {
+		if rec := serve(query); rec.Code != http.StatusBadRequest {
+			t.Fatalf("%s = %d, want 400", query, rec.Code)
+		}
+	}
+}
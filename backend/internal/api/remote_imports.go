@@ -0,0 +1,82 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/importers"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+type remoteImportQueue interface {
+	Remotes() []importers.RemoteInfo
+	Enqueue(ctx context.Context, userID uuid.UUID, payload importers.RemoteImportPayload) (*jobs.Job, error)
+}
+
+// RemoteImportHandlers queue imports from the SFTP and WebDAV folders the
+// operator configured. The jobs run in the background and report their
+// progress through the jobs API.
+type RemoteImportHandlers struct {
+	queue remoteImportQueue
+}
+
+func NewRemoteImportHandlers(queue remoteImportQueue) *RemoteImportHandlers {
+	return &RemoteImportHandlers{queue: queue}
+}
+
+type RemoteImportRequest struct {
+	Remote  string   `json:"remote"`
+	Path    string   `json:"path"`
+	Include []string `json:"include"`
+	Exclude []string `json:"exclude"`
+}
+
+type RemoteListResponse struct {
+	Remotes []importers.RemoteInfo `json:"remotes"`
+}
+
+// ListRemotes handles GET /api/v1/imports/remotes.
+func (h *RemoteImportHandlers) ListRemotes(w http.ResponseWriter, r *http.Request) {
+	writeMaintenanceJSON(w, http.StatusOK, RemoteListResponse{Remotes: h.queue.Remotes()})
+}
+
+// CreateImport handles POST /api/v1/imports/remote.
+func (h *RemoteImportHandlers) CreateImport(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req RemoteImportRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	job, err := h.queue.Enqueue(r.Context(), userCtx.UserID, importers.RemoteImportPayload{
+		Remote:  req.Remote,
+		Path:    req.Path,
+		Include: req.Include,
+		Exclude: req.Exclude,
+	})
+	switch {
+	case errors.Is(err, importers.ErrUnknownRemote):
+		writeMaintenanceError(w, http.StatusNotFound, "REMOTE_NOT_FOUND", "no import remote named "+req.Remote)
+		return
+	case errors.Is(err, importers.ErrInvalidFilter):
+		writeMaintenanceError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	case errors.Is(err, jobs.ErrDuplicate):
+		writeMaintenanceError(w, http.StatusConflict, "JOB_ACTIVE", "an import of this folder is already queued or running")
+		return
+	case err != nil:
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to queue remote import")
+		return
+	}
+	w.Header().Set("Location", middleware.Path(r, "/api/v1/jobs/"+job.ID.String()))
+	writeMaintenanceJSON(w, http.StatusAccepted, jobResponse(job.Summary()))
+}
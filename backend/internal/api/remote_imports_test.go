@@ -0,0 +1,85 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/importers"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+type fakeRemoteImportQueue struct {
+	payloads []importers.RemoteImportPayload
+}
+
+func (f *fakeRemoteImportQueue) Remotes() []importers.RemoteInfo {
+	return []importers.RemoteInfo{{Name: "seedbox", Protocol: "sftp"}}
+}
+
+func (f *fakeRemoteImportQueue) Enqueue(ctx context.Context, userID uuid.UUID, payload importers.RemoteImportPayload) (*jobs.Job, error) {
+	switch {
+	case payload.Remote != "seedbox":
+		return nil, fmt.Errorf("%w: %q", importers.ErrUnknownRemote, payload.Remote)
+	case len(payload.Include) > 0 && payload.Include[0] == "[":
+		return nil, fmt.Errorf("%w: %q", importers.ErrInvalidFilter, payload.Include[0])
+	case len(f.payloads) > 0:
+		return nil, jobs.ErrDuplicate
+	}
+	f.payloads = append(f.payloads, payload)
+	return &jobs.Job{ID: uuid.New(), Kind: "remote_import", UserID: uuid.NullUUID{UUID: userID, Valid: true}, Status: jobs.StatusQueued, MaxAttempts: 3}, nil
+}
+
+func TestRemoteImportsQueueJobsForConfiguredRemotes(t *testing.T) {
+	queue := &fakeRemoteImportQueue{}
+	h := NewRemoteImportHandlers(queue)
+	userID := uuid.New()
+
+	rec := httptest.NewRecorder()
+	h.ListRemotes(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/imports/remotes", nil), userID))
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `{"name":"seedbox","protocol":"sftp"}`) {
+		t.Fatalf("list = %d %s", rec.Code, rec.Body.String())
+	}
+
+	create := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/imports/remote", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.CreateImport(rec, withUser(req, userID))
+		return rec
+	}
+	rec = create(`{"remote":"seedbox","path":"complete/Album","include":["*.flac"],"exclude":["Incomplete"]}`)
+	if rec.Code != http.StatusAccepted {
+		t.Fatalf("status = %d, want 202 (body=%s)", rec.Code, rec.Body.String())
+	}
+	var created JobResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &created); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if created.Kind != "remote_import" || rec.Header().Get("Location") != "/api/v1/jobs/"+created.ID {
+		t.Fatalf("created = %#v, location %q", created, rec.Header().Get("Location"))
+	}
+	if got := queue.payloads[0]; got.Path != "complete/Album" || got.Include[0] != "*.flac" || got.Exclude[0] != "Incomplete" {
+		t.Fatalf("payload = %+v", got)
+	}
+
+	tests := []struct {
+		body string
+		want int
+	}{
+		{`{"remote":"seedbox"}`, http.StatusConflict},
+		{`{"remote":"nas"}`, http.StatusNotFound},
+		{`{"remote":"seedbox","include":["["]}`, http.StatusBadRequest},
+		{`{"remote":"seedbox","url":"sftp://elsewhere"}`, http.StatusBadRequest},
+	}
+	for _, tt := range tests {
+		if rec := create(tt.body); rec.Code != tt.want {
+			t.Errorf("%s: status = %d, want %d", tt.body, rec.Code, tt.want)
+		}
+	}
+}
@@ -15,6 +15,7 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/research"
 )
 
@@ -573,5 +574,5 @@ func writeResearchJSON(w http.ResponseWriter, status int, value any) {
 }
 
 func writeResearchError(w http.ResponseWriter, status int, code, message string) {
-	writeResearchJSON(w, status, map[string]string{"code": code, "message": message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
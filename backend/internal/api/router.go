@@ -30,23 +30,66 @@ type Router struct {
 	validatorHandlers       *validators.Handlers
 	matcherHandlers         *matcher.Handler
 	libraryHandlers         *LibraryHandlers
+	libraryHealthHandlers   *LibraryHealthHandlers
+	libraryInboxHandlers    *LibraryInboxHandlers
 	analysisHandlers        *AnalysisHandlers
 	playbackHandlers        *PlaybackHandlers
+	playbackClientHandlers  *PlaybackClientHandlers
+	artworkHandlers         *ArtworkHandlers
+	lyricsHandlers          *LyricsHandlers
+	trackMetadataHandlers   *TrackMetadataHandlers
+	skipMarkerHandlers      *SkipMarkerHandlers
+	chapterHandlers         *ChapterHandlers
+	trackTechnicalHandlers  *TrackTechnicalHandlers
+	noteHandlers            *NoteHandlers
+	listenLaterHandlers     *ListenLaterHandlers
+	eventHandlers           *EventHandlers
+	activityHandlers        *ActivityHandlers
+	followHandlers          *FollowHandlers
+	activityPubHandlers     *ActivityPubHandlers
+	giftHandlers            *GiftHandlers
+	notificationHandlers    *NotificationHandlers
+	pushDeviceHandlers      *PushDeviceHandlers
+	eqPresetHandlers        *EQPresetHandlers
+	continueHandlers        *ContinueHandlers
+	yearInReviewHandlers    *YearInReviewHandlers
+	shuffleHandlers         *ShuffleHandlers
+	userSettingsHandlers    *UserSettingsHandlers
+	apiKeyHandlers          *APIKeyHandlers
+	credentialHandlers      *SourceCredentialHandlers
+	quickAddHandlers        *QuickAddHandlers
 	queueHandlers           *queue.Handlers
 	discoveryHandlers       *discovery.Handlers
 	agentToolsHandler       http.Handler
 	playlistHandlers        *PlaylistHandlers
 	playlistImportHandlers  *PlaylistImportHandlers
 	playlistMixHandlers     *PlaylistMixHandlers
+	playlistFolderHandlers  *PlaylistFolderHandlers
 	mixPlanHandlers         *MixPlanHandlers
 	downloadHandlers        *DownloadHandlers
 	sourceSelectionHandlers *SourceSelectionHandlers
 	maintenanceHandlers     *MaintenanceHandlers
+	maintenanceJobHandlers  *MaintenanceJobHandlers
+	remoteImportHandlers    *RemoteImportHandlers
+	beetsImportHandlers     *BeetsImportHandlers
+	migrationHandlers       *LibraryMigrationHandlers
+	historyImportHandlers   *HistoryImportHandlers
+	exportHandlers          *LibraryExportHandlers
+	deviceProfileHandlers   *DeviceProfileHandlers
+	guestHandlers           *GuestHandlers
+	streamLimiter           *StreamLimiter
+	tenantHandlers          *TenantHandlers
+	radioHandlers           *RadioHandlers
+	jobHandlers             *JobHandlers
+	jobQueueHandlers        *JobQueueHandlers
 	playEventHandlers       *PlayEventHandlers
+	homeHandlers            *HomeHandlers
 	researchHandlers        *ResearchHandlers
+	idempotencyStore        IdempotencyStore
 	healthHandler           *health.Handler
 	metricsHandler          http.HandlerFunc
 	corsAllowedOrigins      []string
+	webUI                   http.Handler
 }
 
 var defaultCORSAllowedOrigins = []string{
@@ -61,26 +104,72 @@ type RouterConfig struct {
 	SearchHandlers          *search.Handlers
 	MBClient                *musicbrainz.Client
 	MBHandlers              *musicbrainz.Handlers
+	ArtistBios              ArtistBioProvider
+	SimilarArtists          SimilarArtistProvider
 	WSHandler               *websocket.Handler
 	MatcherHandlers         *matcher.Handler
 	LibraryHandlers         *LibraryHandlers
+	LibraryHealthHandlers   *LibraryHealthHandlers
+	LibraryInboxHandlers    *LibraryInboxHandlers
 	AnalysisHandlers        *AnalysisHandlers
 	PlaybackHandlers        *PlaybackHandlers
+	PlaybackClientHandlers  *PlaybackClientHandlers
+	ArtworkHandlers         *ArtworkHandlers
+	LyricsHandlers          *LyricsHandlers
+	TrackMetadataHandlers   *TrackMetadataHandlers
+	SkipMarkerHandlers      *SkipMarkerHandlers
+	ChapterHandlers         *ChapterHandlers
+	TrackTechnicalHandlers  *TrackTechnicalHandlers
+	NoteHandlers            *NoteHandlers
+	ListenLaterHandlers     *ListenLaterHandlers
+	EventHandlers           *EventHandlers
+	ActivityHandlers        *ActivityHandlers
+	FollowHandlers          *FollowHandlers
+	ActivityPubHandlers     *ActivityPubHandlers
+	GiftHandlers            *GiftHandlers
+	NotificationHandlers    *NotificationHandlers
+	PushDeviceHandlers      *PushDeviceHandlers
+	EQPresetHandlers        *EQPresetHandlers
+	ContinueHandlers        *ContinueHandlers
+	YearInReviewHandlers    *YearInReviewHandlers
+	ShuffleHandlers         *ShuffleHandlers
+	UserSettingsHandlers    *UserSettingsHandlers
+	APIKeyHandlers          *APIKeyHandlers
+	CredentialHandlers      *SourceCredentialHandlers
+	QuickAddHandlers        *QuickAddHandlers
 	QueueHandlers           *queue.Handlers
 	DiscoveryHandlers       *discovery.Handlers
 	AgentToolsHandler       http.Handler
 	PlaylistHandlers        *PlaylistHandlers
 	PlaylistImportHandlers  *PlaylistImportHandlers
 	PlaylistMixHandlers     *PlaylistMixHandlers
+	PlaylistFolderHandlers  *PlaylistFolderHandlers
 	MixPlanHandlers         *MixPlanHandlers
 	DownloadHandlers        *DownloadHandlers
 	SourceSelectionHandlers *SourceSelectionHandlers
 	MaintenanceHandlers     *MaintenanceHandlers
+	MaintenanceJobHandlers  *MaintenanceJobHandlers
+	RemoteImportHandlers    *RemoteImportHandlers
+	BeetsImportHandlers     *BeetsImportHandlers
+	MigrationHandlers       *LibraryMigrationHandlers
+	HistoryImportHandlers   *HistoryImportHandlers
+	ExportHandlers          *LibraryExportHandlers
+	DeviceProfileHandlers   *DeviceProfileHandlers
+	GuestHandlers           *GuestHandlers
+	StreamLimiter           *StreamLimiter
+	TenantHandlers          *TenantHandlers
+	RadioHandlers           *RadioHandlers
+	JobHandlers             *JobHandlers
+	JobQueueHandlers        *JobQueueHandlers
 	PlayEventHandlers       *PlayEventHandlers
+	HomeHandlers            *HomeHandlers
 	ResearchHandlers        *ResearchHandlers
+	IdempotencyStore        IdempotencyStore
 	HealthHandler           *health.Handler
 	Metrics                 *metrics.Metrics
 	CORSAllowedOrigins      []string
+	// WebUI, when set, serves the web client on every path no route claims.
+	WebUI http.Handler
 }
 
 func NewRouter(authHandlers *auth.Handlers, authService *auth.Service, searchHandlers *search.Handlers, mbClient *musicbrainz.Client, mbHandlers *musicbrainz.Handlers, wsHandler *websocket.Handler, matcherHandlers *matcher.Handler, libraryHandlers *LibraryHandlers, queueHandlers *queue.Handlers, playlistHandlers *PlaylistHandlers, downloadHandlers *DownloadHandlers) *Router {
@@ -112,42 +201,123 @@ func NewRouterWithConfig(cfg *RouterConfig) *Router {
 		corsAllowedOrigins = defaultCORSAllowedOrigins
 	}
 
+	browseHandlers := NewBrowseHandlers(cfg.MBClient)
+	browseHandlers.bios = cfg.ArtistBios
+	browseHandlers.similar = cfg.SimilarArtists
+	if cfg.LibraryHandlers != nil && cfg.LibraryHandlers.libraryRepo != nil {
+		browseHandlers.related = cfg.LibraryHandlers.libraryRepo
+		browseHandlers.library = cfg.LibraryHandlers.libraryRepo
+	}
+	if cfg.PlayEventHandlers != nil {
+		if plays, ok := cfg.PlayEventHandlers.playEventRepo.(entityPlayRanker); ok {
+			browseHandlers.plays = plays
+		}
+	}
+	if cfg.UserSettingsHandlers != nil && cfg.UserSettingsHandlers.store != nil {
+		browseHandlers.settings = cfg.UserSettingsHandlers.store
+		if cfg.LibraryHandlers != nil {
+			cfg.LibraryHandlers.settings = cfg.UserSettingsHandlers.store
+		}
+		if cfg.RadioHandlers != nil {
+			cfg.RadioHandlers.settings = cfg.UserSettingsHandlers.store
+		}
+		if cfg.ShuffleHandlers != nil {
+			cfg.ShuffleHandlers.settings = cfg.UserSettingsHandlers.store
+		}
+		if cfg.HomeHandlers != nil {
+			cfg.HomeHandlers.settings = cfg.UserSettingsHandlers.store
+		}
+	}
+
 	r := &Router{
 		mux:                     http.NewServeMux(),
 		authHandlers:            cfg.AuthHandlers,
 		authService:             cfg.AuthService,
 		searchHandlers:          cfg.SearchHandlers,
-		browseHandlers:          NewBrowseHandlers(cfg.MBClient),
+		browseHandlers:          browseHandlers,
 		musicbrainzHandlers:     cfg.MBHandlers,
 		wsHandler:               cfg.WSHandler,
 		validatorHandlers:       validators.NewHandlers(validatorRegistry),
 		matcherHandlers:         cfg.MatcherHandlers,
 		libraryHandlers:         cfg.LibraryHandlers,
+		libraryHealthHandlers:   cfg.LibraryHealthHandlers,
+		libraryInboxHandlers:    cfg.LibraryInboxHandlers,
 		analysisHandlers:        cfg.AnalysisHandlers,
 		playbackHandlers:        cfg.PlaybackHandlers,
+		playbackClientHandlers:  cfg.PlaybackClientHandlers,
+		artworkHandlers:         cfg.ArtworkHandlers,
+		lyricsHandlers:          cfg.LyricsHandlers,
+		trackMetadataHandlers:   cfg.TrackMetadataHandlers,
+		skipMarkerHandlers:      cfg.SkipMarkerHandlers,
+		chapterHandlers:         cfg.ChapterHandlers,
+		trackTechnicalHandlers:  cfg.TrackTechnicalHandlers,
+		noteHandlers:            cfg.NoteHandlers,
+		listenLaterHandlers:     cfg.ListenLaterHandlers,
+		eventHandlers:           cfg.EventHandlers,
+		activityHandlers:        cfg.ActivityHandlers,
+		followHandlers:          cfg.FollowHandlers,
+		activityPubHandlers:     cfg.ActivityPubHandlers,
+		giftHandlers:            cfg.GiftHandlers,
+		notificationHandlers:    cfg.NotificationHandlers,
+		pushDeviceHandlers:      cfg.PushDeviceHandlers,
+		eqPresetHandlers:        cfg.EQPresetHandlers,
+		continueHandlers:        cfg.ContinueHandlers,
+		yearInReviewHandlers:    cfg.YearInReviewHandlers,
+		shuffleHandlers:         cfg.ShuffleHandlers,
+		userSettingsHandlers:    cfg.UserSettingsHandlers,
+		apiKeyHandlers:          cfg.APIKeyHandlers,
+		credentialHandlers:      cfg.CredentialHandlers,
+		quickAddHandlers:        cfg.QuickAddHandlers,
 		queueHandlers:           cfg.QueueHandlers,
 		discoveryHandlers:       cfg.DiscoveryHandlers,
 		agentToolsHandler:       cfg.AgentToolsHandler,
 		playlistHandlers:        cfg.PlaylistHandlers,
 		playlistImportHandlers:  cfg.PlaylistImportHandlers,
 		playlistMixHandlers:     cfg.PlaylistMixHandlers,
+		playlistFolderHandlers:  cfg.PlaylistFolderHandlers,
 		mixPlanHandlers:         cfg.MixPlanHandlers,
 		downloadHandlers:        cfg.DownloadHandlers,
 		sourceSelectionHandlers: cfg.SourceSelectionHandlers,
 		maintenanceHandlers:     cfg.MaintenanceHandlers,
+		maintenanceJobHandlers:  cfg.MaintenanceJobHandlers,
+		remoteImportHandlers:    cfg.RemoteImportHandlers,
+		beetsImportHandlers:     cfg.BeetsImportHandlers,
+		migrationHandlers:       cfg.MigrationHandlers,
+		historyImportHandlers:   cfg.HistoryImportHandlers,
+		exportHandlers:          cfg.ExportHandlers,
+		deviceProfileHandlers:   cfg.DeviceProfileHandlers,
+		guestHandlers:           cfg.GuestHandlers,
+		streamLimiter:           cfg.StreamLimiter,
+		tenantHandlers:          cfg.TenantHandlers,
+		radioHandlers:           cfg.RadioHandlers,
+		jobHandlers:             cfg.JobHandlers,
+		jobQueueHandlers:        cfg.JobQueueHandlers,
 		playEventHandlers:       cfg.PlayEventHandlers,
+		homeHandlers:            cfg.HomeHandlers,
 		researchHandlers:        cfg.ResearchHandlers,
+		idempotencyStore:        cfg.IdempotencyStore,
 		healthHandler:           cfg.HealthHandler,
 		metricsHandler:          metricsHandler,
 		corsAllowedOrigins:      corsAllowedOrigins,
+		webUI:                   cfg.WebUI,
 	}
 	r.setupRoutes()
 	return r
 }
 
 func (r *Router) ServeHTTP(w http.ResponseWriter, req *http.Request) {
+	// Record the matched pattern rather than the raw path so log lines group by
+	// route and never carry IDs embedded in URLs.
+	if _, pattern := r.mux.Handler(req); pattern != "" {
+		logger.SetRoute(req.Context(), pattern)
+	}
+
 	// Apply middleware chain: CORS -> Recovery -> RequestID -> Logging -> Routes
-	handler := middleware.CORS(r.corsAllowedOrigins)(
+	cors := middleware.CORS(r.corsAllowedOrigins)
+	if req.URL.Path == quickAddPath && r.quickAddHandlers != nil {
+		cors = middleware.OpenCORS("POST, OPTIONS", "Authorization, Content-Type, X-API-Key")
+	}
+	handler := cors(
 		logger.RecoveryMiddleware(
 			apperrors.RequestIDMiddleware(
 				logger.LoggingMiddleware(r.mux),
@@ -163,6 +333,15 @@ func (r *Router) setupRoutes() {
 	if r.agentToolsHandler != nil {
 		r.mux.Handle("/internal/agent-tools/v1/", r.agentToolsHandler)
 	}
+	// Operator job queue controls, likewise absent without a job control token.
+	if r.jobQueueHandlers != nil {
+		r.mux.HandleFunc("GET /internal/jobs/v1/queues", r.jobQueueHandlers.ListQueues)
+		r.mux.HandleFunc("POST /internal/jobs/v1/queues/{queue}/pause", r.jobQueueHandlers.PauseQueue)
+		r.mux.HandleFunc("POST /internal/jobs/v1/queues/{queue}/resume", r.jobQueueHandlers.ResumeQueue)
+		r.mux.HandleFunc("GET /internal/jobs/v1/dead-letters", r.jobQueueHandlers.ListDeadLetters)
+		r.mux.HandleFunc("POST /internal/jobs/v1/dead-letters/retry", r.jobQueueHandlers.RetryDeadLetters)
+		r.mux.HandleFunc("POST /internal/jobs/v1/dead-letters/discard", r.jobQueueHandlers.DiscardDeadLetters)
+	}
 
 	// Health check endpoints (Kubernetes-compatible)
 	if r.healthHandler != nil {
@@ -241,6 +420,12 @@ func (r *Router) setupRoutes() {
 	r.mux.HandleFunc("GET /api/v1/albums/{mb_id}", r.withAuth(r.browseHandlers.GetAlbum))
 	r.mux.HandleFunc("GET /api/v1/tracks/{mb_id}", r.withAuth(r.browseHandlers.GetTrack))
 
+	if r.radioHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/radio/artists/{mb_id}", r.withAuth(r.radioHandlers.ArtistRadio))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/radio/artists/{mb_id}", r.withAuth(unavailableHandler("Radio is unavailable")))
+	}
+
 	// WebSocket route (auth via query param)
 	r.mux.HandleFunc("GET /api/v1/ws/progress", r.wsHandler.ServeWS)
 
@@ -261,6 +446,19 @@ func (r *Router) setupRoutes() {
 	r.mux.HandleFunc("DELETE /api/v1/library/tracks/{track_id}", r.withAuth(r.libraryHandlers.RemoveTrackFromLibrary))
 	r.mux.HandleFunc("POST /api/v1/library/tracks/{track_id}/like", r.withAuth(r.libraryHandlers.LikeTrack))
 	r.mux.HandleFunc("DELETE /api/v1/library/tracks/{track_id}/like", r.withAuth(r.libraryHandlers.UnlikeTrack))
+	if r.libraryHealthHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/library/health", r.withAuth(r.libraryHealthHandlers.GetLibraryHealth))
+		r.mux.HandleFunc("GET /api/v1/library/health/{issue}", r.withAuth(r.libraryHealthHandlers.GetLibraryHealthIssue))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/library/health", r.withAuth(unavailableHandler("Library health is unavailable")))
+		r.mux.HandleFunc("GET /api/v1/library/health/{issue}", r.withAuth(unavailableHandler("Library health is unavailable")))
+	}
+	// Library inbox review (auth required)
+	if r.libraryInboxHandlers != nil {
+		r.mux.HandleFunc("POST /api/v1/library/inbox/approve", r.withAuth(r.libraryInboxHandlers.Approve))
+		r.mux.HandleFunc("POST /api/v1/library/inbox/discard", r.withAuth(r.libraryInboxHandlers.Discard))
+		r.mux.HandleFunc("POST /api/v1/library/inbox/{track_id}/fix", r.withAuth(r.libraryInboxHandlers.FixTrack))
+	}
 	if r.analysisHandlers != nil {
 		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/analysis", r.withAuth(r.analysisHandlers.GetTrackAnalysis))
 		r.mux.HandleFunc("PATCH /api/v1/tracks/{track_id}/analysis/overrides", r.withAuth(r.analysisHandlers.UpdateTrackAnalysisOverrides))
@@ -271,15 +469,178 @@ func (r *Router) setupRoutes() {
 
 	// Direct playback/download URL issuance (auth required)
 	if r.playbackHandlers != nil {
-		r.mux.HandleFunc("POST /api/v1/playback/urls", r.withAuth(r.playbackHandlers.CreatePlaybackURLs))
+		r.mux.HandleFunc("POST /api/v1/playback/urls", r.withAuth(r.limitStreams(r.playbackHandlers.CreatePlaybackURLs)))
 	} else {
 		r.mux.HandleFunc("POST /api/v1/playback/urls", r.withAuth(unavailableHandler("Playback URL issuance is unavailable")))
 	}
 
+	// Playback client capability handshake (auth required)
+	if r.playbackClientHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/playback/clients", r.withAuth(r.playbackClientHandlers.ListClients))
+		r.mux.HandleFunc("POST /api/v1/playback/clients", r.withAuth(r.playbackClientHandlers.RegisterClient))
+		r.mux.HandleFunc("DELETE /api/v1/playback/clients/{id}", r.withAuth(r.playbackClientHandlers.DeleteClient))
+	}
+
+	// Guest routes (no auth): the read-only public shelf, rate limited per
+	// client.
+	if r.guestHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/guest/shelf", r.guestHandlers.RateLimit(r.guestHandlers.GetShelf))
+		r.mux.HandleFunc("POST /api/v1/guest/playback/urls", r.guestHandlers.RateLimit(r.limitStreams(r.guestHandlers.CreatePlaybackURLs)))
+	} else {
+		guestUnavailable := unavailableHandler("Guest mode is disabled")
+		r.mux.HandleFunc("GET /api/v1/guest/shelf", guestUnavailable)
+		r.mux.HandleFunc("POST /api/v1/guest/playback/urls", guestUnavailable)
+	}
+
+	// Processed artwork: blurhash, palette, and signed variant URLs (auth required)
+	if r.artworkHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/artwork", r.withAuth(r.artworkHandlers.GetTrackArtwork))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/artwork", r.withAuth(unavailableHandler("Artwork is unavailable")))
+	}
+
+	// Lyrics imported from sidecar files (auth required)
+	if r.lyricsHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/lyrics", r.withAuth(r.lyricsHandlers.GetTrackLyrics))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/lyrics", r.withAuth(unavailableHandler("Lyrics are unavailable")))
+	}
+
+	// Per-field metadata provenance and user locks (auth required)
+	if r.trackMetadataHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/metadata/provenance", r.withAuth(r.trackMetadataHandlers.GetTrackProvenance))
+		r.mux.HandleFunc("DELETE /api/v1/tracks/{track_id}/metadata/locks/{field}", r.withAuth(r.trackMetadataHandlers.UnlockTrackField))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/metadata/provenance", r.withAuth(unavailableHandler("Metadata provenance is unavailable")))
+		r.mux.HandleFunc("DELETE /api/v1/tracks/{track_id}/metadata/locks/{field}", r.withAuth(unavailableHandler("Metadata provenance is unavailable")))
+	}
+
+	// Intro/outro skip markers, the caller's own or set for everyone (auth required)
+	if r.skipMarkerHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/skip-markers", r.withAuth(r.skipMarkerHandlers.GetSkipMarkers))
+		r.mux.HandleFunc("PUT /api/v1/tracks/{track_id}/skip-markers", r.withAuth(r.skipMarkerHandlers.PutSkipMarkers))
+		r.mux.HandleFunc("DELETE /api/v1/tracks/{track_id}/skip-markers", r.withAuth(r.skipMarkerHandlers.DeleteSkipMarkers))
+	}
+
+	// Chapters of long mixes, and splitting a mix into its tracks (auth required)
+	if r.chapterHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/chapters", r.withAuth(r.chapterHandlers.ListChapters))
+		r.mux.HandleFunc("POST /api/v1/tracks/{track_id}/chapters/split", r.withAuth(r.chapterHandlers.SplitChapters))
+	}
+
+	// Codec, stream and tag details of a track's stored file (auth required)
+	if r.trackTechnicalHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tracks/{track_id}/technical", r.withAuth(r.trackTechnicalHandlers.GetTechnical))
+	}
+
+	// Notes on tracks, albums and playlists (auth required)
+	if r.noteHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/notes", r.withAuth(r.noteHandlers.ListNotes))
+		r.mux.HandleFunc("GET /api/v1/notes/{type}/{id}", r.withAuth(r.noteHandlers.GetNote))
+		r.mux.HandleFunc("PUT /api/v1/notes/{type}/{id}", r.withAuth(r.noteHandlers.SetNote))
+		r.mux.HandleFunc("DELETE /api/v1/notes/{type}/{id}", r.withAuth(r.noteHandlers.DeleteNote))
+	}
+
+	// Listen later queue (auth required)
+	if r.listenLaterHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/listen-later", r.withAuth(r.listenLaterHandlers.ListItems))
+		r.mux.HandleFunc("POST /api/v1/me/listen-later", r.withAuth(r.listenLaterHandlers.AddItem))
+		r.mux.HandleFunc("POST /api/v1/me/listen-later/finished", r.withAuth(r.listenLaterHandlers.FinishTrack))
+		r.mux.HandleFunc("DELETE /api/v1/me/listen-later/{item_id}", r.withAuth(r.listenLaterHandlers.RemoveItem))
+	}
+
+	// Change journal (auth required)
+	if r.eventHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/events", r.withAuth(r.eventHandlers.ListEvents))
+	}
+
+	// Activity feed (auth required)
+	if r.activityHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/activity", r.withAuth(r.activityHandlers.ListActivity))
+	}
+
+	// Follows between users of the instance (auth required)
+	if r.followHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/users", r.withAuth(r.followHandlers.ListUsers))
+		r.mux.HandleFunc("PUT /api/v1/users/{user_id}/follow", r.withAuth(r.followHandlers.Follow))
+		r.mux.HandleFunc("DELETE /api/v1/users/{user_id}/follow", r.withAuth(r.followHandlers.Unfollow))
+		r.mux.HandleFunc("GET /api/v1/users/{user_id}/playlists", r.withAuth(r.followHandlers.ListUserPlaylists))
+		r.mux.HandleFunc("GET /api/v1/users/{user_id}/playlists/{id}", r.withAuth(r.followHandlers.GetUserPlaylist))
+		r.mux.HandleFunc("GET /api/v1/me/following", r.withAuth(r.followHandlers.ListFollowing))
+		r.mux.HandleFunc("GET /api/v1/me/followers", r.withAuth(r.followHandlers.ListFollowers))
+		r.mux.HandleFunc("DELETE /api/v1/me/followers/{user_id}", r.withAuth(r.followHandlers.RemoveFollower))
+	}
+
+	// Gifts between users of the instance (auth required)
+	if r.giftHandlers != nil {
+		r.mux.HandleFunc("POST /api/v1/users/{user_id}/gifts", r.withAuth(r.giftHandlers.SendGift))
+		r.mux.HandleFunc("GET /api/v1/me/gifts", r.withAuth(r.giftHandlers.ListGifts))
+		r.mux.HandleFunc("POST /api/v1/me/gifts/{id}/accept", r.withAuth(r.giftHandlers.AcceptGift))
+		r.mux.HandleFunc("DELETE /api/v1/me/gifts/{id}", r.withAuth(r.giftHandlers.DismissGift))
+	}
+
+	// Notification routes (auth required)
+	if r.notificationHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/notifications", r.withAuth(r.notificationHandlers.ListNotifications))
+		r.mux.HandleFunc("POST /api/v1/notifications/{id}/read", r.withAuth(r.notificationHandlers.MarkNotificationRead))
+		r.mux.HandleFunc("POST /api/v1/notifications/read-all", r.withAuth(r.notificationHandlers.MarkAllNotificationsRead))
+		r.mux.HandleFunc("GET /api/v1/notifications/preferences", r.withAuth(r.notificationHandlers.GetNotificationPreferences))
+		r.mux.HandleFunc("PUT /api/v1/notifications/preferences", r.withAuth(r.notificationHandlers.UpdateNotificationPreferences))
+	}
+
+	// Mobile push devices (auth required)
+	if r.pushDeviceHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/push/devices", r.withAuth(r.pushDeviceHandlers.ListPushDevices))
+		r.mux.HandleFunc("POST /api/v1/push/devices", r.withAuth(r.pushDeviceHandlers.RegisterPushDevice))
+		r.mux.HandleFunc("DELETE /api/v1/push/devices/{id}", r.withAuth(r.pushDeviceHandlers.DeletePushDevice))
+	}
+
+	// Equalizer presets and per-client overrides (auth required)
+	if r.eqPresetHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/eq-presets", r.withAuth(r.eqPresetHandlers.ListEQPresets))
+		r.mux.HandleFunc("POST /api/v1/eq-presets", r.withAuth(r.eqPresetHandlers.CreateEQPreset))
+		r.mux.HandleFunc("PUT /api/v1/eq-presets/{id}", r.withAuth(r.eqPresetHandlers.UpdateEQPreset))
+		r.mux.HandleFunc("DELETE /api/v1/eq-presets/{id}", r.withAuth(r.eqPresetHandlers.DeleteEQPreset))
+		r.mux.HandleFunc("PUT /api/v1/playback/clients/{id}/eq-preset", r.withAuth(r.eqPresetHandlers.SetClientEQPreset))
+	}
+
+	// ActivityPub federation (no auth required; inbox deliveries are signed)
+	// and fediverse publishing settings (auth required)
+	if r.activityPubHandlers != nil {
+		r.mux.HandleFunc("GET /.well-known/webfinger", r.activityPubHandlers.WebFinger)
+		r.mux.HandleFunc("GET /ap/users/{handle}", r.activityPubHandlers.Actor)
+		r.mux.HandleFunc("POST /ap/users/{handle}/inbox", r.activityPubHandlers.Inbox)
+		r.mux.HandleFunc("GET /ap/users/{handle}/outbox", r.activityPubHandlers.Outbox)
+		r.mux.HandleFunc("GET /ap/users/{handle}/followers", r.activityPubHandlers.Followers)
+		r.mux.HandleFunc("GET /ap/users/{handle}/posts/{id}", r.activityPubHandlers.Post)
+		r.mux.HandleFunc("GET /api/v1/me/fediverse", r.withAuth(r.activityPubHandlers.GetFediverse))
+		r.mux.HandleFunc("PUT /api/v1/me/fediverse", r.withAuth(r.activityPubHandlers.PutFediverse))
+		r.mux.HandleFunc("DELETE /api/v1/me/fediverse", r.withAuth(r.activityPubHandlers.DeleteFediverse))
+	}
+
+	// Continue listening (auth required)
+	if r.continueHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/continue-listening", r.withAuth(r.continueHandlers.GetContinueListening))
+	}
+
+	// Year in review (auth required); shared cards are served to anyone with
+	// the link.
+	if r.yearInReviewHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/year-in-review", r.withAuth(r.yearInReviewHandlers.GetYearInReview))
+		r.mux.HandleFunc("POST /api/v1/me/year-in-review/card", r.withAuth(r.yearInReviewHandlers.ShareYearInReviewCard))
+		r.mux.HandleFunc("DELETE /api/v1/me/year-in-review/card", r.withAuth(r.yearInReviewHandlers.RevokeYearInReviewCards))
+		r.mux.HandleFunc("GET /api/v1/year-in-review/cards/{token}", r.yearInReviewHandlers.GetYearInReviewCard)
+	}
+
+	// Shuffled queues (auth required)
+	if r.shuffleHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/library/shuffle", r.withAuth(r.shuffleHandlers.GetShuffle))
+	}
+
 	// Queue routes (auth required, Redis-backed)
 	if r.queueHandlers != nil {
 		r.mux.HandleFunc("GET /api/v1/queue", r.withAuth(r.queueHandlers.GetQueue))
-		r.mux.HandleFunc("POST /api/v1/queue/items", r.withAuth(r.queueHandlers.AddQueueItem))
+		r.mux.HandleFunc("POST /api/v1/queue/items", r.withAuth(r.withIdempotency(r.queueHandlers.AddQueueItem)))
 		r.mux.HandleFunc("POST /api/v1/queue/items/{queueItemId}/retry", r.withAuth(r.queueHandlers.RetryQueueItem))
 		r.mux.HandleFunc("DELETE /api/v1/queue/items/{queueItemId}", r.withAuth(r.queueHandlers.RemoveQueueItem))
 		r.mux.HandleFunc("PUT /api/v1/queue/reorder", r.withAuth(r.queueHandlers.ReorderQueue))
@@ -296,22 +657,39 @@ func (r *Router) setupRoutes() {
 
 	// Playlist routes (auth required)
 	r.mux.HandleFunc("GET /api/v1/playlists", r.withAuth(r.playlistHandlers.ListPlaylists))
-	r.mux.HandleFunc("POST /api/v1/playlists", r.withAuth(r.playlistHandlers.CreatePlaylist))
+	r.mux.HandleFunc("POST /api/v1/playlists", r.withAuth(r.withIdempotency(r.playlistHandlers.CreatePlaylist)))
 	r.mux.HandleFunc("GET /api/v1/playlists/{id}", r.withAuth(r.playlistHandlers.GetPlaylist))
 	r.mux.HandleFunc("PUT /api/v1/playlists/{id}", r.withAuth(r.playlistHandlers.UpdatePlaylist))
 	r.mux.HandleFunc("DELETE /api/v1/playlists/{id}", r.withAuth(r.playlistHandlers.DeletePlaylist))
-	r.mux.HandleFunc("POST /api/v1/playlists/{id}/tracks", r.withAuth(r.playlistHandlers.AddTracks))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/tracks", r.withAuth(r.withIdempotency(r.playlistHandlers.AddTracks)))
 	r.mux.HandleFunc("DELETE /api/v1/playlists/{id}/tracks/{trackId}", r.withAuth(r.playlistHandlers.RemoveTrack))
-	r.mux.HandleFunc("POST /api/v1/playlists/{id}/tracks/batch-remove", r.withAuth(r.playlistHandlers.BatchRemoveTracks))
-	r.mux.HandleFunc("PUT /api/v1/playlists/{id}/tracks/reorder", r.withAuth(r.playlistHandlers.ReorderTracks))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/tracks/batch-remove", r.withAuth(r.withIdempotency(r.playlistHandlers.BatchRemoveTracks)))
+	r.mux.HandleFunc("PUT /api/v1/playlists/{id}/tracks/reorder", r.withAuth(r.withIdempotency(r.playlistHandlers.ReorderTracks)))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/tracks/move-range", r.withAuth(r.withIdempotency(r.playlistHandlers.MoveTrackRange)))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/tracks/dedupe", r.withAuth(r.withIdempotency(r.playlistHandlers.DeduplicateTracks)))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/copy", r.withAuth(r.withIdempotency(r.playlistHandlers.CopyPlaylist)))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/merge", r.withAuth(r.withIdempotency(r.playlistHandlers.MergePlaylists)))
+	r.mux.HandleFunc("GET /api/v1/playlists/{id}/history", r.withAuth(r.playlistHandlers.PlaylistHistory))
+	r.mux.HandleFunc("POST /api/v1/playlists/{id}/revert", r.withAuth(r.withIdempotency(r.playlistHandlers.RevertPlaylist)))
 	// Flag-gated save-playlist-as-mix seam. The handler itself returns 404 when
 	// the feature is disabled (ENABLE_PLAYLIST_MIX); when the handler is not wired
 	// at all (legacy router construction) the route stays unregistered.
 	if r.playlistMixHandlers != nil {
 		r.mux.HandleFunc("POST /api/v1/playlists/{id}/mix", r.withAuth(r.playlistMixHandlers.CreateMixFromPlaylist))
 	}
+	// Folder and pin organization; unregistered for legacy router construction.
+	if r.playlistFolderHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/playlist-tree", r.withAuth(r.playlistFolderHandlers.GetTree))
+		r.mux.HandleFunc("POST /api/v1/playlist-folders", r.withAuth(r.withIdempotency(r.playlistFolderHandlers.CreateFolder)))
+		r.mux.HandleFunc("PUT /api/v1/playlist-folders/{id}", r.withAuth(r.playlistFolderHandlers.RenameFolder))
+		r.mux.HandleFunc("DELETE /api/v1/playlist-folders/{id}", r.withAuth(r.playlistFolderHandlers.DeleteFolder))
+		r.mux.HandleFunc("POST /api/v1/playlist-folders/{id}/move", r.withAuth(r.playlistFolderHandlers.MoveFolder))
+		r.mux.HandleFunc("POST /api/v1/playlists/{id}/move", r.withAuth(r.playlistFolderHandlers.MovePlaylist))
+		r.mux.HandleFunc("PUT /api/v1/playlists/{id}/pin", r.withAuth(r.playlistFolderHandlers.PinPlaylist))
+		r.mux.HandleFunc("DELETE /api/v1/playlists/{id}/pin", r.withAuth(r.playlistFolderHandlers.UnpinPlaylist))
+	}
 	if r.playlistImportHandlers != nil {
-		r.mux.HandleFunc("POST /api/v1/playlist-imports", r.withAuth(r.playlistImportHandlers.CreateImport))
+		r.mux.HandleFunc("POST /api/v1/playlist-imports", r.withAuth(r.withIdempotency(r.playlistImportHandlers.CreateImport)))
 		r.mux.HandleFunc("GET /api/v1/playlist-imports/{importJobId}", r.withAuth(r.playlistImportHandlers.GetImport))
 	} else {
 		playlistImportUnavailable := r.withAuth(unavailableHandler("Playlist import processing is disabled for this local mode"))
@@ -328,7 +706,7 @@ func (r *Router) setupRoutes() {
 
 	// Download routes (auth required, Redis/worker-backed)
 	if r.downloadHandlers != nil {
-		r.mux.HandleFunc("POST /api/v1/downloads", r.withAuth(r.downloadHandlers.CreateDownload))
+		r.mux.HandleFunc("POST /api/v1/downloads", r.withAuth(r.withIdempotency(r.downloadHandlers.CreateDownload)))
 		r.mux.HandleFunc("GET /api/v1/downloads", r.withAuth(r.downloadHandlers.GetUserJobs))
 		r.mux.HandleFunc("GET /api/v1/downloads/{job_id}", r.withAuth(r.downloadHandlers.GetJob))
 	} else {
@@ -338,25 +716,172 @@ func (r *Router) setupRoutes() {
 		r.mux.HandleFunc("GET /api/v1/downloads/{job_id}", downloadUnavailable)
 	}
 
-	// Play event routes (auth required): record a play and read personal history.
+	// API keys for browser extensions and bookmarklets (auth required). The
+	// keys open quick-add and nothing else.
+	if r.apiKeyHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/api-keys", r.withAuth(r.apiKeyHandlers.ListKeys))
+		r.mux.HandleFunc("POST /api/v1/me/api-keys", r.withAuth(r.apiKeyHandlers.CreateKey))
+		r.mux.HandleFunc("DELETE /api/v1/me/api-keys/{id}", r.withAuth(r.apiKeyHandlers.DeleteKey))
+		if r.quickAddHandlers != nil {
+			r.mux.HandleFunc("POST "+quickAddPath, r.apiKeyHandlers.Authenticate(r.quickAddHandlers.QuickAdd))
+		}
+	}
+
+	// Login cookies for sources downloads need them for (auth required).
+	if r.credentialHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/source-credentials", r.withAuth(r.credentialHandlers.ListCredentials))
+		r.mux.HandleFunc("PUT /api/v1/me/source-credentials/{source}", r.withAuth(r.credentialHandlers.PutCredential))
+		r.mux.HandleFunc("DELETE /api/v1/me/source-credentials/{source}", r.withAuth(r.credentialHandlers.DeleteCredential))
+	} else {
+		credentialsUnavailable := r.withAuth(unavailableHandler("Source credentials are not configured"))
+		r.mux.HandleFunc("GET /api/v1/me/source-credentials", credentialsUnavailable)
+		r.mux.HandleFunc("PUT /api/v1/me/source-credentials/{source}", credentialsUnavailable)
+		r.mux.HandleFunc("DELETE /api/v1/me/source-credentials/{source}", credentialsUnavailable)
+	}
+
+	// Play event routes (auth required): record plays and skips and read personal history.
 	if r.playEventHandlers != nil {
 		r.mux.HandleFunc("POST /api/v1/me/plays", r.withAuth(r.playEventHandlers.RecordPlay))
 		r.mux.HandleFunc("GET /api/v1/me/plays/history", r.withAuth(r.playEventHandlers.PlayHistory))
 		r.mux.HandleFunc("GET /api/v1/me/plays/recent", r.withAuth(r.playEventHandlers.RecentlyPlayed))
 		r.mux.HandleFunc("GET /api/v1/me/plays/top", r.withAuth(r.playEventHandlers.TopTracks))
+		r.mux.HandleFunc("GET /api/v1/me/plays/playlists/{id}", r.withAuth(r.playEventHandlers.PlaylistPlayStats))
+		r.mux.HandleFunc("POST /api/v1/me/plays/skips", r.withAuth(r.playEventHandlers.RecordSkip))
+		r.mux.HandleFunc("GET /api/v1/me/plays/skips", r.withAuth(r.playEventHandlers.SkipStats))
+		r.mux.HandleFunc("POST /api/v1/plays/batch", r.withAuth(r.playEventHandlers.RecordBatch))
 	} else {
 		playEventUnavailable := r.withAuth(unavailableHandler("Play history is unavailable"))
 		r.mux.HandleFunc("POST /api/v1/me/plays", playEventUnavailable)
 		r.mux.HandleFunc("GET /api/v1/me/plays/history", playEventUnavailable)
 		r.mux.HandleFunc("GET /api/v1/me/plays/recent", playEventUnavailable)
 		r.mux.HandleFunc("GET /api/v1/me/plays/top", playEventUnavailable)
+		r.mux.HandleFunc("GET /api/v1/me/plays/playlists/{id}", playEventUnavailable)
+		r.mux.HandleFunc("POST /api/v1/me/plays/skips", playEventUnavailable)
+		r.mux.HandleFunc("GET /api/v1/me/plays/skips", playEventUnavailable)
+		r.mux.HandleFunc("POST /api/v1/plays/batch", playEventUnavailable)
+	}
+
+	// Display preference routes (auth required).
+	if r.userSettingsHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/me/settings", r.withAuth(r.userSettingsHandlers.GetSettings))
+		r.mux.HandleFunc("PUT /api/v1/me/settings", r.withAuth(r.userSettingsHandlers.UpdateSettings))
+	} else {
+		settingsUnavailable := r.withAuth(unavailableHandler("Settings are unavailable"))
+		r.mux.HandleFunc("GET /api/v1/me/settings", settingsUnavailable)
+		r.mux.HandleFunc("PUT /api/v1/me/settings", settingsUnavailable)
+	}
+
+	// Home screen route (auth required): one composed payload for the home screen.
+	if r.homeHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/home", r.withAuth(r.homeHandlers.GetHome))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/home", r.withAuth(unavailableHandler("Home is unavailable")))
 	}
 
 	// Maintenance repair routes (auth required)
 	if r.maintenanceHandlers != nil {
-		r.mux.HandleFunc("POST /api/v1/maintenance/repair", r.withAuth(r.maintenanceHandlers.RepairTracks))
+		r.mux.HandleFunc("POST /api/v1/maintenance/repair", r.withAuth(r.instanceOnly(r.maintenanceHandlers.RepairTracks)))
+		r.mux.HandleFunc("POST /api/v1/maintenance/reverify", r.withAuth(r.instanceOnly(r.maintenanceHandlers.ReverifyTracks)))
+		r.mux.HandleFunc("POST /api/v1/maintenance/quality-scores", r.withAuth(r.instanceOnly(r.maintenanceHandlers.ScoreTrackQuality)))
+		r.mux.HandleFunc("POST /api/v1/maintenance/mood-features", r.withAuth(r.instanceOnly(r.maintenanceHandlers.MeasureTrackMoods)))
 	} else {
 		r.mux.HandleFunc("POST /api/v1/maintenance/repair", r.withAuth(unavailableHandler("Maintenance repair is unavailable")))
+		r.mux.HandleFunc("POST /api/v1/maintenance/reverify", r.withAuth(unavailableHandler("Maintenance repair is unavailable")))
+		r.mux.HandleFunc("POST /api/v1/maintenance/quality-scores", r.withAuth(unavailableHandler("Maintenance repair is unavailable")))
+		r.mux.HandleFunc("POST /api/v1/maintenance/mood-features", r.withAuth(unavailableHandler("Maintenance repair is unavailable")))
+	}
+
+	// Bulk fix-it job routes (auth required)
+	if r.maintenanceJobHandlers != nil {
+		r.mux.HandleFunc("POST /api/v1/maintenance/jobs", r.withAuth(r.maintenanceJobHandlers.CreateJob))
+	} else {
+		r.mux.HandleFunc("POST /api/v1/maintenance/jobs", r.withAuth(unavailableHandler("Maintenance jobs are unavailable")))
+	}
+
+	// Remote folder import routes (auth required)
+	if r.remoteImportHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/imports/remotes", r.withAuth(r.remoteImportHandlers.ListRemotes))
+		r.mux.HandleFunc("POST /api/v1/imports/remote", r.withAuth(r.remoteImportHandlers.CreateImport))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/imports/remotes", r.withAuth(unavailableHandler("Remote imports are not configured")))
+		r.mux.HandleFunc("POST /api/v1/imports/remote", r.withAuth(unavailableHandler("Remote imports are not configured")))
+	}
+
+	// Beets library import routes (auth required)
+	if r.beetsImportHandlers != nil {
+		r.mux.HandleFunc("POST /api/v1/imports/beets", r.withAuth(r.beetsImportHandlers.CreateImport))
+	} else {
+		r.mux.HandleFunc("POST /api/v1/imports/beets", r.withAuth(unavailableHandler("Beets imports are not configured")))
+	}
+
+	// Navidrome/Jellyfin library migration routes (auth required)
+	if r.migrationHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/imports/migrations/sources", r.withAuth(r.migrationHandlers.ListSources))
+		r.mux.HandleFunc("POST /api/v1/imports/migrations", r.withAuth(r.migrationHandlers.CreateMigration))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/imports/migrations/sources", r.withAuth(unavailableHandler("Library migrations are not configured")))
+		r.mux.HandleFunc("POST /api/v1/imports/migrations", r.withAuth(unavailableHandler("Library migrations are not configured")))
+	}
+
+	// Play history backfill routes (auth required)
+	if r.historyImportHandlers != nil {
+		r.mux.HandleFunc("POST /api/v1/imports/play-history", r.withAuth(r.historyImportHandlers.ImportPlayHistory))
+	} else {
+		r.mux.HandleFunc("POST /api/v1/imports/play-history", r.withAuth(unavailableHandler("Play history imports are unavailable")))
+	}
+
+	// Library export routes (auth required)
+	if r.exportHandlers != nil {
+		r.mux.HandleFunc("POST /api/v1/exports", r.withAuth(r.exportHandlers.CreateExport))
+	} else {
+		r.mux.HandleFunc("POST /api/v1/exports", r.withAuth(unavailableHandler("Library exports are not configured")))
+	}
+
+	// Device profile and offline sync manifest routes (auth required)
+	if r.deviceProfileHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/device-profiles", r.withAuth(r.deviceProfileHandlers.ListProfiles))
+		r.mux.HandleFunc("POST /api/v1/device-profiles", r.withAuth(r.deviceProfileHandlers.CreateProfile))
+		r.mux.HandleFunc("PUT /api/v1/device-profiles/{id}", r.withAuth(r.deviceProfileHandlers.UpdateProfile))
+		r.mux.HandleFunc("DELETE /api/v1/device-profiles/{id}", r.withAuth(r.deviceProfileHandlers.DeleteProfile))
+		r.mux.HandleFunc("POST /api/v1/device-profiles/{id}/sync-manifest", r.withAuth(r.limitStreams(r.deviceProfileHandlers.CreateSyncManifest)))
+	} else {
+		deviceProfilesUnavailable := r.withAuth(unavailableHandler("Device profiles are unavailable"))
+		r.mux.HandleFunc("GET /api/v1/device-profiles", deviceProfilesUnavailable)
+		r.mux.HandleFunc("POST /api/v1/device-profiles", deviceProfilesUnavailable)
+		r.mux.HandleFunc("PUT /api/v1/device-profiles/{id}", deviceProfilesUnavailable)
+		r.mux.HandleFunc("DELETE /api/v1/device-profiles/{id}", deviceProfilesUnavailable)
+		r.mux.HandleFunc("POST /api/v1/device-profiles/{id}/sync-manifest", deviceProfilesUnavailable)
+	}
+
+	// Tenant routes (auth required): the caller's tenant and, for its admins,
+	// its members.
+	if r.tenantHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/tenant", r.withAuth(r.tenantHandlers.GetTenant))
+		r.mux.HandleFunc("GET /api/v1/tenant/members", r.withAuth(r.tenantHandlers.ListMembers))
+		r.mux.HandleFunc("PUT /api/v1/tenant/members/{user_id}", r.withAuth(r.tenantHandlers.UpdateMember))
+	} else {
+		tenantsUnavailable := r.withAuth(unavailableHandler("Tenants are unavailable"))
+		r.mux.HandleFunc("GET /api/v1/tenant", tenantsUnavailable)
+		r.mux.HandleFunc("GET /api/v1/tenant/members", tenantsUnavailable)
+		r.mux.HandleFunc("PUT /api/v1/tenant/members/{user_id}", tenantsUnavailable)
+	}
+
+	// Background job status routes (auth required)
+	if r.jobHandlers != nil {
+		r.mux.HandleFunc("GET /api/v1/jobs", r.withAuth(r.jobHandlers.ListJobs))
+		r.mux.HandleFunc("GET /api/v1/jobs/{id}", r.withAuth(r.jobHandlers.GetJob))
+		r.mux.HandleFunc("POST /api/v1/jobs/{id}/cancel", r.withAuth(r.jobHandlers.CancelJob))
+	} else {
+		r.mux.HandleFunc("GET /api/v1/jobs", r.withAuth(unavailableHandler("Job status is unavailable")))
+		r.mux.HandleFunc("GET /api/v1/jobs/{id}", r.withAuth(unavailableHandler("Job status is unavailable")))
+		r.mux.HandleFunc("POST /api/v1/jobs/{id}/cancel", r.withAuth(unavailableHandler("Job status is unavailable")))
+	}
+
+	// Embedded web client. Registered without a method so it stays less
+	// specific than every other route; it refuses methods other than GET
+	// and HEAD itself.
+	if r.webUI != nil {
+		r.mux.Handle("/", r.webUI)
 	}
 }
 
@@ -378,6 +903,24 @@ func (r *Router) withAuth(next http.HandlerFunc) http.HandlerFunc {
 	}
 }
 
+// limitStreams bounds the client's concurrent stream requests when a stream
+// limiter is configured.
+func (r *Router) limitStreams(next http.HandlerFunc) http.HandlerFunc {
+	if r.streamLimiter == nil {
+		return next
+	}
+	return r.streamLimiter.Limit(next)
+}
+
+// instanceOnly refuses tenant members on routes that act across the whole
+// instance.
+func (r *Router) instanceOnly(next http.HandlerFunc) http.HandlerFunc {
+	if r.tenantHandlers == nil {
+		return next
+	}
+	return r.tenantHandlers.RequireInstanceUser(next)
+}
+
 func defaultHealthHandler(w http.ResponseWriter, r *http.Request) {
 	w.Header().Set("Content-Type", "application/json")
 	w.WriteHeader(http.StatusOK)
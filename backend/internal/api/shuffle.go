@@ -0,0 +1,118 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"math"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/shuffle"
+)
+
+// maxShuffleSpacing caps the spacing query parameter of shuffled queues and
+// stations.
+const maxShuffleSpacing = 20
+
+type shuffleLibrary interface {
+	ShuffleCandidates(ctx context.Context, userID uuid.UUID, playlistID int64, includeInbox bool) ([]db.ShuffleTrack, error)
+}
+
+// ShuffleHandlers generate play queues from the caller's library or one of
+// their playlists, ordered by a shuffle mode.
+type ShuffleHandlers struct {
+	library   shuffleLibrary
+	playlists notePlaylistReader
+	// settings decides whether tracks in the caller's library inbox are
+	// shuffled in; nil leaves them out.
+	settings userSettingsReader
+}
+
+func NewShuffleHandlers(library shuffleLibrary, playlists notePlaylistReader) *ShuffleHandlers {
+	return &ShuffleHandlers{library: library, playlists: playlists}
+}
+
+// ShuffleTrackResponse is a track of a generated queue.
+type ShuffleTrackResponse struct {
+	ID           int64      `json:"id"`
+	Title        string     `json:"title"`
+	Artist       string     `json:"artist,omitempty"`
+	Album        string     `json:"album,omitempty"`
+	DurationMs   int        `json:"durationMs,omitempty"`
+	Rating       int        `json:"rating,omitempty"`
+	LastPlayedAt *time.Time `json:"lastPlayedAt,omitempty"`
+}
+
+type ShuffleResponse struct {
+	Mode   string                 `json:"mode"`
+	Tracks []ShuffleTrackResponse `json:"tracks"`
+}
+
+// GetShuffle handles GET /api/v1/library/shuffle.
+// Query params: mode (random|artist_spread|rating|least_recent, default
+// random), playlist_id (shuffle a playlist the caller can see instead of the
+// library), spacing (tracks between one artist's tracks for artist_spread,
+// default 3), limit (default 100).
+func (h *ShuffleHandlers) GetShuffle(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	mode := query.Enum("mode", shuffle.Random, shuffle.Modes...)
+	playlistID := query.Int("playlist_id", 0, 1, math.MaxInt)
+	spacing := query.Int("spacing", shuffle.DefaultSpacing, 1, maxShuffleSpacing)
+	limit := query.Limit(100)
+	if !query.Valid(w, r) {
+		return
+	}
+
+	if playlistID > 0 {
+		playlist, err := h.playlists.GetByID(r.Context(), int64(playlistID))
+		if err != nil && !errors.Is(err, db.ErrPlaylistNotFound) {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load playlist")
+			return
+		}
+		if playlist == nil || (playlist.UserID != userCtx.UserID && !playlist.IsPublic) {
+			writeLibraryError(w, http.StatusNotFound, "PLAYLIST_NOT_FOUND", "playlist not found")
+			return
+		}
+	}
+	includeInbox := displaySettings(r.Context(), h.settings).InboxInShuffle
+	tracks, err := h.library.ShuffleCandidates(r.Context(), userCtx.UserID, int64(playlistID), includeInbox)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load tracks")
+		return
+	}
+
+	byID := make(map[int64]db.ShuffleTrack, len(tracks))
+	items := make([]shuffle.Item, len(tracks))
+	for i, t := range tracks {
+		byID[t.ID] = t
+		items[i] = shuffle.Item{ID: t.ID, Artist: t.Artist.String, Album: t.Album.String, Rating: t.Rating, LastPlayedAt: t.LastPlayedAt.Time}
+	}
+	items = shuffle.Order(items, mode, spacing, nil)
+
+	resp := ShuffleResponse{Mode: mode, Tracks: make([]ShuffleTrackResponse, 0, min(limit, len(items)))}
+	for _, item := range items[:min(limit, len(items))] {
+		t := byID[item.ID]
+		track := ShuffleTrackResponse{
+			ID:         t.ID,
+			Title:      t.Title,
+			Artist:     t.Artist.String,
+			Album:      t.Album.String,
+			DurationMs: int(t.DurationMs.Int32),
+			Rating:     t.Rating,
+		}
+		if t.LastPlayedAt.Valid {
+			track.LastPlayedAt = timePtr(t.LastPlayedAt.Time)
+		}
+		resp.Tracks = append(resp.Tracks, track)
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
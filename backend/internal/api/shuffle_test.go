@@ -0,0 +1,73 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeShuffleLibrary struct {
+	tracks     map[int64][]db.ShuffleTrack
+	playlistID int64
+}
+
+func (f *fakeShuffleLibrary) ShuffleCandidates(_ context.Context, _ uuid.UUID, playlistID int64, _ bool) ([]db.ShuffleTrack, error) {
+	f.playlistID = playlistID
+	return f.tracks[playlistID], nil
+}
+
+type fakeShufflePlaylists map[int64]*db.Playlist
+
+func (f fakeShufflePlaylists) GetByID(_ context.Context, id int64) (*db.Playlist, error) {
+	if playlist, ok := f[id]; ok {
+		return playlist, nil
+	}
+	return nil, db.ErrPlaylistNotFound
+}
+
+func TestShuffleOrdersLibraryAndPlaylists(t *testing.T) {
+	userID := uuid.New()
+	played := sql.NullTime{Time: time.Date(2026, 3, 1, 0, 0, 0, 0, time.UTC), Valid: true}
+	library := &fakeShuffleLibrary{tracks: map[int64][]db.ShuffleTrack{
+		0: {{ID: 1, Title: "Played", LastPlayedAt: played}, {ID: 2, Title: "Never"}},
+		5: {{ID: 3, Title: "Rated", Rating: 4}},
+	}}
+	playlists := fakeShufflePlaylists{
+		5: {ID: 5, UserID: userID},
+		6: {ID: 6, UserID: uuid.New()},
+	}
+	h := NewShuffleHandlers(library, playlists)
+
+	serve := func(query string) (*httptest.ResponseRecorder, ShuffleResponse) {
+		rec := httptest.NewRecorder()
+		h.GetShuffle(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/library/shuffle"+query, nil), userID))
+		var resp ShuffleResponse
+		_ = json.Unmarshal(rec.Body.Bytes(), &resp)
+		return rec, resp
+	}
+
+	rec, resp := serve("?mode=least_recent")
+	if rec.Code != http.StatusOK || resp.Mode != "least_recent" || len(resp.Tracks) != 2 || resp.Tracks[0].ID != 2 || resp.Tracks[1].LastPlayedAt == nil {
+		t.Fatalf("library shuffle = %d %+v", rec.Code, resp)
+	}
+	rec, resp = serve("?playlist_id=5&mode=rating")
+	if rec.Code != http.StatusOK || library.playlistID != 5 || len(resp.Tracks) != 1 || resp.Tracks[0].Rating != 4 {
+		t.Fatalf("playlist shuffle = %d %+v", rec.Code, resp)
+	}
+	for _, query := range []string{"?playlist_id=6", "?playlist_id=7"} {
+		if rec, _ := serve(query); rec.Code != http.StatusNotFound {
+			t.Fatalf("%s = %d, want 404", query, rec.Code)
+		}
+	}
+	if rec, _ := serve("?mode=sorted"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("unknown mode = %d, want 400", rec.Code)
+	}
+}
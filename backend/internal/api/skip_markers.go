@@ -0,0 +1,193 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// Skip marker scopes: the caller's own markers, or those every listener
+// without their own gets.
+const (
+	skipMarkerScopeUser   = "user"
+	skipMarkerScopeGlobal = "global"
+)
+
+// skipMarkerSource resolves the markers a user's playback honors.
+// db.SkipMarkerRepository satisfies this interface.
+type skipMarkerSource interface {
+	ForUser(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]db.SkipMarkers, error)
+}
+
+type skipMarkerStore interface {
+	skipMarkerSource
+	Set(ctx context.Context, trackID int64, userID uuid.NullUUID, markers db.SkipMarkers) error
+	Delete(ctx context.Context, trackID int64, userID uuid.NullUUID) error
+}
+
+// SkipMarkerHandlers let listeners mark where playback of a library track
+// skips in from and out at, such as past a long spoken intro. Markers are
+// returned with playback URLs and honored by radio stations.
+type SkipMarkerHandlers struct {
+	markers skipMarkerStore
+	library playbackLibraryRepository
+	tracks  playbackTrackRepository
+}
+
+func NewSkipMarkerHandlers(markers skipMarkerStore, library playbackLibraryRepository, tracks playbackTrackRepository) *SkipMarkerHandlers {
+	return &SkipMarkerHandlers{markers: markers, library: library, tracks: tracks}
+}
+
+// SkipMarkersRequest sets a track's markers. Scope "user" (the default) sets
+// the caller's own; "global" sets them for every listener without their own.
+type SkipMarkersRequest struct {
+	IntroEndMs   *int   `json:"introEndMs"`
+	OutroStartMs *int   `json:"outroStartMs"`
+	Scope        string `json:"scope,omitempty"`
+}
+
+// SkipMarkersResponse is the markers the caller's playback honors, and where
+// they come from: "user", "global", or "analysis" for long leading or
+// trailing silence. A track without markers has no source.
+type SkipMarkersResponse struct {
+	TrackID      int64  `json:"trackId"`
+	IntroEndMs   *int   `json:"introEndMs,omitempty"`
+	OutroStartMs *int   `json:"outroStartMs,omitempty"`
+	Source       string `json:"source,omitempty"`
+}
+
+// GetSkipMarkers handles GET /api/v1/tracks/{track_id}/skip-markers.
+func (h *SkipMarkerHandlers) GetSkipMarkers(w http.ResponseWriter, r *http.Request) {
+	userID, trackID, ok := requireLibraryTrack(w, r, h.library, writePlaybackError)
+	if !ok {
+		return
+	}
+	h.writeMarkers(w, r, userID, trackID)
+}
+
+// PutSkipMarkers handles PUT /api/v1/tracks/{track_id}/skip-markers.
+func (h *SkipMarkerHandlers) PutSkipMarkers(w http.ResponseWriter, r *http.Request) {
+	userID, trackID, ok := requireLibraryTrack(w, r, h.library, writePlaybackError)
+	if !ok {
+		return
+	}
+	var req SkipMarkersRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid JSON request body")
+		return
+	}
+	owner, ok := skipMarkerOwner(w, userID, req.Scope)
+	if !ok {
+		return
+	}
+	tracks, err := h.tracks.GetByIDs(r.Context(), []int64{trackID})
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+		return
+	}
+	track, ok := tracks[trackID]
+	if !ok {
+		writePlaybackError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+		return
+	}
+	markers, err := validateSkipMarkers(req, track.DurationMs)
+	if err != nil {
+		writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	}
+	if err := h.markers.Set(r.Context(), trackID, owner, markers); err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to save skip markers")
+		return
+	}
+	h.writeMarkers(w, r, userID, trackID)
+}
+
+// DeleteSkipMarkers handles DELETE /api/v1/tracks/{track_id}/skip-markers,
+// removing the caller's markers or, with ?scope=global, everyone's.
+func (h *SkipMarkerHandlers) DeleteSkipMarkers(w http.ResponseWriter, r *http.Request) {
+	userID, trackID, ok := requireLibraryTrack(w, r, h.library, writePlaybackError)
+	if !ok {
+		return
+	}
+	query := newQueryParams(r)
+	scope := query.Enum("scope", skipMarkerScopeUser, skipMarkerScopeUser, skipMarkerScopeGlobal)
+	if !query.Valid(w, r) {
+		return
+	}
+	owner, _ := skipMarkerOwner(w, userID, scope)
+	if err := h.markers.Delete(r.Context(), trackID, owner); err != nil {
+		if errors.Is(err, db.ErrSkipMarkersNotFound) {
+			writePlaybackError(w, http.StatusNotFound, "SKIP_MARKERS_NOT_FOUND", "track has no skip markers in this scope")
+			return
+		}
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to delete skip markers")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (h *SkipMarkerHandlers) writeMarkers(w http.ResponseWriter, r *http.Request, userID uuid.UUID, trackID int64) {
+	markers, err := h.markers.ForUser(r.Context(), userID, []int64{trackID})
+	if err != nil {
+		writePlaybackError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load skip markers")
+		return
+	}
+	resp := SkipMarkersResponse{TrackID: trackID}
+	if m, ok := markers[trackID]; ok {
+		resp.IntroEndMs, resp.OutroStartMs, resp.Source = skipMarkerMs(m.IntroEndMs), skipMarkerMs(m.OutroStartMs), m.Source
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
+
+// skipMarkerOwner maps a scope to the user markers are stored for; global
+// markers have none.
+func skipMarkerOwner(w http.ResponseWriter, userID uuid.UUID, scope string) (uuid.NullUUID, bool) {
+	switch scope {
+	case "", skipMarkerScopeUser:
+		return uuid.NullUUID{UUID: userID, Valid: true}, true
+	case skipMarkerScopeGlobal:
+		return uuid.NullUUID{}, true
+	}
+	writePlaybackError(w, http.StatusBadRequest, "VALIDATION_ERROR", "scope must be user or global")
+	return uuid.NullUUID{}, false
+}
+
+// validateSkipMarkers checks markers fall inside the track, intro before
+// outro, and that at least one is set.
+func validateSkipMarkers(req SkipMarkersRequest, duration sql.NullInt32) (db.SkipMarkers, error) {
+	var markers db.SkipMarkers
+	if req.IntroEndMs == nil && req.OutroStartMs == nil {
+		return markers, errors.New("set introEndMs, outroStartMs, or both")
+	}
+	for _, marker := range []*int{req.IntroEndMs, req.OutroStartMs} {
+		if marker == nil {
+			continue
+		}
+		if *marker <= 0 || (duration.Valid && *marker >= int(duration.Int32)) {
+			return markers, errors.New("skip markers must fall inside the track")
+		}
+	}
+	if req.IntroEndMs != nil && req.OutroStartMs != nil && *req.IntroEndMs >= *req.OutroStartMs {
+		return markers, errors.New("introEndMs must come before outroStartMs")
+	}
+	if req.IntroEndMs != nil {
+		markers.IntroEndMs = sql.NullInt32{Int32: int32(*req.IntroEndMs), Valid: true}
+	}
+	if req.OutroStartMs != nil {
+		markers.OutroStartMs = sql.NullInt32{Int32: int32(*req.OutroStartMs), Valid: true}
+	}
+	return markers, nil
+}
+
+func skipMarkerMs(marker sql.NullInt32) *int {
+	if !marker.Valid {
+		return nil
+	}
+	ms := int(marker.Int32)
+	return &ms
+}
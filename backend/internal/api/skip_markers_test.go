@@ -0,0 +1,132 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// fakeSkipMarkerStore keeps markers per track and owner, uuid.Nil standing
+// for global ones, and resolves them with the repository's precedence.
+type fakeSkipMarkerStore struct {
+	markers map[int64]map[uuid.UUID]db.SkipMarkers
+}
+
+func (f *fakeSkipMarkerStore) Set(_ context.Context, trackID int64, userID uuid.NullUUID, markers db.SkipMarkers) error {
+	if f.markers == nil {
+		f.markers = map[int64]map[uuid.UUID]db.SkipMarkers{}
+	}
+	if f.markers[trackID] == nil {
+		f.markers[trackID] = map[uuid.UUID]db.SkipMarkers{}
+	}
+	f.markers[trackID][userID.UUID] = markers
+	return nil
+}
+
+func (f *fakeSkipMarkerStore) Delete(_ context.Context, trackID int64, userID uuid.NullUUID) error {
+	if _, ok := f.markers[trackID][userID.UUID]; !ok {
+		return db.ErrSkipMarkersNotFound
+	}
+	delete(f.markers[trackID], userID.UUID)
+	return nil
+}
+
+func (f *fakeSkipMarkerStore) ForUser(_ context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]db.SkipMarkers, error) {
+	resolved := map[int64]db.SkipMarkers{}
+	for _, id := range trackIDs {
+		if m, ok := f.markers[id][userID]; ok {
+			m.Source = db.SkipMarkerSourceUser
+			resolved[id] = m
+		} else if m, ok := f.markers[id][uuid.Nil]; ok {
+			m.Source = db.SkipMarkerSourceGlobal
+			resolved[id] = m
+		}
+	}
+	return resolved, nil
+}
+
+func skipMarkerRequest(h *SkipMarkerHandlers, method, trackID, query, body string, userID uuid.UUID) *httptest.ResponseRecorder {
+	req := httptest.NewRequest(method, "/api/v1/tracks/"+trackID+"/skip-markers"+query, strings.NewReader(body))
+	req.SetPathValue("track_id", trackID)
+	rec := httptest.NewRecorder()
+	handler := map[string]http.HandlerFunc{
+		http.MethodGet:    h.GetSkipMarkers,
+		http.MethodPut:    h.PutSkipMarkers,
+		http.MethodDelete: h.DeleteSkipMarkers,
+	}[method]
+	handler(rec, withUser(req, userID))
+	return rec
+}
+
+func TestSkipMarkersUserOverridesGlobal(t *testing.T) {
+	store := &fakeSkipMarkerStore{}
+	h := NewSkipMarkerHandlers(store,
+		&fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true}},
+		&fakePlaybackTrackRepo{tracks: map[int64]*db.Track{7: {ID: 7, DurationMs: sql.NullInt32{Int32: 240000, Valid: true}}}},
+	)
+	alice, bob := uuid.New(), uuid.New()
+
+	if rec := skipMarkerRequest(h, http.MethodPut, "7", "", `{"introEndMs":45000,"scope":"global"}`, alice); rec.Code != http.StatusOK {
+		t.Fatalf("global put status = %d (body=%s)", rec.Code, rec.Body.String())
+	}
+	rec := skipMarkerRequest(h, http.MethodPut, "7", "", `{"introEndMs":10000,"outroStartMs":200000}`, bob)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("user put status = %d (body=%s)", rec.Code, rec.Body.String())
+	}
+	var resp SkipMarkersResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Source != db.SkipMarkerSourceUser || *resp.IntroEndMs != 10000 || *resp.OutroStartMs != 200000 {
+		t.Fatalf("bob's markers = %+v", resp)
+	}
+
+	rec = skipMarkerRequest(h, http.MethodGet, "7", "", "", alice)
+	resp = SkipMarkersResponse{}
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Source != db.SkipMarkerSourceGlobal || *resp.IntroEndMs != 45000 || resp.OutroStartMs != nil {
+		t.Fatalf("alice's markers = %+v", resp)
+	}
+
+	if rec := skipMarkerRequest(h, http.MethodDelete, "7", "", "", bob); rec.Code != http.StatusNoContent {
+		t.Fatalf("delete status = %d", rec.Code)
+	}
+	if rec := skipMarkerRequest(h, http.MethodDelete, "7", "", "", bob); rec.Code != http.StatusNotFound {
+		t.Fatalf("second delete status = %d, want 404", rec.Code)
+	}
+}
+
+func TestPutSkipMarkersRejectsInvalidMarkers(t *testing.T) {
+	h := NewSkipMarkerHandlers(&fakeSkipMarkerStore{},
+		&fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true}},
+		&fakePlaybackTrackRepo{tracks: map[int64]*db.Track{7: {ID: 7, DurationMs: sql.NullInt32{Int32: 240000, Valid: true}}}},
+	)
+	for _, tt := range []struct {
+		name    string
+		trackID string
+		body    string
+		want    int
+	}{
+		{"none set", "7", `{}`, http.StatusBadRequest},
+		{"intro after outro", "7", `{"introEndMs":90000,"outroStartMs":60000}`, http.StatusBadRequest},
+		{"past the end", "7", `{"outroStartMs":300000}`, http.StatusBadRequest},
+		{"unknown scope", "7", `{"introEndMs":1000,"scope":"team"}`, http.StatusBadRequest},
+		{"not in library", "8", `{"introEndMs":1000}`, http.StatusNotFound},
+	} {
+		t.Run(tt.name, func(t *testing.T) {
+			if rec := skipMarkerRequest(h, http.MethodPut, tt.trackID, "", tt.body, uuid.New()); rec.Code != tt.want {
+				t.Fatalf("status = %d, want %d (body=%s)", rec.Code, tt.want, rec.Body.String())
+			}
+		})
+	}
+}
@@ -0,0 +1,142 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"io"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/sourceauth"
+)
+
+// maxCookiesFileBytes bounds an uploaded cookies.txt. A full browser export
+// fits; only the source's cookies are kept.
+const maxCookiesFileBytes = 1 << 20
+
+type sourceCredentialService interface {
+	List(ctx context.Context, userID uuid.UUID) ([]db.SourceCredential, error)
+	Upload(ctx context.Context, userID uuid.UUID, source string, cookiesTxt []byte) (*db.SourceCredential, error)
+	Delete(ctx context.Context, userID uuid.UUID, source string) error
+}
+
+// SourceCredentialHandlers let users upload the login cookies that sources
+// such as private SoundCloud tracks or members-only videos need, for their
+// downloads from those sources. Cookie values are never returned.
+type SourceCredentialHandlers struct {
+	credentials sourceCredentialService
+}
+
+func NewSourceCredentialHandlers(credentials sourceCredentialService) *SourceCredentialHandlers {
+	return &SourceCredentialHandlers{credentials: credentials}
+}
+
+// SourceCredentialResponse describes a source's cookies without revealing
+// them. Status is active, expired once every cookie has lapsed, or rejected
+// once the source turned them away; StatusDetail says why.
+type SourceCredentialResponse struct {
+	Source       string     `json:"source"`
+	CookieCount  int        `json:"cookieCount"`
+	ExpiresAt    *time.Time `json:"expiresAt,omitempty"`
+	Status       string     `json:"status"`
+	StatusDetail string     `json:"statusDetail,omitempty"`
+	LastUsedAt   *time.Time `json:"lastUsedAt,omitempty"`
+	UpdatedAt    time.Time  `json:"updatedAt"`
+}
+
+type SourceCredentialsResponse struct {
+	Credentials []SourceCredentialResponse `json:"credentials"`
+}
+
+// ListCredentials handles GET /api/v1/me/source-credentials.
+func (h *SourceCredentialHandlers) ListCredentials(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	credentials, err := h.credentials.List(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list source credentials")
+		return
+	}
+	resp := SourceCredentialsResponse{Credentials: make([]SourceCredentialResponse, 0, len(credentials))}
+	for _, credential := range credentials {
+		resp.Credentials = append(resp.Credentials, newSourceCredentialResponse(credential))
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
+
+// PutCredential handles PUT /api/v1/me/source-credentials/{source}. The
+// request body is a Netscape cookies.txt, which replaces any uploaded for
+// the source before.
+func (h *SourceCredentialHandlers) PutCredential(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	body, err := io.ReadAll(http.MaxBytesReader(w, r.Body, maxCookiesFileBytes))
+	var maxBytesError *http.MaxBytesError
+	if errors.As(err, &maxBytesError) {
+		writeLibraryError(w, http.StatusRequestEntityTooLarge, "COOKIES_TOO_LARGE", "cookies file is too large")
+		return
+	}
+	if err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "failed to read cookies file")
+		return
+	}
+	credential, err := h.credentials.Upload(r.Context(), userCtx.UserID, r.PathValue("source"), body)
+	switch {
+	case errors.Is(err, sourceauth.ErrInvalidSource), errors.Is(err, sourceauth.ErrInvalidCookies), errors.Is(err, sourceauth.ErrNoCookies):
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	case err != nil:
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to store source credential")
+		return
+	}
+	writeLibraryJSON(w, http.StatusOK, newSourceCredentialResponse(*credential))
+}
+
+// DeleteCredential handles DELETE /api/v1/me/source-credentials/{source}.
+func (h *SourceCredentialHandlers) DeleteCredential(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	err := h.credentials.Delete(r.Context(), userCtx.UserID, r.PathValue("source"))
+	switch {
+	case errors.Is(err, sourceauth.ErrInvalidSource):
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", err.Error())
+		return
+	case errors.Is(err, db.ErrSourceCredentialNotFound):
+		writeLibraryError(w, http.StatusNotFound, "SOURCE_CREDENTIAL_NOT_FOUND", "no cookies stored for this source")
+		return
+	case err != nil:
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to delete source credential")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func newSourceCredentialResponse(credential db.SourceCredential) SourceCredentialResponse {
+	resp := SourceCredentialResponse{
+		Source:       credential.Source,
+		CookieCount:  credential.CookieCount,
+		Status:       credential.Status,
+		StatusDetail: credential.StatusDetail,
+		UpdatedAt:    credential.UpdatedAt,
+	}
+	if credential.ExpiresAt.Valid {
+		resp.ExpiresAt = &credential.ExpiresAt.Time
+	}
+	if credential.LastUsedAt.Valid {
+		resp.LastUsedAt = &credential.LastUsedAt.Time
+	}
+	return resp
+}
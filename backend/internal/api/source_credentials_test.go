@@ -0,0 +1,82 @@
+package api
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/sourceauth"
+)
+
+type fakeSourceCredentials struct {
+	uploaded string
+}
+
+func (f *fakeSourceCredentials) List(ctx context.Context, userID uuid.UUID) ([]db.SourceCredential, error) {
+	return []db.SourceCredential{{Source: "soundcloud.com", SealedCookies: []byte("sealed-secret"), CookieCount: 3, Status: db.SourceCredentialRejected, StatusDetail: "403 Forbidden"}}, nil
+}
+
+func (f *fakeSourceCredentials) Upload(ctx context.Context, userID uuid.UUID, source string, cookiesTxt []byte) (*db.SourceCredential, error) {
+	if source != "soundcloud.com" {
+		return nil, sourceauth.ErrInvalidSource
+	}
+	f.uploaded = string(cookiesTxt)
+	return &db.SourceCredential{Source: source, SealedCookies: []byte("sealed-secret"), CookieCount: 1, Status: db.SourceCredentialActive, UpdatedAt: time.Now()}, nil
+}
+
+func (f *fakeSourceCredentials) Delete(ctx context.Context, userID uuid.UUID, source string) error {
+	return db.ErrSourceCredentialNotFound
+}
+
+func TestSourceCredentialHandlersNeverReturnCookies(t *testing.T) {
+	service := &fakeSourceCredentials{}
+	h := NewSourceCredentialHandlers(service)
+	userID := uuid.New()
+	put := func(source, body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPut, "/api/v1/me/source-credentials/"+source, strings.NewReader(body))
+		req.SetPathValue("source", source)
+		rec := httptest.NewRecorder()
+		h.PutCredential(rec, withUser(req, userID))
+		return rec
+	}
+
+	rec := put("soundcloud.com", ".soundcloud.com\tTRUE\t/\tTRUE\t0\toauth_token\tsecret\n")
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"status":"active"`) || strings.Contains(rec.Body.String(), "secret") {
+		t.Fatalf("put = %d %s", rec.Code, rec.Body.String())
+	}
+	if !strings.Contains(service.uploaded, "oauth_token") {
+		t.Fatalf("uploaded = %q", service.uploaded)
+	}
+	if rec := put("bad_source", "x"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("invalid source = %d %s", rec.Code, rec.Body.String())
+	}
+	if rec := put("soundcloud.com", strings.Repeat("x", maxCookiesFileBytes+1)); rec.Code != http.StatusRequestEntityTooLarge {
+		t.Fatalf("oversized file = %d", rec.Code)
+	}
+
+	rec = httptest.NewRecorder()
+	h.ListCredentials(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/source-credentials", nil), userID))
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `"statusDetail":"403 Forbidden"`) || strings.Contains(rec.Body.String(), "secret") {
+		t.Fatalf("list = %d %s", rec.Code, rec.Body.String())
+	}
+
+	req := httptest.NewRequest(http.MethodDelete, "/api/v1/me/source-credentials/soundcloud.com", nil)
+	req.SetPathValue("source", "soundcloud.com")
+	rec = httptest.NewRecorder()
+	h.DeleteCredential(rec, withUser(req, userID))
+	if rec.Code != http.StatusNotFound {
+		t.Fatalf("delete missing = %d", rec.Code)
+	}
+
+	rec = httptest.NewRecorder()
+	h.ListCredentials(rec, httptest.NewRequest(http.MethodGet, "/api/v1/me/source-credentials", nil))
+	if rec.Code != http.StatusUnauthorized {
+		t.Fatalf("anonymous list = %d", rec.Code)
+	}
+}
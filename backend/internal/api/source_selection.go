@@ -14,6 +14,7 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 // SourceSelectionHandlers exposes the durable, user-owned audit trail for
@@ -215,5 +216,5 @@ func writeSourceSelectionJSON(w http.ResponseWriter, status int, value any) {
 	_ = json.NewEncoder(w).Encode(value)
 }
 func writeSourceSelectionError(w http.ResponseWriter, status int, code, message string) {
-	writeSourceSelectionJSON(w, status, map[string]string{"code": code, "message": message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
@@ -0,0 +1,69 @@
+package api
+
+import (
+	"net/http"
+	"sync"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+)
+
+// StreamLimiter bounds how many stream requests one client has in flight:
+// playback URL batches and sync manifests, which stat every track in object
+// storage. A client opening dozens of parallel connections is refused with
+// 429 rather than holding storage round trips every other listener waits
+// behind. Users are counted by account, guests by client address.
+type StreamLimiter struct {
+	perClient int
+
+	mu     sync.Mutex
+	active map[string]int
+}
+
+func NewStreamLimiter(perClient int) *StreamLimiter {
+	return &StreamLimiter{perClient: perClient, active: map[string]int{}}
+}
+
+// Limit runs next unless the client already has the limit in flight.
+func (l *StreamLimiter) Limit(next http.HandlerFunc) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		client := streamClient(r)
+		if !l.acquire(client) {
+			p := apperrors.NewProblem(http.StatusTooManyRequests, "TOO_MANY_STREAMS",
+				"too many stream requests are in flight for this client; wait for one to finish")
+			p.Details = map[string]any{"maxConcurrent": l.perClient}
+			w.Header().Set("Retry-After", "1")
+			apperrors.WriteProblem(w, r, p)
+			return
+		}
+		defer l.release(client)
+		next(w, r)
+	}
+}
+
+func (l *StreamLimiter) acquire(client string) bool {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	if l.active[client] >= l.perClient {
+		return false
+	}
+	l.active[client]++
+	return true
+}
+
+func (l *StreamLimiter) release(client string) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	if l.active[client] <= 1 {
+		delete(l.active, client)
+		return
+	}
+	l.active[client]--
+}
+
+func streamClient(r *http.Request) string {
+	if user := auth.GetUserFromContext(r.Context()); user != nil {
+		return "user:" + user.UserID.String()
+	}
+	return "guest:" + guestClient(r)
+}
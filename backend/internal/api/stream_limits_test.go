@@ -0,0 +1,68 @@
+package api
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"sync"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestStreamLimiterBoundsConcurrentRequestsPerClient(t *testing.T) {
+	limiter := NewStreamLimiter(2)
+	release := make(chan struct{})
+	var started sync.WaitGroup
+	handler := limiter.Limit(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("X-Block") != "" {
+			started.Done()
+			<-release
+		}
+		w.WriteHeader(http.StatusOK)
+	})
+	send := func(req *http.Request) *httptest.ResponseRecorder {
+		rec := httptest.NewRecorder()
+		handler(rec, req)
+		return rec
+	}
+	userID := uuid.New()
+	userRequest := func(block bool) *http.Request {
+		req := httptest.NewRequest(http.MethodPost, "/api/v1/playback/urls", nil)
+		if block {
+			req.Header.Set("X-Block", "1")
+		}
+		return withUser(req, userID)
+	}
+
+	var done sync.WaitGroup
+	for i := 0; i < 2; i++ {
+		started.Add(1)
+		done.Add(1)
+		go func() {
+			defer done.Done()
+			send(userRequest(true))
+		}()
+	}
+	started.Wait()
+
+	rec := send(userRequest(false))
+	if rec.Code != http.StatusTooManyRequests || rec.Header().Get("Retry-After") != "1" || !strings.Contains(rec.Body.String(), `"maxConcurrent":2`) {
+		t.Fatalf("third request = %d, Retry-After %q, %s", rec.Code, rec.Header().Get("Retry-After"), rec.Body.String())
+	}
+	if rec := send(withUser(httptest.NewRequest(http.MethodPost, "/api/v1/playback/urls", nil), uuid.New())); rec.Code != http.StatusOK {
+		t.Fatalf("another user = %d", rec.Code)
+	}
+	if rec := send(httptest.NewRequest(http.MethodPost, "/api/v1/guest/playback/urls", nil)); rec.Code != http.StatusOK {
+		t.Fatalf("guest = %d", rec.Code)
+	}
+
+	close(release)
+	done.Wait()
+	if rec := send(userRequest(false)); rec.Code != http.StatusOK {
+		t.Fatalf("after in-flight requests finished = %d", rec.Code)
+	}
+	if len(limiter.active) != 0 {
+		t.Fatalf("active = %v, want no clients left", limiter.active)
+	}
+}
@@ -0,0 +1,206 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type tenantRepository interface {
+	ForUser(ctx context.Context, userID uuid.UUID) (*db.TenantMembership, error)
+	Members(ctx context.Context, tenantID int64) ([]db.TenantMember, error)
+	SetAdmin(ctx context.Context, tenantID int64, userID uuid.UUID, admin bool) error
+	Usage(ctx context.Context, tenantID int64) (db.TenantUsage, error)
+}
+
+// TenantHandlers let members see their household's tenant and its admins
+// manage who else administers it. Tenants and their quotas are set up by
+// the operator with `omp tenant`; isolation itself is enforced by the
+// repositories, not here.
+type TenantHandlers struct {
+	tenants tenantRepository
+}
+
+func NewTenantHandlers(tenants tenantRepository) *TenantHandlers {
+	return &TenantHandlers{tenants: tenants}
+}
+
+// TenantResponse describes the caller's tenant. Quotas are omitted when
+// unlimited.
+type TenantResponse struct {
+	Slug                  string `json:"slug"`
+	Name                  string `json:"name"`
+	Admin                 bool   `json:"admin"`
+	StoragePrefix         string `json:"storagePrefix"`
+	Tracks                int64  `json:"tracks"`
+	StorageBytes          int64  `json:"storageBytes"`
+	MaxTracks             *int64 `json:"maxTracks,omitempty"`
+	MaxStorageBytes       *int64 `json:"maxStorageBytes,omitempty"`
+	RemainingTracks       *int64 `json:"remainingTracks,omitempty"`
+	RemainingStorageBytes *int64 `json:"remainingStorageBytes,omitempty"`
+}
+
+type TenantMemberResponse struct {
+	UserID        uuid.UUID `json:"userId"`
+	Email         string    `json:"email"`
+	Username      string    `json:"username"`
+	Admin         bool      `json:"admin"`
+	LibraryTracks int       `json:"libraryTracks"`
+}
+
+type TenantMemberListResponse struct {
+	Members []TenantMemberResponse `json:"members"`
+}
+
+type TenantMemberRequest struct {
+	Admin *bool `json:"admin"`
+}
+
+// GetTenant handles GET /api/v1/tenant.
+func (h *TenantHandlers) GetTenant(w http.ResponseWriter, r *http.Request) {
+	membership, ok := h.membership(w, r)
+	if !ok {
+		return
+	}
+	usage, err := h.tenants.Usage(r.Context(), membership.Tenant.ID)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load tenant usage")
+		return
+	}
+	tenant := membership.Tenant
+	resp := TenantResponse{
+		Slug:          tenant.Slug,
+		Name:          tenant.Name,
+		Admin:         membership.Admin,
+		StoragePrefix: tenant.StoragePrefix,
+		Tracks:        usage.Tracks,
+		StorageBytes:  usage.StorageBytes,
+	}
+	if tenant.MaxTracks.Valid {
+		resp.MaxTracks = &tenant.MaxTracks.Int64
+		resp.RemainingTracks = remaining(tenant.MaxTracks.Int64, usage.Tracks)
+	}
+	if tenant.MaxStorageBytes.Valid {
+		resp.MaxStorageBytes = &tenant.MaxStorageBytes.Int64
+		resp.RemainingStorageBytes = remaining(tenant.MaxStorageBytes.Int64, usage.StorageBytes)
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
+// ListMembers handles GET /api/v1/tenant/members. Admins only.
+func (h *TenantHandlers) ListMembers(w http.ResponseWriter, r *http.Request) {
+	membership, ok := h.adminMembership(w, r)
+	if !ok {
+		return
+	}
+	members, err := h.tenants.Members(r.Context(), membership.Tenant.ID)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to list tenant members")
+		return
+	}
+	resp := TenantMemberListResponse{Members: make([]TenantMemberResponse, 0, len(members))}
+	for _, m := range members {
+		resp.Members = append(resp.Members, TenantMemberResponse{
+			UserID:        m.UserID,
+			Email:         m.Email,
+			Username:      m.Username,
+			Admin:         m.Admin,
+			LibraryTracks: m.LibraryTracks,
+		})
+	}
+	writeMaintenanceJSON(w, http.StatusOK, resp)
+}
+
+// UpdateMember handles PUT /api/v1/tenant/members/{user_id}, granting or
+// revoking admin. Admins only, and not for themselves, so a tenant is never
+// left without one.
+func (h *TenantHandlers) UpdateMember(w http.ResponseWriter, r *http.Request) {
+	membership, ok := h.adminMembership(w, r)
+	if !ok {
+		return
+	}
+	userID, err := uuid.Parse(r.PathValue("user_id"))
+	if err != nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid user_id")
+		return
+	}
+	var req TenantMemberRequest
+	if err := decodeStrictJSON(r, &req); err != nil || req.Admin == nil {
+		writeMaintenanceError(w, http.StatusBadRequest, "INVALID_REQUEST", "body must set admin")
+		return
+	}
+	if userID == auth.GetUserFromContext(r.Context()).UserID {
+		writeMaintenanceError(w, http.StatusBadRequest, "INVALID_REQUEST", "admins cannot change their own role")
+		return
+	}
+	if err := h.tenants.SetAdmin(r.Context(), membership.Tenant.ID, userID, *req.Admin); err != nil {
+		if errors.Is(err, db.ErrUserNotFound) {
+			writeMaintenanceError(w, http.StatusNotFound, "MEMBER_NOT_FOUND", "no such member in this tenant")
+			return
+		}
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to update member")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// RequireInstanceUser keeps tenant members away from instance-wide routes,
+// such as maintenance across every track; tenant admins administer only
+// their own tenant.
+func (h *TenantHandlers) RequireInstanceUser(next http.HandlerFunc) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		userCtx := auth.GetUserFromContext(r.Context())
+		if userCtx == nil {
+			writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+			return
+		}
+		membership, err := h.tenants.ForUser(r.Context(), userCtx.UserID)
+		if err != nil {
+			writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load tenant")
+			return
+		}
+		if membership != nil {
+			writeMaintenanceError(w, http.StatusForbidden, "FORBIDDEN", "tenant members cannot use instance-wide operations")
+			return
+		}
+		next(w, r)
+	}
+}
+
+// membership loads the caller's tenant, answering 404 for instance users.
+func (h *TenantHandlers) membership(w http.ResponseWriter, r *http.Request) (*db.TenantMembership, bool) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeMaintenanceError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return nil, false
+	}
+	membership, err := h.tenants.ForUser(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeMaintenanceError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load tenant")
+		return nil, false
+	}
+	if membership == nil {
+		writeMaintenanceError(w, http.StatusNotFound, "NOT_IN_TENANT", "user does not belong to a tenant")
+		return nil, false
+	}
+	return membership, true
+}
+
+func (h *TenantHandlers) adminMembership(w http.ResponseWriter, r *http.Request) (*db.TenantMembership, bool) {
+	membership, ok := h.membership(w, r)
+	if ok && !membership.Admin {
+		writeMaintenanceError(w, http.StatusForbidden, "FORBIDDEN", "tenant admins only")
+		return nil, false
+	}
+	return membership, ok
+}
+
+func remaining(limit, used int64) *int64 {
+	left := max(limit-used, 0)
+	return &left
+}
@@ -0,0 +1,124 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeTenantRepo struct {
+	memberships map[uuid.UUID]*db.TenantMembership
+	usage       db.TenantUsage
+}
+
+func (f *fakeTenantRepo) ForUser(_ context.Context, userID uuid.UUID) (*db.TenantMembership, error) {
+	return f.memberships[userID], nil
+}
+
+func (f *fakeTenantRepo) Members(_ context.Context, tenantID int64) ([]db.TenantMember, error) {
+	var members []db.TenantMember
+	for id, m := range f.memberships {
+		if m.Tenant.ID == tenantID {
+			members = append(members, db.TenantMember{UserID: id, Admin: m.Admin})
+		}
+	}
+	return members, nil
+}
+
+func (f *fakeTenantRepo) SetAdmin(_ context.Context, tenantID int64, userID uuid.UUID, admin bool) error {
+	m, ok := f.memberships[userID]
+	if !ok || m.Tenant.ID != tenantID {
+		return db.ErrUserNotFound
+	}
+	m.Admin = admin
+	return nil
+}
+
+func (f *fakeTenantRepo) Usage(context.Context, int64) (db.TenantUsage, error) {
+	return f.usage, nil
+}
+
+func TestTenantRolesAndQuotas(t *testing.T) {
+	smiths := db.Tenant{ID: 1, Slug: "smiths", Name: "The Smiths", StoragePrefix: "tenants/smiths", MaxTracks: sql.NullInt64{Int64: 100, Valid: true}}
+	jones := db.Tenant{ID: 2, Slug: "jones"}
+	admin, member, neighbour, instance := uuid.New(), uuid.New(), uuid.New(), uuid.New()
+	repo := &fakeTenantRepo{
+		memberships: map[uuid.UUID]*db.TenantMembership{
+			admin:     {Tenant: smiths, Admin: true},
+			member:    {Tenant: smiths},
+			neighbour: {Tenant: jones},
+		},
+		usage: db.TenantUsage{Tracks: 40, StorageBytes: 1 << 20},
+	}
+	h := NewTenantHandlers(repo)
+
+	rec := httptest.NewRecorder()
+	h.GetTenant(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/tenant", nil), member))
+	var resp TenantResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatal(err)
+	}
+	if rec.Code != http.StatusOK || resp.Slug != "smiths" || resp.Admin || resp.RemainingTracks == nil || *resp.RemainingTracks != 60 || resp.MaxStorageBytes != nil {
+		t.Fatalf("tenant = %d %+v", rec.Code, resp)
+	}
+
+	rec = httptest.NewRecorder()
+	h.GetTenant(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/tenant", nil), instance))
+	if rec.Code != http.StatusNotFound {
+		t.Fatalf("instance user tenant = %d", rec.Code)
+	}
+
+	rec = httptest.NewRecorder()
+	h.ListMembers(rec, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/tenant/members", nil), member))
+	if rec.Code != http.StatusForbidden {
+		t.Fatalf("member lists members = %d", rec.Code)
+	}
+
+	update := func(caller, target uuid.UUID, body string) int {
+		req := withUser(httptest.NewRequest(http.MethodPut, "/api/v1/tenant/members/"+target.String(), strings.NewReader(body)), caller)
+		req.SetPathValue("user_id", target.String())
+		rec := httptest.NewRecorder()
+		h.UpdateMember(rec, req)
+		return rec.Code
+	}
+	tests := []struct {
+		name           string
+		caller, target uuid.UUID
+		body           string
+		want           int
+	}{
+		{"member cannot promote", member, member, `{"admin":true}`, http.StatusForbidden},
+		{"admin keeps own role", admin, admin, `{"admin":false}`, http.StatusBadRequest},
+		{"other tenant's user", admin, neighbour, `{"admin":true}`, http.StatusNotFound},
+		{"missing admin", admin, member, `{}`, http.StatusBadRequest},
+		{"admin promotes member", admin, member, `{"admin":true}`, http.StatusNoContent},
+	}
+	for _, tt := range tests {
+		if got := update(tt.caller, tt.target, tt.body); got != tt.want {
+			t.Errorf("%s: status = %d, want %d", tt.name, got, tt.want)
+		}
+	}
+	if !repo.memberships[member].Admin || repo.memberships[neighbour].Admin {
+		t.Fatalf("roles after updates: member %v, neighbour %v", repo.memberships[member].Admin, repo.memberships[neighbour].Admin)
+	}
+
+	called := false
+	guarded := h.RequireInstanceUser(func(w http.ResponseWriter, r *http.Request) { called = true })
+	rec = httptest.NewRecorder()
+	guarded(rec, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/repair", nil), admin))
+	if rec.Code != http.StatusForbidden || called {
+		t.Fatalf("tenant admin reached instance maintenance: %d", rec.Code)
+	}
+	guarded(httptest.NewRecorder(), withUser(httptest.NewRequest(http.MethodPost, "/api/v1/maintenance/repair", nil), instance))
+	if !called {
+		t.Fatal("instance user was refused")
+	}
+}
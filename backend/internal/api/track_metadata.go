@@ -0,0 +1,113 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fieldProvenanceStore interface {
+	FieldProvenance(ctx context.Context, trackID int64) ([]db.FieldProvenance, error)
+	UnlockField(ctx context.Context, trackID int64, field string) error
+	MetadataVersion(ctx context.Context, trackID int64) (string, error)
+}
+
+// TrackMetadataHandlers exposes where a track's metadata fields came from and
+// lets users release the locks their corrections placed on them.
+type TrackMetadataHandlers struct {
+	store       fieldProvenanceStore
+	libraryRepo playbackLibraryRepository
+}
+
+func NewTrackMetadataHandlers(store fieldProvenanceStore, libraryRepo playbackLibraryRepository) *TrackMetadataHandlers {
+	return &TrackMetadataHandlers{store: store, libraryRepo: libraryRepo}
+}
+
+type FieldProvenanceResponse struct {
+	Field     string    `json:"field"`
+	Source    string    `json:"source"`
+	Locked    bool      `json:"locked"`
+	UpdatedAt time.Time `json:"updatedAt"`
+}
+
+type TrackProvenanceResponse struct {
+	TrackID int64                     `json:"trackId"`
+	Fields  []FieldProvenanceResponse `json:"fields"`
+}
+
+// GetTrackProvenance handles GET /api/v1/tracks/{track_id}/metadata/provenance.
+// Its ETag is the If-Match for metadata edits to the track.
+func (h *TrackMetadataHandlers) GetTrackProvenance(w http.ResponseWriter, r *http.Request) {
+	if !h.available(w) {
+		return
+	}
+	_, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	h.writeProvenance(w, r, trackID)
+}
+
+// UnlockTrackField handles DELETE /api/v1/tracks/{track_id}/metadata/locks/{field}
+// and returns the track's updated provenance. Unlocking a field that is not
+// locked is a no-op.
+func (h *TrackMetadataHandlers) UnlockTrackField(w http.ResponseWriter, r *http.Request) {
+	if !h.available(w) {
+		return
+	}
+	_, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	if err := h.store.UnlockField(r.Context(), trackID, r.PathValue("field")); err != nil {
+		switch {
+		case errors.Is(err, db.ErrUnknownMetadataField):
+			writeLibraryError(w, http.StatusBadRequest, "INVALID_FIELD", "unknown metadata field")
+		case errors.Is(err, db.ErrTrackNotFound):
+			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+		default:
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to unlock field")
+		}
+		return
+	}
+	h.writeProvenance(w, r, trackID)
+}
+
+func (h *TrackMetadataHandlers) available(w http.ResponseWriter) bool {
+	if h == nil || h.store == nil || h.libraryRepo == nil {
+		writeLibraryError(w, http.StatusServiceUnavailable, "SERVICE_DISABLED", "metadata provenance is unavailable")
+		return false
+	}
+	return true
+}
+
+func (h *TrackMetadataHandlers) writeProvenance(w http.ResponseWriter, r *http.Request, trackID int64) {
+	fields, err := h.store.FieldProvenance(r.Context(), trackID)
+	if err != nil {
+		if errors.Is(err, db.ErrTrackNotFound) {
+			writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+			return
+		}
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load metadata provenance")
+		return
+	}
+	version, err := h.store.MetadataVersion(r.Context(), trackID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load metadata provenance")
+		return
+	}
+	w.Header().Set("ETag", resourceETag(version))
+	resp := TrackProvenanceResponse{TrackID: trackID, Fields: make([]FieldProvenanceResponse, 0, len(fields))}
+	for _, field := range fields {
+		resp.Fields = append(resp.Fields, FieldProvenanceResponse{
+			Field:     field.Field,
+			Source:    field.Source,
+			Locked:    field.Locked,
+			UpdatedAt: field.UpdatedAt,
+		})
+	}
+	writePlaybackJSON(w, http.StatusOK, resp)
+}
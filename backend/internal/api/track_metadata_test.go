@@ -0,0 +1,96 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeFieldProvenanceStore struct {
+	byTrack map[int64][]db.FieldProvenance
+}
+
+func (f *fakeFieldProvenanceStore) FieldProvenance(ctx context.Context, trackID int64) ([]db.FieldProvenance, error) {
+	fields, ok := f.byTrack[trackID]
+	if !ok {
+		return nil, db.ErrTrackNotFound
+	}
+	return fields, nil
+}
+
+func (f *fakeFieldProvenanceStore) UnlockField(ctx context.Context, trackID int64, field string) error {
+	if field != "title" && field != "artist" {
+		return db.ErrUnknownMetadataField
+	}
+	fields, ok := f.byTrack[trackID]
+	if !ok {
+		return db.ErrTrackNotFound
+	}
+	for i := range fields {
+		if fields[i].Field == field {
+			fields[i].Locked = false
+		}
+	}
+	return nil
+}
+
+func (f *fakeFieldProvenanceStore) MetadataVersion(ctx context.Context, trackID int64) (string, error) {
+	return "v7", nil
+}
+
+func TestTrackMetadataProvenanceAndUnlock(t *testing.T) {
+	store := &fakeFieldProvenanceStore{byTrack: map[int64][]db.FieldProvenance{
+		7: {
+			{Field: "title", Source: db.FieldSourceUser, Locked: true},
+			{Field: "artist", Source: db.FieldSourceMusicBrainz},
+		},
+	}}
+	h := NewTrackMetadataHandlers(store, &fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true}})
+
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/tracks/7/metadata/provenance", nil)
+	req.SetPathValue("track_id", "7")
+	rec := httptest.NewRecorder()
+	h.GetTrackProvenance(rec, withUser(req, uuid.New()))
+	if rec.Code != http.StatusOK || rec.Header().Get("ETag") != `"v7"` {
+		t.Fatalf("status = %d, ETag %q; want 200 with the metadata version (body=%s)", rec.Code, rec.Header().Get("ETag"), rec.Body.String())
+	}
+	var resp TrackProvenanceResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.TrackID != 7 || len(resp.Fields) != 2 || !resp.Fields[0].Locked || resp.Fields[1].Source != "musicbrainz" {
+		t.Fatalf("provenance = %#v", resp)
+	}
+
+	unlock := func(trackID, field string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodDelete, "/api/v1/tracks/"+trackID+"/metadata/locks/"+field, nil)
+		req.SetPathValue("track_id", trackID)
+		req.SetPathValue("field", field)
+		rec := httptest.NewRecorder()
+		h.UnlockTrackField(rec, withUser(req, uuid.New()))
+		return rec
+	}
+	rec = unlock("7", "title")
+	if rec.Code != http.StatusOK {
+		t.Fatalf("unlock status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.Fields[0].Field != "title" || resp.Fields[0].Locked {
+		t.Fatalf("title still locked after unlock: %#v", resp.Fields[0])
+	}
+
+	if rec := unlock("7", "genre"); rec.Code != http.StatusBadRequest {
+		t.Fatalf("unknown field status = %d, want 400", rec.Code)
+	}
+	if rec := unlock("8", "title"); rec.Code != http.StatusNotFound {
+		t.Fatalf("track outside library status = %d, want 404", rec.Code)
+	}
+}
@@ -0,0 +1,115 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"log"
+	"net/http"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+// technicalProber reads what a track's stored file holds.
+// processor.Processor satisfies this interface.
+type technicalProber interface {
+	TechnicalInfo(ctx context.Context, track *db.Track) (*processor.TechnicalInfo, error)
+}
+
+// TrackTechnicalHandlers expose the codec, stream and tag details of the
+// audio file stored for a library track.
+type TrackTechnicalHandlers struct {
+	libraryRepo playbackLibraryRepository
+	tracks      playbackTrackRepository
+	prober      technicalProber
+}
+
+func NewTrackTechnicalHandlers(libraryRepo playbackLibraryRepository, tracks playbackTrackRepository, prober technicalProber) *TrackTechnicalHandlers {
+	return &TrackTechnicalHandlers{libraryRepo: libraryRepo, tracks: tracks, prober: prober}
+}
+
+// ReplayGainResponse holds a file's gain tags in dB, with peaks as linear
+// sample amplitudes. Opus R128 gains are converted to ReplayGain's reference.
+type ReplayGainResponse struct {
+	TrackGainDB *float64 `json:"trackGainDb,omitempty"`
+	TrackPeak   *float64 `json:"trackPeak,omitempty"`
+	AlbumGainDB *float64 `json:"albumGainDb,omitempty"`
+	AlbumPeak   *float64 `json:"albumPeak,omitempty"`
+}
+
+// TrackTechnicalResponse is what the stored file holds, as read from the file
+// itself rather than the track's metadata.
+type TrackTechnicalResponse struct {
+	TrackID           int64               `json:"trackId"`
+	Codec             string              `json:"codec"`
+	CodecLongName     string              `json:"codecLongName,omitempty"`
+	Container         string              `json:"container"`
+	ContainerLongName string              `json:"containerLongName,omitempty"`
+	BitrateKbps       int                 `json:"bitrateKbps"`
+	SampleRateHz      int                 `json:"sampleRateHz"`
+	BitDepth          int                 `json:"bitDepth,omitempty"`
+	Channels          int                 `json:"channels"`
+	ChannelLayout     string              `json:"channelLayout,omitempty"`
+	DurationMs        int                 `json:"durationMs"`
+	SizeBytes         int64               `json:"sizeBytes"`
+	Encoder           string              `json:"encoder,omitempty"`
+	ReplayGain        *ReplayGainResponse `json:"replayGain,omitempty"`
+	Tags              map[string]string   `json:"tags"`
+}
+
+// GetTechnical handles GET /api/v1/tracks/{track_id}/technical.
+func (h *TrackTechnicalHandlers) GetTechnical(w http.ResponseWriter, r *http.Request) {
+	_, trackID, ok := requireLibraryTrack(w, r, h.libraryRepo, writeLibraryError)
+	if !ok {
+		return
+	}
+	tracks, err := h.tracks.GetByIDs(r.Context(), []int64{trackID})
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load track")
+		return
+	}
+	track := tracks[trackID]
+	if track == nil {
+		writeLibraryError(w, http.StatusNotFound, "TRACK_NOT_FOUND", "track not found")
+		return
+	}
+	info, err := h.prober.TechnicalInfo(r.Context(), track)
+	if errors.Is(err, processor.ErrNoStoredAudio) {
+		writeLibraryError(w, http.StatusNotFound, "AUDIO_NOT_FOUND", "track has no stored audio")
+		return
+	}
+	if err != nil {
+		log.Printf("Failed to probe stored audio of track %d: %v", trackID, err)
+		writeLibraryError(w, http.StatusInternalServerError, "PROBE_FAILED", "failed to read stored audio")
+		return
+	}
+
+	resp := TrackTechnicalResponse{
+		TrackID:           trackID,
+		Codec:             info.Codec,
+		CodecLongName:     info.CodecLongName,
+		Container:         info.Container,
+		ContainerLongName: info.ContainerLongName,
+		BitrateKbps:       info.BitrateKbps,
+		SampleRateHz:      info.SampleRateHz,
+		BitDepth:          info.BitDepth,
+		Channels:          info.Channels,
+		ChannelLayout:     info.ChannelLayout,
+		DurationMs:        info.DurationMs,
+		SizeBytes:         info.SizeBytes,
+		Encoder:           info.Encoder,
+		Tags:              info.Tags,
+	}
+	if gain := info.ReplayGain; gain != nil {
+		resp.ReplayGain = &ReplayGainResponse{
+			TrackGainDB: gain.TrackGainDB,
+			TrackPeak:   gain.TrackPeak,
+			AlbumGainDB: gain.AlbumGainDB,
+			AlbumPeak:   gain.AlbumPeak,
+		}
+	}
+	if resp.Tags == nil {
+		resp.Tags = map[string]string{}
+	}
+	writeLibraryJSON(w, http.StatusOK, resp)
+}
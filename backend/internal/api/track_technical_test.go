@@ -0,0 +1,58 @@
+package api
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+type fakeTechnicalProber struct{}
+
+func (fakeTechnicalProber) TechnicalInfo(_ context.Context, track *db.Track) (*processor.TechnicalInfo, error) {
+	if !track.StorageKey.Valid {
+		return nil, processor.ErrNoStoredAudio
+	}
+	gain := -6.5
+	return &processor.TechnicalInfo{Codec: "flac", Container: "flac", BitDepth: 24, SampleRateHz: 96000, ReplayGain: &processor.ReplayGain{TrackGainDB: &gain}}, nil
+}
+
+func TestGetTechnical(t *testing.T) {
+	h := NewTrackTechnicalHandlers(
+		&fakePlaybackLibraryRepo{allowed: map[int64]bool{7: true, 8: true}},
+		&fakePlaybackTrackRepo{tracks: map[int64]*db.Track{
+			7: {ID: 7, StorageKey: sql.NullString{String: "audio/7.flac", Valid: true}},
+			8: {ID: 8},
+		}},
+		fakeTechnicalProber{},
+	)
+	request := func(trackID string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodGet, "/api/v1/tracks/"+trackID+"/technical", nil)
+		req.SetPathValue("track_id", trackID)
+		rec := httptest.NewRecorder()
+		h.GetTechnical(rec, withUser(req, uuid.New()))
+		return rec
+	}
+
+	rec := request("7")
+	var resp TrackTechnicalResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v (status %d)", err, rec.Code)
+	}
+	if resp.TrackID != 7 || resp.BitDepth != 24 || resp.ReplayGain == nil || *resp.ReplayGain.TrackGainDB != -6.5 || resp.Tags == nil {
+		t.Fatalf("technical = %+v", resp)
+	}
+	if rec := request("8"); rec.Code != http.StatusNotFound {
+		t.Fatalf("track without audio = %d, want 404", rec.Code)
+	}
+	if rec := request("9"); rec.Code != http.StatusNotFound {
+		t.Fatalf("track outside the library = %d, want 404", rec.Code)
+	}
+}
@@ -0,0 +1,225 @@
+package api
+
+import (
+	"context"
+	"errors"
+	"log"
+	"net/http"
+	"regexp"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// metadataLocalePattern accepts a language code with an optional region, such
+// as "ja", "en_GB", or "pt-BR", the forms MusicBrainz uses for alias locales.
+var metadataLocalePattern = regexp.MustCompile(`^[a-z]{2,3}(?:[_-][A-Za-z0-9]{2,8})?$`)
+
+// Bounds of trimSilenceMinMs other than 0, which turns trimming off. Shorter
+// silences are usually part of a track; longer ones are left to be noticed.
+const (
+	minTrimSilenceMs = 1000
+	maxTrimSilenceMs = 10 * 60 * 1000
+)
+
+// maxCrossfadeSeconds matches the longest crossfade the player offers.
+const maxCrossfadeSeconds = 12
+
+// Bounds of normalizationTargetLufs other than 0, which turns leveling off;
+// the same range the server accepts for LOUDNESS_TARGET_LUFS.
+const (
+	minNormalizationTargetLUFS = -30
+	maxNormalizationTargetLUFS = -5
+)
+
+type userSettingsReader interface {
+	Get(ctx context.Context, userID uuid.UUID) (db.UserSettings, error)
+}
+
+type userSettingsStore interface {
+	userSettingsReader
+	Update(ctx context.Context, userID uuid.UUID, settings db.UserSettings) (db.UserSettings, error)
+}
+
+// settingsPlaylistReader checks an inbox playlist belongs to the caller.
+// db.PlaylistRepository satisfies this interface.
+type settingsPlaylistReader interface {
+	GetByID(ctx context.Context, id int64) (*db.Playlist, error)
+}
+
+// UserSettingsHandlers reads and saves the caller's display, download and
+// playback preferences.
+type UserSettingsHandlers struct {
+	store     userSettingsStore
+	playlists settingsPlaylistReader
+}
+
+func NewUserSettingsHandlers(store userSettingsStore, playlists settingsPlaylistReader) *UserSettingsHandlers {
+	return &UserSettingsHandlers{store: store, playlists: playlists}
+}
+
+// UserSettingsRequest replaces the caller's settings. Omitted fields reset to
+// their defaults; gapless defaults to on.
+type UserSettingsRequest struct {
+	MetadataScript          string  `json:"metadataScript"`
+	MetadataLocale          string  `json:"metadataLocale"`
+	TrimSilenceMinMs        int     `json:"trimSilenceMinMs"`
+	NormalizeLoudness       bool    `json:"normalizeLoudness"`
+	InboxPlaylistID         int64   `json:"inboxPlaylistId"`
+	InboxNewTracks          bool    `json:"inboxNewTracks"`
+	InboxInSearch           bool    `json:"inboxInSearch"`
+	InboxInShuffle          bool    `json:"inboxInShuffle"`
+	CrossfadeSeconds        int     `json:"crossfadeSeconds"`
+	Gapless                 *bool   `json:"gapless"`
+	NormalizationTargetLUFS float64 `json:"normalizationTargetLufs"`
+	AcceptFollowers         bool    `json:"acceptFollowers"`
+	ShareListening          bool    `json:"shareListening"`
+	ContextSuggestions      bool    `json:"contextSuggestions"`
+}
+
+type UserSettingsResponse struct {
+	MetadataScript          string     `json:"metadataScript"`
+	MetadataLocale          string     `json:"metadataLocale,omitempty"`
+	TrimSilenceMinMs        int        `json:"trimSilenceMinMs"`
+	NormalizeLoudness       bool       `json:"normalizeLoudness"`
+	InboxPlaylistID         int64      `json:"inboxPlaylistId,omitempty"`
+	InboxNewTracks          bool       `json:"inboxNewTracks"`
+	InboxInSearch           bool       `json:"inboxInSearch"`
+	InboxInShuffle          bool       `json:"inboxInShuffle"`
+	CrossfadeSeconds        int        `json:"crossfadeSeconds"`
+	Gapless                 bool       `json:"gapless"`
+	NormalizationTargetLUFS float64    `json:"normalizationTargetLufs"`
+	AcceptFollowers         bool       `json:"acceptFollowers"`
+	ShareListening          bool       `json:"shareListening"`
+	ContextSuggestions      bool       `json:"contextSuggestions"`
+	UpdatedAt               *time.Time `json:"updatedAt,omitempty"`
+}
+
+// OriginalNames carries the names a track or album is titled with when the
+// caller's display settings replaced them with an alias.
+type OriginalNames struct {
+	OriginalTitle  string `json:"originalTitle,omitempty"`
+	OriginalArtist string `json:"originalArtist,omitempty"`
+}
+
+// GetSettings handles GET /api/v1/me/settings.
+func (h *UserSettingsHandlers) GetSettings(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	settings, err := h.store.Get(r.Context(), userCtx.UserID)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load settings")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, userSettingsResponse(settings))
+}
+
+// UpdateSettings handles PUT /api/v1/me/settings.
+func (h *UserSettingsHandlers) UpdateSettings(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writeLibraryError(w, http.StatusUnauthorized, "UNAUTHORIZED", "user not authenticated")
+		return
+	}
+	var req UserSettingsRequest
+	if err := decodeStrictJSON(r, &req); err != nil {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "invalid request body")
+		return
+	}
+	settings := db.UserSettings{MetadataScript: req.MetadataScript, MetadataLocale: req.MetadataLocale, TrimSilenceMinMs: req.TrimSilenceMinMs, NormalizeLoudness: req.NormalizeLoudness, InboxPlaylistID: req.InboxPlaylistID, InboxNewTracks: req.InboxNewTracks, InboxInSearch: req.InboxInSearch, InboxInShuffle: req.InboxInShuffle, CrossfadeSeconds: req.CrossfadeSeconds, Gapless: req.Gapless == nil || *req.Gapless, NormalizationTargetLUFS: req.NormalizationTargetLUFS, AcceptFollowers: req.AcceptFollowers, ShareListening: req.ShareListening, ContextSuggestions: req.ContextSuggestions}
+	if settings.MetadataScript == "" {
+		settings.MetadataScript = db.MetadataScriptOriginal
+	}
+	if settings.MetadataScript != db.MetadataScriptOriginal && settings.MetadataScript != db.MetadataScriptLatin {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "metadataScript must be one of: original, latin")
+		return
+	}
+	if settings.MetadataLocale != "" && !metadataLocalePattern.MatchString(settings.MetadataLocale) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "metadataLocale must be a language code such as en or pt_BR")
+		return
+	}
+	if settings.TrimSilenceMinMs != 0 && (settings.TrimSilenceMinMs < minTrimSilenceMs || settings.TrimSilenceMinMs > maxTrimSilenceMs) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "trimSilenceMinMs must be 0 or between 1000 and 600000")
+		return
+	}
+	if settings.CrossfadeSeconds < 0 || settings.CrossfadeSeconds > maxCrossfadeSeconds {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "crossfadeSeconds must be between 0 and 12")
+		return
+	}
+	if target := settings.NormalizationTargetLUFS; target != 0 && (target < minNormalizationTargetLUFS || target > maxNormalizationTargetLUFS) {
+		writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "normalizationTargetLufs must be 0 or between -30 and -5")
+		return
+	}
+	if settings.InboxPlaylistID != 0 {
+		playlist, err := h.playlists.GetByID(r.Context(), settings.InboxPlaylistID)
+		if err != nil && !errors.Is(err, db.ErrPlaylistNotFound) {
+			writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load inbox playlist")
+			return
+		}
+		if playlist == nil || playlist.UserID != userCtx.UserID || playlist.ReadOnly {
+			writeLibraryError(w, http.StatusBadRequest, "VALIDATION_ERROR", "inboxPlaylistId must be one of your playlists that is not read-only")
+			return
+		}
+	}
+	saved, err := h.store.Update(r.Context(), userCtx.UserID, settings)
+	if err != nil {
+		writeLibraryError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to save settings")
+		return
+	}
+	writePlaybackJSON(w, http.StatusOK, userSettingsResponse(saved))
+}
+
+func userSettingsResponse(settings db.UserSettings) UserSettingsResponse {
+	resp := UserSettingsResponse{MetadataScript: settings.MetadataScript, MetadataLocale: settings.MetadataLocale, TrimSilenceMinMs: settings.TrimSilenceMinMs, NormalizeLoudness: settings.NormalizeLoudness, InboxPlaylistID: settings.InboxPlaylistID, InboxNewTracks: settings.InboxNewTracks, InboxInSearch: settings.InboxInSearch, InboxInShuffle: settings.InboxInShuffle, CrossfadeSeconds: settings.CrossfadeSeconds, Gapless: settings.Gapless, NormalizationTargetLUFS: settings.NormalizationTargetLUFS, AcceptFollowers: settings.AcceptFollowers, ShareListening: settings.ShareListening, ContextSuggestions: settings.ContextSuggestions}
+	if !settings.UpdatedAt.IsZero() {
+		resp.UpdatedAt = &settings.UpdatedAt
+	}
+	return resp
+}
+
+// displaySettings loads the caller's display preferences, falling back to the
+// defaults, which leave names untouched, when there is no reader, no caller,
+// or the lookup fails.
+func displaySettings(ctx context.Context, reader userSettingsReader) db.UserSettings {
+	userCtx := auth.GetUserFromContext(ctx)
+	if reader == nil || userCtx == nil {
+		return db.DefaultUserSettings()
+	}
+	settings, err := reader.Get(ctx, userCtx.UserID)
+	if err != nil {
+		log.Printf("Loading display settings for %s failed: %v", userCtx.UserID, err)
+		return db.DefaultUserSettings()
+	}
+	return settings
+}
+
+// displaySettingsKind extends a conditional-GET kind with the settings that
+// change how names render, so a settings change invalidates cached lists.
+func displaySettingsKind(kind string, settings db.UserSettings) string {
+	if settings.MetadataScript == db.MetadataScriptOriginal && settings.MetadataLocale == "" {
+		return kind
+	}
+	return kind + ":" + settings.MetadataScript + ":" + settings.MetadataLocale
+}
+
+// localizeNames swaps title and artist for their preferred aliases in place
+// and returns the names it replaced.
+func localizeNames(settings db.UserSettings, title, artist *string, aliases []db.TrackAlias) OriginalNames {
+	var names OriginalNames
+	if name := settings.DisplayName(*title, db.AliasesFor(aliases, db.AliasFieldTitle)); name != *title {
+		names.OriginalTitle, *title = *title, name
+	}
+	if *artist == "" {
+		return names
+	}
+	if name := settings.DisplayName(*artist, db.AliasesFor(aliases, db.AliasFieldArtist)); name != *artist {
+		names.OriginalArtist, *artist = *artist, name
+	}
+	return names
+}
@@ -0,0 +1,108 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeUserSettingsStore struct {
+	byUser map[uuid.UUID]db.UserSettings
+}
+
+func (f *fakeUserSettingsStore) Get(ctx context.Context, userID uuid.UUID) (db.UserSettings, error) {
+	if settings, ok := f.byUser[userID]; ok {
+		return settings, nil
+	}
+	return db.DefaultUserSettings(), nil
+}
+
+func (f *fakeUserSettingsStore) Update(ctx context.Context, userID uuid.UUID, settings db.UserSettings) (db.UserSettings, error) {
+	f.byUser[userID] = settings
+	return settings, nil
+}
+
+type fakeSettingsPlaylists map[int64]uuid.UUID
+
+func (f fakeSettingsPlaylists) GetByID(_ context.Context, id int64) (*db.Playlist, error) {
+	if owner, ok := f[id]; ok {
+		return &db.Playlist{ID: id, UserID: owner}, nil
+	}
+	return nil, db.ErrPlaylistNotFound
+}
+
+func TestUserSettingsUpdateValidatesAndSaves(t *testing.T) {
+	store := &fakeUserSettingsStore{byUser: map[uuid.UUID]db.UserSettings{}}
+	userID := uuid.New()
+	h := NewUserSettingsHandlers(store, fakeSettingsPlaylists{4: userID, 5: uuid.New()})
+
+	put := func(body string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodPut, "/api/v1/me/settings", strings.NewReader(body))
+		rec := httptest.NewRecorder()
+		h.UpdateSettings(rec, withUser(req, userID))
+		return rec
+	}
+	for _, body := range []string{
+		`{"metadataScript":"cyrillic"}`,
+		`{"metadataLocale":"english"}`,
+		`{"metadataScript":"latin","theme":"dark"}`,
+		`{"trimSilenceMinMs":200}`,
+		`{"inboxPlaylistId":5}`,
+		`{"inboxPlaylistId":6}`,
+		`{"crossfadeSeconds":13}`,
+		`{"normalizationTargetLufs":-2}`,
+	} {
+		if rec := put(body); rec.Code != http.StatusBadRequest {
+			t.Fatalf("PUT %s status = %d, want 400", body, rec.Code)
+		}
+	}
+
+	if rec := put(`{"metadataScript":"latin","metadataLocale":"en","trimSilenceMinMs":3000,"normalizeLoudness":true,"inboxPlaylistId":4,"inboxNewTracks":true,"inboxInShuffle":true}`); rec.Code != http.StatusOK {
+		t.Fatalf("PUT status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/me/settings", nil)
+	rec := httptest.NewRecorder()
+	h.GetSettings(rec, withUser(req, userID))
+	var resp UserSettingsResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.MetadataScript != db.MetadataScriptLatin || resp.MetadataLocale != "en" || resp.TrimSilenceMinMs != 3000 || !resp.NormalizeLoudness || resp.InboxPlaylistID != 4 ||
+		!resp.InboxNewTracks || resp.InboxInSearch || !resp.InboxInShuffle || !resp.Gapless {
+		t.Fatalf("settings = %#v", resp)
+	}
+
+	if rec := put(`{"crossfadeSeconds":5,"gapless":false,"normalizationTargetLufs":-16}`); rec.Code != http.StatusOK {
+		t.Fatalf("PUT playback status = %d, want 200 (body=%s)", rec.Code, rec.Body.String())
+	}
+	if saved := store.byUser[userID]; saved.CrossfadeSeconds != 5 || saved.Gapless || saved.NormalizationTargetLUFS != -16 {
+		t.Fatalf("playback settings = %#v", saved)
+	}
+}
+
+func TestLocalizeNamesReportsReplacedNames(t *testing.T) {
+	aliases := []db.TrackAlias{
+		{Field: db.AliasFieldTitle, Name: "Sukiyaki", Locale: "en", Primary: true},
+		{Field: db.AliasFieldArtist, Name: "Kyu Sakamoto"},
+	}
+	title, artist := "上を向いて歩こう", "坂本九"
+	names := localizeNames(db.UserSettings{MetadataScript: db.MetadataScriptLatin}, &title, &artist, aliases)
+	if title != "Sukiyaki" || artist != "Kyu Sakamoto" {
+		t.Fatalf("localized = %q by %q", title, artist)
+	}
+	if names.OriginalTitle != "上を向いて歩こう" || names.OriginalArtist != "坂本九" {
+		t.Fatalf("original names = %#v", names)
+	}
+
+	title, artist = "上を向いて歩こう", "坂本九"
+	if names := localizeNames(db.DefaultUserSettings(), &title, &artist, aliases); names != (OriginalNames{}) || title != "上を向いて歩こう" {
+		t.Fatalf("default settings changed names: %q, %#v", title, names)
+	}
+}
@@ -0,0 +1,222 @@
+package api
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"io"
+	"net/http"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/middleware"
+	"github.com/openmusicplayer/backend/internal/yearcard"
+)
+
+// firstReviewYear is the earliest year a review can be asked for.
+const firstReviewYear = 2000
+
+// yearInReviewStore sums up a user's year of plays.
+// db.PlayEventRepository satisfies this interface.
+type yearInReviewStore interface {
+	YearInReview(ctx context.Context, userID uuid.UUID, year, limit int) (*db.YearInReview, error)
+}
+
+// yearReviewCardStore keeps the links cards are shared by.
+// db.YearReviewCardRepository satisfies this interface.
+type yearReviewCardStore interface {
+	Create(ctx context.Context, userID uuid.UUID, year int) (string, error)
+	Resolve(ctx context.Context, token string) (uuid.UUID, int, error)
+	Revoke(ctx context.Context, userID uuid.UUID, year int) (int, error)
+}
+
+type yearReviewUsers interface {
+	GetByID(ctx context.Context, id uuid.UUID) (*db.User, error)
+}
+
+// YearInReviewHandlers serve the caller's year in review and the cards
+// summing it up, which anyone holding a card's link can see. Cards are
+// rendered from the current stats on each request, so a link shared before
+// the year ends keeps up with it.
+type YearInReviewHandlers struct {
+	reviews yearInReviewStore
+	cards   yearReviewCardStore
+	users   yearReviewUsers
+	now     func() time.Time
+}
+
+func NewYearInReviewHandlers(reviews yearInReviewStore, cards yearReviewCardStore, users yearReviewUsers) *YearInReviewHandlers {
+	return &YearInReviewHandlers{reviews: reviews, cards: cards, users: users, now: time.Now}
+}
+
+type YearTopArtistResponse struct {
+	ArtistID        *uuid.UUID `json:"artistId,omitempty"`
+	Name            string     `json:"name"`
+	Plays           int        `json:"plays"`
+	MinutesListened int        `json:"minutesListened"`
+}
+
+// YearInReviewResponse sums up a calendar year of plays, in UTC. Minutes
+// count each play as the track's full duration.
+type YearInReviewResponse struct {
+	Year            int                      `json:"year"`
+	Plays           int                      `json:"plays"`
+	MinutesListened int                      `json:"minutesListened"`
+	UniqueTracks    int                      `json:"uniqueTracks"`
+	UniqueArtists   int                      `json:"uniqueArtists"`
+	TopArtists      []YearTopArtistResponse  `json:"topArtists"`
+	TopTracks       []PlayEventTrackResponse `json:"topTracks"`
+}
+
+type YearReviewCardRequest struct {
+	Year int `json:"year"`
+}
+
+// YearReviewCardResponse holds a new card link: an absolute URL, under the
+// scheme, host and base path the caller reached the API at, so it can be
+// shared off-site. The token in URL is shown only once.
+type YearReviewCardResponse struct {
+	Year int    `json:"year"`
+	URL  string `json:"url"`
+}
+
+// GetYearInReview handles GET /api/v1/me/year-in-review.
+// Query params: year (default the current year), limit (default 10, max 50).
+func (h *YearInReviewHandlers) GetYearInReview(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	year := query.Int("year", h.now().UTC().Year(), firstReviewYear, h.now().UTC().Year())
+	limit := query.Int("limit", 10, 1, 50)
+	if !query.Valid(w, r) {
+		return
+	}
+
+	review, err := h.reviews.YearInReview(r.Context(), userCtx.UserID, year, limit)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load year in review")
+		return
+	}
+	resp := YearInReviewResponse{
+		Year:            review.Year,
+		Plays:           review.Plays,
+		MinutesListened: review.MinutesListened,
+		UniqueTracks:    review.UniqueTracks,
+		UniqueArtists:   review.UniqueArtists,
+		TopArtists:      make([]YearTopArtistResponse, 0, len(review.TopArtists)),
+		TopTracks:       make([]PlayEventTrackResponse, 0, len(review.TopTracks)),
+	}
+	for _, a := range review.TopArtists {
+		artist := YearTopArtistResponse{Name: a.Name, Plays: a.Plays, MinutesListened: a.MinutesListened}
+		if a.ArtistID.Valid {
+			artist.ArtistID = &a.ArtistID.UUID
+		}
+		resp.TopArtists = append(resp.TopArtists, artist)
+	}
+	for _, t := range review.TopTracks {
+		track := trackToPlayEventResponse(t.Track)
+		track.LastPlayedAt = t.LastPlayedAt
+		track.PlayCount = t.PlayCount
+		resp.TopTracks = append(resp.TopTracks, track)
+	}
+	writePlayEventJSON(w, http.StatusOK, resp)
+}
+
+// ShareYearInReviewCard handles POST /api/v1/me/year-in-review/card. The
+// body's year defaults to the current one. Every call makes a new link;
+// earlier ones keep working until revoked.
+func (h *YearInReviewHandlers) ShareYearInReviewCard(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+	var req YearReviewCardRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil && !errors.Is(err, io.EOF) {
+		writePlayEventError(w, http.StatusBadRequest, "INVALID_REQUEST", "invalid request body")
+		return
+	}
+	thisYear := h.now().UTC().Year()
+	if req.Year == 0 {
+		req.Year = thisYear
+	}
+	if req.Year < firstReviewYear || req.Year > thisYear {
+		writePlayEventError(w, http.StatusBadRequest, "INVALID_REQUEST", "year must be a past or the current year")
+		return
+	}
+
+	token, err := h.cards.Create(r.Context(), userCtx.UserID, req.Year)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to share year in review card")
+		return
+	}
+	writePlayEventJSON(w, http.StatusCreated, YearReviewCardResponse{
+		Year: req.Year,
+		URL:  middleware.URL(r, "/api/v1/year-in-review/cards/"+token),
+	})
+}
+
+// RevokeYearInReviewCards handles DELETE /api/v1/me/year-in-review/card,
+// breaking every link to the card for the year query param.
+func (h *YearInReviewHandlers) RevokeYearInReviewCards(w http.ResponseWriter, r *http.Request) {
+	userCtx := auth.GetUserFromContext(r.Context())
+	if userCtx == nil {
+		writePlayEventError(w, http.StatusUnauthorized, "UNAUTHORIZED", "not authenticated")
+		return
+	}
+	query := newQueryParams(r)
+	year := query.Int("year", h.now().UTC().Year(), firstReviewYear, h.now().UTC().Year())
+	if !query.Valid(w, r) {
+		return
+	}
+	if _, err := h.cards.Revoke(r.Context(), userCtx.UserID, year); err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to revoke year in review cards")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// GetYearInReviewCard handles GET /api/v1/year-in-review/cards/{token}
+// without authentication, serving the shared card as an SVG image.
+func (h *YearInReviewHandlers) GetYearInReviewCard(w http.ResponseWriter, r *http.Request) {
+	userID, year, err := h.cards.Resolve(r.Context(), r.PathValue("token"))
+	if err != nil {
+		if errors.Is(err, db.ErrYearReviewCardNotFound) {
+			writePlayEventError(w, http.StatusNotFound, "NOT_FOUND", "year in review card not found")
+			return
+		}
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load year in review card")
+		return
+	}
+	review, err := h.reviews.YearInReview(r.Context(), userID, year, yearcard.MaxArtists)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load year in review")
+		return
+	}
+	user, err := h.users.GetByID(r.Context(), userID)
+	if err != nil {
+		writePlayEventError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to load year in review card")
+		return
+	}
+
+	card := yearcard.Card{Username: user.Username, Year: year, MinutesListened: review.MinutesListened}
+	for _, a := range review.TopArtists {
+		card.TopArtists = append(card.TopArtists, a.Name)
+	}
+	if len(review.TopTracks) > 0 {
+		card.TopTrack = review.TopTracks[0].Title
+		card.TopTrackArtist = review.TopTracks[0].Artist.String
+	}
+	w.Header().Set("Content-Type", "image/svg+xml")
+	w.Header().Set("Cache-Control", "no-store")
+	w.Header().Set("Content-Security-Policy", "default-src 'none'")
+	w.Header().Set("X-Content-Type-Options", "nosniff")
+	w.WriteHeader(http.StatusOK)
+	_, _ = w.Write(yearcard.SVG(card))
+}
@@ -0,0 +1,164 @@
+package api
+
+import (
+	"bytes"
+	"context"
+	"database/sql"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeYearInReviews map[int]*db.YearInReview
+
+func (f fakeYearInReviews) YearInReview(_ context.Context, _ uuid.UUID, year, limit int) (*db.YearInReview, error) {
+	review, ok := f[year]
+	if !ok {
+		return &db.YearInReview{Year: year}, nil
+	}
+	copied := *review
+	copied.TopArtists = copied.TopArtists[:min(limit, len(copied.TopArtists))]
+	return &copied, nil
+}
+
+type yearReviewCard struct {
+	userID uuid.UUID
+	year   int
+}
+
+type fakeYearReviewCards map[string]yearReviewCard
+
+func (f fakeYearReviewCards) Create(_ context.Context, userID uuid.UUID, year int) (string, error) {
+	token := uuid.NewString()
+	f[token] = yearReviewCard{userID: userID, year: year}
+	return token, nil
+}
+
+func (f fakeYearReviewCards) Resolve(_ context.Context, token string) (uuid.UUID, int, error) {
+	card, ok := f[token]
+	if !ok {
+		return uuid.Nil, 0, db.ErrYearReviewCardNotFound
+	}
+	return card.userID, card.year, nil
+}
+
+func (f fakeYearReviewCards) Revoke(_ context.Context, userID uuid.UUID, year int) (int, error) {
+	n := 0
+	for token, card := range f {
+		if card.userID == userID && card.year == year {
+			delete(f, token)
+			n++
+		}
+	}
+	return n, nil
+}
+
+type fakeYearReviewUsers map[uuid.UUID]*db.User
+
+func (f fakeYearReviewUsers) GetByID(_ context.Context, id uuid.UUID) (*db.User, error) {
+	if user, ok := f[id]; ok {
+		return user, nil
+	}
+	return nil, db.ErrUserNotFound
+}
+
+func newYearInReviewTestHandlers(userID uuid.UUID) *YearInReviewHandlers {
+	anthem := newTrack(1, "Anthem & Co")
+	anthem.Artist = sql.NullString{String: "Artist A", Valid: true}
+	reviews := fakeYearInReviews{2025: {
+		Year:            2025,
+		Plays:           900,
+		MinutesListened: 3000,
+		UniqueTracks:    120,
+		UniqueArtists:   40,
+		TopArtists: []db.YearTopArtist{
+			{ArtistID: uuid.NullUUID{UUID: uuid.New(), Valid: true}, Name: "Artist A", Plays: 300, MinutesListened: 1000},
+			{Name: "<b>Artist B</b>", Plays: 200, MinutesListened: 700},
+		},
+		TopTracks: []db.TopTrack{{Track: *anthem, PlayCount: 80}},
+	}}
+	users := fakeYearReviewUsers{userID: {ID: userID, Username: "ana"}}
+	h := NewYearInReviewHandlers(reviews, fakeYearReviewCards{}, users)
+	h.now = func() time.Time { return time.Date(2026, 2, 1, 12, 0, 0, 0, time.UTC) }
+	return h
+}
+
+func TestGetYearInReview(t *testing.T) {
+	userID := uuid.New()
+	h := newYearInReviewTestHandlers(userID)
+
+	rr := httptest.NewRecorder()
+	h.GetYearInReview(rr, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/year-in-review?year=2025&limit=1", nil), userID))
+	if rr.Code != http.StatusOK {
+		t.Fatalf("status = %d, body = %s", rr.Code, rr.Body.String())
+	}
+	var resp YearInReviewResponse
+	if err := json.NewDecoder(rr.Body).Decode(&resp); err != nil {
+		t.Fatalf("decode: %v", err)
+	}
+	if resp.MinutesListened != 3000 || len(resp.TopArtists) != 1 || resp.TopArtists[0].ArtistID == nil || len(resp.TopTracks) != 1 || resp.TopTracks[0].PlayCount != 80 {
+		t.Fatalf("response = %+v", resp)
+	}
+
+	rr = httptest.NewRecorder()
+	h.GetYearInReview(rr, withUser(httptest.NewRequest(http.MethodGet, "/api/v1/me/year-in-review", nil), userID))
+	if err := json.NewDecoder(rr.Body).Decode(&resp); err != nil || resp.Year != 2026 || resp.TopArtists == nil {
+		t.Fatalf("default year response = %+v, %v; want an empty 2026", resp, err)
+	}
+}
+
+func TestYearInReviewCardLinks(t *testing.T) {
+	userID := uuid.New()
+	h := newYearInReviewTestHandlers(userID)
+
+	rr := httptest.NewRecorder()
+	h.ShareYearInReviewCard(rr, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/me/year-in-review/card", bytes.NewBufferString(`{"year":2030}`)), userID))
+	if rr.Code != http.StatusBadRequest {
+		t.Fatalf("future year status = %d, want 400", rr.Code)
+	}
+
+	rr = httptest.NewRecorder()
+	h.ShareYearInReviewCard(rr, withUser(httptest.NewRequest(http.MethodPost, "/api/v1/me/year-in-review/card", bytes.NewBufferString(`{"year":2025}`)), userID))
+	if rr.Code != http.StatusCreated {
+		t.Fatalf("share status = %d, body = %s", rr.Code, rr.Body.String())
+	}
+	var shared YearReviewCardResponse
+	if err := json.NewDecoder(rr.Body).Decode(&shared); err != nil || !strings.HasPrefix(shared.URL, "http://example.com/api/v1/year-in-review/cards/") {
+		t.Fatalf("share response = %+v, %v", shared, err)
+	}
+	token := strings.TrimPrefix(shared.URL, "http://example.com/api/v1/year-in-review/cards/")
+
+	getCard := func() *httptest.ResponseRecorder {
+		req := httptest.NewRequest(http.MethodGet, shared.URL, nil)
+		req.SetPathValue("token", token)
+		rr := httptest.NewRecorder()
+		h.GetYearInReviewCard(rr, req)
+		return rr
+	}
+	rr = getCard()
+	if rr.Code != http.StatusOK || rr.Header().Get("Content-Type") != "image/svg+xml" || rr.Header().Get("Cache-Control") != "no-store" {
+		t.Fatalf("card status = %d, headers = %v", rr.Code, rr.Header())
+	}
+	svg := rr.Body.String()
+	for _, want := range []string{"ana&#39;s 2025 in music", "3,000", "1. Artist A", "&lt;b&gt;Artist B&lt;/b&gt;", "Anthem &amp; Co — Artist A"} {
+		if !strings.Contains(svg, want) {
+			t.Errorf("card lacks %q: %s", want, svg)
+		}
+	}
+
+	rr = httptest.NewRecorder()
+	h.RevokeYearInReviewCards(rr, withUser(httptest.NewRequest(http.MethodDelete, "/api/v1/me/year-in-review/card?year=2025", nil), userID))
+	if rr.Code != http.StatusNoContent {
+		t.Fatalf("revoke status = %d", rr.Code)
+	}
+	if rr = getCard(); rr.Code != http.StatusNotFound {
+		t.Fatalf("revoked card status = %d, want 404", rr.Code)
+	}
+}
@@ -0,0 +1,103 @@
+// Package artistinfo gathers what artist pages show beyond MusicBrainz:
+// biographies from Wikipedia, found through the artist's Wikidata item, and
+// similar artists from ListenBrainz or Last.fm. Both are kept in the
+// database so each is fetched once per refresh period.
+package artistinfo
+
+import (
+	"context"
+	"errors"
+	"log"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// storedMaxAge is how long a stored bio or similar-artist list, or a stored
+// miss, is served before it is fetched again.
+const storedMaxAge = 30 * 24 * time.Hour
+
+// wikipediaLicense is the license Wikipedia text is reused under; clients
+// show it with the article link.
+const wikipediaLicense = "CC BY-SA 4.0"
+
+type bioStore interface {
+	Get(ctx context.Context, artistID uuid.UUID, language string) (*db.ArtistBio, error)
+	Upsert(ctx context.Context, bio *db.ArtistBio) error
+}
+
+type extractFetcher interface {
+	Extract(ctx context.Context, wikidataID, language string) (*Extract, error)
+}
+
+// BioService serves artist biographies from the database, fetching them
+// from Wikipedia when missing or stale.
+type BioService struct {
+	store bioStore
+	wiki  extractFetcher
+	now   func() time.Time
+}
+
+func NewBioService(store bioStore, wiki extractFetcher) *BioService {
+	return &BioService{store: store, wiki: wiki, now: time.Now}
+}
+
+// ArtistBio returns the artist's bio in the language of locale, such as
+// "de" or "pt-BR", falling back to English when that language has no
+// article. It returns nil when the artist has no article at all. A stale
+// bio is still returned when refreshing it fails.
+func (s *BioService) ArtistBio(ctx context.Context, artistID uuid.UUID, wikidataID, locale string) (*db.ArtistBio, error) {
+	language := ArticleLanguage(locale)
+	cached, err := s.store.Get(ctx, artistID, language)
+	if err != nil && !errors.Is(err, db.ErrArtistBioNotFound) {
+		return nil, err
+	}
+	if cached != nil && s.now().Sub(cached.FetchedAt) < storedMaxAge {
+		return present(cached), nil
+	}
+
+	bio := &db.ArtistBio{MBArtistID: artistID, Language: language, Source: db.ArtistBioSourceWikipedia}
+	extract, err := s.wiki.Extract(ctx, wikidataID, language)
+	switch {
+	case errors.Is(err, ErrNoArticle):
+		// Stored empty, so the miss is not looked up again on every visit.
+	case err != nil:
+		if cached != nil {
+			log.Printf("Refreshing bio for artist %s failed, serving the stored one: %v", artistID, err)
+			return present(cached), nil
+		}
+		return nil, err
+	default:
+		bio.ContentLanguage = extract.Language
+		bio.Extract = extract.Text
+		bio.Title = extract.Title
+		bio.URL = extract.URL
+		bio.License = wikipediaLicense
+	}
+	if err := s.store.Upsert(ctx, bio); err != nil {
+		log.Printf("Storing bio for artist %s failed: %v", artistID, err)
+	}
+	return present(bio), nil
+}
+
+// present hides stored misses from callers.
+func present(bio *db.ArtistBio) *db.ArtistBio {
+	if bio.Extract == "" {
+		return nil
+	}
+	return bio
+}
+
+// ArticleLanguage maps a locale to a Wikipedia language edition: its primary
+// subtag, lowercased, or "en" when it has none.
+func ArticleLanguage(locale string) string {
+	language, _, _ := strings.Cut(strings.ToLower(strings.TrimSpace(locale)), "-")
+	language, _, _ = strings.Cut(language, "_")
+	if !languagePattern.MatchString(language) {
+		return "en"
+	}
+	return language
+}
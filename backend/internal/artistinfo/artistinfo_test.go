@@ -0,0 +1,145 @@
+package artistinfo
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func newWikiServer(t *testing.T, summaries map[string]string) *httptest.Server {
+	t.Helper()
+	mux := http.NewServeMux()
+	mux.HandleFunc("GET /wiki/Special:EntityData/Q44190.json", func(w http.ResponseWriter, r *http.Request) {
+		_, _ = w.Write([]byte(`{"entities": {"Q44190": {"sitelinks": {
+			"enwiki": {"site": "enwiki", "title": "Radiohead"},
+			"dewiki": {"site": "dewiki", "title": "Radiohead (Band)"}
+		}}}}`))
+	})
+	mux.HandleFunc("GET /{lang}/api/rest_v1/page/summary/{title}", func(w http.ResponseWriter, r *http.Request) {
+		body, ok := summaries[r.PathValue("lang")+":"+r.PathValue("title")]
+		if !ok {
+			http.NotFound(w, r)
+			return
+		}
+		_, _ = w.Write([]byte(body))
+	})
+	server := httptest.NewServer(mux)
+	t.Cleanup(server.Close)
+	return server
+}
+
+func TestExtractFollowsSitelinkInPreferredLanguage(t *testing.T) {
+	server := newWikiServer(t, map[string]string{
+		"de:Radiohead_(Band)": `{"title": "Radiohead (Band)", "extract": "Radiohead ist eine britische Rockband.", "lang": "de",
+			"content_urls": {"desktop": {"page": "https://de.wikipedia.org/wiki/Radiohead_(Band)"}}}`,
+		"en:Radiohead": `{"title": "Radiohead", "extract": "Radiohead are an English rock band.", "lang": "en",
+			"content_urls": {"desktop": {"page": "https://en.wikipedia.org/wiki/Radiohead"}}}`,
+	})
+	client := NewWikipediaClient(WithWikidataURL(server.URL), WithWikipediaURL(server.URL+"/%s"))
+	ctx := context.Background()
+
+	extract, err := client.Extract(ctx, "Q44190", "de")
+	if err != nil {
+		t.Fatalf("Extract(de): %v", err)
+	}
+	if extract.Language != "de" || extract.Title != "Radiohead (Band)" || extract.URL != "https://de.wikipedia.org/wiki/Radiohead_(Band)" {
+		t.Fatalf("de extract = %+v", extract)
+	}
+	extract, err = client.Extract(ctx, "Q44190", "ja")
+	if err != nil || extract.Language != "en" || extract.Text != "Radiohead are an English rock band." {
+		t.Fatalf("Extract(ja) = %+v, %v; want the English article", extract, err)
+	}
+	if _, err := client.Extract(ctx, "Q1", "en"); !errors.Is(err, ErrNoArticle) {
+		t.Fatalf("unknown item = %v, want ErrNoArticle", err)
+	}
+	if _, err := client.Extract(ctx, "../Q44190", "en"); err == nil {
+		t.Fatal("malformed Wikidata ID was requested")
+	}
+}
+
+type fakeBioStore struct {
+	bios    map[string]*db.ArtistBio
+	upserts int
+}
+
+func (f *fakeBioStore) Get(_ context.Context, artistID uuid.UUID, language string) (*db.ArtistBio, error) {
+	if bio, ok := f.bios[artistID.String()+language]; ok {
+		copied := *bio
+		return &copied, nil
+	}
+	return nil, db.ErrArtistBioNotFound
+}
+
+func (f *fakeBioStore) Upsert(_ context.Context, bio *db.ArtistBio) error {
+	f.upserts++
+	bio.FetchedAt = time.Now()
+	f.bios[bio.MBArtistID.String()+bio.Language] = bio
+	return nil
+}
+
+type fakeExtractFetcher struct {
+	extract *Extract
+	err     error
+	calls   int
+}
+
+func (f *fakeExtractFetcher) Extract(context.Context, string, string) (*Extract, error) {
+	f.calls++
+	return f.extract, f.err
+}
+
+func TestBioServiceCachesArticlesAndMisses(t *testing.T) {
+	ctx := context.Background()
+	artistID := uuid.New()
+	store := &fakeBioStore{bios: map[string]*db.ArtistBio{}}
+	wiki := &fakeExtractFetcher{extract: &Extract{Title: "Radiohead", Text: "English rock band.", URL: "https://en.wikipedia.org/wiki/Radiohead", Language: "en"}}
+	service := NewBioService(store, wiki)
+
+	for range 2 {
+		bio, err := service.ArtistBio(ctx, artistID, "Q44190", "en-GB")
+		if err != nil || bio == nil || bio.Extract != "English rock band." || bio.License != wikipediaLicense || bio.Language != "en" {
+			t.Fatalf("bio = %+v, %v", bio, err)
+		}
+	}
+	if wiki.calls != 1 || store.upserts != 1 {
+		t.Fatalf("fetched %d times, stored %d; want one of each", wiki.calls, store.upserts)
+	}
+
+	// A stale bio is refreshed, and kept when the refresh fails.
+	service.now = func() time.Time { return time.Now().Add(storedMaxAge + time.Hour) }
+	wiki.err = errors.New("wikipedia unavailable")
+	if bio, err := service.ArtistBio(ctx, artistID, "Q44190", "en"); err != nil || bio == nil {
+		t.Fatalf("stale bio after failed refresh = %+v, %v", bio, err)
+	}
+	if wiki.calls != 2 {
+		t.Fatalf("stale bio was not refreshed")
+	}
+
+	// A missing article is stored so it is not looked up on every visit.
+	service.now = time.Now
+	wiki.err = ErrNoArticle
+	other := uuid.New()
+	for range 2 {
+		if bio, err := service.ArtistBio(ctx, other, "Q1", "en"); err != nil || bio != nil {
+			t.Fatalf("bio without article = %+v, %v", bio, err)
+		}
+	}
+	if wiki.calls != 3 {
+		t.Fatalf("article misses fetched %d times, want once", wiki.calls-2)
+	}
+}
+
+func TestArticleLanguage(t *testing.T) {
+	for locale, want := range map[string]string{"": "en", "ja": "ja", "pt-BR": "pt", "zh_Hant": "zh", "EN": "en", "x-klingon": "en"} {
+		if got := ArticleLanguage(locale); got != want {
+			t.Errorf("ArticleLanguage(%q) = %q, want %q", locale, got, want)
+		}
+	}
+}
@@ -0,0 +1,195 @@
+package artistinfo
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+	"net/http"
+	"net/url"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/tracing"
+)
+
+const (
+	defaultListenBrainzURL = "https://labs.api.listenbrainz.org"
+	defaultLastFMURL       = "https://ws.audioscrobbler.com"
+	// listenBrainzAlgorithm is the similarity dataset ListenBrainz's own
+	// artist pages use.
+	listenBrainzAlgorithm = "session_based_days_7500_session_300_contribution_5_threshold_10_limit_100_filter_True_skip_30"
+	// similarFetchLimit is how many similar artists are fetched and stored;
+	// callers take a prefix.
+	similarFetchLimit = 50
+)
+
+// SimilarSource fetches an artist's similar artists, most similar first.
+type SimilarSource interface {
+	Source() string
+	SimilarArtists(ctx context.Context, artistID uuid.UUID) ([]db.SimilarArtist, error)
+}
+
+type similarStore interface {
+	Get(ctx context.Context, artistID uuid.UUID) (*db.SimilarArtistList, error)
+	Upsert(ctx context.Context, list *db.SimilarArtistList) error
+}
+
+// SimilarService serves similar artists from the database, fetching them
+// from the configured source when missing, stale, or stored from another
+// source.
+type SimilarService struct {
+	store  similarStore
+	source SimilarSource
+	now    func() time.Time
+}
+
+func NewSimilarService(store similarStore, source SimilarSource) *SimilarService {
+	return &SimilarService{store: store, source: source, now: time.Now}
+}
+
+// SimilarArtists returns up to limit of the artist's similar artists. A
+// stale list is still returned when refreshing it fails.
+func (s *SimilarService) SimilarArtists(ctx context.Context, artistID uuid.UUID, limit int) ([]db.SimilarArtist, error) {
+	cached, err := s.store.Get(ctx, artistID)
+	if err != nil && !errors.Is(err, db.ErrSimilarArtistsNotFound) {
+		return nil, err
+	}
+	if cached != nil && cached.Source == s.source.Source() && s.now().Sub(cached.FetchedAt) < storedMaxAge {
+		return firstArtists(cached.Artists, limit), nil
+	}
+
+	artists, err := s.source.SimilarArtists(ctx, artistID)
+	if err != nil {
+		if cached != nil {
+			log.Printf("Refreshing similar artists for %s failed, serving the stored list: %v", artistID, err)
+			return firstArtists(cached.Artists, limit), nil
+		}
+		return nil, err
+	}
+	list := &db.SimilarArtistList{MBArtistID: artistID, Source: s.source.Source(), Artists: artists}
+	if err := s.store.Upsert(ctx, list); err != nil {
+		log.Printf("Storing similar artists for %s failed: %v", artistID, err)
+	}
+	return firstArtists(artists, limit), nil
+}
+
+func firstArtists(artists []db.SimilarArtist, limit int) []db.SimilarArtist {
+	if limit > 0 && len(artists) > limit {
+		return artists[:limit]
+	}
+	return artists
+}
+
+// ListenBrainzClient reads artist similarity from ListenBrainz Labs, which
+// needs no account.
+type ListenBrainzClient struct {
+	httpClient *http.Client
+	baseURL    string
+}
+
+func NewListenBrainzClient(baseURL string) *ListenBrainzClient {
+	if baseURL == "" {
+		baseURL = defaultListenBrainzURL
+	}
+	return &ListenBrainzClient{httpClient: newHTTPClient(), baseURL: strings.TrimSuffix(baseURL, "/")}
+}
+
+func (c *ListenBrainzClient) Source() string { return "listenbrainz" }
+
+func (c *ListenBrainzClient) SimilarArtists(ctx context.Context, artistID uuid.UUID) ([]db.SimilarArtist, error) {
+	query := url.Values{"artist_mbids": {artistID.String()}, "algorithm": {listenBrainzAlgorithm}}
+	var entries []struct {
+		ArtistMBID string  `json:"artist_mbid"`
+		Name       string  `json:"name"`
+		Score      float64 `json:"score"`
+	}
+	err := getJSON(ctx, c.httpClient, c.baseURL+"/similar-artists/json?"+query.Encode(), &entries)
+	if errors.Is(err, errNotFound) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("listenbrainz similar artists for %s: %w", artistID, err)
+	}
+	artists := make([]db.SimilarArtist, 0, min(len(entries), similarFetchLimit))
+	for _, entry := range entries {
+		id, err := uuid.Parse(entry.ArtistMBID)
+		if err != nil || id == artistID || len(artists) == similarFetchLimit {
+			continue
+		}
+		artists = append(artists, db.SimilarArtist{MBArtistID: id, Name: entry.Name, Score: entry.Score})
+	}
+	return artists, nil
+}
+
+// LastFMClient reads artist.getSimilar from Last.fm with an API key.
+// Similar artists Last.fm cannot tie to a MusicBrainz ID are left out.
+type LastFMClient struct {
+	httpClient *http.Client
+	baseURL    string
+	apiKey     string
+}
+
+func NewLastFMClient(baseURL, apiKey string) *LastFMClient {
+	if baseURL == "" {
+		baseURL = defaultLastFMURL
+	}
+	return &LastFMClient{httpClient: newHTTPClient(), baseURL: strings.TrimSuffix(baseURL, "/"), apiKey: apiKey}
+}
+
+func (c *LastFMClient) Source() string { return "lastfm" }
+
+// lastFMArtistNotFound is Last.fm's error code for an unknown artist.
+const lastFMArtistNotFound = 6
+
+func (c *LastFMClient) SimilarArtists(ctx context.Context, artistID uuid.UUID) ([]db.SimilarArtist, error) {
+	query := url.Values{
+		"method":  {"artist.getsimilar"},
+		"mbid":    {artistID.String()},
+		"api_key": {c.apiKey},
+		"format":  {"json"},
+		"limit":   {strconv.Itoa(similarFetchLimit)},
+	}
+	var resp struct {
+		Error          int    `json:"error"`
+		Message        string `json:"message"`
+		SimilarArtists struct {
+			Artist []struct {
+				Name  string `json:"name"`
+				MBID  string `json:"mbid"`
+				Match string `json:"match"`
+			} `json:"artist"`
+		} `json:"similarartists"`
+	}
+	if err := getJSON(ctx, c.httpClient, c.baseURL+"/2.0/?"+query.Encode(), &resp); err != nil && !errors.Is(err, errNotFound) {
+		return nil, fmt.Errorf("last.fm similar artists for %s: %w", artistID, err)
+	}
+	switch resp.Error {
+	case 0:
+	case lastFMArtistNotFound:
+		return nil, nil
+	default:
+		return nil, fmt.Errorf("last.fm similar artists for %s: error %d: %s", artistID, resp.Error, resp.Message)
+	}
+	var artists []db.SimilarArtist
+	for _, entry := range resp.SimilarArtists.Artist {
+		id, err := uuid.Parse(entry.MBID)
+		if err != nil || id == artistID {
+			continue
+		}
+		score, _ := strconv.ParseFloat(entry.Match, 64)
+		artists = append(artists, db.SimilarArtist{MBArtistID: id, Name: entry.Name, Score: score})
+	}
+	return artists, nil
+}
+
+func newHTTPClient() *http.Client {
+	return &http.Client{
+		Timeout:   10 * time.Second,
+		Transport: tracing.NewTransport(nil),
+	}
+}
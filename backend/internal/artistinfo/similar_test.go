@@ -0,0 +1,122 @@
+package artistinfo
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+var (
+	radioheadID = uuid.MustParse("a74b1b7f-71a5-4011-9441-d0b5e4122711")
+	thomYorkeID = uuid.MustParse("8bfac288-ccc5-448d-9573-c33ea2aa5c30")
+)
+
+func TestListenBrainzSimilarArtists(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/similar-artists/json" || r.URL.Query().Get("artist_mbids") != radioheadID.String() || r.URL.Query().Get("algorithm") == "" {
+			t.Errorf("unexpected request %s", r.URL)
+		}
+		_, _ = w.Write([]byte(`[
+			{"artist_mbid": "` + thomYorkeID.String() + `", "name": "Thom Yorke", "comment": "", "type": "Person", "score": 2210},
+			{"artist_mbid": "` + radioheadID.String() + `", "name": "Radiohead", "score": 100},
+			{"artist_mbid": "not-a-uuid", "name": "Broken", "score": 10}
+		]`))
+	}))
+	defer server.Close()
+
+	artists, err := NewListenBrainzClient(server.URL).SimilarArtists(context.Background(), radioheadID)
+	if err != nil {
+		t.Fatalf("SimilarArtists: %v", err)
+	}
+	if len(artists) != 1 || artists[0].MBArtistID != thomYorkeID || artists[0].Name != "Thom Yorke" || artists[0].Score != 2210 {
+		t.Fatalf("artists = %+v; want only Thom Yorke", artists)
+	}
+}
+
+func TestLastFMSimilarArtists(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		query := r.URL.Query()
+		if query.Get("method") != "artist.getsimilar" || query.Get("api_key") != "secret" {
+			t.Errorf("unexpected request %s", r.URL)
+		}
+		if query.Get("mbid") != radioheadID.String() {
+			_, _ = w.Write([]byte(`{"error": 6, "message": "The artist you supplied could not be found"}`))
+			return
+		}
+		_, _ = w.Write([]byte(`{"similarartists": {"artist": [
+			{"name": "Thom Yorke", "mbid": "` + thomYorkeID.String() + `", "match": "1"},
+			{"name": "Unlinked", "mbid": "", "match": "0.8"}
+		]}}`))
+	}))
+	defer server.Close()
+	client := NewLastFMClient(server.URL, "secret")
+
+	artists, err := client.SimilarArtists(context.Background(), radioheadID)
+	if err != nil || len(artists) != 1 || artists[0].Score != 1 {
+		t.Fatalf("artists = %+v, %v; want only the linked artist", artists, err)
+	}
+	artists, err = client.SimilarArtists(context.Background(), uuid.New())
+	if err != nil || len(artists) != 0 {
+		t.Fatalf("unknown artist = %+v, %v; want none", artists, err)
+	}
+}
+
+type fakeSimilarStore struct {
+	lists map[uuid.UUID]*db.SimilarArtistList
+}
+
+func (f *fakeSimilarStore) Get(_ context.Context, artistID uuid.UUID) (*db.SimilarArtistList, error) {
+	if list, ok := f.lists[artistID]; ok {
+		return list, nil
+	}
+	return nil, db.ErrSimilarArtistsNotFound
+}
+
+func (f *fakeSimilarStore) Upsert(_ context.Context, list *db.SimilarArtistList) error {
+	list.FetchedAt = time.Now()
+	f.lists[list.MBArtistID] = list
+	return nil
+}
+
+type fakeSimilarSource struct {
+	name  string
+	err   error
+	calls int
+}
+
+func (f *fakeSimilarSource) Source() string { return f.name }
+
+func (f *fakeSimilarSource) SimilarArtists(context.Context, uuid.UUID) ([]db.SimilarArtist, error) {
+	f.calls++
+	return []db.SimilarArtist{{MBArtistID: thomYorkeID, Name: "Thom Yorke"}, {MBArtistID: uuid.New(), Name: "Atoms for Peace"}}, f.err
+}
+
+func TestSimilarServiceCachesPerSource(t *testing.T) {
+	ctx := context.Background()
+	store := &fakeSimilarStore{lists: map[uuid.UUID]*db.SimilarArtistList{}}
+	source := &fakeSimilarSource{name: "listenbrainz"}
+	service := NewSimilarService(store, source)
+
+	for range 2 {
+		artists, err := service.SimilarArtists(ctx, radioheadID, 1)
+		if err != nil || len(artists) != 1 || artists[0].Name != "Thom Yorke" {
+			t.Fatalf("artists = %+v, %v", artists, err)
+		}
+	}
+	if source.calls != 1 {
+		t.Fatalf("fetched %d times, want once", source.calls)
+	}
+
+	// Switching sources refetches; a failed refresh serves the stored list.
+	service = NewSimilarService(store, &fakeSimilarSource{name: "lastfm", err: errors.New("last.fm unavailable")})
+	if artists, err := service.SimilarArtists(ctx, radioheadID, 0); err != nil || len(artists) != 2 {
+		t.Fatalf("artists after failed refresh = %+v, %v", artists, err)
+	}
+}
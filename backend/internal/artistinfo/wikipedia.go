@@ -0,0 +1,163 @@
+package artistinfo
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"regexp"
+	"strings"
+)
+
+const (
+	defaultWikidataURL  = "https://www.wikidata.org"
+	defaultWikipediaURL = "https://%s.wikipedia.org"
+	userAgent           = "OpenMusicPlayer/1.0.0 (https://github.com/openmusicplayer)"
+	// maxResponseBytes bounds a Wikidata entity, page summary or similarity
+	// list; they run from a few kilobytes to a few hundred.
+	maxResponseBytes = 4 << 20
+)
+
+// ErrNoArticle is returned when the Wikidata item links no Wikipedia article
+// in the requested language or English.
+var ErrNoArticle = errors.New("no wikipedia article")
+
+var errNotFound = errors.New("not found")
+
+var (
+	wikidataIDPattern = regexp.MustCompile(`^Q[0-9]+$`)
+	languagePattern   = regexp.MustCompile(`^[a-z]{2,3}$`)
+)
+
+// Extract is the lead section of a Wikipedia article as plain text.
+type Extract struct {
+	Title    string
+	Text     string
+	URL      string
+	Language string
+}
+
+// WikipediaClient reads article extracts for Wikidata items.
+type WikipediaClient struct {
+	httpClient   *http.Client
+	wikidataURL  string
+	wikipediaURL string
+}
+
+type Option func(*WikipediaClient)
+
+// WithWikidataURL points the client at another Wikidata host.
+func WithWikidataURL(u string) Option {
+	return func(c *WikipediaClient) {
+		c.wikidataURL = strings.TrimSuffix(u, "/")
+	}
+}
+
+// WithWikipediaURL points the client at other Wikipedia hosts; pattern has
+// one %s for the language edition, as in "https://%s.wikipedia.org".
+func WithWikipediaURL(pattern string) Option {
+	return func(c *WikipediaClient) {
+		c.wikipediaURL = strings.TrimSuffix(pattern, "/")
+	}
+}
+
+func NewWikipediaClient(opts ...Option) *WikipediaClient {
+	c := &WikipediaClient{
+		httpClient:   newHTTPClient(),
+		wikidataURL:  defaultWikidataURL,
+		wikipediaURL: defaultWikipediaURL,
+	}
+	for _, opt := range opts {
+		opt(c)
+	}
+	return c
+}
+
+// Extract resolves the Wikidata item to its Wikipedia article in language,
+// or in English when that edition has none, and returns the article's lead.
+func (c *WikipediaClient) Extract(ctx context.Context, wikidataID, language string) (*Extract, error) {
+	if !wikidataIDPattern.MatchString(wikidataID) {
+		return nil, fmt.Errorf("invalid Wikidata ID %q", wikidataID)
+	}
+	if !languagePattern.MatchString(language) {
+		language = "en"
+	}
+
+	var entity struct {
+		Entities map[string]struct {
+			Sitelinks map[string]struct {
+				Title string `json:"title"`
+			} `json:"sitelinks"`
+		} `json:"entities"`
+	}
+	err := getJSON(ctx, c.httpClient, c.wikidataURL+"/wiki/Special:EntityData/"+wikidataID+".json", &entity)
+	if errors.Is(err, errNotFound) {
+		return nil, ErrNoArticle
+	}
+	if err != nil {
+		return nil, fmt.Errorf("wikidata %s: %w", wikidataID, err)
+	}
+	var title string
+	for _, item := range entity.Entities {
+		if link, ok := item.Sitelinks[language+"wiki"]; ok {
+			title = link.Title
+		} else if link, ok := item.Sitelinks["enwiki"]; ok {
+			title, language = link.Title, "en"
+		}
+	}
+	if title == "" {
+		return nil, ErrNoArticle
+	}
+
+	var summary struct {
+		Title       string `json:"title"`
+		Extract     string `json:"extract"`
+		Lang        string `json:"lang"`
+		ContentURLs struct {
+			Desktop struct {
+				Page string `json:"page"`
+			} `json:"desktop"`
+		} `json:"content_urls"`
+	}
+	page := url.PathEscape(strings.ReplaceAll(title, " ", "_"))
+	err = getJSON(ctx, c.httpClient, fmt.Sprintf(c.wikipediaURL, language)+"/api/rest_v1/page/summary/"+page, &summary)
+	if errors.Is(err, errNotFound) {
+		return nil, ErrNoArticle
+	}
+	if err != nil {
+		return nil, fmt.Errorf("wikipedia %s:%s: %w", language, title, err)
+	}
+	if summary.Extract == "" {
+		return nil, ErrNoArticle
+	}
+	extract := &Extract{Title: summary.Title, Text: summary.Extract, URL: summary.ContentURLs.Desktop.Page, Language: summary.Lang}
+	if extract.Language == "" {
+		extract.Language = language
+	}
+	return extract, nil
+}
+
+// getJSON decodes the JSON body of a GET. A 404 is errNotFound.
+func getJSON(ctx context.Context, client *http.Client, reqURL string, v any) error {
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, reqURL, nil)
+	if err != nil {
+		return err
+	}
+	req.Header.Set("User-Agent", userAgent)
+	req.Header.Set("Accept", "application/json")
+	resp, err := client.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode == http.StatusNotFound {
+		return errNotFound
+	}
+	if resp.StatusCode != http.StatusOK {
+		return fmt.Errorf("status %d", resp.StatusCode)
+	}
+	return json.NewDecoder(io.LimitReader(resp.Body, maxResponseBytes)).Decode(v)
+}
@@ -6,6 +6,10 @@ import (
 	"strings"
 
 	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/logger"
 )
 
 type contextKey string
@@ -17,18 +21,23 @@ type UserContext struct {
 	Email  string
 }
 
+// WriteUnauthorized writes a 401 problem response for a rejected credential.
+func WriteUnauthorized(w http.ResponseWriter, r *http.Request, code, message string) {
+	apperrors.WriteProblem(w, r, apperrors.NewProblem(http.StatusUnauthorized, code, message))
+}
+
 func Middleware(authService *Service) func(http.Handler) http.Handler {
 	return func(next http.Handler) http.Handler {
 		return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
 			authHeader := r.Header.Get("Authorization")
 			if authHeader == "" {
-				http.Error(w, `{"code":"UNAUTHORIZED","message":"missing authorization header"}`, http.StatusUnauthorized)
+				WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "missing authorization header")
 				return
 			}
 
 			parts := strings.SplitN(authHeader, " ", 2)
 			if len(parts) != 2 || strings.ToLower(parts[0]) != "bearer" {
-				http.Error(w, `{"code":"UNAUTHORIZED","message":"invalid authorization header format"}`, http.StatusUnauthorized)
+				WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "invalid authorization header format")
 				return
 			}
 
@@ -36,16 +45,16 @@ func Middleware(authService *Service) func(http.Handler) http.Handler {
 			claims, err := authService.ValidateAccessToken(tokenString)
 			if err != nil {
 				if err == ErrTokenExpired {
-					http.Error(w, `{"code":"TOKEN_EXPIRED","message":"access token has expired"}`, http.StatusUnauthorized)
+					WriteUnauthorized(w, r, apperrors.CodeTokenExpired, "access token has expired")
 					return
 				}
-				http.Error(w, `{"code":"UNAUTHORIZED","message":"invalid access token"}`, http.StatusUnauthorized)
+				WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "invalid access token")
 				return
 			}
 
 			userID, err := uuid.Parse(claims.UserID)
 			if err != nil {
-				http.Error(w, `{"code":"UNAUTHORIZED","message":"invalid user ID in token"}`, http.StatusUnauthorized)
+				WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "invalid user ID in token")
 				return
 			}
 
@@ -55,6 +64,8 @@ func Middleware(authService *Service) func(http.Handler) http.Handler {
 			}
 
 			ctx := context.WithValue(r.Context(), UserContextKey, userCtx)
+			ctx = logger.SetRequestUserID(ctx, userID.String())
+			ctx = db.WithActor(ctx, userID)
 			next.ServeHTTP(w, r.WithContext(ctx))
 		})
 	}
@@ -0,0 +1,158 @@
+package certs
+
+import (
+	"context"
+	"crypto/tls"
+	"crypto/x509"
+	"errors"
+	"fmt"
+	"net"
+	"net/http"
+	"strings"
+	"sync/atomic"
+	"time"
+
+	"golang.org/x/crypto/acme"
+	"golang.org/x/crypto/acme/autocert"
+)
+
+// ecdsaSuites ask autocert for the ECDSA certificate modern clients are
+// served, rather than the RSA fallback it issues for older ones.
+var ecdsaSuites = []uint16{tls.TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256}
+
+// Config selects where the TLS listener's certificates come from. With
+// Domains, they are issued over ACME by the CA at DirectoryURL, Let's
+// Encrypt when empty, and cached in CacheDir. Otherwise the certificate in
+// CertFile and its key in KeyFile are served.
+type Config struct {
+	Domains      []string
+	Email        string
+	CacheDir     string
+	DirectoryURL string
+	CertFile     string
+	KeyFile      string
+}
+
+// Enabled reports whether c names any certificate source.
+func (c Config) Enabled() bool {
+	return len(c.Domains) > 0 || c.CertFile != "" || c.KeyFile != ""
+}
+
+// Manager hands out certificates for TLS handshakes.
+type Manager struct {
+	cfg    Config
+	acme   *autocert.Manager
+	static atomic.Pointer[tls.Certificate]
+}
+
+func New(cfg Config) (*Manager, error) {
+	m := &Manager{cfg: cfg}
+	switch {
+	case len(cfg.Domains) > 0 && (cfg.CertFile != "" || cfg.KeyFile != ""):
+		return nil, errors.New("set either ACME domains or a certificate file, not both")
+	case len(cfg.Domains) > 0:
+		if cfg.CacheDir == "" {
+			return nil, errors.New("ACME needs a cache directory for issued certificates")
+		}
+		// Naming the domains is the operator agreeing to the CA's terms.
+		m.acme = &autocert.Manager{
+			Prompt:     autocert.AcceptTOS,
+			HostPolicy: autocert.HostWhitelist(cfg.Domains...),
+			Cache:      autocert.DirCache(cfg.CacheDir),
+			Email:      cfg.Email,
+		}
+		if cfg.DirectoryURL != "" {
+			m.acme.Client = &acme.Client{DirectoryURL: cfg.DirectoryURL}
+		}
+	case cfg.CertFile == "" || cfg.KeyFile == "":
+		return nil, errors.New("a certificate file needs a key file, and a key file a certificate file")
+	default:
+		if _, err := m.load(); err != nil {
+			return nil, err
+		}
+	}
+	return m, nil
+}
+
+// TLSConfig is the configuration for the TLS listener. With ACME it also
+// answers TLS-ALPN-01 challenges.
+func (m *Manager) TLSConfig() *tls.Config {
+	if m.acme != nil {
+		config := m.acme.TLSConfig()
+		config.MinVersion = tls.VersionTLS12
+		return config
+	}
+	return &tls.Config{
+		MinVersion: tls.VersionTLS12,
+		GetCertificate: func(*tls.ClientHelloInfo) (*tls.Certificate, error) {
+			return m.static.Load(), nil
+		},
+	}
+}
+
+// HTTPHandler answers ACME HTTP-01 challenges on the plain HTTP listener
+// and hands every other request to next.
+func (m *Manager) HTTPHandler(next http.Handler) http.Handler {
+	if m.acme == nil {
+		return next
+	}
+	return m.acme.HTTPHandler(next)
+}
+
+// Renew makes sure every domain has a certificate, obtaining any not yet
+// issued, or reloads the certificate files so replaced ones are served. It
+// returns the earliest expiry among the served certificates. autocert
+// renews each certificate it has loaded 30 days before it expires.
+func (m *Manager) Renew(ctx context.Context) (time.Time, error) {
+	if m.acme == nil {
+		return m.load()
+	}
+	var earliest time.Time
+	var errs []error
+	for _, domain := range m.cfg.Domains {
+		if err := ctx.Err(); err != nil {
+			return earliest, err
+		}
+		cert, err := m.acme.GetCertificate(&tls.ClientHelloInfo{ServerName: domain, CipherSuites: ecdsaSuites})
+		if err != nil {
+			errs = append(errs, fmt.Errorf("%s: %w", domain, err))
+			continue
+		}
+		if cert.Leaf != nil && (earliest.IsZero() || cert.Leaf.NotAfter.Before(earliest)) {
+			earliest = cert.Leaf.NotAfter
+		}
+	}
+	return earliest, errors.Join(errs...)
+}
+
+func (m *Manager) load() (time.Time, error) {
+	cert, err := tls.LoadX509KeyPair(m.cfg.CertFile, m.cfg.KeyFile)
+	if err != nil {
+		return time.Time{}, fmt.Errorf("load TLS certificate: %w", err)
+	}
+	if cert.Leaf == nil {
+		if cert.Leaf, err = x509.ParseCertificate(cert.Certificate[0]); err != nil {
+			return time.Time{}, fmt.Errorf("parse TLS certificate: %w", err)
+		}
+	}
+	m.static.Store(&cert)
+	return cert.Leaf.NotAfter, nil
+}
+
+// RedirectHTTPS sends requests to the same URL on the TLS listener at
+// tlsAddr. 308 keeps the method and body of API calls.
+func RedirectHTTPS(tlsAddr string) http.Handler {
+	_, port, _ := net.SplitHostPort(tlsAddr)
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		host, _, err := net.SplitHostPort(r.Host)
+		if err != nil {
+			host = strings.Trim(r.Host, "[]")
+		}
+		if port != "" && port != "443" {
+			host = net.JoinHostPort(host, port)
+		} else if strings.Contains(host, ":") {
+			host = "[" + host + "]"
+		}
+		http.Redirect(w, r, "https://"+host+r.URL.RequestURI(), http.StatusPermanentRedirect)
+	})
+}
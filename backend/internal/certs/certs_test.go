@@ -0,0 +1,107 @@
+package certs
+
+import (
+	"context"
+	"crypto/ecdsa"
+	"crypto/elliptic"
+	"crypto/rand"
+	"crypto/x509"
+	"crypto/x509/pkix"
+	"encoding/pem"
+	"math/big"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+)
+
+func writeCertificate(t *testing.T, dir string, notAfter time.Time) Config {
+	t.Helper()
+	key, err := ecdsa.GenerateKey(elliptic.P256(), rand.Reader)
+	if err != nil {
+		t.Fatal(err)
+	}
+	template := &x509.Certificate{
+		SerialNumber: big.NewInt(1),
+		Subject:      pkix.Name{CommonName: "music.example.com"},
+		DNSNames:     []string{"music.example.com"},
+		NotBefore:    notAfter.Add(-90 * 24 * time.Hour),
+		NotAfter:     notAfter,
+	}
+	der, err := x509.CreateCertificate(rand.Reader, template, template, &key.PublicKey, key)
+	if err != nil {
+		t.Fatal(err)
+	}
+	keyDER, err := x509.MarshalECPrivateKey(key)
+	if err != nil {
+		t.Fatal(err)
+	}
+	cfg := Config{CertFile: filepath.Join(dir, "cert.pem"), KeyFile: filepath.Join(dir, "key.pem")}
+	if err := os.WriteFile(cfg.CertFile, pem.EncodeToMemory(&pem.Block{Type: "CERTIFICATE", Bytes: der}), 0o600); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(cfg.KeyFile, pem.EncodeToMemory(&pem.Block{Type: "EC PRIVATE KEY", Bytes: keyDER}), 0o600); err != nil {
+		t.Fatal(err)
+	}
+	return cfg
+}
+
+func TestRenewReloadsReplacedCertificateFiles(t *testing.T) {
+	dir := t.TempDir()
+	first := time.Now().Add(30 * 24 * time.Hour).Truncate(time.Second).UTC()
+	cfg := writeCertificate(t, dir, first)
+	m, err := New(cfg)
+	if err != nil {
+		t.Fatal(err)
+	}
+	served := func() time.Time {
+		cert, err := m.TLSConfig().GetCertificate(nil)
+		if err != nil {
+			t.Fatal(err)
+		}
+		return cert.Leaf.NotAfter
+	}
+	if got := served(); !got.Equal(first) {
+		t.Fatalf("served certificate expires %v, want %v", got, first)
+	}
+
+	renewed := first.Add(60 * 24 * time.Hour)
+	writeCertificate(t, dir, renewed)
+	expires, err := m.Renew(context.Background())
+	if err != nil || !expires.Equal(renewed) || !served().Equal(renewed) {
+		t.Fatalf("Renew() = %v, %v; served %v; want %v", expires, err, served(), renewed)
+	}
+}
+
+func TestNewRejectsIncompleteSources(t *testing.T) {
+	for _, cfg := range []Config{
+		{CertFile: "cert.pem"},
+		{Domains: []string{"music.example.com"}},
+		{Domains: []string{"music.example.com"}, CacheDir: t.TempDir(), CertFile: "cert.pem", KeyFile: "key.pem"},
+	} {
+		if _, err := New(cfg); err == nil {
+			t.Errorf("New(%+v) accepted", cfg)
+		}
+	}
+}
+
+func TestRedirectHTTPS(t *testing.T) {
+	tests := []struct {
+		tlsAddr string
+		target  string
+		want    string
+	}{
+		{tlsAddr: ":443", target: "http://music.example.com/api/v1/library?limit=5", want: "https://music.example.com/api/v1/library?limit=5"},
+		{tlsAddr: ":8443", target: "http://music.example.com:8080/health", want: "https://music.example.com:8443/health"},
+		{tlsAddr: ":443", target: "http://[::1]:80/health", want: "https://[::1]/health"},
+	}
+	for _, tt := range tests {
+		rec := httptest.NewRecorder()
+		RedirectHTTPS(tt.tlsAddr).ServeHTTP(rec, httptest.NewRequest(http.MethodPost, tt.target, nil))
+		if rec.Code != http.StatusPermanentRedirect || rec.Header().Get("Location") != tt.want {
+			t.Errorf("%s via %s = %d %q, want %q", tt.target, tt.tlsAddr, rec.Code, rec.Header().Get("Location"), tt.want)
+		}
+	}
+}
@@ -0,0 +1,125 @@
+// Package chapters reads the tracklist of a long mix from what its source
+// reports, and splits a stored mix into one track per chapter.
+package chapters
+
+import (
+	"database/sql"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// minChapters is the fewest chapters that make a tracklist; a single
+// timestamp in a description is more likely a note than a track.
+const minChapters = 2
+
+var (
+	// descriptionTimestamp matches a tracklist line with its timestamp first,
+	// as in "12:34 Artist - Title" or "03. [1:02:03] Title".
+	descriptionTimestamp = regexp.MustCompile(`^(?:\d{1,3}[.)]\s*)?[\[(]?((?:\d{1,2}:)?\d{1,2}:\d{2})[\])]?\s*[-–—|:.]?\s*(.+)$`)
+	// trailingTimestamp matches a tracklist line with its timestamp last, as
+	// in "Artist - Title 12:34" or "Artist - Title (12:34)".
+	trailingTimestamp = regexp.MustCompile(`^(?:\d{1,3}[.)]\s*)?(.+?)\s*[-–—|]?\s*[\[(]?((?:\d{1,2}:)?\d{1,2}:\d{2})[\])]?$`)
+	artistSeparator   = regexp.MustCompile(`\s+[-–—]\s+`)
+)
+
+// FromInfo returns the chapters of a yt-dlp info document: its own chapter
+// list when it has one, else the timestamps listed in its description. Each
+// chapter ends where the next starts, the last at durationMs. It returns nil
+// when fewer than two chapters are found.
+func FromInfo(info map[string]interface{}, durationMs int) []db.Chapter {
+	if chapters := fromChapterList(info["chapters"], durationMs); len(chapters) >= minChapters {
+		return chapters
+	}
+	description, _ := info["description"].(string)
+	if chapters := FromDescription(description, durationMs); len(chapters) >= minChapters {
+		return chapters
+	}
+	return nil
+}
+
+func fromChapterList(value interface{}, durationMs int) []db.Chapter {
+	list, _ := value.([]interface{})
+	var chapters []db.Chapter
+	for _, item := range list {
+		entry, ok := item.(map[string]interface{})
+		if !ok {
+			continue
+		}
+		start, _ := entry["start_time"].(float64)
+		end, _ := entry["end_time"].(float64)
+		title, _ := entry["title"].(string)
+		chapters = append(chapters, newChapter(title, int(start*1000), int(end*1000), db.ChapterSourceChapters))
+	}
+	return settle(chapters, durationMs)
+}
+
+// FromDescription reads a tracklist of timestamped lines from a description.
+// Lines without a timestamp are ignored, and so are timestamps that do not
+// move forward.
+func FromDescription(description string, durationMs int) []db.Chapter {
+	var chapters []db.Chapter
+	for _, line := range strings.Split(description, "\n") {
+		line = strings.TrimSpace(line)
+		var stamp, title string
+		if m := descriptionTimestamp.FindStringSubmatch(line); m != nil {
+			stamp, title = m[1], m[2]
+		} else if m := trailingTimestamp.FindStringSubmatch(line); m != nil {
+			stamp, title = m[2], m[1]
+		} else {
+			continue
+		}
+		start, ok := parseTimestamp(stamp)
+		if !ok || (len(chapters) > 0 && start <= chapters[len(chapters)-1].StartMs) {
+			continue
+		}
+		chapters = append(chapters, newChapter(title, start, 0, db.ChapterSourceDescription))
+	}
+	return settle(chapters, durationMs)
+}
+
+// settle ends each chapter where the next starts, or at durationMs for the
+// last, and drops chapters that end up empty or past the end of the mix.
+func settle(chapters []db.Chapter, durationMs int) []db.Chapter {
+	settled := chapters[:0]
+	for i, c := range chapters {
+		switch {
+		case i+1 < len(chapters):
+			c.EndMs = chapters[i+1].StartMs
+		case durationMs > 0:
+			c.EndMs = durationMs
+		}
+		if c.Title == "" || c.StartMs < 0 || c.EndMs <= c.StartMs || (durationMs > 0 && c.EndMs > durationMs) {
+			continue
+		}
+		c.Position = len(settled) + 1
+		settled = append(settled, c)
+	}
+	return settled
+}
+
+// newChapter splits "Artist - Title" chapter names into their parts.
+func newChapter(name string, startMs, endMs int, source string) db.Chapter {
+	c := db.Chapter{Title: strings.TrimSpace(name), StartMs: startMs, EndMs: endMs, Source: source}
+	if parts := artistSeparator.Split(c.Title, 2); len(parts) == 2 && strings.TrimSpace(parts[0]) != "" && strings.TrimSpace(parts[1]) != "" {
+		c.Artist = sql.NullString{String: strings.TrimSpace(parts[0]), Valid: true}
+		c.Title = strings.TrimSpace(parts[1])
+	}
+	return c
+}
+
+// parseTimestamp reads "m:ss", "mm:ss" or "h:mm:ss" as milliseconds.
+func parseTimestamp(stamp string) (int, bool) {
+	parts := strings.Split(stamp, ":")
+	total := 0
+	for i, part := range parts {
+		n, err := strconv.Atoi(part)
+		if err != nil || (i > 0 && n >= 60) {
+			return 0, false
+		}
+		total = total*60 + n
+	}
+	return total * 1000, true
+}
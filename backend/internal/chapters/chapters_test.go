@@ -0,0 +1,69 @@
+package chapters
+
+import (
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestFromInfoPrefersChapterList(t *testing.T) {
+	info := map[string]interface{}{
+		"chapters": []interface{}{
+			map[string]interface{}{"start_time": 0.0, "end_time": 300.0, "title": "Intro"},
+			map[string]interface{}{"start_time": 300.0, "end_time": 610.5, "title": "Bicep - Glue"},
+		},
+		"description": "0:00 Something Else\n5:00 Another",
+	}
+	got := FromInfo(info, 610500)
+	if len(got) != 2 {
+		t.Fatalf("chapters = %+v", got)
+	}
+	if got[0].Title != "Intro" || got[0].Artist.Valid || got[0].StartMs != 0 || got[0].EndMs != 300000 {
+		t.Fatalf("first chapter = %+v", got[0])
+	}
+	if got[1].Title != "Glue" || got[1].Artist.String != "Bicep" || got[1].EndMs != 610500 || got[1].Position != 2 {
+		t.Fatalf("second chapter = %+v", got[1])
+	}
+	if got[1].Source != db.ChapterSourceChapters {
+		t.Fatalf("source = %q", got[1].Source)
+	}
+}
+
+func TestFromDescriptionReadsTimestampedLines(t *testing.T) {
+	description := `Recorded live at the warehouse.
+
+Tracklist:
+00:00 Floating Points - Ratio
+1. [12:30] Four Tet – Baby
+Caribou - Sun (25:05)
+25:00 this timestamp goes backwards
+1:02:03 Jon Hopkins - Emerald Rush
+
+Follow us for more.`
+	got := FromDescription(description, 3900000)
+	want := []struct {
+		artist, title  string
+		startMs, endMs int
+	}{
+		{"Floating Points", "Ratio", 0, 750000},
+		{"Four Tet", "Baby", 750000, 1505000},
+		{"Caribou", "Sun", 1505000, 3723000},
+		{"Jon Hopkins", "Emerald Rush", 3723000, 3900000},
+	}
+	if len(got) != len(want) {
+		t.Fatalf("chapters = %+v", got)
+	}
+	for i, w := range want {
+		c := got[i]
+		if c.Artist.String != w.artist || c.Title != w.title || c.StartMs != w.startMs || c.EndMs != w.endMs || c.Position != i+1 {
+			t.Fatalf("chapter %d = %+v, want %+v", i, c, w)
+		}
+	}
+}
+
+func TestFromInfoIgnoresASingleTimestamp(t *testing.T) {
+	info := map[string]interface{}{"description": "Drop at 1:23, enjoy!"}
+	if got := FromInfo(info, 240000); got != nil {
+		t.Fatalf("chapters = %+v, want none", got)
+	}
+}
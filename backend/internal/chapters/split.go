@@ -0,0 +1,301 @@
+package chapters
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"os/exec"
+	"path"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+// Split is the job kind that cuts a stored mix into one track per chapter
+// and adds them to the requester's library.
+var Split = jobs.NewKind[SplitPayload]("chapter_split")
+
+type SplitPayload struct {
+	TrackID int64 `json:"track_id"`
+}
+
+// SplitProgress is the progress detail of chapter split jobs.
+type SplitProgress struct {
+	Split    int `json:"split"`
+	Existing int `json:"existing"`
+	Failed   int `json:"failed"`
+}
+
+// ErrNoChapters is returned when a track has no chapters to split.
+var ErrNoChapters = errors.New("track has no chapters")
+
+// Queue queues chapter split jobs.
+type Queue struct {
+	store *jobs.Store
+}
+
+func NewQueue(store *jobs.Store) *Queue {
+	return &Queue{store: store}
+}
+
+// Enqueue queues splitting trackID for the user. A user runs at most one
+// split of a mix at a time; a second request while one is queued or running
+// returns jobs.ErrDuplicate.
+func (q *Queue) Enqueue(ctx context.Context, userID uuid.UUID, trackID int64) (*jobs.Job, error) {
+	// Chapters already split are only added to the library, so a retry
+	// resumes where a failed pass stopped.
+	return Split.Enqueue(ctx, q.store, SplitPayload{TrackID: trackID}, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   userID.String() + ":split:" + strconv.FormatInt(trackID, 10),
+	})
+}
+
+// Store lists a mix's chapters and records the tracks cut from them.
+// db.ChapterRepository satisfies this interface.
+type Store interface {
+	ListForTrack(ctx context.Context, trackID int64) ([]db.Chapter, error)
+	SetSplitTrack(ctx context.Context, chapterID, splitTrackID int64) error
+}
+
+// TrackLoader loads tracks. db.TrackRepository satisfies this interface.
+type TrackLoader interface {
+	GetByID(ctx context.Context, id int64) (*db.Track, error)
+}
+
+// Library adds existing tracks to a user's library. db.LibraryRepository
+// satisfies this interface.
+type Library interface {
+	AddTrackToLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*db.LibraryEntry, error)
+}
+
+// Objects reads stored audio. *storage.Client satisfies this interface.
+type Objects interface {
+	GetObject(ctx context.Context, key string) (io.ReadCloser, *storage.ObjectInfo, error)
+}
+
+// Importer stores one file as a track. processor.Processor satisfies this
+// interface.
+type Importer interface {
+	Process(ctx context.Context, job *download.DownloadJob, progress func(int)) error
+}
+
+// Cutter copies part of an audio file. *FFmpeg satisfies this interface.
+type Cutter interface {
+	// Cut copies src from startMs to endMs into dst, replacing its tags with
+	// tags.
+	Cut(ctx context.Context, src, dst string, startMs, endMs int, tags map[string]string) error
+}
+
+// progressReporter records a job's progress. *jobs.Progress satisfies it.
+type progressReporter interface {
+	Report(ctx context.Context, current, total int, detail any) error
+}
+
+// RunnerConfig holds what a Runner reads from and imports through.
+type RunnerConfig struct {
+	Chapters Store
+	Tracks   TrackLoader
+	Library  Library
+	Objects  Objects
+	Importer Importer
+	Cutter   Cutter
+}
+
+// Runner splits mixes into their chapters. Each chapter is cut from the
+// stored audio without re-encoding and imported like a local file, tagged
+// with the chapter's title and artist and the mix's title as album, so it is
+// matched, analyzed and added to the library like any other track.
+type Runner struct {
+	cfg RunnerConfig
+}
+
+func NewRunner(cfg RunnerConfig) *Runner {
+	return &Runner{cfg: cfg}
+}
+
+// Register runs chapter split jobs on w.
+func (r *Runner) Register(w *jobs.Worker) {
+	Split.Handle(w, func(ctx context.Context, job *jobs.Job, payload SplitPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.UserID.UUID, payload, progress)
+	})
+}
+
+// Run splits the payload's mix for the user. Chapters split before are only
+// added to the user's library; chapters that fail are logged and counted
+// rather than ending the job.
+func (r *Runner) Run(ctx context.Context, userID uuid.UUID, payload SplitPayload, progress progressReporter) error {
+	mix, err := r.cfg.Tracks.GetByID(ctx, payload.TrackID)
+	if errors.Is(err, db.ErrTrackNotFound) {
+		return jobs.Permanent(err)
+	}
+	if err != nil {
+		return err
+	}
+	key := strings.TrimSpace(mix.StorageKey.String)
+	if key == "" {
+		return jobs.Permanent(fmt.Errorf("track %d has no stored audio", mix.ID))
+	}
+	chapters, err := r.cfg.Chapters.ListForTrack(ctx, mix.ID)
+	if err != nil {
+		return err
+	}
+	if len(chapters) == 0 {
+		return jobs.Permanent(ErrNoChapters)
+	}
+
+	scratch, err := os.MkdirTemp("", "omp-split-*")
+	if err != nil {
+		return err
+	}
+	defer os.RemoveAll(scratch)
+	ext := strings.ToLower(path.Ext(key))
+	source := filepath.Join(scratch, "mix"+ext)
+	downloaded := false
+
+	var counts SplitProgress
+	if err := progress.Report(ctx, 0, len(chapters), counts); err != nil {
+		return err
+	}
+	for i, chapter := range chapters {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if chapter.SplitTrackID.Valid {
+			_, err := r.cfg.Library.AddTrackToLibrary(ctx, userID, chapter.SplitTrackID.Int64)
+			if err != nil && !errors.Is(err, db.ErrTrackAlreadyInLibrary) {
+				return fmt.Errorf("add track %d to library: %w", chapter.SplitTrackID.Int64, err)
+			}
+			counts.Existing++
+		} else {
+			if !downloaded {
+				if err := r.download(ctx, key, source); err != nil {
+					return err
+				}
+				downloaded = true
+			}
+			if err := r.split(ctx, userID, mix, chapter, source, filepath.Join(scratch, strconv.Itoa(chapter.Position)+ext)); err != nil {
+				log.Printf("Chapter split of track %d: chapter %d failed: %v", mix.ID, chapter.Position, err)
+				counts.Failed++
+			} else {
+				counts.Split++
+			}
+		}
+		if err := progress.Report(ctx, i+1, len(chapters), counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// split cuts one chapter to dst and imports it.
+func (r *Runner) split(ctx context.Context, userID uuid.UUID, mix *db.Track, chapter db.Chapter, source, dst string) error {
+	defer os.Remove(dst)
+	artist := chapter.Artist.String
+	if !chapter.Artist.Valid {
+		artist = mix.Artist.String
+	}
+	tags := map[string]string{
+		"title":  chapter.Title,
+		"artist": artist,
+		"album":  mix.Title,
+		"track":  strconv.Itoa(chapter.Position),
+	}
+	if err := r.cfg.Cutter.Cut(ctx, source, dst, chapter.StartMs, chapter.EndMs, tags); err != nil {
+		return err
+	}
+	job := &download.DownloadJob{
+		ID:         uuid.NewString(),
+		UserID:     userID.String(),
+		URL:        "file://" + dst,
+		SourceType: download.SourceTypeChapter,
+		SourceID:   strconv.FormatInt(mix.ID, 10) + ":" + strconv.Itoa(chapter.Position),
+		Title:      chapter.Title,
+		Artist:     artist,
+		Album:      mix.Title,
+		DurationMs: chapter.EndMs - chapter.StartMs,
+		Status:     download.StatusDownloading,
+		CreatedAt:  time.Now(),
+	}
+	if err := r.cfg.Importer.Process(ctx, job, func(int) {}); err != nil {
+		return err
+	}
+	if job.TrackID == nil {
+		return fmt.Errorf("import of chapter %d recorded no track", chapter.Position)
+	}
+	return r.cfg.Chapters.SetSplitTrack(ctx, chapter.ID, *job.TrackID)
+}
+
+func (r *Runner) download(ctx context.Context, key, dst string) error {
+	object, _, err := r.cfg.Objects.GetObject(ctx, key)
+	if err != nil {
+		return fmt.Errorf("read %s: %w", key, err)
+	}
+	defer object.Close()
+	file, err := os.Create(dst)
+	if err != nil {
+		return err
+	}
+	if _, err := io.Copy(file, object); err != nil {
+		file.Close()
+		return fmt.Errorf("read %s: %w", key, err)
+	}
+	return file.Close()
+}
+
+// FFmpeg cuts files with the ffmpeg command.
+type FFmpeg struct {
+	ffmpeg string
+}
+
+// NewFFmpeg uses the ffmpeg found on PATH, where managed builds are placed
+// ahead of system ones.
+func NewFFmpeg() *FFmpeg {
+	return &FFmpeg{ffmpeg: "ffmpeg"}
+}
+
+func (f *FFmpeg) Cut(ctx context.Context, src, dst string, startMs, endMs int, tags map[string]string) error {
+	cmd := exec.CommandContext(ctx, f.ffmpeg, cutArgs(src, dst, startMs, endMs, tags)...)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return nil
+}
+
+// cutArgs copies the audio of src between the two offsets without
+// re-encoding, dropping the mix's own tags for tags.
+func cutArgs(src, dst string, startMs, endMs int, tags map[string]string) []string {
+	args := []string{
+		"-nostdin", "-v", "error", "-y",
+		"-ss", seconds(startMs),
+		"-i", src,
+		"-t", seconds(endMs - startMs),
+		"-map", "0:a", "-c", "copy",
+		"-map_metadata", "-1",
+	}
+	for _, key := range []string{"title", "artist", "album", "track"} {
+		if value := tags[key]; value != "" {
+			args = append(args, "-metadata", key+"="+value)
+		}
+	}
+	return append(args, dst)
+}
+
+func seconds(ms int) string {
+	return strconv.FormatFloat(float64(ms)/1000, 'f', 3, 64)
+}
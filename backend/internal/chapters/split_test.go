@@ -0,0 +1,162 @@
+package chapters
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"io"
+	"os"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+type fakeProgress struct {
+	last SplitProgress
+}
+
+func (p *fakeProgress) Report(_ context.Context, _, _ int, detail any) error {
+	p.last = detail.(SplitProgress)
+	return nil
+}
+
+type fakeChapterStore struct {
+	chapters []db.Chapter
+	split    map[int64]int64
+}
+
+func (f *fakeChapterStore) ListForTrack(context.Context, int64) ([]db.Chapter, error) {
+	return f.chapters, nil
+}
+
+func (f *fakeChapterStore) SetSplitTrack(_ context.Context, chapterID, splitTrackID int64) error {
+	f.split[chapterID] = splitTrackID
+	return nil
+}
+
+type fakeTracks map[int64]*db.Track
+
+func (f fakeTracks) GetByID(_ context.Context, id int64) (*db.Track, error) {
+	if track, ok := f[id]; ok {
+		return track, nil
+	}
+	return nil, db.ErrTrackNotFound
+}
+
+type fakeLibrary struct{ added []int64 }
+
+func (f *fakeLibrary) AddTrackToLibrary(_ context.Context, _ uuid.UUID, trackID int64) (*db.LibraryEntry, error) {
+	f.added = append(f.added, trackID)
+	return &db.LibraryEntry{}, nil
+}
+
+type fakeObjects map[string]string
+
+func (f fakeObjects) GetObject(_ context.Context, key string) (io.ReadCloser, *storage.ObjectInfo, error) {
+	body, ok := f[key]
+	if !ok {
+		return nil, nil, errors.New("no such object")
+	}
+	return io.NopCloser(strings.NewReader(body)), &storage.ObjectInfo{Size: int64(len(body))}, nil
+}
+
+type fakeCutter struct{ fail string }
+
+func (f fakeCutter) Cut(_ context.Context, _, dst string, _, _ int, tags map[string]string) error {
+	if tags["title"] == f.fail {
+		return errors.New("ffmpeg failed")
+	}
+	return os.WriteFile(dst, []byte(tags["title"]), 0o644)
+}
+
+type fakeImporter struct {
+	jobs   []*download.DownloadJob
+	nextID int64
+}
+
+func (f *fakeImporter) Process(_ context.Context, job *download.DownloadJob, _ func(int)) error {
+	if _, err := os.Stat(strings.TrimPrefix(job.URL, "file://")); err != nil {
+		return err
+	}
+	f.jobs = append(f.jobs, job)
+	f.nextID++
+	job.TrackID = &f.nextID
+	return nil
+}
+
+func TestRunnerSplitsChaptersNotSplitBefore(t *testing.T) {
+	mix := &db.Track{
+		ID:         5,
+		Title:      "Boiler Room Set",
+		Artist:     sql.NullString{String: "DJ Host", Valid: true},
+		StorageKey: sql.NullString{String: "audio/mix.opus", Valid: true},
+	}
+	store := &fakeChapterStore{split: map[int64]int64{}, chapters: []db.Chapter{
+		{ID: 1, Position: 1, Title: "Already Split", StartMs: 0, EndMs: 60000, SplitTrackID: sql.NullInt64{Int64: 40, Valid: true}},
+		{ID: 2, Position: 2, Title: "Glue", Artist: sql.NullString{String: "Bicep", Valid: true}, StartMs: 60000, EndMs: 300000},
+		{ID: 3, Position: 3, Title: "Broken", StartMs: 300000, EndMs: 360000},
+		{ID: 4, Position: 4, Title: "Closer", StartMs: 360000, EndMs: 420000},
+	}}
+	library := &fakeLibrary{}
+	importer := &fakeImporter{nextID: 100}
+	runner := NewRunner(RunnerConfig{
+		Chapters: store,
+		Tracks:   fakeTracks{5: mix},
+		Library:  library,
+		Objects:  fakeObjects{"audio/mix.opus": "mix"},
+		Importer: importer,
+		Cutter:   fakeCutter{fail: "Broken"},
+	})
+
+	progress := &fakeProgress{}
+	if err := runner.Run(context.Background(), uuid.New(), SplitPayload{TrackID: 5}, progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	if want := (SplitProgress{Split: 2, Existing: 1, Failed: 1}); progress.last != want {
+		t.Fatalf("progress = %+v, want %+v", progress.last, want)
+	}
+	if !reflect.DeepEqual(library.added, []int64{40}) {
+		t.Fatalf("added to library = %v, want the chapter split before", library.added)
+	}
+	if want := map[int64]int64{2: 101, 4: 102}; !reflect.DeepEqual(store.split, want) {
+		t.Fatalf("split tracks = %v, want %v", store.split, want)
+	}
+	glue := importer.jobs[0]
+	if glue.Title != "Glue" || glue.Artist != "Bicep" || glue.Album != "Boiler Room Set" || glue.DurationMs != 240000 || glue.SourceID != "5:2" {
+		t.Fatalf("first import = %+v", glue)
+	}
+	if closer := importer.jobs[1]; closer.Artist != "DJ Host" {
+		t.Fatalf("chapter without an artist imported as %q, want the mix's", closer.Artist)
+	}
+}
+
+func TestRunnerRejectsTracksWithoutChapters(t *testing.T) {
+	runner := NewRunner(RunnerConfig{
+		Chapters: &fakeChapterStore{},
+		Tracks:   fakeTracks{5: {ID: 5, StorageKey: sql.NullString{String: "audio/song.mp3", Valid: true}}},
+	})
+	err := runner.Run(context.Background(), uuid.New(), SplitPayload{TrackID: 5}, &fakeProgress{})
+	if !errors.Is(err, ErrNoChapters) {
+		t.Fatalf("Run() error = %v, want ErrNoChapters", err)
+	}
+}
+
+func TestCutArgsCopyTheRangeWithNewTags(t *testing.T) {
+	got := cutArgs("mix.opus", "2.opus", 60000, 300500, map[string]string{"title": "Glue", "artist": "Bicep"})
+	want := []string{
+		"-nostdin", "-v", "error", "-y",
+		"-ss", "60.000", "-i", "mix.opus", "-t", "240.500",
+		"-map", "0:a", "-c", "copy", "-map_metadata", "-1",
+		"-metadata", "title=Glue", "-metadata", "artist=Bicep",
+		"2.opus",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("cutArgs() = %q, want %q", got, want)
+	}
+}
@@ -7,6 +7,7 @@ import (
 	"errors"
 	"fmt"
 	"os"
+	"path/filepath"
 	"strconv"
 	"strings"
 	"time"
@@ -27,6 +28,75 @@ type Config struct {
 	RedisURL           string
 	WorkerCount        int
 
+	// TrustedProxies are the addresses or CIDR ranges of the reverse proxies
+	// whose X-Forwarded-For, -Proto and -Host headers are believed. BasePath
+	// is the sub-path the API is served under behind them, such as "/music".
+	TrustedProxies []string
+	BasePath       string
+
+	// Built-in TLS for servers without a reverse proxy. With ACMEDomains,
+	// certificates are issued by Let's Encrypt, or the CA at
+	// ACMEDirectoryURL, and cached in ACMECacheDir; otherwise TLSCertFile and
+	// TLSKeyFile are served. The API is then served on TLSAddr, and
+	// ServerAddr answers HTTP-01 challenges and, with TLSRedirectHTTP,
+	// redirects every other request to HTTPS.
+	TLSAddr          string
+	TLSCertFile      string
+	TLSKeyFile       string
+	TLSRedirectHTTP  bool
+	ACMEDomains      []string
+	ACMEEmail        string
+	ACMECacheDir     string
+	ACMEDirectoryURL string
+
+	// WebUIEnabled serves the web client embedded in the binary on every path
+	// outside the API; the server's --with-webui flag also turns it on.
+	WebUIEnabled bool
+
+	// Database connection pool tuning; zero keeps the database/sql default.
+	DBMaxOpenConns       int
+	DBMaxIdleConns       int
+	DBConnMaxLifetime    time.Duration
+	DBConnMaxIdleTime    time.Duration
+	DBConnectTimeout     time.Duration
+	DBStatementTimeout   time.Duration
+	DBSlowQueryThreshold time.Duration
+	// Optional read replica for heavy list/search queries. An empty host
+	// disables routing; credentials and database name default to the primary's.
+	DBReplicaHost     string
+	DBReplicaPort     string
+	DBReplicaUser     string
+	DBReplicaPassword string
+	DBReplicaName     string
+
+	// Playlist revision history retention, applied as revisions are recorded.
+	PlaylistHistoryRetentionDays int
+	PlaylistHistoryMaxRevisions  int
+
+	// Play event retention. Raw events older than PlayEventRetentionDays are
+	// deleted once rolled up into daily counts, and daily counts older than
+	// PlayDailyRollupRetentionDays are folded into monthly ones. Zero keeps
+	// that level forever.
+	PlayEventRetentionDays       int
+	PlayDailyRollupRetentionDays int
+
+	// Sidecar artwork filenames (cover.jpg, folder.png, ...) looked up beside
+	// local audio files, highest priority first. Nil keeps the processor
+	// default; an explicitly empty list disables the lookup.
+	ArtworkFolderFilenames []string
+
+	// Metadata source ranking (musicbrainz, tag, provider), highest first.
+	// Enrichment never replaces a field set by a higher-ranked source; user
+	// edits always rank first. Nil keeps the default ranking.
+	MetadataFieldPrecedence []string
+
+	// Leading articles ignored when ordering artists and albums alphabetically.
+	// Nil keeps the default (The, A, An); an empty list disables stripping.
+	SortArticles []string
+	// PostgreSQL collation for alphabetical ordering, such as "und-x-icu".
+	// Empty keeps the database default.
+	SortCollation string
+
 	// S3/MinIO storage configuration
 	S3Endpoint       string
 	S3Region         string
@@ -76,6 +146,127 @@ type Config struct {
 	AgentServiceToken string
 	FirecrawlAPIKey   string
 
+	// Operator token for pausing and resuming job queues. The queue control
+	// routes are registered only when it is set.
+	JobControlToken string
+
+	// Similar artists come from Last.fm when LastFMAPIKey is set, and from
+	// ListenBrainz, which needs no key, otherwise. The key is a secret.
+	LastFMAPIKey string
+
+	// Downloads are refused while free space on DiskMonitorPath is below
+	// DiskMinFreeMB; zero only reports capacity. Threshold crossings are posted
+	// to DiskAlertWebhookURL when it is set.
+	DiskMonitorPath     string
+	DiskMinFreeMB       int
+	DiskAlertWebhookURL string
+
+	// Managed yt-dlp and ffmpeg builds. ToolsDir takes precedence over PATH;
+	// with ToolsAutoDownload, missing tools are installed there at the pinned
+	// versions. yt-dlp is checked for a newer release every
+	// ToolsUpdateCheckInterval (zero disables) and replaced when YTDLPAutoUpdate
+	// is set.
+	ToolsDir                 string
+	ToolsAutoDownload        bool
+	YTDLPVersion             string
+	FFmpegVersion            string
+	ToolsUpdateCheckInterval time.Duration
+	YTDLPAutoUpdate          bool
+
+	// ExternalDownloaders maps a job source type to the command that fetches
+	// it, with {url} and {dir} placeholders. Other sources use the built-in
+	// fixture, file, direct HTTP, and yt-dlp downloaders.
+	ExternalDownloaders map[string][]string
+	// DirectDownloadAllowPrivate lets direct file URLs reach loopback and
+	// private-network hosts, which are refused by default.
+	DirectDownloadAllowPrivate bool
+	// ExternalProxies maps a provider (musicbrainz, discovery, downloads, or
+	// default for the rest) to the http, https or socks5 proxy its requests
+	// go through, or "direct". egress.Parse validates it.
+	ExternalProxies map[string]string
+	// SponsorBlockEnabled looks up non-music segments of YouTube downloads in
+	// SponsorBlockCategories (empty uses sponsorblock.DefaultCategories) on
+	// the server at SponsorBlockURL. Playback skips them, or with
+	// SponsorBlockTrim they are cut from the stored audio.
+	SponsorBlockEnabled    bool
+	SponsorBlockURL        string
+	SponsorBlockCategories []string
+	SponsorBlockTrim       bool
+	// LoudnessTargetLUFS is the integrated loudness that downloads of users
+	// who turned on loudness normalization are re-encoded to. With
+	// LoudnessKeepOriginals the unnormalized file is kept beside them.
+	LoudnessTargetLUFS    float64
+	LoudnessKeepOriginals bool
+	// QualityScoringEnabled measures each new track's dynamic range and
+	// spectrum to score its quality and flag likely upscaled lossless files.
+	QualityScoringEnabled bool
+	// MoodAnalysisEnabled measures each new track's energy, valence and
+	// danceability so the library and radio can filter by mood.
+	MoodAnalysisEnabled bool
+	// AudioHashMatch is how closely a download's decoded audio must match a
+	// stored track's to be stored as that track: off, exact, strict or
+	// loose; see processor.AudioHashMatch.
+	AudioHashMatch string
+	// SourceCredentialsKey is the base64 AES-256 key that seals the login
+	// cookies users upload for sources that need them. Empty turns cookie
+	// uploads off; changing it orphans every stored credential.
+	SourceCredentialsKey string
+	// FormatPolicy is the format all stored audio is converted to, such as
+	// "opus:160"; empty keeps every format. With FormatPolicyKeepLossless,
+	// lossless files are kept as they are.
+	FormatPolicy             string
+	FormatPolicyKeepLossless bool
+	// RemoteImportSources names the SFTP and WebDAV folders users may import
+	// from, as name=URL pairs; see importers.ParseRemote for the URL forms.
+	RemoteImportSources map[string]string
+	// BeetsConfig is the path to the beets config.yaml whose library and music
+	// folder users may import; empty disables beets imports.
+	BeetsConfig string
+	// BeetsExecutable is the beet command that queries the beets library.
+	BeetsExecutable string
+	// MigrationSources names the Navidrome and Jellyfin servers users may
+	// migrate from, as name=URL pairs; see libraryimport.ParseSource.
+	MigrationSources map[string]string
+	// ExportDir is the directory library exports write below, such as where
+	// USB sticks are mounted. Exports are disabled when it is empty.
+	ExportDir string
+	// GuestMode lets visitors without an account browse and stream the
+	// playlists in GuestShelfPlaylists, read-only.
+	GuestMode bool
+	// GuestShelfPlaylists are the IDs of the playlists guests may see.
+	GuestShelfPlaylists []string
+	// GuestRateLimit is how many guest requests one client may make a
+	// minute.
+	GuestRateLimit int
+	// StreamConcurrency is how many playback URL or sync manifest requests
+	// one user, or guest address, may have in flight at once.
+	StreamConcurrency int
+	// TranscodeMaxActive caps the offline transcode jobs queued or running
+	// across all users; 0 leaves them uncapped.
+	TranscodeMaxActive int
+	// TranscodeWarmBudget is how many popular or new tracks are converted
+	// each night to the device profile settings most users share; 0 turns
+	// warming off.
+	TranscodeWarmBudget int
+	// TranscodeWarmHour is the local hour warming runs in.
+	TranscodeWarmHour int
+	// DailyMixCount is how many daily mixes each user gets, of up to
+	// DailyMixSize tracks, refreshed each day after the local DailyMixHour;
+	// 0 turns daily mixes off.
+	DailyMixCount int
+	DailyMixSize  int
+	DailyMixHour  int
+	// ActivityPubEnabled turns on the experimental fediverse actors users
+	// may publish their listening, loved tracks and public playlists with.
+	// ActivityPubBaseURL is the public origin remote servers reach this one
+	// at; activitypub.ParseBaseURL validates it.
+	ActivityPubEnabled bool
+	ActivityPubBaseURL string
+	// PushFCMCredentialsFile is a Firebase service account key; with it,
+	// phones may register for notifications through FCM as well as
+	// UnifiedPush.
+	PushFCMCredentialsFile string
+
 	// Optional out-of-process audio analyzer. Disabled unless configured so the
 	// processor never creates unserviceable pending analysis rows by default.
 	AnalyzerEnabled     bool
@@ -133,6 +324,16 @@ type Config struct {
 	ResearchRunTimeout           time.Duration
 	ResearchCancelGrace          time.Duration
 	ResearchShutdownTimeout      time.Duration
+
+	// Optional OTLP/HTTP trace export. Incoming traceparent headers are always
+	// propagated; spans are recorded and exported only when enabled with an
+	// endpoint. Root spans are sampled by TracingSampleRatio.
+	TracingEnabled       bool
+	TracingEndpoint      string
+	TracingServiceName   string
+	TracingSampleRatio   float64
+	TracingHeaders       map[string]string
+	TracingFlushInterval time.Duration
 }
 
 func Load() *Config {
@@ -166,6 +367,7 @@ func Load() *Config {
 	}
 	analyzerBaseURL := strings.TrimSpace(os.Getenv("ANALYZER_BASE_URL"))
 	analyzerEnabled := parseBoolEnv("ANALYZER_ENABLED", analyzerBaseURL != "")
+	tracingEndpoint := strings.TrimSpace(getEnvOrDefault("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", os.Getenv("OTEL_EXPORTER_OTLP_ENDPOINT")))
 	researchLeaseDuration := parseBoundedDurationMsEnv("RESEARCH_LEASE_DURATION_MS", 30*time.Second, time.Second, 5*time.Minute)
 	researchRenewInterval := parseBoundedDurationMsEnv("RESEARCH_RENEW_INTERVAL_MS", 10*time.Second, time.Second, researchLeaseDuration-time.Millisecond)
 	if researchRenewInterval >= researchLeaseDuration {
@@ -186,6 +388,46 @@ func Load() *Config {
 		RedisURL:           getEnvOrDefault("REDIS_URL", "redis://localhost:6380"),
 		WorkerCount:        workerCount,
 
+		TrustedProxies: parseListEnv("TRUSTED_PROXIES"),
+		BasePath:       strings.TrimSpace(os.Getenv("BASE_PATH")),
+
+		// Built-in TLS
+		TLSAddr:          getEnvOrDefault("TLS_ADDR", ":443"),
+		TLSCertFile:      strings.TrimSpace(os.Getenv("TLS_CERT_FILE")),
+		TLSKeyFile:       strings.TrimSpace(os.Getenv("TLS_KEY_FILE")),
+		TLSRedirectHTTP:  parseBoolEnv("TLS_REDIRECT_HTTP", true),
+		ACMEDomains:      parseListEnv("ACME_DOMAINS"),
+		ACMEEmail:        strings.TrimSpace(os.Getenv("ACME_EMAIL")),
+		ACMECacheDir:     getEnvOrDefault("ACME_CACHE_DIR", defaultACMECacheDir()),
+		ACMEDirectoryURL: strings.TrimSpace(os.Getenv("ACME_DIRECTORY_URL")),
+
+		WebUIEnabled: parseBoolEnv("WEBUI_ENABLED", false),
+
+		// Database pool configuration
+		DBMaxOpenConns:       parseBoundedIntEnv("DB_MAX_OPEN_CONNS", 25, 1, 1000),
+		DBMaxIdleConns:       parseBoundedIntEnv("DB_MAX_IDLE_CONNS", 5, 0, 1000),
+		DBConnMaxLifetime:    parseBoundedDurationMsEnv("DB_CONN_MAX_LIFETIME_MS", 30*time.Minute, 0, 24*time.Hour),
+		DBConnMaxIdleTime:    parseBoundedDurationMsEnv("DB_CONN_MAX_IDLE_TIME_MS", 5*time.Minute, 0, 24*time.Hour),
+		DBConnectTimeout:     parseBoundedDurationMsEnv("DB_CONNECT_TIMEOUT_MS", 5*time.Second, 0, time.Minute),
+		DBStatementTimeout:   parseBoundedDurationMsEnv("DB_STATEMENT_TIMEOUT_MS", 0, 0, time.Hour),
+		DBSlowQueryThreshold: parseBoundedDurationMsEnv("DB_SLOW_QUERY_MS", 500*time.Millisecond, 0, time.Minute),
+		DBReplicaHost:        strings.TrimSpace(os.Getenv("DB_REPLICA_HOST")),
+		DBReplicaPort:        getEnvOrDefault("DB_REPLICA_PORT", getEnvOrDefault("DB_PORT", "5432")),
+		DBReplicaUser:        getEnvOrDefault("DB_REPLICA_USER", getEnvOrDefault("DB_USER", "omp")),
+		DBReplicaPassword:    getEnvOrDefault("DB_REPLICA_PASSWORD", getEnvOrDefault("DB_PASSWORD", "omp_dev_password")),
+		DBReplicaName:        getEnvOrDefault("DB_REPLICA_NAME", getEnvOrDefault("DB_NAME", "openmusicplayer")),
+
+		PlaylistHistoryRetentionDays: parseBoundedIntEnv("PLAYLIST_HISTORY_RETENTION_DAYS", 90, 1, 3650),
+		PlaylistHistoryMaxRevisions:  parseBoundedIntEnv("PLAYLIST_HISTORY_MAX_REVISIONS", 100, 1, 10000),
+
+		PlayEventRetentionDays:       parseBoundedIntEnv("PLAY_EVENT_RETENTION_DAYS", 0, 90, 36500),
+		PlayDailyRollupRetentionDays: parseBoundedIntEnv("PLAY_DAILY_ROLLUP_RETENTION_DAYS", 0, 90, 36500),
+
+		ArtworkFolderFilenames:  parseListEnv("ARTWORK_FOLDER_FILENAMES"),
+		MetadataFieldPrecedence: parseListEnv("METADATA_FIELD_PRECEDENCE"),
+		SortArticles:            parseListEnv("SORT_ARTICLES"),
+		SortCollation:           strings.TrimSpace(os.Getenv("SORT_COLLATION")),
+
 		// S3/MinIO configuration
 		S3Endpoint:       getEnvOrDefault("MINIO_ENDPOINT", "http://localhost:9000"),
 		S3Region:         getEnvOrDefault("S3_REGION", "us-east-1"),
@@ -225,6 +467,89 @@ func Load() *Config {
 
 		AgentServiceToken: strings.TrimSpace(os.Getenv("OMP_AGENT_SERVICE_TOKEN")),
 		FirecrawlAPIKey:   strings.TrimSpace(os.Getenv("FIRECRAWL_API_KEY")),
+		JobControlToken:   strings.TrimSpace(os.Getenv("OMP_JOB_CONTROL_TOKEN")),
+
+		LastFMAPIKey: strings.TrimSpace(os.Getenv("LASTFM_API_KEY")),
+
+		// Disk space admission control
+		DiskMonitorPath:     getEnvOrDefault("DISK_MONITOR_PATH", os.TempDir()),
+		DiskMinFreeMB:       parseBoundedIntEnv("DISK_MIN_FREE_MB", 2048, 0, 1<<20),
+		DiskAlertWebhookURL: strings.TrimSpace(os.Getenv("DISK_ALERT_WEBHOOK_URL")),
+
+		// Managed external binaries
+		ToolsDir:                 getEnvOrDefault("TOOLS_DIR", defaultToolsDir()),
+		ToolsAutoDownload:        parseBoolEnv("TOOLS_AUTO_DOWNLOAD", false),
+		YTDLPVersion:             getEnvOrDefault("YTDLP_VERSION", "2025.09.26"),
+		FFmpegVersion:            getEnvOrDefault("FFMPEG_STATIC_VERSION", "b6.0"),
+		ToolsUpdateCheckInterval: time.Duration(parseBoundedIntEnv("TOOLS_UPDATE_CHECK_HOURS", 24, 0, 24*30)) * time.Hour,
+		YTDLPAutoUpdate:          parseBoolEnv("YTDLP_AUTO_UPDATE", false),
+
+		// Downloader routing
+		ExternalDownloaders:        parseCommandListEnv("EXTERNAL_DOWNLOADERS"),
+		DirectDownloadAllowPrivate: parseBoolEnv("DIRECT_DOWNLOAD_ALLOW_PRIVATE", false),
+		ExternalProxies:            parseKeyValueListEnv("EXTERNAL_PROXIES"),
+
+		// SponsorBlock skip segments
+		SponsorBlockEnabled:    parseBoolEnv("SPONSORBLOCK_ENABLED", true),
+		SponsorBlockURL:        getEnvOrDefault("SPONSORBLOCK_URL", "https://sponsor.ajay.app"),
+		SponsorBlockCategories: parseListEnv("SPONSORBLOCK_CATEGORIES"),
+		SponsorBlockTrim:       parseBoolEnv("SPONSORBLOCK_TRIM", false),
+
+		// Loudness normalization on ingest
+		LoudnessTargetLUFS:    parseBoundedFloatEnv("LOUDNESS_TARGET_LUFS", -14, -30, -5),
+		LoudnessKeepOriginals: parseBoolEnv("LOUDNESS_KEEP_ORIGINALS", true),
+
+		// Dynamic range and quality scoring on ingest
+		QualityScoringEnabled: parseBoolEnv("QUALITY_SCORING_ENABLED", true),
+
+		// Mood features on ingest
+		MoodAnalysisEnabled: parseBoolEnv("MOOD_ANALYSIS_ENABLED", true),
+
+		// Matching downloads to stored tracks by their audio
+		AudioHashMatch: getEnvOrDefault("AUDIO_HASH_MATCH", "strict"),
+
+		// Login cookies for sources downloads need them for
+		SourceCredentialsKey: strings.TrimSpace(os.Getenv("SOURCE_CREDENTIALS_KEY")),
+
+		// Library-wide format policy
+		FormatPolicy:             strings.TrimSpace(os.Getenv("FORMAT_POLICY")),
+		FormatPolicyKeepLossless: parseBoolEnv("FORMAT_POLICY_KEEP_LOSSLESS", true),
+
+		// Remote folder imports
+		RemoteImportSources: parseKeyValueListEnv("REMOTE_IMPORT_SOURCES"),
+
+		// Beets library imports
+		BeetsConfig:     strings.TrimSpace(os.Getenv("BEETS_CONFIG")),
+		BeetsExecutable: getEnvOrDefault("BEETS_EXECUTABLE", "beet"),
+
+		// Navidrome/Jellyfin library migrations
+		MigrationSources: parseKeyValueListEnv("MIGRATION_SOURCES"),
+
+		// Library exports
+		ExportDir: strings.TrimSpace(os.Getenv("EXPORT_DIR")),
+
+		// Guest mode
+		GuestMode:           parseBoolEnv("GUEST_MODE", false),
+		GuestShelfPlaylists: parseListEnv("GUEST_SHELF_PLAYLISTS"),
+		GuestRateLimit:      parseBoundedIntEnv("GUEST_RATE_LIMIT", 60, 1, 10000),
+
+		// Stream backpressure and offline transcodes
+		StreamConcurrency:   parseBoundedIntEnv("STREAM_CONCURRENCY_PER_USER", 4, 1, 100),
+		TranscodeMaxActive:  parseBoundedIntEnv("TRANSCODE_MAX_ACTIVE", 16, 0, 10000),
+		TranscodeWarmBudget: parseBoundedIntEnv("TRANSCODE_WARM_BUDGET", 100, 0, 10000),
+		TranscodeWarmHour:   parseBoundedIntEnv("TRANSCODE_WARM_HOUR", 3, 0, 23),
+
+		// Daily mixes
+		DailyMixCount: parseBoundedIntEnv("DAILY_MIX_COUNT", 6, 0, 12),
+		DailyMixSize:  parseBoundedIntEnv("DAILY_MIX_SIZE", 50, 10, 200),
+		DailyMixHour:  parseBoundedIntEnv("DAILY_MIX_HOUR", 4, 0, 23),
+
+		// Fediverse publishing
+		ActivityPubEnabled: parseBoolEnv("ACTIVITYPUB_ENABLED", false),
+		ActivityPubBaseURL: strings.TrimSpace(os.Getenv("ACTIVITYPUB_BASE_URL")),
+
+		// Mobile push
+		PushFCMCredentialsFile: strings.TrimSpace(os.Getenv("PUSH_FCM_CREDENTIALS_FILE")),
 
 		// Audio analyzer service configuration
 		AnalyzerEnabled:     analyzerEnabled,
@@ -274,6 +599,13 @@ func Load() *Config {
 		ResearchRunTimeout:           parseBoundedDurationMsEnv("RESEARCH_RUN_TIMEOUT_MS", 2*time.Minute, time.Second, 5*time.Minute),
 		ResearchCancelGrace:          parseBoundedDurationMsEnv("RESEARCH_CANCEL_GRACE_MS", 2*time.Second, 100*time.Millisecond, 30*time.Second),
 		ResearchShutdownTimeout:      parseBoundedDurationMsEnv("RESEARCH_SHUTDOWN_TIMEOUT_MS", 30*time.Second, time.Second, 30*time.Second),
+
+		TracingEnabled:       parseBoolEnv("TRACING_ENABLED", tracingEndpoint != ""),
+		TracingEndpoint:      tracingEndpoint,
+		TracingServiceName:   getEnvOrDefault("OTEL_SERVICE_NAME", "openmusicplayer-backend"),
+		TracingSampleRatio:   parseBoundedFloatEnv("OTEL_TRACES_SAMPLER_ARG", 1, 0, 1),
+		TracingHeaders:       parseKeyValueListEnv("OTEL_EXPORTER_OTLP_HEADERS"),
+		TracingFlushInterval: parseBoundedDurationMsEnv("OTEL_BSP_SCHEDULE_DELAY", 5*time.Second, 100*time.Millisecond, time.Minute),
 	}
 }
 
@@ -370,6 +702,63 @@ func parseBoundedIntEnv(key string, defaultValue, minimum, maximum int) int {
 	return parsed
 }
 
+// parseBoundedFloatEnv clamps a float env var into [minimum, maximum], falling
+// back to defaultValue when unset or malformed.
+func parseBoundedFloatEnv(key string, defaultValue, minimum, maximum float64) float64 {
+	value := strings.TrimSpace(os.Getenv(key))
+	parsed, err := strconv.ParseFloat(value, 64)
+	if value == "" || err != nil {
+		return defaultValue
+	}
+	if parsed < minimum {
+		return minimum
+	}
+	if parsed > maximum {
+		return maximum
+	}
+	return parsed
+}
+
+// parseKeyValueListEnv reads the OTEL "k1=v1,k2=v2" header list format.
+// Values are secrets (collector API keys, remote credentials) and must never
+// be logged.
+func parseKeyValueListEnv(key string) map[string]string {
+	value := strings.TrimSpace(os.Getenv(key))
+	if value == "" {
+		return nil
+	}
+	result := make(map[string]string)
+	for _, part := range strings.Split(value, ",") {
+		name, val, ok := strings.Cut(part, "=")
+		name = strings.TrimSpace(name)
+		if !ok || name == "" {
+			continue
+		}
+		result[name] = strings.TrimSpace(val)
+	}
+	return result
+}
+
+// parseCommandListEnv reads "name=command arg {url};name2=command2 ..." into
+// each name's whitespace-separated command line.
+func parseCommandListEnv(key string) map[string][]string {
+	value := strings.TrimSpace(os.Getenv(key))
+	if value == "" {
+		return nil
+	}
+	result := make(map[string][]string)
+	for _, part := range strings.Split(value, ";") {
+		name, command, ok := strings.Cut(part, "=")
+		name = strings.TrimSpace(name)
+		args := strings.Fields(command)
+		if !ok || name == "" || len(args) == 0 {
+			continue
+		}
+		result[name] = args
+	}
+	return result
+}
+
 // parseCohortBPSEnv preserves invalid values so ValidateResearchRollout can
 // reject them instead of silently widening a production rollout.
 func parseCohortBPSEnv(key string) int {
@@ -399,7 +788,9 @@ func parseCORSAllowedOrigins() []string {
 	parts := strings.Split(value, ",")
 	origins := make([]string, 0, len(parts))
 	for _, part := range parts {
-		origin := strings.TrimSpace(part)
+		// Browsers send origins without a trailing slash, so one copied from
+		// the address bar would otherwise never match.
+		origin := strings.TrimRight(strings.TrimSpace(part), "/")
 		if origin != "" {
 			origins = append(origins, origin)
 		}
@@ -407,6 +798,22 @@ func parseCORSAllowedOrigins() []string {
 	return origins
 }
 
+// parseListEnv reads a comma-separated list. An unset variable returns nil and
+// an empty one an empty, non-nil slice.
+func parseListEnv(key string) []string {
+	value, ok := os.LookupEnv(key)
+	if !ok {
+		return nil
+	}
+	items := []string{}
+	for _, part := range strings.Split(value, ",") {
+		if item := strings.TrimSpace(part); item != "" {
+			items = append(items, item)
+		}
+	}
+	return items
+}
+
 func getEnvOrDefault(key, defaultValue string) string {
 	if value := os.Getenv(key); value != "" {
 		return value
@@ -494,6 +901,24 @@ func (c *Config) ResearchChildEnvironment() map[string]string {
 	return environment
 }
 
+// defaultToolsDir keeps managed binaries in the user cache directory, falling
+// back to the temp directory when there is no home (as in minimal containers).
+func defaultToolsDir() string {
+	if dir, err := os.UserCacheDir(); err == nil {
+		return filepath.Join(dir, "open-music-player", "tools")
+	}
+	return filepath.Join(os.TempDir(), "open-music-player-tools")
+}
+
+// defaultACMECacheDir keeps issued certificates beside the managed tools, so
+// restarts reuse them instead of hitting the CA's rate limits.
+func defaultACMECacheDir() string {
+	if dir, err := os.UserCacheDir(); err == nil {
+		return filepath.Join(dir, "open-music-player", "acme")
+	}
+	return filepath.Join(os.TempDir(), "open-music-player-acme")
+}
+
 func generateDefaultSecret() string {
 	bytes := make([]byte, 32)
 	if _, err := rand.Read(bytes); err != nil {
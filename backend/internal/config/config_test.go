@@ -62,7 +62,7 @@ func TestLoadAllowsEmptyCORSAllowedOriginsToDisableHeaders(t *testing.T) {
 
 func TestLoadFallsBackToLegacyCORSAllowedOrigins(t *testing.T) {
 	withUnsetEnv(t, "OMP_CORS_ALLOWED_ORIGINS")
-	t.Setenv("CORS_ALLOWED_ORIGINS", "http://localhost:18145, https://app.example")
+	t.Setenv("CORS_ALLOWED_ORIGINS", "http://localhost:18145, https://app.example/")
 
 	cfg := Load()
 	want := []string{"http://localhost:18145", "https://app.example"}
@@ -76,6 +76,62 @@ func TestLoadFallsBackToLegacyCORSAllowedOrigins(t *testing.T) {
 	}
 }
 
+func TestLoadParsesReverseProxySettings(t *testing.T) {
+	t.Setenv("TRUSTED_PROXIES", "10.0.0.0/8, 172.18.0.2")
+	t.Setenv("BASE_PATH", " /music ")
+
+	cfg := Load()
+	if len(cfg.TrustedProxies) != 2 || cfg.TrustedProxies[1] != "172.18.0.2" || cfg.BasePath != "/music" {
+		t.Fatalf("proxy settings = %#v, %q", cfg.TrustedProxies, cfg.BasePath)
+	}
+}
+
+func TestLoadParsesArtworkFolderFilenames(t *testing.T) {
+	t.Setenv("ARTWORK_FOLDER_FILENAMES", " cover.jpg, ,folder.png ")
+
+	cfg := Load()
+	if len(cfg.ArtworkFolderFilenames) != 2 || cfg.ArtworkFolderFilenames[0] != "cover.jpg" || cfg.ArtworkFolderFilenames[1] != "folder.png" {
+		t.Fatalf("ArtworkFolderFilenames = %#v, want [cover.jpg folder.png]", cfg.ArtworkFolderFilenames)
+	}
+
+	t.Setenv("ARTWORK_FOLDER_FILENAMES", "")
+	if cfg := Load(); cfg.ArtworkFolderFilenames == nil || len(cfg.ArtworkFolderFilenames) != 0 {
+		t.Fatalf("ArtworkFolderFilenames = %#v, want explicit empty slice", cfg.ArtworkFolderFilenames)
+	}
+}
+
+func TestLoadParsesMetadataFieldPrecedence(t *testing.T) {
+	if cfg := Load(); cfg.MetadataFieldPrecedence != nil {
+		t.Fatalf("MetadataFieldPrecedence = %#v, want nil when unset", cfg.MetadataFieldPrecedence)
+	}
+
+	t.Setenv("METADATA_FIELD_PRECEDENCE", "tag, musicbrainz,provider")
+	cfg := Load()
+	if len(cfg.MetadataFieldPrecedence) != 3 || cfg.MetadataFieldPrecedence[0] != "tag" || cfg.MetadataFieldPrecedence[1] != "musicbrainz" {
+		t.Fatalf("MetadataFieldPrecedence = %#v, want [tag musicbrainz provider]", cfg.MetadataFieldPrecedence)
+	}
+}
+
+func TestLoadParsesSortSettings(t *testing.T) {
+	withUnsetEnv(t, "SORT_ARTICLES")
+	withUnsetEnv(t, "SORT_COLLATION")
+	if cfg := Load(); cfg.SortArticles != nil || cfg.SortCollation != "" {
+		t.Fatalf("sort settings = %#v, %q; want defaults when unset", cfg.SortArticles, cfg.SortCollation)
+	}
+
+	t.Setenv("SORT_ARTICLES", "The, Die,Les")
+	t.Setenv("SORT_COLLATION", " und-x-icu ")
+	cfg := Load()
+	if len(cfg.SortArticles) != 3 || cfg.SortArticles[1] != "Die" || cfg.SortCollation != "und-x-icu" {
+		t.Fatalf("sort settings = %#v, %q; want [The Die Les] und-x-icu", cfg.SortArticles, cfg.SortCollation)
+	}
+
+	t.Setenv("SORT_ARTICLES", "")
+	if cfg := Load(); cfg.SortArticles == nil || len(cfg.SortArticles) != 0 {
+		t.Fatalf("SortArticles = %#v, want empty list to disable stripping", cfg.SortArticles)
+	}
+}
+
 func TestLoadAIAssistDisabledByDefault(t *testing.T) {
 	for _, key := range []string{"AI_ASSIST_ENABLED", "AI_ASSIST_BASE_URL", "AI_ASSIST_API_KEY", "AI_ASSIST_MODEL", "AI_ASSIST_TIMEOUT_MS"} {
 		withUnsetEnv(t, key)
@@ -483,6 +539,40 @@ func withUnsetResearchEnv(t *testing.T) {
 	}
 }
 
+func TestLoadDBPoolDefaultsAndReplicaFallbacks(t *testing.T) {
+	for _, key := range []string{"DB_MAX_OPEN_CONNS", "DB_SLOW_QUERY_MS", "DB_STATEMENT_TIMEOUT_MS", "DB_REPLICA_USER", "DB_REPLICA_NAME"} {
+		withUnsetEnv(t, key)
+	}
+	t.Setenv("DB_USER", "primary_user")
+	t.Setenv("DB_NAME", "primary_db")
+	t.Setenv("DB_REPLICA_HOST", " replica.internal ")
+
+	cfg := Load()
+	if cfg.DBMaxOpenConns != 25 || cfg.DBSlowQueryThreshold != 500*time.Millisecond || cfg.DBStatementTimeout != 0 {
+		t.Fatalf("pool defaults = %d/%s/%s", cfg.DBMaxOpenConns, cfg.DBSlowQueryThreshold, cfg.DBStatementTimeout)
+	}
+	if cfg.DBReplicaHost != "replica.internal" || cfg.DBReplicaUser != "primary_user" || cfg.DBReplicaName != "primary_db" {
+		t.Fatalf("replica = %q %q %q, want primary credential fallbacks", cfg.DBReplicaHost, cfg.DBReplicaUser, cfg.DBReplicaName)
+	}
+}
+
+func TestLoadDBPoolClampsAndRejectsInvalidValues(t *testing.T) {
+	t.Setenv("DB_MAX_OPEN_CONNS", "0")
+	t.Setenv("DB_MAX_IDLE_CONNS", "5000")
+	t.Setenv("DB_SLOW_QUERY_MS", "0")
+
+	cfg := Load()
+	if cfg.DBMaxOpenConns != 25 {
+		t.Fatalf("DBMaxOpenConns = %d, want default for below-minimum value", cfg.DBMaxOpenConns)
+	}
+	if cfg.DBMaxIdleConns != 1000 {
+		t.Fatalf("DBMaxIdleConns = %d, want clamp to 1000", cfg.DBMaxIdleConns)
+	}
+	if cfg.DBSlowQueryThreshold != 0 {
+		t.Fatalf("DBSlowQueryThreshold = %s, want explicit zero to disable", cfg.DBSlowQueryThreshold)
+	}
+}
+
 func withUnsetEnv(t *testing.T, key string) {
 	t.Helper()
 	oldValue, hadValue := os.LookupEnv(key)
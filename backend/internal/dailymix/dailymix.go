@@ -0,0 +1,320 @@
+// Package dailymix generates each user's daily mixes: a handful of read-only
+// playlists, one per cluster of their library by genre and mood, weighted
+// toward what they have played and liked lately and refreshed once a day.
+package dailymix
+
+import (
+	"cmp"
+	"context"
+	"fmt"
+	"hash/fnv"
+	"log"
+	"math"
+	"math/rand"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/shuffle"
+)
+
+const (
+	// CheckInterval is how often Run looks for users whose mixes are due.
+	CheckInterval = time.Hour
+
+	// historyWindow is how far back plays count toward a track's weight.
+	// Raw play events are kept at least this long.
+	historyWindow = 90 * 24 * time.Hour
+
+	// dueBatch caps the users one pass refreshes; the rest wait an hour.
+	dueBatch = 500
+
+	// minMixTracks is the fewest tracks a cluster needs to become a mix, and
+	// a library needs to get any mix at all.
+	minMixTracks = 10
+
+	// likedWeight is how many plays liking a track counts as.
+	likedWeight = 3
+)
+
+// Moods, banded from a track's analyzed energy.
+const (
+	Calm      = "calm"
+	Steady    = "steady"
+	Energetic = "energetic"
+)
+
+// Store finds users due for mixes and what their mixes may hold, and saves
+// them. db.DailyMixRepository satisfies this interface.
+type Store interface {
+	DueUsers(ctx context.Context, cutoff time.Time, minTracks, limit int) ([]uuid.UUID, error)
+	Candidates(ctx context.Context, userID uuid.UUID, since time.Time) ([]db.DailyMixCandidate, error)
+	Replace(ctx context.Context, userID uuid.UUID, mixes []db.DailyMix) error
+}
+
+// Generator refreshes users' daily mixes once a day at a set hour.
+type Generator struct {
+	store Store
+	count int
+	size  int
+	hour  int
+	now   func() time.Time
+}
+
+// New creates a generator making up to count mixes of up to size tracks per
+// user, refreshed after hour, in local time, each day.
+func New(store Store, count, size, hour int) *Generator {
+	return &Generator{store: store, count: count, size: size, hour: hour, now: time.Now}
+}
+
+// Run refreshes due mixes every CheckInterval until ctx is done.
+func (g *Generator) Run(ctx context.Context) {
+	ticker := time.NewTicker(CheckInterval)
+	defer ticker.Stop()
+	for {
+		if err := g.RefreshDue(ctx); err != nil {
+			log.Printf("Failed to refresh daily mixes: %v", err)
+		}
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// RefreshDue refreshes the mixes of users who have none yet, or whose mixes
+// predate the latest refresh hour. A user whose refresh fails is logged and
+// retried next pass.
+func (g *Generator) RefreshDue(ctx context.Context) error {
+	users, err := g.store.DueUsers(ctx, lastRefresh(g.now(), g.hour), minMixTracks, dueBatch)
+	if err != nil {
+		return fmt.Errorf("find users due for daily mixes: %w", err)
+	}
+	for _, userID := range users {
+		if ctx.Err() != nil {
+			return ctx.Err()
+		}
+		if _, err := g.Refresh(ctx, userID); err != nil {
+			log.Printf("Failed to refresh daily mixes for user %s: %v", userID, err)
+		}
+	}
+	return nil
+}
+
+// Refresh regenerates the user's mixes now. The same user gets the same
+// mixes for the same library and history within a day.
+func (g *Generator) Refresh(ctx context.Context, userID uuid.UUID) ([]db.DailyMix, error) {
+	now := g.now()
+	candidates, err := g.store.Candidates(ctx, userID, now.Add(-historyWindow))
+	if err != nil {
+		return nil, fmt.Errorf("load candidates: %w", err)
+	}
+	seed := fnv.New64a()
+	seed.Write(userID[:])
+	seed.Write([]byte(now.Format(time.DateOnly)))
+	mixes := Build(candidates, g.count, g.size, rand.New(rand.NewSource(int64(seed.Sum64()))))
+	if err := g.store.Replace(ctx, userID, mixes); err != nil {
+		return nil, fmt.Errorf("save mixes: %w", err)
+	}
+	return mixes, nil
+}
+
+// lastRefresh is the latest time at or before now that is on hour.
+func lastRefresh(now time.Time, hour int) time.Time {
+	at := time.Date(now.Year(), now.Month(), now.Day(), hour, 0, 0, 0, now.Location())
+	if at.After(now) {
+		at = at.AddDate(0, 0, -1)
+	}
+	return at
+}
+
+// MoodOf bands an energy from 0 to 1 into a mood.
+func MoodOf(energy float64) string {
+	switch {
+	case energy < 0.4:
+		return Calm
+	case energy >= 0.7:
+		return Energetic
+	default:
+		return Steady
+	}
+}
+
+// cluster is tracks sharing a genre and, unless mood is empty, a mood.
+type cluster struct {
+	genre  string
+	mood   string
+	tracks []db.DailyMixCandidate
+	weight float64
+}
+
+// Build clusters candidates by genre, then splits each genre by mood where
+// every part is big enough for a mix of its own. The count clusters the user
+// listens to most become mixes of up to size tracks, sampled by weight and
+// ordered to keep artists apart. A library without such clusters gets one
+// mix of everything, and one too small for a mix gets none.
+func Build(candidates []db.DailyMixCandidate, count, size int, rng *rand.Rand) []db.DailyMix {
+	if count <= 0 || size <= 0 || len(candidates) < minMixTracks {
+		return nil
+	}
+	clusters := clusterCandidates(candidates)
+	if len(clusters) == 0 {
+		clusters = []cluster{{tracks: candidates, weight: totalWeight(candidates)}}
+	}
+	slices.SortStableFunc(clusters, func(a, b cluster) int {
+		return cmp.Compare(b.weight, a.weight)
+	})
+	if len(clusters) > count {
+		clusters = clusters[:count]
+	}
+
+	mixes := make([]db.DailyMix, len(clusters))
+	for i, c := range clusters {
+		picked := sample(c.tracks, size, rng)
+		items := make([]shuffle.Item, len(picked))
+		for j, t := range picked {
+			items[j] = shuffle.Item{ID: t.TrackID, Artist: strings.ToLower(t.Artist), Album: t.Album, LastPlayedAt: t.LastPlayedAt}
+		}
+		items = shuffle.Order(items, shuffle.ArtistSpread, shuffle.DefaultSpacing, rng)
+		trackIDs := make([]int64, len(items))
+		for j, item := range items {
+			trackIDs[j] = item.ID
+		}
+		mixes[i] = db.DailyMix{
+			Name:        fmt.Sprintf("Daily Mix %d", i+1),
+			Description: describe(c, picked),
+			Genre:       c.genre,
+			Mood:        c.mood,
+			TrackIDs:    trackIDs,
+		}
+	}
+	return mixes
+}
+
+// clusterCandidates groups tracks with a genre, in order of the genre's
+// first track so results do not depend on map order.
+func clusterCandidates(candidates []db.DailyMixCandidate) []cluster {
+	var genres []string
+	byGenre := map[string][]db.DailyMixCandidate{}
+	names := map[string]string{}
+	for _, c := range candidates {
+		key := strings.ToLower(strings.TrimSpace(c.Genre))
+		if key == "" {
+			continue
+		}
+		if _, ok := byGenre[key]; !ok {
+			genres = append(genres, key)
+			names[key] = strings.TrimSpace(c.Genre)
+		}
+		byGenre[key] = append(byGenre[key], c)
+	}
+
+	var clusters []cluster
+	for _, key := range genres {
+		tracks := byGenre[key]
+		byMood := map[string][]db.DailyMixCandidate{}
+		var rest []db.DailyMixCandidate
+		for _, t := range tracks {
+			if t.Energy.Valid {
+				mood := MoodOf(t.Energy.Float64)
+				byMood[mood] = append(byMood[mood], t)
+			} else {
+				rest = append(rest, t)
+			}
+		}
+		var split []cluster
+		for _, mood := range []string{Calm, Steady, Energetic} {
+			if moodTracks := byMood[mood]; len(moodTracks) >= minMixTracks {
+				split = append(split, cluster{genre: names[key], mood: mood, tracks: moodTracks, weight: totalWeight(moodTracks)})
+			} else {
+				rest = append(rest, moodTracks...)
+			}
+		}
+		// A genre only splits by mood when nothing is left too small to
+		// stand alone; otherwise it stays one mix across moods.
+		if len(split) > 0 && len(rest) == 0 {
+			clusters = append(clusters, split...)
+		} else if len(tracks) >= minMixTracks {
+			clusters = append(clusters, cluster{genre: names[key], tracks: tracks, weight: totalWeight(tracks)})
+		}
+	}
+	return clusters
+}
+
+// weight is how strongly a track pulls its cluster up the ranking and
+// itself into a mix.
+func weight(c db.DailyMixCandidate) float64 {
+	w := float64(1 + c.Plays)
+	if c.Liked {
+		w += likedWeight
+	}
+	return w
+}
+
+func totalWeight(tracks []db.DailyMixCandidate) float64 {
+	var total float64
+	for _, t := range tracks {
+		total += weight(t)
+	}
+	return total
+}
+
+// sample draws up to size tracks without replacement, each as likely as its
+// weight, by keeping the smallest exponential keys.
+func sample(tracks []db.DailyMixCandidate, size int, rng *rand.Rand) []db.DailyMixCandidate {
+	if len(tracks) <= size {
+		return slices.Clone(tracks)
+	}
+	type keyed struct {
+		track db.DailyMixCandidate
+		key   float64
+	}
+	all := make([]keyed, len(tracks))
+	for i, t := range tracks {
+		all[i] = keyed{track: t, key: -math.Log(1-rng.Float64()) / weight(t)}
+	}
+	slices.SortFunc(all, func(a, b keyed) int { return cmp.Compare(a.key, b.key) })
+	picked := make([]db.DailyMixCandidate, size)
+	for i := range picked {
+		picked[i] = all[i].track
+	}
+	return picked
+}
+
+// describe names the cluster and the three artists it leans on most, such as
+// "Rock · calm — A, B and C".
+func describe(c cluster, tracks []db.DailyMixCandidate) string {
+	label := c.genre
+	if label == "" {
+		label = "Your library"
+	}
+	if c.mood != "" {
+		label += " · " + c.mood
+	}
+
+	var artists []string
+	weights := map[string]float64{}
+	for _, t := range tracks {
+		artist := strings.TrimSpace(t.Artist)
+		if artist == "" {
+			continue
+		}
+		if _, ok := weights[artist]; !ok {
+			artists = append(artists, artist)
+		}
+		weights[artist] += weight(t)
+	}
+	slices.SortStableFunc(artists, func(a, b string) int { return cmp.Compare(weights[b], weights[a]) })
+	switch n := min(len(artists), 3); n {
+	case 0:
+		return label
+	case 1:
+		return label + " — " + artists[0]
+	default:
+		return label + " — " + strings.Join(artists[:n-1], ", ") + " and " + artists[n-1]
+	}
+}
@@ -0,0 +1,167 @@
+package dailymix
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+	"math/rand"
+	"reflect"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// tracks makes n candidates of one genre and energy, by rotating artists.
+func tracks(firstID int64, n int, genre string, energy float64) []db.DailyMixCandidate {
+	out := make([]db.DailyMixCandidate, n)
+	for i := range out {
+		out[i] = db.DailyMixCandidate{
+			TrackID: firstID + int64(i),
+			Artist:  fmt.Sprintf("%s artist %d", genre, i%4),
+			Genre:   genre,
+		}
+		if energy >= 0 {
+			out[i].Energy = sql.NullFloat64{Float64: energy, Valid: true}
+		}
+	}
+	return out
+}
+
+func TestBuildClustersByGenreAndMood(t *testing.T) {
+	var candidates []db.DailyMixCandidate
+	candidates = append(candidates, tracks(1, 12, "Jazz", 0.2)...)
+	candidates = append(candidates, tracks(100, 12, "Jazz", 0.9)...)
+	// Rock's calm tracks are too few to stand alone, so Rock stays whole.
+	candidates = append(candidates, tracks(200, 15, "rock", 0.5)...)
+	candidates = append(candidates, tracks(300, 3, "Rock", 0.1)...)
+	// Too few ambient tracks for a mix, and tracks without a genre never
+	// form one.
+	candidates = append(candidates, tracks(400, 5, "Ambient", 0.1)...)
+	candidates = append(candidates, tracks(500, 30, "", 0.5)...)
+	for i := range candidates {
+		if candidates[i].TrackID >= 200 && candidates[i].TrackID < 400 {
+			candidates[i].Plays = 2
+		}
+	}
+
+	mixes := Build(candidates, 6, 50, rand.New(rand.NewSource(1)))
+	var got []string
+	for _, m := range mixes {
+		got = append(got, m.Genre+"/"+m.Mood)
+	}
+	if want := []string{"rock/", "Jazz/calm", "Jazz/energetic"}; !reflect.DeepEqual(got, want) {
+		t.Fatalf("mixes = %v, want %v", got, want)
+	}
+	if len(mixes[0].TrackIDs) != 18 || mixes[0].Name != "Daily Mix 1" || mixes[2].Name != "Daily Mix 3" {
+		t.Fatalf("first mix = %+v", mixes[0])
+	}
+	if want := "Jazz · calm — "; mixes[1].Description[:len(want)] != want {
+		t.Fatalf("description = %q, want prefix %q", mixes[1].Description, want)
+	}
+
+	if top := Build(candidates, 1, 50, rand.New(rand.NewSource(1))); len(top) != 1 || top[0].Genre != "rock" {
+		t.Fatalf("Build(count 1) = %+v, want only the most played cluster", top)
+	}
+}
+
+func TestBuildSamplesAndSpreadsArtists(t *testing.T) {
+	candidates := tracks(1, 40, "Pop", -1)
+	mixes := Build(candidates, 6, 20, rand.New(rand.NewSource(7)))
+	if len(mixes) != 1 || len(mixes[0].TrackIDs) != 20 || mixes[0].Mood != "" {
+		t.Fatalf("mixes = %+v, want one mix of 20 tracks", mixes)
+	}
+	seen := map[int64]bool{}
+	for _, id := range mixes[0].TrackIDs {
+		if seen[id] {
+			t.Fatalf("track %d appears twice", id)
+		}
+		seen[id] = true
+	}
+
+	// Four artists with ten tracks each can always be kept apart.
+	ids := Build(candidates, 6, 40, rand.New(rand.NewSource(7)))[0].TrackIDs
+	for i := range ids {
+		for j := i + 1; j < len(ids) && j <= i+3; j++ {
+			if (ids[i]-1)%4 == (ids[j]-1)%4 {
+				t.Fatalf("tracks %v repeat an artist within 3 tracks", ids)
+			}
+		}
+	}
+}
+
+func TestBuildFallsBackToTheWholeLibrary(t *testing.T) {
+	candidates := append(tracks(1, 6, "Folk", 0.3), tracks(10, 6, "", -1)...)
+	mixes := Build(candidates, 6, 50, rand.New(rand.NewSource(1)))
+	if len(mixes) != 1 || mixes[0].Genre != "" || len(mixes[0].TrackIDs) != 12 {
+		t.Fatalf("mixes = %+v, want one mix of the whole library", mixes)
+	}
+	if Build(candidates[:minMixTracks-1], 6, 50, rand.New(rand.NewSource(1))) != nil {
+		t.Fatal("Build() of a tiny library made mixes")
+	}
+}
+
+func TestLastRefresh(t *testing.T) {
+	for _, tc := range []struct {
+		now  string
+		want string
+	}{
+		{"2026-03-10T05:30:00Z", "2026-03-10T04:00:00Z"},
+		{"2026-03-10T04:00:00Z", "2026-03-10T04:00:00Z"},
+		{"2026-03-10T03:59:00Z", "2026-03-09T04:00:00Z"},
+	} {
+		now, _ := time.Parse(time.RFC3339, tc.now)
+		if got := lastRefresh(now, 4).Format(time.RFC3339); got != tc.want {
+			t.Errorf("lastRefresh(%s) = %s, want %s", tc.now, got, tc.want)
+		}
+	}
+}
+
+type fakeStore struct {
+	due        []uuid.UUID
+	cutoff     time.Time
+	candidates []db.DailyMixCandidate
+	saved      map[uuid.UUID][]db.DailyMix
+}
+
+func (f *fakeStore) DueUsers(ctx context.Context, cutoff time.Time, minTracks, limit int) ([]uuid.UUID, error) {
+	f.cutoff = cutoff
+	return f.due, nil
+}
+
+func (f *fakeStore) Candidates(ctx context.Context, userID uuid.UUID, since time.Time) ([]db.DailyMixCandidate, error) {
+	return f.candidates, nil
+}
+
+func (f *fakeStore) Replace(ctx context.Context, userID uuid.UUID, mixes []db.DailyMix) error {
+	f.saved[userID] = mixes
+	return nil
+}
+
+func TestRefreshDueIsStableWithinADay(t *testing.T) {
+	user := uuid.New()
+	store := &fakeStore{due: []uuid.UUID{user}, candidates: tracks(1, 80, "Soul", 0.5), saved: map[uuid.UUID][]db.DailyMix{}}
+	g := New(store, 2, 25, 4)
+	g.now = func() time.Time { return time.Date(2026, 3, 10, 9, 0, 0, 0, time.UTC) }
+
+	if err := g.RefreshDue(context.Background()); err != nil {
+		t.Fatalf("RefreshDue() error = %v", err)
+	}
+	if !store.cutoff.Equal(time.Date(2026, 3, 10, 4, 0, 0, 0, time.UTC)) {
+		t.Fatalf("cutoff = %v, want today at 4:00", store.cutoff)
+	}
+	first := store.saved[user]
+	if len(first) != 1 || len(first[0].TrackIDs) != 25 {
+		t.Fatalf("saved = %+v", first)
+	}
+	again, err := g.Refresh(context.Background(), user)
+	if err != nil || !reflect.DeepEqual(again[0].TrackIDs, first[0].TrackIDs) {
+		t.Fatalf("Refresh() later the same day = %v, %v; want %v", again, err, first[0].TrackIDs)
+	}
+	g.now = func() time.Time { return time.Date(2026, 3, 11, 9, 0, 0, 0, time.UTC) }
+	if tomorrow, _ := g.Refresh(context.Background(), user); reflect.DeepEqual(tomorrow[0].TrackIDs, first[0].TrackIDs) {
+		t.Fatal("Refresh() the next day picked the same tracks in the same order")
+	}
+}
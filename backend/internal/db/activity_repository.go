@@ -0,0 +1,152 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// Activity kinds besides the playlist event actions, which the feed passes
+// through as they are.
+const (
+	ActivityTracksAdded       = "tracks_added"
+	ActivityDownloadCompleted = "download_completed"
+	ActivityDownloadFailed    = "download_failed"
+	ActivityListened          = "listened"
+)
+
+// activityTrackSample bounds the track IDs listed with one activity; its
+// TrackCount still counts them all.
+const activityTrackSample = 10
+
+// Activity is one entry of a user's "what's new" feed. Library additions made
+// in one transaction are one entry; tracks their own downloads added are left
+// to the download's entry. Playlist fields are set for playlist activity and
+// the job fields for downloads. UserID and Username are set for activity of
+// a followed user: new and changed public playlists, and a day's listening
+// when they share it.
+type Activity struct {
+	Kind         string
+	OccurredAt   time.Time
+	PlaylistID   sql.NullInt64
+	PlaylistName string
+	TrackCount   int
+	TrackIDs     []int64
+	JobID        uuid.NullUUID
+	Title        string
+	Artist       string
+	Error        string
+	UserID       uuid.NullUUID
+	Username     string
+}
+
+// ActivityRepository summarizes the events log, download jobs and followed
+// users' plays into a feed.
+type ActivityRepository struct {
+	db *DB
+}
+
+func NewActivityRepository(db *DB) *ActivityRepository {
+	return &ActivityRepository{db: db}
+}
+
+// List returns a page of the user's activity, newest first, and how many
+// entries the feed has in all.
+func (r *ActivityRepository) List(ctx context.Context, userID uuid.UUID, limit, offset int) ([]Activity, int, error) {
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 100 {
+		limit = 100
+	}
+	if offset < 0 {
+		offset = 0
+	}
+	rows, err := r.db.ReadQueryContext(ctx, `
+		WITH activity AS (
+			SELECT 'tracks_added' AS kind, MIN(e.id) AS ref, MAX(e.created_at) AS occurred_at,
+				NULL::bigint AS playlist_id, NULL::text AS playlist_name,
+				COUNT(*)::int AS track_count, (array_agg(e.entity_id ORDER BY e.id))[1:$4] AS track_ids,
+				NULL::uuid AS job_id, NULL::text AS title, NULL::text AS artist, NULL::text AS error,
+				NULL::uuid AS user_id, NULL::text AS username
+			FROM events e
+			WHERE e.user_id = $1 AND e.action = 'library_added'
+				AND NOT EXISTS (
+					SELECT 1 FROM download_jobs j
+					WHERE j.user_id = $1 AND j.track_id = e.entity_id AND j.status = 'complete'
+				)
+			GROUP BY e.tx_id
+			UNION ALL
+			SELECT e.action, e.id, e.created_at,
+				e.entity_id, COALESCE(p.name, e.payload->>'name'),
+				COALESCE(jsonb_array_length(e.payload->'track_ids'), 0),
+				ARRAY(SELECT jsonb_array_elements_text(e.payload->'track_ids')::bigint LIMIT $4),
+				NULL, NULL, NULL, NULL, NULL, NULL
+			FROM events e
+			LEFT JOIN playlists p ON p.id = e.entity_id
+			WHERE e.user_id = $1 AND e.entity_type = 'playlist'
+			UNION ALL
+			SELECT CASE j.status WHEN 'complete' THEN 'download_completed' ELSE 'download_failed' END, 0, j.completed_at,
+				NULL, NULL,
+				CASE WHEN j.track_id IS NULL THEN 0 ELSE 1 END, ARRAY_REMOVE(ARRAY[j.track_id], NULL),
+				j.id, j.title, j.artist, j.error, NULL, NULL
+			FROM download_jobs j
+			WHERE j.user_id = $1 AND j.status IN ('complete', 'failed') AND j.completed_at IS NOT NULL
+			UNION ALL
+			SELECT e.action, e.id, e.created_at,
+				e.entity_id, p.name,
+				COALESCE(jsonb_array_length(e.payload->'track_ids'), 0),
+				ARRAY(SELECT jsonb_array_elements_text(e.payload->'track_ids')::bigint LIMIT $4),
+				NULL, NULL, NULL, NULL, u.id, u.username
+			FROM user_follows f
+			JOIN users u ON u.id = f.followee_id
+			JOIN events e ON e.user_id = f.followee_id AND e.entity_type = 'playlist'
+				AND e.action IN ('playlist_created', 'playlist_updated', 'playlist_tracks_added')
+			JOIN playlists p ON p.id = e.entity_id AND p.is_public
+			WHERE f.follower_id = $1
+			UNION ALL
+			SELECT 'listened', MAX(pe.id), MAX(pe.played_at),
+				NULL, NULL,
+				COUNT(*)::int, (array_agg(pe.track_id ORDER BY pe.played_at DESC))[1:$4],
+				NULL, NULL, NULL, NULL, u.id, u.username
+			FROM user_follows f
+			JOIN users u ON u.id = f.followee_id
+			JOIN user_settings s ON s.user_id = f.followee_id AND s.share_listening
+			JOIN play_events pe ON pe.user_id = f.followee_id
+			WHERE f.follower_id = $1
+			GROUP BY u.id, u.username, (pe.played_at AT TIME ZONE 'UTC')::date
+		)
+		SELECT kind, occurred_at, playlist_id, playlist_name, track_count, track_ids,
+			job_id, title, artist, error, user_id, username, COUNT(*) OVER()
+		FROM activity
+		ORDER BY occurred_at DESC, ref DESC
+		LIMIT $2 OFFSET $3
+	`, userID, limit, offset, activityTrackSample)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+
+	activity := []Activity{}
+	var total int
+	for rows.Next() {
+		var a Activity
+		var playlistName, title, artist, errMsg, username sql.NullString
+		var trackIDs pq.Int64Array
+		if err := rows.Scan(&a.Kind, &a.OccurredAt, &a.PlaylistID, &playlistName, &a.TrackCount, &trackIDs,
+			&a.JobID, &title, &artist, &errMsg, &a.UserID, &username, &total); err != nil {
+			return nil, 0, err
+		}
+		a.PlaylistName = playlistName.String
+		a.TrackIDs = []int64(trackIDs)
+		a.Title = title.String
+		a.Artist = artist.String
+		a.Error = errMsg.String
+		a.Username = username.String
+		activity = append(activity, a)
+	}
+	return activity, total, rows.Err()
+}
@@ -0,0 +1,347 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrActivityPubActorNotFound = errors.New("activitypub actor not found")
+var ErrActivityPubHandleTaken = errors.New("activitypub handle already in use")
+var ErrActivityPubPostNotFound = errors.New("activitypub post not found")
+
+// Kinds of ActivityPub posts.
+const (
+	ActivityPubPostListening = "listening"
+	ActivityPubPostLoved     = "loved"
+	ActivityPubPostPlaylist  = "playlist"
+)
+
+// ActivityPubActor is a user's fediverse identity: the handle remote servers
+// address them by, the key pair that signs what they publish, and what they
+// chose to publish.
+type ActivityPubActor struct {
+	UserID           uuid.UUID
+	Handle           string
+	PublicKeyPEM     string
+	PrivateKeyPEM    string
+	PublishListening bool
+	PublishLoved     bool
+	PublishPlaylists bool
+	LastEventID      int64
+	LastPlayID       int64
+	CreatedAt        time.Time
+	UpdatedAt        time.Time
+}
+
+// ActivityPubFollower is a remote actor following a user, with the inboxes
+// posts are delivered to.
+type ActivityPubFollower struct {
+	ActorIRI    string
+	Inbox       string
+	SharedInbox string
+	CreatedAt   time.Time
+}
+
+// ActivityPubPost is one published post. PlaylistID is set for playlist
+// posts, each playlist being published once.
+type ActivityPubPost struct {
+	ID          int64
+	UserID      uuid.UUID
+	Kind        string
+	Content     string
+	PlaylistID  int64
+	PublishedAt time.Time
+}
+
+// ActivityPubChange is an event of the user's that may become a post: a
+// favorited track or a change to a playlist, with the names a post needs.
+type ActivityPubChange struct {
+	EventID        int64
+	Action         string
+	TrackTitle     string
+	TrackArtist    string
+	PlaylistID     int64
+	PlaylistName   string
+	PlaylistPublic bool
+}
+
+// ActivityPubPlay is a play that may become a now-playing post.
+type ActivityPubPlay struct {
+	ID          int64
+	TrackTitle  string
+	TrackArtist string
+}
+
+type ActivityPubRepository struct {
+	db *DB
+}
+
+func NewActivityPubRepository(db *DB) *ActivityPubRepository {
+	return &ActivityPubRepository{db: db}
+}
+
+const activityPubActorColumns = `user_id, handle, public_key_pem, private_key_pem, publish_listening, publish_loved,
+	publish_playlists, last_event_id, last_play_id, created_at, updated_at`
+
+func scanActivityPubActor(row rowScanner) (*ActivityPubActor, error) {
+	var a ActivityPubActor
+	err := row.Scan(&a.UserID, &a.Handle, &a.PublicKeyPEM, &a.PrivateKeyPEM, &a.PublishListening, &a.PublishLoved,
+		&a.PublishPlaylists, &a.LastEventID, &a.LastPlayID, &a.CreatedAt, &a.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrActivityPubActorNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &a, nil
+}
+
+// GetByHandle returns the actor with the given handle.
+func (r *ActivityPubRepository) GetByHandle(ctx context.Context, handle string) (*ActivityPubActor, error) {
+	return scanActivityPubActor(r.db.QueryRowContext(ctx, `SELECT `+activityPubActorColumns+` FROM activitypub_actors WHERE handle = $1`, handle))
+}
+
+// GetByUser returns the user's actor.
+func (r *ActivityPubRepository) GetByUser(ctx context.Context, userID uuid.UUID) (*ActivityPubActor, error) {
+	return scanActivityPubActor(r.db.QueryRowContext(ctx, `SELECT `+activityPubActorColumns+` FROM activitypub_actors WHERE user_id = $1`, userID))
+}
+
+// List returns every actor, for the publisher.
+func (r *ActivityPubRepository) List(ctx context.Context) ([]ActivityPubActor, error) {
+	rows, err := r.db.QueryContext(ctx, `SELECT `+activityPubActorColumns+` FROM activitypub_actors ORDER BY created_at`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var actors []ActivityPubActor
+	for rows.Next() {
+		actor, err := scanActivityPubActor(rows)
+		if err != nil {
+			return nil, err
+		}
+		actors = append(actors, *actor)
+	}
+	return actors, rows.Err()
+}
+
+// Put creates the user's actor, or updates its handle and what it publishes.
+// The keys of an existing actor are kept. A new actor starts reading after
+// the user's latest event and play, so nothing from before is published.
+func (r *ActivityPubRepository) Put(ctx context.Context, actor *ActivityPubActor) error {
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO activitypub_actors (user_id, handle, public_key_pem, private_key_pem, publish_listening, publish_loved,
+			publish_playlists, last_event_id, last_play_id)
+		VALUES ($1, $2, $3, $4, $5, $6, $7,
+			(SELECT COALESCE(MAX(id), 0) FROM events WHERE user_id = $1),
+			(SELECT COALESCE(MAX(id), 0) FROM play_events WHERE user_id = $1))
+		ON CONFLICT (user_id) DO UPDATE SET
+			handle = EXCLUDED.handle,
+			publish_listening = EXCLUDED.publish_listening,
+			publish_loved = EXCLUDED.publish_loved,
+			publish_playlists = EXCLUDED.publish_playlists,
+			updated_at = NOW()
+		RETURNING `+activityPubActorColumns+`
+	`, actor.UserID, actor.Handle, actor.PublicKeyPEM, actor.PrivateKeyPEM, actor.PublishListening, actor.PublishLoved,
+		actor.PublishPlaylists,
+	).Scan(&actor.UserID, &actor.Handle, &actor.PublicKeyPEM, &actor.PrivateKeyPEM, &actor.PublishListening, &actor.PublishLoved,
+		&actor.PublishPlaylists, &actor.LastEventID, &actor.LastPlayID, &actor.CreatedAt, &actor.UpdatedAt)
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23505" {
+		return ErrActivityPubHandleTaken
+	}
+	return err
+}
+
+// Delete removes the user's actor with its followers and posts.
+func (r *ActivityPubRepository) Delete(ctx context.Context, userID uuid.UUID) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM activitypub_actors WHERE user_id = $1`, userID)
+	if err != nil {
+		return err
+	}
+	if n, err := result.RowsAffected(); err != nil {
+		return err
+	} else if n == 0 {
+		return ErrActivityPubActorNotFound
+	}
+	return nil
+}
+
+// AddFollower stores a remote follower, updating the inboxes of one that
+// follows again.
+func (r *ActivityPubRepository) AddFollower(ctx context.Context, userID uuid.UUID, follower ActivityPubFollower) error {
+	_, err := r.db.ExecContext(ctx, `
+		INSERT INTO activitypub_followers (user_id, actor_iri, inbox, shared_inbox)
+		VALUES ($1, $2, $3, NULLIF($4, ''))
+		ON CONFLICT (user_id, actor_iri) DO UPDATE SET inbox = EXCLUDED.inbox, shared_inbox = EXCLUDED.shared_inbox
+	`, userID, follower.ActorIRI, follower.Inbox, follower.SharedInbox)
+	return err
+}
+
+// RemoveFollower removes a remote follower; removing one that does not
+// follow does nothing.
+func (r *ActivityPubRepository) RemoveFollower(ctx context.Context, userID uuid.UUID, actorIRI string) error {
+	_, err := r.db.ExecContext(ctx, `DELETE FROM activitypub_followers WHERE user_id = $1 AND actor_iri = $2`, userID, actorIRI)
+	return err
+}
+
+// ListFollowers returns the user's remote followers, oldest first.
+func (r *ActivityPubRepository) ListFollowers(ctx context.Context, userID uuid.UUID) ([]ActivityPubFollower, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT actor_iri, inbox, COALESCE(shared_inbox, ''), created_at
+		FROM activitypub_followers
+		WHERE user_id = $1
+		ORDER BY created_at, actor_iri
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var followers []ActivityPubFollower
+	for rows.Next() {
+		var f ActivityPubFollower
+		if err := rows.Scan(&f.ActorIRI, &f.Inbox, &f.SharedInbox, &f.CreatedAt); err != nil {
+			return nil, err
+		}
+		followers = append(followers, f)
+	}
+	return followers, rows.Err()
+}
+
+// ListPosts returns the user's latest posts, newest first.
+func (r *ActivityPubRepository) ListPosts(ctx context.Context, userID uuid.UUID, limit int) ([]ActivityPubPost, int, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, user_id, kind, content, COALESCE(playlist_id, 0), published_at, COUNT(*) OVER()
+		FROM activitypub_posts
+		WHERE user_id = $1
+		ORDER BY id DESC
+		LIMIT $2
+	`, userID, limit)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+	posts := []ActivityPubPost{}
+	var total int
+	for rows.Next() {
+		var p ActivityPubPost
+		if err := rows.Scan(&p.ID, &p.UserID, &p.Kind, &p.Content, &p.PlaylistID, &p.PublishedAt, &total); err != nil {
+			return nil, 0, err
+		}
+		posts = append(posts, p)
+	}
+	return posts, total, rows.Err()
+}
+
+// GetPost returns one of the user's posts.
+func (r *ActivityPubRepository) GetPost(ctx context.Context, userID uuid.UUID, id int64) (*ActivityPubPost, error) {
+	var p ActivityPubPost
+	err := r.db.QueryRowContext(ctx, `
+		SELECT id, user_id, kind, content, COALESCE(playlist_id, 0), published_at
+		FROM activitypub_posts
+		WHERE user_id = $1 AND id = $2
+	`, userID, id).Scan(&p.ID, &p.UserID, &p.Kind, &p.Content, &p.PlaylistID, &p.PublishedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrActivityPubPostNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &p, nil
+}
+
+// Changes returns up to limit of the user's favorites and playlist events
+// after the event with ID after, oldest first. Events of transactions older
+// than one still running are held back, as EventRepository.ListSince does.
+func (r *ActivityPubRepository) Changes(ctx context.Context, userID uuid.UUID, after int64, limit int) ([]ActivityPubChange, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT e.id, e.action, COALESCE(t.title, ''), COALESCE(t.artist, ''),
+			COALESCE(p.id, 0), COALESCE(p.name, ''), COALESCE(p.is_public, FALSE)
+		FROM events e
+		LEFT JOIN tracks t ON e.entity_type = 'track' AND t.id = e.entity_id
+		LEFT JOIN playlists p ON e.entity_type = 'playlist' AND p.id = e.entity_id
+		WHERE e.user_id = $1 AND e.id > $2
+			AND e.tx_id < pg_snapshot_xmin(pg_current_snapshot())
+			AND e.action IN ('favorited', 'playlist_created', 'playlist_updated', 'playlist_tracks_added')
+		ORDER BY e.id
+		LIMIT $3
+	`, userID, after, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var changes []ActivityPubChange
+	for rows.Next() {
+		var c ActivityPubChange
+		if err := rows.Scan(&c.EventID, &c.Action, &c.TrackTitle, &c.TrackArtist, &c.PlaylistID, &c.PlaylistName, &c.PlaylistPublic); err != nil {
+			return nil, err
+		}
+		changes = append(changes, c)
+	}
+	return changes, rows.Err()
+}
+
+// LatestPlay returns the user's newest play after the play with ID after,
+// or nil when there is none.
+func (r *ActivityPubRepository) LatestPlay(ctx context.Context, userID uuid.UUID, after int64) (*ActivityPubPlay, error) {
+	var p ActivityPubPlay
+	err := r.db.QueryRowContext(ctx, `
+		SELECT pe.id, t.title, t.artist
+		FROM play_events pe
+		JOIN tracks t ON t.id = pe.track_id
+		WHERE pe.user_id = $1 AND pe.id > $2
+		ORDER BY pe.id DESC
+		LIMIT 1
+	`, userID, after).Scan(&p.ID, &p.TrackTitle, &p.TrackArtist)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &p, nil
+}
+
+// Publish stores posts and moves the actor's read positions past what they
+// were made from, in one transaction. A playlist post is dropped when the
+// playlist was published before. It returns the posts it stored.
+func (r *ActivityPubRepository) Publish(ctx context.Context, userID uuid.UUID, posts []ActivityPubPost, lastEventID, lastPlayID int64) ([]ActivityPubPost, error) {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer tx.Rollback()
+
+	var stored []ActivityPubPost
+	for _, post := range posts {
+		err := tx.QueryRowContext(ctx, `
+			INSERT INTO activitypub_posts (user_id, kind, content, playlist_id)
+			SELECT $1, $2, $3, NULLIF($4, 0)
+			WHERE $4 = 0 OR NOT EXISTS (SELECT 1 FROM activitypub_posts WHERE user_id = $1 AND playlist_id = $4)
+			RETURNING id, published_at
+		`, userID, post.Kind, post.Content, post.PlaylistID).Scan(&post.ID, &post.PublishedAt)
+		if errors.Is(err, sql.ErrNoRows) {
+			continue
+		}
+		if err != nil {
+			return nil, err
+		}
+		post.UserID = userID
+		stored = append(stored, post)
+	}
+	if _, err := tx.ExecContext(ctx, `
+		UPDATE activitypub_actors
+		SET last_event_id = GREATEST(last_event_id, $2), last_play_id = GREATEST(last_play_id, $3)
+		WHERE user_id = $1
+	`, userID, lastEventID, lastPlayID); err != nil {
+		return nil, err
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, err
+	}
+	return stored, nil
+}
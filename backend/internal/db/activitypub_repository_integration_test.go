@@ -0,0 +1,89 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+// TestActivityPubAgainstPostgres covers what an actor publishes from: it
+// starts after the user's existing events and plays, sees favorites and
+// playlist changes after that, and posts each playlist once.
+func TestActivityPubAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	playlists := NewPlaylistRepository(database)
+	plays := NewPlayEventRepository(database)
+	repo := NewActivityPubRepository(database)
+
+	user := seedPlayUser(t, database, "fedi@example.test")
+	other := seedPlayUser(t, database, "other@example.test")
+	before := seedPlayTrack(t, trackRepo, ctx, "Artist", "Before")
+	loved := seedPlayTrack(t, trackRepo, ctx, "Artist", "Loved")
+	if err := library.AddFavorite(ctx, user, before, "", ""); err != nil {
+		t.Fatalf("AddFavorite: %v", err)
+	}
+	if err := plays.RecordPlay(ctx, user, before, "", ""); err != nil {
+		t.Fatalf("RecordPlay: %v", err)
+	}
+
+	actor := &ActivityPubActor{UserID: user, Handle: "fedi", PublicKeyPEM: "public", PrivateKeyPEM: "private", PublishLoved: true}
+	if err := repo.Put(ctx, actor); err != nil {
+		t.Fatalf("Put: %v", err)
+	}
+	if actor.LastEventID == 0 || actor.LastPlayID == 0 {
+		t.Fatalf("new actor starts at event %d and play %d; want the user's latest", actor.LastEventID, actor.LastPlayID)
+	}
+	if err := repo.Put(ctx, &ActivityPubActor{UserID: other, Handle: "fedi"}); !errors.Is(err, ErrActivityPubHandleTaken) {
+		t.Fatalf("Put with a taken handle = %v; want ErrActivityPubHandleTaken", err)
+	}
+
+	if err := library.AddFavorite(ctx, user, loved, "", ""); err != nil {
+		t.Fatalf("AddFavorite: %v", err)
+	}
+	playlist := &Playlist{UserID: user, Name: "Shared", IsPublic: true}
+	if err := playlists.Create(ctx, playlist); err != nil {
+		t.Fatalf("Create playlist: %v", err)
+	}
+	var changes []ActivityPubChange
+	for deadline := time.Now().Add(5 * time.Second); len(changes) < 2 && time.Now().Before(deadline); time.Sleep(50 * time.Millisecond) {
+		var err error
+		if changes, err = repo.Changes(ctx, user, actor.LastEventID, 100); err != nil {
+			t.Fatalf("Changes: %v", err)
+		}
+	}
+	if len(changes) != 2 || changes[0].TrackTitle != "Loved" || changes[1].PlaylistID != playlist.ID || !changes[1].PlaylistPublic {
+		t.Fatalf("Changes = %+v; want the new favorite and playlist", changes)
+	}
+	if play, err := repo.LatestPlay(ctx, user, actor.LastPlayID); err != nil || play != nil {
+		t.Fatalf("LatestPlay = %+v, %v; want none after the cursor", play, err)
+	}
+
+	post := ActivityPubPost{Kind: ActivityPubPostPlaylist, Content: "<p>Shared</p>", PlaylistID: playlist.ID}
+	stored, err := repo.Publish(ctx, user, []ActivityPubPost{post}, changes[1].EventID, actor.LastPlayID)
+	if err != nil || len(stored) != 1 {
+		t.Fatalf("Publish = %+v, %v", stored, err)
+	}
+	if stored, err := repo.Publish(ctx, user, []ActivityPubPost{post}, changes[1].EventID, actor.LastPlayID); err != nil || len(stored) != 0 {
+		t.Fatalf("Publish of a playlist posted before = %+v, %v; want nothing stored", stored, err)
+	}
+	if got, err := repo.GetByUser(ctx, user); err != nil || got.LastEventID != changes[1].EventID {
+		t.Fatalf("GetByUser = %+v, %v; want the cursor moved", got, err)
+	}
+	if posts, total, err := repo.ListPosts(ctx, user, 20); err != nil || total != 1 || posts[0].PlaylistID != playlist.ID {
+		t.Fatalf("ListPosts = %+v, %d, %v", posts, total, err)
+	}
+
+	if err := repo.AddFollower(ctx, user, ActivityPubFollower{ActorIRI: "https://remote.example/users/alice", Inbox: "https://remote.example/users/alice/inbox"}); err != nil {
+		t.Fatalf("AddFollower: %v", err)
+	}
+	if err := repo.Delete(ctx, user); err != nil {
+		t.Fatalf("Delete: %v", err)
+	}
+	if followers, err := repo.ListFollowers(ctx, user); err != nil || len(followers) != 0 {
+		t.Fatalf("ListFollowers after Delete = %+v, %v", followers, err)
+	}
+}
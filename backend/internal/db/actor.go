@@ -0,0 +1,65 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+
+	"github.com/google/uuid"
+)
+
+type actorKey struct{}
+
+// WithActor returns a context whose writes are made on behalf of userID. The
+// events triggers record it as each change's actor; writes without one are
+// recorded as the server's.
+func WithActor(ctx context.Context, userID uuid.UUID) context.Context {
+	return context.WithValue(ctx, actorKey{}, userID)
+}
+
+func actorFrom(ctx context.Context) (uuid.UUID, bool) {
+	userID, ok := ctx.Value(actorKey{}).(uuid.UUID)
+	return userID, ok
+}
+
+// setActor names the context's actor in tx's omp.actor setting, which lasts
+// until tx ends.
+func setActor(ctx context.Context, tx *sql.Tx) error {
+	userID, ok := actorFrom(ctx)
+	if !ok {
+		return nil
+	}
+	_, err := tx.ExecContext(ctx, `SELECT set_config('omp.actor', $1, true)`, userID.String())
+	return err
+}
+
+// ExecContext shadows the embedded *sql.DB so repository writes name the
+// context's actor (see WithActor). With one set, the statement runs in a
+// transaction of its own, as omp.actor only outlives a statement inside one.
+func (db *DB) ExecContext(ctx context.Context, query string, args ...any) (sql.Result, error) {
+	if _, ok := actorFrom(ctx); !ok {
+		return db.DB.ExecContext(ctx, query, args...)
+	}
+	tx, err := db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer func() { _ = tx.Rollback() }()
+	result, err := tx.ExecContext(ctx, query, args...)
+	if err != nil {
+		return nil, err
+	}
+	return result, tx.Commit()
+}
+
+// BeginTx starts a transaction on the pool with the context's actor set.
+func (db *DB) BeginTx(ctx context.Context, opts *sql.TxOptions) (*sql.Tx, error) {
+	tx, err := db.DB.BeginTx(ctx, opts)
+	if err != nil {
+		return nil, err
+	}
+	if err := setActor(ctx, tx); err != nil {
+		_ = tx.Rollback()
+		return nil, err
+	}
+	return tx, nil
+}
@@ -0,0 +1,210 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// Playlists and library albums cache their track count, total duration, and
+// total size, so listings read one row each instead of aggregating tracks.
+// Repository methods that change what a total counts refresh it in the same
+// transaction, after locking the rows the refresh rewrites so that concurrent
+// refreshes see each other's work. RepairAggregates fixes totals that drifted
+// anyway, such as after a crash between a track update and its refresh.
+
+// playlistTotalsQuery computes playlist totals from their tracks. Callers add
+// a condition on pl and GROUP BY pl.id.
+const playlistTotalsQuery = `
+	SELECT pl.id, COUNT(t.id) AS track_count, COALESCE(SUM(t.duration_ms), 0) AS duration_ms,
+		   COALESCE(SUM(t.file_size_bytes), 0) AS size_bytes
+	FROM playlists pl
+	LEFT JOIN playlist_tracks pt ON pt.playlist_id = pl.id
+	LEFT JOIN tracks t ON t.id = pt.track_id`
+
+// libraryAlbumTotalsQuery computes library album totals: library tracks
+// grouped by MusicBrainz artist, album name, and release, as artist details
+// list them. Callers add conditions on ul and t and the GROUP BY.
+const libraryAlbumTotalsQuery = `
+	SELECT ul.user_id, t.mb_artist_id, t.album, t.mb_release_id, COUNT(*) AS track_count,
+		   COALESCE(SUM(t.duration_ms), 0) AS duration_ms, COALESCE(SUM(t.file_size_bytes), 0) AS size_bytes
+	FROM user_library ul
+	JOIN tracks t ON t.id = ul.track_id
+	WHERE t.mb_artist_id IS NOT NULL AND COALESCE(t.album, '') <> ''`
+
+const libraryAlbumTotalsGroupBy = ` GROUP BY ul.user_id, t.mb_artist_id, t.album, t.mb_release_id`
+
+// RefreshPlaylistAggregates recomputes the cached totals of the playlists
+// inside tx. Writers outside this package that add or remove playlist tracks
+// call it before committing.
+func RefreshPlaylistAggregates(ctx context.Context, tx *sql.Tx, playlistIDs ...int64) error {
+	if len(playlistIDs) == 0 {
+		return nil
+	}
+	ids := pq.Array(playlistIDs)
+	if _, err := tx.ExecContext(ctx, `SELECT 1 FROM playlists WHERE id = ANY($1) ORDER BY id FOR UPDATE`, ids); err != nil {
+		return err
+	}
+	_, err := tx.ExecContext(ctx, `
+		UPDATE playlists p
+		SET track_count = totals.track_count, duration_ms = totals.duration_ms, size_bytes = totals.size_bytes
+		FROM (`+playlistTotalsQuery+` WHERE pl.id = ANY($1) GROUP BY pl.id) totals
+		WHERE p.id = totals.id
+		  AND (p.track_count, p.duration_ms, p.size_bytes) IS DISTINCT FROM (totals.track_count, totals.duration_ms, totals.size_bytes)
+	`, ids)
+	return err
+}
+
+// refreshLibraryAlbums rebuilds cached library albums inside tx. scope selects
+// (user_id, mb_artist_id) pairs to rebuild, with args as its parameters; a
+// NULL artist rebuilds all of that user's albums.
+func refreshLibraryAlbums(ctx context.Context, tx *sql.Tx, scope string, args ...any) error {
+	scope = `SELECT DISTINCT user_id, mb_artist_id FROM (` + scope + `) scope`
+	if _, err := tx.ExecContext(ctx, `
+		SELECT 1 FROM users WHERE id IN (SELECT user_id FROM (`+scope+`) s) ORDER BY id FOR NO KEY UPDATE
+	`, args...); err != nil {
+		return err
+	}
+	if _, err := tx.ExecContext(ctx, `
+		DELETE FROM library_albums la
+		USING (`+scope+`) s
+		WHERE la.user_id = s.user_id AND (s.mb_artist_id IS NULL OR la.mb_artist_id = s.mb_artist_id)
+	`, args...); err != nil {
+		return err
+	}
+	_, err := tx.ExecContext(ctx, `
+		INSERT INTO library_albums (user_id, mb_artist_id, album, mb_release_id, track_count, duration_ms, size_bytes)
+		`+libraryAlbumTotalsQuery+`
+		  AND EXISTS (
+			SELECT 1 FROM (`+scope+`) s
+			WHERE s.user_id = ul.user_id AND (s.mb_artist_id IS NULL OR s.mb_artist_id = t.mb_artist_id)
+		  )
+		`+libraryAlbumTotalsGroupBy,
+		args...)
+	return err
+}
+
+// refreshLibraryArtistAlbums rebuilds the user's cached albums by the track's
+// artist, after the track joined or left the user's library.
+func refreshLibraryArtistAlbums(ctx context.Context, tx *sql.Tx, userID uuid.UUID, trackID int64) error {
+	return refreshLibraryAlbums(ctx, tx,
+		`SELECT $1::uuid AS user_id, mb_artist_id FROM tracks WHERE id = $2 AND mb_artist_id IS NOT NULL`,
+		userID, trackID)
+}
+
+// refreshTrackAggregates refreshes every total the track counts towards: its
+// playlists and the library albums of the users keeping it. When the track's
+// artist or album may have changed, those users' albums are rebuilt in full,
+// since the track may have left an album by another artist.
+func refreshTrackAggregates(ctx context.Context, database *DB, trackID int64, regrouped bool) error {
+	tx, err := database.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	var playlistIDs pq.Int64Array
+	if err := tx.QueryRowContext(ctx,
+		`SELECT ARRAY(SELECT playlist_id FROM playlist_tracks WHERE track_id = $1)`, trackID,
+	).Scan(&playlistIDs); err != nil {
+		return err
+	}
+	if err := RefreshPlaylistAggregates(ctx, tx, playlistIDs...); err != nil {
+		return err
+	}
+
+	scope := `SELECT ul.user_id, t.mb_artist_id FROM user_library ul JOIN tracks t ON t.id = ul.track_id
+		WHERE ul.track_id = $1 AND t.mb_artist_id IS NOT NULL`
+	if regrouped {
+		scope = `SELECT user_id, NULL::uuid AS mb_artist_id FROM user_library WHERE track_id = $1`
+	}
+	if err := refreshLibraryAlbums(ctx, tx, scope, trackID); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+// AggregateRepair counts what RepairAggregates fixed: playlists, and users
+// whose library albums were rebuilt.
+type AggregateRepair struct {
+	Playlists     int
+	LibraryOwners int
+}
+
+// RepairAggregates recomputes every cached total and rewrites the ones that
+// drifted from the tracks they count: playlists by playlist and library
+// albums by user, each in its own transaction.
+func RepairAggregates(ctx context.Context, database *DB) (AggregateRepair, error) {
+	var repair AggregateRepair
+
+	var playlistIDs pq.Int64Array
+	if err := database.QueryRowContext(ctx, `
+		SELECT ARRAY(
+			SELECT p.id
+			FROM playlists p
+			JOIN (`+playlistTotalsQuery+` GROUP BY pl.id) totals ON totals.id = p.id
+			WHERE (p.track_count, p.duration_ms, p.size_bytes) IS DISTINCT FROM (totals.track_count, totals.duration_ms, totals.size_bytes)
+		)
+	`).Scan(&playlistIDs); err != nil {
+		return repair, err
+	}
+	for _, playlistID := range playlistIDs {
+		if err := inTx(ctx, database, func(tx *sql.Tx) error {
+			return RefreshPlaylistAggregates(ctx, tx, playlistID)
+		}); err != nil {
+			return repair, err
+		}
+		repair.Playlists++
+	}
+
+	var userIDs []uuid.UUID
+	rows, err := database.QueryContext(ctx, `
+		SELECT DISTINCT user_id FROM (
+			(`+libraryAlbumTotalsQuery+libraryAlbumTotalsGroupBy+`
+			 EXCEPT
+			 SELECT user_id, mb_artist_id, album, mb_release_id, track_count, duration_ms, size_bytes FROM library_albums)
+			UNION ALL
+			(SELECT user_id, mb_artist_id, album, mb_release_id, track_count, duration_ms, size_bytes FROM library_albums
+			 EXCEPT
+			 `+libraryAlbumTotalsQuery+libraryAlbumTotalsGroupBy+`)
+		) drifted
+	`)
+	if err != nil {
+		return repair, err
+	}
+	for rows.Next() {
+		var userID uuid.UUID
+		if err := rows.Scan(&userID); err != nil {
+			rows.Close()
+			return repair, err
+		}
+		userIDs = append(userIDs, userID)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return repair, err
+	}
+	for _, userID := range userIDs {
+		if err := inTx(ctx, database, func(tx *sql.Tx) error {
+			return refreshLibraryAlbums(ctx, tx, `SELECT $1::uuid AS user_id, NULL::uuid AS mb_artist_id`, userID)
+		}); err != nil {
+			return repair, err
+		}
+		repair.LibraryOwners++
+	}
+	return repair, nil
+}
+
+func inTx(ctx context.Context, database *DB, fn func(tx *sql.Tx) error) error {
+	tx, err := database.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+	if err := fn(tx); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
@@ -0,0 +1,109 @@
+package db
+
+import (
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestCachedAggregatesFollowWritesAndRepairAgainstPostgres(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	playlists := NewPlaylistRepository(database)
+	library := NewLibraryRepository(database)
+
+	userID := seedPlaylistUser(t, database, "totals@example.test")
+	a := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "a")
+	b := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "b")
+	artistID := uuid.New()
+	for _, id := range []int64{a, b} {
+		if err := trackRepo.UpdateMBMatch(ctx, id, &MBMatchUpdate{MBArtistID: &artistID, ApplyMBIdentity: true}); err != nil {
+			t.Fatalf("set artist of %d: %v", id, err)
+		}
+	}
+
+	pl := &Playlist{UserID: userID, Name: "Totals"}
+	if err := playlists.Create(ctx, pl); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	if _, err := playlists.AddTracks(ctx, pl.ID, []int64{a, b}); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+	if attached, err := trackRepo.AttachStorage(ctx, a, "tracks/a.mp3", 4000); err != nil || !attached {
+		t.Fatalf("attach storage = %v, %v", attached, err)
+	}
+	for _, id := range []int64{a, b} {
+		if _, err := library.AddTrackToLibrary(ctx, userID, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+
+	assertPlaylistTotals := func(wantTracks int, wantDuration, wantSize int64) {
+		t.Helper()
+		listed, _, err := playlists.GetByUserID(ctx, userID, ListPlaylistsParams{})
+		if err != nil {
+			t.Fatalf("list playlists: %v", err)
+		}
+		if len(listed) != 1 {
+			t.Fatalf("listed %d playlists, want 1", len(listed))
+		}
+		got := listed[0]
+		if got.TrackCount != wantTracks || got.DurationMs != wantDuration || got.SizeBytes != wantSize {
+			t.Fatalf("playlist totals = %d tracks, %d ms, %d bytes; want %d, %d, %d",
+				got.TrackCount, got.DurationMs, got.SizeBytes, wantTracks, wantDuration, wantSize)
+		}
+	}
+	albumTracks := func() map[string]int {
+		t.Helper()
+		albums, err := library.ArtistLibraryAlbums(ctx, userID, artistID)
+		if err != nil {
+			t.Fatalf("library albums: %v", err)
+		}
+		counts := make(map[string]int, len(albums))
+		for _, album := range albums {
+			counts[album.Album] = album.Tracks
+		}
+		return counts
+	}
+
+	assertPlaylistTotals(2, 400000, 4000)
+	if got := albumTracks(); len(got) != 2 || got["a Album"] != 1 || got["b Album"] != 1 {
+		t.Fatalf("albums = %v, want one track each in a Album and b Album", got)
+	}
+
+	if err := trackRepo.UpdateMetadata(ctx, b, &MetadataUpdate{Album: "a Album", DurationMs: 100000}, nil); err != nil {
+		t.Fatalf("update metadata: %v", err)
+	}
+	assertPlaylistTotals(2, 300000, 4000)
+	if got := albumTracks(); len(got) != 1 || got["a Album"] != 2 {
+		t.Fatalf("albums after regrouping = %v, want two tracks in a Album", got)
+	}
+
+	if err := library.RemoveTrackFromLibrary(ctx, userID, a); err != nil {
+		t.Fatalf("remove from library: %v", err)
+	}
+	if got := albumTracks(); got["a Album"] != 1 {
+		t.Fatalf("albums after removal = %v, want one track in a Album", got)
+	}
+
+	if _, err := database.Exec(`UPDATE playlists SET track_count = 0, size_bytes = 0`); err != nil {
+		t.Fatalf("drift playlists: %v", err)
+	}
+	if _, err := database.Exec(`DELETE FROM library_albums`); err != nil {
+		t.Fatalf("drift library albums: %v", err)
+	}
+	repair, err := RepairAggregates(ctx, database)
+	if err != nil {
+		t.Fatalf("repair: %v", err)
+	}
+	if repair.Playlists != 1 || repair.LibraryOwners != 1 {
+		t.Fatalf("repair = %+v, want one playlist and one library owner", repair)
+	}
+	assertPlaylistTotals(2, 300000, 4000)
+	if got := albumTracks(); len(got) != 1 || got["a Album"] != 1 {
+		t.Fatalf("albums after repair = %v, want one track in a Album", got)
+	}
+	if repair, err := RepairAggregates(ctx, database); err != nil || repair != (AggregateRepair{}) {
+		t.Fatalf("second repair = %+v, %v; want nothing to fix", repair, err)
+	}
+}
@@ -0,0 +1,98 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+var ErrAPIKeyNotFound = errors.New("api key not found")
+
+// APIKey is a long-lived credential a user issues to a browser extension or
+// bookmarklet. Only the key's hash and its first characters are stored.
+type APIKey struct {
+	ID         uuid.UUID
+	UserID     uuid.UUID
+	Name       string
+	KeyPrefix  string
+	KeyHash    string
+	CreatedAt  time.Time
+	LastUsedAt sql.NullTime
+}
+
+type APIKeyRepository struct {
+	db *DB
+}
+
+func NewAPIKeyRepository(db *DB) *APIKeyRepository {
+	return &APIKeyRepository{db: db}
+}
+
+// List returns the user's API keys, newest first.
+func (r *APIKeyRepository) List(ctx context.Context, userID uuid.UUID) ([]APIKey, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, user_id, name, key_prefix, key_hash, created_at, last_used_at
+		FROM api_keys
+		WHERE user_id = $1
+		ORDER BY created_at DESC, id
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	keys := []APIKey{}
+	for rows.Next() {
+		var k APIKey
+		if err := rows.Scan(&k.ID, &k.UserID, &k.Name, &k.KeyPrefix, &k.KeyHash, &k.CreatedAt, &k.LastUsedAt); err != nil {
+			return nil, err
+		}
+		keys = append(keys, k)
+	}
+	return keys, rows.Err()
+}
+
+// Create stores a new key.
+func (r *APIKeyRepository) Create(ctx context.Context, key *APIKey) error {
+	return r.db.QueryRowContext(ctx, `
+		INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash)
+		VALUES ($1, $2, $3, $4, $5)
+		RETURNING created_at
+	`, key.ID, key.UserID, key.Name, key.KeyPrefix, key.KeyHash).Scan(&key.CreatedAt)
+}
+
+// Authenticate returns the key with the given hash and records its use.
+func (r *APIKeyRepository) Authenticate(ctx context.Context, keyHash string) (*APIKey, error) {
+	var k APIKey
+	err := r.db.QueryRowContext(ctx, `
+		UPDATE api_keys SET last_used_at = NOW()
+		WHERE key_hash = $1
+		RETURNING id, user_id, name, key_prefix, key_hash, created_at, last_used_at
+	`, keyHash).Scan(&k.ID, &k.UserID, &k.Name, &k.KeyPrefix, &k.KeyHash, &k.CreatedAt, &k.LastUsedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrAPIKeyNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &k, nil
+}
+
+// Delete revokes one of the user's keys.
+func (r *APIKeyRepository) Delete(ctx context.Context, userID, id uuid.UUID) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM api_keys WHERE id = $1 AND user_id = $2`, id, userID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrAPIKeyNotFound
+	}
+	return nil
+}
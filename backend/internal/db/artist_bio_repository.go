@@ -0,0 +1,79 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"fmt"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+var ErrArtistBioNotFound = errors.New("artist bio not found")
+
+// ArtistBioSourceWikipedia marks a bio read from a Wikipedia article.
+const ArtistBioSourceWikipedia = "wikipedia"
+
+// ArtistBio is a cached artist biography with the fields its attribution
+// needs. Language is the language it was requested in; ContentLanguage is
+// the article's, which differs when that language had no article. An empty
+// Extract records that no article was found.
+type ArtistBio struct {
+	MBArtistID      uuid.UUID
+	Language        string
+	ContentLanguage string
+	Extract         string
+	Source          string
+	Title           string
+	URL             string
+	License         string
+	FetchedAt       time.Time
+}
+
+type ArtistBioRepository struct {
+	db *DB
+}
+
+func NewArtistBioRepository(db *DB) *ArtistBioRepository {
+	return &ArtistBioRepository{db: db}
+}
+
+// Get returns the bio stored for the artist in language.
+func (r *ArtistBioRepository) Get(ctx context.Context, artistID uuid.UUID, language string) (*ArtistBio, error) {
+	bio := ArtistBio{MBArtistID: artistID, Language: language}
+	err := r.db.QueryRowContext(ctx, `
+		SELECT content_language, extract, source, title, url, license, fetched_at
+		FROM artist_bios
+		WHERE mb_artist_id = $1 AND language = $2
+	`, artistID, language).Scan(&bio.ContentLanguage, &bio.Extract, &bio.Source, &bio.Title, &bio.URL, &bio.License, &bio.FetchedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrArtistBioNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &bio, nil
+}
+
+// Upsert stores the bio, replacing an earlier one in the same language, and
+// sets FetchedAt.
+func (r *ArtistBioRepository) Upsert(ctx context.Context, bio *ArtistBio) error {
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO artist_bios (mb_artist_id, language, content_language, extract, source, title, url, license)
+		VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+		ON CONFLICT (mb_artist_id, language) DO UPDATE SET
+			content_language = EXCLUDED.content_language,
+			extract = EXCLUDED.extract,
+			source = EXCLUDED.source,
+			title = EXCLUDED.title,
+			url = EXCLUDED.url,
+			license = EXCLUDED.license,
+			fetched_at = NOW()
+		RETURNING fetched_at
+	`, bio.MBArtistID, bio.Language, bio.ContentLanguage, bio.Extract, bio.Source, bio.Title, bio.URL, bio.License).Scan(&bio.FetchedAt)
+	if err != nil {
+		return fmt.Errorf("save artist bio: %w", err)
+	}
+	return nil
+}
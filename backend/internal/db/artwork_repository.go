@@ -0,0 +1,293 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"time"
+
+	"github.com/lib/pq"
+)
+
+var (
+	ErrArtworkNotFound  = errors.New("artwork not found")
+	ErrArtworkNotLinked = errors.New("track artwork not linked")
+)
+
+// Track artwork sources, in order of precedence: art embedded in the track's
+// own file, a sidecar image (cover.jpg and the like) beside the album's files,
+// art embedded consistently across its album, then art fetched from the cover
+// art URL.
+const (
+	ArtworkSourceEmbedded = "embedded"
+	ArtworkSourceFolder   = "folder"
+	ArtworkSourceAlbum    = "album"
+	ArtworkSourceRemote   = "remote"
+)
+
+// ArtworkVariant is one stored rendition of an artwork image.
+type ArtworkVariant struct {
+	Name       string `json:"name"`
+	StorageKey string `json:"storageKey"`
+	Width      int    `json:"width"`
+	Height     int    `json:"height"`
+	SizeBytes  int64  `json:"sizeBytes"`
+}
+
+// Artwork is a processed cover image. Remote artwork is keyed by source URL
+// and local artwork (embedded or sidecar), which has no SourceURL, by content
+// hash, so every track sharing an image shares its renditions, blurhash, and
+// palette.
+type Artwork struct {
+	ID            int64
+	SourceURL     string
+	ContentSHA256 string
+	Width         int
+	Height        int
+	Variants      []ArtworkVariant
+	Blurhash      string
+	DominantColor string
+	Palette       []string
+	CreatedAt     time.Time
+}
+
+type ArtworkRepository struct {
+	db *DB
+}
+
+func NewArtworkRepository(db *DB) *ArtworkRepository {
+	return &ArtworkRepository{db: db}
+}
+
+const artworkColumns = `a.id, COALESCE(a.source_url, ''), COALESCE(a.content_sha256, ''), a.width, a.height, a.variants, a.blurhash, a.dominant_color, a.palette, a.created_at`
+
+// artworkAlbumCTE selects the tracks on the same album as track $1, matched
+// case-insensitively on album and artist. Tracks without an album have none.
+const artworkAlbumCTE = `
+	src AS (
+		SELECT lower(album) AS album, lower(COALESCE(artist, '')) AS artist
+		FROM tracks
+		WHERE id = $1 AND NULLIF(album, '') IS NOT NULL
+	),
+	siblings AS (
+		SELECT t.id, t.artwork_id, t.artwork_source
+		FROM tracks t
+		JOIN src ON lower(t.album) = src.album AND lower(COALESCE(t.artist, '')) = src.artist
+	)`
+
+func scanArtwork(row rowScanner) (*Artwork, error) {
+	var (
+		artwork  Artwork
+		variants []byte
+	)
+	if err := row.Scan(
+		&artwork.ID, &artwork.SourceURL, &artwork.ContentSHA256, &artwork.Width, &artwork.Height, &variants,
+		&artwork.Blurhash, &artwork.DominantColor, pq.Array(&artwork.Palette), &artwork.CreatedAt,
+	); err != nil {
+		return nil, err
+	}
+	if err := json.Unmarshal(variants, &artwork.Variants); err != nil {
+		return nil, fmt.Errorf("decode artwork variants: %w", err)
+	}
+	return &artwork, nil
+}
+
+func (r *ArtworkRepository) findOne(ctx context.Context, query string, args ...any) (*Artwork, error) {
+	artwork, err := scanArtwork(r.db.QueryRowContext(ctx, query, args...))
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrArtworkNotFound
+	}
+	return artwork, err
+}
+
+// FindBySourceURL returns the processed remote artwork for sourceURL.
+func (r *ArtworkRepository) FindBySourceURL(ctx context.Context, sourceURL string) (*Artwork, error) {
+	return r.findOne(ctx, `
+		SELECT `+artworkColumns+`
+		FROM artwork_images a
+		WHERE a.source_url = $1
+	`, sourceURL)
+}
+
+// FindEmbedded returns the processed local artwork, embedded or sidecar, with
+// the given content hash.
+func (r *ArtworkRepository) FindEmbedded(ctx context.Context, contentSHA256 string) (*Artwork, error) {
+	return r.findOne(ctx, `
+		SELECT `+artworkColumns+`
+		FROM artwork_images a
+		WHERE a.content_sha256 = $1 AND a.source_url IS NULL
+	`, contentSHA256)
+}
+
+// GetForTrack returns the artwork linked to trackID.
+func (r *ArtworkRepository) GetForTrack(ctx context.Context, trackID int64) (*Artwork, error) {
+	return r.findOne(ctx, `
+		SELECT `+artworkColumns+`
+		FROM tracks t
+		JOIN artwork_images a ON a.id = t.artwork_id
+		WHERE t.id = $1
+	`, trackID)
+}
+
+// Upsert stores processed artwork, replacing any earlier processing of the
+// same source URL (or, for embedded artwork, the same content hash), and sets
+// ID and CreatedAt.
+func (r *ArtworkRepository) Upsert(ctx context.Context, artwork *Artwork) error {
+	variants, err := json.Marshal(artwork.Variants)
+	if err != nil {
+		return fmt.Errorf("encode artwork variants: %w", err)
+	}
+	palette := artwork.Palette
+	if palette == nil {
+		palette = []string{}
+	}
+	conflict := `(source_url)`
+	if artwork.SourceURL == "" {
+		conflict = `(content_sha256) WHERE source_url IS NULL`
+	}
+	return r.db.QueryRowContext(ctx, `
+		INSERT INTO artwork_images (source_url, content_sha256, width, height, variants, blurhash, dominant_color, palette)
+		VALUES (NULLIF($1, ''), NULLIF($2, ''), $3, $4, $5, $6, $7, $8)
+		ON CONFLICT `+conflict+` DO UPDATE SET
+			content_sha256 = EXCLUDED.content_sha256,
+			width = EXCLUDED.width,
+			height = EXCLUDED.height,
+			variants = EXCLUDED.variants,
+			blurhash = EXCLUDED.blurhash,
+			dominant_color = EXCLUDED.dominant_color,
+			palette = EXCLUDED.palette
+		RETURNING id, created_at
+	`, artwork.SourceURL, artwork.ContentSHA256, artwork.Width, artwork.Height, variants,
+		artwork.Blurhash, artwork.DominantColor, pq.Array(palette),
+	).Scan(&artwork.ID, &artwork.CreatedAt)
+}
+
+// LinkTrack points trackID at remote artwork. The link is only made while the
+// track's cover art URL still matches sourceURL and the track has no local
+// artwork; otherwise ErrArtworkNotLinked is returned.
+func (r *ArtworkRepository) LinkTrack(ctx context.Context, trackID, artworkID int64, sourceURL string) error {
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE tracks SET artwork_id = $2, artwork_source = '`+ArtworkSourceRemote+`'
+		WHERE id = $1 AND cover_art_url = $3
+		  AND (artwork_source IS NULL OR artwork_source = '`+ArtworkSourceRemote+`')
+	`, trackID, artworkID, sourceURL)
+	if err != nil {
+		return fmt.Errorf("link track artwork: %w", err)
+	}
+	rows, err := result.RowsAffected()
+	if err != nil {
+		return fmt.Errorf("link track artwork: %w", err)
+	}
+	if rows == 0 {
+		return ErrArtworkNotLinked
+	}
+	return nil
+}
+
+// LinkEmbedded points trackID at artwork embedded in its own file, then
+// reconciles album-level artwork: while every embedded image on the album is
+// this one, album tracks without embedded art adopt it; once the album's
+// embedded images disagree, album-level links are withdrawn.
+func (r *ArtworkRepository) LinkEmbedded(ctx context.Context, trackID, artworkID int64) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	result, err := tx.ExecContext(ctx, `
+		UPDATE tracks SET artwork_id = $2, artwork_source = '`+ArtworkSourceEmbedded+`'
+		WHERE id = $1
+	`, trackID, artworkID)
+	if err != nil {
+		return fmt.Errorf("link embedded artwork: %w", err)
+	}
+	if rows, err := result.RowsAffected(); err != nil {
+		return fmt.Errorf("link embedded artwork: %w", err)
+	} else if rows == 0 {
+		return ErrTrackNotFound
+	}
+
+	if _, err := tx.ExecContext(ctx, `
+		WITH `+artworkAlbumCTE+`,
+		consistent AS (
+			SELECT NOT EXISTS (
+				SELECT 1 FROM siblings
+				WHERE artwork_source = '`+ArtworkSourceEmbedded+`' AND artwork_id <> $2
+			) AS ok
+		)
+		UPDATE tracks t SET
+			artwork_id = CASE WHEN c.ok THEN $2 ELSE NULL END,
+			artwork_source = CASE WHEN c.ok THEN '`+ArtworkSourceAlbum+`' ELSE NULL END
+		FROM siblings s, consistent c
+		WHERE t.id = s.id AND t.id <> $1
+		  AND (s.artwork_source = '`+ArtworkSourceAlbum+`'
+		       OR (c.ok AND (s.artwork_source IS NULL OR s.artwork_source = '`+ArtworkSourceRemote+`')))
+	`, trackID, artworkID); err != nil {
+		return fmt.Errorf("link album artwork: %w", err)
+	}
+	return tx.Commit()
+}
+
+// LinkFolder points trackID and the rest of its album at a sidecar image
+// found beside the track's file. Tracks with art embedded in their own file
+// keep it. ErrArtworkNotLinked is returned when trackID itself was not linked.
+func (r *ArtworkRepository) LinkFolder(ctx context.Context, trackID, artworkID int64) error {
+	var linked bool
+	err := r.db.QueryRowContext(ctx, `
+		WITH `+artworkAlbumCTE+`,
+		updated AS (
+			UPDATE tracks t SET artwork_id = $2, artwork_source = '`+ArtworkSourceFolder+`'
+			WHERE (t.id = $1 OR t.id IN (SELECT id FROM siblings))
+			  AND (t.artwork_source IS NULL OR t.artwork_source <> '`+ArtworkSourceEmbedded+`')
+			RETURNING t.id
+		)
+		SELECT EXISTS (SELECT 1 FROM updated WHERE id = $1)
+	`, trackID, artworkID).Scan(&linked)
+	if err != nil {
+		return fmt.Errorf("link folder artwork: %w", err)
+	}
+	if !linked {
+		return ErrArtworkNotLinked
+	}
+	return nil
+}
+
+// AdoptAlbumArtwork links trackID to artwork its album already agrees on: a
+// single sidecar image, or failing that a single embedded image. Tracks with
+// their own embedded or sidecar art are left alone. It returns
+// ErrArtworkNotFound when there is nothing to adopt.
+func (r *ArtworkRepository) AdoptAlbumArtwork(ctx context.Context, trackID int64) (*Artwork, error) {
+	var artworkID int64
+	err := r.db.QueryRowContext(ctx, `
+		WITH `+artworkAlbumCTE+`,
+		candidates AS (
+			SELECT artwork_source, MIN(artwork_id) AS artwork_id, COUNT(DISTINCT artwork_id) AS images
+			FROM siblings
+			WHERE artwork_source IN ('`+ArtworkSourceFolder+`', '`+ArtworkSourceEmbedded+`')
+			GROUP BY artwork_source
+		),
+		chosen AS (
+			SELECT artwork_id,
+				CASE WHEN artwork_source = '`+ArtworkSourceFolder+`' THEN '`+ArtworkSourceFolder+`' ELSE '`+ArtworkSourceAlbum+`' END AS artwork_source
+			FROM candidates
+			WHERE images = 1
+			ORDER BY artwork_source = '`+ArtworkSourceFolder+`' DESC
+			LIMIT 1
+		)
+		UPDATE tracks t SET artwork_id = c.artwork_id, artwork_source = c.artwork_source
+		FROM chosen c
+		WHERE t.id = $1
+		  AND (t.artwork_source IS NULL OR t.artwork_source IN ('`+ArtworkSourceAlbum+`', '`+ArtworkSourceRemote+`'))
+		RETURNING t.artwork_id
+	`, trackID).Scan(&artworkID)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrArtworkNotFound
+	}
+	if err != nil {
+		return nil, fmt.Errorf("adopt album artwork: %w", err)
+	}
+	return r.GetForTrack(ctx, trackID)
+}
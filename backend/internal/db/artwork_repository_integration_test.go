@@ -0,0 +1,183 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+func TestArtworkRepositorySharesArtworkAcrossTracks(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewArtworkRepository(database)
+
+	const cover = "https://coverartarchive.org/release/abc/front.jpg"
+	first := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "one")
+	second := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "two")
+	if _, err := database.Exec(`UPDATE tracks SET cover_art_url = $1 WHERE id IN ($2, $3)`, cover, first, second); err != nil {
+		t.Fatalf("set cover art: %v", err)
+	}
+
+	if _, err := repo.FindBySourceURL(ctx, cover); !errors.Is(err, ErrArtworkNotFound) {
+		t.Fatalf("FindBySourceURL before upsert error = %v, want ErrArtworkNotFound", err)
+	}
+	artwork := &Artwork{
+		SourceURL:     cover,
+		Width:         1000,
+		Height:        1000,
+		Variants:      []ArtworkVariant{{Name: "thumb", StorageKey: "artwork/abc/thumb.jpg", Width: 128, Height: 128, SizeBytes: 4000}},
+		Blurhash:      "LEHV6nWB2yk8pyo0adR*.7kCMdnj",
+		DominantColor: "#dc141e",
+		Palette:       []string{"#dc141e", "#0a28c8"},
+	}
+	if err := repo.Upsert(ctx, artwork); err != nil {
+		t.Fatalf("Upsert: %v", err)
+	}
+	for _, trackID := range []int64{first, second} {
+		if err := repo.LinkTrack(ctx, trackID, artwork.ID, cover); err != nil {
+			t.Fatalf("LinkTrack %d: %v", trackID, err)
+		}
+	}
+
+	got, err := repo.GetForTrack(ctx, second)
+	if err != nil {
+		t.Fatalf("GetForTrack: %v", err)
+	}
+	if got.ID != artwork.ID || got.DominantColor != "#dc141e" || len(got.Palette) != 2 ||
+		len(got.Variants) != 1 || got.Variants[0].StorageKey != "artwork/abc/thumb.jpg" {
+		t.Fatalf("artwork = %#v", got)
+	}
+
+	// Reprocessing the same source updates the row in place.
+	artwork.DominantColor = "#0a28c8"
+	if err := repo.Upsert(ctx, artwork); err != nil {
+		t.Fatalf("re-Upsert: %v", err)
+	}
+	if got, err := repo.GetForTrack(ctx, first); err != nil || got.ID != artwork.ID || got.DominantColor != "#0a28c8" {
+		t.Fatalf("after re-upsert = %#v, %v", got, err)
+	}
+
+	if _, err := database.Exec(`UPDATE tracks SET cover_art_url = 'https://example.test/other.jpg' WHERE id = $1`, second); err != nil {
+		t.Fatalf("change cover art: %v", err)
+	}
+	if err := repo.LinkTrack(ctx, second, artwork.ID, cover); !errors.Is(err, ErrArtworkNotLinked) {
+		t.Fatalf("LinkTrack with stale cover error = %v, want ErrArtworkNotLinked", err)
+	}
+}
+
+func TestArtworkRepositoryEmbeddedArtworkCoversAlbum(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewArtworkRepository(database)
+
+	const cover = "https://coverartarchive.org/release/abc/front.jpg"
+	tagged := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "one")
+	untagged := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "two")
+	other := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "three")
+	if _, err := database.Exec(`UPDATE tracks SET album = 'Record', cover_art_url = $1 WHERE id IN ($2, $3, $4)`, cover, tagged, untagged, other); err != nil {
+		t.Fatalf("set album: %v", err)
+	}
+
+	remote := &Artwork{SourceURL: cover, Width: 500, Height: 500, Variants: []ArtworkVariant{}}
+	if err := repo.Upsert(ctx, remote); err != nil {
+		t.Fatalf("Upsert remote: %v", err)
+	}
+	if err := repo.LinkTrack(ctx, untagged, remote.ID, cover); err != nil {
+		t.Fatalf("LinkTrack: %v", err)
+	}
+
+	embedded := &Artwork{ContentSHA256: "aa11", Width: 600, Height: 600, Variants: []ArtworkVariant{}}
+	if err := repo.Upsert(ctx, embedded); err != nil {
+		t.Fatalf("Upsert embedded: %v", err)
+	}
+	if found, err := repo.FindEmbedded(ctx, "aa11"); err != nil || found.ID != embedded.ID || found.SourceURL != "" {
+		t.Fatalf("FindEmbedded = %#v, %v", found, err)
+	}
+	if err := repo.LinkEmbedded(ctx, tagged, embedded.ID); err != nil {
+		t.Fatalf("LinkEmbedded: %v", err)
+	}
+
+	// The album's single embedded image replaces the fetched cover on its
+	// untagged tracks, and fetched covers can no longer override it.
+	for _, trackID := range []int64{untagged, other} {
+		if got, err := repo.GetForTrack(ctx, trackID); err != nil || got.ID != embedded.ID {
+			t.Fatalf("track %d artwork = %#v, %v; want album artwork %d", trackID, got, err, embedded.ID)
+		}
+	}
+	if err := repo.LinkTrack(ctx, untagged, remote.ID, cover); !errors.Is(err, ErrArtworkNotLinked) {
+		t.Fatalf("LinkTrack over album artwork error = %v, want ErrArtworkNotLinked", err)
+	}
+
+	// A second, different embedded image on the album withdraws album links.
+	second := &Artwork{ContentSHA256: "bb22", Width: 600, Height: 600, Variants: []ArtworkVariant{}}
+	if err := repo.Upsert(ctx, second); err != nil {
+		t.Fatalf("Upsert second embedded: %v", err)
+	}
+	if err := repo.LinkEmbedded(ctx, other, second.ID); err != nil {
+		t.Fatalf("LinkEmbedded second: %v", err)
+	}
+	if _, err := repo.GetForTrack(ctx, untagged); !errors.Is(err, ErrArtworkNotFound) {
+		t.Fatalf("untagged track after inconsistent album error = %v, want ErrArtworkNotFound", err)
+	}
+	if _, err := repo.AdoptAlbumArtwork(ctx, untagged); !errors.Is(err, ErrArtworkNotFound) {
+		t.Fatalf("AdoptAlbumArtwork on inconsistent album error = %v, want ErrArtworkNotFound", err)
+	}
+	if err := repo.LinkTrack(ctx, untagged, remote.ID, cover); err != nil {
+		t.Fatalf("LinkTrack after album links withdrawn: %v", err)
+	}
+
+	// Once the album agrees again the untagged track can adopt its art.
+	if err := repo.LinkEmbedded(ctx, other, embedded.ID); err != nil {
+		t.Fatalf("LinkEmbedded matching: %v", err)
+	}
+	if got, err := repo.AdoptAlbumArtwork(ctx, untagged); err != nil || got.ID != embedded.ID {
+		t.Fatalf("AdoptAlbumArtwork = %#v, %v; want %d", got, err, embedded.ID)
+	}
+	if err := repo.LinkEmbedded(ctx, 999999, embedded.ID); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("LinkEmbedded for missing track error = %v, want ErrTrackNotFound", err)
+	}
+}
+
+func TestArtworkRepositoryFolderArtworkCoversAlbum(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewArtworkRepository(database)
+
+	scanned := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "one")
+	tagged := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "two")
+	later := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "three")
+	if _, err := database.Exec(`UPDATE tracks SET album = 'Record' WHERE id IN ($1, $2)`, scanned, tagged); err != nil {
+		t.Fatalf("set album: %v", err)
+	}
+
+	embedded := &Artwork{ContentSHA256: "aa11", Variants: []ArtworkVariant{}}
+	folder := &Artwork{ContentSHA256: "cc33", Variants: []ArtworkVariant{}}
+	for _, artwork := range []*Artwork{embedded, folder} {
+		if err := repo.Upsert(ctx, artwork); err != nil {
+			t.Fatalf("Upsert: %v", err)
+		}
+	}
+	if err := repo.LinkEmbedded(ctx, tagged, embedded.ID); err != nil {
+		t.Fatalf("LinkEmbedded: %v", err)
+	}
+	if err := repo.LinkFolder(ctx, scanned, folder.ID); err != nil {
+		t.Fatalf("LinkFolder: %v", err)
+	}
+	if got, err := repo.GetForTrack(ctx, tagged); err != nil || got.ID != embedded.ID {
+		t.Fatalf("tagged track artwork = %#v, %v; want its embedded art", got, err)
+	}
+	if err := repo.LinkFolder(ctx, tagged, folder.ID); !errors.Is(err, ErrArtworkNotLinked) {
+		t.Fatalf("LinkFolder over embedded art error = %v, want ErrArtworkNotLinked", err)
+	}
+
+	// A track joining the album later prefers the sidecar over embedded art.
+	if _, err := database.Exec(`UPDATE tracks SET album = 'Record' WHERE id = $1`, later); err != nil {
+		t.Fatalf("set album: %v", err)
+	}
+	if got, err := repo.AdoptAlbumArtwork(ctx, later); err != nil || got.ID != folder.ID {
+		t.Fatalf("AdoptAlbumArtwork = %#v, %v; want folder artwork %d", got, err, folder.ID)
+	}
+}
@@ -0,0 +1,66 @@
+package db
+
+import "testing"
+
+func TestBatchLoadersUseOneQueryPerPageAgainstPostgres(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	playlists := NewPlaylistRepository(database)
+	library := NewLibraryRepository(database)
+
+	userID := seedPlaylistUser(t, database, "batch@example.test")
+	var trackIDs []int64
+	for _, title := range []string{"a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"} {
+		trackIDs = append(trackIDs, seedPlaylistTrack(t, trackRepo, ctx, "Artist", title))
+	}
+	for _, id := range trackIDs[:6] {
+		if _, err := library.AddTrackToLibrary(ctx, userID, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+	var playlistIDs []int64
+	for i, name := range []string{"One", "Two", "Three"} {
+		pl := &Playlist{UserID: userID, Name: name}
+		if err := playlists.Create(ctx, pl); err != nil {
+			t.Fatalf("create playlist: %v", err)
+		}
+		if _, err := playlists.AddTracks(ctx, pl.ID, trackIDs[i*4:(i+1)*4]); err != nil {
+			t.Fatalf("add tracks: %v", err)
+		}
+		playlistIDs = append(playlistIDs, pl.ID)
+	}
+	missing := int64(1 << 40)
+
+	countCtx, counter := WithQueryCounter(ctx)
+	tracks, err := trackRepo.GetByIDs(countCtx, append(trackIDs, missing))
+	if err != nil {
+		t.Fatalf("GetByIDs: %v", err)
+	}
+	if len(tracks) != len(trackIDs) || tracks[trackIDs[3]] == nil || tracks[trackIDs[3]].Title != "d" || tracks[missing] != nil {
+		t.Fatalf("GetByIDs loaded %d tracks, want %d without the missing ID", len(tracks), len(trackIDs))
+	}
+	visible, err := trackRepo.GetByIDsForUser(countCtx, userID, trackIDs)
+	if err != nil || len(visible) != len(trackIDs) {
+		t.Fatalf("GetByIDsForUser = %d tracks, %v", len(visible), err)
+	}
+	inLibrary, err := library.TracksInLibrary(countCtx, userID, trackIDs)
+	if err != nil {
+		t.Fatalf("TracksInLibrary: %v", err)
+	}
+	if len(inLibrary) != 6 || !inLibrary[trackIDs[0]] || inLibrary[trackIDs[6]] {
+		t.Fatalf("inLibrary = %v, want the first six tracks", inLibrary)
+	}
+	loaded, err := playlists.GetManyWithTracks(countCtx, append(playlistIDs, missing))
+	if err != nil {
+		t.Fatalf("GetManyWithTracks: %v", err)
+	}
+	if len(loaded) != 3 || len(loaded[playlistIDs[1]].Tracks) != 4 || loaded[playlistIDs[1]].Tracks[0].ID != trackIDs[4] {
+		t.Fatalf("GetManyWithTracks loaded %d playlists", len(loaded))
+	}
+	if _, _, err := playlists.GetByUserID(countCtx, userID, ListPlaylistsParams{}); err != nil {
+		t.Fatalf("GetByUserID: %v", err)
+	}
+	if got := counter.Count(); got != 5 {
+		t.Fatalf("queries = %d, want one per batch load", got)
+	}
+}
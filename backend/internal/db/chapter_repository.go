@@ -0,0 +1,99 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+)
+
+// Where a mix's chapters were read from: the source's own chapter list, or
+// timestamps in its description.
+const (
+	ChapterSourceChapters    = "chapters"
+	ChapterSourceDescription = "description"
+)
+
+// Chapter is one track of a long mix. SplitTrackID is the track cut from it,
+// once the mix has been split.
+type Chapter struct {
+	ID           int64
+	TrackID      int64
+	Position     int
+	Title        string
+	Artist       sql.NullString
+	StartMs      int
+	EndMs        int
+	Source       string
+	SplitTrackID sql.NullInt64
+}
+
+type ChapterRepository struct {
+	db *DB
+}
+
+func NewChapterRepository(db *DB) *ChapterRepository {
+	return &ChapterRepository{db: db}
+}
+
+// Save stores chapters for trackID, numbered in order from 1. A track that
+// already has chapters keeps them, and the tracks split from them.
+func (r *ChapterRepository) Save(ctx context.Context, trackID int64, chapters []Chapter) error {
+	if len(chapters) == 0 {
+		return nil
+	}
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	var exists bool
+	if err := tx.QueryRowContext(ctx,
+		`SELECT EXISTS (SELECT 1 FROM track_chapters WHERE track_id = $1)`, trackID).Scan(&exists); err != nil {
+		return fmt.Errorf("check chapters: %w", err)
+	}
+	if exists {
+		return nil
+	}
+	for i, c := range chapters {
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO track_chapters (track_id, position, title, artist, start_ms, end_ms, source)
+			VALUES ($1, $2, $3, $4, $5, $6, $7)
+			ON CONFLICT (track_id, position) DO NOTHING
+		`, trackID, i+1, c.Title, c.Artist, c.StartMs, c.EndMs, c.Source); err != nil {
+			return fmt.Errorf("save chapter %d: %w", i+1, err)
+		}
+	}
+	return tx.Commit()
+}
+
+// ListForTrack returns trackID's chapters in order, or none.
+func (r *ChapterRepository) ListForTrack(ctx context.Context, trackID int64) ([]Chapter, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, track_id, position, title, artist, start_ms, end_ms, source, split_track_id
+		FROM track_chapters
+		WHERE track_id = $1
+		ORDER BY position
+	`, trackID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var chapters []Chapter
+	for rows.Next() {
+		var c Chapter
+		if err := rows.Scan(&c.ID, &c.TrackID, &c.Position, &c.Title, &c.Artist, &c.StartMs, &c.EndMs, &c.Source, &c.SplitTrackID); err != nil {
+			return nil, err
+		}
+		chapters = append(chapters, c)
+	}
+	return chapters, rows.Err()
+}
+
+// SetSplitTrack records splitTrackID as the track cut from the chapter.
+func (r *ChapterRepository) SetSplitTrack(ctx context.Context, chapterID, splitTrackID int64) error {
+	_, err := r.db.ExecContext(ctx,
+		`UPDATE track_chapters SET split_track_id = $2 WHERE id = $1`, chapterID, splitTrackID)
+	return err
+}
@@ -0,0 +1,40 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"testing"
+)
+
+func TestChaptersAreSavedOnceAndKeepSplitTracks(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewChapterRepository(database)
+	trackRepo := NewTrackRepository(database)
+	mix := seedPlayTrack(t, trackRepo, ctx, "Mix Host", "Warehouse Set")
+	split := seedPlayTrack(t, trackRepo, ctx, "Floating Points", "Ratio")
+
+	first := []Chapter{
+		{Title: "Ratio", Artist: sql.NullString{String: "Floating Points", Valid: true}, StartMs: 0, EndMs: 90000, Source: ChapterSourceDescription},
+		{Title: "Baby", StartMs: 90000, EndMs: 200000, Source: ChapterSourceDescription},
+	}
+	if err := repo.Save(ctx, mix, first); err != nil {
+		t.Fatalf("Save() error = %v", err)
+	}
+	chapters, err := repo.ListForTrack(ctx, mix)
+	if err != nil || len(chapters) != 2 || chapters[0].Position != 1 || chapters[1].Title != "Baby" || chapters[1].Artist.Valid {
+		t.Fatalf("ListForTrack() = %+v, %v", chapters, err)
+	}
+	if err := repo.SetSplitTrack(ctx, chapters[0].ID, split); err != nil {
+		t.Fatalf("SetSplitTrack() error = %v", err)
+	}
+
+	// A second download of the mix keeps the chapters it was split by.
+	if err := repo.Save(ctx, mix, []Chapter{{Title: "Other", StartMs: 0, EndMs: 200000, Source: ChapterSourceChapters}}); err != nil {
+		t.Fatalf("second Save() error = %v", err)
+	}
+	chapters, err = repo.ListForTrack(ctx, mix)
+	if err != nil || len(chapters) != 2 || chapters[0].SplitTrackID != (sql.NullInt64{Int64: split, Valid: true}) {
+		t.Fatalf("chapters after second save = %+v, %v", chapters, err)
+	}
+}
@@ -0,0 +1,246 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// DailyMixCandidate is a reviewed library track a daily mix can hold, with
+// how its owner has listened to it lately. Genre is empty when unknown and
+// Energy unset until the track is analyzed or its mood features measured.
+// The analyzer's energy wins over the measured one.
+type DailyMixCandidate struct {
+	TrackID      int64
+	Artist       string
+	Album        string
+	Genre        string
+	Energy       sql.NullFloat64
+	Plays        int
+	Liked        bool
+	LastPlayedAt time.Time
+}
+
+// DailyMix is one of a user's daily mixes: a read-only playlist numbered by
+// Position from 1. Genre is empty for a mix of the whole library and Mood
+// when the mix spans moods. TrackIDs is only set when saving.
+type DailyMix struct {
+	Position    int
+	PlaylistID  int64
+	Name        string
+	Description string
+	Genre       string
+	Mood        string
+	TrackIDs    []int64
+	TrackCount  int
+	DurationMs  int64
+	GeneratedAt time.Time
+}
+
+type DailyMixRepository struct {
+	db *DB
+}
+
+func NewDailyMixRepository(db *DB) *DailyMixRepository {
+	return &DailyMixRepository{db: db}
+}
+
+// DueUsers returns up to limit users with at least minTracks reviewed
+// library tracks whose mixes were last generated before cutoff, or never,
+// longest waiting first.
+func (r *DailyMixRepository) DueUsers(ctx context.Context, cutoff time.Time, minTracks, limit int) ([]uuid.UUID, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT ul.user_id
+		FROM user_library ul
+		LEFT JOIN (
+			SELECT user_id, MIN(generated_at) AS generated_at
+			FROM daily_mixes
+			GROUP BY user_id
+		) dm ON dm.user_id = ul.user_id
+		WHERE NOT ul.in_inbox
+		GROUP BY ul.user_id, dm.generated_at
+		HAVING COUNT(*) >= $2 AND (dm.generated_at IS NULL OR dm.generated_at < $1)
+		ORDER BY dm.generated_at ASC NULLS FIRST, ul.user_id
+		LIMIT $3
+	`, cutoff, minTracks, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var users []uuid.UUID
+	for rows.Next() {
+		var id uuid.UUID
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		users = append(users, id)
+	}
+	return users, rows.Err()
+}
+
+// Candidates returns the user's reviewed library tracks with their plays
+// and latest play since the given time.
+func (r *DailyMixRepository) Candidates(ctx context.Context, userID uuid.UUID, since time.Time) ([]DailyMixCandidate, error) {
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT t.id, COALESCE(t.artist, ''), COALESCE(t.album, ''), COALESCE(btrim(t.genre), ''),
+			   ta.summary_json->'energy', ta.overrides_json->'energy', t.energy,
+			   COALESCE(p.plays, 0), p.last_played_at,
+			   fav.track_id IS NOT NULL
+		FROM user_library ul
+		JOIN tracks t ON t.id = ul.track_id
+		LEFT JOIN track_analysis ta ON ta.track_id = t.id
+		LEFT JOIN track_favorites fav ON fav.user_id = ul.user_id AND fav.track_id = t.id
+		LEFT JOIN (
+			SELECT track_id, COUNT(*) AS plays, MAX(played_at) AS last_played_at
+			FROM play_events
+			WHERE user_id = $1 AND played_at >= $2
+			GROUP BY track_id
+		) p ON p.track_id = t.id
+		WHERE ul.user_id = $1 AND NOT ul.in_inbox
+		ORDER BY t.id
+	`, userID, since)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var candidates []DailyMixCandidate
+	for rows.Next() {
+		var c DailyMixCandidate
+		var energy, energyOverride []byte
+		var measured sql.NullFloat64
+		var lastPlayed sql.NullTime
+		if err := rows.Scan(&c.TrackID, &c.Artist, &c.Album, &c.Genre, &energy, &energyOverride, &measured, &c.Plays, &lastPlayed, &c.Liked); err != nil {
+			return nil, err
+		}
+		merged := mergeCompactNumberValue(decodeCompactNumberValue(json.RawMessage(energy)), decodeCompactNumberValue(json.RawMessage(energyOverride)))
+		if merged != nil && merged.Value != nil {
+			c.Energy = sql.NullFloat64{Float64: *merged.Value, Valid: true}
+		} else {
+			c.Energy = measured
+		}
+		c.LastPlayedAt = lastPlayed.Time
+		candidates = append(candidates, c)
+	}
+	return candidates, rows.Err()
+}
+
+// Replace makes mixes the user's daily mixes, numbered in order. Each keeps
+// the playlist of the mix it replaces, so links and pins to "Daily Mix 1"
+// survive a refresh; mixes beyond the new count are deleted. It sets each
+// mix's Position, PlaylistID and GeneratedAt.
+func (r *DailyMixRepository) Replace(ctx context.Context, userID uuid.UUID, mixes []DailyMix) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	// Lock the user's mixes so two refreshes cannot interleave.
+	rows, err := tx.QueryContext(ctx, `SELECT position, playlist_id FROM daily_mixes WHERE user_id = $1 FOR UPDATE`, userID)
+	if err != nil {
+		return err
+	}
+	existing := map[int]int64{}
+	for rows.Next() {
+		var position int
+		var playlistID int64
+		if err := rows.Scan(&position, &playlistID); err != nil {
+			rows.Close()
+			return err
+		}
+		existing[position] = playlistID
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return err
+	}
+
+	playlistIDs := make([]int64, 0, len(mixes))
+	for i := range mixes {
+		mix := &mixes[i]
+		mix.Position = i + 1
+		description := sql.NullString{String: mix.Description, Valid: mix.Description != ""}
+		if id, ok := existing[mix.Position]; ok {
+			mix.PlaylistID = id
+			if _, err := tx.ExecContext(ctx, `UPDATE playlists SET name = $2, description = $3, updated_at = NOW() WHERE id = $1`,
+				id, mix.Name, description); err != nil {
+				return err
+			}
+			if _, err := tx.ExecContext(ctx, `DELETE FROM playlist_tracks WHERE playlist_id = $1`, id); err != nil {
+				return err
+			}
+		} else if err := tx.QueryRowContext(ctx, `
+			INSERT INTO playlists (user_id, name, description, read_only, position)
+			VALUES ($1, $2, $3, TRUE, (
+				SELECT COALESCE(MAX(position) + 1, 0) FROM playlists WHERE user_id = $1 AND folder_id IS NULL
+			))
+			RETURNING id
+		`, userID, mix.Name, description).Scan(&mix.PlaylistID); err != nil {
+			return err
+		}
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO playlist_tracks (playlist_id, track_id, position)
+			SELECT $1, m.track_id, m.ord - 1
+			FROM unnest($2::bigint[]) WITH ORDINALITY AS m(track_id, ord)
+		`, mix.PlaylistID, pq.Array(mix.TrackIDs)); err != nil {
+			return err
+		}
+		if err := tx.QueryRowContext(ctx, `
+			INSERT INTO daily_mixes (user_id, position, playlist_id, genre, mood)
+			VALUES ($1, $2, $3, $4, $5)
+			ON CONFLICT (user_id, position) DO UPDATE SET
+				genre = EXCLUDED.genre,
+				mood = EXCLUDED.mood,
+				generated_at = NOW()
+			RETURNING generated_at
+		`, userID, mix.Position, mix.PlaylistID, mix.Genre, sql.NullString{String: mix.Mood, Valid: mix.Mood != ""},
+		).Scan(&mix.GeneratedAt); err != nil {
+			return err
+		}
+		playlistIDs = append(playlistIDs, mix.PlaylistID)
+	}
+
+	if _, err := tx.ExecContext(ctx, `
+		DELETE FROM playlists
+		WHERE id IN (SELECT playlist_id FROM daily_mixes WHERE user_id = $1 AND position > $2)
+	`, userID, len(mixes)); err != nil {
+		return err
+	}
+	if err := RefreshPlaylistAggregates(ctx, tx, playlistIDs...); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+// List returns the user's daily mixes in order, without their tracks.
+func (r *DailyMixRepository) List(ctx context.Context, userID uuid.UUID) ([]DailyMix, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT dm.position, dm.playlist_id, p.name, COALESCE(p.description, ''), dm.genre, COALESCE(dm.mood, ''),
+			   p.track_count, p.duration_ms, dm.generated_at
+		FROM daily_mixes dm
+		JOIN playlists p ON p.id = dm.playlist_id
+		WHERE dm.user_id = $1
+		ORDER BY dm.position
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	mixes := []DailyMix{}
+	for rows.Next() {
+		var m DailyMix
+		if err := rows.Scan(&m.Position, &m.PlaylistID, &m.Name, &m.Description, &m.Genre, &m.Mood,
+			&m.TrackCount, &m.DurationMs, &m.GeneratedAt); err != nil {
+			return nil, err
+		}
+		mixes = append(mixes, m)
+	}
+	return mixes, rows.Err()
+}
@@ -0,0 +1,103 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+func TestDailyMixesReplaceReadOnlyPlaylists(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	playlists := NewPlaylistRepository(database)
+	repo := NewDailyMixRepository(database)
+	userID := seedPlayUser(t, database, "mixes@example.test")
+
+	var ids []int64
+	for _, title := range []string{"a", "b", "c", "d"} {
+		id := seedPlayTrack(t, tracks, ctx, "Artist", title)
+		if _, err := library.AddTrackToLibrary(ctx, userID, id); err != nil {
+			t.Fatalf("add track %s: %v", title, err)
+		}
+		ids = append(ids, id)
+	}
+	inboxed := seedPlayTrack(t, tracks, ctx, "Artist", "inboxed")
+	if _, err := library.AddTrackToInbox(ctx, userID, inboxed); err != nil {
+		t.Fatalf("add inbox track: %v", err)
+	}
+	if _, err := database.Exec(`UPDATE tracks SET genre = 'Jazz' WHERE id = $1`, ids[0]); err != nil {
+		t.Fatalf("set genre: %v", err)
+	}
+	if _, err := database.Exec(`INSERT INTO track_favorites (user_id, track_id) VALUES ($1, $2)`, userID, ids[1]); err != nil {
+		t.Fatalf("favorite: %v", err)
+	}
+	now := time.Now()
+	insertPlayAt(t, database, userID, ids[0], now.Add(-time.Hour))
+	insertPlayAt(t, database, userID, ids[0], now.Add(-2*time.Hour))
+	insertPlayAt(t, database, userID, ids[2], now.Add(-200*24*time.Hour))
+
+	due, err := repo.DueUsers(ctx, now, 4, 10)
+	if err != nil || len(due) != 1 || due[0] != userID {
+		t.Fatalf("DueUsers() = %v, %v; want the user", due, err)
+	}
+	if due, _ := repo.DueUsers(ctx, now, 5, 10); len(due) != 0 {
+		t.Fatalf("DueUsers(minTracks 5) = %v; inbox tracks must not count", due)
+	}
+
+	candidates, err := repo.Candidates(ctx, userID, now.Add(-90*24*time.Hour))
+	if err != nil || len(candidates) != 4 {
+		t.Fatalf("Candidates() = %+v, %v; want the four reviewed tracks", candidates, err)
+	}
+	if c := candidates[0]; c.Genre != "Jazz" || c.Plays != 2 || c.LastPlayedAt.IsZero() || c.Liked {
+		t.Fatalf("played candidate = %+v", c)
+	}
+	if !candidates[1].Liked || candidates[2].Plays != 0 {
+		t.Fatalf("candidates = %+v; old plays must not count", candidates)
+	}
+
+	mixes := []DailyMix{
+		{Name: "Daily Mix 1", Description: "Jazz", Genre: "Jazz", Mood: "calm", TrackIDs: []int64{ids[2], ids[0]}},
+		{Name: "Daily Mix 2", TrackIDs: []int64{ids[1], ids[3]}},
+	}
+	if err := repo.Replace(ctx, userID, mixes); err != nil {
+		t.Fatalf("Replace() error = %v", err)
+	}
+	first := mixes[0].PlaylistID
+	playlist, err := playlists.GetByID(ctx, first)
+	if err != nil || !playlist.ReadOnly || playlist.Name != "Daily Mix 1" {
+		t.Fatalf("mix playlist = %+v, %v; want a read-only playlist", playlist, err)
+	}
+	if got := playlistOrder(t, database, first); len(got) != 2 || got[0] != ids[2] || got[1] != ids[0] {
+		t.Fatalf("mix order = %v", got)
+	}
+	if due, _ := repo.DueUsers(ctx, now.Add(-time.Minute), 4, 10); len(due) != 0 {
+		t.Fatalf("DueUsers() after generating = %v, want none", due)
+	}
+
+	target := &Playlist{UserID: userID, Name: "Mine"}
+	if err := playlists.Create(ctx, target); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	if _, err := playlists.Merge(ctx, userID, first, []int64{target.ID}, false); !errors.Is(err, ErrPlaylistReadOnly) {
+		t.Fatalf("Merge() into a mix error = %v, want ErrPlaylistReadOnly", err)
+	}
+	if _, err := playlists.Merge(ctx, userID, target.ID, []int64{first}, false); err != nil {
+		t.Fatalf("Merge() from a mix error = %v", err)
+	}
+
+	// Refreshing keeps the first mix's playlist and drops the second.
+	second := mixes[1].PlaylistID
+	if err := repo.Replace(ctx, userID, []DailyMix{{Name: "Daily Mix 1", TrackIDs: []int64{ids[3]}}}); err != nil {
+		t.Fatalf("Replace() again error = %v", err)
+	}
+	listed, err := repo.List(ctx, userID)
+	if err != nil || len(listed) != 1 || listed[0].PlaylistID != first || listed[0].TrackCount != 1 || listed[0].Mood != "" {
+		t.Fatalf("List() = %+v, %v", listed, err)
+	}
+	if _, err := playlists.GetByID(ctx, second); !errors.Is(err, ErrPlaylistNotFound) {
+		t.Fatalf("dropped mix playlist error = %v, want ErrPlaylistNotFound", err)
+	}
+}
@@ -3,6 +3,7 @@ package db
 import (
 	"context"
 	"database/sql"
+	"errors"
 	"fmt"
 	"log"
 
@@ -17,25 +18,28 @@ type DB struct {
 	// stays on the FTS path only. Repositories read this flag to decide whether the
 	// similarity() typo-tolerance fallback is available.
 	TrigramEnabled bool
+
+	replica *sql.DB
+
+	// Alphabetical ordering settings; see SetSortArticles and SetSortCollation.
+	sortArticles    []string
+	sortArticlesSet bool
+	sortCollation   string
 }
 
+// New connects with database/sql pool defaults and no replica.
 func New(host, port, user, password, dbname string) (*DB, error) {
-	connStr := fmt.Sprintf(
-		"host=%s port=%s user=%s password=%s dbname=%s sslmode=disable",
-		host, port, user, password, dbname,
-	)
-
-	db, err := sql.Open("postgres", connStr)
-	if err != nil {
-		return nil, fmt.Errorf("failed to open database: %w", err)
-	}
+	return NewWithConfig(ConnConfig{Host: host, Port: port, User: user, Password: password, Name: dbname}, PoolConfig{})
+}
 
-	if err := db.Ping(); err != nil {
-		return nil, fmt.Errorf("failed to ping database: %w", err)
-	}
+// SchemaVersion is the number of the newest reference file in
+// internal/db/migrations. Migrate records it so a database left behind by an
+// older build can be spotted; bump it with each new migration file.
+const SchemaVersion = 71
 
-	return &DB{DB: db}, nil
-}
+// ErrSchemaTooNew is returned by Migrate when a newer build has migrated the
+// database, as happens when a container image is rolled back.
+var ErrSchemaTooNew = errors.New("database schema is newer than this build")
 
 func (db *DB) Migrate() error {
 	// Multiple API processes and integration tests can initialize concurrently.
@@ -53,6 +57,17 @@ func (db *DB) Migrate() error {
 		_, _ = conn.ExecContext(context.Background(), `SELECT pg_advisory_unlock(hashtextextended('open_music_player_schema_migrate', 0))`)
 	}()
 
+	// An older build would run its schema over a newer one, reverting
+	// constraints and defaults the newer code relies on.
+	version, err := db.MigratedVersion(context.Background())
+	if err != nil {
+		return fmt.Errorf("read schema version: %w", err)
+	}
+	if version > SchemaVersion {
+		return fmt.Errorf("%w: the database is at schema version %d and this build only knows %d; run the release that migrated it, or first take the database back with that release's `omp migrate --down-to %d`",
+			ErrSchemaTooNew, version, SchemaVersion, SchemaVersion)
+	}
+
 	// Keep startup self-sufficient for local-first dogfood. The SQL files under
 	// internal/db/migrations are reference notes for backend-owned schema slices,
 	// but a fresh server must be able to create every backend table needed by auth,
@@ -408,8 +423,6 @@ func (db *DB) Migrate() error {
 	CREATE INDEX IF NOT EXISTS idx_playlist_import_items_source_entry
 		ON playlist_import_items(playlist_source_entry_id) WHERE playlist_source_entry_id IS NOT NULL;
 
-	CREATE INDEX IF NOT EXISTS idx_tracks_fulltext ON tracks USING GIN (to_tsvector('english', COALESCE(title, '') || ' ' || COALESCE(artist, '') || ' ' || COALESCE(album, '')));
-
 	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS source_url TEXT;
 	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS source_type VARCHAR(50);
 	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS storage_key VARCHAR(500);
@@ -427,8 +440,39 @@ func (db *DB) Migrate() error {
 	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS cover_art_url TEXT;
 	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS metadata_user_edited BOOLEAN NOT NULL DEFAULT FALSE;
 	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS genre VARCHAR(200);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS mb_checked_at TIMESTAMP WITH TIME ZONE;
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS release_year SMALLINT CHECK (release_year >= 1);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS release_month SMALLINT CHECK (release_month BETWEEN 1 AND 12);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS release_day SMALLINT CHECK (release_day BETWEEN 1 AND 31);
+
+	-- Search text folded by NormalizeSearchText. search_aliases holds extra
+	-- names from tags, such as romanized sort titles, that search should match.
+	-- Edits clear search_text and the search indexer refills it; until then
+	-- the document falls back to the raw title, artist, and album.
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_text TEXT;
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_artist TEXT;
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_album TEXT;
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_aliases TEXT[] NOT NULL DEFAULT '{}';
+	DROP INDEX IF EXISTS idx_tracks_fulltext;
+	CREATE INDEX IF NOT EXISTS idx_tracks_search_document ON tracks USING GIN (to_tsvector('english', COALESCE(search_text, COALESCE(title, '') || ' ' || COALESCE(artist, '') || ' ' || COALESCE(album, ''))));
+	CREATE INDEX IF NOT EXISTS idx_tracks_search_pending ON tracks(id) WHERE search_text IS NULL;
 
 	CREATE INDEX IF NOT EXISTS idx_tracks_genre ON tracks(genre);
+	CREATE INDEX IF NOT EXISTS idx_tracks_mb_checked_at ON tracks(COALESCE(mb_checked_at, created_at)) WHERE mb_recording_id IS NOT NULL;
+	CREATE INDEX IF NOT EXISTS idx_tracks_release_date ON tracks((release_year * 10000 + COALESCE(release_month, 0) * 100 + COALESCE(release_day, 0))) WHERE release_year IS NOT NULL;
+
+	-- Backfill release dates from the MusicBrainz match recorded before they had columns.
+	UPDATE tracks t
+	SET release_year = split_part(d.value, '-', 1)::smallint,
+		release_month = NULLIF(split_part(d.value, '-', 2), '')::smallint,
+		release_day = NULLIF(split_part(d.value, '-', 3), '')::smallint
+	FROM (
+		SELECT id, metadata_provenance #>> '{musicbrainz,best_match,release_date}' AS value
+		FROM tracks
+		WHERE release_year IS NULL AND metadata_provenance ? 'musicbrainz'
+	) d
+	WHERE d.id = t.id
+	  AND d.value ~ '^[1-9][0-9]{3}(-(0[1-9]|1[0-2])(-(0[1-9]|[12][0-9]|3[01]))?)?$';
 
 	CREATE TABLE IF NOT EXISTS mix_plans (
 		id UUID PRIMARY KEY,
@@ -751,6 +795,937 @@ func (db *DB) Migrate() error {
 		CONSTRAINT chk_research_user_runtime_slots_active_runs CHECK (active_run_count >= 0)
 	);
 
+	CREATE TABLE IF NOT EXISTS idempotency_keys (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		idempotency_key VARCHAR(255) NOT NULL,
+		request_hash VARCHAR(64) NOT NULL,
+		response_status INTEGER,
+		response_content_type VARCHAR(255),
+		response_body BYTEA,
+		locked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		completed_at TIMESTAMP WITH TIME ZONE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (user_id, idempotency_key)
+	);
+	CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+
+	CREATE TABLE IF NOT EXISTS playlist_folders (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		parent_id BIGINT REFERENCES playlist_folders(id) ON DELETE CASCADE,
+		name VARCHAR(255) NOT NULL,
+		position INTEGER NOT NULL DEFAULT 0,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE INDEX IF NOT EXISTS idx_playlist_folders_user_parent ON playlist_folders(user_id, parent_id);
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS folder_id BIGINT REFERENCES playlist_folders(id) ON DELETE SET NULL;
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS pin_position INTEGER;
+	CREATE INDEX IF NOT EXISTS idx_playlists_folder_id ON playlists(folder_id) WHERE folder_id IS NOT NULL;
+
+	CREATE TABLE IF NOT EXISTS playlist_revisions (
+		id BIGSERIAL PRIMARY KEY,
+		playlist_id BIGINT NOT NULL REFERENCES playlists(id) ON DELETE CASCADE,
+		action VARCHAR(32) NOT NULL,
+		name VARCHAR(255) NOT NULL,
+		description TEXT,
+		cover_url TEXT,
+		is_public BOOLEAN NOT NULL DEFAULT FALSE,
+		track_ids BIGINT[] NOT NULL DEFAULT '{}',
+		tracks_added INTEGER,
+		tracks_removed INTEGER,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE INDEX IF NOT EXISTS idx_playlist_revisions_playlist_created ON playlist_revisions(playlist_id, created_at DESC);
+
+	ALTER TABLE track_favorites ADD COLUMN IF NOT EXISTS context_type VARCHAR(32);
+	ALTER TABLE track_favorites ADD COLUMN IF NOT EXISTS context_id TEXT;
+	CREATE INDEX IF NOT EXISTS idx_play_events_user_context ON play_events(user_id, context_type, context_id, played_at DESC);
+
+	CREATE TABLE IF NOT EXISTS artwork_images (
+		id BIGSERIAL PRIMARY KEY,
+		source_url TEXT NOT NULL UNIQUE,
+		width INTEGER NOT NULL,
+		height INTEGER NOT NULL,
+		variants JSONB NOT NULL DEFAULT '[]',
+		blurhash TEXT NOT NULL,
+		dominant_color VARCHAR(7) NOT NULL,
+		palette TEXT[] NOT NULL DEFAULT '{}',
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS artwork_id BIGINT REFERENCES artwork_images(id) ON DELETE SET NULL;
+	CREATE INDEX IF NOT EXISTS idx_tracks_artwork_id ON tracks(artwork_id) WHERE artwork_id IS NOT NULL;
+
+	ALTER TABLE artwork_images ALTER COLUMN source_url DROP NOT NULL;
+	ALTER TABLE artwork_images ADD COLUMN IF NOT EXISTS content_sha256 VARCHAR(64);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_artwork_images_embedded_sha256 ON artwork_images(content_sha256) WHERE source_url IS NULL;
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS artwork_source VARCHAR(16);
+
+	CREATE TABLE IF NOT EXISTS track_lyrics (
+		track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+		lyrics TEXT NOT NULL,
+		synced BOOLEAN NOT NULL DEFAULT FALSE,
+		source VARCHAR(16) NOT NULL,
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+	CREATE TABLE IF NOT EXISTS track_field_provenance (
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		field VARCHAR(32) NOT NULL,
+		source VARCHAR(16) NOT NULL,
+		locked BOOLEAN NOT NULL DEFAULT FALSE,
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (track_id, field)
+	);
+	-- Tracks edited before per-field provenance keep every field locked.
+	INSERT INTO track_field_provenance (track_id, field, source, locked)
+	SELECT t.id, f.field, 'user', TRUE
+	FROM tracks t
+	CROSS JOIN (VALUES ('title'), ('artist'), ('album'), ('duration_ms'), ('cover_art_url')) AS f(field)
+	WHERE t.metadata_user_edited
+	  AND NOT EXISTS (SELECT 1 FROM track_field_provenance p WHERE p.track_id = t.id)
+	ON CONFLICT (track_id, field) DO NOTHING;
+
+	-- MusicBrainz remix/sample/cover relationships of a track's recording.
+	CREATE TABLE IF NOT EXISTS track_relations (
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		relation VARCHAR(16) NOT NULL CHECK (relation IN ('remix_of', 'remixed_by', 'samples', 'sampled_by', 'cover_of', 'performance_of')),
+		target_type VARCHAR(16) NOT NULL CHECK (target_type IN ('recording', 'work')),
+		target_mbid UUID NOT NULL,
+		target_title TEXT,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (track_id, relation, target_mbid)
+	);
+	CREATE INDEX IF NOT EXISTS idx_track_relations_target ON track_relations(target_mbid, relation);
+
+	-- Artists credited on a track, in credit order.
+	CREATE TABLE IF NOT EXISTS track_artists (
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		position SMALLINT NOT NULL,
+		name TEXT NOT NULL,
+		role VARCHAR(16) NOT NULL CHECK (role IN ('main', 'featured', 'remixer')),
+		mb_artist_id UUID,
+		PRIMARY KEY (track_id, role, name)
+	);
+	CREATE INDEX IF NOT EXISTS idx_track_artists_name ON track_artists(name);
+	-- Tracks stored before credits were modeled get their artist tag as the
+	-- main credit; re-verification replaces it with the MusicBrainz credits.
+	INSERT INTO track_artists (track_id, position, name, role)
+	SELECT t.id, 0, btrim(regexp_replace(t.artist, '\s+(feat\.?|ft\.?|featuring)\s+.*$', '', 'i')), 'main'
+	FROM tracks t
+	WHERE btrim(COALESCE(t.artist, '')) <> ''
+	  AND NOT EXISTS (SELECT 1 FROM track_artists ta WHERE ta.track_id = t.id)
+	ON CONFLICT (track_id, role, name) DO NOTHING;
+
+	-- MusicBrainz sort names keyed by the artist name shown on tracks, so
+	-- every track credited to "The Beatles" orders as "Beatles, The".
+	CREATE TABLE IF NOT EXISTS artist_sort_names (
+		name TEXT PRIMARY KEY,
+		sort_name TEXT NOT NULL,
+		mb_artist_id UUID,
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+	-- Per-user display preferences.
+	CREATE TABLE IF NOT EXISTS user_settings (
+		user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+		metadata_script VARCHAR(16) NOT NULL DEFAULT 'original' CHECK (metadata_script IN ('original', 'latin')),
+		metadata_locale VARCHAR(16),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+	-- MusicBrainz aliases of a track's title and artist, such as translations
+	-- and transliterations. An empty locale means no particular language.
+	CREATE TABLE IF NOT EXISTS track_aliases (
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		field VARCHAR(16) NOT NULL CHECK (field IN ('title', 'artist')),
+		name TEXT NOT NULL,
+		locale VARCHAR(16) NOT NULL DEFAULT '',
+		is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+		PRIMARY KEY (track_id, field, name, locale)
+	);
+
+	-- Durable background jobs run by the jobs package. Each kind decodes its
+	-- own payload; a unique key allows one queued or running job per kind and
+	-- key.
+	CREATE TABLE IF NOT EXISTS jobs (
+		id UUID PRIMARY KEY,
+		kind VARCHAR(64) NOT NULL,
+		user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+		unique_key VARCHAR(255),
+		payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+		status VARCHAR(16) NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'succeeded', 'failed', 'canceled')),
+		priority INTEGER NOT NULL DEFAULT 0,
+		attempts INTEGER NOT NULL DEFAULT 0,
+		max_attempts INTEGER NOT NULL DEFAULT 3 CHECK (max_attempts > 0),
+		run_after TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		progress_current INTEGER NOT NULL DEFAULT 0,
+		progress_total INTEGER NOT NULL DEFAULT 0,
+		progress_detail JSONB,
+		error TEXT,
+		cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		started_at TIMESTAMP WITH TIME ZONE,
+		finished_at TIMESTAMP WITH TIME ZONE,
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_unique_active ON jobs(kind, unique_key) WHERE unique_key IS NOT NULL AND status IN ('queued', 'running');
+	CREATE INDEX IF NOT EXISTS idx_jobs_due ON jobs(priority DESC, run_after, created_at) WHERE status = 'queued';
+	CREATE INDEX IF NOT EXISTS idx_jobs_running ON jobs(updated_at) WHERE status = 'running';
+	CREATE INDEX IF NOT EXISTS idx_jobs_user_created ON jobs(user_id, created_at DESC) WHERE user_id IS NOT NULL;
+
+	-- Bulk fix-it jobs now run as jobs of kind library_repair.
+	DROP TABLE IF EXISTS maintenance_jobs;
+
+	-- Paused job kinds; workers claim no queued jobs of a paused kind.
+	CREATE TABLE IF NOT EXISTS job_queue_pauses (
+		kind VARCHAR(64) PRIMARY KEY,
+		reason TEXT,
+		paused_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+	-- Jobs of any kind that failed for good, kept with their error context
+	-- until an operator retries or discards them.
+	CREATE TABLE IF NOT EXISTS job_dead_letters (
+		id BIGSERIAL PRIMARY KEY,
+		kind VARCHAR(64) NOT NULL,
+		job_id VARCHAR(64) NOT NULL,
+		user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+		error TEXT NOT NULL,
+		category VARCHAR(32) NOT NULL,
+		attempts INTEGER NOT NULL DEFAULT 0,
+		context JSONB NOT NULL DEFAULT '{}'::jsonb,
+		failed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		UNIQUE (kind, job_id)
+	);
+	CREATE INDEX IF NOT EXISTS idx_job_dead_letters_failed ON job_dead_letters(failed_at DESC);
+
+	-- Per-user star ratings, 1 to 5; carried over by library migrations.
+	CREATE TABLE IF NOT EXISTS track_ratings (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (user_id, track_id)
+	);
+	CREATE INDEX IF NOT EXISTS idx_track_ratings_track_id ON track_ratings(track_id);
+
+	-- Per-user device profiles: the format exports and offline sync write
+	-- tracks in for one target device, such as a car's USB stick.
+	CREATE TABLE IF NOT EXISTS device_profiles (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		name VARCHAR(100) NOT NULL,
+		format VARCHAR(16) NOT NULL DEFAULT 'original',
+		bitrate_kbps INTEGER NOT NULL DEFAULT 0 CHECK (bitrate_kbps >= 0),
+		max_artwork_bytes BIGINT NOT NULL DEFAULT 0 CHECK (max_artwork_bytes >= 0),
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		UNIQUE (user_id, name)
+	);
+
+	-- Households sharing one instance. Users and tracks without a tenant
+	-- belong to the instance itself. A tenant's tracks are visible only to its
+	-- members and its audio is stored below storage_prefix. NULL quotas are
+	-- unlimited.
+	CREATE TABLE IF NOT EXISTS tenants (
+		id BIGSERIAL PRIMARY KEY,
+		slug VARCHAR(64) NOT NULL UNIQUE,
+		name VARCHAR(255) NOT NULL,
+		storage_prefix VARCHAR(100) NOT NULL UNIQUE,
+		max_tracks INTEGER CHECK (max_tracks >= 0),
+		max_storage_bytes BIGINT CHECK (max_storage_bytes >= 0),
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	ALTER TABLE users ADD COLUMN IF NOT EXISTS tenant_id BIGINT REFERENCES tenants(id) ON DELETE RESTRICT;
+	ALTER TABLE users ADD COLUMN IF NOT EXISTS tenant_admin BOOLEAN NOT NULL DEFAULT FALSE;
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS tenant_id BIGINT REFERENCES tenants(id) ON DELETE RESTRICT;
+	CREATE INDEX IF NOT EXISTS idx_users_tenant_id ON users(tenant_id) WHERE tenant_id IS NOT NULL;
+	CREATE INDEX IF NOT EXISTS idx_tracks_tenant_id ON tracks(tenant_id) WHERE tenant_id IS NOT NULL;
+
+	-- Artist biographies read from Wikipedia through the artist's Wikidata
+	-- item, one per requested language. An empty extract records that no
+	-- article exists, so the lookup waits until the row is stale.
+	CREATE TABLE IF NOT EXISTS artist_bios (
+		mb_artist_id UUID NOT NULL,
+		language VARCHAR(16) NOT NULL,
+		extract TEXT NOT NULL DEFAULT '',
+		content_language VARCHAR(16) NOT NULL DEFAULT '',
+		source VARCHAR(16) NOT NULL,
+		title VARCHAR(500) NOT NULL DEFAULT '',
+		url TEXT NOT NULL DEFAULT '',
+		license VARCHAR(64) NOT NULL DEFAULT '',
+		fetched_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (mb_artist_id, language)
+	);
+
+	-- Similar artists per MusicBrainz artist as last read from ListenBrainz or
+	-- Last.fm, most similar first. An empty list records that the source knew
+	-- none, so the lookup waits until the row is stale.
+	CREATE TABLE IF NOT EXISTS similar_artists (
+		mb_artist_id UUID PRIMARY KEY,
+		source VARCHAR(16) NOT NULL,
+		artists JSONB NOT NULL DEFAULT '[]'::jsonb,
+		fetched_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+	-- Tracks the user moved past before they counted as a play, with how far
+	-- in they got. Skips never count as plays; frequent skips keep a track out
+	-- of radio and home recommendations.
+	CREATE TABLE IF NOT EXISTS track_skips (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		skipped_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+		position_ms INTEGER NOT NULL DEFAULT 0 CHECK (position_ms >= 0),
+		context_type VARCHAR(32),
+		context_id TEXT
+	);
+	CREATE INDEX IF NOT EXISTS idx_track_skips_user_track ON track_skips(user_id, track_id, skipped_at DESC);
+	CREATE INDEX IF NOT EXISTS idx_track_skips_user_skipped_at ON track_skips(user_id, skipped_at DESC);
+
+	-- Client-generated IDs of plays and skips reported in batches, such as
+	-- listens made offline, so a resent batch records each event once.
+	ALTER TABLE play_events ADD COLUMN IF NOT EXISTS client_event_id UUID;
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_play_events_user_client_event ON play_events(user_id, client_event_id) WHERE client_event_id IS NOT NULL;
+	ALTER TABLE track_skips ADD COLUMN IF NOT EXISTS client_event_id UUID;
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_track_skips_user_client_event ON track_skips(user_id, client_event_id) WHERE client_event_id IS NOT NULL;
+
+	CREATE TABLE IF NOT EXISTS play_daily_rollups (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		day DATE NOT NULL,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		context_type VARCHAR(32) NOT NULL DEFAULT '',
+		context_id TEXT NOT NULL DEFAULT '',
+		play_count INTEGER NOT NULL CHECK (play_count > 0),
+		last_played_at TIMESTAMPTZ NOT NULL,
+		PRIMARY KEY (user_id, day, track_id, context_type, context_id)
+	);
+	CREATE TABLE IF NOT EXISTS play_monthly_rollups (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		month DATE NOT NULL,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		context_type VARCHAR(32) NOT NULL DEFAULT '',
+		context_id TEXT NOT NULL DEFAULT '',
+		play_count INTEGER NOT NULL CHECK (play_count > 0),
+		last_played_at TIMESTAMPTZ NOT NULL,
+		PRIMARY KEY (user_id, month, track_id, context_type, context_id)
+	);
+	ALTER TABLE play_events ADD COLUMN IF NOT EXISTS rolled_up BOOLEAN NOT NULL DEFAULT FALSE;
+	CREATE INDEX IF NOT EXISTS idx_play_events_pending_rollup ON play_events(id) WHERE NOT rolled_up;
+
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS track_count INTEGER NOT NULL DEFAULT 0;
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS duration_ms BIGINT NOT NULL DEFAULT 0;
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS size_bytes BIGINT NOT NULL DEFAULT 0;
+	CREATE TABLE IF NOT EXISTS library_albums (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		mb_artist_id UUID NOT NULL,
+		album TEXT NOT NULL,
+		mb_release_id UUID,
+		track_count INTEGER NOT NULL,
+		duration_ms BIGINT NOT NULL,
+		size_bytes BIGINT NOT NULL
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_library_albums_key ON library_albums(user_id, mb_artist_id, album, mb_release_id) NULLS NOT DISTINCT;
+
+	-- Where playback of a track skips in from and out at: past a long spoken
+	-- intro, or before a hidden track. Rows without a user apply to everyone;
+	-- a user's own row takes precedence.
+	CREATE TABLE IF NOT EXISTS track_skip_markers (
+		id BIGSERIAL PRIMARY KEY,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+		intro_end_ms INTEGER CHECK (intro_end_ms > 0),
+		outro_start_ms INTEGER CHECK (outro_start_ms > 0),
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		CONSTRAINT chk_track_skip_markers_order CHECK (intro_end_ms < outro_start_ms),
+		CONSTRAINT chk_track_skip_markers_set CHECK (intro_end_ms IS NOT NULL OR outro_start_ms IS NOT NULL)
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_track_skip_markers_key ON track_skip_markers(track_id, user_id) NULLS NOT DISTINCT;
+
+	-- Chapters of a long mix, read from the source's chapter list or the
+	-- timestamps in its description. split_track_id is the track cut from the
+	-- chapter, once the mix has been split.
+	CREATE TABLE IF NOT EXISTS track_chapters (
+		id BIGSERIAL PRIMARY KEY,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		position INTEGER NOT NULL,
+		title VARCHAR(500) NOT NULL,
+		artist VARCHAR(500),
+		start_ms INTEGER NOT NULL,
+		end_ms INTEGER NOT NULL,
+		source VARCHAR(32) NOT NULL,
+		split_track_id BIGINT REFERENCES tracks(id) ON DELETE SET NULL,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		CONSTRAINT chk_track_chapters_range CHECK (start_ms >= 0 AND end_ms > start_ms),
+		CONSTRAINT chk_track_chapters_source CHECK (source IN ('chapters', 'description')),
+		UNIQUE (track_id, position)
+	);
+
+	-- Non-music segments of a track's source video, such as sponsor reads and
+	-- intros, that playback skips. They come from a community database such as
+	-- SponsorBlock and are read when the track is first downloaded.
+	CREATE TABLE IF NOT EXISTS track_skip_segments (
+		id BIGSERIAL PRIMARY KEY,
+		track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+		category VARCHAR(32) NOT NULL,
+		start_ms INTEGER NOT NULL,
+		end_ms INTEGER NOT NULL,
+		source VARCHAR(32) NOT NULL,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		CONSTRAINT chk_track_skip_segments_range CHECK (start_ms >= 0 AND end_ms > start_ms)
+	);
+	CREATE INDEX IF NOT EXISTS idx_track_skip_segments_track ON track_skip_segments(track_id, start_ms);
+
+	-- Users may have leading and trailing silence of at least
+	-- trim_silence_min_ms trimmed from their downloads; 0 keeps files as they are.
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS trim_silence_min_ms INTEGER NOT NULL DEFAULT 0;
+
+	-- Silence trimmed from a track's audio when it was downloaded. The untrimmed
+	-- file is kept at original_storage_key.
+	CREATE TABLE IF NOT EXISTS track_silence_trims (
+		track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+		original_storage_key TEXT NOT NULL,
+		original_size_bytes BIGINT NOT NULL,
+		leading_ms INTEGER NOT NULL,
+		trailing_ms INTEGER NOT NULL,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		CONSTRAINT chk_track_silence_trims_trimmed CHECK (leading_ms >= 0 AND trailing_ms >= 0 AND leading_ms + trailing_ms > 0)
+	);
+
+	-- Users may have their downloads re-encoded to the server's target loudness.
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS normalize_loudness BOOLEAN NOT NULL DEFAULT FALSE;
+
+	-- Loudness normalization applied to a track's audio when it was
+	-- downloaded. original_storage_key is the unnormalized file, when kept.
+	CREATE TABLE IF NOT EXISTS track_loudness_normalizations (
+		track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+		input_lufs DOUBLE PRECISION NOT NULL,
+		target_lufs DOUBLE PRECISION NOT NULL,
+		original_storage_key TEXT,
+		original_size_bytes BIGINT,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
+	-- API keys let browser extensions and bookmarklets quick-add downloads
+	-- without a session. Only a SHA-256 hash of each key is kept.
+	CREATE TABLE IF NOT EXISTS api_keys (
+		id UUID PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		name VARCHAR(100) NOT NULL,
+		key_prefix VARCHAR(16) NOT NULL,
+		key_hash VARCHAR(64) NOT NULL UNIQUE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		last_used_at TIMESTAMP WITH TIME ZONE
+	);
+	CREATE INDEX IF NOT EXISTS idx_api_keys_user_id ON api_keys(user_id);
+
+	-- Quick-added downloads land in the user's inbox playlist, when set.
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_playlist_id BIGINT REFERENCES playlists(id) ON DELETE SET NULL;
+
+	-- Newly ingested tracks can wait in the library inbox for review before
+	-- they show up in search and shuffle.
+	ALTER TABLE user_library ADD COLUMN IF NOT EXISTS in_inbox BOOLEAN NOT NULL DEFAULT FALSE;
+	CREATE INDEX IF NOT EXISTS idx_user_library_inbox ON user_library(user_id, added_at DESC) WHERE in_inbox;
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_new_tracks BOOLEAN NOT NULL DEFAULT FALSE;
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_in_search BOOLEAN NOT NULL DEFAULT FALSE;
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_in_shuffle BOOLEAN NOT NULL DEFAULT FALSE;
+
+	-- Per-user freeform notes on a track, a MusicBrainz release, or a
+	-- playlist; exactly one target is set. search_text is the normalized body.
+	CREATE TABLE IF NOT EXISTS user_notes (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		track_id BIGINT REFERENCES tracks(id) ON DELETE CASCADE,
+		mb_release_id UUID,
+		playlist_id BIGINT REFERENCES playlists(id) ON DELETE CASCADE,
+		body TEXT NOT NULL,
+		search_text TEXT NOT NULL DEFAULT '',
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		CHECK (num_nonnulls(track_id, mb_release_id, playlist_id) = 1)
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_user_notes_track ON user_notes(user_id, track_id) WHERE track_id IS NOT NULL;
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_user_notes_album ON user_notes(user_id, mb_release_id) WHERE mb_release_id IS NOT NULL;
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_user_notes_playlist ON user_notes(user_id, playlist_id) WHERE playlist_id IS NOT NULL;
+	CREATE INDEX IF NOT EXISTS idx_user_notes_search ON user_notes USING GIN (to_tsvector('english', search_text));
+
+	-- Tracks and not-yet-downloaded discovery results a user saved to hear
+	-- soon, apart from playlists; exactly one of track_id and source_url is
+	-- set. Playing the track, or a track downloaded from the source URL, to
+	-- the end removes the entry.
+	CREATE TABLE IF NOT EXISTS listen_later (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		track_id BIGINT REFERENCES tracks(id) ON DELETE CASCADE,
+		provider VARCHAR(50),
+		source_url TEXT,
+		title TEXT,
+		artist TEXT,
+		duration_ms INTEGER,
+		thumbnail_url TEXT,
+		added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		CHECK (num_nonnulls(track_id, source_url) = 1)
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_listen_later_track ON listen_later(user_id, track_id) WHERE track_id IS NOT NULL;
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_listen_later_source ON listen_later(user_id, source_url) WHERE source_url IS NOT NULL;
+	CREATE INDEX IF NOT EXISTS idx_listen_later_user ON listen_later(user_id, added_at DESC);
+
+	-- Playback preferences every client of the user applies to the queue.
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS crossfade_seconds INTEGER NOT NULL DEFAULT 0;
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS gapless BOOLEAN NOT NULL DEFAULT TRUE;
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS normalization_target_lufs DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+	-- Players a user registered with the codecs they decode and the highest
+	-- bitrate they want, so playback URLs come in a format they play.
+	CREATE TABLE IF NOT EXISTS playback_clients (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		name VARCHAR(100) NOT NULL,
+		codecs TEXT[] NOT NULL DEFAULT '{}',
+		max_bitrate_kbps INTEGER NOT NULL DEFAULT 0 CHECK (max_bitrate_kbps >= 0),
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		UNIQUE (user_id, name)
+	);
+
+	-- Every change to a user's library, favorites, ratings and playlists, and
+	-- to catalog track metadata. Triggers append the rows, so no write path
+	-- can skip them, and the change journal reads them back. user_id is whose
+	-- data changed, NULL for catalog tracks; actor_id is who changed it, NULL
+	-- for the server: repositories name the user a request acts for in the
+	-- transaction-local omp.actor setting (see WithActor). Rows are never
+	-- updated and go only with their user.
+	CREATE TABLE IF NOT EXISTS events (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+		actor_id UUID,
+		entity_type VARCHAR(20) NOT NULL CHECK (entity_type IN ('track', 'playlist')),
+		entity_id BIGINT NOT NULL,
+		action VARCHAR(40) NOT NULL,
+		payload JSONB NOT NULL DEFAULT '{}',
+		tx_id XID8 NOT NULL DEFAULT pg_current_xact_id(),
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE INDEX IF NOT EXISTS idx_events_user ON events(user_id, id);
+	CREATE INDEX IF NOT EXISTS idx_events_entity ON events(entity_type, entity_id, id);
+
+	CREATE OR REPLACE FUNCTION reject_event_update()
+	RETURNS TRIGGER AS $$
+	BEGIN
+		RAISE EXCEPTION 'events are append-only';
+	END;
+	$$ LANGUAGE plpgsql;
+	DROP TRIGGER IF EXISTS trg_events_append_only ON events;
+	CREATE TRIGGER trg_events_append_only
+		BEFORE UPDATE ON events
+		FOR EACH ROW EXECUTE FUNCTION reject_event_update();
+
+	-- The user the current transaction acts for, or NULL for the server.
+	CREATE OR REPLACE FUNCTION event_actor()
+	RETURNS UUID AS $$
+		SELECT NULLIF(current_setting('omp.actor', true), '')::uuid;
+	$$ LANGUAGE sql STABLE;
+
+	-- Library, favorite and rating rows: TG_ARGV[0] names the action, and the
+	-- payload is the row without its keys and timestamps. Deletes cascading
+	-- from a deleted user are not recorded.
+	CREATE OR REPLACE FUNCTION record_user_track_event()
+	RETURNS TRIGGER AS $$
+	BEGIN
+		IF TG_OP = 'INSERT' THEN
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT n.user_id, event_actor(), 'track', n.track_id, TG_ARGV[0],
+				to_jsonb(n) - 'user_id' - 'track_id' - 'added_at' - 'created_at' - 'updated_at'
+			FROM new_rows n;
+		ELSIF TG_OP = 'UPDATE' THEN
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT n.user_id, event_actor(), 'track', n.track_id, TG_ARGV[0],
+				to_jsonb(n) - 'user_id' - 'track_id' - 'added_at' - 'created_at' - 'updated_at'
+			FROM new_rows n
+			JOIN old_rows o ON o.user_id = n.user_id AND o.track_id = n.track_id
+			WHERE to_jsonb(n) - 'added_at' - 'created_at' - 'updated_at'
+				IS DISTINCT FROM to_jsonb(o) - 'added_at' - 'created_at' - 'updated_at';
+		ELSE
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action)
+			SELECT o.user_id, event_actor(), 'track', o.track_id, TG_ARGV[0]
+			FROM old_rows o
+			JOIN users u ON u.id = o.user_id;
+		END IF;
+		RETURN NULL;
+	END;
+	$$ LANGUAGE plpgsql;
+
+	CREATE OR REPLACE FUNCTION record_playlist_event()
+	RETURNS TRIGGER AS $$
+	BEGIN
+		IF TG_OP = 'INSERT' THEN
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT n.user_id, event_actor(), 'playlist', n.id, 'playlist_created', jsonb_build_object('name', n.name)
+			FROM new_rows n;
+		ELSIF TG_OP = 'UPDATE' THEN
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT n.user_id, event_actor(), 'playlist', n.id, 'playlist_updated',
+				jsonb_build_object('name', n.name, 'description', n.description, 'cover_url', n.cover_url, 'is_public', n.is_public)
+			FROM new_rows n
+			JOIN old_rows o ON o.id = n.id
+			WHERE (n.name, n.description, n.cover_url, n.is_public)
+				IS DISTINCT FROM (o.name, o.description, o.cover_url, o.is_public);
+		ELSE
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT o.user_id, event_actor(), 'playlist', o.id, 'playlist_deleted', jsonb_build_object('name', o.name)
+			FROM old_rows o
+			JOIN users u ON u.id = o.user_id;
+		END IF;
+		RETURN NULL;
+	END;
+	$$ LANGUAGE plpgsql;
+
+	-- One event per playlist and statement, listing the tracks added or
+	-- removed. Moves take several statements, so they record one reorder
+	-- per transaction, and none when the transaction also removed tracks,
+	-- whose renumbering is no reorder. Rows going with a deleted playlist
+	-- are not recorded.
+	CREATE OR REPLACE FUNCTION record_playlist_tracks_event()
+	RETURNS TRIGGER AS $$
+	BEGIN
+		IF TG_OP = 'INSERT' THEN
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT p.user_id, event_actor(), 'playlist', p.id, 'playlist_tracks_added', jsonb_build_object('track_ids', added.track_ids)
+			FROM (
+				SELECT playlist_id, jsonb_agg(track_id ORDER BY position) AS track_ids
+				FROM new_rows GROUP BY playlist_id
+			) added
+			JOIN playlists p ON p.id = added.playlist_id;
+		ELSIF TG_OP = 'UPDATE' THEN
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action)
+			SELECT p.user_id, event_actor(), 'playlist', p.id, 'playlist_reordered'
+			FROM playlists p
+			WHERE p.id IN (
+				SELECT n.playlist_id FROM new_rows n
+				JOIN old_rows o ON o.playlist_id = n.playlist_id AND o.track_id = n.track_id
+				WHERE n.position <> o.position
+			)
+			AND NOT EXISTS (
+				SELECT 1 FROM events e
+				WHERE e.entity_type = 'playlist' AND e.entity_id = p.id
+					AND e.action IN ('playlist_reordered', 'playlist_tracks_removed')
+					AND e.tx_id = pg_current_xact_id()
+			);
+		ELSE
+			INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+			SELECT p.user_id, event_actor(), 'playlist', p.id, 'playlist_tracks_removed', jsonb_build_object('track_ids', removed.track_ids)
+			FROM (
+				SELECT playlist_id, jsonb_agg(track_id ORDER BY position) AS track_ids
+				FROM old_rows GROUP BY playlist_id
+			) removed
+			JOIN playlists p ON p.id = removed.playlist_id;
+		END IF;
+		RETURN NULL;
+	END;
+	$$ LANGUAGE plpgsql;
+
+	-- Catalog tracks are shared, so their events have no user; only changes
+	-- to the metadata players show are recorded.
+	CREATE OR REPLACE FUNCTION record_track_event()
+	RETURNS TRIGGER AS $$
+	BEGIN
+		IF TG_OP = 'UPDATE' THEN
+			INSERT INTO events (actor_id, entity_type, entity_id, action, payload)
+			SELECT event_actor(), 'track', n.id, 'track_updated', jsonb_build_object(
+				'title', n.title, 'artist', n.artist, 'album', n.album, 'duration_ms', n.duration_ms,
+				'cover_art_url', n.cover_art_url, 'user_edited', n.metadata_user_edited)
+			FROM new_rows n
+			JOIN old_rows o ON o.id = n.id
+			WHERE (n.title, n.artist, n.album, n.duration_ms, n.cover_art_url)
+				IS DISTINCT FROM (o.title, o.artist, o.album, o.duration_ms, o.cover_art_url);
+		ELSE
+			INSERT INTO events (actor_id, entity_type, entity_id, action, payload)
+			SELECT event_actor(), 'track', o.id, 'track_deleted', jsonb_build_object('title', o.title, 'artist', o.artist)
+			FROM old_rows o;
+		END IF;
+		RETURN NULL;
+	END;
+	$$ LANGUAGE plpgsql;
+
+	DROP TRIGGER IF EXISTS trg_user_library_events_insert ON user_library;
+	CREATE TRIGGER trg_user_library_events_insert AFTER INSERT ON user_library
+		REFERENCING NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('library_added');
+	DROP TRIGGER IF EXISTS trg_user_library_events_update ON user_library;
+	CREATE TRIGGER trg_user_library_events_update AFTER UPDATE ON user_library
+		REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('library_updated');
+	DROP TRIGGER IF EXISTS trg_user_library_events_delete ON user_library;
+	CREATE TRIGGER trg_user_library_events_delete AFTER DELETE ON user_library
+		REFERENCING OLD TABLE AS old_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('library_removed');
+
+	DROP TRIGGER IF EXISTS trg_track_favorites_events_insert ON track_favorites;
+	CREATE TRIGGER trg_track_favorites_events_insert AFTER INSERT ON track_favorites
+		REFERENCING NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('favorited');
+	DROP TRIGGER IF EXISTS trg_track_favorites_events_update ON track_favorites;
+	CREATE TRIGGER trg_track_favorites_events_update AFTER UPDATE ON track_favorites
+		REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('favorited');
+	DROP TRIGGER IF EXISTS trg_track_favorites_events_delete ON track_favorites;
+	CREATE TRIGGER trg_track_favorites_events_delete AFTER DELETE ON track_favorites
+		REFERENCING OLD TABLE AS old_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('unfavorited');
+
+	DROP TRIGGER IF EXISTS trg_track_ratings_events_insert ON track_ratings;
+	CREATE TRIGGER trg_track_ratings_events_insert AFTER INSERT ON track_ratings
+		REFERENCING NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('rated');
+	DROP TRIGGER IF EXISTS trg_track_ratings_events_update ON track_ratings;
+	CREATE TRIGGER trg_track_ratings_events_update AFTER UPDATE ON track_ratings
+		REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('rated');
+	DROP TRIGGER IF EXISTS trg_track_ratings_events_delete ON track_ratings;
+	CREATE TRIGGER trg_track_ratings_events_delete AFTER DELETE ON track_ratings
+		REFERENCING OLD TABLE AS old_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('unrated');
+
+	DROP TRIGGER IF EXISTS trg_playlists_events_insert ON playlists;
+	CREATE TRIGGER trg_playlists_events_insert AFTER INSERT ON playlists
+		REFERENCING NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_event();
+	DROP TRIGGER IF EXISTS trg_playlists_events_update ON playlists;
+	CREATE TRIGGER trg_playlists_events_update AFTER UPDATE ON playlists
+		REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_event();
+	DROP TRIGGER IF EXISTS trg_playlists_events_delete ON playlists;
+	CREATE TRIGGER trg_playlists_events_delete AFTER DELETE ON playlists
+		REFERENCING OLD TABLE AS old_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_event();
+
+	DROP TRIGGER IF EXISTS trg_playlist_tracks_events_insert ON playlist_tracks;
+	CREATE TRIGGER trg_playlist_tracks_events_insert AFTER INSERT ON playlist_tracks
+		REFERENCING NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_tracks_event();
+	DROP TRIGGER IF EXISTS trg_playlist_tracks_events_update ON playlist_tracks;
+	CREATE TRIGGER trg_playlist_tracks_events_update AFTER UPDATE ON playlist_tracks
+		REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_tracks_event();
+	DROP TRIGGER IF EXISTS trg_playlist_tracks_events_delete ON playlist_tracks;
+	CREATE TRIGGER trg_playlist_tracks_events_delete AFTER DELETE ON playlist_tracks
+		REFERENCING OLD TABLE AS old_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_tracks_event();
+
+	DROP TRIGGER IF EXISTS trg_tracks_events_update ON tracks;
+	CREATE TRIGGER trg_tracks_events_update AFTER UPDATE ON tracks
+		REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_track_event();
+	DROP TRIGGER IF EXISTS trg_tracks_events_delete ON tracks;
+	CREATE TRIGGER trg_tracks_events_delete AFTER DELETE ON tracks
+		REFERENCING OLD TABLE AS old_rows
+		FOR EACH STATEMENT EXECUTE FUNCTION record_track_event();
+
+	-- A signature of the decoded audio, so another encoding of a stored
+	-- recording is found whatever its tags say; see processor.audioHash. An
+	-- empty hash records audio too short or flat to tell apart.
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS audio_hash VARCHAR(64);
+	CREATE INDEX IF NOT EXISTS idx_tracks_audio_hash_duration ON tracks(duration_ms) WHERE audio_hash <> '';
+
+	-- Login cookies users upload for sources that need them, sealed with
+	-- SOURCE_CREDENTIALS_KEY; see sourceauth.
+	CREATE TABLE IF NOT EXISTS source_credentials (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		source VARCHAR(253) NOT NULL,
+		sealed_cookies BYTEA NOT NULL,
+		cookie_count INTEGER NOT NULL,
+		expires_at TIMESTAMP WITH TIME ZONE,
+		status VARCHAR(16) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'expired', 'rejected')),
+		status_detail TEXT NOT NULL DEFAULT '',
+		last_used_at TIMESTAMP WITH TIME ZONE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		UNIQUE (user_id, source)
+	);
+
+	-- Users following each other on the instance. Followers see the
+	-- followee's public playlists, and their listening when share_listening
+	-- is on; only users with accept_followers on can gain followers.
+	CREATE TABLE IF NOT EXISTS user_follows (
+		follower_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		followee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (follower_id, followee_id),
+		CHECK (follower_id <> followee_id)
+	);
+	CREATE INDEX IF NOT EXISTS idx_user_follows_followee ON user_follows(followee_id);
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS accept_followers BOOLEAN NOT NULL DEFAULT FALSE;
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS share_listening BOOLEAN NOT NULL DEFAULT FALSE;
+
+	-- ActivityPub actors of users publishing to the fediverse, their remote
+	-- followers, and the posts they published; see the activitypub package.
+	-- last_event_id and last_play_id are how far the publisher has read.
+	CREATE TABLE IF NOT EXISTS activitypub_actors (
+		user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+		handle VARCHAR(30) NOT NULL UNIQUE,
+		public_key_pem TEXT NOT NULL,
+		private_key_pem TEXT NOT NULL,
+		publish_listening BOOLEAN NOT NULL DEFAULT FALSE,
+		publish_loved BOOLEAN NOT NULL DEFAULT TRUE,
+		publish_playlists BOOLEAN NOT NULL DEFAULT TRUE,
+		last_event_id BIGINT NOT NULL DEFAULT 0,
+		last_play_id BIGINT NOT NULL DEFAULT 0,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE TABLE IF NOT EXISTS activitypub_followers (
+		user_id UUID NOT NULL REFERENCES activitypub_actors(user_id) ON DELETE CASCADE,
+		actor_iri TEXT NOT NULL,
+		inbox TEXT NOT NULL,
+		shared_inbox TEXT,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (user_id, actor_iri)
+	);
+	CREATE TABLE IF NOT EXISTS activitypub_posts (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES activitypub_actors(user_id) ON DELETE CASCADE,
+		kind VARCHAR(16) NOT NULL CHECK (kind IN ('listening', 'loved', 'playlist')),
+		content TEXT NOT NULL,
+		playlist_id BIGINT,
+		published_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE INDEX IF NOT EXISTS idx_activitypub_posts_user ON activitypub_posts(user_id, id DESC);
+
+	-- Tracks and playlists users of an instance send each other. The
+	-- recipient accepts one into their library, which references the shared
+	-- catalog track, or dismisses it.
+	CREATE TABLE IF NOT EXISTS gifts (
+		id BIGSERIAL PRIMARY KEY,
+		sender_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		recipient_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		track_id BIGINT REFERENCES tracks(id) ON DELETE CASCADE,
+		playlist_id BIGINT REFERENCES playlists(id) ON DELETE CASCADE,
+		message TEXT NOT NULL DEFAULT '',
+		status VARCHAR(16) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'dismissed')),
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		responded_at TIMESTAMP WITH TIME ZONE,
+		CHECK ((track_id IS NULL) <> (playlist_id IS NULL)),
+		CHECK (sender_id <> recipient_id)
+	);
+	CREATE INDEX IF NOT EXISTS idx_gifts_recipient ON gifts(recipient_id, status, id DESC);
+
+	CREATE TABLE IF NOT EXISTS notifications (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		type VARCHAR(32) NOT NULL,
+		dedupe_key VARCHAR(255) NOT NULL,
+		title VARCHAR(255) NOT NULL,
+		body TEXT NOT NULL DEFAULT '',
+		data JSONB NOT NULL DEFAULT '{}',
+		read_at TIMESTAMP WITH TIME ZONE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_dedupe ON notifications(user_id, type, dedupe_key);
+	CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, id DESC);
+	CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
+
+	CREATE TABLE IF NOT EXISTS notification_preferences (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		type VARCHAR(32) NOT NULL,
+		enabled BOOLEAN NOT NULL,
+		PRIMARY KEY (user_id, type)
+	);
+
+	CREATE TABLE IF NOT EXISTS push_devices (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		provider VARCHAR(16) NOT NULL CHECK (provider IN ('unifiedpush', 'fcm')),
+		token TEXT NOT NULL,
+		name VARCHAR(100) NOT NULL DEFAULT '',
+		types TEXT[] NOT NULL DEFAULT '{}',
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		UNIQUE (provider, token)
+	);
+	CREATE INDEX IF NOT EXISTS idx_push_devices_user ON push_devices(user_id);
+
+	-- Named equalizer settings a user keeps on the server so every player
+	-- applies the same EQ. bands is a JSON array of {frequencyHz, gainDb, q};
+	-- at most one preset per user is the default, which players use unless
+	-- their registration overrides it with eq_preset_id.
+	CREATE TABLE IF NOT EXISTS eq_presets (
+		id BIGSERIAL PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		name VARCHAR(100) NOT NULL,
+		preamp_db REAL NOT NULL DEFAULT 0,
+		bands JSONB NOT NULL DEFAULT '[]',
+		is_default BOOLEAN NOT NULL DEFAULT FALSE,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		UNIQUE (user_id, name)
+	);
+	CREATE UNIQUE INDEX IF NOT EXISTS idx_eq_presets_default ON eq_presets(user_id) WHERE is_default;
+	ALTER TABLE playback_clients ADD COLUMN IF NOT EXISTS eq_preset_id BIGINT REFERENCES eq_presets(id) ON DELETE SET NULL;
+
+	-- Measured quality of each track's stored file: its dynamic range (DR)
+	-- score, the frequency its spectrum stops at when that is below what its
+	-- sample rate carries, and whether a lossless file looks upscaled from a
+	-- lossy source. score rates the file from 0 to 100. storage_key is the
+	-- object measured, so a converted file is measured again.
+	CREATE TABLE IF NOT EXISTS track_quality (
+		track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+		storage_key TEXT NOT NULL,
+		dr_score SMALLINT,
+		spectral_cutoff_hz INTEGER,
+		upscale_suspected BOOLEAN NOT NULL DEFAULT FALSE,
+		score SMALLINT NOT NULL CHECK (score BETWEEN 0 AND 100),
+		analyzed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE INDEX IF NOT EXISTS idx_track_quality_score ON track_quality(score);
+
+	-- Daily mixes: playlists the server regenerates each day from a user's
+	-- library and listening, one per cluster of genre and mood. They are
+	-- owned by the user but read_only, so only the generator changes them.
+	ALTER TABLE playlists ADD COLUMN IF NOT EXISTS read_only BOOLEAN NOT NULL DEFAULT FALSE;
+	CREATE TABLE IF NOT EXISTS daily_mixes (
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		position SMALLINT NOT NULL CHECK (position > 0),
+		playlist_id BIGINT NOT NULL UNIQUE REFERENCES playlists(id) ON DELETE CASCADE,
+		genre TEXT NOT NULL,
+		mood TEXT,
+		generated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+		PRIMARY KEY (user_id, position)
+	);
+
+	-- Mood features: rough 0-1 proxies for a track's energy, valence and
+	-- danceability, measured from its stored audio's loudness, dynamics,
+	-- spectrum and pulse. mood_storage_key is the object measured, so a
+	-- converted file is measured again.
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS energy REAL CHECK (energy BETWEEN 0 AND 1);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS valence REAL CHECK (valence BETWEEN 0 AND 1);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS danceability REAL CHECK (danceability BETWEEN 0 AND 1);
+	ALTER TABLE tracks ADD COLUMN IF NOT EXISTS mood_storage_key TEXT;
+
+	-- Suggestions biased by the time of day the user listens at, off until
+	-- the user turns them on.
+	ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS context_suggestions BOOLEAN NOT NULL DEFAULT FALSE;
+
+	-- Year-in-review cards shared by link. Only a SHA-256 hash of each link's
+	-- token is kept; the card itself is rendered from the stats on request.
+	CREATE TABLE IF NOT EXISTS year_review_cards (
+		token_hash VARCHAR(64) PRIMARY KEY,
+		user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+		year INTEGER NOT NULL,
+		created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+	CREATE INDEX IF NOT EXISTS idx_year_review_cards_user_year ON year_review_cards(user_id, year);
+
+	CREATE TABLE IF NOT EXISTS schema_version (
+		id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+		version INTEGER NOT NULL,
+		migrated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+	);
+
 	`
 
 	_, err = db.Exec(schema)
@@ -771,9 +1746,31 @@ func (db *DB) Migrate() error {
 	// gracefully to the FTS path — the server still starts and search still works.
 	db.TrigramEnabled = db.tryEnableTrigram()
 
+	if _, err := db.Exec(`INSERT INTO schema_version (id, version) VALUES (TRUE, $1)
+		ON CONFLICT (id) DO UPDATE SET version = EXCLUDED.version, migrated_at = NOW()`, SchemaVersion); err != nil {
+		return fmt.Errorf("record schema version: %w", err)
+	}
 	return nil
 }
 
+// MigratedVersion returns the SchemaVersion of the build that last migrated
+// the database, or 0 when no build that records it ever has.
+func (db *DB) MigratedVersion(ctx context.Context) (int, error) {
+	var exists bool
+	if err := db.QueryRowContext(ctx, `SELECT to_regclass('schema_version') IS NOT NULL`).Scan(&exists); err != nil {
+		return 0, err
+	}
+	if !exists {
+		return 0, nil
+	}
+	var version int
+	err := db.QueryRowContext(ctx, `SELECT version FROM schema_version`).Scan(&version)
+	if errors.Is(err, sql.ErrNoRows) {
+		return 0, nil
+	}
+	return version, err
+}
+
 func (db *DB) refreshResearchSchemaConstraints() error {
 	_, err := db.Exec(`
 		DROP TRIGGER IF EXISTS trg_research_revisions_immutable ON research_revisions;
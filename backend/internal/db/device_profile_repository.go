@@ -0,0 +1,149 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrDeviceProfileNotFound = errors.New("device profile not found")
+var ErrDeviceProfileNameTaken = errors.New("device profile name already in use")
+
+// DeviceProfile is a user's named settings for one target device: the format
+// and bitrate tracks are converted to, and the largest cover art kept.
+// Format "original" keeps stored files' formats; BitrateKbps is 0 for
+// lossless formats and MaxArtworkBytes 0 for no limit.
+type DeviceProfile struct {
+	ID              int64
+	UserID          uuid.UUID
+	Name            string
+	Format          string
+	BitrateKbps     int
+	MaxArtworkBytes int64
+	CreatedAt       time.Time
+	UpdatedAt       time.Time
+}
+
+type DeviceProfileRepository struct {
+	db *DB
+}
+
+func NewDeviceProfileRepository(db *DB) *DeviceProfileRepository {
+	return &DeviceProfileRepository{db: db}
+}
+
+const deviceProfileColumns = `id, user_id, name, format, bitrate_kbps, max_artwork_bytes, created_at, updated_at`
+
+// List returns the user's device profiles by name.
+func (r *DeviceProfileRepository) List(ctx context.Context, userID uuid.UUID) ([]DeviceProfile, error) {
+	rows, err := r.db.QueryContext(ctx,
+		`SELECT `+deviceProfileColumns+` FROM device_profiles WHERE user_id = $1 ORDER BY name, id`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	profiles := []DeviceProfile{}
+	for rows.Next() {
+		var p DeviceProfile
+		if err := rows.Scan(&p.ID, &p.UserID, &p.Name, &p.Format, &p.BitrateKbps, &p.MaxArtworkBytes, &p.CreatedAt, &p.UpdatedAt); err != nil {
+			return nil, err
+		}
+		profiles = append(profiles, p)
+	}
+	return profiles, rows.Err()
+}
+
+// Get returns one of the user's device profiles. Other users' profiles are
+// reported as not found.
+func (r *DeviceProfileRepository) Get(ctx context.Context, userID uuid.UUID, id int64) (*DeviceProfile, error) {
+	var p DeviceProfile
+	err := r.db.QueryRowContext(ctx,
+		`SELECT `+deviceProfileColumns+` FROM device_profiles WHERE id = $1 AND user_id = $2`, id, userID,
+	).Scan(&p.ID, &p.UserID, &p.Name, &p.Format, &p.BitrateKbps, &p.MaxArtworkBytes, &p.CreatedAt, &p.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrDeviceProfileNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &p, nil
+}
+
+// Create inserts a profile. Names are unique per user.
+func (r *DeviceProfileRepository) Create(ctx context.Context, profile *DeviceProfile) error {
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO device_profiles (user_id, name, format, bitrate_kbps, max_artwork_bytes)
+		VALUES ($1, $2, $3, $4, $5)
+		RETURNING id, created_at, updated_at
+	`, profile.UserID, profile.Name, profile.Format, profile.BitrateKbps, profile.MaxArtworkBytes,
+	).Scan(&profile.ID, &profile.CreatedAt, &profile.UpdatedAt)
+	return deviceProfileError(err)
+}
+
+// Update replaces the settings of one of the user's profiles.
+func (r *DeviceProfileRepository) Update(ctx context.Context, profile *DeviceProfile) error {
+	err := r.db.QueryRowContext(ctx, `
+		UPDATE device_profiles
+		SET name = $1, format = $2, bitrate_kbps = $3, max_artwork_bytes = $4, updated_at = NOW()
+		WHERE id = $5 AND user_id = $6
+		RETURNING created_at, updated_at
+	`, profile.Name, profile.Format, profile.BitrateKbps, profile.MaxArtworkBytes, profile.ID, profile.UserID,
+	).Scan(&profile.CreatedAt, &profile.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return ErrDeviceProfileNotFound
+	}
+	return deviceProfileError(err)
+}
+
+// Delete removes one of the user's profiles.
+func (r *DeviceProfileRepository) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM device_profiles WHERE id = $1 AND user_id = $2`, id, userID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrDeviceProfileNotFound
+	}
+	return nil
+}
+
+// MostCommonSettings returns the conversion settings shared by the most
+// users' device profiles, with only Format, BitrateKbps and MaxArtworkBytes
+// set, or nil when no profile converts anything.
+func (r *DeviceProfileRepository) MostCommonSettings(ctx context.Context) (*DeviceProfile, error) {
+	var p DeviceProfile
+	err := r.db.QueryRowContext(ctx, `
+		SELECT format, bitrate_kbps, max_artwork_bytes
+		FROM device_profiles
+		WHERE format <> 'original' OR max_artwork_bytes > 0
+		GROUP BY format, bitrate_kbps, max_artwork_bytes
+		ORDER BY COUNT(DISTINCT user_id) DESC, COUNT(*) DESC, format, bitrate_kbps DESC, max_artwork_bytes
+		LIMIT 1
+	`).Scan(&p.Format, &p.BitrateKbps, &p.MaxArtworkBytes)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &p, nil
+}
+
+// deviceProfileError reports a clash with another of the user's profile
+// names as ErrDeviceProfileNameTaken.
+func deviceProfileError(err error) error {
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23505" {
+		return ErrDeviceProfileNameTaken
+	}
+	return err
+}
@@ -0,0 +1,67 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+func TestDeviceProfilesAreScopedToTheirUser(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewDeviceProfileRepository(database)
+	userID := seedPlayUser(t, database, "profiles@example.test")
+	otherUser := seedPlayUser(t, database, "profiles-other@example.test")
+
+	car := &DeviceProfile{UserID: userID, Name: "Car USB", Format: "mp3", BitrateKbps: 320, MaxArtworkBytes: 512000}
+	if err := repo.Create(ctx, car); err != nil {
+		t.Fatalf("create profile: %v", err)
+	}
+	dap := &DeviceProfile{UserID: userID, Name: "DAP", Format: "flac"}
+	if err := repo.Create(ctx, dap); err != nil {
+		t.Fatalf("create second profile: %v", err)
+	}
+	if err := repo.Create(ctx, &DeviceProfile{UserID: userID, Name: "Car USB", Format: "original"}); !errors.Is(err, ErrDeviceProfileNameTaken) {
+		t.Fatalf("duplicate name error = %v, want ErrDeviceProfileNameTaken", err)
+	}
+	if err := repo.Create(ctx, &DeviceProfile{UserID: otherUser, Name: "Car USB", Format: "original"}); err != nil {
+		t.Fatalf("another user's profile with the same name: %v", err)
+	}
+
+	profiles, err := repo.List(ctx, userID)
+	if err != nil || len(profiles) != 2 || profiles[0].Name != "Car USB" || profiles[1].Name != "DAP" {
+		t.Fatalf("List() = %+v, %v", profiles, err)
+	}
+	got, err := repo.Get(ctx, userID, car.ID)
+	if err != nil || got.Format != "mp3" || got.BitrateKbps != 320 || got.MaxArtworkBytes != 512000 {
+		t.Fatalf("Get() = %+v, %v", got, err)
+	}
+	if _, err := repo.Get(ctx, otherUser, car.ID); !errors.Is(err, ErrDeviceProfileNotFound) {
+		t.Fatalf("Get() by another user error = %v, want ErrDeviceProfileNotFound", err)
+	}
+	if err := repo.Create(ctx, &DeviceProfile{UserID: otherUser, Name: "Phone", Format: "mp3", BitrateKbps: 320, MaxArtworkBytes: 512000}); err != nil {
+		t.Fatalf("create other user's second profile: %v", err)
+	}
+	common, err := repo.MostCommonSettings(ctx)
+	if err != nil || common == nil || common.Format != "mp3" || common.BitrateKbps != 320 || common.MaxArtworkBytes != 512000 {
+		t.Fatalf("MostCommonSettings() = %+v, %v; want the settings both users convert to", common, err)
+	}
+
+	car.BitrateKbps = 192
+	if err := repo.Update(ctx, car); err != nil {
+		t.Fatalf("update profile: %v", err)
+	}
+	dap.Name = "Car USB"
+	if err := repo.Update(ctx, dap); !errors.Is(err, ErrDeviceProfileNameTaken) {
+		t.Fatalf("rename onto another profile error = %v, want ErrDeviceProfileNameTaken", err)
+	}
+	if err := repo.Delete(ctx, otherUser, car.ID); !errors.Is(err, ErrDeviceProfileNotFound) {
+		t.Fatalf("Delete() by another user error = %v, want ErrDeviceProfileNotFound", err)
+	}
+	if err := repo.Delete(ctx, userID, car.ID); err != nil {
+		t.Fatalf("delete profile: %v", err)
+	}
+	if _, err := repo.Get(ctx, userID, car.ID); !errors.Is(err, ErrDeviceProfileNotFound) {
+		t.Fatalf("Get() after delete error = %v, want ErrDeviceProfileNotFound", err)
+	}
+}
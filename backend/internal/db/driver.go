@@ -0,0 +1,217 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"database/sql/driver"
+	"strings"
+	"time"
+
+	"github.com/lib/pq"
+	"go.opentelemetry.io/otel/attribute"
+	"go.opentelemetry.io/otel/trace"
+
+	"github.com/openmusicplayer/backend/internal/logger"
+	"github.com/openmusicplayer/backend/internal/tracing"
+)
+
+// Statements are observed at the driver connection rather than on DB, so
+// those run through a *sql.Tx or *sql.Conn get a client span, are counted (see
+// WithQueryCounter) and are checked against the slow-query threshold the same
+// as pool statements. Spans summarize statements by verb only; bound arguments
+// and SQL text never leave the process.
+
+// queryObserver counts statements and logs the slow ones.
+type queryObserver struct {
+	slowQueryThreshold time.Duration
+	log                *logger.Logger
+}
+
+// openObserved opens a pool on the lib/pq DSN whose connections report every
+// statement to observer. replica marks the pool's spans.
+func openObserved(dsn string, observer *queryObserver, replica bool) (*sql.DB, error) {
+	connector, err := pq.NewConnector(dsn)
+	if err != nil {
+		return nil, err
+	}
+	return sql.OpenDB(&observedConnector{Connector: connector, observer: observer, replica: replica}), nil
+}
+
+type observedConnector struct {
+	driver.Connector
+	observer *queryObserver
+	replica  bool
+}
+
+func (c *observedConnector) Connect(ctx context.Context) (driver.Conn, error) {
+	conn, err := c.Connector.Connect(ctx)
+	if err != nil {
+		return nil, err
+	}
+	return &observedConn{Conn: conn, connector: c}, nil
+}
+
+// observedConn wraps a driver connection, passing through every optional
+// interface database/sql probes for and observing Exec and Query. Prepared
+// statements are not observed; the repositories do not use them.
+type observedConn struct {
+	driver.Conn
+	connector *observedConnector
+}
+
+func (c *observedConn) ExecContext(ctx context.Context, query string, args []driver.NamedValue) (driver.Result, error) {
+	execer, ok := c.Conn.(driver.ExecerContext)
+	if !ok {
+		return nil, driver.ErrSkip
+	}
+	ctx, span := c.startQuerySpan(ctx, query)
+	started := time.Now()
+	result, err := execer.ExecContext(ctx, query, args)
+	c.finish(ctx, span, query, started, err)
+	return result, err
+}
+
+// QueryContext observes statement execution; row iteration happens after the
+// span ends.
+func (c *observedConn) QueryContext(ctx context.Context, query string, args []driver.NamedValue) (driver.Rows, error) {
+	queryer, ok := c.Conn.(driver.QueryerContext)
+	if !ok {
+		return nil, driver.ErrSkip
+	}
+	ctx, span := c.startQuerySpan(ctx, query)
+	started := time.Now()
+	rows, err := queryer.QueryContext(ctx, query, args)
+	c.finish(ctx, span, query, started, err)
+	return rows, err
+}
+
+// BeginTx starts a transaction inside a database span covering BEGIN only.
+func (c *observedConn) BeginTx(ctx context.Context, opts driver.TxOptions) (driver.Tx, error) {
+	beginner, ok := c.Conn.(driver.ConnBeginTx)
+	if !ok {
+		return c.Conn.Begin() //nolint:staticcheck // Fallback for drivers without BeginTx.
+	}
+	ctx, span := tracing.Tracer().Start(ctx, "db.begin",
+		trace.WithSpanKind(trace.SpanKindClient),
+		trace.WithAttributes(c.attributes()...),
+	)
+	tx, err := beginner.BeginTx(ctx, opts)
+	tracing.End(span, err)
+	return tx, err
+}
+
+func (c *observedConn) PrepareContext(ctx context.Context, query string) (driver.Stmt, error) {
+	if preparer, ok := c.Conn.(driver.ConnPrepareContext); ok {
+		return preparer.PrepareContext(ctx, query)
+	}
+	return c.Conn.Prepare(query)
+}
+
+func (c *observedConn) Ping(ctx context.Context) error {
+	if pinger, ok := c.Conn.(driver.Pinger); ok {
+		return pinger.Ping(ctx)
+	}
+	return nil
+}
+
+func (c *observedConn) ResetSession(ctx context.Context) error {
+	if resetter, ok := c.Conn.(driver.SessionResetter); ok {
+		return resetter.ResetSession(ctx)
+	}
+	return nil
+}
+
+func (c *observedConn) IsValid() bool {
+	if validator, ok := c.Conn.(driver.Validator); ok {
+		return validator.IsValid()
+	}
+	return true
+}
+
+func (c *observedConn) CheckNamedValue(value *driver.NamedValue) error {
+	if checker, ok := c.Conn.(driver.NamedValueChecker); ok {
+		return checker.CheckNamedValue(value)
+	}
+	return driver.ErrSkip
+}
+
+func (c *observedConn) startQuerySpan(ctx context.Context, query string) (context.Context, trace.Span) {
+	operation := queryOperation(query)
+	return tracing.Tracer().Start(ctx, "db."+strings.ToLower(operation),
+		trace.WithSpanKind(trace.SpanKindClient),
+		trace.WithAttributes(append(c.attributes(), attribute.String("db.operation.name", operation))...),
+	)
+}
+
+func (c *observedConn) attributes() []attribute.KeyValue {
+	attributes := []attribute.KeyValue{attribute.String("db.system", "postgresql")}
+	if c.connector.replica {
+		attributes = append(attributes, attribute.Bool("db.replica", true))
+	}
+	return attributes
+}
+
+// finish ends span and observes the statement, unless the driver declined it
+// with driver.ErrSkip and database/sql is about to retry it another way.
+func (c *observedConn) finish(ctx context.Context, span trace.Span, query string, started time.Time, err error) {
+	if err == driver.ErrSkip {
+		span.End()
+		return
+	}
+	c.connector.observer.observe(ctx, query, started, err)
+	tracing.End(span, err)
+}
+
+// observe counts the statement (see WithQueryCounter) and logs it when it
+// exceeds the slow-query threshold. Only the statement text is logged, never
+// its bound arguments.
+func (o *queryObserver) observe(ctx context.Context, query string, started time.Time, err error) {
+	countQuery(ctx)
+	if o == nil || o.slowQueryThreshold <= 0 || o.log == nil {
+		return
+	}
+	elapsed := time.Since(started)
+	if elapsed < o.slowQueryThreshold {
+		return
+	}
+	o.log.Warn(ctx, "Slow database query", map[string]interface{}{
+		"operation":   queryOperation(query),
+		"duration_ms": elapsed.Milliseconds(),
+		"query":       compactQuery(query, 512),
+		"failed":      err != nil,
+	})
+}
+
+// queryOperation returns the leading SQL verb, skipping CTE bodies so
+// "WITH ... UPDATE" statements are reported by their top-level verb.
+func queryOperation(query string) string {
+	fields := strings.Fields(query)
+	if len(fields) == 0 {
+		return "UNKNOWN"
+	}
+	verb := strings.ToUpper(fields[0])
+	if verb != "WITH" {
+		return verb
+	}
+	depth := 0
+	for _, field := range fields[1:] {
+		opens := strings.Count(field, "(")
+		if depth == 0 && opens == 0 {
+			switch upper := strings.ToUpper(strings.TrimRight(field, ")")); upper {
+			case "SELECT", "INSERT", "UPDATE", "DELETE":
+				return upper
+			}
+		}
+		depth += opens - strings.Count(field, ")")
+	}
+	return verb
+}
+
+// compactQuery collapses whitespace and truncates query for logging.
+func compactQuery(query string, maximum int) string {
+	compact := strings.Join(strings.Fields(query), " ")
+	if len(compact) > maximum {
+		return compact[:maximum] + "..."
+	}
+	return compact
+}
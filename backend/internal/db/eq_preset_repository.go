@@ -0,0 +1,225 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrEQPresetNotFound = errors.New("EQ preset not found")
+var ErrEQPresetNameTaken = errors.New("EQ preset name already in use")
+
+// EQBand is one peaking filter of an equalizer preset. Q of 0 leaves the
+// width to the player.
+type EQBand struct {
+	FrequencyHz float64 `json:"frequencyHz"`
+	GainDB      float64 `json:"gainDb"`
+	Q           float64 `json:"q,omitempty"`
+}
+
+// EQPreset is a user's named equalizer settings. Players apply the user's
+// default preset unless their registration overrides it.
+type EQPreset struct {
+	ID        int64
+	UserID    uuid.UUID
+	Name      string
+	PreampDB  float64
+	Bands     []EQBand
+	IsDefault bool
+	CreatedAt time.Time
+	UpdatedAt time.Time
+}
+
+type EQPresetRepository struct {
+	db *DB
+}
+
+func NewEQPresetRepository(db *DB) *EQPresetRepository {
+	return &EQPresetRepository{db: db}
+}
+
+const eqPresetColumns = `id, user_id, name, preamp_db, bands, is_default, created_at, updated_at`
+
+func scanEQPreset(row rowScanner) (*EQPreset, error) {
+	var p EQPreset
+	var bands []byte
+	if err := row.Scan(&p.ID, &p.UserID, &p.Name, &p.PreampDB, &bands, &p.IsDefault, &p.CreatedAt, &p.UpdatedAt); err != nil {
+		return nil, err
+	}
+	if err := json.Unmarshal(bands, &p.Bands); err != nil {
+		return nil, fmt.Errorf("decode EQ bands: %w", err)
+	}
+	return &p, nil
+}
+
+// List returns the user's presets by name.
+func (r *EQPresetRepository) List(ctx context.Context, userID uuid.UUID) ([]EQPreset, error) {
+	rows, err := r.db.QueryContext(ctx,
+		`SELECT `+eqPresetColumns+` FROM eq_presets WHERE user_id = $1 ORDER BY name, id`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	presets := []EQPreset{}
+	for rows.Next() {
+		p, err := scanEQPreset(rows)
+		if err != nil {
+			return nil, err
+		}
+		presets = append(presets, *p)
+	}
+	return presets, rows.Err()
+}
+
+// Get returns one of the user's presets. Other users' presets are reported
+// as not found.
+func (r *EQPresetRepository) Get(ctx context.Context, userID uuid.UUID, id int64) (*EQPreset, error) {
+	p, err := scanEQPreset(r.db.QueryRowContext(ctx,
+		`SELECT `+eqPresetColumns+` FROM eq_presets WHERE id = $1 AND user_id = $2`, id, userID))
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrEQPresetNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return p, nil
+}
+
+// ForClient returns the preset a registered player applies: the one its
+// registration overrides the default with, otherwise the user's default. It
+// returns nil when neither is set.
+func (r *EQPresetRepository) ForClient(ctx context.Context, userID uuid.UUID, clientID int64) (*EQPreset, error) {
+	p, err := scanEQPreset(r.db.QueryRowContext(ctx, `
+		SELECT `+eqPresetColumns+`
+		FROM eq_presets
+		WHERE user_id = $1
+		  AND (is_default OR id = (SELECT eq_preset_id FROM playback_clients WHERE id = $2 AND user_id = $1))
+		ORDER BY is_default
+		LIMIT 1
+	`, userID, clientID))
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	return p, nil
+}
+
+// Create inserts a preset. Names are unique per user, and a new default
+// preset takes over from the user's earlier one.
+func (r *EQPresetRepository) Create(ctx context.Context, preset *EQPreset) error {
+	bands, err := json.Marshal(preset.Bands)
+	if err != nil {
+		return err
+	}
+	return r.save(ctx, preset, func(tx *sql.Tx) error {
+		return tx.QueryRowContext(ctx, `
+			INSERT INTO eq_presets (user_id, name, preamp_db, bands, is_default)
+			VALUES ($1, $2, $3, $4, $5)
+			RETURNING id, created_at, updated_at
+		`, preset.UserID, preset.Name, preset.PreampDB, bands, preset.IsDefault,
+		).Scan(&preset.ID, &preset.CreatedAt, &preset.UpdatedAt)
+	})
+}
+
+// Update replaces the settings of one of the user's presets.
+func (r *EQPresetRepository) Update(ctx context.Context, preset *EQPreset) error {
+	bands, err := json.Marshal(preset.Bands)
+	if err != nil {
+		return err
+	}
+	err = r.save(ctx, preset, func(tx *sql.Tx) error {
+		return tx.QueryRowContext(ctx, `
+			UPDATE eq_presets
+			SET name = $1, preamp_db = $2, bands = $3, is_default = $4, updated_at = NOW()
+			WHERE id = $5 AND user_id = $6
+			RETURNING created_at, updated_at
+		`, preset.Name, preset.PreampDB, bands, preset.IsDefault, preset.ID, preset.UserID,
+		).Scan(&preset.CreatedAt, &preset.UpdatedAt)
+	})
+	if errors.Is(err, sql.ErrNoRows) {
+		return ErrEQPresetNotFound
+	}
+	return err
+}
+
+// save runs write in a transaction that first clears the user's other
+// default when preset becomes the default.
+func (r *EQPresetRepository) save(ctx context.Context, preset *EQPreset, write func(tx *sql.Tx) error) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if preset.IsDefault {
+		if _, err := tx.ExecContext(ctx,
+			`UPDATE eq_presets SET is_default = FALSE, updated_at = NOW() WHERE user_id = $1 AND is_default AND id <> $2`,
+			preset.UserID, preset.ID); err != nil {
+			return err
+		}
+	}
+	if err := write(tx); err != nil {
+		return eqPresetError(err)
+	}
+	return tx.Commit()
+}
+
+// Delete removes one of the user's presets. Players that overrode the
+// default with it go back to the default.
+func (r *EQPresetRepository) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM eq_presets WHERE id = $1 AND user_id = $2`, id, userID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrEQPresetNotFound
+	}
+	return nil
+}
+
+// SetClientPreset makes one of the user's players apply presetID instead of
+// the default, or the default again when presetID is nil.
+func (r *EQPresetRepository) SetClientPreset(ctx context.Context, userID uuid.UUID, clientID int64, presetID *int64) error {
+	if presetID != nil {
+		if _, err := r.Get(ctx, userID, *presetID); err != nil {
+			return err
+		}
+	}
+	result, err := r.db.ExecContext(ctx,
+		`UPDATE playback_clients SET eq_preset_id = $3, updated_at = NOW() WHERE id = $1 AND user_id = $2`,
+		clientID, userID, presetID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrPlaybackClientNotFound
+	}
+	return nil
+}
+
+// eqPresetError reports a clash with another of the user's preset names as
+// ErrEQPresetNameTaken.
+func eqPresetError(err error) error {
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23505" {
+		return ErrEQPresetNameTaken
+	}
+	return err
+}
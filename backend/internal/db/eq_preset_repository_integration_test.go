@@ -0,0 +1,68 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+func TestEQPresetsDefaultAndClientOverride(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewEQPresetRepository(database)
+	clients := NewPlaybackClientRepository(database)
+	userID := seedPlayUser(t, database, "eq@example.test")
+	otherUser := seedPlayUser(t, database, "eq-other@example.test")
+
+	flat := &EQPreset{UserID: userID, Name: "Flat", Bands: []EQBand{{FrequencyHz: 1000}}, IsDefault: true}
+	bass := &EQPreset{UserID: userID, Name: "Bass", PreampDB: -3, Bands: []EQBand{{FrequencyHz: 60, GainDB: 6, Q: 0.7}}}
+	for _, p := range []*EQPreset{flat, bass} {
+		if err := repo.Create(ctx, p); err != nil {
+			t.Fatalf("create %s: %v", p.Name, err)
+		}
+	}
+	if err := repo.Create(ctx, &EQPreset{UserID: userID, Name: "Bass", Bands: []EQBand{{FrequencyHz: 60}}}); !errors.Is(err, ErrEQPresetNameTaken) {
+		t.Fatalf("create with a taken name error = %v, want ErrEQPresetNameTaken", err)
+	}
+	if _, err := repo.Get(ctx, otherUser, bass.ID); !errors.Is(err, ErrEQPresetNotFound) {
+		t.Fatalf("Get() by another user error = %v, want ErrEQPresetNotFound", err)
+	}
+
+	client := &PlaybackClient{UserID: userID, Name: "Phone", Codecs: []string{"aac"}}
+	if err := clients.Register(ctx, client); err != nil {
+		t.Fatalf("register client: %v", err)
+	}
+	if got, err := repo.ForClient(ctx, userID, client.ID); err != nil || got == nil || got.ID != flat.ID {
+		t.Fatalf("ForClient() = %+v, %v; want the default preset", got, err)
+	}
+	if err := repo.SetClientPreset(ctx, otherUser, client.ID, nil); !errors.Is(err, ErrPlaybackClientNotFound) {
+		t.Fatalf("SetClientPreset() by another user error = %v, want ErrPlaybackClientNotFound", err)
+	}
+	if err := repo.SetClientPreset(ctx, userID, client.ID, &bass.ID); err != nil {
+		t.Fatalf("override client preset: %v", err)
+	}
+	got, err := repo.ForClient(ctx, userID, client.ID)
+	if err != nil || got == nil || got.ID != bass.ID || len(got.Bands) != 1 || got.Bands[0].GainDB != 6 || got.PreampDB != -3 {
+		t.Fatalf("ForClient() = %+v, %v; want the override", got, err)
+	}
+	if err := clients.Register(ctx, &PlaybackClient{UserID: userID, Name: "Phone", Codecs: []string{"opus"}}); err != nil {
+		t.Fatalf("register client again: %v", err)
+	}
+	if c, err := clients.Get(ctx, userID, client.ID); err != nil || c.EQPresetID.Int64 != bass.ID {
+		t.Fatalf("client after a second handshake = %+v, %v; want the override kept", c, err)
+	}
+
+	bass.IsDefault = true
+	if err := repo.Update(ctx, bass); err != nil {
+		t.Fatalf("make bass the default: %v", err)
+	}
+	if got, err := repo.Get(ctx, userID, flat.ID); err != nil || got.IsDefault {
+		t.Fatalf("old default = %+v, %v; want it no longer the default", got, err)
+	}
+	if err := repo.Delete(ctx, userID, bass.ID); err != nil {
+		t.Fatalf("delete preset: %v", err)
+	}
+	if got, err := repo.ForClient(ctx, userID, client.ID); err != nil || got != nil {
+		t.Fatalf("ForClient() after deleting the override and default = %+v, %v; want none", got, err)
+	}
+}
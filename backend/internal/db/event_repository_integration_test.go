@@ -0,0 +1,171 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"slices"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// TestEventsRecordMutationsAgainstPostgres covers the triggers feeding the
+// events log and reading it back as a user's change journal.
+func TestEventsRecordMutationsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	playlists := NewPlaylistRepository(database)
+	events := NewEventRepository(database)
+
+	user := seedPlayUser(t, database, "events@example.test")
+	other := seedPlayUser(t, database, "events-other@example.test")
+	ctx := WithActor(context.Background(), user)
+	first := seedPlayTrack(t, trackRepo, ctx, "Artist A", "First")
+	second := seedPlayTrack(t, trackRepo, ctx, "Artist B", "Second")
+	var start int64
+	if err := database.QueryRowContext(ctx, `SELECT COALESCE(MAX(id), 0) FROM events`).Scan(&start); err != nil {
+		t.Fatalf("last event before the test: %v", err)
+	}
+
+	for _, trackID := range []int64{first, second} {
+		if _, err := library.AddTrackToLibrary(ctx, user, trackID); err != nil {
+			t.Fatalf("AddTrackToLibrary: %v", err)
+		}
+	}
+	if _, err := library.AddTrackToLibrary(WithActor(ctx, other), other, second); err != nil {
+		t.Fatalf("AddTrackToLibrary(other): %v", err)
+	}
+	if err := library.AddFavorite(ctx, user, first, "", ""); err != nil {
+		t.Fatalf("AddFavorite: %v", err)
+	}
+	if err := library.SetRating(ctx, user, first, 4); err != nil {
+		t.Fatalf("SetRating: %v", err)
+	}
+	playlist := &Playlist{UserID: user, Name: "Mix"}
+	if err := playlists.Create(ctx, playlist); err != nil {
+		t.Fatalf("Create playlist: %v", err)
+	}
+	if _, err := playlists.AddTracks(ctx, playlist.ID, []int64{first, second}); err != nil {
+		t.Fatalf("AddTracks: %v", err)
+	}
+	if err := playlists.ReorderTrack(ctx, playlist.ID, second, 0, nil); err != nil {
+		t.Fatalf("ReorderTrack: %v", err)
+	}
+	if err := playlists.RemoveTrack(ctx, playlist.ID, second); err != nil {
+		t.Fatalf("RemoveTrack: %v", err)
+	}
+	playlist.Description = sql.NullString{String: "renamed", Valid: true}
+	if err := playlists.Update(ctx, playlist, nil); err != nil {
+		t.Fatalf("Update playlist: %v", err)
+	}
+	if err := trackRepo.UpdateMetadata(ctx, second, &MetadataUpdate{Title: "Second (Remastered)"}, nil); err != nil {
+		t.Fatalf("UpdateMetadata: %v", err)
+	}
+	if err := library.RemoveTrackFromLibrary(ctx, user, second); err != nil {
+		t.Fatalf("RemoveTrackFromLibrary: %v", err)
+	}
+	if err := playlists.Delete(ctx, playlist.ID); err != nil {
+		t.Fatalf("Delete playlist: %v", err)
+	}
+
+	want := []string{
+		EventLibraryAdded, EventLibraryAdded, EventFavorited, EventRated,
+		EventPlaylistCreated, EventPlaylistTracksAdded, EventPlaylistReordered, EventPlaylistTracksRemoved,
+		EventPlaylistUpdated, EventTrackUpdated, EventLibraryRemoved, EventPlaylistDeleted,
+	}
+	got := listEventsEventually(t, events, ctx, user, start, len(want))
+	var actions []string
+	for _, e := range got {
+		actions = append(actions, e.Action)
+		if e.ActorID.UUID != user || (e.Action != EventTrackUpdated && e.UserID.UUID != user) {
+			t.Errorf("%s event has user %v and actor %v; want %s for both", e.Action, e.UserID, e.ActorID, user)
+		}
+	}
+	if !slices.Equal(actions, want) {
+		t.Fatalf("journal actions = %v\nwant %v", actions, want)
+	}
+	if string(got[3].Payload) != `{"rating": 4}` {
+		t.Errorf("rated payload = %s", got[3].Payload)
+	}
+	if got[9].EntityID != second || got[9].UserID.Valid {
+		t.Errorf("track_updated event = %+v; want catalog track %d with no user", got[9], second)
+	}
+
+	// The other user sees their own addition and the catalog change to a
+	// track still in their library, and resumes after the last event seen.
+	others := listEventsEventually(t, events, ctx, other, start, 2)
+	if len(others) != 2 || others[0].Action != EventLibraryAdded || others[1].Action != EventTrackUpdated {
+		t.Fatalf("other user's journal = %+v", others)
+	}
+	if rest, err := events.ListSince(ctx, other, others[0].ID, 10); err != nil || len(rest) != 1 || rest[0].ID != others[1].ID {
+		t.Fatalf("ListSince after the first event = %+v, %v", rest, err)
+	}
+
+	if _, err := database.ExecContext(ctx, `UPDATE events SET action = 'rewritten'`); err == nil {
+		t.Fatal("updating an event succeeded; the log must be append-only")
+	}
+}
+
+// TestEventsRecordActorApartFromOwner covers changes made to one user's data
+// on another's request, and by the server on its own.
+func TestEventsRecordActorApartFromOwner(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	playlists := NewPlaylistRepository(database)
+	events := NewEventRepository(database)
+
+	owner := seedPlayUser(t, database, "events-owner@example.test")
+	admin := seedPlayUser(t, database, "events-admin@example.test")
+	first := seedPlayTrack(t, trackRepo, ctx, "Artist A", "First")
+	second := seedPlayTrack(t, trackRepo, ctx, "Artist B", "Second")
+	var start int64
+	if err := database.QueryRowContext(ctx, `SELECT COALESCE(MAX(id), 0) FROM events`).Scan(&start); err != nil {
+		t.Fatalf("last event before the test: %v", err)
+	}
+
+	asAdmin := WithActor(ctx, admin)
+	if _, err := library.AddTrackToLibrary(asAdmin, owner, first); err != nil {
+		t.Fatalf("AddTrackToLibrary: %v", err)
+	}
+	if err := library.SetRating(asAdmin, owner, first, 5); err != nil {
+		t.Fatalf("SetRating: %v", err)
+	}
+	if err := playlists.Create(asAdmin, &Playlist{UserID: owner, Name: "Made for you"}); err != nil {
+		t.Fatalf("Create playlist: %v", err)
+	}
+	if _, err := library.AddTrackToLibrary(ctx, owner, second); err != nil {
+		t.Fatalf("AddTrackToLibrary by the server: %v", err)
+	}
+
+	want := []uuid.NullUUID{{UUID: admin, Valid: true}, {UUID: admin, Valid: true}, {UUID: admin, Valid: true}, {}}
+	got := listEventsEventually(t, events, ctx, owner, start, len(want))
+	if len(got) != len(want) {
+		t.Fatalf("owner's journal = %+v, want %d events", got, len(want))
+	}
+	for i, e := range got {
+		if e.UserID.UUID != owner || e.ActorID != want[i] {
+			t.Errorf("%s event has user %v and actor %v; want user %s and actor %v", e.Action, e.UserID, e.ActorID, owner, want[i])
+		}
+	}
+}
+
+// listEventsEventually waits for want events: the journal holds events back
+// while an older transaction, perhaps another test's, is still running.
+func listEventsEventually(t *testing.T, events *EventRepository, ctx context.Context, userID uuid.UUID, after int64, want int) []Event {
+	t.Helper()
+	deadline := time.Now().Add(5 * time.Second)
+	for {
+		got, err := events.ListSince(ctx, userID, after, 100)
+		if err != nil {
+			t.Fatalf("ListSince: %v", err)
+		}
+		if len(got) >= want || time.Now().After(deadline) {
+			return got
+		}
+		time.Sleep(50 * time.Millisecond)
+	}
+}
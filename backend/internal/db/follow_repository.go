@@ -0,0 +1,173 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// ErrFollowNotFound is returned when removing a follow that does not exist.
+var ErrFollowNotFound = errors.New("follow not found")
+
+// ErrFollowNotAccepted is returned when following a user whose settings do
+// not accept followers.
+var ErrFollowNotAccepted = errors.New("user does not accept followers")
+
+// ErrFollowSelf is returned when a user tries to follow themselves.
+var ErrFollowSelf = errors.New("users cannot follow themselves")
+
+// FollowedUser is one side of a follow, as the other side sees them.
+// FollowedAt is when the follow began; it is zero in the directory for users
+// the caller does not follow.
+type FollowedUser struct {
+	UserID         uuid.UUID
+	Username       string
+	ShareListening bool
+	Following      bool
+	FollowedAt     time.Time
+}
+
+// FollowRepository stores who follows whom. Users only see and follow users
+// of their own tenant, or of the instance when they have none.
+type FollowRepository struct {
+	db *DB
+}
+
+func NewFollowRepository(db *DB) *FollowRepository {
+	return &FollowRepository{db: db}
+}
+
+// Follow makes followerID follow followeeID, who must share their tenant and
+// accept followers. Following a user again keeps the original follow.
+func (r *FollowRepository) Follow(ctx context.Context, followerID, followeeID uuid.UUID) (*FollowedUser, error) {
+	if followerID == followeeID {
+		return nil, ErrFollowSelf
+	}
+	followee := FollowedUser{UserID: followeeID, Following: true}
+	var accepts bool
+	err := r.db.QueryRowContext(ctx, `
+		SELECT u.username, COALESCE(s.accept_followers, FALSE), COALESCE(s.share_listening, FALSE)
+		FROM users u
+		LEFT JOIN user_settings s ON s.user_id = u.id
+		WHERE u.id = $2 AND `+tenantScopeSQL("u.tenant_id", "$1")+`
+	`, followerID, followeeID).Scan(&followee.Username, &accepts, &followee.ShareListening)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrUserNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	err = r.db.QueryRowContext(ctx, `
+		SELECT created_at FROM user_follows WHERE follower_id = $1 AND followee_id = $2
+	`, followerID, followeeID).Scan(&followee.FollowedAt)
+	if err == nil {
+		return &followee, nil
+	}
+	if !errors.Is(err, sql.ErrNoRows) {
+		return nil, err
+	}
+	if !accepts {
+		return nil, ErrFollowNotAccepted
+	}
+	err = r.db.QueryRowContext(ctx, `
+		INSERT INTO user_follows (follower_id, followee_id)
+		VALUES ($1, $2)
+		ON CONFLICT (follower_id, followee_id) DO UPDATE SET created_at = user_follows.created_at
+		RETURNING created_at
+	`, followerID, followeeID).Scan(&followee.FollowedAt)
+	if err != nil {
+		return nil, err
+	}
+	return &followee, nil
+}
+
+// Unfollow ends followerID following followeeID. Followees end it the same
+// way to remove a follower.
+func (r *FollowRepository) Unfollow(ctx context.Context, followerID, followeeID uuid.UUID) error {
+	result, err := r.db.ExecContext(ctx, `
+		DELETE FROM user_follows WHERE follower_id = $1 AND followee_id = $2
+	`, followerID, followeeID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrFollowNotFound
+	}
+	return nil
+}
+
+// IsFollowing reports whether followerID follows followeeID.
+func (r *FollowRepository) IsFollowing(ctx context.Context, followerID, followeeID uuid.UUID) (bool, error) {
+	var following bool
+	err := r.db.QueryRowContext(ctx, `
+		SELECT EXISTS (SELECT 1 FROM user_follows WHERE follower_id = $1 AND followee_id = $2)
+	`, followerID, followeeID).Scan(&following)
+	return following, err
+}
+
+// ListFollowing returns the users userID follows, most recently followed
+// first.
+func (r *FollowRepository) ListFollowing(ctx context.Context, userID uuid.UUID) ([]FollowedUser, error) {
+	return r.list(ctx, `
+		SELECT u.id, u.username, COALESCE(s.share_listening, FALSE), TRUE, f.created_at
+		FROM user_follows f
+		JOIN users u ON u.id = f.followee_id
+		LEFT JOIN user_settings s ON s.user_id = u.id
+		WHERE f.follower_id = $1
+		ORDER BY f.created_at DESC, u.id
+	`, userID)
+}
+
+// ListFollowers returns the users following userID, most recent first.
+// Following reports whether userID follows them back.
+func (r *FollowRepository) ListFollowers(ctx context.Context, userID uuid.UUID) ([]FollowedUser, error) {
+	return r.list(ctx, `
+		SELECT u.id, u.username, COALESCE(s.share_listening, FALSE),
+			EXISTS (SELECT 1 FROM user_follows b WHERE b.follower_id = $1 AND b.followee_id = u.id), f.created_at
+		FROM user_follows f
+		JOIN users u ON u.id = f.follower_id
+		LEFT JOIN user_settings s ON s.user_id = u.id
+		WHERE f.followee_id = $1
+		ORDER BY f.created_at DESC, u.id
+	`, userID)
+}
+
+// Directory returns the other users of userID's tenant who accept followers,
+// by username, and whether userID follows each.
+func (r *FollowRepository) Directory(ctx context.Context, userID uuid.UUID) ([]FollowedUser, error) {
+	return r.list(ctx, `
+		SELECT u.id, u.username, s.share_listening, f.created_at IS NOT NULL, f.created_at
+		FROM users u
+		JOIN user_settings s ON s.user_id = u.id AND s.accept_followers
+		LEFT JOIN user_follows f ON f.follower_id = $1 AND f.followee_id = u.id
+		WHERE u.id <> $1 AND `+tenantScopeSQL("u.tenant_id", "$1")+`
+		ORDER BY LOWER(u.username), u.id
+	`, userID)
+}
+
+func (r *FollowRepository) list(ctx context.Context, query string, userID uuid.UUID) ([]FollowedUser, error) {
+	rows, err := r.db.ReadQueryContext(ctx, query, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	users := []FollowedUser{}
+	for rows.Next() {
+		var u FollowedUser
+		var followedAt sql.NullTime
+		if err := rows.Scan(&u.UserID, &u.Username, &u.ShareListening, &u.Following, &followedAt); err != nil {
+			return nil, err
+		}
+		u.FollowedAt = followedAt.Time
+		users = append(users, u)
+	}
+	return users, rows.Err()
+}
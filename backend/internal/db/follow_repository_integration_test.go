@@ -0,0 +1,96 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+// TestFollowsAgainstPostgres covers opt-in follows and what they put in the
+// follower's activity feed: the followee's public playlists, and their
+// listening only while they share it.
+func TestFollowsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	playlistRepo := NewPlaylistRepository(database)
+	settingsRepo := NewUserSettingsRepository(database)
+	follows := NewFollowRepository(database)
+	activity := NewActivityRepository(database)
+
+	follower := seedPlayUser(t, database, "follower@example.test")
+	followee := seedPlayUser(t, database, "followee@example.test")
+	track := seedPlayTrack(t, trackRepo, ctx, "Artist", "Shared")
+
+	if _, err := follows.Follow(ctx, follower, followee); !errors.Is(err, ErrFollowNotAccepted) {
+		t.Fatalf("Follow before opting in = %v; want ErrFollowNotAccepted", err)
+	}
+	settings := DefaultUserSettings()
+	settings.AcceptFollowers = true
+	if _, err := settingsRepo.Update(ctx, followee, settings); err != nil {
+		t.Fatalf("Update settings: %v", err)
+	}
+	if directory, err := follows.Directory(ctx, follower); err != nil || len(directory) != 1 || directory[0].UserID != followee || directory[0].Following {
+		t.Fatalf("Directory = %+v, %v", directory, err)
+	}
+	followed, err := follows.Follow(ctx, follower, followee)
+	if err != nil || !followed.Following || followed.FollowedAt.IsZero() {
+		t.Fatalf("Follow = %+v, %v", followed, err)
+	}
+	if _, err := follows.Follow(ctx, follower, follower); !errors.Is(err, ErrFollowSelf) {
+		t.Fatalf("Follow(self) = %v; want ErrFollowSelf", err)
+	}
+	if followers, err := follows.ListFollowers(ctx, followee); err != nil || len(followers) != 1 || followers[0].UserID != follower || followers[0].Following {
+		t.Fatalf("ListFollowers = %+v, %v", followers, err)
+	}
+
+	public := &Playlist{UserID: followee, Name: "Shared Mix", IsPublic: true}
+	private := &Playlist{UserID: followee, Name: "Private Mix"}
+	for _, p := range []*Playlist{public, private} {
+		if err := playlistRepo.Create(ctx, p); err != nil {
+			t.Fatalf("Create playlist: %v", err)
+		}
+		if err := playlistRepo.AddTrack(ctx, p.ID, track); err != nil {
+			t.Fatalf("AddTrack: %v", err)
+		}
+	}
+	if _, err := database.ExecContext(ctx, `INSERT INTO play_events (user_id, track_id) VALUES ($1, $2)`, followee, track); err != nil {
+		t.Fatalf("record play: %v", err)
+	}
+
+	items, _, err := activity.List(ctx, follower, 50, 0)
+	if err != nil {
+		t.Fatalf("List: %v", err)
+	}
+	for _, item := range items {
+		if item.PlaylistID.Int64 == private.ID || item.Kind == ActivityListened {
+			t.Fatalf("feed shows %+v; want only the public playlist", item)
+		}
+		if item.UserID.UUID != followee || item.PlaylistID.Int64 != public.ID {
+			t.Fatalf("feed entry %+v is not the followee's public playlist", item)
+		}
+	}
+	if len(items) != 2 {
+		t.Fatalf("feed = %+v; want the public playlist's creation and track", items)
+	}
+
+	settings.ShareListening = true
+	if _, err := settingsRepo.Update(ctx, followee, settings); err != nil {
+		t.Fatalf("Update settings: %v", err)
+	}
+	items, _, err = activity.List(ctx, follower, 50, 0)
+	if err != nil || items[0].Kind != ActivityListened || items[0].TrackCount != 1 || items[0].TrackIDs[0] != track {
+		t.Fatalf("feed with shared listening = %+v, %v", items, err)
+	}
+
+	// Unfollowing takes the followee's activity out of the feed.
+	if err := follows.Unfollow(ctx, follower, followee); err != nil {
+		t.Fatalf("Unfollow: %v", err)
+	}
+	if _, total, err := activity.List(ctx, follower, 50, 0); err != nil || total != 0 {
+		t.Fatalf("feed after unfollowing: total %d, %v; want empty", total, err)
+	}
+	if err := follows.Unfollow(ctx, follower, followee); !errors.Is(err, ErrFollowNotFound) {
+		t.Fatalf("Unfollow again = %v; want ErrFollowNotFound", err)
+	}
+}
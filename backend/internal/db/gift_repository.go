@@ -0,0 +1,253 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// ErrGiftNotFound is returned for a gift that does not exist or was not sent
+// to the user.
+var ErrGiftNotFound = errors.New("gift not found")
+
+// ErrGiftSelf is returned when a user sends a gift to themselves.
+var ErrGiftSelf = errors.New("users cannot send gifts to themselves")
+
+// ErrGiftLimit is returned when the sender already has maxPendingGifts
+// waiting with the recipient.
+var ErrGiftLimit = errors.New("too many gifts waiting with this user")
+
+// Gift statuses.
+const (
+	GiftPending   = "pending"
+	GiftAccepted  = "accepted"
+	GiftDismissed = "dismissed"
+)
+
+// maxPendingGifts is how many unanswered gifts one user may have waiting
+// with another, so nobody's inbox can be flooded.
+const maxPendingGifts = 20
+
+// Gift is a track or playlist one user sent another. Exactly one of TrackID
+// and PlaylistID is set; the names are filled in when read.
+type Gift struct {
+	ID             int64
+	SenderID       uuid.UUID
+	SenderUsername string
+	RecipientID    uuid.UUID
+	TrackID        int64
+	TrackTitle     string
+	TrackArtist    string
+	PlaylistID     int64
+	PlaylistName   string
+	PlaylistTracks int
+	Message        string
+	Status         string
+	CreatedAt      time.Time
+	RespondedAt    *time.Time
+}
+
+// GiftRepository stores gifts between users of one tenant, or of the
+// instance when they have none.
+type GiftRepository struct {
+	db *DB
+}
+
+func NewGiftRepository(db *DB) *GiftRepository {
+	return &GiftRepository{db: db}
+}
+
+const giftSelect = `
+	SELECT g.id, g.sender_id, su.username, g.recipient_id, COALESCE(g.track_id, 0), COALESCE(t.title, ''), COALESCE(t.artist, ''),
+		COALESCE(g.playlist_id, 0), COALESCE(p.name, ''), (SELECT COUNT(*) FROM playlist_tracks pt WHERE pt.playlist_id = g.playlist_id),
+		g.message, g.status, g.created_at, g.responded_at`
+
+const giftJoins = `
+	FROM gifts g
+	JOIN users su ON su.id = g.sender_id
+	LEFT JOIN tracks t ON t.id = g.track_id
+	LEFT JOIN playlists p ON p.id = g.playlist_id`
+
+func scanGift(row rowScanner, extra ...any) (*Gift, error) {
+	var g Gift
+	var respondedAt sql.NullTime
+	dest := append([]any{&g.ID, &g.SenderID, &g.SenderUsername, &g.RecipientID, &g.TrackID, &g.TrackTitle, &g.TrackArtist,
+		&g.PlaylistID, &g.PlaylistName, &g.PlaylistTracks, &g.Message, &g.Status, &g.CreatedAt, &respondedAt}, extra...)
+	if err := row.Scan(dest...); err != nil {
+		return nil, err
+	}
+	if respondedAt.Valid {
+		g.RespondedAt = &respondedAt.Time
+	}
+	return &g, nil
+}
+
+// Send stores gift from gift.SenderID to gift.RecipientID, who must share a
+// tenant. A track must be one the sender may see; a playlist must be the
+// sender's own or a public one of their tenant. It fills in the ID, status
+// and names.
+func (r *GiftRepository) Send(ctx context.Context, gift *Gift) error {
+	if gift.SenderID == gift.RecipientID {
+		return ErrGiftSelf
+	}
+	var recipientFound, itemFound bool
+	var pending int
+	err := r.db.QueryRowContext(ctx, `
+		SELECT
+			EXISTS (SELECT 1 FROM users u WHERE u.id = $2 AND `+tenantScopeSQL("u.tenant_id", "$1")+`),
+			CASE WHEN $3::BIGINT <> 0
+				THEN EXISTS (SELECT 1 FROM tracks t WHERE t.id = $3 AND `+tenantScopeSQL("t.tenant_id", "$1")+`)
+				ELSE EXISTS (
+					SELECT 1 FROM playlists p JOIN users o ON o.id = p.user_id
+					WHERE p.id = $4 AND (p.user_id = $1 OR (p.is_public AND `+tenantScopeSQL("o.tenant_id", "$1")+`))
+				)
+			END,
+			(SELECT COUNT(*) FROM gifts WHERE sender_id = $1 AND recipient_id = $2 AND status = 'pending')
+	`, gift.SenderID, gift.RecipientID, gift.TrackID, gift.PlaylistID).Scan(&recipientFound, &itemFound, &pending)
+	if err != nil {
+		return err
+	}
+	switch {
+	case !recipientFound:
+		return ErrUserNotFound
+	case !itemFound && gift.TrackID != 0:
+		return ErrTrackNotFound
+	case !itemFound:
+		return ErrPlaylistNotFound
+	case pending >= maxPendingGifts:
+		return ErrGiftLimit
+	}
+
+	var id int64
+	err = r.db.QueryRowContext(ctx, `
+		INSERT INTO gifts (sender_id, recipient_id, track_id, playlist_id, message)
+		VALUES ($1, $2, NULLIF($3, 0), NULLIF($4, 0), $5)
+		RETURNING id
+	`, gift.SenderID, gift.RecipientID, gift.TrackID, gift.PlaylistID, gift.Message).Scan(&id)
+	if err != nil {
+		return err
+	}
+	stored, err := r.Get(ctx, gift.RecipientID, id)
+	if err != nil {
+		return err
+	}
+	*gift = *stored
+	return nil
+}
+
+// Get returns a gift sent to recipientID.
+func (r *GiftRepository) Get(ctx context.Context, recipientID uuid.UUID, id int64) (*Gift, error) {
+	gift, err := scanGift(r.db.QueryRowContext(ctx, giftSelect+giftJoins+`
+		WHERE g.recipient_id = $1 AND g.id = $2
+	`, recipientID, id))
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrGiftNotFound
+	}
+	return gift, err
+}
+
+// List returns the gifts sent to recipientID with the given status, or all
+// of them when status is empty, newest first, with their total.
+func (r *GiftRepository) List(ctx context.Context, recipientID uuid.UUID, status string, limit, offset int) ([]Gift, int, error) {
+	rows, err := r.db.QueryContext(ctx, giftSelect+`, COUNT(*) OVER()`+giftJoins+`
+		WHERE g.recipient_id = $1 AND ($2 = '' OR g.status = $2)
+		ORDER BY g.id DESC
+		LIMIT $3 OFFSET $4
+	`, recipientID, status, limit, offset)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+	gifts := []Gift{}
+	var total int
+	for rows.Next() {
+		gift, err := scanGift(rows, &total)
+		if err != nil {
+			return nil, 0, err
+		}
+		gifts = append(gifts, *gift)
+	}
+	return gifts, total, rows.Err()
+}
+
+// Accept adds a gift to the recipient's library and marks it accepted: the
+// track, or every track of the playlist the recipient may see. The library
+// references the catalog tracks, so nothing is copied. It returns the
+// tracks that were not in the library before; accepting again adds none.
+func (r *GiftRepository) Accept(ctx context.Context, recipientID uuid.UUID, id int64) ([]int64, error) {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer tx.Rollback()
+
+	var trackID, playlistID int64
+	err = tx.QueryRowContext(ctx, `
+		SELECT COALESCE(track_id, 0), COALESCE(playlist_id, 0) FROM gifts WHERE recipient_id = $1 AND id = $2 FOR UPDATE
+	`, recipientID, id).Scan(&trackID, &playlistID)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrGiftNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	rows, err := tx.QueryContext(ctx, `
+		INSERT INTO user_library (user_id, track_id, added_at, in_inbox)
+		SELECT u.id, t.id, NOW(), FALSE
+		FROM users u
+		JOIN tracks t ON t.tenant_id IS NOT DISTINCT FROM u.tenant_id
+		WHERE u.id = $1
+			AND (t.id = $2 OR t.id IN (SELECT track_id FROM playlist_tracks WHERE playlist_id = $3))
+		ON CONFLICT (user_id, track_id) DO NOTHING
+		RETURNING track_id
+	`, recipientID, trackID, playlistID)
+	if err != nil {
+		return nil, err
+	}
+	added := []int64{}
+	for rows.Next() {
+		var trackID int64
+		if err := rows.Scan(&trackID); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		added = append(added, trackID)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	if _, err := tx.ExecContext(ctx, `
+		UPDATE gifts SET status = 'accepted', responded_at = NOW()
+		WHERE id = $1 AND status <> 'accepted'
+	`, id); err != nil {
+		return nil, err
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, err
+	}
+	return added, nil
+}
+
+// Dismiss marks a pending gift dismissed. A gift already answered is left
+// as it is.
+func (r *GiftRepository) Dismiss(ctx context.Context, recipientID uuid.UUID, id int64) error {
+	var found bool
+	err := r.db.QueryRowContext(ctx, `
+		WITH dismissed AS (
+			UPDATE gifts SET status = 'dismissed', responded_at = NOW()
+			WHERE recipient_id = $1 AND id = $2 AND status = 'pending'
+		)
+		SELECT EXISTS (SELECT 1 FROM gifts WHERE recipient_id = $1 AND id = $2)
+	`, recipientID, id).Scan(&found)
+	if err != nil {
+		return err
+	}
+	if !found {
+		return ErrGiftNotFound
+	}
+	return nil
+}
@@ -0,0 +1,78 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"slices"
+	"testing"
+)
+
+// TestGiftsAgainstPostgres covers sending a track and a playlist, and
+// accepting them into the recipient's library without copying tracks.
+func TestGiftsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	playlists := NewPlaylistRepository(database)
+	gifts := NewGiftRepository(database)
+
+	sender := seedPlayUser(t, database, "sender@example.test")
+	recipient := seedPlayUser(t, database, "recipient@example.test")
+	song := seedPlayTrack(t, trackRepo, ctx, "Artist", "Song")
+	owned := seedPlayTrack(t, trackRepo, ctx, "Artist", "Owned")
+	if _, err := library.AddTrackToLibrary(ctx, recipient, owned); err != nil {
+		t.Fatalf("AddTrackToLibrary: %v", err)
+	}
+
+	if err := gifts.Send(ctx, &Gift{SenderID: sender, RecipientID: sender, TrackID: song}); !errors.Is(err, ErrGiftSelf) {
+		t.Fatalf("Send to self = %v; want ErrGiftSelf", err)
+	}
+	if err := gifts.Send(ctx, &Gift{SenderID: sender, RecipientID: recipient, TrackID: song + owned}); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("Send of a missing track = %v; want ErrTrackNotFound", err)
+	}
+	private := &Playlist{UserID: recipient, Name: "Private"}
+	if err := playlists.Create(ctx, private); err != nil {
+		t.Fatalf("Create playlist: %v", err)
+	}
+	if err := gifts.Send(ctx, &Gift{SenderID: sender, RecipientID: recipient, PlaylistID: private.ID}); !errors.Is(err, ErrPlaylistNotFound) {
+		t.Fatalf("Send of another user's private playlist = %v; want ErrPlaylistNotFound", err)
+	}
+
+	trackGift := &Gift{SenderID: sender, RecipientID: recipient, TrackID: song, Message: "for you"}
+	if err := gifts.Send(ctx, trackGift); err != nil || trackGift.TrackTitle != "Song" || trackGift.Status != GiftPending {
+		t.Fatalf("Send track = %+v, %v", trackGift, err)
+	}
+	mix := &Playlist{UserID: sender, Name: "Mix"}
+	if err := playlists.Create(ctx, mix); err != nil {
+		t.Fatalf("Create playlist: %v", err)
+	}
+	if _, err := playlists.AddTracks(ctx, mix.ID, []int64{song, owned}); err != nil {
+		t.Fatalf("AddTracks: %v", err)
+	}
+	playlistGift := &Gift{SenderID: sender, RecipientID: recipient, PlaylistID: mix.ID}
+	if err := gifts.Send(ctx, playlistGift); err != nil || playlistGift.PlaylistTracks != 2 || playlistGift.SenderUsername == "" {
+		t.Fatalf("Send playlist = %+v, %v", playlistGift, err)
+	}
+
+	if pending, total, err := gifts.List(ctx, recipient, GiftPending, 10, 0); err != nil || total != 2 || pending[0].ID != playlistGift.ID {
+		t.Fatalf("List pending = %+v, %d, %v", pending, total, err)
+	}
+	if _, err := gifts.Accept(ctx, sender, trackGift.ID); !errors.Is(err, ErrGiftNotFound) {
+		t.Fatalf("Accept by the sender = %v; want ErrGiftNotFound", err)
+	}
+	added, err := gifts.Accept(ctx, recipient, trackGift.ID)
+	if err != nil || !slices.Equal(added, []int64{song}) {
+		t.Fatalf("Accept track = %v, %v", added, err)
+	}
+	if added, err := gifts.Accept(ctx, recipient, playlistGift.ID); err != nil || len(added) != 0 {
+		t.Fatalf("Accept playlist of tracks already in the library = %v, %v", added, err)
+	}
+	if err := gifts.Dismiss(ctx, recipient, playlistGift.ID); err != nil {
+		t.Fatalf("Dismiss: %v", err)
+	}
+	accepted, total, err := gifts.List(ctx, recipient, GiftAccepted, 10, 0)
+	if err != nil || total != 2 || accepted[0].RespondedAt == nil {
+		t.Fatalf("List accepted = %+v, %d, %v; want both, dismissing an accepted gift changes nothing", accepted, total, err)
+	}
+}
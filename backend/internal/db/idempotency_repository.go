@@ -0,0 +1,136 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// IdempotencyRecord is the stored outcome of a request made with an
+// Idempotency-Key. Completed is false while the original request is running.
+type IdempotencyRecord struct {
+	UserID      uuid.UUID
+	Key         string
+	RequestHash string
+	Status      int
+	ContentType string
+	Body        []byte
+	Completed   bool
+	CreatedAt   time.Time
+}
+
+// IdempotencyRepository stores per-user idempotency keys and the responses
+// they produced so retried mutations can be replayed instead of re-executed.
+type IdempotencyRepository struct {
+	db *DB
+}
+
+func NewIdempotencyRepository(db *DB) *IdempotencyRepository {
+	return &IdempotencyRepository{db: db}
+}
+
+// Reserve claims key for userID. reserved is true when the caller now owns the
+// key and must execute the request; otherwise the existing record is returned.
+// Keys older than ttl, and in-progress locks older than lockTimeout (a crashed
+// request), are reclaimed so they never block a client forever.
+func (r *IdempotencyRepository) Reserve(ctx context.Context, userID uuid.UUID, key, requestHash string, ttl, lockTimeout time.Duration) (record *IdempotencyRecord, reserved bool, err error) {
+	query := `
+		INSERT INTO idempotency_keys (user_id, idempotency_key, request_hash)
+		VALUES ($1, $2, $3)
+		ON CONFLICT (user_id, idempotency_key) DO UPDATE
+		SET request_hash = EXCLUDED.request_hash,
+			response_status = NULL,
+			response_content_type = NULL,
+			response_body = NULL,
+			locked_at = NOW(),
+			completed_at = NULL,
+			created_at = NOW()
+		WHERE idempotency_keys.created_at < NOW() - make_interval(secs => $4::double precision)
+			OR (idempotency_keys.completed_at IS NULL
+				AND idempotency_keys.locked_at < NOW() - make_interval(secs => $5::double precision))
+		RETURNING user_id
+	`
+
+	var owner uuid.UUID
+	err = r.db.QueryRowContext(ctx, query, userID, key, requestHash, ttl.Seconds(), lockTimeout.Seconds()).Scan(&owner)
+	if err == nil {
+		return nil, true, nil
+	}
+	if !errors.Is(err, sql.ErrNoRows) {
+		return nil, false, err
+	}
+
+	record, err = r.get(ctx, userID, key)
+	if errors.Is(err, sql.ErrNoRows) {
+		// Released between the insert attempt and the read; report it as still
+		// in progress so the client retries.
+		return &IdempotencyRecord{UserID: userID, Key: key, RequestHash: requestHash}, false, nil
+	}
+	if err != nil {
+		return nil, false, err
+	}
+	return record, false, nil
+}
+
+func (r *IdempotencyRepository) get(ctx context.Context, userID uuid.UUID, key string) (*IdempotencyRecord, error) {
+	query := `
+		SELECT user_id, idempotency_key, request_hash, response_status, response_content_type,
+			response_body, completed_at IS NOT NULL, created_at
+		FROM idempotency_keys
+		WHERE user_id = $1 AND idempotency_key = $2
+	`
+
+	record := &IdempotencyRecord{}
+	var status sql.NullInt64
+	var contentType sql.NullString
+	err := r.db.QueryRowContext(ctx, query, userID, key).Scan(
+		&record.UserID, &record.Key, &record.RequestHash, &status, &contentType,
+		&record.Body, &record.Completed, &record.CreatedAt,
+	)
+	if err != nil {
+		return nil, err
+	}
+	record.Status = int(status.Int64)
+	record.ContentType = contentType.String
+	return record, nil
+}
+
+// Complete stores the response produced for a reserved key.
+func (r *IdempotencyRepository) Complete(ctx context.Context, userID uuid.UUID, key string, status int, contentType string, body []byte) error {
+	query := `
+		UPDATE idempotency_keys
+		SET response_status = $3, response_content_type = $4, response_body = $5, completed_at = NOW()
+		WHERE user_id = $1 AND idempotency_key = $2
+	`
+
+	_, err := r.db.ExecContext(ctx, query, userID, key, status, contentType, body)
+	return err
+}
+
+// Release drops an unfinished reservation so the client can retry the request.
+func (r *IdempotencyRepository) Release(ctx context.Context, userID uuid.UUID, key string) error {
+	query := `
+		DELETE FROM idempotency_keys
+		WHERE user_id = $1 AND idempotency_key = $2 AND completed_at IS NULL
+	`
+
+	_, err := r.db.ExecContext(ctx, query, userID, key)
+	return err
+}
+
+// DeleteExpired removes keys created more than ttl ago.
+func (r *IdempotencyRepository) DeleteExpired(ctx context.Context, ttl time.Duration) (int64, error) {
+	query := `
+		DELETE FROM idempotency_keys
+		WHERE created_at < NOW() - make_interval(secs => $1::double precision)
+	`
+
+	result, err := r.db.ExecContext(ctx, query, ttl.Seconds())
+	if err != nil {
+		return 0, err
+	}
+	return result.RowsAffected()
+}
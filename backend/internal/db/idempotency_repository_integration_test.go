@@ -0,0 +1,65 @@
+package db
+
+import (
+	"context"
+	"testing"
+	"time"
+)
+
+func TestIdempotencyReserveReplayAndReclaimAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewIdempotencyRepository(database)
+	user := seedPlayUser(t, database, "idem@example.test")
+
+	_, reserved, err := repo.Reserve(ctx, user, "key-1", "hash-a", time.Hour, time.Minute)
+	if err != nil || !reserved {
+		t.Fatalf("first Reserve = %v, %v; want reserved", reserved, err)
+	}
+
+	record, reserved, err := repo.Reserve(ctx, user, "key-1", "hash-a", time.Hour, time.Minute)
+	if err != nil || reserved || record.Completed {
+		t.Fatalf("concurrent Reserve = %+v, %v, %v; want in-progress record", record, reserved, err)
+	}
+
+	if err := repo.Complete(ctx, user, "key-1", 201, "application/json", []byte(`{"id":1}`)); err != nil {
+		t.Fatalf("Complete: %v", err)
+	}
+	record, reserved, err = repo.Reserve(ctx, user, "key-1", "hash-a", time.Hour, time.Minute)
+	if err != nil || reserved || !record.Completed || record.Status != 201 || string(record.Body) != `{"id":1}` || record.RequestHash != "hash-a" {
+		t.Fatalf("replay Reserve = %+v, %v, %v", record, reserved, err)
+	}
+
+	// Another user's identical key is independent.
+	other := seedPlayUser(t, database, "other@example.test")
+	if _, reserved, err := repo.Reserve(ctx, other, "key-1", "hash-b", time.Hour, time.Minute); err != nil || !reserved {
+		t.Fatalf("other user Reserve = %v, %v; keys must be scoped per user", reserved, err)
+	}
+
+	// An abandoned lock is reclaimed once it exceeds the lock timeout.
+	if _, reserved, err := repo.Reserve(ctx, user, "key-2", "hash-c", time.Hour, time.Minute); err != nil || !reserved {
+		t.Fatalf("Reserve key-2 = %v, %v", reserved, err)
+	}
+	if _, err := database.Exec(`UPDATE idempotency_keys SET locked_at = NOW() - INTERVAL '2 minutes' WHERE idempotency_key = 'key-2'`); err != nil {
+		t.Fatal(err)
+	}
+	if _, reserved, err := repo.Reserve(ctx, user, "key-2", "hash-c", time.Hour, time.Minute); err != nil || !reserved {
+		t.Fatalf("stale lock Reserve = %v, %v; want reclaimed", reserved, err)
+	}
+
+	// Released reservations can be retried immediately.
+	if err := repo.Release(ctx, user, "key-2"); err != nil {
+		t.Fatalf("Release: %v", err)
+	}
+	if _, reserved, err := repo.Reserve(ctx, user, "key-2", "hash-c", time.Hour, time.Minute); err != nil || !reserved {
+		t.Fatalf("Reserve after Release = %v, %v", reserved, err)
+	}
+
+	if _, err := database.Exec(`UPDATE idempotency_keys SET created_at = NOW() - INTERVAL '2 days' WHERE user_id = $1 AND idempotency_key = 'key-1'`, user); err != nil {
+		t.Fatal(err)
+	}
+	deleted, err := repo.DeleteExpired(ctx, 24*time.Hour)
+	if err != nil || deleted != 1 {
+		t.Fatalf("DeleteExpired = %d, %v; want 1", deleted, err)
+	}
+}
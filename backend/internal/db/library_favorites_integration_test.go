@@ -82,11 +82,11 @@ func TestFavoritesAgainstPostgres(t *testing.T) {
 		t.Fatalf("add t2 to library: %v", err)
 	}
 
-	// Like is idempotent.
-	if err := libRepo.AddFavorite(ctx, user, t1); err != nil {
+	// Like is idempotent and keeps the context of the first like.
+	if err := libRepo.AddFavorite(ctx, user, t1, "playlist", "42"); err != nil {
 		t.Fatalf("like t1: %v", err)
 	}
-	if err := libRepo.AddFavorite(ctx, user, t1); err != nil {
+	if err := libRepo.AddFavorite(ctx, user, t1, "", ""); err != nil {
 		t.Fatalf("re-like t1 (should be idempotent): %v", err)
 	}
 	if liked, err := libRepo.IsFavorite(ctx, user, t1); err != nil || !liked {
@@ -104,6 +104,9 @@ func TestFavoritesAgainstPostgres(t *testing.T) {
 	likedByID := map[int64]bool{}
 	for _, lt := range tracks {
 		likedByID[lt.ID] = lt.IsLiked
+		if lt.ID == t1 && (lt.LikedContextType.String != "playlist" || lt.LikedContextID.String != "42") {
+			t.Errorf("t1 liked from %v/%v; want playlist/42", lt.LikedContextType, lt.LikedContextID)
+		}
 	}
 	if !likedByID[t1] {
 		t.Errorf("t1 is_liked = false; want true")
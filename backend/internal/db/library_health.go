@@ -0,0 +1,224 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"strconv"
+	"strings"
+
+	"github.com/google/uuid"
+)
+
+// Problems the library health report counts.
+const (
+	HealthIssueMissingArtwork  = "missing_artwork"
+	HealthIssueMissingMBLink   = "missing_mb_link"
+	HealthIssueCorruptFile     = "corrupt_file"
+	HealthIssueZeroDuration    = "zero_duration"
+	HealthIssueDuplicate       = "duplicate"
+	HealthIssueLowBitrate      = "low_bitrate"
+	HealthIssueUnmatchedImport = "unmatched_import"
+)
+
+// LibraryHealthTrackIssues are the health issues found on library tracks, in
+// report order. HealthIssueUnmatchedImport is reported after them.
+var LibraryHealthTrackIssues = []string{
+	HealthIssueMissingArtwork,
+	HealthIssueMissingMBLink,
+	HealthIssueCorruptFile,
+	HealthIssueZeroDuration,
+	HealthIssueDuplicate,
+	HealthIssueLowBitrate,
+}
+
+// LowBitrateKbps is the bitrate below which a file is reported as low quality.
+const LowBitrateKbps = 128
+
+var ErrUnknownHealthIssue = errors.New("unknown library health issue")
+
+// libraryHealthConditions select the tracks with each issue from
+// libraryHealthTracksCTE. Artwork counts as present when the track has a
+// stored image or the listing can show a Cover Art Archive image for the
+// release. A file is corrupt when it is
+// stored but probing it never yielded a codec. Duplicates share a MusicBrainz
+// recording or, when unmatched, the same title and artist.
+var libraryHealthConditions = map[string]string{
+	HealthIssueMissingArtwork: "t.artwork_id IS NULL AND NULLIF(btrim(t.cover_art_url), '') IS NULL AND t.mb_release_id IS NULL",
+	HealthIssueMissingMBLink:  "t.mb_recording_id IS NULL",
+	HealthIssueCorruptFile:    "NULLIF(btrim(t.storage_key), '') IS NOT NULL AND t.audio_quality_probe_attempted_at IS NOT NULL AND NULLIF(btrim(t.codec), '') IS NULL",
+	HealthIssueZeroDuration:   "COALESCE(t.duration_ms, 0) <= 0",
+	HealthIssueDuplicate:      "t.duplicate_count > 1",
+	HealthIssueLowBitrate:     "t.bitrate_kbps > 0 AND t.bitrate_kbps < " + strconv.Itoa(LowBitrateKbps),
+}
+
+const libraryHealthTracksCTE = `
+	WITH library_tracks AS (
+		SELECT t.*,
+			   COALESCE(t.mb_recording_id::text, lower(btrim(t.title)) || E'\x1f' || lower(btrim(COALESCE(t.artist, '')))) AS duplicate_key,
+			   COUNT(*) OVER (PARTITION BY COALESCE(t.mb_recording_id::text, lower(btrim(t.title)) || E'\x1f' || lower(btrim(COALESCE(t.artist, ''))))) AS duplicate_count
+		FROM user_library ul
+		JOIN tracks t ON t.id = ul.track_id
+		WHERE ul.user_id = $1
+	)`
+
+// LibraryHealthReport counts the problems in a user's library.
+type LibraryHealthReport struct {
+	TotalTracks int
+	// Counts maps each issue in LibraryHealthTrackIssues and
+	// HealthIssueUnmatchedImport to the number of affected items.
+	Counts map[string]int
+}
+
+// LibraryHealthTrack is a library track with a health issue.
+type LibraryHealthTrack struct {
+	TrackID        int64
+	Title          string
+	Artist         sql.NullString
+	Album          sql.NullString
+	DurationMs     sql.NullInt32
+	BitrateKbps    sql.NullInt32
+	DuplicateCount int
+}
+
+// UnmatchedImportItem is a playlist import entry that never resolved to a
+// track.
+type UnmatchedImportItem struct {
+	ID          int64
+	ImportJobID uuid.UUID
+	PlaylistID  int64
+	Title       string
+	Artist      string
+	SourceURL   string
+	Error       sql.NullString
+}
+
+// LibraryHealth counts each health issue across the user's library and their
+// playlist imports.
+func (r *LibraryRepository) LibraryHealth(ctx context.Context, userID uuid.UUID) (LibraryHealthReport, error) {
+	columns := []string{"COUNT(*)"}
+	for _, issue := range LibraryHealthTrackIssues {
+		columns = append(columns, "COUNT(*) FILTER (WHERE "+libraryHealthConditions[issue]+")")
+	}
+	counts := make([]int, len(columns))
+	dest := make([]any, len(columns))
+	for i := range counts {
+		dest[i] = &counts[i]
+	}
+	if err := r.db.QueryRowContext(ctx, libraryHealthTracksCTE+`
+		SELECT `+strings.Join(columns, ", ")+`
+		FROM library_tracks t
+	`, userID).Scan(dest...); err != nil {
+		return LibraryHealthReport{}, err
+	}
+
+	report := LibraryHealthReport{TotalTracks: counts[0], Counts: make(map[string]int, len(columns))}
+	for i, issue := range LibraryHealthTrackIssues {
+		report.Counts[issue] = counts[i+1]
+	}
+	var unmatched int
+	if err := r.db.QueryRowContext(ctx, `
+		SELECT COUNT(*)
+		FROM playlist_import_items i
+		JOIN playlist_import_jobs j ON j.id = i.import_job_id
+		WHERE j.user_id = $1 AND i.status = 'failed' AND i.track_id IS NULL
+	`, userID).Scan(&unmatched); err != nil {
+		return LibraryHealthReport{}, err
+	}
+	report.Counts[HealthIssueUnmatchedImport] = unmatched
+	return report, nil
+}
+
+// LibraryHealthTracks pages through the library tracks with issue, which must
+// be one of LibraryHealthTrackIssues. Duplicates are grouped together.
+func (r *LibraryRepository) LibraryHealthTracks(ctx context.Context, userID uuid.UUID, issue string, limit, offset int) ([]LibraryHealthTrack, int, error) {
+	condition, ok := libraryHealthConditions[issue]
+	if !ok {
+		return nil, 0, ErrUnknownHealthIssue
+	}
+	orderBy := "t.id"
+	if issue == HealthIssueDuplicate {
+		orderBy = "t.duplicate_key, t.id"
+	}
+	rows, err := r.db.ReadQueryContext(ctx, libraryHealthTracksCTE+`
+		SELECT t.id, t.title, t.artist, t.album, t.duration_ms, t.bitrate_kbps, t.duplicate_count,
+			   COUNT(*) OVER() AS total_count
+		FROM library_tracks t
+		WHERE `+condition+`
+		ORDER BY `+orderBy+`
+		LIMIT $2 OFFSET $3
+	`, userID, limit, offset)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+
+	var tracks []LibraryHealthTrack
+	var total int
+	for rows.Next() {
+		var track LibraryHealthTrack
+		if err := rows.Scan(&track.TrackID, &track.Title, &track.Artist, &track.Album, &track.DurationMs, &track.BitrateKbps, &track.DuplicateCount, &total); err != nil {
+			return nil, 0, err
+		}
+		tracks = append(tracks, track)
+	}
+	return tracks, total, rows.Err()
+}
+
+// LibraryHealthTrackIDs returns the ids of every library track with issue, in
+// track order, for bulk repairs.
+func (r *LibraryRepository) LibraryHealthTrackIDs(ctx context.Context, userID uuid.UUID, issue string) ([]int64, error) {
+	condition, ok := libraryHealthConditions[issue]
+	if !ok {
+		return nil, ErrUnknownHealthIssue
+	}
+	rows, err := r.db.ReadQueryContext(ctx, libraryHealthTracksCTE+`
+		SELECT t.id
+		FROM library_tracks t
+		WHERE `+condition+`
+		ORDER BY t.id
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	return ids, rows.Err()
+}
+
+// UnmatchedImportItems pages through the user's failed playlist import
+// entries, newest first.
+func (r *LibraryRepository) UnmatchedImportItems(ctx context.Context, userID uuid.UUID, limit, offset int) ([]UnmatchedImportItem, int, error) {
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT i.id, i.import_job_id, j.playlist_id, i.title, i.artist, i.source_url, i.error,
+			   COUNT(*) OVER() AS total_count
+		FROM playlist_import_items i
+		JOIN playlist_import_jobs j ON j.id = i.import_job_id
+		WHERE j.user_id = $1 AND i.status = 'failed' AND i.track_id IS NULL
+		ORDER BY i.updated_at DESC, i.id DESC
+		LIMIT $2 OFFSET $3
+	`, userID, limit, offset)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+
+	var items []UnmatchedImportItem
+	var total int
+	for rows.Next() {
+		var item UnmatchedImportItem
+		if err := rows.Scan(&item.ID, &item.ImportJobID, &item.PlaylistID, &item.Title, &item.Artist, &item.SourceURL, &item.Error, &total); err != nil {
+			return nil, 0, err
+		}
+		items = append(items, item)
+	}
+	return items, total, rows.Err()
+}
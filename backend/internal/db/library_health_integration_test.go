@@ -0,0 +1,129 @@
+package db
+
+import (
+	"errors"
+	"reflect"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestLibraryHealthCountsAndListsIssues(t *testing.T) {
+	database, ctx := newLibraryQueryTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	user := seedQueryUser(t, database, "owner@test.local")
+
+	healthy := seedQueryTrack(t, trackRepo, ctx, "Radiohead", "Airbag", "OK Computer", 284000)
+	original := seedQueryTrack(t, trackRepo, ctx, "Radiohead", "Lucky", "OK Computer", 259000)
+	reissue := seedQueryTrack(t, trackRepo, ctx, "Radiohead", "Lucky", "Lucky EP", 259000)
+	broken := seedQueryTrack(t, trackRepo, ctx, "Unknown", "Untitled", "", 0)
+	for _, id := range []int64{healthy, original, reissue, broken} {
+		if _, err := libRepo.AddTrackToLibrary(ctx, user, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+	recording := uuid.New()
+	if _, err := database.Exec(`
+		UPDATE tracks SET mb_recording_id = CASE WHEN id = $1 THEN $4::uuid ELSE $5::uuid END,
+			mb_release_id = $5::uuid, bitrate_kbps = 320, codec = 'mp3'
+		WHERE id IN ($1, $2, $3)
+	`, healthy, original, reissue, uuid.New(), recording); err != nil {
+		t.Fatalf("match tracks: %v", err)
+	}
+	if _, err := database.Exec(`
+		UPDATE tracks SET storage_key = 'audio/broken', bitrate_kbps = 64,
+			audio_quality_probe_attempted_at = NOW()
+		WHERE id = $1
+	`, broken); err != nil {
+		t.Fatalf("mark broken track: %v", err)
+	}
+
+	var playlistID int64
+	if err := database.QueryRow(`INSERT INTO playlists (user_id, name) VALUES ($1, $2) RETURNING id`, user, "Imported").Scan(&playlistID); err != nil {
+		t.Fatalf("seed playlist: %v", err)
+	}
+	importJobID := uuid.New()
+	if _, err := database.Exec(`INSERT INTO playlist_import_jobs (id, user_id, playlist_id, source_url) VALUES ($1, $2, $3, $4)`, importJobID, user, playlistID, "https://example.test/playlist"); err != nil {
+		t.Fatalf("seed import job: %v", err)
+	}
+	if _, err := database.Exec(`
+		INSERT INTO playlist_import_items (import_job_id, source_index, playlist_position, title, status, error)
+		VALUES ($1, 0, 0, 'Gone', 'failed', 'unavailable'), ($1, 1, 1, 'Found', 'imported', NULL)
+	`, importJobID); err != nil {
+		t.Fatalf("seed import items: %v", err)
+	}
+
+	report, err := libRepo.LibraryHealth(ctx, user)
+	if err != nil {
+		t.Fatalf("LibraryHealth: %v", err)
+	}
+	want := map[string]int{
+		HealthIssueMissingArtwork:  1,
+		HealthIssueMissingMBLink:   1,
+		HealthIssueCorruptFile:     1,
+		HealthIssueZeroDuration:    1,
+		HealthIssueDuplicate:       2,
+		HealthIssueLowBitrate:      1,
+		HealthIssueUnmatchedImport: 1,
+	}
+	if report.TotalTracks != 4 {
+		t.Fatalf("TotalTracks = %d; want 4", report.TotalTracks)
+	}
+	for issue, count := range want {
+		if report.Counts[issue] != count {
+			t.Errorf("%s count = %d; want %d", issue, report.Counts[issue], count)
+		}
+	}
+
+	dupes, total, err := libRepo.LibraryHealthTracks(ctx, user, HealthIssueDuplicate, 10, 0)
+	if err != nil {
+		t.Fatalf("LibraryHealthTracks(duplicate): %v", err)
+	}
+	if total != 2 || len(dupes) != 2 || dupes[0].TrackID != original || dupes[1].TrackID != reissue || dupes[0].DuplicateCount != 2 {
+		t.Fatalf("duplicates = %+v (total %d); want tracks %d and %d", dupes, total, original, reissue)
+	}
+	if _, _, err := libRepo.LibraryHealthTracks(ctx, user, "haunted", 10, 0); !errors.Is(err, ErrUnknownHealthIssue) {
+		t.Fatalf("unknown issue error = %v; want ErrUnknownHealthIssue", err)
+	}
+
+	items, total, err := libRepo.UnmatchedImportItems(ctx, user, 10, 0)
+	if err != nil {
+		t.Fatalf("UnmatchedImportItems: %v", err)
+	}
+	if total != 1 || len(items) != 1 || items[0].Title != "Gone" || items[0].PlaylistID != playlistID || items[0].Error.String != "unavailable" {
+		t.Fatalf("unmatched imports = %+v (total %d)", items, total)
+	}
+}
+
+func TestLibraryHealthTrackIDsListsEveryAffectedTrack(t *testing.T) {
+	database, ctx := newLibraryQueryTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	user := seedQueryUser(t, database, "owner@test.local")
+
+	healthy := seedQueryTrack(t, trackRepo, ctx, "Radiohead", "Airbag", "OK Computer", 284000)
+	broken := seedQueryTrack(t, trackRepo, ctx, "Radiohead", "Lucky", "OK Computer", 259000)
+	for _, id := range []int64{healthy, broken} {
+		if _, err := libRepo.AddTrackToLibrary(ctx, user, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+	if _, err := database.Exec(`UPDATE tracks SET storage_key = 'audio/broken', audio_quality_probe_attempted_at = NOW() WHERE id = $1`, broken); err != nil {
+		t.Fatalf("mark broken track: %v", err)
+	}
+
+	ids, err := libRepo.LibraryHealthTrackIDs(ctx, user, HealthIssueCorruptFile)
+	if err != nil || !reflect.DeepEqual(ids, []int64{broken}) {
+		t.Fatalf("corrupt ids = %v, %v; want [%d]", ids, err, broken)
+	}
+	if err := trackRepo.ReplaceStoredAudio(ctx, broken, "audio/fixed.mp3", 1024, "mp3", 320, 44100, 2, "audio/mpeg"); err != nil {
+		t.Fatalf("ReplaceStoredAudio: %v", err)
+	}
+	if ids, err = libRepo.LibraryHealthTrackIDs(ctx, user, HealthIssueCorruptFile); err != nil || len(ids) != 0 {
+		t.Fatalf("corrupt ids after re-download = %v, %v; want none", ids, err)
+	}
+	if _, err := libRepo.LibraryHealthTrackIDs(ctx, user, "haunted"); !errors.Is(err, ErrUnknownHealthIssue) {
+		t.Fatalf("unknown issue error = %v", err)
+	}
+}
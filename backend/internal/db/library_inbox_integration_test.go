@@ -0,0 +1,63 @@
+package db
+
+import (
+	"testing"
+)
+
+// TestLibraryInboxAgainstPostgres covers the inbox round trip: tracks added to
+// the inbox are listed apart from reviewed ones, approval only moves the
+// caller's inbox tracks, and the library version changes with it.
+func TestLibraryInboxAgainstPostgres(t *testing.T) {
+	database, ctx := newFavoritesTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+
+	user := seedFavUser(t, database, "inbox@test.local")
+	kept := seedFavTrack(t, trackRepo, ctx, "Artist A", "Reviewed Song")
+	pending := seedFavTrack(t, trackRepo, ctx, "Artist B", "Pending Song")
+	if _, err := libRepo.AddTrackToLibrary(ctx, user, kept); err != nil {
+		t.Fatalf("add reviewed track: %v", err)
+	}
+	if _, err := libRepo.AddTrackToInbox(ctx, user, pending); err != nil {
+		t.Fatalf("add inbox track: %v", err)
+	}
+
+	inbox := true
+	tracks, total, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{Inbox: &inbox})
+	if err != nil {
+		t.Fatalf("GetUserLibrary(inbox): %v", err)
+	}
+	if total != 1 || tracks[0].ID != pending || !tracks[0].InInbox {
+		t.Fatalf("inbox listing = %+v (total %d); want only the pending track", tracks, total)
+	}
+	inInbox, err := libRepo.TracksInInbox(ctx, user, []int64{kept, pending})
+	if err != nil {
+		t.Fatalf("TracksInInbox: %v", err)
+	}
+	if inInbox[kept] || !inInbox[pending] {
+		t.Fatalf("TracksInInbox = %v; want only %d", inInbox, pending)
+	}
+
+	before, err := libRepo.LibraryVersion(ctx, user)
+	if err != nil {
+		t.Fatalf("LibraryVersion: %v", err)
+	}
+	approved, err := libRepo.ApproveInboxTracks(ctx, user, []int64{kept, pending})
+	if err != nil {
+		t.Fatalf("ApproveInboxTracks: %v", err)
+	}
+	if len(approved) != 1 || approved[0] != pending {
+		t.Fatalf("approved = %v; want [%d]", approved, pending)
+	}
+	after, err := libRepo.LibraryVersion(ctx, user)
+	if err != nil {
+		t.Fatalf("LibraryVersion: %v", err)
+	}
+	if before == after {
+		t.Fatal("library version did not change when the inbox was approved")
+	}
+
+	if _, total, err = libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{Inbox: &inbox}); err != nil || total != 0 {
+		t.Fatalf("inbox listing after approval: total %d, err %v; want empty", total, err)
+	}
+}
@@ -0,0 +1,71 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// TestLibraryMigrationWritesAgainstPostgres covers the repository methods
+// library migrations rely on: locating tracks by source URL or MBID, and
+// idempotent rating and play-count imports.
+func TestLibraryMigrationWritesAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	playRepo := NewPlayEventRepository(database)
+
+	user := seedPlayUser(t, database, "migrate@example.test")
+	byPath := seedPlayTrack(t, trackRepo, ctx, "Low", "Starfire")
+	byMBID := seedPlayTrack(t, trackRepo, ctx, "Low", "Lullaby")
+	recordingID := uuid.New()
+	if _, err := database.Exec(`UPDATE tracks SET source_url = 'file:///music/Low/01 Starfire.flac' WHERE id = $1`, byPath); err != nil {
+		t.Fatalf("set source url: %v", err)
+	}
+	if _, err := database.Exec(`UPDATE tracks SET mb_recording_id = $1 WHERE id = $2`, recordingID, byMBID); err != nil {
+		t.Fatalf("set recording id: %v", err)
+	}
+
+	if id, err := trackRepo.LocateTrack(ctx, "file:///music/Low/01 Starfire.flac", recordingID.String()); err != nil || id != byPath {
+		t.Fatalf("LocateTrack(path) = %d, %v, want %d", id, err, byPath)
+	}
+	if id, err := trackRepo.LocateTrack(ctx, "file:///elsewhere/Lullaby.flac", recordingID.String()); err != nil || id != byMBID {
+		t.Fatalf("LocateTrack(mbid) = %d, %v, want %d", id, err, byMBID)
+	}
+	if _, err := trackRepo.LocateTrack(ctx, "file:///elsewhere/Missing.flac", ""); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("LocateTrack(missing) error = %v, want ErrTrackNotFound", err)
+	}
+
+	for _, rating := range []int{3, 5} {
+		if err := libRepo.SetRating(ctx, user, byPath, rating); err != nil {
+			t.Fatalf("SetRating(%d): %v", rating, err)
+		}
+	}
+	var rating int
+	if err := database.QueryRow(`SELECT rating FROM track_ratings WHERE user_id = $1 AND track_id = $2`, user, byPath).Scan(&rating); err != nil || rating != 5 {
+		t.Fatalf("rating = %d, %v, want 5", rating, err)
+	}
+	if err := libRepo.SetRating(ctx, user, byPath, 6); err == nil {
+		t.Fatal("SetRating(6) succeeded, want a check violation")
+	}
+
+	lastPlayed := time.Date(2024, 5, 1, 20, 30, 0, 0, time.UTC)
+	for _, tt := range []struct{ count, want int }{{3, 3}, {3, 0}, {5, 2}, {1, 0}} {
+		added, err := playRepo.ImportPlays(ctx, user, byPath, "home", tt.count, lastPlayed)
+		if err != nil || added != tt.want {
+			t.Fatalf("ImportPlays(%d) = %d, %v, want %d", tt.count, added, err, tt.want)
+		}
+	}
+	var plays int
+	var latest time.Time
+	if err := database.QueryRow(`SELECT COUNT(*), MAX(played_at) FROM play_events WHERE user_id = $1 AND context_type = 'import'`, user).Scan(&plays, &latest); err != nil || plays != 5 || !latest.Equal(lastPlayed) {
+		t.Fatalf("imported plays = %d at %v, %v", plays, latest, err)
+	}
+	if added, err := playRepo.ImportPlays(ctx, user, byPath, "other", 1, time.Time{}); err != nil || added != 1 {
+		t.Fatalf("ImportPlays from another source = %d, %v, want 1", added, err)
+	}
+}
@@ -31,7 +31,7 @@ func newLibraryQueryTestDB(t *testing.T) (*DB, context.Context) {
 	if err := database.Migrate(); err != nil {
 		t.Fatalf("migrate test database: %v", err)
 	}
-	if _, err := database.Exec("TRUNCATE TABLE track_favorites, user_library, tracks, users RESTART IDENTITY CASCADE"); err != nil {
+	if _, err := database.Exec("TRUNCATE TABLE track_favorites, user_library, tracks, users, artist_sort_names RESTART IDENTITY CASCADE"); err != nil {
 		t.Fatalf("truncate test database: %v", err)
 	}
 
@@ -223,3 +223,140 @@ func TestLibraryQueryExpansionAgainstPostgres(t *testing.T) {
 		t.Fatalf("no-match query returned %d rows (total %d); want empty", len(none), noneTotal)
 	}
 }
+
+// TestLibraryReleaseDateRangeAgainstPostgres checks that partial release dates
+// sort by precision and that a year-only upper bound covers the whole year.
+func TestLibraryReleaseDateRangeAgainstPostgres(t *testing.T) {
+	database, ctx := newLibraryQueryTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	user := seedQueryUser(t, database, "owner@test.local")
+
+	dates := []string{"1989-12-31", "1990", "1995-06", "1999-12-31", "2000-01-01", ""}
+	ids := make([]int64, len(dates))
+	for i, date := range dates {
+		ids[i] = seedQueryTrack(t, trackRepo, ctx, "Artist", "Track "+date, "Album", 100000)
+		if date != "" {
+			parsed, _ := ParsePartialDate(date)
+			year, month, day := partialDateArgs(parsed)
+			if _, err := database.Exec(`UPDATE tracks SET release_year = $1, release_month = $2, release_day = $3 WHERE id = $4`,
+				year, month, day, ids[i]); err != nil {
+				t.Fatalf("set release date on %d: %v", ids[i], err)
+			}
+		}
+		if _, err := libRepo.AddTrackToLibrary(ctx, user, ids[i]); err != nil {
+			t.Fatalf("add %d to library: %v", ids[i], err)
+		}
+	}
+
+	nineties, total, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{
+		SortBy:       "release_date",
+		SortOrder:    "asc",
+		ReleasedFrom: PartialDate{Year: 1990},
+		ReleasedTo:   PartialDate{Year: 1999},
+	})
+	if err != nil {
+		t.Fatalf("nineties: %v", err)
+	}
+	want := []int64{ids[1], ids[2], ids[3]}
+	if got := idOrder(nineties); total != len(want) || len(got) != len(want) || got[0] != want[0] || got[1] != want[1] || got[2] != want[2] {
+		t.Fatalf("nineties = %v (total %d); want %v", got, total, want)
+	}
+	if nineties[1].ReleaseDate != (PartialDate{Year: 1995, Month: 6}) {
+		t.Fatalf("release date = %+v; want 1995-06", nineties[1].ReleaseDate)
+	}
+
+	all, _, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{SortBy: "release_date", SortOrder: "desc"})
+	if err != nil {
+		t.Fatalf("release_date desc: %v", err)
+	}
+	if got := idOrder(all); got[0] != ids[4] || got[len(got)-1] != ids[5] {
+		t.Fatalf("release_date desc = %v; want newest first and undated last", got)
+	}
+}
+
+// TestLibraryArtistListingIncludesCreditedTracks checks that an artist page
+// lists tracks the artist is only featured on.
+func TestLibraryArtistListingIncludesCreditedTracks(t *testing.T) {
+	database, ctx := newLibraryQueryTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	user := seedQueryUser(t, database, "owner@test.local")
+
+	own := seedQueryTrack(t, trackRepo, ctx, "Guest", "Solo", "Album", 100000)
+	featured := seedQueryTrack(t, trackRepo, ctx, "Host feat. Guest", "Duet", "Album", 200000)
+	other := seedQueryTrack(t, trackRepo, ctx, "Host", "Alone", "Album", 300000)
+	for _, id := range []int64{own, featured, other} {
+		if _, err := libRepo.AddTrackToLibrary(ctx, user, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+	uncredited, err := libRepo.LibraryVersion(ctx, user)
+	if err != nil {
+		t.Fatalf("LibraryVersion before credits: %v", err)
+	}
+	if err := trackRepo.ReplaceArtistCredits(ctx, featured, ParseArtistCredits("Host feat. Guest", "Duet"), FieldSourceTag); err != nil {
+		t.Fatalf("ReplaceArtistCredits: %v", err)
+	}
+	if credited, err := libRepo.LibraryVersion(ctx, user); err != nil || credited == uncredited {
+		t.Fatalf("LibraryVersion after credits = %q, %v; want a change from %q", credited, err, uncredited)
+	}
+
+	rows, total, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{Artist: "Guest", SortBy: "duration", SortOrder: "asc"})
+	if err != nil {
+		t.Fatalf("artist=Guest: %v", err)
+	}
+	if got := idOrder(rows); total != 2 || len(got) != 2 || got[0] != own || got[1] != featured {
+		t.Fatalf("artist=Guest = %v (total %d); want [%d %d]", got, total, own, featured)
+	}
+	want := []TrackArtist{{Name: "Host", Role: ArtistRoleMain}, {Name: "Guest", Role: ArtistRoleFeatured}}
+	if len(rows[1].Artists) != 2 || rows[1].Artists[0] != want[0] || rows[1].Artists[1] != want[1] {
+		t.Fatalf("artists = %+v; want %+v", rows[1].Artists, want)
+	}
+}
+
+// TestLibraryArtistAndAlbumSortIgnoreArticles checks that artist ordering
+// prefers stored MusicBrainz sort names and otherwise skips leading articles,
+// and that album ordering skips articles too.
+func TestLibraryArtistAndAlbumSortIgnoreArticles(t *testing.T) {
+	database, ctx := newLibraryQueryTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	user := seedQueryUser(t, database, "owner@test.local")
+
+	beatles := seedQueryTrack(t, trackRepo, ctx, "The Beatles", "Help!", "A Hard Day's Night", 100000)
+	bowie := seedQueryTrack(t, trackRepo, ctx, "David Bowie", "Heroes", "Low", 200000)
+	abba := seedQueryTrack(t, trackRepo, ctx, "ABBA", "SOS", "The Album", 300000)
+	for _, id := range []int64{beatles, bowie, abba} {
+		if _, err := libRepo.AddTrackToLibrary(ctx, user, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+
+	byArtist, _, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{SortBy: "artist", SortOrder: "asc"})
+	if err != nil {
+		t.Fatalf("artist sort: %v", err)
+	}
+	if got := idOrder(byArtist); got[0] != abba || got[1] != beatles || got[2] != bowie {
+		t.Fatalf("artist sort = %v; want [%d %d %d]", got, abba, beatles, bowie)
+	}
+
+	if err := trackRepo.RecordArtistSortName(ctx, "David Bowie", "Bowie, David", ""); err != nil {
+		t.Fatalf("RecordArtistSortName: %v", err)
+	}
+	byArtist, _, err = libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{SortBy: "artist", SortOrder: "asc"})
+	if err != nil {
+		t.Fatalf("artist sort with sort name: %v", err)
+	}
+	if got := idOrder(byArtist); got[0] != abba || got[1] != bowie || got[2] != beatles {
+		t.Fatalf("artist sort with sort name = %v; want [%d %d %d]", got, abba, bowie, beatles)
+	}
+
+	byAlbum, _, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{SortBy: "album", SortOrder: "asc"})
+	if err != nil {
+		t.Fatalf("album sort: %v", err)
+	}
+	if got := idOrder(byAlbum); got[0] != abba || got[1] != beatles || got[2] != bowie {
+		t.Fatalf("album sort = %v; want [%d %d %d]", got, abba, beatles, bowie)
+	}
+}
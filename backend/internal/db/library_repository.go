@@ -8,6 +8,7 @@ import (
 	"time"
 
 	"github.com/google/uuid"
+	"github.com/lib/pq"
 )
 
 var ErrTrackAlreadyInLibrary = errors.New("track already in library")
@@ -26,7 +27,21 @@ type LibraryTrack struct {
 	AnalysisSummary   json.RawMessage
 	AnalysisUpdatedAt sql.NullTime
 	IsLiked           bool
+	LikedContextType  sql.NullString
+	LikedContextID    sql.NullString
 	Genre             sql.NullString
+	ReleaseDate       PartialDate
+	Artists           []TrackArtist
+	Aliases           []TrackAlias
+	// InInbox is set while a newly ingested track waits for review.
+	InInbox bool
+	// The quality measurements are unset until the track is measured.
+	QualityScore     sql.NullInt32
+	DRScore          sql.NullInt32
+	SpectralCutoffHz sql.NullInt32
+	UpscaleSuspected sql.NullBool
+	// Mood is nil until the track's mood features are measured.
+	Mood *MoodFeatures
 }
 
 type LibraryRepository struct {
@@ -52,16 +67,19 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 	args := []interface{}{userID}
 	argIndex := 2
 
-	// Use full-text search for search queries (sanitized; see buildPrefixTSQuery).
+	// Use full-text search for search queries (normalized and sanitized; see searchTSQuery).
 	if opts.Search != "" {
-		tsQuery := buildPrefixTSQuery(opts.Search)
+		tsQuery := searchTSQuery(opts.Search)
 		if tsQuery == "" {
 			// The search term had no searchable lexemes (e.g. punctuation only). Return
 			// no matches rather than silently dropping the filter and listing the whole
 			// library — this mirrors the track/artist/release search paths.
 			return []LibraryTrack{}, 0, nil
 		}
-		baseCondition += " AND to_tsvector('english', COALESCE(t.title, '') || ' ' || COALESCE(t.artist, '') || ' ' || COALESCE(t.album, '')) @@ to_tsquery('english', $" + itoa(argIndex) + ")"
+		// The caller's note on a track is searched along with its metadata.
+		baseCondition += " AND (" + trackSearchDocumentSQL("t.") + " @@ to_tsquery('english', $" + itoa(argIndex) + ")" +
+			" OR EXISTS (SELECT 1 FROM user_notes n WHERE n.user_id = ul.user_id AND n.track_id = t.id" +
+			" AND to_tsvector('english', n.search_text) @@ to_tsquery('english', $" + itoa(argIndex) + ")))"
 		args = append(args, tsQuery)
 		argIndex++
 	}
@@ -86,8 +104,9 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 	}
 
 	// Exact-match artist/album filters back the local artist/album listing pages.
+	// An artist page also lists the tracks the artist is featured on or remixed.
 	if opts.Artist != "" {
-		baseCondition += " AND t.artist = $" + itoa(argIndex)
+		baseCondition += " AND (t.artist = $" + itoa(argIndex) + " OR EXISTS (SELECT 1 FROM track_artists tar WHERE tar.track_id = t.id AND tar.name = $" + itoa(argIndex) + "))"
 		args = append(args, opts.Artist)
 		argIndex++
 	}
@@ -107,6 +126,48 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 		baseCondition += " AND EXISTS (SELECT 1 FROM track_favorites tf WHERE tf.user_id = ul.user_id AND tf.track_id = t.id)"
 	}
 
+	// Inbox filter: true lists only tracks awaiting review, false only
+	// reviewed ones.
+	if opts.Inbox != nil {
+		baseCondition += " AND ul.in_inbox = $" + itoa(argIndex)
+		args = append(args, *opts.Inbox)
+		argIndex++
+	}
+
+	// Release-date range. Bounds compare at their own precision, so a "1990" to
+	// "1999" range covers every date in the nineties, and tracks with no
+	// release date never match.
+	if !opts.ReleasedFrom.IsZero() {
+		baseCondition += " AND t.release_year IS NOT NULL AND " + releaseDateKeySQL + " >= $" + itoa(argIndex)
+		args = append(args, opts.ReleasedFrom.sortKey())
+		argIndex++
+	}
+	if !opts.ReleasedTo.IsZero() {
+		baseCondition += " AND t.release_year IS NOT NULL AND " + releaseDateKeySQL + " <= $" + itoa(argIndex)
+		args = append(args, opts.ReleasedTo.endKey())
+		argIndex++
+	}
+
+	// Quality filters leave out tracks that have not been measured.
+	if opts.QualityMax != nil {
+		baseCondition += " AND tq.score <= $" + itoa(argIndex)
+		args = append(args, *opts.QualityMax)
+		argIndex++
+	}
+	if opts.DRMax != nil {
+		baseCondition += " AND tq.dr_score <= $" + itoa(argIndex)
+		args = append(args, *opts.DRMax)
+		argIndex++
+	}
+	if opts.UpscaleSuspected != nil {
+		baseCondition += " AND tq.upscale_suspected = $" + itoa(argIndex)
+		args = append(args, *opts.UpscaleSuspected)
+		argIndex++
+	}
+	if condition := moodCondition(opts.Mood); condition != "" {
+		baseCondition += " AND " + condition
+	}
+
 	// Determine sort order
 	orderBy := "ul.added_at DESC" // default
 	switch opts.SortBy {
@@ -118,15 +179,21 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 		}
 	case "title":
 		if opts.SortOrder == "desc" {
-			orderBy = "t.title DESC"
+			orderBy = r.db.collateSQL("t.title") + " DESC"
 		} else {
-			orderBy = "t.title ASC"
+			orderBy = r.db.collateSQL("t.title") + " ASC"
 		}
 	case "artist":
 		if opts.SortOrder == "desc" {
-			orderBy = "t.artist DESC NULLS LAST"
+			orderBy = r.db.artistSortSQL("t.artist") + " DESC NULLS LAST"
 		} else {
-			orderBy = "t.artist ASC NULLS LAST"
+			orderBy = r.db.artistSortSQL("t.artist") + " ASC NULLS LAST"
+		}
+	case "album":
+		if opts.SortOrder == "desc" {
+			orderBy = r.db.albumSortSQL("t.album") + " DESC NULLS LAST, t.id DESC"
+		} else {
+			orderBy = r.db.albumSortSQL("t.album") + " ASC NULLS LAST, t.id ASC"
 		}
 	case "duration":
 		if opts.SortOrder == "desc" {
@@ -134,6 +201,18 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 		} else {
 			orderBy = "t.duration_ms ASC NULLS LAST"
 		}
+	case "release_date":
+		if opts.SortOrder == "desc" {
+			orderBy = releaseDateKeySQL + " DESC NULLS LAST, t.id DESC"
+		} else {
+			orderBy = releaseDateKeySQL + " ASC NULLS LAST, t.id ASC"
+		}
+	case "quality":
+		if opts.SortOrder == "desc" {
+			orderBy = "tq.score DESC NULLS LAST, t.id DESC"
+		} else {
+			orderBy = "tq.score ASC NULLS LAST, t.id ASC"
+		}
 	}
 
 	// Single query with window function for total count (eliminates separate COUNT query)
@@ -143,23 +222,31 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
 			   t.codec, t.bitrate_kbps, t.sample_rate_hz, t.channels, t.content_type,
 			   t.metadata_json, t.metadata_status, t.metadata_confidence, t.metadata_provenance,
-			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at, ul.added_at,
+			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at, ul.added_at, ul.in_inbox,
 			   ta.status, COALESCE(` + analysisCompactSummaryExpression + `, '{}'::jsonb) AS analysis_summary,
 			   COALESCE(` + analysisCompactOverridesExpression + `, '{}'::jsonb) AS analysis_overrides,
 			   ta.updated_at AS analysis_updated_at,
-			   EXISTS(SELECT 1 FROM track_favorites tf WHERE tf.user_id = ul.user_id AND tf.track_id = t.id) AS is_liked,
-			   t.genre,
+			   fav.track_id IS NOT NULL AS is_liked, fav.context_type, fav.context_id,
+			   t.genre, t.release_year, t.release_month, t.release_day,
+			   (SELECT json_agg(json_build_object('name', tar.name, 'role', tar.role, 'mb_artist_id', tar.mb_artist_id) ORDER BY tar.position)
+				FROM track_artists tar WHERE tar.track_id = t.id) AS artists,
+			   (SELECT json_agg(json_build_object('field', tal.field, 'name', tal.name, 'locale', tal.locale, 'primary', tal.is_primary))
+				FROM track_aliases tal WHERE tal.track_id = t.id) AS aliases,
+			   tq.score, tq.dr_score, tq.spectral_cutoff_hz, tq.upscale_suspected,
+			   t.energy, t.valence, t.danceability,
 			   COUNT(*) OVER() as total_count
 		FROM user_library ul
 		JOIN tracks t ON ul.track_id = t.id
 		LEFT JOIN track_analysis ta ON ta.track_id = t.id
+		LEFT JOIN track_quality tq ON tq.track_id = t.id
+		LEFT JOIN track_favorites fav ON fav.user_id = ul.user_id AND fav.track_id = t.id
 		WHERE ` + baseCondition + `
 		ORDER BY ` + orderBy + `
 		LIMIT $` + itoa(argIndex) + ` OFFSET $` + itoa(argIndex+1)
 
 	args = append(args, opts.Limit, opts.Offset)
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, args...)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, args...)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -170,18 +257,37 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 	for rows.Next() {
 		var lt LibraryTrack
 		var analysisOverrides json.RawMessage
+		var releaseYear, releaseMonth, releaseDay sql.NullInt32
+		var artists, aliases []byte
+		var energy, valence, danceability sql.NullFloat64
 		err := rows.Scan(
 			&lt.ID, &lt.IdentityHash, &lt.Title, &lt.Artist, &lt.Album, &lt.DurationMs, &lt.Version,
 			&lt.MBRecordingID, &lt.MBReleaseID, &lt.MBArtistID, &lt.MBVerified,
 			&lt.SourceURL, &lt.SourceType, &lt.StorageKey, &lt.FileSizeBytes,
 			&lt.Codec, &lt.BitrateKbps, &lt.SampleRateHz, &lt.Channels, &lt.ContentType,
 			&lt.MetadataJSON, &lt.MetadataStatus, &lt.MetadataConfidence, &lt.MetadataProvenance,
-			&lt.CoverArtURL, &lt.MetadataUserEdited, &lt.CreatedAt, &lt.UpdatedAt, &lt.AddedAt,
-			&lt.AnalysisStatus, &lt.AnalysisSummary, &analysisOverrides, &lt.AnalysisUpdatedAt, &lt.IsLiked, &lt.Genre, &total,
+			&lt.CoverArtURL, &lt.MetadataUserEdited, &lt.CreatedAt, &lt.UpdatedAt, &lt.AddedAt, &lt.InInbox,
+			&lt.AnalysisStatus, &lt.AnalysisSummary, &analysisOverrides, &lt.AnalysisUpdatedAt,
+			&lt.IsLiked, &lt.LikedContextType, &lt.LikedContextID, &lt.Genre,
+			&releaseYear, &releaseMonth, &releaseDay, &artists, &aliases,
+			&lt.QualityScore, &lt.DRScore, &lt.SpectralCutoffHz, &lt.UpscaleSuspected,
+			&energy, &valence, &danceability, &total,
 		)
 		if err != nil {
 			return nil, 0, err
 		}
+		lt.Mood = moodFeaturesFromColumns(energy, valence, danceability)
+		lt.ReleaseDate = partialDateFromColumns(releaseYear, releaseMonth, releaseDay)
+		if len(artists) > 0 {
+			if err := json.Unmarshal(artists, &lt.Artists); err != nil {
+				return nil, 0, err
+			}
+		}
+		if len(aliases) > 0 {
+			if err := json.Unmarshal(aliases, &lt.Aliases); err != nil {
+				return nil, 0, err
+			}
+		}
 		lt.AnalysisSummary, _ = projectCompactAnalysis(lt.AnalysisSummary, analysisOverrides)
 		tracks = append(tracks, lt)
 	}
@@ -193,21 +299,62 @@ func (r *LibraryRepository) GetUserLibrary(ctx context.Context, userID uuid.UUID
 	return tracks, total, nil
 }
 
-// AddTrackToLibrary adds a track to a user's library.
+// AddTrackToLibrary adds a track to a user's library. Only tracks the user
+// may see can be added (see TrackRepository.GetByIDForUser); any other track
+// is reported as ErrTrackNotFound, so no library spans tenants.
 func (r *LibraryRepository) AddTrackToLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*LibraryEntry, error) {
+	return r.addTrackToLibrary(ctx, userID, trackID, false)
+}
+
+// AddTrackToInbox adds a track to a user's library in the inbox, where it
+// waits for review. It reports errors as AddTrackToLibrary does.
+func (r *LibraryRepository) AddTrackToInbox(ctx context.Context, userID uuid.UUID, trackID int64) (*LibraryEntry, error) {
+	return r.addTrackToLibrary(ctx, userID, trackID, true)
+}
+
+func (r *LibraryRepository) addTrackToLibrary(ctx context.Context, userID uuid.UUID, trackID int64, inbox bool) (*LibraryEntry, error) {
+	// A transaction, so the events triggers see the context's actor.
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer func() { _ = tx.Rollback() }()
+	entry, err := addLibraryTrack(ctx, tx, userID, trackID, inbox)
+	if err != nil {
+		return nil, err
+	}
+	return entry, tx.Commit()
+}
+
+func addLibraryTrack(ctx context.Context, q queryRower, userID uuid.UUID, trackID int64, inbox bool) (*LibraryEntry, error) {
 	query := `
-		INSERT INTO user_library (user_id, track_id, added_at)
-		VALUES ($1, $2, NOW())
+		INSERT INTO user_library (user_id, track_id, added_at, in_inbox)
+		SELECT u.id, t.id, NOW(), $3
+		FROM users u
+		JOIN tracks t ON t.id = $2 AND t.tenant_id IS NOT DISTINCT FROM u.tenant_id
+		WHERE u.id = $1
 		ON CONFLICT (user_id, track_id) DO NOTHING
 		RETURNING user_id, track_id, added_at
 	`
 
 	var entry LibraryEntry
-	err := r.db.QueryRowContext(ctx, query, userID, trackID).Scan(&entry.UserID, &entry.TrackID, &entry.AddedAt)
+	err := q.QueryRowContext(ctx, query, userID, trackID, inbox).Scan(&entry.UserID, &entry.TrackID, &entry.AddedAt)
 	if err != nil {
+		if isForeignKeyViolation(err) {
+			// The track was released while this add waited on its row.
+			return nil, ErrTrackNotFound
+		}
 		if errors.Is(err, sql.ErrNoRows) {
-			// ON CONFLICT DO NOTHING returns no rows if already exists
-			return nil, ErrTrackAlreadyInLibrary
+			// No row comes back both when the track is already in the
+			// library (ON CONFLICT DO NOTHING) and when it is out of reach.
+			inLibrary, err := trackInLibrary(ctx, q, userID, trackID)
+			if err != nil {
+				return nil, err
+			}
+			if inLibrary {
+				return nil, ErrTrackAlreadyInLibrary
+			}
+			return nil, ErrTrackNotFound
 		}
 		return nil, err
 	}
@@ -239,8 +386,145 @@ func (r *LibraryRepository) RemoveTrackFromLibrary(ctx context.Context, userID u
 	return nil
 }
 
+// TracksInInbox reports which of trackIDs wait in the user's library inbox.
+func (r *LibraryRepository) TracksInInbox(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]bool, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT track_id FROM user_library
+		WHERE user_id = $1 AND track_id = ANY($2::bigint[]) AND in_inbox
+	`, userID, pq.Array(trackIDs))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	found := make(map[int64]bool)
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		found[id] = true
+	}
+	return found, rows.Err()
+}
+
+// ApproveInboxTracks moves trackIDs out of the user's library inbox and
+// returns the ones that were in it.
+func (r *LibraryRepository) ApproveInboxTracks(ctx context.Context, userID uuid.UUID, trackIDs []int64) ([]int64, error) {
+	// A transaction, so the events triggers see the context's actor.
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer func() { _ = tx.Rollback() }()
+	rows, err := tx.QueryContext(ctx, `
+		UPDATE user_library SET in_inbox = FALSE
+		WHERE user_id = $1 AND track_id = ANY($2::bigint[]) AND in_inbox
+		RETURNING track_id
+	`, userID, pq.Array(trackIDs))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	approved := []int64{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		approved = append(approved, id)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	return approved, tx.Commit()
+}
+
+// ReleasedTrack is a track deleted because its last reference went away.
+// StorageKey is its stored object, which the caller deletes once the row is
+// gone; it is empty when there is none or another track still points at it.
+// OriginalStorageKey is the unprocessed file kept when silence was trimmed
+// or loudness normalized.
+type ReleasedTrack struct {
+	ID                 int64
+	StorageKey         string
+	OriginalStorageKey string
+}
+
+// ReleaseTrackFromLibrary removes a track from the user's library and, when
+// that was the track's last reference (see TrackReferences), deletes the
+// track too, returning it. Otherwise it returns nil.
+//
+// Everything happens in one transaction holding the track row. Adding the
+// track to a library or playlist, or linking a download to it, needs that row
+// through its foreign key, so a concurrent reference either commits first and
+// is counted, or waits and then fails because the track is gone.
+func (r *LibraryRepository) ReleaseTrackFromLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*ReleasedTrack, error) {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer tx.Rollback()
+
+	var storageKey sql.NullString
+	err = tx.QueryRowContext(ctx, `SELECT storage_key FROM tracks WHERE id = $1 FOR UPDATE`, trackID).Scan(&storageKey)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrTrackNotInLibrary
+	}
+	if err != nil {
+		return nil, err
+	}
+	result, err := tx.ExecContext(ctx, `DELETE FROM user_library WHERE user_id = $1 AND track_id = $2`, userID, trackID)
+	if err != nil {
+		return nil, err
+	}
+	if n, err := result.RowsAffected(); err != nil {
+		return nil, err
+	} else if n == 0 {
+		return nil, ErrTrackNotInLibrary
+	}
+
+	refs, err := scanTrackReferences(ctx, tx, trackID)
+	if err != nil {
+		return nil, err
+	}
+	if refs.Total() > 0 {
+		return nil, tx.Commit()
+	}
+	var originalKey sql.NullString
+	err = tx.QueryRowContext(ctx, `
+		SELECT COALESCE(
+			(SELECT original_storage_key FROM track_silence_trims WHERE track_id = $1),
+			(SELECT original_storage_key FROM track_loudness_normalizations WHERE track_id = $1)
+		)
+	`, trackID).Scan(&originalKey)
+	if err != nil {
+		return nil, err
+	}
+	if _, err := tx.ExecContext(ctx, `DELETE FROM tracks WHERE id = $1`, trackID); err != nil {
+		return nil, err
+	}
+	released := &ReleasedTrack{ID: trackID, OriginalStorageKey: originalKey.String}
+	if storageKey.Valid && storageKey.String != "" {
+		var shared bool
+		if err := tx.QueryRowContext(ctx, `SELECT EXISTS(SELECT 1 FROM tracks WHERE storage_key = $1)`, storageKey.String).Scan(&shared); err != nil {
+			return nil, err
+		}
+		if !shared {
+			released.StorageKey = storageKey.String
+		}
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, err
+	}
+	return released, nil
+}
+
 // IsTrackInLibrary checks if a track is in a user's library.
 func (r *LibraryRepository) IsTrackInLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (bool, error) {
+	return trackInLibrary(ctx, r.db, userID, trackID)
+}
+
+func trackInLibrary(ctx context.Context, q queryRower, userID uuid.UUID, trackID int64) (bool, error) {
 	query := `
 		SELECT EXISTS(
 			SELECT 1 FROM user_library
@@ -249,7 +533,7 @@ func (r *LibraryRepository) IsTrackInLibrary(ctx context.Context, userID uuid.UU
 	`
 
 	var exists bool
-	err := r.db.QueryRowContext(ctx, query, userID, trackID).Scan(&exists)
+	err := q.QueryRowContext(ctx, query, userID, trackID).Scan(&exists)
 	if err != nil {
 		return false, err
 	}
@@ -257,16 +541,280 @@ func (r *LibraryRepository) IsTrackInLibrary(ctx context.Context, userID uuid.UU
 	return exists, nil
 }
 
+// TracksInLibrary reports which of trackIDs are in the user's library, in one
+// query; the batched form of IsTrackInLibrary for callers checking a page.
+func (r *LibraryRepository) TracksInLibrary(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]bool, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT track_id FROM user_library
+		WHERE user_id = $1 AND track_id = ANY($2::bigint[])
+	`, userID, pq.Array(trackIDs))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	inLibrary := make(map[int64]bool, len(trackIDs))
+	for rows.Next() {
+		var trackID int64
+		if err := rows.Scan(&trackID); err != nil {
+			return nil, err
+		}
+		inLibrary[trackID] = true
+	}
+	return inLibrary, rows.Err()
+}
+
+// LibraryAlbum is an album of the user's library tracks by one artist.
+type LibraryAlbum struct {
+	Album       string
+	MBReleaseID *uuid.UUID
+	Tracks      int
+	DurationMs  int64
+	SizeBytes   int64
+}
+
+// ArtistLibraryAlbums lists the albums the user keeps tracks from by the
+// MusicBrainz artist, with how many tracks of each are in the library and
+// their total duration and size, read from the cached library albums.
+func (r *LibraryRepository) ArtistLibraryAlbums(ctx context.Context, userID, artistMBID uuid.UUID) ([]LibraryAlbum, error) {
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT album, mb_release_id, track_count, duration_ms, size_bytes
+		FROM library_albums
+		WHERE user_id = $1 AND mb_artist_id = $2
+		ORDER BY album, mb_release_id
+	`, userID, artistMBID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var albums []LibraryAlbum
+	for rows.Next() {
+		var album LibraryAlbum
+		if err := rows.Scan(&album.Album, &album.MBReleaseID, &album.Tracks, &album.DurationMs, &album.SizeBytes); err != nil {
+			return nil, err
+		}
+		albums = append(albums, album)
+	}
+	return albums, rows.Err()
+}
+
+// LibraryRecordings maps the MusicBrainz recordings the user keeps in their
+// library to the library track holding each. Recordings not in the library
+// are absent.
+func (r *LibraryRepository) LibraryRecordings(ctx context.Context, userID uuid.UUID, recordingIDs []uuid.UUID) (map[uuid.UUID]int64, error) {
+	found := make(map[uuid.UUID]int64)
+	if len(recordingIDs) == 0 {
+		return found, nil
+	}
+	ids := make([]string, len(recordingIDs))
+	for i, id := range recordingIDs {
+		ids[i] = id.String()
+	}
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT t.mb_recording_id, MIN(t.id)
+		FROM user_library ul
+		JOIN tracks t ON t.id = ul.track_id
+		WHERE ul.user_id = $1 AND t.mb_recording_id = ANY($2::uuid[])
+		GROUP BY t.mb_recording_id
+	`, userID, pq.Array(ids))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	for rows.Next() {
+		var recordingID uuid.UUID
+		var trackID int64
+		if err := rows.Scan(&recordingID, &trackID); err != nil {
+			return nil, err
+		}
+		found[recordingID] = trackID
+	}
+	return found, rows.Err()
+}
+
+// ArtistTrack is a library track by a MusicBrainz artist, with the user's
+// rating (0 when unrated) and last play for shuffling.
+type ArtistTrack struct {
+	ID           int64
+	Title        string
+	Artist       sql.NullString
+	Album        sql.NullString
+	DurationMs   sql.NullInt32
+	MBArtistID   uuid.UUID
+	Rating       int
+	LastPlayedAt sql.NullTime
+}
+
+// LibraryTracksByArtists picks up to perArtist random library tracks by each
+// of the MusicBrainz artists. Tracks still in the library inbox are only
+// picked with includeInbox, and a mood from MoodNames picks only measured
+// tracks in that mood.
+func (r *LibraryRepository) LibraryTracksByArtists(ctx context.Context, userID uuid.UUID, artistIDs []uuid.UUID, perArtist int, includeInbox bool, mood string) ([]ArtistTrack, error) {
+	if len(artistIDs) == 0 || perArtist <= 0 {
+		return nil, nil
+	}
+	moodFilter := ""
+	if condition := moodCondition(mood); condition != "" {
+		moodFilter = " AND " + condition
+	}
+	ids := make([]string, len(artistIDs))
+	for i, id := range artistIDs {
+		ids[i] = id.String()
+	}
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT id, title, artist, album, duration_ms, mb_artist_id, rating, last_played_at
+		FROM (
+			SELECT t.id, t.title, t.artist, t.album, t.duration_ms, t.mb_artist_id,
+				   COALESCE(tr.rating, 0) AS rating, ` + lastPlayedAtExpression + ` AS last_played_at,
+				   row_number() OVER (PARTITION BY t.mb_artist_id ORDER BY random()) AS pick
+			FROM user_library ul
+			JOIN tracks t ON t.id = ul.track_id
+			LEFT JOIN track_ratings tr ON tr.user_id = ul.user_id AND tr.track_id = t.id
+			WHERE ul.user_id = $1 AND t.mb_artist_id = ANY($2::uuid[])
+			  AND ($4 OR NOT ul.in_inbox)
+			  AND t.id NOT IN (` + frequentlySkippedTracks + `)` + moodFilter + `
+		) picked
+		WHERE pick <= $3
+	`, userID, pq.Array(ids), perArtist, includeInbox)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var tracks []ArtistTrack
+	for rows.Next() {
+		var t ArtistTrack
+		if err := rows.Scan(&t.ID, &t.Title, &t.Artist, &t.Album, &t.DurationMs, &t.MBArtistID, &t.Rating, &t.LastPlayedAt); err != nil {
+			return nil, err
+		}
+		tracks = append(tracks, t)
+	}
+	return tracks, rows.Err()
+}
+
+// lastPlayedAtExpression is the user's ($1) last play of track t.
+const lastPlayedAtExpression = `(SELECT MAX(pe.played_at) FROM play_events pe WHERE pe.user_id = $1 AND pe.track_id = t.id)`
+
+// maxShuffleCandidates bounds how many tracks ShuffleCandidates samples.
+const maxShuffleCandidates = 2000
+
+// ShuffleTrack is a track to shuffle, with the user's rating (0 when unrated)
+// and last play.
+type ShuffleTrack struct {
+	ID           int64
+	Title        string
+	Artist       sql.NullString
+	Album        sql.NullString
+	DurationMs   sql.NullInt32
+	Rating       int
+	LastPlayedAt sql.NullTime
+}
+
+// ShuffleCandidates returns the tracks to shuffle into a queue: the user's
+// library, or the tracks of playlistID when it is set. Library tracks still
+// in the inbox are only included with includeInbox. Larger libraries are
+// sampled at random down to maxShuffleCandidates tracks.
+func (r *LibraryRepository) ShuffleCandidates(ctx context.Context, userID uuid.UUID, playlistID int64, includeInbox bool) ([]ShuffleTrack, error) {
+	source := `
+		FROM user_library ul
+		JOIN tracks t ON t.id = ul.track_id
+		WHERE ul.user_id = $1 AND ($2 OR NOT ul.in_inbox)`
+	args := []any{userID, includeInbox}
+	if playlistID > 0 {
+		source = `
+		FROM playlist_tracks pt
+		JOIN tracks t ON t.id = pt.track_id
+		WHERE pt.playlist_id = $2`
+		args = []any{userID, playlistID}
+	}
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT t.id, t.title, t.artist, t.album, t.duration_ms,
+			   COALESCE((SELECT tr.rating FROM track_ratings tr WHERE tr.user_id = $1 AND tr.track_id = t.id), 0),
+			   `+lastPlayedAtExpression+source+`
+		ORDER BY random()
+		LIMIT `+itoa(maxShuffleCandidates), args...)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	tracks := []ShuffleTrack{}
+	for rows.Next() {
+		var t ShuffleTrack
+		if err := rows.Scan(&t.ID, &t.Title, &t.Artist, &t.Album, &t.DurationMs, &t.Rating, &t.LastPlayedAt); err != nil {
+			return nil, err
+		}
+		tracks = append(tracks, t)
+	}
+	return tracks, rows.Err()
+}
+
+// LibraryTrackKey is what a library track can be matched on when importing
+// listening history from elsewhere. SourceURLs holds the track's source URL
+// and those of its other known sources.
+type LibraryTrackKey struct {
+	ID            int64
+	Artist        string
+	Title         string
+	MBRecordingID string
+	SourceURLs    []string
+}
+
+// LibraryTrackKeys returns the match keys of every track in the user's
+// library, oldest track first.
+func (r *LibraryRepository) LibraryTrackKeys(ctx context.Context, userID uuid.UUID) ([]LibraryTrackKey, error) {
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT t.id, COALESCE(t.artist, ''), t.title, COALESCE(t.mb_recording_id::text, ''),
+			   ARRAY(
+				   SELECT url FROM (
+					   SELECT t.source_url AS url
+					   UNION
+					   SELECT ts.source_url FROM track_sources ts WHERE ts.track_id = t.id
+				   ) urls
+				   WHERE COALESCE(url, '') <> ''
+			   )
+		FROM user_library ul
+		JOIN tracks t ON t.id = ul.track_id
+		WHERE ul.user_id = $1
+		ORDER BY t.created_at, t.id
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var keys []LibraryTrackKey
+	for rows.Next() {
+		var key LibraryTrackKey
+		if err := rows.Scan(&key.ID, &key.Artist, &key.Title, &key.MBRecordingID, pq.Array(&key.SourceURLs)); err != nil {
+			return nil, err
+		}
+		keys = append(keys, key)
+	}
+	return keys, rows.Err()
+}
+
 // AddFavorite marks a track as liked ("Liked Songs") for a user. Idempotent:
-// liking an already-liked track is a no-op success. Favorites are membership +
-// timestamp only and do NOT change user_library membership.
-func (r *LibraryRepository) AddFavorite(ctx context.Context, userID uuid.UUID, trackID int64) error {
+// liking an already-liked track is a no-op success that keeps the original
+// like time and context. contextType and contextID record where the like
+// happened (a playlist, album, radio seed, ...); empty strings are stored as
+// SQL NULL. Favorites do NOT change user_library membership.
+func (r *LibraryRepository) AddFavorite(ctx context.Context, userID uuid.UUID, trackID int64, contextType, contextID string) error {
+	return addFavorite(ctx, r.db, userID, trackID, contextType, contextID)
+}
+
+func addFavorite(ctx context.Context, q querier, userID uuid.UUID, trackID int64, contextType, contextID string) error {
 	query := `
-		INSERT INTO track_favorites (user_id, track_id, created_at)
-		VALUES ($1, $2, NOW())
+		INSERT INTO track_favorites (user_id, track_id, created_at, context_type, context_id)
+		VALUES ($1, $2, NOW(), $3, $4)
 		ON CONFLICT (user_id, track_id) DO NOTHING
 	`
-	_, err := r.db.ExecContext(ctx, query, userID, trackID)
+	_, err := q.ExecContext(ctx, query, userID, trackID,
+		sql.NullString{String: contextType, Valid: contextType != ""},
+		sql.NullString{String: contextID, Valid: contextID != ""},
+	)
 	return err
 }
 
@@ -278,6 +826,19 @@ func (r *LibraryRepository) RemoveFavorite(ctx context.Context, userID uuid.UUID
 	return err
 }
 
+// SetRating records a user's 1-5 star rating of a track, replacing any earlier
+// rating.
+func (r *LibraryRepository) SetRating(ctx context.Context, userID uuid.UUID, trackID int64, rating int) error {
+	query := `
+		INSERT INTO track_ratings (user_id, track_id, rating, updated_at)
+		VALUES ($1, $2, $3, NOW())
+		ON CONFLICT (user_id, track_id) DO UPDATE
+		SET rating = EXCLUDED.rating, updated_at = EXCLUDED.updated_at
+	`
+	_, err := r.db.ExecContext(ctx, query, userID, trackID, rating)
+	return err
+}
+
 // IsFavorite reports whether a track is liked by a user.
 func (r *LibraryRepository) IsFavorite(ctx context.Context, userID uuid.UUID, trackID int64) (bool, error) {
 	var exists bool
@@ -287,18 +848,152 @@ func (r *LibraryRepository) IsFavorite(ctx context.Context, userID uuid.UUID, tr
 	return exists, err
 }
 
+// LibraryVersion returns a token that changes whenever the user's library,
+// favorites or track notes change, or a track in the library gets new
+// metadata, analysis or quality score.
+// Handlers derive list ETags from it without loading the list.
+func (r *LibraryRepository) LibraryVersion(ctx context.Context, userID uuid.UUID) (string, error) {
+	query := `
+		SELECT
+			(SELECT COUNT(*) FROM user_library WHERE user_id = $1),
+			(SELECT COUNT(*) FROM track_favorites WHERE user_id = $1),
+			(SELECT COUNT(*) FROM user_library WHERE user_id = $1 AND in_inbox),
+			(SELECT COUNT(*) FROM user_notes WHERE user_id = $1 AND track_id IS NOT NULL),
+			(SELECT COUNT(*)
+			 FROM user_library ul
+			 JOIN track_quality tq ON tq.track_id = ul.track_id
+			 WHERE ul.user_id = $1),
+			(SELECT MAX(added_at) FROM user_library WHERE user_id = $1),
+			(SELECT MAX(created_at) FROM track_favorites WHERE user_id = $1),
+			(SELECT MAX(updated_at) FROM user_notes WHERE user_id = $1 AND track_id IS NOT NULL),
+			(SELECT MAX(GREATEST(t.updated_at, ta.updated_at, tq.analyzed_at))
+			 FROM user_library ul
+			 JOIN tracks t ON ul.track_id = t.id
+			 LEFT JOIN track_analysis ta ON ta.track_id = t.id
+			 LEFT JOIN track_quality tq ON tq.track_id = t.id
+			 WHERE ul.user_id = $1)
+	`
+
+	// Read from the same pool as GetUserLibrary: a version taken from the
+	// primary could be newer than a lagging replica's list and pin stale data
+	// behind a fresh ETag.
+	rows, err := r.db.ReadQueryContext(ctx, query, userID)
+	if err != nil {
+		return "", err
+	}
+	defer rows.Close()
+	if !rows.Next() {
+		if err := rows.Err(); err != nil {
+			return "", err
+		}
+		return "", sql.ErrNoRows
+	}
+
+	var libraryCount, favoriteCount, inboxCount, noteCount, qualityCount int64
+	var addedAt, likedAt, notedAt, tracksUpdatedAt sql.NullTime
+	if err := rows.Scan(&libraryCount, &favoriteCount, &inboxCount, &noteCount, &qualityCount, &addedAt, &likedAt, &notedAt, &tracksUpdatedAt); err != nil {
+		return "", err
+	}
+	return versionToken([]int64{libraryCount, favoriteCount, inboxCount, noteCount, qualityCount}, addedAt, likedAt, notedAt, tracksUpdatedAt), nil
+}
+
+// NewTracksFromLibraryArtists returns catalog tracks added within the trailing
+// window of days by artists the user keeps in their library or has liked,
+// excluding tracks already in the library, newest first. Artist names match
+// case-insensitively.
+func (r *LibraryRepository) NewTracksFromLibraryArtists(ctx context.Context, userID uuid.UUID, days, limit int) ([]Track, error) {
+	if days <= 0 {
+		days = 30
+	}
+	if limit <= 0 {
+		limit = 20
+	}
+	if limit > 100 {
+		limit = 100
+	}
+
+	query := `
+		WITH artists AS (
+			SELECT lower(lt.artist) AS artist
+			FROM user_library ul
+			JOIN tracks lt ON lt.id = ul.track_id
+			WHERE ul.user_id = $1 AND COALESCE(lt.artist, '') <> ''
+			UNION
+			SELECT lower(ft.artist)
+			FROM track_favorites tf
+			JOIN tracks ft ON ft.id = tf.track_id
+			WHERE tf.user_id = $1 AND COALESCE(ft.artist, '') <> ''
+		)
+		SELECT t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
+			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
+			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
+			   t.codec, t.bitrate_kbps, t.sample_rate_hz, t.channels, t.content_type,
+			   t.metadata_json, t.metadata_status, t.metadata_confidence, t.metadata_provenance,
+			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at,
+			   ta.status, COALESCE(` + analysisCompactSummaryExpression + `, '{}'::jsonb),
+			   COALESCE(` + analysisCompactOverridesExpression + `, '{}'::jsonb),
+			   ta.updated_at
+		FROM tracks t
+		JOIN artists a ON a.artist = lower(t.artist)
+		LEFT JOIN track_analysis ta ON ta.track_id = t.id
+		WHERE t.created_at >= NOW() - make_interval(days => $2)
+		  AND NOT EXISTS (SELECT 1 FROM user_library ul WHERE ul.user_id = $1 AND ul.track_id = t.id)
+		  AND t.id NOT IN (` + frequentlySkippedTracks + `)
+		ORDER BY t.created_at DESC, t.id DESC
+		LIMIT $3
+	`
+
+	rows, err := r.db.ReadQueryContext(ctx, query, userID, days, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var tracks []Track
+	for rows.Next() {
+		var t Track
+		var analysisOverrides json.RawMessage
+		if err := rows.Scan(
+			&t.ID, &t.IdentityHash, &t.Title, &t.Artist, &t.Album, &t.DurationMs, &t.Version,
+			&t.MBRecordingID, &t.MBReleaseID, &t.MBArtistID, &t.MBVerified,
+			&t.SourceURL, &t.SourceType, &t.StorageKey, &t.FileSizeBytes,
+			&t.Codec, &t.BitrateKbps, &t.SampleRateHz, &t.Channels, &t.ContentType,
+			&t.MetadataJSON, &t.MetadataStatus, &t.MetadataConfidence, &t.MetadataProvenance,
+			&t.CoverArtURL, &t.MetadataUserEdited, &t.CreatedAt, &t.UpdatedAt,
+			&t.AnalysisStatus, &t.AnalysisSummary, &analysisOverrides, &t.AnalysisUpdatedAt,
+		); err != nil {
+			return nil, err
+		}
+		t.AnalysisSummary, _ = projectCompactAnalysis(t.AnalysisSummary, analysisOverrides)
+		tracks = append(tracks, t)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	return tracks, nil
+}
+
 // LibraryQueryOptions contains options for querying the user library.
 type LibraryQueryOptions struct {
-	Limit      int
-	Offset     int
-	SortBy     string // "added_at", "title", "artist", "duration"
-	SortOrder  string // "asc", "desc"
-	Search     string // Search query for title/artist/album
-	MBVerified *bool  // Filter by MusicBrainz verification status
-	Liked      bool   // When true, return only liked tracks
-	Genre      string // Exact genre match; "Unknown" matches NULL/empty genre
-	Artist     string // Exact artist match (local artist listing)
-	Album      string // Exact album match (local album listing)
+	Limit        int
+	Offset       int
+	SortBy       string      // "added_at", "title", "artist", "album", "duration", "release_date"
+	SortOrder    string      // "asc", "desc"
+	Search       string      // Search query for title/artist/album
+	MBVerified   *bool       // Filter by MusicBrainz verification status
+	Liked        bool        // When true, return only liked tracks
+	Inbox        *bool       // Filter by library inbox state
+	Genre        string      // Exact genre match; "Unknown" matches NULL/empty genre
+	Artist       string      // Exact artist match, including featured and remixer credits (local artist listing)
+	Album        string      // Exact album match (local album listing)
+	ReleasedFrom PartialDate // Inclusive lower bound on release date
+	ReleasedTo   PartialDate // Inclusive upper bound, covering the bound's whole year or month
+	// Quality filters match measured tracks only (see TrackQuality).
+	QualityMax       *int  // Highest quality score, 0-100
+	DRMax            *int  // Highest dynamic range score
+	UpscaleSuspected *bool // Filter by suspected lossy-to-lossless upscale
+	// Mood is one of MoodNames; it matches measured tracks only.
+	Mood string
 }
 
 // itoa converts an integer to a string (simple implementation to avoid importing strconv)
@@ -0,0 +1,227 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrListenLaterItemNotFound = errors.New("listen later item not found")
+
+// ListenLaterSource is a discovery result saved before it was downloaded.
+type ListenLaterSource struct {
+	Provider     string
+	SourceURL    string
+	Title        string
+	Artist       string
+	DurationMs   int
+	ThumbnailURL string
+}
+
+// ListenLaterItem is an entry of a user's listen later queue: a track, or a
+// discovery result when Track is nil.
+type ListenLaterItem struct {
+	ID      int64
+	Track   *Track
+	Source  *ListenLaterSource
+	AddedAt time.Time
+}
+
+// ListenLaterRepository keeps each user's listen later queue, a lightweight
+// list of things to hear soon that is not a playlist. Entries leave the queue
+// when they are played to the end (see Finish) or removed.
+type ListenLaterRepository struct {
+	db *DB
+}
+
+func NewListenLaterRepository(db *DB) *ListenLaterRepository {
+	return &ListenLaterRepository{db: db}
+}
+
+// AddTrack saves a track to the user's queue. Saving a track that is already
+// queued keeps its place.
+func (r *ListenLaterRepository) AddTrack(ctx context.Context, userID uuid.UUID, track *Track) (*ListenLaterItem, error) {
+	item := &ListenLaterItem{Track: track}
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO listen_later (user_id, track_id)
+		VALUES ($1, $2)
+		ON CONFLICT (user_id, track_id) WHERE track_id IS NOT NULL DO UPDATE SET added_at = listen_later.added_at
+		RETURNING id, added_at
+	`, userID, track.ID).Scan(&item.ID, &item.AddedAt)
+	if err != nil {
+		return nil, err
+	}
+	return item, nil
+}
+
+// AddSource saves a discovery result to the user's queue, keyed by its
+// source URL. Saving it again refreshes the stored title and artwork but
+// keeps its place.
+func (r *ListenLaterRepository) AddSource(ctx context.Context, userID uuid.UUID, source ListenLaterSource) (*ListenLaterItem, error) {
+	item := &ListenLaterItem{Source: &source}
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO listen_later (user_id, provider, source_url, title, artist, duration_ms, thumbnail_url)
+		VALUES ($1, $2, $3, $4, $5, $6, $7)
+		ON CONFLICT (user_id, source_url) WHERE source_url IS NOT NULL DO UPDATE SET
+			provider = EXCLUDED.provider,
+			title = EXCLUDED.title,
+			artist = EXCLUDED.artist,
+			duration_ms = EXCLUDED.duration_ms,
+			thumbnail_url = EXCLUDED.thumbnail_url
+		RETURNING id, added_at
+	`, userID, source.Provider, source.SourceURL, source.Title,
+		sql.NullString{String: source.Artist, Valid: source.Artist != ""},
+		sql.NullInt32{Int32: int32(source.DurationMs), Valid: source.DurationMs > 0},
+		sql.NullString{String: source.ThumbnailURL, Valid: source.ThumbnailURL != ""},
+	).Scan(&item.ID, &item.AddedAt)
+	if err != nil {
+		return nil, err
+	}
+	return item, nil
+}
+
+// Remove deletes an entry from the user's queue.
+func (r *ListenLaterRepository) Remove(ctx context.Context, userID uuid.UUID, itemID int64) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM listen_later WHERE id = $1 AND user_id = $2`, itemID, userID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrListenLaterItemNotFound
+	}
+	return nil
+}
+
+// Finish takes a track the user played to the end off their queue, along
+// with the discovery result it was downloaded from, and returns the IDs of
+// the removed entries.
+func (r *ListenLaterRepository) Finish(ctx context.Context, userID uuid.UUID, trackID int64) ([]int64, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		DELETE FROM listen_later l
+		USING tracks t
+		WHERE l.user_id = $1 AND t.id = $2
+		  AND (l.track_id = t.id OR l.source_url = t.source_url)
+		RETURNING l.id
+	`, userID, trackID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	removed := []int64{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		removed = append(removed, id)
+	}
+	return removed, rows.Err()
+}
+
+// List returns a page of the user's queue, most recently saved first, with
+// the total number of entries.
+func (r *ListenLaterRepository) List(ctx context.Context, userID uuid.UUID, limit, offset int) ([]ListenLaterItem, int, error) {
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 100 {
+		limit = 100
+	}
+	if offset < 0 {
+		offset = 0
+	}
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT id, track_id, provider, source_url, title, artist, duration_ms, thumbnail_url, added_at, COUNT(*) OVER()
+		FROM listen_later
+		WHERE user_id = $1
+		ORDER BY added_at DESC, id DESC
+		LIMIT $2 OFFSET $3
+	`, userID, limit, offset)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+
+	items := []ListenLaterItem{}
+	trackIDs := []int64{}
+	var total int
+	for rows.Next() {
+		var item ListenLaterItem
+		var trackID sql.NullInt64
+		var provider, sourceURL, title, artist, thumbnail sql.NullString
+		var duration sql.NullInt32
+		if err := rows.Scan(&item.ID, &trackID, &provider, &sourceURL, &title, &artist, &duration, &thumbnail, &item.AddedAt, &total); err != nil {
+			return nil, 0, err
+		}
+		if trackID.Valid {
+			item.Track = &Track{ID: trackID.Int64}
+			trackIDs = append(trackIDs, trackID.Int64)
+		} else {
+			item.Source = &ListenLaterSource{
+				Provider:     provider.String,
+				SourceURL:    sourceURL.String,
+				Title:        title.String,
+				Artist:       artist.String,
+				DurationMs:   int(duration.Int32),
+				ThumbnailURL: thumbnail.String,
+			}
+		}
+		items = append(items, item)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, 0, err
+	}
+	if len(trackIDs) == 0 {
+		return items, total, nil
+	}
+
+	tracks, err := r.tracks(ctx, trackIDs)
+	if err != nil {
+		return nil, 0, err
+	}
+	// A track deleted since the page was read drops out of it.
+	kept := items[:0]
+	for _, item := range items {
+		if item.Track != nil {
+			track, ok := tracks[item.Track.ID]
+			if !ok {
+				continue
+			}
+			item.Track = track
+		}
+		kept = append(kept, item)
+	}
+	return kept, total, nil
+}
+
+// tracks loads the queued tracks of a page in one query.
+func (r *ListenLaterRepository) tracks(ctx context.Context, ids []int64) (map[int64]*Track, error) {
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT `+trackColumns+`
+		FROM tracks
+		WHERE id = ANY($1::bigint[])
+	`, pq.Array(ids))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	tracks := make(map[int64]*Track, len(ids))
+	for rows.Next() {
+		t, err := scanTrack(rows)
+		if err != nil {
+			return nil, err
+		}
+		tracks[t.ID] = t
+	}
+	return tracks, rows.Err()
+}
@@ -0,0 +1,63 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+// TestListenLaterAgainstPostgres covers the listen later queue: saving is
+// idempotent, and finishing a track removes it and the discovery result it
+// was downloaded from.
+func TestListenLaterAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewListenLaterRepository(database)
+
+	user := seedPlayUser(t, database, "later@example.test")
+	other := seedPlayUser(t, database, "later-other@example.test")
+	first := seedPlayTrack(t, trackRepo, ctx, "Artist A", "First")
+	second := seedPlayTrack(t, trackRepo, ctx, "Artist B", "Second")
+	if _, err := database.ExecContext(ctx, `UPDATE tracks SET source_url = 'https://example.test/second' WHERE id = $1`, second); err != nil {
+		t.Fatalf("set source url: %v", err)
+	}
+
+	saved, err := repo.AddTrack(ctx, user, &Track{ID: first})
+	if err != nil {
+		t.Fatalf("AddTrack: %v", err)
+	}
+	again, err := repo.AddTrack(ctx, user, &Track{ID: first})
+	if err != nil || again.ID != saved.ID || !again.AddedAt.Equal(saved.AddedAt) {
+		t.Fatalf("AddTrack again = %+v, %v; want the saved entry %+v", again, err, saved)
+	}
+	source, err := repo.AddSource(ctx, user, ListenLaterSource{Provider: "youtube", SourceURL: "https://example.test/second", Title: "Second (live)"})
+	if err != nil {
+		t.Fatalf("AddSource: %v", err)
+	}
+	if _, err := repo.AddSource(ctx, other, ListenLaterSource{Provider: "youtube", SourceURL: "https://example.test/second", Title: "Second"}); err != nil {
+		t.Fatalf("AddSource(other): %v", err)
+	}
+	items, total, err := repo.List(ctx, user, 10, 0)
+	if err != nil || total != 2 || items[0].Source == nil || items[1].Track == nil || items[1].Track.Title != "First" {
+		t.Fatalf("List = %+v (total %d), %v", items, total, err)
+	}
+
+	if removed, err := repo.Finish(ctx, user, first); err != nil || len(removed) != 1 || removed[0] != saved.ID {
+		t.Fatalf("Finish(first) = %v, %v; want [%d]", removed, err, saved.ID)
+	}
+	// The downloaded track finishes the discovery result it came from, for
+	// this user only.
+	if removed, err := repo.Finish(ctx, user, second); err != nil || len(removed) != 1 || removed[0] != source.ID {
+		t.Fatalf("Finish(second) = %v, %v; want [%d]", removed, err, source.ID)
+	}
+	if _, total, err = repo.List(ctx, user, 10, 0); err != nil || total != 0 {
+		t.Fatalf("List after finishing: total %d, %v; want empty", total, err)
+	}
+	if _, total, err = repo.List(ctx, other, 10, 0); err != nil || total != 1 {
+		t.Fatalf("other user's List: total %d, %v; want 1", total, err)
+	}
+	if err := repo.Remove(ctx, user, saved.ID); !errors.Is(err, ErrListenLaterItemNotFound) {
+		t.Fatalf("Remove of a finished entry = %v; want ErrListenLaterItemNotFound", err)
+	}
+}
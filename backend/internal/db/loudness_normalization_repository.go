@@ -0,0 +1,32 @@
+package db
+
+import "context"
+
+// LoudnessNormalization records a track's audio being re-encoded to
+// TargetLUFS when it was downloaded. OriginalStorageKey is the unnormalized
+// file in object storage, or empty when it was not kept.
+type LoudnessNormalization struct {
+	TrackID            int64
+	InputLUFS          float64
+	TargetLUFS         float64
+	OriginalStorageKey string
+	OriginalSizeBytes  int64
+}
+
+type LoudnessNormalizationRepository struct {
+	db *DB
+}
+
+func NewLoudnessNormalizationRepository(db *DB) *LoudnessNormalizationRepository {
+	return &LoudnessNormalizationRepository{db: db}
+}
+
+// Save records normalization. A track keeps the first one recorded for it.
+func (r *LoudnessNormalizationRepository) Save(ctx context.Context, normalization *LoudnessNormalization) error {
+	_, err := r.db.ExecContext(ctx, `
+		INSERT INTO track_loudness_normalizations (track_id, input_lufs, target_lufs, original_storage_key, original_size_bytes)
+		VALUES ($1, $2, $3, NULLIF($4, ''), NULLIF($5, 0))
+		ON CONFLICT (track_id) DO NOTHING
+	`, normalization.TrackID, normalization.InputLUFS, normalization.TargetLUFS, normalization.OriginalStorageKey, normalization.OriginalSizeBytes)
+	return err
+}
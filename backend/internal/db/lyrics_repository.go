@@ -0,0 +1,68 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"fmt"
+	"time"
+)
+
+var ErrLyricsNotFound = errors.New("lyrics not found")
+
+// LyricsSourceLRC marks lyrics read from an .lrc file beside the audio.
+const LyricsSourceLRC = "lrc"
+
+// Lyrics is the lyrics text stored for a track. Synced lyrics keep their LRC
+// timestamps in Text.
+type Lyrics struct {
+	TrackID   int64
+	Text      string
+	Synced    bool
+	Source    string
+	UpdatedAt time.Time
+}
+
+type LyricsRepository struct {
+	db *DB
+}
+
+func NewLyricsRepository(db *DB) *LyricsRepository {
+	return &LyricsRepository{db: db}
+}
+
+// Upsert stores the lyrics for lyrics.TrackID, replacing any earlier ones, and
+// sets UpdatedAt.
+func (r *LyricsRepository) Upsert(ctx context.Context, lyrics *Lyrics) error {
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO track_lyrics (track_id, lyrics, synced, source)
+		VALUES ($1, $2, $3, $4)
+		ON CONFLICT (track_id) DO UPDATE SET
+			lyrics = EXCLUDED.lyrics,
+			synced = EXCLUDED.synced,
+			source = EXCLUDED.source,
+			updated_at = NOW()
+		RETURNING updated_at
+	`, lyrics.TrackID, lyrics.Text, lyrics.Synced, lyrics.Source).Scan(&lyrics.UpdatedAt)
+	if err != nil {
+		return fmt.Errorf("save lyrics: %w", err)
+	}
+	return nil
+}
+
+// GetForTrack returns the lyrics stored for trackID.
+func (r *LyricsRepository) GetForTrack(ctx context.Context, trackID int64) (*Lyrics, error) {
+	lyrics := Lyrics{TrackID: trackID}
+	err := r.db.QueryRowContext(ctx, `
+		SELECT lyrics, synced, source, updated_at
+		FROM track_lyrics
+		WHERE track_id = $1
+	`, trackID).Scan(&lyrics.Text, &lyrics.Synced, &lyrics.Source, &lyrics.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrLyricsNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &lyrics, nil
+}
@@ -0,0 +1,246 @@
+package db
+
+import (
+	"context"
+	"embed"
+	"fmt"
+	"io/fs"
+	"path"
+	"strconv"
+	"strings"
+)
+
+// migrationFiles holds the reference migrations. MigrateDown runs their down
+// halves; the up halves only describe what Migrate applies.
+//
+//go:embed migrations/*.sql
+var migrationFiles embed.FS
+
+// OldestReversibleVersion is the oldest schema MigrateDown can go back to:
+// the one before the first reference migration file.
+const OldestReversibleVersion = 10
+
+// Migration is one reference migration, named after its file.
+type Migration struct {
+	Version int
+	Name    string
+}
+
+// migrationFilesBySuffix maps each version to its file ending in suffix.
+func migrationFilesBySuffix(suffix string) (map[int]string, error) {
+	names, err := fs.Glob(migrationFiles, "migrations/*"+suffix)
+	if err != nil {
+		return nil, err
+	}
+	byVersion := map[int]string{}
+	for _, name := range names {
+		prefix, _, _ := strings.Cut(path.Base(name), "_")
+		v, err := strconv.Atoi(prefix)
+		if err != nil {
+			return nil, fmt.Errorf("migration file %s has no version prefix", name)
+		}
+		byVersion[v] = name
+	}
+	return byVersion, nil
+}
+
+// PendingMigrations lists the reference migrations newer than version up to
+// SchemaVersion, oldest first. A database never migrated by a build that
+// records its version gets every change from OldestReversibleVersion on,
+// most of which Migrate will find already applied.
+func PendingMigrations(version int) ([]Migration, error) {
+	files, err := migrationFilesBySuffix(".up.sql")
+	if err != nil {
+		return nil, err
+	}
+	var pending []Migration
+	for v := max(version, OldestReversibleVersion) + 1; v <= SchemaVersion; v++ {
+		name, ok := files[v]
+		if !ok {
+			return nil, fmt.Errorf("no migration file for version %d", v)
+		}
+		base := strings.TrimSuffix(path.Base(name), ".up.sql")
+		_, title, _ := strings.Cut(base, "_")
+		pending = append(pending, Migration{Version: v, Name: strings.ReplaceAll(title, "_", " ")})
+	}
+	return pending, nil
+}
+
+// MigrationScript returns the reference up script for version.
+func MigrationScript(version int) (string, error) {
+	return readMigration(version, ".up.sql")
+}
+
+// DownMigrationScript returns the script MigrateDown runs to undo version.
+func DownMigrationScript(version int) (string, error) {
+	return readMigration(version, ".down.sql")
+}
+
+func readMigration(version int, suffix string) (string, error) {
+	files, err := migrationFilesBySuffix(suffix)
+	if err != nil {
+		return "", err
+	}
+	name, ok := files[version]
+	if !ok {
+		return "", fmt.Errorf("no migration file for version %d", version)
+	}
+	script, err := fs.ReadFile(migrationFiles, name)
+	return string(script), err
+}
+
+type migrationStep struct {
+	version int
+	name    string
+}
+
+// downMigrations lists the down files for the versions above target up to
+// version, newest first, failing if any version in between has none.
+func downMigrations(target, version int) ([]migrationStep, error) {
+	byVersion, err := migrationFilesBySuffix(".down.sql")
+	if err != nil {
+		return nil, err
+	}
+	var steps []migrationStep
+	for v := version; v > target; v-- {
+		name, ok := byVersion[v]
+		if !ok {
+			return nil, fmt.Errorf("no down migration for version %d", v)
+		}
+		steps = append(steps, migrationStep{version: v, name: name})
+	}
+	return steps, nil
+}
+
+// MigrateDown undoes the schema changes newer than target, newest first, in
+// one transaction, and records target as the schema version. It is for going
+// back to the release whose SchemaVersion is target: this build's Migrate
+// reapplies the changes on its next start. Columns and tables the changes
+// added are dropped with their data.
+func (db *DB) MigrateDown(ctx context.Context, target int) error {
+	version, err := db.MigratedVersion(ctx)
+	if err != nil {
+		return err
+	}
+	if version > SchemaVersion {
+		return fmt.Errorf("schema is at version %d, newer than this build's %d; use the omp of the release that migrated it", version, SchemaVersion)
+	}
+	if target < OldestReversibleVersion || target >= version {
+		return fmt.Errorf("schema is at version %d; it can go back to versions %d through %d", version, OldestReversibleVersion, version-1)
+	}
+	steps, err := downMigrations(target, version)
+	if err != nil {
+		return err
+	}
+
+	tx, err := db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+	// The same lock Migrate holds, so a server starting meanwhile waits.
+	if _, err := tx.ExecContext(ctx, `SELECT pg_advisory_xact_lock(hashtextextended('open_music_player_schema_migrate', 0))`); err != nil {
+		return fmt.Errorf("lock schema migration: %w", err)
+	}
+	for _, step := range steps {
+		script, err := fs.ReadFile(migrationFiles, step.name)
+		if err != nil {
+			return err
+		}
+		if _, err := tx.ExecContext(ctx, string(script)); err != nil {
+			return fmt.Errorf("undo %s: %w", path.Base(step.name), err)
+		}
+	}
+	if _, err := tx.ExecContext(ctx, `UPDATE schema_version SET version = $1, migrated_at = NOW()`, target); err != nil {
+		return fmt.Errorf("record schema version: %w", err)
+	}
+	return tx.Commit()
+}
+
+// DataCheck is an invariant a healthy database holds that its constraints do
+// not enforce. Query counts the rows breaking it, and Fix tells the operator
+// what to do about them.
+type DataCheck struct {
+	Name  string
+	Query string
+	Fix   string
+	// Warn marks invariants the server restores by itself, so breaking
+	// them is worth reporting but not failing.
+	Warn bool
+}
+
+// DataChecks are run after migrating, to catch a migration that damaged data
+// rather than failed outright.
+var DataChecks = []DataCheck{
+	{
+		Name: "tenant-libraries",
+		Query: `SELECT COUNT(*) FROM user_library ul
+			JOIN users u ON u.id = ul.user_id
+			JOIN tracks t ON t.id = ul.track_id
+			WHERE t.tenant_id IS DISTINCT FROM u.tenant_id`,
+		Fix: "restore the backup taken before migrating; libraries hold tracks from another tenant",
+	},
+	{
+		Name:  "future-plays",
+		Query: `SELECT COUNT(*) FROM play_events WHERE played_at > NOW() + INTERVAL '1 day'`,
+		Fix:   "restore the backup taken before migrating; play times were shifted into the future",
+	},
+	{
+		Name:  "search-index",
+		Query: `SELECT COUNT(*) FROM tracks WHERE search_text IS NULL`,
+		Fix:   "start the server; it indexes these tracks in the background",
+		Warn:  true,
+	},
+	{
+		// Playlist imports can place two tracks at one position.
+		Name: "playlist-positions",
+		Query: `SELECT COUNT(*) FROM (
+			SELECT 1 FROM playlist_tracks GROUP BY playlist_id, position HAVING COUNT(*) > 1
+		) duplicates`,
+		Fix:  "none needed; removing a track from an affected playlist renumbers it",
+		Warn: true,
+	},
+}
+
+// CountViolations returns how many rows break check.
+func (db *DB) CountViolations(ctx context.Context, check DataCheck) (int64, error) {
+	var count int64
+	err := db.QueryRowContext(ctx, check.Query).Scan(&count)
+	return count, err
+}
+
+// CountedTables are the tables whose rows no migration should lose.
+var CountedTables = []string{"users", "tracks", "user_library", "playlists", "playlist_tracks", "track_favorites", "play_events"}
+
+// RowCounts counts the rows of each of CountedTables that exists.
+func (db *DB) RowCounts(ctx context.Context) (map[string]int64, error) {
+	counts := map[string]int64{}
+	for _, table := range CountedTables {
+		var exists bool
+		if err := db.QueryRowContext(ctx, `SELECT to_regclass($1) IS NOT NULL`, table).Scan(&exists); err != nil {
+			return nil, err
+		}
+		if !exists {
+			continue
+		}
+		var count int64
+		// table comes from CountedTables, never from input.
+		if err := db.QueryRowContext(ctx, `SELECT COUNT(*) FROM `+table).Scan(&count); err != nil {
+			return nil, fmt.Errorf("count %s: %w", table, err)
+		}
+		counts[table] = count
+	}
+	return counts, nil
+}
+
+// ShrunkTables lists the tables with fewer rows in after than in before,
+// in CountedTables order.
+func ShrunkTables(before, after map[string]int64) []string {
+	var shrunk []string
+	for _, table := range CountedTables {
+		if count, ok := before[table]; ok && after[table] < count {
+			shrunk = append(shrunk, table)
+		}
+	}
+	return shrunk
+}
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_playlists_folder_id;
+ALTER TABLE playlists DROP COLUMN IF EXISTS pin_position;
+ALTER TABLE playlists DROP COLUMN IF EXISTS position;
+ALTER TABLE playlists DROP COLUMN IF EXISTS folder_id;
+DROP TABLE IF EXISTS playlist_folders;
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS playlist_folders (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    parent_id BIGINT REFERENCES playlist_folders(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_playlist_folders_user_parent ON playlist_folders(user_id, parent_id);
+
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS folder_id BIGINT REFERENCES playlist_folders(id) ON DELETE SET NULL;
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS pin_position INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_playlists_folder_id ON playlists(folder_id) WHERE folder_id IS NOT NULL;
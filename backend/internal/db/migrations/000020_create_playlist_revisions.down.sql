@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_playlist_revisions_playlist_created;
+DROP TABLE IF EXISTS playlist_revisions;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS playlist_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    playlist_id BIGINT NOT NULL REFERENCES playlists(id) ON DELETE CASCADE,
+    action VARCHAR(32) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    cover_url TEXT,
+    is_public BOOLEAN NOT NULL DEFAULT FALSE,
+    track_ids BIGINT[] NOT NULL DEFAULT '{}',
+    tracks_added INTEGER,
+    tracks_removed INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_playlist_revisions_playlist_created ON playlist_revisions(playlist_id, created_at DESC);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_play_events_user_context;
+ALTER TABLE track_favorites DROP COLUMN IF EXISTS context_id;
+ALTER TABLE track_favorites DROP COLUMN IF EXISTS context_type;
@@ -0,0 +1,4 @@
+ALTER TABLE track_favorites ADD COLUMN IF NOT EXISTS context_type VARCHAR(32);
+ALTER TABLE track_favorites ADD COLUMN IF NOT EXISTS context_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_play_events_user_context ON play_events(user_id, context_type, context_id, played_at DESC);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_tracks_artwork_id;
+ALTER TABLE tracks DROP COLUMN IF EXISTS artwork_id;
+DROP TABLE IF EXISTS artwork_images;
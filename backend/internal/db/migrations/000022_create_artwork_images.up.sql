@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS artwork_images (
+    id BIGSERIAL PRIMARY KEY,
+    source_url TEXT NOT NULL UNIQUE,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    variants JSONB NOT NULL DEFAULT '[]',
+    blurhash TEXT NOT NULL,
+    dominant_color VARCHAR(7) NOT NULL,
+    palette TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS artwork_id BIGINT REFERENCES artwork_images(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_tracks_artwork_id ON tracks(artwork_id) WHERE artwork_id IS NOT NULL;
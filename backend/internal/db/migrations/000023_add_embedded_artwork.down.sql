@@ -0,0 +1,6 @@
+ALTER TABLE tracks DROP COLUMN IF EXISTS artwork_source;
+
+DROP INDEX IF EXISTS idx_artwork_images_embedded_sha256;
+DELETE FROM artwork_images WHERE source_url IS NULL;
+ALTER TABLE artwork_images DROP COLUMN IF EXISTS content_sha256;
+ALTER TABLE artwork_images ALTER COLUMN source_url SET NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE artwork_images ALTER COLUMN source_url DROP NOT NULL;
+ALTER TABLE artwork_images ADD COLUMN IF NOT EXISTS content_sha256 VARCHAR(64);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_artwork_images_embedded_sha256 ON artwork_images(content_sha256) WHERE source_url IS NULL;
+
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS artwork_source VARCHAR(16);
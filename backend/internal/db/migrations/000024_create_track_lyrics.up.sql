@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS track_lyrics (
+    track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    lyrics TEXT NOT NULL,
+    synced BOOLEAN NOT NULL DEFAULT FALSE,
+    source VARCHAR(16) NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS track_field_provenance (
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    field VARCHAR(32) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    locked BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (track_id, field)
+);
+
+-- Tracks edited before per-field provenance keep every field locked.
+INSERT INTO track_field_provenance (track_id, field, source, locked)
+SELECT t.id, f.field, 'user', TRUE
+FROM tracks t
+CROSS JOIN (VALUES ('title'), ('artist'), ('album'), ('duration_ms'), ('cover_art_url')) AS f(field)
+WHERE t.metadata_user_edited
+  AND NOT EXISTS (SELECT 1 FROM track_field_provenance p WHERE p.track_id = t.id)
+ON CONFLICT (track_id, field) DO NOTHING;
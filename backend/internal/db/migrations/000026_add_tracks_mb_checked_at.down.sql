@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_tracks_mb_checked_at;
+ALTER TABLE tracks DROP COLUMN IF EXISTS mb_checked_at;
@@ -0,0 +1,2 @@
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS mb_checked_at TIMESTAMP WITH TIME ZONE;
+CREATE INDEX IF NOT EXISTS idx_tracks_mb_checked_at ON tracks(COALESCE(mb_checked_at, created_at)) WHERE mb_recording_id IS NOT NULL;
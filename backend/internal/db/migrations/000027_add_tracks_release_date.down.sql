@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_tracks_release_date;
+ALTER TABLE tracks DROP COLUMN IF EXISTS release_day;
+ALTER TABLE tracks DROP COLUMN IF EXISTS release_month;
+ALTER TABLE tracks DROP COLUMN IF EXISTS release_year;
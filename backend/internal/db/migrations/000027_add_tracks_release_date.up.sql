@@ -0,0 +1,17 @@
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS release_year SMALLINT CHECK (release_year >= 1);
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS release_month SMALLINT CHECK (release_month BETWEEN 1 AND 12);
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS release_day SMALLINT CHECK (release_day BETWEEN 1 AND 31);
+CREATE INDEX IF NOT EXISTS idx_tracks_release_date ON tracks((release_year * 10000 + COALESCE(release_month, 0) * 100 + COALESCE(release_day, 0))) WHERE release_year IS NOT NULL;
+
+-- Backfill release dates from the MusicBrainz match recorded before they had columns.
+UPDATE tracks t
+SET release_year = split_part(d.value, '-', 1)::smallint,
+    release_month = NULLIF(split_part(d.value, '-', 2), '')::smallint,
+    release_day = NULLIF(split_part(d.value, '-', 3), '')::smallint
+FROM (
+    SELECT id, metadata_provenance #>> '{musicbrainz,best_match,release_date}' AS value
+    FROM tracks
+    WHERE release_year IS NULL AND metadata_provenance ? 'musicbrainz'
+) d
+WHERE d.id = t.id
+  AND d.value ~ '^[1-9][0-9]{3}(-(0[1-9]|1[0-2])(-(0[1-9]|[12][0-9]|3[01]))?)?$';
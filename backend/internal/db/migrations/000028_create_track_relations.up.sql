@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS track_relations (
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    relation VARCHAR(16) NOT NULL CHECK (relation IN ('remix_of', 'remixed_by', 'samples', 'sampled_by', 'cover_of', 'performance_of')),
+    target_type VARCHAR(16) NOT NULL CHECK (target_type IN ('recording', 'work')),
+    target_mbid UUID NOT NULL,
+    target_title TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (track_id, relation, target_mbid)
+);
+CREATE INDEX IF NOT EXISTS idx_track_relations_target ON track_relations(target_mbid, relation);
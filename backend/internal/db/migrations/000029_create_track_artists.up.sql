@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS track_artists (
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    position SMALLINT NOT NULL,
+    name TEXT NOT NULL,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('main', 'featured', 'remixer')),
+    mb_artist_id UUID,
+    PRIMARY KEY (track_id, role, name)
+);
+CREATE INDEX IF NOT EXISTS idx_track_artists_name ON track_artists(name);
+
+-- Backfill the artist tag as the main credit.
+INSERT INTO track_artists (track_id, position, name, role)
+SELECT t.id, 0, btrim(regexp_replace(t.artist, '\s+(feat\.?|ft\.?|featuring)\s+.*$', '', 'i')), 'main'
+FROM tracks t
+WHERE btrim(COALESCE(t.artist, '')) <> ''
+  AND NOT EXISTS (SELECT 1 FROM track_artists ta WHERE ta.track_id = t.id)
+ON CONFLICT (track_id, role, name) DO NOTHING;
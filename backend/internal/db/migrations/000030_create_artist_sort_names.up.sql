@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS artist_sort_names (
+    name TEXT PRIMARY KEY,
+    sort_name TEXT NOT NULL,
+    mb_artist_id UUID,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_tracks_search_pending;
+DROP INDEX IF EXISTS idx_tracks_search_document;
+CREATE INDEX IF NOT EXISTS idx_tracks_fulltext ON tracks USING GIN (to_tsvector('english', COALESCE(title, '') || ' ' || COALESCE(artist, '') || ' ' || COALESCE(album, '')));
+ALTER TABLE tracks DROP COLUMN IF EXISTS search_aliases;
+ALTER TABLE tracks DROP COLUMN IF EXISTS search_album;
+ALTER TABLE tracks DROP COLUMN IF EXISTS search_artist;
+ALTER TABLE tracks DROP COLUMN IF EXISTS search_text;
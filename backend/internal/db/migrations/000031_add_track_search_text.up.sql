@@ -0,0 +1,9 @@
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_text TEXT;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_artist TEXT;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_album TEXT;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS search_aliases TEXT[] NOT NULL DEFAULT '{}';
+
+-- Search reads the folded text when present and the raw fields otherwise.
+DROP INDEX IF EXISTS idx_tracks_fulltext;
+CREATE INDEX IF NOT EXISTS idx_tracks_search_document ON tracks USING GIN (to_tsvector('english', COALESCE(search_text, COALESCE(title, '') || ' ' || COALESCE(artist, '') || ' ' || COALESCE(album, ''))));
+CREATE INDEX IF NOT EXISTS idx_tracks_search_pending ON tracks(id) WHERE search_text IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS track_aliases;
+DROP TABLE IF EXISTS user_settings;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS user_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    metadata_script VARCHAR(16) NOT NULL DEFAULT 'original' CHECK (metadata_script IN ('original', 'latin')),
+    metadata_locale VARCHAR(16),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS track_aliases (
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    field VARCHAR(16) NOT NULL CHECK (field IN ('title', 'artist')),
+    name TEXT NOT NULL,
+    locale VARCHAR(16) NOT NULL DEFAULT '',
+    is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (track_id, field, name, locale)
+);
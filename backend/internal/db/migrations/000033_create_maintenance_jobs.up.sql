@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS maintenance_jobs (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action VARCHAR(32) NOT NULL CHECK (action IN ('fetch_artwork', 'match_unverified', 'redownload_corrupt')),
+    status VARCHAR(16) NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'completed', 'failed')),
+    total_items INTEGER NOT NULL DEFAULT 0,
+    processed_items INTEGER NOT NULL DEFAULT 0,
+    succeeded_items INTEGER NOT NULL DEFAULT 0,
+    skipped_items INTEGER NOT NULL DEFAULT 0,
+    failed_items INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMP WITH TIME ZONE,
+    finished_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_maintenance_jobs_active ON maintenance_jobs(user_id, action) WHERE status IN ('queued', 'running');
+CREATE INDEX IF NOT EXISTS idx_maintenance_jobs_queued ON maintenance_jobs(created_at) WHERE status = 'queued';
+CREATE INDEX IF NOT EXISTS idx_maintenance_jobs_user_created ON maintenance_jobs(user_id, created_at DESC);
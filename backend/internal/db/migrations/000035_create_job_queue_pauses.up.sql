@@ -0,0 +1,6 @@
+-- Paused job kinds; workers claim no queued jobs of a paused kind.
+CREATE TABLE IF NOT EXISTS job_queue_pauses (
+    kind VARCHAR(64) PRIMARY KEY,
+    reason TEXT,
+    paused_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
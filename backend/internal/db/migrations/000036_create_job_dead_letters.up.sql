@@ -0,0 +1,15 @@
+-- Jobs of any kind that failed for good, kept with their error context until
+-- an operator retries or discards them.
+CREATE TABLE IF NOT EXISTS job_dead_letters (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(64) NOT NULL,
+    job_id VARCHAR(64) NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    error TEXT NOT NULL,
+    category VARCHAR(32) NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    context JSONB NOT NULL DEFAULT '{}'::jsonb,
+    failed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, job_id)
+);
+CREATE INDEX IF NOT EXISTS idx_job_dead_letters_failed ON job_dead_letters(failed_at DESC);
@@ -0,0 +1,7 @@
+-- The schema version of the build that last migrated the database, so
+-- `omp doctor` can spot a database left behind by an older build.
+CREATE TABLE IF NOT EXISTS schema_version (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    version INTEGER NOT NULL,
+    migrated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
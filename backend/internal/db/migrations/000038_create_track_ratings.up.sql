@@ -0,0 +1,9 @@
+-- Per-user star ratings, 1 to 5; carried over by library migrations.
+CREATE TABLE IF NOT EXISTS track_ratings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, track_id)
+);
+CREATE INDEX IF NOT EXISTS idx_track_ratings_track_id ON track_ratings(track_id);
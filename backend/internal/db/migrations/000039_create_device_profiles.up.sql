@@ -0,0 +1,13 @@
+-- Per-user device profiles: the format exports and offline sync write
+-- tracks in for one target device, such as a car's USB stick.
+CREATE TABLE IF NOT EXISTS device_profiles (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    format VARCHAR(16) NOT NULL DEFAULT 'original',
+    bitrate_kbps INTEGER NOT NULL DEFAULT 0 CHECK (bitrate_kbps >= 0),
+    max_artwork_bytes BIGINT NOT NULL DEFAULT 0 CHECK (max_artwork_bytes >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
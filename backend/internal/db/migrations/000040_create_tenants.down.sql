@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_tracks_tenant_id;
+DROP INDEX IF EXISTS idx_users_tenant_id;
+ALTER TABLE tracks DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE users DROP COLUMN IF EXISTS tenant_admin;
+ALTER TABLE users DROP COLUMN IF EXISTS tenant_id;
+DROP TABLE IF EXISTS tenants;
@@ -0,0 +1,19 @@
+-- Households sharing one instance. Users and tracks without a tenant
+-- belong to the instance itself. A tenant's tracks are visible only to its
+-- members and its audio is stored below storage_prefix. NULL quotas are
+-- unlimited.
+CREATE TABLE IF NOT EXISTS tenants (
+    id BIGSERIAL PRIMARY KEY,
+    slug VARCHAR(64) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    storage_prefix VARCHAR(100) NOT NULL UNIQUE,
+    max_tracks INTEGER CHECK (max_tracks >= 0),
+    max_storage_bytes BIGINT CHECK (max_storage_bytes >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS tenant_id BIGINT REFERENCES tenants(id) ON DELETE RESTRICT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS tenant_admin BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS tenant_id BIGINT REFERENCES tenants(id) ON DELETE RESTRICT;
+CREATE INDEX IF NOT EXISTS idx_users_tenant_id ON users(tenant_id) WHERE tenant_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tracks_tenant_id ON tracks(tenant_id) WHERE tenant_id IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Artist biographies read from Wikipedia through the artist's Wikidata
+-- item, one per requested language. An empty extract records that no
+-- article exists, so the lookup waits until the row is stale.
+CREATE TABLE IF NOT EXISTS artist_bios (
+    mb_artist_id UUID NOT NULL,
+    language VARCHAR(16) NOT NULL,
+    extract TEXT NOT NULL DEFAULT '',
+    content_language VARCHAR(16) NOT NULL DEFAULT '',
+    source VARCHAR(16) NOT NULL,
+    title VARCHAR(500) NOT NULL DEFAULT '',
+    url TEXT NOT NULL DEFAULT '',
+    license VARCHAR(64) NOT NULL DEFAULT '',
+    fetched_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mb_artist_id, language)
+);
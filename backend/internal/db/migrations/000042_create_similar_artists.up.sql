@@ -0,0 +1,9 @@
+-- Similar artists per MusicBrainz artist as last read from ListenBrainz or
+-- Last.fm, most similar first. An empty list records that the source knew
+-- none, so the lookup waits until the row is stale.
+CREATE TABLE IF NOT EXISTS similar_artists (
+    mb_artist_id UUID PRIMARY KEY,
+    source VARCHAR(16) NOT NULL,
+    artists JSONB NOT NULL DEFAULT '[]'::jsonb,
+    fetched_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
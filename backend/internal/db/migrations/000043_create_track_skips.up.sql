@@ -0,0 +1,14 @@
+-- Tracks the user moved past before they counted as a play, with how far in
+-- they got. Skips never count as plays; frequent skips keep a track out of
+-- radio and home recommendations.
+CREATE TABLE IF NOT EXISTS track_skips (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    skipped_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    position_ms INTEGER NOT NULL DEFAULT 0 CHECK (position_ms >= 0),
+    context_type VARCHAR(32),
+    context_id TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_track_skips_user_track ON track_skips(user_id, track_id, skipped_at DESC);
+CREATE INDEX IF NOT EXISTS idx_track_skips_user_skipped_at ON track_skips(user_id, skipped_at DESC);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_track_skips_user_client_event;
+ALTER TABLE track_skips DROP COLUMN IF EXISTS client_event_id;
+DROP INDEX IF EXISTS idx_play_events_user_client_event;
+ALTER TABLE play_events DROP COLUMN IF EXISTS client_event_id;
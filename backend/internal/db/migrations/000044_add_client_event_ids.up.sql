@@ -0,0 +1,6 @@
+-- Client-generated IDs of plays and skips reported in batches, such as listens
+-- made offline, so a resent batch records each event once.
+ALTER TABLE play_events ADD COLUMN IF NOT EXISTS client_event_id UUID;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_play_events_user_client_event ON play_events(user_id, client_event_id) WHERE client_event_id IS NOT NULL;
+ALTER TABLE track_skips ADD COLUMN IF NOT EXISTS client_event_id UUID;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_track_skips_user_client_event ON track_skips(user_id, client_event_id) WHERE client_event_id IS NOT NULL;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_play_events_pending_rollup;
+ALTER TABLE play_events DROP COLUMN IF EXISTS rolled_up;
+DROP TABLE IF EXISTS play_monthly_rollups;
+DROP TABLE IF EXISTS play_daily_rollups;
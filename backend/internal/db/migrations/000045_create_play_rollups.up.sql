@@ -0,0 +1,26 @@
+-- Daily and monthly play counts, so raw play events past their retention can
+-- be deleted without changing stats. rolled_up marks the events already
+-- counted in play_daily_rollups; daily rows past their own retention move to
+-- play_monthly_rollups.
+CREATE TABLE IF NOT EXISTS play_daily_rollups (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    context_type VARCHAR(32) NOT NULL DEFAULT '',
+    context_id TEXT NOT NULL DEFAULT '',
+    play_count INTEGER NOT NULL CHECK (play_count > 0),
+    last_played_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, day, track_id, context_type, context_id)
+);
+CREATE TABLE IF NOT EXISTS play_monthly_rollups (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    month DATE NOT NULL,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    context_type VARCHAR(32) NOT NULL DEFAULT '',
+    context_id TEXT NOT NULL DEFAULT '',
+    play_count INTEGER NOT NULL CHECK (play_count > 0),
+    last_played_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, month, track_id, context_type, context_id)
+);
+ALTER TABLE play_events ADD COLUMN IF NOT EXISTS rolled_up BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE INDEX IF NOT EXISTS idx_play_events_pending_rollup ON play_events(id) WHERE NOT rolled_up;
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS library_albums;
+ALTER TABLE playlists DROP COLUMN IF EXISTS size_bytes;
+ALTER TABLE playlists DROP COLUMN IF EXISTS duration_ms;
+ALTER TABLE playlists DROP COLUMN IF EXISTS track_count;
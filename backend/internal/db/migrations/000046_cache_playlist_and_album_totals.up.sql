@@ -0,0 +1,17 @@
+-- Cached track count, total duration, and total size of playlists and of
+-- library albums (library tracks grouped by MusicBrainz artist, album, and
+-- release), refreshed by the repositories that change them. The server's
+-- aggregate repair pass fills them in after this migration.
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS track_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS duration_ms BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS size_bytes BIGINT NOT NULL DEFAULT 0;
+CREATE TABLE IF NOT EXISTS library_albums (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mb_artist_id UUID NOT NULL,
+    album TEXT NOT NULL,
+    mb_release_id UUID,
+    track_count INTEGER NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    size_bytes BIGINT NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_library_albums_key ON library_albums(user_id, mb_artist_id, album, mb_release_id) NULLS NOT DISTINCT;
@@ -0,0 +1,15 @@
+-- Where playback of a track skips in from and out at: past a long spoken
+-- intro, or before a hidden track. Rows without a user apply to everyone; a
+-- user's own row takes precedence.
+CREATE TABLE IF NOT EXISTS track_skip_markers (
+    id BIGSERIAL PRIMARY KEY,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    intro_end_ms INTEGER CHECK (intro_end_ms > 0),
+    outro_start_ms INTEGER CHECK (outro_start_ms > 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_track_skip_markers_order CHECK (intro_end_ms < outro_start_ms),
+    CONSTRAINT chk_track_skip_markers_set CHECK (intro_end_ms IS NOT NULL OR outro_start_ms IS NOT NULL)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_track_skip_markers_key ON track_skip_markers(track_id, user_id) NULLS NOT DISTINCT;
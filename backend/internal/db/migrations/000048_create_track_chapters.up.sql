@@ -0,0 +1,18 @@
+-- Chapters of a long mix, read from the source's chapter list or the
+-- timestamps in its description. split_track_id is the track cut from the
+-- chapter, once the mix has been split.
+CREATE TABLE IF NOT EXISTS track_chapters (
+    id BIGSERIAL PRIMARY KEY,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    title VARCHAR(500) NOT NULL,
+    artist VARCHAR(500),
+    start_ms INTEGER NOT NULL,
+    end_ms INTEGER NOT NULL,
+    source VARCHAR(32) NOT NULL,
+    split_track_id BIGINT REFERENCES tracks(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_track_chapters_range CHECK (start_ms >= 0 AND end_ms > start_ms),
+    CONSTRAINT chk_track_chapters_source CHECK (source IN ('chapters', 'description')),
+    UNIQUE (track_id, position)
+);
@@ -0,0 +1,14 @@
+-- Non-music segments of a track's source video, such as sponsor reads and
+-- intros, that playback skips. They come from a community database such as
+-- SponsorBlock and are read when the track is first downloaded.
+CREATE TABLE IF NOT EXISTS track_skip_segments (
+    id BIGSERIAL PRIMARY KEY,
+    track_id BIGINT NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    category VARCHAR(32) NOT NULL,
+    start_ms INTEGER NOT NULL,
+    end_ms INTEGER NOT NULL,
+    source VARCHAR(32) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_track_skip_segments_range CHECK (start_ms >= 0 AND end_ms > start_ms)
+);
+CREATE INDEX IF NOT EXISTS idx_track_skip_segments_track ON track_skip_segments(track_id, start_ms);
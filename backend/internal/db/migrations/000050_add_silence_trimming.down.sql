@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS track_silence_trims;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS trim_silence_min_ms;
@@ -0,0 +1,15 @@
+-- Users may have leading and trailing silence of at least
+-- trim_silence_min_ms trimmed from their downloads; 0 keeps files as they are.
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS trim_silence_min_ms INTEGER NOT NULL DEFAULT 0;
+
+-- Silence trimmed from a track's audio when it was downloaded. The untrimmed
+-- file is kept at original_storage_key.
+CREATE TABLE IF NOT EXISTS track_silence_trims (
+    track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    original_storage_key TEXT NOT NULL,
+    original_size_bytes BIGINT NOT NULL,
+    leading_ms INTEGER NOT NULL,
+    trailing_ms INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_track_silence_trims_trimmed CHECK (leading_ms >= 0 AND trailing_ms >= 0 AND leading_ms + trailing_ms > 0)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS track_loudness_normalizations;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS normalize_loudness;
@@ -0,0 +1,13 @@
+-- Users may have their downloads re-encoded to the server's target loudness.
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS normalize_loudness BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Loudness normalization applied to a track's audio when it was
+-- downloaded. original_storage_key is the unnormalized file, when kept.
+CREATE TABLE IF NOT EXISTS track_loudness_normalizations (
+    track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    input_lufs DOUBLE PRECISION NOT NULL,
+    target_lufs DOUBLE PRECISION NOT NULL,
+    original_storage_key TEXT,
+    original_size_bytes BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
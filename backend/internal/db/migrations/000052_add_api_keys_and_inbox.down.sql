@@ -0,0 +1,2 @@
+ALTER TABLE user_settings DROP COLUMN IF EXISTS inbox_playlist_id;
+DROP TABLE IF EXISTS api_keys;
@@ -0,0 +1,15 @@
+-- API keys let browser extensions and bookmarklets quick-add downloads
+-- without a session. Only a SHA-256 hash of each key is kept.
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_api_keys_user_id ON api_keys(user_id);
+
+-- Quick-added downloads land in the user's inbox playlist, when set.
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_playlist_id BIGINT REFERENCES playlists(id) ON DELETE SET NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE user_settings DROP COLUMN IF EXISTS inbox_in_shuffle;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS inbox_in_search;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS inbox_new_tracks;
+DROP INDEX IF EXISTS idx_user_library_inbox;
+ALTER TABLE user_library DROP COLUMN IF EXISTS in_inbox;
@@ -0,0 +1,7 @@
+-- Newly ingested tracks can wait in the library inbox for review before
+-- they show up in search and shuffle.
+ALTER TABLE user_library ADD COLUMN IF NOT EXISTS in_inbox BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE INDEX IF NOT EXISTS idx_user_library_inbox ON user_library(user_id, added_at DESC) WHERE in_inbox;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_new_tracks BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_in_search BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS inbox_in_shuffle BOOLEAN NOT NULL DEFAULT FALSE;
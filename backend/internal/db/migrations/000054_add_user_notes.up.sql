@@ -0,0 +1,18 @@
+-- Per-user freeform notes on a track, a MusicBrainz release, or a
+-- playlist; exactly one target is set. search_text is the normalized body.
+CREATE TABLE IF NOT EXISTS user_notes (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    track_id BIGINT REFERENCES tracks(id) ON DELETE CASCADE,
+    mb_release_id UUID,
+    playlist_id BIGINT REFERENCES playlists(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    search_text TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(track_id, mb_release_id, playlist_id) = 1)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_notes_track ON user_notes(user_id, track_id) WHERE track_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_notes_album ON user_notes(user_id, mb_release_id) WHERE mb_release_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_notes_playlist ON user_notes(user_id, playlist_id) WHERE playlist_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_user_notes_search ON user_notes USING GIN (to_tsvector('english', search_text));
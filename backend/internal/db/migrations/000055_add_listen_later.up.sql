@@ -0,0 +1,20 @@
+-- Tracks and not-yet-downloaded discovery results a user saved to hear
+-- soon, apart from playlists; exactly one of track_id and source_url is
+-- set. Playing the track, or a track downloaded from the source URL, to
+-- the end removes the entry.
+CREATE TABLE IF NOT EXISTS listen_later (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    track_id BIGINT REFERENCES tracks(id) ON DELETE CASCADE,
+    provider VARCHAR(50),
+    source_url TEXT,
+    title TEXT,
+    artist TEXT,
+    duration_ms INTEGER,
+    thumbnail_url TEXT,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(track_id, source_url) = 1)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_listen_later_track ON listen_later(user_id, track_id) WHERE track_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_listen_later_source ON listen_later(user_id, source_url) WHERE source_url IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_listen_later_user ON listen_later(user_id, added_at DESC);
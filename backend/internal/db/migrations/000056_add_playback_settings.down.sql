@@ -0,0 +1,3 @@
+ALTER TABLE user_settings DROP COLUMN IF EXISTS normalization_target_lufs;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS gapless;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS crossfade_seconds;
@@ -0,0 +1,4 @@
+-- Playback preferences every client of the user applies to the queue.
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS crossfade_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS gapless BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS normalization_target_lufs DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- Players a user registered with the codecs they decode and the highest
+-- bitrate they want, so playback URLs come in a format they play.
+CREATE TABLE IF NOT EXISTS playback_clients (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    codecs TEXT[] NOT NULL DEFAULT '{}',
+    max_bitrate_kbps INTEGER NOT NULL DEFAULT 0 CHECK (max_bitrate_kbps >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
@@ -0,0 +1,24 @@
+DROP TRIGGER IF EXISTS trg_user_library_events_insert ON user_library;
+DROP TRIGGER IF EXISTS trg_user_library_events_update ON user_library;
+DROP TRIGGER IF EXISTS trg_user_library_events_delete ON user_library;
+DROP TRIGGER IF EXISTS trg_track_favorites_events_insert ON track_favorites;
+DROP TRIGGER IF EXISTS trg_track_favorites_events_update ON track_favorites;
+DROP TRIGGER IF EXISTS trg_track_favorites_events_delete ON track_favorites;
+DROP TRIGGER IF EXISTS trg_track_ratings_events_insert ON track_ratings;
+DROP TRIGGER IF EXISTS trg_track_ratings_events_update ON track_ratings;
+DROP TRIGGER IF EXISTS trg_track_ratings_events_delete ON track_ratings;
+DROP TRIGGER IF EXISTS trg_playlists_events_insert ON playlists;
+DROP TRIGGER IF EXISTS trg_playlists_events_update ON playlists;
+DROP TRIGGER IF EXISTS trg_playlists_events_delete ON playlists;
+DROP TRIGGER IF EXISTS trg_playlist_tracks_events_insert ON playlist_tracks;
+DROP TRIGGER IF EXISTS trg_playlist_tracks_events_update ON playlist_tracks;
+DROP TRIGGER IF EXISTS trg_playlist_tracks_events_delete ON playlist_tracks;
+DROP TRIGGER IF EXISTS trg_tracks_events_update ON tracks;
+DROP TRIGGER IF EXISTS trg_tracks_events_delete ON tracks;
+DROP FUNCTION IF EXISTS record_user_track_event();
+DROP FUNCTION IF EXISTS record_playlist_event();
+DROP FUNCTION IF EXISTS record_playlist_tracks_event();
+DROP FUNCTION IF EXISTS record_track_event();
+DROP FUNCTION IF EXISTS event_actor();
+DROP TABLE IF EXISTS events;
+DROP FUNCTION IF EXISTS reject_event_update();
@@ -0,0 +1,232 @@
+-- Every change to a user's library, favorites, ratings and playlists, and
+-- to catalog track metadata. Triggers append the rows, so no write path
+-- can skip them, and the change journal reads them back. user_id is whose
+-- data changed, NULL for catalog tracks; actor_id is who changed it, NULL
+-- for the server: repositories name the user a request acts for in the
+-- transaction-local omp.actor setting (see WithActor). Rows are never
+-- updated and go only with their user.
+CREATE TABLE IF NOT EXISTS events (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    actor_id UUID,
+    entity_type VARCHAR(20) NOT NULL CHECK (entity_type IN ('track', 'playlist')),
+    entity_id BIGINT NOT NULL,
+    action VARCHAR(40) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    tx_id XID8 NOT NULL DEFAULT pg_current_xact_id(),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_events_user ON events(user_id, id);
+CREATE INDEX IF NOT EXISTS idx_events_entity ON events(entity_type, entity_id, id);
+
+CREATE OR REPLACE FUNCTION reject_event_update()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'events are append-only';
+END;
+$$ LANGUAGE plpgsql;
+DROP TRIGGER IF EXISTS trg_events_append_only ON events;
+CREATE TRIGGER trg_events_append_only
+    BEFORE UPDATE ON events
+    FOR EACH ROW EXECUTE FUNCTION reject_event_update();
+
+-- The user the current transaction acts for, or NULL for the server.
+CREATE OR REPLACE FUNCTION event_actor()
+RETURNS UUID AS $$
+    SELECT NULLIF(current_setting('omp.actor', true), '')::uuid;
+$$ LANGUAGE sql STABLE;
+
+-- Library, favorite and rating rows: TG_ARGV[0] names the action, and the
+-- payload is the row without its keys and timestamps. Deletes cascading
+-- from a deleted user are not recorded.
+CREATE OR REPLACE FUNCTION record_user_track_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT n.user_id, event_actor(), 'track', n.track_id, TG_ARGV[0],
+            to_jsonb(n) - 'user_id' - 'track_id' - 'added_at' - 'created_at' - 'updated_at'
+        FROM new_rows n;
+    ELSIF TG_OP = 'UPDATE' THEN
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT n.user_id, event_actor(), 'track', n.track_id, TG_ARGV[0],
+            to_jsonb(n) - 'user_id' - 'track_id' - 'added_at' - 'created_at' - 'updated_at'
+        FROM new_rows n
+        JOIN old_rows o ON o.user_id = n.user_id AND o.track_id = n.track_id
+        WHERE to_jsonb(n) - 'added_at' - 'created_at' - 'updated_at'
+            IS DISTINCT FROM to_jsonb(o) - 'added_at' - 'created_at' - 'updated_at';
+    ELSE
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action)
+        SELECT o.user_id, event_actor(), 'track', o.track_id, TG_ARGV[0]
+        FROM old_rows o
+        JOIN users u ON u.id = o.user_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION record_playlist_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT n.user_id, event_actor(), 'playlist', n.id, 'playlist_created', jsonb_build_object('name', n.name)
+        FROM new_rows n;
+    ELSIF TG_OP = 'UPDATE' THEN
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT n.user_id, event_actor(), 'playlist', n.id, 'playlist_updated',
+            jsonb_build_object('name', n.name, 'description', n.description, 'cover_url', n.cover_url, 'is_public', n.is_public)
+        FROM new_rows n
+        JOIN old_rows o ON o.id = n.id
+        WHERE (n.name, n.description, n.cover_url, n.is_public)
+            IS DISTINCT FROM (o.name, o.description, o.cover_url, o.is_public);
+    ELSE
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT o.user_id, event_actor(), 'playlist', o.id, 'playlist_deleted', jsonb_build_object('name', o.name)
+        FROM old_rows o
+        JOIN users u ON u.id = o.user_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- One event per playlist and statement, listing the tracks added or
+-- removed. Moves take several statements, so they record one reorder
+-- per transaction, and none when the transaction also removed tracks,
+-- whose renumbering is no reorder. Rows going with a deleted playlist
+-- are not recorded.
+CREATE OR REPLACE FUNCTION record_playlist_tracks_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT p.user_id, event_actor(), 'playlist', p.id, 'playlist_tracks_added', jsonb_build_object('track_ids', added.track_ids)
+        FROM (
+            SELECT playlist_id, jsonb_agg(track_id ORDER BY position) AS track_ids
+            FROM new_rows GROUP BY playlist_id
+        ) added
+        JOIN playlists p ON p.id = added.playlist_id;
+    ELSIF TG_OP = 'UPDATE' THEN
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action)
+        SELECT p.user_id, event_actor(), 'playlist', p.id, 'playlist_reordered'
+        FROM playlists p
+        WHERE p.id IN (
+            SELECT n.playlist_id FROM new_rows n
+            JOIN old_rows o ON o.playlist_id = n.playlist_id AND o.track_id = n.track_id
+            WHERE n.position <> o.position
+        )
+        AND NOT EXISTS (
+            SELECT 1 FROM events e
+            WHERE e.entity_type = 'playlist' AND e.entity_id = p.id
+                AND e.action IN ('playlist_reordered', 'playlist_tracks_removed')
+                AND e.tx_id = pg_current_xact_id()
+        );
+    ELSE
+        INSERT INTO events (user_id, actor_id, entity_type, entity_id, action, payload)
+        SELECT p.user_id, event_actor(), 'playlist', p.id, 'playlist_tracks_removed', jsonb_build_object('track_ids', removed.track_ids)
+        FROM (
+            SELECT playlist_id, jsonb_agg(track_id ORDER BY position) AS track_ids
+            FROM old_rows GROUP BY playlist_id
+        ) removed
+        JOIN playlists p ON p.id = removed.playlist_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Catalog tracks are shared, so their events have no user; only changes
+-- to the metadata players show are recorded.
+CREATE OR REPLACE FUNCTION record_track_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' THEN
+        INSERT INTO events (actor_id, entity_type, entity_id, action, payload)
+        SELECT event_actor(), 'track', n.id, 'track_updated', jsonb_build_object(
+            'title', n.title, 'artist', n.artist, 'album', n.album, 'duration_ms', n.duration_ms,
+            'cover_art_url', n.cover_art_url, 'user_edited', n.metadata_user_edited)
+        FROM new_rows n
+        JOIN old_rows o ON o.id = n.id
+        WHERE (n.title, n.artist, n.album, n.duration_ms, n.cover_art_url)
+            IS DISTINCT FROM (o.title, o.artist, o.album, o.duration_ms, o.cover_art_url);
+    ELSE
+        INSERT INTO events (actor_id, entity_type, entity_id, action, payload)
+        SELECT event_actor(), 'track', o.id, 'track_deleted', jsonb_build_object('title', o.title, 'artist', o.artist)
+        FROM old_rows o;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_user_library_events_insert ON user_library;
+CREATE TRIGGER trg_user_library_events_insert AFTER INSERT ON user_library
+    REFERENCING NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('library_added');
+DROP TRIGGER IF EXISTS trg_user_library_events_update ON user_library;
+CREATE TRIGGER trg_user_library_events_update AFTER UPDATE ON user_library
+    REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('library_updated');
+DROP TRIGGER IF EXISTS trg_user_library_events_delete ON user_library;
+CREATE TRIGGER trg_user_library_events_delete AFTER DELETE ON user_library
+    REFERENCING OLD TABLE AS old_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('library_removed');
+
+DROP TRIGGER IF EXISTS trg_track_favorites_events_insert ON track_favorites;
+CREATE TRIGGER trg_track_favorites_events_insert AFTER INSERT ON track_favorites
+    REFERENCING NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('favorited');
+DROP TRIGGER IF EXISTS trg_track_favorites_events_update ON track_favorites;
+CREATE TRIGGER trg_track_favorites_events_update AFTER UPDATE ON track_favorites
+    REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('favorited');
+DROP TRIGGER IF EXISTS trg_track_favorites_events_delete ON track_favorites;
+CREATE TRIGGER trg_track_favorites_events_delete AFTER DELETE ON track_favorites
+    REFERENCING OLD TABLE AS old_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('unfavorited');
+
+DROP TRIGGER IF EXISTS trg_track_ratings_events_insert ON track_ratings;
+CREATE TRIGGER trg_track_ratings_events_insert AFTER INSERT ON track_ratings
+    REFERENCING NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('rated');
+DROP TRIGGER IF EXISTS trg_track_ratings_events_update ON track_ratings;
+CREATE TRIGGER trg_track_ratings_events_update AFTER UPDATE ON track_ratings
+    REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('rated');
+DROP TRIGGER IF EXISTS trg_track_ratings_events_delete ON track_ratings;
+CREATE TRIGGER trg_track_ratings_events_delete AFTER DELETE ON track_ratings
+    REFERENCING OLD TABLE AS old_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_user_track_event('unrated');
+
+DROP TRIGGER IF EXISTS trg_playlists_events_insert ON playlists;
+CREATE TRIGGER trg_playlists_events_insert AFTER INSERT ON playlists
+    REFERENCING NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_event();
+DROP TRIGGER IF EXISTS trg_playlists_events_update ON playlists;
+CREATE TRIGGER trg_playlists_events_update AFTER UPDATE ON playlists
+    REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_event();
+DROP TRIGGER IF EXISTS trg_playlists_events_delete ON playlists;
+CREATE TRIGGER trg_playlists_events_delete AFTER DELETE ON playlists
+    REFERENCING OLD TABLE AS old_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_event();
+
+DROP TRIGGER IF EXISTS trg_playlist_tracks_events_insert ON playlist_tracks;
+CREATE TRIGGER trg_playlist_tracks_events_insert AFTER INSERT ON playlist_tracks
+    REFERENCING NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_tracks_event();
+DROP TRIGGER IF EXISTS trg_playlist_tracks_events_update ON playlist_tracks;
+CREATE TRIGGER trg_playlist_tracks_events_update AFTER UPDATE ON playlist_tracks
+    REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_tracks_event();
+DROP TRIGGER IF EXISTS trg_playlist_tracks_events_delete ON playlist_tracks;
+CREATE TRIGGER trg_playlist_tracks_events_delete AFTER DELETE ON playlist_tracks
+    REFERENCING OLD TABLE AS old_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_playlist_tracks_event();
+
+DROP TRIGGER IF EXISTS trg_tracks_events_update ON tracks;
+CREATE TRIGGER trg_tracks_events_update AFTER UPDATE ON tracks
+    REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_track_event();
+DROP TRIGGER IF EXISTS trg_tracks_events_delete ON tracks;
+CREATE TRIGGER trg_tracks_events_delete AFTER DELETE ON tracks
+    REFERENCING OLD TABLE AS old_rows
+    FOR EACH STATEMENT EXECUTE FUNCTION record_track_event();
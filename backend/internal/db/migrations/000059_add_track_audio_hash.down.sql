@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_tracks_audio_hash_duration;
+ALTER TABLE tracks DROP COLUMN IF EXISTS audio_hash;
@@ -0,0 +1,5 @@
+-- A signature of the decoded audio, so another encoding of a stored
+-- recording is found whatever its tags say. An empty hash records audio too
+-- short or flat to tell apart.
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS audio_hash VARCHAR(64);
+CREATE INDEX IF NOT EXISTS idx_tracks_audio_hash_duration ON tracks(duration_ms) WHERE audio_hash <> '';
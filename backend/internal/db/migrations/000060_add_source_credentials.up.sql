@@ -0,0 +1,16 @@
+-- Login cookies users upload for sources that need them, sealed with
+-- SOURCE_CREDENTIALS_KEY.
+CREATE TABLE IF NOT EXISTS source_credentials (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source VARCHAR(253) NOT NULL,
+    sealed_cookies BYTEA NOT NULL,
+    cookie_count INTEGER NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    status VARCHAR(16) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'expired', 'rejected')),
+    status_detail TEXT NOT NULL DEFAULT '',
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, source)
+);
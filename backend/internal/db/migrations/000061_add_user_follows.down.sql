@@ -0,0 +1,3 @@
+ALTER TABLE user_settings DROP COLUMN IF EXISTS share_listening;
+ALTER TABLE user_settings DROP COLUMN IF EXISTS accept_followers;
+DROP TABLE IF EXISTS user_follows;
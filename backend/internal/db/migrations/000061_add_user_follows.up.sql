@@ -0,0 +1,14 @@
+-- Users following each other on the instance. Followers see the followee's
+-- public playlists, and their listening when share_listening is on; only
+-- users with accept_followers on can gain followers.
+CREATE TABLE IF NOT EXISTS user_follows (
+    follower_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    followee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+);
+CREATE INDEX IF NOT EXISTS idx_user_follows_followee ON user_follows(followee_id);
+
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS accept_followers BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS share_listening BOOLEAN NOT NULL DEFAULT FALSE;
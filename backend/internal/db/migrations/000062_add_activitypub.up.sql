@@ -0,0 +1,33 @@
+-- ActivityPub actors of users publishing to the fediverse, their remote
+-- followers, and the posts they published; see the activitypub package.
+-- last_event_id and last_play_id are how far the publisher has read.
+CREATE TABLE IF NOT EXISTS activitypub_actors (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    handle VARCHAR(30) NOT NULL UNIQUE,
+    public_key_pem TEXT NOT NULL,
+    private_key_pem TEXT NOT NULL,
+    publish_listening BOOLEAN NOT NULL DEFAULT FALSE,
+    publish_loved BOOLEAN NOT NULL DEFAULT TRUE,
+    publish_playlists BOOLEAN NOT NULL DEFAULT TRUE,
+    last_event_id BIGINT NOT NULL DEFAULT 0,
+    last_play_id BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE TABLE IF NOT EXISTS activitypub_followers (
+    user_id UUID NOT NULL REFERENCES activitypub_actors(user_id) ON DELETE CASCADE,
+    actor_iri TEXT NOT NULL,
+    inbox TEXT NOT NULL,
+    shared_inbox TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, actor_iri)
+);
+CREATE TABLE IF NOT EXISTS activitypub_posts (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES activitypub_actors(user_id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('listening', 'loved', 'playlist')),
+    content TEXT NOT NULL,
+    playlist_id BIGINT,
+    published_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_activitypub_posts_user ON activitypub_posts(user_id, id DESC);
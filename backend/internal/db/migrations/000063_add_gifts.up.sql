@@ -0,0 +1,17 @@
+-- Tracks and playlists users of an instance send each other. The recipient
+-- accepts one into their library, which references the shared catalog
+-- track, or dismisses it.
+CREATE TABLE IF NOT EXISTS gifts (
+    id BIGSERIAL PRIMARY KEY,
+    sender_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    recipient_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    track_id BIGINT REFERENCES tracks(id) ON DELETE CASCADE,
+    playlist_id BIGINT REFERENCES playlists(id) ON DELETE CASCADE,
+    message TEXT NOT NULL DEFAULT '',
+    status VARCHAR(16) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'dismissed')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    responded_at TIMESTAMP WITH TIME ZONE,
+    CHECK ((track_id IS NULL) <> (playlist_id IS NULL)),
+    CHECK (sender_id <> recipient_id)
+);
+CREATE INDEX IF NOT EXISTS idx_gifts_recipient ON gifts(recipient_id, status, id DESC);
@@ -0,0 +1,15 @@
+-- Phones registered for push notifications. token is the UnifiedPush
+-- endpoint URL or the FCM registration token; types are the notification
+-- types the device opted in to.
+CREATE TABLE IF NOT EXISTS push_devices (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider VARCHAR(16) NOT NULL CHECK (provider IN ('unifiedpush', 'fcm')),
+    token TEXT NOT NULL,
+    name VARCHAR(100) NOT NULL DEFAULT '',
+    types TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (provider, token)
+);
+CREATE INDEX IF NOT EXISTS idx_push_devices_user ON push_devices(user_id);
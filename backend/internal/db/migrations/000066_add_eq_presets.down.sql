@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS playback_clients DROP COLUMN IF EXISTS eq_preset_id;
+DROP TABLE IF EXISTS eq_presets;
@@ -0,0 +1,17 @@
+-- Named equalizer settings a user keeps on the server so every player
+-- applies the same EQ. bands is a JSON array of {frequencyHz, gainDb, q};
+-- at most one preset per user is the default, which players use unless
+-- their registration overrides it with eq_preset_id.
+CREATE TABLE IF NOT EXISTS eq_presets (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    preamp_db REAL NOT NULL DEFAULT 0,
+    bands JSONB NOT NULL DEFAULT '[]',
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_eq_presets_default ON eq_presets(user_id) WHERE is_default;
+ALTER TABLE playback_clients ADD COLUMN IF NOT EXISTS eq_preset_id BIGINT REFERENCES eq_presets(id) ON DELETE SET NULL;
@@ -0,0 +1,15 @@
+-- Measured quality of each track's stored file: its dynamic range (DR)
+-- score, the frequency its spectrum stops at when that is below what its
+-- sample rate carries, and whether a lossless file looks upscaled from a
+-- lossy source. score rates the file from 0 to 100. storage_key is the
+-- object measured, so a converted file is measured again.
+CREATE TABLE IF NOT EXISTS track_quality (
+    track_id BIGINT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    storage_key TEXT NOT NULL,
+    dr_score SMALLINT,
+    spectral_cutoff_hz INTEGER,
+    upscale_suspected BOOLEAN NOT NULL DEFAULT FALSE,
+    score SMALLINT NOT NULL CHECK (score BETWEEN 0 AND 100),
+    analyzed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_track_quality_score ON track_quality(score);
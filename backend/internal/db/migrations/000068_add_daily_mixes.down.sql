@@ -0,0 +1,3 @@
+DELETE FROM playlists WHERE id IN (SELECT playlist_id FROM daily_mixes);
+DROP TABLE IF EXISTS daily_mixes;
+ALTER TABLE IF EXISTS playlists DROP COLUMN IF EXISTS read_only;
@@ -0,0 +1,13 @@
+-- Daily mixes: playlists the server regenerates each day from a user's
+-- library and listening, one per cluster of genre and mood. They are owned
+-- by the user but read_only, so only the generator changes them.
+ALTER TABLE playlists ADD COLUMN IF NOT EXISTS read_only BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE TABLE IF NOT EXISTS daily_mixes (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    position SMALLINT NOT NULL CHECK (position > 0),
+    playlist_id BIGINT NOT NULL UNIQUE REFERENCES playlists(id) ON DELETE CASCADE,
+    genre TEXT NOT NULL,
+    mood TEXT,
+    generated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, position)
+);
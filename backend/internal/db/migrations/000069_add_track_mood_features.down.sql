@@ -0,0 +1,4 @@
+ALTER TABLE tracks DROP COLUMN IF EXISTS mood_storage_key;
+ALTER TABLE tracks DROP COLUMN IF EXISTS danceability;
+ALTER TABLE tracks DROP COLUMN IF EXISTS valence;
+ALTER TABLE tracks DROP COLUMN IF EXISTS energy;
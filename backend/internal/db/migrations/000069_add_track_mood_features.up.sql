@@ -0,0 +1,8 @@
+-- Mood features: rough 0-1 proxies for a track's energy, valence and
+-- danceability, measured from its stored audio's loudness, dynamics,
+-- spectrum and pulse. mood_storage_key is the object measured, so a
+-- converted file is measured again.
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS energy REAL CHECK (energy BETWEEN 0 AND 1);
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS valence REAL CHECK (valence BETWEEN 0 AND 1);
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS danceability REAL CHECK (danceability BETWEEN 0 AND 1);
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS mood_storage_key TEXT;
@@ -0,0 +1,3 @@
+-- Suggestions biased by the time of day the user listens at, off until the
+-- user turns them on.
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS context_suggestions BOOLEAN NOT NULL DEFAULT FALSE;
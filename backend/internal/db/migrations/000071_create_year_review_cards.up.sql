@@ -0,0 +1,9 @@
+-- Year-in-review cards shared by link. Only a SHA-256 hash of each link's
+-- token is kept; the card itself is rendered from the stats on request.
+CREATE TABLE IF NOT EXISTS year_review_cards (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    year INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_year_review_cards_user_year ON year_review_cards(user_id, year);
@@ -0,0 +1,85 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+func TestDownMigrationsCoverEveryReversibleVersion(t *testing.T) {
+	steps, err := downMigrations(OldestReversibleVersion, SchemaVersion)
+	if err != nil {
+		t.Fatalf("downMigrations: %v", err)
+	}
+	if len(steps) != SchemaVersion-OldestReversibleVersion || steps[0].version != SchemaVersion || !strings.HasSuffix(steps[0].name, ".down.sql") {
+		t.Fatalf("downMigrations = %d steps starting %+v", len(steps), steps[0])
+	}
+	if _, err := downMigrations(OldestReversibleVersion, SchemaVersion+1); err == nil {
+		t.Fatal("downMigrations accepted a version without a down file")
+	}
+}
+
+func TestShrunkTables(t *testing.T) {
+	before := map[string]int64{"users": 3, "tracks": 200, "play_events": 900}
+	after := map[string]int64{"users": 3, "tracks": 150, "play_events": 950, "playlists": 4}
+	if got, want := ShrunkTables(before, after), []string{"tracks"}; !reflect.DeepEqual(got, want) {
+		t.Fatalf("ShrunkTables = %v, want %v", got, want)
+	}
+}
+
+func TestMigrateDownAndDataChecksAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	seedPlayUser(t, database, "migrate@example.test")
+
+	for _, check := range DataChecks {
+		if count, err := database.CountViolations(ctx, check); err != nil || count != 0 {
+			t.Errorf("%s on a fresh schema = %d, %v", check.Name, count, err)
+		}
+	}
+	before, err := database.RowCounts(ctx)
+	if err != nil || before["users"] != 1 || len(before) != len(CountedTables) {
+		t.Fatalf("RowCounts = %v, %v", before, err)
+	}
+
+	if err := database.MigrateDown(ctx, SchemaVersion); err == nil {
+		t.Fatal("MigrateDown to the current version succeeded")
+	}
+	// Version 55 is the schema before the playback settings columns.
+	const target = 55
+	if err := database.MigrateDown(ctx, target); err != nil {
+		t.Fatalf("MigrateDown: %v", err)
+	}
+	var events, clients, gapless bool
+	err = database.QueryRowContext(ctx, `SELECT to_regclass('events') IS NOT NULL, to_regclass('playback_clients') IS NOT NULL,
+		EXISTS (SELECT 1 FROM information_schema.columns WHERE table_name = 'user_settings' AND column_name = 'gapless')`).Scan(&events, &clients, &gapless)
+	if err != nil || events || clients || gapless {
+		t.Fatalf("after MigrateDown: events %v, playback_clients %v, gapless column %v, err %v", events, clients, gapless, err)
+	}
+	if version, err := database.MigratedVersion(ctx); err != nil || version != target {
+		t.Fatalf("MigratedVersion = %d, %v; want %d", version, err, target)
+	}
+
+	if err := database.Migrate(); err != nil {
+		t.Fatalf("Migrate after MigrateDown: %v", err)
+	}
+	after, err := database.RowCounts(ctx)
+	if err != nil || len(ShrunkTables(before, after)) != 0 {
+		t.Fatalf("RowCounts after round trip = %v, %v; before %v", after, err, before)
+	}
+}
+
+func TestMigrateRefusesANewerSchemaAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	if _, err := database.Exec(`UPDATE schema_version SET version = $1`, SchemaVersion+1); err != nil {
+		t.Fatal(err)
+	}
+	if err := database.Migrate(); !errors.Is(err, ErrSchemaTooNew) {
+		t.Fatalf("Migrate over a newer schema = %v, want ErrSchemaTooNew", err)
+	}
+	if version, err := database.MigratedVersion(context.Background()); err != nil || version != SchemaVersion+1 {
+		t.Fatalf("MigratedVersion = %d, %v; the refused migration must not record its version", version, err)
+	}
+}
@@ -0,0 +1,201 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// Note targets: a track, an album by MusicBrainz release ID, or a playlist.
+const (
+	NoteTargetTrack    = "track"
+	NoteTargetAlbum    = "album"
+	NoteTargetPlaylist = "playlist"
+)
+
+var ErrNoteNotFound = errors.New("note not found")
+
+// Note is a user's freeform annotation of one track, album, or playlist.
+// TargetID is the track or playlist ID, or the album's MusicBrainz release
+// ID, as text.
+type Note struct {
+	ID         int64
+	TargetType string
+	TargetID   string
+	Body       string
+	CreatedAt  time.Time
+	UpdatedAt  time.Time
+}
+
+// NoteQueryOptions filters a listing of a user's notes.
+type NoteQueryOptions struct {
+	TargetType string // One of the NoteTarget values; empty lists every target
+	Search     string // Full-text search of the note bodies
+	Limit      int
+	Offset     int
+}
+
+type NoteRepository struct {
+	db *DB
+}
+
+func NewNoteRepository(db *DB) *NoteRepository {
+	return &NoteRepository{db: db}
+}
+
+// noteTargetColumn returns the user_notes column and SQL type holding
+// targets of targetType, or "" for an unknown type.
+func noteTargetColumn(targetType string) (column, sqlType string) {
+	switch targetType {
+	case NoteTargetTrack:
+		return "track_id", "bigint"
+	case NoteTargetAlbum:
+		return "mb_release_id", "uuid"
+	case NoteTargetPlaylist:
+		return "playlist_id", "bigint"
+	}
+	return "", ""
+}
+
+const noteColumns = `id,
+	CASE WHEN track_id IS NOT NULL THEN 'track' WHEN mb_release_id IS NOT NULL THEN 'album' ELSE 'playlist' END,
+	COALESCE(track_id::text, mb_release_id::text, playlist_id::text),
+	body, created_at, updated_at`
+
+func scanNote(row rowScanner) (*Note, error) {
+	var n Note
+	if err := row.Scan(&n.ID, &n.TargetType, &n.TargetID, &n.Body, &n.CreatedAt, &n.UpdatedAt); err != nil {
+		return nil, err
+	}
+	return &n, nil
+}
+
+// Get returns the user's note on a target.
+func (r *NoteRepository) Get(ctx context.Context, userID uuid.UUID, targetType, targetID string) (*Note, error) {
+	column, sqlType := noteTargetColumn(targetType)
+	if column == "" {
+		return nil, ErrNoteNotFound
+	}
+	note, err := scanNote(r.db.QueryRowContext(ctx, `
+		SELECT `+noteColumns+`
+		FROM user_notes
+		WHERE user_id = $1 AND `+column+` = $2::`+sqlType, userID, targetID))
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrNoteNotFound
+	}
+	return note, err
+}
+
+// Set saves the user's note on a target, replacing any earlier one.
+func (r *NoteRepository) Set(ctx context.Context, userID uuid.UUID, targetType, targetID, body string) (*Note, error) {
+	column, sqlType := noteTargetColumn(targetType)
+	if column == "" {
+		return nil, ErrNoteNotFound
+	}
+	return scanNote(r.db.QueryRowContext(ctx, `
+		INSERT INTO user_notes (user_id, `+column+`, body, search_text)
+		VALUES ($1, $2::`+sqlType+`, $3, $4)
+		ON CONFLICT (user_id, `+column+`) WHERE `+column+` IS NOT NULL DO UPDATE SET
+			body = EXCLUDED.body,
+			search_text = EXCLUDED.search_text,
+			updated_at = NOW()
+		RETURNING `+noteColumns, userID, targetID, body, SearchText(body)))
+}
+
+// Delete removes the user's note on a target.
+func (r *NoteRepository) Delete(ctx context.Context, userID uuid.UUID, targetType, targetID string) error {
+	column, sqlType := noteTargetColumn(targetType)
+	if column == "" {
+		return ErrNoteNotFound
+	}
+	result, err := r.db.ExecContext(ctx, `DELETE FROM user_notes WHERE user_id = $1 AND `+column+` = $2::`+sqlType, userID, targetID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrNoteNotFound
+	}
+	return nil
+}
+
+// List returns a page of the user's notes, most recently edited first, with
+// the total matching count.
+func (r *NoteRepository) List(ctx context.Context, userID uuid.UUID, opts NoteQueryOptions) ([]Note, int, error) {
+	if opts.Limit <= 0 {
+		opts.Limit = 50
+	}
+	if opts.Limit > 100 {
+		opts.Limit = 100
+	}
+	condition := "user_id = $1"
+	args := []interface{}{userID}
+	if column, _ := noteTargetColumn(opts.TargetType); column != "" {
+		condition += " AND " + column + " IS NOT NULL"
+	}
+	if opts.Search != "" {
+		tsQuery := searchTSQuery(opts.Search)
+		if tsQuery == "" {
+			return []Note{}, 0, nil
+		}
+		args = append(args, tsQuery)
+		condition += " AND to_tsvector('english', search_text) @@ to_tsquery('english', $" + itoa(len(args)) + ")"
+	}
+	args = append(args, opts.Limit, opts.Offset)
+
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT `+noteColumns+`, COUNT(*) OVER()
+		FROM user_notes
+		WHERE `+condition+`
+		ORDER BY updated_at DESC, id DESC
+		LIMIT $`+itoa(len(args)-1)+` OFFSET $`+itoa(len(args)), args...)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+
+	notes := []Note{}
+	var total int
+	for rows.Next() {
+		var n Note
+		if err := rows.Scan(&n.ID, &n.TargetType, &n.TargetID, &n.Body, &n.CreatedAt, &n.UpdatedAt, &total); err != nil {
+			return nil, 0, err
+		}
+		notes = append(notes, n)
+	}
+	return notes, total, rows.Err()
+}
+
+// NotesFor returns the user's notes on the given targets of one type, keyed
+// by target ID. Targets without a note are left out.
+func (r *NoteRepository) NotesFor(ctx context.Context, userID uuid.UUID, targetType string, targetIDs []string) (map[string]string, error) {
+	column, sqlType := noteTargetColumn(targetType)
+	notes := make(map[string]string)
+	if column == "" || len(targetIDs) == 0 {
+		return notes, nil
+	}
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT `+column+`::text, body
+		FROM user_notes
+		WHERE user_id = $1 AND `+column+` = ANY($2::`+sqlType+`[])
+	`, userID, pq.Array(targetIDs))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var id, body string
+		if err := rows.Scan(&id, &body); err != nil {
+			return nil, err
+		}
+		notes[id] = body
+	}
+	return notes, rows.Err()
+}
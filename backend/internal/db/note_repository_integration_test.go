@@ -0,0 +1,75 @@
+package db
+
+import (
+	"errors"
+	"strconv"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+// TestNotesAgainstPostgres covers the note round trip: notes are upserted per
+// target, found by note and library search, kept private to their author, and
+// removed with the track they annotate.
+func TestNotesAgainstPostgres(t *testing.T) {
+	database, ctx := newFavoritesTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	notes := NewNoteRepository(database)
+
+	user := seedFavUser(t, database, "notes@test.local")
+	other := seedFavUser(t, database, "other-notes@test.local")
+	track := seedFavTrack(t, trackRepo, ctx, "Artist A", "Plain Title")
+	if _, err := libRepo.AddTrackToLibrary(ctx, user, track); err != nil {
+		t.Fatalf("add track: %v", err)
+	}
+	trackID := strconv.FormatInt(track, 10)
+	release := uuid.New().String()
+
+	if _, err := notes.Set(ctx, user, NoteTargetTrack, trackID, "Ripped from a cassette"); err != nil {
+		t.Fatalf("Set(track): %v", err)
+	}
+	note, err := notes.Set(ctx, user, NoteTargetTrack, trackID, "Ripped from a vinyl bootleg")
+	if err != nil {
+		t.Fatalf("Set(track) again: %v", err)
+	}
+	if note.TargetType != NoteTargetTrack || note.TargetID != trackID || note.Body != "Ripped from a vinyl bootleg" {
+		t.Fatalf("updated note = %+v", note)
+	}
+	if _, err := notes.Set(ctx, user, NoteTargetAlbum, release, "First pressing"); err != nil {
+		t.Fatalf("Set(album): %v", err)
+	}
+
+	found, total, err := notes.List(ctx, user, NoteQueryOptions{Search: "bootleg"})
+	if err != nil {
+		t.Fatalf("List(search): %v", err)
+	}
+	if total != 1 || found[0].TargetID != trackID {
+		t.Fatalf("search results = %+v (total %d); want the track note", found, total)
+	}
+	if _, total, err = notes.List(ctx, user, NoteQueryOptions{TargetType: NoteTargetAlbum}); err != nil || total != 1 {
+		t.Fatalf("album notes: total %d, err %v; want 1", total, err)
+	}
+	if _, err := notes.Get(ctx, other, NoteTargetTrack, trackID); !errors.Is(err, ErrNoteNotFound) {
+		t.Fatalf("Get as another user: err %v; want ErrNoteNotFound", err)
+	}
+
+	tracks, _, err := libRepo.GetUserLibrary(ctx, user, LibraryQueryOptions{Search: "bootleg"})
+	if err != nil {
+		t.Fatalf("GetUserLibrary(search): %v", err)
+	}
+	if len(tracks) != 1 || tracks[0].ID != track {
+		t.Fatalf("library search by note = %+v; want the noted track", tracks)
+	}
+	byAlbum, err := notes.NotesFor(ctx, user, NoteTargetAlbum, []string{release, uuid.New().String()})
+	if err != nil || len(byAlbum) != 1 || byAlbum[release] != "First pressing" {
+		t.Fatalf("NotesFor(album) = %v, err %v", byAlbum, err)
+	}
+
+	if _, err := database.ExecContext(ctx, `DELETE FROM tracks WHERE id = $1`, track); err != nil {
+		t.Fatalf("delete track: %v", err)
+	}
+	if _, err := notes.Get(ctx, user, NoteTargetTrack, trackID); !errors.Is(err, ErrNoteNotFound) {
+		t.Fatalf("note outlived its track: err %v", err)
+	}
+}
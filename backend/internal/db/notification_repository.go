@@ -0,0 +1,289 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// ErrNotificationNotFound is returned for a notification that does not exist
+// or belongs to another user.
+var ErrNotificationNotFound = errors.New("notification not found")
+
+// Notification types.
+const (
+	NotificationDownloadCompleted = "download_completed"
+	NotificationDownloadFailed    = "download_failed"
+	NotificationNewRelease        = "new_release"
+	NotificationGiftReceived      = "gift_received"
+	NotificationQuotaWarning      = "quota_warning"
+)
+
+// NotificationTypes lists every notification type, in the order preferences
+// are shown.
+var NotificationTypes = []string{
+	NotificationDownloadCompleted,
+	NotificationDownloadFailed,
+	NotificationNewRelease,
+	NotificationGiftReceived,
+	NotificationQuotaWarning,
+}
+
+// Notification is one message in a user's notification inbox. DedupeKey
+// identifies what it is about, so the same event never notifies twice.
+type Notification struct {
+	ID        int64
+	UserID    uuid.UUID
+	Type      string
+	DedupeKey string
+	Title     string
+	Body      string
+	Data      json.RawMessage
+	ReadAt    *time.Time
+	CreatedAt time.Time
+}
+
+// NewRelease is a track that joined the catalog by an artist the user already
+// has in their library.
+type NewRelease struct {
+	UserID  uuid.UUID
+	TrackID int64
+	Title   string
+	Artist  string
+}
+
+// QuotaWarning is a tenant admin to warn that their tenant is close to one of
+// its quotas.
+type QuotaWarning struct {
+	UserID          uuid.UUID
+	TenantID        int64
+	TenantName      string
+	Usage           TenantUsage
+	MaxTracks       sql.NullInt64
+	MaxStorageBytes sql.NullInt64
+}
+
+type NotificationRepository struct {
+	db *DB
+}
+
+func NewNotificationRepository(db *DB) *NotificationRepository {
+	return &NotificationRepository{db: db}
+}
+
+// Create stores n unless the user turned its type off or already has a
+// notification of the type with the same DedupeKey. It reports whether n was
+// stored, filling in its ID and CreatedAt.
+func (r *NotificationRepository) Create(ctx context.Context, n *Notification) (bool, error) {
+	data := n.Data
+	if len(data) == 0 {
+		data = json.RawMessage(`{}`)
+	}
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO notifications (user_id, type, dedupe_key, title, body, data)
+		SELECT $1, $2, $3, $4, $5, $6
+		WHERE NOT EXISTS (
+			SELECT 1 FROM notification_preferences WHERE user_id = $1 AND type = $2 AND NOT enabled
+		)
+		ON CONFLICT (user_id, type, dedupe_key) DO NOTHING
+		RETURNING id, created_at
+	`, n.UserID, n.Type, n.DedupeKey, n.Title, n.Body, []byte(data)).Scan(&n.ID, &n.CreatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return false, nil
+	}
+	if err != nil {
+		return false, err
+	}
+	n.Data = data
+	return true, nil
+}
+
+// List returns the user's notifications, newest first, only unread ones when
+// unreadOnly is set, with their total.
+func (r *NotificationRepository) List(ctx context.Context, userID uuid.UUID, unreadOnly bool, limit, offset int) ([]Notification, int, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, user_id, type, dedupe_key, title, body, data, read_at, created_at, COUNT(*) OVER()
+		FROM notifications
+		WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+		ORDER BY id DESC
+		LIMIT $3 OFFSET $4
+	`, userID, unreadOnly, limit, offset)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+	notifications := []Notification{}
+	var total int
+	for rows.Next() {
+		var n Notification
+		var data []byte
+		var readAt sql.NullTime
+		if err := rows.Scan(&n.ID, &n.UserID, &n.Type, &n.DedupeKey, &n.Title, &n.Body, &data, &readAt, &n.CreatedAt, &total); err != nil {
+			return nil, 0, err
+		}
+		n.Data = data
+		if readAt.Valid {
+			n.ReadAt = &readAt.Time
+		}
+		notifications = append(notifications, n)
+	}
+	return notifications, total, rows.Err()
+}
+
+// UnreadCount counts the user's unread notifications.
+func (r *NotificationRepository) UnreadCount(ctx context.Context, userID uuid.UUID) (int, error) {
+	var count int
+	err := r.db.QueryRowContext(ctx, `
+		SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL
+	`, userID).Scan(&count)
+	return count, err
+}
+
+// MarkRead marks one of the user's notifications read. One read before keeps
+// its first read time.
+func (r *NotificationRepository) MarkRead(ctx context.Context, userID uuid.UUID, id int64) error {
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE notifications SET read_at = COALESCE(read_at, NOW())
+		WHERE user_id = $1 AND id = $2
+	`, userID, id)
+	if err != nil {
+		return err
+	}
+	affected, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if affected == 0 {
+		return ErrNotificationNotFound
+	}
+	return nil
+}
+
+// MarkAllRead marks every unread notification of the user read and returns
+// how many there were.
+func (r *NotificationRepository) MarkAllRead(ctx context.Context, userID uuid.UUID) (int64, error) {
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL
+	`, userID)
+	if err != nil {
+		return 0, err
+	}
+	return result.RowsAffected()
+}
+
+// Preferences reports for every notification type whether the user receives
+// it. Types they never changed are on.
+func (r *NotificationRepository) Preferences(ctx context.Context, userID uuid.UUID) (map[string]bool, error) {
+	prefs := make(map[string]bool, len(NotificationTypes))
+	for _, kind := range NotificationTypes {
+		prefs[kind] = true
+	}
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT type, enabled FROM notification_preferences WHERE user_id = $1
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var kind string
+		var enabled bool
+		if err := rows.Scan(&kind, &enabled); err != nil {
+			return nil, err
+		}
+		if _, ok := prefs[kind]; ok {
+			prefs[kind] = enabled
+		}
+	}
+	return prefs, rows.Err()
+}
+
+// SetPreferences turns the given notification types on or off for the user,
+// leaving the others as they are.
+func (r *NotificationRepository) SetPreferences(ctx context.Context, userID uuid.UUID, prefs map[string]bool) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+	for kind, enabled := range prefs {
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO notification_preferences (user_id, type, enabled) VALUES ($1, $2, $3)
+			ON CONFLICT (user_id, type) DO UPDATE SET enabled = EXCLUDED.enabled
+		`, userID, kind, enabled); err != nil {
+			return err
+		}
+	}
+	return tx.Commit()
+}
+
+// NewReleases returns tracks added to the catalog after since by artists the
+// user has other tracks of, for every user of the track's tenant who does not
+// have the track yet. It returns at most limit of them, oldest first.
+func (r *NotificationRepository) NewReleases(ctx context.Context, since time.Time, limit int) ([]NewRelease, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT DISTINCT ul.user_id, t.id, t.title, t.artist
+		FROM tracks t
+		JOIN tracks known ON known.artist = t.artist AND known.id <> t.id
+			AND known.tenant_id IS NOT DISTINCT FROM t.tenant_id
+		JOIN user_library ul ON ul.track_id = known.id
+		WHERE t.created_at > $1 AND COALESCE(t.artist, '') <> ''
+			AND NOT EXISTS (SELECT 1 FROM user_library own WHERE own.user_id = ul.user_id AND own.track_id = t.id)
+		ORDER BY t.id, ul.user_id
+		LIMIT $2
+	`, since, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	releases := []NewRelease{}
+	for rows.Next() {
+		var release NewRelease
+		if err := rows.Scan(&release.UserID, &release.TrackID, &release.Title, &release.Artist); err != nil {
+			return nil, err
+		}
+		releases = append(releases, release)
+	}
+	return releases, rows.Err()
+}
+
+// QuotaWarnings returns the admins of every tenant that has used at least
+// ratio of its track or storage quota.
+func (r *NotificationRepository) QuotaWarnings(ctx context.Context, ratio float64) ([]QuotaWarning, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT u.id, t.id, t.name, usage.tracks, usage.bytes, t.max_tracks, t.max_storage_bytes
+		FROM tenants t
+		CROSS JOIN LATERAL (
+			SELECT COUNT(*) AS tracks, COALESCE(SUM(file_size_bytes), 0) AS bytes FROM tracks WHERE tenant_id = t.id
+		) usage
+		JOIN users u ON u.tenant_id = t.id AND u.tenant_admin
+		WHERE usage.tracks >= $1 * t.max_tracks OR usage.bytes >= $1 * t.max_storage_bytes
+		ORDER BY t.id, u.id
+	`, ratio)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	warnings := []QuotaWarning{}
+	for rows.Next() {
+		var w QuotaWarning
+		if err := rows.Scan(&w.UserID, &w.TenantID, &w.TenantName, &w.Usage.Tracks, &w.Usage.StorageBytes, &w.MaxTracks, &w.MaxStorageBytes); err != nil {
+			return nil, err
+		}
+		warnings = append(warnings, w)
+	}
+	return warnings, rows.Err()
+}
+
+// DeleteOlderThan removes notifications created before cutoff.
+func (r *NotificationRepository) DeleteOlderThan(ctx context.Context, cutoff time.Time) (int64, error) {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM notifications WHERE created_at < $1`, cutoff)
+	if err != nil {
+		return 0, err
+	}
+	return result.RowsAffected()
+}
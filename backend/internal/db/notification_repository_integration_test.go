@@ -0,0 +1,63 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+// TestNotificationsAgainstPostgres covers deduplication, preferences, read
+// state, and finding new releases by artists already in a library.
+func TestNotificationsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	repo := NewNotificationRepository(database)
+
+	user := seedPlayUser(t, database, "notified@example.test")
+	other := seedPlayUser(t, database, "other@example.test")
+
+	n := &Notification{UserID: user, Type: NotificationGiftReceived, DedupeKey: "gift:1", Title: "A gift"}
+	if created, err := repo.Create(ctx, n); err != nil || !created || n.ID == 0 {
+		t.Fatalf("Create = %v, %v, id %d", created, err, n.ID)
+	}
+	if created, err := repo.Create(ctx, &Notification{UserID: user, Type: NotificationGiftReceived, DedupeKey: "gift:1", Title: "Again"}); err != nil || created {
+		t.Fatalf("Create of a duplicate = %v, %v; want nothing stored", created, err)
+	}
+	if err := repo.SetPreferences(ctx, user, map[string]bool{NotificationGiftReceived: false}); err != nil {
+		t.Fatalf("SetPreferences: %v", err)
+	}
+	if created, err := repo.Create(ctx, &Notification{UserID: user, Type: NotificationGiftReceived, DedupeKey: "gift:2", Title: "Muted"}); err != nil || created {
+		t.Fatalf("Create of a muted type = %v, %v; want nothing stored", created, err)
+	}
+	if prefs, err := repo.Preferences(ctx, user); err != nil || prefs[NotificationGiftReceived] || !prefs[NotificationNewRelease] {
+		t.Fatalf("Preferences = %v, %v", prefs, err)
+	}
+
+	if err := repo.MarkRead(ctx, other, n.ID); !errors.Is(err, ErrNotificationNotFound) {
+		t.Fatalf("MarkRead of another user's notification = %v; want ErrNotificationNotFound", err)
+	}
+	if err := repo.MarkRead(ctx, user, n.ID); err != nil {
+		t.Fatalf("MarkRead: %v", err)
+	}
+	if unread, total, err := repo.List(ctx, user, true, 10, 0); err != nil || total != 0 || len(unread) != 0 {
+		t.Fatalf("List unread = %+v, %d, %v", unread, total, err)
+	}
+	if all, total, err := repo.List(ctx, user, false, 10, 0); err != nil || total != 1 || all[0].ReadAt == nil || string(all[0].Data) != "{}" {
+		t.Fatalf("List = %+v, %d, %v", all, total, err)
+	}
+
+	since := time.Now().Add(-time.Minute)
+	known := seedPlayTrack(t, trackRepo, ctx, "Band", "Old Song")
+	if _, err := library.AddTrackToLibrary(ctx, user, known); err != nil {
+		t.Fatalf("AddTrackToLibrary: %v", err)
+	}
+	fresh := seedPlayTrack(t, trackRepo, ctx, "Band", "New Song")
+	seedPlayTrack(t, trackRepo, ctx, "Stranger", "Unrelated")
+	releases, err := repo.NewReleases(ctx, since, 100)
+	if err != nil || len(releases) != 1 || releases[0].UserID != user || releases[0].TrackID != fresh {
+		t.Fatalf("NewReleases = %+v, %v; want only the new track for the user", releases, err)
+	}
+}
@@ -0,0 +1,137 @@
+package db
+
+import (
+	"database/sql"
+	"errors"
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+)
+
+var ErrInvalidPartialDate = errors.New("invalid partial date")
+
+// PartialDate is a release date known to year, month, or day precision, as
+// MusicBrainz reports them ("1998", "1998-05", "1998-05-12"). Month and Day
+// are zero when unknown; the zero PartialDate means no date at all.
+//
+// Dates order by year, then month, then day, with an unknown part sorting
+// before any known one, so "1998" < "1998-01" < "1998-01-01" < "1999".
+type PartialDate struct {
+	Year  int
+	Month int
+	Day   int
+}
+
+// ParsePartialDate parses YYYY, YYYY-MM, or YYYY-MM-DD. An empty string is
+// the zero date.
+func ParsePartialDate(value string) (PartialDate, error) {
+	value = strings.TrimSpace(value)
+	if value == "" {
+		return PartialDate{}, nil
+	}
+	parts := strings.Split(value, "-")
+	if len(parts) > 3 || len(parts[0]) != 4 {
+		return PartialDate{}, fmt.Errorf("%w: %q", ErrInvalidPartialDate, value)
+	}
+	var fields [3]int
+	for i, part := range parts {
+		n, err := strconv.Atoi(part)
+		if err != nil || (i > 0 && len(part) != 2) {
+			return PartialDate{}, fmt.Errorf("%w: %q", ErrInvalidPartialDate, value)
+		}
+		fields[i] = n
+	}
+	date := PartialDate{Year: fields[0], Month: fields[1], Day: fields[2]}
+	if !date.valid() {
+		return PartialDate{}, fmt.Errorf("%w: %q", ErrInvalidPartialDate, value)
+	}
+	return date, nil
+}
+
+func (d PartialDate) valid() bool {
+	if d.Year < 1 || d.Month < 0 || d.Month > 12 || d.Day < 0 {
+		return false
+	}
+	if d.Day > 0 {
+		// time.Date normalises overflow, so a day past the month's end
+		// lands in the following month.
+		return d.Month > 0 && time.Date(d.Year, time.Month(d.Month), d.Day, 0, 0, 0, 0, time.UTC).Day() == d.Day
+	}
+	return true
+}
+
+// IsZero reports whether the date is unknown.
+func (d PartialDate) IsZero() bool {
+	return d.Year == 0
+}
+
+// String formats the date at its own precision, or "" for the zero date.
+func (d PartialDate) String() string {
+	switch {
+	case d.IsZero():
+		return ""
+	case d.Month == 0:
+		return fmt.Sprintf("%04d", d.Year)
+	case d.Day == 0:
+		return fmt.Sprintf("%04d-%02d", d.Year, d.Month)
+	default:
+		return fmt.Sprintf("%04d-%02d-%02d", d.Year, d.Month, d.Day)
+	}
+}
+
+// Compare returns -1, 0, or 1 as d sorts before, with, or after other.
+func (d PartialDate) Compare(other PartialDate) int {
+	switch a, b := d.sortKey(), other.sortKey(); {
+	case a < b:
+		return -1
+	case a > b:
+		return 1
+	default:
+		return 0
+	}
+}
+
+// sortKey orders dates as YYYYMMDD with unknown parts as zero. It matches
+// releaseDateKeySQL.
+func (d PartialDate) sortKey() int {
+	return d.Year*10000 + d.Month*100 + d.Day
+}
+
+// endKey is the sort key of the last day d covers, making an upper bound of
+// "1999" include every date in 1999.
+func (d PartialDate) endKey() int {
+	month, day := d.Month, d.Day
+	if month == 0 {
+		month = 12
+	}
+	if day == 0 {
+		day = 31
+	}
+	return d.Year*10000 + month*100 + day
+}
+
+// releaseDateKeySQL is sortKey over a tracks row aliased t.
+const releaseDateKeySQL = "(t.release_year * 10000 + COALESCE(t.release_month, 0) * 100 + COALESCE(t.release_day, 0))"
+
+// partialDateArgs returns the column values for d, with NULL for unknown parts.
+func partialDateArgs(d PartialDate) (year, month, day sql.NullInt32) {
+	if d.IsZero() {
+		return
+	}
+	year = sql.NullInt32{Int32: int32(d.Year), Valid: true}
+	if d.Month > 0 {
+		month = sql.NullInt32{Int32: int32(d.Month), Valid: true}
+	}
+	if d.Day > 0 {
+		day = sql.NullInt32{Int32: int32(d.Day), Valid: true}
+	}
+	return
+}
+
+func partialDateFromColumns(year, month, day sql.NullInt32) PartialDate {
+	if !year.Valid {
+		return PartialDate{}
+	}
+	return PartialDate{Year: int(year.Int32), Month: int(month.Int32), Day: int(day.Int32)}
+}
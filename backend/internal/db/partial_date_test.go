@@ -0,0 +1,58 @@
+package db
+
+import (
+	"errors"
+	"strings"
+	"testing"
+)
+
+func TestParsePartialDate(t *testing.T) {
+	for _, tc := range []struct {
+		in   string
+		want PartialDate
+	}{
+		{"", PartialDate{}},
+		{"1998", PartialDate{Year: 1998}},
+		{"1998-05", PartialDate{Year: 1998, Month: 5}},
+		{" 1998-05-12 ", PartialDate{Year: 1998, Month: 5, Day: 12}},
+		{"2024-02-29", PartialDate{Year: 2024, Month: 2, Day: 29}},
+	} {
+		got, err := ParsePartialDate(tc.in)
+		if err != nil || got != tc.want {
+			t.Fatalf("ParsePartialDate(%q) = %+v, %v; want %+v", tc.in, got, err, tc.want)
+		}
+		if want := strings.TrimSpace(tc.in); got.String() != want {
+			t.Fatalf("String() = %q; want %q", got.String(), want)
+		}
+	}
+
+	for _, in := range []string{"98", "1998-5", "1998-13", "1998-00", "2023-02-29", "1998-05-12-01", "0000", "abcd"} {
+		if _, err := ParsePartialDate(in); !errors.Is(err, ErrInvalidPartialDate) {
+			t.Fatalf("ParsePartialDate(%q) error = %v; want ErrInvalidPartialDate", in, err)
+		}
+	}
+}
+
+func TestPartialDateOrdering(t *testing.T) {
+	ordered := []PartialDate{
+		{Year: 1998},
+		{Year: 1998, Month: 1},
+		{Year: 1998, Month: 1, Day: 1},
+		{Year: 1998, Month: 12, Day: 31},
+		{Year: 1999},
+	}
+	for i := 1; i < len(ordered); i++ {
+		if ordered[i-1].Compare(ordered[i]) != -1 || ordered[i].Compare(ordered[i-1]) != 1 {
+			t.Fatalf("%v should sort before %v", ordered[i-1], ordered[i])
+		}
+	}
+
+	// An upper bound of 1999 covers the last day of 1999 but not 2000.
+	upper := PartialDate{Year: 1999}
+	if (PartialDate{Year: 1999, Month: 12, Day: 31}).sortKey() > upper.endKey() {
+		t.Fatal("1999-12-31 should fall within an upper bound of 1999")
+	}
+	if (PartialDate{Year: 2000}).sortKey() <= upper.endKey() {
+		t.Fatal("2000 should fall past an upper bound of 1999")
+	}
+}
@@ -35,10 +35,105 @@ type PlayHistoryEvent struct {
 	ContextID   sql.NullString
 }
 
+// RecentPlayContext is a playlist, album, artist, or radio seed the user has
+// played from, with the track played last in it. Name is the playlist name for
+// playlist contexts and NULL otherwise.
+type RecentPlayContext struct {
+	ContextType  string
+	ContextID    string
+	Name         sql.NullString
+	LastPlayedAt time.Time
+	Track        Track
+}
+
+// ListeningEvent is a play or a skip in a user's listening history.
+// ContextType and ContextID are empty when the client reported no context.
+type ListeningEvent struct {
+	TrackID     int64
+	At          time.Time
+	Skipped     bool
+	ContextType string
+	ContextID   string
+}
+
+// HourlyArtistPlays counts a user's plays of one artist in one hour of one
+// weekday, in the time zone asked for. Energy averages the measured energy of
+// the Measured plays whose tracks have it.
+type HourlyArtistPlays struct {
+	Weekday  time.Weekday
+	Hour     int
+	ArtistID uuid.NullUUID
+	Artist   string
+	Plays    int
+	Measured int
+	Energy   float64
+}
+
+// PlayContextStats summarizes a user's plays from one playback context, such as
+// a single playlist. TopTracks holds the context's most-played tracks.
+type PlayContextStats struct {
+	PlayCount    int
+	UniqueTracks int
+	LastPlayedAt sql.NullTime
+	TopTracks    []TopTrack
+}
+
+// SkippedTrack is a track the user skipped within a stats window, with its
+// plays over the same window and the average position it was skipped at.
+type SkippedTrack struct {
+	Track
+	SkipCount     int
+	PlayCount     int
+	AvgPositionMs int
+	LastSkippedAt time.Time
+}
+
+// SkipStats compares a user's skips with their plays within a window. Tracks
+// holds the most-skipped tracks.
+type SkipStats struct {
+	PlayCount int
+	SkipCount int
+	Tracks    []SkippedTrack
+}
+
+// Kinds of ClientPlayEvent.
+const (
+	PlayEventKindPlay = "play"
+	PlayEventKindSkip = "skip"
+)
+
+// ClientPlayEvent is a play or skip a client reported after the fact, such as
+// a listen made offline, dated by the client and identified by an ID the
+// client generated. PositionMs applies to skips.
+type ClientPlayEvent struct {
+	EventID     uuid.UUID
+	Kind        string
+	TrackID     int64
+	OccurredAt  time.Time
+	PositionMs  int
+	ContextType string
+	ContextID   string
+}
+
+// frequentlySkippedTracks selects the IDs of the tracks user $1 keeps
+// skipping: at least three skips in the last 90 days, outnumbering the plays
+// over the same days. A skip is recorded instead of a play, so this is a skip
+// rate above one half. Radio and home recommendations leave these tracks out.
+const frequentlySkippedTracks = `
+	SELECT s.track_id
+	FROM track_skips s
+	WHERE s.user_id = $1 AND s.skipped_at >= NOW() - INTERVAL '90 days'
+	GROUP BY s.track_id
+	HAVING COUNT(*) >= 3 AND COUNT(*) > (
+		SELECT COUNT(*) FROM play_events pe
+		WHERE pe.user_id = $1 AND pe.track_id = s.track_id AND pe.played_at >= NOW() - INTERVAL '90 days'
+	)`
+
 // PlayEventRepository records play events and serves recently-played / top-track
 // listings. All reads and writes are scoped to a single user.
 type PlayEventRepository struct {
-	db *DB
+	db        *DB
+	retention PlayRetentionPolicy
 }
 
 func NewPlayEventRepository(db *DB) *PlayEventRepository {
@@ -61,6 +156,96 @@ func (r *PlayEventRepository) RecordPlay(ctx context.Context, userID uuid.UUID,
 	return err
 }
 
+// RecordSkip records that the user moved past a track positionMs into it,
+// before it counted as a play. contextType and contextID are optional; empty
+// strings are stored as SQL NULL.
+func (r *PlayEventRepository) RecordSkip(ctx context.Context, userID uuid.UUID, trackID int64, positionMs int, contextType, contextID string) error {
+	query := `
+		INSERT INTO track_skips (user_id, track_id, position_ms, context_type, context_id)
+		VALUES ($1, $2, $3, $4, $5)
+	`
+	_, err := r.db.ExecContext(ctx, query,
+		userID,
+		trackID,
+		max(positionMs, 0),
+		sql.NullString{String: contextType, Valid: contextType != ""},
+		sql.NullString{String: contextID, Valid: contextID != ""},
+	)
+	return err
+}
+
+// RecordClientEvents records plays and skips reported by a client, in one
+// transaction. An event whose ID the user already recorded is left alone, so
+// a client can resend a batch it is unsure was delivered. The returned slice
+// reports, per event, whether it was newly recorded.
+func (r *PlayEventRepository) RecordClientEvents(ctx context.Context, userID uuid.UUID, events []ClientPlayEvent) ([]bool, error) {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer tx.Rollback()
+
+	recorded := make([]bool, len(events))
+	for i, event := range events {
+		query := `
+			INSERT INTO play_events (user_id, track_id, played_at, context_type, context_id, client_event_id)
+			VALUES ($1, $2, $3, $4, $5, $6)
+			ON CONFLICT (user_id, client_event_id) WHERE client_event_id IS NOT NULL DO NOTHING
+		`
+		args := []any{
+			userID,
+			event.TrackID,
+			event.OccurredAt,
+			sql.NullString{String: event.ContextType, Valid: event.ContextType != ""},
+			sql.NullString{String: event.ContextID, Valid: event.ContextID != ""},
+			event.EventID,
+		}
+		if event.Kind == PlayEventKindSkip {
+			query = `
+				INSERT INTO track_skips (user_id, track_id, skipped_at, context_type, context_id, client_event_id, position_ms)
+				VALUES ($1, $2, $3, $4, $5, $6, $7)
+				ON CONFLICT (user_id, client_event_id) WHERE client_event_id IS NOT NULL DO NOTHING
+			`
+			args = append(args, max(event.PositionMs, 0))
+		}
+		result, err := tx.ExecContext(ctx, query, args...)
+		if err != nil {
+			return nil, err
+		}
+		inserted, err := result.RowsAffected()
+		if err != nil {
+			return nil, err
+		}
+		recorded[i] = inserted > 0
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, err
+	}
+	return recorded, nil
+}
+
+// ImportPlays brings a user's play count for a track from another server up
+// to count, recording the missing plays at lastPlayed (or now, when zero)
+// under context type "import" and contextID naming the source. It returns how
+// many plays it recorded, so importing the same count again records none.
+func (r *PlayEventRepository) ImportPlays(ctx context.Context, userID uuid.UUID, trackID int64, contextID string, count int, lastPlayed time.Time) (int, error) {
+	query := `
+		INSERT INTO play_events (user_id, track_id, played_at, context_type, context_id)
+		SELECT $1, $2, COALESCE($5, NOW()), 'import', $3
+		FROM generate_series(1, $4::bigint - (
+			SELECT COALESCE(SUM(play_count), 0) FROM ` + r.playCounts() + `
+			WHERE user_id = $1 AND track_id = $2 AND context_type = 'import' AND context_id = $3
+		))
+	`
+	result, err := r.db.ExecContext(ctx, query, userID, trackID, contextID, count,
+		sql.NullTime{Time: lastPlayed, Valid: !lastPlayed.IsZero()})
+	if err != nil {
+		return 0, err
+	}
+	added, err := result.RowsAffected()
+	return int(added), err
+}
+
 // RecentlyPlayed returns the user's recently played tracks deduped by track (one
 // row per track at its most recent play), newest first, honoring limit/offset.
 func (r *PlayEventRepository) RecentlyPlayed(ctx context.Context, userID uuid.UUID, limit, offset int) ([]RecentlyPlayedTrack, error) {
@@ -207,6 +392,64 @@ func (r *PlayEventRepository) TopTracks(ctx context.Context, userID uuid.UUID, d
 		limit = 100
 	}
 
+	return r.topTracks(ctx, "user_id = $1 AND played_at >= NOW() - make_interval(days => $2)", limit, userID, days)
+}
+
+// TopTracksForArtist returns the user's all-time most-played tracks by the
+// MusicBrainz artist.
+func (r *PlayEventRepository) TopTracksForArtist(ctx context.Context, userID, artistMBID uuid.UUID, limit int) ([]TopTrack, error) {
+	return r.topTracks(ctx, "user_id = $1 AND track_id IN (SELECT id FROM tracks WHERE mb_artist_id = $2)", clampTopLimit(limit), userID, artistMBID)
+}
+
+// TopTracksForRelease returns the user's all-time most-played tracks from the
+// MusicBrainz release.
+func (r *PlayEventRepository) TopTracksForRelease(ctx context.Context, userID, releaseMBID uuid.UUID, limit int) ([]TopTrack, error) {
+	return r.topTracks(ctx, "user_id = $1 AND track_id IN (SELECT id FROM tracks WHERE mb_release_id = $2)", clampTopLimit(limit), userID, releaseMBID)
+}
+
+func clampTopLimit(limit int) int {
+	if limit <= 0 {
+		return 10
+	}
+	return min(limit, 100)
+}
+
+// ContextStats summarizes the user's plays recorded with the given context
+// type and ID within the trailing window of days (all time when days <= 0),
+// including the limit most-played tracks from that context.
+func (r *PlayEventRepository) ContextStats(ctx context.Context, userID uuid.UUID, contextType, contextID string, days, limit int) (*PlayContextStats, error) {
+	if days < 0 {
+		days = 0
+	}
+	if limit <= 0 {
+		limit = 10
+	}
+	if limit > 100 {
+		limit = 100
+	}
+
+	condition := "user_id = $1 AND context_type = $2 AND context_id = $3 AND ($4 = 0 OR played_at >= NOW() - make_interval(days => $4))"
+	args := []any{userID, contextType, contextID, days}
+
+	var stats PlayContextStats
+	err := r.db.QueryRowContext(ctx,
+		`SELECT COALESCE(SUM(play_count), 0), COUNT(DISTINCT track_id), MAX(last_played_at) FROM `+r.playCounts()+` WHERE `+condition,
+		args...,
+	).Scan(&stats.PlayCount, &stats.UniqueTracks, &stats.LastPlayedAt)
+	if err != nil {
+		return nil, err
+	}
+
+	if stats.TopTracks, err = r.topTracks(ctx, condition, limit, args...); err != nil {
+		return nil, err
+	}
+	return &stats, nil
+}
+
+// topTracks ranks tracks by play count among the play events matching
+// condition, whose placeholders are numbered to match args. The limit is bound
+// after args.
+func (r *PlayEventRepository) topTracks(ctx context.Context, condition string, limit int, args ...any) ([]TopTrack, error) {
 	query := `
 		SELECT t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
 			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
@@ -219,18 +462,17 @@ func (r *PlayEventRepository) TopTracks(ctx context.Context, userID uuid.UUID, d
 			   ta.updated_at,
 			   agg.play_count, agg.last_played_at
 		FROM (
-			SELECT track_id, COUNT(*) AS play_count, MAX(played_at) AS last_played_at
-			FROM play_events
-			WHERE user_id = $1 AND played_at >= NOW() - make_interval(days => $2)
+			SELECT track_id, SUM(play_count) AS play_count, MAX(last_played_at) AS last_played_at
+			FROM ` + r.playCounts() + `
+			WHERE ` + condition + `
 			GROUP BY track_id
 		) agg
 		JOIN tracks t ON t.id = agg.track_id
 		LEFT JOIN track_analysis ta ON ta.track_id = t.id
 		ORDER BY agg.play_count DESC, agg.last_played_at DESC, t.id DESC
-		LIMIT $3
-	`
+		LIMIT $` + itoa(len(args)+1)
 
-	rows, err := r.db.QueryContext(ctx, query, userID, days, limit)
+	rows, err := r.db.QueryContext(ctx, query, append(args, limit)...)
 	if err != nil {
 		return nil, err
 	}
@@ -260,3 +502,257 @@ func (r *PlayEventRepository) TopTracks(ctx context.Context, userID uuid.UUID, d
 	}
 	return tracks, nil
 }
+
+// PopularTrackIDs returns the IDs of the limit tracks with stored audio
+// played most by all users within the trailing window of days, most played
+// first.
+func (r *PlayEventRepository) PopularTrackIDs(ctx context.Context, days, limit int) ([]int64, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT play_counts.track_id
+		FROM `+r.playCounts()+`
+		JOIN tracks t ON t.id = play_counts.track_id
+		WHERE play_counts.played_at >= NOW() - make_interval(days => $1) AND t.storage_key IS NOT NULL
+		GROUP BY play_counts.track_id
+		ORDER BY SUM(play_counts.play_count) DESC, MAX(play_counts.last_played_at) DESC, play_counts.track_id DESC
+		LIMIT $2
+	`, days, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	return ids, rows.Err()
+}
+
+// SkipStats counts the user's plays and skips within the trailing window of
+// days and returns the limit most-skipped tracks, ordered by skip count desc
+// then most-recent skip.
+func (r *PlayEventRepository) SkipStats(ctx context.Context, userID uuid.UUID, days, limit int) (*SkipStats, error) {
+	if days <= 0 {
+		days = 30
+	}
+	if limit <= 0 {
+		limit = 20
+	}
+	if limit > 100 {
+		limit = 100
+	}
+
+	var stats SkipStats
+	err := r.db.QueryRowContext(ctx, `
+		SELECT
+			(SELECT COALESCE(SUM(play_count), 0) FROM `+r.playCounts()+` WHERE user_id = $1 AND played_at >= NOW() - make_interval(days => $2)),
+			(SELECT COUNT(*) FROM track_skips WHERE user_id = $1 AND skipped_at >= NOW() - make_interval(days => $2))
+	`, userID, days).Scan(&stats.PlayCount, &stats.SkipCount)
+	if err != nil {
+		return nil, err
+	}
+
+	query := `
+		SELECT t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
+			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
+			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
+			   t.codec, t.bitrate_kbps, t.sample_rate_hz, t.channels, t.content_type,
+			   t.metadata_json, t.metadata_status, t.metadata_confidence, t.metadata_provenance,
+			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at,
+			   ta.status, COALESCE(` + analysisCompactSummaryExpression + `, '{}'::jsonb),
+			   COALESCE(` + analysisCompactOverridesExpression + `, '{}'::jsonb),
+			   ta.updated_at,
+			   s.skip_count, p.play_count, s.avg_position_ms, s.last_skipped_at
+		FROM (
+			SELECT track_id, COUNT(*) AS skip_count, AVG(position_ms)::int AS avg_position_ms,
+				   MAX(skipped_at) AS last_skipped_at
+			FROM track_skips
+			WHERE user_id = $1 AND skipped_at >= NOW() - make_interval(days => $2)
+			GROUP BY track_id
+		) s
+		JOIN tracks t ON t.id = s.track_id
+		LEFT JOIN track_analysis ta ON ta.track_id = t.id
+		CROSS JOIN LATERAL (
+			SELECT COALESCE(SUM(play_counts.play_count), 0) AS play_count
+			FROM ` + r.playCounts() + `
+			WHERE play_counts.user_id = $1 AND play_counts.track_id = s.track_id AND play_counts.played_at >= NOW() - make_interval(days => $2)
+		) p
+		ORDER BY s.skip_count DESC, s.last_skipped_at DESC, t.id DESC
+		LIMIT $3
+	`
+
+	rows, err := r.db.QueryContext(ctx, query, userID, days, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	for rows.Next() {
+		var st SkippedTrack
+		var analysisOverrides json.RawMessage
+		if err := rows.Scan(
+			&st.ID, &st.IdentityHash, &st.Title, &st.Artist, &st.Album, &st.DurationMs, &st.Version,
+			&st.MBRecordingID, &st.MBReleaseID, &st.MBArtistID, &st.MBVerified,
+			&st.SourceURL, &st.SourceType, &st.StorageKey, &st.FileSizeBytes,
+			&st.Codec, &st.BitrateKbps, &st.SampleRateHz, &st.Channels, &st.ContentType,
+			&st.MetadataJSON, &st.MetadataStatus, &st.MetadataConfidence, &st.MetadataProvenance,
+			&st.CoverArtURL, &st.MetadataUserEdited, &st.CreatedAt, &st.UpdatedAt,
+			&st.AnalysisStatus, &st.AnalysisSummary, &analysisOverrides, &st.AnalysisUpdatedAt,
+			&st.SkipCount, &st.PlayCount, &st.AvgPositionMs, &st.LastSkippedAt,
+		); err != nil {
+			return nil, err
+		}
+		st.AnalysisSummary, _ = projectCompactAnalysis(st.AnalysisSummary, analysisOverrides)
+		stats.Tracks = append(stats.Tracks, st)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	return &stats, nil
+}
+
+// RecentContexts returns the contexts the user most recently played from,
+// newest first, one row per context. Playlists that were deleted or are no
+// longer visible to the user are skipped.
+func (r *PlayEventRepository) RecentContexts(ctx context.Context, userID uuid.UUID, limit int) ([]RecentPlayContext, error) {
+	if limit <= 0 {
+		limit = 10
+	}
+	if limit > 100 {
+		limit = 100
+	}
+
+	query := `
+		SELECT c.context_type, c.context_id, p.name, c.last_played_at,
+			   t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
+			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
+			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
+			   t.codec, t.bitrate_kbps, t.sample_rate_hz, t.channels, t.content_type,
+			   t.metadata_json, t.metadata_status, t.metadata_confidence, t.metadata_provenance,
+			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at,
+			   ta.status, COALESCE(` + analysisCompactSummaryExpression + `, '{}'::jsonb),
+			   COALESCE(` + analysisCompactOverridesExpression + `, '{}'::jsonb),
+			   ta.updated_at
+		FROM (
+			SELECT DISTINCT ON (context_type, context_id)
+				   context_type, context_id, track_id, played_at AS last_played_at
+			FROM play_events
+			WHERE user_id = $1
+			  AND context_type IN ('playlist', 'album', 'artist', 'radio')
+			  AND COALESCE(context_id, '') <> ''
+			ORDER BY context_type, context_id, played_at DESC, id DESC
+		) c
+		JOIN tracks t ON t.id = c.track_id
+		LEFT JOIN track_analysis ta ON ta.track_id = t.id
+		LEFT JOIN playlists p ON c.context_type = 'playlist' AND p.id::text = c.context_id
+		WHERE c.context_type <> 'playlist' OR (p.id IS NOT NULL AND (p.user_id = $1 OR p.is_public))
+		ORDER BY c.last_played_at DESC, c.context_type, c.context_id
+		LIMIT $2
+	`
+
+	rows, err := r.db.QueryContext(ctx, query, userID, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var contexts []RecentPlayContext
+	for rows.Next() {
+		var rc RecentPlayContext
+		var analysisOverrides json.RawMessage
+		if err := rows.Scan(
+			&rc.ContextType, &rc.ContextID, &rc.Name, &rc.LastPlayedAt,
+			&rc.Track.ID, &rc.Track.IdentityHash, &rc.Track.Title, &rc.Track.Artist, &rc.Track.Album, &rc.Track.DurationMs, &rc.Track.Version,
+			&rc.Track.MBRecordingID, &rc.Track.MBReleaseID, &rc.Track.MBArtistID, &rc.Track.MBVerified,
+			&rc.Track.SourceURL, &rc.Track.SourceType, &rc.Track.StorageKey, &rc.Track.FileSizeBytes,
+			&rc.Track.Codec, &rc.Track.BitrateKbps, &rc.Track.SampleRateHz, &rc.Track.Channels, &rc.Track.ContentType,
+			&rc.Track.MetadataJSON, &rc.Track.MetadataStatus, &rc.Track.MetadataConfidence, &rc.Track.MetadataProvenance,
+			&rc.Track.CoverArtURL, &rc.Track.MetadataUserEdited, &rc.Track.CreatedAt, &rc.Track.UpdatedAt,
+			&rc.Track.AnalysisStatus, &rc.Track.AnalysisSummary, &analysisOverrides, &rc.Track.AnalysisUpdatedAt,
+		); err != nil {
+			return nil, err
+		}
+		rc.Track.AnalysisSummary, _ = projectCompactAnalysis(rc.Track.AnalysisSummary, analysisOverrides)
+		contexts = append(contexts, rc)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	return contexts, nil
+}
+
+// maxListeningEvents bounds how much history ListeningEvents reads.
+const maxListeningEvents = 5000
+
+// ListeningEvents returns the user's plays and skips since the given time,
+// oldest first. Only the most recent maxListeningEvents are returned.
+func (r *PlayEventRepository) ListeningEvents(ctx context.Context, userID uuid.UUID, since time.Time) ([]ListeningEvent, error) {
+	query := `
+		SELECT track_id, at, skipped, context_type, context_id
+		FROM (
+			SELECT id, track_id, played_at AS at, FALSE AS skipped,
+				   COALESCE(context_type, '') AS context_type, COALESCE(context_id, '') AS context_id
+			FROM play_events
+			WHERE user_id = $1 AND played_at >= $2
+			UNION ALL
+			SELECT id, track_id, skipped_at, TRUE,
+				   COALESCE(context_type, ''), COALESCE(context_id, '')
+			FROM track_skips
+			WHERE user_id = $1 AND skipped_at >= $2
+			ORDER BY at DESC, skipped, id DESC
+			LIMIT $3
+		) e
+		ORDER BY at, skipped DESC
+	`
+
+	rows, err := r.db.ReadQueryContext(ctx, query, userID, since, maxListeningEvents)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	events := []ListeningEvent{}
+	for rows.Next() {
+		var e ListeningEvent
+		if err := rows.Scan(&e.TrackID, &e.At, &e.Skipped, &e.ContextType, &e.ContextID); err != nil {
+			return nil, err
+		}
+		events = append(events, e)
+	}
+	return events, rows.Err()
+}
+
+// ListeningHours counts the user's plays since the given time by weekday,
+// hour and artist, with the weekday and hour read in zone, an IANA time zone
+// name such as "Europe/Berlin".
+func (r *PlayEventRepository) ListeningHours(ctx context.Context, userID uuid.UUID, since time.Time, zone string) ([]HourlyArtistPlays, error) {
+	rows, err := r.db.ReadQueryContext(ctx, `
+		SELECT EXTRACT(DOW FROM pe.played_at AT TIME ZONE $3)::int AS weekday,
+			   EXTRACT(HOUR FROM pe.played_at AT TIME ZONE $3)::int AS hour,
+			   t.mb_artist_id, COALESCE(t.artist, ''),
+			   COUNT(*), COUNT(t.energy), COALESCE(AVG(t.energy), 0)
+		FROM play_events pe
+		JOIN tracks t ON t.id = pe.track_id
+		WHERE pe.user_id = $1 AND pe.played_at >= $2
+		GROUP BY 1, 2, 3, 4
+		ORDER BY 1, 2, 5 DESC
+	`, userID, since, zone)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	hours := []HourlyArtistPlays{}
+	for rows.Next() {
+		var h HourlyArtistPlays
+		if err := rows.Scan(&h.Weekday, &h.Hour, &h.ArtistID, &h.Artist, &h.Plays, &h.Measured, &h.Energy); err != nil {
+			return nil, err
+		}
+		hours = append(hours, h)
+	}
+	return hours, rows.Err()
+}
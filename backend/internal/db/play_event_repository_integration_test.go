@@ -11,34 +11,6 @@ import (
 	_ "github.com/lib/pq"
 )
 
-func newPlayEventTestDB(t *testing.T) (*DB, context.Context) {
-	t.Helper()
-
-	dsn := postgresTestDSN()
-	if dsn == "" {
-		t.Skip("set OMP_POSTGRES_TEST_DSN, QA_DATABASE_URL, or DATABASE_URL to run Postgres play-event integration tests")
-	}
-
-	rawDB, err := sql.Open("postgres", dsn)
-	if err != nil {
-		t.Fatalf("open test database: %v", err)
-	}
-	t.Cleanup(func() { _ = rawDB.Close() })
-
-	database := &DB{DB: rawDB}
-	if err := database.Ping(); err != nil {
-		t.Fatalf("ping test database: %v", err)
-	}
-	if err := database.Migrate(); err != nil {
-		t.Fatalf("migrate test database: %v", err)
-	}
-	if _, err := database.Exec("TRUNCATE TABLE play_events, user_library, tracks, users RESTART IDENTITY CASCADE"); err != nil {
-		t.Fatalf("truncate test database: %v", err)
-	}
-
-	return database, context.Background()
-}
-
 func seedPlayUser(t *testing.T, database *DB, email string) uuid.UUID {
 	t.Helper()
 	id := uuid.New()
@@ -73,7 +45,8 @@ func insertPlayAt(t *testing.T, database *DB, userID uuid.UUID, trackID int64, a
 }
 
 func TestPlayEventRecordAndListingsAgainstPostgres(t *testing.T) {
-	database, ctx := newPlayEventTestDB(t)
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
 	trackRepo := NewTrackRepository(database)
 	repo := NewPlayEventRepository(database)
 
@@ -230,8 +203,246 @@ func TestPlayEventRecordAndListingsAgainstPostgres(t *testing.T) {
 	}
 }
 
+func TestPlayEventContextStatsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlayEventRepository(database)
+
+	user := seedPlayUser(t, database, "context@example.test")
+	other := seedPlayUser(t, database, "context-other@example.test")
+	trackA := seedPlayTrack(t, trackRepo, ctx, "Artist A", "Alpha")
+	trackB := seedPlayTrack(t, trackRepo, ctx, "Artist B", "Bravo")
+
+	plays := []struct {
+		user        uuid.UUID
+		track       int64
+		contextType string
+		contextID   string
+	}{
+		{user, trackA, "playlist", "7"},
+		{user, trackB, "playlist", "7"},
+		{user, trackB, "playlist", "7"},
+		{user, trackA, "playlist", "8"},
+		{user, trackA, "album", "7"},
+		{other, trackA, "playlist", "7"},
+	}
+	for _, p := range plays {
+		if err := repo.RecordPlay(ctx, p.user, p.track, p.contextType, p.contextID); err != nil {
+			t.Fatalf("RecordPlay: %v", err)
+		}
+	}
+	// An old play counts toward all-time stats but not a 30-day window.
+	if _, err := database.Exec(
+		`INSERT INTO play_events (user_id, track_id, played_at, context_type, context_id) VALUES ($1, $2, $3, 'playlist', '7')`,
+		user, trackA, time.Now().Add(-60*24*time.Hour)); err != nil {
+		t.Fatalf("insert old play: %v", err)
+	}
+
+	stats, err := repo.ContextStats(ctx, user, "playlist", "7", 0, 10)
+	if err != nil {
+		t.Fatalf("ContextStats: %v", err)
+	}
+	if stats.PlayCount != 4 || stats.UniqueTracks != 2 || !stats.LastPlayedAt.Valid {
+		t.Fatalf("all-time stats = %d plays of %d tracks (last %v), want 4 of 2", stats.PlayCount, stats.UniqueTracks, stats.LastPlayedAt)
+	}
+	if len(stats.TopTracks) != 2 || stats.TopTracks[0].PlayCount != 2 || stats.TopTracks[1].PlayCount != 2 {
+		t.Fatalf("all-time top tracks = %#v, want two tracks with 2 plays each", stats.TopTracks)
+	}
+
+	windowed, err := repo.ContextStats(ctx, user, "playlist", "7", 30, 1)
+	if err != nil {
+		t.Fatalf("ContextStats(30 days): %v", err)
+	}
+	if windowed.PlayCount != 3 || len(windowed.TopTracks) != 1 || windowed.TopTracks[0].ID != trackB {
+		t.Fatalf("windowed stats = %d plays, top %#v; want 3 plays with trackB on top", windowed.PlayCount, windowed.TopTracks)
+	}
+
+	empty, err := repo.ContextStats(ctx, user, "playlist", "999", 0, 10)
+	if err != nil {
+		t.Fatalf("ContextStats(unplayed): %v", err)
+	}
+	if empty.PlayCount != 0 || empty.LastPlayedAt.Valid || len(empty.TopTracks) != 0 {
+		t.Fatalf("unplayed context stats = %#v, want empty", empty)
+	}
+}
+
+func TestPlayEventSkipsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	libraryRepo := NewLibraryRepository(database)
+	repo := NewPlayEventRepository(database)
+
+	user := seedPlayUser(t, database, "skips@example.test")
+	artist := uuid.New()
+	skipped := seedPlayTrack(t, trackRepo, ctx, "Artist A", "Skipped")
+	kept := seedPlayTrack(t, trackRepo, ctx, "Artist A", "Kept")
+	for _, id := range []int64{skipped, kept} {
+		if _, err := database.Exec(`UPDATE tracks SET mb_artist_id = $1 WHERE id = $2`, artist, id); err != nil {
+			t.Fatalf("set artist: %v", err)
+		}
+		if _, err := libraryRepo.AddTrackToLibrary(ctx, user, id); err != nil {
+			t.Fatalf("add to library: %v", err)
+		}
+	}
+
+	for _, positionMs := range []int{4000, 8000, 12000} {
+		if err := repo.RecordSkip(ctx, user, skipped, positionMs, "radio", artist.String()); err != nil {
+			t.Fatalf("RecordSkip: %v", err)
+		}
+	}
+	if err := repo.RecordSkip(ctx, user, kept, 20000, "", ""); err != nil {
+		t.Fatalf("RecordSkip: %v", err)
+	}
+	if err := repo.RecordPlay(ctx, user, kept, "", ""); err != nil {
+		t.Fatalf("RecordPlay: %v", err)
+	}
+
+	stats, err := repo.SkipStats(ctx, user, 30, 10)
+	if err != nil {
+		t.Fatalf("SkipStats: %v", err)
+	}
+	if stats.SkipCount != 4 || stats.PlayCount != 1 || len(stats.Tracks) != 2 {
+		t.Fatalf("stats = %d skips, %d plays, %d tracks; want 4, 1, 2", stats.SkipCount, stats.PlayCount, len(stats.Tracks))
+	}
+	if top := stats.Tracks[0]; top.ID != skipped || top.SkipCount != 3 || top.PlayCount != 0 || top.AvgPositionMs != 8000 {
+		t.Fatalf("most skipped = %+v", top)
+	}
+
+	tracks, err := libraryRepo.LibraryTracksByArtists(ctx, user, []uuid.UUID{artist}, 5, false, "")
+	if err != nil {
+		t.Fatalf("LibraryTracksByArtists: %v", err)
+	}
+	if len(tracks) != 1 || tracks[0].ID != kept {
+		t.Fatalf("radio candidates = %+v, want only the track that is not frequently skipped", tracks)
+	}
+}
+
+func TestPlayEventClientEventsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlayEventRepository(database)
+
+	user := seedPlayUser(t, database, "offline@example.test")
+	other := seedPlayUser(t, database, "offline-other@example.test")
+	track := seedPlayTrack(t, trackRepo, ctx, "Artist A", "Offline")
+	at := time.Now().Add(-48 * time.Hour).UTC().Truncate(time.Second)
+	events := []ClientPlayEvent{
+		{EventID: uuid.New(), Kind: PlayEventKindPlay, TrackID: track, OccurredAt: at, ContextType: "album", ContextID: "Offline Album"},
+		{EventID: uuid.New(), Kind: PlayEventKindSkip, TrackID: track, OccurredAt: at.Add(time.Minute), PositionMs: 7000},
+	}
+
+	recorded, err := repo.RecordClientEvents(ctx, user, events)
+	if err != nil || len(recorded) != 2 || !recorded[0] || !recorded[1] {
+		t.Fatalf("first batch = %v, %v; want both recorded", recorded, err)
+	}
+	recorded, err = repo.RecordClientEvents(ctx, user, events)
+	if err != nil || recorded[0] || recorded[1] {
+		t.Fatalf("resent batch = %v, %v; want both deduplicated", recorded, err)
+	}
+	// Event IDs are scoped per user.
+	if recorded, err := repo.RecordClientEvents(ctx, other, events[:1]); err != nil || !recorded[0] {
+		t.Fatalf("other user's batch = %v, %v", recorded, err)
+	}
+
+	history, err := repo.PlayHistory(ctx, user, 10, 0)
+	if err != nil || len(history) != 1 || !history[0].PlayedAt.Equal(at) {
+		t.Fatalf("history = %+v, %v; want one play at the client's time", history, err)
+	}
+	stats, err := repo.SkipStats(ctx, user, 30, 10)
+	if err != nil || stats.SkipCount != 1 || stats.Tracks[0].AvgPositionMs != 7000 {
+		t.Fatalf("skip stats = %+v, %v", stats, err)
+	}
+
+	listening, err := repo.ListeningEvents(ctx, user, at.Add(-time.Hour))
+	want := []ListeningEvent{
+		{TrackID: track, At: at, ContextType: "album", ContextID: "Offline Album"},
+		{TrackID: track, At: at.Add(time.Minute), Skipped: true},
+	}
+	if err != nil || len(listening) != 2 {
+		t.Fatalf("listening events = %+v, %v", listening, err)
+	}
+	for i := range want {
+		if got := listening[i]; got.TrackID != want[i].TrackID || !got.At.Equal(want[i].At) || got.Skipped != want[i].Skipped || got.ContextType != want[i].ContextType || got.ContextID != want[i].ContextID {
+			t.Fatalf("listening event %d = %+v, want %+v", i, got, want[i])
+		}
+	}
+	if recent, err := repo.ListeningEvents(ctx, user, at.Add(30*time.Second)); err != nil || len(recent) != 1 || !recent[0].Skipped {
+		t.Fatalf("listening events since the play = %+v, %v", recent, err)
+	}
+}
+
+func TestPlayRollupsAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlayEventRepository(database)
+	repo.SetRetentionPolicy(PlayRetentionPolicy{RawDays: 90, DailyDays: 180})
+
+	user := seedPlayUser(t, database, "rollups@example.test")
+	old := seedPlayTrack(t, trackRepo, ctx, "Artist A", "Old Favorite")
+	recent := seedPlayTrack(t, trackRepo, ctx, "Artist A", "Recent")
+	now := time.Now()
+	insertPlayAt(t, database, user, old, now.AddDate(0, 0, -400))
+	insertPlayAt(t, database, user, old, now.AddDate(0, 0, -400))
+	insertPlayAt(t, database, user, old, now.AddDate(0, 0, -120))
+	insertPlayAt(t, database, user, recent, now.AddDate(0, 0, -3))
+
+	allTime := func() map[int64]int {
+		t.Helper()
+		top, err := repo.TopTracks(ctx, user, 3650, 10)
+		if err != nil {
+			t.Fatalf("TopTracks: %v", err)
+		}
+		counts := map[int64]int{}
+		for _, tt := range top {
+			counts[tt.ID] = tt.PlayCount
+		}
+		return counts
+	}
+	if counts := allTime(); counts[old] != 3 || counts[recent] != 1 {
+		t.Fatalf("counts before rollup = %v", counts)
+	}
+
+	if counted, err := repo.RollUpPlays(ctx, 100); err != nil || counted != 4 {
+		t.Fatalf("RollUpPlays = %d, %v; want 4", counted, err)
+	}
+	if purged, err := repo.PurgeRawPlays(ctx, 100); err != nil || purged != 3 {
+		t.Fatalf("PurgeRawPlays = %d, %v; want the 3 plays past 90 days", purged, err)
+	}
+	if folded, err := repo.CompactDailyRollups(ctx, 100); err != nil || folded != 1 {
+		t.Fatalf("CompactDailyRollups = %d, %v; want the day past 180 days", folded, err)
+	}
+	if counts := allTime(); counts[old] != 3 || counts[recent] != 1 {
+		t.Fatalf("counts after rollup = %v; want them unchanged", counts)
+	}
+
+	// A late play before the raw window counts before and after its rollup.
+	insertPlayAt(t, database, user, old, now.AddDate(0, 0, -200))
+	if counts := allTime(); counts[old] != 4 {
+		t.Fatalf("counts with a pending late play = %v", counts)
+	}
+	if counted, err := repo.RollUpPlays(ctx, 100); err != nil || counted != 1 {
+		t.Fatalf("second RollUpPlays = %d, %v", counted, err)
+	}
+	if counts := allTime(); counts[old] != 4 {
+		t.Fatalf("counts after the late rollup = %v", counts)
+	}
+
+	recentTop, err := repo.TopTracks(ctx, user, 30, 10)
+	if err != nil || len(recentTop) != 1 || recentTop[0].ID != recent {
+		t.Fatalf("30-day top tracks = %+v, %v; want only the recent track", recentTop, err)
+	}
+	history, err := repo.PlayHistory(ctx, user, 10, 0)
+	if err != nil || len(history) != 2 {
+		t.Fatalf("history = %d events, %v; want the recent and the late play", len(history), err)
+	}
+}
+
 func TestPlayEventsIndexExists(t *testing.T) {
-	database, _ := newPlayEventTestDB(t)
+	database := newIsolatedTestDB(t)
 
 	var exists bool
 	if err := database.QueryRow(
@@ -245,3 +456,41 @@ func TestPlayEventsIndexExists(t *testing.T) {
 		t.Fatal("expected idx_play_events_user_played_at index on play_events(user_id, played_at DESC)")
 	}
 }
+
+func TestPlayEventListeningHoursInTimeZone(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlayEventRepository(database)
+	settingsRepo := NewUserSettingsRepository(database)
+	user := seedPlayUser(t, database, "hours@example.test")
+	track := seedPlayTrack(t, trackRepo, ctx, "Night Owl", "late")
+	if _, err := database.Exec(`UPDATE tracks SET energy = 0.3 WHERE id = $1`, track); err != nil {
+		t.Fatalf("set energy: %v", err)
+	}
+
+	// Sunday 23:30 in UTC is Monday 08:30 in Tokyo.
+	at := time.Date(2026, 3, 1, 23, 30, 0, 0, time.UTC)
+	insertPlayAt(t, database, user, track, at)
+	insertPlayAt(t, database, user, track, at.Add(10*time.Minute))
+
+	hours, err := repo.ListeningHours(ctx, user, at.Add(-time.Hour), "Asia/Tokyo")
+	if err != nil {
+		t.Fatalf("ListeningHours: %v", err)
+	}
+	if len(hours) != 1 {
+		t.Fatalf("hours = %+v, want one hour", hours)
+	}
+	if h := hours[0]; h.Weekday != time.Monday || h.Hour != 8 || h.Artist != "Night Owl" || h.Plays != 2 || h.Measured != 2 || h.Energy < 0.29 || h.Energy > 0.31 {
+		t.Fatalf("hour = %+v, want two Monday 8:00 plays", h)
+	}
+
+	settings := DefaultUserSettings()
+	settings.ContextSuggestions = true
+	if _, err := settingsRepo.Update(ctx, user, settings); err != nil {
+		t.Fatalf("update settings: %v", err)
+	}
+	if saved, err := settingsRepo.Get(ctx, user); err != nil || !saved.ContextSuggestions {
+		t.Fatalf("settings = %+v, %v; want context suggestions on", saved, err)
+	}
+}
@@ -0,0 +1,155 @@
+package db
+
+import (
+	"context"
+)
+
+// minPlayRawRetentionDays keeps the raw events behind the 90-day windows
+// (frequently skipped tracks, offline batches) whole.
+const minPlayRawRetentionDays = 90
+
+// PlayRetentionPolicy bounds how long play events are kept at full detail.
+// Raw events older than RawDays are deleted once they are counted in the
+// daily rollups, and daily rollups older than DailyDays are folded into
+// monthly ones. Zero keeps that level forever.
+//
+// Stats read raw events for the last RawDays and rollups before that, so a
+// window reaching further back counts whole days, or whole months past
+// DailyDays, that start inside it. Listings of single plays, such as play
+// history, only reach back RawDays.
+type PlayRetentionPolicy struct {
+	RawDays   int
+	DailyDays int
+}
+
+// SetRetentionPolicy replaces the retention policy applied by the rollup
+// maintenance methods and used to route stats queries. RawDays is raised to
+// 90 and DailyDays to RawDays when they are set.
+func (r *PlayEventRepository) SetRetentionPolicy(policy PlayRetentionPolicy) {
+	if policy.RawDays > 0 {
+		policy.RawDays = max(policy.RawDays, minPlayRawRetentionDays)
+	}
+	if policy.DailyDays > 0 {
+		policy.DailyDays = max(policy.DailyDays, policy.RawDays, minPlayRawRetentionDays)
+	}
+	r.retention = policy
+}
+
+// playCounts is the relation stats read plays from. Each row counts
+// play_count plays of a track in one context, with columns named after
+// play_events; played_at is the start of the day or month for rollup rows.
+// Without raw retention it is play_events itself. Otherwise raw events are
+// read from the first retained day on, along with events not yet rolled up,
+// and rollups before that day.
+func (r *PlayEventRepository) playCounts() string {
+	raw := `SELECT user_id, track_id, context_type, context_id, played_at, 1 AS play_count, played_at AS last_played_at
+		FROM play_events`
+	if r.retention.RawDays <= 0 {
+		return "(" + raw + ") play_counts"
+	}
+	firstRawDay := "((NOW() AT TIME ZONE 'UTC')::date - " + itoa(r.retention.RawDays) + ")"
+	return `(
+		` + raw + `
+		WHERE NOT rolled_up OR played_at >= ` + firstRawDay + `::timestamp AT TIME ZONE 'UTC'
+		UNION ALL
+		SELECT user_id, track_id, NULLIF(context_type, ''), NULLIF(context_id, ''),
+			   day::timestamp AT TIME ZONE 'UTC', play_count, last_played_at
+		FROM play_daily_rollups
+		WHERE day < ` + firstRawDay + `
+		UNION ALL
+		SELECT user_id, track_id, NULLIF(context_type, ''), NULLIF(context_id, ''),
+			   month::timestamp AT TIME ZONE 'UTC', play_count, last_played_at
+		FROM play_monthly_rollups
+	) play_counts`
+}
+
+// RollUpPlays counts up to limit play events not yet rolled up into the daily
+// rollups (by UTC day) and marks them rolled up. It returns how many events it
+// counted; late events, such as offline plays and imports, are added to their
+// day whenever they arrive.
+func (r *PlayEventRepository) RollUpPlays(ctx context.Context, limit int) (int, error) {
+	query := `
+		WITH batch AS (
+			UPDATE play_events SET rolled_up = TRUE
+			WHERE NOT rolled_up AND id IN (
+				SELECT id FROM play_events
+				WHERE NOT rolled_up
+				ORDER BY id
+				LIMIT $1
+				FOR UPDATE SKIP LOCKED
+			)
+			RETURNING user_id, track_id, played_at, context_type, context_id
+		), counted AS (
+			INSERT INTO play_daily_rollups (user_id, day, track_id, context_type, context_id, play_count, last_played_at)
+			SELECT user_id, (played_at AT TIME ZONE 'UTC')::date, track_id,
+				   COALESCE(context_type, ''), COALESCE(context_id, ''), COUNT(*), MAX(played_at)
+			FROM batch
+			GROUP BY 1, 2, 3, 4, 5
+			ON CONFLICT (user_id, day, track_id, context_type, context_id) DO UPDATE
+			SET play_count = play_daily_rollups.play_count + EXCLUDED.play_count,
+				last_played_at = GREATEST(play_daily_rollups.last_played_at, EXCLUDED.last_played_at)
+		)
+		SELECT COUNT(*) FROM batch
+	`
+	var counted int
+	err := r.db.QueryRowContext(ctx, query, limit).Scan(&counted)
+	return counted, err
+}
+
+// CompactDailyRollups folds up to limit daily rollup rows older than the
+// policy's DailyDays into the monthly rollups. It returns how many daily rows
+// it folded, and does nothing when daily rollups are kept forever.
+func (r *PlayEventRepository) CompactDailyRollups(ctx context.Context, limit int) (int, error) {
+	if r.retention.DailyDays <= 0 {
+		return 0, nil
+	}
+	query := `
+		WITH moved AS (
+			DELETE FROM play_daily_rollups
+			WHERE (user_id, day, track_id, context_type, context_id) IN (
+				SELECT user_id, day, track_id, context_type, context_id
+				FROM play_daily_rollups
+				WHERE day < (NOW() AT TIME ZONE 'UTC')::date - $1::int
+				LIMIT $2
+				FOR UPDATE SKIP LOCKED
+			)
+			RETURNING user_id, day, track_id, context_type, context_id, play_count, last_played_at
+		), folded AS (
+			INSERT INTO play_monthly_rollups (user_id, month, track_id, context_type, context_id, play_count, last_played_at)
+			SELECT user_id, date_trunc('month', day)::date, track_id, context_type, context_id,
+				   SUM(play_count), MAX(last_played_at)
+			FROM moved
+			GROUP BY 1, 2, 3, 4, 5
+			ON CONFLICT (user_id, month, track_id, context_type, context_id) DO UPDATE
+			SET play_count = play_monthly_rollups.play_count + EXCLUDED.play_count,
+				last_played_at = GREATEST(play_monthly_rollups.last_played_at, EXCLUDED.last_played_at)
+		)
+		SELECT COUNT(*) FROM moved
+	`
+	var moved int
+	err := r.db.QueryRowContext(ctx, query, r.retention.DailyDays, limit).Scan(&moved)
+	return moved, err
+}
+
+// PurgeRawPlays deletes up to limit rolled-up play events from before the
+// policy's first raw day. It returns how many it deleted, and does nothing
+// when raw events are kept forever. Events not yet rolled up are kept until
+// they are.
+func (r *PlayEventRepository) PurgeRawPlays(ctx context.Context, limit int) (int, error) {
+	if r.retention.RawDays <= 0 {
+		return 0, nil
+	}
+	result, err := r.db.ExecContext(ctx, `
+		DELETE FROM play_events
+		WHERE id IN (
+			SELECT id FROM play_events
+			WHERE rolled_up AND played_at < ((NOW() AT TIME ZONE 'UTC')::date - $1::int)::timestamp AT TIME ZONE 'UTC'
+			LIMIT $2
+		)
+	`, r.retention.RawDays, limit)
+	if err != nil {
+		return 0, err
+	}
+	deleted, err := result.RowsAffected()
+	return int(deleted), err
+}
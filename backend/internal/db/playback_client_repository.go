@@ -0,0 +1,106 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrPlaybackClientNotFound = errors.New("playback client not found")
+
+// PlaybackClient is a player a user registered along with what it plays, so
+// playback URLs can be issued in a format it decodes. Codecs are ffprobe
+// codec names; MaxBitrateKbps is 0 for no limit. EQPresetID is the EQ preset
+// the client applies instead of the user's default, when set.
+type PlaybackClient struct {
+	ID             int64
+	UserID         uuid.UUID
+	Name           string
+	Codecs         []string
+	MaxBitrateKbps int
+	EQPresetID     sql.NullInt64
+	CreatedAt      time.Time
+	UpdatedAt      time.Time
+}
+
+type PlaybackClientRepository struct {
+	db *DB
+}
+
+func NewPlaybackClientRepository(db *DB) *PlaybackClientRepository {
+	return &PlaybackClientRepository{db: db}
+}
+
+const playbackClientColumns = `id, user_id, name, codecs, max_bitrate_kbps, eq_preset_id, created_at, updated_at`
+
+// Register saves a client's capabilities. Registering again under a name the
+// user already registered replaces that client's capabilities and keeps its
+// ID and EQ preset, so a player can repeat the handshake each time it starts.
+func (r *PlaybackClientRepository) Register(ctx context.Context, client *PlaybackClient) error {
+	return r.db.QueryRowContext(ctx, `
+		INSERT INTO playback_clients (user_id, name, codecs, max_bitrate_kbps)
+		VALUES ($1, $2, $3, $4)
+		ON CONFLICT (user_id, name) DO UPDATE SET
+			codecs = EXCLUDED.codecs,
+			max_bitrate_kbps = EXCLUDED.max_bitrate_kbps,
+			updated_at = NOW()
+		RETURNING id, eq_preset_id, created_at, updated_at
+	`, client.UserID, client.Name, pq.Array(client.Codecs), client.MaxBitrateKbps,
+	).Scan(&client.ID, &client.EQPresetID, &client.CreatedAt, &client.UpdatedAt)
+}
+
+// List returns the user's registered clients by name.
+func (r *PlaybackClientRepository) List(ctx context.Context, userID uuid.UUID) ([]PlaybackClient, error) {
+	rows, err := r.db.QueryContext(ctx,
+		`SELECT `+playbackClientColumns+` FROM playback_clients WHERE user_id = $1 ORDER BY name, id`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	clients := []PlaybackClient{}
+	for rows.Next() {
+		var c PlaybackClient
+		if err := rows.Scan(&c.ID, &c.UserID, &c.Name, pq.Array(&c.Codecs), &c.MaxBitrateKbps, &c.EQPresetID, &c.CreatedAt, &c.UpdatedAt); err != nil {
+			return nil, err
+		}
+		clients = append(clients, c)
+	}
+	return clients, rows.Err()
+}
+
+// Get returns one of the user's clients. Other users' clients are reported
+// as not found.
+func (r *PlaybackClientRepository) Get(ctx context.Context, userID uuid.UUID, id int64) (*PlaybackClient, error) {
+	var c PlaybackClient
+	err := r.db.QueryRowContext(ctx,
+		`SELECT `+playbackClientColumns+` FROM playback_clients WHERE id = $1 AND user_id = $2`, id, userID,
+	).Scan(&c.ID, &c.UserID, &c.Name, pq.Array(&c.Codecs), &c.MaxBitrateKbps, &c.EQPresetID, &c.CreatedAt, &c.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrPlaybackClientNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &c, nil
+}
+
+// Delete removes one of the user's clients.
+func (r *PlaybackClientRepository) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM playback_clients WHERE id = $1 AND user_id = $2`, id, userID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrPlaybackClientNotFound
+	}
+	return nil
+}
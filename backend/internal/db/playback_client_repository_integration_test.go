@@ -0,0 +1,47 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+func TestPlaybackClientsRegisterByName(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewPlaybackClientRepository(database)
+	userID := seedPlayUser(t, database, "clients@example.test")
+	otherUser := seedPlayUser(t, database, "clients-other@example.test")
+
+	cast := &PlaybackClient{UserID: userID, Name: "Living room", Codecs: []string{"mp3", "aac"}, MaxBitrateKbps: 256}
+	if err := repo.Register(ctx, cast); err != nil {
+		t.Fatalf("register client: %v", err)
+	}
+	again := &PlaybackClient{UserID: userID, Name: "Living room", Codecs: []string{"opus"}}
+	if err := repo.Register(ctx, again); err != nil || again.ID != cast.ID {
+		t.Fatalf("register again = id %d, %v; want the same client %d", again.ID, err, cast.ID)
+	}
+	if err := repo.Register(ctx, &PlaybackClient{UserID: otherUser, Name: "Living room", Codecs: []string{"mp3"}}); err != nil {
+		t.Fatalf("another user's client with the same name: %v", err)
+	}
+
+	got, err := repo.Get(ctx, userID, cast.ID)
+	if err != nil || len(got.Codecs) != 1 || got.Codecs[0] != "opus" || got.MaxBitrateKbps != 0 {
+		t.Fatalf("Get() = %+v, %v; want the second registration", got, err)
+	}
+	if clients, err := repo.List(ctx, userID); err != nil || len(clients) != 1 {
+		t.Fatalf("List() = %+v, %v", clients, err)
+	}
+	if _, err := repo.Get(ctx, otherUser, cast.ID); !errors.Is(err, ErrPlaybackClientNotFound) {
+		t.Fatalf("Get() by another user error = %v, want ErrPlaybackClientNotFound", err)
+	}
+	if err := repo.Delete(ctx, otherUser, cast.ID); !errors.Is(err, ErrPlaybackClientNotFound) {
+		t.Fatalf("Delete() by another user error = %v, want ErrPlaybackClientNotFound", err)
+	}
+	if err := repo.Delete(ctx, userID, cast.ID); err != nil {
+		t.Fatalf("delete client: %v", err)
+	}
+	if _, err := repo.Get(ctx, userID, cast.ID); !errors.Is(err, ErrPlaybackClientNotFound) {
+		t.Fatalf("Get() after delete error = %v, want ErrPlaybackClientNotFound", err)
+	}
+}
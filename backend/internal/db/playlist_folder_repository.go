@@ -0,0 +1,576 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrPlaylistFolderNotFound = errors.New("playlist folder not found")
+var ErrPlaylistFolderCycle = errors.New("playlist folder cannot be moved into itself or a descendant")
+var ErrPlaylistFolderTooDeep = errors.New("playlist folder nesting too deep")
+
+// MaxPlaylistFolderDepth bounds folder nesting so the tree stays cheap to
+// render and to walk with recursive queries.
+const MaxPlaylistFolderDepth = 8
+
+// PlaylistFolder groups playlists (and other folders) for one user. A NULL
+// ParentID places the folder at the root.
+type PlaylistFolder struct {
+	ID        int64
+	UserID    uuid.UUID
+	ParentID  sql.NullInt64
+	Name      string
+	Position  int
+	CreatedAt time.Time
+	UpdatedAt time.Time
+}
+
+// OrganizedPlaylist is a playlist summary with its place in the user's tree.
+// PinPosition is set only for pinned playlists.
+type OrganizedPlaylist struct {
+	PlaylistWithTracks
+	FolderID    sql.NullInt64
+	Position    int
+	PinPosition sql.NullInt32
+}
+
+// PlaylistTree is a user's folders and playlists, each ordered by parent and
+// then position, ready to be nested by the caller.
+type PlaylistTree struct {
+	Folders   []PlaylistFolder
+	Playlists []OrganizedPlaylist
+}
+
+type PlaylistFolderRepository struct {
+	db *DB
+}
+
+func NewPlaylistFolderRepository(db *DB) *PlaylistFolderRepository {
+	return &PlaylistFolderRepository{db: db}
+}
+
+// Create inserts a folder at the end of its parent. The parent must belong to
+// the same user and leave room for one more nesting level.
+func (r *PlaylistFolderRepository) Create(ctx context.Context, folder *PlaylistFolder) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := lockPlaylistOrganization(ctx, tx, folder.UserID); err != nil {
+		return err
+	}
+	if folder.ParentID.Valid {
+		if err := ensureFolderOwned(ctx, tx, folder.UserID, folder.ParentID.Int64); err != nil {
+			return err
+		}
+		depth, err := folderDepth(ctx, tx, folder.ParentID.Int64)
+		if err != nil {
+			return err
+		}
+		if depth+1 > MaxPlaylistFolderDepth {
+			return ErrPlaylistFolderTooDeep
+		}
+	}
+
+	query := `
+		INSERT INTO playlist_folders (user_id, parent_id, name, position)
+		VALUES ($1, $2, $3, (
+			SELECT COALESCE(MAX(position) + 1, 0) FROM playlist_folders
+			WHERE user_id = $1 AND parent_id IS NOT DISTINCT FROM $2
+		))
+		RETURNING id, position, created_at, updated_at
+	`
+	if err := tx.QueryRowContext(ctx, query, folder.UserID, folder.ParentID, folder.Name).Scan(
+		&folder.ID, &folder.Position, &folder.CreatedAt, &folder.UpdatedAt,
+	); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// GetByID retrieves a folder by its ID.
+func (r *PlaylistFolderRepository) GetByID(ctx context.Context, id int64) (*PlaylistFolder, error) {
+	query := `
+		SELECT id, user_id, parent_id, name, position, created_at, updated_at
+		FROM playlist_folders
+		WHERE id = $1
+	`
+
+	var f PlaylistFolder
+	err := r.db.QueryRowContext(ctx, query, id).Scan(
+		&f.ID, &f.UserID, &f.ParentID, &f.Name, &f.Position, &f.CreatedAt, &f.UpdatedAt,
+	)
+	if err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return nil, ErrPlaylistFolderNotFound
+		}
+		return nil, err
+	}
+	return &f, nil
+}
+
+// Rename updates a folder's name.
+func (r *PlaylistFolderRepository) Rename(ctx context.Context, folder *PlaylistFolder) error {
+	query := `UPDATE playlist_folders SET name = $1, updated_at = NOW() WHERE id = $2 RETURNING updated_at`
+	if err := r.db.QueryRowContext(ctx, query, folder.Name, folder.ID).Scan(&folder.UpdatedAt); err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return ErrPlaylistFolderNotFound
+		}
+		return err
+	}
+	return nil
+}
+
+// Move places a folder under parentID (NULL for the root) at position, or at
+// the end when position is nil. Moving a folder into itself or one of its
+// descendants fails with ErrPlaylistFolderCycle.
+func (r *PlaylistFolderRepository) Move(ctx context.Context, userID uuid.UUID, folderID int64, parentID sql.NullInt64, position *int) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := lockPlaylistOrganization(ctx, tx, userID); err != nil {
+		return err
+	}
+	var oldParent sql.NullInt64
+	err = tx.QueryRowContext(ctx,
+		`SELECT parent_id FROM playlist_folders WHERE id = $1 AND user_id = $2`, folderID, userID,
+	).Scan(&oldParent)
+	if err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return ErrPlaylistFolderNotFound
+		}
+		return err
+	}
+
+	if parentID.Valid {
+		if err := ensureFolderOwned(ctx, tx, userID, parentID.Int64); err != nil {
+			return err
+		}
+
+		subtreeQuery := `
+			WITH RECURSIVE subtree AS (
+				SELECT id, 1 AS level FROM playlist_folders WHERE id = $1
+				UNION ALL
+				SELECT f.id, subtree.level + 1
+				FROM playlist_folders f
+				JOIN subtree ON f.parent_id = subtree.id
+			)
+			SELECT BOOL_OR(id = $2), MAX(level) FROM subtree
+		`
+		var containsParent bool
+		var height int
+		if err := tx.QueryRowContext(ctx, subtreeQuery, folderID, parentID.Int64).Scan(&containsParent, &height); err != nil {
+			return err
+		}
+		if containsParent {
+			return ErrPlaylistFolderCycle
+		}
+		depth, err := folderDepth(ctx, tx, parentID.Int64)
+		if err != nil {
+			return err
+		}
+		if depth+height > MaxPlaylistFolderDepth {
+			return ErrPlaylistFolderTooDeep
+		}
+	}
+
+	siblings, err := folderSiblingIDs(ctx, tx, userID, parentID, folderID)
+	if err != nil {
+		return err
+	}
+	if _, err := tx.ExecContext(ctx,
+		`UPDATE playlist_folders SET parent_id = $1, updated_at = NOW() WHERE id = $2`, parentID, folderID); err != nil {
+		return err
+	}
+	if err := writeOrder(ctx, tx, "playlist_folders", "position", insertAt(siblings, folderID, position)); err != nil {
+		return err
+	}
+	if oldParent != parentID {
+		remaining, err := folderSiblingIDs(ctx, tx, userID, oldParent, folderID)
+		if err != nil {
+			return err
+		}
+		if err := writeOrder(ctx, tx, "playlist_folders", "position", remaining); err != nil {
+			return err
+		}
+	}
+
+	return tx.Commit()
+}
+
+// Delete removes a folder. Its subfolders and playlists move up to the
+// folder's parent, after the parent's existing entries, so deleting a folder
+// never deletes playlists.
+func (r *PlaylistFolderRepository) Delete(ctx context.Context, userID uuid.UUID, folderID int64) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := lockPlaylistOrganization(ctx, tx, userID); err != nil {
+		return err
+	}
+	var parentID sql.NullInt64
+	err = tx.QueryRowContext(ctx,
+		`SELECT parent_id FROM playlist_folders WHERE id = $1 AND user_id = $2`, folderID, userID,
+	).Scan(&parentID)
+	if err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return ErrPlaylistFolderNotFound
+		}
+		return err
+	}
+	folder := sql.NullInt64{Int64: folderID, Valid: true}
+
+	parentFolders, err := folderSiblingIDs(ctx, tx, userID, parentID, folderID)
+	if err != nil {
+		return err
+	}
+	childFolders, err := folderSiblingIDs(ctx, tx, userID, folder, 0)
+	if err != nil {
+		return err
+	}
+	parentPlaylists, err := playlistSiblingIDs(ctx, tx, userID, parentID, 0)
+	if err != nil {
+		return err
+	}
+	childPlaylists, err := playlistSiblingIDs(ctx, tx, userID, folder, 0)
+	if err != nil {
+		return err
+	}
+
+	if _, err := tx.ExecContext(ctx,
+		`UPDATE playlist_folders SET parent_id = $1 WHERE parent_id = $2`, parentID, folderID); err != nil {
+		return err
+	}
+	if _, err := tx.ExecContext(ctx,
+		`UPDATE playlists SET folder_id = $1 WHERE folder_id = $2`, parentID, folderID); err != nil {
+		return err
+	}
+	if err := writeOrder(ctx, tx, "playlist_folders", "position", append(parentFolders, childFolders...)); err != nil {
+		return err
+	}
+	if err := writeOrder(ctx, tx, "playlists", "position", append(parentPlaylists, childPlaylists...)); err != nil {
+		return err
+	}
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM playlist_folders WHERE id = $1`, folderID); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// MovePlaylist places a playlist in folderID (NULL for the root) at position,
+// or at the end when position is nil. Pins are unaffected.
+func (r *PlaylistFolderRepository) MovePlaylist(ctx context.Context, userID uuid.UUID, playlistID int64, folderID sql.NullInt64, position *int) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := lockPlaylistOrganization(ctx, tx, userID); err != nil {
+		return err
+	}
+	oldFolder, _, err := organizedPlaylistPlacement(ctx, tx, userID, playlistID)
+	if err != nil {
+		return err
+	}
+	if folderID.Valid {
+		if err := ensureFolderOwned(ctx, tx, userID, folderID.Int64); err != nil {
+			return err
+		}
+	}
+
+	siblings, err := playlistSiblingIDs(ctx, tx, userID, folderID, playlistID)
+	if err != nil {
+		return err
+	}
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET folder_id = $1 WHERE id = $2`, folderID, playlistID); err != nil {
+		return err
+	}
+	if err := writeOrder(ctx, tx, "playlists", "position", insertAt(siblings, playlistID, position)); err != nil {
+		return err
+	}
+	if oldFolder != folderID {
+		remaining, err := playlistSiblingIDs(ctx, tx, userID, oldFolder, playlistID)
+		if err != nil {
+			return err
+		}
+		if err := writeOrder(ctx, tx, "playlists", "position", remaining); err != nil {
+			return err
+		}
+	}
+
+	return tx.Commit()
+}
+
+// Pin adds a playlist to the user's pinned list at position, or at the end
+// when position is nil. Pinning an already pinned playlist moves it.
+func (r *PlaylistFolderRepository) Pin(ctx context.Context, userID uuid.UUID, playlistID int64, position *int) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := lockPlaylistOrganization(ctx, tx, userID); err != nil {
+		return err
+	}
+	if _, _, err := organizedPlaylistPlacement(ctx, tx, userID, playlistID); err != nil {
+		return err
+	}
+
+	pinned, err := pinnedPlaylistIDs(ctx, tx, userID, playlistID)
+	if err != nil {
+		return err
+	}
+	if err := writeOrder(ctx, tx, "playlists", "pin_position", insertAt(pinned, playlistID, position)); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// Unpin removes a playlist from the pinned list. Unpinning a playlist that is
+// not pinned is a no-op.
+func (r *PlaylistFolderRepository) Unpin(ctx context.Context, userID uuid.UUID, playlistID int64) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := lockPlaylistOrganization(ctx, tx, userID); err != nil {
+		return err
+	}
+	_, pinPosition, err := organizedPlaylistPlacement(ctx, tx, userID, playlistID)
+	if err != nil {
+		return err
+	}
+	if !pinPosition.Valid {
+		return nil
+	}
+
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET pin_position = NULL WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+	remaining, err := pinnedPlaylistIDs(ctx, tx, userID, playlistID)
+	if err != nil {
+		return err
+	}
+	if err := writeOrder(ctx, tx, "playlists", "pin_position", remaining); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// Tree returns all of the user's folders and playlists with their cached track
+// counts, durations, and sizes, in display order.
+func (r *PlaylistFolderRepository) Tree(ctx context.Context, userID uuid.UUID) (*PlaylistTree, error) {
+	tree := &PlaylistTree{Folders: []PlaylistFolder{}, Playlists: []OrganizedPlaylist{}}
+
+	folderQuery := `
+		SELECT id, user_id, parent_id, name, position, created_at, updated_at
+		FROM playlist_folders
+		WHERE user_id = $1
+		ORDER BY parent_id NULLS FIRST, position ASC, created_at ASC, id ASC
+	`
+	rows, err := r.db.QueryContext(ctx, folderQuery, userID)
+	if err != nil {
+		return nil, err
+	}
+	for rows.Next() {
+		var f PlaylistFolder
+		if err := rows.Scan(&f.ID, &f.UserID, &f.ParentID, &f.Name, &f.Position, &f.CreatedAt, &f.UpdatedAt); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		tree.Folders = append(tree.Folders, f)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	playlistQuery := `
+		SELECT p.id, p.user_id, p.name, p.description, p.cover_url, p.is_public, p.read_only, p.created_at, p.updated_at,
+			   p.track_count, p.duration_ms, p.size_bytes,
+			   p.folder_id, p.position, p.pin_position
+		FROM playlists p
+		WHERE p.user_id = $1
+		ORDER BY p.folder_id NULLS FIRST, p.position ASC, p.created_at ASC, p.id ASC
+	`
+	rows, err = r.db.QueryContext(ctx, playlistQuery, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var p OrganizedPlaylist
+		if err := rows.Scan(
+			&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.ReadOnly, &p.CreatedAt, &p.UpdatedAt,
+			&p.TrackCount, &p.DurationMs, &p.SizeBytes,
+			&p.FolderID, &p.Position, &p.PinPosition,
+		); err != nil {
+			return nil, err
+		}
+		tree.Playlists = append(tree.Playlists, p)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	return tree, nil
+}
+
+// lockPlaylistOrganization serializes tree edits per user; positions are
+// rewritten wholesale, so two concurrent moves must not interleave. NO KEY
+// UPDATE leaves inserts that reference the user unblocked.
+func lockPlaylistOrganization(ctx context.Context, tx *sql.Tx, userID uuid.UUID) error {
+	_, err := tx.ExecContext(ctx, `SELECT 1 FROM users WHERE id = $1 FOR NO KEY UPDATE`, userID)
+	return err
+}
+
+func ensureFolderOwned(ctx context.Context, tx *sql.Tx, userID uuid.UUID, folderID int64) error {
+	var exists bool
+	err := tx.QueryRowContext(ctx,
+		`SELECT EXISTS (SELECT 1 FROM playlist_folders WHERE id = $1 AND user_id = $2)`, folderID, userID,
+	).Scan(&exists)
+	if err != nil {
+		return err
+	}
+	if !exists {
+		return ErrPlaylistFolderNotFound
+	}
+	return nil
+}
+
+// folderDepth returns how many folders lie on the path from folderID up to
+// the root, counting folderID itself.
+func folderDepth(ctx context.Context, tx *sql.Tx, folderID int64) (int, error) {
+	query := `
+		WITH RECURSIVE ancestors AS (
+			SELECT id, parent_id FROM playlist_folders WHERE id = $1
+			UNION ALL
+			SELECT f.id, f.parent_id
+			FROM playlist_folders f
+			JOIN ancestors ON f.id = ancestors.parent_id
+		)
+		SELECT COUNT(*) FROM ancestors
+	`
+	var depth int
+	err := tx.QueryRowContext(ctx, query, folderID).Scan(&depth)
+	return depth, err
+}
+
+// organizedPlaylistPlacement returns a playlist's folder and pin position,
+// mapping missing and foreign playlists to the playlist repository errors.
+func organizedPlaylistPlacement(ctx context.Context, tx *sql.Tx, userID uuid.UUID, playlistID int64) (sql.NullInt64, sql.NullInt32, error) {
+	var owner uuid.UUID
+	var folderID sql.NullInt64
+	var pinPosition sql.NullInt32
+	err := tx.QueryRowContext(ctx,
+		`SELECT user_id, folder_id, pin_position FROM playlists WHERE id = $1`, playlistID,
+	).Scan(&owner, &folderID, &pinPosition)
+	if err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return folderID, pinPosition, ErrPlaylistNotFound
+		}
+		return folderID, pinPosition, err
+	}
+	if owner != userID {
+		return folderID, pinPosition, ErrPlaylistNotOwned
+	}
+	return folderID, pinPosition, nil
+}
+
+// folderSiblingIDs lists the folders under parentID in display order,
+// leaving out exclude.
+func folderSiblingIDs(ctx context.Context, tx *sql.Tx, userID uuid.UUID, parentID sql.NullInt64, exclude int64) ([]int64, error) {
+	return queryIDs(ctx, tx, `
+		SELECT id FROM playlist_folders
+		WHERE user_id = $1 AND parent_id IS NOT DISTINCT FROM $2 AND id <> $3
+		ORDER BY position ASC, created_at ASC, id ASC
+	`, userID, parentID, exclude)
+}
+
+// playlistSiblingIDs lists the playlists in folderID in display order,
+// leaving out exclude.
+func playlistSiblingIDs(ctx context.Context, tx *sql.Tx, userID uuid.UUID, folderID sql.NullInt64, exclude int64) ([]int64, error) {
+	return queryIDs(ctx, tx, `
+		SELECT id FROM playlists
+		WHERE user_id = $1 AND folder_id IS NOT DISTINCT FROM $2 AND id <> $3
+		ORDER BY position ASC, created_at ASC, id ASC
+	`, userID, folderID, exclude)
+}
+
+func pinnedPlaylistIDs(ctx context.Context, tx *sql.Tx, userID uuid.UUID, exclude int64) ([]int64, error) {
+	return queryIDs(ctx, tx, `
+		SELECT id FROM playlists
+		WHERE user_id = $1 AND pin_position IS NOT NULL AND id <> $2
+		ORDER BY pin_position ASC, id ASC
+	`, userID, exclude)
+}
+
+func queryIDs(ctx context.Context, tx *sql.Tx, query string, args ...any) ([]int64, error) {
+	rows, err := tx.QueryContext(ctx, query, args...)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	ids := []int64{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	return ids, rows.Err()
+}
+
+// insertAt returns ids with id inserted at position, clamped to the list;
+// a nil position appends.
+func insertAt(ids []int64, id int64, position *int) []int64 {
+	at := len(ids)
+	if position != nil && *position >= 0 && *position < at {
+		at = *position
+	}
+	out := make([]int64, 0, len(ids)+1)
+	out = append(out, ids[:at]...)
+	out = append(out, id)
+	return append(out, ids[at:]...)
+}
+
+// writeOrder sets column to each row's index in ids. table and column are
+// always package constants, never caller input.
+func writeOrder(ctx context.Context, tx *sql.Tx, table, column string, ids []int64) error {
+	if len(ids) == 0 {
+		return nil
+	}
+	query := `
+		UPDATE ` + table + ` AS target
+		SET ` + column + ` = ordered.ord - 1
+		FROM unnest($1::bigint[]) WITH ORDINALITY AS ordered(id, ord)
+		WHERE target.id = ordered.id
+		  AND target.` + column + ` IS DISTINCT FROM ordered.ord - 1
+	`
+	_, err := tx.ExecContext(ctx, query, pq.Array(ids))
+	return err
+}
@@ -0,0 +1,158 @@
+package db
+
+import (
+	"database/sql"
+	"errors"
+	"testing"
+)
+
+func folderOrder(t *testing.T, tree *PlaylistTree, parent sql.NullInt64) []int64 {
+	t.Helper()
+	var ids []int64
+	for _, f := range tree.Folders {
+		if f.ParentID == parent {
+			ids = append(ids, f.ID)
+		}
+	}
+	return ids
+}
+
+func folderPlaylistOrder(t *testing.T, tree *PlaylistTree, folder sql.NullInt64) []int64 {
+	t.Helper()
+	var ids []int64
+	for _, p := range tree.Playlists {
+		if p.FolderID == folder {
+			ids = append(ids, p.ID)
+		}
+	}
+	return ids
+}
+
+func TestPlaylistFoldersOrganizeTree(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	playlists := NewPlaylistRepository(database)
+	repo := NewPlaylistFolderRepository(database)
+
+	userID := seedPlaylistUser(t, database, "folders@example.test")
+	otherUser := seedPlaylistUser(t, database, "folders-other@example.test")
+	root := sql.NullInt64{}
+
+	var ids []int64
+	for _, name := range []string{"A", "B", "C"} {
+		p := &Playlist{UserID: userID, Name: name}
+		if err := playlists.Create(ctx, p); err != nil {
+			t.Fatalf("create playlist %s: %v", name, err)
+		}
+		ids = append(ids, p.ID)
+	}
+
+	moods := &PlaylistFolder{UserID: userID, Name: "Moods"}
+	if err := repo.Create(ctx, moods); err != nil {
+		t.Fatalf("create folder: %v", err)
+	}
+	moodsID := sql.NullInt64{Int64: moods.ID, Valid: true}
+	calm := &PlaylistFolder{UserID: userID, Name: "Calm", ParentID: moodsID}
+	if err := repo.Create(ctx, calm); err != nil {
+		t.Fatalf("create subfolder: %v", err)
+	}
+	calmID := sql.NullInt64{Int64: calm.ID, Valid: true}
+
+	foreign := &PlaylistFolder{UserID: otherUser, Name: "Theirs"}
+	if err := repo.Create(ctx, foreign); err != nil {
+		t.Fatalf("create foreign folder: %v", err)
+	}
+	if err := repo.Create(ctx, &PlaylistFolder{UserID: userID, Name: "Sneaky", ParentID: sql.NullInt64{Int64: foreign.ID, Valid: true}}); !errors.Is(err, ErrPlaylistFolderNotFound) {
+		t.Fatalf("create under foreign folder = %v, want ErrPlaylistFolderNotFound", err)
+	}
+
+	// Move B then A into Calm, A first.
+	if err := repo.MovePlaylist(ctx, userID, ids[1], calmID, nil); err != nil {
+		t.Fatalf("move B: %v", err)
+	}
+	zero := 0
+	if err := repo.MovePlaylist(ctx, userID, ids[0], calmID, &zero); err != nil {
+		t.Fatalf("move A: %v", err)
+	}
+	if err := repo.MovePlaylist(ctx, otherUser, ids[2], root, nil); !errors.Is(err, ErrPlaylistNotOwned) {
+		t.Fatalf("move foreign playlist = %v, want ErrPlaylistNotOwned", err)
+	}
+
+	if err := repo.Pin(ctx, userID, ids[2], nil); err != nil {
+		t.Fatalf("pin C: %v", err)
+	}
+	if err := repo.Pin(ctx, userID, ids[1], &zero); err != nil {
+		t.Fatalf("pin B: %v", err)
+	}
+
+	tree, err := repo.Tree(ctx, userID)
+	if err != nil {
+		t.Fatalf("Tree: %v", err)
+	}
+	assertOrder(t, folderOrder(t, tree, root), []int64{moods.ID})
+	assertOrder(t, folderOrder(t, tree, moodsID), []int64{calm.ID})
+	assertOrder(t, folderPlaylistOrder(t, tree, calmID), []int64{ids[0], ids[1]})
+	assertOrder(t, folderPlaylistOrder(t, tree, root), []int64{ids[2]})
+	for _, p := range tree.Playlists {
+		want := map[int64]int32{ids[1]: 0, ids[2]: 1}
+		if pos, pinned := want[p.ID]; pinned != p.PinPosition.Valid || (pinned && p.PinPosition.Int32 != pos) {
+			t.Fatalf("playlist %d pin = %+v", p.ID, p.PinPosition)
+		}
+	}
+
+	if err := repo.Move(ctx, userID, moods.ID, calmID, nil); !errors.Is(err, ErrPlaylistFolderCycle) {
+		t.Fatalf("move into descendant = %v, want ErrPlaylistFolderCycle", err)
+	}
+	if err := repo.Move(ctx, userID, calm.ID, root, &zero); err != nil {
+		t.Fatalf("move Calm to root: %v", err)
+	}
+
+	if err := repo.Unpin(ctx, userID, ids[1]); err != nil {
+		t.Fatalf("unpin B: %v", err)
+	}
+
+	// Deleting Calm keeps its playlists, now at the root after C.
+	if err := repo.Delete(ctx, userID, calm.ID); err != nil {
+		t.Fatalf("delete Calm: %v", err)
+	}
+	tree, err = repo.Tree(ctx, userID)
+	if err != nil {
+		t.Fatalf("Tree: %v", err)
+	}
+	assertOrder(t, folderOrder(t, tree, root), []int64{moods.ID})
+	assertOrder(t, folderPlaylistOrder(t, tree, root), []int64{ids[2], ids[0], ids[1]})
+	for _, p := range tree.Playlists {
+		if p.PinPosition.Valid != (p.ID == ids[2]) || (p.PinPosition.Valid && p.PinPosition.Int32 != 0) {
+			t.Fatalf("playlist %d pin after unpin = %+v", p.ID, p.PinPosition)
+		}
+	}
+}
+
+func TestPlaylistFolderDepthLimit(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	repo := NewPlaylistFolderRepository(database)
+	userID := seedPlaylistUser(t, database, "deep@example.test")
+
+	var parent sql.NullInt64
+	for i := 0; i < MaxPlaylistFolderDepth; i++ {
+		f := &PlaylistFolder{UserID: userID, Name: "level", ParentID: parent}
+		if err := repo.Create(ctx, f); err != nil {
+			t.Fatalf("create level %d: %v", i+1, err)
+		}
+		parent = sql.NullInt64{Int64: f.ID, Valid: true}
+	}
+	if err := repo.Create(ctx, &PlaylistFolder{UserID: userID, Name: "too deep", ParentID: parent}); !errors.Is(err, ErrPlaylistFolderTooDeep) {
+		t.Fatalf("create beyond max depth = %v, want ErrPlaylistFolderTooDeep", err)
+	}
+
+	// A two-level subtree cannot move under the deepest folder either.
+	top := &PlaylistFolder{UserID: userID, Name: "top"}
+	if err := repo.Create(ctx, top); err != nil {
+		t.Fatalf("create top: %v", err)
+	}
+	if err := repo.Create(ctx, &PlaylistFolder{UserID: userID, Name: "child", ParentID: sql.NullInt64{Int64: top.ID, Valid: true}}); err != nil {
+		t.Fatalf("create child: %v", err)
+	}
+	if err := repo.Move(ctx, userID, top.ID, sql.NullInt64{Int64: parent.Int64, Valid: true}, nil); !errors.Is(err, ErrPlaylistFolderTooDeep) {
+		t.Fatalf("move subtree too deep = %v, want ErrPlaylistFolderTooDeep", err)
+	}
+}
@@ -0,0 +1,337 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/lib/pq"
+)
+
+var ErrPlaylistRevisionNotFound = errors.New("playlist revision not found")
+
+// Playlist revision actions. Each names the mutation that produced the
+// snapshot.
+const (
+	PlaylistActionCreated       = "created"
+	PlaylistActionUpdated       = "updated"
+	PlaylistActionTracksAdded   = "tracks_added"
+	PlaylistActionTracksRemoved = "tracks_removed"
+	PlaylistActionReordered     = "reordered"
+	PlaylistActionDeduplicated  = "deduplicated"
+	PlaylistActionMerged        = "merged"
+	PlaylistActionReverted      = "reverted"
+)
+
+// playlistRevisionCoalesceWindow folds bursts of the same mutation (an import
+// adding tracks one by one, a drag session of reorders) into one revision.
+const playlistRevisionCoalesceWindow = time.Minute
+
+// PlaylistHistoryPolicy bounds how much history is kept per playlist.
+// Revisions older than MaxAge or beyond the newest MaxRevisions are pruned
+// whenever a new one is recorded.
+type PlaylistHistoryPolicy struct {
+	MaxAge       time.Duration
+	MaxRevisions int
+}
+
+// DefaultPlaylistHistoryPolicy keeps 90 days and at most 100 revisions.
+var DefaultPlaylistHistoryPolicy = PlaylistHistoryPolicy{MaxAge: 90 * 24 * time.Hour, MaxRevisions: 100}
+
+// PlaylistRevision is a snapshot of a playlist after a mutation. TracksAdded
+// and TracksRemoved compare against the previous revision and are NULL when
+// there was none to compare with (playlists that predate history).
+type PlaylistRevision struct {
+	ID            int64
+	PlaylistID    int64
+	Action        string
+	Name          string
+	Description   sql.NullString
+	CoverURL      sql.NullString
+	IsPublic      bool
+	TrackIDs      []int64
+	TracksAdded   sql.NullInt32
+	TracksRemoved sql.NullInt32
+	CreatedAt     time.Time
+}
+
+// SetHistoryPolicy replaces the retention policy applied when revisions are
+// recorded.
+func (r *PlaylistRepository) SetHistoryPolicy(policy PlaylistHistoryPolicy) {
+	r.history = policy
+}
+
+// HistoryPolicy returns the retention policy applied when revisions are
+// recorded, for a Tx that writes the repository's playlists.
+func (r *PlaylistRepository) HistoryPolicy() PlaylistHistoryPolicy {
+	return r.history
+}
+
+// History lists a playlist's revisions, newest first.
+func (r *PlaylistRepository) History(ctx context.Context, playlistID int64, limit, offset int) ([]PlaylistRevision, int, error) {
+	query := `
+		SELECT id, playlist_id, action, name, description, cover_url, is_public, track_ids,
+			   tracks_added, tracks_removed, created_at,
+			   COUNT(*) OVER() AS total
+		FROM playlist_revisions
+		WHERE playlist_id = $1
+		ORDER BY created_at DESC, id DESC
+		LIMIT $2 OFFSET $3
+	`
+
+	rows, err := r.db.QueryContext(ctx, query, playlistID, limit, offset)
+	if err != nil {
+		return nil, 0, err
+	}
+	defer rows.Close()
+
+	revisions := []PlaylistRevision{}
+	total := 0
+	for rows.Next() {
+		rev, err := scanPlaylistRevision(rows, &total)
+		if err != nil {
+			return nil, 0, err
+		}
+		revisions = append(revisions, *rev)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, 0, err
+	}
+
+	// Past the last page the window total is unavailable; count directly.
+	if len(revisions) == 0 && offset > 0 {
+		if err := r.db.QueryRowContext(ctx,
+			`SELECT COUNT(*) FROM playlist_revisions WHERE playlist_id = $1`, playlistID,
+		).Scan(&total); err != nil {
+			return nil, 0, err
+		}
+	}
+
+	return revisions, total, nil
+}
+
+// Revert restores a playlist's details and track order to a revision, in one
+// transaction, and records the restore as a new revision so it can itself be
+// undone. Tracks deleted from the library since the revision are left out.
+func (r *PlaylistRepository) Revert(ctx context.Context, playlistID, revisionID int64) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.ExecContext(ctx, `SELECT 1 FROM playlists WHERE id = $1 FOR UPDATE`, playlistID); err != nil {
+		return err
+	}
+
+	var name string
+	var description, coverURL sql.NullString
+	var isPublic bool
+	var trackIDs pq.Int64Array
+	err = tx.QueryRowContext(ctx, `
+		SELECT name, description, cover_url, is_public, track_ids
+		FROM playlist_revisions
+		WHERE id = $1 AND playlist_id = $2
+	`, revisionID, playlistID).Scan(&name, &description, &coverURL, &isPublic, &trackIDs)
+	if err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return ErrPlaylistRevisionNotFound
+		}
+		return err
+	}
+
+	if _, err := tx.ExecContext(ctx, `
+		UPDATE playlists
+		SET name = $1, description = $2, cover_url = $3, is_public = $4, updated_at = clock_timestamp()
+		WHERE id = $5
+	`, name, description, coverURL, isPublic, playlistID); err != nil {
+		return err
+	}
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM playlist_tracks WHERE playlist_id = $1`, playlistID); err != nil {
+		return err
+	}
+	restoreQuery := `
+		INSERT INTO playlist_tracks (playlist_id, track_id, position)
+		SELECT $1, snapshot.track_id, ROW_NUMBER() OVER (ORDER BY snapshot.ord) - 1
+		FROM unnest($2::bigint[]) WITH ORDINALITY AS snapshot(track_id, ord)
+		JOIN tracks t ON t.id = snapshot.track_id
+	`
+	if _, err := tx.ExecContext(ctx, restoreQuery, playlistID, trackIDs); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionReverted, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// recordPlaylistRevision snapshots the playlist as it stands inside tx. A
+// repeat of the latest revision's action within the coalesce window updates
+// that revision instead of adding one, keeping its diff against the revision
+// before it. Old revisions are pruned per policy.
+func recordPlaylistRevision(ctx context.Context, tx *sql.Tx, playlistID int64, action string, policy PlaylistHistoryPolicy) error {
+	var name string
+	var description, coverURL sql.NullString
+	var isPublic bool
+	var trackIDs pq.Int64Array
+	err := tx.QueryRowContext(ctx, `
+		SELECT p.name, p.description, p.cover_url, p.is_public,
+			   ARRAY(
+				   SELECT pt.track_id FROM playlist_tracks pt
+				   WHERE pt.playlist_id = p.id
+				   ORDER BY pt.position ASC, pt.added_at ASC, pt.track_id ASC
+			   )
+		FROM playlists p
+		WHERE p.id = $1
+	`, playlistID).Scan(&name, &description, &coverURL, &isPublic, &trackIDs)
+	if err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return ErrPlaylistNotFound
+		}
+		return err
+	}
+
+	type recent struct {
+		id       int64
+		action   string
+		trackIDs pq.Int64Array
+		fresh    bool
+	}
+	rows, err := tx.QueryContext(ctx, `
+		SELECT id, action, track_ids, created_at > NOW() - make_interval(secs => $2)
+		FROM playlist_revisions
+		WHERE playlist_id = $1
+		ORDER BY created_at DESC, id DESC
+		LIMIT 2
+	`, playlistID, playlistRevisionCoalesceWindow.Seconds())
+	if err != nil {
+		return err
+	}
+	var latest []recent
+	for rows.Next() {
+		var rev recent
+		if err := rows.Scan(&rev.id, &rev.action, &rev.trackIDs, &rev.fresh); err != nil {
+			rows.Close()
+			return err
+		}
+		latest = append(latest, rev)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return err
+	}
+
+	coalesce := len(latest) > 0 &&
+		action != PlaylistActionReverted &&
+		latest[0].action == action &&
+		latest[0].fresh
+
+	var baseline pq.Int64Array
+	haveBaseline := false
+	switch {
+	case action == PlaylistActionCreated:
+		haveBaseline = true
+	case coalesce && len(latest) > 1:
+		baseline, haveBaseline = latest[1].trackIDs, true
+	case !coalesce && len(latest) > 0:
+		baseline, haveBaseline = latest[0].trackIDs, true
+	}
+	var added, removed sql.NullInt32
+	if haveBaseline {
+		a, rm := diffTrackIDs(baseline, trackIDs)
+		added = sql.NullInt32{Int32: int32(a), Valid: true}
+		removed = sql.NullInt32{Int32: int32(rm), Valid: true}
+	}
+
+	if coalesce {
+		_, err = tx.ExecContext(ctx, `
+			UPDATE playlist_revisions
+			SET name = $1, description = $2, cover_url = $3, is_public = $4, track_ids = $5,
+				tracks_added = $6, tracks_removed = $7, created_at = NOW()
+			WHERE id = $8
+		`, name, description, coverURL, isPublic, trackIDs, added, removed, latest[0].id)
+	} else {
+		_, err = tx.ExecContext(ctx, `
+			INSERT INTO playlist_revisions
+				(playlist_id, action, name, description, cover_url, is_public, track_ids, tracks_added, tracks_removed)
+			VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+		`, playlistID, action, name, description, coverURL, isPublic, trackIDs, added, removed)
+	}
+	if err != nil {
+		return err
+	}
+
+	return prunePlaylistRevisions(ctx, tx, playlistID, policy)
+}
+
+func prunePlaylistRevisions(ctx context.Context, tx *sql.Tx, playlistID int64, policy PlaylistHistoryPolicy) error {
+	if policy.MaxAge > 0 {
+		if _, err := tx.ExecContext(ctx, `
+			DELETE FROM playlist_revisions
+			WHERE playlist_id = $1 AND created_at < NOW() - make_interval(secs => $2)
+		`, playlistID, policy.MaxAge.Seconds()); err != nil {
+			return err
+		}
+	}
+	if policy.MaxRevisions > 0 {
+		if _, err := tx.ExecContext(ctx, `
+			DELETE FROM playlist_revisions
+			WHERE playlist_id = $1 AND id NOT IN (
+				SELECT id FROM playlist_revisions
+				WHERE playlist_id = $1
+				ORDER BY created_at DESC, id DESC
+				LIMIT $2
+			)
+		`, playlistID, policy.MaxRevisions); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// diffTrackIDs counts tracks present only in after (added) and only in before
+// (removed).
+func diffTrackIDs(before, after []int64) (added, removed int) {
+	inBefore := make(map[int64]bool, len(before))
+	for _, id := range before {
+		inBefore[id] = true
+	}
+	inAfter := make(map[int64]bool, len(after))
+	for _, id := range after {
+		inAfter[id] = true
+		if !inBefore[id] {
+			added++
+		}
+	}
+	for _, id := range before {
+		if !inAfter[id] {
+			removed++
+		}
+	}
+	return added, removed
+}
+
+type rowScanner interface {
+	Scan(dest ...any) error
+}
+
+func scanPlaylistRevision(row rowScanner, extra ...any) (*PlaylistRevision, error) {
+	var rev PlaylistRevision
+	var trackIDs pq.Int64Array
+	dest := []any{
+		&rev.ID, &rev.PlaylistID, &rev.Action, &rev.Name, &rev.Description, &rev.CoverURL, &rev.IsPublic, &trackIDs,
+		&rev.TracksAdded, &rev.TracksRemoved, &rev.CreatedAt,
+	}
+	if err := row.Scan(append(dest, extra...)...); err != nil {
+		return nil, err
+	}
+	rev.TrackIDs = []int64(trackIDs)
+	if rev.TrackIDs == nil {
+		rev.TrackIDs = []int64{}
+	}
+	return &rev, nil
+}
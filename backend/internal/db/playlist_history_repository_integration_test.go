@@ -0,0 +1,147 @@
+package db
+
+import (
+	"database/sql"
+	"errors"
+	"testing"
+)
+
+func revisionActions(revisions []PlaylistRevision) []string {
+	actions := make([]string, 0, len(revisions))
+	for _, rev := range revisions {
+		actions = append(actions, rev.Action)
+	}
+	return actions
+}
+
+func assertRevisionDiff(t *testing.T, rev PlaylistRevision, added, removed int32) {
+	t.Helper()
+	if !rev.TracksAdded.Valid || rev.TracksAdded.Int32 != added || !rev.TracksRemoved.Valid || rev.TracksRemoved.Int32 != removed {
+		t.Fatalf("revision %d (%s) diff = +%v -%v, want +%d -%d",
+			rev.ID, rev.Action, rev.TracksAdded, rev.TracksRemoved, added, removed)
+	}
+}
+
+func TestPlaylistHistoryAndRevert(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlaylistRepository(database)
+
+	userID := seedPlaylistUser(t, database, "history@example.test")
+	a := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "a")
+	b := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "b")
+	c := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "c")
+
+	pl := &Playlist{UserID: userID, Name: "Mix"}
+	if err := repo.Create(ctx, pl); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	if _, err := repo.AddTracks(ctx, pl.ID, []int64{a, b}); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+	// A second add right after the first folds into the same revision.
+	if err := repo.AddTrack(ctx, pl.ID, c); err != nil {
+		t.Fatalf("add track: %v", err)
+	}
+	if err := repo.RemoveTracks(ctx, pl.ID, []int64{a}); err != nil {
+		t.Fatalf("remove tracks: %v", err)
+	}
+	pl.Name = "Renamed"
+	pl.Description = sql.NullString{String: "new", Valid: true}
+	if err := repo.Update(ctx, pl, nil); err != nil {
+		t.Fatalf("update playlist: %v", err)
+	}
+
+	history, total, err := repo.History(ctx, pl.ID, 10, 0)
+	if err != nil {
+		t.Fatalf("History: %v", err)
+	}
+	wantActions := []string{PlaylistActionUpdated, PlaylistActionTracksRemoved, PlaylistActionTracksAdded, PlaylistActionCreated}
+	if got := revisionActions(history); total != len(wantActions) || len(got) != len(wantActions) {
+		t.Fatalf("history = %v (total %d), want %v", got, total, wantActions)
+	}
+	for i, want := range wantActions {
+		if history[i].Action != want {
+			t.Fatalf("history actions = %v, want %v", revisionActions(history), wantActions)
+		}
+	}
+	assertRevisionDiff(t, history[0], 0, 0)
+	assertRevisionDiff(t, history[1], 0, 1)
+	assertRevisionDiff(t, history[2], 3, 0)
+	assertRevisionDiff(t, history[3], 0, 0)
+	assertOrder(t, history[2].TrackIDs, []int64{a, b, c})
+
+	if err := repo.Revert(ctx, pl.ID, history[2].ID); err != nil {
+		t.Fatalf("Revert: %v", err)
+	}
+	assertOrder(t, playlistOrder(t, database, pl.ID), []int64{a, b, c})
+	reverted, err := repo.GetByID(ctx, pl.ID)
+	if err != nil {
+		t.Fatalf("get reverted playlist: %v", err)
+	}
+	if reverted.Name != "Mix" || reverted.Description.Valid {
+		t.Fatalf("reverted details = %q %v, want Mix with no description", reverted.Name, reverted.Description)
+	}
+
+	history, total, err = repo.History(ctx, pl.ID, 1, 0)
+	if err != nil {
+		t.Fatalf("History after revert: %v", err)
+	}
+	if total != 5 || len(history) != 1 || history[0].Action != PlaylistActionReverted {
+		t.Fatalf("latest revision = %v (total %d), want reverted of 5", revisionActions(history), total)
+	}
+	assertRevisionDiff(t, history[0], 1, 0)
+
+	if err := repo.Revert(ctx, pl.ID, history[0].ID+1000); !errors.Is(err, ErrPlaylistRevisionNotFound) {
+		t.Fatalf("revert unknown revision error = %v, want ErrPlaylistRevisionNotFound", err)
+	}
+	other := &Playlist{UserID: userID, Name: "Other"}
+	if err := repo.Create(ctx, other); err != nil {
+		t.Fatalf("create other playlist: %v", err)
+	}
+	if err := repo.Revert(ctx, other.ID, history[0].ID); !errors.Is(err, ErrPlaylistRevisionNotFound) {
+		t.Fatalf("revert foreign revision error = %v, want ErrPlaylistRevisionNotFound", err)
+	}
+}
+
+func TestPlaylistHistoryRetention(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlaylistRepository(database)
+	repo.SetHistoryPolicy(PlaylistHistoryPolicy{MaxRevisions: 2})
+
+	userID := seedPlaylistUser(t, database, "retention@example.test")
+	a := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "a")
+	b := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "b")
+
+	pl := &Playlist{UserID: userID, Name: "Short memory"}
+	if err := repo.Create(ctx, pl); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	if _, err := repo.AddTracks(ctx, pl.ID, []int64{a, b}); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+	if err := repo.RemoveTrack(ctx, pl.ID, a); err != nil {
+		t.Fatalf("remove track: %v", err)
+	}
+
+	history, total, err := repo.History(ctx, pl.ID, 10, 0)
+	if err != nil {
+		t.Fatalf("History: %v", err)
+	}
+	if total != 2 || len(history) != 2 ||
+		history[0].Action != PlaylistActionTracksRemoved || history[1].Action != PlaylistActionTracksAdded {
+		t.Fatalf("history = %v (total %d), want the newest two revisions", revisionActions(history), total)
+	}
+
+	if err := repo.Delete(ctx, pl.ID); err != nil {
+		t.Fatalf("delete playlist: %v", err)
+	}
+	var remaining int
+	if err := database.QueryRow(`SELECT COUNT(*) FROM playlist_revisions WHERE playlist_id = $1`, pl.ID).Scan(&remaining); err != nil {
+		t.Fatalf("count revisions: %v", err)
+	}
+	if remaining != 0 {
+		t.Fatalf("revisions after delete = %d, want 0", remaining)
+	}
+}
@@ -14,8 +14,11 @@ import (
 
 var ErrPlaylistNotFound = errors.New("playlist not found")
 var ErrPlaylistNotOwned = errors.New("playlist not owned by user")
+var ErrPlaylistReadOnly = errors.New("playlist is read-only")
 var ErrTrackNotInPlaylist = errors.New("track not in playlist")
 var ErrTrackAlreadyInPlaylist = errors.New("track already in playlist")
+var ErrPlaylistVersionMismatch = errors.New("playlist version mismatch")
+var ErrInvalidTrackRange = errors.New("track range out of bounds")
 
 type Playlist struct {
 	ID          int64
@@ -24,8 +27,11 @@ type Playlist struct {
 	Description sql.NullString
 	CoverURL    sql.NullString
 	IsPublic    bool
-	CreatedAt   time.Time
-	UpdatedAt   time.Time
+	// ReadOnly playlists, such as daily mixes, are kept up by the server;
+	// their owner can play and copy them but not change them.
+	ReadOnly  bool
+	CreatedAt time.Time
+	UpdatedAt time.Time
 }
 
 // ListPlaylistsParams controls search, sorting, and pagination for
@@ -37,6 +43,9 @@ type ListPlaylistsParams struct {
 	Order  string // "asc" | "desc"; anything else falls back to the sort default
 	Limit  int
 	Offset int
+	// PublicOnly leaves out playlists that are not public, for listing
+	// another user's playlists.
+	PublicOnly bool
 }
 
 type PlaylistTrack struct {
@@ -51,42 +60,62 @@ type PlaylistWithTracks struct {
 	Tracks     []Track
 	TrackCount int
 	DurationMs int64
+	SizeBytes  int64
 }
 
 type PlaylistRepository struct {
-	db *DB
+	db      *DB
+	history PlaylistHistoryPolicy
 }
 
 func NewPlaylistRepository(db *DB) *PlaylistRepository {
-	return &PlaylistRepository{db: db}
+	return &PlaylistRepository{db: db, history: DefaultPlaylistHistoryPolicy}
 }
 
-// Create inserts a new playlist into the database.
+// Create inserts a new playlist into the database, after the user's other
+// root-level playlists.
 func (r *PlaylistRepository) Create(ctx context.Context, playlist *Playlist) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := createPlaylist(ctx, tx, playlist, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+func createPlaylist(ctx context.Context, tx *sql.Tx, playlist *Playlist, history PlaylistHistoryPolicy) error {
 	query := `
-		INSERT INTO playlists (user_id, name, description, cover_url, is_public)
-		VALUES ($1, $2, $3, $4, $5)
+		INSERT INTO playlists (user_id, name, description, cover_url, is_public, position)
+		VALUES ($1, $2, $3, $4, $5, (
+			SELECT COALESCE(MAX(position) + 1, 0) FROM playlists WHERE user_id = $1 AND folder_id IS NULL
+		))
 		RETURNING id, created_at, updated_at
 	`
-
-	err := r.db.QueryRowContext(ctx, query,
+	err := tx.QueryRowContext(ctx, query,
 		playlist.UserID, playlist.Name, playlist.Description, playlist.CoverURL, playlist.IsPublic,
 	).Scan(&playlist.ID, &playlist.CreatedAt, &playlist.UpdatedAt)
-
-	return err
+	if err != nil {
+		return err
+	}
+	return recordPlaylistRevision(ctx, tx, playlist.ID, PlaylistActionCreated, history)
 }
 
 // GetByID retrieves a playlist by its ID.
 func (r *PlaylistRepository) GetByID(ctx context.Context, id int64) (*Playlist, error) {
 	query := `
-		SELECT id, user_id, name, description, cover_url, is_public, created_at, updated_at
+		SELECT id, user_id, name, description, cover_url, is_public, read_only, created_at, updated_at
 		FROM playlists
 		WHERE id = $1
 	`
 
 	var p Playlist
 	err := r.db.QueryRowContext(ctx, query, id).Scan(
-		&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.CreatedAt, &p.UpdatedAt,
+		&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.ReadOnly, &p.CreatedAt, &p.UpdatedAt,
 	)
 	if err != nil {
 		if errors.Is(err, sql.ErrNoRows) {
@@ -100,9 +129,23 @@ func (r *PlaylistRepository) GetByID(ctx context.Context, id int64) (*Playlist,
 
 // GetByIDWithTracks retrieves a playlist with all its tracks in a single query.
 func (r *PlaylistRepository) GetByIDWithTracks(ctx context.Context, id int64) (*PlaylistWithTracks, error) {
-	// Single query to get playlist info and all tracks
+	playlists, err := r.GetManyWithTracks(ctx, []int64{id})
+	if err != nil {
+		return nil, err
+	}
+	playlist, ok := playlists[id]
+	if !ok {
+		return nil, ErrPlaylistNotFound
+	}
+	return playlist, nil
+}
+
+// GetManyWithTracks retrieves the playlists with the given IDs and all their
+// tracks in a single query, keyed by playlist ID. IDs without a playlist are
+// absent from the map.
+func (r *PlaylistRepository) GetManyWithTracks(ctx context.Context, ids []int64) (map[int64]*PlaylistWithTracks, error) {
 	query := `
-		SELECT p.id, p.user_id, p.name, p.description, p.cover_url, p.is_public, p.created_at, p.updated_at,
+		SELECT p.id, p.user_id, p.name, p.description, p.cover_url, p.is_public, p.read_only, p.created_at, p.updated_at,
 			   t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
 			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
 			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
@@ -116,20 +159,17 @@ func (r *PlaylistRepository) GetByIDWithTracks(ctx context.Context, id int64) (*
 		LEFT JOIN playlist_tracks pt ON p.id = pt.playlist_id
 		LEFT JOIN tracks t ON pt.track_id = t.id
 		LEFT JOIN track_analysis ta ON ta.track_id = t.id
-		WHERE p.id = $1
-		ORDER BY pt.position ASC
+		WHERE p.id = ANY($1::bigint[])
+		ORDER BY p.id, pt.position ASC
 	`
 
-	rows, err := r.db.QueryContext(ctx, query, id)
+	rows, err := r.db.QueryContext(ctx, query, pq.Array(ids))
 	if err != nil {
 		return nil, err
 	}
 	defer rows.Close()
 
-	var result *PlaylistWithTracks
-	var tracks []Track
-	var totalDuration int64
-
+	playlists := make(map[int64]*PlaylistWithTracks, len(ids))
 	for rows.Next() {
 		var p Playlist
 		var t Track
@@ -137,7 +177,7 @@ func (r *PlaylistRepository) GetByIDWithTracks(ctx context.Context, id int64) (*
 		var analysisOverrides json.RawMessage
 
 		err := rows.Scan(
-			&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.CreatedAt, &p.UpdatedAt,
+			&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.ReadOnly, &p.CreatedAt, &p.UpdatedAt,
 			&trackID, &t.IdentityHash, &t.Title, &t.Artist, &t.Album, &t.DurationMs, &t.Version,
 			&t.MBRecordingID, &t.MBReleaseID, &t.MBArtistID, &t.MBVerified,
 			&t.SourceURL, &t.SourceType, &t.StorageKey, &t.FileSizeBytes,
@@ -149,18 +189,24 @@ func (r *PlaylistRepository) GetByIDWithTracks(ctx context.Context, id int64) (*
 			return nil, err
 		}
 
-		// Initialize playlist on first row
-		if result == nil {
+		// Initialize each playlist on its first row
+		result, ok := playlists[p.ID]
+		if !ok {
 			result = &PlaylistWithTracks{Playlist: p}
+			playlists[p.ID] = result
 		}
 
 		// Add track if present (LEFT JOIN may return NULL for empty playlists)
 		if trackID.Valid {
 			t.ID = trackID.Int64
 			t.AnalysisSummary, _ = projectCompactAnalysis(t.AnalysisSummary, analysisOverrides)
-			tracks = append(tracks, t)
+			result.Tracks = append(result.Tracks, t)
+			result.TrackCount++
 			if t.DurationMs.Valid {
-				totalDuration += int64(t.DurationMs.Int32)
+				result.DurationMs += int64(t.DurationMs.Int32)
+			}
+			if t.FileSizeBytes.Valid {
+				result.SizeBytes += t.FileSizeBytes.Int64
 			}
 		}
 	}
@@ -169,15 +215,7 @@ func (r *PlaylistRepository) GetByIDWithTracks(ctx context.Context, id int64) (*
 		return nil, err
 	}
 
-	if result == nil {
-		return nil, ErrPlaylistNotFound
-	}
-
-	result.Tracks = tracks
-	result.TrackCount = len(tracks)
-	result.DurationMs = totalDuration
-
-	return result, nil
+	return playlists, nil
 }
 
 // GetByUserID retrieves playlists for a user with optional case-insensitive
@@ -197,7 +235,7 @@ func (r *PlaylistRepository) GetByUserID(ctx context.Context, userID uuid.UUID,
 	}
 
 	// Resolve ORDER BY from a whitelist so untrusted query params can never be
-	// concatenated into SQL. track_count aliases the aggregate expression.
+	// concatenated into SQL.
 	orderColumn := "p.updated_at"
 	defaultDesc := true
 	switch strings.ToLower(params.Sort) {
@@ -205,7 +243,7 @@ func (r *PlaylistRepository) GetByUserID(ctx context.Context, userID uuid.UUID,
 		orderColumn = "LOWER(p.name)"
 		defaultDesc = false
 	case "track_count":
-		orderColumn = "track_count"
+		orderColumn = "p.track_count"
 		defaultDesc = true
 	}
 
@@ -222,23 +260,21 @@ func (r *PlaylistRepository) GetByUserID(ctx context.Context, userID uuid.UUID,
 	}
 
 	// Single query with window function for total count (eliminates separate COUNT query).
+	// Totals come from the playlist's cached aggregates (see aggregates.go).
 	// $2 is the case-insensitive name filter ("" => match all).
 	selectQuery := `
-		SELECT p.id, p.user_id, p.name, p.description, p.cover_url, p.is_public, p.created_at, p.updated_at,
-			   COALESCE(COUNT(pt.track_id), 0) as track_count,
-			   COALESCE(SUM(t.duration_ms), 0) as total_duration,
+		SELECT p.id, p.user_id, p.name, p.description, p.cover_url, p.is_public, p.read_only, p.created_at, p.updated_at,
+			   p.track_count, p.duration_ms, p.size_bytes,
 			   COUNT(*) OVER() as total_playlists
 		FROM playlists p
-		LEFT JOIN playlist_tracks pt ON p.id = pt.playlist_id
-		LEFT JOIN tracks t ON pt.track_id = t.id
 		WHERE p.user_id = $1
 		  AND ($2 = '' OR p.name ILIKE '%' || $2 || '%')
-		GROUP BY p.id
+		  AND (NOT $5 OR p.is_public)
 		ORDER BY ` + orderColumn + ` ` + direction + `, p.id ASC
 		LIMIT $3 OFFSET $4
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, userID, params.Query, limit, offset)
+	rows, err := r.db.QueryContext(ctx, selectQuery, userID, params.Query, limit, offset, params.PublicOnly)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -249,8 +285,8 @@ func (r *PlaylistRepository) GetByUserID(ctx context.Context, userID uuid.UUID,
 	for rows.Next() {
 		var p PlaylistWithTracks
 		err := rows.Scan(
-			&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.CreatedAt, &p.UpdatedAt,
-			&p.TrackCount, &p.DurationMs, &total,
+			&p.ID, &p.UserID, &p.Name, &p.Description, &p.CoverURL, &p.IsPublic, &p.ReadOnly, &p.CreatedAt, &p.UpdatedAt,
+			&p.TrackCount, &p.DurationMs, &p.SizeBytes, &total,
 		)
 		if err != nil {
 			return nil, 0, err
@@ -265,8 +301,9 @@ func (r *PlaylistRepository) GetByUserID(ctx context.Context, userID uuid.UUID,
 	return playlists, total, nil
 }
 
-// Update updates a playlist's name and description.
-func (r *PlaylistRepository) Update(ctx context.Context, playlist *Playlist) error {
+// Update updates a playlist's name and description. precondition behaves as
+// in ReorderTrack.
+func (r *PlaylistRepository) Update(ctx context.Context, playlist *Playlist, precondition func(version string) bool) error {
 	query := `
 		UPDATE playlists
 		SET name = $1, description = $2, cover_url = $3, is_public = $4, updated_at = NOW()
@@ -274,7 +311,23 @@ func (r *PlaylistRepository) Update(ctx context.Context, playlist *Playlist) err
 		RETURNING updated_at
 	`
 
-	err := r.db.QueryRowContext(ctx, query,
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if precondition != nil {
+		_, version, err := playlistVersion(ctx, tx, playlist.ID, true)
+		if err != nil {
+			return err
+		}
+		if !precondition(version) {
+			return ErrPlaylistVersionMismatch
+		}
+	}
+
+	err = tx.QueryRowContext(ctx, query,
 		playlist.Name, playlist.Description, playlist.CoverURL, playlist.IsPublic, playlist.ID,
 	).Scan(&playlist.UpdatedAt)
 
@@ -285,7 +338,11 @@ func (r *PlaylistRepository) Update(ctx context.Context, playlist *Playlist) err
 		return err
 	}
 
-	return nil
+	if err := recordPlaylistRevision(ctx, tx, playlist.ID, PlaylistActionUpdated, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
 }
 
 // Delete removes a playlist and all its track associations.
@@ -311,10 +368,16 @@ func (r *PlaylistRepository) Delete(ctx context.Context, id int64) error {
 
 // AddTrack adds a track to a playlist at the end.
 func (r *PlaylistRepository) AddTrack(ctx context.Context, playlistID, trackID int64) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
 	// Get the next position
 	var maxPosition sql.NullInt32
 	posQuery := `SELECT MAX(position) FROM playlist_tracks WHERE playlist_id = $1`
-	if err := r.db.QueryRowContext(ctx, posQuery, playlistID).Scan(&maxPosition); err != nil {
+	if err := tx.QueryRowContext(ctx, posQuery, playlistID).Scan(&maxPosition); err != nil {
 		return err
 	}
 
@@ -328,7 +391,7 @@ func (r *PlaylistRepository) AddTrack(ctx context.Context, playlistID, trackID i
 		VALUES ($1, $2, $3)
 	`
 
-	_, err := r.db.ExecContext(ctx, query, playlistID, trackID, nextPosition)
+	_, err = tx.ExecContext(ctx, query, playlistID, trackID, nextPosition)
 	if err != nil {
 		if isUniqueViolation(err) {
 			return ErrTrackAlreadyInPlaylist
@@ -337,14 +400,34 @@ func (r *PlaylistRepository) AddTrack(ctx context.Context, playlistID, trackID i
 	}
 
 	// Update playlist's updated_at
-	_, err = r.db.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID)
-	return err
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionTracksAdded, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
 }
 
 // AddTrackAtPosition adds a track to a playlist at a specific source-order
 // position. Existing playlist membership is left intact so duplicate playlist
 // imports do not reshuffle user-curated tracks.
 func (r *PlaylistRepository) AddTrackAtPosition(ctx context.Context, playlistID, trackID int64, position int) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if err := addPlaylistTrackAtPosition(ctx, tx, playlistID, trackID, position, r.history); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+func addPlaylistTrackAtPosition(ctx context.Context, tx *sql.Tx, playlistID, trackID int64, position int, history PlaylistHistoryPolicy) error {
 	if position < 0 {
 		position = 0
 	}
@@ -353,7 +436,7 @@ func (r *PlaylistRepository) AddTrackAtPosition(ctx context.Context, playlistID,
 		VALUES ($1, $2, $3)
 		ON CONFLICT (playlist_id, track_id) DO NOTHING
 	`
-	result, err := r.db.ExecContext(ctx, query, playlistID, trackID, position)
+	result, err := tx.ExecContext(ctx, query, playlistID, trackID, position)
 	if err != nil {
 		return err
 	}
@@ -364,8 +447,10 @@ func (r *PlaylistRepository) AddTrackAtPosition(ctx context.Context, playlistID,
 	if rows == 0 {
 		return ErrTrackAlreadyInPlaylist
 	}
-	_, err = r.db.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID)
-	return err
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+	return recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionTracksAdded, history)
 }
 
 // AddTracksResult reports which track IDs were newly appended to the playlist
@@ -382,71 +467,118 @@ type AddTracksResult struct {
 // duplicate rows). It reports the added and skipped IDs rather than erroring on
 // duplicates.
 func (r *PlaylistRepository) AddTracks(ctx context.Context, playlistID int64, trackIDs []int64) (AddTracksResult, error) {
-	result := AddTracksResult{Added: []int64{}, Skipped: []int64{}}
+	empty := AddTracksResult{Added: []int64{}, Skipped: []int64{}}
 	if len(trackIDs) == 0 {
-		return result, nil
+		return empty, nil
 	}
 
 	tx, err := r.db.BeginTx(ctx, nil)
 	if err != nil {
-		return result, err
+		return empty, err
 	}
 	defer tx.Rollback()
 
-	// Get the next position
+	result, err := addPlaylistTracks(ctx, tx, playlistID, trackIDs, r.history)
+	if err != nil {
+		return empty, err
+	}
+	if err := tx.Commit(); err != nil {
+		return empty, err
+	}
+	return result, nil
+}
+
+// addPlaylistTracks appends trackIDs and records the revision when any were
+// added.
+func addPlaylistTracks(ctx context.Context, tx *sql.Tx, playlistID int64, trackIDs []int64, history PlaylistHistoryPolicy) (AddTracksResult, error) {
+	result, err := appendPlaylistTracks(ctx, tx, playlistID, trackIDs)
+	if err != nil {
+		return result, err
+	}
+	if len(result.Added) > 0 {
+		if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionTracksAdded, history); err != nil {
+			return result, err
+		}
+	}
+	return result, nil
+}
+
+// appendPlaylistTracks appends trackIDs after the playlist's last position in
+// one statement. The playlist row is locked first so concurrent appends cannot
+// hand out the same positions. Members already present, tracks outside the
+// tenant of the playlist's owner, and repeats within trackIDs after the first
+// are reported as skipped.
+func appendPlaylistTracks(ctx context.Context, tx *sql.Tx, playlistID int64, trackIDs []int64) (AddTracksResult, error) {
+	result := AddTracksResult{Added: []int64{}, Skipped: []int64{}}
+
+	var locked int64
+	if err := tx.QueryRowContext(ctx, `SELECT id FROM playlists WHERE id = $1 FOR UPDATE`, playlistID).Scan(&locked); err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return result, ErrPlaylistNotFound
+		}
+		return result, err
+	}
+
 	var maxPosition sql.NullInt32
 	posQuery := `SELECT MAX(position) FROM playlist_tracks WHERE playlist_id = $1`
 	if err := tx.QueryRowContext(ctx, posQuery, playlistID).Scan(&maxPosition); err != nil {
 		return result, err
 	}
-
 	nextPosition := 0
 	if maxPosition.Valid {
 		nextPosition = int(maxPosition.Int32) + 1
 	}
 
-	// Insert tracks one by one; RowsAffected distinguishes an insert (added)
-	// from an ON CONFLICT no-op (skipped). Repeated IDs within the same request
-	// also collapse to a single row and are reported as skipped after the first.
+	rows, err := tx.QueryContext(ctx, `
+		SELECT t.id
+		FROM unnest($2::bigint[]) AS t(id)
+		WHERE EXISTS (SELECT 1 FROM playlist_tracks pt WHERE pt.playlist_id = $1 AND pt.track_id = t.id)
+		   OR NOT EXISTS (
+			   SELECT 1 FROM tracks tr
+			   WHERE tr.id = t.id AND `+tenantScopeSQL("tr.tenant_id", "(SELECT user_id FROM playlists WHERE id = $1)")+`
+		   )
+	`, playlistID, pq.Array(trackIDs))
+	if err != nil {
+		return result, err
+	}
 	seen := make(map[int64]bool, len(trackIDs))
-	pos := nextPosition
+	for rows.Next() {
+		var trackID int64
+		if err := rows.Scan(&trackID); err != nil {
+			rows.Close()
+			return result, err
+		}
+		seen[trackID] = true
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return result, err
+	}
+
 	for _, trackID := range trackIDs {
 		if seen[trackID] {
 			result.Skipped = append(result.Skipped, trackID)
 			continue
 		}
 		seen[trackID] = true
-
-		query := `
-			INSERT INTO playlist_tracks (playlist_id, track_id, position)
-			VALUES ($1, $2, $3)
-			ON CONFLICT (playlist_id, track_id) DO NOTHING
-		`
-		res, err := tx.ExecContext(ctx, query, playlistID, trackID, pos)
-		if err != nil {
-			return AddTracksResult{Added: []int64{}, Skipped: []int64{}}, err
-		}
-		affected, err := res.RowsAffected()
-		if err != nil {
-			return AddTracksResult{Added: []int64{}, Skipped: []int64{}}, err
-		}
-		if affected > 0 {
-			result.Added = append(result.Added, trackID)
-			pos++
-		} else {
-			result.Skipped = append(result.Skipped, trackID)
-		}
+		result.Added = append(result.Added, trackID)
 	}
-
-	// Update playlist's updated_at
-	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
-		return AddTracksResult{Added: []int64{}, Skipped: []int64{}}, err
+	if len(result.Added) == 0 {
+		return result, nil
 	}
 
-	if err := tx.Commit(); err != nil {
-		return AddTracksResult{Added: []int64{}, Skipped: []int64{}}, err
+	insertQuery := `
+		INSERT INTO playlist_tracks (playlist_id, track_id, position)
+		SELECT $1, t.track_id, $3 + t.ord - 1
+		FROM unnest($2::bigint[]) WITH ORDINALITY AS t(track_id, ord)
+	`
+	if _, err := tx.ExecContext(ctx, insertQuery, playlistID, pq.Array(result.Added), nextPosition); err != nil {
+		return result, err
 	}
 
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
+		return result, err
+	}
 	return result, nil
 }
 
@@ -468,11 +600,28 @@ func (r *PlaylistRepository) RemoveTracks(ctx context.Context, playlistID int64,
 		return err
 	}
 
-	// Renumber remaining rows to contiguous positions starting at 0, preserving
-	// their existing relative order.
+	if err := renumberPlaylistTracks(ctx, tx, playlistID); err != nil {
+		return err
+	}
+
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionTracksRemoved, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// renumberPlaylistTracks rewrites positions to be contiguous from 0,
+// preserving the existing relative order. Ties (possible after imports place
+// tracks at source positions) are broken by insertion time.
+func renumberPlaylistTracks(ctx context.Context, tx *sql.Tx, playlistID int64) error {
 	renumberQuery := `
 		WITH ordered AS (
-			SELECT track_id, (ROW_NUMBER() OVER (ORDER BY position ASC) - 1) AS new_position
+			SELECT track_id, (ROW_NUMBER() OVER (ORDER BY position ASC, added_at ASC, track_id ASC) - 1) AS new_position
 			FROM playlist_tracks
 			WHERE playlist_id = $1
 		)
@@ -483,23 +632,22 @@ func (r *PlaylistRepository) RemoveTracks(ctx context.Context, playlistID int64,
 		  AND pt.track_id = ordered.track_id
 		  AND pt.position <> ordered.new_position
 	`
-	if _, err := tx.ExecContext(ctx, renumberQuery, playlistID); err != nil {
-		return err
-	}
-
-	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
-		return err
-	}
-
-	return tx.Commit()
+	_, err := tx.ExecContext(ctx, renumberQuery, playlistID)
+	return err
 }
 
 // RemoveTrack removes a track from a playlist and reorders remaining tracks.
 func (r *PlaylistRepository) RemoveTrack(ctx context.Context, playlistID, trackID int64) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
 	// Get the position of the track being removed
 	var position int
 	posQuery := `SELECT position FROM playlist_tracks WHERE playlist_id = $1 AND track_id = $2`
-	err := r.db.QueryRowContext(ctx, posQuery, playlistID, trackID).Scan(&position)
+	err = tx.QueryRowContext(ctx, posQuery, playlistID, trackID).Scan(&position)
 	if err != nil {
 		if errors.Is(err, sql.ErrNoRows) {
 			return ErrTrackNotInPlaylist
@@ -509,7 +657,7 @@ func (r *PlaylistRepository) RemoveTrack(ctx context.Context, playlistID, trackI
 
 	// Delete the track
 	deleteQuery := `DELETE FROM playlist_tracks WHERE playlist_id = $1 AND track_id = $2`
-	_, err = r.db.ExecContext(ctx, deleteQuery, playlistID, trackID)
+	_, err = tx.ExecContext(ctx, deleteQuery, playlistID, trackID)
 	if err != nil {
 		return err
 	}
@@ -520,22 +668,49 @@ func (r *PlaylistRepository) RemoveTrack(ctx context.Context, playlistID, trackI
 		SET position = position - 1
 		WHERE playlist_id = $1 AND position > $2
 	`
-	_, err = r.db.ExecContext(ctx, reorderQuery, playlistID, position)
+	_, err = tx.ExecContext(ctx, reorderQuery, playlistID, position)
 	if err != nil {
 		return err
 	}
 
 	// Update playlist's updated_at
-	_, err = r.db.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID)
-	return err
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionTracksRemoved, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
 }
 
-// ReorderTrack moves a track to a new position within the playlist.
-func (r *PlaylistRepository) ReorderTrack(ctx context.Context, playlistID, trackID int64, newPosition int) error {
+// ReorderTrack moves a track to a new position within the playlist. When
+// precondition is non-nil it is called with the playlist's current version
+// while the playlist row is locked; returning false aborts the move with
+// ErrPlaylistVersionMismatch, so two clients editing the same playlist cannot
+// silently overwrite each other's order.
+func (r *PlaylistRepository) ReorderTrack(ctx context.Context, playlistID, trackID int64, newPosition int, precondition func(version string) bool) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if precondition != nil {
+		_, version, err := playlistVersion(ctx, tx, playlistID, true)
+		if err != nil {
+			return err
+		}
+		if !precondition(version) {
+			return ErrPlaylistVersionMismatch
+		}
+	}
+
 	// Get the current position
 	var currentPosition int
 	posQuery := `SELECT position FROM playlist_tracks WHERE playlist_id = $1 AND track_id = $2`
-	err := r.db.QueryRowContext(ctx, posQuery, playlistID, trackID).Scan(&currentPosition)
+	err = tx.QueryRowContext(ctx, posQuery, playlistID, trackID).Scan(&currentPosition)
 	if err != nil {
 		if errors.Is(err, sql.ErrNoRows) {
 			return ErrTrackNotInPlaylist
@@ -550,7 +725,7 @@ func (r *PlaylistRepository) ReorderTrack(ctx context.Context, playlistID, track
 	// Get the max position to validate newPosition
 	var maxPosition int
 	maxQuery := `SELECT COALESCE(MAX(position), 0) FROM playlist_tracks WHERE playlist_id = $1`
-	if err := r.db.QueryRowContext(ctx, maxQuery, playlistID).Scan(&maxPosition); err != nil {
+	if err := tx.QueryRowContext(ctx, maxQuery, playlistID).Scan(&maxPosition); err != nil {
 		return err
 	}
 
@@ -569,7 +744,7 @@ func (r *PlaylistRepository) ReorderTrack(ctx context.Context, playlistID, track
 			SET position = position + 1
 			WHERE playlist_id = $1 AND position >= $2 AND position < $3
 		`
-		_, err = r.db.ExecContext(ctx, shiftQuery, playlistID, newPosition, currentPosition)
+		_, err = tx.ExecContext(ctx, shiftQuery, playlistID, newPosition, currentPosition)
 	} else {
 		// Moving down: shift tracks between currentPosition and newPosition up
 		shiftQuery := `
@@ -577,7 +752,7 @@ func (r *PlaylistRepository) ReorderTrack(ctx context.Context, playlistID, track
 			SET position = position - 1
 			WHERE playlist_id = $1 AND position > $2 AND position <= $3
 		`
-		_, err = r.db.ExecContext(ctx, shiftQuery, playlistID, currentPosition, newPosition)
+		_, err = tx.ExecContext(ctx, shiftQuery, playlistID, currentPosition, newPosition)
 	}
 	if err != nil {
 		return err
@@ -585,12 +760,353 @@ func (r *PlaylistRepository) ReorderTrack(ctx context.Context, playlistID, track
 
 	// Update the track's position
 	updateQuery := `UPDATE playlist_tracks SET position = $1 WHERE playlist_id = $2 AND track_id = $3`
-	_, err = r.db.ExecContext(ctx, updateQuery, newPosition, playlistID, trackID)
+	if _, err := tx.ExecContext(ctx, updateQuery, newPosition, playlistID, trackID); err != nil {
+		return err
+	}
+
+	// Update playlist's updated_at. clock_timestamp() rather than NOW() so two
+	// reorders in quick succession never share a version.
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = clock_timestamp() WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionReordered, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// Version returns the playlist's owner and a token that changes whenever the
+// playlist, its track list, or the metadata of its tracks change.
+func (r *PlaylistRepository) Version(ctx context.Context, playlistID int64) (uuid.UUID, string, error) {
+	return playlistVersion(ctx, r.db, playlistID, false)
+}
+
+func playlistVersion(ctx context.Context, q rowQueryer, playlistID int64, lock bool) (uuid.UUID, string, error) {
+	query := `
+		SELECT p.user_id, p.updated_at,
+			(SELECT MAX(GREATEST(t.updated_at, ta.updated_at))
+			 FROM playlist_tracks pt
+			 JOIN tracks t ON pt.track_id = t.id
+			 LEFT JOIN track_analysis ta ON ta.track_id = t.id
+			 WHERE pt.playlist_id = p.id)
+		FROM playlists p
+		WHERE p.id = $1
+	`
+	if lock {
+		query += " FOR UPDATE OF p"
+	}
+
+	var owner uuid.UUID
+	var updatedAt time.Time
+	var tracksUpdatedAt sql.NullTime
+	if err := q.QueryRowContext(ctx, query, playlistID).Scan(&owner, &updatedAt, &tracksUpdatedAt); err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return uuid.Nil, "", ErrPlaylistNotFound
+		}
+		return uuid.Nil, "", err
+	}
+	return owner, versionToken(nil, sql.NullTime{Time: updatedAt, Valid: true}, tracksUpdatedAt), nil
+}
+
+// ListVersion returns a token that changes whenever any of the user's
+// playlists is created, deleted, or modified. Track additions and removals
+// bump the playlist's updated_at; the cached totals are summed as well, since
+// a track's duration or size can change without touching its playlists.
+func (r *PlaylistRepository) ListVersion(ctx context.Context, userID uuid.UUID) (string, error) {
+	query := `
+		SELECT COUNT(*), COALESCE(SUM(track_count), 0), COALESCE(SUM(duration_ms), 0), COALESCE(SUM(size_bytes), 0), MAX(updated_at)
+		FROM playlists
+		WHERE user_id = $1
+	`
+
+	var count, tracks, durationMs, sizeBytes int64
+	var updatedAt sql.NullTime
+	if err := r.db.QueryRowContext(ctx, query, userID).Scan(&count, &tracks, &durationMs, &sizeBytes, &updatedAt); err != nil {
+		return "", err
+	}
+	return versionToken([]int64{count, tracks, durationMs, sizeBytes}, updatedAt), nil
+}
+
+// MoveTrackRange moves count contiguous tracks starting at from so the block
+// starts at position to in the resulting order. Positions are renumbered
+// first, so to ranges over 0..len-count. precondition behaves as in
+// ReorderTrack.
+func (r *PlaylistRepository) MoveTrackRange(ctx context.Context, playlistID int64, from, count, to int, precondition func(version string) bool) error {
+	tx, err := r.db.BeginTx(ctx, nil)
 	if err != nil {
 		return err
 	}
+	defer tx.Rollback()
 
-	// Update playlist's updated_at
-	_, err = r.db.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID)
-	return err
+	_, version, err := playlistVersion(ctx, tx, playlistID, true)
+	if err != nil {
+		return err
+	}
+	if precondition != nil && !precondition(version) {
+		return ErrPlaylistVersionMismatch
+	}
+
+	if err := renumberPlaylistTracks(ctx, tx, playlistID); err != nil {
+		return err
+	}
+
+	var total int
+	if err := tx.QueryRowContext(ctx, `SELECT COUNT(*) FROM playlist_tracks WHERE playlist_id = $1`, playlistID).Scan(&total); err != nil {
+		return err
+	}
+	if from < 0 || count < 1 || from+count > total || to < 0 || to > total-count {
+		return ErrInvalidTrackRange
+	}
+	if from == to {
+		return nil
+	}
+
+	// Tracks inside the block keep their offset from to. Every other track
+	// takes its rank among the non-moved tracks, shifted past the block when
+	// it lands at or after to.
+	moveQuery := `
+		UPDATE playlist_tracks
+		SET position = CASE
+			WHEN position >= $2 AND position < $2 + $3 THEN $4 + position - $2
+			WHEN (CASE WHEN position < $2 THEN position ELSE position - $3 END) < $4
+				THEN (CASE WHEN position < $2 THEN position ELSE position - $3 END)
+			ELSE (CASE WHEN position < $2 THEN position ELSE position - $3 END) + $3
+		END
+		WHERE playlist_id = $1
+	`
+	if _, err := tx.ExecContext(ctx, moveQuery, playlistID, from, count, to); err != nil {
+		return err
+	}
+
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = clock_timestamp() WHERE id = $1`, playlistID); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionReordered, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// DeduplicateTracks removes tracks that repeat an earlier entry's recording,
+// keeping the first occurrence. Tracks match on MusicBrainz recording ID, or
+// on case-insensitive title and artist when they have none. The playlist_tracks
+// PK already prevents the same track row twice; this catches the same song
+// downloaded from different sources. Removed IDs are returned in playlist
+// order.
+func (r *PlaylistRepository) DeduplicateTracks(ctx context.Context, playlistID int64) ([]int64, error) {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer tx.Rollback()
+
+	dedupeQuery := `
+		WITH ranked AS (
+			SELECT pt.track_id,
+				ROW_NUMBER() OVER (
+					PARTITION BY COALESCE(
+						'mb:' || t.mb_recording_id::text,
+						'meta:' || lower(btrim(t.title)) || chr(31) || lower(btrim(COALESCE(t.artist, '')))
+					)
+					ORDER BY pt.position ASC, pt.added_at ASC, pt.track_id ASC
+				) AS occurrence
+			FROM playlist_tracks pt
+			JOIN tracks t ON t.id = pt.track_id
+			WHERE pt.playlist_id = $1
+		), removed AS (
+			DELETE FROM playlist_tracks pt
+			USING ranked
+			WHERE pt.playlist_id = $1
+			  AND pt.track_id = ranked.track_id
+			  AND ranked.occurrence > 1
+			RETURNING pt.track_id, pt.position
+		)
+		SELECT track_id FROM removed ORDER BY position ASC, track_id ASC
+	`
+	rows, err := tx.QueryContext(ctx, dedupeQuery, playlistID)
+	if err != nil {
+		return nil, err
+	}
+	removed := []int64{}
+	for rows.Next() {
+		var trackID int64
+		if err := rows.Scan(&trackID); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		removed = append(removed, trackID)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	if len(removed) == 0 {
+		return removed, nil
+	}
+	if err := renumberPlaylistTracks(ctx, tx, playlistID); err != nil {
+		return nil, err
+	}
+	if _, err := tx.ExecContext(ctx, `UPDATE playlists SET updated_at = NOW() WHERE id = $1`, playlistID); err != nil {
+		return nil, err
+	}
+	if err := recordPlaylistRevision(ctx, tx, playlistID, PlaylistActionDeduplicated, r.history); err != nil {
+		return nil, err
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, err
+	}
+	return removed, nil
+}
+
+// Copy creates dest (UserID, Name, Description, CoverURL, IsPublic taken from
+// it) holding the source playlist's tracks in the same order, in a single
+// transaction.
+func (r *PlaylistRepository) Copy(ctx context.Context, sourceID int64, dest *Playlist) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	createQuery := `
+		INSERT INTO playlists (user_id, name, description, cover_url, is_public, position)
+		VALUES ($1, $2, $3, $4, $5, (
+			SELECT COALESCE(MAX(position) + 1, 0) FROM playlists WHERE user_id = $1 AND folder_id IS NULL
+		))
+		RETURNING id, created_at, updated_at
+	`
+	if err := tx.QueryRowContext(ctx, createQuery,
+		dest.UserID, dest.Name, dest.Description, dest.CoverURL, dest.IsPublic,
+	).Scan(&dest.ID, &dest.CreatedAt, &dest.UpdatedAt); err != nil {
+		return err
+	}
+
+	copyQuery := `
+		INSERT INTO playlist_tracks (playlist_id, track_id, position)
+		SELECT $2, track_id, ROW_NUMBER() OVER (ORDER BY position ASC, added_at ASC, track_id ASC) - 1
+		FROM playlist_tracks
+		WHERE playlist_id = $1
+	`
+	if _, err := tx.ExecContext(ctx, copyQuery, sourceID, dest.ID); err != nil {
+		return err
+	}
+
+	if err := recordPlaylistRevision(ctx, tx, dest.ID, PlaylistActionCreated, r.history); err != nil {
+		return err
+	}
+
+	return tx.Commit()
+}
+
+// Merge appends the tracks of sourceIDs, in the given playlist order, to the
+// target playlist in a single transaction, skipping tracks the target already
+// holds. Every playlist must belong to userID. When deleteSources is set the
+// source playlists are deleted in the same transaction.
+func (r *PlaylistRepository) Merge(ctx context.Context, userID uuid.UUID, targetID int64, sourceIDs []int64, deleteSources bool) (AddTracksResult, error) {
+	empty := AddTracksResult{Added: []int64{}, Skipped: []int64{}}
+
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return empty, err
+	}
+	defer tx.Rollback()
+
+	// Lock in id order so two merges over overlapping playlists cannot
+	// deadlock.
+	ids := append([]int64{targetID}, sourceIDs...)
+	rows, err := tx.QueryContext(ctx,
+		`SELECT id, user_id, read_only FROM playlists WHERE id = ANY($1) ORDER BY id FOR UPDATE`, pq.Array(ids))
+	if err != nil {
+		return empty, err
+	}
+	owners := make(map[int64]uuid.UUID, len(ids))
+	readOnly := make(map[int64]bool, len(ids))
+	for rows.Next() {
+		var id int64
+		var owner uuid.UUID
+		var locked bool
+		if err := rows.Scan(&id, &owner, &locked); err != nil {
+			rows.Close()
+			return empty, err
+		}
+		owners[id] = owner
+		readOnly[id] = locked
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return empty, err
+	}
+	for _, id := range ids {
+		owner, ok := owners[id]
+		if !ok {
+			return empty, ErrPlaylistNotFound
+		}
+		if owner != userID {
+			return empty, ErrPlaylistNotOwned
+		}
+	}
+	// Read-only sources may be merged from but not deleted.
+	if readOnly[targetID] {
+		return empty, ErrPlaylistReadOnly
+	}
+	if deleteSources {
+		for _, id := range sourceIDs {
+			if readOnly[id] {
+				return empty, ErrPlaylistReadOnly
+			}
+		}
+	}
+
+	sourceQuery := `
+		SELECT pt.track_id
+		FROM unnest($1::bigint[]) WITH ORDINALITY AS s(playlist_id, ord)
+		JOIN playlist_tracks pt ON pt.playlist_id = s.playlist_id
+		ORDER BY s.ord ASC, pt.position ASC, pt.added_at ASC, pt.track_id ASC
+	`
+	rows, err = tx.QueryContext(ctx, sourceQuery, pq.Array(sourceIDs))
+	if err != nil {
+		return empty, err
+	}
+	var trackIDs []int64
+	for rows.Next() {
+		var trackID int64
+		if err := rows.Scan(&trackID); err != nil {
+			rows.Close()
+			return empty, err
+		}
+		trackIDs = append(trackIDs, trackID)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return empty, err
+	}
+
+	result := empty
+	if len(trackIDs) > 0 {
+		if result, err = appendPlaylistTracks(ctx, tx, targetID, trackIDs); err != nil {
+			return empty, err
+		}
+	}
+
+	if deleteSources {
+		if _, err := tx.ExecContext(ctx, `DELETE FROM playlists WHERE id = ANY($1)`, pq.Array(sourceIDs)); err != nil {
+			return empty, err
+		}
+	}
+
+	if len(result.Added) > 0 {
+		if err := recordPlaylistRevision(ctx, tx, targetID, PlaylistActionMerged, r.history); err != nil {
+			return empty, err
+		}
+	}
+
+	if err := tx.Commit(); err != nil {
+		return empty, err
+	}
+	return result, nil
 }
@@ -2,41 +2,20 @@ package db
 
 import (
 	"context"
-	"database/sql"
 	"encoding/json"
+	"errors"
 	"testing"
 
 	"github.com/google/uuid"
 	_ "github.com/lib/pq"
 )
 
-// newPlaylistTestDB provisions a fresh, migrated Postgres for playlist repository
-// tests, truncating the relevant tables so each test starts clean.
+// newPlaylistTestDB provisions a private, migrated Postgres database for
+// playlist repository tests so they can run in parallel.
 func newPlaylistTestDB(t *testing.T) (*DB, context.Context) {
 	t.Helper()
 
-	dsn := postgresTestDSN()
-	if dsn == "" {
-		t.Skip("set OMP_POSTGRES_TEST_DSN, QA_DATABASE_URL, or DATABASE_URL to run Postgres playlist integration tests")
-	}
-
-	rawDB, err := sql.Open("postgres", dsn)
-	if err != nil {
-		t.Fatalf("open test database: %v", err)
-	}
-	t.Cleanup(func() { _ = rawDB.Close() })
-
-	database := &DB{DB: rawDB}
-	if err := database.Ping(); err != nil {
-		t.Fatalf("ping test database: %v", err)
-	}
-	if err := database.Migrate(); err != nil {
-		t.Fatalf("migrate test database: %v", err)
-	}
-	if _, err := database.Exec("TRUNCATE TABLE playlist_tracks, playlists, user_library, tracks, users RESTART IDENTITY CASCADE"); err != nil {
-		t.Fatalf("truncate test database: %v", err)
-	}
-
+	database := newIsolatedTestDB(t)
 	return database, context.Background()
 }
 
@@ -155,7 +134,7 @@ func TestPlaylistBatchRemoveRenumbersContiguously(t *testing.T) {
 		t.Fatalf("re-add tracks: %v", err)
 	}
 	// Now order is t3(0), t0(1), t2(2). Move t2 to front.
-	if err := repo.ReorderTrack(ctx, pl.ID, trackIDs[2], 0); err != nil {
+	if err := repo.ReorderTrack(ctx, pl.ID, trackIDs[2], 0, nil); err != nil {
 		t.Fatalf("reorder: %v", err)
 	}
 	positions = playlistPositions(t, database, pl.ID)
@@ -390,7 +369,7 @@ func TestPlaylistCoverAndPublicRoundTrip(t *testing.T) {
 	got.Name = "Cover Test 2"
 	got.CoverURL = sql.NullString{String: "https://img.test/other.png", Valid: true}
 	got.IsPublic = false
-	if err := repo.Update(ctx, got); err != nil {
+	if err := repo.Update(ctx, got, nil); err != nil {
 		t.Fatalf("update: %v", err)
 	}
 	reloaded, err := repo.GetByID(ctx, pl.ID)
@@ -406,7 +385,7 @@ func TestPlaylistCoverAndPublicRoundTrip(t *testing.T) {
 
 	// Update clearing cover_url (empty => NULL).
 	reloaded.CoverURL = sql.NullString{}
-	if err := repo.Update(ctx, reloaded); err != nil {
+	if err := repo.Update(ctx, reloaded, nil); err != nil {
 		t.Fatalf("update clear cover: %v", err)
 	}
 	cleared, err := repo.GetByID(ctx, pl.ID)
@@ -417,3 +396,271 @@ func TestPlaylistCoverAndPublicRoundTrip(t *testing.T) {
 		t.Fatalf("cover_url should be NULL after clear, got %#v", cleared.CoverURL)
 	}
 }
+
+func TestPlaylistVersionGuardsReorder(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlaylistRepository(database)
+
+	userID := seedPlaylistUser(t, database, "version@example.test")
+	pl := &Playlist{UserID: userID, Name: "Versioned"}
+	if err := repo.Create(ctx, pl); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	a := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "a")
+	b := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "b")
+	if _, err := repo.AddTracks(ctx, pl.ID, []int64{a, b}); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+
+	listBefore, err := repo.ListVersion(ctx, userID)
+	if err != nil {
+		t.Fatalf("ListVersion: %v", err)
+	}
+	owner, version, err := repo.Version(ctx, pl.ID)
+	if err != nil || owner != userID {
+		t.Fatalf("Version = %v, %q, %v", owner, version, err)
+	}
+
+	if err := repo.ReorderTrack(ctx, pl.ID, b, 0, func(current string) bool { return current == version }); err != nil {
+		t.Fatalf("reorder with current version: %v", err)
+	}
+	_, after, err := repo.Version(ctx, pl.ID)
+	if err != nil || after == version {
+		t.Fatalf("version after reorder = %q, %v; want it to change from %q", after, err, version)
+	}
+	if listAfter, _ := repo.ListVersion(ctx, userID); listAfter == listBefore {
+		t.Fatal("playlist list version must change when a playlist is reordered")
+	}
+
+	// A second client still holding the old version loses the race cleanly.
+	err = repo.ReorderTrack(ctx, pl.ID, a, 0, func(current string) bool { return current == version })
+	if !errors.Is(err, ErrPlaylistVersionMismatch) {
+		t.Fatalf("stale reorder error = %v, want ErrPlaylistVersionMismatch", err)
+	}
+	if positions := playlistPositions(t, database, pl.ID); positions[b] != 0 {
+		t.Fatalf("stale reorder must not move tracks: %v", positions)
+	}
+
+	// Renames are guarded the same way, and so are edits to a track's
+	// metadata.
+	pl.Name = "Stale rename"
+	if err := repo.Update(ctx, pl, func(current string) bool { return current == version }); !errors.Is(err, ErrPlaylistVersionMismatch) {
+		t.Fatalf("stale update error = %v, want ErrPlaylistVersionMismatch", err)
+	}
+	pl.Name = "Renamed"
+	if err := repo.Update(ctx, pl, func(current string) bool { return current == after }); err != nil {
+		t.Fatalf("update with current version: %v", err)
+	}
+	if got, err := repo.GetByID(ctx, pl.ID); err != nil || got.Name != "Renamed" {
+		t.Fatalf("playlist after updates = %+v, %v; want only the current-version rename", got, err)
+	}
+	metadataVersion, err := trackRepo.MetadataVersion(ctx, a)
+	if err != nil {
+		t.Fatalf("MetadataVersion: %v", err)
+	}
+	if err := trackRepo.UpdateMetadata(ctx, a, &MetadataUpdate{Title: "a (edit)"}, func(current string) bool { return current == metadataVersion }); err != nil {
+		t.Fatalf("metadata edit with current version: %v", err)
+	}
+	err = trackRepo.UpdateMetadata(ctx, a, &MetadataUpdate{Title: "a (stale)"}, func(current string) bool { return current == metadataVersion })
+	if !errors.Is(err, ErrTrackVersionMismatch) {
+		t.Fatalf("stale metadata edit error = %v, want ErrTrackVersionMismatch", err)
+	}
+	if track, err := trackRepo.GetByID(ctx, a); err != nil || track.Title != "a (edit)" {
+		t.Fatalf("track after edits = %v, %v; want the first edit kept", track, err)
+	}
+
+	if _, _, err := repo.Version(ctx, pl.ID+1000); !errors.Is(err, ErrPlaylistNotFound) {
+		t.Fatalf("Version of missing playlist = %v, want ErrPlaylistNotFound", err)
+	}
+}
+
+// playlistOrder returns the playlist's track IDs in position order.
+func playlistOrder(t *testing.T, database *DB, playlistID int64) []int64 {
+	t.Helper()
+	positions := playlistPositions(t, database, playlistID)
+	contiguousPositions(t, positions, len(positions))
+	order := make([]int64, len(positions))
+	for trackID, pos := range positions {
+		order[pos] = trackID
+	}
+	return order
+}
+
+func assertOrder(t *testing.T, got, want []int64) {
+	t.Helper()
+	if len(got) != len(want) {
+		t.Fatalf("order = %v, want %v", got, want)
+	}
+	for i := range want {
+		if got[i] != want[i] {
+			t.Fatalf("order = %v, want %v", got, want)
+		}
+	}
+}
+
+func TestPlaylistMoveTrackRange(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlaylistRepository(database)
+
+	userID := seedPlaylistUser(t, database, "move-range@example.test")
+	pl := &Playlist{UserID: userID, Name: "Ranges"}
+	if err := repo.Create(ctx, pl); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	ids := make([]int64, 6)
+	for i := range ids {
+		ids[i] = seedPlaylistTrack(t, trackRepo, ctx, "Artist", string(rune('a'+i)))
+	}
+	if _, err := repo.AddTracks(ctx, pl.ID, ids); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+
+	// [a b c d e f] -> move b,c to the end -> [a d e f b c]
+	if err := repo.MoveTrackRange(ctx, pl.ID, 1, 2, 4, nil); err != nil {
+		t.Fatalf("move down: %v", err)
+	}
+	assertOrder(t, playlistOrder(t, database, pl.ID), []int64{ids[0], ids[3], ids[4], ids[5], ids[1], ids[2]})
+
+	// Move f,b,c to the front -> [f b c a d e]
+	if err := repo.MoveTrackRange(ctx, pl.ID, 3, 3, 0, nil); err != nil {
+		t.Fatalf("move up: %v", err)
+	}
+	assertOrder(t, playlistOrder(t, database, pl.ID), []int64{ids[5], ids[1], ids[2], ids[0], ids[3], ids[4]})
+
+	for _, bad := range [][3]int{{5, 2, 0}, {0, 0, 1}, {0, 2, 5}, {-1, 1, 0}} {
+		if err := repo.MoveTrackRange(ctx, pl.ID, bad[0], bad[1], bad[2], nil); !errors.Is(err, ErrInvalidTrackRange) {
+			t.Fatalf("MoveTrackRange%v error = %v, want ErrInvalidTrackRange", bad, err)
+		}
+	}
+
+	_, version, err := repo.Version(ctx, pl.ID)
+	if err != nil {
+		t.Fatalf("Version: %v", err)
+	}
+	err = repo.MoveTrackRange(ctx, pl.ID, 0, 1, 1, func(current string) bool { return current != version })
+	if !errors.Is(err, ErrPlaylistVersionMismatch) {
+		t.Fatalf("stale move error = %v, want ErrPlaylistVersionMismatch", err)
+	}
+}
+
+func TestPlaylistDeduplicateTracks(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlaylistRepository(database)
+
+	userID := seedPlaylistUser(t, database, "dedupe@example.test")
+	pl := &Playlist{UserID: userID, Name: "Dupes"}
+	if err := repo.Create(ctx, pl); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+
+	recording := uuid.New()
+	seed := func(artist, title, album string, opts ...TrackOption) int64 {
+		t.Helper()
+		opts = append(opts, WithMetadata(json.RawMessage(`{}`)))
+		track, _, err := trackRepo.CreateTrackFromMetadata(ctx, artist, title, album, 200000, opts...)
+		if err != nil {
+			t.Fatalf("seed track %q: %v", title, err)
+		}
+		return track.ID
+	}
+	first := seed("Artist", "Song", "Album")
+	other := seed("Artist", "Other", "Album")
+	sameMeta := seed("ARTIST", " song ", "Single")
+	mbA := seed("Artist", "Take Two", "Album", WithMusicBrainzIDs(&recording, nil, nil))
+	mbB := seed("Someone", "Different Title", "Live", WithMusicBrainzIDs(&recording, nil, nil))
+
+	if _, err := repo.AddTracks(ctx, pl.ID, []int64{first, other, sameMeta, mbA, mbB}); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+
+	removed, err := repo.DeduplicateTracks(ctx, pl.ID)
+	if err != nil {
+		t.Fatalf("DeduplicateTracks: %v", err)
+	}
+	assertOrder(t, removed, []int64{sameMeta, mbB})
+	assertOrder(t, playlistOrder(t, database, pl.ID), []int64{first, other, mbA})
+
+	removed, err = repo.DeduplicateTracks(ctx, pl.ID)
+	if err != nil || len(removed) != 0 {
+		t.Fatalf("second DeduplicateTracks = %v, %v; want nothing removed", removed, err)
+	}
+}
+
+func TestPlaylistCopyAndMerge(t *testing.T) {
+	database, ctx := newPlaylistTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	repo := NewPlaylistRepository(database)
+
+	userID := seedPlaylistUser(t, database, "merge@example.test")
+	otherUser := seedPlaylistUser(t, database, "merge-other@example.test")
+
+	a := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "a")
+	b := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "b")
+	c := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "c")
+	d := seedPlaylistTrack(t, trackRepo, ctx, "Artist", "d")
+
+	source := &Playlist{UserID: userID, Name: "Source"}
+	if err := repo.Create(ctx, source); err != nil {
+		t.Fatalf("create source: %v", err)
+	}
+	if _, err := repo.AddTracks(ctx, source.ID, []int64{c, a, b}); err != nil {
+		t.Fatalf("add tracks: %v", err)
+	}
+
+	copied := &Playlist{UserID: userID, Name: "Source (copy)"}
+	if err := repo.Copy(ctx, source.ID, copied); err != nil {
+		t.Fatalf("Copy: %v", err)
+	}
+	if copied.ID == 0 || copied.ID == source.ID {
+		t.Fatalf("copy ID = %d", copied.ID)
+	}
+	assertOrder(t, playlistOrder(t, database, copied.ID), []int64{c, a, b})
+
+	target := &Playlist{UserID: userID, Name: "Target"}
+	if err := repo.Create(ctx, target); err != nil {
+		t.Fatalf("create target: %v", err)
+	}
+	if _, err := repo.AddTracks(ctx, target.ID, []int64{a}); err != nil {
+		t.Fatalf("add target track: %v", err)
+	}
+	extra := &Playlist{UserID: userID, Name: "Extra"}
+	if err := repo.Create(ctx, extra); err != nil {
+		t.Fatalf("create extra: %v", err)
+	}
+	if _, err := repo.AddTracks(ctx, extra.ID, []int64{d, c}); err != nil {
+		t.Fatalf("add extra tracks: %v", err)
+	}
+	foreign := &Playlist{UserID: otherUser, Name: "Foreign"}
+	if err := repo.Create(ctx, foreign); err != nil {
+		t.Fatalf("create foreign: %v", err)
+	}
+
+	if _, err := repo.Merge(ctx, userID, target.ID, []int64{source.ID, foreign.ID}, true); !errors.Is(err, ErrPlaylistNotOwned) {
+		t.Fatalf("merge foreign error = %v, want ErrPlaylistNotOwned", err)
+	}
+	if _, err := repo.Merge(ctx, userID, target.ID, []int64{source.ID + 1000}, false); !errors.Is(err, ErrPlaylistNotFound) {
+		t.Fatalf("merge missing error = %v, want ErrPlaylistNotFound", err)
+	}
+	// Failed merges must leave everything untouched.
+	assertOrder(t, playlistOrder(t, database, target.ID), []int64{a})
+
+	report, err := repo.Merge(ctx, userID, target.ID, []int64{source.ID, extra.ID}, true)
+	if err != nil {
+		t.Fatalf("Merge: %v", err)
+	}
+	assertOrder(t, report.Added, []int64{c, b, d})
+	assertOrder(t, report.Skipped, []int64{a, c})
+	assertOrder(t, playlistOrder(t, database, target.ID), []int64{a, c, b, d})
+
+	for _, id := range []int64{source.ID, extra.ID} {
+		if _, err := repo.GetByID(ctx, id); !errors.Is(err, ErrPlaylistNotFound) {
+			t.Fatalf("source %d after merge: %v, want deleted", id, err)
+		}
+	}
+	if _, err := repo.GetByID(ctx, copied.ID); err != nil {
+		t.Fatalf("copy must survive merging its original: %v", err)
+	}
+}
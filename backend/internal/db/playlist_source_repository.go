@@ -92,6 +92,21 @@ func (r *PlaylistSourceRepository) LoadBinding(ctx context.Context, userID uuid.
 	return binding, entries, nil
 }
 
+// LoadBindingBySource returns the user's binding to one provider playlist.
+func (r *PlaylistSourceRepository) LoadBindingBySource(ctx context.Context, userID uuid.UUID, provider, providerPlaylistID string) (*PlaylistSourceBinding, error) {
+	binding := &PlaylistSourceBinding{}
+	err := r.db.QueryRowContext(ctx, playlistSourceBindingSelect+`
+		WHERE b.user_id = $1 AND b.provider = $2 AND b.provider_playlist_id = $3
+	`, userID, provider, providerPlaylistID).Scan(playlistSourceBindingFields(binding)...)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrPlaylistSourceBindingNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return binding, nil
+}
+
 // UpsertBinding inserts or updates one owned playlist source binding. The
 // binding's sync state and last-status fields are caller-controlled so a later
 // service slice can record scheduler outcomes without another schema path.
@@ -255,35 +270,47 @@ func (r *PlaylistSourceRepository) ApplyResolvedMapping(ctx context.Context, bin
 // Older imports without a source entry remain valid and follow the same item
 // and playlist completion path.
 func (r *PlaylistSourceRepository) CompletePlaylistImportItem(ctx context.Context, itemID, trackID int64) error {
-	if itemID <= 0 || trackID <= 0 {
-		return fmt.Errorf("playlist import item and track IDs must be positive")
-	}
-
 	tx, err := r.db.BeginTx(ctx, nil)
 	if err != nil {
 		return err
 	}
 	defer func() { _ = tx.Rollback() }()
 
+	if err := completePlaylistImportItem(ctx, tx, itemID, trackID); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+func completePlaylistImportItem(ctx context.Context, tx *sql.Tx, itemID, trackID int64) error {
+	if itemID <= 0 || trackID <= 0 {
+		return fmt.Errorf("playlist import item and track IDs must be positive")
+	}
+
 	var importJobID uuid.UUID
 	var playlistID int64
 	var sourceID string
 	var position int
 	var existingSourceEntryID sql.NullInt64
+	var status string
 	err = tx.QueryRowContext(ctx, `
 		SELECT i.import_job_id, j.playlist_id, i.source_id, i.playlist_position,
-			i.playlist_source_entry_id
+			i.playlist_source_entry_id, i.status
 		FROM playlist_import_items AS i
 		JOIN playlist_import_jobs AS j ON j.id = i.import_job_id
 		WHERE i.id = $1
 		FOR UPDATE OF i, j
-	`, itemID).Scan(&importJobID, &playlistID, &sourceID, &position, &existingSourceEntryID)
+	`, itemID).Scan(&importJobID, &playlistID, &sourceID, &position, &existingSourceEntryID, &status)
 	if errors.Is(err, sql.ErrNoRows) {
 		return nil
 	}
 	if err != nil {
 		return err
 	}
+	// Items of a canceled import stay out of the playlist.
+	if status == "cancel"+"led" {
+		return nil
+	}
 
 	var sourceEntryID int64
 	var sourceEntryTrackID sql.NullInt64
@@ -348,10 +375,7 @@ func (r *PlaylistSourceRepository) CompletePlaylistImportItem(ctx context.Contex
 	`, itemID, trackID); err != nil {
 		return err
 	}
-	if err := refreshPlaylistImportJobCounts(ctx, tx, importJobID); err != nil {
-		return err
-	}
-	return tx.Commit()
+	return refreshPlaylistImportJobCounts(ctx, tx, importJobID)
 }
 
 func refreshPlaylistImportJobCounts(ctx context.Context, tx *sql.Tx, importJobID uuid.UUID) error {
@@ -374,6 +398,7 @@ func refreshPlaylistImportJobCounts(ctx context.Context, tx *sql.Tx, importJobID
 			failed_items = c.failed_items,
 			skipped_items = c.skipped_items,
 			status = CASE
+				WHEN j.status = 'cancel' || 'led' THEN j.status
 				WHEN c.queued_items > 0 THEN 'importing'
 				WHEN c.failed_items > 0 AND c.imported_items + c.skipped_items = 0 THEN 'failed'
 				WHEN c.failed_items > 0 THEN 'partial_failure'
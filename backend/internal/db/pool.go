@@ -0,0 +1,139 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/logger"
+)
+
+// ConnConfig identifies one PostgreSQL server.
+type ConnConfig struct {
+	Host     string
+	Port     string
+	User     string
+	Password string
+	Name     string
+}
+
+// PoolConfig tunes the database/sql pool and per-connection settings. Zero
+// values keep the database/sql defaults.
+//
+// lib/pq has no client-side prepared statement cache; StatementTimeout is the
+// server-side guard against runaway list and search queries instead.
+type PoolConfig struct {
+	MaxOpenConns    int
+	MaxIdleConns    int
+	ConnMaxLifetime time.Duration
+	ConnMaxIdleTime time.Duration
+	// ConnectTimeout bounds establishing a new connection (rounded up to whole
+	// seconds, the granularity lib/pq supports).
+	ConnectTimeout time.Duration
+	// StatementTimeout is applied as the statement_timeout session setting.
+	StatementTimeout time.Duration
+	// SlowQueryThreshold logs statements slower than this at warn level.
+	SlowQueryThreshold time.Duration
+	// Replica, when set, receives the heavy read-only list and search queries
+	// routed through ReadQueryContext. Writes always go to the primary.
+	Replica *ConnConfig
+	// Logger receives slow-query warnings. Defaults to logger.Default().
+	Logger *logger.Logger
+}
+
+// NewWithConfig connects to the primary (and optional replica) using pool.
+func NewWithConfig(conn ConnConfig, pool PoolConfig) (*DB, error) {
+	log := pool.Logger
+	if log == nil {
+		log = logger.Default()
+	}
+	observer := &queryObserver{slowQueryThreshold: pool.SlowQueryThreshold, log: log.WithComponent("db")}
+
+	primary, err := openPool(conn, pool, observer, false)
+	if err != nil {
+		return nil, err
+	}
+
+	db := &DB{DB: primary}
+	if pool.Replica != nil && pool.Replica.Host != "" {
+		replica, err := openPool(*pool.Replica, pool, observer, true)
+		if err != nil {
+			primary.Close()
+			return nil, fmt.Errorf("replica: %w", err)
+		}
+		db.replica = replica
+	}
+	return db, nil
+}
+
+func openPool(conn ConnConfig, pool PoolConfig, observer *queryObserver, replica bool) (*sql.DB, error) {
+	connStr := fmt.Sprintf(
+		"host=%s port=%s user=%s password=%s dbname=%s sslmode=disable",
+		conn.Host, conn.Port, conn.User, conn.Password, conn.Name,
+	)
+	if pool.ConnectTimeout > 0 {
+		seconds := (pool.ConnectTimeout + time.Second - 1) / time.Second
+		connStr += fmt.Sprintf(" connect_timeout=%d", seconds)
+	}
+	if pool.StatementTimeout > 0 {
+		// lib/pq forwards unrecognized keys as session run-time parameters.
+		connStr += fmt.Sprintf(" statement_timeout=%d", pool.StatementTimeout.Milliseconds())
+	}
+
+	db, err := openObserved(connStr, observer, replica)
+	if err != nil {
+		return nil, fmt.Errorf("failed to open database: %w", err)
+	}
+	if pool.MaxOpenConns > 0 {
+		db.SetMaxOpenConns(pool.MaxOpenConns)
+	}
+	if pool.MaxIdleConns > 0 {
+		db.SetMaxIdleConns(pool.MaxIdleConns)
+	}
+	if pool.ConnMaxLifetime > 0 {
+		db.SetConnMaxLifetime(pool.ConnMaxLifetime)
+	}
+	if pool.ConnMaxIdleTime > 0 {
+		db.SetConnMaxIdleTime(pool.ConnMaxIdleTime)
+	}
+
+	if err := db.Ping(); err != nil {
+		db.Close()
+		return nil, fmt.Errorf("failed to ping database: %w", err)
+	}
+	return db, nil
+}
+
+// Close closes the replica pool, if any, and the primary pool.
+func (db *DB) Close() error {
+	if db.replica != nil {
+		db.replica.Close()
+	}
+	return db.DB.Close()
+}
+
+// HasReplica reports whether read-only queries are routed to a replica.
+func (db *DB) HasReplica() bool {
+	return db.replica != nil
+}
+
+// ReadQueryContext runs a read-only query on the replica when one is
+// configured, otherwise on the primary. Only use it for queries that tolerate
+// replication lag; read-your-writes paths must use QueryContext.
+func (db *DB) ReadQueryContext(ctx context.Context, query string, args ...any) (*sql.Rows, error) {
+	if db.replica == nil {
+		return db.QueryContext(ctx, query, args...)
+	}
+	return db.replica.QueryContext(ctx, query, args...)
+}
+
+// PoolStats returns connection pool statistics keyed by pool role
+// ("primary" and, when configured, "replica").
+func (db *DB) PoolStats() map[string]sql.DBStats {
+	stats := map[string]sql.DBStats{"primary": db.DB.Stats()}
+	if db.replica != nil {
+		stats["replica"] = db.replica.Stats()
+	}
+	return stats
+}
@@ -0,0 +1,18 @@
+package db
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestCompactQueryCollapsesWhitespaceAndTruncates(t *testing.T) {
+	got := compactQuery("SELECT id\n\t\tFROM tracks\n  WHERE id = $1", 512)
+	if got != "SELECT id FROM tracks WHERE id = $1" {
+		t.Fatalf("compactQuery = %q", got)
+	}
+
+	long := compactQuery("SELECT "+strings.Repeat("x", 600), 16)
+	if long != "SELECT xxxxxxxxx..." {
+		t.Fatalf("truncated compactQuery = %q", long)
+	}
+}
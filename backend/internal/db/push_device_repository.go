@@ -0,0 +1,124 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// ErrPushDeviceNotFound is returned for a device that does not exist or is
+// registered to another user.
+var ErrPushDeviceNotFound = errors.New("push device not found")
+
+// Push providers.
+const (
+	PushUnifiedPush = "unifiedpush"
+	PushFCM         = "fcm"
+)
+
+// PushDevice is a phone registered for push notifications. Token is the
+// UnifiedPush endpoint URL or the FCM registration token, and Types the
+// notification types the device opted in to.
+type PushDevice struct {
+	ID        int64
+	UserID    uuid.UUID
+	Provider  string
+	Token     string
+	Name      string
+	Types     []string
+	CreatedAt time.Time
+	UpdatedAt time.Time
+}
+
+type PushDeviceRepository struct {
+	db *DB
+}
+
+func NewPushDeviceRepository(db *DB) *PushDeviceRepository {
+	return &PushDeviceRepository{db: db}
+}
+
+const pushDeviceColumns = `id, user_id, provider, token, name, types, created_at, updated_at`
+
+func scanPushDevice(row rowScanner) (*PushDevice, error) {
+	var d PushDevice
+	if err := row.Scan(&d.ID, &d.UserID, &d.Provider, &d.Token, &d.Name, pq.Array(&d.Types), &d.CreatedAt, &d.UpdatedAt); err != nil {
+		return nil, err
+	}
+	return &d, nil
+}
+
+// Register stores a device for device.UserID. Registering a token again
+// replaces its name and types, and moves it to the user when the phone
+// signed in to another account before.
+func (r *PushDeviceRepository) Register(ctx context.Context, device *PushDevice) error {
+	types := device.Types
+	if types == nil {
+		types = []string{}
+	}
+	stored, err := scanPushDevice(r.db.QueryRowContext(ctx, `
+		INSERT INTO push_devices (user_id, provider, token, name, types)
+		VALUES ($1, $2, $3, $4, $5)
+		ON CONFLICT (provider, token) DO UPDATE
+			SET user_id = EXCLUDED.user_id, name = EXCLUDED.name, types = EXCLUDED.types, updated_at = NOW()
+		RETURNING `+pushDeviceColumns,
+		device.UserID, device.Provider, device.Token, device.Name, pq.Array(types)))
+	if err != nil {
+		return err
+	}
+	*device = *stored
+	return nil
+}
+
+// List returns the user's devices, newest first.
+func (r *PushDeviceRepository) List(ctx context.Context, userID uuid.UUID) ([]PushDevice, error) {
+	return r.query(ctx, `SELECT `+pushDeviceColumns+` FROM push_devices WHERE user_id = $1 ORDER BY id DESC`, userID)
+}
+
+// ForNotification returns the user's devices that opted in to the type.
+func (r *PushDeviceRepository) ForNotification(ctx context.Context, userID uuid.UUID, kind string) ([]PushDevice, error) {
+	return r.query(ctx, `SELECT `+pushDeviceColumns+` FROM push_devices WHERE user_id = $1 AND $2 = ANY(types)`, userID, kind)
+}
+
+func (r *PushDeviceRepository) query(ctx context.Context, query string, args ...any) ([]PushDevice, error) {
+	rows, err := r.db.QueryContext(ctx, query, args...)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	devices := []PushDevice{}
+	for rows.Next() {
+		device, err := scanPushDevice(rows)
+		if err != nil {
+			return nil, err
+		}
+		devices = append(devices, *device)
+	}
+	return devices, rows.Err()
+}
+
+// Delete removes one of the user's devices.
+func (r *PushDeviceRepository) Delete(ctx context.Context, userID uuid.UUID, id int64) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM push_devices WHERE user_id = $1 AND id = $2`, userID, id)
+	if err != nil {
+		return err
+	}
+	affected, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if affected == 0 {
+		return ErrPushDeviceNotFound
+	}
+	return nil
+}
+
+// Forget removes a device its provider no longer knows, whoever it belongs
+// to.
+func (r *PushDeviceRepository) Forget(ctx context.Context, id int64) error {
+	_, err := r.db.ExecContext(ctx, `DELETE FROM push_devices WHERE id = $1`, id)
+	return err
+}
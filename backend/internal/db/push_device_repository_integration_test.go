@@ -0,0 +1,48 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+// TestPushDevicesAgainstPostgres covers registering a device twice, moving
+// it to another account, and finding devices by notification type.
+func TestPushDevicesAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewPushDeviceRepository(database)
+	user := seedPlayUser(t, database, "phone@example.test")
+	other := seedPlayUser(t, database, "other@example.test")
+
+	device := &PushDevice{UserID: user, Provider: PushUnifiedPush, Token: "https://push.example.test/up/abc", Name: "Pixel", Types: []string{NotificationNewRelease}}
+	if err := repo.Register(ctx, device); err != nil || device.ID == 0 {
+		t.Fatalf("Register = %+v, %v", device, err)
+	}
+	again := &PushDevice{UserID: user, Provider: PushUnifiedPush, Token: device.Token, Types: []string{NotificationDownloadCompleted, NotificationNewRelease}}
+	if err := repo.Register(ctx, again); err != nil || again.ID != device.ID || len(again.Types) != 2 {
+		t.Fatalf("Register again = %+v, %v; want the same device updated", again, err)
+	}
+	if devices, err := repo.ForNotification(ctx, user, NotificationDownloadCompleted); err != nil || len(devices) != 1 {
+		t.Fatalf("ForNotification = %+v, %v", devices, err)
+	}
+	if devices, err := repo.ForNotification(ctx, user, NotificationGiftReceived); err != nil || len(devices) != 0 {
+		t.Fatalf("ForNotification of a type not opted in to = %+v, %v", devices, err)
+	}
+
+	if err := repo.Register(ctx, &PushDevice{UserID: other, Provider: PushUnifiedPush, Token: device.Token}); err != nil {
+		t.Fatalf("Register for another account: %v", err)
+	}
+	if devices, err := repo.List(ctx, user); err != nil || len(devices) != 0 {
+		t.Fatalf("List after the phone moved accounts = %+v, %v", devices, err)
+	}
+	if err := repo.Delete(ctx, user, device.ID); !errors.Is(err, ErrPushDeviceNotFound) {
+		t.Fatalf("Delete of another user's device = %v; want ErrPushDeviceNotFound", err)
+	}
+	if err := repo.Forget(ctx, device.ID); err != nil {
+		t.Fatalf("Forget: %v", err)
+	}
+	if devices, err := repo.List(ctx, other); err != nil || len(devices) != 0 {
+		t.Fatalf("List after Forget = %+v, %v", devices, err)
+	}
+}
@@ -0,0 +1,34 @@
+package db
+
+import (
+	"context"
+	"sync/atomic"
+)
+
+// QueryCounter counts the statements run through a DB with a context carrying
+// it, so tests can assert that a page costs a constant number of queries
+// however many items it holds. Statements inside a transaction are counted
+// too; BEGIN and COMMIT are not.
+type QueryCounter struct {
+	n atomic.Int64
+}
+
+type queryCounterKey struct{}
+
+// WithQueryCounter returns a context that counts the statements run with it,
+// and the counter.
+func WithQueryCounter(ctx context.Context) (context.Context, *QueryCounter) {
+	counter := &QueryCounter{}
+	return context.WithValue(ctx, queryCounterKey{}, counter), counter
+}
+
+// Count returns how many statements have run so far.
+func (c *QueryCounter) Count() int {
+	return int(c.n.Load())
+}
+
+func countQuery(ctx context.Context) {
+	if counter, ok := ctx.Value(queryCounterKey{}).(*QueryCounter); ok {
+		counter.n.Add(1)
+	}
+}
@@ -0,0 +1,28 @@
+package db
+
+import (
+	"os"
+	"strconv"
+	"strings"
+	"testing"
+)
+
+func TestSchemaVersionMatchesNewestMigration(t *testing.T) {
+	entries, err := os.ReadDir("migrations")
+	if err != nil {
+		t.Fatalf("read migrations: %v", err)
+	}
+	newest := 0
+	for _, entry := range entries {
+		prefix, _, ok := strings.Cut(entry.Name(), "_")
+		if !ok {
+			continue
+		}
+		if n, err := strconv.Atoi(prefix); err == nil && n > newest {
+			newest = n
+		}
+	}
+	if newest != SchemaVersion {
+		t.Fatalf("SchemaVersion = %d, newest migration is %06d", SchemaVersion, newest)
+	}
+}
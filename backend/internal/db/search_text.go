@@ -0,0 +1,151 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"strings"
+	"time"
+	"unicode"
+
+	"github.com/lib/pq"
+	"golang.org/x/text/unicode/norm"
+)
+
+// searchTransliterations spells out letters that have no Unicode
+// decomposition into a base letter plus marks.
+var searchTransliterations = strings.NewReplacer(
+	"ß", "ss", "ẞ", "ss",
+	"æ", "ae", "Æ", "ae",
+	"œ", "oe", "Œ", "oe",
+	"ø", "o", "Ø", "o",
+	"đ", "d", "Đ", "d",
+	"ð", "d", "Ð", "d",
+	"ł", "l", "Ł", "l",
+	"þ", "th", "Þ", "th",
+	"ı", "i",
+)
+
+// NormalizeSearchText folds text for the search index and for search queries,
+// so "Björk", "Bjork", and a decomposed "Björk" all index as "bjork".
+// It applies NFC, lowercases, drops diacritics, and transliterates letters such
+// as ß and ø. Han, kana, and Hangul characters become one token each because
+// CJK text does not separate words with spaces; their marks are kept.
+func NormalizeSearchText(s string) string {
+	s = searchTransliterations.Replace(norm.NFC.String(s))
+	var b strings.Builder
+	prevCJK := false
+	for _, r := range s {
+		switch {
+		case unicode.Is(unicode.Mn, r):
+			// Combining marks left after NFC belong to the previous letter.
+			if prevCJK {
+				b.WriteRune(r)
+			}
+			continue
+		case isCJK(r):
+			b.WriteByte(' ')
+			b.WriteRune(r)
+			prevCJK = true
+			continue
+		}
+		if prevCJK {
+			b.WriteByte(' ')
+			prevCJK = false
+		}
+		for _, d := range norm.NFD.String(string(r)) {
+			if !unicode.Is(unicode.Mn, d) {
+				b.WriteRune(unicode.ToLower(d))
+			}
+		}
+	}
+	return strings.Join(strings.Fields(b.String()), " ")
+}
+
+func isCJK(r rune) bool {
+	return unicode.In(r, unicode.Han, unicode.Hiragana, unicode.Katakana, unicode.Hangul)
+}
+
+// SearchText joins the normalized form of each value into one search
+// document, skipping empty and repeated values.
+func SearchText(values ...string) string {
+	seen := make(map[string]bool, len(values))
+	var parts []string
+	for _, value := range values {
+		if value = NormalizeSearchText(value); value != "" && !seen[value] {
+			seen[value] = true
+			parts = append(parts, value)
+		}
+	}
+	return strings.Join(parts, " ")
+}
+
+// trackSearchDocumentSQL is the full-text document for a track: the
+// normalized search_text, or the raw title, artist, and album for rows the
+// search indexer has not reached yet. It must match idx_tracks_search_document.
+func trackSearchDocumentSQL(alias string) string {
+	return "to_tsvector('english', COALESCE(" + alias + "search_text, COALESCE(" + alias + "title, '') || ' ' || COALESCE(" + alias + "artist, '') || ' ' || COALESCE(" + alias + "album, '')))"
+}
+
+// searchTSQuery builds the prefix tsquery for user input after applying the
+// same normalization as the index.
+func searchTSQuery(query string) string {
+	return buildPrefixTSQuery(NormalizeSearchText(query))
+}
+
+// trackSearchFields computes the search columns stored on a track.
+func trackSearchFields(title, artist, album string, aliases []string) (text, artistText, albumText string) {
+	return SearchText(append([]string{title, artist, album}, aliases...)...), NormalizeSearchText(artist), NormalizeSearchText(album)
+}
+
+// IndexSearchText fills the normalized search columns for up to limit tracks
+// that are missing them: tracks stored before normalization existed and
+// tracks whose title, artist, or album changed since they were indexed. It
+// returns how many tracks it indexed.
+func (r *TrackRepository) IndexSearchText(ctx context.Context, limit int) (int, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, title, artist, album, search_aliases, updated_at
+		FROM tracks
+		WHERE search_text IS NULL
+		ORDER BY id
+		LIMIT $1
+	`, limit)
+	if err != nil {
+		return 0, err
+	}
+	type pending struct {
+		id                          int64
+		updatedAt                   time.Time
+		text, artistText, albumText string
+	}
+	var batch []pending
+	for rows.Next() {
+		var id int64
+		var title string
+		var artist, album sql.NullString
+		var aliases []string
+		var updatedAt time.Time
+		if err := rows.Scan(&id, &title, &artist, &album, pq.Array(&aliases), &updatedAt); err != nil {
+			rows.Close()
+			return 0, err
+		}
+		text, artistText, albumText := trackSearchFields(title, artist.String, album.String, aliases)
+		batch = append(batch, pending{id: id, updatedAt: updatedAt, text: text, artistText: artistText, albumText: albumText})
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return 0, err
+	}
+
+	// A track edited since it was read keeps search_text NULL and is picked
+	// up again by the next pass.
+	for _, track := range batch {
+		if _, err := r.db.ExecContext(ctx, `
+			UPDATE tracks
+			SET search_text = $3, search_artist = NULLIF($4, ''), search_album = NULLIF($5, '')
+			WHERE id = $1 AND updated_at = $2
+		`, track.id, track.updatedAt, track.text, track.artistText, track.albumText); err != nil {
+			return 0, err
+		}
+	}
+	return len(batch), nil
+}
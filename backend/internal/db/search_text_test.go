@@ -0,0 +1,57 @@
+package db
+
+import "testing"
+
+func TestNormalizeSearchText(t *testing.T) {
+	cases := []struct {
+		name  string
+		input string
+		want  string
+	}{
+		{"diacritics folded", "Björk", "bjork"},
+		{"decomposed input matches precomposed", "Bjo\u0308rk", "bjork"},
+		{"letters without decomposition", "Straße Øresund Łódź", "strasse oresund lodz"},
+		{"ligature spelled out", "Mæstro", "maestro"},
+		{"Greek accents", "Ελλάδα", "ελλαδα"},
+		{"CJK split per character", "東京事変", "東 京 事 変"},
+		{"kana keeps voiced marks", "ガガ", "ガ ガ"},
+		{"mixed scripts", "Perfume ポリリズム remix", "perfume ポ リ リ ズ ム remix"},
+		{"hangul syllables kept whole", "방탄소년단", "방 탄 소 년 단"},
+		{"whitespace collapsed", "  Sigur   Rós ", "sigur ros"},
+		{"empty", "", ""},
+	}
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			if got := NormalizeSearchText(tc.input); got != tc.want {
+				t.Fatalf("NormalizeSearchText(%q) = %q, want %q", tc.input, got, tc.want)
+			}
+		})
+	}
+}
+
+func TestSearchTextSkipsEmptyAndRepeatedValues(t *testing.T) {
+	if got, want := SearchText("Björk", "Bjork", "", "Homogenic"), "bjork homogenic"; got != want {
+		t.Fatalf("SearchText = %q, want %q", got, want)
+	}
+}
+
+func TestSearchTSQueryNormalizesInput(t *testing.T) {
+	if got, want := searchTSQuery("Björk / 東京"), "bjork:* & 東:* & 京:*"; got != want {
+		t.Fatalf("searchTSQuery = %q, want %q", got, want)
+	}
+}
+
+// The search benchmarks cover the per-request and per-track normalization
+// work; scripts/loadtest bench runs them.
+func BenchmarkSearchTSQuery(b *testing.B) {
+	for b.Loop() {
+		searchTSQuery("Sigur Rós ágætis byrjun 東京事変")
+	}
+}
+
+func BenchmarkTrackSearchFields(b *testing.B) {
+	aliases := []string{"Björk Guðmundsdóttir", "ビョーク"}
+	for b.Loop() {
+		trackSearchFields("Jóga (Live at Shepherd's Bush Empire)", "Björk", "Homogenic", aliases)
+	}
+}
@@ -0,0 +1,32 @@
+package db
+
+import "context"
+
+// SilenceTrim records silence cut from a track's audio when it was
+// downloaded. The untrimmed file stays in object storage at
+// OriginalStorageKey.
+type SilenceTrim struct {
+	TrackID            int64
+	OriginalStorageKey string
+	OriginalSizeBytes  int64
+	LeadingMs          int
+	TrailingMs         int
+}
+
+type SilenceTrimRepository struct {
+	db *DB
+}
+
+func NewSilenceTrimRepository(db *DB) *SilenceTrimRepository {
+	return &SilenceTrimRepository{db: db}
+}
+
+// Save records trim. A track keeps the first trim recorded for it.
+func (r *SilenceTrimRepository) Save(ctx context.Context, trim *SilenceTrim) error {
+	_, err := r.db.ExecContext(ctx, `
+		INSERT INTO track_silence_trims (track_id, original_storage_key, original_size_bytes, leading_ms, trailing_ms)
+		VALUES ($1, $2, $3, $4, $5)
+		ON CONFLICT (track_id) DO NOTHING
+	`, trim.TrackID, trim.OriginalStorageKey, trim.OriginalSizeBytes, trim.LeadingMs, trim.TrailingMs)
+	return err
+}
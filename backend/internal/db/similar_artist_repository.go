@@ -0,0 +1,85 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+var ErrSimilarArtistsNotFound = errors.New("similar artists not found")
+
+// SimilarArtist is an artist listeners of another also play. Score is the
+// source's similarity, higher meaning closer; scales differ by source.
+type SimilarArtist struct {
+	MBArtistID uuid.UUID `json:"mbArtistId"`
+	Name       string    `json:"name"`
+	Score      float64   `json:"score"`
+}
+
+// SimilarArtistList is the stored similar-artist list of one artist, most
+// similar first.
+type SimilarArtistList struct {
+	MBArtistID uuid.UUID
+	Source     string
+	Artists    []SimilarArtist
+	FetchedAt  time.Time
+}
+
+type SimilarArtistRepository struct {
+	db *DB
+}
+
+func NewSimilarArtistRepository(db *DB) *SimilarArtistRepository {
+	return &SimilarArtistRepository{db: db}
+}
+
+// Get returns the list stored for the artist.
+func (r *SimilarArtistRepository) Get(ctx context.Context, artistID uuid.UUID) (*SimilarArtistList, error) {
+	list := SimilarArtistList{MBArtistID: artistID}
+	var artists []byte
+	err := r.db.QueryRowContext(ctx, `
+		SELECT source, artists, fetched_at
+		FROM similar_artists
+		WHERE mb_artist_id = $1
+	`, artistID).Scan(&list.Source, &artists, &list.FetchedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrSimilarArtistsNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	if err := json.Unmarshal(artists, &list.Artists); err != nil {
+		return nil, fmt.Errorf("decode similar artists: %w", err)
+	}
+	return &list, nil
+}
+
+// Upsert stores the list, replacing the artist's earlier one, and sets
+// FetchedAt.
+func (r *SimilarArtistRepository) Upsert(ctx context.Context, list *SimilarArtistList) error {
+	artists, err := json.Marshal(list.Artists)
+	if err != nil {
+		return err
+	}
+	if list.Artists == nil {
+		artists = []byte("[]")
+	}
+	err = r.db.QueryRowContext(ctx, `
+		INSERT INTO similar_artists (mb_artist_id, source, artists)
+		VALUES ($1, $2, $3)
+		ON CONFLICT (mb_artist_id) DO UPDATE SET
+			source = EXCLUDED.source,
+			artists = EXCLUDED.artists,
+			fetched_at = NOW()
+		RETURNING fetched_at
+	`, list.MBArtistID, list.Source, artists).Scan(&list.FetchedAt)
+	if err != nil {
+		return fmt.Errorf("save similar artists: %w", err)
+	}
+	return nil
+}
@@ -0,0 +1,139 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrSkipMarkersNotFound = errors.New("skip markers not found")
+
+// Where a track's skip markers come from, in order of precedence: the user's
+// own, ones set for everyone, and ones suggested by audio analysis.
+const (
+	SkipMarkerSourceUser     = "user"
+	SkipMarkerSourceGlobal   = "global"
+	SkipMarkerSourceAnalysis = "analysis"
+)
+
+// minAnalysisSkipMs is the shortest leading or trailing silence analysis
+// suggests skipping; shorter gaps are part of a track's pacing.
+const minAnalysisSkipMs = 5000
+
+// SkipMarkers are where playback of a track starts and stops: IntroEndMs
+// skips everything before it, OutroStartMs everything from it on. Either may
+// be unset.
+type SkipMarkers struct {
+	IntroEndMs   sql.NullInt32
+	OutroStartMs sql.NullInt32
+	Source       string
+}
+
+type SkipMarkerRepository struct {
+	db *DB
+}
+
+func NewSkipMarkerRepository(db *DB) *SkipMarkerRepository {
+	return &SkipMarkerRepository{db: db}
+}
+
+// Set stores markers for the track, for the user or, with an invalid userID,
+// for everyone, replacing any set before.
+func (r *SkipMarkerRepository) Set(ctx context.Context, trackID int64, userID uuid.NullUUID, markers SkipMarkers) error {
+	_, err := r.db.ExecContext(ctx, `
+		INSERT INTO track_skip_markers (track_id, user_id, intro_end_ms, outro_start_ms)
+		VALUES ($1, $2, $3, $4)
+		ON CONFLICT (track_id, user_id) DO UPDATE
+		SET intro_end_ms = EXCLUDED.intro_end_ms, outro_start_ms = EXCLUDED.outro_start_ms, updated_at = NOW()
+	`, trackID, userID, markers.IntroEndMs, markers.OutroStartMs)
+	return err
+}
+
+// Delete removes the track's markers for the user or, with an invalid userID,
+// for everyone.
+func (r *SkipMarkerRepository) Delete(ctx context.Context, trackID int64, userID uuid.NullUUID) error {
+	result, err := r.db.ExecContext(ctx,
+		`DELETE FROM track_skip_markers WHERE track_id = $1 AND user_id IS NOT DISTINCT FROM $2`, trackID, userID)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrSkipMarkersNotFound
+	}
+	return nil
+}
+
+// ForUser returns the markers the user's playback honors for each of the
+// tracks, keyed by track ID: the user's own, else those set for everyone,
+// else skips over long leading and trailing silence found by analysis.
+// Tracks without any are absent.
+func (r *SkipMarkerRepository) ForUser(ctx context.Context, userID uuid.UUID, trackIDs []int64) (map[int64]SkipMarkers, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT t.id, t.duration_ms,
+			   mine.id IS NOT NULL, mine.intro_end_ms, mine.outro_start_ms,
+			   everyone.id IS NOT NULL, everyone.intro_end_ms, everyone.outro_start_ms,
+			   CASE WHEN ta.status = 'analyzed' AND jsonb_typeof(ta.summary_json #> '{trim,start_ms}') = 'number'
+					THEN (ta.summary_json #>> '{trim,start_ms}')::numeric END,
+			   CASE WHEN ta.status = 'analyzed' AND jsonb_typeof(ta.summary_json #> '{trim,end_ms}') = 'number'
+					THEN (ta.summary_json #>> '{trim,end_ms}')::numeric END
+		FROM tracks t
+		LEFT JOIN track_skip_markers mine ON mine.track_id = t.id AND mine.user_id = $2
+		LEFT JOIN track_skip_markers everyone ON everyone.track_id = t.id AND everyone.user_id IS NULL
+		LEFT JOIN track_analysis ta ON ta.track_id = t.id
+		WHERE t.id = ANY($1::bigint[])
+	`, pq.Array(trackIDs), userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	markers := make(map[int64]SkipMarkers)
+	for rows.Next() {
+		var id int64
+		var duration sql.NullInt32
+		var hasMine, hasEveryone bool
+		var mine, everyone SkipMarkers
+		var trimStart, trimEnd sql.NullFloat64
+		if err := rows.Scan(&id, &duration,
+			&hasMine, &mine.IntroEndMs, &mine.OutroStartMs,
+			&hasEveryone, &everyone.IntroEndMs, &everyone.OutroStartMs,
+			&trimStart, &trimEnd,
+		); err != nil {
+			return nil, err
+		}
+		switch {
+		case hasMine:
+			mine.Source = SkipMarkerSourceUser
+			markers[id] = mine
+		case hasEveryone:
+			everyone.Source = SkipMarkerSourceGlobal
+			markers[id] = everyone
+		default:
+			if suggested, ok := analysisSkipMarkers(duration, trimStart, trimEnd); ok {
+				markers[id] = suggested
+			}
+		}
+	}
+	return markers, rows.Err()
+}
+
+// analysisSkipMarkers suggests skipping a track's leading and trailing
+// silence, as trimmed by analysis, when it is long enough to notice.
+func analysisSkipMarkers(duration sql.NullInt32, trimStart, trimEnd sql.NullFloat64) (SkipMarkers, bool) {
+	markers := SkipMarkers{Source: SkipMarkerSourceAnalysis}
+	if trimStart.Valid && trimStart.Float64 >= minAnalysisSkipMs {
+		markers.IntroEndMs = sql.NullInt32{Int32: int32(trimStart.Float64), Valid: true}
+	}
+	if trimEnd.Valid && duration.Valid && float64(duration.Int32)-trimEnd.Float64 >= minAnalysisSkipMs &&
+		trimEnd.Float64 > float64(markers.IntroEndMs.Int32) {
+		markers.OutroStartMs = sql.NullInt32{Int32: int32(trimEnd.Float64), Valid: true}
+	}
+	return markers, markers.IntroEndMs.Valid || markers.OutroStartMs.Valid
+}
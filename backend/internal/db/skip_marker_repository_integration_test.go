@@ -0,0 +1,69 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestSkipMarkersResolveUserThenGlobalThenAnalysis(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewSkipMarkerRepository(database)
+	trackRepo := NewTrackRepository(database)
+	userID := seedPlayUser(t, database, "skips@example.test")
+	otherUser := seedPlayUser(t, database, "skips-other@example.test")
+	talky := seedPlayTrack(t, trackRepo, ctx, "Skip Artist", "Spoken Intro")
+	quiet := seedPlayTrack(t, trackRepo, ctx, "Skip Artist", "Quiet Ending")
+	plain := seedPlayTrack(t, trackRepo, ctx, "Skip Artist", "Plain")
+
+	ms := func(n int32) sql.NullInt32 { return sql.NullInt32{Int32: n, Valid: true} }
+	if err := repo.Set(ctx, talky, uuid.NullUUID{}, SkipMarkers{IntroEndMs: ms(40000)}); err != nil {
+		t.Fatalf("set global markers: %v", err)
+	}
+	mine := uuid.NullUUID{UUID: userID, Valid: true}
+	if err := repo.Set(ctx, talky, mine, SkipMarkers{IntroEndMs: ms(20000)}); err != nil {
+		t.Fatalf("set user markers: %v", err)
+	}
+	if err := repo.Set(ctx, talky, mine, SkipMarkers{IntroEndMs: ms(25000), OutroStartMs: ms(190000)}); err != nil {
+		t.Fatalf("replace user markers: %v", err)
+	}
+	// Seeded tracks run 200s; analysis trimmed 8s of trailing silence.
+	if _, err := database.Exec(
+		`INSERT INTO track_analysis (track_id, status, summary_json) VALUES ($1, 'analyzed', '{"trim": {"start_ms": 1200, "end_ms": 192000}}')`,
+		quiet); err != nil {
+		t.Fatalf("seed analysis: %v", err)
+	}
+
+	got, err := repo.ForUser(ctx, userID, []int64{talky, quiet, plain})
+	if err != nil {
+		t.Fatalf("ForUser() error = %v", err)
+	}
+	if m := got[talky]; m.Source != SkipMarkerSourceUser || m.IntroEndMs != ms(25000) || m.OutroStartMs != ms(190000) {
+		t.Fatalf("user markers = %+v", m)
+	}
+	if m := got[quiet]; m.Source != SkipMarkerSourceAnalysis || m.IntroEndMs.Valid || m.OutroStartMs != ms(192000) {
+		t.Fatalf("analysis markers = %+v", m)
+	}
+	if _, ok := got[plain]; ok {
+		t.Fatalf("plain track has markers: %+v", got[plain])
+	}
+
+	got, err = repo.ForUser(ctx, otherUser, []int64{talky})
+	if err != nil || got[talky].Source != SkipMarkerSourceGlobal || got[talky].IntroEndMs != ms(40000) {
+		t.Fatalf("other user's markers = %+v, %v", got, err)
+	}
+
+	if err := repo.Delete(ctx, talky, mine); err != nil {
+		t.Fatalf("delete user markers: %v", err)
+	}
+	if err := repo.Delete(ctx, talky, mine); !errors.Is(err, ErrSkipMarkersNotFound) {
+		t.Fatalf("second delete error = %v, want ErrSkipMarkersNotFound", err)
+	}
+	if got, err := repo.ForUser(ctx, userID, []int64{talky}); err != nil || got[talky].Source != SkipMarkerSourceGlobal {
+		t.Fatalf("markers after delete = %+v, %v", got, err)
+	}
+}
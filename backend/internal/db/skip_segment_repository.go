@@ -0,0 +1,77 @@
+package db
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/lib/pq"
+)
+
+// SkipSegmentSourceSponsorBlock marks segments read from SponsorBlock.
+const SkipSegmentSourceSponsorBlock = "sponsorblock"
+
+// SkipSegment is a non-music stretch of a track, such as a sponsor read,
+// that playback skips. Category is the source's name for it.
+type SkipSegment struct {
+	Category string
+	StartMs  int
+	EndMs    int
+	Source   string
+}
+
+type SkipSegmentRepository struct {
+	db *DB
+}
+
+func NewSkipSegmentRepository(db *DB) *SkipSegmentRepository {
+	return &SkipSegmentRepository{db: db}
+}
+
+// Replace stores segments as trackID's skip segments, dropping any stored
+// before.
+func (r *SkipSegmentRepository) Replace(ctx context.Context, trackID int64, segments []SkipSegment) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM track_skip_segments WHERE track_id = $1`, trackID); err != nil {
+		return fmt.Errorf("clear skip segments: %w", err)
+	}
+	for _, s := range segments {
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO track_skip_segments (track_id, category, start_ms, end_ms, source)
+			VALUES ($1, $2, $3, $4, $5)
+		`, trackID, s.Category, s.StartMs, s.EndMs, s.Source); err != nil {
+			return fmt.Errorf("save skip segment: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// ForTracks returns the skip segments of each of the tracks in order, keyed
+// by track ID. Tracks without any are absent.
+func (r *SkipSegmentRepository) ForTracks(ctx context.Context, trackIDs []int64) (map[int64][]SkipSegment, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT track_id, category, start_ms, end_ms, source
+		FROM track_skip_segments
+		WHERE track_id = ANY($1)
+		ORDER BY track_id, start_ms, id
+	`, pq.Array(trackIDs))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	segments := make(map[int64][]SkipSegment)
+	for rows.Next() {
+		var trackID int64
+		var s SkipSegment
+		if err := rows.Scan(&trackID, &s.Category, &s.StartMs, &s.EndMs, &s.Source); err != nil {
+			return nil, err
+		}
+		segments[trackID] = append(segments[trackID], s)
+	}
+	return segments, rows.Err()
+}
@@ -0,0 +1,34 @@
+package db
+
+import (
+	"context"
+	"reflect"
+	"testing"
+)
+
+func TestSkipSegmentsAreReplacedPerTrack(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewSkipSegmentRepository(database)
+	trackRepo := NewTrackRepository(database)
+	video := seedPlayTrack(t, trackRepo, ctx, "Segment Artist", "Official Video")
+	plain := seedPlayTrack(t, trackRepo, ctx, "Segment Artist", "Audio")
+
+	sponsor := SkipSegment{Category: "sponsor", StartMs: 0, EndMs: 15000, Source: SkipSegmentSourceSponsorBlock}
+	outro := SkipSegment{Category: "music_offtopic", StartMs: 185000, EndMs: 200000, Source: SkipSegmentSourceSponsorBlock}
+	if err := repo.Replace(ctx, video, []SkipSegment{sponsor}); err != nil {
+		t.Fatalf("Replace() error = %v", err)
+	}
+	if err := repo.Replace(ctx, video, []SkipSegment{outro, sponsor}); err != nil {
+		t.Fatalf("second Replace() error = %v", err)
+	}
+
+	got, err := repo.ForTracks(ctx, []int64{video, plain})
+	if err != nil {
+		t.Fatalf("ForTracks() error = %v", err)
+	}
+	want := map[int64][]SkipSegment{video: {sponsor, outro}}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("ForTracks() = %+v, want %+v", got, want)
+	}
+}
@@ -0,0 +1,119 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"regexp"
+	"strings"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// DefaultSortArticles are the leading articles moved behind artist and album
+// names for alphabetical ordering, so "The Beatles" sorts as "Beatles, The".
+var DefaultSortArticles = []string{"The", "A", "An"}
+
+var (
+	ErrInvalidSortArticle = errors.New("invalid sort article")
+	ErrUnknownCollation   = errors.New("unknown collation")
+)
+
+var sortArticlePattern = regexp.MustCompile(`^\p{L}+$`)
+
+// SetSortArticles replaces the leading articles ignored when ordering artists
+// and albums. Matching is case-insensitive. An empty list disables article
+// stripping; until this is called DefaultSortArticles applies.
+func (db *DB) SetSortArticles(articles []string) error {
+	cleaned := make([]string, 0, len(articles))
+	for _, article := range articles {
+		article = strings.TrimSpace(article)
+		if !sortArticlePattern.MatchString(article) {
+			return fmt.Errorf("%w: %q", ErrInvalidSortArticle, article)
+		}
+		cleaned = append(cleaned, article)
+	}
+	db.sortArticles = cleaned
+	db.sortArticlesSet = true
+	return nil
+}
+
+// SetSortCollation orders titles, artists, and albums with the named
+// collation, such as "und-x-icu" for ICU root collation on servers built with
+// ICU. The name must exist in pg_collation. An empty name restores the
+// database default.
+func (db *DB) SetSortCollation(ctx context.Context, name string) error {
+	if name == "" {
+		db.sortCollation = ""
+		return nil
+	}
+	var exists bool
+	if err := db.QueryRowContext(ctx, `SELECT EXISTS (SELECT 1 FROM pg_collation WHERE collname = $1)`, name).Scan(&exists); err != nil {
+		return fmt.Errorf("look up collation: %w", err)
+	}
+	if !exists {
+		return fmt.Errorf("%w: %q", ErrUnknownCollation, name)
+	}
+	db.sortCollation = name
+	return nil
+}
+
+func (db *DB) articles() []string {
+	if db.sortArticlesSet {
+		return db.sortArticles
+	}
+	return DefaultSortArticles
+}
+
+// collateSQL applies the configured collation to an ordering expression.
+func (db *DB) collateSQL(expr string) string {
+	if db.sortCollation == "" {
+		return expr
+	}
+	return expr + " COLLATE " + pq.QuoteIdentifier(db.sortCollation)
+}
+
+// albumSortSQL is the ordering expression for an album column: the name with
+// any leading article moved to the end, in the configured collation.
+func (db *DB) albumSortSQL(column string) string {
+	return db.collateSQL(db.stripArticleSQL(column))
+}
+
+// artistSortSQL is the ordering expression for an artist column: the stored
+// MusicBrainz sort name for that artist when there is one, otherwise the name
+// with any leading article moved to the end, in the configured collation.
+func (db *DB) artistSortSQL(column string) string {
+	return db.collateSQL("COALESCE((SELECT asn.sort_name FROM artist_sort_names asn WHERE asn.name = " + column + "), " + db.stripArticleSQL(column) + ")")
+}
+
+func (db *DB) stripArticleSQL(column string) string {
+	articles := db.articles()
+	if len(articles) == 0 {
+		return column
+	}
+	// Articles are validated as letters only, so they are safe to inline.
+	return `regexp_replace(` + column + `, '^(` + strings.Join(articles, "|") + `)\s+(.+)$', '\2, \1', 'i')`
+}
+
+// RecordArtistSortName stores the MusicBrainz sort name for an artist name as
+// it appears on tracks. Later lookups for the same name replace it. An
+// unparseable artist MBID is stored as NULL.
+func (r *TrackRepository) RecordArtistSortName(ctx context.Context, name, sortName, mbArtistID string) error {
+	name = strings.TrimSpace(name)
+	sortName = strings.TrimSpace(sortName)
+	if name == "" || sortName == "" {
+		return nil
+	}
+	var artistID *uuid.UUID
+	if id, err := uuid.Parse(mbArtistID); err == nil {
+		artistID = &id
+	}
+	_, err := r.db.ExecContext(ctx, `
+		INSERT INTO artist_sort_names (name, sort_name, mb_artist_id, updated_at)
+		VALUES ($1, $2, $3, NOW())
+		ON CONFLICT (name) DO UPDATE
+		SET sort_name = EXCLUDED.sort_name, mb_artist_id = EXCLUDED.mb_artist_id, updated_at = NOW()
+	`, name, sortName, artistID)
+	return err
+}
@@ -0,0 +1,38 @@
+package db
+
+import (
+	"errors"
+	"strings"
+	"testing"
+)
+
+func TestAlbumSortSQLStripsConfiguredArticles(t *testing.T) {
+	database := &DB{}
+	if got, want := database.albumSortSQL("t.album"), `regexp_replace(t.album, '^(The|A|An)\s+(.+)$', '\2, \1', 'i')`; got != want {
+		t.Fatalf("default albumSortSQL = %s; want %s", got, want)
+	}
+
+	if err := database.SetSortArticles([]string{"Die", " Les "}); err != nil {
+		t.Fatalf("SetSortArticles: %v", err)
+	}
+	database.sortCollation = "und-x-icu"
+	if got, want := database.albumSortSQL("album"), `regexp_replace(album, '^(Die|Les)\s+(.+)$', '\2, \1', 'i') COLLATE "und-x-icu"`; got != want {
+		t.Fatalf("configured albumSortSQL = %s; want %s", got, want)
+	}
+
+	if err := database.SetSortArticles([]string{}); err != nil {
+		t.Fatalf("SetSortArticles(empty): %v", err)
+	}
+	if got := database.artistSortSQL("t.artist"); strings.Contains(got, "regexp_replace") || !strings.Contains(got, "artist_sort_names") {
+		t.Fatalf("artistSortSQL without articles = %s", got)
+	}
+}
+
+func TestSetSortArticlesRejectsNonLetters(t *testing.T) {
+	database := &DB{}
+	for _, article := range []string{"", "The|A", "it's", "a b"} {
+		if err := database.SetSortArticles([]string{article}); !errors.Is(err, ErrInvalidSortArticle) {
+			t.Fatalf("SetSortArticles(%q) error = %v; want ErrInvalidSortArticle", article, err)
+		}
+	}
+}
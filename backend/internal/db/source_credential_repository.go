@@ -0,0 +1,119 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+var ErrSourceCredentialNotFound = errors.New("source credential not found")
+
+// Source credential statuses. Only active credentials are used; expired and
+// rejected ones wait for the user to upload fresh cookies.
+const (
+	SourceCredentialActive   = "active"
+	SourceCredentialExpired  = "expired"
+	SourceCredentialRejected = "rejected"
+)
+
+// SourceCredential is the login cookies a user uploaded for one source, such
+// as soundcloud.com. The cookies are stored sealed, so the database alone
+// does not reveal them. ExpiresAt is when the last of them lapses, and is
+// NULL when any lasts only for a browser session.
+type SourceCredential struct {
+	ID            int64
+	UserID        uuid.UUID
+	Source        string
+	SealedCookies []byte
+	CookieCount   int
+	ExpiresAt     sql.NullTime
+	Status        string
+	StatusDetail  string
+	LastUsedAt    sql.NullTime
+	CreatedAt     time.Time
+	UpdatedAt     time.Time
+}
+
+type SourceCredentialRepository struct {
+	db *DB
+}
+
+func NewSourceCredentialRepository(db *DB) *SourceCredentialRepository {
+	return &SourceCredentialRepository{db: db}
+}
+
+// List returns the user's credentials by source.
+func (r *SourceCredentialRepository) List(ctx context.Context, userID uuid.UUID) ([]SourceCredential, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, user_id, source, sealed_cookies, cookie_count, expires_at, status, status_detail, last_used_at, created_at, updated_at
+		FROM source_credentials
+		WHERE user_id = $1
+		ORDER BY source
+	`, userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	credentials := []SourceCredential{}
+	for rows.Next() {
+		var c SourceCredential
+		if err := rows.Scan(&c.ID, &c.UserID, &c.Source, &c.SealedCookies, &c.CookieCount, &c.ExpiresAt, &c.Status, &c.StatusDetail, &c.LastUsedAt, &c.CreatedAt, &c.UpdatedAt); err != nil {
+			return nil, err
+		}
+		credentials = append(credentials, c)
+	}
+	return credentials, rows.Err()
+}
+
+// Put stores the user's cookies for a source, replacing any uploaded before
+// and making the credential active again.
+func (r *SourceCredentialRepository) Put(ctx context.Context, credential *SourceCredential) error {
+	return r.db.QueryRowContext(ctx, `
+		INSERT INTO source_credentials (user_id, source, sealed_cookies, cookie_count, expires_at)
+		VALUES ($1, $2, $3, $4, $5)
+		ON CONFLICT (user_id, source) DO UPDATE
+		SET sealed_cookies = EXCLUDED.sealed_cookies,
+			cookie_count = EXCLUDED.cookie_count,
+			expires_at = EXCLUDED.expires_at,
+			status = 'active',
+			status_detail = '',
+			updated_at = NOW()
+		RETURNING id, status, status_detail, last_used_at, created_at, updated_at
+	`, credential.UserID, credential.Source, credential.SealedCookies, credential.CookieCount, credential.ExpiresAt,
+	).Scan(&credential.ID, &credential.Status, &credential.StatusDetail, &credential.LastUsedAt, &credential.CreatedAt, &credential.UpdatedAt)
+}
+
+// Delete removes the user's cookies for a source.
+func (r *SourceCredentialRepository) Delete(ctx context.Context, userID uuid.UUID, source string) error {
+	result, err := r.db.ExecContext(ctx, `DELETE FROM source_credentials WHERE user_id = $1 AND source = $2`, userID, source)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrSourceCredentialNotFound
+	}
+	return nil
+}
+
+// MarkUsed records a download the credential's cookies fetched.
+func (r *SourceCredentialRepository) MarkUsed(ctx context.Context, id int64) error {
+	_, err := r.db.ExecContext(ctx, `UPDATE source_credentials SET last_used_at = NOW() WHERE id = $1`, id)
+	return err
+}
+
+// SetStatus records why a credential stopped being used.
+func (r *SourceCredentialRepository) SetStatus(ctx context.Context, id int64, status, detail string) error {
+	_, err := r.db.ExecContext(ctx, `
+		UPDATE source_credentials SET status = $2, status_detail = $3, updated_at = NOW()
+		WHERE id = $1
+	`, id, status, detail)
+	return err
+}
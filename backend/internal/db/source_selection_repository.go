@@ -11,6 +11,8 @@ import (
 	"time"
 
 	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/download"
 )
 
 const (
@@ -506,7 +508,9 @@ func marshalTrustedSourceSelectionCandidate(candidate TrustedSourceSelectionCand
 		return nil, nil, fmt.Errorf("%w: required candidate fields", ErrInvalidTrustedSourceCandidate)
 	}
 	parsedURL, err := url.ParseRequestURI(candidate.SourceURL)
-	if err != nil || parsedURL.Scheme != "https" || parsedURL.Host == "" || parsedURL.User != nil {
+	// Direct file URLs may use plain http; every other provider is https-only.
+	allowedScheme := parsedURL != nil && (parsedURL.Scheme == "https" || parsedURL.Scheme == "http" && candidate.Provider == download.SourceTypeDirect)
+	if err != nil || !allowedScheme || parsedURL.Host == "" || parsedURL.User != nil {
 		return nil, nil, fmt.Errorf("%w: source URL", ErrInvalidTrustedSourceCandidate)
 	}
 
@@ -0,0 +1,262 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+var ErrTenantNotFound = errors.New("tenant not found")
+var ErrTenantSlugTaken = errors.New("tenant slug already in use")
+var ErrTenantQuotaExceeded = errors.New("tenant quota exceeded")
+
+// ErrTenantLibraryConflict is returned when moving a user into a tenant
+// while their library holds tracks the tenant cannot see.
+var ErrTenantLibraryConflict = errors.New("library holds tracks outside the tenant")
+
+// Tenant is one household sharing the instance. Its members see only its
+// tracks, its audio is stored below StoragePrefix, and it may be limited in
+// how many tracks and bytes it stores. Users and tracks without a tenant
+// belong to the instance itself.
+type Tenant struct {
+	ID              int64
+	Slug            string
+	Name            string
+	StoragePrefix   string
+	MaxTracks       sql.NullInt64
+	MaxStorageBytes sql.NullInt64
+	CreatedAt       time.Time
+	UpdatedAt       time.Time
+}
+
+// ObjectKey places an object storage key below the tenant's prefix.
+func (t *Tenant) ObjectKey(key string) string {
+	return t.StoragePrefix + "/" + key
+}
+
+// TenantMembership is the tenant a user belongs to and whether they
+// administer it.
+type TenantMembership struct {
+	Tenant Tenant
+	Admin  bool
+}
+
+// TenantMember is one user of a tenant, as its admins see them.
+type TenantMember struct {
+	UserID        uuid.UUID
+	Email         string
+	Username      string
+	Admin         bool
+	LibraryTracks int
+}
+
+// TenantUsage is what a tenant stores, counted against its quotas.
+type TenantUsage struct {
+	Tracks       int64
+	StorageBytes int64
+}
+
+type TenantRepository struct {
+	db *DB
+}
+
+func NewTenantRepository(db *DB) *TenantRepository {
+	return &TenantRepository{db: db}
+}
+
+// queryRower is satisfied by both *DB and *sql.Tx.
+type queryRower interface {
+	QueryRowContext(ctx context.Context, query string, args ...any) *sql.Row
+}
+
+// tenantScopeSQL matches rows whose tenant column is the tenant of the user
+// bound to param. Users without a tenant match instance rows, as does a
+// user that does not exist.
+func tenantScopeSQL(column, param string) string {
+	return column + " IS NOT DISTINCT FROM (SELECT tenant_id FROM users WHERE id = " + param + ")"
+}
+
+const tenantColumns = `t.id, t.slug, t.name, t.storage_prefix, t.max_tracks, t.max_storage_bytes, t.created_at, t.updated_at`
+
+const tenantUsageQuery = `SELECT COUNT(*), COALESCE(SUM(file_size_bytes), 0) FROM tracks WHERE tenant_id = $1`
+
+func scanTenant(row rowScanner, extra ...any) (*Tenant, error) {
+	var t Tenant
+	dest := append([]any{&t.ID, &t.Slug, &t.Name, &t.StoragePrefix, &t.MaxTracks, &t.MaxStorageBytes, &t.CreatedAt, &t.UpdatedAt}, extra...)
+	if err := row.Scan(dest...); err != nil {
+		return nil, err
+	}
+	return &t, nil
+}
+
+// Create inserts a tenant. An empty StoragePrefix becomes "tenants/<slug>";
+// the prefix never changes afterwards, since stored objects live below it.
+func (r *TenantRepository) Create(ctx context.Context, tenant *Tenant) error {
+	if tenant.StoragePrefix == "" {
+		tenant.StoragePrefix = "tenants/" + tenant.Slug
+	}
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO tenants (slug, name, storage_prefix, max_tracks, max_storage_bytes)
+		VALUES ($1, $2, $3, $4, $5)
+		RETURNING id, created_at, updated_at
+	`, tenant.Slug, tenant.Name, tenant.StoragePrefix, tenant.MaxTracks, tenant.MaxStorageBytes,
+	).Scan(&tenant.ID, &tenant.CreatedAt, &tenant.UpdatedAt)
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23505" {
+		return ErrTenantSlugTaken
+	}
+	return err
+}
+
+// GetBySlug returns the tenant with the given slug.
+func (r *TenantRepository) GetBySlug(ctx context.Context, slug string) (*Tenant, error) {
+	tenant, err := scanTenant(r.db.QueryRowContext(ctx, `SELECT `+tenantColumns+` FROM tenants t WHERE t.slug = $1`, slug))
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrTenantNotFound
+	}
+	return tenant, err
+}
+
+// ForUser returns the tenant the user belongs to, or nil for instance users.
+func (r *TenantRepository) ForUser(ctx context.Context, userID uuid.UUID) (*TenantMembership, error) {
+	var admin bool
+	tenant, err := scanTenant(r.db.QueryRowContext(ctx, `
+		SELECT `+tenantColumns+`, u.tenant_admin
+		FROM users u
+		JOIN tenants t ON t.id = u.tenant_id
+		WHERE u.id = $1
+	`, userID), &admin)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &TenantMembership{Tenant: *tenant, Admin: admin}, nil
+}
+
+// AssignUser moves the user with the given email into the tenant, returning
+// their ID. A user whose library holds tracks the tenant cannot see is
+// refused with ErrTenantLibraryConflict, so no library ever spans tenants.
+func (r *TenantRepository) AssignUser(ctx context.Context, tenantID int64, email string, admin bool) (uuid.UUID, error) {
+	var userID uuid.UUID
+	err := r.db.QueryRowContext(ctx, `
+		UPDATE users u
+		SET tenant_id = $1, tenant_admin = $3, updated_at = NOW()
+		WHERE u.email = $2
+			AND NOT EXISTS (
+				SELECT 1 FROM user_library ul
+				JOIN tracks t ON t.id = ul.track_id
+				WHERE ul.user_id = u.id AND t.tenant_id IS DISTINCT FROM $1
+			)
+		RETURNING u.id
+	`, tenantID, email, admin).Scan(&userID)
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23503" {
+		return uuid.Nil, ErrTenantNotFound
+	}
+	if !errors.Is(err, sql.ErrNoRows) {
+		return userID, err
+	}
+	var exists bool
+	if err := r.db.QueryRowContext(ctx, `SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)`, email).Scan(&exists); err != nil {
+		return uuid.Nil, err
+	}
+	if !exists {
+		return uuid.Nil, ErrUserNotFound
+	}
+	return uuid.Nil, ErrTenantLibraryConflict
+}
+
+// Members lists the tenant's users by username.
+func (r *TenantRepository) Members(ctx context.Context, tenantID int64) ([]TenantMember, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT u.id, u.email, u.username, u.tenant_admin,
+			(SELECT COUNT(*) FROM user_library ul WHERE ul.user_id = u.id)
+		FROM users u
+		WHERE u.tenant_id = $1
+		ORDER BY u.username, u.id
+	`, tenantID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	members := []TenantMember{}
+	for rows.Next() {
+		var m TenantMember
+		if err := rows.Scan(&m.UserID, &m.Email, &m.Username, &m.Admin, &m.LibraryTracks); err != nil {
+			return nil, err
+		}
+		members = append(members, m)
+	}
+	return members, rows.Err()
+}
+
+// SetAdmin grants or revokes a member's admin role. Users outside the tenant
+// are reported as ErrUserNotFound.
+func (r *TenantRepository) SetAdmin(ctx context.Context, tenantID int64, userID uuid.UUID, admin bool) error {
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE users SET tenant_admin = $3, updated_at = NOW()
+		WHERE id = $2 AND tenant_id = $1
+	`, tenantID, userID, admin)
+	if err != nil {
+		return err
+	}
+	n, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if n == 0 {
+		return ErrUserNotFound
+	}
+	return nil
+}
+
+// Usage counts the tenant's tracks and stored bytes.
+func (r *TenantRepository) Usage(ctx context.Context, tenantID int64) (TenantUsage, error) {
+	var usage TenantUsage
+	err := r.db.QueryRowContext(ctx, tenantUsageQuery, tenantID).Scan(&usage.Tracks, &usage.StorageBytes)
+	return usage, err
+}
+
+// CheckQuota reports ErrTenantQuotaExceeded when one more track of addBytes
+// would take the tenant past a quota, so callers can stop before storing
+// audio that TrackRepository.Create would refuse.
+func (r *TenantRepository) CheckQuota(ctx context.Context, tenantID, addBytes int64) error {
+	return checkTenantQuota(ctx, r.db, tenantID, addBytes, false)
+}
+
+// checkTenantQuota implements CheckQuota. With lock it holds the tenant row
+// until the transaction ends, so concurrent inserts are counted in turn.
+func checkTenantQuota(ctx context.Context, q queryRower, tenantID, addBytes int64, lock bool) error {
+	query := `SELECT max_tracks, max_storage_bytes FROM tenants WHERE id = $1`
+	if lock {
+		query += ` FOR UPDATE`
+	}
+	var maxTracks, maxBytes sql.NullInt64
+	if err := q.QueryRowContext(ctx, query, tenantID).Scan(&maxTracks, &maxBytes); err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return ErrTenantNotFound
+		}
+		return err
+	}
+	if !maxTracks.Valid && !maxBytes.Valid {
+		return nil
+	}
+	var usage TenantUsage
+	if err := q.QueryRowContext(ctx, tenantUsageQuery, tenantID).Scan(&usage.Tracks, &usage.StorageBytes); err != nil {
+		return err
+	}
+	if maxTracks.Valid && usage.Tracks >= maxTracks.Int64 {
+		return ErrTenantQuotaExceeded
+	}
+	if maxBytes.Valid && usage.StorageBytes+addBytes > maxBytes.Int64 {
+		return ErrTenantQuotaExceeded
+	}
+	return nil
+}
@@ -0,0 +1,141 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestTenantIsolationAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tenants := NewTenantRepository(database)
+	trackRepo := NewTrackRepository(database)
+	libraryRepo := NewLibraryRepository(database)
+
+	smiths := &Tenant{Slug: "smiths", Name: "The Smiths", MaxTracks: sql.NullInt64{Int64: 1, Valid: true}}
+	if err := tenants.Create(ctx, smiths); err != nil {
+		t.Fatalf("create tenant: %v", err)
+	}
+	if smiths.StoragePrefix != "tenants/smiths" {
+		t.Fatalf("storage prefix = %q", smiths.StoragePrefix)
+	}
+	if err := tenants.Create(ctx, &Tenant{Slug: "smiths", Name: "Again"}); !errors.Is(err, ErrTenantSlugTaken) {
+		t.Fatalf("duplicate slug = %v", err)
+	}
+
+	seedPlayUser(t, database, "instance@example.com")
+	instanceUser := seedPlayUser(t, database, "instance2@example.com")
+	member := seedPlayUser(t, database, "member@example.com")
+	if _, err := tenants.AssignUser(ctx, smiths.ID, "member@example.com", true); err != nil {
+		t.Fatalf("assign member: %v", err)
+	}
+	if _, err := tenants.AssignUser(ctx, smiths.ID, "nobody@example.com", false); !errors.Is(err, ErrUserNotFound) {
+		t.Fatalf("assign unknown user = %v", err)
+	}
+
+	instanceTrack := seedPlayTrack(t, trackRepo, ctx, "Low", "Starfire")
+	tenantTrack, created, err := trackRepo.CreateTrackFromMetadata(ctx, "Low", "Starfire", "Starfire Album", 200000, WithTenant(smiths.ID))
+	if err != nil || !created || tenantTrack.ID == instanceTrack {
+		t.Fatalf("tenant copy = %+v, created %v, err %v", tenantTrack, created, err)
+	}
+	if _, _, err := trackRepo.CreateTrackFromMetadata(ctx, "Low", "Lullaby", "", 0, WithTenant(smiths.ID)); !errors.Is(err, ErrTenantQuotaExceeded) {
+		t.Fatalf("track past quota = %v", err)
+	}
+
+	if _, err := libraryRepo.AddTrackToLibrary(ctx, member, instanceTrack); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("member adds instance track = %v", err)
+	}
+	if _, err := libraryRepo.AddTrackToLibrary(ctx, member, tenantTrack.ID); err != nil {
+		t.Fatalf("member adds tenant track: %v", err)
+	}
+	if _, err := libraryRepo.AddTrackToLibrary(ctx, instanceUser, tenantTrack.ID); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("instance user adds tenant track = %v", err)
+	}
+	if _, err := trackRepo.GetByIDForUser(ctx, instanceUser, tenantTrack.ID); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("instance user reads tenant track = %v", err)
+	}
+
+	for _, tt := range []struct {
+		name   string
+		userID uuid.UUID
+		want   int64
+	}{
+		{"member", member, tenantTrack.ID},
+		{"instance user", instanceUser, instanceTrack},
+	} {
+		results, total, err := trackRepo.SearchRecordings(ctx, tt.userID, "starfire", 10, 0)
+		if err != nil {
+			t.Fatalf("%s search: %v", tt.name, err)
+		}
+		if total != 1 || len(results) != 1 || results[0].ID != tt.want {
+			t.Fatalf("%s search = %d results (total %d), want only track %d", tt.name, len(results), total, tt.want)
+		}
+	}
+
+	if _, err := libraryRepo.AddTrackToLibrary(ctx, instanceUser, instanceTrack); err != nil {
+		t.Fatalf("instance user adds instance track: %v", err)
+	}
+	if _, err := tenants.AssignUser(ctx, smiths.ID, "instance2@example.com", false); !errors.Is(err, ErrTenantLibraryConflict) {
+		t.Fatalf("assign user with instance library = %v", err)
+	}
+
+	usage, err := tenants.Usage(ctx, smiths.ID)
+	if err != nil || usage.Tracks != 1 {
+		t.Fatalf("usage = %+v, %v", usage, err)
+	}
+	membership, err := tenants.ForUser(ctx, member)
+	if err != nil || membership == nil || !membership.Admin || membership.Tenant.Slug != "smiths" {
+		t.Fatalf("membership = %+v, %v", membership, err)
+	}
+	if membership, err := tenants.ForUser(ctx, instanceUser); err != nil || membership != nil {
+		t.Fatalf("instance user membership = %+v, %v", membership, err)
+	}
+}
+
+func TestTenantIsolationForPlaylistAdds(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tenants := NewTenantRepository(database)
+	trackRepo := NewTrackRepository(database)
+	playlists := NewPlaylistRepository(database)
+
+	smiths := &Tenant{Slug: "smiths", Name: "The Smiths"}
+	if err := tenants.Create(ctx, smiths); err != nil {
+		t.Fatalf("create tenant: %v", err)
+	}
+	instanceUser := seedPlayUser(t, database, "instance@example.com")
+	seedPlayUser(t, database, "member@example.com")
+	if _, err := tenants.AssignUser(ctx, smiths.ID, "member@example.com", false); err != nil {
+		t.Fatalf("assign member: %v", err)
+	}
+	instanceTrack := seedPlayTrack(t, trackRepo, ctx, "Low", "Starfire")
+	tenantTrack, _, err := trackRepo.CreateTrackFromMetadata(ctx, "Low", "Lullaby", "", 0, WithTenant(smiths.ID))
+	if err != nil {
+		t.Fatalf("create tenant track: %v", err)
+	}
+
+	missing, err := trackRepo.MissingIDs(ctx, instanceUser, []int64{tenantTrack.ID, instanceTrack})
+	if err != nil || len(missing) != 1 || missing[0] != tenantTrack.ID {
+		t.Fatalf("MissingIDs() = %v, %v; want the tenant's track", missing, err)
+	}
+
+	playlist := &Playlist{UserID: instanceUser, Name: "Mine"}
+	if err := playlists.Create(ctx, playlist); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	result, err := playlists.AddTracks(ctx, playlist.ID, []int64{tenantTrack.ID, instanceTrack})
+	if err != nil {
+		t.Fatalf("AddTracks() error = %v", err)
+	}
+	if len(result.Added) != 1 || result.Added[0] != instanceTrack || len(result.Skipped) != 1 || result.Skipped[0] != tenantTrack.ID {
+		t.Fatalf("AddTracks() = %+v; want the tenant's track skipped", result)
+	}
+	withTracks, err := playlists.GetByIDWithTracks(ctx, playlist.ID)
+	if err != nil || len(withTracks.Tracks) != 1 || withTracks.Tracks[0].ID != instanceTrack {
+		t.Fatalf("playlist tracks = %+v, %v; want only the instance track", withTracks, err)
+	}
+}
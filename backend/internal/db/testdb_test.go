@@ -0,0 +1,33 @@
+package db
+
+import (
+	"database/sql"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/testutil"
+)
+
+// newIsolatedTestDB returns a migrated database private to the calling test,
+// cloned from a per-process template, and marks the test parallel. Tests using
+// it must not call t.Setenv.
+//
+// It connects through the observed driver, as NewWithConfig does, so query
+// counting and spans cover the test's statements.
+func newIsolatedTestDB(t *testing.T) *DB {
+	t.Helper()
+
+	dsn := testutil.NewPostgresDSN(t, func(raw *sql.DB) error {
+		return (&DB{DB: raw}).Migrate()
+	})
+	raw, err := openObserved(dsn, &queryObserver{}, false)
+	if err != nil {
+		t.Fatalf("open test database: %v", err)
+	}
+	t.Cleanup(func() { _ = raw.Close() })
+	t.Parallel()
+
+	database := &DB{DB: raw}
+	// Clones keep the extension but not the in-memory flag Migrate() sets.
+	database.TrigramEnabled = database.tryEnableTrigram()
+	return database
+}
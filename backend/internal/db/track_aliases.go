@@ -0,0 +1,108 @@
+package db
+
+import (
+	"context"
+	"fmt"
+	"strings"
+	"unicode"
+)
+
+// Fields a track alias can name.
+const (
+	AliasFieldTitle  = "title"
+	AliasFieldArtist = "artist"
+)
+
+// TrackAlias is another name MusicBrainz gives a track's title or artist,
+// such as a translation or a Latin-script transliteration. Locale is empty
+// for aliases not tied to a language.
+type TrackAlias struct {
+	Field   string `json:"field"`
+	Name    string `json:"name"`
+	Locale  string `json:"locale,omitempty"`
+	Primary bool   `json:"primary,omitempty"`
+}
+
+// ReplaceAliases swaps a track's stored aliases for aliases.
+func (r *TrackRepository) ReplaceAliases(ctx context.Context, trackID int64, aliases []TrackAlias) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM track_aliases WHERE track_id = $1`, trackID); err != nil {
+		return fmt.Errorf("clear track aliases: %w", err)
+	}
+	for _, alias := range aliases {
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO track_aliases (track_id, field, name, locale, is_primary)
+			VALUES ($1, $2, $3, $4, $5)
+			ON CONFLICT (track_id, field, name, locale) DO UPDATE SET is_primary = track_aliases.is_primary OR EXCLUDED.is_primary
+		`, trackID, alias.Field, alias.Name, alias.Locale, alias.Primary); err != nil {
+			return fmt.Errorf("insert track alias: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// DisplayName picks the name to show for original under the user's metadata
+// preferences. An alias in the preferred locale wins, then, when the user
+// prefers Latin script and original is written in another script, a Latin
+// alias. Primary aliases win ties. Without a match the original is kept.
+func (s UserSettings) DisplayName(original string, aliases []TrackAlias) string {
+	if s.MetadataLocale != "" {
+		if alias, ok := bestAlias(aliases, func(a TrackAlias) bool { return localeMatches(a.Locale, s.MetadataLocale) }); ok {
+			return alias.Name
+		}
+	}
+	if s.MetadataScript == MetadataScriptLatin && !isLatinText(original) {
+		if alias, ok := bestAlias(aliases, func(a TrackAlias) bool { return isLatinText(a.Name) }); ok {
+			return alias.Name
+		}
+	}
+	return original
+}
+
+// AliasesFor returns the aliases naming field.
+func AliasesFor(aliases []TrackAlias, field string) []TrackAlias {
+	var out []TrackAlias
+	for _, alias := range aliases {
+		if alias.Field == field {
+			out = append(out, alias)
+		}
+	}
+	return out
+}
+
+func bestAlias(aliases []TrackAlias, match func(TrackAlias) bool) (TrackAlias, bool) {
+	var best TrackAlias
+	found := false
+	for _, alias := range aliases {
+		if !match(alias) {
+			continue
+		}
+		if !found || (alias.Primary && !best.Primary) {
+			best, found = alias, true
+		}
+	}
+	return best, found
+}
+
+// localeMatches treats a preferred language as matching its regional
+// variants, so "en" matches an "en_GB" alias.
+func localeMatches(locale, preferred string) bool {
+	locale, preferred = strings.ToLower(locale), strings.ToLower(preferred)
+	return locale == preferred || strings.HasPrefix(locale, preferred+"_") || strings.HasPrefix(locale, preferred+"-")
+}
+
+// isLatinText reports whether every letter in s is Latin script. Text with
+// no letters counts as Latin, since there is nothing to transliterate.
+func isLatinText(s string) bool {
+	for _, r := range s {
+		if unicode.IsLetter(r) && !unicode.Is(unicode.Latin, r) {
+			return false
+		}
+	}
+	return true
+}
@@ -0,0 +1,29 @@
+package db
+
+import "testing"
+
+func TestDisplayNamePrefersLocaleThenLatinScript(t *testing.T) {
+	aliases := []TrackAlias{
+		{Field: AliasFieldTitle, Name: "Paranoid Android (romaji)"},
+		{Field: AliasFieldTitle, Name: "Paranoid Android", Primary: true},
+		{Field: AliasFieldTitle, Name: "パラノイド・アンドロイド", Locale: "ja", Primary: true},
+		{Field: AliasFieldTitle, Name: "Androïde paranoïaque", Locale: "fr_FR"},
+	}
+	cases := []struct {
+		name     string
+		settings UserSettings
+		original string
+		want     string
+	}{
+		{"default keeps original", DefaultUserSettings(), "パラノイド", "パラノイド"},
+		{"locale prefix match", UserSettings{MetadataScript: MetadataScriptOriginal, MetadataLocale: "fr"}, "Paranoid Android", "Androïde paranoïaque"},
+		{"latin prefers primary", UserSettings{MetadataScript: MetadataScriptLatin}, "パラノイド", "Paranoid Android"},
+		{"latin leaves latin original", UserSettings{MetadataScript: MetadataScriptLatin}, "Paranoid Android", "Paranoid Android"},
+		{"unmatched locale falls back to script", UserSettings{MetadataScript: MetadataScriptLatin, MetadataLocale: "de"}, "パラノイド", "Paranoid Android"},
+	}
+	for _, tc := range cases {
+		if got := tc.settings.DisplayName(tc.original, aliases); got != tc.want {
+			t.Errorf("%s: DisplayName(%q) = %q; want %q", tc.name, tc.original, got, tc.want)
+		}
+	}
+}
@@ -1,12 +1,16 @@
 package db
 
 import (
+	"context"
 	"encoding/json"
 	"testing"
+
+	"github.com/google/uuid"
 )
 
 func TestTrackAnalysisProjectsIntoSongListingsAgainstPostgres(t *testing.T) {
-	database, ctx := newPlayEventTestDB(t)
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
 	trackRepo := NewTrackRepository(database)
 	analysisRepo := NewAnalysisRepository(database)
 	playlistRepo := NewPlaylistRepository(database)
@@ -53,7 +57,7 @@ func TestTrackAnalysisProjectsIntoSongListingsAgainstPostgres(t *testing.T) {
 		t.Fatal("compact analysis revision is missing")
 	}
 
-	searchTracks, _, err := trackRepo.SearchRecordings(ctx, "Projection", 20, 0)
+	searchTracks, _, err := trackRepo.SearchRecordings(ctx, uuid.Nil, "Projection", 20, 0)
 	if err != nil {
 		t.Fatalf("search recordings: %v", err)
 	}
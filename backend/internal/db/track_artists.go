@@ -0,0 +1,120 @@
+package db
+
+import (
+	"context"
+	"fmt"
+	"regexp"
+	"strings"
+
+	"github.com/google/uuid"
+)
+
+// Artist credit roles on a track.
+const (
+	ArtistRoleMain     = "main"
+	ArtistRoleFeatured = "featured"
+	ArtistRoleRemixer  = "remixer"
+)
+
+// TrackArtist is one artist credited on a track. MBArtistID is set for
+// credits that came from MusicBrainz.
+type TrackArtist struct {
+	Name       string     `json:"name"`
+	Role       string     `json:"role"`
+	MBArtistID *uuid.UUID `json:"mb_artist_id,omitempty"`
+}
+
+var (
+	// featArtistPattern splits "Main feat. Guest" in an artist tag.
+	featArtistPattern = regexp.MustCompile(`(?i)\s+(?:feat\.?|ft\.?|featuring)\s+`)
+	// featTitlePattern matches "(feat. Guest)" or "[ft. Guest]" in a title.
+	featTitlePattern = regexp.MustCompile(`(?i)[(\[]\s*(?:feat\.?|ft\.?|featuring)\s+([^)\]]+)[)\]]`)
+	// remixTitlePattern matches "(Name Remix)" in a title, but not a bare
+	// "(Remix)" or a dated "(2011 Remix)".
+	remixTitlePattern = regexp.MustCompile(`(?i)[(\[]\s*([^)\]]*[^\d\s)\]][^)\]]*?)\s+(?:remix|rmx)\s*[)\]]`)
+	// guestSeparator splits a list of featured artists. Main artists are not
+	// split, since "&" is as likely to be part of a band name.
+	guestSeparator = regexp.MustCompile(`\s*(?:,|&|\band\b)\s*`)
+)
+
+// ParseArtistCredits derives artist credits from an artist tag and a title:
+// the artist tag before any "feat." is the main credit, guests after it or in
+// a "(feat. ...)" title suffix are featured, and "(Name Remix)" credits a
+// remixer.
+func ParseArtistCredits(artist, title string) []TrackArtist {
+	var credits []TrackArtist
+	parts := featArtistPattern.Split(strings.TrimSpace(artist), 2)
+	if main := strings.TrimSpace(parts[0]); main != "" {
+		credits = append(credits, TrackArtist{Name: main, Role: ArtistRoleMain})
+	}
+	if len(parts) == 2 {
+		credits = appendGuests(credits, parts[1])
+	}
+	return append(credits, TitleArtistCredits(title)...)
+}
+
+// TitleArtistCredits returns the featured artists and remixers named in a
+// title.
+func TitleArtistCredits(title string) []TrackArtist {
+	var credits []TrackArtist
+	for _, match := range featTitlePattern.FindAllStringSubmatch(title, -1) {
+		credits = appendGuests(credits, match[1])
+	}
+	for _, match := range remixTitlePattern.FindAllStringSubmatch(title, -1) {
+		credits = append(credits, TrackArtist{Name: strings.TrimSpace(match[1]), Role: ArtistRoleRemixer})
+	}
+	return credits
+}
+
+func appendGuests(credits []TrackArtist, guests string) []TrackArtist {
+	for _, name := range guestSeparator.Split(guests, -1) {
+		if name = strings.TrimSpace(name); name != "" {
+			credits = append(credits, TrackArtist{Name: name, Role: ArtistRoleFeatured})
+		}
+	}
+	return credits
+}
+
+// ReplaceArtistCredits swaps a track's artist credits for credits, keeping
+// their order. Automatic sources leave the credits alone while the user has
+// locked the artist field; a user source always applies. Replacing them moves
+// the track's updated_at, so library versions change.
+func (r *TrackRepository) ReplaceArtistCredits(ctx context.Context, trackID int64, credits []TrackArtist, source string) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if source != FieldSourceUser {
+		var locked bool
+		if err := tx.QueryRowContext(ctx, `
+			SELECT EXISTS (
+				SELECT 1 FROM track_field_provenance
+				WHERE track_id = $1 AND field = 'artist' AND locked
+			)
+		`, trackID).Scan(&locked); err != nil {
+			return fmt.Errorf("check artist lock: %w", err)
+		}
+		if locked {
+			return nil
+		}
+	}
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM track_artists WHERE track_id = $1`, trackID); err != nil {
+		return fmt.Errorf("clear artist credits: %w", err)
+	}
+	for i, credit := range credits {
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO track_artists (track_id, position, name, role, mb_artist_id)
+			VALUES ($1, $2, $3, $4, $5)
+			ON CONFLICT (track_id, role, name) DO NOTHING
+		`, trackID, i, credit.Name, credit.Role, credit.MBArtistID); err != nil {
+			return fmt.Errorf("insert artist credit: %w", err)
+		}
+	}
+	if _, err := tx.ExecContext(ctx, `UPDATE tracks SET updated_at = NOW() WHERE id = $1`, trackID); err != nil {
+		return fmt.Errorf("touch track: %w", err)
+	}
+	return tx.Commit()
+}
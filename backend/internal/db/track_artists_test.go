@@ -0,0 +1,32 @@
+package db
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestParseArtistCredits(t *testing.T) {
+	for _, tc := range []struct {
+		artist, title string
+		want          []TrackArtist
+	}{
+		{"Simon & Garfunkel", "The Boxer", []TrackArtist{{Name: "Simon & Garfunkel", Role: ArtistRoleMain}}},
+		{"Main ft. A, B & C", "Song", []TrackArtist{
+			{Name: "Main", Role: ArtistRoleMain},
+			{Name: "A", Role: ArtistRoleFeatured},
+			{Name: "B", Role: ArtistRoleFeatured},
+			{Name: "C", Role: ArtistRoleFeatured},
+		}},
+		{"Main", "Song (feat. Guest) [Someone Remix]", []TrackArtist{
+			{Name: "Main", Role: ArtistRoleMain},
+			{Name: "Guest", Role: ArtistRoleFeatured},
+			{Name: "Someone", Role: ArtistRoleRemixer},
+		}},
+		{"Main", "Song (2011 Remix)", []TrackArtist{{Name: "Main", Role: ArtistRoleMain}}},
+		{"", "Song (Remix)", nil},
+	} {
+		if got := ParseArtistCredits(tc.artist, tc.title); !reflect.DeepEqual(got, tc.want) {
+			t.Fatalf("ParseArtistCredits(%q, %q) = %+v; want %+v", tc.artist, tc.title, got, tc.want)
+		}
+	}
+}
@@ -266,6 +266,13 @@ func CalculateIdentityHash(artist, title, album string, durationMs int, version
 	return hex.EncodeToString(hash[:])[:16]
 }
 
+// TenantIdentityHash scopes an identity hash to one tenant, in the same
+// 16-character form.
+func TenantIdentityHash(tenantID int64, identityHash string) string {
+	hash := sha256.Sum256([]byte(fmt.Sprintf("tenant:%d|%s", tenantID, identityHash)))
+	return hex.EncodeToString(hash[:])[:16]
+}
+
 // CalculateIdentityHashFromTrack calculates the identity hash from a TrackIdentity struct.
 func CalculateIdentityHashFromTrack(t TrackIdentity) string {
 	return CalculateIdentityHash(t.Artist, t.Title, t.Album, t.DurationMs, t.Version)
@@ -0,0 +1,143 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+)
+
+// MoodFeatures are rough proxies, each from 0 to 1, for how energetic,
+// positive and danceable a track sounds.
+type MoodFeatures struct {
+	Energy       float64
+	Valence      float64
+	Danceability float64
+}
+
+// moodBucket is a mood tracks can be filtered by: a condition on their mood
+// features, in SQL over tracks t and in Go.
+type moodBucket struct {
+	name      string
+	condition string
+	matches   func(MoodFeatures) bool
+}
+
+// moodBuckets overlap; a track can be both chill and happy.
+var moodBuckets = []moodBucket{
+	{"chill", "t.energy < 0.4", func(f MoodFeatures) bool { return f.Energy < 0.4 }},
+	{"high_energy", "t.energy >= 0.7", func(f MoodFeatures) bool { return f.Energy >= 0.7 }},
+	{"happy", "t.valence >= 0.6", func(f MoodFeatures) bool { return f.Valence >= 0.6 }},
+	{"sad", "t.valence < 0.4 AND t.energy < 0.5", func(f MoodFeatures) bool { return f.Valence < 0.4 && f.Energy < 0.5 }},
+	{"danceable", "t.danceability >= 0.65", func(f MoodFeatures) bool { return f.Danceability >= 0.65 }},
+}
+
+// MoodNames lists every mood bucket, for validating requests.
+var MoodNames = func() []string {
+	names := make([]string, len(moodBuckets))
+	for i, b := range moodBuckets {
+		names[i] = b.name
+	}
+	return names
+}()
+
+// Moods names the buckets a track with these features falls in.
+func (f MoodFeatures) Moods() []string {
+	moods := []string{}
+	for _, b := range moodBuckets {
+		if b.matches(f) {
+			moods = append(moods, b.name)
+		}
+	}
+	return moods
+}
+
+// moodCondition is the SQL condition on tracks t for the named mood, or
+// empty for an unknown one. Tracks not measured yet match no mood.
+func moodCondition(mood string) string {
+	for _, b := range moodBuckets {
+		if b.name == mood {
+			return "(" + b.condition + ")"
+		}
+	}
+	return ""
+}
+
+// moodFeaturesFromColumns returns the features read from a track's columns,
+// or nil when it has not been measured.
+func moodFeaturesFromColumns(energy, valence, danceability sql.NullFloat64) *MoodFeatures {
+	if !energy.Valid || !valence.Valid || !danceability.Valid {
+		return nil
+	}
+	return &MoodFeatures{Energy: energy.Float64, Valence: valence.Float64, Danceability: danceability.Float64}
+}
+
+// SaveMoodFeatures records the mood features measured from the track's
+// stored object storageKey. Nil features mark the object measured without
+// result, such as for silent audio, so it is not measured again. The track's
+// updated_at moves with them, so library versions change.
+func (r *TrackRepository) SaveMoodFeatures(ctx context.Context, trackID int64, storageKey string, features *MoodFeatures) error {
+	var energy, valence, danceability sql.NullFloat64
+	if features != nil {
+		energy = sql.NullFloat64{Float64: features.Energy, Valid: true}
+		valence = sql.NullFloat64{Float64: features.Valence, Valid: true}
+		danceability = sql.NullFloat64{Float64: features.Danceability, Valid: true}
+	}
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE tracks
+		SET energy = $2, valence = $3, danceability = $4, mood_storage_key = $5, updated_at = NOW()
+		WHERE id = $1
+	`, trackID, energy, valence, danceability, storageKey)
+	if err != nil {
+		return err
+	}
+	if n, err := result.RowsAffected(); err == nil && n == 0 {
+		return ErrTrackNotFound
+	}
+	return nil
+}
+
+// GetMoodFeatureCandidates returns a bounded, stable batch of stored tracks
+// whose current file has not had its mood features measured.
+func (r *TrackRepository) GetMoodFeatureCandidates(ctx context.Context, limit int) ([]Track, error) {
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 200 {
+		limit = 200
+	}
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
+			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
+			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
+			   t.codec, t.bitrate_kbps, t.sample_rate_hz, t.channels, t.content_type,
+			   COALESCE(t.metadata_json, '{}'::jsonb), t.metadata_status, t.metadata_confidence,
+			   COALESCE(t.metadata_provenance, '{}'::jsonb),
+			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at
+		FROM tracks t
+		WHERE t.storage_key IS NOT NULL
+		  AND btrim(t.storage_key) <> ''
+		  AND t.mood_storage_key IS DISTINCT FROM t.storage_key
+		ORDER BY t.id ASC
+		LIMIT $1
+	`, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	tracks := make([]Track, 0, limit)
+	for rows.Next() {
+		var track Track
+		if err := rows.Scan(
+			&track.ID, &track.IdentityHash, &track.Title, &track.Artist, &track.Album, &track.DurationMs, &track.Version,
+			&track.MBRecordingID, &track.MBReleaseID, &track.MBArtistID, &track.MBVerified,
+			&track.SourceURL, &track.SourceType, &track.StorageKey, &track.FileSizeBytes,
+			&track.Codec, &track.BitrateKbps, &track.SampleRateHz, &track.Channels, &track.ContentType,
+			&track.MetadataJSON, &track.MetadataStatus, &track.MetadataConfidence, &track.MetadataProvenance,
+			&track.CoverArtURL, &track.MetadataUserEdited, &track.CreatedAt, &track.UpdatedAt,
+		); err != nil {
+			return nil, err
+		}
+		tracks = append(tracks, track)
+	}
+	return tracks, rows.Err()
+}
@@ -0,0 +1,78 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"strconv"
+	"testing"
+)
+
+func TestMoodFeaturesFilterTheLibrary(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	userID := seedPlayUser(t, database, "moods@example.test")
+
+	calm := seedPlayTrack(t, tracks, ctx, "Artist", "calm")
+	loud := seedPlayTrack(t, tracks, ctx, "Artist", "loud")
+	silent := seedPlayTrack(t, tracks, ctx, "Artist", "silent")
+	for _, id := range []int64{calm, loud, silent} {
+		if _, err := database.Exec(`UPDATE tracks SET storage_key = 'tracks/' || id WHERE id = $1`, id); err != nil {
+			t.Fatalf("set storage key: %v", err)
+		}
+		if _, err := library.AddTrackToLibrary(ctx, userID, id); err != nil {
+			t.Fatalf("add track: %v", err)
+		}
+	}
+
+	candidates, err := tracks.GetMoodFeatureCandidates(ctx, 10)
+	if err != nil || len(candidates) != 3 {
+		t.Fatalf("GetMoodFeatureCandidates() = %d tracks, %v; want 3", len(candidates), err)
+	}
+	unmeasured, err := library.LibraryVersion(ctx, userID)
+	if err != nil {
+		t.Fatalf("LibraryVersion() before measuring error = %v", err)
+	}
+	saves := map[int64]*MoodFeatures{
+		calm:   {Energy: 0.2, Valence: 0.5, Danceability: 0.3},
+		loud:   {Energy: 0.9, Valence: 0.7, Danceability: 0.8},
+		silent: nil,
+	}
+	for id, features := range saves {
+		if err := tracks.SaveMoodFeatures(ctx, id, "tracks/"+strconv.FormatInt(id, 10), features); err != nil {
+			t.Fatalf("SaveMoodFeatures(%d) error = %v", id, err)
+		}
+	}
+	if err := tracks.SaveMoodFeatures(ctx, -1, "tracks/-1", nil); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("SaveMoodFeatures(missing) error = %v, want ErrTrackNotFound", err)
+	}
+	if candidates, _ := tracks.GetMoodFeatureCandidates(ctx, 10); len(candidates) != 0 {
+		t.Fatalf("GetMoodFeatureCandidates() after measuring = %+v, want none", candidates)
+	}
+	if measured, err := library.LibraryVersion(ctx, userID); err != nil || measured == unmeasured {
+		t.Fatalf("LibraryVersion() after measuring = %q, %v; want a change from %q", measured, err, unmeasured)
+	}
+
+	chill, total, err := library.GetUserLibrary(ctx, userID, LibraryQueryOptions{Mood: "chill"})
+	if err != nil || total != 1 || chill[0].ID != calm || chill[0].Mood == nil || chill[0].Mood.Energy != 0.2 {
+		t.Fatalf("chill tracks = %+v, %d, %v; want only the calm one", chill, total, err)
+	}
+	all, _, err := library.GetUserLibrary(ctx, userID, LibraryQueryOptions{})
+	if err != nil || len(all) != 3 {
+		t.Fatalf("library = %+v, %v", all, err)
+	}
+	for _, track := range all {
+		if (track.Mood == nil) != (track.ID == silent) {
+			t.Fatalf("track %d mood = %+v; only the silent track is unrated", track.ID, track.Mood)
+		}
+	}
+
+	// Replacing a track's file measures it again.
+	if _, err := database.Exec(`UPDATE tracks SET storage_key = 'tracks/new' WHERE id = $1`, loud); err != nil {
+		t.Fatalf("replace storage key: %v", err)
+	}
+	if candidates, _ := tracks.GetMoodFeatureCandidates(ctx, 10); len(candidates) != 1 || candidates[0].ID != loud {
+		t.Fatalf("GetMoodFeatureCandidates() after replacing = %+v, want the loud track", candidates)
+	}
+}
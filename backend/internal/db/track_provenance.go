@@ -0,0 +1,170 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"time"
+
+	"github.com/lib/pq"
+)
+
+var ErrUnknownMetadataField = errors.New("unknown metadata field")
+
+// Metadata field sources. A user source always outranks the others and locks
+// the field; the automatic sources are ranked by the repository's precedence.
+const (
+	FieldSourceUser        = "user"
+	FieldSourceMusicBrainz = "musicbrainz"
+	FieldSourceTag         = "tag"
+	FieldSourceProvider    = "provider"
+)
+
+// MetadataFields are the track fields whose provenance is tracked.
+var MetadataFields = []string{"title", "artist", "album", "duration_ms", "cover_art_url"}
+
+// DefaultFieldPrecedence ranks metadata sources, highest first: enrichment
+// from a source never replaces a value that came from a higher-ranked one.
+var DefaultFieldPrecedence = []string{FieldSourceUser, FieldSourceMusicBrainz, FieldSourceTag, FieldSourceProvider}
+
+// FieldProvenance records where a track field's current value came from and
+// whether it is locked against automatic enrichment.
+type FieldProvenance struct {
+	Field     string
+	Source    string
+	Locked    bool
+	UpdatedAt time.Time
+}
+
+// SetFieldPrecedence replaces the source ranking used by enrichment. The user
+// source is always kept first, unknown sources are dropped, and sources left
+// out are ranked last in their default order.
+func (r *TrackRepository) SetFieldPrecedence(precedence []string) {
+	ranked := []string{FieldSourceUser}
+	seen := map[string]bool{FieldSourceUser: true}
+	for _, source := range append(append([]string(nil), precedence...), DefaultFieldPrecedence...) {
+		switch source {
+		case FieldSourceMusicBrainz, FieldSourceTag, FieldSourceProvider:
+			if !seen[source] {
+				seen[source] = true
+				ranked = append(ranked, source)
+			}
+		}
+	}
+	r.precedence = ranked
+}
+
+func (r *TrackRepository) fieldPrecedence() []string {
+	if len(r.precedence) == 0 {
+		return DefaultFieldPrecedence
+	}
+	return r.precedence
+}
+
+func isMetadataField(field string) bool {
+	for _, known := range MetadataFields {
+		if field == known {
+			return true
+		}
+	}
+	return false
+}
+
+// fieldBlocked is a SQL predicate that is true when field of the track whose
+// id is trackExpr may not be written by source: the field is locked, or its
+// current value came from a source ranked above source in precedence. A user
+// value only protects its field while locked, so unlocking releases it.
+func fieldBlocked(trackExpr, field, source, precedence string) string {
+	return `EXISTS (
+		SELECT 1 FROM track_field_provenance fp
+		WHERE fp.track_id = ` + trackExpr + ` AND fp.field = ` + field + `
+		  AND (fp.locked OR (fp.source <> 'user' AND array_position(` + precedence + `, fp.source) < array_position(` + precedence + `, ` + source + `)))
+	)`
+}
+
+// RecordFieldSources notes the source of each field of a newly created track.
+// Existing provenance is left alone, so re-importing a track cannot displace a
+// higher-ranked source.
+func (r *TrackRepository) RecordFieldSources(ctx context.Context, trackID int64, sources map[string]string) error {
+	fields := make([]string, 0, len(sources))
+	values := make([]string, 0, len(sources))
+	for field, source := range sources {
+		if isMetadataField(field) && source != "" {
+			fields = append(fields, field)
+			values = append(values, source)
+		}
+	}
+	if len(fields) == 0 {
+		return nil
+	}
+	_, err := r.db.ExecContext(ctx, `
+		INSERT INTO track_field_provenance (track_id, field, source)
+		SELECT $1, f.field, f.source
+		FROM unnest($2::text[], $3::text[]) AS f(field, source)
+		ON CONFLICT (track_id, field) DO NOTHING
+	`, trackID, pq.Array(fields), pq.Array(values))
+	if err != nil {
+		return fmt.Errorf("record field sources: %w", err)
+	}
+	return nil
+}
+
+// FieldProvenance lists the recorded provenance of trackID's fields.
+func (r *TrackRepository) FieldProvenance(ctx context.Context, trackID int64) ([]FieldProvenance, error) {
+	if _, err := r.GetByID(ctx, trackID); err != nil {
+		return nil, err
+	}
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT field, source, locked, updated_at
+		FROM track_field_provenance
+		WHERE track_id = $1
+		ORDER BY array_position($2::text[], field::text)
+	`, trackID, pq.Array(MetadataFields))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	fields := []FieldProvenance{}
+	for rows.Next() {
+		var field FieldProvenance
+		if err := rows.Scan(&field.Field, &field.Source, &field.Locked, &field.UpdatedAt); err != nil {
+			return nil, err
+		}
+		fields = append(fields, field)
+	}
+	return fields, rows.Err()
+}
+
+// UnlockField releases a user lock so enrichment may update field again. The
+// track stays marked as user-edited while any other field is locked.
+func (r *TrackRepository) UnlockField(ctx context.Context, trackID int64, field string) error {
+	if !isMetadataField(field) {
+		return ErrUnknownMetadataField
+	}
+	result, err := r.db.ExecContext(ctx, `
+		WITH unlocked AS (
+			UPDATE track_field_provenance
+			SET locked = FALSE, updated_at = NOW()
+			WHERE track_id = $1 AND field = $2
+		)
+		UPDATE tracks
+		SET metadata_user_edited = EXISTS (
+				SELECT 1 FROM track_field_provenance
+				WHERE track_id = $1 AND field <> $2 AND locked
+			),
+			updated_at = NOW()
+		WHERE id = $1
+	`, trackID, field)
+	if err != nil {
+		return fmt.Errorf("unlock field: %w", err)
+	}
+	rows, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if rows == 0 {
+		return ErrTrackNotFound
+	}
+	return nil
+}
@@ -0,0 +1,109 @@
+package db
+
+import (
+	"encoding/json"
+	"errors"
+	"testing"
+)
+
+func TestFieldProvenanceLocksAndPrecedenceAgainstPostgres(t *testing.T) {
+	repo, ctx := newPostgresTestRepository(t)
+
+	track, _, err := repo.CreateTrackFromMetadata(ctx, "Tag Artist", "Tag Title", "Provider Album", 200000,
+		WithMetadata(json.RawMessage(`{}`)),
+		WithMetadataEnrichment("provider", nil, json.RawMessage(`{}`), ""))
+	if err != nil {
+		t.Fatalf("create track: %v", err)
+	}
+	if err := repo.RecordFieldSources(ctx, track.ID, map[string]string{
+		"title":  FieldSourceTag,
+		"artist": FieldSourceTag,
+		"album":  FieldSourceProvider,
+		"genre":  FieldSourceTag,
+	}); err != nil {
+		t.Fatalf("RecordFieldSources: %v", err)
+	}
+	if err := repo.UpdateMetadata(ctx, track.ID, &MetadataUpdate{Title: "User Title"}, nil); err != nil {
+		t.Fatalf("UpdateMetadata: %v", err)
+	}
+
+	enrich := func() {
+		t.Helper()
+		if err := repo.UpdateMBMatch(ctx, track.ID, &MBMatchUpdate{
+			RespectUserEdits: true,
+			MetadataStatus:   "enriched",
+			Title:            "MB Title",
+			Artist:           "MB Artist",
+			Album:            "MB Album",
+		}); err != nil {
+			t.Fatalf("UpdateMBMatch: %v", err)
+		}
+	}
+	enrich()
+	got, err := repo.GetByID(ctx, track.ID)
+	if err != nil {
+		t.Fatalf("reload track: %v", err)
+	}
+	if got.Title != "User Title" || got.Artist.String != "MB Artist" || got.Album.String != "MB Album" {
+		t.Fatalf("after enrichment = %q / %q / %q, want locked title and enriched artist/album", got.Title, got.Artist.String, got.Album.String)
+	}
+
+	fields, err := repo.FieldProvenance(ctx, track.ID)
+	if err != nil {
+		t.Fatalf("FieldProvenance: %v", err)
+	}
+	want := []FieldProvenance{
+		{Field: "title", Source: FieldSourceUser, Locked: true},
+		{Field: "artist", Source: FieldSourceMusicBrainz},
+		{Field: "album", Source: FieldSourceMusicBrainz},
+	}
+	if len(fields) != len(want) {
+		t.Fatalf("provenance = %#v, want %d fields", fields, len(want))
+	}
+	for i, field := range fields {
+		if field.Field != want[i].Field || field.Source != want[i].Source || field.Locked != want[i].Locked {
+			t.Fatalf("provenance[%d] = %#v, want %#v", i, field, want[i])
+		}
+	}
+
+	// With tags ranked above MusicBrainz, a tag-sourced field is kept.
+	other, _, err := repo.CreateTrackFromMetadata(ctx, "Other Artist", "Other Title", "", 200000,
+		WithMetadata(json.RawMessage(`{}`)),
+		WithMetadataEnrichment("provider", nil, json.RawMessage(`{}`), ""))
+	if err != nil {
+		t.Fatalf("create second track: %v", err)
+	}
+	if err := repo.RecordFieldSources(ctx, other.ID, map[string]string{"title": FieldSourceTag}); err != nil {
+		t.Fatalf("RecordFieldSources: %v", err)
+	}
+	repo.SetFieldPrecedence([]string{FieldSourceTag, FieldSourceMusicBrainz, FieldSourceProvider})
+	if err := repo.UpdateMBMatch(ctx, other.ID, &MBMatchUpdate{RespectUserEdits: true, Title: "MB Title"}); err != nil {
+		t.Fatalf("UpdateMBMatch: %v", err)
+	}
+	if got, err := repo.GetByID(ctx, other.ID); err != nil || got.Title != "Other Title" {
+		t.Fatalf("tag-ranked title = %#v, %v; want Other Title kept", got, err)
+	}
+	repo.SetFieldPrecedence(nil)
+
+	if err := repo.UnlockField(ctx, track.ID, "title"); err != nil {
+		t.Fatalf("UnlockField: %v", err)
+	}
+	got, err = repo.GetByID(ctx, track.ID)
+	if err != nil {
+		t.Fatalf("reload track: %v", err)
+	}
+	if got.MetadataUserEdited {
+		t.Fatal("track still marked user-edited after its only lock was released")
+	}
+	enrich()
+	if got, err = repo.GetByID(ctx, track.ID); err != nil || got.Title != "MB Title" {
+		t.Fatalf("title after unlock = %#v, %v; want MB Title", got, err)
+	}
+
+	if err := repo.UnlockField(ctx, track.ID, "genre"); !errors.Is(err, ErrUnknownMetadataField) {
+		t.Fatalf("UnlockField(genre) error = %v, want ErrUnknownMetadataField", err)
+	}
+	if err := repo.UnlockField(ctx, track.ID+1000, "title"); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("UnlockField(missing track) error = %v, want ErrTrackNotFound", err)
+	}
+}
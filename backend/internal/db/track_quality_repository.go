@@ -0,0 +1,67 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+)
+
+var ErrTrackQualityNotFound = errors.New("track quality not measured")
+
+// TrackQuality is what measuring a track's stored file found. DRScore is
+// unset for audio too short or quiet to rate, and SpectralCutoffHz when the
+// spectrum reaches as high as was measured. Score rates the file from 0 to
+// 100.
+type TrackQuality struct {
+	TrackID          int64
+	StorageKey       string
+	DRScore          sql.NullInt32
+	SpectralCutoffHz sql.NullInt32
+	UpscaleSuspected bool
+	Score            int
+	AnalyzedAt       time.Time
+}
+
+type TrackQualityRepository struct {
+	db *DB
+}
+
+func NewTrackQualityRepository(db *DB) *TrackQualityRepository {
+	return &TrackQualityRepository{db: db}
+}
+
+// Save stores a measurement, replacing the track's earlier one, and sets
+// AnalyzedAt.
+func (r *TrackQualityRepository) Save(ctx context.Context, quality *TrackQuality) error {
+	return r.db.QueryRowContext(ctx, `
+		INSERT INTO track_quality (track_id, storage_key, dr_score, spectral_cutoff_hz, upscale_suspected, score)
+		VALUES ($1, $2, $3, $4, $5, $6)
+		ON CONFLICT (track_id) DO UPDATE SET
+			storage_key = EXCLUDED.storage_key,
+			dr_score = EXCLUDED.dr_score,
+			spectral_cutoff_hz = EXCLUDED.spectral_cutoff_hz,
+			upscale_suspected = EXCLUDED.upscale_suspected,
+			score = EXCLUDED.score,
+			analyzed_at = NOW()
+		RETURNING analyzed_at
+	`, quality.TrackID, quality.StorageKey, quality.DRScore, quality.SpectralCutoffHz, quality.UpscaleSuspected, quality.Score,
+	).Scan(&quality.AnalyzedAt)
+}
+
+// Get returns the track's latest measurement.
+func (r *TrackQualityRepository) Get(ctx context.Context, trackID int64) (*TrackQuality, error) {
+	q := TrackQuality{TrackID: trackID}
+	err := r.db.QueryRowContext(ctx, `
+		SELECT storage_key, dr_score, spectral_cutoff_hz, upscale_suspected, score, analyzed_at
+		FROM track_quality
+		WHERE track_id = $1
+	`, trackID).Scan(&q.StorageKey, &q.DRScore, &q.SpectralCutoffHz, &q.UpscaleSuspected, &q.Score, &q.AnalyzedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrTrackQualityNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &q, nil
+}
@@ -0,0 +1,76 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"testing"
+)
+
+func TestTrackQualityScoresAndLibraryFilters(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	repo := NewTrackQualityRepository(database)
+	userID := seedPlayUser(t, database, "quality@example.test")
+
+	genuine := seedPlayTrack(t, tracks, ctx, "Artist", "Genuine")
+	upscaled := seedPlayTrack(t, tracks, ctx, "Artist", "Upscaled")
+	unmeasured := seedPlayTrack(t, tracks, ctx, "Artist", "Unmeasured")
+	for _, id := range []int64{genuine, upscaled, unmeasured} {
+		if _, err := database.Exec(`UPDATE tracks SET storage_key = 'audio/' || id WHERE id = $1`, id); err != nil {
+			t.Fatalf("store track %d: %v", id, err)
+		}
+		if _, err := library.AddTrackToLibrary(ctx, userID, id); err != nil {
+			t.Fatalf("add track %d: %v", id, err)
+		}
+	}
+
+	if _, err := repo.Get(ctx, genuine); !errors.Is(err, ErrTrackQualityNotFound) {
+		t.Fatalf("Get() before measuring error = %v, want ErrTrackQualityNotFound", err)
+	}
+	unscored, err := library.LibraryVersion(ctx, userID)
+	if err != nil {
+		t.Fatalf("LibraryVersion() before measuring error = %v", err)
+	}
+	for _, q := range []*TrackQuality{
+		{TrackID: genuine, StorageKey: "audio/" + itoa(int(genuine)), DRScore: sql.NullInt32{Int32: 12, Valid: true}, Score: 100},
+		{TrackID: upscaled, StorageKey: "audio/old", DRScore: sql.NullInt32{Int32: 6, Valid: true}, SpectralCutoffHz: sql.NullInt32{Int32: 16000, Valid: true}, UpscaleSuspected: true, Score: 26},
+	} {
+		if err := repo.Save(ctx, q); err != nil || q.AnalyzedAt.IsZero() {
+			t.Fatalf("Save(%d) = %v, analyzed at %v", q.TrackID, err, q.AnalyzedAt)
+		}
+	}
+	if scored, err := library.LibraryVersion(ctx, userID); err != nil || scored == unscored {
+		t.Fatalf("LibraryVersion() after measuring = %q, %v; want a change from %q", scored, err, unscored)
+	}
+	got, err := repo.Get(ctx, upscaled)
+	if err != nil || !got.UpscaleSuspected || got.SpectralCutoffHz.Int32 != 16000 || got.Score != 26 {
+		t.Fatalf("Get() = %+v, %v", got, err)
+	}
+
+	// The upscaled track's file changed since it was measured.
+	candidates, err := tracks.GetQualityScoreCandidates(ctx, 10)
+	if err != nil {
+		t.Fatalf("GetQualityScoreCandidates() error = %v", err)
+	}
+	if len(candidates) != 2 || candidates[0].ID != upscaled || candidates[1].ID != unmeasured {
+		t.Fatalf("candidates = %+v, want the upscaled and unmeasured tracks", candidates)
+	}
+
+	suspected := true
+	listed, _, err := library.GetUserLibrary(ctx, userID, LibraryQueryOptions{UpscaleSuspected: &suspected})
+	if err != nil || len(listed) != 1 || listed[0].ID != upscaled || listed[0].QualityScore.Int32 != 26 {
+		t.Fatalf("upscale_suspected library = %+v, %v", listed, err)
+	}
+	drMax := 8
+	listed, _, err = library.GetUserLibrary(ctx, userID, LibraryQueryOptions{DRMax: &drMax})
+	if err != nil || len(listed) != 1 || listed[0].ID != upscaled {
+		t.Fatalf("dr_max library = %+v, %v", listed, err)
+	}
+	listed, _, err = library.GetUserLibrary(ctx, userID, LibraryQueryOptions{SortBy: "quality"})
+	if err != nil || len(listed) != 3 || listed[0].ID != upscaled || listed[2].ID != unmeasured || listed[2].QualityScore.Valid {
+		t.Fatalf("library by quality = %+v, %v; want unmeasured tracks last", listed, err)
+	}
+}
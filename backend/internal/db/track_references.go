@@ -0,0 +1,73 @@
+package db
+
+import (
+	"context"
+	"errors"
+
+	"github.com/lib/pq"
+)
+
+// TrackReferences counts what holds a catalog track. Tracks are shared:
+// identical recordings resolve to one row and one stored object, which no
+// user owns. Users hold references instead, and a track with none left is
+// unowned and may be removed with its object.
+type TrackReferences struct {
+	Libraries   int
+	Playlists   int
+	PendingJobs int
+}
+
+// Total is the number of references; zero means the track is unowned.
+func (r TrackReferences) Total() int {
+	return r.Libraries + r.Playlists + r.PendingJobs
+}
+
+// trackReferencesQuery counts references to the track bound to $1. Pending
+// jobs are downloads still working on the track and queued or running
+// background jobs that list it, such as exports and transcodes.
+const trackReferencesQuery = `
+	SELECT
+		(SELECT COUNT(*) FROM user_library WHERE track_id = $1),
+		(SELECT COUNT(*) FROM playlist_tracks WHERE track_id = $1),
+		(SELECT COUNT(*) FROM download_jobs
+			WHERE track_id = $1 AND status IN ('queued', 'downloading', 'processing', 'uploading'))
+		+ (SELECT COUNT(*) FROM jobs
+			WHERE status IN ('queued', 'running') AND payload->'track_ids' @> jsonb_build_array($1::bigint))
+`
+
+// References counts the references held on a track.
+func (r *TrackRepository) References(ctx context.Context, trackID int64) (TrackReferences, error) {
+	return scanTrackReferences(ctx, r.db, trackID)
+}
+
+func scanTrackReferences(ctx context.Context, q queryRower, trackID int64) (TrackReferences, error) {
+	var refs TrackReferences
+	err := q.QueryRowContext(ctx, trackReferencesQuery, trackID).Scan(&refs.Libraries, &refs.Playlists, &refs.PendingJobs)
+	return refs, err
+}
+
+// AttachStorage stores an uploaded object as the track's audio when the track
+// has none yet. It reports false when the track already has an object; that
+// object stays, since a shared track is stored once.
+func (r *TrackRepository) AttachStorage(ctx context.Context, trackID int64, storageKey string, fileSizeBytes int64) (bool, error) {
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE tracks
+		SET storage_key = $2, file_size_bytes = NULLIF($3, 0), updated_at = NOW()
+		WHERE id = $1 AND storage_key IS NULL
+	`, trackID, storageKey, fileSizeBytes)
+	if err != nil {
+		return false, err
+	}
+	n, err := result.RowsAffected()
+	if err != nil || n == 0 {
+		return false, err
+	}
+	return true, refreshTrackAggregates(ctx, r.db, trackID, false)
+}
+
+// isForeignKeyViolation reports a write that referenced a row that is gone,
+// such as a track released while the write waited on it.
+func isForeignKeyViolation(err error) bool {
+	var pqErr *pq.Error
+	return errors.As(err, &pqErr) && pqErr.Code == "23503"
+}
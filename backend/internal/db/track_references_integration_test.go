@@ -0,0 +1,215 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"sync"
+	"sync/atomic"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestTrackReferencesAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	libraryRepo := NewLibraryRepository(database)
+	playlistRepo := NewPlaylistRepository(database)
+	alice := seedPlayUser(t, database, "alice@example.com")
+	bob := seedPlayUser(t, database, "bob@example.com")
+	trackID := seedPlayTrack(t, trackRepo, ctx, "Low", "Words")
+
+	refs, err := trackRepo.References(ctx, trackID)
+	if err != nil || refs.Total() != 0 {
+		t.Fatalf("fresh track references = %+v, %v; want unowned", refs, err)
+	}
+
+	for _, user := range []any{alice, bob} {
+		if _, err := database.Exec(`INSERT INTO user_library (user_id, track_id) VALUES ($1, $2)`, user, trackID); err != nil {
+			t.Fatalf("add to library: %v", err)
+		}
+	}
+	playlist := &Playlist{UserID: alice, Name: "Slowcore"}
+	if err := playlistRepo.Create(ctx, playlist); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	if _, err := database.Exec(`INSERT INTO playlist_tracks (playlist_id, track_id, position) VALUES ($1, $2, 0)`, playlist.ID, trackID); err != nil {
+		t.Fatalf("add to playlist: %v", err)
+	}
+	if _, err := database.Exec(`INSERT INTO jobs (id, kind, user_id, payload) VALUES (gen_random_uuid(), 'library_export', $1, jsonb_build_object('track_ids', jsonb_build_array($2::bigint)))`, alice, trackID); err != nil {
+		t.Fatalf("queue export job: %v", err)
+	}
+
+	refs, err = trackRepo.References(ctx, trackID)
+	if err != nil {
+		t.Fatalf("references: %v", err)
+	}
+	if refs != (TrackReferences{Libraries: 2, Playlists: 1, PendingJobs: 1}) {
+		t.Fatalf("references = %+v", refs)
+	}
+
+	if err := libraryRepo.RemoveTrackFromLibrary(ctx, bob, trackID); err != nil {
+		t.Fatalf("remove from library: %v", err)
+	}
+	if refs, _ := trackRepo.References(ctx, trackID); refs.Libraries != 1 {
+		t.Fatalf("libraries after removal = %d, want 1", refs.Libraries)
+	}
+
+	attached, err := trackRepo.AttachStorage(ctx, trackID, "tracks/fixture/words.mp3", 2048)
+	if err != nil || !attached {
+		t.Fatalf("attach storage = %v, %v", attached, err)
+	}
+	attached, err = trackRepo.AttachStorage(ctx, trackID, "tracks/fixture/duplicate.mp3", 4096)
+	if err != nil || attached {
+		t.Fatalf("second attach = %v, %v; want the first object kept", attached, err)
+	}
+	track, err := trackRepo.GetByID(ctx, trackID)
+	if err != nil || track.StorageKey.String != "tracks/fixture/words.mp3" || track.FileSizeBytes.Int64 != 2048 {
+		t.Fatalf("track storage = %+v, %v", track, err)
+	}
+}
+
+func TestReleaseTrackFromLibraryAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	libraryRepo := NewLibraryRepository(database)
+	playlistRepo := NewPlaylistRepository(database)
+	alice := seedPlayUser(t, database, "alice@example.com")
+	bob := seedPlayUser(t, database, "bob@example.com")
+
+	track, _, err := trackRepo.CreateTrackFromMetadata(ctx, "Low", "Words", "", 0, WithStorage("tracks/fixture/words.mp3", 2048))
+	if err != nil {
+		t.Fatalf("seed track: %v", err)
+	}
+	for _, user := range []uuid.UUID{alice, bob} {
+		if _, err := libraryRepo.AddTrackToLibrary(ctx, user, track.ID); err != nil {
+			t.Fatalf("add to library: %v", err)
+		}
+	}
+	playlist := &Playlist{UserID: bob, Name: "Keep"}
+	if err := playlistRepo.Create(ctx, playlist); err != nil {
+		t.Fatalf("create playlist: %v", err)
+	}
+	if err := playlistRepo.AddTrack(ctx, playlist.ID, track.ID); err != nil {
+		t.Fatalf("add to playlist: %v", err)
+	}
+
+	if released, err := libraryRepo.ReleaseTrackFromLibrary(ctx, alice, track.ID); err != nil || released != nil {
+		t.Fatalf("release while bob holds it = %+v, %v", released, err)
+	}
+	if _, err := libraryRepo.ReleaseTrackFromLibrary(ctx, alice, track.ID); !errors.Is(err, ErrTrackNotInLibrary) {
+		t.Fatalf("second release = %v, want ErrTrackNotInLibrary", err)
+	}
+	if released, err := libraryRepo.ReleaseTrackFromLibrary(ctx, bob, track.ID); err != nil || released != nil {
+		t.Fatalf("release while bob's playlist holds it = %+v, %v", released, err)
+	}
+	if err := playlistRepo.RemoveTrack(ctx, playlist.ID, track.ID); err != nil {
+		t.Fatalf("remove from playlist: %v", err)
+	}
+	if _, err := libraryRepo.AddTrackToLibrary(ctx, bob, track.ID); err != nil {
+		t.Fatalf("re-add: %v", err)
+	}
+	released, err := libraryRepo.ReleaseTrackFromLibrary(ctx, bob, track.ID)
+	if err != nil || released == nil || released.StorageKey != "tracks/fixture/words.mp3" {
+		t.Fatalf("last release = %+v, %v; want the track and its object", released, err)
+	}
+	if _, err := trackRepo.GetByID(ctx, track.ID); !errors.Is(err, ErrTrackNotFound) {
+		t.Fatalf("released track lookup = %v, want ErrTrackNotFound", err)
+	}
+}
+
+func TestConcurrentReleasesDeleteTrackOnce(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	libraryRepo := NewLibraryRepository(database)
+	users := make([]uuid.UUID, 8)
+	for i := range users {
+		users[i] = seedPlayUser(t, database, fmt.Sprintf("user%d@example.com", i))
+	}
+
+	for round := 0; round < 10; round++ {
+		trackID := seedPlayTrack(t, trackRepo, ctx, "Low", fmt.Sprintf("Round %d", round))
+		for _, user := range users {
+			if _, err := libraryRepo.AddTrackToLibrary(ctx, user, trackID); err != nil {
+				t.Fatalf("add to library: %v", err)
+			}
+		}
+		var released atomic.Int32
+		var wg sync.WaitGroup
+		for _, user := range users {
+			wg.Add(1)
+			go func() {
+				defer wg.Done()
+				track, err := libraryRepo.ReleaseTrackFromLibrary(ctx, user, trackID)
+				if err != nil {
+					t.Errorf("release: %v", err)
+				}
+				if track != nil {
+					released.Add(1)
+				}
+			}()
+		}
+		wg.Wait()
+		if released.Load() != 1 {
+			t.Fatalf("round %d: %d releases deleted the track, want exactly 1", round, released.Load())
+		}
+		if _, err := trackRepo.GetByID(ctx, trackID); !errors.Is(err, ErrTrackNotFound) {
+			t.Fatalf("round %d: track survived its last release: %v", round, err)
+		}
+	}
+}
+
+func TestReleaseRacingAnAddKeepsReferencedTracks(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	trackRepo := NewTrackRepository(database)
+	libraryRepo := NewLibraryRepository(database)
+	owner := seedPlayUser(t, database, "owner@example.com")
+	adder := seedPlayUser(t, database, "adder@example.com")
+
+	for round := 0; round < 20; round++ {
+		trackID := seedPlayTrack(t, trackRepo, ctx, "Low", fmt.Sprintf("Race %d", round))
+		if _, err := libraryRepo.AddTrackToLibrary(ctx, owner, trackID); err != nil {
+			t.Fatalf("add to owner's library: %v", err)
+		}
+		var released *ReleasedTrack
+		var releaseErr, addErr error
+		var wg sync.WaitGroup
+		wg.Add(2)
+		go func() {
+			defer wg.Done()
+			released, releaseErr = libraryRepo.ReleaseTrackFromLibrary(ctx, owner, trackID)
+		}()
+		go func() {
+			defer wg.Done()
+			_, addErr = libraryRepo.AddTrackToLibrary(ctx, adder, trackID)
+		}()
+		wg.Wait()
+		if releaseErr != nil {
+			t.Fatalf("round %d: release: %v", round, releaseErr)
+		}
+
+		_, getErr := trackRepo.GetByID(ctx, trackID)
+		switch {
+		case addErr == nil:
+			// The add won: the track must survive with the adder's reference.
+			if released != nil || getErr != nil {
+				t.Fatalf("round %d: track added by another user was released (%+v, %v)", round, released, getErr)
+			}
+			if in, err := libraryRepo.IsTrackInLibrary(ctx, adder, trackID); err != nil || !in {
+				t.Fatalf("round %d: adder's library entry = %v, %v", round, in, err)
+			}
+		case errors.Is(addErr, ErrTrackNotFound):
+			// The release won: the track is gone and nothing points at it.
+			if released == nil || !errors.Is(getErr, ErrTrackNotFound) {
+				t.Fatalf("round %d: add refused but track kept (%+v, %v)", round, released, getErr)
+			}
+		default:
+			t.Fatalf("round %d: add: %v", round, addErr)
+		}
+	}
+}
@@ -0,0 +1,122 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+)
+
+// TrackRelation is a MusicBrainz relationship of a track's recording, stored
+// with the musicbrainz.Relation kinds: remix_of, remixed_by, samples,
+// sampled_by, cover_of, or performance_of.
+type TrackRelation struct {
+	Relation    string
+	TargetType  string
+	TargetMBID  uuid.UUID
+	TargetTitle string
+}
+
+// RelatedLibraryTrack is a library track related to a recording. Relation
+// reads from the library track's side, so "remix_of" means it is a remix of
+// the recording that was looked up.
+type RelatedLibraryTrack struct {
+	TrackID       int64
+	Title         string
+	Artist        sql.NullString
+	Album         sql.NullString
+	MBRecordingID *uuid.UUID
+	Relation      string
+}
+
+// ReplaceRelations swaps a track's stored relationships for rels.
+func (r *TrackRepository) ReplaceRelations(ctx context.Context, trackID int64, rels []TrackRelation) error {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM track_relations WHERE track_id = $1`, trackID); err != nil {
+		return fmt.Errorf("clear track relations: %w", err)
+	}
+	for _, rel := range rels {
+		if _, err := tx.ExecContext(ctx, `
+			INSERT INTO track_relations (track_id, relation, target_type, target_mbid, target_title)
+			VALUES ($1, $2, $3, $4, NULLIF($5, ''))
+			ON CONFLICT (track_id, relation, target_mbid) DO NOTHING
+		`, trackID, rel.Relation, rel.TargetType, rel.TargetMBID, rel.TargetTitle); err != nil {
+			return fmt.Errorf("insert track relation: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// RelatedLibraryTracks finds tracks in the user's library that remix, sample,
+// or cover the recording, or that it remixes, samples, or covers. rels are the
+// recording's own relationships as just fetched from MusicBrainz; they match
+// library tracks whose relationships were never stored, and the stored
+// relationships of library tracks match the recording in turn.
+func (r *LibraryRepository) RelatedLibraryTracks(ctx context.Context, userID, recordingID uuid.UUID, rels []TrackRelation) ([]RelatedLibraryTrack, error) {
+	kinds := make([]string, len(rels))
+	targets := make([]string, len(rels))
+	for i, rel := range rels {
+		kinds[i] = rel.Relation
+		targets[i] = rel.TargetMBID.String()
+	}
+
+	rows, err := r.db.ReadQueryContext(ctx, `
+		WITH viewed AS (
+			SELECT relation, target_mbid::uuid AS target_mbid
+			FROM unnest($3::text[], $4::text[]) AS v(relation, target_mbid)
+		)
+		SELECT t.id, t.title, t.artist, t.album, t.mb_recording_id, rel.relation
+		FROM user_library ul
+		JOIN tracks t ON t.id = ul.track_id
+		JOIN LATERAL (
+			-- The library track's stored relationship names the recording.
+			SELECT tr.relation
+			FROM track_relations tr
+			WHERE tr.track_id = t.id AND tr.target_mbid = $2
+			  AND tr.relation IN ('remix_of', 'remixed_by', 'samples', 'sampled_by')
+			UNION
+			-- The library track covers a work the recording performs, or
+			-- performs a work the recording covers.
+			SELECT tr.relation
+			FROM track_relations tr
+			JOIN viewed v ON v.target_mbid = tr.target_mbid
+			WHERE tr.track_id = t.id
+			  AND ((tr.relation = 'cover_of' AND v.relation IN ('cover_of', 'performance_of'))
+			       OR (tr.relation = 'performance_of' AND v.relation = 'cover_of'))
+			UNION
+			-- The recording's relationship names the library track.
+			SELECT CASE v.relation
+				WHEN 'remix_of' THEN 'remixed_by'
+				WHEN 'remixed_by' THEN 'remix_of'
+				WHEN 'samples' THEN 'sampled_by'
+				ELSE 'samples'
+			END
+			FROM viewed v
+			WHERE v.target_mbid = t.mb_recording_id
+			  AND v.relation IN ('remix_of', 'remixed_by', 'samples', 'sampled_by')
+		) rel ON TRUE
+		WHERE ul.user_id = $1 AND t.mb_recording_id IS DISTINCT FROM $2
+		ORDER BY rel.relation, t.title, t.id
+	`, userID, recordingID, pq.Array(kinds), pq.Array(targets))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	related := []RelatedLibraryTrack{}
+	for rows.Next() {
+		var track RelatedLibraryTrack
+		if err := rows.Scan(&track.TrackID, &track.Title, &track.Artist, &track.Album, &track.MBRecordingID, &track.Relation); err != nil {
+			return nil, err
+		}
+		related = append(related, track)
+	}
+	return related, rows.Err()
+}
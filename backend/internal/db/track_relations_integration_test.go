@@ -0,0 +1,47 @@
+package db
+
+import (
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestRelatedLibraryTracksAgainstPostgres(t *testing.T) {
+	database, ctx := newLibraryQueryTestDB(t)
+	trackRepo := NewTrackRepository(database)
+	libRepo := NewLibraryRepository(database)
+	user := seedQueryUser(t, database, "owner@test.local")
+
+	original := uuid.MustParse("11111111-1111-1111-1111-111111111111")
+	work := uuid.MustParse("22222222-2222-2222-2222-222222222222")
+	remixRecording := uuid.MustParse("33333333-3333-3333-3333-333333333333")
+
+	cover := seedQueryTrack(t, trackRepo, ctx, "Cover Band", "Song", "Covers", 200000)
+	remix := seedQueryTrack(t, trackRepo, ctx, "DJ", "Song (Remix)", "Remixes", 300000)
+	unrelated := seedQueryTrack(t, trackRepo, ctx, "Other", "Other Song", "Other", 100000)
+	for _, id := range []int64{cover, remix, unrelated} {
+		if _, err := libRepo.AddTrackToLibrary(ctx, user, id); err != nil {
+			t.Fatalf("add %d to library: %v", id, err)
+		}
+	}
+	if err := trackRepo.ReplaceRelations(ctx, cover, []TrackRelation{{Relation: "cover_of", TargetType: "work", TargetMBID: work}}); err != nil {
+		t.Fatalf("store cover relation: %v", err)
+	}
+	// The remix has no stored relations; only the original's lookup names it.
+	if _, err := database.Exec(`UPDATE tracks SET mb_recording_id = $1 WHERE id = $2`, remixRecording, remix); err != nil {
+		t.Fatalf("link remix recording: %v", err)
+	}
+
+	related, err := libRepo.RelatedLibraryTracks(ctx, user, original, []TrackRelation{
+		{Relation: "performance_of", TargetType: "work", TargetMBID: work},
+		{Relation: "remixed_by", TargetType: "recording", TargetMBID: remixRecording},
+	})
+	if err != nil {
+		t.Fatalf("RelatedLibraryTracks: %v", err)
+	}
+	if len(related) != 2 ||
+		related[0].TrackID != cover || related[0].Relation != "cover_of" ||
+		related[1].TrackID != remix || related[1].Relation != "remix_of" {
+		t.Fatalf("related = %+v; want cover_of %d then remix_of %d", related, cover, remix)
+	}
+}
@@ -9,10 +9,12 @@ import (
 	"time"
 
 	"github.com/google/uuid"
+	"github.com/lib/pq"
 )
 
 var ErrTrackNotFound = errors.New("track not found")
 var ErrDuplicateTrack = errors.New("track with this identity hash already exists")
+var ErrTrackVersionMismatch = errors.New("track version mismatch")
 
 // trigramSearchThreshold is the minimum pg_trgm similarity() score a row must reach
 // to be considered a fuzzy match. It is deliberately loose enough that a single-character
@@ -53,6 +55,13 @@ type Track struct {
 	AnalysisUpdatedAt  sql.NullTime
 	CreatedAt          time.Time
 	UpdatedAt          time.Time
+	// SearchAliases are extra names search should match, such as a romanized
+	// sort title. Only Create writes them.
+	SearchAliases []string
+	// tenantID is the tenant a new track belongs to, set by WithTenant.
+	tenantID sql.NullInt64
+	// audioHash is a new track's audio signature, set by WithAudioHash.
+	audioHash sql.NullString
 }
 
 type Artist struct {
@@ -71,15 +80,18 @@ type Release struct {
 }
 
 type TrackRepository struct {
-	db *DB
+	db         *DB
+	precedence []string
 }
 
 func NewTrackRepository(db *DB) *TrackRepository {
 	return &TrackRepository{db: db}
 }
 
-// SearchRecordings searches tracks by title with optional artist filter using full-text search
-func (r *TrackRepository) SearchRecordings(ctx context.Context, query string, limit, offset int) ([]Track, int, error) {
+// SearchRecordings searches tracks by title with optional artist filter using full-text search.
+// Only tracks the user may see are searched (see GetByIDForUser); the same
+// holds for SearchArtists and SearchReleases.
+func (r *TrackRepository) SearchRecordings(ctx context.Context, userID uuid.UUID, query string, limit, offset int) ([]Track, int, error) {
 	if limit <= 0 {
 		limit = 20
 	}
@@ -87,8 +99,8 @@ func (r *TrackRepository) SearchRecordings(ctx context.Context, query string, li
 		limit = 100
 	}
 
-	// Sanitize free-form input into a safe prefix-matching tsquery (see buildPrefixTSQuery).
-	tsQuery := buildPrefixTSQuery(query)
+	// Normalize and sanitize free-form input into a safe prefix-matching tsquery (see searchTSQuery).
+	tsQuery := searchTSQuery(query)
 	if tsQuery == "" {
 		return []Track{}, 0, nil
 	}
@@ -102,10 +114,11 @@ func (r *TrackRepository) SearchRecordings(ctx context.Context, query string, li
 				   codec, bitrate_kbps, sample_rate_hz, channels, content_type,
 				   metadata_json, metadata_status, metadata_confidence, metadata_provenance,
 				   cover_art_url, metadata_user_edited, created_at, updated_at,
-				   ts_rank(to_tsvector('english', COALESCE(title, '') || ' ' || COALESCE(artist, '') || ' ' || COALESCE(album, '')), to_tsquery('english', $1)) as rank,
+				   ts_rank(` + trackSearchDocumentSQL("") + `, to_tsquery('english', $1)) as rank,
 				   COUNT(*) OVER() as total_count
 			FROM tracks
-			WHERE to_tsvector('english', COALESCE(title, '') || ' ' || COALESCE(artist, '') || ' ' || COALESCE(album, '')) @@ to_tsquery('english', $1)
+			WHERE ` + trackSearchDocumentSQL("") + ` @@ to_tsquery('english', $1)
+				AND ` + tenantScopeSQL("tenant_id", "$4") + `
 		)
 		SELECT sr.id, sr.identity_hash, sr.title, sr.artist, sr.album, sr.duration_ms, sr.version,
 			   sr.mb_recording_id, sr.mb_release_id, sr.mb_artist_id, sr.mb_verified,
@@ -119,11 +132,11 @@ func (r *TrackRepository) SearchRecordings(ctx context.Context, query string, li
 			   sr.total_count
 		FROM search_results sr
 		LEFT JOIN track_analysis ta ON ta.track_id = sr.id
-		ORDER BY sr.rank DESC, sr.title ASC
+		ORDER BY sr.rank DESC, ` + r.db.collateSQL("sr.title") + ` ASC
 		LIMIT $2 OFFSET $3
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, tsQuery, limit, offset)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, tsQuery, limit, offset, userID)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -158,7 +171,7 @@ func (r *TrackRepository) SearchRecordings(ctx context.Context, query string, li
 	// retry with a trigram similarity() match so a typo still surfaces the track. When
 	// the extension is absent we return the (empty) FTS result unchanged.
 	if total == 0 && r.db.TrigramEnabled {
-		return r.searchRecordingsTrigram(ctx, query, limit, offset)
+		return r.searchRecordingsTrigram(ctx, userID, query, limit, offset)
 	}
 
 	return tracks, total, nil
@@ -168,7 +181,7 @@ func (r *TrackRepository) SearchRecordings(ctx context.Context, query string, li
 // tracks by the best similarity() across title/artist/album against the raw query and
 // keeps only rows at or above trigramSearchThreshold. Callers must gate this on
 // r.db.TrigramEnabled; it assumes the extension is installed.
-func (r *TrackRepository) searchRecordingsTrigram(ctx context.Context, query string, limit, offset int) ([]Track, int, error) {
+func (r *TrackRepository) searchRecordingsTrigram(ctx context.Context, userID uuid.UUID, query string, limit, offset int) ([]Track, int, error) {
 	q := strings.TrimSpace(query)
 	if q == "" {
 		return []Track{}, 0, nil
@@ -194,6 +207,7 @@ func (r *TrackRepository) searchRecordingsTrigram(ctx context.Context, query str
 					  similarity(COALESCE(artist, ''), $1),
 					  similarity(COALESCE(album, ''), $1)
 				  ) >= $4
+				AND ` + tenantScopeSQL("tenant_id", "$5") + `
 		)
 		SELECT sr.id, sr.identity_hash, sr.title, sr.artist, sr.album, sr.duration_ms, sr.version,
 			   sr.mb_recording_id, sr.mb_release_id, sr.mb_artist_id, sr.mb_verified,
@@ -207,11 +221,11 @@ func (r *TrackRepository) searchRecordingsTrigram(ctx context.Context, query str
 			   sr.total_count
 		FROM search_results sr
 		LEFT JOIN track_analysis ta ON ta.track_id = sr.id
-		ORDER BY sr.rank DESC, sr.title ASC
+		ORDER BY sr.rank DESC, ` + r.db.collateSQL("sr.title") + ` ASC
 		LIMIT $2 OFFSET $3
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, q, limit, offset, trigramSearchThreshold)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, q, limit, offset, trigramSearchThreshold, userID)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -246,7 +260,7 @@ func (r *TrackRepository) searchRecordingsTrigram(ctx context.Context, query str
 }
 
 // SearchArtists searches distinct artists by name using full-text search
-func (r *TrackRepository) SearchArtists(ctx context.Context, query string, limit, offset int) ([]Artist, int, error) {
+func (r *TrackRepository) SearchArtists(ctx context.Context, userID uuid.UUID, query string, limit, offset int) ([]Artist, int, error) {
 	if limit <= 0 {
 		limit = 20
 	}
@@ -254,8 +268,8 @@ func (r *TrackRepository) SearchArtists(ctx context.Context, query string, limit
 		limit = 100
 	}
 
-	// Sanitize free-form input into a safe prefix-matching tsquery (see buildPrefixTSQuery).
-	tsQuery := buildPrefixTSQuery(query)
+	// Normalize and sanitize free-form input into a safe prefix-matching tsquery (see searchTSQuery).
+	tsQuery := searchTSQuery(query)
 	if tsQuery == "" {
 		return []Artist{}, 0, nil
 	}
@@ -264,20 +278,21 @@ func (r *TrackRepository) SearchArtists(ctx context.Context, query string, limit
 	selectQuery := `
 		WITH artist_results AS (
 			SELECT artist, mb_artist_id, COUNT(*) as track_count,
-				   ts_rank(to_tsvector('english', artist), to_tsquery('english', $1)) as rank,
+				   ts_rank(to_tsvector('english', COALESCE(search_artist, artist)), to_tsquery('english', $1)) as rank,
 				   COUNT(*) OVER() as total_groups
 			FROM tracks
 			WHERE artist IS NOT NULL
-				AND to_tsvector('english', artist) @@ to_tsquery('english', $1)
+				AND to_tsvector('english', COALESCE(search_artist, artist)) @@ to_tsquery('english', $1)
+				AND ` + tenantScopeSQL("tenant_id", "$4") + `
 			GROUP BY artist, mb_artist_id
 		)
 		SELECT artist, mb_artist_id, track_count, total_groups
 		FROM artist_results
-		ORDER BY rank DESC, track_count DESC, artist ASC
+		ORDER BY rank DESC, track_count DESC, ` + r.db.artistSortSQL("artist") + ` ASC
 		LIMIT $2 OFFSET $3
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, tsQuery, limit, offset)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, tsQuery, limit, offset, userID)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -299,7 +314,7 @@ func (r *TrackRepository) SearchArtists(ctx context.Context, query string, limit
 	}
 
 	if total == 0 && r.db.TrigramEnabled {
-		return r.searchArtistsTrigram(ctx, query, limit, offset)
+		return r.searchArtistsTrigram(ctx, userID, query, limit, offset)
 	}
 
 	return artists, total, nil
@@ -308,7 +323,7 @@ func (r *TrackRepository) SearchArtists(ctx context.Context, query string, limit
 // searchArtistsTrigram is the pg_trgm fuzzy fallback for SearchArtists, ranking distinct
 // artists by similarity() against the raw query. Callers must gate this on
 // r.db.TrigramEnabled.
-func (r *TrackRepository) searchArtistsTrigram(ctx context.Context, query string, limit, offset int) ([]Artist, int, error) {
+func (r *TrackRepository) searchArtistsTrigram(ctx context.Context, userID uuid.UUID, query string, limit, offset int) ([]Artist, int, error) {
 	q := strings.TrimSpace(query)
 	if q == "" {
 		return []Artist{}, 0, nil
@@ -322,15 +337,16 @@ func (r *TrackRepository) searchArtistsTrigram(ctx context.Context, query string
 			FROM tracks
 			WHERE artist IS NOT NULL
 				AND similarity(artist, $1) >= $4
+				AND ` + tenantScopeSQL("tenant_id", "$5") + `
 			GROUP BY artist, mb_artist_id
 		)
 		SELECT artist, mb_artist_id, track_count, total_groups
 		FROM artist_results
-		ORDER BY rank DESC, track_count DESC, artist ASC
+		ORDER BY rank DESC, track_count DESC, ` + r.db.artistSortSQL("artist") + ` ASC
 		LIMIT $2 OFFSET $3
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, q, limit, offset, trigramSearchThreshold)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, q, limit, offset, trigramSearchThreshold, userID)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -354,7 +370,7 @@ func (r *TrackRepository) searchArtistsTrigram(ctx context.Context, query string
 }
 
 // SearchReleases searches distinct albums/releases by name using full-text search
-func (r *TrackRepository) SearchReleases(ctx context.Context, query string, limit, offset int) ([]Release, int, error) {
+func (r *TrackRepository) SearchReleases(ctx context.Context, userID uuid.UUID, query string, limit, offset int) ([]Release, int, error) {
 	if limit <= 0 {
 		limit = 20
 	}
@@ -362,8 +378,8 @@ func (r *TrackRepository) SearchReleases(ctx context.Context, query string, limi
 		limit = 100
 	}
 
-	// Sanitize free-form input into a safe prefix-matching tsquery (see buildPrefixTSQuery).
-	tsQuery := buildPrefixTSQuery(query)
+	// Normalize and sanitize free-form input into a safe prefix-matching tsquery (see searchTSQuery).
+	tsQuery := searchTSQuery(query)
 	if tsQuery == "" {
 		return []Release{}, 0, nil
 	}
@@ -372,20 +388,21 @@ func (r *TrackRepository) SearchReleases(ctx context.Context, query string, limi
 	selectQuery := `
 		WITH release_results AS (
 			SELECT MIN(id) as id, album, artist, mb_release_id, MAX(cover_art_url) as cover_art_url, COUNT(*) as track_count,
-				   ts_rank(to_tsvector('english', album), to_tsquery('english', $1)) as rank,
+				   ts_rank(to_tsvector('english', COALESCE(search_album, album)), to_tsquery('english', $1)) as rank,
 				   COUNT(*) OVER() as total_groups
 			FROM tracks
 			WHERE album IS NOT NULL
-				AND to_tsvector('english', album) @@ to_tsquery('english', $1)
+				AND to_tsvector('english', COALESCE(search_album, album)) @@ to_tsquery('english', $1)
+				AND ` + tenantScopeSQL("tenant_id", "$4") + `
 			GROUP BY album, artist, mb_release_id
 		)
 		SELECT id, album, artist, mb_release_id, cover_art_url, track_count, total_groups
 		FROM release_results
-		ORDER BY rank DESC, track_count DESC, album ASC
+		ORDER BY rank DESC, track_count DESC, ` + r.db.albumSortSQL("album") + ` ASC
 		LIMIT $2 OFFSET $3
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, tsQuery, limit, offset)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, tsQuery, limit, offset, userID)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -411,7 +428,7 @@ func (r *TrackRepository) SearchReleases(ctx context.Context, query string, limi
 	}
 
 	if total == 0 && r.db.TrigramEnabled {
-		return r.searchReleasesTrigram(ctx, query, limit, offset)
+		return r.searchReleasesTrigram(ctx, userID, query, limit, offset)
 	}
 
 	return releases, total, nil
@@ -420,7 +437,7 @@ func (r *TrackRepository) SearchReleases(ctx context.Context, query string, limi
 // searchReleasesTrigram is the pg_trgm fuzzy fallback for SearchReleases, ranking distinct
 // albums by similarity() against the raw query. Callers must gate this on
 // r.db.TrigramEnabled.
-func (r *TrackRepository) searchReleasesTrigram(ctx context.Context, query string, limit, offset int) ([]Release, int, error) {
+func (r *TrackRepository) searchReleasesTrigram(ctx context.Context, userID uuid.UUID, query string, limit, offset int) ([]Release, int, error) {
 	q := strings.TrimSpace(query)
 	if q == "" {
 		return []Release{}, 0, nil
@@ -434,15 +451,16 @@ func (r *TrackRepository) searchReleasesTrigram(ctx context.Context, query strin
 			FROM tracks
 			WHERE album IS NOT NULL
 				AND similarity(album, $1) >= $4
+				AND ` + tenantScopeSQL("tenant_id", "$5") + `
 			GROUP BY album, artist, mb_release_id
 		)
 		SELECT id, album, artist, mb_release_id, cover_art_url, track_count, total_groups
 		FROM release_results
-		ORDER BY rank DESC, track_count DESC, album ASC
+		ORDER BY rank DESC, track_count DESC, ` + r.db.albumSortSQL("album") + ` ASC
 		LIMIT $2 OFFSET $3
 	`
 
-	rows, err := r.db.QueryContext(ctx, selectQuery, q, limit, offset, trigramSearchThreshold)
+	rows, err := r.db.ReadQueryContext(ctx, selectQuery, q, limit, offset, trigramSearchThreshold, userID)
 	if err != nil {
 		return nil, 0, err
 	}
@@ -471,19 +489,27 @@ func (r *TrackRepository) searchReleasesTrigram(ctx context.Context, query strin
 
 // GetByID retrieves a track by its ID
 func (r *TrackRepository) GetByID(ctx context.Context, id int64) (*Track, error) {
-	query := `
-		SELECT id, identity_hash, title, artist, album, duration_ms, version,
+	return r.getByID(ctx, "", id)
+}
+
+// GetByIDForUser retrieves a track the user may see: one of their tenant's
+// tracks, or an instance track when they belong to no tenant. Other tracks
+// are reported as ErrTrackNotFound.
+func (r *TrackRepository) GetByIDForUser(ctx context.Context, userID uuid.UUID, id int64) (*Track, error) {
+	return r.getByID(ctx, " AND "+tenantScopeSQL("tenant_id", "$2"), id, userID)
+}
+
+// trackColumns are the tracks columns scanTrack reads, in order.
+const trackColumns = `id, identity_hash, title, artist, album, duration_ms, version,
 			   mb_recording_id, mb_release_id, mb_artist_id, mb_verified,
 			   source_url, source_type, storage_key, file_size_bytes,
 			   codec, bitrate_kbps, sample_rate_hz, channels, content_type,
 			   metadata_json, metadata_status, metadata_confidence, metadata_provenance,
-			   cover_art_url, metadata_user_edited, created_at, updated_at
-		FROM tracks
-		WHERE id = $1
-	`
+			   cover_art_url, metadata_user_edited, created_at, updated_at`
 
+func scanTrack(row rowScanner) (*Track, error) {
 	var t Track
-	err := r.db.QueryRowContext(ctx, query, id).Scan(
+	err := row.Scan(
 		&t.ID, &t.IdentityHash, &t.Title, &t.Artist, &t.Album, &t.DurationMs, &t.Version,
 		&t.MBRecordingID, &t.MBReleaseID, &t.MBArtistID, &t.MBVerified,
 		&t.SourceURL, &t.SourceType, &t.StorageKey, &t.FileSizeBytes,
@@ -491,6 +517,19 @@ func (r *TrackRepository) GetByID(ctx context.Context, id int64) (*Track, error)
 		&t.MetadataJSON, &t.MetadataStatus, &t.MetadataConfidence, &t.MetadataProvenance,
 		&t.CoverArtURL, &t.MetadataUserEdited, &t.CreatedAt, &t.UpdatedAt,
 	)
+	if err != nil {
+		return nil, err
+	}
+	return &t, nil
+}
+
+func (r *TrackRepository) getByID(ctx context.Context, scope string, args ...any) (*Track, error) {
+	query := `
+		SELECT ` + trackColumns + `
+		FROM tracks
+		WHERE id = $1` + scope
+
+	t, err := scanTrack(r.db.QueryRowContext(ctx, query, args...))
 	if err != nil {
 		if errors.Is(err, sql.ErrNoRows) {
 			return nil, ErrTrackNotFound
@@ -498,7 +537,99 @@ func (r *TrackRepository) GetByID(ctx context.Context, id int64) (*Track, error)
 		return nil, err
 	}
 
-	return &t, nil
+	return t, nil
+}
+
+// GetByIDs loads the tracks with the given IDs in one query, keyed by ID, so
+// a page of items resolves its tracks without a query per item. IDs without
+// a track are absent from the map.
+func (r *TrackRepository) GetByIDs(ctx context.Context, ids []int64) (map[int64]*Track, error) {
+	return r.getByIDs(ctx, "", pq.Array(ids))
+}
+
+// GetByIDsForUser is GetByIDs limited to the tracks the user may see (see
+// GetByIDForUser).
+func (r *TrackRepository) GetByIDsForUser(ctx context.Context, userID uuid.UUID, ids []int64) (map[int64]*Track, error) {
+	return r.getByIDs(ctx, " AND "+tenantScopeSQL("tenant_id", "$2"), pq.Array(ids), userID)
+}
+
+func (r *TrackRepository) getByIDs(ctx context.Context, scope string, args ...any) (map[int64]*Track, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT `+trackColumns+`
+		FROM tracks
+		WHERE id = ANY($1::bigint[])`+scope,
+		args...)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	tracks := make(map[int64]*Track)
+	for rows.Next() {
+		t, err := scanTrack(rows)
+		if err != nil {
+			return nil, err
+		}
+		tracks[t.ID] = t
+	}
+	return tracks, rows.Err()
+}
+
+// MissingIDs returns the IDs in ids that have no track in the user's tenant,
+// in request order, so bulk callers can validate a whole batch in one round
+// trip. Another tenant's tracks count as missing.
+func (r *TrackRepository) MissingIDs(ctx context.Context, userID uuid.UUID, ids []int64) ([]int64, error) {
+	query := `
+		SELECT t.id
+		FROM unnest($1::bigint[]) WITH ORDINALITY AS t(id, ord)
+		WHERE NOT EXISTS (
+			SELECT 1 FROM tracks
+			WHERE tracks.id = t.id AND ` + tenantScopeSQL("tracks.tenant_id", "$2") + `
+		)
+		ORDER BY t.ord
+	`
+
+	rows, err := r.db.QueryContext(ctx, query, pq.Array(ids), userID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var missing []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		missing = append(missing, id)
+	}
+	return missing, rows.Err()
+}
+
+// RecentlyAddedTrackIDs returns the IDs of the limit tracks with stored audio
+// added most recently, newest first.
+func (r *TrackRepository) RecentlyAddedTrackIDs(ctx context.Context, limit int) ([]int64, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id
+		FROM tracks
+		WHERE storage_key IS NOT NULL
+		ORDER BY created_at DESC, id DESC
+		LIMIT $1
+	`, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	return ids, rows.Err()
 }
 
 // MBMatchUpdate contains the MusicBrainz match data to update
@@ -521,11 +652,35 @@ type MBMatchUpdate struct {
 	Artist                  string
 	Album                   string
 	DurationMs              int
+	// ReleaseDate replaces the stored release date when non-zero.
+	ReleaseDate PartialDate
 }
 
-// UpdateMBMatch updates a track's MusicBrainz identifiers and verification status
+// UpdateMBMatch updates a track's MusicBrainz identifiers and verification status.
+// With RespectUserEdits, each of title, artist, album, duration, and cover art
+// is only written when its field is unlocked and no higher-ranked source
+// supplied the current value; written fields are recorded as MusicBrainz.
 func (r *TrackRepository) UpdateMBMatch(ctx context.Context, trackID int64, match *MBMatchUpdate) error {
 	query := `
+		WITH writable AS (
+			SELECT f.field
+			FROM (VALUES
+				('title', $11 <> ''),
+				('artist', $12 <> ''),
+				('album', $13 <> ''),
+				('duration_ms', $14 > 0),
+				('cover_art_url', $10 <> '')
+			) AS f(field, present)
+			WHERE f.present
+			  AND EXISTS (SELECT 1 FROM tracks WHERE id = $1)
+			  AND ($16 = FALSE OR NOT ` + fieldBlocked("$1", "f.field", "$18::text", "$19::text[]") + `)
+		), recorded AS (
+			INSERT INTO track_field_provenance (track_id, field, source)
+			SELECT $1, field, $18::text FROM writable
+			ON CONFLICT (track_id, field) DO UPDATE SET
+				source = EXCLUDED.source,
+				updated_at = NOW()
+		)
 		UPDATE tracks
 		SET mb_recording_id = CASE WHEN $15 AND (metadata_user_edited = FALSE OR $16 = FALSE) THEN $2 ELSE mb_recording_id END,
 			mb_release_id = CASE WHEN $15 AND (metadata_user_edited = FALSE OR $16 = FALSE) THEN $3 ELSE mb_release_id END,
@@ -543,15 +698,21 @@ func (r *TrackRepository) UpdateMBMatch(ctx context.Context, trackID int64, matc
 				ELSE metadata_confidence
 			END,
 			metadata_provenance = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE THEN COALESCE(metadata_provenance, '{}'::jsonb) || COALESCE($9::jsonb, '{}'::jsonb) ELSE metadata_provenance END,
-			cover_art_url = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE THEN COALESCE(NULLIF($10, ''), cover_art_url) ELSE cover_art_url END,
-			title = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE THEN COALESCE(NULLIF($11, ''), title) ELSE title END,
-			artist = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE THEN COALESCE(NULLIF($12, ''), artist) ELSE artist END,
-			album = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE THEN COALESCE(NULLIF($13, ''), album) ELSE album END,
-			duration_ms = CASE WHEN (metadata_user_edited = FALSE OR $16 = FALSE) AND $14 > 0 THEN $14 ELSE duration_ms END,
+			cover_art_url = CASE WHEN 'cover_art_url' IN (SELECT field FROM writable) THEN $10 ELSE cover_art_url END,
+			title = CASE WHEN 'title' IN (SELECT field FROM writable) THEN $11 ELSE title END,
+			artist = CASE WHEN 'artist' IN (SELECT field FROM writable) THEN $12 ELSE artist END,
+			album = CASE WHEN 'album' IN (SELECT field FROM writable) THEN $13 ELSE album END,
+			duration_ms = CASE WHEN 'duration_ms' IN (SELECT field FROM writable) THEN $14 ELSE duration_ms END,
+			release_year = CASE WHEN $20::smallint IS NOT NULL AND (metadata_user_edited = FALSE OR $16 = FALSE) THEN $20::smallint ELSE release_year END,
+			release_month = CASE WHEN $20::smallint IS NOT NULL AND (metadata_user_edited = FALSE OR $16 = FALSE) THEN $21::smallint ELSE release_month END,
+			release_day = CASE WHEN $20::smallint IS NOT NULL AND (metadata_user_edited = FALSE OR $16 = FALSE) THEN $22::smallint ELSE release_day END,
+			search_text = CASE WHEN EXISTS (SELECT 1 FROM writable WHERE field IN ('title', 'artist', 'album')) THEN NULL ELSE search_text END,
+			mb_checked_at = NOW(),
 			updated_at = NOW()
 		WHERE id = $1
 	`
 
+	releaseYear, releaseMonth, releaseDay := partialDateArgs(match.ReleaseDate)
 	result, err := r.db.ExecContext(ctx, query,
 		trackID,
 		match.MBRecordingID,
@@ -570,6 +731,11 @@ func (r *TrackRepository) UpdateMBMatch(ctx context.Context, trackID int64, matc
 		match.ApplyMBIdentity,
 		match.RespectUserEdits,
 		match.ClearMetadataConfidence,
+		FieldSourceMusicBrainz,
+		pq.Array(r.fieldPrecedence()),
+		releaseYear,
+		releaseMonth,
+		releaseDay,
 	)
 	if err != nil {
 		return err
@@ -583,7 +749,7 @@ func (r *TrackRepository) UpdateMBMatch(ctx context.Context, trackID int64, matc
 		return ErrTrackNotFound
 	}
 
-	return nil
+	return refreshTrackAggregates(ctx, r.db, trackID, true)
 }
 
 func nullableRawJSON(raw json.RawMessage) any {
@@ -642,19 +808,56 @@ type MetadataUpdate struct {
 }
 
 // UpdateMetadata updates a track's metadata fields (title, artist, album, duration)
-func (r *TrackRepository) UpdateMetadata(ctx context.Context, trackID int64, update *MetadataUpdate) error {
+// as a user correction: every field it sets is recorded as a locked user value.
+// When precondition is non-nil it is called with the track's current
+// MetadataVersion while the track row is locked; returning false aborts the
+// edit with ErrTrackVersionMismatch, so two clients correcting the same track
+// cannot silently overwrite each other.
+func (r *TrackRepository) UpdateMetadata(ctx context.Context, trackID int64, update *MetadataUpdate, precondition func(version string) bool) error {
 	query := `
+		WITH locked AS (
+			INSERT INTO track_field_provenance (track_id, field, source, locked)
+			SELECT $1, f.field, 'user', TRUE
+			FROM (VALUES
+				('title', $2 <> ''),
+				('artist', $3 <> ''),
+				('album', $4 <> ''),
+				('duration_ms', $5 > 0)
+			) AS f(field, present)
+			WHERE f.present AND EXISTS (SELECT 1 FROM tracks WHERE id = $1)
+			ON CONFLICT (track_id, field) DO UPDATE SET
+				source = EXCLUDED.source,
+				locked = TRUE,
+				updated_at = NOW()
+		)
 		UPDATE tracks
 		SET title = COALESCE(NULLIF($2, ''), title),
 			artist = COALESCE(NULLIF($3, ''), artist),
 			album = COALESCE(NULLIF($4, ''), album),
 			duration_ms = CASE WHEN $5 > 0 THEN $5 ELSE duration_ms END,
 			metadata_user_edited = TRUE,
+			search_text = NULL,
 			updated_at = NOW()
 		WHERE id = $1
 	`
 
-	result, err := r.db.ExecContext(ctx, query,
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+
+	if precondition != nil {
+		version, err := trackVersion(ctx, tx, trackID, true)
+		if err != nil {
+			return err
+		}
+		if !precondition(version) {
+			return ErrTrackVersionMismatch
+		}
+	}
+
+	result, err := tx.ExecContext(ctx, query,
 		trackID,
 		update.Title,
 		update.Artist,
@@ -672,12 +875,40 @@ func (r *TrackRepository) UpdateMetadata(ctx context.Context, trackID int64, upd
 	if rows == 0 {
 		return ErrTrackNotFound
 	}
+	if err := tx.Commit(); err != nil {
+		return err
+	}
 
-	return nil
+	return refreshTrackAggregates(ctx, r.db, trackID, true)
+}
+
+// MetadataVersion returns a token that changes whenever the track does, for
+// clients to send back as the If-Match of a metadata edit.
+func (r *TrackRepository) MetadataVersion(ctx context.Context, trackID int64) (string, error) {
+	return trackVersion(ctx, r.db, trackID, false)
+}
+
+func trackVersion(ctx context.Context, q rowQueryer, trackID int64, lock bool) (string, error) {
+	query := `SELECT updated_at FROM tracks WHERE id = $1`
+	if lock {
+		query += " FOR UPDATE"
+	}
+	var updatedAt time.Time
+	if err := q.QueryRowContext(ctx, query, trackID).Scan(&updatedAt); err != nil {
+		if errors.Is(err, sql.ErrNoRows) {
+			return "", ErrTrackNotFound
+		}
+		return "", err
+	}
+	return versionToken(nil, sql.NullTime{Time: updatedAt, Valid: true}), nil
 }
 
 // GetByIdentityHash retrieves a track by its identity hash.
 func (r *TrackRepository) GetByIdentityHash(ctx context.Context, identityHash string) (*Track, error) {
+	return trackByIdentityHash(ctx, r.db, identityHash)
+}
+
+func trackByIdentityHash(ctx context.Context, q queryRower, identityHash string) (*Track, error) {
 	query := `
 		SELECT id, identity_hash, title, artist, album, duration_ms, version,
 			   mb_recording_id, mb_release_id, mb_artist_id, mb_verified,
@@ -690,7 +921,7 @@ func (r *TrackRepository) GetByIdentityHash(ctx context.Context, identityHash st
 	`
 
 	var t Track
-	err := r.db.QueryRowContext(ctx, query, identityHash).Scan(
+	err := q.QueryRowContext(ctx, query, identityHash).Scan(
 		&t.ID, &t.IdentityHash, &t.Title, &t.Artist, &t.Album, &t.DurationMs, &t.Version,
 		&t.MBRecordingID, &t.MBReleaseID, &t.MBArtistID, &t.MBVerified,
 		&t.SourceURL, &t.SourceType, &t.StorageKey, &t.FileSizeBytes,
@@ -708,26 +939,91 @@ func (r *TrackRepository) GetByIdentityHash(ctx context.Context, identityHash st
 	return &t, nil
 }
 
+// LocateTrack returns the ID of the track stored from sourceURL or, failing
+// that, the oldest track with the MusicBrainz recording ID mbRecordingID.
+// Either may be empty. It returns ErrTrackNotFound when neither matches.
+func (r *TrackRepository) LocateTrack(ctx context.Context, sourceURL, mbRecordingID string) (int64, error) {
+	var id int64
+	if sourceURL != "" {
+		err := r.db.QueryRowContext(ctx, `
+			SELECT t.id FROM tracks t
+			WHERE t.source_url = $1
+			   OR EXISTS (SELECT 1 FROM track_sources ts WHERE ts.track_id = t.id AND ts.source_url = $1)
+			ORDER BY t.created_at ASC
+			LIMIT 1
+		`, sourceURL).Scan(&id)
+		if err == nil {
+			return id, nil
+		}
+		if !errors.Is(err, sql.ErrNoRows) {
+			return 0, err
+		}
+	}
+	if recordingID, err := uuid.Parse(mbRecordingID); err == nil {
+		err := r.db.QueryRowContext(ctx, `
+			SELECT id FROM tracks
+			WHERE mb_recording_id = $1
+			ORDER BY created_at ASC
+			LIMIT 1
+		`, recordingID).Scan(&id)
+		if err == nil {
+			return id, nil
+		}
+		if !errors.Is(err, sql.ErrNoRows) {
+			return 0, err
+		}
+	}
+	return 0, ErrTrackNotFound
+}
+
 // Create inserts a new track into the database.
 // Returns ErrDuplicateTrack if a track with the same identity hash already exists.
+// A tenant's track is only inserted while the tenant is within its quotas;
+// otherwise Create returns ErrTenantQuotaExceeded.
 func (r *TrackRepository) Create(ctx context.Context, track *Track) error {
+	if !track.tenantID.Valid {
+		return insertTrack(ctx, r.db, track)
+	}
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+	if err := checkTenantQuota(ctx, tx, track.tenantID.Int64, track.FileSizeBytes.Int64, true); err != nil {
+		return err
+	}
+	if err := insertTrack(ctx, tx, track); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+func insertTrack(ctx context.Context, q queryRower, track *Track) error {
 	query := `
 		INSERT INTO tracks (
 			identity_hash, title, artist, album, duration_ms, version,
 			mb_recording_id, mb_release_id, mb_artist_id, mb_verified,
 			source_url, source_type, storage_key, file_size_bytes, metadata_json,
 			codec, bitrate_kbps, sample_rate_hz, channels, content_type,
-			metadata_status, metadata_confidence, metadata_provenance, cover_art_url, metadata_user_edited
-		) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, COALESCE($21, 'provider'), $22, $23, $24, $25)
+			metadata_status, metadata_confidence, metadata_provenance, cover_art_url, metadata_user_edited,
+			search_text, search_artist, search_album, search_aliases, tenant_id, audio_hash
+		) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, COALESCE($21, 'provider'), $22, $23, $24, $25,
+			$26, NULLIF($27, ''), NULLIF($28, ''), $29, $30, $31)
 		RETURNING id, created_at, updated_at
 	`
 
-	err := r.db.QueryRowContext(ctx, query,
+	searchText, searchArtist, searchAlbum := trackSearchFields(track.Title, track.Artist.String, track.Album.String, track.SearchAliases)
+	aliases := track.SearchAliases
+	if aliases == nil {
+		aliases = []string{}
+	}
+	err := q.QueryRowContext(ctx, query,
 		track.IdentityHash, track.Title, track.Artist, track.Album, track.DurationMs, track.Version,
 		track.MBRecordingID, track.MBReleaseID, track.MBArtistID, track.MBVerified,
 		track.SourceURL, track.SourceType, track.StorageKey, track.FileSizeBytes, nullableRawJSON(track.MetadataJSON),
 		track.Codec, track.BitrateKbps, track.SampleRateHz, track.Channels, track.ContentType,
 		track.MetadataStatus, track.MetadataConfidence, nullableRawJSON(track.MetadataProvenance), track.CoverArtURL, track.MetadataUserEdited,
+		searchText, searchArtist, searchAlbum, pq.Array(aliases), track.tenantID, track.audioHash,
 	).Scan(&track.ID, &track.CreatedAt, &track.UpdatedAt)
 
 	if err != nil {
@@ -748,37 +1044,54 @@ func (r *TrackRepository) Create(ctx context.Context, track *Track) error {
 // The second return value indicates whether a new track was created (true)
 // or an existing track was returned (false).
 func (r *TrackRepository) CreateOrGet(ctx context.Context, track *Track) (*Track, bool, error) {
-	// First, try to get existing track by identity hash
-	existing, err := r.GetByIdentityHash(ctx, track.IdentityHash)
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, false, err
+	}
+	defer tx.Rollback()
+	result, created, err := createOrGetTrack(ctx, tx, track)
+	if err != nil {
+		return nil, false, err
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, false, err
+	}
+	return result, created, nil
+}
+
+// createOrGetTrack holds a transaction lock on the track's identity hash
+// while it looks for the track and inserts it, so concurrent creators of the
+// same track, in a Tx or not, queue up instead of racing to the insert.
+func createOrGetTrack(ctx context.Context, tx *sql.Tx, track *Track) (*Track, bool, error) {
+	if _, err := tx.ExecContext(ctx, `SELECT pg_advisory_xact_lock(hashtextextended($1, 0))`, track.IdentityHash); err != nil {
+		return nil, false, err
+	}
+	existing, err := trackByIdentityHash(ctx, tx, track.IdentityHash)
 	if err == nil {
-		// Track already exists, return it
 		return existing, false, nil
 	}
 	if !errors.Is(err, ErrTrackNotFound) {
-		// Unexpected error
 		return nil, false, err
 	}
-
-	// Track doesn't exist, try to create it
-	if err := r.Create(ctx, track); err != nil {
-		if errors.Is(err, ErrDuplicateTrack) {
-			// Race condition: another process created the track
-			// Try to fetch it again
-			existing, err = r.GetByIdentityHash(ctx, track.IdentityHash)
-			if err != nil {
-				return nil, false, err
-			}
-			return existing, false, nil
+	if track.tenantID.Valid {
+		if err := checkTenantQuota(ctx, tx, track.tenantID.Int64, track.FileSizeBytes.Int64, true); err != nil {
+			return nil, false, err
 		}
+	}
+	if err := insertTrack(ctx, tx, track); err != nil {
 		return nil, false, err
 	}
-
 	return track, true, nil
 }
 
 // CreateTrackFromMetadata creates a track from raw metadata, handling normalization
 // and identity hash calculation automatically. Returns the created or existing track.
 func (r *TrackRepository) CreateTrackFromMetadata(ctx context.Context, artist, title, album string, durationMs int, opts ...TrackOption) (*Track, bool, error) {
+	return r.CreateOrGet(ctx, trackFromMetadata(artist, title, album, durationMs, opts...))
+}
+
+// trackFromMetadata builds an unsaved track from raw metadata.
+func trackFromMetadata(artist, title, album string, durationMs int, opts ...TrackOption) *Track {
 	// Parse metadata and extract version
 	identity := ParseTrackMetadata(artist, title, album, durationMs)
 
@@ -799,8 +1112,10 @@ func (r *TrackRepository) CreateTrackFromMetadata(ctx context.Context, artist, t
 	for _, opt := range opts {
 		opt(track)
 	}
-
-	return r.CreateOrGet(ctx, track)
+	if track.tenantID.Valid {
+		track.IdentityHash = TenantIdentityHash(track.tenantID.Int64, track.IdentityHash)
+	}
+	return track
 }
 
 // TrackOption is a functional option for configuring a track during creation.
@@ -818,6 +1133,23 @@ func WithMusicBrainzIDs(recordingID, releaseID, artistID *uuid.UUID) TrackOption
 	}
 }
 
+// WithTenant makes the track one of the tenant's own. Its identity is scoped
+// to the tenant, so another household's copy of the same recording stays a
+// separate track.
+func WithTenant(tenantID int64) TrackOption {
+	return func(t *Track) {
+		t.tenantID = sql.NullInt64{Int64: tenantID, Valid: true}
+	}
+}
+
+// WithAudioHash records the signature of the track's decoded audio. An empty
+// hash records that the audio had none worth matching.
+func WithAudioHash(hash string) TrackOption {
+	return func(t *Track) {
+		t.audioHash = sql.NullString{String: hash, Valid: true}
+	}
+}
+
 // WithSource sets the source URL and type on the track.
 func WithSource(sourceURL, sourceType string) TrackOption {
 	return func(t *Track) {
@@ -870,6 +1202,143 @@ func (r *TrackRepository) UpdateAudioQuality(ctx context.Context, trackID int64,
 	return nil
 }
 
+// ReplaceStoredAudio points a track at a newly stored audio object along with
+// the facts probed from it.
+func (r *TrackRepository) ReplaceStoredAudio(ctx context.Context, trackID int64, storageKey string, fileSizeBytes int64, codec string, bitrateKbps, sampleRateHz, channels int, contentType string) error {
+	result, err := r.db.ExecContext(ctx, `
+		UPDATE tracks
+		SET storage_key = $2,
+			file_size_bytes = $3,
+			codec = NULLIF($4, ''),
+			bitrate_kbps = NULLIF($5, 0),
+			sample_rate_hz = NULLIF($6, 0),
+			channels = NULLIF($7, 0),
+			content_type = NULLIF($8, ''),
+			audio_quality_probe_attempted_at = NOW(),
+			updated_at = NOW()
+		WHERE id = $1
+	`, trackID, storageKey, fileSizeBytes, codec, bitrateKbps, sampleRateHz, channels, contentType)
+	if err != nil {
+		return err
+	}
+	rows, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if rows == 0 {
+		return ErrTrackNotFound
+	}
+	return refreshTrackAggregates(ctx, r.db, trackID, false)
+}
+
+// StorageKeyInUse reports whether any track's audio is stored at key.
+func (r *TrackRepository) StorageKeyInUse(ctx context.Context, key string) (bool, error) {
+	var inUse bool
+	err := r.db.QueryRowContext(ctx, `SELECT EXISTS(SELECT 1 FROM tracks WHERE storage_key = $1)`, key).Scan(&inUse)
+	return inUse, err
+}
+
+// StoredAudio is what a track's audio object is stored as.
+type StoredAudio struct {
+	TrackID       int64
+	Codec         string
+	BitrateKbps   int
+	FileSizeBytes int64
+	DurationMs    int
+}
+
+// ListStoredAudio lists the format of every track with stored audio, by id.
+func (r *TrackRepository) ListStoredAudio(ctx context.Context) ([]StoredAudio, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, COALESCE(codec, ''), COALESCE(bitrate_kbps, 0), COALESCE(file_size_bytes, 0), COALESCE(duration_ms, 0)
+		FROM tracks
+		WHERE storage_key IS NOT NULL AND storage_key <> ''
+		ORDER BY id
+	`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var audio []StoredAudio
+	for rows.Next() {
+		var a StoredAudio
+		if err := rows.Scan(&a.TrackID, &a.Codec, &a.BitrateKbps, &a.FileSizeBytes, &a.DurationMs); err != nil {
+			return nil, err
+		}
+		audio = append(audio, a)
+	}
+	return audio, rows.Err()
+}
+
+// FindByAudioHash returns the track of the tenant (0 for instance tracks)
+// whose audio hash differs least from hash, provided it differs in at most
+// maxDistance of its 256 bits and the track's duration is within two seconds
+// of durationMs. It returns ErrTrackNotFound when no track qualifies.
+func (r *TrackRepository) FindByAudioHash(ctx context.Context, hash string, durationMs int, tenantID int64, maxDistance int) (*Track, error) {
+	if len(hash) != 64 || durationMs <= 0 {
+		return nil, ErrTrackNotFound
+	}
+	var id int64
+	err := r.db.QueryRowContext(ctx, `
+		SELECT id
+		FROM tracks
+		WHERE audio_hash <> ''
+		  AND duration_ms BETWEEN $2 - 2000 AND $2 + 2000
+		  AND tenant_id IS NOT DISTINCT FROM NULLIF($3, 0)
+		  AND bit_count(('x' || audio_hash)::bit(256) # ('x' || $1)::bit(256)) <= $4
+		ORDER BY bit_count(('x' || audio_hash)::bit(256) # ('x' || $1)::bit(256)), id
+		LIMIT 1
+	`, hash, durationMs, tenantID, maxDistance).Scan(&id)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrTrackNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	return r.GetByID(ctx, id)
+}
+
+// SetAudioHash records the signature of a track's stored audio; see
+// WithAudioHash.
+func (r *TrackRepository) SetAudioHash(ctx context.Context, trackID int64, hash string) error {
+	result, err := r.db.ExecContext(ctx, `UPDATE tracks SET audio_hash = $2 WHERE id = $1`, trackID, hash)
+	if err != nil {
+		return err
+	}
+	rows, err := result.RowsAffected()
+	if err != nil {
+		return err
+	}
+	if rows == 0 {
+		return ErrTrackNotFound
+	}
+	return nil
+}
+
+// ListUnhashedAudio lists the tracks with stored audio but no audio hash yet,
+// by id.
+func (r *TrackRepository) ListUnhashedAudio(ctx context.Context) ([]int64, error) {
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id
+		FROM tracks
+		WHERE storage_key IS NOT NULL AND storage_key <> '' AND audio_hash IS NULL
+		ORDER BY id
+	`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	return ids, rows.Err()
+}
+
 // MarkAudioQualityProbeAttempt moves a failed artifact to the end of the
 // maintenance queue so one corrupt object cannot starve later rows.
 func (r *TrackRepository) MarkAudioQualityProbeAttempt(ctx context.Context, trackID int64) error {
@@ -881,6 +1350,18 @@ func (r *TrackRepository) MarkAudioQualityProbeAttempt(ctx context.Context, trac
 	return err
 }
 
+// WithSearchAliases adds names search should match besides the title,
+// artist, and album, such as romanized sort tags.
+func WithSearchAliases(aliases ...string) TrackOption {
+	return func(t *Track) {
+		for _, alias := range aliases {
+			if alias = strings.TrimSpace(alias); alias != "" {
+				t.SearchAliases = append(t.SearchAliases, alias)
+			}
+		}
+	}
+}
+
 // WithMetadata sets additional metadata JSON on the track.
 func WithMetadata(metadata json.RawMessage) TrackOption {
 	return func(t *Track) {
@@ -1029,6 +1510,113 @@ func (r *TrackRepository) GetMaintenanceCandidates(ctx context.Context, includeM
 	return tracks, nil
 }
 
+// GetMBReverifyCandidates returns MusicBrainz-linked tracks whose match was
+// last checked more than staleAfter ago, least recently checked first.
+func (r *TrackRepository) GetMBReverifyCandidates(ctx context.Context, staleAfter time.Duration, limit int) ([]Track, error) {
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 200 {
+		limit = 200
+	}
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT id, identity_hash, title, artist, album, duration_ms, version,
+			   mb_recording_id, mb_release_id, mb_artist_id, mb_verified,
+			   source_url, source_type, storage_key, file_size_bytes,
+			   codec, bitrate_kbps, sample_rate_hz, channels, content_type,
+			   COALESCE(metadata_json, '{}'::jsonb), metadata_status, metadata_confidence,
+			   COALESCE(metadata_provenance, '{}'::jsonb),
+			   cover_art_url, metadata_user_edited, created_at, updated_at
+		FROM tracks
+		WHERE mb_recording_id IS NOT NULL
+		  AND COALESCE(mb_checked_at, created_at) < NOW() - ($1::bigint * INTERVAL '1 second')
+		ORDER BY COALESCE(mb_checked_at, created_at) ASC, id ASC
+		LIMIT $2
+	`, int64(staleAfter.Seconds()), limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	tracks := make([]Track, 0, limit)
+	for rows.Next() {
+		var track Track
+		if err := rows.Scan(
+			&track.ID, &track.IdentityHash, &track.Title, &track.Artist, &track.Album, &track.DurationMs, &track.Version,
+			&track.MBRecordingID, &track.MBReleaseID, &track.MBArtistID, &track.MBVerified,
+			&track.SourceURL, &track.SourceType, &track.StorageKey, &track.FileSizeBytes,
+			&track.Codec, &track.BitrateKbps, &track.SampleRateHz, &track.Channels, &track.ContentType,
+			&track.MetadataJSON, &track.MetadataStatus, &track.MetadataConfidence, &track.MetadataProvenance,
+			&track.CoverArtURL, &track.MetadataUserEdited, &track.CreatedAt, &track.UpdatedAt,
+		); err != nil {
+			return nil, err
+		}
+		tracks = append(tracks, track)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	return tracks, nil
+}
+
+// MarkMBChecked records a MusicBrainz re-check that could not update the track,
+// so a failing recording does not hold the front of the stale queue.
+func (r *TrackRepository) MarkMBChecked(ctx context.Context, trackID int64) error {
+	_, err := r.db.ExecContext(ctx, `UPDATE tracks SET mb_checked_at = NOW() WHERE id = $1`, trackID)
+	return err
+}
+
+// GetQualityScoreCandidates returns stored tracks whose file has not been
+// measured for a quality score, oldest first.
+func (r *TrackRepository) GetQualityScoreCandidates(ctx context.Context, limit int) ([]Track, error) {
+	if limit <= 0 {
+		limit = 50
+	}
+	if limit > 200 {
+		limit = 200
+	}
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT t.id, t.identity_hash, t.title, t.artist, t.album, t.duration_ms, t.version,
+			   t.mb_recording_id, t.mb_release_id, t.mb_artist_id, t.mb_verified,
+			   t.source_url, t.source_type, t.storage_key, t.file_size_bytes,
+			   t.codec, t.bitrate_kbps, t.sample_rate_hz, t.channels, t.content_type,
+			   COALESCE(t.metadata_json, '{}'::jsonb), t.metadata_status, t.metadata_confidence,
+			   COALESCE(t.metadata_provenance, '{}'::jsonb),
+			   t.cover_art_url, t.metadata_user_edited, t.created_at, t.updated_at
+		FROM tracks t
+		LEFT JOIN track_quality tq ON tq.track_id = t.id
+		WHERE t.storage_key IS NOT NULL
+		  AND btrim(t.storage_key) <> ''
+		  AND (tq.track_id IS NULL OR tq.storage_key <> t.storage_key)
+		ORDER BY t.id ASC
+		LIMIT $1
+	`, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	tracks := make([]Track, 0, limit)
+	for rows.Next() {
+		var track Track
+		if err := rows.Scan(
+			&track.ID, &track.IdentityHash, &track.Title, &track.Artist, &track.Album, &track.DurationMs, &track.Version,
+			&track.MBRecordingID, &track.MBReleaseID, &track.MBArtistID, &track.MBVerified,
+			&track.SourceURL, &track.SourceType, &track.StorageKey, &track.FileSizeBytes,
+			&track.Codec, &track.BitrateKbps, &track.SampleRateHz, &track.Channels, &track.ContentType,
+			&track.MetadataJSON, &track.MetadataStatus, &track.MetadataConfidence, &track.MetadataProvenance,
+			&track.CoverArtURL, &track.MetadataUserEdited, &track.CreatedAt, &track.UpdatedAt,
+		); err != nil {
+			return nil, err
+		}
+		tracks = append(tracks, track)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	return tracks, nil
+}
+
 // GetAudioQualityMaintenanceCandidates returns a bounded, stable batch of
 // stored artifacts whose ffprobe facts have not been fully persisted.
 func (r *TrackRepository) GetAudioQualityMaintenanceCandidates(ctx context.Context, limit int) ([]Track, error) {
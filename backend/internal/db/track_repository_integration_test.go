@@ -55,28 +55,7 @@ func TestNormalMaintenanceCandidatesRetainAudioQualityFacts(t *testing.T) {
 func newPostgresTestRepository(t *testing.T) (*TrackRepository, context.Context) {
 	t.Helper()
 
-	dsn := postgresTestDSN()
-	if dsn == "" {
-		t.Skip("set OMP_POSTGRES_TEST_DSN, QA_DATABASE_URL, or DATABASE_URL to run Postgres repository integration tests")
-	}
-
-	rawDB, err := sql.Open("postgres", dsn)
-	if err != nil {
-		t.Fatalf("open test database: %v", err)
-	}
-	t.Cleanup(func() { _ = rawDB.Close() })
-
-	database := &DB{DB: rawDB}
-	if err := database.Ping(); err != nil {
-		t.Fatalf("ping test database: %v", err)
-	}
-	if err := database.Migrate(); err != nil {
-		t.Fatalf("migrate test database: %v", err)
-	}
-	if _, err := database.Exec("TRUNCATE TABLE tracks RESTART IDENTITY CASCADE"); err != nil {
-		t.Fatalf("truncate test database: %v", err)
-	}
-
+	database := newIsolatedTestDB(t)
 	return NewTrackRepository(database), context.Background()
 }
 
@@ -228,7 +207,7 @@ func TestSearchReleasesReturnsStableNumericIDAgainstPostgres(t *testing.T) {
 		t.Fatalf("seed second track: %v", err)
 	}
 
-	releases, total, err := repo.SearchReleases(ctx, "Catalog Numeric", 20, 0)
+	releases, total, err := repo.SearchReleases(ctx, uuid.Nil, "Catalog Numeric", 20, 0)
 	if err != nil {
 		t.Fatalf("SearchReleases: %v", err)
 	}
@@ -5,6 +5,7 @@ import (
 	"database/sql"
 	"database/sql/driver"
 	"errors"
+	"io"
 	"strings"
 	"sync"
 	"testing"
@@ -30,6 +31,13 @@ type captureUpdateDriver struct{}
 
 type captureUpdateConn struct{}
 
+// captureUpdateTx and captureUpdateRows let the aggregate refresh that follows
+// a track update run against the capture driver as a no-op: the track is in
+// no playlist, and its other statements are not captured.
+type captureUpdateTx struct{}
+
+type captureUpdateRows struct{ done bool }
+
 func (captureUpdateDriver) Open(string) (driver.Conn, error) { return captureUpdateConn{}, nil }
 
 func (captureUpdateConn) Prepare(string) (driver.Stmt, error) {
@@ -38,16 +46,36 @@ func (captureUpdateConn) Prepare(string) (driver.Stmt, error) {
 
 func (captureUpdateConn) Close() error { return nil }
 
-func (captureUpdateConn) Begin() (driver.Tx, error) {
-	return nil, errors.New("transactions not supported")
-}
+func (captureUpdateConn) Begin() (driver.Tx, error) { return captureUpdateTx{}, nil }
 
 func (captureUpdateConn) CheckNamedValue(*driver.NamedValue) error { return nil }
 
+func (captureUpdateConn) QueryContext(context.Context, string, []driver.NamedValue) (driver.Rows, error) {
+	return &captureUpdateRows{}, nil
+}
+
+func (captureUpdateTx) Commit() error   { return nil }
+func (captureUpdateTx) Rollback() error { return nil }
+
+func (*captureUpdateRows) Columns() []string { return []string{"playlist_ids"} }
+func (*captureUpdateRows) Close() error      { return nil }
+
+func (r *captureUpdateRows) Next(dest []driver.Value) error {
+	if r.done {
+		return io.EOF
+	}
+	r.done = true
+	dest[0] = "{}"
+	return nil
+}
+
 func (captureUpdateConn) ExecContext(_ context.Context, query string, args []driver.NamedValue) (driver.Result, error) {
 	captureUpdateMu.Lock()
 	defer captureUpdateMu.Unlock()
 
+	if !strings.Contains(query, "UPDATE tracks") {
+		return driver.RowsAffected(0), nil
+	}
 	captureUpdate.query = query
 	captureUpdate.args = append([]driver.NamedValue(nil), args...)
 	if captureUpdate.rows == 0 {
@@ -96,8 +124,8 @@ func TestUpdateMBMatchAutomaticFallbackDoesNotClearExistingIdentity(t *testing.T
 	}
 
 	capture := latestCapturedUpdate(t)
-	if len(capture.args) != 17 {
-		t.Fatalf("arg count = %d, want 17", len(capture.args))
+	if len(capture.args) != 22 {
+		t.Fatalf("arg count = %d, want 22", len(capture.args))
 	}
 	if !isNilValue(capture.args[4].Value) {
 		t.Fatalf("MBVerified arg = %#v, want nil so existing verification is left unchanged", capture.args[4].Value)
@@ -111,6 +139,12 @@ func TestUpdateMBMatchAutomaticFallbackDoesNotClearExistingIdentity(t *testing.T
 	if capture.args[16].Value != true {
 		t.Fatalf("ClearMetadataConfidence arg = %#v, want true", capture.args[16].Value)
 	}
+	if capture.args[17].Value != FieldSourceMusicBrainz {
+		t.Fatalf("field source arg = %#v, want %q", capture.args[17].Value, FieldSourceMusicBrainz)
+	}
+	if !isNilValue(capture.args[19].Value) {
+		t.Fatalf("release year arg = %#v, want nil so the stored release date is kept", capture.args[19].Value)
+	}
 }
 
 func TestUpdateMBMatchUserEditedGuardCoversAutomaticEnrichmentFields(t *testing.T) {
@@ -144,8 +178,11 @@ func TestUpdateMBMatchUserEditedGuardCoversAutomaticEnrichmentFields(t *testing.
 		"metadata_confidence = CASE",
 		"WHEN metadata_user_edited = FALSE OR $16 = FALSE THEN",
 		"metadata_provenance = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE",
-		"cover_art_url = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE",
-		"title = CASE WHEN metadata_user_edited = FALSE OR $16 = FALSE",
+		"($16 = FALSE OR NOT EXISTS (",
+		"fp.locked OR (fp.source <> 'user' AND array_position($19::text[], fp.source) < array_position($19::text[], $18::text)",
+		"cover_art_url = CASE WHEN 'cover_art_url' IN (SELECT field FROM writable)",
+		"title = CASE WHEN 'title' IN (SELECT field FROM writable)",
+		"release_year = CASE WHEN $20::smallint IS NOT NULL AND (metadata_user_edited = FALSE OR $16 = FALSE)",
 	} {
 		if !strings.Contains(query, fragment) {
 			t.Fatalf("UpdateMBMatch query missing user-edit guard fragment %q\nquery:\n%s", fragment, query)
@@ -3,6 +3,8 @@ package db
 import (
 	"encoding/json"
 	"testing"
+
+	"github.com/google/uuid"
 )
 
 // TestTrigramFuzzySearchAgainstPostgres exercises the optional pg_trgm fuzzy fallback
@@ -36,7 +38,7 @@ func TestTrigramFuzzySearchAgainstPostgres(t *testing.T) {
 	}
 
 	// FTS-only sanity: exact/prefix search must always work regardless of pg_trgm.
-	tracks, total, err := repo.SearchRecordings(ctx, "Radiohead", 20, 0)
+	tracks, total, err := repo.SearchRecordings(ctx, uuid.Nil, "Radiohead", 20, 0)
 	if err != nil {
 		t.Fatalf("SearchRecordings exact: %v", err)
 	}
@@ -50,7 +52,7 @@ func TestTrigramFuzzySearchAgainstPostgres(t *testing.T) {
 	if !database.TrigramEnabled {
 		t.Log("pg_trgm NOT enabled on test DB: verified FTS path still returns results and does not error; fuzzy fallback skipped")
 		// A query that FTS cannot match returns empty (no fallback, no error) — not a 500.
-		got, gotTotal, err := repo.SearchRecordings(ctx, "Radiohede", 20, 0)
+		got, gotTotal, err := repo.SearchRecordings(ctx, uuid.Nil, "Radiohede", 20, 0)
 		if err != nil {
 			t.Fatalf("typo search without pg_trgm errored: %v", err)
 		}
@@ -64,7 +66,7 @@ func TestTrigramFuzzySearchAgainstPostgres(t *testing.T) {
 
 	// Typo of the artist: "Radiohede" does not share the "radiohead" lexeme, so FTS
 	// returns nothing and the trigram fallback must surface the track.
-	typoTracks, typoTotal, err := repo.SearchRecordings(ctx, "Radiohede", 20, 0)
+	typoTracks, typoTotal, err := repo.SearchRecordings(ctx, uuid.Nil, "Radiohede", 20, 0)
 	if err != nil {
 		t.Fatalf("SearchRecordings typo: %v", err)
 	}
@@ -76,7 +78,7 @@ func TestTrigramFuzzySearchAgainstPostgres(t *testing.T) {
 	}
 
 	// Typo via SearchArtists as well.
-	artists, artistTotal, err := repo.SearchArtists(ctx, "Radiohede", 20, 0)
+	artists, artistTotal, err := repo.SearchArtists(ctx, uuid.Nil, "Radiohede", 20, 0)
 	if err != nil {
 		t.Fatalf("SearchArtists typo: %v", err)
 	}
@@ -86,7 +88,7 @@ func TestTrigramFuzzySearchAgainstPostgres(t *testing.T) {
 
 	// Exact match still ranks first among fuzzy candidates: an exact query returns the
 	// exact track ahead of any looser match.
-	exact, _, err := repo.SearchRecordings(ctx, "Paranoid Android", 20, 0)
+	exact, _, err := repo.SearchRecordings(ctx, uuid.Nil, "Paranoid Android", 20, 0)
 	if err != nil {
 		t.Fatalf("SearchRecordings exact-title: %v", err)
 	}
@@ -96,7 +98,7 @@ func TestTrigramFuzzySearchAgainstPostgres(t *testing.T) {
 
 	// Typo via SearchReleases must preserve the stable numeric album id selected
 	// by the trigram fallback query.
-	releases, releaseTotal, err := repo.SearchReleases(ctx, "OK Compoter", 20, 0)
+	releases, releaseTotal, err := repo.SearchReleases(ctx, uuid.Nil, "OK Compoter", 20, 0)
 	if err != nil {
 		t.Fatalf("SearchReleases typo: %v", err)
 	}
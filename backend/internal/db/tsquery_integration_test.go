@@ -58,13 +58,13 @@ func TestSearchSanitizesSpecialCharactersAgainstPostgres(t *testing.T) {
 	// Before the fix, each of these produced "syntax error in tsquery" -> 500.
 	specials := []string{"AC/DC", "foo!", "a:b", "(x)", "foo &", "!", ":", "&|!:()", "back:in", "  "}
 	for _, q := range specials {
-		if _, _, err := trackRepo.SearchRecordings(ctx, q, 20, 0); err != nil {
+		if _, _, err := trackRepo.SearchRecordings(ctx, uuid.Nil, q, 20, 0); err != nil {
 			t.Errorf("SearchRecordings(%q) = error %v; want nil (no tsquery 500)", q, err)
 		}
-		if _, _, err := trackRepo.SearchArtists(ctx, q, 20, 0); err != nil {
+		if _, _, err := trackRepo.SearchArtists(ctx, uuid.Nil, q, 20, 0); err != nil {
 			t.Errorf("SearchArtists(%q) = error %v; want nil", q, err)
 		}
-		if _, _, err := trackRepo.SearchReleases(ctx, q, 20, 0); err != nil {
+		if _, _, err := trackRepo.SearchReleases(ctx, uuid.Nil, q, 20, 0); err != nil {
 			t.Errorf("SearchReleases(%q) = error %v; want nil", q, err)
 		}
 		if _, _, err := libRepo.GetUserLibrary(ctx, uuid.New(), LibraryQueryOptions{Search: q}); err != nil {
@@ -73,7 +73,7 @@ func TestSearchSanitizesSpecialCharactersAgainstPostgres(t *testing.T) {
 	}
 
 	// Prefix matching is preserved after sanitization: "High" finds "Highway to Hell".
-	tracks, total, err := trackRepo.SearchRecordings(ctx, "High", 20, 0)
+	tracks, total, err := trackRepo.SearchRecordings(ctx, uuid.Nil, "High", 20, 0)
 	if err != nil {
 		t.Fatalf("prefix search: %v", err)
 	}
@@ -122,3 +122,48 @@ func TestLibrarySearchEmptyTSQueryReturnsNoRows(t *testing.T) {
 		t.Fatalf("punctuation library search returned %d rows (total %d); want 0, not the full library", len(rows), total)
 	}
 }
+
+// TestSearchMatchesFoldedAndRomanizedText checks that accent-free queries find
+// accented names, CJK titles are found by a substring, and romanized sort tags
+// stored as aliases are searchable, both at insert and after the indexer
+// refreshes an edited track.
+func TestSearchMatchesFoldedAndRomanizedText(t *testing.T) {
+	database, ctx := newSearchTestDB(t)
+	trackRepo := NewTrackRepository(database)
+
+	bjork, _, err := trackRepo.CreateTrackFromMetadata(ctx, "Björk", "Jóga", "Homogenic", 305000,
+		WithMetadata(json.RawMessage(`{}`)))
+	if err != nil {
+		t.Fatalf("seed Björk: %v", err)
+	}
+	if _, _, err := trackRepo.CreateTrackFromMetadata(ctx, "東京事変", "群青日和", "教育", 231000,
+		WithMetadata(json.RawMessage(`{}`)),
+		WithSearchAliases("Gunjou Biyori", "Tokyo Jihen")); err != nil {
+		t.Fatalf("seed Tokyo Jihen: %v", err)
+	}
+
+	for query, want := range map[string]string{"bjork joga": "Jóga", "Gunjou": "群青日和", "青日": "群青日和"} {
+		tracks, total, err := trackRepo.SearchRecordings(ctx, uuid.Nil, query, 10, 0)
+		if err != nil {
+			t.Fatalf("Search(%q): %v", query, err)
+		}
+		if total != 1 || tracks[0].Title != want {
+			t.Fatalf("Search(%q) = %d results; want %q", query, total, want)
+		}
+	}
+	artists, _, err := trackRepo.SearchArtists(ctx, uuid.Nil, "Bjork", 10, 0)
+	if err != nil || len(artists) != 1 || artists[0].Name != "Björk" {
+		t.Fatalf("SearchArtists(Bjork) = %+v, %v", artists, err)
+	}
+
+	if err := trackRepo.UpdateMetadata(ctx, bjork.ID, &MetadataUpdate{Title: "Hyperballad"}, nil); err != nil {
+		t.Fatalf("UpdateMetadata: %v", err)
+	}
+	if indexed, err := trackRepo.IndexSearchText(ctx, 10); err != nil || indexed != 1 {
+		t.Fatalf("IndexSearchText = %d, %v; want 1 edited track", indexed, err)
+	}
+	tracks, total, err := trackRepo.SearchRecordings(ctx, uuid.Nil, "bjork hyperballad", 10, 0)
+	if err != nil || total != 1 || tracks[0].ID != bjork.ID {
+		t.Fatalf("Search after edit = %d results, %v", total, err)
+	}
+}
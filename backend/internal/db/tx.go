@@ -0,0 +1,137 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+
+	"github.com/google/uuid"
+)
+
+// querier is satisfied by *DB and *sql.Tx so a write can run on its own or
+// as one step of a Tx.
+type querier interface {
+	ExecContext(context.Context, string, ...any) (sql.Result, error)
+	QueryContext(context.Context, string, ...any) (*sql.Rows, error)
+	QueryRowContext(context.Context, string, ...any) *sql.Row
+}
+
+// Tx is a transaction with the repository writes that multi-step operations
+// chain together, such as storing a track, adding it to a library and
+// appending it to a playlist. Each method behaves like the repository method
+// of the same name, but nothing is visible to other connections until Commit,
+// and Rollback undoes every step. The events triggers write in the same
+// transaction, so the change journal gets all of the steps or none.
+type Tx struct {
+	tx      *sql.Tx
+	history PlaylistHistoryPolicy
+}
+
+// Begin starts a Tx whose playlist writes prune history by history, the
+// policy the PlaylistRepository was given. The caller must Commit or
+// Rollback it; see WithTx.
+func (db *DB) Begin(ctx context.Context, history PlaylistHistoryPolicy) (*Tx, error) {
+	tx, err := db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	return &Tx{tx: tx, history: history}, nil
+}
+
+// WithTx runs fn in a Tx, committing when fn returns nil and rolling back
+// otherwise.
+func (db *DB) WithTx(ctx context.Context, history PlaylistHistoryPolicy, fn func(tx *Tx) error) error {
+	tx, err := db.Begin(ctx, history)
+	if err != nil {
+		return err
+	}
+	defer tx.Rollback()
+	if err := fn(tx); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+// Commit makes every step visible at once.
+func (t *Tx) Commit() error {
+	return t.tx.Commit()
+}
+
+// Rollback undoes the transaction. After Commit it does nothing, so it can
+// be deferred.
+func (t *Tx) Rollback() error {
+	if err := t.tx.Rollback(); !errors.Is(err, sql.ErrTxDone) {
+		return err
+	}
+	return nil
+}
+
+// CreateTrackFromMetadata stores a track as TrackRepository.CreateTrackFromMetadata
+// does. It waits on any other transaction or TrackRepository.CreateOrGet
+// creating the same track, then returns the track that one committed rather
+// than failing on the identity hash.
+func (t *Tx) CreateTrackFromMetadata(ctx context.Context, artist, title, album string, durationMs int, opts ...TrackOption) (*Track, bool, error) {
+	return createOrGetTrack(ctx, t.tx, trackFromMetadata(artist, title, album, durationMs, opts...))
+}
+
+// ExecContext, QueryContext and QueryRowContext run statements in the
+// transaction, for repositories outside this package whose writes join it.
+func (t *Tx) ExecContext(ctx context.Context, query string, args ...any) (sql.Result, error) {
+	return t.tx.ExecContext(ctx, query, args...)
+}
+
+func (t *Tx) QueryContext(ctx context.Context, query string, args ...any) (*sql.Rows, error) {
+	return t.tx.QueryContext(ctx, query, args...)
+}
+
+func (t *Tx) QueryRowContext(ctx context.Context, query string, args ...any) *sql.Row {
+	return t.tx.QueryRowContext(ctx, query, args...)
+}
+
+// CreateUser inserts a user as UserRepository.Create does.
+func (t *Tx) CreateUser(ctx context.Context, user *User) error {
+	return createUser(ctx, t.tx, user)
+}
+
+// AddTrackToLibrary adds a track to a user's library as
+// LibraryRepository.AddTrackToLibrary does.
+func (t *Tx) AddTrackToLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*LibraryEntry, error) {
+	return addLibraryTrack(ctx, t.tx, userID, trackID, false)
+}
+
+// AddTrackToInbox adds a track to a user's library inbox as
+// LibraryRepository.AddTrackToInbox does.
+func (t *Tx) AddTrackToInbox(ctx context.Context, userID uuid.UUID, trackID int64) (*LibraryEntry, error) {
+	return addLibraryTrack(ctx, t.tx, userID, trackID, true)
+}
+
+// AddFavorite likes a track as LibraryRepository.AddFavorite does.
+func (t *Tx) AddFavorite(ctx context.Context, userID uuid.UUID, trackID int64, contextType, contextID string) error {
+	return addFavorite(ctx, t.tx, userID, trackID, contextType, contextID)
+}
+
+// CreatePlaylist inserts a playlist as PlaylistRepository.Create does.
+func (t *Tx) CreatePlaylist(ctx context.Context, playlist *Playlist) error {
+	return createPlaylist(ctx, t.tx, playlist, t.history)
+}
+
+// AddTrackToPlaylistAtPosition adds a track to a playlist as
+// PlaylistRepository.AddTrackAtPosition does.
+func (t *Tx) AddTrackToPlaylistAtPosition(ctx context.Context, playlistID, trackID int64, position int) error {
+	return addPlaylistTrackAtPosition(ctx, t.tx, playlistID, trackID, position, t.history)
+}
+
+// CompletePlaylistImportItem finishes a queued import item as
+// PlaylistSourceRepository.CompletePlaylistImportItem does.
+func (t *Tx) CompletePlaylistImportItem(ctx context.Context, itemID, trackID int64) error {
+	return completePlaylistImportItem(ctx, t.tx, itemID, trackID)
+}
+
+// AddTracksToPlaylist appends tracks to a playlist as
+// PlaylistRepository.AddTracks does.
+func (t *Tx) AddTracksToPlaylist(ctx context.Context, playlistID int64, trackIDs []int64) (AddTracksResult, error) {
+	if len(trackIDs) == 0 {
+		return AddTracksResult{Added: []int64{}, Skipped: []int64{}}, nil
+	}
+	return addPlaylistTracks(ctx, t.tx, playlistID, trackIDs, t.history)
+}
@@ -0,0 +1,144 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+// TestTxCommitsOrRollsBackEveryStepAgainstPostgres covers a track stored,
+// added to a library and appended to a playlist as one unit.
+func TestTxCommitsOrRollsBackEveryStepAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+	library := NewLibraryRepository(database)
+	playlists := NewPlaylistRepository(database)
+	user := seedPlayUser(t, database, "tx@example.test")
+
+	store := func(title string, fail error) (*Track, *Playlist, error) {
+		var track *Track
+		playlist := &Playlist{UserID: user, Name: title + " mix"}
+		err := database.WithTx(ctx, DefaultPlaylistHistoryPolicy, func(tx *Tx) error {
+			var err error
+			if track, _, err = tx.CreateTrackFromMetadata(ctx, "Artist", title, "Album", 180000); err != nil {
+				return err
+			}
+			if _, err := tx.AddTrackToLibrary(ctx, user, track.ID); err != nil {
+				return err
+			}
+			if err := tx.CreatePlaylist(ctx, playlist); err != nil {
+				return err
+			}
+			if _, err := tx.AddTracksToPlaylist(ctx, playlist.ID, []int64{track.ID}); err != nil {
+				return err
+			}
+			return fail
+		})
+		return track, playlist, err
+	}
+
+	errLater := errors.New("a later step failed")
+	track, playlist, err := store("Undone", errLater)
+	if !errors.Is(err, errLater) {
+		t.Fatalf("WithTx = %v; want the step's error", err)
+	}
+	if _, err := tracks.GetByID(ctx, track.ID); !errors.Is(err, ErrTrackNotFound) {
+		t.Errorf("rolled-back track lookup = %v; want ErrTrackNotFound", err)
+	}
+	if _, err := playlists.GetByID(ctx, playlist.ID); !errors.Is(err, ErrPlaylistNotFound) {
+		t.Errorf("rolled-back playlist lookup = %v; want ErrPlaylistNotFound", err)
+	}
+
+	track, playlist, err = store("Kept", nil)
+	if err != nil {
+		t.Fatalf("WithTx: %v", err)
+	}
+	if inLibrary, err := library.IsTrackInLibrary(ctx, user, track.ID); err != nil || !inLibrary {
+		t.Errorf("IsTrackInLibrary = %v, %v; want the committed track", inLibrary, err)
+	}
+	withTracks, err := playlists.GetByIDWithTracks(ctx, playlist.ID)
+	if err != nil || len(withTracks.Tracks) != 1 || withTracks.Tracks[0].ID != track.ID {
+		t.Fatalf("committed playlist = %+v, %v; want track %d", withTracks, err, track.ID)
+	}
+
+	// A second transaction storing the same recording gets the committed track.
+	err = database.WithTx(ctx, DefaultPlaylistHistoryPolicy, func(tx *Tx) error {
+		again, isNew, err := tx.CreateTrackFromMetadata(ctx, "Artist", "Kept", "Album", 180000)
+		if err != nil || isNew || again.ID != track.ID {
+			t.Errorf("CreateTrackFromMetadata again = %+v, %v, %v; want track %d", again, isNew, err, track.ID)
+		}
+		if _, err := tx.AddTrackToLibrary(ctx, user, track.ID); !errors.Is(err, ErrTrackAlreadyInLibrary) {
+			t.Errorf("AddTrackToLibrary again = %v; want ErrTrackAlreadyInLibrary", err)
+		}
+		return nil
+	})
+	if err != nil {
+		t.Fatalf("WithTx: %v", err)
+	}
+}
+
+func TestTxPrunesPlaylistHistoryByItsPolicyAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+	playlists := NewPlaylistRepository(database)
+	user := seedPlayUser(t, database, "tx-history@example.test")
+	trackID := seedPlayTrack(t, tracks, ctx, "Artist", "Pruned")
+
+	playlist := &Playlist{UserID: user, Name: "Pruned mix"}
+	err := database.WithTx(ctx, PlaylistHistoryPolicy{MaxAge: time.Hour, MaxRevisions: 1}, func(tx *Tx) error {
+		if err := tx.CreatePlaylist(ctx, playlist); err != nil {
+			return err
+		}
+		_, err := tx.AddTracksToPlaylist(ctx, playlist.ID, []int64{trackID})
+		return err
+	})
+	if err != nil {
+		t.Fatalf("WithTx: %v", err)
+	}
+	if revisions, total, err := playlists.History(ctx, playlist.ID, 10, 0); err != nil || total != 1 || revisions[0].Action != PlaylistActionTracksAdded {
+		t.Fatalf("History = %+v, %d, %v; want only the newest revision", revisions, total, err)
+	}
+}
+
+// TestCreateOrGetWaitsForTxCreatingTheSameTrackAgainstPostgres covers a track
+// created outside a transaction while a Tx is creating it too.
+func TestCreateOrGetWaitsForTxCreatingTheSameTrackAgainstPostgres(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+
+	tx, err := database.Begin(ctx, DefaultPlaylistHistoryPolicy)
+	if err != nil {
+		t.Fatalf("Begin: %v", err)
+	}
+	defer tx.Rollback()
+	created, isNew, err := tx.CreateTrackFromMetadata(ctx, "Artist", "Raced", "Album", 180000)
+	if err != nil || !isNew {
+		t.Fatalf("Tx.CreateTrackFromMetadata = %v, %v", isNew, err)
+	}
+
+	type result struct {
+		track *Track
+		isNew bool
+		err   error
+	}
+	done := make(chan result, 1)
+	go func() {
+		track, isNew, err := tracks.CreateTrackFromMetadata(ctx, "Artist", "Raced", "Album", 180000)
+		done <- result{track, isNew, err}
+	}()
+	select {
+	case r := <-done:
+		t.Fatalf("CreateTrackFromMetadata returned %+v before the Tx committed", r)
+	case <-time.After(200 * time.Millisecond):
+	}
+	if err := tx.Commit(); err != nil {
+		t.Fatalf("Commit: %v", err)
+	}
+	if r := <-done; r.err != nil || r.isNew || r.track.ID != created.ID {
+		t.Fatalf("CreateTrackFromMetadata = %+v, %v, %v; want track %d", r.track, r.isNew, r.err, created.ID)
+	}
+}
@@ -30,12 +30,16 @@ func NewUserRepository(db *DB) *UserRepository {
 }
 
 func (r *UserRepository) Create(ctx context.Context, user *User) error {
+	return createUser(ctx, r.db, user)
+}
+
+func createUser(ctx context.Context, q querier, user *User) error {
 	query := `
 		INSERT INTO users (id, email, username, password_hash, created_at, updated_at)
 		VALUES ($1, $2, $3, $4, $5, $6)
 	`
 
-	_, err := r.db.ExecContext(ctx, query,
+	_, err := q.ExecContext(ctx, query,
 		user.ID, user.Email, user.Username, user.PasswordHash, user.CreatedAt, user.UpdatedAt,
 	)
 	if err != nil {
@@ -0,0 +1,134 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// Scripts a user can prefer for track metadata.
+const (
+	// MetadataScriptOriginal shows names as tagged or as MusicBrainz titles them.
+	MetadataScriptOriginal = "original"
+	// MetadataScriptLatin shows a Latin-script alias for names in other scripts.
+	MetadataScriptLatin = "latin"
+)
+
+// UserSettings are a user's display, download and playback preferences.
+type UserSettings struct {
+	// MetadataScript is MetadataScriptOriginal or MetadataScriptLatin.
+	MetadataScript string
+	// MetadataLocale is the preferred language for titles and artist names,
+	// such as "en" or "ja". Empty keeps the original names.
+	MetadataLocale string
+	// TrimSilenceMinMs trims leading and trailing silence at least this long
+	// from the user's downloads, keeping the untrimmed file. 0 keeps
+	// downloads as they are.
+	TrimSilenceMinMs int
+	// NormalizeLoudness re-encodes the user's downloads to the server's
+	// target loudness.
+	NormalizeLoudness bool
+	// CrossfadeSeconds overlaps consecutive tracks by this many seconds
+	// during playback; 0 plays them one after another.
+	CrossfadeSeconds int
+	// Gapless drops the silence decoders pad tracks with, so back-to-back
+	// tracks play without a gap when there is no crossfade.
+	Gapless bool
+	// NormalizationTargetLUFS is the loudness players level tracks to, or 0
+	// to play tracks at their own loudness.
+	NormalizationTargetLUFS float64
+	// InboxPlaylistID is the playlist quick-added downloads are appended to,
+	// or 0 for none.
+	InboxPlaylistID int64
+	// InboxNewTracks holds the user's downloads in the library inbox until
+	// they are reviewed.
+	InboxNewTracks bool
+	// InboxInSearch and InboxInShuffle let tracks still in the inbox show up
+	// in library search and in shuffled listings.
+	InboxInSearch  bool
+	InboxInShuffle bool
+	// AcceptFollowers lets other users of the instance follow the user and
+	// see their public playlists. Turning it off keeps existing followers.
+	AcceptFollowers bool
+	// ShareListening shows followers what the user plays.
+	ShareListening bool
+	// ContextSuggestions biases home suggestions and artist radio toward
+	// what the user tends to play at the current time of day and week.
+	ContextSuggestions bool
+	UpdatedAt          time.Time
+}
+
+// DefaultUserSettings are the settings of a user who never saved any.
+func DefaultUserSettings() UserSettings {
+	return UserSettings{MetadataScript: MetadataScriptOriginal, Gapless: true}
+}
+
+// UserSettingsRepository stores per-user preferences.
+type UserSettingsRepository struct {
+	db *DB
+}
+
+func NewUserSettingsRepository(db *DB) *UserSettingsRepository {
+	return &UserSettingsRepository{db: db}
+}
+
+// Get returns the user's settings, or DefaultUserSettings when none are saved.
+func (r *UserSettingsRepository) Get(ctx context.Context, userID uuid.UUID) (UserSettings, error) {
+	settings := DefaultUserSettings()
+	var locale sql.NullString
+	var inbox sql.NullInt64
+	err := r.db.QueryRowContext(ctx, `
+		SELECT metadata_script, metadata_locale, trim_silence_min_ms, normalize_loudness, inbox_playlist_id,
+			   inbox_new_tracks, inbox_in_search, inbox_in_shuffle, crossfade_seconds, gapless,
+			   normalization_target_lufs, accept_followers, share_listening, context_suggestions, updated_at
+		FROM user_settings
+		WHERE user_id = $1
+	`, userID).Scan(&settings.MetadataScript, &locale, &settings.TrimSilenceMinMs, &settings.NormalizeLoudness, &inbox,
+		&settings.InboxNewTracks, &settings.InboxInSearch, &settings.InboxInShuffle, &settings.CrossfadeSeconds, &settings.Gapless,
+		&settings.NormalizationTargetLUFS, &settings.AcceptFollowers, &settings.ShareListening, &settings.ContextSuggestions, &settings.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return DefaultUserSettings(), nil
+	}
+	if err != nil {
+		return UserSettings{}, err
+	}
+	settings.MetadataLocale = locale.String
+	settings.InboxPlaylistID = inbox.Int64
+	return settings, nil
+}
+
+// Update saves the user's settings and returns them as stored.
+func (r *UserSettingsRepository) Update(ctx context.Context, userID uuid.UUID, settings UserSettings) (UserSettings, error) {
+	err := r.db.QueryRowContext(ctx, `
+		INSERT INTO user_settings (user_id, metadata_script, metadata_locale, trim_silence_min_ms, normalize_loudness, inbox_playlist_id,
+			inbox_new_tracks, inbox_in_search, inbox_in_shuffle, crossfade_seconds, gapless, normalization_target_lufs,
+			accept_followers, share_listening, context_suggestions, updated_at)
+		VALUES ($1, $2, NULLIF($3, ''), $4, $5, NULLIF($6, 0), $7, $8, $9, $10, $11, $12, $13, $14, $15, NOW())
+		ON CONFLICT (user_id) DO UPDATE SET
+			metadata_script = EXCLUDED.metadata_script,
+			metadata_locale = EXCLUDED.metadata_locale,
+			trim_silence_min_ms = EXCLUDED.trim_silence_min_ms,
+			normalize_loudness = EXCLUDED.normalize_loudness,
+			inbox_playlist_id = EXCLUDED.inbox_playlist_id,
+			inbox_new_tracks = EXCLUDED.inbox_new_tracks,
+			inbox_in_search = EXCLUDED.inbox_in_search,
+			inbox_in_shuffle = EXCLUDED.inbox_in_shuffle,
+			crossfade_seconds = EXCLUDED.crossfade_seconds,
+			gapless = EXCLUDED.gapless,
+			normalization_target_lufs = EXCLUDED.normalization_target_lufs,
+			accept_followers = EXCLUDED.accept_followers,
+			share_listening = EXCLUDED.share_listening,
+			context_suggestions = EXCLUDED.context_suggestions,
+			updated_at = NOW()
+		RETURNING updated_at
+	`, userID, settings.MetadataScript, settings.MetadataLocale, settings.TrimSilenceMinMs, settings.NormalizeLoudness, settings.InboxPlaylistID,
+		settings.InboxNewTracks, settings.InboxInSearch, settings.InboxInShuffle, settings.CrossfadeSeconds, settings.Gapless,
+		settings.NormalizationTargetLUFS, settings.AcceptFollowers, settings.ShareListening, settings.ContextSuggestions).Scan(&settings.UpdatedAt)
+	if err != nil {
+		return UserSettings{}, err
+	}
+	return settings, nil
+}
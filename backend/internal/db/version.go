@@ -0,0 +1,32 @@
+package db
+
+import (
+	"context"
+	"database/sql"
+	"strconv"
+	"strings"
+)
+
+// rowQueryer is satisfied by *DB and *sql.Tx so version reads can run inside
+// the transaction that checks them.
+type rowQueryer interface {
+	QueryRowContext(context.Context, string, ...any) *sql.Row
+}
+
+// versionToken folds row counts and latest-change timestamps into an opaque
+// token for conditional requests. Inserts and deletes move a count or a
+// timestamp; updates move a timestamp.
+func versionToken(counts []int64, times ...sql.NullTime) string {
+	parts := make([]string, 0, len(counts)+len(times))
+	for _, count := range counts {
+		parts = append(parts, strconv.FormatInt(count, 10))
+	}
+	for _, t := range times {
+		if t.Valid {
+			parts = append(parts, strconv.FormatInt(t.Time.UnixMicro(), 10))
+		} else {
+			parts = append(parts, "0")
+		}
+	}
+	return strings.Join(parts, ".")
+}
@@ -0,0 +1,153 @@
+package db
+
+import (
+	"context"
+	"crypto/rand"
+	"crypto/sha256"
+	"database/sql"
+	"encoding/hex"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+var ErrYearReviewCardNotFound = errors.New("year in review card not found")
+
+// YearInReview sums up the user's plays in a calendar year, in UTC.
+// Minutes count each play as the track's full duration.
+type YearInReview struct {
+	Year            int
+	Plays           int
+	MinutesListened int
+	UniqueTracks    int
+	UniqueArtists   int
+	TopArtists      []YearTopArtist
+	TopTracks       []TopTrack
+}
+
+// YearTopArtist is one of the year's most played artists. Tracks are
+// grouped by MusicBrainz artist when known and by name otherwise; Name is
+// the spelling played most.
+type YearTopArtist struct {
+	ArtistID        uuid.NullUUID
+	Name            string
+	Plays           int
+	MinutesListened int
+}
+
+// YearInReview sums up the user's plays in year, with its limit most played
+// artists and tracks.
+func (r *PlayEventRepository) YearInReview(ctx context.Context, userID uuid.UUID, year, limit int) (*YearInReview, error) {
+	limit = clampTopLimit(limit)
+	since := time.Date(year, time.January, 1, 0, 0, 0, 0, time.UTC)
+	until := since.AddDate(1, 0, 0)
+	plays := `
+		FROM ` + r.playCounts() + `
+		JOIN tracks t ON t.id = play_counts.track_id
+		WHERE play_counts.user_id = $1 AND play_counts.played_at >= $2 AND play_counts.played_at < $3`
+
+	review := &YearInReview{Year: year}
+	if err := r.db.QueryRowContext(ctx, `
+		SELECT COALESCE(SUM(play_counts.play_count), 0),
+			   FLOOR(COALESCE(SUM(play_counts.play_count::bigint * t.duration_ms), 0) / 60000)::bigint,
+			   COUNT(DISTINCT play_counts.track_id),
+			   COUNT(DISTINCT COALESCE(t.mb_artist_id::text, NULLIF(lower(btrim(t.artist)), '')))
+		`+plays, userID, since, until).Scan(
+		&review.Plays, &review.MinutesListened, &review.UniqueTracks, &review.UniqueArtists,
+	); err != nil {
+		return nil, err
+	}
+	if review.Plays == 0 {
+		return review, nil
+	}
+
+	rows, err := r.db.QueryContext(ctx, `
+		SELECT (array_agg(t.mb_artist_id))[1],
+			   (array_agg(btrim(t.artist) ORDER BY play_counts.play_count DESC, t.id))[1],
+			   SUM(play_counts.play_count) AS plays,
+			   FLOOR(COALESCE(SUM(play_counts.play_count::bigint * t.duration_ms), 0) / 60000)::bigint
+		`+plays+` AND btrim(COALESCE(t.artist, '')) <> ''
+		GROUP BY COALESCE(t.mb_artist_id::text, lower(btrim(t.artist)))
+		ORDER BY plays DESC, 2 ASC
+		LIMIT $4
+	`, userID, since, until, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var artist YearTopArtist
+		if err := rows.Scan(&artist.ArtistID, &artist.Name, &artist.Plays, &artist.MinutesListened); err != nil {
+			return nil, err
+		}
+		review.TopArtists = append(review.TopArtists, artist)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	review.TopTracks, err = r.topTracks(ctx, "user_id = $1 AND played_at >= $2 AND played_at < $3", limit, userID, since, until)
+	if err != nil {
+		return nil, err
+	}
+	return review, nil
+}
+
+// YearReviewCardRepository keeps the links users share their year-in-review
+// cards by.
+type YearReviewCardRepository struct {
+	db *DB
+}
+
+func NewYearReviewCardRepository(db *DB) *YearReviewCardRepository {
+	return &YearReviewCardRepository{db: db}
+}
+
+// Create shares the user's card for year under a new random token, which
+// is returned once; only its hash is stored.
+func (r *YearReviewCardRepository) Create(ctx context.Context, userID uuid.UUID, year int) (string, error) {
+	secret := make([]byte, 32)
+	if _, err := rand.Read(secret); err != nil {
+		return "", err
+	}
+	token := hex.EncodeToString(secret)
+	if _, err := r.db.ExecContext(ctx, `
+		INSERT INTO year_review_cards (token_hash, user_id, year)
+		VALUES ($1, $2, $3)
+	`, hashCardToken(token), userID, year); err != nil {
+		return "", err
+	}
+	return token, nil
+}
+
+// Resolve returns whose card for which year token shares.
+func (r *YearReviewCardRepository) Resolve(ctx context.Context, token string) (uuid.UUID, int, error) {
+	var userID uuid.UUID
+	var year int
+	err := r.db.QueryRowContext(ctx, `
+		SELECT user_id, year FROM year_review_cards WHERE token_hash = $1
+	`, hashCardToken(token)).Scan(&userID, &year)
+	if errors.Is(err, sql.ErrNoRows) {
+		return uuid.Nil, 0, ErrYearReviewCardNotFound
+	}
+	return userID, year, err
+}
+
+// Revoke stops every link to the user's card for year, returning how many
+// there were.
+func (r *YearReviewCardRepository) Revoke(ctx context.Context, userID uuid.UUID, year int) (int, error) {
+	result, err := r.db.ExecContext(ctx, `
+		DELETE FROM year_review_cards WHERE user_id = $1 AND year = $2
+	`, userID, year)
+	if err != nil {
+		return 0, err
+	}
+	n, err := result.RowsAffected()
+	return int(n), err
+}
+
+func hashCardToken(token string) string {
+	hash := sha256.Sum256([]byte(token))
+	return hex.EncodeToString(hash[:])
+}
@@ -0,0 +1,75 @@
+package db
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+func TestYearInReviewSumsTheYear(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	tracks := NewTrackRepository(database)
+	repo := NewPlayEventRepository(database)
+	userID := seedPlayUser(t, database, "review@example.test")
+
+	anthem := seedPlayTrack(t, tracks, ctx, "Artist A", "Anthem")
+	ballad := seedPlayTrack(t, tracks, ctx, "Artist A", "Ballad")
+	other := seedPlayTrack(t, tracks, ctx, "Artist B", "Other")
+	june := time.Date(2025, time.June, 1, 12, 0, 0, 0, time.UTC)
+	for i := range 3 {
+		insertPlayAt(t, database, userID, anthem, june.Add(time.Duration(i)*time.Hour))
+	}
+	insertPlayAt(t, database, userID, ballad, june)
+	insertPlayAt(t, database, userID, other, june)
+	insertPlayAt(t, database, userID, other, june.Add(time.Hour))
+	insertPlayAt(t, database, userID, other, time.Date(2024, time.December, 31, 23, 0, 0, 0, time.UTC))
+	insertPlayAt(t, database, userID, other, time.Date(2026, time.January, 1, 0, 0, 0, 0, time.UTC))
+
+	review, err := repo.YearInReview(ctx, userID, 2025, 5)
+	if err != nil {
+		t.Fatalf("YearInReview() error = %v", err)
+	}
+	if review.Plays != 6 || review.MinutesListened != 20 || review.UniqueTracks != 3 || review.UniqueArtists != 2 {
+		t.Fatalf("review = %+v; want 6 plays, 20 minutes, 3 tracks, 2 artists", review)
+	}
+	if len(review.TopArtists) != 2 || review.TopArtists[0].Name != "Artist A" || review.TopArtists[0].Plays != 4 || review.TopArtists[0].MinutesListened != 13 {
+		t.Fatalf("top artists = %+v", review.TopArtists)
+	}
+	if len(review.TopTracks) != 3 || review.TopTracks[0].ID != anthem || review.TopTracks[0].PlayCount != 3 {
+		t.Fatalf("top tracks = %+v", review.TopTracks)
+	}
+
+	empty, err := repo.YearInReview(ctx, userID, 2023, 5)
+	if err != nil || empty.Plays != 0 || len(empty.TopArtists) != 0 {
+		t.Fatalf("YearInReview(2023) = %+v, %v; want nothing", empty, err)
+	}
+}
+
+func TestYearReviewCardLinks(t *testing.T) {
+	database := newIsolatedTestDB(t)
+	ctx := context.Background()
+	repo := NewYearReviewCardRepository(database)
+	userID := seedPlayUser(t, database, "cards@example.test")
+
+	token, err := repo.Create(ctx, userID, 2025)
+	if err != nil || len(token) != 64 {
+		t.Fatalf("Create() = %q, %v", token, err)
+	}
+	owner, year, err := repo.Resolve(ctx, token)
+	if err != nil || owner != userID || year != 2025 {
+		t.Fatalf("Resolve() = %v, %d, %v", owner, year, err)
+	}
+	var stored string
+	if err := database.QueryRow(`SELECT token_hash FROM year_review_cards`).Scan(&stored); err != nil || stored == token {
+		t.Fatalf("stored token = %q, %v; want only its hash", stored, err)
+	}
+
+	if n, err := repo.Revoke(ctx, userID, 2025); err != nil || n != 1 {
+		t.Fatalf("Revoke() = %d, %v", n, err)
+	}
+	if _, _, err := repo.Resolve(ctx, token); !errors.Is(err, ErrYearReviewCardNotFound) {
+		t.Fatalf("Resolve() after revoking error = %v, want ErrYearReviewCardNotFound", err)
+	}
+}
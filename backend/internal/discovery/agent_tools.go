@@ -141,6 +141,9 @@ func NewAgentToolsHandler(cfg AgentToolsConfig) *AgentToolsHandler {
 		cfg.HTTPClient = &http.Client{Timeout: defaultFirecrawlTimeout}
 	}
 	clientCopy := *cfg.HTTPClient
+	if clientCopy.Timeout <= 0 {
+		clientCopy.Timeout = defaultFirecrawlTimeout
+	}
 	clientCopy.CheckRedirect = func(_ *http.Request, _ []*http.Request) error { return http.ErrUseLastResponse }
 	cfg.HTTPClient = &clientCopy
 	if cfg.FirecrawlURL == "" {
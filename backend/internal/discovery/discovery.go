@@ -18,6 +18,7 @@ import (
 
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/musicbrainz"
 )
 
@@ -170,6 +171,21 @@ func NewService(cfg ServiceConfig) *Service {
 	}
 }
 
+// proxiedProvider is a provider whose searches can go through a proxy.
+type proxiedProvider interface {
+	useProxy(proxy string)
+}
+
+// UseProxy sends the searches of every provider that supports it, the yt-dlp
+// ones, through proxy, an http, https or socks5 URL.
+func (s *Service) UseProxy(proxy string) {
+	for _, provider := range s.providers {
+		if proxied, ok := provider.(proxiedProvider); ok {
+			proxied.useProxy(proxy)
+		}
+	}
+}
+
 func NewDefaultService() *Service {
 	return NewDefaultServiceWithCatalogAndSourceQualityJudge(nil, nil)
 }
@@ -656,7 +672,7 @@ func writeJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writeError(w http.ResponseWriter, status int, code, message string) {
-	writeJSON(w, status, map[string]interface{}{"code": code, "message": message})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
 
 // YTDLPProvider shells out to yt-dlp for local dogfood discovery. If yt-dlp is
@@ -666,6 +682,7 @@ type YTDLPProvider struct {
 	prefix    string
 	urlPrefix string
 	music     bool
+	proxy     string
 }
 
 func NewYTDLPProvider(name, prefix, urlPrefix string) *YTDLPProvider {
@@ -754,9 +771,15 @@ func (p *YTDLPProvider) commandArgs(query string, limit int) []string {
 	// Search surfaces expose a playlist-like result set. Flattening it prevents
 	// yt-dlp from resolving every result's full watch metadata before discovery
 	// can rank candidates under the provider deadline.
-	return []string{"--flat-playlist", "--playlist-end", strconv.Itoa(limit), "--dump-json", "--skip-download", p.searchArg(query, limit)}
+	args := []string{"--flat-playlist", "--playlist-end", strconv.Itoa(limit), "--dump-json", "--skip-download"}
+	if p.proxy != "" {
+		args = append(args, "--proxy", p.proxy)
+	}
+	return append(args, p.searchArg(query, limit))
 }
 
+func (p *YTDLPProvider) useProxy(proxy string) { p.proxy = proxy }
+
 func ytdlpCandidateMetadata(raw map[string]interface{}, provider string, music bool) map[string]interface{} {
 	metadata := map[string]interface{}{"providerRawType": stringValue(raw, "_type")}
 	if music {
@@ -787,6 +810,14 @@ func newCombinedProvider(name string, providers []Provider) *combinedProvider {
 
 func (p *combinedProvider) Name() string { return p.name }
 
+func (p *combinedProvider) useProxy(proxy string) {
+	for _, provider := range p.providers {
+		if proxied, ok := provider.(proxiedProvider); ok {
+			proxied.useProxy(proxy)
+		}
+	}
+}
+
 func (p *combinedProvider) Search(ctx context.Context, query string, limit int) ([]Candidate, error) {
 	type result struct {
 		items []Candidate
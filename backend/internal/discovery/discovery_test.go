@@ -7,6 +7,7 @@ import (
 	"net/http"
 	"net/http/httptest"
 	"reflect"
+	"slices"
 	"strings"
 	"sync/atomic"
 	"testing"
@@ -187,6 +188,18 @@ func TestYouTubeSearchUsesFlatPlaylistAcquisition(t *testing.T) {
 	}
 }
 
+func TestUseProxyReachesCombinedYTDLPProviders(t *testing.T) {
+	service := NewDefaultService()
+	service.UseProxy("socks5://vpn:1080")
+	youtube := service.providers["youtube"].(*combinedProvider)
+	for _, provider := range youtube.providers {
+		args := provider.(*YTDLPProvider).commandArgs("query", 5)
+		if !slices.Contains(args, "--proxy") || !slices.Contains(args, "socks5://vpn:1080") {
+			t.Fatalf("yt-dlp args = %#v, want the proxy", args)
+		}
+	}
+}
+
 func TestYouTubeMusicCandidatesNeverExceedRequestedLimit(t *testing.T) {
 	provider := NewYouTubeMusicProvider("youtube")
 	output := strings.Join([]string{
@@ -0,0 +1,236 @@
+package diskspace
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net/http"
+	"sync"
+	"time"
+)
+
+const (
+	defaultInterval = 30 * time.Second
+	notifyTimeout   = 5 * time.Second
+)
+
+// Events sent to notifiers when free space crosses the threshold.
+const (
+	EventLow       = "disk_space_low"
+	EventRecovered = "disk_space_recovered"
+)
+
+// ErrLowSpace is returned by Admit while free space is below the threshold.
+var ErrLowSpace = errors.New("not enough free disk space")
+
+// Usage is a filesystem's capacity in bytes.
+type Usage struct {
+	TotalBytes uint64
+	FreeBytes  uint64
+}
+
+// Status is the monitor's latest reading.
+type Status struct {
+	Path         string
+	TotalBytes   uint64
+	FreeBytes    uint64
+	MinFreeBytes uint64
+	Low          bool
+	CheckedAt    time.Time
+	// Err is set when the latest reading failed. The previous reading, and
+	// whether it was low, is kept until a reading succeeds.
+	Err error
+}
+
+// Event reports free space crossing the threshold in either direction.
+type Event struct {
+	Type   string
+	Status Status
+}
+
+// Notifier receives threshold events, such as by posting them to a webhook.
+type Notifier interface {
+	Notify(ctx context.Context, event Event) error
+}
+
+// NotifierFunc adapts a function to Notifier.
+type NotifierFunc func(ctx context.Context, event Event) error
+
+func (f NotifierFunc) Notify(ctx context.Context, event Event) error {
+	return f(ctx, event)
+}
+
+// Config configures a Monitor.
+type Config struct {
+	// Path is a directory on the filesystem new downloads are written to.
+	Path string
+	// MinFreeBytes is the free space below which new work is refused.
+	MinFreeBytes uint64
+	// Interval is how often Run takes a reading. Zero uses 30 seconds.
+	Interval  time.Duration
+	Notifiers []Notifier
+}
+
+// Monitor watches free space on the filesystem downloads are written to and
+// refuses new work while it is low, so jobs do not leave half-written files.
+type Monitor struct {
+	path         string
+	minFreeBytes uint64
+	interval     time.Duration
+	notifiers    []Notifier
+	usage        func(path string) (Usage, error)
+
+	mu     sync.Mutex
+	status Status
+}
+
+func NewMonitor(cfg Config) *Monitor {
+	return newMonitor(cfg, statfs)
+}
+
+func newMonitor(cfg Config, usage func(path string) (Usage, error)) *Monitor {
+	interval := cfg.Interval
+	if interval <= 0 {
+		interval = defaultInterval
+	}
+	return &Monitor{
+		path:         cfg.Path,
+		minFreeBytes: cfg.MinFreeBytes,
+		interval:     interval,
+		notifiers:    cfg.Notifiers,
+		usage:        usage,
+		status:       Status{Path: cfg.Path, MinFreeBytes: cfg.MinFreeBytes},
+	}
+}
+
+// Check takes a reading and notifies when free space has crossed the
+// threshold since the last one. Once low, space counts as recovered only when
+// it is a tenth above the threshold, so a job finishing near the line does not
+// flap warnings.
+func (m *Monitor) Check(ctx context.Context) Status {
+	usage, err := m.usage(m.path)
+
+	m.mu.Lock()
+	status := m.status
+	status.CheckedAt = time.Now()
+	status.Err = err
+	if err == nil {
+		status.TotalBytes = usage.TotalBytes
+		status.FreeBytes = usage.FreeBytes
+		if status.Low {
+			status.Low = usage.FreeBytes < m.minFreeBytes+m.minFreeBytes/10
+		} else {
+			status.Low = usage.FreeBytes < m.minFreeBytes
+		}
+	}
+	changed := status.Low != m.status.Low
+	m.status = status
+	m.mu.Unlock()
+
+	if changed {
+		event := Event{Type: EventRecovered, Status: status}
+		if status.Low {
+			event.Type = EventLow
+		}
+		m.notify(event)
+	}
+	return status
+}
+
+// Status returns the latest reading without taking a new one.
+func (m *Monitor) Status() Status {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	return m.status
+}
+
+// Admit returns ErrLowSpace while free space is below the threshold. A failed
+// reading does not refuse work on its own.
+func (m *Monitor) Admit(ctx context.Context) error {
+	status := m.Check(ctx)
+	if !status.Low {
+		return nil
+	}
+	return fmt.Errorf("%w: %d MiB free on %s, %d MiB required", ErrLowSpace, status.FreeBytes>>20, status.Path, status.MinFreeBytes>>20)
+}
+
+// Run takes a reading every interval until ctx is canceled, so warnings go
+// out even when no new work arrives.
+func (m *Monitor) Run(ctx context.Context) {
+	ticker := time.NewTicker(m.interval)
+	defer ticker.Stop()
+	for {
+		if status := m.Check(ctx); status.Err != nil {
+			log.Printf("Failed to read free disk space on %s: %v", m.path, status.Err)
+		}
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// notify delivers event to every notifier in the background so a slow
+// webhook never holds up admission.
+func (m *Monitor) notify(event Event) {
+	for _, notifier := range m.notifiers {
+		go func(notifier Notifier) {
+			ctx, cancel := context.WithTimeout(context.Background(), notifyTimeout)
+			defer cancel()
+			if err := notifier.Notify(ctx, event); err != nil {
+				log.Printf("Failed to deliver %s notification: %v", event.Type, err)
+			}
+		}(notifier)
+	}
+}
+
+// Webhook posts threshold events as JSON to an operator URL.
+type Webhook struct {
+	url    string
+	client *http.Client
+}
+
+func NewWebhook(url string) *Webhook {
+	return &Webhook{url: url, client: &http.Client{Timeout: notifyTimeout}}
+}
+
+type webhookPayload struct {
+	Event        string    `json:"event"`
+	Path         string    `json:"path"`
+	FreeBytes    uint64    `json:"freeBytes"`
+	TotalBytes   uint64    `json:"totalBytes"`
+	MinFreeBytes uint64    `json:"minFreeBytes"`
+	CheckedAt    time.Time `json:"checkedAt"`
+}
+
+func (w *Webhook) Notify(ctx context.Context, event Event) error {
+	body, err := json.Marshal(webhookPayload{
+		Event:        event.Type,
+		Path:         event.Status.Path,
+		FreeBytes:    event.Status.FreeBytes,
+		TotalBytes:   event.Status.TotalBytes,
+		MinFreeBytes: event.Status.MinFreeBytes,
+		CheckedAt:    event.Status.CheckedAt,
+	})
+	if err != nil {
+		return err
+	}
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, w.url, bytes.NewReader(body))
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Content-Type", "application/json")
+	resp, err := w.client.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode >= http.StatusMultipleChoices {
+		return fmt.Errorf("webhook returned %s", resp.Status)
+	}
+	return nil
+}
@@ -0,0 +1,103 @@
+package diskspace
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+)
+
+func TestMonitorAdmitsUntilSpaceRunsLowAndNotifiesOnEachCrossing(t *testing.T) {
+	free := uint64(2000)
+	var readErr error
+	events := make(chan Event, 4)
+	m := newMonitor(Config{
+		Path:         "/downloads",
+		MinFreeBytes: 1000,
+		Notifiers: []Notifier{NotifierFunc(func(ctx context.Context, event Event) error {
+			events <- event
+			return nil
+		})},
+	}, func(path string) (Usage, error) {
+		return Usage{TotalBytes: 10000, FreeBytes: free}, readErr
+	})
+	expectEvent := func(want string) {
+		t.Helper()
+		select {
+		case event := <-events:
+			if event.Type != want {
+				t.Fatalf("event = %s, want %s", event.Type, want)
+			}
+		case <-time.After(time.Second):
+			t.Fatalf("no %s event", want)
+		}
+	}
+
+	if err := m.Admit(context.Background()); err != nil {
+		t.Fatalf("Admit() with free space = %v", err)
+	}
+
+	free = 900
+	if err := m.Admit(context.Background()); !errors.Is(err, ErrLowSpace) {
+		t.Fatalf("Admit() below threshold = %v, want ErrLowSpace", err)
+	}
+	expectEvent(EventLow)
+
+	// Space just above the threshold is not enough to recover.
+	free = 1050
+	if err := m.Admit(context.Background()); !errors.Is(err, ErrLowSpace) {
+		t.Fatalf("Admit() just above threshold = %v, want ErrLowSpace", err)
+	}
+
+	// A failed reading keeps the last known state.
+	readErr = errors.New("stale file handle")
+	if status := m.Check(context.Background()); !status.Low || status.Err == nil {
+		t.Fatalf("status after failed reading = %#v", status)
+	}
+
+	readErr = nil
+	free = 1200
+	if err := m.Admit(context.Background()); err != nil {
+		t.Fatalf("Admit() after recovery = %v", err)
+	}
+	expectEvent(EventRecovered)
+
+	select {
+	case event := <-events:
+		t.Fatalf("unexpected event %s", event.Type)
+	default:
+	}
+}
+
+func TestWebhookPostsEvent(t *testing.T) {
+	var got webhookPayload
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if err := json.NewDecoder(r.Body).Decode(&got); err != nil {
+			t.Errorf("decode: %v", err)
+		}
+		w.WriteHeader(http.StatusNoContent)
+	}))
+	defer server.Close()
+
+	err := NewWebhook(server.URL).Notify(context.Background(), Event{
+		Type:   EventLow,
+		Status: Status{Path: "/downloads", FreeBytes: 900, TotalBytes: 10000, MinFreeBytes: 1000},
+	})
+	if err != nil {
+		t.Fatalf("Notify() error = %v", err)
+	}
+	if got.Event != EventLow || got.Path != "/downloads" || got.FreeBytes != 900 || got.MinFreeBytes != 1000 {
+		t.Fatalf("payload = %#v", got)
+	}
+
+	failing := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusBadGateway)
+	}))
+	defer failing.Close()
+	if err := NewWebhook(failing.URL).Notify(context.Background(), Event{Type: EventLow}); err == nil {
+		t.Fatal("Notify() to a failing webhook returned nil")
+	}
+}
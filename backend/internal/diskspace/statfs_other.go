@@ -0,0 +1,9 @@
+//go:build !linux && !darwin
+
+package diskspace
+
+import "errors"
+
+func statfs(path string) (Usage, error) {
+	return Usage{}, errors.New("disk space monitoring is not supported on this platform")
+}
@@ -0,0 +1,15 @@
+//go:build linux || darwin
+
+package diskspace
+
+import "syscall"
+
+func statfs(path string) (Usage, error) {
+	var st syscall.Statfs_t
+	if err := syscall.Statfs(path, &st); err != nil {
+		return Usage{}, err
+	}
+	blockSize := uint64(st.Bsize)
+	// Bavail excludes blocks reserved for root, which downloads cannot use.
+	return Usage{TotalBytes: st.Blocks * blockSize, FreeBytes: st.Bavail * blockSize}, nil
+}
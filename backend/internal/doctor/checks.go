@@ -0,0 +1,333 @@
+package doctor
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/redis/go-redis/v9"
+
+	"github.com/openmusicplayer/backend/internal/config"
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/diskspace"
+	"github.com/openmusicplayer/backend/internal/egress"
+	"github.com/openmusicplayer/backend/internal/middleware"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+	"github.com/openmusicplayer/backend/internal/storage"
+	"github.com/openmusicplayer/backend/internal/tools"
+)
+
+// Doctor builds the checks for one loaded configuration. Close releases the
+// database connection the database check opens.
+type Doctor struct {
+	cfg *config.Config
+
+	// Seams for tests; New wires the real implementations.
+	lookPath    func(file string) (string, error)
+	command     func(ctx context.Context, name string, args ...string) ([]byte, error)
+	httpClient  *http.Client
+	musicBrainz *musicbrainz.Client
+
+	proxies  *egress.Proxies
+	database *db.DB
+}
+
+// New returns a Doctor for cfg.
+func New(cfg *config.Config) *Doctor {
+	// An invalid EXTERNAL_PROXIES is reported by the config check; the other
+	// checks then connect directly.
+	proxies, _ := egress.Parse(cfg.ExternalProxies)
+	return &Doctor{
+		cfg:      cfg,
+		lookPath: tools.NewManager(tools.Config{Dir: cfg.ToolsDir}).Locate,
+		command: func(ctx context.Context, name string, args ...string) ([]byte, error) {
+			return exec.CommandContext(ctx, name, args...).CombinedOutput()
+		},
+		httpClient:  &http.Client{},
+		musicBrainz: musicbrainz.NewClient(nil, musicbrainz.WithTransport(proxies.Transport(egress.MusicBrainz))),
+		proxies:     proxies,
+	}
+}
+
+// Close releases resources opened by the checks.
+func (d *Doctor) Close() error {
+	if d.database == nil {
+		return nil
+	}
+	err := d.database.Close()
+	d.database = nil
+	return err
+}
+
+// Checks returns every check, in the order omp doctor runs them.
+func (d *Doctor) Checks() []Check {
+	checks := []Check{
+		{Name: "config", Run: d.checkConfig},
+		{Name: "database", Run: d.checkDatabase},
+		{Name: "migrations", Run: d.checkMigrations},
+		{Name: "redis", Run: d.checkRedis},
+		{Name: "object-storage", Run: d.checkObjectStorage},
+		{Name: "download-dir", Run: d.checkDownloadDir},
+	}
+	checks = append(checks, d.toolChecks()...)
+	checks = append(checks, d.proxyChecks()...)
+	checks = append(checks, Check{Name: "musicbrainz", Run: d.checkMusicBrainz})
+	return append(checks, d.endpointChecks()...)
+}
+
+// StartupChecks returns the checks that need no network services, so the
+// server can run them before connecting to anything.
+func (d *Doctor) StartupChecks() []Check {
+	checks := []Check{
+		{Name: "config", Run: d.checkConfig},
+		{Name: "download-dir", Run: d.checkDownloadDir},
+	}
+	return append(checks, d.toolChecks()...)
+}
+
+func (d *Doctor) checkConfig(ctx context.Context) Result {
+	var problems []string
+	if err := d.cfg.ValidateResearchRollout(); err != nil {
+		problems = append(problems, err.Error())
+	}
+	if _, err := middleware.NewProxy(d.cfg.TrustedProxies, d.cfg.BasePath); err != nil {
+		problems = append(problems, "TRUSTED_PROXIES or BASE_PATH: "+err.Error())
+	}
+	if _, err := egress.Parse(d.cfg.ExternalProxies); err != nil {
+		problems = append(problems, "EXTERNAL_PROXIES: "+err.Error())
+	}
+	if info, err := os.Stat(d.cfg.DiskMonitorPath); err != nil || !info.IsDir() {
+		problems = append(problems, fmt.Sprintf("DISK_MONITOR_PATH %q is not a directory", d.cfg.DiskMonitorPath))
+	}
+	if len(problems) > 0 {
+		return fail("correct the listed settings in the environment or .env file", "%s", strings.Join(problems, "; "))
+	}
+	if os.Getenv("JWT_SECRET") == "" {
+		return warn("set JWT_SECRET to a long random value", "JWT_SECRET is not set; a random secret signs out every session on restart")
+	}
+	return ok("valid")
+}
+
+func (d *Doctor) checkDatabase(ctx context.Context) Result {
+	d.Close()
+	database, err := db.NewWithConfig(db.ConnConfig{
+		Host:     d.cfg.DBHost,
+		Port:     d.cfg.DBPort,
+		User:     d.cfg.DBUser,
+		Password: d.cfg.DBPassword,
+		Name:     d.cfg.DBName,
+	}, db.PoolConfig{MaxOpenConns: 2, ConnectTimeout: d.cfg.DBConnectTimeout})
+	if err != nil {
+		return fail("check DB_HOST, DB_PORT, DB_USER, DB_PASSWORD and DB_NAME, and that PostgreSQL is running",
+			"cannot connect to %s:%s/%s: %v", d.cfg.DBHost, d.cfg.DBPort, d.cfg.DBName, err)
+	}
+	d.database = database
+	var serverVersion string
+	if err := database.QueryRowContext(ctx, `SHOW server_version`).Scan(&serverVersion); err != nil {
+		return fail("check that DB_USER can run queries against DB_NAME", "connected but query failed: %v", err)
+	}
+	return ok("connected to %s:%s/%s (PostgreSQL %s)", d.cfg.DBHost, d.cfg.DBPort, d.cfg.DBName, serverVersion)
+}
+
+func (d *Doctor) checkMigrations(ctx context.Context) Result {
+	if d.database == nil {
+		return warn("fix the database check first", "skipped: no database connection")
+	}
+	version, err := d.database.MigratedVersion(ctx)
+	if err != nil {
+		return fail("check that DB_USER can read DB_NAME", "read schema version: %v", err)
+	}
+	switch {
+	case version == 0:
+		return fail("start the server once; it migrates the schema on startup",
+			"schema has not been migrated by this build (expected version %d)", db.SchemaVersion)
+	case version < db.SchemaVersion:
+		return fail("start the server once; it migrates the schema on startup",
+			"schema is at version %d, this build expects %d", version, db.SchemaVersion)
+	case version > db.SchemaVersion:
+		return warn("upgrade omp to the same release as the server",
+			"schema is at version %d, newer than this build's %d", version, db.SchemaVersion)
+	}
+	return ok("schema at version %d", version)
+}
+
+func (d *Doctor) checkRedis(ctx context.Context) Result {
+	if !d.cfg.RedisEnabled {
+		return ok("disabled (REDIS_ENABLED=false); queue and download features are off")
+	}
+	client := redis.NewClient(&redis.Options{Addr: d.cfg.RedisAddr})
+	defer client.Close()
+	if err := client.Ping(ctx).Err(); err != nil {
+		return fail("start Redis at REDIS_ADDR, or set REDIS_ENABLED=false to run without queues and downloads",
+			"cannot reach %s: %v", d.cfg.RedisAddr, err)
+	}
+	return ok("reachable at %s", d.cfg.RedisAddr)
+}
+
+// checkObjectStorage writes, reads back and deletes a small object so that
+// bucket permissions are exercised, not just reachability.
+func (d *Doctor) checkObjectStorage(ctx context.Context) Result {
+	const fix = "check MINIO_ENDPOINT, MINIO_ACCESS_KEY and MINIO_SECRET_KEY, and that MINIO_BUCKET exists and is writable"
+	client, err := storage.New(&storage.Config{
+		Endpoint:  d.cfg.MinioEndpoint,
+		Region:    d.cfg.S3Region,
+		AccessKey: d.cfg.MinioAccessKey,
+		SecretKey: d.cfg.MinioSecretKey,
+		Bucket:    d.cfg.MinioBucket,
+		UseSSL:    d.cfg.MinioUseSSL,
+	})
+	if err != nil {
+		return fail(fix, "%v", err)
+	}
+	key := "doctor/" + strconv.FormatInt(time.Now().UnixNano(), 10)
+	payload := []byte("omp doctor")
+	if err := client.PutObject(ctx, key, bytes.NewReader(payload), int64(len(payload)), "text/plain"); err != nil {
+		return fail(fix, "write to bucket %q: %v", d.cfg.MinioBucket, err)
+	}
+	reader, _, err := client.GetObject(ctx, key)
+	if err != nil {
+		return fail(fix, "read from bucket %q: %v", d.cfg.MinioBucket, err)
+	}
+	got, err := io.ReadAll(reader)
+	reader.Close()
+	if err != nil || !bytes.Equal(got, payload) {
+		return fail(fix, "object read back from bucket %q does not match what was written", d.cfg.MinioBucket)
+	}
+	if err := client.DeleteObject(ctx, key); err != nil {
+		return warn("grant delete permission on MINIO_BUCKET; track deletion needs it too",
+			"read/write ok but delete failed, %s was left behind: %v", key, err)
+	}
+	return ok("read/write ok on bucket %q at %s", d.cfg.MinioBucket, d.cfg.MinioEndpoint)
+}
+
+// checkDownloadDir writes a scratch file where downloads are staged and reports
+// free space against the admission threshold.
+func (d *Doctor) checkDownloadDir(ctx context.Context) Result {
+	path := d.cfg.DiskMonitorPath
+	file, err := os.CreateTemp(path, "omp-doctor-*")
+	if err != nil {
+		return fail("point DISK_MONITOR_PATH at a directory the server user can write", "cannot write to %s: %v", path, err)
+	}
+	_, writeErr := file.WriteString("omp doctor")
+	file.Close()
+	os.Remove(file.Name())
+	if writeErr != nil {
+		return fail("free up space or fix permissions on DISK_MONITOR_PATH", "cannot write to %s: %v", path, writeErr)
+	}
+
+	status := diskspace.NewMonitor(diskspace.Config{
+		Path:         path,
+		MinFreeBytes: uint64(d.cfg.DiskMinFreeMB) << 20,
+	}).Check(ctx)
+	switch {
+	case status.Err != nil:
+		return warn("", "writable, but free space is unknown: %v", status.Err)
+	case status.Low:
+		return warn("free up space or lower DISK_MIN_FREE_MB",
+			"%s has %d MB free, below DISK_MIN_FREE_MB=%d; new downloads will be refused",
+			path, status.FreeBytes>>20, d.cfg.DiskMinFreeMB)
+	}
+	return ok("%s writable, %d MB free", path, status.FreeBytes>>20)
+}
+
+type tool struct {
+	name    string
+	version string
+	fix     string
+}
+
+var requiredTools = []tool{
+	{name: tools.FFmpeg, version: "-version", fix: "set TOOLS_AUTO_DOWNLOAD=true, or install ffmpeg on PATH"},
+	{name: tools.FFprobe, version: "-version", fix: "set TOOLS_AUTO_DOWNLOAD=true, or install ffmpeg (which ships ffprobe) on PATH"},
+	{name: tools.YTDLP, version: "--version", fix: "set TOOLS_AUTO_DOWNLOAD=true, or install yt-dlp on PATH (for example pip install -U yt-dlp)"},
+}
+
+func (d *Doctor) toolChecks() []Check {
+	checks := make([]Check, 0, len(requiredTools))
+	for _, t := range requiredTools {
+		checks = append(checks, Check{Name: t.name, Run: func(ctx context.Context) Result {
+			path, err := d.lookPath(t.name)
+			if err != nil {
+				return fail(t.fix, "not found on PATH")
+			}
+			output, err := d.command(ctx, path, t.version)
+			if err != nil {
+				return fail(t.fix, "%s %s failed: %v", path, t.version, err)
+			}
+			version, _, _ := strings.Cut(strings.TrimSpace(string(output)), "\n")
+			return ok("%s (%s)", version, path)
+		}})
+	}
+	return checks
+}
+
+func (d *Doctor) checkMusicBrainz(ctx context.Context) Result {
+	if err := d.musicBrainz.Ping(ctx); err != nil {
+		return warn("allow outbound HTTPS to musicbrainz.org; metadata search and matching depend on it",
+			"unreachable: %v", err)
+	}
+	return ok("reachable")
+}
+
+// proxyChecks loads a page of each provider that has a proxy through it, so
+// a proxy that is down, or a region the provider still blocks, shows up
+// before searches and downloads start failing.
+func (d *Doctor) proxyChecks() []Check {
+	var checks []Check
+	for _, provider := range d.proxies.Configured() {
+		checks = append(checks, Check{Name: "proxy-" + provider, Run: func(ctx context.Context) Result {
+			if err := d.proxies.Check(ctx, provider); err != nil {
+				return warn(fmt.Sprintf("check the %s proxy in EXTERNAL_PROXIES and that it is running; %s requests fail until it works", provider, provider),
+					"%v", err)
+			}
+			return ok("reachable through %s", d.proxies.Redacted(provider))
+		}})
+	}
+	return checks
+}
+
+type endpoint struct {
+	name    string
+	enabled bool
+	url     string
+	env     string
+}
+
+// endpointChecks covers the optional HTTP services. Each is checked only when
+// enabled, and any HTTP response counts as reachable: the goal is to catch a
+// wrong URL or a service that is down, not to validate credentials.
+func (d *Doctor) endpointChecks() []Check {
+	endpoints := []endpoint{
+		{name: "ai-assist", enabled: d.cfg.AIAssistEnabled, url: d.cfg.AIAssistBaseURL, env: "AI_ASSIST_BASE_URL"},
+		{name: "metadata-llm", enabled: d.cfg.MetadataLLMEnabled, url: d.cfg.MetadataLLMBaseURL, env: "METADATA_LLM_BASE_URL"},
+		{name: "source-quality-llm", enabled: d.cfg.SourceQualityLLMEnabled, url: d.cfg.SourceQualityLLMBaseURL, env: "SOURCE_QUALITY_LLM_BASE_URL"},
+		{name: "analyzer", enabled: d.cfg.AnalyzerEnabled, url: d.cfg.AnalyzerBaseURL, env: "ANALYZER_BASE_URL"},
+	}
+	var checks []Check
+	for _, e := range endpoints {
+		if !e.enabled {
+			continue
+		}
+		checks = append(checks, Check{Name: e.name, Run: func(ctx context.Context) Result {
+			fix := fmt.Sprintf("check %s and that the service is running; the feature degrades until it is reachable", e.env)
+			req, err := http.NewRequestWithContext(ctx, http.MethodGet, e.url, nil)
+			if err != nil {
+				return warn(fix, "invalid URL %q: %v", e.url, err)
+			}
+			resp, err := d.httpClient.Do(req)
+			if err != nil {
+				return warn(fix, "unreachable: %v", err)
+			}
+			resp.Body.Close()
+			return ok("reachable at %s (HTTP %d)", e.url, resp.StatusCode)
+		}})
+	}
+	return checks
+}
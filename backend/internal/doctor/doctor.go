@@ -0,0 +1,107 @@
+// Package doctor runs self-checks against a backend's configuration and the
+// services it depends on, and renders them as an actionable report. The omp
+// CLI runs the full set; the server runs the cheap local ones at startup.
+package doctor
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"strings"
+	"time"
+)
+
+// Status is the outcome of one check.
+type Status string
+
+const (
+	StatusOK   Status = "ok"
+	StatusWarn Status = "warn"
+	StatusFail Status = "fail"
+)
+
+// Result is one check's outcome. Fix tells the operator what to change and is
+// only set when Status is not ok.
+type Result struct {
+	Name   string `json:"name"`
+	Status Status `json:"status"`
+	Detail string `json:"detail"`
+	Fix    string `json:"fix,omitempty"`
+}
+
+// Check is a named probe. Run should honor ctx cancellation.
+type Check struct {
+	Name string
+	Run  func(ctx context.Context) Result
+}
+
+// DefaultTimeout bounds each check when Run is given no timeout.
+const DefaultTimeout = 10 * time.Second
+
+// Run executes checks in order, each under its own timeout, so a later check
+// may rely on state an earlier one set up (migrations reuse the database
+// connection, for example).
+func Run(ctx context.Context, checks []Check, timeout time.Duration) []Result {
+	if timeout <= 0 {
+		timeout = DefaultTimeout
+	}
+	results := make([]Result, 0, len(checks))
+	for _, check := range checks {
+		checkCtx, cancel := context.WithTimeout(ctx, timeout)
+		result := check.Run(checkCtx)
+		cancel()
+		result.Name = check.Name
+		results = append(results, result)
+	}
+	return results
+}
+
+// Passed reports whether no check failed. Warnings do not fail the run.
+func Passed(results []Result) bool {
+	for _, result := range results {
+		if result.Status == StatusFail {
+			return false
+		}
+	}
+	return true
+}
+
+// WriteReport prints one line per check, the fix under each check that did
+// not pass, and a summary line.
+func WriteReport(w io.Writer, results []Result) {
+	width := 0
+	for _, result := range results {
+		width = max(width, len(result.Name))
+	}
+	indent := strings.Repeat(" ", 4+2+width+2)
+	var warned, failed int
+	for _, result := range results {
+		label := "ok"
+		switch result.Status {
+		case StatusWarn:
+			label = "WARN"
+			warned++
+		case StatusFail:
+			label = "FAIL"
+			failed++
+		}
+		fmt.Fprintf(w, "%-4s  %-*s  %s\n", label, width, result.Name, result.Detail)
+		if result.Status != StatusOK && result.Fix != "" {
+			fmt.Fprintf(w, "%sfix: %s\n", indent, result.Fix)
+		}
+	}
+	fmt.Fprintf(w, "\n%d checks: %d ok, %d warnings, %d failed\n",
+		len(results), len(results)-warned-failed, warned, failed)
+}
+
+func ok(format string, args ...any) Result {
+	return Result{Status: StatusOK, Detail: fmt.Sprintf(format, args...)}
+}
+
+func warn(fix, format string, args ...any) Result {
+	return Result{Status: StatusWarn, Detail: fmt.Sprintf(format, args...), Fix: fix}
+}
+
+func fail(fix, format string, args ...any) Result {
+	return Result{Status: StatusFail, Detail: fmt.Sprintf(format, args...), Fix: fix}
+}
@@ -0,0 +1,113 @@
+package doctor
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/config"
+)
+
+func TestWriteReportPrintsFixesAndSummary(t *testing.T) {
+	results := []Result{
+		{Name: "config", Status: StatusOK, Detail: "valid"},
+		{Name: "database", Status: StatusFail, Detail: "connection refused", Fix: "start PostgreSQL"},
+		{Name: "yt-dlp", Status: StatusWarn, Detail: "old", Fix: "upgrade yt-dlp"},
+	}
+	var out bytes.Buffer
+	WriteReport(&out, results)
+
+	want := "" +
+		"ok    config    valid\n" +
+		"FAIL  database  connection refused\n" +
+		"                fix: start PostgreSQL\n" +
+		"WARN  yt-dlp    old\n" +
+		"                fix: upgrade yt-dlp\n" +
+		"\n" +
+		"3 checks: 1 ok, 1 warnings, 1 failed\n"
+	if out.String() != want {
+		t.Fatalf("report =\n%s\nwant\n%s", out.String(), want)
+	}
+	if Passed(results) {
+		t.Fatal("Passed() = true with a failed check")
+	}
+	if !Passed(results[2:]) {
+		t.Fatal("Passed() = false with only a warning")
+	}
+}
+
+func TestToolChecksReportVersionsAndMissingTools(t *testing.T) {
+	d := New(&config.Config{})
+	d.lookPath = func(file string) (string, error) {
+		if file == "yt-dlp" {
+			return "", errors.New("not found")
+		}
+		return "/usr/bin/" + file, nil
+	}
+	d.command = func(ctx context.Context, name string, args ...string) ([]byte, error) {
+		return []byte(name + " version 6.1\nbuilt with gcc\n"), nil
+	}
+
+	results := Run(context.Background(), d.toolChecks(), 0)
+	got := map[string]Result{}
+	for _, result := range results {
+		got[result.Name] = result
+	}
+	if r := got["ffmpeg"]; r.Status != StatusOK || r.Detail != "/usr/bin/ffmpeg version 6.1 (/usr/bin/ffmpeg)" {
+		t.Fatalf("ffmpeg = %#v", r)
+	}
+	if r := got["yt-dlp"]; r.Status != StatusFail || !strings.Contains(r.Fix, "install yt-dlp") {
+		t.Fatalf("yt-dlp = %#v", r)
+	}
+}
+
+func TestConfigCheckFlagsInvalidSettings(t *testing.T) {
+	t.Setenv("JWT_SECRET", "secret")
+	d := New(&config.Config{DiskMonitorPath: t.TempDir()})
+	if r := d.checkConfig(context.Background()); r.Status != StatusOK {
+		t.Fatalf("valid config = %#v", r)
+	}
+
+	d = New(&config.Config{DiskMonitorPath: t.TempDir() + "/missing", ExternalProxies: map[string]string{"downloads": "ftp://vpn:21"}})
+	r := d.checkConfig(context.Background())
+	if r.Status != StatusFail || !strings.Contains(r.Detail, "DISK_MONITOR_PATH") || !strings.Contains(r.Detail, "EXTERNAL_PROXIES") {
+		t.Fatalf("invalid config = %#v", r)
+	}
+}
+
+func TestEndpointChecksOnlyCoverEnabledServices(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusUnauthorized)
+	}))
+	defer server.Close()
+
+	d := New(&config.Config{
+		AnalyzerEnabled:    true,
+		AnalyzerBaseURL:    server.URL,
+		MetadataLLMEnabled: true,
+		MetadataLLMBaseURL: "http://127.0.0.1:1",
+		AIAssistBaseURL:    server.URL,
+	})
+	results := Run(context.Background(), d.endpointChecks(), 0)
+	if len(results) != 2 {
+		t.Fatalf("results = %#v, want analyzer and metadata-llm only", results)
+	}
+	for _, r := range results {
+		switch r.Name {
+		case "analyzer":
+			if r.Status != StatusOK {
+				t.Fatalf("analyzer answering 401 = %#v, want ok", r)
+			}
+		case "metadata-llm":
+			if r.Status != StatusWarn || !strings.Contains(r.Fix, "METADATA_LLM_BASE_URL") {
+				t.Fatalf("unreachable metadata-llm = %#v", r)
+			}
+		default:
+			t.Fatalf("unexpected check %q", r.Name)
+		}
+	}
+}
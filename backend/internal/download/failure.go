@@ -0,0 +1,132 @@
+package download
+
+import "strings"
+
+// Failure categories sort a failed download by what the user can do about
+// it, from what yt-dlp and the other downloaders said. Dead letters are
+// sorted for operators by jobs.ClassifyFailure instead.
+const (
+	FailureRemoved           = "removed"
+	FailureGeoBlocked        = "geo_blocked"
+	FailureAgeRestricted     = "age_restricted"
+	FailureLoginRequired     = "login_required"
+	FailureExtractorOutdated = "extractor_outdated"
+	FailureUnknown           = "unknown"
+)
+
+// failureMarkers are the lowercased error fragments that identify each
+// category, checked in order so the more specific causes win: YouTube says
+// "Sign in to confirm your age" for age-restricted videos and "Video
+// unavailable" before a geo-block.
+var failureMarkers = []struct {
+	category string
+	markers  []string
+}{
+	{FailureGeoBlocked, []string{
+		"not available in your country", "not made this video available in your country",
+		"blocked it in your country", "geo restriction", "geo-restricted", "geo restricted", "georestricted",
+	}},
+	{FailureAgeRestricted, []string{
+		"confirm your age", "age-restricted", "age restricted", "inappropriate for some users", "age-gated",
+	}},
+	{FailureLoginRequired, []string{
+		"sign in", "log in", "login required", "requires authentication", "members-only", "members only",
+		"private video", "video is private", "cookies are no longer valid", "http error 401", "http error 403", "403 forbidden",
+	}},
+	{FailureRemoved, []string{
+		"has been removed", "no longer available", "account associated with this video has been terminated",
+		"video unavailable", "http error 404", "404 not found", "does not exist", "track not found",
+	}},
+	{FailureExtractorOutdated, []string{
+		"unsupported url", "unable to extract", "nsig extraction failed", "signature extraction failed",
+		"please report this issue", "update to the latest version", "confirm you are on the latest version",
+		"requested format is not available",
+	}},
+}
+
+// failureAdvice is the message and remediation shown for each category.
+var failureAdvice = map[string]struct{ message, remediation string }{
+	FailureRemoved: {
+		"The source no longer has this track.",
+		"Pick another source for the track.",
+	},
+	FailureGeoBlocked: {
+		"The source blocks this track in the server's region.",
+		"Pick another source, or ask the server admin to route downloads through a proxy in another region (EXTERNAL_PROXIES).",
+	},
+	FailureAgeRestricted: {
+		"The source only plays this track to signed-in adults.",
+		"Upload cookies from a browser signed in to an adult account on the source under source credentials, then retry.",
+	},
+	FailureLoginRequired: {
+		"The source needs a login for this track.",
+		"Upload cookies from a browser signed in to the source under source credentials, then retry. If you already did, they may have expired.",
+	},
+	FailureExtractorOutdated: {
+		"The downloader could not read the source's page; it usually needs an update when this happens.",
+		"Ask the server admin to update yt-dlp (YTDLP_AUTO_UPDATE=true keeps it current), then retry.",
+	},
+	FailureUnknown: {
+		"",
+		"Retry later. If it keeps failing, the server admin can find the full error in the server log.",
+	},
+}
+
+// maxFailureMessage bounds the line of an unclassified error shown to users.
+const maxFailureMessage = 300
+
+// Failure is a failed download explained for the user who asked for it.
+type Failure struct {
+	Category    string
+	Message     string
+	Remediation string
+}
+
+// ClassifyFailure sorts a download error into one of the Failure categories,
+// FailureUnknown when nothing in it is recognized.
+func ClassifyFailure(errMsg string) string {
+	lower := strings.ToLower(errMsg)
+	for _, group := range failureMarkers {
+		for _, marker := range group.markers {
+			if strings.Contains(lower, marker) {
+				return group.category
+			}
+		}
+	}
+	return FailureUnknown
+}
+
+// Failure explains a failed job, or returns nil for any other. Jobs that
+// failed before categories were stored are classified from their error.
+func (j *DownloadJob) Failure() *Failure {
+	if j.Status != StatusFailed {
+		return nil
+	}
+	category := j.FailureCategory
+	if _, ok := failureAdvice[category]; !ok {
+		category = ClassifyFailure(j.Error)
+	}
+	advice := failureAdvice[category]
+	message := advice.message
+	if message == "" {
+		message = failureLine(j.Error)
+	}
+	return &Failure{Category: category, Message: message, Remediation: advice.remediation}
+}
+
+// failureLine picks the line of an unclassified error worth showing: the last
+// one yt-dlp marked ERROR, else the first, without the tool output around it.
+func failureLine(errMsg string) string {
+	lines := strings.Split(strings.TrimSpace(errMsg), "\n")
+	line := lines[0]
+	for _, candidate := range lines {
+		if i := strings.Index(candidate, "ERROR:"); i >= 0 {
+			line = candidate[i:]
+		}
+	}
+	line = strings.TrimSpace(line)
+	if len(line) > maxFailureMessage {
+		line = line[:maxFailureMessage] + "..."
+	}
+	return line
+}
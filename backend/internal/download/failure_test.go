@@ -0,0 +1,46 @@
+package download
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestClassifyFailureRecognizesYTDLPErrors(t *testing.T) {
+	tests := []struct {
+		err  string
+		want string
+	}{
+		{"yt-dlp failed: exit status 1: ERROR: [youtube] abc: Video unavailable. This video has been removed by the uploader", FailureRemoved},
+		{"yt-dlp failed: exit status 1: ERROR: [youtube] abc: Video unavailable. The uploader has not made this video available in your country", FailureGeoBlocked},
+		{"yt-dlp failed: exit status 1: ERROR: [youtube] abc: Sign in to confirm your age. This video may be inappropriate for some users.", FailureAgeRestricted},
+		{"yt-dlp failed: exit status 1: ERROR: [youtube] abc: Sign in to confirm you're not a bot", FailureLoginRequired},
+		{"yt-dlp failed: exit status 1: ERROR: [soundcloud] 123: Unable to download JSON metadata: HTTP Error 404: Not Found", FailureRemoved},
+		{"yt-dlp failed: exit status 1: ERROR: [youtube] abc: nsig extraction failed: Some formats may be missing", FailureExtractorOutdated},
+		{"yt-dlp failed: exit status 1: ERROR: Unsupported URL: https://example.com/page", FailureExtractorOutdated},
+		{"http: GET https://host/a.mp3: 403 Forbidden", FailureLoginRequired},
+		{"yt-dlp failed: signal: killed", FailureUnknown},
+	}
+	for _, tt := range tests {
+		if got := ClassifyFailure(tt.err); got != tt.want {
+			t.Errorf("ClassifyFailure(%q) = %q, want %q", tt.err, got, tt.want)
+		}
+	}
+}
+
+func TestFailureExplainsOnlyFailedJobs(t *testing.T) {
+	job := &DownloadJob{Status: StatusFailed, Error: "yt-dlp failed: exit status 1: [youtube] abc: Downloading webpage\nERROR: [youtube] abc: This video is not available in your country"}
+	failure := job.Failure()
+	if failure == nil || failure.Category != FailureGeoBlocked || strings.Contains(failure.Message, "yt-dlp") || !strings.Contains(failure.Remediation, "EXTERNAL_PROXIES") {
+		t.Fatalf("Failure() of a stored job = %+v", failure)
+	}
+
+	job = &DownloadJob{Status: StatusFailed, FailureCategory: FailureUnknown, Error: "yt-dlp failed: exit status 2: [generic] x: Downloading webpage\nERROR: something odd\nmore output"}
+	if failure := job.Failure(); failure.Message != "ERROR: something odd" || failure.Remediation == "" {
+		t.Fatalf("Failure() of an unknown error = %+v", failure)
+	}
+
+	job = &DownloadJob{Status: StatusCanceled, Error: canceledMessage}
+	if failure := job.Failure(); failure != nil {
+		t.Fatalf("Failure() of a canceled job = %+v, want nil", failure)
+	}
+}
@@ -1,6 +1,7 @@
 package download
 
 import (
+	"encoding/json"
 	"time"
 )
 
@@ -12,8 +13,48 @@ const (
 	StatusUploading   = "uploading"
 	StatusComplete    = "complete"
 	StatusFailed      = "failed"
+	StatusCanceled    = "canceled"
 )
 
+// SourceTypeDirect marks jobs for a plain audio file URL, fetched over HTTP
+// rather than through yt-dlp.
+const SourceTypeDirect = "direct"
+
+// SourceTypeRemote marks jobs for a file on an operator-configured SFTP or
+// WebDAV remote, queued by a remote folder import.
+const SourceTypeRemote = "remote"
+
+// SourceTypeChapter marks jobs for one chapter cut from a stored mix, queued
+// by a chapter split.
+const SourceTypeChapter = "chapter"
+
+// MetadataRequestHeaders is the job metadata key holding the headers sent
+// when fetching a direct source. They may carry credentials; see Redacted.
+const MetadataRequestHeaders = "requestHeaders"
+
+// SourceTypeBeets marks jobs for a file in a beets-managed library, queued by
+// a beets import.
+const SourceTypeBeets = "beets"
+
+// MetadataLibrary is the job metadata key holding what an external library
+// manager recorded for a local file; see Library.
+const MetadataLibrary = "library"
+
+// MetadataInboxPlaylistID is the job metadata key holding the playlist a
+// quick-added download is appended to once it is processed.
+const MetadataInboxPlaylistID = "inboxPlaylistId"
+
+// LibraryMetadata is what an external library manager, such as beets, knows
+// about a local file beyond the job's title, artist, and album. When it is
+// present those job fields outrank the file's own tags, since the manager's
+// database may be newer than what it wrote into the file.
+type LibraryMetadata struct {
+	Manager     string `json:"manager"`
+	MBReleaseID string `json:"mb_release_id,omitempty"`
+	MBArtistID  string `json:"mb_artist_id,omitempty"`
+	ArtworkPath string `json:"artwork_path,omitempty"`
+}
+
 // DownloadJob represents a download task in the queue
 type DownloadJob struct {
 	ID                   string                 `json:"id"`
@@ -23,6 +64,7 @@ type DownloadJob struct {
 	Status               string                 `json:"status"`
 	Progress             int                    `json:"progress"`
 	Error                string                 `json:"error,omitempty"`
+	FailureCategory      string                 `json:"failure_category,omitempty"`
 	RetryCount           int                    `json:"retry_count"`
 	MBRecordingID        *string                `json:"mb_recording_id,omitempty"`
 	TrackID              *int64                 `json:"track_id,omitempty"`
@@ -39,6 +81,7 @@ type DownloadJob struct {
 	PlaylistImportItemID int64                  `json:"playlist_import_item_id,omitempty"`
 	PlaylistID           int64                  `json:"playlist_id,omitempty"`
 	PlaylistPosition     int                    `json:"playlist_position,omitempty"`
+	TraceParent          string                 `json:"trace_parent,omitempty"`
 	CreatedAt            time.Time              `json:"created_at"`
 	UpdatedAt            time.Time              `json:"updated_at"`
 	StartedAt            *time.Time             `json:"started_at,omitempty"`
@@ -47,7 +90,80 @@ type DownloadJob struct {
 
 // IsTerminal returns true if the job is in a terminal state
 func (j *DownloadJob) IsTerminal() bool {
-	return j.Status == StatusComplete || j.Status == StatusFailed
+	return j.Status == StatusComplete || j.Status == StatusFailed || j.Status == StatusCanceled
+}
+
+// RequestHeaders returns the headers to send when fetching the job's source.
+// Metadata decoded from JSON holds them as map[string]interface{}.
+func (j *DownloadJob) RequestHeaders() map[string]string {
+	switch raw := j.Metadata[MetadataRequestHeaders].(type) {
+	case map[string]string:
+		return raw
+	case map[string]interface{}:
+		headers := make(map[string]string, len(raw))
+		for name, value := range raw {
+			if s, ok := value.(string); ok {
+				headers[name] = s
+			}
+		}
+		return headers
+	}
+	return nil
+}
+
+// Library returns the library manager metadata stored on the job, or nil.
+func (j *DownloadJob) Library() *LibraryMetadata {
+	var library LibraryMetadata
+	switch raw := j.Metadata[MetadataLibrary].(type) {
+	case LibraryMetadata:
+		library = raw
+	case *LibraryMetadata:
+		if raw == nil {
+			return nil
+		}
+		library = *raw
+	case map[string]interface{}:
+		// Jobs read back from the queue hold the decoded JSON object.
+		data, err := json.Marshal(raw)
+		if err != nil || json.Unmarshal(data, &library) != nil {
+			return nil
+		}
+	default:
+		return nil
+	}
+	return &library
+}
+
+// InboxPlaylistID returns the inbox playlist stored on the job, or 0.
+func (j *DownloadJob) InboxPlaylistID() int64 {
+	switch raw := j.Metadata[MetadataInboxPlaylistID].(type) {
+	case int64:
+		return raw
+	case float64:
+		// Jobs read back from the queue hold the decoded JSON number.
+		return int64(raw)
+	}
+	return 0
+}
+
+// Redacted returns a copy of the job whose request header values are masked,
+// for logs and operator-facing records.
+func (j *DownloadJob) Redacted() *DownloadJob {
+	headers := j.RequestHeaders()
+	if len(headers) == 0 {
+		return j
+	}
+	redacted := *j
+	redacted.Metadata = make(map[string]interface{}, len(j.Metadata))
+	for key, value := range j.Metadata {
+		redacted.Metadata[key] = value
+	}
+	masked := make(map[string]string, len(headers))
+	for name := range headers {
+		masked[name] = "[redacted]"
+	}
+	redacted.Metadata[MetadataRequestHeaders] = masked
+	return &redacted
 }
 
 // CanRetry returns true if the job can be retried
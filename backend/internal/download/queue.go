@@ -9,22 +9,35 @@ import (
 
 	"github.com/google/uuid"
 	"github.com/redis/go-redis/v9"
+
+	"github.com/openmusicplayer/backend/internal/tracing"
 )
 
 const (
 	// Redis key prefixes
-	keyJobQueue  = "download:queue"
-	keyJobStatus = "download:job:"
-	keyProgress  = "download:progress"
+	keyJobQueue   = "download:queue"
+	keyJobStatus  = "download:job:"
+	keyJobCancel  = "download:cancel:"
+	keyProgress   = "download:progress"
+	keyQueuePause = "download:paused"
 
 	// Default timeout for blocking operations
 	defaultBlockTimeout = 5 * time.Second
+
+	// cancelRequestTTL bounds how long a cancel request waits for the worker
+	// running the job to notice it.
+	cancelRequestTTL = time.Hour
+
+	// canceledMessage is the error recorded on canceled jobs.
+	canceledMessage = "canceled"
 )
 
 var (
-	ErrJobNotFound     = errors.New("job not found")
-	ErrQueueEmpty      = errors.New("queue is empty")
-	ErrJobNotRetryable = errors.New("job is not retryable")
+	ErrJobNotFound      = errors.New("job not found")
+	ErrQueueEmpty       = errors.New("queue is empty")
+	ErrJobNotRetryable  = errors.New("job is not retryable")
+	ErrJobNotCancelable = errors.New("job is not cancelable")
+	ErrAdmissionRefused = errors.New("new downloads are not being accepted")
 )
 
 // Queue manages download jobs using Redis
@@ -215,6 +228,10 @@ func (q *Queue) enqueueJob(ctx context.Context, job *DownloadJob) (*DownloadJob,
 	job.RetryCount = 0
 	job.CreatedAt = now
 	job.UpdatedAt = now
+	// Carry the enqueuing request's trace so the worker span joins it.
+	if job.TraceParent == "" {
+		job.TraceParent = tracing.TraceParentFromContext(ctx)
+	}
 
 	if err := q.saveJob(ctx, job); err != nil {
 		return nil, err
@@ -278,6 +295,10 @@ func (q *Queue) UpdateStatus(ctx context.Context, jobID, status string, progress
 	job.Status = status
 	job.Progress = progress
 	job.Error = errMsg
+	job.FailureCategory = ""
+	if status == StatusFailed {
+		job.FailureCategory = ClassifyFailure(errMsg)
+	}
 	job.UpdatedAt = time.Now()
 
 	if status == StatusDownloading && job.StartedAt == nil {
@@ -285,7 +306,7 @@ func (q *Queue) UpdateStatus(ctx context.Context, jobID, status string, progress
 		job.StartedAt = &now
 	}
 
-	if status == StatusComplete || status == StatusFailed {
+	if status == StatusComplete || status == StatusFailed || status == StatusCanceled {
 		now := time.Now()
 		job.CompletedAt = &now
 	}
@@ -326,6 +347,7 @@ func (q *Queue) IncrementRetry(ctx context.Context, jobID string) error {
 	job.RetryCount++
 	job.Status = StatusQueued
 	job.Error = ""
+	job.FailureCategory = ""
 	job.UpdatedAt = time.Now()
 
 	data, err := json.Marshal(job)
@@ -340,6 +362,130 @@ func (q *Queue) IncrementRetry(ctx context.Context, jobID string) error {
 	return err
 }
 
+// RequeueDead puts a failed job back on the queue with its retry count reset.
+func (q *Queue) RequeueDead(ctx context.Context, jobID string) error {
+	job, err := q.GetJob(ctx, jobID)
+	if err != nil {
+		return err
+	}
+	if job.Status != StatusFailed {
+		return ErrJobNotRetryable
+	}
+
+	job.RetryCount = 0
+	job.Status = StatusQueued
+	job.Progress = 0
+	job.Error = ""
+	job.FailureCategory = ""
+	job.CompletedAt = nil
+	job.UpdatedAt = time.Now()
+
+	data, err := json.Marshal(job)
+	if err != nil {
+		return fmt.Errorf("failed to marshal job: %w", err)
+	}
+
+	pipe := q.client.TxPipeline()
+	pipe.Set(ctx, keyJobStatus+job.ID, data, 0)
+	pipe.LPush(ctx, keyJobQueue, jobID)
+	_, err = pipe.Exec(ctx)
+	return err
+}
+
+// Cancel cancels a job. A job still waiting in the queue is removed from it and
+// marked canceled in one Redis pipeline. A running job is flagged, and its
+// worker stops it and marks it canceled. Finished jobs return
+// ErrJobNotCancelable.
+func (q *Queue) Cancel(ctx context.Context, jobID string) (*DownloadJob, error) {
+	job, err := q.GetJob(ctx, jobID)
+	if err != nil {
+		return nil, err
+	}
+	if job.IsTerminal() {
+		return nil, ErrJobNotCancelable
+	}
+	// The flag also covers a queued job a worker has already popped but not
+	// yet started.
+	if err := q.client.Set(ctx, keyJobCancel+jobID, "1", cancelRequestTTL).Err(); err != nil {
+		return nil, fmt.Errorf("failed to request cancellation: %w", err)
+	}
+	if job.Status != StatusQueued {
+		return job, nil
+	}
+
+	now := time.Now()
+	job.Status = StatusCanceled
+	job.Error = canceledMessage
+	job.UpdatedAt = now
+	job.CompletedAt = &now
+
+	data, err := json.Marshal(job)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal job: %w", err)
+	}
+
+	pipe := q.client.TxPipeline()
+	pipe.Set(ctx, keyJobStatus+job.ID, data, 0)
+	pipe.LRem(ctx, keyJobQueue, 0, jobID)
+	if _, err := pipe.Exec(ctx); err != nil {
+		return nil, err
+	}
+	if err := q.publishProgress(ctx, job); err != nil {
+		return nil, err
+	}
+	return job, nil
+}
+
+// CancelRequested reports whether the job's cancellation has been requested.
+func (q *Queue) CancelRequested(ctx context.Context, jobID string) (bool, error) {
+	count, err := q.client.Exists(ctx, keyJobCancel+jobID).Result()
+	if err != nil {
+		return false, err
+	}
+	return count > 0, nil
+}
+
+// PauseState describes a paused download queue.
+type PauseState struct {
+	Reason   string    `json:"reason,omitempty"`
+	PausedAt time.Time `json:"paused_at"`
+}
+
+// Pause stops workers from starting queued jobs until Resume is called. Jobs
+// already running finish, and new jobs can still be queued.
+func (q *Queue) Pause(ctx context.Context, reason string) (*PauseState, error) {
+	state := &PauseState{Reason: reason, PausedAt: time.Now()}
+	data, err := json.Marshal(state)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal pause state: %w", err)
+	}
+	if err := q.client.Set(ctx, keyQueuePause, data, 0).Err(); err != nil {
+		return nil, fmt.Errorf("failed to pause queue: %w", err)
+	}
+	return state, nil
+}
+
+// Resume lets workers start queued jobs again.
+func (q *Queue) Resume(ctx context.Context) error {
+	return q.client.Del(ctx, keyQueuePause).Err()
+}
+
+// Paused returns the queue's pause state, or nil when it is running.
+func (q *Queue) Paused(ctx context.Context) (*PauseState, error) {
+	data, err := q.client.Get(ctx, keyQueuePause).Result()
+	if errors.Is(err, redis.Nil) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("failed to get pause state: %w", err)
+	}
+	var state PauseState
+	if err := json.Unmarshal([]byte(data), &state); err != nil {
+		return nil, fmt.Errorf("failed to unmarshal pause state: %w", err)
+	}
+	return &state, nil
+}
+
 // PrepareRetry persists retry metadata before a worker waits for its backoff.
 func (q *Queue) PrepareRetry(ctx context.Context, jobID string) (*DownloadJob, error) {
 	job, err := q.GetJob(ctx, jobID)
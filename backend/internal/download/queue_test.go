@@ -2,6 +2,7 @@ package download
 
 import (
 	"context"
+	"errors"
 	"os"
 	"testing"
 	"time"
@@ -284,6 +285,95 @@ func TestQueue_QueueLength(t *testing.T) {
 	queue.Dequeue(ctx, 1*time.Second)
 }
 
+func TestQueue_RequeueDeadResetsRetries(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	job, err := queue.Enqueue(ctx, "dead-user", "https://example.com/dead.mp3", "test", nil)
+	if err != nil {
+		t.Fatalf("Failed to enqueue job: %v", err)
+	}
+	if err := queue.RequeueDead(ctx, job.ID); !errors.Is(err, ErrJobNotRetryable) {
+		t.Fatalf("Expected ErrJobNotRetryable for a queued job, got %v", err)
+	}
+	if _, err := queue.Dequeue(ctx, time.Second); err != nil {
+		t.Fatalf("Failed to dequeue job: %v", err)
+	}
+	for i := 0; i < 2; i++ {
+		if err := queue.UpdateStatus(ctx, job.ID, StatusFailed, 40, "connection reset by peer"); err != nil {
+			t.Fatalf("Failed to fail job: %v", err)
+		}
+		if i == 0 {
+			if err := queue.IncrementRetry(ctx, job.ID); err != nil {
+				t.Fatalf("Failed to retry job: %v", err)
+			}
+			if _, err := queue.Dequeue(ctx, time.Second); err != nil {
+				t.Fatalf("Failed to dequeue retry: %v", err)
+			}
+		}
+	}
+
+	if err := queue.RequeueDead(ctx, job.ID); err != nil {
+		t.Fatalf("Failed to requeue dead job: %v", err)
+	}
+	requeued, err := queue.GetJob(ctx, job.ID)
+	if err != nil {
+		t.Fatalf("Failed to get job: %v", err)
+	}
+	if requeued.Status != StatusQueued || requeued.RetryCount != 0 || requeued.Error != "" || requeued.CompletedAt != nil {
+		t.Fatalf("Expected a fresh queued job, got %+v", requeued)
+	}
+	if length, _ := queue.QueueLength(ctx); length != 1 {
+		t.Fatalf("Expected the job back on the queue, queue length %d", length)
+	}
+}
+
+func TestQueue_CancelRemovesQueuedJob(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	job, err := queue.Enqueue(ctx, "user-cancel", "https://example.com/cancel.mp3", "youtube", nil)
+	if err != nil {
+		t.Fatalf("Failed to enqueue job: %v", err)
+	}
+
+	canceled, err := queue.Cancel(ctx, job.ID)
+	if err != nil {
+		t.Fatalf("Failed to cancel job: %v", err)
+	}
+	if canceled.Status != StatusCanceled || canceled.CompletedAt == nil {
+		t.Fatalf("Expected a completed canceled job, got status %s", canceled.Status)
+	}
+	if length, err := queue.QueueLength(ctx); err != nil || length != 0 {
+		t.Fatalf("Expected an empty queue after cancel, got %d (%v)", length, err)
+	}
+	if _, err := queue.Cancel(ctx, job.ID); !errors.Is(err, ErrJobNotCancelable) {
+		t.Fatalf("Expected ErrJobNotCancelable for a canceled job, got %v", err)
+	}
+}
+
+func TestQueue_PauseAndResume(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	if state, err := queue.Paused(ctx); err != nil || state != nil {
+		t.Fatalf("Expected a running queue, got %+v (%v)", state, err)
+	}
+	if _, err := queue.Pause(ctx, "storage maintenance"); err != nil {
+		t.Fatalf("Failed to pause queue: %v", err)
+	}
+	state, err := queue.Paused(ctx)
+	if err != nil || state == nil || state.Reason != "storage maintenance" {
+		t.Fatalf("Expected a paused queue, got %+v (%v)", state, err)
+	}
+	if err := queue.Resume(ctx); err != nil {
+		t.Fatalf("Failed to resume queue: %v", err)
+	}
+	if state, err := queue.Paused(ctx); err != nil || state != nil {
+		t.Fatalf("Expected a resumed queue, got %+v (%v)", state, err)
+	}
+}
+
 func TestDownloadJob_IsTerminal(t *testing.T) {
 	tests := []struct {
 		status   string
@@ -295,6 +385,7 @@ func TestDownloadJob_IsTerminal(t *testing.T) {
 		{StatusUploading, false},
 		{StatusComplete, true},
 		{StatusFailed, true},
+		{StatusCanceled, true},
 	}
 
 	for _, tt := range tests {
@@ -2,6 +2,8 @@ package download
 
 import (
 	"context"
+	"errors"
+	"fmt"
 	"log"
 	"time"
 )
@@ -11,6 +13,7 @@ type Service struct {
 	queue      *Queue
 	workerPool *WorkerPool
 	lifecycle  JobLifecycle
+	admission  Admission
 	maxRetries int
 }
 
@@ -20,6 +23,13 @@ type ServiceConfig struct {
 	WorkerCount int
 	MaxRetries  int
 	JobTimeout  time.Duration
+	DeadLetters DeadLetterQueue
+	// Admission, when set, refuses new and retried downloads while it
+	// returns an error. Queued jobs wait until it admits them again.
+	Admission Admission
+	// Notifier, when set, hears about every job that completed or failed
+	// for good.
+	Notifier Notifier
 }
 
 // NewService creates a new download service
@@ -38,6 +48,9 @@ func NewService(config *ServiceConfig, processor JobProcessor, lifecycle ...JobL
 		WorkerCount: &workerCount,
 		MaxRetries:  maxRetries,
 		JobTimeout:  config.JobTimeout,
+		DeadLetters: config.DeadLetters,
+		Admission:   config.Admission,
+		Notifier:    config.Notifier,
 	}
 	if len(lifecycle) > 0 {
 		workerConfig.Lifecycle = lifecycle[0]
@@ -48,6 +61,7 @@ func NewService(config *ServiceConfig, processor JobProcessor, lifecycle ...JobL
 		queue:      queue,
 		workerPool: workerPool,
 		lifecycle:  workerConfig.Lifecycle,
+		admission:  config.Admission,
 		maxRetries: maxRetries,
 	}, nil
 }
@@ -72,17 +86,26 @@ func (s *Service) Queue() *Queue {
 
 // EnqueueDownload adds a new download job to the queue
 func (s *Service) EnqueueDownload(ctx context.Context, userID, url, sourceType string, mbRecordingID *string) (*DownloadJob, error) {
+	if err := s.Admit(ctx); err != nil {
+		return nil, err
+	}
 	return s.queue.Enqueue(ctx, userID, url, sourceType, mbRecordingID)
 }
 
 // EnqueueSourceCandidate queues a normalized discovery candidate for download.
 func (s *Service) EnqueueSourceCandidate(ctx context.Context, userID string, candidate SourceCandidate, mbRecordingID *string) (*DownloadJob, error) {
+	if err := s.Admit(ctx); err != nil {
+		return nil, err
+	}
 	return s.queue.EnqueueCandidate(ctx, userID, candidate, mbRecordingID)
 }
 
 // EnqueueSourceCandidateWithID queues a normalized discovery candidate using a
 // job ID already persisted in the playback queue item.
 func (s *Service) EnqueueSourceCandidateWithID(ctx context.Context, jobID, userID string, candidate SourceCandidate, mbRecordingID *string) (*DownloadJob, error) {
+	if err := s.Admit(ctx); err != nil {
+		return nil, err
+	}
 	return s.queue.EnqueueCandidateWithID(ctx, jobID, userID, candidate, mbRecordingID)
 }
 
@@ -94,10 +117,16 @@ func (s *Service) EnsureSourceCandidateWithID(ctx context.Context, jobID, userID
 }
 
 func (s *Service) EnqueuePlaylistImportItem(ctx context.Context, userID string, candidate SourceCandidate, importJobID string, importItemID int64, playlistID int64, playlistPosition int) (*DownloadJob, error) {
+	if err := s.Admit(ctx); err != nil {
+		return nil, err
+	}
 	return s.queue.EnqueuePlaylistImportItem(ctx, userID, candidate, importJobID, importItemID, playlistID, playlistPosition)
 }
 
 func (s *Service) EnqueuePlaylistImportItemWithID(ctx context.Context, jobID, userID string, candidate SourceCandidate, importJobID string, importItemID int64, playlistID int64, playlistPosition int) (*DownloadJob, error) {
+	if err := s.Admit(ctx); err != nil {
+		return nil, err
+	}
 	return s.queue.EnqueuePlaylistImportItemWithID(ctx, jobID, userID, candidate, importJobID, importItemID, playlistID, playlistPosition)
 }
 
@@ -117,6 +146,9 @@ func (s *Service) GetUserJobs(ctx context.Context, userID string) ([]*DownloadJo
 
 // RetryJob increments retry metadata and places a failed job back on the queue.
 func (s *Service) RetryJob(ctx context.Context, jobID string) error {
+	if err := s.Admit(ctx); err != nil {
+		return err
+	}
 	job, err := s.queue.GetJob(ctx, jobID)
 	if err != nil {
 		return err
@@ -137,6 +169,74 @@ func (s *Service) RetryJob(ctx context.Context, jobID string) error {
 	return s.queue.IncrementRetry(ctx, jobID)
 }
 
+// RetryDeadJob queues a job that failed for good again with a fresh retry
+// budget. Jobs that are not failed return ErrJobNotRetryable.
+func (s *Service) RetryDeadJob(ctx context.Context, jobID string) error {
+	if err := s.Admit(ctx); err != nil {
+		return err
+	}
+	job, err := s.queue.GetJob(ctx, jobID)
+	if err != nil {
+		return err
+	}
+	if job.Status != StatusFailed {
+		return ErrJobNotRetryable
+	}
+	if s.lifecycle != nil {
+		retrying := *job
+		retrying.Status = StatusQueued
+		retrying.Progress = 0
+		retrying.Error = ""
+		retrying.RetryCount = 0
+		if err := s.lifecycle.Requeue(ctx, &retrying, 0); err != nil {
+			return err
+		}
+	}
+	return s.queue.RequeueDead(ctx, jobID)
+}
+
+// Admit returns ErrAdmissionRefused while new downloads are refused, so
+// callers can turn work away before persisting anything for it.
+func (s *Service) Admit(ctx context.Context) error {
+	if s.admission == nil {
+		return nil
+	}
+	if err := s.admission.Admit(ctx); err != nil {
+		return fmt.Errorf("%w: %w", ErrAdmissionRefused, err)
+	}
+	return nil
+}
+
+// CancelJob cancels a job. A queued job is canceled at once; a running job is
+// stopped by its worker shortly after.
+func (s *Service) CancelJob(ctx context.Context, jobID string) (*DownloadJob, error) {
+	job, err := s.queue.Cancel(ctx, jobID)
+	if err != nil {
+		return nil, err
+	}
+	if s.lifecycle != nil && job.Status == StatusCanceled {
+		if err := s.lifecycle.Fail(ctx, job, errors.New(job.Error)); err != nil {
+			return nil, err
+		}
+	}
+	return job, nil
+}
+
+// PauseQueue stops workers from starting queued jobs.
+func (s *Service) PauseQueue(ctx context.Context, reason string) (*PauseState, error) {
+	return s.queue.Pause(ctx, reason)
+}
+
+// ResumeQueue lets workers start queued jobs again.
+func (s *Service) ResumeQueue(ctx context.Context) error {
+	return s.queue.Resume(ctx)
+}
+
+// QueuePaused returns the queue's pause state, or nil when it is running.
+func (s *Service) QueuePaused(ctx context.Context) (*PauseState, error) {
+	return s.queue.Paused(ctx)
+}
+
 // GetQueueLength returns the number of pending jobs
 func (s *Service) GetQueueLength(ctx context.Context) (int64, error) {
 	return s.queue.QueueLength(ctx)
@@ -7,7 +7,13 @@ import (
 	"log"
 	"math"
 	"sync"
+	"sync/atomic"
 	"time"
+
+	"go.opentelemetry.io/otel/attribute"
+	"go.opentelemetry.io/otel/trace"
+
+	"github.com/openmusicplayer/backend/internal/tracing"
 )
 
 const (
@@ -20,6 +26,9 @@ const (
 	// Redis blocking pop when the queue is idle.
 	workerDequeueTimeout = 1 * time.Second
 
+	// Running jobs check for cancel requests on this interval.
+	cancelPollInterval = 2 * time.Second
+
 	// Exponential backoff parameters
 	baseBackoff = 1 * time.Second
 	maxBackoff  = 5 * time.Minute
@@ -38,6 +47,24 @@ type JobLifecycle interface {
 	Requeue(context.Context, *DownloadJob, int) error
 }
 
+// DeadLetterQueue receives jobs that failed for good, with the error that
+// ended them, so operators can triage and retry them.
+type DeadLetterQueue interface {
+	DeadLetter(context.Context, *DownloadJob, error) error
+}
+
+// Admission decides whether new downloads may start. A non-nil error refuses
+// them, such as while the disk they are written to is nearly full.
+type Admission interface {
+	Admit(context.Context) error
+}
+
+// Notifier hears about jobs that completed or failed for good, so their
+// users can be told while they are not watching the queue.
+type Notifier interface {
+	JobFinished(context.Context, *DownloadJob)
+}
+
 // WorkerPool manages a pool of workers that process download jobs
 type WorkerPool struct {
 	queue        *Queue
@@ -46,6 +73,9 @@ type WorkerPool struct {
 	jobTimeout   time.Duration
 	processor    JobProcessor
 	lifecycle    JobLifecycle
+	deadLetters  DeadLetterQueue
+	admission    Admission
+	notifier     Notifier
 	prepareRetry func(context.Context, string) (*DownloadJob, error)
 
 	wg         sync.WaitGroup
@@ -61,6 +91,9 @@ type WorkerPoolConfig struct {
 	MaxRetries  int
 	JobTimeout  time.Duration
 	Lifecycle   JobLifecycle
+	DeadLetters DeadLetterQueue
+	Admission   Admission
+	Notifier    Notifier
 }
 
 // NewWorkerPool creates a new worker pool
@@ -94,6 +127,9 @@ func NewWorkerPool(queue *Queue, processor JobProcessor, config *WorkerPoolConfi
 		jobTimeout:  jobTimeout,
 		processor:   processor,
 		lifecycle:   config.Lifecycle,
+		deadLetters: config.DeadLetters,
+		admission:   config.Admission,
+		notifier:    config.Notifier,
 		stopChan:    make(chan struct{}),
 	}
 	if queue != nil {
@@ -183,6 +219,14 @@ func (wp *WorkerPool) worker(stopCtx context.Context, id int) {
 
 // processNextJob dequeues and processes the next available job
 func (wp *WorkerPool) processNextJob(dequeueCtx context.Context, workerID int) {
+	if !wp.admitting(dequeueCtx) {
+		select {
+		case <-dequeueCtx.Done():
+		case <-time.After(workerDequeueTimeout):
+		}
+		return
+	}
+
 	job, err := wp.queue.Dequeue(dequeueCtx, workerDequeueTimeout)
 	if err != nil {
 		if errors.Is(err, ErrQueueEmpty) || errors.Is(err, context.Canceled) {
@@ -192,15 +236,46 @@ func (wp *WorkerPool) processNextJob(dequeueCtx context.Context, workerID int) {
 		return
 	}
 
+	if job.Status == StatusCanceled {
+		return
+	}
+
 	log.Printf("Worker %d: processing job %s", workerID, job.ID)
 	wp.processJob(context.Background(), workerID, job)
 }
 
+// admitting reports whether workers may start queued jobs. While the queue is
+// paused or admission refuses new work, jobs wait in the queue rather than
+// failing.
+func (wp *WorkerPool) admitting(ctx context.Context) bool {
+	if paused, err := wp.queue.Paused(ctx); err == nil && paused != nil {
+		return false
+	}
+	return wp.admission == nil || wp.admission.Admit(ctx) == nil
+}
+
 // processJob handles the full lifecycle of a single job
 func (wp *WorkerPool) processJob(ctx context.Context, workerID int, job *DownloadJob) {
+	ctx = tracing.ContextWithTraceParent(ctx, job.TraceParent)
+	ctx, span := tracing.Tracer().Start(ctx, "download.process",
+		trace.WithSpanKind(trace.SpanKindConsumer),
+		trace.WithAttributes(
+			attribute.String("download.job_id", job.ID),
+			attribute.String("download.source_type", job.SourceType),
+			attribute.Int("download.retry_count", job.RetryCount),
+			attribute.Int("worker.id", workerID),
+		),
+	)
+	var spanErr error
+	defer func() { tracing.End(span, spanErr) }()
+
 	jobCtx, cancel := context.WithTimeout(ctx, wp.jobTimeout)
 	defer cancel()
 
+	if requested, err := wp.queue.CancelRequested(ctx, job.ID); err == nil && requested {
+		wp.finishCanceled(ctx, workerID, job)
+		return
+	}
 	if err := wp.queue.UpdateStatus(ctx, job.ID, StatusDownloading, 0, ""); err != nil {
 		log.Printf("Worker %d: failed to update job status to downloading: %v", workerID, err)
 		return
@@ -227,9 +302,16 @@ func (wp *WorkerPool) processJob(ctx context.Context, workerID int, job *Downloa
 		}
 	}
 
+	stopWatching := wp.watchCancellation(jobCtx, job.ID, cancel)
 	err := wp.processor(jobCtx, job, progressFn)
+	canceled := stopWatching()
 
 	if err != nil {
+		spanErr = err
+		if canceled {
+			wp.finishCanceled(ctx, workerID, job)
+			return
+		}
 		wp.handleJobFailure(ctx, workerID, job, err)
 		return
 	}
@@ -253,10 +335,61 @@ func (wp *WorkerPool) processJob(ctx context.Context, workerID int, job *Downloa
 	if err := wp.queue.UpdateStatus(ctx, job.ID, StatusComplete, 100, ""); err != nil {
 		log.Printf("Worker %d: failed to update job status to complete: %v", workerID, err)
 	}
+	job.Status = StatusComplete
+	wp.notify(ctx, job)
 
 	log.Printf("Worker %d: job %s completed successfully", workerID, job.ID)
 }
 
+// watchCancellation cancels a running job's context once its cancellation is
+// requested. The returned function stops watching and reports whether the job
+// was canceled.
+func (wp *WorkerPool) watchCancellation(ctx context.Context, jobID string, cancel context.CancelFunc) func() bool {
+	var canceled atomic.Bool
+	done := make(chan struct{})
+	stopped := make(chan struct{})
+	go func() {
+		defer close(stopped)
+		ticker := time.NewTicker(cancelPollInterval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-done:
+				return
+			case <-ctx.Done():
+				return
+			case <-ticker.C:
+				if requested, err := wp.queue.CancelRequested(ctx, jobID); err == nil && requested {
+					canceled.Store(true)
+					cancel()
+					return
+				}
+			}
+		}
+	}()
+	return func() bool {
+		close(done)
+		<-stopped
+		return canceled.Load()
+	}
+}
+
+// finishCanceled marks a job canceled instead of failed or retried.
+func (wp *WorkerPool) finishCanceled(ctx context.Context, workerID int, job *DownloadJob) {
+	log.Printf("Worker %d: job %s canceled", workerID, job.ID)
+	if err := wp.queue.UpdateStatus(ctx, job.ID, StatusCanceled, job.Progress, canceledMessage); err != nil {
+		log.Printf("Worker %d: failed to update job status to canceled: %v", workerID, err)
+		return
+	}
+	job.Status = StatusCanceled
+	job.Error = canceledMessage
+	if wp.lifecycle != nil {
+		if err := wp.lifecycle.Fail(ctx, job, errors.New(canceledMessage)); err != nil {
+			log.Printf("Worker %d: failed to mirror job cancellation for %s: %v", workerID, job.ID, err)
+		}
+	}
+}
+
 // handleJobFailure handles a failed job, implementing retry logic with exponential backoff
 func (wp *WorkerPool) handleJobFailure(ctx context.Context, workerID int, job *DownloadJob, jobErr error) {
 	errMsg := jobErr.Error()
@@ -302,6 +435,25 @@ func (wp *WorkerPool) handleJobFailure(ctx context.Context, workerID int, job *D
 			log.Printf("Worker %d: failed to mirror job failure for %s: %v", workerID, job.ID, err)
 		}
 	}
+	wp.deadLetter(ctx, workerID, job, jobErr)
+	wp.notify(ctx, job)
+}
+
+// notify tells the notifier a job finished.
+func (wp *WorkerPool) notify(ctx context.Context, job *DownloadJob) {
+	if wp.notifier != nil {
+		wp.notifier.JobFinished(ctx, job)
+	}
+}
+
+// deadLetter hands a job that failed for good to the dead-letter queue.
+func (wp *WorkerPool) deadLetter(ctx context.Context, workerID int, job *DownloadJob, jobErr error) {
+	if wp.deadLetters == nil {
+		return
+	}
+	if err := wp.deadLetters.DeadLetter(ctx, job, jobErr); err != nil {
+		log.Printf("Worker %d: failed to dead-letter job %s: %v", workerID, job.ID, err)
+	}
 }
 
 // failRetryPreparation reconciles retry setup failures to a terminal state. A
@@ -320,6 +472,8 @@ func (wp *WorkerPool) failRetryPreparation(ctx context.Context, workerID int, jo
 			log.Printf("Worker %d: failed to mark retry preparation failure for job %s durable: %v", workerID, job.ID, err)
 		}
 	}
+	wp.deadLetter(ctx, workerID, &failed, failure)
+	wp.notify(ctx, &failed)
 }
 
 type retryableError interface{ Retryable() bool }
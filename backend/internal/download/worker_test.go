@@ -167,6 +167,193 @@ func TestWorkerPool_RetryOnFailure(t *testing.T) {
 	}
 }
 
+func TestWorkerPool_CancelStopsRunningJob(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	started := make(chan struct{})
+	processor := func(ctx context.Context, job *DownloadJob, progress func(int)) error {
+		close(started)
+		<-ctx.Done()
+		return ctx.Err()
+	}
+	pool := NewWorkerPool(queue, processor, &WorkerPoolConfig{WorkerCount: workerCountPtr(0), MaxRetries: 3, JobTimeout: time.Minute})
+
+	job, err := queue.Enqueue(ctx, "cancel-user", "https://example.com/cancel-running.mp3", "test", nil)
+	if err != nil {
+		t.Fatalf("Failed to enqueue job: %v", err)
+	}
+	done := make(chan struct{})
+	go func() {
+		defer close(done)
+		pool.processNextJob(ctx, 0)
+	}()
+	<-started
+
+	running, err := queue.Cancel(ctx, job.ID)
+	if err != nil {
+		t.Fatalf("Failed to cancel running job: %v", err)
+	}
+	if running.Status != StatusDownloading {
+		t.Fatalf("Cancel should leave a running job to its worker, got status %s", running.Status)
+	}
+
+	select {
+	case <-done:
+	case <-time.After(5 * time.Second):
+		t.Fatal("Worker did not stop the canceled job")
+	}
+	updated, err := queue.GetJob(ctx, job.ID)
+	if err != nil {
+		t.Fatalf("Failed to get job: %v", err)
+	}
+	if updated.Status != StatusCanceled || updated.RetryCount != 0 {
+		t.Fatalf("Expected canceled job without retries, got status %s retry %d", updated.Status, updated.RetryCount)
+	}
+}
+
+func TestWorkerPool_PausedQueueStartsNoJobs(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	var processedCount int32
+	processor := func(ctx context.Context, job *DownloadJob, progress func(int)) error {
+		atomic.AddInt32(&processedCount, 1)
+		return nil
+	}
+	pool := NewWorkerPool(queue, processor, &WorkerPoolConfig{WorkerCount: workerCountPtr(0)})
+
+	if _, err := queue.Enqueue(ctx, "pause-user", "https://example.com/paused.mp3", "test", nil); err != nil {
+		t.Fatalf("Failed to enqueue job: %v", err)
+	}
+	if _, err := queue.Pause(ctx, "storage maintenance"); err != nil {
+		t.Fatalf("Failed to pause queue: %v", err)
+	}
+	pool.processNextJob(ctx, 0)
+	if count := atomic.LoadInt32(&processedCount); count != 0 {
+		t.Fatalf("Paused queue processed %d jobs", count)
+	}
+	if length, _ := queue.QueueLength(ctx); length != 1 {
+		t.Fatalf("Expected the job to stay queued while paused, queue length %d", length)
+	}
+
+	if err := queue.Resume(ctx); err != nil {
+		t.Fatalf("Failed to resume queue: %v", err)
+	}
+	pool.processNextJob(ctx, 0)
+	if count := atomic.LoadInt32(&processedCount); count != 1 {
+		t.Fatalf("Resumed queue processed %d jobs, want 1", count)
+	}
+}
+
+type switchAdmission struct {
+	err error
+}
+
+func (a *switchAdmission) Admit(context.Context) error { return a.err }
+
+func TestWorkerPool_RefusedAdmissionLeavesJobsQueued(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	var processedCount int32
+	processor := func(ctx context.Context, job *DownloadJob, progress func(int)) error {
+		atomic.AddInt32(&processedCount, 1)
+		return nil
+	}
+	admission := &switchAdmission{err: errors.New("not enough free disk space")}
+	pool := NewWorkerPool(queue, processor, &WorkerPoolConfig{WorkerCount: workerCountPtr(0), Admission: admission})
+
+	if _, err := queue.Enqueue(ctx, "disk-user", "https://example.com/large.flac", "test", nil); err != nil {
+		t.Fatalf("Failed to enqueue job: %v", err)
+	}
+	pool.processNextJob(ctx, 0)
+	if count := atomic.LoadInt32(&processedCount); count != 0 {
+		t.Fatalf("Refused admission processed %d jobs", count)
+	}
+	if length, _ := queue.QueueLength(ctx); length != 1 {
+		t.Fatalf("Expected the job to stay queued while refused, queue length %d", length)
+	}
+
+	admission.err = nil
+	pool.processNextJob(ctx, 0)
+	if count := atomic.LoadInt32(&processedCount); count != 1 {
+		t.Fatalf("Admitted queue processed %d jobs, want 1", count)
+	}
+}
+
+type notRetryableError struct{ error }
+
+func (notRetryableError) Retryable() bool { return false }
+
+type recordingDeadLetters struct {
+	jobs   []*DownloadJob
+	errors []error
+}
+
+func (d *recordingDeadLetters) DeadLetter(_ context.Context, job *DownloadJob, err error) error {
+	d.jobs = append(d.jobs, job)
+	d.errors = append(d.errors, err)
+	return nil
+}
+
+func TestWorkerPool_FinalFailureIsDeadLettered(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	processor := func(ctx context.Context, job *DownloadJob, progress func(int)) error {
+		return notRetryableError{errors.New("HTTP Error 403: Forbidden")}
+	}
+	deadLetters := &recordingDeadLetters{}
+	pool := NewWorkerPool(queue, processor, &WorkerPoolConfig{WorkerCount: workerCountPtr(0), DeadLetters: deadLetters})
+
+	job, err := queue.Enqueue(ctx, "dead-user", "https://example.com/blocked.mp3", "test", nil)
+	if err != nil {
+		t.Fatalf("Failed to enqueue job: %v", err)
+	}
+	pool.processNextJob(ctx, 0)
+
+	if len(deadLetters.jobs) != 1 || deadLetters.jobs[0].ID != job.ID || deadLetters.jobs[0].Status != StatusFailed {
+		t.Fatalf("Expected the failed job to be dead-lettered once, got %+v", deadLetters.jobs)
+	}
+	if deadLetters.errors[0].Error() != "HTTP Error 403: Forbidden" {
+		t.Fatalf("Dead letter error = %v", deadLetters.errors[0])
+	}
+}
+
+type recordingNotifier struct {
+	statuses []string
+}
+
+func (n *recordingNotifier) JobFinished(_ context.Context, job *DownloadJob) {
+	n.statuses = append(n.statuses, job.Status)
+}
+
+func TestWorkerPool_NotifiesFinishedJobs(t *testing.T) {
+	queue := newTestQueue(t)
+	ctx := context.Background()
+
+	processor := func(ctx context.Context, job *DownloadJob, progress func(int)) error {
+		if job.URL == "https://example.com/blocked.mp3" {
+			return notRetryableError{errors.New("HTTP Error 403: Forbidden")}
+		}
+		return nil
+	}
+	notifier := &recordingNotifier{}
+	pool := NewWorkerPool(queue, processor, &WorkerPoolConfig{WorkerCount: workerCountPtr(0), Notifier: notifier})
+
+	for _, url := range []string{"https://example.com/song.mp3", "https://example.com/blocked.mp3"} {
+		if _, err := queue.Enqueue(ctx, "notified-user", url, "test", nil); err != nil {
+			t.Fatalf("Failed to enqueue job: %v", err)
+		}
+		pool.processNextJob(ctx, 0)
+	}
+
+	if len(notifier.statuses) != 2 || notifier.statuses[0] != StatusComplete || notifier.statuses[1] != StatusFailed {
+		t.Fatalf("Notified statuses = %v, want complete then failed", notifier.statuses)
+	}
+}
+
 func TestWorkerPool_ZeroValueConfigUsesDefaultWorkerCount(t *testing.T) {
 	pool := NewWorkerPool(nil, nil, &WorkerPoolConfig{})
 
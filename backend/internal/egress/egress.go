@@ -0,0 +1,187 @@
+// Package egress routes the server's requests to outside providers through
+// proxies, for operators whose providers are geo-blocked where the server
+// runs or who send all outside traffic through a VPN. EXTERNAL_PROXIES names
+// an HTTP, HTTPS or SOCKS5 proxy per provider, such as
+// "default=socks5://vpn:1080,musicbrainz=direct".
+package egress
+
+import (
+	"context"
+	"fmt"
+	"net/http"
+	"net/url"
+	"slices"
+	"strings"
+	"time"
+)
+
+// Providers a proxy can be named for.
+const (
+	// MusicBrainz is the MusicBrainz web service client.
+	MusicBrainz = "musicbrainz"
+	// Discovery is source search: yt-dlp searches and the research gateway's
+	// web fetches.
+	Discovery = "discovery"
+	// Downloads is yt-dlp, external downloader commands and direct file
+	// URLs fetching download jobs.
+	Downloads = "downloads"
+)
+
+const (
+	// Default names the proxy of every provider without its own.
+	Default = "default"
+	// Direct as a provider's proxy sends its requests without one, whatever
+	// Default says.
+	Direct = "direct"
+)
+
+// Providers lists the providers in the order their proxies are checked.
+var Providers = []string{MusicBrainz, Discovery, Downloads}
+
+// probeURLs are the pages Check loads through each provider's proxy: ones
+// the provider's own requests go to, so a geo-block shows up too.
+var probeURLs = map[string]string{
+	MusicBrainz: "https://musicbrainz.org/",
+	Discovery:   "https://www.youtube.com/",
+	Downloads:   "https://soundcloud.com/",
+}
+
+const checkTimeout = 15 * time.Second
+
+// Proxies is the proxy of each provider. The zero value and nil send every
+// request as Go and yt-dlp do by default, honoring HTTP_PROXY and friends.
+type Proxies struct {
+	byProvider map[string]*url.URL
+}
+
+// Parse reads provider=proxy entries, as config.Config.ExternalProxies
+// holds them. A proxy is an http, https or socks5 URL, or "direct".
+func Parse(entries map[string]string) (*Proxies, error) {
+	explicit := make(map[string]*url.URL)
+	for name, value := range entries {
+		name = strings.ToLower(strings.TrimSpace(name))
+		if name != Default && !slices.Contains(Providers, name) {
+			return nil, fmt.Errorf("unknown proxy provider %q: want %s or %s", name, Default, strings.Join(Providers, ", "))
+		}
+		proxy, err := parseProxy(value)
+		if err != nil {
+			return nil, fmt.Errorf("proxy for %s: %w", name, err)
+		}
+		explicit[name] = proxy
+	}
+	proxies := &Proxies{byProvider: make(map[string]*url.URL)}
+	for _, provider := range Providers {
+		proxy, ok := explicit[provider]
+		if !ok {
+			proxy = explicit[Default]
+		}
+		if proxy != nil {
+			proxies.byProvider[provider] = proxy
+		}
+	}
+	return proxies, nil
+}
+
+func parseProxy(value string) (*url.URL, error) {
+	value = strings.TrimSpace(value)
+	if strings.EqualFold(value, Direct) {
+		return nil, nil
+	}
+	proxy, err := url.Parse(value)
+	if err != nil {
+		return nil, fmt.Errorf("invalid URL: %w", err)
+	}
+	switch proxy.Scheme {
+	case "http", "https", "socks5":
+	default:
+		return nil, fmt.Errorf("scheme %q is not http, https or socks5", proxy.Scheme)
+	}
+	if proxy.Hostname() == "" || strings.Trim(proxy.Path, "/") != "" {
+		return nil, fmt.Errorf("want scheme://[user:password@]host:port, got %s", proxy.Redacted())
+	}
+	return proxy, nil
+}
+
+// URL returns provider's proxy, or nil when it has none.
+func (p *Proxies) URL(provider string) *url.URL {
+	if p == nil {
+		return nil
+	}
+	return p.byProvider[provider]
+}
+
+// Configured lists the providers that have a proxy.
+func (p *Proxies) Configured() []string {
+	var configured []string
+	for _, provider := range Providers {
+		if p.URL(provider) != nil {
+			configured = append(configured, provider)
+		}
+	}
+	return configured
+}
+
+// Redacted returns provider's proxy URL with any password masked, for logs
+// and reports, or "direct".
+func (p *Proxies) Redacted(provider string) string {
+	if proxy := p.URL(provider); proxy != nil {
+		return proxy.Redacted()
+	}
+	return Direct
+}
+
+// ProxyFunc returns the http.Transport Proxy function for provider.
+// Providers without a proxy keep the environment's.
+func (p *Proxies) ProxyFunc(provider string) func(*http.Request) (*url.URL, error) {
+	if proxy := p.URL(provider); proxy != nil {
+		return http.ProxyURL(proxy)
+	}
+	return http.ProxyFromEnvironment
+}
+
+// Transport returns a copy of http.DefaultTransport that sends provider's
+// requests through its proxy.
+func (p *Proxies) Transport(provider string) *http.Transport {
+	transport := http.DefaultTransport.(*http.Transport).Clone()
+	transport.Proxy = p.ProxyFunc(provider)
+	return transport
+}
+
+// Env returns the environment variables that send a child process's
+// requests for provider through its proxy, for tools that honor them, or
+// nil when it has none.
+func (p *Proxies) Env(provider string) []string {
+	proxy := p.URL(provider)
+	if proxy == nil {
+		return nil
+	}
+	var env []string
+	for _, name := range []string{"HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"} {
+		env = append(env, name+"="+proxy.String(), strings.ToLower(name)+"="+proxy.String())
+	}
+	return env
+}
+
+// Check loads a page of provider's through its proxy, failing when the
+// proxy cannot be reached, refuses the request, or the provider answers
+// with an error as it does to blocked regions.
+func (p *Proxies) Check(ctx context.Context, provider string) error {
+	probe, ok := probeURLs[provider]
+	if !ok {
+		return fmt.Errorf("unknown provider %q", provider)
+	}
+	client := &http.Client{Timeout: checkTimeout, Transport: p.Transport(provider)}
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, probe, nil)
+	if err != nil {
+		return err
+	}
+	resp, err := client.Do(req)
+	if err != nil {
+		return fmt.Errorf("load %s through %s: %w", probe, p.Redacted(provider), err)
+	}
+	resp.Body.Close()
+	if resp.StatusCode >= 400 {
+		return fmt.Errorf("%s answered %s through %s", probe, resp.Status, p.Redacted(provider))
+	}
+	return nil
+}
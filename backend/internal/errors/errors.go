@@ -26,6 +26,11 @@ const (
 	CodeConflict        = "CONFLICT"
 	CodeRateLimited     = "RATE_LIMITED"
 
+	// Conditional requests
+	CodePreconditionRequired = "PRECONDITION_REQUIRED"
+	CodePreconditionFailed   = "PRECONDITION_FAILED"
+	CodeVersionConflict      = "VERSION_CONFLICT"
+
 	// Authentication specific
 	CodeInvalidCredentials = "INVALID_CREDENTIALS"
 	CodeInvalidToken       = "INVALID_TOKEN"
@@ -49,6 +54,7 @@ const (
 	CodeMusicBrainzError = "MUSICBRAINZ_ERROR"
 	CodeDownloadError    = "DOWNLOAD_ERROR"
 	CodeExternalTimeout  = "EXTERNAL_TIMEOUT"
+	CodeTimeout          = "TIMEOUT"
 )
 
 // AppError represents a structured application error
@@ -86,19 +92,6 @@ func (e *AppError) WithCause(err error) *AppError {
 	return e
 }
 
-// ErrorResponse is the JSON structure returned to clients
-type ErrorResponse struct {
-	Error ErrorBody `json:"error"`
-}
-
-// ErrorBody contains the error details
-type ErrorBody struct {
-	Code      string         `json:"code"`
-	Message   string         `json:"message"`
-	RequestID string         `json:"request_id,omitempty"`
-	Details   map[string]any `json:"details,omitempty"`
-}
-
 // New creates a new AppError
 func New(code string, message string, category ErrorCategory, httpStatus int) *AppError {
 	return &AppError{
@@ -207,33 +200,12 @@ func ExternalTimeout(service string) *AppError {
 	return New(CodeExternalTimeout, fmt.Sprintf("%s request timed out", service), CategoryExternal, http.StatusGatewayTimeout)
 }
 
-// WriteError writes an error response to the HTTP response writer
+// WriteError writes err as an application/problem+json response. Errors that
+// are not AppErrors are reported as internal errors without their cause.
 func WriteError(w http.ResponseWriter, requestID string, err error) {
-	var appErr *AppError
-
-	switch e := err.(type) {
-	case *AppError:
-		appErr = e
-	default:
-		// Wrap unknown errors as internal errors
-		appErr = InternalError("an unexpected error occurred").WithCause(err)
-	}
-
-	resp := ErrorResponse{
-		Error: ErrorBody{
-			Code:      appErr.Code,
-			Message:   appErr.Message,
-			RequestID: requestID,
-			Details:   appErr.Details,
-		},
-	}
-
-	w.Header().Set("Content-Type", "application/json")
-	if requestID != "" {
-		w.Header().Set("X-Request-ID", requestID)
-	}
-	w.WriteHeader(appErr.HTTPStatus)
-	json.NewEncoder(w).Encode(resp)
+	p := FromError(err)
+	p.RequestID = requestID
+	WriteProblem(w, nil, p)
 }
 
 // WriteJSON writes a JSON response with the request ID header
@@ -9,11 +9,16 @@ const (
 	RequestIDHeader = "X-Request-ID"
 )
 
-// RequestIDMiddleware injects a request ID into the context and response headers
+// RequestIDMiddleware injects a request ID into the context and response headers.
+// An ID already assigned by an outer middleware wins over the request header so
+// a single request never reports two different IDs.
 func RequestIDMiddleware(next http.Handler) http.Handler {
 	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
-		// Check if request ID is provided in header, otherwise generate one
-		requestID := r.Header.Get(RequestIDHeader)
+		// Reuse an upstream ID, then the request header, otherwise generate one
+		requestID := GetRequestID(r.Context())
+		if requestID == "" {
+			requestID = r.Header.Get(RequestIDHeader)
+		}
 		if requestID == "" {
 			requestID = GenerateRequestID()
 		}
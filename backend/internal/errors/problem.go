@@ -0,0 +1,153 @@
+package errors
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	stderrors "errors"
+	"net/http"
+	"strings"
+
+	"github.com/lib/pq"
+)
+
+// ProblemContentType is the RFC 9457 media type for problem details.
+const ProblemContentType = "application/problem+json"
+
+// problemTypePrefix namespaces stable problem type URIs. Clients branch on the
+// type (or the equivalent code); neither changes once published.
+const problemTypePrefix = "urn:openmusicplayer:problem:"
+
+// Problem is an RFC 9457 problem details body. Code and Message mirror the
+// legacy {code, message} envelope so existing clients keep working.
+type Problem struct {
+	Type      string         `json:"type"`
+	Title     string         `json:"title"`
+	Status    int            `json:"status"`
+	Detail    string         `json:"detail,omitempty"`
+	Instance  string         `json:"instance,omitempty"`
+	Code      string         `json:"code"`
+	Message   string         `json:"message"`
+	RequestID string         `json:"request_id,omitempty"`
+	Errors    []FieldError   `json:"errors,omitempty"`
+	Details   map[string]any `json:"details,omitempty"`
+}
+
+// FieldError describes one invalid input field.
+type FieldError struct {
+	Field  string `json:"field"`
+	Code   string `json:"code"`
+	Detail string `json:"detail"`
+}
+
+// Field error codes shared by input validation.
+const (
+	FieldInvalidInteger = "invalid_integer"
+	FieldOutOfRange     = "out_of_range"
+	FieldInvalidEnum    = "invalid_enum"
+	FieldInvalidUUID    = "invalid_uuid"
+	FieldInvalidBoolean = "invalid_boolean"
+	FieldTooLong        = "too_long"
+	FieldInvalidDate    = "invalid_date"
+	FieldInvalidZone    = "invalid_time_zone"
+)
+
+// ProblemType returns the stable type URI for an error code, e.g.
+// VALIDATION_ERROR -> urn:openmusicplayer:problem:validation-error.
+func ProblemType(code string) string {
+	return problemTypePrefix + strings.ReplaceAll(strings.ToLower(code), "_", "-")
+}
+
+// NewProblem builds a problem for code with the given status and detail.
+func NewProblem(status int, code, detail string) *Problem {
+	return &Problem{
+		Type:    ProblemType(code),
+		Title:   http.StatusText(status),
+		Status:  status,
+		Detail:  detail,
+		Code:    code,
+		Message: detail,
+	}
+}
+
+// ValidationProblem reports invalid request input as 422 Unprocessable Content.
+func ValidationProblem(errs []FieldError) *Problem {
+	detail := "request validation failed"
+	if len(errs) == 1 {
+		detail = errs[0].Detail
+	}
+	p := NewProblem(http.StatusUnprocessableEntity, CodeValidationError, detail)
+	p.Title = "Invalid request parameters"
+	p.Errors = errs
+	return p
+}
+
+// Problem converts the error into problem details.
+func (e *AppError) Problem() *Problem {
+	p := NewProblem(e.HTTPStatus, e.Code, e.Message)
+	p.Details = e.Details
+	return p
+}
+
+// PostgreSQL SQLSTATE codes surfaced as client errors.
+const (
+	pqUniqueViolation     = "23505"
+	pqForeignKeyViolation = "23503"
+)
+
+// FromError maps an error to problem details. AppErrors keep their status and
+// code; well-known database and context failures get stable codes; anything
+// else is a 500 whose message never leaks the underlying cause.
+func FromError(err error) *Problem {
+	var appErr *AppError
+	if stderrors.As(err, &appErr) {
+		return appErr.Problem()
+	}
+
+	var pqErr *pq.Error
+	switch {
+	case stderrors.Is(err, sql.ErrNoRows):
+		return NewProblem(http.StatusNotFound, CodeNotFound, "resource not found")
+	case stderrors.As(err, &pqErr) && pqErr.Code == pqUniqueViolation:
+		return NewProblem(http.StatusConflict, CodeConflict, "resource already exists")
+	case stderrors.As(err, &pqErr) && pqErr.Code == pqForeignKeyViolation:
+		return NewProblem(http.StatusConflict, CodeConflict, "referenced resource does not exist")
+	case stderrors.Is(err, context.DeadlineExceeded):
+		return NewProblem(http.StatusGatewayTimeout, CodeTimeout, "request timed out")
+	default:
+		return NewProblem(http.StatusInternalServerError, CodeInternalError, "an unexpected error occurred")
+	}
+}
+
+// WriteProblemError writes err as problem details.
+func WriteProblemError(w http.ResponseWriter, r *http.Request, err error) {
+	WriteProblem(w, r, FromError(err))
+}
+
+// WriteCodeProblem writes a problem for handlers that only have a status,
+// code and message at hand. The request ID is taken from the response header
+// set by the request ID middleware.
+func WriteCodeProblem(w http.ResponseWriter, status int, code, message string) {
+	p := NewProblem(status, code, message)
+	p.RequestID = w.Header().Get(RequestIDHeader)
+	WriteProblem(w, nil, p)
+}
+
+// WriteProblem writes p as application/problem+json, filling the instance and
+// request ID from r when they are not already set.
+func WriteProblem(w http.ResponseWriter, r *http.Request, p *Problem) {
+	if r != nil {
+		if p.Instance == "" {
+			p.Instance = r.URL.Path
+		}
+		if p.RequestID == "" {
+			p.RequestID = GetRequestID(r.Context())
+		}
+	}
+	if p.RequestID != "" {
+		w.Header().Set(RequestIDHeader, p.RequestID)
+	}
+	w.Header().Set("Content-Type", ProblemContentType)
+	w.WriteHeader(p.Status)
+	json.NewEncoder(w).Encode(p)
+}
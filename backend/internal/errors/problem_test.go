@@ -0,0 +1,76 @@
+package errors
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/lib/pq"
+)
+
+func TestFromErrorMapsKnownFailures(t *testing.T) {
+	tests := []struct {
+		name       string
+		err        error
+		wantStatus int
+		wantCode   string
+	}{
+		{"app error", EmailExists(), http.StatusConflict, CodeEmailExists},
+		{"wrapped app error", fmt.Errorf("register: %w", TokenExpired()), http.StatusUnauthorized, CodeTokenExpired},
+		{"musicbrainz", MusicBrainzError("server error: 503"), http.StatusBadGateway, CodeMusicBrainzError},
+		{"no rows", fmt.Errorf("get track: %w", sql.ErrNoRows), http.StatusNotFound, CodeNotFound},
+		{"unique violation", &pq.Error{Code: "23505"}, http.StatusConflict, CodeConflict},
+		{"deadline", context.DeadlineExceeded, http.StatusGatewayTimeout, CodeTimeout},
+		{"unknown", fmt.Errorf("dial tcp 10.0.0.5:5432: refused"), http.StatusInternalServerError, CodeInternalError},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			p := FromError(tt.err)
+			if p.Status != tt.wantStatus || p.Code != tt.wantCode || p.Type != ProblemType(tt.wantCode) {
+				t.Fatalf("FromError = %d %s %s, want %d %s", p.Status, p.Code, p.Type, tt.wantStatus, tt.wantCode)
+			}
+		})
+	}
+
+	if p := FromError(fmt.Errorf("dial tcp 10.0.0.5:5432: refused")); p.Detail != "an unexpected error occurred" {
+		t.Fatalf("internal error detail %q must not leak the cause", p.Detail)
+	}
+}
+
+func TestWriteErrorEmitsProblemDetails(t *testing.T) {
+	rec := httptest.NewRecorder()
+	WriteError(rec, "req-1", NotFound("playlist").WithDetails(map[string]any{"id": "p1"}))
+
+	if rec.Code != http.StatusNotFound || rec.Header().Get("Content-Type") != ProblemContentType {
+		t.Fatalf("status %d content type %q", rec.Code, rec.Header().Get("Content-Type"))
+	}
+	if rec.Header().Get(RequestIDHeader) != "req-1" {
+		t.Fatalf("request ID header = %q", rec.Header().Get(RequestIDHeader))
+	}
+	var body Problem
+	if err := json.NewDecoder(rec.Body).Decode(&body); err != nil {
+		t.Fatal(err)
+	}
+	if body.Type != "urn:openmusicplayer:problem:not-found" || body.Title != "Not Found" ||
+		body.Code != CodeNotFound || body.Message != "playlist not found" || body.RequestID != "req-1" || body.Details["id"] != "p1" {
+		t.Fatalf("problem = %+v", body)
+	}
+}
+
+func TestWriteCodeProblemUsesResponseRequestID(t *testing.T) {
+	rec := httptest.NewRecorder()
+	rec.Header().Set(RequestIDHeader, "req-2")
+	WriteCodeProblem(rec, http.StatusConflict, "TRACK_ALREADY_IN_PLAYLIST", "track already in playlist")
+
+	var body Problem
+	if err := json.NewDecoder(rec.Body).Decode(&body); err != nil {
+		t.Fatal(err)
+	}
+	if body.Status != http.StatusConflict || body.Type != "urn:openmusicplayer:problem:track-already-in-playlist" || body.RequestID != "req-2" {
+		t.Fatalf("problem = %+v", body)
+	}
+}
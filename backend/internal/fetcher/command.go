@@ -0,0 +1,126 @@
+package fetcher
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io/fs"
+	"mime"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strings"
+)
+
+// Command runs an external tool, such as gallery-dl or streamrip, that writes
+// audio into a directory. Args may contain the placeholders {url} and {dir};
+// the largest audio file the tool leaves in {dir} is the result.
+type Command struct {
+	name     string
+	args     []string
+	env      []string
+	maxBytes int64
+}
+
+// NewCommand returns a downloader that runs args[0] with args[1:]. The
+// downloader's name is the executable's base name.
+func NewCommand(args []string, maxBytes int64) (*Command, error) {
+	if len(args) == 0 || strings.TrimSpace(args[0]) == "" {
+		return nil, errors.New("downloader command is empty")
+	}
+	if maxBytes <= 0 {
+		maxBytes = DefaultMaxBytes
+	}
+	return &Command{name: filepath.Base(args[0]), args: args, maxBytes: maxBytes}, nil
+}
+
+func (c *Command) Name() string { return c.name }
+
+// WithEnv adds env, as KEY=value entries, to the server's environment the
+// tool runs with, such as HTTPS_PROXY for a proxy it should use.
+func (c *Command) WithEnv(env []string) *Command {
+	c.env = env
+	return c
+}
+
+// Probe checks that the executable is installed.
+func (c *Command) Probe(ctx context.Context, req Request) error {
+	if _, err := exec.LookPath(c.args[0]); err != nil {
+		return fmt.Errorf("%w: %s is not installed", ErrUnavailable, c.args[0])
+	}
+	return nil
+}
+
+// Metadata is empty: external tools only describe a track by downloading it.
+func (c *Command) Metadata(ctx context.Context, req Request) (Metadata, error) {
+	return Metadata{}, nil
+}
+
+// Fetch runs the tool in a fresh directory and moves the largest audio file
+// it produced out before the directory is removed.
+func (c *Command) Fetch(ctx context.Context, req Request, progress ProgressFunc) (*Result, error) {
+	if err := c.Probe(ctx, req); err != nil {
+		return nil, err
+	}
+	dir, err := os.MkdirTemp("", "omp-fetch-*")
+	if err != nil {
+		return nil, err
+	}
+	defer os.RemoveAll(dir)
+
+	args := make([]string, len(c.args)-1)
+	for i, arg := range c.args[1:] {
+		args[i] = strings.NewReplacer("{url}", req.URL, "{dir}", dir).Replace(arg)
+	}
+	cmd := exec.CommandContext(ctx, c.args[0], args...)
+	cmd.Dir = dir
+	if len(c.env) > 0 {
+		cmd.Env = append(os.Environ(), c.env...)
+	}
+	if output, err := cmd.CombinedOutput(); err != nil {
+		const maxLog = 4096
+		detail := strings.TrimSpace(string(output))
+		if len(detail) > maxLog {
+			detail = detail[len(detail)-maxLog:]
+		}
+		return nil, fmt.Errorf("%s failed: %w: %s", c.name, err, detail)
+	}
+
+	var best string
+	var bestSize int64
+	err = filepath.WalkDir(dir, func(path string, entry fs.DirEntry, err error) error {
+		if err != nil || entry.IsDir() || !audioExtensions[strings.ToLower(filepath.Ext(path))] {
+			return err
+		}
+		info, err := entry.Info()
+		if err != nil {
+			return err
+		}
+		if info.Size() > bestSize {
+			best, bestSize = path, info.Size()
+		}
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	if best == "" {
+		return nil, fmt.Errorf("%s did not produce an audio file", c.name)
+	}
+	if bestSize > c.maxBytes {
+		return nil, fmt.Errorf("downloaded file too large: %d bytes", bestSize)
+	}
+
+	ext := strings.ToLower(filepath.Ext(best))
+	out, err := os.CreateTemp("", "omp-download-*"+ext)
+	if err != nil {
+		return nil, err
+	}
+	out.Close()
+	if err := os.Rename(best, out.Name()); err != nil {
+		os.Remove(out.Name())
+		return nil, err
+	}
+	report(progress, 100)
+	return &Result{Path: out.Name(), ContentType: mime.TypeByExtension(ext)}, nil
+}
@@ -0,0 +1,127 @@
+package fetcher
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"net/http"
+	"net/http/cookiejar"
+	"net/url"
+	"os"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// ParseCookies reads a Netscape cookies.txt, the format browser cookie
+// exporters write and yt-dlp's --cookies reads. A cookie's Domain keeps the
+// file's leading dot when it is sent to subdomains too; host-only cookies
+// have none. Session cookies have a zero Expires.
+func ParseCookies(data []byte) ([]*http.Cookie, error) {
+	var cookies []*http.Cookie
+	scanner := bufio.NewScanner(bytes.NewReader(data))
+	for line := 1; scanner.Scan(); line++ {
+		text := strings.TrimRight(scanner.Text(), "\r")
+		httpOnly := false
+		if rest, ok := strings.CutPrefix(text, "#HttpOnly_"); ok {
+			text, httpOnly = rest, true
+		}
+		if strings.TrimSpace(text) == "" || strings.HasPrefix(text, "#") {
+			continue
+		}
+		fields := strings.Split(text, "\t")
+		if len(fields) == 6 {
+			// Some exporters drop the tab before an empty value.
+			fields = append(fields, "")
+		}
+		if len(fields) != 7 {
+			return nil, fmt.Errorf("line %d: want 7 tab-separated fields, got %d", line, len(fields))
+		}
+		host := strings.ToLower(strings.TrimPrefix(fields[0], "."))
+		if host == "" || fields[5] == "" {
+			return nil, fmt.Errorf("line %d: cookie has no domain or name", line)
+		}
+		expires, err := strconv.ParseInt(fields[4], 10, 64)
+		if err != nil {
+			return nil, fmt.Errorf("line %d: invalid expiry %q", line, fields[4])
+		}
+		cookie := &http.Cookie{
+			Name:     fields[5],
+			Value:    fields[6],
+			Domain:   host,
+			Path:     fields[2],
+			Secure:   strings.EqualFold(fields[3], "TRUE"),
+			HttpOnly: httpOnly,
+		}
+		if strings.EqualFold(fields[1], "TRUE") {
+			cookie.Domain = "." + host
+		}
+		if expires > 0 {
+			cookie.Expires = time.Unix(expires, 0).UTC()
+		}
+		cookies = append(cookies, cookie)
+	}
+	return cookies, scanner.Err()
+}
+
+// FormatCookies writes cookies in the format ParseCookies reads.
+func FormatCookies(cookies []*http.Cookie) []byte {
+	var buf bytes.Buffer
+	buf.WriteString("# Netscape HTTP Cookie File\n")
+	for _, c := range cookies {
+		if c.HttpOnly {
+			buf.WriteString("#HttpOnly_")
+		}
+		var expires int64
+		if !c.Expires.IsZero() {
+			expires = c.Expires.Unix()
+		}
+		fmt.Fprintf(&buf, "%s\t%s\t%s\t%s\t%d\t%s\t%s\n",
+			c.Domain, netscapeBool(strings.HasPrefix(c.Domain, ".")), c.Path, netscapeBool(c.Secure), expires, c.Name, c.Value)
+	}
+	return buf.Bytes()
+}
+
+// WriteCookieFile writes cookies to a new temporary cookies.txt readable only
+// by this user, for tools that take a cookie file. The caller removes it.
+func WriteCookieFile(cookies []*http.Cookie) (string, error) {
+	file, err := os.CreateTemp("", "omp-cookies-*.txt")
+	if err != nil {
+		return "", err
+	}
+	_, err = file.Write(FormatCookies(cookies))
+	if closeErr := file.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		os.Remove(file.Name())
+		return "", err
+	}
+	return file.Name(), nil
+}
+
+// cookieJar holds cookies read by ParseCookies, so that each request,
+// redirects included, carries only the cookies of its own host.
+func cookieJar(cookies []*http.Cookie) http.CookieJar {
+	jar, _ := cookiejar.New(nil)
+	for _, c := range cookies {
+		cookie := *c
+		scheme := "http"
+		if cookie.Secure {
+			scheme = "https"
+		}
+		host := strings.TrimPrefix(cookie.Domain, ".")
+		if !strings.HasPrefix(cookie.Domain, ".") {
+			cookie.Domain = ""
+		}
+		jar.SetCookies(&url.URL{Scheme: scheme, Host: host, Path: cookie.Path}, []*http.Cookie{&cookie})
+	}
+	return jar
+}
+
+func netscapeBool(value bool) string {
+	if value {
+		return "TRUE"
+	}
+	return "FALSE"
+}
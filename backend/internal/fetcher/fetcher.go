@@ -0,0 +1,167 @@
+// Package fetcher abstracts how a download job's audio is obtained. Each
+// Downloader handles one kind of source (yt-dlp sites, direct file URLs,
+// external tools such as gallery-dl or streamrip); a Registry routes each job
+// to one by its source type, then by URL, then to a fallback.
+package fetcher
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/http"
+	"net/url"
+	"strings"
+)
+
+var (
+	// ErrNoDownloader is returned when nothing is registered for a source.
+	ErrNoDownloader = errors.New("no downloader for source")
+	// ErrUnavailable is returned by Probe when a downloader cannot run, for
+	// example because its binary is not installed.
+	ErrUnavailable = errors.New("downloader unavailable")
+	// ErrNotAudio is returned when a source serves something other than audio,
+	// such as a login page.
+	ErrNotAudio = errors.New("not an audio file")
+)
+
+// Request identifies the source to fetch.
+type Request struct {
+	JobID      string
+	SourceType string
+	URL        string
+	// Headers are sent with HTTP requests for the source, for example to
+	// authenticate against a private file host.
+	Headers map[string]string
+	// Cookies are the requesting user's login cookies for the source, as
+	// read by ParseCookies. Downloaders send each only to its own domain.
+	Cookies []*http.Cookie
+}
+
+// Metadata is what a source reports about a track. Empty fields are unknown.
+type Metadata struct {
+	Title      string
+	Artist     string
+	Album      string
+	Uploader   string
+	DurationMs int
+	// Raw is the source's own metadata document, when it has one.
+	Raw map[string]interface{}
+}
+
+// Result is a fetched audio file. The caller owns Path and removes it.
+type Result struct {
+	Path        string
+	ContentType string
+	Metadata    Metadata
+	// SHA256 is the hex digest of the file when the downloader hashed it
+	// while streaming, and empty otherwise.
+	SHA256 string
+}
+
+// ProgressFunc receives download progress as a percentage from 0 to 100.
+type ProgressFunc func(percent int)
+
+// Downloader fetches audio for the sources it is registered for.
+type Downloader interface {
+	// Name identifies the downloader in logs and errors.
+	Name() string
+	// Probe checks that the source can be fetched now without downloading it.
+	Probe(ctx context.Context, req Request) error
+	// Metadata reports what the source says about the track without fetching
+	// the audio. Downloaders that cannot tell return an empty Metadata.
+	Metadata(ctx context.Context, req Request) (Metadata, error)
+	// Fetch downloads the audio into a temporary file, reporting progress
+	// when it can. progress may be nil.
+	Fetch(ctx context.Context, req Request, progress ProgressFunc) (*Result, error)
+}
+
+type urlMatch struct {
+	match      func(*url.URL) bool
+	downloader Downloader
+}
+
+// Registry routes requests to downloaders. A registered source type wins, then
+// the URL scheme, then URL matchers in registration order, then the fallback.
+// Register everything before the registry is shared between goroutines.
+type Registry struct {
+	bySourceType map[string]Downloader
+	byScheme     map[string]Downloader
+	matchers     []urlMatch
+	fallback     Downloader
+}
+
+// NewRegistry returns a registry that sends unmatched sources to fallback,
+// which may be nil.
+func NewRegistry(fallback Downloader) *Registry {
+	return &Registry{
+		bySourceType: make(map[string]Downloader),
+		byScheme:     make(map[string]Downloader),
+		fallback:     fallback,
+	}
+}
+
+// RegisterSourceType routes jobs whose source type is sourceType to d.
+func (r *Registry) RegisterSourceType(sourceType string, d Downloader) *Registry {
+	r.bySourceType[normalize(sourceType)] = d
+	return r
+}
+
+// RegisterScheme routes URLs with the given scheme (such as "file") to d.
+func (r *Registry) RegisterScheme(scheme string, d Downloader) *Registry {
+	r.byScheme[normalize(scheme)] = d
+	return r
+}
+
+// RegisterMatch routes URLs for which match returns true to d.
+func (r *Registry) RegisterMatch(match func(*url.URL) bool, d Downloader) *Registry {
+	r.matchers = append(r.matchers, urlMatch{match: match, downloader: d})
+	return r
+}
+
+// Route returns the downloader for req.
+func (r *Registry) Route(req Request) (Downloader, error) {
+	if d, ok := r.bySourceType[normalize(req.SourceType)]; ok {
+		return d, nil
+	}
+	// Schemes are matched on the raw prefix so that local paths which are
+	// not valid URLs (spaces, percent signs) still route as file://.
+	if scheme, _, ok := strings.Cut(req.URL, "://"); ok {
+		if d, ok := r.byScheme[normalize(scheme)]; ok {
+			return d, nil
+		}
+	}
+	if parsed, err := url.Parse(req.URL); err == nil {
+		for _, m := range r.matchers {
+			if m.match(parsed) {
+				return m.downloader, nil
+			}
+		}
+	}
+	if r.fallback != nil {
+		return r.fallback, nil
+	}
+	return nil, fmt.Errorf("%w: source type %q, url %q", ErrNoDownloader, req.SourceType, req.URL)
+}
+
+// Fetch routes req and fetches it.
+func (r *Registry) Fetch(ctx context.Context, req Request, progress ProgressFunc) (*Result, error) {
+	d, err := r.Route(req)
+	if err != nil {
+		return nil, err
+	}
+	result, err := d.Fetch(ctx, req, progress)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", d.Name(), err)
+	}
+	return result, nil
+}
+
+func normalize(key string) string {
+	return strings.ToLower(strings.TrimSpace(key))
+}
+
+func report(progress ProgressFunc, percent int) {
+	if progress != nil {
+		progress(min(max(percent, 0), 100))
+	}
+}
@@ -0,0 +1,254 @@
+package fetcher
+
+import (
+	"bytes"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"os"
+	"path/filepath"
+	"runtime"
+	"strconv"
+	"strings"
+	"testing"
+)
+
+type stubDownloader struct{ name string }
+
+func (s stubDownloader) Name() string { return s.name }
+
+func (s stubDownloader) Probe(ctx context.Context, req Request) error { return nil }
+
+func (s stubDownloader) Metadata(ctx context.Context, req Request) (Metadata, error) {
+	return Metadata{}, nil
+}
+
+func (s stubDownloader) Fetch(ctx context.Context, req Request, progress ProgressFunc) (*Result, error) {
+	return nil, errors.New("boom")
+}
+
+func TestRegistryRoutesBySourceTypeThenSchemeThenMatcher(t *testing.T) {
+	registry := NewRegistry(stubDownloader{"fallback"}).
+		RegisterSourceType("Bandcamp", stubDownloader{"bandcamp"}).
+		RegisterScheme("file", stubDownloader{"file"}).
+		RegisterMatch(IsDirectAudioURL, stubDownloader{"direct"})
+
+	tests := []struct {
+		req  Request
+		want string
+	}{
+		{Request{SourceType: "bandcamp", URL: "https://x.bandcamp.com/track/a.mp3"}, "bandcamp"},
+		{Request{SourceType: "local", URL: "file:///music/100% pure.flac"}, "file"},
+		{Request{SourceType: "url", URL: "https://cdn.example/song.MP3?sig=1"}, "direct"},
+		{Request{SourceType: "youtube", URL: "https://youtube.com/watch?v=1"}, "fallback"},
+	}
+	for _, tt := range tests {
+		d, err := registry.Route(tt.req)
+		if err != nil || d.Name() != tt.want {
+			t.Errorf("Route(%+v) = %v, %v; want %s", tt.req, d, err, tt.want)
+		}
+	}
+
+	if _, err := NewRegistry(nil).Route(Request{URL: "https://example.test"}); !errors.Is(err, ErrNoDownloader) {
+		t.Fatalf("Route() without fallback = %v, want ErrNoDownloader", err)
+	}
+	if _, err := registry.Fetch(context.Background(), Request{SourceType: "bandcamp"}, nil); err == nil || err.Error() != "bandcamp: boom" {
+		t.Fatalf("Fetch() error = %v, want it prefixed with the downloader name", err)
+	}
+}
+
+func TestHTTPFetchReportsProgressAndEnforcesLimit(t *testing.T) {
+	body := append([]byte("ID3"), bytes.Repeat([]byte{0}, 997)...)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "audio/mpeg")
+		w.Header().Set("Content-Length", strconv.Itoa(len(body)))
+		w.Write(body)
+	}))
+	defer server.Close()
+	req := Request{URL: server.URL + "/track.mp3"}
+
+	var reported []int
+	result, err := NewHTTP(server.Client(), 0).Fetch(context.Background(), req, func(percent int) {
+		reported = append(reported, percent)
+	})
+	if err != nil {
+		t.Fatalf("Fetch() error = %v", err)
+	}
+	defer os.Remove(result.Path)
+	if data, _ := os.ReadFile(result.Path); !bytes.Equal(data, body) {
+		t.Fatalf("fetched %d bytes, want %d", len(data), len(body))
+	}
+	if result.ContentType != "audio/mpeg" || filepath.Ext(result.Path) != ".mp3" {
+		t.Fatalf("result = %+v", result)
+	}
+	if len(reported) == 0 || reported[len(reported)-1] != 100 {
+		t.Fatalf("progress = %v, want it to end at 100", reported)
+	}
+	for i := 1; i < len(reported); i++ {
+		if reported[i] <= reported[i-1] {
+			t.Fatalf("progress = %v, want strictly increasing", reported)
+		}
+	}
+
+	small := NewHTTP(server.Client(), 100)
+	if err := small.Probe(context.Background(), req); err == nil || !strings.Contains(err.Error(), "too large") {
+		t.Fatalf("Probe() over the limit = %v", err)
+	}
+	if _, err := small.Fetch(context.Background(), req, nil); err == nil || !strings.Contains(err.Error(), "too large") {
+		t.Fatalf("Fetch() over the limit = %v", err)
+	}
+}
+
+func TestHTTPFetchSendsHeadersSniffsTypeAndHashes(t *testing.T) {
+	flac := append([]byte("fLaC"), bytes.Repeat([]byte{0}, 60)...)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("Authorization") != "Bearer secret" {
+			http.Error(w, "unauthorized", http.StatusUnauthorized)
+			return
+		}
+		switch r.URL.Path {
+		case "/download":
+			w.Header().Set("Content-Type", "application/octet-stream")
+			w.Write(flac)
+		case "/login.mp3":
+			w.Header().Set("Content-Type", "audio/mpeg")
+			w.Write([]byte("<!DOCTYPE html><html><body>Please sign in</body></html>"))
+		}
+	}))
+	defer server.Close()
+	downloader := NewHTTP(server.Client(), 0)
+	headers := map[string]string{"Authorization": "Bearer secret"}
+
+	if _, err := downloader.Fetch(context.Background(), Request{URL: server.URL + "/download"}, nil); err == nil || !strings.Contains(err.Error(), "401") {
+		t.Fatalf("Fetch() without headers = %v, want 401", err)
+	}
+	result, err := downloader.Fetch(context.Background(), Request{URL: server.URL + "/download", Headers: headers}, nil)
+	if err != nil {
+		t.Fatalf("Fetch() error = %v", err)
+	}
+	defer os.Remove(result.Path)
+	sum := sha256.Sum256(flac)
+	if result.ContentType != "audio/flac" || filepath.Ext(result.Path) != ".flac" || result.SHA256 != hex.EncodeToString(sum[:]) {
+		t.Fatalf("result = %+v", result)
+	}
+
+	if _, err := downloader.Fetch(context.Background(), Request{URL: server.URL + "/login.mp3", Headers: headers}, nil); !errors.Is(err, ErrNotAudio) {
+		t.Fatalf("Fetch() of an HTML page = %v, want ErrNotAudio", err)
+	}
+}
+
+func TestParseCookiesRoundTripsAndHTTPSendsThemToTheirHost(t *testing.T) {
+	file := "# Netscape HTTP Cookie File\n" +
+		"127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc\n" +
+		"#HttpOnly_.other.example\tTRUE\t/\tTRUE\t4102444800\tsid\txyz\n" +
+		"127.0.0.1\tFALSE\t/\tFALSE\t0\tempty\n"
+	cookies, err := ParseCookies([]byte(file))
+	if err != nil {
+		t.Fatalf("ParseCookies() error = %v", err)
+	}
+	if len(cookies) != 3 || cookies[1].Domain != ".other.example" || !cookies[1].HttpOnly || !cookies[1].Secure || cookies[1].Expires.Unix() != 4102444800 || cookies[2].Value != "" {
+		t.Fatalf("ParseCookies() = %+v", cookies)
+	}
+	again, err := ParseCookies(FormatCookies(cookies))
+	if err != nil || len(again) != 3 || again[0].Domain != "127.0.0.1" || again[1].String() != cookies[1].String() {
+		t.Fatalf("ParseCookies(FormatCookies()) = %+v, %v", again, err)
+	}
+	if _, err := ParseCookies([]byte("example.com\tFALSE\t/\n")); err == nil || !strings.Contains(err.Error(), "line 1") {
+		t.Fatalf("ParseCookies() of a short line = %v", err)
+	}
+
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path == "/private" {
+			http.Redirect(w, r, "/private.mp3", http.StatusFound)
+			return
+		}
+		if got := r.Header.Get("Cookie"); !strings.Contains(got, "session=abc") || strings.Contains(got, "sid=") {
+			http.Error(w, "cookies "+got, http.StatusForbidden)
+			return
+		}
+		w.Write([]byte("ID3\x04\x00\x00\x00\x00\x00\x00audio"))
+	}))
+	defer server.Close()
+	result, err := NewHTTP(server.Client(), 0).Fetch(context.Background(), Request{URL: server.URL + "/private", Cookies: cookies}, nil)
+	if err != nil {
+		t.Fatalf("Fetch() with cookies error = %v", err)
+	}
+	os.Remove(result.Path)
+}
+
+func TestHTTPDefaultClientRefusesNonPublicAddresses(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		t.Error("request reached a loopback server")
+	}))
+	defer server.Close()
+
+	if _, err := NewHTTP(nil, 0).Fetch(context.Background(), Request{URL: server.URL + "/track.mp3"}, nil); !errors.Is(err, ErrPrivateAddress) {
+		t.Fatalf("Fetch() from loopback = %v, want ErrPrivateAddress", err)
+	}
+
+	// A proxy on the server's own network is fine; loopback targets still are not.
+	proxy := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Host != "203.0.113.7" {
+			t.Errorf("proxy asked for %s", r.URL)
+		}
+		w.Write([]byte("ID3\x04\x00audio"))
+	}))
+	defer proxy.Close()
+	proxyURL, _ := url.Parse(proxy.URL)
+	proxied := NewHTTP(PublicClient(proxyURL), 0)
+	if _, err := proxied.Fetch(context.Background(), Request{URL: server.URL + "/track.mp3"}, nil); !errors.Is(err, ErrPrivateAddress) {
+		t.Fatalf("Fetch() from loopback through a proxy = %v, want ErrPrivateAddress", err)
+	}
+	result, err := proxied.Fetch(context.Background(), Request{URL: "http://203.0.113.7/track.mp3"}, nil)
+	if err != nil {
+		t.Fatalf("Fetch() through a loopback proxy error = %v", err)
+	}
+	os.Remove(result.Path)
+}
+
+func TestCommandFetchKeepsLargestAudioFile(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("fake downloader is a shell script")
+	}
+	script := filepath.Join(t.TempDir(), "fake-rip")
+	err := os.WriteFile(script, []byte(`#!/bin/sh
+set -e
+echo "$2" > "$1/cover.jpg"
+echo short > "$1/intro.flac"
+echo "a much longer track body" > "$1/track.flac"
+`), 0o755)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	command, err := NewCommand([]string{script, "{dir}", "{url}"}, 0)
+	if err != nil {
+		t.Fatalf("NewCommand() error = %v", err)
+	}
+	if command.Name() != "fake-rip" {
+		t.Fatalf("Name() = %q", command.Name())
+	}
+	result, err := command.Fetch(context.Background(), Request{URL: "https://example.test/album"}, nil)
+	if err != nil {
+		t.Fatalf("Fetch() error = %v", err)
+	}
+	defer os.Remove(result.Path)
+	if data, _ := os.ReadFile(result.Path); string(data) != "a much longer track body\n" {
+		t.Fatalf("fetched %q, want the largest audio file", data)
+	}
+	if filepath.Ext(result.Path) != ".flac" {
+		t.Fatalf("Path = %q, want the track's extension kept", result.Path)
+	}
+
+	missing, _ := NewCommand([]string{filepath.Join(t.TempDir(), "missing")}, 0)
+	if err := missing.Probe(context.Background(), Request{}); !errors.Is(err, ErrUnavailable) {
+		t.Fatalf("Probe() for a missing binary = %v, want ErrUnavailable", err)
+	}
+	if _, err := NewCommand(nil, 0); err == nil {
+		t.Fatal("NewCommand(nil) succeeded")
+	}
+}
@@ -0,0 +1,325 @@
+package fetcher
+
+import (
+	"bytes"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"mime"
+	"net"
+	"net/http"
+	"net/netip"
+	"net/url"
+	"os"
+	"path"
+	"strings"
+	"syscall"
+	"time"
+)
+
+// DefaultMaxBytes bounds a single fetched file.
+const DefaultMaxBytes = 256 * 1024 * 1024
+
+// audioExtensions are the file types a direct URL may point at.
+var audioExtensions = map[string]bool{
+	".mp3": true, ".m4a": true, ".aac": true, ".flac": true, ".ogg": true,
+	".oga": true, ".opus": true, ".wav": true, ".aif": true, ".aiff": true,
+}
+
+// extensionsByType gives fetched files the extension of their sniffed type,
+// whatever the URL ended in.
+var extensionsByType = map[string]string{
+	"audio/mpeg": ".mp3", "audio/mp4": ".m4a", "audio/aac": ".aac", "audio/flac": ".flac",
+	"audio/ogg": ".ogg", "application/ogg": ".ogg", "audio/wav": ".wav", "audio/aiff": ".aiff",
+}
+
+// ErrPrivateAddress is returned when the default client is asked to connect
+// to a loopback, private, or link-local address.
+var ErrPrivateAddress = errors.New("refusing to connect to a non-public address")
+
+// IsDirectAudioURL reports whether u is an http(s) URL whose path names an
+// audio file, so it can be fetched as-is rather than through an extractor.
+func IsDirectAudioURL(u *url.URL) bool {
+	if u.Scheme != "http" && u.Scheme != "https" {
+		return false
+	}
+	return IsAudioFile(u.Path)
+}
+
+// IsAudioFile reports whether name has one of the audio file extensions a
+// downloader will accept.
+func IsAudioFile(name string) bool {
+	return audioExtensions[strings.ToLower(path.Ext(name))]
+}
+
+// HTTP fetches audio files served directly over HTTP(S).
+type HTTP struct {
+	client   *http.Client
+	maxBytes int64
+}
+
+// NewHTTP returns an HTTP downloader. A nil client uses PublicClient(nil);
+// maxBytes <= 0 uses DefaultMaxBytes.
+func NewHTTP(client *http.Client, maxBytes int64) *HTTP {
+	if client == nil {
+		client = PublicClient(nil)
+	}
+	if maxBytes <= 0 {
+		maxBytes = DefaultMaxBytes
+	}
+	return &HTTP{client: client, maxBytes: maxBytes}
+}
+
+// PublicClient returns a client with a generous timeout that only reaches
+// public addresses, so user-submitted URLs cannot reach services on the
+// server's own network. With a proxy, which may itself be on that network,
+// requests go through it once their host resolves only to public addresses.
+func PublicClient(proxy *url.URL) *http.Client {
+	transport := http.DefaultTransport.(*http.Transport).Clone()
+	if proxy == nil {
+		dialer := &net.Dialer{Timeout: 30 * time.Second, Control: refuseNonPublicAddress}
+		transport.DialContext = dialer.DialContext
+	} else {
+		transport.Proxy = func(req *http.Request) (*url.URL, error) {
+			if err := refuseNonPublicHost(req.Context(), req.URL.Hostname()); err != nil {
+				return nil, err
+			}
+			return proxy, nil
+		}
+	}
+	return &http.Client{Timeout: 10 * time.Minute, Transport: transport}
+}
+
+func (h *HTTP) Name() string { return "http" }
+
+// Probe issues a HEAD request and checks the size and content type.
+func (h *HTTP) Probe(ctx context.Context, req Request) error {
+	resp, err := h.do(ctx, http.MethodHead, req)
+	if err != nil {
+		return err
+	}
+	resp.Body.Close()
+	if resp.ContentLength > h.maxBytes {
+		return fmt.Errorf("file too large: %d bytes", resp.ContentLength)
+	}
+	if contentType := mediaType(resp.Header.Get("Content-Type")); contentType != "" && !acceptedContentType(contentType) {
+		return fmt.Errorf("%w: %s serves %s, not audio", ErrUnavailable, req.URL, contentType)
+	}
+	return nil
+}
+
+// Metadata uses the file name as the title; plain HTTP carries nothing else.
+func (h *HTTP) Metadata(ctx context.Context, req Request) (Metadata, error) {
+	u, err := url.Parse(req.URL)
+	if err != nil {
+		return Metadata{}, err
+	}
+	name := path.Base(u.Path)
+	return Metadata{Title: strings.TrimSuffix(name, path.Ext(name))}, nil
+}
+
+// Fetch streams the body to a temporary file with Save, reporting progress
+// when the server sends a Content-Length.
+func (h *HTTP) Fetch(ctx context.Context, req Request, progress ProgressFunc) (*Result, error) {
+	resp, err := h.do(ctx, http.MethodGet, req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+	if resp.ContentLength > h.maxBytes {
+		return nil, fmt.Errorf("downloaded file too large: %d bytes", resp.ContentLength)
+	}
+	u, _ := url.Parse(req.URL)
+	return Save(resp.Body, resp.ContentLength, mediaType(resp.Header.Get("Content-Type")), u.Path, h.maxBytes, progress)
+}
+
+// Save streams body to a temporary file, hashing it on the way and reporting
+// progress against size when it is known. The content type is sniffed from
+// the first bytes, so a mislabelled file still gets the right type and an
+// HTML error page is rejected; declared and name's extension are fallbacks.
+// maxBytes <= 0 uses DefaultMaxBytes.
+func Save(body io.Reader, size int64, declared, name string, maxBytes int64, progress ProgressFunc) (*Result, error) {
+	if maxBytes <= 0 {
+		maxBytes = DefaultMaxBytes
+	}
+	head := make([]byte, 512)
+	n, err := io.ReadFull(body, head)
+	if err != nil && err != io.EOF && err != io.ErrUnexpectedEOF {
+		return nil, err
+	}
+	head = head[:n]
+	ext := strings.ToLower(path.Ext(name))
+	contentType, err := detectAudioType(declared, head, ext)
+	if err != nil {
+		return nil, err
+	}
+	if byType, ok := extensionsByType[contentType]; ok {
+		ext = byType
+	} else if !audioExtensions[ext] {
+		ext = ""
+	}
+
+	out, err := os.CreateTemp("", "omp-download-*"+ext)
+	if err != nil {
+		return nil, err
+	}
+	hash := sha256.New()
+	written, err := io.Copy(io.MultiWriter(out, hash), &progressReader{
+		reader:   io.LimitReader(io.MultiReader(bytes.NewReader(head), body), maxBytes+1),
+		total:    size,
+		progress: progress,
+	})
+	if closeErr := out.Close(); err == nil {
+		err = closeErr
+	}
+	if err == nil && written > maxBytes {
+		err = fmt.Errorf("downloaded file too large: more than %d bytes", maxBytes)
+	}
+	if err != nil {
+		os.Remove(out.Name())
+		return nil, err
+	}
+	report(progress, 100)
+	return &Result{Path: out.Name(), ContentType: contentType, SHA256: hex.EncodeToString(hash.Sum(nil))}, nil
+}
+
+func (h *HTTP) do(ctx context.Context, method string, req Request) (*http.Response, error) {
+	httpReq, err := http.NewRequestWithContext(ctx, method, req.URL, nil)
+	if err != nil {
+		return nil, err
+	}
+	for name, value := range req.Headers {
+		httpReq.Header.Set(name, value)
+	}
+	client := h.client
+	if len(req.Cookies) > 0 {
+		withCookies := *h.client
+		withCookies.Jar = cookieJar(req.Cookies)
+		client = &withCookies
+	}
+	resp, err := client.Do(httpReq)
+	if err != nil {
+		return nil, err
+	}
+	if resp.StatusCode < 200 || resp.StatusCode > 299 {
+		resp.Body.Close()
+		return nil, fmt.Errorf("%s %s: %s", method, req.URL, resp.Status)
+	}
+	return resp, nil
+}
+
+// detectAudioType prefers what the bytes say over the declared type. Text and
+// images are rejected; bytes nothing recognizes fall back to the declared
+// type, then the URL's extension.
+func detectAudioType(declared string, head []byte, ext string) (string, error) {
+	if sniffed := sniffAudioType(head); sniffed != "" {
+		return sniffed, nil
+	}
+	if detected := mediaType(http.DetectContentType(head)); strings.HasPrefix(detected, "text/") || strings.HasPrefix(detected, "image/") {
+		return "", fmt.Errorf("%w: content looks like %s", ErrNotAudio, detected)
+	}
+	if acceptedContentType(declared) && declared != "application/octet-stream" {
+		return declared, nil
+	}
+	if audioExtensions[ext] {
+		return mime.TypeByExtension(ext), nil
+	}
+	return "application/octet-stream", nil
+}
+
+// sniffAudioType recognizes the container signatures of the formats in
+// audioExtensions, returning "" for anything else.
+func sniffAudioType(head []byte) string {
+	switch {
+	case bytes.HasPrefix(head, []byte("ID3")):
+		return "audio/mpeg"
+	case bytes.HasPrefix(head, []byte("fLaC")):
+		return "audio/flac"
+	case bytes.HasPrefix(head, []byte("OggS")):
+		return "audio/ogg"
+	case len(head) >= 12 && string(head[:4]) == "RIFF" && string(head[8:12]) == "WAVE":
+		return "audio/wav"
+	case len(head) >= 12 && string(head[:4]) == "FORM" && (string(head[8:12]) == "AIFF" || string(head[8:12]) == "AIFC"):
+		return "audio/aiff"
+	case len(head) >= 8 && string(head[4:8]) == "ftyp":
+		return "audio/mp4"
+	case len(head) >= 2 && head[0] == 0xFF && head[1]&0xE0 == 0xE0:
+		// MPEG frame sync; layer bits of zero mean ADTS AAC.
+		if head[1]&0x06 == 0 {
+			return "audio/aac"
+		}
+		return "audio/mpeg"
+	}
+	return ""
+}
+
+func refuseNonPublicAddress(network, address string, _ syscall.RawConn) error {
+	host, _, err := net.SplitHostPort(address)
+	if err != nil {
+		return err
+	}
+	addr, err := netip.ParseAddr(host)
+	if err != nil {
+		return err
+	}
+	return refusePrivate(addr)
+}
+
+// refuseNonPublicHost resolves host, failing unless every address it has is
+// public; the proxy a request goes through connects to it, not the dialer.
+func refuseNonPublicHost(ctx context.Context, host string) error {
+	addrs, err := net.DefaultResolver.LookupNetIP(ctx, "ip", host)
+	if err != nil {
+		return err
+	}
+	for _, addr := range addrs {
+		if err := refusePrivate(addr); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func refusePrivate(addr netip.Addr) error {
+	if addr = addr.Unmap(); !addr.IsGlobalUnicast() || addr.IsPrivate() {
+		return fmt.Errorf("%w: %s", ErrPrivateAddress, addr)
+	}
+	return nil
+}
+
+func mediaType(header string) string {
+	mediaType, _, err := mime.ParseMediaType(header)
+	if err != nil {
+		return ""
+	}
+	return mediaType
+}
+
+func acceptedContentType(contentType string) bool {
+	return strings.HasPrefix(contentType, "audio/") || contentType == "application/octet-stream" || contentType == "application/ogg"
+}
+
+// progressReader reports the share of total read so far. It stays quiet when
+// total is unknown and only reports whole-percent changes.
+type progressReader struct {
+	reader   io.Reader
+	total    int64
+	read     int64
+	last     int
+	progress ProgressFunc
+}
+
+func (r *progressReader) Read(p []byte) (int, error) {
+	n, err := r.reader.Read(p)
+	r.read += int64(n)
+	if r.total > 0 && r.progress != nil {
+		if percent := int(r.read * 100 / r.total); percent > r.last && percent < 100 {
+			r.last = percent
+			r.progress(percent)
+		}
+	}
+	return n, err
+}
@@ -0,0 +1,193 @@
+package formatpolicy
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+)
+
+// Conversion is the job kind that converts tracks' stored audio to the format
+// policy.
+var Conversion = jobs.NewKind[ConversionPayload]("format_conversion")
+
+type ConversionPayload struct {
+	TrackIDs []int64 `json:"track_ids"`
+}
+
+// ConversionProgress is the progress detail of format conversion jobs.
+type ConversionProgress struct {
+	Converted int `json:"converted"`
+	Skipped   int `json:"skipped"`
+	Failed    int `json:"failed"`
+}
+
+// ingestDelay holds a new track's conversion back until its analysis and
+// first plays, which read the downloaded file, are likely done.
+const ingestDelay = 15 * time.Minute
+
+// Queue queues format conversion jobs.
+type Queue struct {
+	store *jobs.Store
+}
+
+func NewQueue(store *jobs.Store) *Queue {
+	return &Queue{store: store}
+}
+
+// Enqueue queues converting trackIDs, no sooner than runAfter. A batch runs
+// at most once at a time; queuing it again while it is queued or running
+// returns jobs.ErrDuplicate.
+func (q *Queue) Enqueue(ctx context.Context, trackIDs []int64, runAfter time.Time) (*jobs.Job, error) {
+	if len(trackIDs) == 0 {
+		return nil, errors.New("no tracks to convert")
+	}
+	return Conversion.Enqueue(ctx, q.store, ConversionPayload{TrackIDs: trackIDs}, jobs.Options{
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   fmt.Sprintf("tracks:%d-%d:%d", trackIDs[0], trackIDs[len(trackIDs)-1], len(trackIDs)),
+		RunAfter:    runAfter,
+	})
+}
+
+// Enforcer queues a conversion for each new track that breaks the policy.
+type Enforcer struct {
+	policy Policy
+	queue  *Queue
+}
+
+func NewEnforcer(policy Policy, queue *Queue) *Enforcer {
+	return &Enforcer{policy: policy, queue: queue}
+}
+
+// Enforce queues track for conversion when its stored audio breaks the
+// policy.
+func (e *Enforcer) Enforce(ctx context.Context, track *db.Track) error {
+	if !e.policy.Applies(track.Codec.String, int(track.BitrateKbps.Int32)) {
+		return nil
+	}
+	_, err := e.queue.Enqueue(ctx, []int64{track.ID}, time.Now().Add(ingestDelay))
+	if errors.Is(err, jobs.ErrDuplicate) {
+		return nil
+	}
+	return err
+}
+
+// TrackLoader loads the tracks a job converts. db.TrackRepository satisfies
+// this interface.
+type TrackLoader interface {
+	GetByID(ctx context.Context, id int64) (*db.Track, error)
+}
+
+// Converter re-encodes one track's stored audio. processor.Processor
+// satisfies this interface.
+type Converter interface {
+	ConvertStoredAudio(ctx context.Context, track *db.Track, profile libraryexport.Profile) error
+}
+
+// progressReporter records a job's progress. *jobs.Progress satisfies it.
+type progressReporter interface {
+	Report(ctx context.Context, current, total int, detail any) error
+}
+
+// Runner converts the tracks of format conversion jobs under the policy in
+// force when they run.
+type Runner struct {
+	policy    Policy
+	tracks    TrackLoader
+	converter Converter
+}
+
+func NewRunner(policy Policy, tracks TrackLoader, converter Converter) *Runner {
+	return &Runner{policy: policy, tracks: tracks, converter: converter}
+}
+
+// Register runs format conversion jobs on w.
+func (r *Runner) Register(w *jobs.Worker) {
+	Conversion.Handle(w, func(ctx context.Context, job *jobs.Job, payload ConversionPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, payload, progress)
+	})
+}
+
+// Run converts the job's tracks that still break the policy. Tracks that
+// were deleted, already converted, or allowed by a since-changed policy are
+// skipped; tracks that fail are logged and counted rather than ending the
+// job.
+func (r *Runner) Run(ctx context.Context, jobID uuid.UUID, payload ConversionPayload, progress progressReporter) error {
+	var counts ConversionProgress
+	total := len(payload.TrackIDs)
+	if err := progress.Report(ctx, 0, total, counts); err != nil {
+		return err
+	}
+	for i, id := range payload.TrackIDs {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		track, err := r.tracks.GetByID(ctx, id)
+		switch {
+		case errors.Is(err, db.ErrTrackNotFound):
+			counts.Skipped++
+		case err != nil:
+			log.Printf("Format conversion job %s: failed to load track %d: %v", jobID, id, err)
+			counts.Failed++
+		case !r.policy.Applies(track.Codec.String, int(track.BitrateKbps.Int32)):
+			counts.Skipped++
+		default:
+			if err := r.converter.ConvertStoredAudio(ctx, track, r.policy.Profile); err != nil {
+				log.Printf("Format conversion job %s: failed to convert track %d: %v", jobID, id, err)
+				counts.Failed++
+			} else {
+				counts.Converted++
+			}
+		}
+		if err := progress.Report(ctx, i+1, total, counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// Estimate is what converting a library to a policy would do.
+type Estimate struct {
+	// Tracks is how many tracks break the policy.
+	Tracks int
+	// CurrentBytes is how much those tracks' audio takes now, and
+	// EstimatedBytes about how much it would take converted. Tracks whose
+	// converted size cannot be told count the same in both.
+	CurrentBytes   int64
+	EstimatedBytes int64
+	// TrackIDs are the tracks to convert, in id order.
+	TrackIDs []int64
+}
+
+// SavedBytes is about how much space converting would free; it is negative
+// when the converted files would be larger.
+func (e Estimate) SavedBytes() int64 {
+	return e.CurrentBytes - e.EstimatedBytes
+}
+
+// Estimate finds the stored audio that breaks p and sizes its conversion.
+func (p Policy) Estimate(audio []db.StoredAudio) Estimate {
+	var estimate Estimate
+	for _, a := range audio {
+		if !p.Applies(a.Codec, a.BitrateKbps) {
+			continue
+		}
+		converted := p.EstimatedBytes(a.DurationMs)
+		if converted == 0 {
+			converted = a.FileSizeBytes
+		}
+		estimate.Tracks++
+		estimate.CurrentBytes += a.FileSizeBytes
+		estimate.EstimatedBytes += converted
+		estimate.TrackIDs = append(estimate.TrackIDs, a.TrackID)
+	}
+	return estimate
+}
@@ -0,0 +1,113 @@
+// Package formatpolicy keeps stored audio in one library-wide format, such as
+// "everything as Opus 160 except lossless files". New downloads that break
+// the policy are converted by background jobs, and existing tracks can be
+// converted retroactively with an estimate of the space saved.
+package formatpolicy
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+)
+
+// Codecs ffprobe reports for each format a policy converts to.
+var formatCodecs = map[string]string{
+	libraryexport.FormatMP3:  "mp3",
+	libraryexport.FormatAAC:  "aac",
+	libraryexport.FormatOpus: "opus",
+	libraryexport.FormatFLAC: "flac",
+}
+
+// Policy is the format stored audio is converted to. The zero Policy keeps
+// every file as downloaded.
+type Policy struct {
+	// Profile is the format and bitrate files are converted to.
+	Profile libraryexport.Profile
+	// KeepLossless leaves lossless files, such as FLAC and WAV rips, as they
+	// are.
+	KeepLossless bool
+}
+
+// Parse reads a policy written as "format" or "format:kbps", such as
+// "opus:160". An empty spec is the zero Policy.
+func Parse(spec string, keepLossless bool) (Policy, error) {
+	spec = strings.ToLower(strings.TrimSpace(spec))
+	if spec == "" || spec == libraryexport.FormatOriginal {
+		return Policy{}, nil
+	}
+	format, kbps, hasKbps := strings.Cut(spec, ":")
+	profile := libraryexport.Profile{Format: format}
+	if hasKbps {
+		bitrate, err := strconv.Atoi(strings.TrimSuffix(kbps, "k"))
+		if err != nil {
+			return Policy{}, fmt.Errorf("invalid format policy %q: bitrate must be a number of kbps", spec)
+		}
+		profile.BitrateKbps = bitrate
+	}
+	profile, err := profile.Normalize()
+	if err != nil {
+		return Policy{}, fmt.Errorf("invalid format policy %q: %w", spec, err)
+	}
+	return Policy{Profile: profile, KeepLossless: keepLossless}, nil
+}
+
+// Enabled reports whether p converts anything.
+func (p Policy) Enabled() bool {
+	return !p.Profile.IsOriginal()
+}
+
+// String names p's settings, such as "opus-160k", with "+lossless" when
+// lossless files are kept.
+func (p Policy) String() string {
+	if !p.Enabled() {
+		return libraryexport.FormatOriginal
+	}
+	if p.KeepLossless {
+		return p.Profile.Fingerprint() + "+lossless"
+	}
+	return p.Profile.Fingerprint()
+}
+
+// Applies reports whether audio stored with codec at bitrateKbps breaks p
+// and should be converted. Files already in p's codec at or below its
+// bitrate are kept, and lossy files are never converted to a lossless
+// format, which would only make them bigger.
+func (p Policy) Applies(codec string, bitrateKbps int) bool {
+	if !p.Enabled() || codec == "" {
+		return false
+	}
+	codec = strings.ToLower(codec)
+	lossless := IsLossless(codec)
+	target := formatCodecs[p.Profile.Format]
+	switch {
+	case lossless && p.KeepLossless:
+		return false
+	case p.Profile.BitrateKbps == 0:
+		// A lossless target.
+		return lossless && codec != target
+	case codec == target:
+		return bitrateKbps > p.Profile.BitrateKbps
+	default:
+		return true
+	}
+}
+
+// EstimatedBytes is about how large durationMs of audio converted under p
+// is, or 0 when that cannot be told, as for lossless targets.
+func (p Policy) EstimatedBytes(durationMs int) int64 {
+	if p.Profile.BitrateKbps == 0 || durationMs <= 0 {
+		return 0
+	}
+	return int64(p.Profile.BitrateKbps) * int64(durationMs) / 8
+}
+
+// IsLossless reports whether the ffprobe codec name is a lossless one.
+func IsLossless(codec string) bool {
+	switch codec {
+	case "flac", "alac", "wavpack", "ape", "tta", "mlp", "truehd":
+		return true
+	}
+	return strings.HasPrefix(codec, "pcm_")
+}
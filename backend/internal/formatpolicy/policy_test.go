@@ -0,0 +1,119 @@
+package formatpolicy
+
+import (
+	"context"
+	"database/sql"
+	"reflect"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+)
+
+func TestParse(t *testing.T) {
+	policy, err := Parse(" Opus:160k ", true)
+	if err != nil {
+		t.Fatalf("Parse() error = %v", err)
+	}
+	if want := (libraryexport.Profile{Format: libraryexport.FormatOpus, BitrateKbps: 160}); policy.Profile != want || policy.String() != "opus-160k+lossless" {
+		t.Fatalf("Parse() = %+v (%s)", policy, policy)
+	}
+	if policy, err := Parse("mp3", false); err != nil || policy.Profile.BitrateKbps != 320 {
+		t.Fatalf("Parse(mp3) = %+v, %v; want the default bitrate", policy, err)
+	}
+	if policy, err := Parse("", true); err != nil || policy.Enabled() {
+		t.Fatalf("Parse(\"\") = %+v, %v; want no policy", policy, err)
+	}
+	for _, spec := range []string{"ogg", "opus:loud", "opus:512", "flac:900"} {
+		if _, err := Parse(spec, true); err == nil {
+			t.Errorf("Parse(%q) succeeded", spec)
+		}
+	}
+}
+
+func TestAppliesKeepsLosslessAndSmallerFiles(t *testing.T) {
+	opus, _ := Parse("opus:160", true)
+	for _, tc := range []struct {
+		codec string
+		kbps  int
+		want  bool
+	}{
+		{"mp3", 320, true},
+		{"aac", 128, true},
+		{"opus", 256, true},
+		{"opus", 160, false},
+		{"opus", 128, false},
+		{"flac", 900, false},
+		{"pcm_s16le", 1411, false},
+		{"", 0, false},
+	} {
+		if got := opus.Applies(tc.codec, tc.kbps); got != tc.want {
+			t.Errorf("Applies(%s %d) = %v, want %v", tc.codec, tc.kbps, got, tc.want)
+		}
+	}
+	if opusAll, _ := Parse("opus:160", false); !opusAll.Applies("flac", 900) {
+		t.Error("lossless file kept without KeepLossless")
+	}
+	flac, _ := Parse("flac", false)
+	if flac.Applies("mp3", 320) || !flac.Applies("alac", 1000) || flac.Applies("flac", 900) {
+		t.Error("FLAC policy should convert only other lossless formats")
+	}
+}
+
+func TestEstimateSizesConversions(t *testing.T) {
+	opus, _ := Parse("opus:160", true)
+	estimate := opus.Estimate([]db.StoredAudio{
+		{TrackID: 1, Codec: "mp3", BitrateKbps: 320, FileSizeBytes: 8_000_000, DurationMs: 200_000},
+		{TrackID: 2, Codec: "flac", BitrateKbps: 900, FileSizeBytes: 22_000_000, DurationMs: 200_000},
+		{TrackID: 3, Codec: "aac", BitrateKbps: 256, FileSizeBytes: 6_400_000, DurationMs: 0},
+	})
+	// 160 kbps for 200 s is 4 MB; the track of unknown length counts as is.
+	want := Estimate{Tracks: 2, CurrentBytes: 14_400_000, EstimatedBytes: 10_400_000, TrackIDs: []int64{1, 3}}
+	if !reflect.DeepEqual(estimate, want) || estimate.SavedBytes() != 4_000_000 {
+		t.Fatalf("Estimate() = %+v, want %+v", estimate, want)
+	}
+}
+
+type fakeTracks map[int64]*db.Track
+
+func (f fakeTracks) GetByID(_ context.Context, id int64) (*db.Track, error) {
+	if track, ok := f[id]; ok {
+		return track, nil
+	}
+	return nil, db.ErrTrackNotFound
+}
+
+type fakeConverter []int64
+
+func (f *fakeConverter) ConvertStoredAudio(_ context.Context, track *db.Track, _ libraryexport.Profile) error {
+	*f = append(*f, track.ID)
+	return nil
+}
+
+type fakeProgress struct {
+	last ConversionProgress
+}
+
+func (p *fakeProgress) Report(_ context.Context, _, _ int, detail any) error {
+	p.last = detail.(ConversionProgress)
+	return nil
+}
+
+func TestRunnerConvertsTracksStillBreakingThePolicy(t *testing.T) {
+	stored := func(id int64, codec string, kbps int32) *db.Track {
+		return &db.Track{ID: id, Codec: sql.NullString{String: codec, Valid: true}, BitrateKbps: sql.NullInt32{Int32: kbps, Valid: true}}
+	}
+	opus, _ := Parse("opus:160", true)
+	var converter fakeConverter
+	runner := NewRunner(opus, fakeTracks{1: stored(1, "mp3", 320), 2: stored(2, "opus", 160), 4: stored(4, "flac", 900)}, &converter)
+
+	var progress fakeProgress
+	if err := runner.Run(context.Background(), uuid.New(), ConversionPayload{TrackIDs: []int64{1, 2, 3, 4}}, &progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	if !reflect.DeepEqual([]int64(converter), []int64{1}) || progress.last != (ConversionProgress{Converted: 1, Skipped: 3}) {
+		t.Fatalf("converted %v, progress %+v", converter, progress.last)
+	}
+}
@@ -9,6 +9,8 @@ import (
 	"time"
 
 	"github.com/redis/go-redis/v9"
+
+	"github.com/openmusicplayer/backend/internal/diskspace"
 )
 
 // Status represents the health status of a component
@@ -22,9 +24,10 @@ const (
 
 // ComponentHealth represents the health of a single component
 type ComponentHealth struct {
-	Status   Status `json:"status"`
-	Message  string `json:"message,omitempty"`
-	Duration string `json:"duration,omitempty"`
+	Status   Status         `json:"status"`
+	Message  string         `json:"message,omitempty"`
+	Duration string         `json:"duration,omitempty"`
+	Details  map[string]any `json:"details,omitempty"`
 }
 
 // HealthResponse represents the full health check response
@@ -40,6 +43,7 @@ type Checker struct {
 	db           *sql.DB
 	redis        *redis.Client
 	storageCheck func(ctx context.Context) error
+	diskCheck    func(ctx context.Context) diskspace.Status
 	version      string
 	checkTimeout time.Duration
 }
@@ -49,8 +53,11 @@ type CheckerConfig struct {
 	DB           *sql.DB
 	Redis        *redis.Client
 	StorageCheck func(ctx context.Context) error
-	Version      string
-	Timeout      time.Duration
+	// DiskCheck reports free space on the download disk. The disk component
+	// is left out of readiness when it is nil.
+	DiskCheck func(ctx context.Context) diskspace.Status
+	Version   string
+	Timeout   time.Duration
 }
 
 // NewChecker creates a new health checker
@@ -63,6 +70,7 @@ func NewChecker(cfg *CheckerConfig) *Checker {
 		db:           cfg.DB,
 		redis:        cfg.Redis,
 		storageCheck: cfg.StorageCheck,
+		diskCheck:    cfg.DiskCheck,
 		version:      cfg.Version,
 		checkTimeout: timeout,
 	}
@@ -162,6 +170,33 @@ func (c *Checker) CheckStorage(ctx context.Context) ComponentHealth {
 	}
 }
 
+// CheckDisk reports free space on the download disk. Low space degrades
+// readiness rather than failing it: playback still works while new downloads
+// are refused.
+func (c *Checker) CheckDisk(ctx context.Context) ComponentHealth {
+	start := time.Now()
+	status := c.diskCheck(ctx)
+	result := ComponentHealth{
+		Status:   StatusHealthy,
+		Duration: time.Since(start).String(),
+		Details: map[string]any{
+			"path":         status.Path,
+			"totalBytes":   status.TotalBytes,
+			"freeBytes":    status.FreeBytes,
+			"minFreeBytes": status.MinFreeBytes,
+		},
+	}
+	switch {
+	case status.Low:
+		result.Status = StatusDegraded
+		result.Message = "free disk space is below the threshold; new downloads are refused"
+	case status.Err != nil:
+		result.Status = StatusDegraded
+		result.Message = "disk space check failed"
+	}
+	return result
+}
+
 // Check performs a basic health check (liveness)
 func (c *Checker) Check(ctx context.Context) *HealthResponse {
 	return &HealthResponse{
@@ -189,6 +224,9 @@ func (c *Checker) DeepCheck(ctx context.Context) *HealthResponse {
 		"redis":    c.CheckRedis,
 		"storage":  c.CheckStorage,
 	}
+	if c.diskCheck != nil {
+		checks["disk"] = c.CheckDisk
+	}
 
 	for name, check := range checks {
 		wg.Add(1)
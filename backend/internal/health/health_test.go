@@ -8,6 +8,8 @@ import (
 	"net/http/httptest"
 	"testing"
 	"time"
+
+	"github.com/openmusicplayer/backend/internal/diskspace"
 )
 
 func TestChecker_BasicHealth(t *testing.T) {
@@ -66,6 +68,32 @@ func TestChecker_DeepCheck_StorageUnhealthy(t *testing.T) {
 	}
 }
 
+func TestChecker_DeepCheck_LowDiskDegradesAndReportsCapacity(t *testing.T) {
+	checker := NewChecker(&CheckerConfig{
+		StorageCheck: func(ctx context.Context) error {
+			return nil
+		},
+		DiskCheck: func(ctx context.Context) diskspace.Status {
+			return diskspace.Status{Path: "/tmp", TotalBytes: 10 << 30, FreeBytes: 512 << 20, MinFreeBytes: 1 << 30, Low: true}
+		},
+		Version: "1.0.0",
+		Timeout: 5 * time.Second,
+	})
+
+	disk := checker.DeepCheck(context.Background()).Components["disk"]
+	if disk.Status != StatusDegraded {
+		t.Errorf("expected disk component degraded, got %s", disk.Status)
+	}
+	if disk.Details["freeBytes"] != uint64(512<<20) || disk.Details["minFreeBytes"] != uint64(1<<30) {
+		t.Errorf("unexpected disk details: %v", disk.Details)
+	}
+
+	checker = NewChecker(&CheckerConfig{Version: "1.0.0"})
+	if _, ok := checker.DeepCheck(context.Background()).Components["disk"]; ok {
+		t.Error("expected no disk component without a disk check")
+	}
+}
+
 func TestHandler_LivenessHandler(t *testing.T) {
 	checker := NewChecker(&CheckerConfig{
 		Version: "1.0.0",
@@ -0,0 +1,352 @@
+package images
+
+import (
+	"bytes"
+	"encoding/binary"
+	"errors"
+	"io"
+)
+
+// ErrNoEmbeddedArtwork reports an audio file without a usable attached picture.
+var ErrNoEmbeddedArtwork = errors.New("no embedded artwork")
+
+// pictureTypeFrontCover is the ID3v2/FLAC picture type for the front cover.
+const pictureTypeFrontCover = 3
+
+// maxMP4AtomDepth bounds recursion through nested MP4 atoms.
+const maxMP4AtomDepth = 8
+
+// ExtractEmbedded returns the cover art embedded in an audio file's tags. It
+// reads ID3v2 (MP3), FLAC, and MP4/M4A metadata and prefers the front cover
+// when a file carries several pictures.
+func ExtractEmbedded(r io.ReaderAt, size int64) ([]byte, error) {
+	header := make([]byte, 12)
+	n, err := r.ReadAt(header, 0)
+	if n < len(header) {
+		if err == nil || errors.Is(err, io.EOF) {
+			return nil, ErrNoEmbeddedArtwork
+		}
+		return nil, err
+	}
+	switch {
+	case bytes.HasPrefix(header, []byte("ID3")):
+		return extractID3(r)
+	case bytes.HasPrefix(header, []byte("fLaC")):
+		return extractFLAC(r, size)
+	case bytes.Equal(header[4:8], []byte("ftyp")):
+		return extractMP4(r, size)
+	}
+	return nil, ErrNoEmbeddedArtwork
+}
+
+// picker keeps the first picture seen unless a front cover turns up later.
+type picker struct {
+	data  []byte
+	front bool
+}
+
+func (p *picker) offer(data []byte, pictureType byte) {
+	if len(data) == 0 || len(data) > maxSourceBytes || p.front {
+		return
+	}
+	if p.data == nil || pictureType == pictureTypeFrontCover {
+		p.data = data
+		p.front = pictureType == pictureTypeFrontCover
+	}
+}
+
+func (p *picker) result() ([]byte, error) {
+	if p.data == nil {
+		return nil, ErrNoEmbeddedArtwork
+	}
+	return p.data, nil
+}
+
+func synchsafe(b []byte) int {
+	return int(b[0]&0x7f)<<21 | int(b[1]&0x7f)<<14 | int(b[2]&0x7f)<<7 | int(b[3]&0x7f)
+}
+
+// removeUnsynchronisation undoes ID3v2 unsynchronisation, which inserts a
+// zero byte after every 0xFF.
+func removeUnsynchronisation(b []byte) []byte {
+	return bytes.ReplaceAll(b, []byte{0xff, 0x00}, []byte{0xff})
+}
+
+func extractID3(r io.ReaderAt) ([]byte, error) {
+	header := make([]byte, 10)
+	if _, err := r.ReadAt(header, 0); err != nil {
+		return nil, ErrNoEmbeddedArtwork
+	}
+	version, flags := header[3], header[5]
+	if version < 2 || version > 4 {
+		return nil, ErrNoEmbeddedArtwork
+	}
+	tagSize := synchsafe(header[6:10])
+	if tagSize > maxSourceBytes*2 {
+		return nil, ErrNoEmbeddedArtwork
+	}
+	tag := make([]byte, tagSize)
+	if _, err := r.ReadAt(tag, 10); err != nil && !errors.Is(err, io.EOF) {
+		return nil, err
+	}
+	if flags&0x80 != 0 && version < 4 {
+		tag = removeUnsynchronisation(tag)
+	}
+	if flags&0x40 != 0 && version > 2 && len(tag) >= 4 {
+		extSize := synchsafe(tag[:4])
+		if version == 3 {
+			extSize = int(binary.BigEndian.Uint32(tag[:4])) + 4
+		}
+		if extSize > len(tag) {
+			return nil, ErrNoEmbeddedArtwork
+		}
+		tag = tag[extSize:]
+	}
+
+	idLen, headerLen := 4, 10
+	if version == 2 {
+		idLen, headerLen = 3, 6
+	}
+	var pick picker
+	for len(tag) >= headerLen && tag[0] != 0 {
+		id := string(tag[:idLen])
+		var frameSize int
+		var frameFlags byte
+		switch version {
+		case 2:
+			frameSize = int(tag[3])<<16 | int(tag[4])<<8 | int(tag[5])
+		case 3:
+			frameSize = int(binary.BigEndian.Uint32(tag[4:8]))
+			frameFlags = tag[9]
+		default:
+			frameSize = synchsafe(tag[4:8])
+			frameFlags = tag[9]
+		}
+		if frameSize > len(tag)-headerLen {
+			break
+		}
+		body := tag[headerLen : headerLen+frameSize]
+		tag = tag[headerLen+frameSize:]
+
+		if id != "APIC" && id != "PIC" {
+			continue
+		}
+		body, ok := id3FrameBody(version, frameFlags, body)
+		if !ok {
+			continue
+		}
+		if data, pictureType, ok := parseID3Picture(version, body); ok {
+			pick.offer(data, pictureType)
+		}
+	}
+	return pick.result()
+}
+
+// id3FrameBody strips per-frame headers signalled by the frame flags. Frames
+// that are compressed or encrypted are skipped.
+func id3FrameBody(version, flags byte, body []byte) ([]byte, bool) {
+	switch version {
+	case 3:
+		if flags&0xc0 != 0 {
+			return nil, false
+		}
+		if flags&0x20 != 0 && len(body) > 0 {
+			body = body[1:]
+		}
+	case 4:
+		if flags&0x0c != 0 {
+			return nil, false
+		}
+		if flags&0x40 != 0 && len(body) > 0 {
+			body = body[1:]
+		}
+		if flags&0x01 != 0 {
+			if len(body) < 4 {
+				return nil, false
+			}
+			body = body[4:]
+		}
+		if flags&0x02 != 0 {
+			body = removeUnsynchronisation(body)
+		}
+	}
+	return body, true
+}
+
+// parseID3Picture decodes an APIC (v2.3/v2.4) or PIC (v2.2) frame body.
+func parseID3Picture(version byte, body []byte) ([]byte, byte, bool) {
+	if len(body) < 2 {
+		return nil, 0, false
+	}
+	encoding := body[0]
+	rest := body[1:]
+	if version == 2 {
+		if len(rest) < 3 {
+			return nil, 0, false
+		}
+		rest = rest[3:]
+	} else {
+		end := bytes.IndexByte(rest, 0)
+		if end < 0 {
+			return nil, 0, false
+		}
+		rest = rest[end+1:]
+	}
+	if len(rest) < 1 {
+		return nil, 0, false
+	}
+	pictureType := rest[0]
+	rest = rest[1:]
+
+	// The description is terminated by one zero byte, or two aligned zero
+	// bytes for the UTF-16 encodings.
+	if encoding == 1 || encoding == 2 {
+		for i := 0; ; i += 2 {
+			if i+1 >= len(rest) {
+				return nil, 0, false
+			}
+			if rest[i] == 0 && rest[i+1] == 0 {
+				rest = rest[i+2:]
+				break
+			}
+		}
+	} else {
+		end := bytes.IndexByte(rest, 0)
+		if end < 0 {
+			return nil, 0, false
+		}
+		rest = rest[end+1:]
+	}
+	return rest, pictureType, len(rest) > 0
+}
+
+func extractFLAC(r io.ReaderAt, size int64) ([]byte, error) {
+	var pick picker
+	offset := int64(4)
+	blockHeader := make([]byte, 4)
+	for offset+4 <= size {
+		if _, err := r.ReadAt(blockHeader, offset); err != nil {
+			return nil, err
+		}
+		last := blockHeader[0]&0x80 != 0
+		blockType := blockHeader[0] & 0x7f
+		length := int64(blockHeader[1])<<16 | int64(blockHeader[2])<<8 | int64(blockHeader[3])
+		offset += 4
+		if offset+length > size {
+			break
+		}
+		if blockType == 6 {
+			block := make([]byte, length)
+			if _, err := r.ReadAt(block, offset); err != nil {
+				return nil, err
+			}
+			if data, pictureType, ok := parseFLACPicture(block); ok {
+				pick.offer(data, pictureType)
+			}
+		}
+		offset += length
+		if last {
+			break
+		}
+	}
+	return pick.result()
+}
+
+// parseFLACPicture decodes a METADATA_BLOCK_PICTURE body.
+func parseFLACPicture(block []byte) ([]byte, byte, bool) {
+	read32 := func() (uint32, bool) {
+		if len(block) < 4 {
+			return 0, false
+		}
+		v := binary.BigEndian.Uint32(block[:4])
+		block = block[4:]
+		return v, true
+	}
+	skip := func(n uint32) bool {
+		if uint64(n) > uint64(len(block)) {
+			return false
+		}
+		block = block[n:]
+		return true
+	}
+
+	pictureType, ok := read32()
+	if !ok {
+		return nil, 0, false
+	}
+	for i := 0; i < 2; i++ { // MIME type, then description
+		n, ok := read32()
+		if !ok || !skip(n) {
+			return nil, 0, false
+		}
+	}
+	if !skip(16) { // width, height, color depth, palette size
+		return nil, 0, false
+	}
+	n, ok := read32()
+	if !ok || uint64(n) > uint64(len(block)) {
+		return nil, 0, false
+	}
+	return block[:n], byte(pictureType), n > 0
+}
+
+// extractMP4 walks moov/udta/meta/ilst/covr to the first cover data atom.
+func extractMP4(r io.ReaderAt, size int64) ([]byte, error) {
+	path := []string{"moov", "udta", "meta", "ilst", "covr", "data"}
+	start, end, ok := findMP4Atom(r, 0, size, path, 0)
+	if !ok {
+		return nil, ErrNoEmbeddedArtwork
+	}
+	// A data atom starts with a 4-byte type indicator and a 4-byte locale.
+	start += 8
+	if end-start <= 0 || end-start > maxSourceBytes {
+		return nil, ErrNoEmbeddedArtwork
+	}
+	data := make([]byte, end-start)
+	if _, err := r.ReadAt(data, start); err != nil && !errors.Is(err, io.EOF) {
+		return nil, err
+	}
+	return data, nil
+}
+
+// findMP4Atom returns the payload range of the atom at path within
+// [start, end).
+func findMP4Atom(r io.ReaderAt, start, end int64, path []string, depth int) (int64, int64, bool) {
+	if depth > maxMP4AtomDepth {
+		return 0, 0, false
+	}
+	header := make([]byte, 16)
+	for offset := start; offset+8 <= end; {
+		if _, err := r.ReadAt(header[:8], offset); err != nil {
+			return 0, 0, false
+		}
+		atomSize := int64(binary.BigEndian.Uint32(header[:4]))
+		atomType := string(header[4:8])
+		headerLen := int64(8)
+		switch atomSize {
+		case 0:
+			atomSize = end - offset
+		case 1:
+			if _, err := r.ReadAt(header[8:16], offset+8); err != nil {
+				return 0, 0, false
+			}
+			atomSize = int64(binary.BigEndian.Uint64(header[8:16]))
+			headerLen = 16
+		}
+		if atomSize < headerLen || offset+atomSize > end {
+			return 0, 0, false
+		}
+		if atomType == path[0] {
+			payload := offset + headerLen
+			if atomType == "meta" {
+				payload += 4 // meta is a full box: version and flags precede its children
+			}
+			if len(path) == 1 {
+				return payload, offset + atomSize, true
+			}
+			return findMP4Atom(r, payload, offset+atomSize, path[1:], depth+1)
+		}
+		offset += atomSize
+	}
+	return 0, 0, false
+}
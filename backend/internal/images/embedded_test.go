@@ -0,0 +1,125 @@
+package images
+
+import (
+	"bytes"
+	"encoding/binary"
+	"errors"
+	"testing"
+)
+
+func id3Frame(id string, body []byte) []byte {
+	frame := append([]byte(id), 0, 0, 0, 0, 0, 0)
+	binary.BigEndian.PutUint32(frame[4:8], uint32(len(body)))
+	return append(frame, body...)
+}
+
+func apicBody(pictureType byte, data []byte) []byte {
+	body := []byte{0} // ISO-8859-1
+	body = append(body, "image/png"...)
+	body = append(body, 0, pictureType)
+	body = append(body, "cover"...)
+	body = append(body, 0)
+	return append(body, data...)
+}
+
+func id3v23(frames ...[]byte) []byte {
+	var tag []byte
+	for _, frame := range frames {
+		tag = append(tag, frame...)
+	}
+	tag = append(tag, make([]byte, 16)...) // padding
+	size := len(tag)
+	header := []byte{'I', 'D', '3', 3, 0, 0,
+		byte(size >> 21 & 0x7f), byte(size >> 14 & 0x7f), byte(size >> 7 & 0x7f), byte(size & 0x7f)}
+	out := append(header, tag...)
+	return append(out, 0xff, 0xfb, 0x90, 0x00) // start of an MPEG frame
+}
+
+func flacWithPicture(pictureType uint32, data []byte) []byte {
+	var picture bytes.Buffer
+	write32 := func(v uint32) { _ = binary.Write(&picture, binary.BigEndian, v) }
+	write32(pictureType)
+	write32(uint32(len("image/jpeg")))
+	picture.WriteString("image/jpeg")
+	write32(0) // empty description
+	write32(500)
+	write32(500)
+	write32(24)
+	write32(0)
+	write32(uint32(len(data)))
+	picture.Write(data)
+
+	out := []byte("fLaC")
+	streamInfo := make([]byte, 34)
+	out = append(out, 0, 0, 0, byte(len(streamInfo)))
+	out = append(out, streamInfo...)
+	n := picture.Len()
+	out = append(out, 0x80|6, byte(n>>16), byte(n>>8), byte(n))
+	return append(out, picture.Bytes()...)
+}
+
+func mp4Atom(kind string, payload ...[]byte) []byte {
+	var body []byte
+	for _, p := range payload {
+		body = append(body, p...)
+	}
+	atom := make([]byte, 8)
+	binary.BigEndian.PutUint32(atom[:4], uint32(8+len(body)))
+	copy(atom[4:], kind)
+	return append(atom, body...)
+}
+
+func mp4WithCover(data []byte) []byte {
+	dataAtom := mp4Atom("data", []byte{0, 0, 0, 13, 0, 0, 0, 0}, data)
+	meta := mp4Atom("meta", []byte{0, 0, 0, 0}, mp4Atom("hdlr", make([]byte, 25)), mp4Atom("ilst", mp4Atom("covr", dataAtom)))
+	return append(
+		mp4Atom("ftyp", []byte("M4A "), []byte{0, 0, 0, 0}),
+		append(mp4Atom("mdat", make([]byte, 64)), mp4Atom("moov", mp4Atom("mvhd", make([]byte, 100)), mp4Atom("udta", meta))...)...,
+	)
+}
+
+func extract(t *testing.T, file []byte) ([]byte, error) {
+	t.Helper()
+	return ExtractEmbedded(bytes.NewReader(file), int64(len(file)))
+}
+
+func TestExtractEmbeddedID3PrefersFrontCover(t *testing.T) {
+	file := id3v23(
+		id3Frame("TIT2", []byte("\x00Song")),
+		id3Frame("APIC", apicBody(0, []byte("other-picture"))),
+		id3Frame("APIC", apicBody(pictureTypeFrontCover, []byte("front-cover"))),
+	)
+	got, err := extract(t, file)
+	if err != nil || string(got) != "front-cover" {
+		t.Fatalf("ExtractEmbedded = %q, %v; want front-cover", got, err)
+	}
+
+	got, err = extract(t, id3v23(id3Frame("APIC", apicBody(0, []byte("only-picture")))))
+	if err != nil || string(got) != "only-picture" {
+		t.Fatalf("ExtractEmbedded without front cover = %q, %v; want only-picture", got, err)
+	}
+}
+
+func TestExtractEmbeddedFLACAndMP4(t *testing.T) {
+	got, err := extract(t, flacWithPicture(pictureTypeFrontCover, []byte("flac-cover")))
+	if err != nil || string(got) != "flac-cover" {
+		t.Fatalf("FLAC = %q, %v; want flac-cover", got, err)
+	}
+	got, err = extract(t, mp4WithCover([]byte("mp4-cover")))
+	if err != nil || string(got) != "mp4-cover" {
+		t.Fatalf("MP4 = %q, %v; want mp4-cover", got, err)
+	}
+}
+
+func TestExtractEmbeddedWithoutArtwork(t *testing.T) {
+	for name, file := range map[string][]byte{
+		"id3 without picture": id3v23(id3Frame("TIT2", []byte("\x00Song"))),
+		"wav":                 append([]byte("RIFF\x24\x00\x00\x00WAVEfmt "), make([]byte, 32)...),
+		"tiny":                []byte("ID3"),
+		"truncated flac":      flacWithPicture(pictureTypeFrontCover, []byte("cover"))[:60],
+	} {
+		if _, err := extract(t, file); !errors.Is(err, ErrNoEmbeddedArtwork) {
+			t.Fatalf("%s: error = %v, want ErrNoEmbeddedArtwork", name, err)
+		}
+	}
+}
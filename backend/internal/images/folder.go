@@ -0,0 +1,73 @@
+package images
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// ErrNoFolderArtwork reports a directory without a recognised sidecar image.
+var ErrNoFolderArtwork = errors.New("no folder artwork")
+
+// DefaultFolderArtworkNames is the sidecar filename priority used when none is
+// configured.
+var DefaultFolderArtworkNames = []string{
+	"cover.jpg", "cover.jpeg", "cover.png",
+	"folder.jpg", "folder.jpeg", "folder.png",
+	"front.jpg", "front.jpeg", "front.png",
+	"album.jpg", "album.jpeg", "album.png",
+}
+
+// FindFolderArtwork returns the highest-priority sidecar image in dir. Names
+// are matched case-insensitively, so Cover.JPG satisfies cover.jpg.
+func FindFolderArtwork(dir string, names []string) (string, []byte, error) {
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		return "", nil, fmt.Errorf("read artwork directory: %w", err)
+	}
+	files := make(map[string]string, len(entries))
+	for _, entry := range entries {
+		if entry.Type().IsRegular() {
+			files[strings.ToLower(entry.Name())] = entry.Name()
+		}
+	}
+	for _, name := range names {
+		actual, ok := files[strings.ToLower(name)]
+		if !ok {
+			continue
+		}
+		path := filepath.Join(dir, actual)
+		data, err := readBounded(path)
+		if err != nil {
+			return "", nil, err
+		}
+		return path, data, nil
+	}
+	return "", nil, ErrNoFolderArtwork
+}
+
+// ReadArtworkFile reads an image another tool recorded as an album's cover,
+// such as the artpath beets keeps, with the same size bound as folder
+// artwork.
+func ReadArtworkFile(path string) ([]byte, error) {
+	return readBounded(path)
+}
+
+func readBounded(path string) ([]byte, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, fmt.Errorf("open folder artwork: %w", err)
+	}
+	defer file.Close()
+	data, err := io.ReadAll(io.LimitReader(file, maxSourceBytes+1))
+	if err != nil {
+		return nil, fmt.Errorf("read folder artwork: %w", err)
+	}
+	if len(data) > maxSourceBytes {
+		return nil, ErrImageTooLarge
+	}
+	return data, nil
+}
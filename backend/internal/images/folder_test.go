@@ -0,0 +1,33 @@
+package images
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestFindFolderArtworkFollowsPriority(t *testing.T) {
+	dir := t.TempDir()
+	for name, body := range map[string]string{
+		"Folder.PNG":  "folder",
+		"front.jpg":   "front",
+		"track01.mp3": "audio",
+	} {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(body), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	path, data, err := FindFolderArtwork(dir, DefaultFolderArtworkNames)
+	if err != nil || filepath.Base(path) != "Folder.PNG" || string(data) != "folder" {
+		t.Fatalf("FindFolderArtwork = %q, %q, %v; want Folder.PNG", path, data, err)
+	}
+	path, _, err = FindFolderArtwork(dir, []string{"front.jpg", "folder.png"})
+	if err != nil || filepath.Base(path) != "front.jpg" {
+		t.Fatalf("custom priority = %q, %v; want front.jpg", path, err)
+	}
+	if _, _, err := FindFolderArtwork(dir, []string{"cover.jpg"}); !errors.Is(err, ErrNoFolderArtwork) {
+		t.Fatalf("missing sidecar error = %v, want ErrNoFolderArtwork", err)
+	}
+}
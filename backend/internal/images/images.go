@@ -0,0 +1,141 @@
+// Package images turns source artwork into the renditions clients display:
+// bounded-size JPEG variants, a blurhash placeholder, and a color palette.
+package images
+
+import (
+	"bytes"
+	"errors"
+	"fmt"
+	"image"
+	"image/color"
+	"image/draw"
+	_ "image/gif" // register decoders for artwork formats seen in the wild
+	"image/jpeg"
+	_ "image/png"
+)
+
+const (
+	// maxSourcePixels rejects images whose decoded size would dwarf any cover
+	// art; the header is checked before the pixels are decoded.
+	maxSourcePixels = 40_000_000
+
+	jpegQuality = 85
+
+	// blurhashSampleSize and paletteSampleSize bound the work done for the
+	// placeholder and palette; neither needs full resolution.
+	blurhashSampleSize = 32
+	paletteSampleSize  = 64
+
+	blurhashXComponents = 4
+	blurhashYComponents = 3
+	paletteColors       = 5
+)
+
+var (
+	ErrUnsupportedImage = errors.New("unsupported image")
+	ErrImageTooLarge    = errors.New("image too large")
+)
+
+// Variant names a rendition and the bound on its longest edge.
+type Variant struct {
+	Name         string
+	MaxDimension int
+}
+
+// DefaultVariants are the renditions generated for ingested artwork.
+var DefaultVariants = []Variant{
+	{Name: "thumb", MaxDimension: 128},
+	{Name: "small", MaxDimension: 300},
+	{Name: "medium", MaxDimension: 600},
+	{Name: "large", MaxDimension: 1200},
+}
+
+// EncodedVariant is a rendered JPEG for one Variant.
+type EncodedVariant struct {
+	Name   string
+	Width  int
+	Height int
+	Data   []byte
+}
+
+// Result is everything derived from one source image.
+type Result struct {
+	Width         int
+	Height        int
+	Variants      []EncodedVariant
+	Blurhash      string
+	DominantColor string
+	Palette       []string
+}
+
+// Process decodes data and renders each variant, the blurhash, and the
+// palette. Variants are never upscaled past the source size.
+func Process(data []byte, variants []Variant) (*Result, error) {
+	cfg, _, err := image.DecodeConfig(bytes.NewReader(data))
+	if err != nil {
+		return nil, fmt.Errorf("%w: %v", ErrUnsupportedImage, err)
+	}
+	if cfg.Width <= 0 || cfg.Height <= 0 {
+		return nil, ErrUnsupportedImage
+	}
+	if cfg.Width*cfg.Height > maxSourcePixels {
+		return nil, ErrImageTooLarge
+	}
+	decoded, _, err := image.Decode(bytes.NewReader(data))
+	if err != nil {
+		return nil, fmt.Errorf("%w: %v", ErrUnsupportedImage, err)
+	}
+
+	src := flatten(decoded)
+	width, height := src.Bounds().Dx(), src.Bounds().Dy()
+	result := &Result{Width: width, Height: height}
+
+	for _, variant := range variants {
+		w, h := fitWithin(width, height, variant.MaxDimension)
+		var buf bytes.Buffer
+		if err := jpeg.Encode(&buf, resize(src, w, h), &jpeg.Options{Quality: jpegQuality}); err != nil {
+			return nil, fmt.Errorf("encode %s variant: %w", variant.Name, err)
+		}
+		result.Variants = append(result.Variants, EncodedVariant{
+			Name: variant.Name, Width: w, Height: h, Data: buf.Bytes(),
+		})
+	}
+
+	w, h := fitWithin(width, height, blurhashSampleSize)
+	result.Blurhash = encodeBlurhash(resize(src, w, h), blurhashXComponents, blurhashYComponents)
+
+	w, h = fitWithin(width, height, paletteSampleSize)
+	result.Palette = extractPalette(resize(src, w, h), paletteColors)
+	if len(result.Palette) > 0 {
+		result.DominantColor = result.Palette[0]
+	}
+	return result, nil
+}
+
+// flatten composites img onto white so transparent PNG artwork renders the
+// same in JPEG variants as it does on a light background.
+func flatten(img image.Image) *image.RGBA {
+	bounds := img.Bounds()
+	dst := image.NewRGBA(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+	draw.Draw(dst, dst.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+	draw.Draw(dst, dst.Bounds(), img, bounds.Min, draw.Over)
+	return dst
+}
+
+// fitWithin scales width x height down so the longest edge is at most limit.
+func fitWithin(width, height, limit int) (int, int) {
+	if limit <= 0 || (width <= limit && height <= limit) {
+		return width, height
+	}
+	if width >= height {
+		return limit, clampDimension(height * limit / width)
+	}
+	return clampDimension(width * limit / height), limit
+}
+
+func clampDimension(n int) int {
+	if n < 1 {
+		return 1
+	}
+	return n
+}
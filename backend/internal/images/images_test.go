@@ -0,0 +1,304 @@
+package images
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"image"
+	"image/color"
+	"image/png"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// twoToneImage is 800x400: red on the left three quarters, blue on the rest.
+func twoToneImage(t *testing.T) []byte {
+	t.Helper()
+	img := image.NewRGBA(image.Rect(0, 0, 800, 400))
+	for y := 0; y < 400; y++ {
+		for x := 0; x < 800; x++ {
+			c := color.RGBA{R: 220, G: 20, B: 30, A: 255}
+			if x >= 600 {
+				c = color.RGBA{R: 10, G: 40, B: 200, A: 255}
+			}
+			img.SetRGBA(x, y, c)
+		}
+	}
+	var buf bytes.Buffer
+	if err := png.Encode(&buf, img); err != nil {
+		t.Fatalf("encode png: %v", err)
+	}
+	return buf.Bytes()
+}
+
+func decodeBase83(s string) int {
+	value := 0
+	for _, c := range s {
+		value = value*83 + strings.IndexRune(base83Chars, c)
+	}
+	return value
+}
+
+func TestProcessRendersVariantsBlurhashAndPalette(t *testing.T) {
+	result, err := Process(twoToneImage(t), DefaultVariants)
+	if err != nil {
+		t.Fatalf("Process: %v", err)
+	}
+	if result.Width != 800 || result.Height != 400 {
+		t.Fatalf("source size = %dx%d, want 800x400", result.Width, result.Height)
+	}
+
+	want := map[string][2]int{"thumb": {128, 64}, "small": {300, 150}, "medium": {600, 300}, "large": {800, 400}}
+	if len(result.Variants) != len(want) {
+		t.Fatalf("variants = %d, want %d", len(result.Variants), len(want))
+	}
+	for _, v := range result.Variants {
+		if dims := want[v.Name]; v.Width != dims[0] || v.Height != dims[1] {
+			t.Fatalf("%s variant = %dx%d, want %dx%d", v.Name, v.Width, v.Height, dims[0], dims[1])
+		}
+		decoded, format, err := image.Decode(bytes.NewReader(v.Data))
+		if err != nil || format != "jpeg" {
+			t.Fatalf("%s variant decode = %s, %v; want jpeg", v.Name, format, err)
+		}
+		if b := decoded.Bounds(); b.Dx() != v.Width || b.Dy() != v.Height {
+			t.Fatalf("%s variant encoded as %dx%d", v.Name, b.Dx(), b.Dy())
+		}
+	}
+
+	// 4x3 components: size flag, max AC, 4-char DC, then 11 two-char ACs.
+	if len(result.Blurhash) != 28 || decodeBase83(result.Blurhash[:1]) != 3+2*9 {
+		t.Fatalf("blurhash = %q, want a 4x3 hash", result.Blurhash)
+	}
+
+	if result.DominantColor != "#dc141e" {
+		t.Fatalf("dominant color = %q, want the red majority #dc141e", result.DominantColor)
+	}
+	if len(result.Palette) != 2 || result.Palette[1] != "#0a28c8" {
+		t.Fatalf("palette = %v, want red then blue", result.Palette)
+	}
+}
+
+func TestEncodeBlurhashSolidColor(t *testing.T) {
+	img := image.NewRGBA(image.Rect(0, 0, 8, 8))
+	for i := range img.Pix {
+		img.Pix[i] = 255
+	}
+	hash := encodeBlurhash(img, 4, 3)
+	if dc := decodeBase83(hash[2:6]); dc != 0xffffff {
+		t.Fatalf("DC of solid white = %06x, want ffffff (hash %q)", dc, hash)
+	}
+}
+
+func TestProcessRejectsNonImages(t *testing.T) {
+	if _, err := Process([]byte("not an image"), DefaultVariants); !errors.Is(err, ErrUnsupportedImage) {
+		t.Fatalf("Process error = %v, want ErrUnsupportedImage", err)
+	}
+}
+
+type fakeObjectStorage struct {
+	objects map[string]int
+}
+
+func (f *fakeObjectStorage) PutObject(ctx context.Context, key string, reader io.Reader, size int64, contentType string) error {
+	data, err := io.ReadAll(reader)
+	if err != nil {
+		return err
+	}
+	if int64(len(data)) != size || contentType != "image/jpeg" {
+		return errors.New("unexpected object")
+	}
+	f.objects[key] = len(data)
+	return nil
+}
+
+type fakeArtworkStore struct {
+	bySource map[string]*db.Artwork
+	byHash   map[string]*db.Artwork
+	links    map[int64]int64
+	embedded map[int64]int64
+	folder   map[int64]int64
+	albumArt *db.Artwork
+	nextID   int64
+}
+
+func newFakeArtworkStore() *fakeArtworkStore {
+	return &fakeArtworkStore{
+		bySource: map[string]*db.Artwork{},
+		byHash:   map[string]*db.Artwork{},
+		links:    map[int64]int64{},
+		embedded: map[int64]int64{},
+		folder:   map[int64]int64{},
+	}
+}
+
+func (f *fakeArtworkStore) FindBySourceURL(ctx context.Context, sourceURL string) (*db.Artwork, error) {
+	if artwork, ok := f.bySource[sourceURL]; ok {
+		return artwork, nil
+	}
+	return nil, db.ErrArtworkNotFound
+}
+
+func (f *fakeArtworkStore) FindEmbedded(ctx context.Context, contentSHA256 string) (*db.Artwork, error) {
+	if artwork, ok := f.byHash[contentSHA256]; ok {
+		return artwork, nil
+	}
+	return nil, db.ErrArtworkNotFound
+}
+
+func (f *fakeArtworkStore) Upsert(ctx context.Context, artwork *db.Artwork) error {
+	f.nextID++
+	artwork.ID = f.nextID
+	artwork.CreatedAt = time.Now()
+	if artwork.SourceURL != "" {
+		f.bySource[artwork.SourceURL] = artwork
+	} else {
+		f.byHash[artwork.ContentSHA256] = artwork
+	}
+	return nil
+}
+
+func (f *fakeArtworkStore) LinkTrack(ctx context.Context, trackID, artworkID int64, sourceURL string) error {
+	f.links[trackID] = artworkID
+	return nil
+}
+
+func (f *fakeArtworkStore) LinkEmbedded(ctx context.Context, trackID, artworkID int64) error {
+	f.embedded[trackID] = artworkID
+	return nil
+}
+
+func (f *fakeArtworkStore) LinkFolder(ctx context.Context, trackID, artworkID int64) error {
+	f.folder[trackID] = artworkID
+	return nil
+}
+
+func (f *fakeArtworkStore) AdoptAlbumArtwork(ctx context.Context, trackID int64) (*db.Artwork, error) {
+	if f.albumArt == nil {
+		return nil, db.ErrArtworkNotFound
+	}
+	f.links[trackID] = f.albumArt.ID
+	return f.albumArt, nil
+}
+
+func TestIngestTrackArtworkProcessesEachSourceOnce(t *testing.T) {
+	data := twoToneImage(t)
+	var fetches atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/front.png" {
+			http.NotFound(w, r)
+			return
+		}
+		fetches.Add(1)
+		_, _ = w.Write(data)
+	}))
+	defer server.Close()
+
+	storage := &fakeObjectStorage{objects: map[string]int{}}
+	store := newFakeArtworkStore()
+	svc := NewService(storage, store)
+	ctx := context.Background()
+
+	first, err := svc.IngestTrackArtwork(ctx, 1, server.URL+"/front.png")
+	if err != nil {
+		t.Fatalf("ingest first track: %v", err)
+	}
+	if len(first.Variants) != len(DefaultVariants) || len(storage.objects) != len(DefaultVariants) {
+		t.Fatalf("variants = %d, stored objects = %d, want %d", len(first.Variants), len(storage.objects), len(DefaultVariants))
+	}
+	for _, v := range first.Variants {
+		if !strings.HasPrefix(v.StorageKey, "artwork/") || storage.objects[v.StorageKey] != int(v.SizeBytes) {
+			t.Fatalf("variant %s stored as %q (%d bytes)", v.Name, v.StorageKey, v.SizeBytes)
+		}
+	}
+
+	second, err := svc.IngestTrackArtwork(ctx, 2, server.URL+"/front.png")
+	if err != nil {
+		t.Fatalf("ingest second track: %v", err)
+	}
+	if second.ID != first.ID || fetches.Load() != 1 {
+		t.Fatalf("second ingest artwork %d after %d fetches, want shared artwork %d and one fetch", second.ID, fetches.Load(), first.ID)
+	}
+	if store.links[1] != first.ID || store.links[2] != first.ID {
+		t.Fatalf("links = %v, want both tracks on artwork %d", store.links, first.ID)
+	}
+
+	if _, err := svc.IngestTrackArtwork(ctx, 3, server.URL+"/missing.png"); err == nil {
+		t.Fatal("ingest of a missing image succeeded, want error")
+	}
+	if _, err := svc.IngestTrackArtwork(ctx, 3, "file:///etc/passwd"); err == nil {
+		t.Fatal("ingest of a non-HTTP URL succeeded, want error")
+	}
+	if _, ok := store.links[3]; ok {
+		t.Fatal("failed ingests must not link the track")
+	}
+}
+
+func TestIngestEmbeddedArtworkDedupesByContent(t *testing.T) {
+	storage := &fakeObjectStorage{objects: map[string]int{}}
+	store := newFakeArtworkStore()
+	svc := NewService(storage, store)
+	ctx := context.Background()
+	data := twoToneImage(t)
+
+	first, err := svc.IngestEmbeddedArtwork(ctx, 1, data)
+	if err != nil {
+		t.Fatalf("ingest first track: %v", err)
+	}
+	if first.SourceURL != "" || len(first.ContentSHA256) != 64 {
+		t.Fatalf("embedded artwork source = %q, hash = %q", first.SourceURL, first.ContentSHA256)
+	}
+	for _, v := range first.Variants {
+		if !strings.HasPrefix(v.StorageKey, "artwork/local/") {
+			t.Fatalf("variant %s stored as %q, want artwork/local/ prefix", v.Name, v.StorageKey)
+		}
+	}
+	second, err := svc.IngestEmbeddedArtwork(ctx, 2, bytes.Clone(data))
+	if err != nil {
+		t.Fatalf("ingest second track: %v", err)
+	}
+	if second.ID != first.ID || len(storage.objects) != len(DefaultVariants) {
+		t.Fatalf("second ingest = artwork %d with %d objects, want shared artwork %d", second.ID, len(storage.objects), first.ID)
+	}
+	if store.embedded[1] != first.ID || store.embedded[2] != first.ID {
+		t.Fatalf("embedded links = %v", store.embedded)
+	}
+
+	// The same image as a sidecar file reuses the embedded processing.
+	folder, err := svc.IngestFolderArtwork(ctx, 3, data)
+	if err != nil || folder.ID != first.ID || store.folder[3] != first.ID {
+		t.Fatalf("IngestFolderArtwork = %v, %v; folder links = %v", folder, err, store.folder)
+	}
+}
+
+func TestIngestTrackArtworkPrefersAlbumArtOverCoverURL(t *testing.T) {
+	var fetches atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fetches.Add(1)
+		http.NotFound(w, r)
+	}))
+	defer server.Close()
+
+	store := newFakeArtworkStore()
+	store.albumArt = &db.Artwork{ID: 42}
+	svc := NewService(&fakeObjectStorage{objects: map[string]int{}}, store)
+
+	artwork, err := svc.IngestTrackArtwork(context.Background(), 5, server.URL+"/front.jpg")
+	if err != nil || artwork.ID != 42 {
+		t.Fatalf("IngestTrackArtwork = %v, %v; want album artwork 42", artwork, err)
+	}
+	if fetches.Load() != 0 {
+		t.Fatalf("cover URL fetched %d times despite album artwork", fetches.Load())
+	}
+
+	store.albumArt = nil
+	if _, err := svc.IngestTrackArtwork(context.Background(), 6, ""); !errors.Is(err, db.ErrArtworkNotFound) {
+		t.Fatalf("no album art and no URL error = %v, want ErrArtworkNotFound", err)
+	}
+}
@@ -0,0 +1,80 @@
+package images
+
+import (
+	"fmt"
+	"image"
+	"sort"
+)
+
+// minPaletteDistance is the squared RGB distance below which two swatches
+// count as the same color; it keeps the palette from listing near-duplicates.
+const minPaletteDistance = 48 * 48
+
+type colorBucket struct {
+	r, g, b, count int
+}
+
+func (c colorBucket) rgb() (int, int, int) {
+	return c.r / c.count, c.g / c.count, c.b / c.count
+}
+
+// extractPalette returns up to n hex colors ordered by how much of img they
+// cover. Pixels are grouped into 5-bit-per-channel buckets, and each swatch
+// is the average of the pixels in its bucket.
+func extractPalette(img *image.RGBA, n int) []string {
+	buckets := make(map[int]*colorBucket)
+	width, height := img.Bounds().Dx(), img.Bounds().Dy()
+	for y := 0; y < height; y++ {
+		row := img.Pix[y*img.Stride:]
+		for x := 0; x < width; x++ {
+			p := row[x*4 : x*4+3]
+			key := int(p[0]>>3)<<10 | int(p[1]>>3)<<5 | int(p[2]>>3)
+			bucket := buckets[key]
+			if bucket == nil {
+				bucket = &colorBucket{}
+				buckets[key] = bucket
+			}
+			bucket.r += int(p[0])
+			bucket.g += int(p[1])
+			bucket.b += int(p[2])
+			bucket.count++
+		}
+	}
+
+	sorted := make([]colorBucket, 0, len(buckets))
+	for _, bucket := range buckets {
+		sorted = append(sorted, *bucket)
+	}
+	sort.Slice(sorted, func(i, j int) bool {
+		if sorted[i].count != sorted[j].count {
+			return sorted[i].count > sorted[j].count
+		}
+		// Deterministic output for ties regardless of map order.
+		ri, gi, bi := sorted[i].rgb()
+		rj, gj, bj := sorted[j].rgb()
+		return ri<<16|gi<<8|bi < rj<<16|gj<<8|bj
+	})
+
+	palette := make([]string, 0, n)
+	chosen := make([][3]int, 0, n)
+	for _, bucket := range sorted {
+		if len(palette) == n {
+			break
+		}
+		r, g, b := bucket.rgb()
+		distinct := true
+		for _, c := range chosen {
+			dr, dg, db := r-c[0], g-c[1], b-c[2]
+			if dr*dr+dg*dg+db*db < minPaletteDistance {
+				distinct = false
+				break
+			}
+		}
+		if !distinct {
+			continue
+		}
+		chosen = append(chosen, [3]int{r, g, b})
+		palette = append(palette, fmt.Sprintf("#%02x%02x%02x", r, g, b))
+	}
+	return palette
+}
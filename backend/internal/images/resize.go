@@ -0,0 +1,52 @@
+package images
+
+import "image"
+
+// resize scales src to width x height by averaging the source pixels each
+// destination pixel covers. That is a box filter, which is cheap and free of
+// aliasing when shrinking, the only direction artwork is resized in.
+func resize(src *image.RGBA, width, height int) *image.RGBA {
+	sw, sh := src.Bounds().Dx(), src.Bounds().Dy()
+	if sw == width && sh == height {
+		return src
+	}
+	dst := image.NewRGBA(image.Rect(0, 0, width, height))
+	for dy := 0; dy < height; dy++ {
+		y0, y1 := span(dy, height, sh)
+		for dx := 0; dx < width; dx++ {
+			x0, x1 := span(dx, width, sw)
+			var r, g, b, a, n int
+			for y := y0; y < y1; y++ {
+				row := src.Pix[y*src.Stride:]
+				for x := x0; x < x1; x++ {
+					p := row[x*4 : x*4+4]
+					r += int(p[0])
+					g += int(p[1])
+					b += int(p[2])
+					a += int(p[3])
+					n++
+				}
+			}
+			out := dst.Pix[dy*dst.Stride+dx*4:]
+			out[0] = uint8(r / n)
+			out[1] = uint8(g / n)
+			out[2] = uint8(b / n)
+			out[3] = uint8(a / n)
+		}
+	}
+	return dst
+}
+
+// span maps destination index i of n onto the half-open source range it
+// covers out of size source pixels. Every range holds at least one pixel.
+func span(i, n, size int) (int, int) {
+	start := i * size / n
+	end := (i + 1) * size / n
+	if end <= start {
+		end = start + 1
+	}
+	if end > size {
+		end = size
+	}
+	return start, end
+}
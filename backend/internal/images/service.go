@@ -0,0 +1,201 @@
+package images
+
+import (
+	"bytes"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	fetchTimeout = 20 * time.Second
+
+	// maxSourceBytes bounds a downloaded source image. Cover Art Archive
+	// originals are occasionally several megabytes; anything past this is not
+	// plausible cover art.
+	maxSourceBytes = 20 * 1024 * 1024
+)
+
+// ObjectStorage is the object store surface used for rendered variants.
+// storage.Client satisfies it.
+type ObjectStorage interface {
+	PutObject(ctx context.Context, key string, reader io.Reader, size int64, contentType string) error
+}
+
+// ArtworkStore persists processed artwork. db.ArtworkRepository satisfies it.
+type ArtworkStore interface {
+	FindBySourceURL(ctx context.Context, sourceURL string) (*db.Artwork, error)
+	FindEmbedded(ctx context.Context, contentSHA256 string) (*db.Artwork, error)
+	Upsert(ctx context.Context, artwork *db.Artwork) error
+	LinkTrack(ctx context.Context, trackID, artworkID int64, sourceURL string) error
+	LinkEmbedded(ctx context.Context, trackID, artworkID int64) error
+	LinkFolder(ctx context.Context, trackID, artworkID int64) error
+	AdoptAlbumArtwork(ctx context.Context, trackID int64) (*db.Artwork, error)
+}
+
+// Service ingests artwork: it processes each distinct image once, stores the
+// rendered variants, and links tracks to the result.
+type Service struct {
+	storage  ObjectStorage
+	store    ArtworkStore
+	client   *http.Client
+	variants []Variant
+}
+
+func NewService(storage ObjectStorage, store ArtworkStore) *Service {
+	return &Service{
+		storage:  storage,
+		store:    store,
+		client:   &http.Client{Timeout: fetchTimeout},
+		variants: DefaultVariants,
+	}
+}
+
+// IngestEmbeddedArtwork links trackID to cover art extracted from its audio
+// file. Identical images are processed once, whichever tracks carry them.
+func (s *Service) IngestEmbeddedArtwork(ctx context.Context, trackID int64, data []byte) (*db.Artwork, error) {
+	artwork, err := s.localArtwork(ctx, data)
+	if err != nil {
+		return nil, err
+	}
+	if err := s.store.LinkEmbedded(ctx, trackID, artwork.ID); err != nil {
+		return nil, err
+	}
+	return artwork, nil
+}
+
+// IngestFolderArtwork links trackID, and the rest of its album, to a sidecar
+// image found beside the track's file.
+func (s *Service) IngestFolderArtwork(ctx context.Context, trackID int64, data []byte) (*db.Artwork, error) {
+	artwork, err := s.localArtwork(ctx, data)
+	if err != nil {
+		return nil, err
+	}
+	if err := s.store.LinkFolder(ctx, trackID, artwork.ID); err != nil {
+		return nil, err
+	}
+	return artwork, nil
+}
+
+// localArtwork returns processed artwork for image bytes read from disk,
+// processing each distinct image only once.
+func (s *Service) localArtwork(ctx context.Context, data []byte) (*db.Artwork, error) {
+	hash := contentHash(data)
+	artwork, err := s.store.FindEmbedded(ctx, hash)
+	if errors.Is(err, db.ErrArtworkNotFound) {
+		return s.process(ctx, "", data, "artwork/local/"+hash[:32]+"/")
+	}
+	return artwork, err
+}
+
+// IngestTrackArtwork links trackID to artwork for a track without local art.
+// Artwork the track's album agrees on wins; only without it is
+// sourceURL fetched, once per URL. An empty sourceURL with no album art
+// returns db.ErrArtworkNotFound.
+func (s *Service) IngestTrackArtwork(ctx context.Context, trackID int64, sourceURL string) (*db.Artwork, error) {
+	artwork, err := s.store.AdoptAlbumArtwork(ctx, trackID)
+	if !errors.Is(err, db.ErrArtworkNotFound) {
+		return artwork, err
+	}
+	if sourceURL == "" {
+		return nil, db.ErrArtworkNotFound
+	}
+
+	artwork, err = s.store.FindBySourceURL(ctx, sourceURL)
+	if errors.Is(err, db.ErrArtworkNotFound) {
+		var data []byte
+		data, err = s.fetch(ctx, sourceURL)
+		if err != nil {
+			return nil, err
+		}
+		artwork, err = s.process(ctx, sourceURL, data, storagePrefix(sourceURL))
+	}
+	if err != nil {
+		return nil, err
+	}
+	if err := s.store.LinkTrack(ctx, trackID, artwork.ID, sourceURL); err != nil {
+		return nil, err
+	}
+	return artwork, nil
+}
+
+func (s *Service) process(ctx context.Context, sourceURL string, data []byte, prefix string) (*db.Artwork, error) {
+	result, err := Process(data, s.variants)
+	if err != nil {
+		return nil, err
+	}
+
+	artwork := &db.Artwork{
+		SourceURL:     sourceURL,
+		ContentSHA256: contentHash(data),
+		Width:         result.Width,
+		Height:        result.Height,
+		Blurhash:      result.Blurhash,
+		DominantColor: result.DominantColor,
+		Palette:       result.Palette,
+	}
+	for _, variant := range result.Variants {
+		key := prefix + variant.Name + ".jpg"
+		if err := s.storage.PutObject(ctx, key, bytes.NewReader(variant.Data), int64(len(variant.Data)), "image/jpeg"); err != nil {
+			return nil, fmt.Errorf("store %s artwork variant: %w", variant.Name, err)
+		}
+		artwork.Variants = append(artwork.Variants, db.ArtworkVariant{
+			Name:       variant.Name,
+			StorageKey: key,
+			Width:      variant.Width,
+			Height:     variant.Height,
+			SizeBytes:  int64(len(variant.Data)),
+		})
+	}
+	if err := s.store.Upsert(ctx, artwork); err != nil {
+		return nil, fmt.Errorf("save artwork: %w", err)
+	}
+	return artwork, nil
+}
+
+func (s *Service) fetch(ctx context.Context, sourceURL string) ([]byte, error) {
+	parsed, err := url.Parse(sourceURL)
+	if err != nil || (parsed.Scheme != "http" && parsed.Scheme != "https") || parsed.Host == "" {
+		return nil, fmt.Errorf("invalid artwork URL %q", sourceURL)
+	}
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, sourceURL, nil)
+	if err != nil {
+		return nil, fmt.Errorf("build artwork request: %w", err)
+	}
+	resp, err := s.client.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("fetch artwork: %w", err)
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("fetch artwork: unexpected status %d", resp.StatusCode)
+	}
+	data, err := io.ReadAll(io.LimitReader(resp.Body, maxSourceBytes+1))
+	if err != nil {
+		return nil, fmt.Errorf("read artwork: %w", err)
+	}
+	if len(data) > maxSourceBytes {
+		return nil, ErrImageTooLarge
+	}
+	return data, nil
+}
+
+// storagePrefix derives the object key prefix from the source URL, so
+// reprocessing the same source overwrites its earlier variants in place.
+func storagePrefix(sourceURL string) string {
+	sum := sha256.Sum256([]byte(sourceURL))
+	return "artwork/" + hex.EncodeToString(sum[:16]) + "/"
+}
+
+func contentHash(data []byte) string {
+	sum := sha256.Sum256(data)
+	return hex.EncodeToString(sum[:])
+}
@@ -0,0 +1,547 @@
+package importers
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+	"gopkg.in/yaml.v3"
+
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/fetcher"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// Beets import modes.
+const (
+	// BeetsModeLibrary reads items, MusicBrainz IDs, and album art from the
+	// beets library database through `beet ls`.
+	BeetsModeLibrary = "library"
+	// BeetsModeFolder scans the folder beets manages, recovering fields the
+	// files lack from the configured path formats.
+	BeetsModeFolder = "folder"
+)
+
+// beetsDefaultPaths are beets' built-in path formats.
+var beetsDefaultPaths = map[string]string{
+	"default":   "$albumartist/$album%aunique{}/$track $title",
+	"singleton": "Non-Album/$artist/$title",
+	"comp":      "Compilations/$album%aunique{}/$track $title",
+}
+
+// beetsFieldSeparator separates fields in `beet ls -f` output; it cannot
+// appear in tags beets writes.
+const beetsFieldSeparator = "\x1f"
+
+const (
+	beetsItemFormat  = "$id\x1f$album_id\x1f$path\x1f$title\x1f$artist\x1f$album\x1f$length\x1f$mb_trackid\x1f$mb_albumid\x1f$mb_artistid"
+	beetsAlbumFormat = "$id\x1f$artpath"
+)
+
+// BeetsImport is the job kind that imports a beets library, or the folder
+// beets manages, into a user's library.
+var BeetsImport = jobs.NewKind[BeetsImportPayload]("beets_import")
+
+type BeetsImportPayload struct {
+	// Mode is BeetsModeLibrary (the default) or BeetsModeFolder.
+	Mode string `json:"mode,omitempty"`
+	// Query is a beets query, such as ["artist:Low", "year:1994"], limiting
+	// a library import. Empty imports every item.
+	Query []string `json:"query,omitempty"`
+	// Path limits a folder import to a directory under the beets directory.
+	Path string `json:"path,omitempty"`
+}
+
+// BeetsItem is one file beets manages.
+type BeetsItem struct {
+	Path       string
+	Title      string
+	Artist     string
+	Album      string
+	DurationMs int
+	MBTrackID  string
+	MBAlbumID  string
+	MBArtistID string
+	ArtPath    string
+	// FromLibrary marks items read from the beets database, whose fields
+	// outrank the file's tags.
+	FromLibrary bool
+}
+
+// Beets reads a beets setup: its config file, library database, and music
+// directory.
+type Beets struct {
+	executable string
+	config     string
+	library    string
+	directory  string
+	formats    []*PathFormat
+}
+
+type beetsConfigFile struct {
+	Directory string            `yaml:"directory"`
+	Library   string            `yaml:"library"`
+	Paths     map[string]string `yaml:"paths"`
+}
+
+// LoadBeets reads the beets config file at configPath for the music
+// directory, library database, and path formats, falling back to beets'
+// defaults for anything it leaves out. executable is the beet command used to
+// query the library; "beet" when empty.
+func LoadBeets(configPath, executable string) (*Beets, error) {
+	data, err := os.ReadFile(configPath)
+	if err != nil {
+		return nil, fmt.Errorf("read beets config: %w", err)
+	}
+	var file beetsConfigFile
+	if err := yaml.Unmarshal(data, &file); err != nil {
+		return nil, fmt.Errorf("parse beets config %s: %w", configPath, err)
+	}
+	configDir := filepath.Dir(configPath)
+	b := &Beets{
+		executable: executable,
+		config:     configPath,
+		library:    expandBeetsPath(configDir, file.Library, "library.db"),
+		directory:  expandBeetsPath(configDir, file.Directory, "~/Music"),
+	}
+	if b.executable == "" {
+		b.executable = "beet"
+	}
+
+	paths := make(map[string]string, len(beetsDefaultPaths)+len(file.Paths))
+	for key, template := range beetsDefaultPaths {
+		paths[key] = template
+	}
+	for key, template := range file.Paths {
+		paths[key] = template
+	}
+	// Query-keyed formats are the most specific; beets' fallbacks go last.
+	keys := make([]string, 0, len(paths))
+	for key := range paths {
+		keys = append(keys, key)
+	}
+	rank := map[string]int{"comp": 1, "singleton": 2, "default": 3}
+	slices.SortFunc(keys, func(a, b string) int {
+		if rank[a] != rank[b] {
+			return rank[a] - rank[b]
+		}
+		return strings.Compare(a, b)
+	})
+	for _, key := range keys {
+		format, err := ParsePathFormat(paths[key])
+		if err != nil {
+			return nil, fmt.Errorf("beets path format %s: %w", key, err)
+		}
+		b.formats = append(b.formats, format)
+	}
+	return b, nil
+}
+
+// expandBeetsPath resolves a path from the beets config the way beets does:
+// "~" is the home directory and relative paths sit beside the config file.
+func expandBeetsPath(configDir, value, fallback string) string {
+	value = strings.TrimSpace(value)
+	if value == "" {
+		value = fallback
+	}
+	if rest, ok := strings.CutPrefix(value, "~"); ok {
+		if home, err := os.UserHomeDir(); err == nil {
+			value = home + rest
+		}
+	}
+	if !filepath.IsAbs(value) {
+		value = filepath.Join(configDir, value)
+	}
+	return filepath.Clean(value)
+}
+
+// Items lists the library items matching query, with their album's art.
+func (b *Beets) Items(ctx context.Context, query []string) ([]BeetsItem, error) {
+	output, err := b.ls(ctx, append([]string{"-f", beetsItemFormat, "--"}, query...))
+	if err != nil {
+		return nil, err
+	}
+	albumOutput, err := b.ls(ctx, []string{"-a", "-f", beetsAlbumFormat})
+	if err != nil {
+		return nil, err
+	}
+	artPaths := map[string]string{}
+	for _, fields := range beetsRows(albumOutput, 2) {
+		if fields[1] != "" {
+			artPaths[fields[0]] = fields[1]
+		}
+	}
+
+	var items []BeetsItem
+	for _, fields := range beetsRows(output, 10) {
+		items = append(items, BeetsItem{
+			Path:        fields[2],
+			Title:       fields[3],
+			Artist:      fields[4],
+			Album:       fields[5],
+			DurationMs:  beetsLengthMs(fields[6]),
+			MBTrackID:   fields[7],
+			MBAlbumID:   fields[8],
+			MBArtistID:  fields[9],
+			ArtPath:     artPaths[fields[1]],
+			FromLibrary: true,
+		})
+	}
+	return items, nil
+}
+
+func (b *Beets) ls(ctx context.Context, args []string) ([]byte, error) {
+	args = append([]string{"-c", b.config, "-l", b.library, "ls"}, args...)
+	cmd := exec.CommandContext(ctx, b.executable, args...)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	output, err := cmd.Output()
+	if errors.Is(err, exec.ErrNotFound) {
+		return nil, jobs.Permanent(fmt.Errorf("%s is not installed", b.executable))
+	}
+	if err != nil {
+		return nil, fmt.Errorf("beet ls: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return output, nil
+}
+
+// beetsRows splits `beet ls -f` output into rows of n fields. Rows with a
+// different count, such as titles containing line breaks, are dropped.
+func beetsRows(output []byte, n int) [][]string {
+	var rows [][]string
+	for _, line := range strings.Split(string(output), "\n") {
+		fields := strings.Split(line, beetsFieldSeparator)
+		if len(fields) != n {
+			continue
+		}
+		for i := range fields {
+			fields[i] = strings.TrimSpace(fields[i])
+		}
+		rows = append(rows, fields)
+	}
+	return rows
+}
+
+// beetsLengthMs parses a length as beets prints it: "3:07", "1:02:03", or
+// raw seconds with format_raw_length.
+func beetsLengthMs(value string) int {
+	seconds := 0.0
+	for _, part := range strings.Split(value, ":") {
+		n, err := strconv.ParseFloat(part, 64)
+		if err != nil {
+			return 0
+		}
+		seconds = seconds*60 + n
+	}
+	return int(seconds * 1000)
+}
+
+// ScanFolder lists the audio files under dir, relative to the beets
+// directory, filling the fields the path formats recover.
+func (b *Beets) ScanFolder(ctx context.Context, dir string) ([]BeetsItem, error) {
+	root := filepath.Join(b.directory, filepath.FromSlash(CleanPath(dir)))
+	var items []BeetsItem
+	err := filepath.WalkDir(root, func(file string, entry fs.DirEntry, err error) error {
+		if err != nil {
+			return err
+		}
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if !entry.Type().IsRegular() || !fetcher.IsAudioFile(file) {
+			return nil
+		}
+		if len(items) == MaxFilesPerImport {
+			return jobs.Permanent(fmt.Errorf("more than %d audio files under %s; narrow the path", MaxFilesPerImport, root))
+		}
+		item := BeetsItem{Path: file}
+		if rel, err := filepath.Rel(b.directory, file); err == nil {
+			for _, format := range b.formats {
+				if fields, ok := format.Match(filepath.ToSlash(rel)); ok {
+					item.Title = fields["title"]
+					item.Artist = firstNonEmpty(fields["artist"], fields["albumartist"])
+					item.Album = fields["album"]
+					break
+				}
+			}
+		}
+		items = append(items, item)
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return items, nil
+}
+
+// Job is the download job that imports item for userID.
+func (item BeetsItem) Job(userID uuid.UUID) *download.DownloadJob {
+	job := &download.DownloadJob{
+		ID:         uuid.NewString(),
+		UserID:     userID.String(),
+		URL:        "file://" + item.Path,
+		SourceType: download.SourceTypeBeets,
+		SourceID:   item.Path,
+		Title:      item.Title,
+		Artist:     item.Artist,
+		Album:      item.Album,
+		DurationMs: item.DurationMs,
+		Status:     download.StatusDownloading,
+		CreatedAt:  time.Now(),
+	}
+	if id, err := uuid.Parse(item.MBTrackID); err == nil {
+		mbid := id.String()
+		job.MBRecordingID = &mbid
+	}
+	if item.FromLibrary {
+		job.Metadata = map[string]interface{}{
+			download.MetadataLibrary: download.LibraryMetadata{
+				Manager:     "beets",
+				MBReleaseID: item.MBAlbumID,
+				MBArtistID:  item.MBArtistID,
+				ArtworkPath: item.ArtPath,
+			},
+		}
+	}
+	return job
+}
+
+// BeetsQueue queues beets import jobs.
+type BeetsQueue struct {
+	store *jobs.Store
+}
+
+func NewBeetsQueue(store *jobs.Store) *BeetsQueue {
+	return &BeetsQueue{store: store}
+}
+
+// Enqueue validates payload and queues it for the user. A user runs at most
+// one beets import at a time; a second request while one is queued or running
+// returns jobs.ErrDuplicate.
+func (q *BeetsQueue) Enqueue(ctx context.Context, userID uuid.UUID, payload BeetsImportPayload) (*jobs.Job, error) {
+	if payload.Mode == "" {
+		payload.Mode = BeetsModeLibrary
+	}
+	switch {
+	case payload.Mode != BeetsModeLibrary && payload.Mode != BeetsModeFolder:
+		return nil, fmt.Errorf("%w: %q", ErrInvalidBeetsImport, payload.Mode)
+	case payload.Mode == BeetsModeLibrary && payload.Path != "":
+		return nil, fmt.Errorf("%w: path applies to folder imports; use a query", ErrInvalidBeetsImport)
+	case payload.Mode == BeetsModeFolder && len(payload.Query) > 0:
+		return nil, fmt.Errorf("%w: query applies to library imports; use a path", ErrInvalidBeetsImport)
+	}
+	payload.Path = CleanPath(payload.Path)
+	return BeetsImport.Enqueue(ctx, q.store, payload, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   userID.String() + ":beets",
+	})
+}
+
+// BeetsRunner imports the items of a beets library that are not already in
+// the user's library.
+type BeetsRunner struct {
+	beets     *Beets
+	tracks    trackImporter
+	admission Admission
+}
+
+func NewBeetsRunner(beets *Beets, sources TrackSources, library Library, importer Importer) *BeetsRunner {
+	return &BeetsRunner{beets: beets, tracks: trackImporter{sources: sources, library: library, importer: importer}}
+}
+
+// GuardDownloads stops imports while admission refuses new downloads.
+func (r *BeetsRunner) GuardDownloads(admission Admission) *BeetsRunner {
+	r.admission = admission
+	return r
+}
+
+// Register runs beets import jobs on w.
+func (r *BeetsRunner) Register(w *jobs.Worker) {
+	BeetsImport.Handle(w, func(ctx context.Context, job *jobs.Job, payload BeetsImportPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, job.UserID.UUID, payload, progress)
+	})
+}
+
+// Run performs one pass of a beets import job.
+func (r *BeetsRunner) Run(ctx context.Context, jobID, userID uuid.UUID, payload BeetsImportPayload, progress progressReporter) error {
+	var items []BeetsItem
+	var err error
+	switch payload.Mode {
+	case "", BeetsModeLibrary:
+		items, err = r.beets.Items(ctx, payload.Query)
+	case BeetsModeFolder:
+		items, err = r.beets.ScanFolder(ctx, payload.Path)
+	default:
+		return jobs.Permanent(fmt.Errorf("%w: %q", ErrInvalidBeetsImport, payload.Mode))
+	}
+	if err != nil {
+		return err
+	}
+	if len(items) > MaxFilesPerImport {
+		return jobs.Permanent(fmt.Errorf("query matches more than %d items; narrow it", MaxFilesPerImport))
+	}
+
+	var counts ImportProgress
+	if err := progress.Report(ctx, 0, len(items), counts); err != nil {
+		return err
+	}
+	for i, item := range items {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if r.admission != nil {
+			if err := r.admission.Admit(ctx); err != nil {
+				return err
+			}
+		}
+		status, err := r.tracks.importJob(ctx, "Beets import job "+jobID.String(), item.Job(userID))
+		if err != nil {
+			return err
+		}
+		counts.add(status)
+		if err := progress.Report(ctx, i+1, len(items), counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// PathFormat matches paths produced by a beets path format, such as
+// "$albumartist/$album%aunique{}/$track $title", and recovers the fields it
+// names. Template functions match whatever they could have produced.
+type PathFormat struct {
+	pattern *regexp.Regexp
+	fields  []string
+}
+
+var beetsNumericFields = map[string]bool{
+	"track": true, "tracktotal": true, "disc": true, "disctotal": true,
+	"year": true, "month": true, "day": true, "original_year": true, "bpm": true,
+}
+
+// ParsePathFormat compiles a beets path format.
+func ParsePathFormat(template string) (*PathFormat, error) {
+	var pattern strings.Builder
+	var fields []string
+	pattern.WriteString("^")
+	for i := 0; i < len(template); {
+		switch c := template[i]; {
+		case c == '$' && i+1 < len(template) && strings.IndexByte("$%},", template[i+1]) >= 0:
+			// An escaped template character stands for itself.
+			pattern.WriteString(regexp.QuoteMeta(template[i+1 : i+2]))
+			i += 2
+		case c == '$':
+			name, width := beetsFieldName(template[i+1:])
+			if name == "" {
+				return nil, fmt.Errorf("bad field reference at offset %d in %q", i, template)
+			}
+			if beetsNumericFields[name] {
+				pattern.WriteString(`(\d+)`)
+			} else {
+				pattern.WriteString(`([^/]+?)`)
+			}
+			fields = append(fields, name)
+			i += 1 + width
+		case c == '%':
+			open := strings.IndexByte(template[i:], '{')
+			end := -1
+			if open > 0 {
+				end = matchingBrace(template, i+open)
+			}
+			if end < 0 {
+				return nil, fmt.Errorf("unterminated function at offset %d in %q", i, template)
+			}
+			if template[i+1:i+open] == "aunique" {
+				// Album disambiguation, " [2005]" with beets' defaults.
+				pattern.WriteString(`(?: [\[(][^/]*[\])])?`)
+			} else {
+				pattern.WriteString(`[^/]*?`)
+			}
+			i = end + 1
+		default:
+			pattern.WriteString(regexp.QuoteMeta(string(c)))
+			i++
+		}
+	}
+	pattern.WriteString(`\.[^./]+$`)
+	compiled, err := regexp.Compile(pattern.String())
+	if err != nil {
+		return nil, fmt.Errorf("compile %q: %w", template, err)
+	}
+	return &PathFormat{pattern: compiled, fields: fields}, nil
+}
+
+// beetsFieldName reads "name" or "{name}" after a "$", returning the name and
+// how many bytes it spans.
+func beetsFieldName(s string) (string, int) {
+	if rest, ok := strings.CutPrefix(s, "{"); ok {
+		end := strings.IndexByte(rest, '}')
+		if end <= 0 {
+			return "", 0
+		}
+		return rest[:end], end + 2
+	}
+	end := 0
+	for end < len(s) && (s[end] == '_' || s[end] >= 'a' && s[end] <= 'z' || s[end] >= 'A' && s[end] <= 'Z' || s[end] >= '0' && s[end] <= '9') {
+		end++
+	}
+	return s[:end], end
+}
+
+// matchingBrace returns the index of the "}" closing the "{" at open, or -1.
+func matchingBrace(s string, open int) int {
+	if open < 0 || open >= len(s) || s[open] != '{' {
+		return -1
+	}
+	depth := 0
+	for i := open; i < len(s); i++ {
+		switch s[i] {
+		case '{':
+			depth++
+		case '}':
+			if depth--; depth == 0 {
+				return i
+			}
+		}
+	}
+	return -1
+}
+
+// Match reports whether rel, a path relative to the beets directory, fits
+// the format, returning the fields it recovered. A field that appears more
+// than once keeps its first value.
+func (f *PathFormat) Match(rel string) (map[string]string, bool) {
+	groups := f.pattern.FindStringSubmatch(rel)
+	if groups == nil {
+		return nil, false
+	}
+	fields := make(map[string]string, len(f.fields))
+	for i, name := range f.fields {
+		if _, ok := fields[name]; !ok {
+			fields[name] = groups[i+1]
+		}
+	}
+	return fields, true
+}
+
+func firstNonEmpty(values ...string) string {
+	for _, value := range values {
+		if value != "" {
+			return value
+		}
+	}
+	return ""
+}
@@ -0,0 +1,208 @@
+package importers
+
+import (
+	"context"
+	"errors"
+	"os"
+	"path/filepath"
+	"reflect"
+	"runtime"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+func TestPathFormatRecoversFields(t *testing.T) {
+	tests := []struct {
+		template string
+		path     string
+		want     map[string]string
+	}{
+		{
+			template: "$albumartist/$album%aunique{}/$track $title",
+			path:     "Low/Things We Lost in the Fire [2001]/03 Sunflower.flac",
+			want:     map[string]string{"albumartist": "Low", "album": "Things We Lost in the Fire", "track": "03", "title": "Sunflower"},
+		},
+		{
+			template: "$albumartist/$album%aunique{}/$track $title",
+			path:     "Low/Long Division/01 Just Like Christmas.mp3",
+			want:     map[string]string{"albumartist": "Low", "album": "Long Division", "track": "01", "title": "Just Like Christmas"},
+		},
+		{
+			template: "${artist} - %if{$year,$year - }${title}",
+			path:     "Nina Simone - 1965 - Sinnerman.ogg",
+			want:     map[string]string{"artist": "Nina Simone", "title": "1965 - Sinnerman"},
+		},
+		{
+			template: "Non-Album/$artist/$title",
+			path:     "Non-Album/Kraftwerk/Das Model.m4a",
+			want:     map[string]string{"artist": "Kraftwerk", "title": "Das Model"},
+		},
+		{template: "Non-Album/$artist/$title", path: "Other/Kraftwerk/Das Model.m4a"},
+		{template: "$albumartist/$album/$track $title", path: "Low/Album/Sunflower.flac"},
+	}
+	for _, tt := range tests {
+		format, err := ParsePathFormat(tt.template)
+		if err != nil {
+			t.Fatalf("ParsePathFormat(%q) error = %v", tt.template, err)
+		}
+		got, ok := format.Match(tt.path)
+		if ok != (tt.want != nil) || !reflect.DeepEqual(got, tt.want) {
+			t.Errorf("%q.Match(%q) = %v, %v; want %v", tt.template, tt.path, got, ok, tt.want)
+		}
+	}
+
+	for _, template := range []string{"$artist/%upper{$title", "$/$title"} {
+		if _, err := ParsePathFormat(template); err == nil {
+			t.Errorf("ParsePathFormat(%q) succeeded", template)
+		}
+	}
+}
+
+// writeBeets sets up a beets config under a temporary directory, with script
+// standing in for the beet command.
+func writeBeets(t *testing.T, config, script string) *Beets {
+	t.Helper()
+	dir := t.TempDir()
+	configPath := filepath.Join(dir, "config.yaml")
+	if err := os.WriteFile(configPath, []byte(config), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	executable := filepath.Join(dir, "beet")
+	if err := os.WriteFile(executable, []byte(script), 0o755); err != nil {
+		t.Fatal(err)
+	}
+	beets, err := LoadBeets(configPath, executable)
+	if err != nil {
+		t.Fatalf("LoadBeets() error = %v", err)
+	}
+	return beets
+}
+
+func TestBeetsItemsReadsLibrary(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("fake beet is a shell script")
+	}
+	sep := "\x1f"
+	items := strings.Join([]string{
+		strings.Join([]string{"1", "9", "/music/Low/Secret Name/01 Starfire.flac", "Starfire", "Low", "Secret Name", "4:05", "11111111-1111-1111-1111-111111111111", "22222222-2222-2222-2222-222222222222", "33333333-3333-3333-3333-333333333333"}, sep),
+		strings.Join([]string{"2", "", "/music/Non-Album/Low/Demo.mp3", "Demo", "Low", "", "1:02:03.5", "", "", ""}, sep),
+		"a line a title broke",
+	}, "\n")
+	albums := strings.Join([]string{"9", "/music/Low/Secret Name/cover.jpg"}, sep)
+	script := "#!/bin/sh\n" +
+		"[ \"$1 $3 $5\" = \"-c -l ls\" ] || exit 2\n" +
+		"if [ \"$6\" = \"-a\" ]; then printf '%s\\n' '" + albums + "'; exit 0; fi\n" +
+		"[ \"$8 $9\" = \"-- artist:Low\" ] || exit 3\n" +
+		"printf '%s\\n' '" + items + "'\n"
+	beets := writeBeets(t, "directory: /music\nlibrary: beets.db\n", script)
+
+	got, err := beets.Items(context.Background(), []string{"artist:Low"})
+	if err != nil {
+		t.Fatalf("Items() error = %v", err)
+	}
+	want := []BeetsItem{
+		{
+			Path:        "/music/Low/Secret Name/01 Starfire.flac",
+			Title:       "Starfire",
+			Artist:      "Low",
+			Album:       "Secret Name",
+			DurationMs:  245000,
+			MBTrackID:   "11111111-1111-1111-1111-111111111111",
+			MBAlbumID:   "22222222-2222-2222-2222-222222222222",
+			MBArtistID:  "33333333-3333-3333-3333-333333333333",
+			ArtPath:     "/music/Low/Secret Name/cover.jpg",
+			FromLibrary: true,
+		},
+		{Path: "/music/Non-Album/Low/Demo.mp3", Title: "Demo", Artist: "Low", DurationMs: 3723500, FromLibrary: true},
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("Items() = %+v, want %+v", got, want)
+	}
+
+	userID := uuid.New()
+	job := got[0].Job(userID)
+	library := job.Library()
+	if job.URL != "file:///music/Low/Secret Name/01 Starfire.flac" || job.SourceType != download.SourceTypeBeets || job.UserID != userID.String() || job.MBRecordingID == nil || *job.MBRecordingID != want[0].MBTrackID {
+		t.Fatalf("job = %+v", job)
+	}
+	if library == nil || library.Manager != "beets" || library.MBReleaseID != want[0].MBAlbumID || library.ArtworkPath != want[0].ArtPath {
+		t.Fatalf("job library metadata = %+v", library)
+	}
+}
+
+func TestBeetsScanFolderUsesPathFormats(t *testing.T) {
+	beets := writeBeets(t, "directory: music\npaths:\n  genre:Jazz: Jazz/$artist/$title\n", "#!/bin/sh\nexit 1\n")
+	files := []string{
+		"Jazz/Nina Simone/Sinnerman.flac",
+		"Low/Secret Name [1999]/01 Starfire.flac",
+		"Low/Secret Name [1999]/cover.jpg",
+		"Non-Album/Low/Demo.mp3",
+	}
+	for _, file := range files {
+		path := filepath.Join(beets.directory, filepath.FromSlash(file))
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte("audio"), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	got, err := beets.ScanFolder(context.Background(), "")
+	if err != nil {
+		t.Fatalf("ScanFolder() error = %v", err)
+	}
+	want := []BeetsItem{
+		{Path: filepath.Join(beets.directory, "Jazz", "Nina Simone", "Sinnerman.flac"), Title: "Sinnerman", Artist: "Nina Simone"},
+		{Path: filepath.Join(beets.directory, "Low", "Secret Name [1999]", "01 Starfire.flac"), Title: "Starfire", Artist: "Low", Album: "Secret Name"},
+		{Path: filepath.Join(beets.directory, "Non-Album", "Low", "Demo.mp3"), Title: "Demo", Artist: "Low"},
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("ScanFolder() = %+v, want %+v", got, want)
+	}
+	if job := got[0].Job(uuid.New()); job.Library() != nil || job.MBRecordingID != nil {
+		t.Fatalf("folder job = %+v, want file tags to outrank the path", job)
+	}
+
+	got, err = beets.ScanFolder(context.Background(), "../Low")
+	if err != nil || len(got) != 1 || got[0].Album != "Secret Name" {
+		t.Fatalf("ScanFolder(../Low) = %+v, %v", got, err)
+	}
+}
+
+func TestBeetsRunnerImportsFolder(t *testing.T) {
+	beets := writeBeets(t, "directory: music\n", "#!/bin/sh\nexit 1\n")
+	for _, file := range []string{"Low/Album/01 Intro.flac", "Low/Album/02 Song.flac"} {
+		path := filepath.Join(beets.directory, filepath.FromSlash(file))
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte("audio"), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	intro := "file://" + filepath.Join(beets.directory, "Low", "Album", "01 Intro.flac")
+	library := &fakeLibrary{}
+	importer := &fakeImporter{}
+	runner := NewBeetsRunner(beets, fakeTrackSources{"beets " + intro: 4}, library, importer)
+
+	progress := &fakeProgress{}
+	if err := runner.Run(context.Background(), uuid.New(), uuid.New(), BeetsImportPayload{Mode: BeetsModeFolder}, progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	if want := (ImportProgress{Imported: 1, Skipped: 1}); progress.reports[len(progress.reports)-1] != want || progress.total != 2 {
+		t.Fatalf("progress = %+v, want %+v", progress.reports, want)
+	}
+	if len(importer.jobs) != 1 || importer.jobs[0].Title != "Song" || !reflect.DeepEqual(library.added, []int64{4}) {
+		t.Fatalf("imported %+v, library adds %v", importer.jobs, library.added)
+	}
+
+	err := runner.Run(context.Background(), uuid.New(), uuid.New(), BeetsImportPayload{Mode: "database"}, progress)
+	if !errors.Is(err, ErrInvalidBeetsImport) {
+		t.Fatalf("Run() with an unknown mode = %v", err)
+	}
+}
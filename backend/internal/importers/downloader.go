@@ -0,0 +1,65 @@
+package importers
+
+import (
+	"context"
+	"fmt"
+	"mime"
+	"path"
+
+	"github.com/openmusicplayer/backend/internal/fetcher"
+)
+
+// Downloader fetches remote://<name>/<path> URLs queued by import jobs. Each
+// fetch opens its own connection, so jobs never share a session.
+type Downloader struct {
+	remotes  Remotes
+	maxBytes int64
+}
+
+// NewDownloader returns a downloader for remotes. maxBytes <= 0 uses
+// fetcher.DefaultMaxBytes.
+func NewDownloader(remotes Remotes, maxBytes int64) *Downloader {
+	return &Downloader{remotes: remotes, maxBytes: maxBytes}
+}
+
+func (d *Downloader) Name() string { return "remote" }
+
+func (d *Downloader) Probe(ctx context.Context, req fetcher.Request) error {
+	_, _, err := d.remote(req)
+	return err
+}
+
+// Metadata is empty; the file's own tags are read after the download.
+func (d *Downloader) Metadata(ctx context.Context, req fetcher.Request) (fetcher.Metadata, error) {
+	return fetcher.Metadata{}, nil
+}
+
+func (d *Downloader) Fetch(ctx context.Context, req fetcher.Request, progress fetcher.ProgressFunc) (*fetcher.Result, error) {
+	remote, file, err := d.remote(req)
+	if err != nil {
+		return nil, err
+	}
+	source, err := remote.Connect(ctx)
+	if err != nil {
+		return nil, fmt.Errorf("connect to %s: %w", remote.Name, err)
+	}
+	defer source.Close()
+	body, size, err := source.Open(ctx, file)
+	if err != nil {
+		return nil, err
+	}
+	defer body.Close()
+	return fetcher.Save(body, size, mime.TypeByExtension(path.Ext(file)), file, d.maxBytes, progress)
+}
+
+func (d *Downloader) remote(req fetcher.Request) (Remote, string, error) {
+	name, file, err := ParseSourceURL(req.URL)
+	if err != nil {
+		return Remote{}, "", err
+	}
+	remote, ok := d.remotes[name]
+	if !ok {
+		return Remote{}, "", fmt.Errorf("%w: %q", ErrUnknownRemote, name)
+	}
+	return remote, file, nil
+}
@@ -0,0 +1,168 @@
+package importers
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"path"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// davServer serves files, keyed by path under /dav/, over WebDAV.
+func davServer(t *testing.T, files map[string]string) *httptest.Server {
+	t.Helper()
+	return httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if user, pass, _ := r.BasicAuth(); user != "me" || pass != "secret" {
+			w.WriteHeader(http.StatusUnauthorized)
+			return
+		}
+		rel, ok := strings.CutPrefix(r.URL.Path, "/dav/")
+		if !ok {
+			w.WriteHeader(http.StatusNotFound)
+			return
+		}
+		if r.Method == http.MethodGet {
+			body, ok := files[rel]
+			if !ok {
+				w.WriteHeader(http.StatusNotFound)
+				return
+			}
+			io.WriteString(w, body)
+			return
+		}
+		if r.Method != "PROPFIND" || r.Header.Get("Depth") != "1" {
+			w.WriteHeader(http.StatusMethodNotAllowed)
+			return
+		}
+		dir := strings.TrimSuffix(rel, "/")
+		var b strings.Builder
+		b.WriteString(`<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">`)
+		fmt.Fprintf(&b, `<d:response><d:href>%s</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>`, r.URL.EscapedPath())
+		seen := map[string]bool{}
+		for name, body := range files {
+			parent := path.Dir(name)
+			if parent == "." {
+				parent = ""
+			}
+			child, isDir := name, false
+			for parent != dir && strings.HasPrefix(parent, dir) {
+				child, isDir = parent, true
+				if parent = path.Dir(parent); parent == "." {
+					parent = ""
+				}
+			}
+			if parent != dir || seen[child] {
+				continue
+			}
+			seen[child] = true
+			href := (&url.URL{Path: "/dav/" + child}).EscapedPath()
+			if isDir {
+				fmt.Fprintf(&b, `<d:response><d:href>%s/</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>`, href)
+				continue
+			}
+			fmt.Fprintf(&b, `<d:response><d:href>%s</d:href><d:propstat><d:prop><d:resourcetype/><d:getcontentlength>%d</d:getcontentlength><d:getlastmodified>Mon, 12 Jan 2026 10:00:00 GMT</d:getlastmodified></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>`, href, len(body))
+		}
+		b.WriteString(`</d:multistatus>`)
+		w.WriteHeader(http.StatusMultiStatus)
+		io.WriteString(w, b.String())
+	}))
+}
+
+func davRemote(t *testing.T, server *httptest.Server) Remote {
+	t.Helper()
+	remote, err := ParseRemote("nas", strings.Replace(server.URL, "http://", "webdav://me:secret@", 1)+"/dav")
+	if err != nil {
+		t.Fatalf("ParseRemote() error = %v", err)
+	}
+	return remote
+}
+
+func TestFilterMatchesGlobsAgainstNamesPathsAndFolders(t *testing.T) {
+	filter := Filter{Include: []string{"*.flac", "Live/*"}, Exclude: []string{"Incomplete", "*[Ss]ample*"}}
+	tests := map[string]bool{
+		"Artist/Album/01 Intro.flac":      true,
+		"Artist/Album/02 Song.mp3":        false,
+		"Live/2024/encore.mp3":            true,
+		"Artist/Incomplete/03 Part.flac":  false,
+		"Artist/Album/04 Sample Cut.flac": false,
+		"Artist/Album/cover.jpg":          false,
+	}
+	for rel, want := range tests {
+		if got := filter.Match(rel); got != want {
+			t.Errorf("Match(%q) = %v, want %v", rel, got, want)
+		}
+	}
+	if !(Filter{}).Match("Any/Folder/track.opus") {
+		t.Error("empty filter should take every audio file")
+	}
+	if err := (Filter{Exclude: []string{"[unclosed"}}).Validate(); !errors.Is(err, ErrInvalidFilter) {
+		t.Fatalf("Validate() = %v, want ErrInvalidFilter", err)
+	}
+}
+
+func TestParseRemoteAndSourceURLs(t *testing.T) {
+	if _, err := ParseRemote("box", "ftp://me@host/music"); err == nil {
+		t.Error("ParseRemote accepted ftp")
+	}
+	if _, err := ParseRemote("box", "sftp://host/music"); err == nil {
+		t.Error("ParseRemote accepted sftp without a user")
+	}
+	remote, err := ParseRemote("box", "sftp://me@host:2222/music?key=/keys/id")
+	if err != nil || remote.Protocol() != "sftp" {
+		t.Fatalf("ParseRemote() = %+v, %v", remote, err)
+	}
+
+	raw := SourceURL("box", "Artist/100% Pure #1.flac")
+	name, file, err := ParseSourceURL(raw)
+	if err != nil || name != "box" || file != "Artist/100% Pure #1.flac" {
+		t.Fatalf("ParseSourceURL(%q) = %q, %q, %v", raw, name, file, err)
+	}
+	if got := CleanPath("../../etc/./passwd/"); got != "etc/passwd" {
+		t.Fatalf("CleanPath() = %q, want the path kept under the base", got)
+	}
+}
+
+func TestWebDAVWalksTreeAndOpensFiles(t *testing.T) {
+	server := davServer(t, map[string]string{
+		"Album/01 Intro.flac":       "intro",
+		"Album/Disc 2/02 Song.mp3":  "song",
+		"notes.txt":                 "hello",
+		"Other Album/03 Outro.flac": "outro",
+	})
+	defer server.Close()
+	source, err := davRemote(t, server).Connect(context.Background())
+	if err != nil {
+		t.Fatalf("Connect() error = %v", err)
+	}
+	defer source.Close()
+
+	var files []string
+	err = source.Walk(context.Background(), "Album", func(file RemoteFile) error {
+		files = append(files, fmt.Sprintf("%s:%d", file.Path, file.Size))
+		if file.ModTime.IsZero() {
+			t.Errorf("%s has no modification time", file.Path)
+		}
+		return nil
+	})
+	if err != nil {
+		t.Fatalf("Walk() error = %v", err)
+	}
+	if want := []string{"Album/01 Intro.flac:5", "Album/Disc 2/02 Song.mp3:4"}; !reflect.DeepEqual(files, want) {
+		t.Fatalf("Walk() found %v, want %v", files, want)
+	}
+
+	body, size, err := source.Open(context.Background(), "Album/Disc 2/02 Song.mp3")
+	if err != nil {
+		t.Fatalf("Open() error = %v", err)
+	}
+	defer body.Close()
+	if data, _ := io.ReadAll(body); string(data) != "song" || size != 4 {
+		t.Fatalf("Open() = %q (%d bytes)", data, size)
+	}
+}
@@ -0,0 +1,275 @@
+package importers
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+	"path"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// MaxFilesPerImport bounds how many files one import job takes on; a larger
+// folder needs a narrower path or include globs.
+const MaxFilesPerImport = 10000
+
+// RemoteImport is the job kind that pulls a remote folder into a user's
+// library.
+var RemoteImport = jobs.NewKind[RemoteImportPayload]("remote_import")
+
+type RemoteImportPayload struct {
+	Remote  string   `json:"remote"`
+	Path    string   `json:"path,omitempty"`
+	Include []string `json:"include,omitempty"`
+	Exclude []string `json:"exclude,omitempty"`
+}
+
+// ImportProgress is the progress detail of remote and beets import jobs.
+type ImportProgress struct {
+	Imported int `json:"imported"`
+	Skipped  int `json:"skipped"`
+	Failed   int `json:"failed"`
+}
+
+// RemoteInfo describes a configured remote without its connection details.
+type RemoteInfo struct {
+	Name     string `json:"name"`
+	Protocol string `json:"protocol"`
+}
+
+// Queue queues remote import jobs.
+type Queue struct {
+	store   *jobs.Store
+	remotes Remotes
+}
+
+func NewQueue(store *jobs.Store, remotes Remotes) *Queue {
+	return &Queue{store: store, remotes: remotes}
+}
+
+// Remotes lists the remotes users may import from.
+func (q *Queue) Remotes() []RemoteInfo {
+	infos := make([]RemoteInfo, 0, len(q.remotes))
+	for _, name := range q.remotes.Names() {
+		infos = append(infos, RemoteInfo{Name: name, Protocol: q.remotes[name].Protocol()})
+	}
+	return infos
+}
+
+// Enqueue validates payload and queues it for the user. A user runs at most
+// one import of a folder at a time; a second request while one is queued or
+// running returns jobs.ErrDuplicate.
+func (q *Queue) Enqueue(ctx context.Context, userID uuid.UUID, payload RemoteImportPayload) (*jobs.Job, error) {
+	if _, ok := q.remotes[payload.Remote]; !ok {
+		return nil, fmt.Errorf("%w: %q", ErrUnknownRemote, payload.Remote)
+	}
+	if err := (Filter{Include: payload.Include, Exclude: payload.Exclude}).Validate(); err != nil {
+		return nil, err
+	}
+	payload.Path = CleanPath(payload.Path)
+	// Files already imported are skipped, so a retry resumes where a failed
+	// pass stopped.
+	return RemoteImport.Enqueue(ctx, q.store, payload, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   userID.String() + ":" + payload.Remote + ":/" + payload.Path,
+	})
+}
+
+// TrackSources finds tracks by where they were downloaded from.
+// playlistimport.TrackSourceRepository satisfies this interface.
+type TrackSources interface {
+	FindTrackBySource(ctx context.Context, provider, sourceID, sourceURL string) (*db.Track, error)
+}
+
+// Library adds existing tracks to a user's library. db.LibraryRepository
+// satisfies this interface.
+type Library interface {
+	AddTrackToLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*db.LibraryEntry, error)
+}
+
+// Importer downloads and stores one file. processor.Processor satisfies this
+// interface.
+type Importer interface {
+	Process(ctx context.Context, job *download.DownloadJob, progress func(int)) error
+}
+
+// Admission decides whether downloads may start. *diskspace.Monitor
+// satisfies this interface.
+type Admission interface {
+	Admit(ctx context.Context) error
+}
+
+// progressReporter records a job's progress. *jobs.Progress satisfies it.
+type progressReporter interface {
+	Report(ctx context.Context, current, total int, detail any) error
+}
+
+// Runner walks a remote folder and imports each matching audio file that is
+// not already in the library.
+type Runner struct {
+	remotes   Remotes
+	tracks    trackImporter
+	admission Admission
+}
+
+func NewRunner(remotes Remotes, sources TrackSources, library Library, importer Importer) *Runner {
+	return &Runner{remotes: remotes, tracks: trackImporter{sources: sources, library: library, importer: importer}}
+}
+
+// GuardDownloads stops imports while admission refuses new downloads. The
+// job fails and retries later, skipping the files it already imported.
+func (r *Runner) GuardDownloads(admission Admission) *Runner {
+	r.admission = admission
+	return r
+}
+
+// Register runs remote import jobs on w.
+func (r *Runner) Register(w *jobs.Worker) {
+	RemoteImport.Handle(w, func(ctx context.Context, job *jobs.Job, payload RemoteImportPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, job.UserID.UUID, payload, progress)
+	})
+}
+
+// Run performs one pass of a remote import job.
+func (r *Runner) Run(ctx context.Context, jobID, userID uuid.UUID, payload RemoteImportPayload, progress progressReporter) error {
+	remote, ok := r.remotes[payload.Remote]
+	if !ok {
+		return jobs.Permanent(fmt.Errorf("%w: %q", ErrUnknownRemote, payload.Remote))
+	}
+	filter := Filter{Include: payload.Include, Exclude: payload.Exclude}
+	if err := filter.Validate(); err != nil {
+		return jobs.Permanent(err)
+	}
+	files, err := r.list(ctx, remote, CleanPath(payload.Path), filter)
+	if err != nil {
+		return err
+	}
+
+	var counts ImportProgress
+	if err := progress.Report(ctx, 0, len(files), counts); err != nil {
+		return err
+	}
+	for i, file := range files {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if r.admission != nil {
+			if err := r.admission.Admit(ctx); err != nil {
+				return err
+			}
+		}
+		status, err := r.tracks.importJob(ctx, "Remote import job "+jobID.String(), remoteJob(userID, remote.Name, file))
+		if err != nil {
+			return err
+		}
+		counts.add(status)
+		if err := progress.Report(ctx, i+1, len(files), counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// list returns the files under dir that filter picks, in path order.
+func (r *Runner) list(ctx context.Context, remote Remote, dir string, filter Filter) ([]RemoteFile, error) {
+	source, err := remote.Connect(ctx)
+	if err != nil {
+		return nil, fmt.Errorf("connect to %s: %w", remote.Name, err)
+	}
+	defer source.Close()
+
+	var files []RemoteFile
+	err = source.Walk(ctx, dir, func(file RemoteFile) error {
+		if !filter.Match(file.Path) {
+			return nil
+		}
+		if len(files) == MaxFilesPerImport {
+			return jobs.Permanent(fmt.Errorf("more than %d audio files under /%s; narrow the path or include globs", MaxFilesPerImport, dir))
+		}
+		files = append(files, file)
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	slices.SortFunc(files, func(a, b RemoteFile) int { return strings.Compare(a.Path, b.Path) })
+	return files, nil
+}
+
+// remoteJob is the download job that imports file from the named remote.
+func remoteJob(userID uuid.UUID, remote string, file RemoteFile) *download.DownloadJob {
+	name := path.Base(file.Path)
+	return &download.DownloadJob{
+		ID:         uuid.NewString(),
+		UserID:     userID.String(),
+		URL:        SourceURL(remote, file.Path),
+		SourceType: download.SourceTypeRemote,
+		SourceID:   remote + ":" + file.Path,
+		Title:      strings.TrimSuffix(name, path.Ext(name)),
+		Status:     download.StatusDownloading,
+		CreatedAt:  time.Now(),
+	}
+}
+
+// add counts one import outcome.
+func (p *ImportProgress) add(status string) {
+	switch status {
+	case "imported":
+		p.Imported++
+	case "skipped":
+		p.Skipped++
+	default:
+		p.Failed++
+	}
+}
+
+// trackImporter imports one file at a time for the import runners.
+type trackImporter struct {
+	sources  TrackSources
+	library  Library
+	importer Importer
+}
+
+// importJob runs job and returns the outcome status. A file already
+// downloaded from the same source is only added to the library. Import
+// failures are logged under logPrefix and counted; the returned error is
+// reserved for failures that should stop the job.
+func (t trackImporter) importJob(ctx context.Context, logPrefix string, job *download.DownloadJob) (string, error) {
+	track, err := t.sources.FindTrackBySource(ctx, job.SourceType, job.SourceID, job.URL)
+	switch {
+	case err == nil:
+		userID, err := uuid.Parse(job.UserID)
+		if err != nil {
+			return "", err
+		}
+		_, err = t.library.AddTrackToLibrary(ctx, userID, track.ID)
+		if err == nil || errors.Is(err, db.ErrTrackAlreadyInLibrary) {
+			return "skipped", nil
+		}
+		if !errors.Is(err, db.ErrTrackNotFound) {
+			return "", fmt.Errorf("add track %d to library: %w", track.ID, err)
+		}
+		// Another tenant imported the file; this user gets a copy of their own.
+	case !errors.Is(err, db.ErrTrackNotFound):
+		return "", fmt.Errorf("look up %s: %w", job.URL, err)
+	}
+
+	if err := t.importer.Process(ctx, job, func(int) {}); err != nil {
+		if ctx.Err() != nil {
+			return "", ctx.Err()
+		}
+		log.Printf("%s: failed to import %s: %v", logPrefix, job.URL, err)
+		return "failed", nil
+	}
+	return "imported", nil
+}
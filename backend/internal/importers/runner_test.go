@@ -0,0 +1,97 @@
+package importers
+
+import (
+	"context"
+	"errors"
+	"reflect"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+type fakeProgress struct {
+	reports []ImportProgress
+	current int
+	total   int
+}
+
+func (p *fakeProgress) Report(_ context.Context, current, total int, detail any) error {
+	p.reports = append(p.reports, detail.(ImportProgress))
+	p.current, p.total = current, total
+	return nil
+}
+
+// fakeTrackSources holds track IDs keyed by provider and source URL.
+type fakeTrackSources map[string]int64
+
+func (f fakeTrackSources) FindTrackBySource(_ context.Context, provider, sourceID, sourceURL string) (*db.Track, error) {
+	if id, ok := f[provider+" "+sourceURL]; ok {
+		return &db.Track{ID: id}, nil
+	}
+	return nil, db.ErrTrackNotFound
+}
+
+type fakeLibrary struct{ added []int64 }
+
+func (f *fakeLibrary) AddTrackToLibrary(_ context.Context, _ uuid.UUID, trackID int64) (*db.LibraryEntry, error) {
+	f.added = append(f.added, trackID)
+	return nil, db.ErrTrackAlreadyInLibrary
+}
+
+type fakeImporter struct {
+	jobs []*download.DownloadJob
+	fail string
+}
+
+func (f *fakeImporter) Process(_ context.Context, job *download.DownloadJob, _ func(int)) error {
+	f.jobs = append(f.jobs, job)
+	if job.Title == f.fail {
+		return errors.New("probe failed")
+	}
+	return nil
+}
+
+func TestRunnerImportsMatchingFilesAndSkipsDuplicates(t *testing.T) {
+	server := davServer(t, map[string]string{
+		"Album/01 Intro.flac":        "intro",
+		"Album/02 Song.mp3":          "song",
+		"Album/03 Broken.flac":       "broken",
+		"Album/Incomplete/04.flac":   "partial",
+		"Album/cover.jpg":            "jpeg",
+		"Elsewhere/05 Unwanted.flac": "other",
+	})
+	defer server.Close()
+	remotes := Remotes{"nas": davRemote(t, server)}
+	library := &fakeLibrary{}
+	importer := &fakeImporter{fail: "03 Broken"}
+	sources := fakeTrackSources{"remote " + SourceURL("nas", "Album/01 Intro.flac"): 7}
+	runner := NewRunner(remotes, sources, library, importer)
+
+	progress := &fakeProgress{}
+	payload := RemoteImportPayload{Remote: "nas", Path: "/Album/", Exclude: []string{"Incomplete"}}
+	if err := runner.Run(context.Background(), uuid.New(), uuid.New(), payload, progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+
+	if want := (ImportProgress{Imported: 1, Skipped: 1, Failed: 1}); progress.reports[len(progress.reports)-1] != want || progress.current != 3 || progress.total != 3 {
+		t.Fatalf("progress = %+v (%d/%d), want %+v", progress.reports, progress.current, progress.total, want)
+	}
+	if !reflect.DeepEqual(library.added, []int64{7}) {
+		t.Fatalf("library adds = %v, want the already imported track", library.added)
+	}
+	if len(importer.jobs) != 2 {
+		t.Fatalf("imported %d files, want 2", len(importer.jobs))
+	}
+	job := importer.jobs[0]
+	if job.URL != SourceURL("nas", "Album/02 Song.mp3") || job.SourceType != download.SourceTypeRemote || job.SourceID != "nas:Album/02 Song.mp3" || job.Title != "02 Song" {
+		t.Fatalf("job = %+v", job)
+	}
+
+	err := runner.Run(context.Background(), uuid.New(), uuid.New(), RemoteImportPayload{Remote: "gone"}, progress)
+	if !errors.Is(err, ErrUnknownRemote) {
+		t.Fatalf("Run() for an unknown remote = %v", err)
+	}
+}
@@ -0,0 +1,497 @@
+package importers
+
+import (
+	"context"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+	"sync"
+	"time"
+
+	"golang.org/x/crypto/ssh"
+	"golang.org/x/crypto/ssh/knownhosts"
+)
+
+// SFTP version 3 packet types and constants (draft-ietf-secsh-filexfer-02),
+// the version OpenSSH and every seedbox speak.
+const (
+	sftpInit     = 1
+	sftpVersion  = 2
+	sftpOpen     = 3
+	sftpClose    = 4
+	sftpRead     = 5
+	sftpOpendir  = 11
+	sftpReaddir  = 12
+	sftpStat     = 17
+	sftpStatus   = 101
+	sftpHandle   = 102
+	sftpData     = 103
+	sftpName     = 104
+	sftpAttrs    = 105
+	sftpOpenRead = 1
+
+	sftpStatusOK  = 0
+	sftpStatusEOF = 1
+
+	sftpAttrSize        = 0x1
+	sftpAttrUIDGID      = 0x2
+	sftpAttrPermissions = 0x4
+	sftpAttrACModTime   = 0x8
+	sftpAttrExtended    = 0x80000000
+
+	sftpModeType    = 0o170000
+	sftpModeDir     = 0o040000
+	sftpModeRegular = 0o100000
+
+	// sftpReadSize is what servers reliably return per read request.
+	sftpReadSize = 32 * 1024
+	// maxSFTPPacket bounds a response so a broken server cannot make us
+	// allocate without limit.
+	maxSFTPPacket = 4 * 1024 * 1024
+)
+
+// sftpSource is an SSH connection running the sftp subsystem.
+type sftpSource struct {
+	conn   *ssh.Client
+	client *sftpClient
+	base   string
+	stop   func() bool
+}
+
+// dialSFTP connects to u. The connection is closed when ctx ends.
+func dialSFTP(ctx context.Context, u *url.URL) (*sftpSource, error) {
+	config, err := sshConfig(u)
+	if err != nil {
+		return nil, err
+	}
+	addr := u.Host
+	if u.Port() == "" {
+		addr = net.JoinHostPort(u.Hostname(), "22")
+	}
+	dialer := net.Dialer{Timeout: config.Timeout}
+	netConn, err := dialer.DialContext(ctx, "tcp", addr)
+	if err != nil {
+		return nil, err
+	}
+	netConn.SetDeadline(time.Now().Add(config.Timeout))
+	sshConn, chans, reqs, err := ssh.NewClientConn(netConn, addr, config)
+	if err != nil {
+		netConn.Close()
+		return nil, fmt.Errorf("ssh %s: %w", addr, err)
+	}
+	netConn.SetDeadline(time.Time{})
+	conn := ssh.NewClient(sshConn, chans, reqs)
+
+	client, err := startSFTP(conn)
+	if err != nil {
+		conn.Close()
+		return nil, fmt.Errorf("sftp %s: %w", addr, err)
+	}
+	base := strings.TrimPrefix(u.Path, "/~/")
+	if base == "" || base == "/~" {
+		base = "."
+	}
+	return &sftpSource{
+		conn:   conn,
+		client: client,
+		base:   base,
+		stop:   context.AfterFunc(ctx, func() { conn.Close() }),
+	}, nil
+}
+
+func sshConfig(u *url.URL) (*ssh.ClientConfig, error) {
+	query := u.Query()
+	var auth []ssh.AuthMethod
+	if keyFile := query.Get("key"); keyFile != "" {
+		pem, err := os.ReadFile(keyFile)
+		if err != nil {
+			return nil, fmt.Errorf("read ssh key: %w", err)
+		}
+		signer, err := ssh.ParsePrivateKey(pem)
+		if err != nil {
+			return nil, fmt.Errorf("parse ssh key %s: %w", keyFile, err)
+		}
+		auth = append(auth, ssh.PublicKeys(signer))
+	}
+	if password, ok := u.User.Password(); ok {
+		auth = append(auth, ssh.Password(password))
+	}
+	if len(auth) == 0 {
+		return nil, errors.New("sftp remote needs a password or key")
+	}
+
+	var hostKey ssh.HostKeyCallback
+	if fingerprint := query.Get("host_key"); fingerprint != "" {
+		hostKey = func(hostname string, remote net.Addr, key ssh.PublicKey) error {
+			if got := ssh.FingerprintSHA256(key); got != fingerprint {
+				return fmt.Errorf("host key %s does not match the configured %s", got, fingerprint)
+			}
+			return nil
+		}
+	} else {
+		file := query.Get("known_hosts")
+		if file == "" {
+			home, err := os.UserHomeDir()
+			if err != nil {
+				return nil, fmt.Errorf("locate known_hosts: %w", err)
+			}
+			file = filepath.Join(home, ".ssh", "known_hosts")
+		}
+		var err error
+		if hostKey, err = knownhosts.New(file); err != nil {
+			return nil, fmt.Errorf("load known_hosts: %w", err)
+		}
+	}
+	return &ssh.ClientConfig{
+		User:            u.User.Username(),
+		Auth:            auth,
+		HostKeyCallback: hostKey,
+		Timeout:         30 * time.Second,
+	}, nil
+}
+
+func startSFTP(conn *ssh.Client) (*sftpClient, error) {
+	session, err := conn.NewSession()
+	if err != nil {
+		return nil, err
+	}
+	stdin, err := session.StdinPipe()
+	if err != nil {
+		return nil, err
+	}
+	stdout, err := session.StdoutPipe()
+	if err != nil {
+		return nil, err
+	}
+	if err := session.RequestSubsystem("sftp"); err != nil {
+		return nil, err
+	}
+	return newSFTPClient(stdout, stdin)
+}
+
+func (s *sftpSource) Walk(ctx context.Context, dir string, fn func(RemoteFile) error) error {
+	pending := []string{CleanPath(dir)}
+	for len(pending) > 0 {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		current := pending[len(pending)-1]
+		pending = pending[:len(pending)-1]
+		entries, err := s.client.readDir(path.Join(s.base, current))
+		if err != nil {
+			return fmt.Errorf("list /%s: %w", current, err)
+		}
+		for _, entry := range entries {
+			if entry.name == "." || entry.name == ".." {
+				continue
+			}
+			rel := path.Join(current, entry.name)
+			switch entry.attrs.mode & sftpModeType {
+			case sftpModeDir:
+				pending = append(pending, rel)
+			case sftpModeRegular:
+				if err := fn(RemoteFile{Path: rel, Size: entry.attrs.size, ModTime: entry.attrs.modTime}); err != nil {
+					return err
+				}
+			}
+		}
+	}
+	return nil
+}
+
+func (s *sftpSource) Open(ctx context.Context, file string) (io.ReadCloser, int64, error) {
+	full := path.Join(s.base, CleanPath(file))
+	attrs, err := s.client.stat(full)
+	if err != nil {
+		return nil, 0, fmt.Errorf("stat /%s: %w", file, err)
+	}
+	handle, err := s.client.open(full)
+	if err != nil {
+		return nil, 0, fmt.Errorf("open /%s: %w", file, err)
+	}
+	size := int64(-1)
+	if attrs.flags&sftpAttrSize != 0 {
+		size = attrs.size
+	}
+	return &sftpFile{client: s.client, handle: handle}, size, nil
+}
+
+func (s *sftpSource) Close() error {
+	s.stop()
+	return s.conn.Close()
+}
+
+// sftpClient speaks the client side of SFTP v3, one request at a time.
+type sftpClient struct {
+	mu     sync.Mutex
+	r      io.Reader
+	w      io.Writer
+	nextID uint32
+}
+
+func newSFTPClient(r io.Reader, w io.Writer) (*sftpClient, error) {
+	c := &sftpClient{r: r, w: w}
+	if err := c.writePacket(sftpInit, binary.BigEndian.AppendUint32(nil, 3)); err != nil {
+		return nil, err
+	}
+	typ, payload, err := c.readPacket()
+	if err != nil {
+		return nil, err
+	}
+	if typ != sftpVersion || len(payload) < 4 {
+		return nil, fmt.Errorf("unexpected sftp handshake packet %d", typ)
+	}
+	if version := binary.BigEndian.Uint32(payload); version < 3 {
+		return nil, fmt.Errorf("unsupported sftp version %d", version)
+	}
+	return c, nil
+}
+
+func (c *sftpClient) writePacket(typ byte, payload []byte) error {
+	packet := binary.BigEndian.AppendUint32(nil, uint32(len(payload)+1))
+	packet = append(packet, typ)
+	_, err := c.w.Write(append(packet, payload...))
+	return err
+}
+
+func (c *sftpClient) readPacket() (byte, []byte, error) {
+	var header [5]byte
+	if _, err := io.ReadFull(c.r, header[:]); err != nil {
+		return 0, nil, err
+	}
+	length := binary.BigEndian.Uint32(header[:4])
+	if length < 1 || length > maxSFTPPacket {
+		return 0, nil, fmt.Errorf("sftp packet of %d bytes", length)
+	}
+	payload := make([]byte, length-1)
+	if _, err := io.ReadFull(c.r, payload); err != nil {
+		return 0, nil, err
+	}
+	return header[4], payload, nil
+}
+
+// request sends one request and returns the response type and the payload
+// after the request ID. A non-OK status becomes an error; EOF is io.EOF.
+func (c *sftpClient) request(typ byte, payload []byte) (byte, *sftpBuffer, error) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.nextID++
+	id := c.nextID
+	if err := c.writePacket(typ, append(binary.BigEndian.AppendUint32(nil, id), payload...)); err != nil {
+		return 0, nil, err
+	}
+	respType, respPayload, err := c.readPacket()
+	if err != nil {
+		return 0, nil, err
+	}
+	buf := &sftpBuffer{b: respPayload}
+	if got := buf.uint32(); buf.err != nil || got != id {
+		return 0, nil, fmt.Errorf("sftp response for request %d, want %d", got, id)
+	}
+	if respType == sftpStatus {
+		code, message := buf.uint32(), buf.string()
+		switch {
+		case buf.err != nil:
+			return 0, nil, buf.err
+		case code == sftpStatusOK:
+		case code == sftpStatusEOF:
+			return 0, nil, io.EOF
+		default:
+			return 0, nil, fmt.Errorf("sftp status %d: %s", code, message)
+		}
+	}
+	return respType, buf, nil
+}
+
+func (c *sftpClient) handle(typ byte, payload []byte) (string, error) {
+	respType, buf, err := c.request(typ, payload)
+	if err != nil {
+		return "", err
+	}
+	if respType != sftpHandle {
+		return "", fmt.Errorf("unexpected sftp response %d", respType)
+	}
+	handle := buf.string()
+	return handle, buf.err
+}
+
+func (c *sftpClient) close(handle string) error {
+	_, _, err := c.request(sftpClose, appendSFTPString(nil, handle))
+	return err
+}
+
+type sftpEntry struct {
+	name  string
+	attrs sftpAttributes
+}
+
+func (c *sftpClient) readDir(dir string) ([]sftpEntry, error) {
+	handle, err := c.handle(sftpOpendir, appendSFTPString(nil, dir))
+	if err != nil {
+		return nil, err
+	}
+	defer c.close(handle)
+	var entries []sftpEntry
+	for {
+		respType, buf, err := c.request(sftpReaddir, appendSFTPString(nil, handle))
+		if err == io.EOF {
+			return entries, nil
+		}
+		if err != nil {
+			return nil, err
+		}
+		if respType != sftpName {
+			return nil, fmt.Errorf("unexpected sftp response %d", respType)
+		}
+		for count := buf.uint32(); count > 0 && buf.err == nil; count-- {
+			name := buf.string()
+			buf.string() // long name, ls -l style
+			entries = append(entries, sftpEntry{name: name, attrs: buf.attributes()})
+		}
+		if buf.err != nil {
+			return nil, buf.err
+		}
+	}
+}
+
+func (c *sftpClient) stat(file string) (sftpAttributes, error) {
+	respType, buf, err := c.request(sftpStat, appendSFTPString(nil, file))
+	if err != nil {
+		return sftpAttributes{}, err
+	}
+	if respType != sftpAttrs {
+		return sftpAttributes{}, fmt.Errorf("unexpected sftp response %d", respType)
+	}
+	attrs := buf.attributes()
+	return attrs, buf.err
+}
+
+func (c *sftpClient) open(file string) (string, error) {
+	payload := appendSFTPString(nil, file)
+	payload = binary.BigEndian.AppendUint32(payload, sftpOpenRead)
+	payload = binary.BigEndian.AppendUint32(payload, 0) // no attributes
+	return c.handle(sftpOpen, payload)
+}
+
+// read returns up to n bytes at offset, or io.EOF at the end of the file.
+func (c *sftpClient) read(handle string, offset uint64, n uint32) ([]byte, error) {
+	payload := appendSFTPString(nil, handle)
+	payload = binary.BigEndian.AppendUint64(payload, offset)
+	payload = binary.BigEndian.AppendUint32(payload, n)
+	respType, buf, err := c.request(sftpRead, payload)
+	if err != nil {
+		return nil, err
+	}
+	if respType != sftpData {
+		return nil, fmt.Errorf("unexpected sftp response %d", respType)
+	}
+	data := buf.bytes()
+	return data, buf.err
+}
+
+// sftpFile reads an open remote file sequentially.
+type sftpFile struct {
+	client *sftpClient
+	handle string
+	offset uint64
+}
+
+func (f *sftpFile) Read(p []byte) (int, error) {
+	if len(p) == 0 {
+		return 0, nil
+	}
+	data, err := f.client.read(f.handle, f.offset, uint32(min(len(p), sftpReadSize)))
+	if err != nil {
+		return 0, err
+	}
+	n := copy(p, data)
+	f.offset += uint64(n)
+	return n, nil
+}
+
+func (f *sftpFile) Close() error {
+	return f.client.close(f.handle)
+}
+
+type sftpAttributes struct {
+	flags   uint32
+	size    int64
+	mode    uint32
+	modTime time.Time
+}
+
+// sftpBuffer decodes SFTP fields, remembering the first short read.
+type sftpBuffer struct {
+	b   []byte
+	err error
+}
+
+func (b *sftpBuffer) take(n int) []byte {
+	if b.err != nil || n < 0 || len(b.b) < n {
+		if b.err == nil {
+			b.err = errors.New("short sftp packet")
+		}
+		return nil
+	}
+	out := b.b[:n]
+	b.b = b.b[n:]
+	return out
+}
+
+func (b *sftpBuffer) uint32() uint32 {
+	if v := b.take(4); v != nil {
+		return binary.BigEndian.Uint32(v)
+	}
+	return 0
+}
+
+func (b *sftpBuffer) uint64() uint64 {
+	if v := b.take(8); v != nil {
+		return binary.BigEndian.Uint64(v)
+	}
+	return 0
+}
+
+func (b *sftpBuffer) bytes() []byte {
+	return b.take(int(b.uint32()))
+}
+
+func (b *sftpBuffer) string() string {
+	return string(b.bytes())
+}
+
+func (b *sftpBuffer) attributes() sftpAttributes {
+	attrs := sftpAttributes{flags: b.uint32()}
+	if attrs.flags&sftpAttrSize != 0 {
+		attrs.size = int64(b.uint64())
+	}
+	if attrs.flags&sftpAttrUIDGID != 0 {
+		b.take(8)
+	}
+	if attrs.flags&sftpAttrPermissions != 0 {
+		attrs.mode = b.uint32()
+	}
+	if attrs.flags&sftpAttrACModTime != 0 {
+		b.uint32() // access time
+		attrs.modTime = time.Unix(int64(b.uint32()), 0)
+	}
+	if attrs.flags&sftpAttrExtended != 0 {
+		for count := b.uint32(); count > 0 && b.err == nil; count-- {
+			b.string()
+			b.string()
+		}
+	}
+	return attrs
+}
+
+func appendSFTPString(b []byte, s string) []byte {
+	b = binary.BigEndian.AppendUint32(b, uint32(len(s)))
+	return append(b, s...)
+}
@@ -0,0 +1,155 @@
+package importers
+
+import (
+	"context"
+	"encoding/binary"
+	"io"
+	"path"
+	"reflect"
+	"sort"
+	"strings"
+	"testing"
+	"time"
+)
+
+// serveSFTP answers the client's requests from files, keyed by absolute
+// path, until the client side of the pipe closes.
+func serveSFTP(t *testing.T, r io.Reader, w io.Writer, files map[string]string) {
+	t.Helper()
+	listed := map[string]bool{}
+	attrs := func(b []byte, mode uint32, size int) []byte {
+		b = binary.BigEndian.AppendUint32(b, sftpAttrSize|sftpAttrPermissions|sftpAttrACModTime)
+		b = binary.BigEndian.AppendUint64(b, uint64(size))
+		b = binary.BigEndian.AppendUint32(b, mode)
+		b = binary.BigEndian.AppendUint32(b, 1767225600)
+		return binary.BigEndian.AppendUint32(b, 1767225600)
+	}
+	send := func(typ byte, id uint32, payload []byte) {
+		body := binary.BigEndian.AppendUint32([]byte{typ}, id)
+		packet := binary.BigEndian.AppendUint32(nil, uint32(len(body)+len(payload)))
+		w.Write(append(append(packet, body...), payload...))
+	}
+	status := func(id, code uint32) {
+		send(sftpStatus, id, appendSFTPString(appendSFTPString(binary.BigEndian.AppendUint32(nil, code), "status"), ""))
+	}
+
+	server := &sftpClient{r: r, w: w}
+	typ, _, err := server.readPacket()
+	if err != nil || typ != sftpInit {
+		t.Errorf("handshake packet %d, %v", typ, err)
+		return
+	}
+	w.Write(append(binary.BigEndian.AppendUint32(nil, 5), sftpVersion, 0, 0, 0, 3))
+	for {
+		typ, payload, err := server.readPacket()
+		if err != nil {
+			return
+		}
+		buf := &sftpBuffer{b: payload}
+		id, name := buf.uint32(), buf.string()
+		switch typ {
+		case sftpOpendir, sftpOpen:
+			send(sftpHandle, id, appendSFTPString(nil, name))
+		case sftpClose:
+			status(id, sftpStatusOK)
+		case sftpStat:
+			body, ok := files[name]
+			if !ok {
+				status(id, 2)
+				continue
+			}
+			send(sftpAttrs, id, attrs(nil, sftpModeRegular|0o644, len(body)))
+		case sftpReaddir:
+			if listed[name] {
+				status(id, sftpStatusEOF)
+				continue
+			}
+			listed[name] = true
+			children := map[string]bool{".": true, "..": true, "link": true}
+			for file := range files {
+				if rest, ok := strings.CutPrefix(file, name+"/"); ok {
+					child, _, isDir := strings.Cut(rest, "/")
+					children[child] = children[child] || isDir
+				}
+			}
+			names := make([]string, 0, len(children))
+			for child := range children {
+				names = append(names, child)
+			}
+			sort.Strings(names)
+			entries := binary.BigEndian.AppendUint32(nil, uint32(len(names)))
+			for _, child := range names {
+				mode, size := uint32(sftpModeRegular|0o644), len(files[path.Join(name, child)])
+				switch {
+				case child == "link":
+					mode = 0o120777
+				case children[child]:
+					mode = sftpModeDir | 0o755
+				}
+				entries = appendSFTPString(entries, child)
+				entries = appendSFTPString(entries, "-rw-r--r-- 1 me me "+child)
+				entries = attrs(entries, mode, size)
+			}
+			send(sftpName, id, entries)
+		case sftpRead:
+			offset, n := buf.uint64(), buf.uint32()
+			body := files[name]
+			if offset >= uint64(len(body)) {
+				status(id, sftpStatusEOF)
+				continue
+			}
+			end := min(offset+uint64(n), offset+3, uint64(len(body)))
+			send(sftpData, id, appendSFTPString(nil, body[offset:end]))
+		}
+	}
+}
+
+func TestSFTPClientWalksAndReads(t *testing.T) {
+	files := map[string]string{
+		"/srv/music/Album/01 Intro.flac":      "intro bytes",
+		"/srv/music/Album/Disc 2/02 Song.mp3": "song",
+		"/srv/music/notes.txt":                "hello",
+	}
+	clientR, serverW := io.Pipe()
+	serverR, clientW := io.Pipe()
+	defer clientW.Close()
+	defer serverW.Close()
+	go serveSFTP(t, serverR, serverW, files)
+
+	client, err := newSFTPClient(clientR, clientW)
+	if err != nil {
+		t.Fatalf("newSFTPClient() error = %v", err)
+	}
+	source := &sftpSource{client: client, base: "/srv/music"}
+
+	var found []string
+	err = source.Walk(context.Background(), "", func(file RemoteFile) error {
+		found = append(found, file.Path)
+		if !file.ModTime.Equal(time.Unix(1767225600, 0)) {
+			t.Errorf("%s modified at %v", file.Path, file.ModTime)
+		}
+		return nil
+	})
+	if err != nil {
+		t.Fatalf("Walk() error = %v", err)
+	}
+	sort.Strings(found)
+	if want := []string{"Album/01 Intro.flac", "Album/Disc 2/02 Song.mp3", "notes.txt"}; !reflect.DeepEqual(found, want) {
+		t.Fatalf("Walk() found %v, want %v", found, want)
+	}
+
+	body, size, err := source.Open(context.Background(), "Album/01 Intro.flac")
+	if err != nil {
+		t.Fatalf("Open() error = %v", err)
+	}
+	data, err := io.ReadAll(body)
+	if err != nil || string(data) != "intro bytes" || size != int64(len("intro bytes")) {
+		t.Fatalf("read %q (%d bytes), %v", data, size, err)
+	}
+	if err := body.Close(); err != nil {
+		t.Fatalf("Close() error = %v", err)
+	}
+	if _, _, err := source.Open(context.Background(), "missing.flac"); err == nil || !strings.Contains(err.Error(), "sftp status 2") {
+		t.Fatalf("Open() of a missing file = %v", err)
+	}
+}
@@ -0,0 +1,194 @@
+package importers
+
+import (
+	"context"
+	"encoding/xml"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"path"
+	"strings"
+	"time"
+)
+
+// maxPROPFINDBytes bounds one directory listing response.
+const maxPROPFINDBytes = 32 * 1024 * 1024
+
+const propfindBody = `<?xml version="1.0" encoding="utf-8"?>
+<propfind xmlns="DAV:"><prop><resourcetype/><getcontentlength/><getlastmodified/></prop></propfind>`
+
+// webDAV lists a WebDAV share one directory at a time with PROPFIND, since
+// many servers refuse Depth: infinity.
+type webDAV struct {
+	client   *http.Client
+	base     *url.URL
+	user     string
+	password string
+}
+
+// newWebDAV connects to the share at u. A nil client uses one that only
+// bounds the wait for response headers, so large files can stream.
+func newWebDAV(u *url.URL, client *http.Client) *webDAV {
+	if client == nil {
+		transport := http.DefaultTransport.(*http.Transport).Clone()
+		transport.ResponseHeaderTimeout = time.Minute
+		client = &http.Client{Transport: transport}
+	}
+	base := *u
+	switch base.Scheme {
+	case "webdav":
+		base.Scheme = "http"
+	case "webdavs":
+		base.Scheme = "https"
+	}
+	password, _ := base.User.Password()
+	d := &webDAV{client: client, user: base.User.Username(), password: password}
+	base.User = nil
+	base.Path = strings.TrimSuffix(base.Path, "/") + "/"
+	base.RawPath = ""
+	d.base = &base
+	return d
+}
+
+type davMultistatus struct {
+	Responses []struct {
+		Href     string `xml:"DAV: href"`
+		Propstat []struct {
+			Status string `xml:"DAV: status"`
+			Prop   struct {
+				ResourceType struct {
+					Collection *struct{} `xml:"DAV: collection"`
+				} `xml:"DAV: resourcetype"`
+				ContentLength int64  `xml:"DAV: getcontentlength"`
+				LastModified  string `xml:"DAV: getlastmodified"`
+			} `xml:"DAV: prop"`
+		} `xml:"DAV: propstat"`
+	} `xml:"DAV: response"`
+}
+
+func (d *webDAV) Walk(ctx context.Context, dir string, fn func(RemoteFile) error) error {
+	pending := []string{CleanPath(dir)}
+	visited := map[string]bool{pending[0]: true}
+	for len(pending) > 0 {
+		current := pending[len(pending)-1]
+		pending = pending[:len(pending)-1]
+		entries, err := d.propfind(ctx, current)
+		if err != nil {
+			return err
+		}
+		for _, entry := range entries {
+			if entry.dir {
+				if !visited[entry.file.Path] {
+					visited[entry.file.Path] = true
+					pending = append(pending, entry.file.Path)
+				}
+				continue
+			}
+			if err := fn(entry.file); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+type davEntry struct {
+	file RemoteFile
+	dir  bool
+}
+
+// propfind lists the direct children of dir.
+func (d *webDAV) propfind(ctx context.Context, dir string) ([]davEntry, error) {
+	target := d.url(dir)
+	if !strings.HasSuffix(target, "/") {
+		target += "/"
+	}
+	req, err := http.NewRequestWithContext(ctx, "PROPFIND", target, strings.NewReader(propfindBody))
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Set("Depth", "1")
+	req.Header.Set("Content-Type", `application/xml; charset="utf-8"`)
+	resp, err := d.do(req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusMultiStatus {
+		return nil, fmt.Errorf("PROPFIND /%s: %s", dir, resp.Status)
+	}
+	var status davMultistatus
+	if err := xml.NewDecoder(io.LimitReader(resp.Body, maxPROPFINDBytes)).Decode(&status); err != nil {
+		return nil, fmt.Errorf("PROPFIND /%s: decode: %w", dir, err)
+	}
+
+	var entries []davEntry
+	for _, response := range status.Responses {
+		rel, ok := d.relative(response.Href)
+		if !ok || rel == dir {
+			continue
+		}
+		for _, propstat := range response.Propstat {
+			if !strings.Contains(propstat.Status, " 200") {
+				continue
+			}
+			prop := propstat.Prop
+			modTime, _ := http.ParseTime(prop.LastModified)
+			entries = append(entries, davEntry{
+				file: RemoteFile{Path: rel, Size: prop.ContentLength, ModTime: modTime},
+				dir:  prop.ResourceType.Collection != nil,
+			})
+			break
+		}
+	}
+	return entries, nil
+}
+
+// relative maps an href from a listing, which may be a full URL or an
+// absolute path, to a path under the share. Hrefs outside it are dropped.
+func (d *webDAV) relative(href string) (string, bool) {
+	u, err := url.Parse(strings.TrimSpace(href))
+	if err != nil {
+		return "", false
+	}
+	rel, ok := strings.CutPrefix(path.Clean(u.Path)+"/", d.base.Path)
+	if !ok {
+		return "", false
+	}
+	return strings.TrimSuffix(rel, "/"), true
+}
+
+func (d *webDAV) Open(ctx context.Context, file string) (io.ReadCloser, int64, error) {
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, d.url(file), nil)
+	if err != nil {
+		return nil, 0, err
+	}
+	resp, err := d.do(req)
+	if err != nil {
+		return nil, 0, err
+	}
+	if resp.StatusCode != http.StatusOK {
+		resp.Body.Close()
+		return nil, 0, fmt.Errorf("GET /%s: %s", file, resp.Status)
+	}
+	return resp.Body, resp.ContentLength, nil
+}
+
+func (d *webDAV) Close() error {
+	d.client.CloseIdleConnections()
+	return nil
+}
+
+func (d *webDAV) url(file string) string {
+	u := *d.base
+	u.Path += CleanPath(file)
+	return u.String()
+}
+
+func (d *webDAV) do(req *http.Request) (*http.Response, error) {
+	if d.user != "" {
+		req.SetBasicAuth(d.user, d.password)
+	}
+	return d.client.Do(req)
+}
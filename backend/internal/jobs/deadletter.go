@@ -0,0 +1,201 @@
+package jobs
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// Failure categories group dead-lettered jobs by cause for triage and metrics.
+const (
+	FailureNetwork         = "network"
+	FailureProviderBlocked = "provider_blocked"
+	FailureDiskFull        = "disk_full"
+	FailureOther           = "other"
+)
+
+// FailureCategories lists every failure category.
+var FailureCategories = []string{FailureNetwork, FailureProviderBlocked, FailureDiskFull, FailureOther}
+
+// ErrNotRetryable is returned when a dead-lettered job is no longer failed,
+// such as one already retried some other way.
+var ErrNotRetryable = errors.New("job is no longer failed")
+
+// failureMarkers are the lowercased error fragments that identify each
+// category, checked in order.
+var failureMarkers = []struct {
+	category string
+	markers  []string
+}{
+	{FailureDiskFull, []string{"no space left on device", "disk full", "disk quota exceeded", "enospc", "free disk space"}},
+	{FailureProviderBlocked, []string{
+		"http error 403", "403 forbidden", "http error 429", "429 too many requests", "too many requests",
+		"sign in to confirm", "confirm you're not a bot", "rate limit", "blocked", "not available in your country",
+		"geo restricted",
+	}},
+	{FailureNetwork, []string{
+		"connection refused", "connection reset", "connection timed out", "i/o timeout", "timeout",
+		"no such host", "network is unreachable", "tls handshake", "broken pipe", "unexpected eof",
+		"temporary failure in name resolution", "deadline exceeded",
+	}},
+}
+
+// ClassifyFailure sorts a failure into a category from its error message.
+func ClassifyFailure(message string) string {
+	message = strings.ToLower(message)
+	for _, group := range failureMarkers {
+		for _, marker := range group.markers {
+			if strings.Contains(message, marker) {
+				return group.category
+			}
+		}
+	}
+	return FailureOther
+}
+
+// DeadLetter is a job of any kind that failed for good, kept with its error
+// context until it is retried or discarded.
+type DeadLetter struct {
+	ID       int64
+	Kind     string
+	JobID    string
+	UserID   string
+	Error    string
+	Category string
+	Attempts int
+	// Context carries kind-specific details, such as the job's payload or the
+	// source a download was fetching.
+	Context  json.RawMessage
+	FailedAt time.Time
+}
+
+// DeadLetterFilter selects dead letters. Empty fields match everything.
+type DeadLetterFilter struct {
+	IDs      []int64
+	Kind     string
+	Category string
+	Limit    int
+}
+
+// Empty reports whether the filter selects every dead letter.
+func (f DeadLetterFilter) Empty() bool {
+	return len(f.IDs) == 0 && f.Kind == "" && f.Category == ""
+}
+
+// Retrier queues a dead-lettered job again with a fresh retry budget.
+// Retriers return ErrNotFound for kinds they do not run, so DeadLetters can
+// ask each in turn.
+type Retrier interface {
+	RetryDeadLetter(ctx context.Context, letter DeadLetter) error
+}
+
+// deadLetterStore is the persistence DeadLetters needs. *Store satisfies it.
+type deadLetterStore interface {
+	ListDeadLetters(ctx context.Context, filter DeadLetterFilter) ([]DeadLetter, error)
+	DeleteDeadLetters(ctx context.Context, ids []int64) (int64, error)
+	DeadLetterCounts(ctx context.Context) (map[string]int, error)
+}
+
+// deadLetterBatchLimit bounds how many dead letters one bulk action touches.
+const deadLetterBatchLimit = 500
+
+// DeadLetters triages failed jobs of every kind: listing them, retrying them
+// through the retrier that runs their kind, and discarding them.
+type DeadLetters struct {
+	store    deadLetterStore
+	retriers []Retrier
+}
+
+func NewDeadLetters(store *Store, retriers ...Retrier) *DeadLetters {
+	return newDeadLetters(store, retriers...)
+}
+
+func newDeadLetters(store deadLetterStore, retriers ...Retrier) *DeadLetters {
+	return &DeadLetters{store: store, retriers: retriers}
+}
+
+// RetryOutcome reports a bulk retry. Dead letters that could not be retried
+// stay listed and are returned in Failed with the reason.
+type RetryOutcome struct {
+	Retried []int64
+	Failed  map[int64]string
+}
+
+// List returns the dead letters matching filter, most recent first.
+func (d *DeadLetters) List(ctx context.Context, filter DeadLetterFilter) ([]DeadLetter, error) {
+	if filter.Limit <= 0 || filter.Limit > deadLetterBatchLimit {
+		filter.Limit = deadLetterBatchLimit
+	}
+	return d.store.ListDeadLetters(ctx, filter)
+}
+
+// Counts returns the number of dead letters in each failure category.
+func (d *DeadLetters) Counts(ctx context.Context) (map[string]int, error) {
+	return d.store.DeadLetterCounts(ctx)
+}
+
+// Retry queues the jobs of the dead letters matching filter again and removes
+// their dead letters. A job that fails again is dead-lettered anew.
+func (d *DeadLetters) Retry(ctx context.Context, filter DeadLetterFilter) (*RetryOutcome, error) {
+	letters, err := d.List(ctx, filter)
+	if err != nil {
+		return nil, err
+	}
+	outcome := &RetryOutcome{Failed: map[int64]string{}}
+	for _, letter := range letters {
+		if err := d.retry(ctx, letter); err != nil {
+			outcome.Failed[letter.ID] = err.Error()
+			continue
+		}
+		outcome.Retried = append(outcome.Retried, letter.ID)
+	}
+	if len(outcome.Retried) > 0 {
+		if _, err := d.store.DeleteDeadLetters(ctx, outcome.Retried); err != nil {
+			return nil, err
+		}
+	}
+	return outcome, nil
+}
+
+func (d *DeadLetters) retry(ctx context.Context, letter DeadLetter) error {
+	for _, retrier := range d.retriers {
+		err := retrier.RetryDeadLetter(ctx, letter)
+		if !errors.Is(err, ErrNotFound) {
+			return err
+		}
+	}
+	return ErrNotFound
+}
+
+// Discard removes the dead letters matching filter without retrying their
+// jobs, which stay failed.
+func (d *DeadLetters) Discard(ctx context.Context, filter DeadLetterFilter) (int64, error) {
+	letters, err := d.List(ctx, filter)
+	if err != nil {
+		return 0, err
+	}
+	if len(letters) == 0 {
+		return 0, nil
+	}
+	ids := make([]int64, 0, len(letters))
+	for _, letter := range letters {
+		ids = append(ids, letter.ID)
+	}
+	return d.store.DeleteDeadLetters(ctx, ids)
+}
+
+// RetryDeadLetter implements Retrier for the kinds registered on the worker.
+func (w *Worker) RetryDeadLetter(ctx context.Context, letter DeadLetter) error {
+	if _, ok := w.handlers[letter.Kind]; !ok {
+		return ErrNotFound
+	}
+	id, err := uuid.Parse(letter.JobID)
+	if err != nil {
+		return ErrNotRetryable
+	}
+	return w.store.Requeue(ctx, id)
+}
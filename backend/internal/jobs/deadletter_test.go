@@ -0,0 +1,109 @@
+package jobs
+
+import (
+	"context"
+	"reflect"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+func TestClassifyFailure(t *testing.T) {
+	tests := map[string]string{
+		"write /data/audio.tmp: no space left on device":                    FailureDiskFull,
+		"yt-dlp: ERROR: [youtube] abc: Sign in to confirm you're not a bot": FailureProviderBlocked,
+		"yt-dlp: ERROR: unable to download webpage: HTTP Error 429":         FailureProviderBlocked,
+		"not enough free disk space: 12 MiB free on /tmp":                   FailureDiskFull,
+		"dial tcp: lookup storage: no such host":                            FailureNetwork,
+		"read tcp 10.0.0.2:443: i/o timeout":                                FailureNetwork,
+		"ffmpeg exited with status 1":                                       FailureOther,
+	}
+	for message, want := range tests {
+		if got := ClassifyFailure(message); got != want {
+			t.Errorf("ClassifyFailure(%q) = %q, want %q", message, got, want)
+		}
+	}
+}
+
+type fakeDeadLetterStore struct {
+	letters []DeadLetter
+	deleted []int64
+}
+
+func (s *fakeDeadLetterStore) ListDeadLetters(_ context.Context, filter DeadLetterFilter) ([]DeadLetter, error) {
+	var matched []DeadLetter
+	for _, letter := range s.letters {
+		if filter.Category != "" && letter.Category != filter.Category {
+			continue
+		}
+		if filter.Kind != "" && letter.Kind != filter.Kind {
+			continue
+		}
+		matched = append(matched, letter)
+	}
+	return matched, nil
+}
+
+func (s *fakeDeadLetterStore) DeleteDeadLetters(_ context.Context, ids []int64) (int64, error) {
+	s.deleted = append(s.deleted, ids...)
+	return int64(len(ids)), nil
+}
+
+func (s *fakeDeadLetterStore) DeadLetterCounts(context.Context) (map[string]int, error) {
+	counts := map[string]int{}
+	for _, letter := range s.letters {
+		counts[letter.Category]++
+	}
+	return counts, nil
+}
+
+type fakeRetrier struct {
+	kind string
+	err  error
+}
+
+func (r fakeRetrier) RetryDeadLetter(_ context.Context, letter DeadLetter) error {
+	if letter.Kind != r.kind {
+		return ErrNotFound
+	}
+	return r.err
+}
+
+func TestDeadLettersRetryRoutesEachKindAndKeepsFailures(t *testing.T) {
+	jobID := uuid.New()
+	letters := &fakeDeadLetterStore{letters: []DeadLetter{
+		{ID: 1, Kind: testKind.Name, JobID: jobID.String(), Category: FailureNetwork},
+		{ID: 2, Kind: "download", JobID: "dl-1", Category: FailureNetwork},
+		{ID: 3, Kind: "transcode", JobID: "tc-1", Category: FailureNetwork},
+		{ID: 4, Kind: "download", JobID: "dl-2", Category: FailureDiskFull},
+	}}
+	store := &fakeStore{}
+	w := newWorker(store, WorkerConfig{})
+	testKind.Handle(w, func(context.Context, *Job, testPayload, *Progress) error { return nil })
+	downloads := fakeRetrier{kind: "download", err: ErrNotRetryable}
+
+	outcome, err := newDeadLetters(letters, w, downloads).Retry(context.Background(), DeadLetterFilter{Category: FailureNetwork})
+	if err != nil {
+		t.Fatalf("Retry() error = %v", err)
+	}
+	if !reflect.DeepEqual(outcome.Retried, []int64{1}) || !reflect.DeepEqual(letters.deleted, []int64{1}) {
+		t.Fatalf("retried %v, deleted %v; want only dead letter 1", outcome.Retried, letters.deleted)
+	}
+	if len(store.requeued) != 1 || store.requeued[0] != jobID {
+		t.Fatalf("requeued = %v, want %s", store.requeued, jobID)
+	}
+	if outcome.Failed[2] != ErrNotRetryable.Error() || outcome.Failed[3] != ErrNotFound.Error() || len(outcome.Failed) != 2 {
+		t.Fatalf("failed = %v, want dead letters 2 and 3 with reasons", outcome.Failed)
+	}
+}
+
+func TestDeadLettersDiscardDeletesMatchingLetters(t *testing.T) {
+	letters := &fakeDeadLetterStore{letters: []DeadLetter{
+		{ID: 1, Kind: "download", Category: FailureProviderBlocked},
+		{ID: 2, Kind: "download", Category: FailureDiskFull},
+	}}
+	discarded, err := newDeadLetters(letters).Discard(context.Background(), DeadLetterFilter{Category: FailureProviderBlocked})
+	if err != nil || discarded != 1 || !reflect.DeepEqual(letters.deleted, []int64{1}) {
+		t.Fatalf("Discard() = %d, %v; deleted %v", discarded, err, letters.deleted)
+	}
+}
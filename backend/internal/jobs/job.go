@@ -0,0 +1,139 @@
+// Package jobs runs durable background work. Each job has a kind with a typed
+// JSON payload, a priority, a retry budget, reported progress, and can be
+// canceled by the user who queued it.
+package jobs
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// Job states. Queued jobs become running when a worker claims them; a failed
+// attempt with retries left goes back to queued.
+const (
+	StatusQueued    = "queued"
+	StatusRunning   = "running"
+	StatusSucceeded = "succeeded"
+	StatusFailed    = "failed"
+	StatusCanceled  = "canceled"
+)
+
+// Priorities order queued work; higher runs first.
+const (
+	PriorityLow     = -10
+	PriorityDefault = 0
+	PriorityHigh    = 10
+)
+
+// DefaultMaxAttempts is the retry budget of a job enqueued without one.
+const DefaultMaxAttempts = 3
+
+var (
+	ErrNotFound      = errors.New("job not found")
+	ErrDuplicate     = errors.New("job with this unique key is already queued or running")
+	ErrNotCancelable = errors.New("job can no longer be canceled")
+	// ErrCanceled is returned by Progress.Report once the job's cancellation
+	// has been requested. Handlers should stop and return it.
+	ErrCanceled = errors.New("job canceled")
+)
+
+// Job is one unit of background work.
+type Job struct {
+	ID       uuid.UUID
+	Kind     string
+	UserID   uuid.NullUUID
+	Payload  json.RawMessage
+	Status   string
+	Priority int
+	// Attempts counts the times a worker has claimed the job.
+	Attempts    int
+	MaxAttempts int
+	RunAfter    time.Time
+	// ProgressCurrent and ProgressTotal count the job's work items; Detail
+	// carries kind-specific progress such as outcome counts.
+	ProgressCurrent int
+	ProgressTotal   int
+	ProgressDetail  json.RawMessage
+	Error           sql.NullString
+	CancelRequested bool
+	CreatedAt       time.Time
+	StartedAt       sql.NullTime
+	FinishedAt      sql.NullTime
+	UpdatedAt       time.Time
+}
+
+// Terminal reports whether the job has finished for good.
+func (j *Job) Terminal() bool {
+	return j.Status == StatusSucceeded || j.Status == StatusFailed || j.Status == StatusCanceled
+}
+
+// Options tune how a job is queued.
+type Options struct {
+	// UserID owns the job; system jobs leave it unset.
+	UserID uuid.NullUUID
+	// Priority defaults to PriorityDefault.
+	Priority int
+	// MaxAttempts defaults to DefaultMaxAttempts.
+	MaxAttempts int
+	// UniqueKey, when set, allows one queued or running job per kind and key.
+	UniqueKey string
+	// RunAfter delays the first attempt.
+	RunAfter time.Time
+}
+
+// Kind names a job type and fixes its payload type.
+type Kind[P any] struct {
+	Name string
+}
+
+func NewKind[P any](name string) Kind[P] {
+	return Kind[P]{Name: name}
+}
+
+// HandlerFunc runs one attempt of a job of kind P.
+type HandlerFunc[P any] func(ctx context.Context, job *Job, payload P, progress *Progress) error
+
+// Enqueue queues a job of this kind.
+func (k Kind[P]) Enqueue(ctx context.Context, store *Store, payload P, opts Options) (*Job, error) {
+	data, err := json.Marshal(payload)
+	if err != nil {
+		return nil, err
+	}
+	return store.Enqueue(ctx, k.Name, data, opts)
+}
+
+// Handle registers fn to run this kind's jobs on w.
+func (k Kind[P]) Handle(w *Worker, fn HandlerFunc[P]) {
+	w.register(k.Name, func(ctx context.Context, job *Job, progress *Progress) error {
+		var payload P
+		if err := json.Unmarshal(job.Payload, &payload); err != nil {
+			return Permanent(err)
+		}
+		return fn(ctx, job, payload, progress)
+	})
+}
+
+type permanentError struct {
+	err error
+}
+
+func (e permanentError) Error() string { return e.err.Error() }
+func (e permanentError) Unwrap() error { return e.err }
+
+// Permanent marks err as not worth retrying; the job fails immediately.
+func Permanent(err error) error {
+	if err == nil {
+		return nil
+	}
+	return permanentError{err: err}
+}
+
+func isPermanent(err error) bool {
+	var permanent permanentError
+	return errors.As(err, &permanent)
+}
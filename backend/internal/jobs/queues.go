@@ -0,0 +1,124 @@
+package jobs
+
+import (
+	"context"
+	"errors"
+	"sort"
+	"time"
+)
+
+// QueueState reports whether one queue of jobs is starting new work.
+type QueueState struct {
+	Name     string
+	Paused   bool
+	Reason   string
+	PausedAt *time.Time
+}
+
+// QueueControl pauses and resumes the queues behind one family of jobs.
+// Pausing a queue stops new jobs from starting; running jobs finish. Controls
+// return ErrNotFound for queues they do not own.
+type QueueControl interface {
+	Queues(ctx context.Context) ([]QueueState, error)
+	PauseQueue(ctx context.Context, name, reason string) (*QueueState, error)
+	ResumeQueue(ctx context.Context, name string) (*QueueState, error)
+}
+
+// Controls merges queue controls behind one view, for pausing work during
+// maintenance windows.
+type Controls struct {
+	controls []QueueControl
+}
+
+func NewControls(controls ...QueueControl) *Controls {
+	return &Controls{controls: controls}
+}
+
+// Queues returns every queue's state, ordered by name.
+func (c *Controls) Queues(ctx context.Context) ([]QueueState, error) {
+	var all []QueueState
+	for _, control := range c.controls {
+		queues, err := control.Queues(ctx)
+		if err != nil {
+			return nil, err
+		}
+		all = append(all, queues...)
+	}
+	sort.Slice(all, func(i, j int) bool { return all[i].Name < all[j].Name })
+	return all, nil
+}
+
+// Pause pauses the named queue through whichever control owns it.
+func (c *Controls) Pause(ctx context.Context, name, reason string) (*QueueState, error) {
+	for _, control := range c.controls {
+		state, err := control.PauseQueue(ctx, name, reason)
+		if !errors.Is(err, ErrNotFound) {
+			return state, err
+		}
+	}
+	return nil, ErrNotFound
+}
+
+// Resume resumes the named queue through whichever control owns it.
+func (c *Controls) Resume(ctx context.Context, name string) (*QueueState, error) {
+	for _, control := range c.controls {
+		state, err := control.ResumeQueue(ctx, name)
+		if !errors.Is(err, ErrNotFound) {
+			return state, err
+		}
+	}
+	return nil, ErrNotFound
+}
+
+// Queues implements QueueControl with one queue per kind registered on the
+// worker. Pauses are stored with the jobs, so they hold across restarts and
+// for every worker.
+func (w *Worker) Queues(ctx context.Context) ([]QueueState, error) {
+	paused, err := w.store.PausedKinds(ctx)
+	if err != nil {
+		return nil, err
+	}
+	kinds := w.kinds()
+	states := make([]QueueState, 0, len(kinds))
+	for _, kind := range kinds {
+		state, ok := paused[kind]
+		if !ok {
+			state = QueueState{Name: kind}
+		}
+		states = append(states, state)
+	}
+	return states, nil
+}
+
+// PauseQueue implements QueueControl.
+func (w *Worker) PauseQueue(ctx context.Context, name, reason string) (*QueueState, error) {
+	if _, ok := w.handlers[name]; !ok {
+		return nil, ErrNotFound
+	}
+	if err := w.store.PauseKind(ctx, name, reason); err != nil {
+		return nil, err
+	}
+	return w.queueState(ctx, name)
+}
+
+// ResumeQueue implements QueueControl.
+func (w *Worker) ResumeQueue(ctx context.Context, name string) (*QueueState, error) {
+	if _, ok := w.handlers[name]; !ok {
+		return nil, ErrNotFound
+	}
+	if err := w.store.ResumeKind(ctx, name); err != nil {
+		return nil, err
+	}
+	return w.queueState(ctx, name)
+}
+
+func (w *Worker) queueState(ctx context.Context, kind string) (*QueueState, error) {
+	paused, err := w.store.PausedKinds(ctx)
+	if err != nil {
+		return nil, err
+	}
+	if state, ok := paused[kind]; ok {
+		return &state, nil
+	}
+	return &QueueState{Name: kind}, nil
+}
@@ -0,0 +1,167 @@
+package jobs
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"sort"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+// Summary is one job of any kind, including jobs run outside this package
+// such as downloads, in the shape the unified status API reports.
+type Summary struct {
+	ID              string
+	Kind            string
+	Status          string
+	Priority        int
+	Attempts        int
+	MaxAttempts     int
+	ProgressCurrent int
+	ProgressTotal   int
+	ProgressDetail  json.RawMessage
+	Error           string
+	FailureCategory string
+	Remediation     string
+	Cancelable      bool
+	CreatedAt       time.Time
+	UpdatedAt       time.Time
+	StartedAt       *time.Time
+	FinishedAt      *time.Time
+}
+
+// Source lists and cancels one family of a user's jobs. Sources return
+// ErrNotFound for IDs they do not own, so a Directory can ask each in turn.
+type Source interface {
+	ListJobs(ctx context.Context, userID uuid.UUID) ([]Summary, error)
+	GetJob(ctx context.Context, userID uuid.UUID, id string) (*Summary, error)
+	CancelJob(ctx context.Context, userID uuid.UUID, id string) (*Summary, error)
+}
+
+// Directory merges job sources behind one status view.
+type Directory struct {
+	sources []Source
+}
+
+func NewDirectory(sources ...Source) *Directory {
+	directory := &Directory{}
+	for _, source := range sources {
+		if source != nil {
+			directory.sources = append(directory.sources, source)
+		}
+	}
+	return directory
+}
+
+// List returns the user's jobs from every source, newest first.
+func (d *Directory) List(ctx context.Context, userID uuid.UUID) ([]Summary, error) {
+	var all []Summary
+	for _, source := range d.sources {
+		jobs, err := source.ListJobs(ctx, userID)
+		if err != nil {
+			return nil, err
+		}
+		all = append(all, jobs...)
+	}
+	sort.SliceStable(all, func(i, j int) bool {
+		if !all[i].CreatedAt.Equal(all[j].CreatedAt) {
+			return all[i].CreatedAt.After(all[j].CreatedAt)
+		}
+		return all[i].ID < all[j].ID
+	})
+	return all, nil
+}
+
+// Get returns one of the user's jobs from whichever source owns it.
+func (d *Directory) Get(ctx context.Context, userID uuid.UUID, id string) (*Summary, error) {
+	for _, source := range d.sources {
+		summary, err := source.GetJob(ctx, userID, id)
+		if !errors.Is(err, ErrNotFound) {
+			return summary, err
+		}
+	}
+	return nil, ErrNotFound
+}
+
+// Cancel cancels one of the user's jobs through whichever source owns it.
+func (d *Directory) Cancel(ctx context.Context, userID uuid.UUID, id string) (*Summary, error) {
+	for _, source := range d.sources {
+		summary, err := source.CancelJob(ctx, userID, id)
+		if !errors.Is(err, ErrNotFound) {
+			return summary, err
+		}
+	}
+	return nil, ErrNotFound
+}
+
+// storeListLimit bounds how many of a user's jobs the store source lists.
+const storeListLimit = 100
+
+// ListJobs implements Source for jobs run by this package.
+func (s *Store) ListJobs(ctx context.Context, userID uuid.UUID) ([]Summary, error) {
+	jobs, err := s.ListForUser(ctx, userID, storeListLimit)
+	if err != nil {
+		return nil, err
+	}
+	summaries := make([]Summary, 0, len(jobs))
+	for i := range jobs {
+		summaries = append(summaries, jobs[i].Summary())
+	}
+	return summaries, nil
+}
+
+// GetJob implements Source for jobs run by this package.
+func (s *Store) GetJob(ctx context.Context, userID uuid.UUID, id string) (*Summary, error) {
+	jobID, err := uuid.Parse(id)
+	if err != nil {
+		return nil, ErrNotFound
+	}
+	job, err := s.Get(ctx, userID, jobID)
+	if err != nil {
+		return nil, err
+	}
+	summary := job.Summary()
+	return &summary, nil
+}
+
+// CancelJob implements Source for jobs run by this package.
+func (s *Store) CancelJob(ctx context.Context, userID uuid.UUID, id string) (*Summary, error) {
+	jobID, err := uuid.Parse(id)
+	if err != nil {
+		return nil, ErrNotFound
+	}
+	job, err := s.Cancel(ctx, userID, jobID)
+	if err != nil {
+		return nil, err
+	}
+	summary := job.Summary()
+	return &summary, nil
+}
+
+// Summary describes the job for the unified status API.
+func (j *Job) Summary() Summary {
+	summary := Summary{
+		ID:              j.ID.String(),
+		Kind:            j.Kind,
+		Status:          j.Status,
+		Priority:        j.Priority,
+		Attempts:        j.Attempts,
+		MaxAttempts:     j.MaxAttempts,
+		ProgressCurrent: j.ProgressCurrent,
+		ProgressTotal:   j.ProgressTotal,
+		ProgressDetail:  j.ProgressDetail,
+		Error:           j.Error.String,
+		Cancelable:      !j.Terminal() && !j.CancelRequested,
+		CreatedAt:       j.CreatedAt,
+		UpdatedAt:       j.UpdatedAt,
+	}
+	if j.StartedAt.Valid {
+		summary.StartedAt = &j.StartedAt.Time
+	}
+	if j.FinishedAt.Valid {
+		summary.FinishedAt = &j.FinishedAt.Time
+	}
+	return summary
+}
@@ -0,0 +1,409 @@
+package jobs
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"time"
+
+	"github.com/google/uuid"
+	"github.com/lib/pq"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// Store persists jobs in Postgres.
+type Store struct {
+	db *db.DB
+}
+
+func NewStore(database *db.DB) *Store {
+	return &Store{db: database}
+}
+
+const jobColumns = `id, kind, user_id, payload, status, priority, attempts, max_attempts, run_after,
+	progress_current, progress_total, progress_detail, error, cancel_requested,
+	created_at, started_at, finished_at, updated_at`
+
+func scanJob(row interface{ Scan(...any) error }) (*Job, error) {
+	var job Job
+	var detail []byte
+	err := row.Scan(&job.ID, &job.Kind, &job.UserID, &job.Payload, &job.Status, &job.Priority, &job.Attempts, &job.MaxAttempts, &job.RunAfter,
+		&job.ProgressCurrent, &job.ProgressTotal, &detail, &job.Error, &job.CancelRequested,
+		&job.CreatedAt, &job.StartedAt, &job.FinishedAt, &job.UpdatedAt)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	if len(detail) > 0 {
+		job.ProgressDetail = detail
+	}
+	return &job, nil
+}
+
+func scanJobs(rows *sql.Rows) ([]Job, error) {
+	defer rows.Close()
+	var jobs []Job
+	for rows.Next() {
+		job, err := scanJob(rows)
+		if err != nil {
+			return nil, err
+		}
+		jobs = append(jobs, *job)
+	}
+	return jobs, rows.Err()
+}
+
+// Enqueue queues a job of kind with an encoded payload. A job whose unique
+// key matches a queued or running job of the same kind returns ErrDuplicate.
+func (s *Store) Enqueue(ctx context.Context, kind string, payload json.RawMessage, opts Options) (*Job, error) {
+	maxAttempts := opts.MaxAttempts
+	if maxAttempts <= 0 {
+		maxAttempts = DefaultMaxAttempts
+	}
+	runAfter := opts.RunAfter
+	if runAfter.IsZero() {
+		runAfter = time.Now()
+	}
+	job, err := scanJob(s.db.QueryRowContext(ctx, `
+		INSERT INTO jobs (id, kind, user_id, unique_key, payload, priority, max_attempts, run_after)
+		VALUES ($1, $2, $3, NULLIF($4, ''), $5, $6, $7, $8)
+		RETURNING `+jobColumns,
+		uuid.New(), kind, opts.UserID, opts.UniqueKey, payload, opts.Priority, maxAttempts, runAfter))
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23505" {
+		return nil, ErrDuplicate
+	}
+	return job, err
+}
+
+// Get returns one of the user's jobs.
+func (s *Store) Get(ctx context.Context, userID, id uuid.UUID) (*Job, error) {
+	return scanJob(s.db.QueryRowContext(ctx, `
+		SELECT `+jobColumns+`
+		FROM jobs
+		WHERE id = $1 AND user_id = $2
+	`, id, userID))
+}
+
+// ListForUser returns the user's most recent jobs, newest first.
+func (s *Store) ListForUser(ctx context.Context, userID uuid.UUID, limit int) ([]Job, error) {
+	rows, err := s.db.QueryContext(ctx, `
+		SELECT `+jobColumns+`
+		FROM jobs
+		WHERE user_id = $1
+		ORDER BY created_at DESC, id
+		LIMIT $2
+	`, userID, limit)
+	if err != nil {
+		return nil, err
+	}
+	return scanJobs(rows)
+}
+
+// FindActive returns the queued or running job of kind holding uniqueKey, or
+// ErrNotFound when there is none.
+func (s *Store) FindActive(ctx context.Context, kind, uniqueKey string) (*Job, error) {
+	return scanJob(s.db.QueryRowContext(ctx, `
+		SELECT `+jobColumns+`
+		FROM jobs
+		WHERE kind = $1 AND unique_key = $2 AND status IN ('queued', 'running')
+	`, kind, uniqueKey))
+}
+
+// CountActive returns how many jobs of kind are queued or running, across
+// every user.
+func (s *Store) CountActive(ctx context.Context, kind string) (int, error) {
+	var n int
+	err := s.db.QueryRowContext(ctx, `
+		SELECT COUNT(*)
+		FROM jobs
+		WHERE kind = $1 AND status IN ('queued', 'running')
+	`, kind).Scan(&n)
+	return n, err
+}
+
+// Claim marks the highest-priority due job of one of kinds running and
+// returns it, or returns ErrNotFound when none is due. Paused kinds are
+// skipped.
+func (s *Store) Claim(ctx context.Context, kinds []string) (*Job, error) {
+	return scanJob(s.db.QueryRowContext(ctx, `
+		UPDATE jobs
+		SET status = 'running', attempts = attempts + 1, started_at = COALESCE(started_at, NOW()), updated_at = NOW()
+		WHERE id = (
+			SELECT id FROM jobs
+			WHERE status = 'queued' AND run_after <= NOW() AND kind = ANY($1)
+				AND NOT EXISTS (SELECT 1 FROM job_queue_pauses AS p WHERE p.kind = jobs.kind)
+			ORDER BY priority DESC, run_after, created_at, id
+			FOR UPDATE SKIP LOCKED
+			LIMIT 1
+		)
+		RETURNING `+jobColumns, pq.Array(kinds)))
+}
+
+// UpdateProgress records a running job's progress and reports whether its
+// cancellation has been requested.
+func (s *Store) UpdateProgress(ctx context.Context, id uuid.UUID, current, total int, detail json.RawMessage) (bool, error) {
+	var cancelRequested bool
+	err := s.db.QueryRowContext(ctx, `
+		UPDATE jobs
+		SET progress_current = $2, progress_total = $3, progress_detail = COALESCE($4, progress_detail), updated_at = NOW()
+		WHERE id = $1
+		RETURNING cancel_requested
+	`, id, current, total, nullableJSON(detail)).Scan(&cancelRequested)
+	if errors.Is(err, sql.ErrNoRows) {
+		return false, ErrNotFound
+	}
+	return cancelRequested, err
+}
+
+// Complete marks a running job succeeded.
+func (s *Store) Complete(ctx context.Context, id uuid.UUID) error {
+	return s.finish(ctx, id, StatusSucceeded, "")
+}
+
+// Fail records a failed attempt. With retryAt set the job is queued again for
+// then; otherwise it fails for good and is dead-lettered.
+func (s *Store) Fail(ctx context.Context, id uuid.UUID, errMsg string, retryAt time.Time) error {
+	if retryAt.IsZero() {
+		_, err := s.db.ExecContext(ctx, `
+			WITH failed AS (
+				UPDATE jobs
+				SET status = 'failed', error = NULLIF($2, ''), finished_at = NOW(), updated_at = NOW()
+				WHERE id = $1
+				RETURNING id, kind, user_id, error, attempts, max_attempts, payload
+			)
+			INSERT INTO job_dead_letters (kind, job_id, user_id, error, category, attempts, context)
+			SELECT kind, id::text, user_id, COALESCE(error, ''), $3::text, attempts,
+				jsonb_build_object('payload', payload, 'maxAttempts', max_attempts)
+			FROM failed
+			`+upsertDeadLetter+`
+		`, id, errMsg, ClassifyFailure(errMsg))
+		return err
+	}
+	_, err := s.db.ExecContext(ctx, `
+		UPDATE jobs
+		SET status = 'queued', error = $2, run_after = $3, updated_at = NOW()
+		WHERE id = $1
+	`, id, errMsg, retryAt)
+	return err
+}
+
+// MarkCanceled ends a running job whose cancellation was requested.
+func (s *Store) MarkCanceled(ctx context.Context, id uuid.UUID) error {
+	return s.finish(ctx, id, StatusCanceled, "")
+}
+
+func (s *Store) finish(ctx context.Context, id uuid.UUID, status, errMsg string) error {
+	_, err := s.db.ExecContext(ctx, `
+		UPDATE jobs
+		SET status = $2, error = NULLIF($3, ''), finished_at = NOW(), updated_at = NOW()
+		WHERE id = $1
+	`, id, status, errMsg)
+	return err
+}
+
+// Cancel cancels one of the user's jobs. A queued job is canceled at once; a
+// running job is flagged and stops the next time it reports progress.
+func (s *Store) Cancel(ctx context.Context, userID, id uuid.UUID) (*Job, error) {
+	job, err := scanJob(s.db.QueryRowContext(ctx, `
+		UPDATE jobs
+		SET status = CASE WHEN status = 'queued' THEN 'canceled' ELSE status END,
+			finished_at = CASE WHEN status = 'queued' THEN NOW() ELSE finished_at END,
+			cancel_requested = TRUE,
+			updated_at = NOW()
+		WHERE id = $1 AND user_id = $2 AND status IN ('queued', 'running')
+		RETURNING `+jobColumns, id, userID))
+	if !errors.Is(err, ErrNotFound) {
+		return job, err
+	}
+	if _, err := s.Get(ctx, userID, id); err != nil {
+		return nil, err
+	}
+	return nil, ErrNotCancelable
+}
+
+// RequeueStale puts running jobs that have not reported progress within
+// staleAfter back in the queue, recovering jobs whose worker stopped mid-run.
+// The interrupted attempt counts against the job's retry budget; jobs out of
+// attempts are dead-lettered.
+func (s *Store) RequeueStale(ctx context.Context, staleAfter time.Duration) (int64, error) {
+	var requeued int64
+	err := s.db.QueryRowContext(ctx, `
+		WITH recovered AS (
+			UPDATE jobs
+			SET status = CASE
+					WHEN cancel_requested THEN 'canceled'
+					WHEN attempts >= max_attempts THEN 'failed'
+					ELSE 'queued'
+				END,
+				error = CASE WHEN attempts >= max_attempts AND NOT cancel_requested THEN $2 ELSE error END,
+				finished_at = CASE WHEN cancel_requested OR attempts >= max_attempts THEN NOW() ELSE finished_at END,
+				updated_at = NOW()
+			WHERE status = 'running' AND updated_at < NOW() - ($1::bigint * INTERVAL '1 second')
+			RETURNING id, kind, user_id, status, error, attempts, max_attempts, payload
+		), dead AS (
+			INSERT INTO job_dead_letters (kind, job_id, user_id, error, category, attempts, context)
+			SELECT kind, id::text, user_id, COALESCE(error, ''), $3::text, attempts,
+				jsonb_build_object('payload', payload, 'maxAttempts', max_attempts)
+			FROM recovered
+			WHERE status = 'failed'
+			`+upsertDeadLetter+`
+		)
+		SELECT COUNT(*) FROM recovered
+	`, int64(staleAfter.Seconds()), staleJobError, FailureOther).Scan(&requeued)
+	return requeued, err
+}
+
+// staleJobError is the error recorded on a job whose worker stopped while it
+// was out of attempts.
+const staleJobError = "worker stopped before the job finished"
+
+// upsertDeadLetter replaces the dead letter of a job that fails for good
+// again.
+const upsertDeadLetter = `ON CONFLICT (kind, job_id) DO UPDATE
+	SET user_id = EXCLUDED.user_id, error = EXCLUDED.error, category = EXCLUDED.category,
+		attempts = EXCLUDED.attempts, context = EXCLUDED.context, failed_at = NOW()`
+
+// Requeue queues a failed job again with a fresh retry budget.
+func (s *Store) Requeue(ctx context.Context, id uuid.UUID) error {
+	result, err := s.db.ExecContext(ctx, `
+		UPDATE jobs
+		SET status = 'queued', attempts = 0, error = NULL, run_after = NOW(), cancel_requested = FALSE,
+			progress_current = 0, progress_total = 0, progress_detail = NULL,
+			started_at = NULL, finished_at = NULL, updated_at = NOW()
+		WHERE id = $1 AND status = 'failed'
+	`, id)
+	var pqErr *pq.Error
+	if errors.As(err, &pqErr) && pqErr.Code == "23505" {
+		return ErrDuplicate
+	}
+	if err != nil {
+		return err
+	}
+	if rows, err := result.RowsAffected(); err != nil {
+		return err
+	} else if rows == 0 {
+		return ErrNotRetryable
+	}
+	return nil
+}
+
+// PausedKinds returns the paused job kinds keyed by kind.
+func (s *Store) PausedKinds(ctx context.Context) (map[string]QueueState, error) {
+	rows, err := s.db.QueryContext(ctx, `SELECT kind, reason, paused_at FROM job_queue_pauses`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	paused := map[string]QueueState{}
+	for rows.Next() {
+		var kind string
+		var reason sql.NullString
+		var pausedAt time.Time
+		if err := rows.Scan(&kind, &reason, &pausedAt); err != nil {
+			return nil, err
+		}
+		paused[kind] = QueueState{Name: kind, Paused: true, Reason: reason.String, PausedAt: &pausedAt}
+	}
+	return paused, rows.Err()
+}
+
+// PauseKind stops workers from claiming queued jobs of kind. Pausing an
+// already paused kind keeps its original pause time and updates the reason.
+func (s *Store) PauseKind(ctx context.Context, kind, reason string) error {
+	_, err := s.db.ExecContext(ctx, `
+		INSERT INTO job_queue_pauses (kind, reason)
+		VALUES ($1, NULLIF($2, ''))
+		ON CONFLICT (kind) DO UPDATE SET reason = EXCLUDED.reason
+	`, kind, reason)
+	return err
+}
+
+// ResumeKind lets workers claim queued jobs of kind again.
+func (s *Store) ResumeKind(ctx context.Context, kind string) error {
+	_, err := s.db.ExecContext(ctx, `DELETE FROM job_queue_pauses WHERE kind = $1`, kind)
+	return err
+}
+
+// RecordDeadLetter dead-letters a job that failed for good outside this
+// package, such as a download. A job dead-lettered again replaces its
+// previous dead letter.
+func (s *Store) RecordDeadLetter(ctx context.Context, letter DeadLetter) error {
+	_, err := s.db.ExecContext(ctx, `
+		INSERT INTO job_dead_letters (kind, job_id, user_id, error, category, attempts, context)
+		VALUES ($1, $2, NULLIF($3, '')::uuid, $4, $5, $6, COALESCE($7, '{}'::jsonb))
+		`+upsertDeadLetter+`
+	`, letter.Kind, letter.JobID, letter.UserID, letter.Error, letter.Category, letter.Attempts, nullableJSON(letter.Context))
+	return err
+}
+
+// ListDeadLetters returns the dead letters matching filter, most recent first.
+func (s *Store) ListDeadLetters(ctx context.Context, filter DeadLetterFilter) ([]DeadLetter, error) {
+	rows, err := s.db.QueryContext(ctx, `
+		SELECT id, kind, job_id, COALESCE(user_id::text, ''), error, category, attempts, context, failed_at
+		FROM job_dead_letters
+		WHERE (cardinality($1::bigint[]) = 0 OR id = ANY($1))
+			AND ($2 = '' OR kind = $2)
+			AND ($3 = '' OR category = $3)
+		ORDER BY failed_at DESC, id DESC
+		LIMIT $4
+	`, pq.Array(filter.IDs), filter.Kind, filter.Category, filter.Limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var letters []DeadLetter
+	for rows.Next() {
+		var letter DeadLetter
+		var letterContext []byte
+		if err := rows.Scan(&letter.ID, &letter.Kind, &letter.JobID, &letter.UserID, &letter.Error, &letter.Category, &letter.Attempts, &letterContext, &letter.FailedAt); err != nil {
+			return nil, err
+		}
+		letter.Context = letterContext
+		letters = append(letters, letter)
+	}
+	return letters, rows.Err()
+}
+
+// DeleteDeadLetters removes dead letters by ID.
+func (s *Store) DeleteDeadLetters(ctx context.Context, ids []int64) (int64, error) {
+	result, err := s.db.ExecContext(ctx, `DELETE FROM job_dead_letters WHERE id = ANY($1)`, pq.Array(ids))
+	if err != nil {
+		return 0, err
+	}
+	return result.RowsAffected()
+}
+
+// DeadLetterCounts returns the number of dead letters in each failure
+// category.
+func (s *Store) DeadLetterCounts(ctx context.Context) (map[string]int, error) {
+	rows, err := s.db.QueryContext(ctx, `SELECT category, COUNT(*) FROM job_dead_letters GROUP BY category`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	counts := map[string]int{}
+	for rows.Next() {
+		var category string
+		var count int
+		if err := rows.Scan(&category, &count); err != nil {
+			return nil, err
+		}
+		counts[category] = count
+	}
+	return counts, rows.Err()
+}
+
+func nullableJSON(data json.RawMessage) any {
+	if len(data) == 0 {
+		return nil
+	}
+	return []byte(data)
+}
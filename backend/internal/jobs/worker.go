@@ -0,0 +1,210 @@
+package jobs
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"math"
+	"sort"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+const (
+	defaultPollInterval = 5 * time.Second
+	defaultStaleAfter   = 30 * time.Minute
+	defaultRetryBackoff = 30 * time.Second
+	maxRetryBackoff     = time.Hour
+)
+
+// store is the persistence the worker needs. *Store satisfies it.
+type store interface {
+	Claim(ctx context.Context, kinds []string) (*Job, error)
+	UpdateProgress(ctx context.Context, id uuid.UUID, current, total int, detail json.RawMessage) (bool, error)
+	Complete(ctx context.Context, id uuid.UUID) error
+	Fail(ctx context.Context, id uuid.UUID, errMsg string, retryAt time.Time) error
+	MarkCanceled(ctx context.Context, id uuid.UUID) error
+	Requeue(ctx context.Context, id uuid.UUID) error
+	RequeueStale(ctx context.Context, staleAfter time.Duration) (int64, error)
+	PausedKinds(ctx context.Context) (map[string]QueueState, error)
+	PauseKind(ctx context.Context, kind, reason string) error
+	ResumeKind(ctx context.Context, kind string) error
+}
+
+type handler func(ctx context.Context, job *Job, progress *Progress) error
+
+// WorkerConfig tunes a Worker. Zero values use the defaults.
+type WorkerConfig struct {
+	// PollInterval is how long an idle worker waits before looking for work.
+	PollInterval time.Duration
+	// StaleAfter is how long a running job may go without reporting progress
+	// before it is assumed orphaned and requeued.
+	StaleAfter time.Duration
+	// RetryBackoff is the delay before a failed job's first retry; it doubles
+	// with each further attempt.
+	RetryBackoff time.Duration
+}
+
+// Worker runs queued jobs of the kinds registered on it, one at a time.
+type Worker struct {
+	store        store
+	handlers     map[string]handler
+	pollInterval time.Duration
+	staleAfter   time.Duration
+	retryBackoff time.Duration
+}
+
+func NewWorker(store *Store, config WorkerConfig) *Worker {
+	return newWorker(store, config)
+}
+
+func newWorker(store store, config WorkerConfig) *Worker {
+	w := &Worker{
+		store:        store,
+		handlers:     map[string]handler{},
+		pollInterval: config.PollInterval,
+		staleAfter:   config.StaleAfter,
+		retryBackoff: config.RetryBackoff,
+	}
+	if w.pollInterval <= 0 {
+		w.pollInterval = defaultPollInterval
+	}
+	if w.staleAfter <= 0 {
+		w.staleAfter = defaultStaleAfter
+	}
+	if w.retryBackoff <= 0 {
+		w.retryBackoff = defaultRetryBackoff
+	}
+	return w
+}
+
+func (w *Worker) register(kind string, fn handler) {
+	w.handlers[kind] = fn
+}
+
+func (w *Worker) kinds() []string {
+	kinds := make([]string, 0, len(w.handlers))
+	for kind := range w.handlers {
+		kinds = append(kinds, kind)
+	}
+	sort.Strings(kinds)
+	return kinds
+}
+
+// Run works through due jobs until ctx is canceled, recovering stale jobs
+// between passes. A job interrupted by ctx is left running and requeued once
+// it goes stale.
+func (w *Worker) Run(ctx context.Context) {
+	ticker := time.NewTicker(w.pollInterval)
+	defer ticker.Stop()
+	for {
+		if requeued, err := w.store.RequeueStale(ctx, w.staleAfter); err != nil {
+			if ctx.Err() == nil {
+				log.Printf("Failed to recover stale jobs: %v", err)
+			}
+		} else if requeued > 0 {
+			log.Printf("Recovered %d stale jobs", requeued)
+		}
+		for {
+			ran, err := w.RunNext(ctx)
+			if err != nil {
+				if ctx.Err() == nil {
+					log.Printf("Failed to run job: %v", err)
+				}
+				break
+			}
+			if !ran {
+				break
+			}
+		}
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// RunNext claims the next due job and runs one attempt of it. It reports false
+// when no job is due.
+func (w *Worker) RunNext(ctx context.Context) (bool, error) {
+	if len(w.handlers) == 0 {
+		return false, nil
+	}
+	job, err := w.store.Claim(ctx, w.kinds())
+	if errors.Is(err, ErrNotFound) {
+		return false, nil
+	}
+	if err != nil {
+		return false, err
+	}
+
+	jobCtx, cancel := context.WithCancel(ctx)
+	defer cancel()
+	progress := &Progress{store: w.store, job: job, cancel: cancel}
+	runErr := w.handlers[job.Kind](jobCtx, job, progress)
+	if ctx.Err() != nil {
+		return true, ctx.Err()
+	}
+
+	switch {
+	case progress.canceled || errors.Is(runErr, ErrCanceled):
+		return true, w.store.MarkCanceled(ctx, job.ID)
+	case runErr == nil:
+		return true, w.store.Complete(ctx, job.ID)
+	case isPermanent(runErr) || job.Attempts >= job.MaxAttempts:
+		log.Printf("Job %s (%s) failed: %v", job.ID, job.Kind, runErr)
+		return true, w.store.Fail(ctx, job.ID, runErr.Error(), time.Time{})
+	default:
+		retryAt := time.Now().Add(w.backoff(job.Attempts))
+		log.Printf("Job %s (%s) attempt %d/%d failed, retrying at %s: %v", job.ID, job.Kind, job.Attempts, job.MaxAttempts, retryAt.Format(time.RFC3339), runErr)
+		return true, w.store.Fail(ctx, job.ID, runErr.Error(), retryAt)
+	}
+}
+
+// backoff returns the delay before retrying a job that has failed attempts
+// times, doubling from the configured backoff up to maxRetryBackoff.
+func (w *Worker) backoff(attempts int) time.Duration {
+	backoff := time.Duration(math.Pow(2, float64(attempts-1))) * w.retryBackoff
+	if backoff <= 0 || backoff > maxRetryBackoff {
+		return maxRetryBackoff
+	}
+	return backoff
+}
+
+// Progress reports a running job's progress. Reporting also delivers
+// cancellation: once the job's owner cancels it, Report cancels the handler's
+// context and returns ErrCanceled.
+type Progress struct {
+	store    store
+	job      *Job
+	cancel   context.CancelFunc
+	canceled bool
+}
+
+// Report records that current of total work items are done. A non-nil detail
+// is stored as the job's kind-specific progress.
+func (p *Progress) Report(ctx context.Context, current, total int, detail any) error {
+	var encoded json.RawMessage
+	if detail != nil {
+		data, err := json.Marshal(detail)
+		if err != nil {
+			return fmt.Errorf("encode progress detail: %w", err)
+		}
+		encoded = data
+	}
+	cancelRequested, err := p.store.UpdateProgress(ctx, p.job.ID, current, total, encoded)
+	if err != nil {
+		return err
+	}
+	p.job.ProgressCurrent, p.job.ProgressTotal = current, total
+	if cancelRequested {
+		p.canceled = true
+		p.cancel()
+		return ErrCanceled
+	}
+	return nil
+}
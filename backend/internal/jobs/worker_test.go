@@ -0,0 +1,251 @@
+package jobs
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+)
+
+type fakeStore struct {
+	queued          []*Job
+	paused          map[string]QueueState
+	cancelRequested bool
+	progress        []string
+	completed       []uuid.UUID
+	failed          []uuid.UUID
+	retryAt         time.Time
+	canceled        []uuid.UUID
+	requeued        []uuid.UUID
+}
+
+func (s *fakeStore) Claim(_ context.Context, kinds []string) (*Job, error) {
+	for i, job := range s.queued {
+		for _, kind := range kinds {
+			if _, paused := s.paused[kind]; paused {
+				continue
+			}
+			if job.Kind == kind {
+				s.queued = append(s.queued[:i], s.queued[i+1:]...)
+				job.Attempts++
+				return job, nil
+			}
+		}
+	}
+	return nil, ErrNotFound
+}
+
+func (s *fakeStore) UpdateProgress(_ context.Context, _ uuid.UUID, _, _ int, detail json.RawMessage) (bool, error) {
+	s.progress = append(s.progress, string(detail))
+	return s.cancelRequested, nil
+}
+
+func (s *fakeStore) Complete(_ context.Context, id uuid.UUID) error {
+	s.completed = append(s.completed, id)
+	return nil
+}
+
+func (s *fakeStore) Fail(_ context.Context, id uuid.UUID, _ string, retryAt time.Time) error {
+	s.failed = append(s.failed, id)
+	s.retryAt = retryAt
+	return nil
+}
+
+func (s *fakeStore) MarkCanceled(_ context.Context, id uuid.UUID) error {
+	s.canceled = append(s.canceled, id)
+	return nil
+}
+
+func (s *fakeStore) Requeue(_ context.Context, id uuid.UUID) error {
+	s.requeued = append(s.requeued, id)
+	return nil
+}
+
+func (s *fakeStore) RequeueStale(context.Context, time.Duration) (int64, error) {
+	return 0, nil
+}
+
+func (s *fakeStore) PausedKinds(context.Context) (map[string]QueueState, error) {
+	return s.paused, nil
+}
+
+func (s *fakeStore) PauseKind(_ context.Context, kind, reason string) error {
+	if s.paused == nil {
+		s.paused = map[string]QueueState{}
+	}
+	s.paused[kind] = QueueState{Name: kind, Paused: true, Reason: reason}
+	return nil
+}
+
+func (s *fakeStore) ResumeKind(_ context.Context, kind string) error {
+	delete(s.paused, kind)
+	return nil
+}
+
+type testPayload struct {
+	Name string `json:"name"`
+}
+
+var testKind = NewKind[testPayload]("test")
+
+func newTestJob(attempts, maxAttempts int) *Job {
+	return &Job{ID: uuid.New(), Kind: testKind.Name, Payload: json.RawMessage(`{"name":"x"}`), Attempts: attempts, MaxAttempts: maxAttempts}
+}
+
+func TestRunNextCompletesSuccessfulJob(t *testing.T) {
+	job := newTestJob(0, 3)
+	store := &fakeStore{queued: []*Job{job}}
+	w := newWorker(store, WorkerConfig{})
+	var got testPayload
+	testKind.Handle(w, func(ctx context.Context, _ *Job, payload testPayload, progress *Progress) error {
+		got = payload
+		return progress.Report(ctx, 1, 1, map[string]int{"done": 1})
+	})
+
+	ran, err := w.RunNext(context.Background())
+	if err != nil || !ran {
+		t.Fatalf("RunNext() = %v, %v; want true, nil", ran, err)
+	}
+	if got.Name != "x" {
+		t.Fatalf("payload = %+v, want name x", got)
+	}
+	if len(store.completed) != 1 || store.completed[0] != job.ID {
+		t.Fatalf("completed = %v, want %s", store.completed, job.ID)
+	}
+	if len(store.progress) != 1 || store.progress[0] != `{"done":1}` {
+		t.Fatalf("progress = %v", store.progress)
+	}
+}
+
+func TestRunNextRetriesFailedAttemptWithBackoff(t *testing.T) {
+	store := &fakeStore{queued: []*Job{newTestJob(1, 3)}}
+	w := newWorker(store, WorkerConfig{RetryBackoff: time.Minute})
+	testKind.Handle(w, func(context.Context, *Job, testPayload, *Progress) error {
+		return errors.New("flaky")
+	})
+
+	before := time.Now()
+	if _, err := w.RunNext(context.Background()); err != nil {
+		t.Fatalf("RunNext() error = %v", err)
+	}
+	if len(store.failed) != 1 {
+		t.Fatalf("failed = %v, want one failure", store.failed)
+	}
+	// The second attempt failed, so the retry waits twice the base backoff.
+	if delay := store.retryAt.Sub(before); delay < 2*time.Minute || delay > 2*time.Minute+time.Second {
+		t.Fatalf("retry delay = %s, want about 2m", delay)
+	}
+}
+
+func TestRunNextFailsWithoutRetry(t *testing.T) {
+	tests := []struct {
+		name string
+		job  *Job
+		err  error
+	}{
+		{name: "permanent error", job: newTestJob(0, 3), err: Permanent(errors.New("bad input"))},
+		{name: "attempts exhausted", job: newTestJob(2, 3), err: errors.New("flaky")},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			store := &fakeStore{queued: []*Job{tt.job}}
+			w := newWorker(store, WorkerConfig{})
+			testKind.Handle(w, func(context.Context, *Job, testPayload, *Progress) error {
+				return tt.err
+			})
+
+			if _, err := w.RunNext(context.Background()); err != nil {
+				t.Fatalf("RunNext() error = %v", err)
+			}
+			if len(store.failed) != 1 || !store.retryAt.IsZero() {
+				t.Fatalf("failed = %v retryAt = %s, want a final failure", store.failed, store.retryAt)
+			}
+		})
+	}
+}
+
+func TestRunNextFailsUndecodablePayload(t *testing.T) {
+	job := newTestJob(0, 3)
+	job.Payload = json.RawMessage(`[]`)
+	store := &fakeStore{queued: []*Job{job}}
+	w := newWorker(store, WorkerConfig{})
+	testKind.Handle(w, func(context.Context, *Job, testPayload, *Progress) error {
+		t.Fatal("handler ran with an undecodable payload")
+		return nil
+	})
+
+	if _, err := w.RunNext(context.Background()); err != nil {
+		t.Fatalf("RunNext() error = %v", err)
+	}
+	if len(store.failed) != 1 || !store.retryAt.IsZero() {
+		t.Fatalf("failed = %v retryAt = %s, want a final failure", store.failed, store.retryAt)
+	}
+}
+
+func TestRunNextStopsCanceledJob(t *testing.T) {
+	job := newTestJob(0, 3)
+	store := &fakeStore{queued: []*Job{job}, cancelRequested: true}
+	w := newWorker(store, WorkerConfig{})
+	var handlerCtx context.Context
+	testKind.Handle(w, func(ctx context.Context, _ *Job, _ testPayload, progress *Progress) error {
+		handlerCtx = ctx
+		if err := progress.Report(ctx, 1, 10, nil); !errors.Is(err, ErrCanceled) {
+			t.Fatalf("Report() error = %v, want ErrCanceled", err)
+		}
+		return nil
+	})
+
+	if _, err := w.RunNext(context.Background()); err != nil {
+		t.Fatalf("RunNext() error = %v", err)
+	}
+	if handlerCtx.Err() == nil {
+		t.Fatal("handler context was not canceled")
+	}
+	if len(store.canceled) != 1 || len(store.completed) != 0 {
+		t.Fatalf("canceled = %v completed = %v, want the job canceled", store.canceled, store.completed)
+	}
+}
+
+func TestRunNextReportsNoWork(t *testing.T) {
+	store := &fakeStore{queued: []*Job{{ID: uuid.New(), Kind: "other"}}}
+	w := newWorker(store, WorkerConfig{})
+	if ran, err := w.RunNext(context.Background()); ran || err != nil {
+		t.Fatalf("RunNext() without handlers = %v, %v; want false, nil", ran, err)
+	}
+
+	testKind.Handle(w, func(context.Context, *Job, testPayload, *Progress) error { return nil })
+	if ran, err := w.RunNext(context.Background()); ran || err != nil {
+		t.Fatalf("RunNext() with no due job = %v, %v; want false, nil", ran, err)
+	}
+}
+
+func TestPausedQueueStartsNoJobsUntilResumed(t *testing.T) {
+	store := &fakeStore{queued: []*Job{newTestJob(0, 3)}}
+	w := newWorker(store, WorkerConfig{})
+	testKind.Handle(w, func(context.Context, *Job, testPayload, *Progress) error { return nil })
+	ctx := context.Background()
+
+	state, err := w.PauseQueue(ctx, testKind.Name, "database upgrade")
+	if err != nil || !state.Paused || state.Reason != "database upgrade" {
+		t.Fatalf("PauseQueue() = %+v, %v", state, err)
+	}
+	if ran, err := w.RunNext(ctx); ran || err != nil {
+		t.Fatalf("RunNext() while paused = %v, %v; want false, nil", ran, err)
+	}
+	if _, err := w.PauseQueue(ctx, "unknown", ""); !errors.Is(err, ErrNotFound) {
+		t.Fatalf("PauseQueue(unknown) error = %v, want ErrNotFound", err)
+	}
+
+	if state, err := w.ResumeQueue(ctx, testKind.Name); err != nil || state.Paused {
+		t.Fatalf("ResumeQueue() = %+v, %v", state, err)
+	}
+	if ran, err := w.RunNext(ctx); !ran || err != nil {
+		t.Fatalf("RunNext() after resume = %v, %v; want true, nil", ran, err)
+	}
+	if len(store.completed) != 1 {
+		t.Fatalf("completed = %v, want the queued job", store.completed)
+	}
+}
@@ -0,0 +1,137 @@
+package libraryexport
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"os/exec"
+	"path/filepath"
+	"slices"
+	"strings"
+)
+
+// Tagger reads the tags of stored audio files and writes tagged copies.
+type Tagger interface {
+	ReadTags(ctx context.Context, path string) (map[string]string, error)
+	// Write copies src to dst in profile's format, setting tags and, when
+	// cover is not empty, embedding the image at cover.
+	Write(ctx context.Context, src, cover, dst string, tags map[string]string, profile Profile) error
+}
+
+// FFmpeg tags files with the ffprobe and ffmpeg commands.
+type FFmpeg struct {
+	ffmpeg  string
+	ffprobe string
+}
+
+// NewFFmpeg uses the ffmpeg and ffprobe found on PATH, where managed builds
+// are placed ahead of system ones.
+func NewFFmpeg() *FFmpeg {
+	return &FFmpeg{ffmpeg: "ffmpeg", ffprobe: "ffprobe"}
+}
+
+// ReadTags returns the file's container and first audio stream tags with
+// lower-cased names.
+func (f *FFmpeg) ReadTags(ctx context.Context, path string) (map[string]string, error) {
+	cmd := exec.CommandContext(ctx, f.ffprobe,
+		"-v", "error",
+		"-select_streams", "a:0",
+		"-show_entries", "stream_tags:format_tags",
+		"-of", "json",
+		path,
+	)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	output, err := cmd.Output()
+	if err != nil {
+		return nil, fmt.Errorf("ffprobe failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	var probed struct {
+		Streams []struct {
+			Tags map[string]string `json:"tags"`
+		} `json:"streams"`
+		Format struct {
+			Tags map[string]string `json:"tags"`
+		} `json:"format"`
+	}
+	if err := json.Unmarshal(output, &probed); err != nil {
+		return nil, fmt.Errorf("decode ffprobe output: %w", err)
+	}
+	tags := map[string]string{}
+	for _, stream := range probed.Streams {
+		for key, value := range stream.Tags {
+			tags[strings.ToLower(key)] = strings.TrimSpace(value)
+		}
+	}
+	// Container tags win; Ogg files carry theirs on the stream instead.
+	for key, value := range probed.Format.Tags {
+		tags[strings.ToLower(key)] = strings.TrimSpace(value)
+	}
+	return tags, nil
+}
+
+func (f *FFmpeg) Write(ctx context.Context, src, cover, dst string, tags map[string]string, profile Profile) error {
+	cmd := exec.CommandContext(ctx, f.ffmpeg, ffmpegArgs(src, cover, dst, tags, profile)...)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return nil
+}
+
+// ffmpegArgs copies or converts the audio of src for profile, keeps its
+// existing tags, replaces the given tags, and swaps any embedded picture for
+// cover.
+func ffmpegArgs(src, cover, dst string, tags map[string]string, profile Profile) []string {
+	args := []string{"-nostdin", "-v", "error", "-y", "-i", src}
+	switch {
+	case cover != "":
+		args = append(args, "-i", cover, "-map", "0:a", "-map", "1:v", "-disposition:v", "attached_pic")
+	case profile.MaxArtworkBytes > 0 || !embedsArtwork(strings.TrimPrefix(filepath.Ext(dst), ".")):
+		// A picture already in src is of unknown size, and Ogg and similar
+		// containers take none.
+		args = append(args, "-map", "0:a")
+	default:
+		args = append(args, "-map", "0:a", "-map", "0:v?")
+	}
+	args = append(args, profile.codecArgs()...)
+	args = append(args, "-map_metadata", "0")
+	keys := make([]string, 0, len(tags))
+	for key := range tags {
+		keys = append(keys, key)
+	}
+	slices.Sort(keys)
+	for _, key := range keys {
+		args = append(args, "-metadata", key+"="+tags[key])
+	}
+	if strings.EqualFold(filepath.Ext(dst), ".mp3") {
+		// ID3v2.3 is what most car stereos and players read.
+		args = append(args, "-id3v2_version", "3")
+	}
+	return append(args, dst)
+}
+
+// embedsArtwork reports whether ffmpeg can attach a cover picture to files
+// with extension ext. Other formats get a cover.jpg beside them instead.
+func embedsArtwork(ext string) bool {
+	switch strings.ToLower(ext) {
+	case "mp3", "flac", "m4a", "m4b", "mp4":
+		return true
+	}
+	return false
+}
+
+// mbidTags names the MusicBrainz ID tags the way Picard writes them for each
+// format, so the processor reads them back on re-import. MP4 files are left
+// alone since ffmpeg cannot write iTunes freeform atoms.
+func mbidTags(ext string) (recording, release, artist string) {
+	switch strings.ToLower(ext) {
+	case "mp3":
+		return "MusicBrainz Track Id", "MusicBrainz Album Id", "MusicBrainz Artist Id"
+	case "flac", "ogg", "oga", "opus":
+		return "MUSICBRAINZ_TRACKID", "MUSICBRAINZ_ALBUMID", "MUSICBRAINZ_ARTISTID"
+	}
+	return "", "", ""
+}
@@ -0,0 +1,45 @@
+package libraryexport
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestFFmpegArgs(t *testing.T) {
+	got := ffmpegArgs("in.mp3", "cover.jpg", "out.mp3", map[string]string{"title": "Starfire", "artist": "Low"}, Profile{})
+	want := []string{
+		"-nostdin", "-v", "error", "-y", "-i", "in.mp3",
+		"-i", "cover.jpg", "-map", "0:a", "-map", "1:v", "-disposition:v", "attached_pic",
+		"-c", "copy", "-map_metadata", "0",
+		"-metadata", "artist=Low", "-metadata", "title=Starfire",
+		"-id3v2_version", "3",
+		"out.mp3",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("ffmpegArgs() = %q, want %q", got, want)
+	}
+
+	got = ffmpegArgs("in.flac", "", "out.flac", map[string]string{"MUSICBRAINZ_TRACKID": "11111111-1111-1111-1111-111111111111"}, Profile{})
+	want = []string{
+		"-nostdin", "-v", "error", "-y", "-i", "in.flac",
+		"-map", "0:a", "-map", "0:v?",
+		"-c", "copy", "-map_metadata", "0",
+		"-metadata", "MUSICBRAINZ_TRACKID=11111111-1111-1111-1111-111111111111",
+		"out.flac",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("ffmpegArgs() without cover = %q, want %q", got, want)
+	}
+
+	got = ffmpegArgs("in.flac", "", "out.opus", map[string]string{"title": "Starfire"}, Profile{Format: FormatOpus, BitrateKbps: 128})
+	want = []string{
+		"-nostdin", "-v", "error", "-y", "-i", "in.flac",
+		"-map", "0:a",
+		"-c:a", "libopus", "-b:a", "128k", "-c:v", "copy", "-map_metadata", "0",
+		"-metadata", "title=Starfire",
+		"out.opus",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("ffmpegArgs() converting = %q, want %q", got, want)
+	}
+}
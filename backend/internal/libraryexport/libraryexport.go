@@ -0,0 +1,169 @@
+// Package libraryexport copies a user's tracks and playlists out of Open
+// Music Player into a plain folder tree, for a car USB stick or a digital
+// audio player. Each file is renamed through a path template and written with
+// its tags and cover art embedded, and each playlist becomes an M3U8 file of
+// relative paths.
+//
+// Exports write below a directory the operator configures. A manifest in the
+// export folder records what was written, so exporting to the same folder
+// again only rewrites tracks that changed.
+//
+// A device profile can convert files on the way out, to a format and bitrate
+// a car stereo or player handles, and leave out oversized cover art. The same
+// conversion fills object storage with copies for devices syncing offline.
+package libraryexport
+
+import (
+	"errors"
+	"fmt"
+	"path"
+	"strings"
+	"unicode/utf8"
+)
+
+// DefaultPathTemplate lays files out as Artist/Album/NN Title.ext.
+const DefaultPathTemplate = "{artist}/{album}/{track} {title}.{ext}"
+
+// MaxSelection bounds how many track and playlist IDs one export names.
+const MaxSelection = 10000
+
+// maxComponentBytes keeps every path component within the 255-character
+// limit of FAT32 and exFAT, leaving room for a collision suffix.
+const maxComponentBytes = 200
+
+// ErrInvalidExport is returned for export requests that cannot run.
+var ErrInvalidExport = errors.New("invalid library export")
+
+// templateFields are the placeholders path templates may use.
+var templateFields = map[string]bool{
+	"artist":      true,
+	"albumartist": true,
+	"album":       true,
+	"title":       true,
+	"track":       true,
+	"disc":        true,
+	"year":        true,
+	"genre":       true,
+	"ext":         true,
+}
+
+// PathTemplate renders a track's relative path in an export, such as
+// "{artist}/{album}/{track} {title}.{ext}".
+type PathTemplate struct {
+	components [][]templatePart
+}
+
+type templatePart struct {
+	literal string
+	field   string
+}
+
+// ParsePathTemplate parses a template of "/"-separated components mixing
+// literal text and {field} placeholders. It must end in ".{ext}".
+func ParsePathTemplate(template string) (*PathTemplate, error) {
+	template = strings.TrimSpace(template)
+	if !strings.HasSuffix(template, ".{ext}") {
+		return nil, fmt.Errorf("%w: path template must end in .{ext}", ErrInvalidExport)
+	}
+	if path.IsAbs(template) {
+		return nil, fmt.Errorf("%w: path template must be relative", ErrInvalidExport)
+	}
+	t := &PathTemplate{}
+	for _, component := range strings.Split(template, "/") {
+		var parts []templatePart
+		for component != "" {
+			open := strings.IndexByte(component, '{')
+			if open < 0 {
+				parts = append(parts, templatePart{literal: component})
+				break
+			}
+			if open > 0 {
+				parts = append(parts, templatePart{literal: component[:open]})
+			}
+			end := strings.IndexByte(component[open:], '}')
+			if end < 0 {
+				return nil, fmt.Errorf("%w: unclosed { in path template", ErrInvalidExport)
+			}
+			field := component[open+1 : open+end]
+			if !templateFields[field] {
+				return nil, fmt.Errorf("%w: unknown path template field {%s}", ErrInvalidExport, field)
+			}
+			parts = append(parts, templatePart{field: field})
+			component = component[open+end+1:]
+		}
+		t.components = append(t.components, parts)
+	}
+	return t, nil
+}
+
+// Render fills the template from fields. Components are made safe for FAT
+// file systems, and directory components that render empty are left out.
+func (t *PathTemplate) Render(fields map[string]string) string {
+	components := make([]string, 0, len(t.components))
+	for i, parts := range t.components {
+		var b strings.Builder
+		for _, part := range parts {
+			if part.field != "" {
+				b.WriteString(fields[part.field])
+			} else {
+				b.WriteString(part.literal)
+			}
+		}
+		last := i == len(t.components)-1
+		component := sanitizeComponent(b.String(), last)
+		if component == "" {
+			continue
+		}
+		components = append(components, component)
+	}
+	return strings.Join(components, "/")
+}
+
+// sanitizeComponent replaces characters FAT file systems reject, collapses
+// whitespace, trims the spaces and dots Windows drops, and shortens long
+// names. The last component keeps its extension when shortened.
+func sanitizeComponent(name string, last bool) string {
+	name = strings.Map(func(r rune) rune {
+		switch {
+		case r < 0x20 || r == 0x7f:
+			return ' '
+		case strings.ContainsRune(`<>:"/\|?*`, r):
+			return '_'
+		}
+		return r
+	}, name)
+	name = strings.Join(strings.Fields(name), " ")
+	ext := ""
+	if last {
+		ext = path.Ext(name)
+		name = strings.TrimSuffix(name, ext)
+	}
+	name = strings.Trim(name, " .")
+	if len(name) > maxComponentBytes-len(ext) {
+		name = truncateUTF8(name, maxComponentBytes-len(ext))
+		name = strings.TrimRight(name, " .")
+	}
+	if name == "" {
+		if last {
+			return "_" + ext
+		}
+		return ""
+	}
+	return name + ext
+}
+
+func truncateUTF8(s string, n int) string {
+	if n <= 0 {
+		return ""
+	}
+	for n > 0 && !utf8.RuneStart(s[n]) {
+		n--
+	}
+	return s[:n]
+}
+
+// CleanTarget turns a user-supplied folder into a path relative to the export
+// directory that cannot climb out of it.
+func CleanTarget(dir string) string {
+	return strings.TrimPrefix(path.Clean("/"+strings.TrimSpace(dir)), "/")
+}
@@ -0,0 +1,82 @@
+package libraryexport
+
+import (
+	"errors"
+	"strings"
+	"testing"
+)
+
+func TestParsePathTemplate(t *testing.T) {
+	for _, template := range []string{
+		DefaultPathTemplate,
+		"{albumartist}/{year} - {album}/{disc}-{track} {title}.{ext}",
+		"{title}.{ext}",
+	} {
+		if _, err := ParsePathTemplate(template); err != nil {
+			t.Errorf("ParsePathTemplate(%q) error = %v", template, err)
+		}
+	}
+	for _, template := range []string{
+		"{artist}/{title}",
+		"/{artist}/{title}.{ext}",
+		"{artist}/{composer}/{title}.{ext}",
+		"{artist/{title}.{ext}",
+	} {
+		if _, err := ParsePathTemplate(template); !errors.Is(err, ErrInvalidExport) {
+			t.Errorf("ParsePathTemplate(%q) error = %v, want ErrInvalidExport", template, err)
+		}
+	}
+}
+
+func TestPathTemplateRenderIsFATSafe(t *testing.T) {
+	template, err := ParsePathTemplate("{artist}/{disc}/{album}/{track} {title}.{ext}")
+	if err != nil {
+		t.Fatal(err)
+	}
+	tests := []struct {
+		fields map[string]string
+		want   string
+	}{
+		{
+			map[string]string{"artist": "Low", "album": "Secret Name", "track": "01", "title": "Starfire", "ext": "flac"},
+			"Low/Secret Name/01 Starfire.flac",
+		},
+		{
+			map[string]string{"artist": "AC/DC", "album": "What?  Now: \"Live\"...", "title": "Who*\tAre|You", "ext": "mp3"},
+			"AC_DC/What_ Now_ _Live_/Who_ Are_You.mp3",
+		},
+		{
+			map[string]string{"artist": "..", "album": ".", "title": "..", "ext": "ogg"},
+			"_.ogg",
+		},
+	}
+	for _, tt := range tests {
+		if got := template.Render(tt.fields); got != tt.want {
+			t.Errorf("Render(%v) = %q, want %q", tt.fields, got, tt.want)
+		}
+	}
+
+	long := template.Render(map[string]string{"artist": strings.Repeat("é", 150), "title": strings.Repeat("x", 300), "ext": "flac"})
+	parts := strings.Split(long, "/")
+	if len(parts) != 2 || len(parts[0]) > maxComponentBytes || len(parts[1]) != maxComponentBytes || !strings.HasSuffix(parts[1], ".flac") {
+		t.Fatalf("long Render = %q", long)
+	}
+	if !strings.HasPrefix(parts[0], "é") || strings.ContainsRune(parts[0], '�') || len(parts[0])%2 != 0 {
+		t.Fatalf("long artist component cut mid-rune: %q", parts[0])
+	}
+}
+
+func TestCleanTarget(t *testing.T) {
+	tests := map[string]string{
+		"":             "",
+		"car":          "car",
+		" /usb/car/ ":  "usb/car",
+		"../../etc":    "etc",
+		"car/../../..": "",
+	}
+	for target, want := range tests {
+		if got := CleanTarget(target); got != want {
+			t.Errorf("CleanTarget(%q) = %q, want %q", target, got, want)
+		}
+	}
+}
@@ -0,0 +1,138 @@
+package libraryexport
+
+import (
+	"errors"
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// Formats device profiles write tracks in. FormatOriginal keeps each track's
+// stored format.
+const (
+	FormatOriginal = "original"
+	FormatMP3      = "mp3"
+	FormatAAC      = "aac"
+	FormatOpus     = "opus"
+	FormatFLAC     = "flac"
+)
+
+// ErrInvalidProfile is returned for device profile settings that cannot be
+// used.
+var ErrInvalidProfile = errors.New("invalid device profile")
+
+type encoding struct {
+	codec       string
+	ext         string
+	contentType string
+	// Bitrates in kbps; all zero for lossless formats.
+	defaultKbps int
+	minKbps     int
+	maxKbps     int
+}
+
+var encodings = map[string]encoding{
+	FormatMP3:  {codec: "libmp3lame", ext: "mp3", contentType: "audio/mpeg", defaultKbps: 320, minKbps: 64, maxKbps: 320},
+	FormatAAC:  {codec: "aac", ext: "m4a", contentType: "audio/mp4", defaultKbps: 256, minKbps: 64, maxKbps: 320},
+	FormatOpus: {codec: "libopus", ext: "opus", contentType: "audio/ogg", defaultKbps: 160, minKbps: 32, maxKbps: 256},
+	FormatFLAC: {codec: "flac", ext: "flac", contentType: "audio/flac"},
+}
+
+// Profile is how tracks are written for one target device, such as "MP3 320,
+// no cover art over 500 KB" for a car stereo. The zero Profile writes stored
+// files in their own format with all their artwork.
+type Profile struct {
+	Format      string `json:"format,omitempty"`
+	BitrateKbps int    `json:"bitrate_kbps,omitempty"`
+	// MaxArtworkBytes leaves out cover art larger than this; 0 keeps cover
+	// art of any size.
+	MaxArtworkBytes int64 `json:"max_artwork_bytes,omitempty"`
+}
+
+// Normalize checks p and fills in its format's default bitrate.
+func (p Profile) Normalize() (Profile, error) {
+	if p.Format == "" {
+		p.Format = FormatOriginal
+	}
+	if p.MaxArtworkBytes < 0 {
+		return p, fmt.Errorf("%w: maximum artwork size cannot be negative", ErrInvalidProfile)
+	}
+	if p.Format == FormatOriginal {
+		if p.BitrateKbps != 0 {
+			return p, fmt.Errorf("%w: bitrate needs a format to convert to", ErrInvalidProfile)
+		}
+		return p, nil
+	}
+	enc, ok := encodings[p.Format]
+	switch {
+	case !ok:
+		return p, fmt.Errorf("%w: unknown format %q (want original, mp3, aac, opus, or flac)", ErrInvalidProfile, p.Format)
+	case enc.defaultKbps == 0 && p.BitrateKbps != 0:
+		return p, fmt.Errorf("%w: %s is lossless and takes no bitrate", ErrInvalidProfile, p.Format)
+	case enc.defaultKbps == 0:
+		return p, nil
+	case p.BitrateKbps == 0:
+		p.BitrateKbps = enc.defaultKbps
+	case p.BitrateKbps < enc.minKbps || p.BitrateKbps > enc.maxKbps:
+		return p, fmt.Errorf("%w: %s bitrate must be %d to %d kbps", ErrInvalidProfile, p.Format, enc.minKbps, enc.maxKbps)
+	}
+	return p, nil
+}
+
+// IsOriginal reports whether p leaves stored files as they are.
+func (p Profile) IsOriginal() bool {
+	return p.Fingerprint() == ""
+}
+
+// Fingerprint names p's settings, such as "mp3-320k-art512000", or is empty
+// when p leaves stored files as they are. Files written under one
+// fingerprint are reused for any profile with the same settings.
+func (p Profile) Fingerprint() string {
+	var parts []string
+	if enc, ok := encodings[p.Format]; ok {
+		parts = append(parts, p.Format)
+		if enc.defaultKbps != 0 {
+			parts = append(parts, strconv.Itoa(p.BitrateKbps)+"k")
+		}
+	}
+	if p.MaxArtworkBytes > 0 {
+		if len(parts) == 0 {
+			parts = append(parts, FormatOriginal)
+		}
+		parts = append(parts, "art"+strconv.FormatInt(p.MaxArtworkBytes, 10))
+	}
+	return strings.Join(parts, "-")
+}
+
+// Extension is the extension p writes a file stored with extension ext as.
+func (p Profile) Extension(ext string) string {
+	if enc, ok := encodings[p.Format]; ok {
+		return enc.ext
+	}
+	return ext
+}
+
+// ContentType is the media type of files p converts, or "" when p keeps
+// stored formats.
+func (p Profile) ContentType() string {
+	return encodings[p.Format].contentType
+}
+
+// keepsArtwork reports whether a cover of size bytes stays within p's limit.
+func (p Profile) keepsArtwork(size int64) bool {
+	return p.MaxArtworkBytes == 0 || size <= p.MaxArtworkBytes
+}
+
+// codecArgs are the ffmpeg arguments that encode the audio for p, copying any
+// cover picture as it is.
+func (p Profile) codecArgs() []string {
+	enc, ok := encodings[p.Format]
+	if !ok {
+		return []string{"-c", "copy"}
+	}
+	args := []string{"-c:a", enc.codec}
+	if enc.defaultKbps != 0 {
+		args = append(args, "-b:a", strconv.Itoa(p.BitrateKbps)+"k")
+	}
+	return append(args, "-c:v", "copy")
+}
@@ -0,0 +1,50 @@
+package libraryexport
+
+import (
+	"errors"
+	"testing"
+)
+
+func TestProfileNormalize(t *testing.T) {
+	tests := []struct {
+		in          Profile
+		want        Profile
+		fingerprint string
+		wantErr     bool
+	}{
+		{in: Profile{}, want: Profile{Format: FormatOriginal}},
+		{in: Profile{MaxArtworkBytes: 512000}, want: Profile{Format: FormatOriginal, MaxArtworkBytes: 512000}, fingerprint: "original-art512000"},
+		{in: Profile{Format: FormatMP3}, want: Profile{Format: FormatMP3, BitrateKbps: 320}, fingerprint: "mp3-320k"},
+		{in: Profile{Format: FormatOpus, BitrateKbps: 96, MaxArtworkBytes: 1}, want: Profile{Format: FormatOpus, BitrateKbps: 96, MaxArtworkBytes: 1}, fingerprint: "opus-96k-art1"},
+		{in: Profile{Format: FormatFLAC}, want: Profile{Format: FormatFLAC}, fingerprint: "flac"},
+		{in: Profile{Format: FormatFLAC, BitrateKbps: 320}, wantErr: true},
+		{in: Profile{Format: FormatMP3, BitrateKbps: 500}, wantErr: true},
+		{in: Profile{BitrateKbps: 320}, wantErr: true},
+		{in: Profile{Format: "wma"}, wantErr: true},
+		{in: Profile{MaxArtworkBytes: -1}, wantErr: true},
+	}
+	for _, tt := range tests {
+		got, err := tt.in.Normalize()
+		if tt.wantErr {
+			if !errors.Is(err, ErrInvalidProfile) {
+				t.Errorf("Normalize(%+v) error = %v, want ErrInvalidProfile", tt.in, err)
+			}
+			continue
+		}
+		if err != nil || got != tt.want {
+			t.Errorf("Normalize(%+v) = %+v, %v, want %+v", tt.in, got, err, tt.want)
+			continue
+		}
+		if fp := got.Fingerprint(); fp != tt.fingerprint {
+			t.Errorf("Fingerprint(%+v) = %q, want %q", got, fp, tt.fingerprint)
+		}
+	}
+
+	aac := Profile{Format: FormatAAC, BitrateKbps: 256}
+	if got := aac.Extension("flac"); got != "m4a" {
+		t.Errorf("AAC extension = %q, want m4a", got)
+	}
+	if got := (Profile{Format: FormatOriginal}).Extension("flac"); got != "flac" {
+		t.Errorf("original extension = %q, want flac", got)
+	}
+}
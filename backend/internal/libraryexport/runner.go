@@ -0,0 +1,714 @@
+package libraryexport
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"io/fs"
+	"log"
+	"os"
+	"path"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+// LibraryExport is the job kind that writes a user's selected tracks and
+// playlists to a folder.
+var LibraryExport = jobs.NewKind[LibraryExportPayload]("library_export")
+
+type LibraryExportPayload struct {
+	// Target is the folder to write, relative to the export directory.
+	Target      string  `json:"target"`
+	Template    string  `json:"template"`
+	TrackIDs    []int64 `json:"track_ids,omitempty"`
+	PlaylistIDs []int64 `json:"playlist_ids,omitempty"`
+	// Profile is the device profile's settings when the export was queued;
+	// nil writes stored files as they are.
+	Profile *Profile `json:"profile,omitempty"`
+}
+
+// LibraryExportProgress is the progress detail of library export jobs.
+type LibraryExportProgress struct {
+	Exported  int `json:"exported"`
+	Unchanged int `json:"unchanged"`
+	Failed    int `json:"failed"`
+	Playlists int `json:"playlists"`
+}
+
+// Queue queues library export jobs.
+type Queue struct {
+	store *jobs.Store
+}
+
+func NewQueue(store *jobs.Store) *Queue {
+	return &Queue{store: store}
+}
+
+// Enqueue validates payload and queues it for the user. A user runs at most
+// one export to a folder at a time; a second request while one is queued or
+// running returns jobs.ErrDuplicate.
+func (q *Queue) Enqueue(ctx context.Context, userID uuid.UUID, payload LibraryExportPayload) (*jobs.Job, error) {
+	payload.Target = CleanTarget(payload.Target)
+	if strings.TrimSpace(payload.Template) == "" {
+		payload.Template = DefaultPathTemplate
+	}
+	if _, err := ParsePathTemplate(payload.Template); err != nil {
+		return nil, err
+	}
+	if payload.Profile != nil {
+		profile, err := payload.Profile.Normalize()
+		if err != nil {
+			return nil, fmt.Errorf("%w: %v", ErrInvalidExport, err)
+		}
+		payload.Profile = &profile
+	}
+	switch selected := len(payload.TrackIDs) + len(payload.PlaylistIDs); {
+	case selected == 0:
+		return nil, fmt.Errorf("%w: select at least one track or playlist", ErrInvalidExport)
+	case selected > MaxSelection:
+		return nil, fmt.Errorf("%w: select at most %d tracks and playlists", ErrInvalidExport, MaxSelection)
+	}
+	// The manifest makes a retry skip the tracks the failed attempt wrote.
+	return LibraryExport.Enqueue(ctx, q.store, payload, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   userID.String() + ":export:" + payload.Target,
+	})
+}
+
+// TrackLoader loads tracks. db.TrackRepository satisfies this interface.
+type TrackLoader interface {
+	GetByID(ctx context.Context, id int64) (*db.Track, error)
+}
+
+// Library checks library membership. db.LibraryRepository satisfies this
+// interface.
+type Library interface {
+	IsTrackInLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (bool, error)
+}
+
+// Playlists loads playlists with their tracks. db.PlaylistRepository
+// satisfies this interface.
+type Playlists interface {
+	GetByIDWithTracks(ctx context.Context, id int64) (*db.PlaylistWithTracks, error)
+}
+
+// Artwork finds a track's cover. db.ArtworkRepository satisfies this
+// interface.
+type Artwork interface {
+	GetForTrack(ctx context.Context, trackID int64) (*db.Artwork, error)
+}
+
+// Objects reads stored audio and artwork. *storage.Client satisfies this
+// interface.
+type Objects interface {
+	GetObject(ctx context.Context, key string) (io.ReadCloser, *storage.ObjectInfo, error)
+}
+
+// Notes loads a user's notes on tracks, albums and playlists.
+// db.NoteRepository satisfies this interface.
+type Notes interface {
+	NotesFor(ctx context.Context, userID uuid.UUID, targetType string, targetIDs []string) (map[string]string, error)
+}
+
+// RunnerConfig holds what a Runner reads from.
+type RunnerConfig struct {
+	Tracks    TrackLoader
+	Library   Library
+	Playlists Playlists
+	Artwork   Artwork
+	Objects   Objects
+	Tagger    Tagger
+	// Notes, when set, writes the user's notes on what was exported to
+	// notes.json in the folder.
+	Notes Notes
+}
+
+// progressReporter records a job's progress. *jobs.Progress satisfies it.
+type progressReporter interface {
+	Report(ctx context.Context, current, total int, detail any) error
+}
+
+// Runner writes export jobs below an export directory.
+type Runner struct {
+	root string
+	cfg  RunnerConfig
+}
+
+func NewRunner(root string, cfg RunnerConfig) *Runner {
+	return &Runner{root: root, cfg: cfg}
+}
+
+// Register runs library export jobs on w.
+func (r *Runner) Register(w *jobs.Worker) {
+	LibraryExport.Handle(w, func(ctx context.Context, job *jobs.Job, payload LibraryExportPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, job.UserID.UUID, payload, progress)
+	})
+}
+
+// Run performs one export. Tracks that fail are logged and counted rather
+// than ending the job.
+func (r *Runner) Run(ctx context.Context, jobID, userID uuid.UUID, payload LibraryExportPayload, progress progressReporter) error {
+	template, err := ParsePathTemplate(payload.Template)
+	if err != nil {
+		return jobs.Permanent(err)
+	}
+	var profile Profile
+	if payload.Profile != nil {
+		profile = *payload.Profile
+	}
+	dir := filepath.Join(r.root, filepath.FromSlash(CleanTarget(payload.Target)))
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return fmt.Errorf("create export folder: %w", err)
+	}
+	manifest, err := loadManifest(dir)
+	if err != nil {
+		return err
+	}
+	scratch, err := os.MkdirTemp("", "omp-export-*")
+	if err != nil {
+		return err
+	}
+	defer os.RemoveAll(scratch)
+
+	var counts LibraryExportProgress
+	tracks, playlists, err := r.selection(ctx, jobID, userID, payload, &counts)
+	if err != nil {
+		return err
+	}
+	if err := progress.Report(ctx, 0, len(tracks), counts); err != nil {
+		return err
+	}
+	w := &writer{
+		cfg:      r.cfg,
+		dir:      dir,
+		scratch:  scratch,
+		template: template,
+		profile:  profile,
+		manifest: manifest,
+		covers:   map[int64]string{},
+	}
+	paths := make(map[int64]string, len(tracks))
+	for i, track := range tracks {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		rel, unchanged, err := w.track(ctx, track)
+		switch {
+		case err != nil:
+			log.Printf("Library export job %s: failed to export track %d: %v", jobID, track.ID, err)
+			counts.Failed++
+		case unchanged:
+			counts.Unchanged++
+		default:
+			counts.Exported++
+		}
+		if rel != "" {
+			paths[track.ID] = rel
+		}
+		if err := progress.Report(ctx, i+1, len(tracks), counts); err != nil {
+			return err
+		}
+	}
+	if err := manifest.save(dir); err != nil {
+		return err
+	}
+
+	names := map[string]bool{}
+	for _, playlist := range playlists {
+		if err := writePlaylist(dir, playlist, paths, names); err != nil {
+			return fmt.Errorf("write playlist %q: %w", playlist.Name, err)
+		}
+		counts.Playlists++
+	}
+	if r.cfg.Notes != nil {
+		if err := r.writeNotes(ctx, dir, userID, tracks, playlists, paths); err != nil {
+			return fmt.Errorf("write notes: %w", err)
+		}
+	}
+	return progress.Report(ctx, len(tracks), len(tracks), counts)
+}
+
+// selection loads the selected tracks and the tracks of the selected
+// playlists, each once. Tracks outside the user's library count as failed;
+// playlists the user does not own are skipped.
+func (r *Runner) selection(ctx context.Context, jobID, userID uuid.UUID, payload LibraryExportPayload, counts *LibraryExportProgress) ([]db.Track, []*db.PlaylistWithTracks, error) {
+	var tracks []db.Track
+	seen := map[int64]bool{}
+	for _, id := range payload.TrackIDs {
+		if seen[id] {
+			continue
+		}
+		seen[id] = true
+		inLibrary, err := r.cfg.Library.IsTrackInLibrary(ctx, userID, id)
+		if err != nil {
+			return nil, nil, fmt.Errorf("check library for track %d: %w", id, err)
+		}
+		if !inLibrary {
+			counts.Failed++
+			continue
+		}
+		track, err := r.cfg.Tracks.GetByID(ctx, id)
+		if errors.Is(err, db.ErrTrackNotFound) {
+			counts.Failed++
+			continue
+		}
+		if err != nil {
+			return nil, nil, fmt.Errorf("load track %d: %w", id, err)
+		}
+		tracks = append(tracks, *track)
+	}
+
+	var playlists []*db.PlaylistWithTracks
+	for _, id := range payload.PlaylistIDs {
+		playlist, err := r.cfg.Playlists.GetByIDWithTracks(ctx, id)
+		if errors.Is(err, db.ErrPlaylistNotFound) || (err == nil && playlist.UserID != userID) {
+			log.Printf("Library export job %s: playlist %d not found", jobID, id)
+			continue
+		}
+		if err != nil {
+			return nil, nil, fmt.Errorf("load playlist %d: %w", id, err)
+		}
+		playlists = append(playlists, playlist)
+		for _, track := range playlist.Tracks {
+			if !seen[track.ID] {
+				seen[track.ID] = true
+				tracks = append(tracks, track)
+			}
+		}
+	}
+	return tracks, playlists, nil
+}
+
+// writer exports tracks into one folder.
+type writer struct {
+	cfg      RunnerConfig
+	dir      string
+	scratch  string
+	template *PathTemplate
+	profile  Profile
+	manifest *manifest
+	// covers caches downloaded artwork by artwork ID; "" means none.
+	covers map[int64]string
+}
+
+// track writes one track, returning its path relative to the folder and
+// whether an up-to-date copy was already there.
+func (w *writer) track(ctx context.Context, track db.Track) (string, bool, error) {
+	id := strconv.FormatInt(track.ID, 10)
+	previous, ok := w.manifest.Tracks[id]
+	if ok && previous.UpdatedAt.Equal(track.UpdatedAt) && previous.Profile == w.profile.Fingerprint() && fileExists(filepath.Join(w.dir, filepath.FromSlash(previous.Path))) {
+		return previous.Path, true, nil
+	}
+	key := strings.TrimSpace(track.StorageKey.String)
+	if key == "" {
+		return "", false, errors.New("track has no stored audio")
+	}
+	ext := strings.ToLower(strings.TrimPrefix(path.Ext(key), "."))
+	if ext == "" {
+		ext = "bin"
+	}
+	src := filepath.Join(w.scratch, "track."+ext)
+	if err := w.download(ctx, key, src); err != nil {
+		return "", false, err
+	}
+	defer os.Remove(src)
+	fileTags, err := w.cfg.Tagger.ReadTags(ctx, src)
+	if err != nil {
+		return "", false, err
+	}
+	ext = w.profile.Extension(ext)
+
+	rel := w.manifest.claim(id, w.template.Render(templateValues(track, fileTags, ext)))
+	dst := filepath.Join(w.dir, filepath.FromSlash(rel))
+	if err := os.MkdirAll(filepath.Dir(dst), 0o755); err != nil {
+		return "", false, err
+	}
+	cover, err := w.cover(ctx, track.ID)
+	if err != nil {
+		return "", false, err
+	}
+	embed := cover
+	if !embedsArtwork(ext) {
+		embed = ""
+	}
+	// ffmpeg picks the output format from the extension, so the partial file
+	// keeps it.
+	part := filepath.Join(filepath.Dir(dst), ".omp-part-"+filepath.Base(dst))
+	if err := w.cfg.Tagger.Write(ctx, src, embed, part, writeTags(track, ext), w.profile); err != nil {
+		os.Remove(part)
+		return "", false, err
+	}
+	if err := os.Rename(part, dst); err != nil {
+		return "", false, err
+	}
+	if cover != "" && embed == "" {
+		if err := copyIfMissing(cover, filepath.Join(filepath.Dir(dst), "cover.jpg")); err != nil {
+			return "", false, err
+		}
+	}
+	// A rename in the source library moves the file; drop the old copy.
+	if ok && previous.Path != rel {
+		os.Remove(filepath.Join(w.dir, filepath.FromSlash(previous.Path)))
+		delete(w.manifest.owners, strings.ToLower(previous.Path))
+	}
+	w.manifest.Tracks[id] = manifestEntry{Path: rel, UpdatedAt: track.UpdatedAt, Profile: w.profile.Fingerprint()}
+	return rel, false, nil
+}
+
+// cover downloads the largest rendition of the track's artwork once per
+// job, returning "" when the track has none or it is over the profile's size
+// limit.
+func (w *writer) cover(ctx context.Context, trackID int64) (string, error) {
+	artwork, err := w.cfg.Artwork.GetForTrack(ctx, trackID)
+	if errors.Is(err, db.ErrArtworkNotFound) {
+		return "", nil
+	}
+	if err != nil {
+		return "", err
+	}
+	if cached, ok := w.covers[artwork.ID]; ok {
+		return cached, nil
+	}
+	var largest *db.ArtworkVariant
+	for i := range artwork.Variants {
+		if largest == nil || artwork.Variants[i].Width > largest.Width {
+			largest = &artwork.Variants[i]
+		}
+	}
+	file := ""
+	if largest != nil {
+		file = filepath.Join(w.scratch, fmt.Sprintf("cover-%d.jpg", artwork.ID))
+		if err := w.download(ctx, largest.StorageKey, file); err != nil {
+			return "", err
+		}
+		info, err := os.Stat(file)
+		if err != nil {
+			return "", err
+		}
+		if !w.profile.keepsArtwork(info.Size()) {
+			file = ""
+		}
+	}
+	w.covers[artwork.ID] = file
+	return file, nil
+}
+
+func (w *writer) download(ctx context.Context, key, dst string) error {
+	object, _, err := w.cfg.Objects.GetObject(ctx, key)
+	if err != nil {
+		return fmt.Errorf("read %s: %w", key, err)
+	}
+	defer object.Close()
+	file, err := os.Create(dst)
+	if err != nil {
+		return err
+	}
+	if _, err := io.Copy(file, object); err != nil {
+		file.Close()
+		return fmt.Errorf("read %s: %w", key, err)
+	}
+	return file.Close()
+}
+
+// templateValues fills the path template from the track, falling back to the
+// file's own tags for what the library does not store.
+func templateValues(track db.Track, tags map[string]string, ext string) map[string]string {
+	artist := firstNonEmpty(track.Artist.String, tags["artist"], "Unknown Artist")
+	values := map[string]string{
+		"artist":      artist,
+		"albumartist": firstNonEmpty(tags["album_artist"], tags["albumartist"], artist),
+		"album":       firstNonEmpty(track.Album.String, tags["album"], "Unknown Album"),
+		"title":       firstNonEmpty(track.Title, tags["title"], "Track "+strconv.FormatInt(track.ID, 10)),
+		"disc":        leadingNumber(firstNonEmpty(tags["disc"], tags["discnumber"])),
+		"genre":       tags["genre"],
+		"ext":         ext,
+	}
+	if n := leadingNumber(firstNonEmpty(tags["track"], tags["tracknumber"])); n != "" {
+		if len(n) < 2 {
+			n = "0" + n
+		}
+		values["track"] = n
+	}
+	if year := firstNonEmpty(tags["date"], tags["year"], tags["originaldate"]); len(year) >= 4 {
+		values["year"] = leadingNumber(year[:4])
+	}
+	return values
+}
+
+// writeTags are the tags written over the stored file's own.
+func writeTags(track db.Track, ext string) map[string]string {
+	tags := map[string]string{"title": track.Title}
+	if track.Artist.Valid && track.Artist.String != "" {
+		tags["artist"] = track.Artist.String
+	}
+	if track.Album.Valid && track.Album.String != "" {
+		tags["album"] = track.Album.String
+	}
+	recording, release, artist := mbidTags(ext)
+	if recording != "" {
+		for name, id := range map[string]*uuid.UUID{recording: track.MBRecordingID, release: track.MBReleaseID, artist: track.MBArtistID} {
+			if id != nil {
+				tags[name] = id.String()
+			}
+		}
+	}
+	return tags
+}
+
+// leadingNumber returns the number a tag such as "3/12" starts with, without
+// leading zeros.
+func leadingNumber(value string) string {
+	end := 0
+	for end < len(value) && value[end] >= '0' && value[end] <= '9' {
+		end++
+	}
+	n, err := strconv.Atoi(value[:end])
+	if err != nil || n <= 0 {
+		return ""
+	}
+	return strconv.Itoa(n)
+}
+
+func firstNonEmpty(values ...string) string {
+	for _, value := range values {
+		if value = strings.TrimSpace(value); value != "" {
+			return value
+		}
+	}
+	return ""
+}
+
+// writePlaylist writes playlist as an M3U8 file of paths relative to dir,
+// leaving out tracks that failed to export.
+func writePlaylist(dir string, playlist *db.PlaylistWithTracks, paths map[int64]string, names map[string]bool) error {
+	base := sanitizeComponent(playlist.Name, false)
+	if base == "" {
+		base = "Playlist"
+	}
+	name := base
+	for n := 2; names[strings.ToLower(name)]; n++ {
+		name = fmt.Sprintf("%s (%d)", base, n)
+	}
+	names[strings.ToLower(name)] = true
+
+	return writeFile(filepath.Join(dir, name+".m3u8"), func(out io.Writer) error {
+		if _, err := io.WriteString(out, "#EXTM3U\n"); err != nil {
+			return err
+		}
+		for _, track := range playlist.Tracks {
+			rel, ok := paths[track.ID]
+			if !ok {
+				continue
+			}
+			seconds := -1
+			if track.DurationMs.Valid {
+				seconds = int(track.DurationMs.Int32 / 1000)
+			}
+			title := track.Title
+			if track.Artist.String != "" {
+				title = track.Artist.String + " - " + title
+			}
+			if _, err := fmt.Fprintf(out, "#EXTINF:%d,%s\n%s\n", seconds, strings.ReplaceAll(title, "\n", " "), rel); err != nil {
+				return err
+			}
+		}
+		return nil
+	})
+}
+
+// notesName is the file in an export folder holding the user's notes.
+const notesName = "notes.json"
+
+// exportNote is one entry of notes.json. Path is the exported file of a
+// noted track.
+type exportNote struct {
+	Type  string `json:"type"`
+	ID    string `json:"id"`
+	Title string `json:"title"`
+	Path  string `json:"path,omitempty"`
+	Note  string `json:"note"`
+}
+
+// writeNotes writes the user's notes on the exported tracks, their albums and
+// the exported playlists to notes.json, or removes a stale one when there are
+// none.
+func (r *Runner) writeNotes(ctx context.Context, dir string, userID uuid.UUID, tracks []db.Track, playlists []*db.PlaylistWithTracks, paths map[int64]string) error {
+	var trackIDs, albumIDs []string
+	albums := map[string]string{}
+	for _, track := range tracks {
+		if _, ok := paths[track.ID]; !ok {
+			continue
+		}
+		trackIDs = append(trackIDs, strconv.FormatInt(track.ID, 10))
+		if track.MBReleaseID != nil {
+			id := track.MBReleaseID.String()
+			if _, ok := albums[id]; !ok {
+				albums[id] = track.Album.String
+				albumIDs = append(albumIDs, id)
+			}
+		}
+	}
+	playlistIDs := make([]string, 0, len(playlists))
+	for _, playlist := range playlists {
+		playlistIDs = append(playlistIDs, strconv.FormatInt(playlist.ID, 10))
+	}
+
+	var entries []exportNote
+	trackNotes, err := r.cfg.Notes.NotesFor(ctx, userID, db.NoteTargetTrack, trackIDs)
+	if err != nil {
+		return err
+	}
+	for _, track := range tracks {
+		id := strconv.FormatInt(track.ID, 10)
+		if note, ok := trackNotes[id]; ok {
+			entries = append(entries, exportNote{Type: db.NoteTargetTrack, ID: id, Title: track.Title, Path: paths[track.ID], Note: note})
+		}
+	}
+	albumNotes, err := r.cfg.Notes.NotesFor(ctx, userID, db.NoteTargetAlbum, albumIDs)
+	if err != nil {
+		return err
+	}
+	for _, id := range albumIDs {
+		if note, ok := albumNotes[id]; ok {
+			entries = append(entries, exportNote{Type: db.NoteTargetAlbum, ID: id, Title: albums[id], Note: note})
+		}
+	}
+	playlistNotes, err := r.cfg.Notes.NotesFor(ctx, userID, db.NoteTargetPlaylist, playlistIDs)
+	if err != nil {
+		return err
+	}
+	for i, playlist := range playlists {
+		if note, ok := playlistNotes[playlistIDs[i]]; ok {
+			entries = append(entries, exportNote{Type: db.NoteTargetPlaylist, ID: playlistIDs[i], Title: playlist.Name, Note: note})
+		}
+	}
+
+	path := filepath.Join(dir, notesName)
+	if len(entries) == 0 {
+		if err := os.Remove(path); err != nil && !errors.Is(err, fs.ErrNotExist) {
+			return err
+		}
+		return nil
+	}
+	return writeFile(path, func(out io.Writer) error {
+		enc := json.NewEncoder(out)
+		enc.SetIndent("", "  ")
+		return enc.Encode(entries)
+	})
+}
+
+// writeFile streams what fill writes into the file at path through a fixed
+// buffer, so large playlists and manifests are never held whole in memory.
+// New files get mode 0644, as os.WriteFile gave them before.
+func writeFile(path string, fill func(out io.Writer) error) error {
+	file, err := os.OpenFile(path, os.O_CREATE|os.O_TRUNC|os.O_WRONLY, 0o644)
+	if err != nil {
+		return err
+	}
+	buffered := bufio.NewWriter(file)
+	if err := fill(buffered); err != nil {
+		file.Close()
+		return err
+	}
+	if err := buffered.Flush(); err != nil {
+		file.Close()
+		return err
+	}
+	return file.Close()
+}
+
+func fileExists(path string) bool {
+	info, err := os.Stat(path)
+	return err == nil && info.Mode().IsRegular()
+}
+
+func copyIfMissing(src, dst string) error {
+	if _, err := os.Stat(dst); err == nil || !errors.Is(err, fs.ErrNotExist) {
+		return err
+	}
+	in, err := os.Open(src)
+	if err != nil {
+		return err
+	}
+	defer in.Close()
+	return writeFile(dst, func(out io.Writer) error {
+		_, err := io.Copy(out, in)
+		return err
+	})
+}
+
+// manifestName is the file in an export folder recording what was written.
+const manifestName = ".omp-export.json"
+
+type manifestEntry struct {
+	Path      string    `json:"path"`
+	UpdatedAt time.Time `json:"updated_at"`
+	// Profile is the fingerprint of the profile the file was written with.
+	Profile string `json:"profile,omitempty"`
+}
+
+type manifest struct {
+	Tracks map[string]manifestEntry `json:"tracks"`
+	// owners maps lower-cased paths to the track ID holding them, since FAT
+	// file systems ignore case.
+	owners map[string]string
+}
+
+func loadManifest(dir string) (*manifest, error) {
+	m := &manifest{Tracks: map[string]manifestEntry{}}
+	data, err := os.ReadFile(filepath.Join(dir, manifestName))
+	if err != nil && !errors.Is(err, fs.ErrNotExist) {
+		return nil, err
+	}
+	if err == nil {
+		if err := json.Unmarshal(data, m); err != nil {
+			return nil, fmt.Errorf("read %s: %w", manifestName, err)
+		}
+		if m.Tracks == nil {
+			m.Tracks = map[string]manifestEntry{}
+		}
+	}
+	m.owners = make(map[string]string, len(m.Tracks))
+	for id, entry := range m.Tracks {
+		m.owners[strings.ToLower(entry.Path)] = id
+	}
+	return m, nil
+}
+
+// claim reserves rel for track id, adding " (2)", " (3)", and so on before
+// the extension when another track already holds it.
+func (m *manifest) claim(id, rel string) string {
+	ext := path.Ext(rel)
+	base := strings.TrimSuffix(rel, ext)
+	candidate := rel
+	for n := 2; ; n++ {
+		owner, taken := m.owners[strings.ToLower(candidate)]
+		if !taken || owner == id {
+			break
+		}
+		candidate = fmt.Sprintf("%s (%d)%s", base, n, ext)
+	}
+	m.owners[strings.ToLower(candidate)] = id
+	return candidate
+}
+
+func (m *manifest) save(dir string) error {
+	return writeFile(filepath.Join(dir, manifestName), func(out io.Writer) error {
+		enc := json.NewEncoder(out)
+		enc.SetIndent("", "  ")
+		return enc.Encode(m)
+	})
+}
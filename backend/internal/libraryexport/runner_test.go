@@ -0,0 +1,336 @@
+package libraryexport
+
+import (
+	"context"
+	"database/sql"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/storage"
+)
+
+type fakeObjects map[string]string
+
+func (f fakeObjects) GetObject(_ context.Context, key string) (io.ReadCloser, *storage.ObjectInfo, error) {
+	data, ok := f[key]
+	if !ok {
+		return nil, nil, os.ErrNotExist
+	}
+	return io.NopCloser(strings.NewReader(data)), &storage.ObjectInfo{}, nil
+}
+
+// fakeTagger reads tags keyed by file content and writes the content with
+// the tags, cover, and conversion it was given appended.
+type fakeTagger struct {
+	tags   map[string]map[string]string
+	writes int
+}
+
+func (f *fakeTagger) ReadTags(_ context.Context, path string) (map[string]string, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	return f.tags[string(data)], nil
+}
+
+func (f *fakeTagger) Write(_ context.Context, src, cover, dst string, tags map[string]string, profile Profile) error {
+	f.writes++
+	data, err := os.ReadFile(src)
+	if err != nil {
+		return err
+	}
+	out := string(data) + " title=" + tags["title"] + " mbid=" + tags["MusicBrainz Track Id"]
+	if cover != "" {
+		image, err := os.ReadFile(cover)
+		if err != nil {
+			return err
+		}
+		out += " cover=" + string(image)
+	}
+	if !profile.IsOriginal() {
+		out += " as=" + profile.Fingerprint()
+	}
+	return os.WriteFile(dst, []byte(out), 0o644)
+}
+
+type fakeExportLibrary struct {
+	tracks    map[int64]*db.Track
+	playlists map[int64]*db.PlaylistWithTracks
+	artwork   map[int64]*db.Artwork
+}
+
+func (f *fakeExportLibrary) GetByID(_ context.Context, id int64) (*db.Track, error) {
+	if track, ok := f.tracks[id]; ok {
+		return track, nil
+	}
+	return nil, db.ErrTrackNotFound
+}
+
+func (f *fakeExportLibrary) IsTrackInLibrary(_ context.Context, _ uuid.UUID, trackID int64) (bool, error) {
+	_, ok := f.tracks[trackID]
+	return ok, nil
+}
+
+func (f *fakeExportLibrary) GetByIDWithTracks(_ context.Context, id int64) (*db.PlaylistWithTracks, error) {
+	if playlist, ok := f.playlists[id]; ok {
+		return playlist, nil
+	}
+	return nil, db.ErrPlaylistNotFound
+}
+
+func (f *fakeExportLibrary) GetForTrack(_ context.Context, trackID int64) (*db.Artwork, error) {
+	if artwork, ok := f.artwork[trackID]; ok {
+		return artwork, nil
+	}
+	return nil, db.ErrArtworkNotFound
+}
+
+type fakeProgress struct {
+	last    LibraryExportProgress
+	current int
+	total   int
+}
+
+func (p *fakeProgress) Report(_ context.Context, current, total int, detail any) error {
+	p.last, p.current, p.total = detail.(LibraryExportProgress), current, total
+	return nil
+}
+
+func exportTrack(id int64, title, artist, album, key string) *db.Track {
+	return &db.Track{
+		ID:         id,
+		Title:      title,
+		Artist:     sql.NullString{String: artist, Valid: artist != ""},
+		Album:      sql.NullString{String: album, Valid: album != ""},
+		DurationMs: sql.NullInt32{Int32: 245000, Valid: true},
+		StorageKey: sql.NullString{String: key, Valid: key != ""},
+		UpdatedAt:  time.Date(2024, 5, 1, 0, 0, 0, 0, time.UTC),
+	}
+}
+
+func readExport(t *testing.T, root, rel string) string {
+	t.Helper()
+	data, err := os.ReadFile(filepath.Join(root, filepath.FromSlash(rel)))
+	if err != nil {
+		t.Fatalf("read %s: %v", rel, err)
+	}
+	return string(data)
+}
+
+func TestRunnerExportsTracksAndPlaylists(t *testing.T) {
+	userID := uuid.New()
+	recording := uuid.MustParse("11111111-1111-1111-1111-111111111111")
+	starfire := exportTrack(1, "Starfire", "Low", "Secret Name", "tracks/local/a.mp3")
+	starfire.MBRecordingID = &recording
+	lullaby := exportTrack(2, "Lullaby", "Low", "Secret Name", "tracks/local/b.ogg")
+	missing := exportTrack(3, "Gone", "Low", "", "")
+	dupe := exportTrack(4, "Starfire", "Low", "Secret Name", "tracks/local/c.mp3")
+	library := &fakeExportLibrary{
+		tracks: map[int64]*db.Track{1: starfire, 2: lullaby, 3: missing, 4: dupe},
+		playlists: map[int64]*db.PlaylistWithTracks{
+			8: {Playlist: db.Playlist{ID: 8, UserID: userID, Name: "Night: Drive"}, Tracks: []db.Track{*lullaby, *missing, *starfire}},
+			9: {Playlist: db.Playlist{ID: 9, UserID: uuid.New(), Name: "Someone else's"}, Tracks: []db.Track{*dupe}},
+		},
+		artwork: map[int64]*db.Artwork{
+			1: {ID: 5, Variants: []db.ArtworkVariant{{Name: "small", StorageKey: "art/small.jpg", Width: 300}, {Name: "large", StorageKey: "art/large.jpg", Width: 1200}}},
+			2: {ID: 5, Variants: []db.ArtworkVariant{{Name: "large", StorageKey: "art/large.jpg", Width: 1200}}},
+		},
+	}
+	objects := fakeObjects{
+		"tracks/local/a.mp3": "audio-a",
+		"tracks/local/b.ogg": "audio-b",
+		"tracks/local/c.mp3": "audio-c",
+		"art/large.jpg":      "jpeg",
+	}
+	tagger := &fakeTagger{tags: map[string]map[string]string{
+		"audio-a": {"track": "3/10", "date": "1996-03-19"},
+		"audio-b": {"tracknumber": "7"},
+		"audio-c": {"track": "3"},
+	}}
+	root := t.TempDir()
+	runner := NewRunner(root, RunnerConfig{Tracks: library, Library: library, Playlists: library, Artwork: library, Objects: objects, Tagger: tagger})
+	payload := LibraryExportPayload{Target: "usb", Template: DefaultPathTemplate, TrackIDs: []int64{4, 1, 99}, PlaylistIDs: []int64{8, 9, 10}}
+
+	progress := &fakeProgress{}
+	if err := runner.Run(context.Background(), uuid.New(), userID, payload, progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	want := LibraryExportProgress{Exported: 3, Failed: 2, Playlists: 1}
+	if progress.last != want || progress.current != 4 || progress.total != 4 {
+		t.Fatalf("progress = %+v (%d/%d), want %+v", progress.last, progress.current, progress.total, want)
+	}
+
+	dir := filepath.Join(root, "usb")
+	if got := readExport(t, dir, "Low/Secret Name/03 Starfire (2).mp3"); got != "audio-a title=Starfire mbid=11111111-1111-1111-1111-111111111111 cover=jpeg" {
+		t.Errorf("Starfire = %q", got)
+	}
+	if got := readExport(t, dir, "Low/Secret Name/03 Starfire.mp3"); got != "audio-c title=Starfire mbid=" {
+		t.Errorf("duplicate Starfire = %q", got)
+	}
+	// Ogg gets no embedded picture, so the cover goes beside it.
+	if got := readExport(t, dir, "Low/Secret Name/07 Lullaby.ogg"); got != "audio-b title=Lullaby mbid=" {
+		t.Errorf("Lullaby = %q", got)
+	}
+	if got := readExport(t, dir, "Low/Secret Name/cover.jpg"); got != "jpeg" {
+		t.Errorf("cover.jpg = %q", got)
+	}
+	wantPlaylist := "#EXTM3U\n" +
+		"#EXTINF:245,Low - Lullaby\nLow/Secret Name/07 Lullaby.ogg\n" +
+		"#EXTINF:245,Low - Starfire\nLow/Secret Name/03 Starfire (2).mp3\n"
+	if got := readExport(t, dir, "Night_ Drive.m3u8"); got != wantPlaylist {
+		t.Errorf("playlist = %q, want %q", got, wantPlaylist)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "Someone else's.m3u8")); err == nil {
+		t.Error("exported a playlist the user does not own")
+	}
+
+	// Exporting again rewrites only the track that changed.
+	lullaby.UpdatedAt = lullaby.UpdatedAt.Add(time.Hour)
+	library.playlists[8].Tracks[0] = *lullaby
+	tagger.writes = 0
+	if err := runner.Run(context.Background(), uuid.New(), userID, payload, progress); err != nil {
+		t.Fatalf("second Run() error = %v", err)
+	}
+	if want := (LibraryExportProgress{Exported: 1, Unchanged: 2, Failed: 2, Playlists: 1}); progress.last != want || tagger.writes != 1 {
+		t.Fatalf("second run progress = %+v with %d writes, want %+v", progress.last, tagger.writes, want)
+	}
+	if got := readExport(t, dir, "Night_ Drive.m3u8"); got != wantPlaylist {
+		t.Errorf("second run playlist = %q", got)
+	}
+
+	// A device profile converts everything and drops covers over its limit.
+	payload.Profile = &Profile{Format: FormatMP3, BitrateKbps: 320, MaxArtworkBytes: 3}
+	if err := runner.Run(context.Background(), uuid.New(), userID, payload, progress); err != nil {
+		t.Fatalf("profile Run() error = %v", err)
+	}
+	if want := (LibraryExportProgress{Exported: 3, Failed: 2, Playlists: 1}); progress.last != want {
+		t.Fatalf("profile run progress = %+v, want %+v", progress.last, want)
+	}
+	if got := readExport(t, dir, "Low/Secret Name/07 Lullaby.mp3"); got != "audio-b title=Lullaby mbid= as=mp3-320k-art3" {
+		t.Errorf("converted Lullaby = %q", got)
+	}
+	if got := readExport(t, dir, "Low/Secret Name/03 Starfire (2).mp3"); got != "audio-a title=Starfire mbid=11111111-1111-1111-1111-111111111111 as=mp3-320k-art3" {
+		t.Errorf("converted Starfire = %q", got)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "Low", "Secret Name", "07 Lullaby.ogg")); err == nil {
+		t.Error("kept the Ogg copy after converting it")
+	}
+}
+
+type fakeNotes map[string]map[string]string
+
+func (f fakeNotes) NotesFor(_ context.Context, _ uuid.UUID, targetType string, targetIDs []string) (map[string]string, error) {
+	notes := map[string]string{}
+	for _, id := range targetIDs {
+		if note, ok := f[targetType][id]; ok {
+			notes[id] = note
+		}
+	}
+	return notes, nil
+}
+
+func TestRunnerWritesNotesOnExportedItems(t *testing.T) {
+	userID := uuid.New()
+	release := uuid.MustParse("22222222-2222-2222-2222-222222222222")
+	starfire := exportTrack(1, "Starfire", "Low", "Secret Name", "tracks/local/a.mp3")
+	starfire.MBReleaseID = &release
+	missing := exportTrack(3, "Gone", "Low", "", "")
+	library := &fakeExportLibrary{
+		tracks: map[int64]*db.Track{1: starfire, 3: missing},
+		playlists: map[int64]*db.PlaylistWithTracks{
+			8: {Playlist: db.Playlist{ID: 8, UserID: userID, Name: "Night Drive"}, Tracks: []db.Track{*starfire}},
+		},
+	}
+	notes := fakeNotes{
+		db.NoteTargetTrack:    {"1": "Ripped from the 1996 CD", "3": "Never exported"},
+		db.NoteTargetAlbum:    {release.String(): "First pressing"},
+		db.NoteTargetPlaylist: {"8": "For long drives"},
+	}
+	root := t.TempDir()
+	runner := NewRunner(root, RunnerConfig{
+		Tracks:    library,
+		Library:   library,
+		Playlists: library,
+		Artwork:   library,
+		Objects:   fakeObjects{"tracks/local/a.mp3": "audio-a"},
+		Tagger:    &fakeTagger{},
+		Notes:     notes,
+	})
+	payload := LibraryExportPayload{Target: "usb", Template: "{artist}/{title}.{ext}", TrackIDs: []int64{1, 3}, PlaylistIDs: []int64{8}}
+	if err := runner.Run(context.Background(), uuid.New(), userID, payload, &fakeProgress{}); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+
+	dir := filepath.Join(root, "usb")
+	got := readExport(t, dir, notesName)
+	for _, want := range []string{`"path": "Low/Starfire.mp3"`, "Ripped from the 1996 CD", `"title": "Secret Name"`, "First pressing", "For long drives"} {
+		if !strings.Contains(got, want) {
+			t.Errorf("notes.json missing %q:\n%s", want, got)
+		}
+	}
+	if strings.Contains(got, "Never exported") {
+		t.Errorf("notes.json has the note of a track that failed to export:\n%s", got)
+	}
+
+	// Notes removed since the last export leave no stale file behind.
+	delete(notes, db.NoteTargetTrack)
+	delete(notes, db.NoteTargetAlbum)
+	delete(notes, db.NoteTargetPlaylist)
+	if err := runner.Run(context.Background(), uuid.New(), userID, payload, &fakeProgress{}); err != nil {
+		t.Fatalf("second Run() error = %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, notesName)); err == nil {
+		t.Error("kept notes.json after every note was removed")
+	}
+}
+
+func TestWriteFileStreamsPlaylistsAndManifest(t *testing.T) {
+	dir := t.TempDir()
+	untimed := exportTrack(3, "Line\nBreak", "", "", "c")
+	untimed.DurationMs = sql.NullInt32{}
+	playlist := &db.PlaylistWithTracks{
+		Playlist: db.Playlist{ID: 7, Name: "Night"},
+		Tracks:   []db.Track{*exportTrack(1, "Starfire", "Low", "Secret Name", "a"), *exportTrack(2, "Skipped", "Low", "", "b"), *untimed},
+	}
+	paths := map[int64]string{1: "Low/Secret Name/01 Starfire.flac", 3: "Unknown/Line Break.flac"}
+	if err := writePlaylist(dir, playlist, paths, map[string]bool{}); err != nil {
+		t.Fatalf("writePlaylist() error = %v", err)
+	}
+	want := "#EXTM3U\n" +
+		"#EXTINF:245,Low - Starfire\nLow/Secret Name/01 Starfire.flac\n" +
+		"#EXTINF:-1,Line Break\nUnknown/Line Break.flac\n"
+	if got := readExport(t, dir, "Night.m3u8"); got != want {
+		t.Fatalf("playlist = %q, want %q", got, want)
+	}
+
+	m, err := loadManifest(dir)
+	if err != nil {
+		t.Fatalf("loadManifest() error = %v", err)
+	}
+	updated := time.Date(2026, 3, 1, 12, 0, 0, 0, time.UTC)
+	m.Tracks["1"] = manifestEntry{Path: "Low/Secret Name/01 Starfire.flac", UpdatedAt: updated}
+	if err := m.save(dir); err != nil {
+		t.Fatalf("save() error = %v", err)
+	}
+	wantManifest := "{\n  \"tracks\": {\n    \"1\": {\n      \"path\": \"Low/Secret Name/01 Starfire.flac\",\n      \"updated_at\": \"2026-03-01T12:00:00Z\"\n    }\n  }\n}\n"
+	if got := readExport(t, dir, manifestName); got != wantManifest {
+		t.Fatalf("manifest = %q, want %q", got, wantManifest)
+	}
+
+	// Rewriting truncates the old contents.
+	delete(m.Tracks, "1")
+	if err := m.save(dir); err != nil {
+		t.Fatalf("save() again error = %v", err)
+	}
+	if got := readExport(t, dir, manifestName); got != "{\n  \"tracks\": {}\n}\n" {
+		t.Fatalf("rewritten manifest = %q", got)
+	}
+}
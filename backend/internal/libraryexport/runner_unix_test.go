@@ -0,0 +1,31 @@
+//go:build unix
+
+package libraryexport
+
+import (
+	"io"
+	"os"
+	"path/filepath"
+	"syscall"
+	"testing"
+)
+
+func TestWriteFileCreatesFilesWithMode0644(t *testing.T) {
+	// With no umask, the mode writeFile asks for is the mode the file gets.
+	defer syscall.Umask(syscall.Umask(0))
+
+	path := filepath.Join(t.TempDir(), "Night.m3u8")
+	if err := writeFile(path, func(out io.Writer) error {
+		_, err := io.WriteString(out, "#EXTM3U\n")
+		return err
+	}); err != nil {
+		t.Fatalf("writeFile() error = %v", err)
+	}
+	info, err := os.Stat(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if mode := info.Mode().Perm(); mode != 0o644 {
+		t.Fatalf("mode = %v, want -rw-r--r--", mode)
+	}
+}
@@ -0,0 +1,58 @@
+package libraryexport
+
+import "strings"
+
+// Capabilities are what a playback client declared it plays: codecs by their
+// ffprobe names, such as "opus" or "mp3", and the highest bitrate it wants
+// streamed in kbps, 0 for no limit.
+type Capabilities struct {
+	Codecs         []string
+	MaxBitrateKbps int
+}
+
+// streamFormats are the formats StreamProfile converts to, best first: at one
+// bitrate Opus sounds better than AAC and AAC better than MP3. FLAC is only
+// picked for clients without a bitrate limit.
+var streamFormats = []string{FormatOpus, FormatAAC, FormatMP3, FormatFLAC}
+
+// Decodes reports whether the client plays codec.
+func (c Capabilities) Decodes(codec string) bool {
+	for _, declared := range c.Codecs {
+		if strings.EqualFold(declared, codec) {
+			return true
+		}
+	}
+	return false
+}
+
+// StreamProfile picks how a track stored as codec at bitrateKbps is streamed
+// to a client with caps. The stored file is streamed as it is when the client
+// plays its codec within its bitrate limit; an unknown bitrate (0) is taken to
+// fit. Otherwise the track is converted to the best format the client plays,
+// at that format's default bitrate or the client's limit when lower. ok is
+// false when no format the server writes fits the client.
+func StreamProfile(caps Capabilities, codec string, bitrateKbps int) (profile Profile, ok bool) {
+	if caps.Decodes(codec) && (caps.MaxBitrateKbps == 0 || bitrateKbps <= caps.MaxBitrateKbps) {
+		return Profile{}, true
+	}
+	for _, format := range streamFormats {
+		enc := encodings[format]
+		if !caps.Decodes(format) {
+			continue
+		}
+		if enc.defaultKbps == 0 {
+			if caps.MaxBitrateKbps == 0 {
+				return Profile{Format: format}, true
+			}
+			continue
+		}
+		kbps := enc.defaultKbps
+		if caps.MaxBitrateKbps != 0 {
+			kbps = min(kbps, caps.MaxBitrateKbps)
+		}
+		if kbps >= enc.minKbps {
+			return Profile{Format: format, BitrateKbps: kbps}, true
+		}
+	}
+	return Profile{}, false
+}
@@ -0,0 +1,35 @@
+package libraryexport
+
+import "testing"
+
+func TestStreamProfile(t *testing.T) {
+	tests := []struct {
+		name    string
+		caps    Capabilities
+		codec   string
+		kbps    int
+		want    Profile
+		wantNot bool
+	}{
+		{name: "stored codec within limit", caps: Capabilities{Codecs: []string{"MP3"}, MaxBitrateKbps: 320}, codec: "mp3", kbps: 256, want: Profile{}},
+		{name: "stored codec without limit", caps: Capabilities{Codecs: []string{"flac"}}, codec: "flac", kbps: 900, want: Profile{}},
+		{name: "unknown bitrate fits", caps: Capabilities{Codecs: []string{"opus"}, MaxBitrateKbps: 96}, codec: "opus", want: Profile{}},
+		{name: "best declared format", caps: Capabilities{Codecs: []string{"mp3", "aac", "opus"}}, codec: "vorbis", kbps: 160, want: Profile{Format: FormatOpus, BitrateKbps: 160}},
+		{name: "over the limit", caps: Capabilities{Codecs: []string{"mp3", "aac"}, MaxBitrateKbps: 128}, codec: "aac", kbps: 256, want: Profile{Format: FormatAAC, BitrateKbps: 128}},
+		{name: "flac only without limit", caps: Capabilities{Codecs: []string{"flac"}}, codec: "alac", kbps: 1000, want: Profile{Format: FormatFLAC}},
+		{name: "flac client with limit", caps: Capabilities{Codecs: []string{"flac", "mp3"}, MaxBitrateKbps: 192}, codec: "flac", kbps: 900, want: Profile{Format: FormatMP3, BitrateKbps: 192}},
+		{name: "limit below every format", caps: Capabilities{Codecs: []string{"mp3", "aac"}, MaxBitrateKbps: 48}, codec: "flac", kbps: 900, wantNot: true},
+		{name: "nothing the server writes", caps: Capabilities{Codecs: []string{"wma"}}, codec: "mp3", kbps: 320, wantNot: true},
+	}
+	for _, tt := range tests {
+		got, ok := StreamProfile(tt.caps, tt.codec, tt.kbps)
+		if ok == tt.wantNot || (ok && got != tt.want) {
+			t.Errorf("%s: StreamProfile() = %+v, %v; want %+v, %v", tt.name, got, ok, tt.want, !tt.wantNot)
+		}
+		if ok {
+			if _, err := got.Normalize(); err != nil {
+				t.Errorf("%s: picked an invalid profile %+v: %v", tt.name, got, err)
+			}
+		}
+	}
+}
@@ -0,0 +1,226 @@
+package libraryexport
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"path"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// Transcode is the job kind that converts tracks to a device profile's
+// settings in object storage, for devices syncing them offline.
+var Transcode = jobs.NewKind[TranscodePayload]("offline_transcode")
+
+type TranscodePayload struct {
+	Profile  Profile `json:"profile"`
+	TrackIDs []int64 `json:"track_ids"`
+}
+
+// TranscodeProgress is the progress detail of offline transcode jobs.
+type TranscodeProgress struct {
+	Converted int `json:"converted"`
+	Existing  int `json:"existing"`
+	Failed    int `json:"failed"`
+}
+
+// TranscodeKey is where the copy of the file stored at storageKey written
+// for profile is kept. Keys follow the profile's settings, so profiles with
+// the same settings share copies and an edited profile gets new ones, and the
+// stored file's key, so a replaced file is converted again.
+func TranscodeKey(profile Profile, storageKey string) string {
+	sum := sha256.Sum256([]byte(storageKey))
+	ext := strings.ToLower(strings.TrimPrefix(path.Ext(storageKey), "."))
+	return "transcodes/" + profile.Fingerprint() + "/" + hex.EncodeToString(sum[:12]) + "." + profile.Extension(ext)
+}
+
+// ErrTranscodeBusy is returned when as many transcode jobs as the server
+// admits are already queued or running.
+var ErrTranscodeBusy = errors.New("too many offline transcodes are queued or running")
+
+// TranscodeQueue queues offline transcode jobs.
+type TranscodeQueue struct {
+	store     *jobs.Store
+	maxActive int
+}
+
+// NewTranscodeQueue returns a queue admitting up to maxActive queued or
+// running transcode jobs across all users; 0 admits any number.
+func NewTranscodeQueue(store *jobs.Store, maxActive int) *TranscodeQueue {
+	return &TranscodeQueue{store: store, maxActive: maxActive}
+}
+
+// Enqueue queues converting trackIDs for profile on the user's behalf. A user
+// runs at most one transcode per profile setting at a time; a second request
+// while one is queued or running returns jobs.ErrDuplicate. Past the queue's
+// admission limit, a request that would queue a new job returns
+// ErrTranscodeBusy. The limit is checked before inserting, so concurrent
+// requests may overshoot it by a few jobs.
+func (q *TranscodeQueue) Enqueue(ctx context.Context, userID uuid.UUID, profile Profile, trackIDs []int64) (*jobs.Job, error) {
+	uniqueKey := userID.String() + ":transcode:" + profile.Fingerprint()
+	_, err := q.store.FindActive(ctx, Transcode.Name, uniqueKey)
+	switch {
+	case err == nil:
+		return nil, jobs.ErrDuplicate
+	case !errors.Is(err, jobs.ErrNotFound):
+		return nil, err
+	}
+	if q.maxActive > 0 {
+		active, err := q.store.CountActive(ctx, Transcode.Name)
+		if err != nil {
+			return nil, err
+		}
+		if active >= q.maxActive {
+			return nil, ErrTranscodeBusy
+		}
+	}
+	return Transcode.Enqueue(ctx, q.store, TranscodePayload{Profile: profile, TrackIDs: trackIDs}, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   uniqueKey,
+	})
+}
+
+// Renditions stores converted files. *storage.Client satisfies this
+// interface.
+type Renditions interface {
+	Objects
+	ObjectExists(ctx context.Context, key string) (bool, error)
+	PutObject(ctx context.Context, key string, reader io.Reader, size int64, contentType string) error
+}
+
+// TranscodeConfig holds what a TranscodeRunner reads from and writes to.
+type TranscodeConfig struct {
+	Tracks  TrackLoader
+	Artwork Artwork
+	Objects Renditions
+	Tagger  Tagger
+}
+
+// TranscodeRunner converts tracks into object storage per job.
+type TranscodeRunner struct {
+	cfg TranscodeConfig
+}
+
+func NewTranscodeRunner(cfg TranscodeConfig) *TranscodeRunner {
+	return &TranscodeRunner{cfg: cfg}
+}
+
+// Register runs offline transcode jobs on w.
+func (r *TranscodeRunner) Register(w *jobs.Worker) {
+	Transcode.Handle(w, func(ctx context.Context, job *jobs.Job, payload TranscodePayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, payload, progress)
+	})
+}
+
+// Run converts the job's tracks that have no copy for its profile yet. Tracks
+// that fail are logged and counted rather than ending the job.
+func (r *TranscodeRunner) Run(ctx context.Context, jobID uuid.UUID, payload TranscodePayload, progress progressReporter) error {
+	scratch, err := os.MkdirTemp("", "omp-transcode-*")
+	if err != nil {
+		return err
+	}
+	defer os.RemoveAll(scratch)
+
+	w := &writer{
+		cfg:     RunnerConfig{Tracks: r.cfg.Tracks, Artwork: r.cfg.Artwork, Objects: r.cfg.Objects, Tagger: r.cfg.Tagger},
+		scratch: scratch,
+		profile: payload.Profile,
+		covers:  map[int64]string{},
+	}
+	var counts TranscodeProgress
+	total := len(payload.TrackIDs)
+	if err := progress.Report(ctx, 0, total, counts); err != nil {
+		return err
+	}
+	for i, id := range payload.TrackIDs {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		converted, err := r.transcode(ctx, w, id)
+		switch {
+		case err != nil:
+			log.Printf("Offline transcode job %s: failed to convert track %d: %v", jobID, id, err)
+			counts.Failed++
+		case converted:
+			counts.Converted++
+		default:
+			counts.Existing++
+		}
+		if err := progress.Report(ctx, i+1, total, counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// transcode stores track id's copy for w's profile, reporting false when one
+// was already stored.
+func (r *TranscodeRunner) transcode(ctx context.Context, w *writer, id int64) (bool, error) {
+	track, err := r.cfg.Tracks.GetByID(ctx, id)
+	if err != nil {
+		return false, err
+	}
+	key := strings.TrimSpace(track.StorageKey.String)
+	if key == "" {
+		return false, errors.New("track has no stored audio")
+	}
+	target := TranscodeKey(w.profile, key)
+	exists, err := r.cfg.Objects.ObjectExists(ctx, target)
+	if err != nil || exists {
+		return false, err
+	}
+
+	ext := strings.ToLower(strings.TrimPrefix(path.Ext(key), "."))
+	if ext == "" {
+		ext = "bin"
+	}
+	src := filepath.Join(w.scratch, "track."+ext)
+	if err := w.download(ctx, key, src); err != nil {
+		return false, err
+	}
+	defer os.Remove(src)
+	cover, err := w.cover(ctx, id)
+	if err != nil {
+		return false, err
+	}
+	ext = w.profile.Extension(ext)
+	if !embedsArtwork(ext) {
+		cover = ""
+	}
+	dst := filepath.Join(w.scratch, "rendition-"+strconv.FormatInt(id, 10)+"."+ext)
+	defer os.Remove(dst)
+	if err := w.cfg.Tagger.Write(ctx, src, cover, dst, writeTags(*track, ext), w.profile); err != nil {
+		return false, err
+	}
+
+	file, err := os.Open(dst)
+	if err != nil {
+		return false, err
+	}
+	defer file.Close()
+	info, err := file.Stat()
+	if err != nil {
+		return false, err
+	}
+	contentType := w.profile.ContentType()
+	if contentType == "" {
+		contentType = "application/octet-stream"
+	}
+	if err := r.cfg.Objects.PutObject(ctx, target, file, info.Size(), contentType); err != nil {
+		return false, fmt.Errorf("store %s: %w", target, err)
+	}
+	return true, nil
+}
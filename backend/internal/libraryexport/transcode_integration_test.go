@@ -0,0 +1,34 @@
+package libraryexport
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/testutil"
+)
+
+func TestTranscodeQueueReportsDuplicateBeforeAdmissionLimit(t *testing.T) {
+	raw := testutil.NewPostgresDatabase(t, func(raw *sql.DB) error {
+		return (&db.DB{DB: raw}).Migrate()
+	})
+	t.Parallel()
+	ctx := context.Background()
+	fixtures := testutil.NewFixtures(t, raw)
+	owner, other := fixtures.User(""), fixtures.User("")
+	queue := NewTranscodeQueue(jobs.NewStore(&db.DB{DB: raw}), 1)
+	profile := Profile{Format: FormatOpus, BitrateKbps: 128}
+
+	if _, err := queue.Enqueue(ctx, owner, profile, []int64{1}); err != nil {
+		t.Fatalf("first Enqueue: %v", err)
+	}
+	if _, err := queue.Enqueue(ctx, owner, profile, []int64{2}); !errors.Is(err, jobs.ErrDuplicate) {
+		t.Fatalf("repeat Enqueue at the limit = %v, want ErrDuplicate", err)
+	}
+	if _, err := queue.Enqueue(ctx, other, profile, []int64{1}); !errors.Is(err, ErrTranscodeBusy) {
+		t.Fatalf("new Enqueue at the limit = %v, want ErrTranscodeBusy", err)
+	}
+}
@@ -0,0 +1,91 @@
+package libraryexport
+
+import (
+	"context"
+	"io"
+	"strings"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func (f fakeObjects) ObjectExists(_ context.Context, key string) (bool, error) {
+	_, ok := f[key]
+	return ok, nil
+}
+
+func (f fakeObjects) PutObject(_ context.Context, key string, reader io.Reader, _ int64, _ string) error {
+	data, err := io.ReadAll(reader)
+	if err != nil {
+		return err
+	}
+	f[key] = string(data)
+	return nil
+}
+
+type fakeTranscodeProgress struct {
+	last TranscodeProgress
+}
+
+func (p *fakeTranscodeProgress) Report(_ context.Context, _, _ int, detail any) error {
+	p.last = detail.(TranscodeProgress)
+	return nil
+}
+
+func TestTranscodeKey(t *testing.T) {
+	mp3 := Profile{Format: FormatMP3, BitrateKbps: 320}
+	key := TranscodeKey(mp3, "tracks/local/a.flac")
+	if !strings.HasPrefix(key, "transcodes/mp3-320k/") || !strings.HasSuffix(key, ".mp3") {
+		t.Fatalf("TranscodeKey() = %q", key)
+	}
+	if TranscodeKey(mp3, "tracks/local/b.flac") == key {
+		t.Error("different files share a key")
+	}
+	if TranscodeKey(Profile{Format: FormatMP3, BitrateKbps: 192}, "tracks/local/a.flac") == key {
+		t.Error("different bitrates share a key")
+	}
+}
+
+func TestTranscodeRunnerStoresConvertedCopies(t *testing.T) {
+	profile := Profile{Format: FormatMP3, BitrateKbps: 320}
+	starfire := exportTrack(1, "Starfire", "Low", "Secret Name", "tracks/local/a.flac")
+	lullaby := exportTrack(2, "Lullaby", "Low", "Secret Name", "tracks/local/b.ogg")
+	missing := exportTrack(3, "Gone", "Low", "", "")
+	library := &fakeExportLibrary{
+		tracks:  map[int64]*db.Track{1: starfire, 2: lullaby, 3: missing},
+		artwork: map[int64]*db.Artwork{1: {ID: 5, Variants: []db.ArtworkVariant{{Name: "large", StorageKey: "art/large.jpg", Width: 1200}}}},
+	}
+	objects := fakeObjects{
+		"tracks/local/a.flac": "audio-a",
+		"tracks/local/b.ogg":  "audio-b",
+		"art/large.jpg":       "jpeg",
+	}
+	objects[TranscodeKey(profile, "tracks/local/b.ogg")] = "converted earlier"
+	tagger := &fakeTagger{}
+	runner := NewTranscodeRunner(TranscodeConfig{Tracks: library, Artwork: library, Objects: objects, Tagger: tagger})
+	payload := TranscodePayload{Profile: profile, TrackIDs: []int64{1, 2, 3, 99}}
+
+	progress := &fakeTranscodeProgress{}
+	if err := runner.Run(context.Background(), uuid.New(), payload, progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	if want := (TranscodeProgress{Converted: 1, Existing: 1, Failed: 2}); progress.last != want {
+		t.Fatalf("progress = %+v, want %+v", progress.last, want)
+	}
+	if got := objects[TranscodeKey(profile, "tracks/local/a.flac")]; got != "audio-a title=Starfire mbid= cover=jpeg as=mp3-320k" {
+		t.Errorf("converted Starfire = %q", got)
+	}
+	if got := objects[TranscodeKey(profile, "tracks/local/b.ogg")]; got != "converted earlier" {
+		t.Errorf("Lullaby was converted again: %q", got)
+	}
+
+	tagger.writes = 0
+	if err := runner.Run(context.Background(), uuid.New(), payload, progress); err != nil {
+		t.Fatalf("second Run() error = %v", err)
+	}
+	if want := (TranscodeProgress{Existing: 2, Failed: 2}); progress.last != want || tagger.writes != 0 {
+		t.Fatalf("second run progress = %+v with %d writes, want %+v", progress.last, tagger.writes, want)
+	}
+}
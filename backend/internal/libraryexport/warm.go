@@ -0,0 +1,149 @@
+package libraryexport
+
+import (
+	"context"
+	"errors"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// warmPlayDays is the window of plays that ranks tracks for warming.
+const warmPlayDays = 30
+
+// CommonProfiles finds the device profile settings most users convert to.
+// db.DeviceProfileRepository satisfies this interface.
+type CommonProfiles interface {
+	MostCommonSettings(ctx context.Context) (*db.DeviceProfile, error)
+}
+
+// PopularTracks ranks tracks by plays across all users.
+// db.PlayEventRepository satisfies this interface.
+type PopularTracks interface {
+	PopularTrackIDs(ctx context.Context, days, limit int) ([]int64, error)
+}
+
+// RecentTracks lists and loads tracks by when they were added.
+// db.TrackRepository satisfies this interface.
+type RecentTracks interface {
+	RecentlyAddedTrackIDs(ctx context.Context, limit int) ([]int64, error)
+	GetByIDs(ctx context.Context, ids []int64) (map[int64]*db.Track, error)
+}
+
+// WarmConfig holds what a TranscodeWarmer ranks tracks with and how many it
+// converts per pass.
+type WarmConfig struct {
+	Profiles CommonProfiles
+	Plays    PopularTracks
+	Tracks   RecentTracks
+	Objects  Renditions
+	// Budget is how many tracks one pass queues for conversion.
+	Budget int
+}
+
+// TranscodeWarmer converts tracks ahead of demand, so the first sync of a
+// popular or new track finds its copy already made. Each pass takes the
+// settings most users' device profiles share, and queues a transcode job for
+// up to Budget of the most-played and most recently added tracks that have
+// no copy for them yet.
+type TranscodeWarmer struct {
+	cfg   WarmConfig
+	store *jobs.Store
+}
+
+func NewTranscodeWarmer(store *jobs.Store, cfg WarmConfig) *TranscodeWarmer {
+	return &TranscodeWarmer{cfg: cfg, store: store}
+}
+
+// Warm runs one pass. It returns the queued job, or nil when there is
+// nothing to convert or the previous pass's job is still queued or running.
+func (w *TranscodeWarmer) Warm(ctx context.Context) (*jobs.Job, error) {
+	settings, err := w.cfg.Profiles.MostCommonSettings(ctx)
+	if err != nil || settings == nil {
+		return nil, err
+	}
+	profile, err := Profile{
+		Format:          settings.Format,
+		BitrateKbps:     settings.BitrateKbps,
+		MaxArtworkBytes: settings.MaxArtworkBytes,
+	}.Normalize()
+	if err != nil || profile.IsOriginal() {
+		return nil, err
+	}
+
+	trackIDs, err := w.pick(ctx, profile)
+	if err != nil || len(trackIDs) == 0 {
+		return nil, err
+	}
+	job, err := Transcode.Enqueue(ctx, w.store, TranscodePayload{Profile: profile, TrackIDs: trackIDs}, jobs.Options{
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   "warm:transcode:" + profile.Fingerprint(),
+	})
+	if errors.Is(err, jobs.ErrDuplicate) {
+		return nil, nil
+	}
+	return job, err
+}
+
+// pick returns up to the budget of candidate tracks without a copy for
+// profile, alternating between the most-played and newest so neither list
+// crowds out the other.
+func (w *TranscodeWarmer) pick(ctx context.Context, profile Profile) ([]int64, error) {
+	budget := w.cfg.Budget
+	if budget <= 0 {
+		return nil, nil
+	}
+	popular, err := w.cfg.Plays.PopularTrackIDs(ctx, warmPlayDays, 2*budget)
+	if err != nil {
+		return nil, err
+	}
+	recent, err := w.cfg.Tracks.RecentlyAddedTrackIDs(ctx, 2*budget)
+	if err != nil {
+		return nil, err
+	}
+	candidates := interleave(popular, recent)
+	tracks, err := w.cfg.Tracks.GetByIDs(ctx, candidates)
+	if err != nil {
+		return nil, err
+	}
+
+	var picked []int64
+	for _, id := range candidates {
+		if len(picked) == budget {
+			break
+		}
+		track, ok := tracks[id]
+		if !ok {
+			continue
+		}
+		key := strings.TrimSpace(track.StorageKey.String)
+		if key == "" {
+			continue
+		}
+		exists, err := w.cfg.Objects.ObjectExists(ctx, TranscodeKey(profile, key))
+		if err != nil {
+			return nil, err
+		}
+		if !exists {
+			picked = append(picked, id)
+		}
+	}
+	return picked, nil
+}
+
+// interleave merges a and b by taking from each in turn, dropping repeats.
+func interleave(a, b []int64) []int64 {
+	seen := make(map[int64]bool, len(a)+len(b))
+	merged := make([]int64, 0, len(a)+len(b))
+	for i := 0; i < len(a) || i < len(b); i++ {
+		for _, list := range [][]int64{a, b} {
+			if i < len(list) && !seen[list[i]] {
+				seen[list[i]] = true
+				merged = append(merged, list[i])
+			}
+		}
+	}
+	return merged
+}
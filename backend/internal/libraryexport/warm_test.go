@@ -0,0 +1,66 @@
+package libraryexport
+
+import (
+	"context"
+	"reflect"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeWarmSources struct {
+	popular []int64
+	recent  []int64
+	tracks  map[int64]*db.Track
+}
+
+func (f *fakeWarmSources) PopularTrackIDs(_ context.Context, _, limit int) ([]int64, error) {
+	return f.popular[:min(limit, len(f.popular))], nil
+}
+
+func (f *fakeWarmSources) RecentlyAddedTrackIDs(_ context.Context, limit int) ([]int64, error) {
+	return f.recent[:min(limit, len(f.recent))], nil
+}
+
+func (f *fakeWarmSources) GetByIDs(_ context.Context, ids []int64) (map[int64]*db.Track, error) {
+	tracks := map[int64]*db.Track{}
+	for _, id := range ids {
+		if track, ok := f.tracks[id]; ok {
+			tracks[id] = track
+		}
+	}
+	return tracks, nil
+}
+
+func TestTranscodeWarmerPicksPopularAndNewTracksWithoutCopies(t *testing.T) {
+	profile := Profile{Format: FormatOpus, BitrateKbps: 160}
+	sources := &fakeWarmSources{
+		popular: []int64{1, 2, 3, 4},
+		recent:  []int64{9, 2, 8, 7},
+		tracks: map[int64]*db.Track{
+			1: exportTrack(1, "One", "A", "", "tracks/1.flac"),
+			2: exportTrack(2, "Two", "A", "", "tracks/2.flac"),
+			3: exportTrack(3, "Three", "A", "", "tracks/3.flac"),
+			4: exportTrack(4, "Four", "A", "", "tracks/4.flac"),
+			7: exportTrack(7, "Seven", "B", "", "tracks/7.flac"),
+			8: exportTrack(8, "Eight", "B", "", ""),
+			9: exportTrack(9, "Nine", "B", "", "tracks/9.flac"),
+		},
+	}
+	objects := fakeObjects{TranscodeKey(profile, "tracks/1.flac"): "converted earlier"}
+	warmer := NewTranscodeWarmer(nil, WarmConfig{Plays: sources, Tracks: sources, Objects: objects, Budget: 3})
+
+	picked, err := warmer.pick(context.Background(), profile)
+	if err != nil {
+		t.Fatalf("pick() error = %v", err)
+	}
+	// 1 already has a copy, and 2, both popular and new, is picked once.
+	if want := []int64{9, 2, 3}; !reflect.DeepEqual(picked, want) {
+		t.Fatalf("picked = %v, want %v", picked, want)
+	}
+
+	warmer.cfg.Budget = 0
+	if picked, err := warmer.pick(context.Background(), profile); err != nil || picked != nil {
+		t.Fatalf("pick() without budget = %v, %v", picked, err)
+	}
+}
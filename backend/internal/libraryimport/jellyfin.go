@@ -0,0 +1,187 @@
+package libraryimport
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"math"
+	"net/http"
+	"net/url"
+	"strconv"
+	"time"
+)
+
+// jellyfinPageSize is how many items one Jellyfin API request lists.
+const jellyfinPageSize = 500
+
+// jellyfinClientHeader identifies this server to Jellyfin, which requires
+// a client description on sign-in.
+const jellyfinClientHeader = `MediaBrowser Client="Open Music Player", Device="Open Music Player", DeviceId="open-music-player-migration", Version="1.0"`
+
+// jellyfin reads a Jellyfin server through its API with an operator API key.
+type jellyfin struct {
+	base   *url.URL
+	apiKey string
+	paths  pathMap
+	client *http.Client
+}
+
+func newJellyfin(base *url.URL, apiKey string, paths pathMap, client *http.Client) *jellyfin {
+	if client == nil {
+		client = &http.Client{Timeout: time.Minute}
+	}
+	return &jellyfin{base: base, apiKey: apiKey, paths: paths, client: client}
+}
+
+func (j *jellyfin) Protocol() string { return "jellyfin" }
+
+// SignIn checks the password by signing in, then signs the new session out.
+func (j *jellyfin) SignIn(ctx context.Context, username, password string) (string, error) {
+	body, err := json.Marshal(map[string]string{"Username": username, "Pw": password})
+	if err != nil {
+		return "", err
+	}
+	var result struct {
+		User struct {
+			ID string `json:"Id"`
+		} `json:"User"`
+		AccessToken string `json:"AccessToken"`
+	}
+	status, err := j.do(ctx, http.MethodPost, "/Users/AuthenticateByName", nil, jellyfinClientHeader, body, &result)
+	if status == http.StatusUnauthorized || status == http.StatusForbidden {
+		return "", ErrSignInFailed
+	}
+	if err != nil {
+		return "", err
+	}
+	if result.AccessToken != "" {
+		// Best effort; a leftover session only clutters the user's device list.
+		j.do(ctx, http.MethodPost, "/Sessions/Logout", nil, jellyfinClientHeader+`, Token="`+result.AccessToken+`"`, nil, nil)
+	}
+	if result.User.ID == "" {
+		return "", fmt.Errorf("jellyfin sign-in returned no user")
+	}
+	return result.User.ID, nil
+}
+
+type jellyfinItem struct {
+	ID          string            `json:"Id"`
+	Name        string            `json:"Name"`
+	Path        string            `json:"Path"`
+	ProviderIDs map[string]string `json:"ProviderIds"`
+	UserData    struct {
+		PlayCount      int     `json:"PlayCount"`
+		IsFavorite     bool    `json:"IsFavorite"`
+		LastPlayedDate string  `json:"LastPlayedDate"`
+		Rating         float64 `json:"Rating"`
+	} `json:"UserData"`
+}
+
+// Export lists the account's audio items that were played, favorited, or
+// rated, and its playlists with their items.
+func (j *jellyfin) Export(ctx context.Context, accountID string) (*Export, error) {
+	export := &Export{}
+	seen := map[string]bool{}
+	add := func(item jellyfinItem) {
+		if seen[item.ID] {
+			return
+		}
+		seen[item.ID] = true
+		export.Tracks = append(export.Tracks, Track{
+			ID:            item.ID,
+			Path:          j.paths.apply(item.Path),
+			MBRecordingID: item.ProviderIDs["MusicBrainzRecording"],
+			PlayCount:     item.UserData.PlayCount,
+			LastPlayed:    parseTime(item.UserData.LastPlayedDate),
+			Favorite:      item.UserData.IsFavorite,
+			// Jellyfin rates out of 10.
+			Rating: min(max(int(math.Round(item.UserData.Rating/2)), 0), 5),
+		})
+	}
+
+	audio := url.Values{"UserId": {accountID}, "IncludeItemTypes": {"Audio"}, "Recursive": {"true"}, "Fields": {"Path,ProviderIds"}, "EnableUserData": {"true"}}
+	err := j.pages(ctx, "/Items", audio, func(item jellyfinItem) {
+		if item.UserData.PlayCount > 0 || item.UserData.IsFavorite || item.UserData.Rating > 0 {
+			add(item)
+		}
+	})
+	if err != nil {
+		return nil, err
+	}
+
+	var playlists []jellyfinItem
+	err = j.pages(ctx, "/Items", url.Values{"UserId": {accountID}, "IncludeItemTypes": {"Playlist"}, "Recursive": {"true"}}, func(item jellyfinItem) {
+		playlists = append(playlists, item)
+	})
+	if err != nil {
+		return nil, err
+	}
+	for _, playlist := range playlists {
+		entry := Playlist{ID: playlist.ID, Name: playlist.Name}
+		query := url.Values{"UserId": {accountID}, "Fields": {"Path,ProviderIds"}, "EnableUserData": {"true"}}
+		err := j.pages(ctx, "/Playlists/"+playlist.ID+"/Items", query, func(item jellyfinItem) {
+			add(item)
+			entry.TrackIDs = append(entry.TrackIDs, item.ID)
+		})
+		if err != nil {
+			return nil, err
+		}
+		export.Playlists = append(export.Playlists, entry)
+	}
+	return export, nil
+}
+
+// pages calls fn for every item an item listing returns, page by page.
+func (j *jellyfin) pages(ctx context.Context, path string, query url.Values, fn func(jellyfinItem)) error {
+	for start := 0; ; start += jellyfinPageSize {
+		query.Set("StartIndex", strconv.Itoa(start))
+		query.Set("Limit", strconv.Itoa(jellyfinPageSize))
+		var page struct {
+			Items            []jellyfinItem `json:"Items"`
+			TotalRecordCount int            `json:"TotalRecordCount"`
+		}
+		if _, err := j.do(ctx, http.MethodGet, path, query, `MediaBrowser Token="`+j.apiKey+`"`, nil, &page); err != nil {
+			return err
+		}
+		for _, item := range page.Items {
+			fn(item)
+		}
+		if len(page.Items) < jellyfinPageSize || start+len(page.Items) >= page.TotalRecordCount {
+			return nil
+		}
+	}
+}
+
+// do sends one API request, decoding a JSON response into out when it is
+// not nil. It returns the response status alongside any error.
+func (j *jellyfin) do(ctx context.Context, method, path string, query url.Values, authorization string, body []byte, out any) (int, error) {
+	u := *j.base
+	u.Path += path
+	u.RawQuery = query.Encode()
+	req, err := http.NewRequestWithContext(ctx, method, u.String(), bytes.NewReader(body))
+	if err != nil {
+		return 0, err
+	}
+	req.Header.Set("Authorization", authorization)
+	if body != nil {
+		req.Header.Set("Content-Type", "application/json")
+	}
+	resp, err := j.client.Do(req)
+	if err != nil {
+		return 0, fmt.Errorf("jellyfin %s: %w", path, err)
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode < 200 || resp.StatusCode > 299 {
+		return resp.StatusCode, fmt.Errorf("jellyfin %s: %s", path, resp.Status)
+	}
+	if out == nil {
+		_, err := io.Copy(io.Discard, resp.Body)
+		return resp.StatusCode, err
+	}
+	if err := json.NewDecoder(resp.Body).Decode(out); err != nil {
+		return resp.StatusCode, fmt.Errorf("jellyfin %s: decode: %w", path, err)
+	}
+	return resp.StatusCode, nil
+}
@@ -0,0 +1,105 @@
+package libraryimport
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"reflect"
+	"testing"
+	"time"
+)
+
+func fakeJellyfin(t *testing.T) (*jellyfin, *[]string) {
+	t.Helper()
+	var logouts []string
+	items := map[string]string{
+		"Audio": `[
+			{"Id":"a1","Path":"/media/Low/01 Starfire.flac","ProviderIds":{"MusicBrainzRecording":"11111111-1111-1111-1111-111111111111"},"UserData":{"PlayCount":3,"IsFavorite":true,"LastPlayedDate":"2024-05-01T20:30:00.0000000Z","Rating":7}},
+			{"Id":"a2","Path":"/media/Low/02 Untouched.flac","UserData":{}},
+			{"Id":"a3","Path":"/media/Low/03 Lullaby.flac","UserData":{"PlayCount":1}}
+		]`,
+		"Playlist": `[{"Id":"p1","Name":"Night"}]`,
+	}
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/jf/Users/AuthenticateByName":
+			var body struct{ Username, Pw string }
+			json.NewDecoder(r.Body).Decode(&body)
+			if body.Username != "alice" || body.Pw != "secret" {
+				w.WriteHeader(http.StatusUnauthorized)
+				return
+			}
+			w.Write([]byte(`{"User":{"Id":"u1"},"AccessToken":"session"}`))
+			return
+		case "/jf/Sessions/Logout":
+			logouts = append(logouts, r.Header.Get("Authorization"))
+			w.WriteHeader(http.StatusNoContent)
+			return
+		}
+		if r.Header.Get("Authorization") != `MediaBrowser Token="KEY"` || r.URL.Query().Get("UserId") != "u1" {
+			w.WriteHeader(http.StatusUnauthorized)
+			return
+		}
+		switch r.URL.Path {
+		case "/jf/Items":
+			w.Write([]byte(`{"Items":` + items[r.URL.Query().Get("IncludeItemTypes")] + `,"TotalRecordCount":3}`))
+		case "/jf/Playlists/p1/Items":
+			w.Write([]byte(`{"Items":[{"Id":"a2","Path":"/media/Low/02 Untouched.flac","UserData":{}},{"Id":"a1","Path":"/media/Low/01 Starfire.flac","UserData":{"PlayCount":3}}],"TotalRecordCount":2}`))
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	t.Cleanup(server.Close)
+	base, err := url.Parse(server.URL + "/jf")
+	if err != nil {
+		t.Fatal(err)
+	}
+	return newJellyfin(base, "KEY", pathMap{from: "/media", to: "/srv/music"}, server.Client()), &logouts
+}
+
+func TestJellyfinSignIn(t *testing.T) {
+	j, logouts := fakeJellyfin(t)
+	ctx := context.Background()
+
+	id, err := j.SignIn(ctx, "alice", "secret")
+	if err != nil || id != "u1" {
+		t.Fatalf("SignIn() = %q, %v", id, err)
+	}
+	if len(*logouts) != 1 || (*logouts)[0] != jellyfinClientHeader+`, Token="session"` {
+		t.Fatalf("logouts = %v", *logouts)
+	}
+	if _, err := j.SignIn(ctx, "alice", "wrong"); !errors.Is(err, ErrSignInFailed) {
+		t.Fatalf("SignIn() with a wrong password error = %v, want ErrSignInFailed", err)
+	}
+}
+
+func TestJellyfinExport(t *testing.T) {
+	j, _ := fakeJellyfin(t)
+
+	got, err := j.Export(context.Background(), "u1")
+	if err != nil {
+		t.Fatalf("Export() error = %v", err)
+	}
+	want := &Export{
+		Tracks: []Track{
+			{
+				ID:            "a1",
+				Path:          "/srv/music/Low/01 Starfire.flac",
+				MBRecordingID: "11111111-1111-1111-1111-111111111111",
+				PlayCount:     3,
+				LastPlayed:    time.Date(2024, 5, 1, 20, 30, 0, 0, time.UTC),
+				Favorite:      true,
+				Rating:        4,
+			},
+			{ID: "a3", Path: "/srv/music/Low/03 Lullaby.flac", PlayCount: 1},
+			{ID: "a2", Path: "/srv/music/Low/02 Untouched.flac"},
+		},
+		Playlists: []Playlist{{ID: "p1", Name: "Night", TrackIDs: []string{"a2", "a1"}}},
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("Export() = %+v, want %+v", got, want)
+	}
+}
@@ -0,0 +1,189 @@
+// Package libraryimport migrates a user's listening data from another
+// self-hosted music server, Navidrome or Jellyfin, into Open Music Player:
+// play counts, favorites, star ratings, and playlists.
+//
+// Sources are configured by the operator and referenced by name. A user links
+// their account on a source by signing in to it once; only the source's
+// account ID is kept in the migration job, never the password. Passwords
+// cannot be carried over, so Open Music Player accounts are not created from
+// source users.
+//
+// Source tracks are matched to tracks already in Open Music Player by file
+// path, then by MusicBrainz recording ID. Tracks that match nothing are
+// counted and skipped; playlist entries for them are kept unresolved so a
+// later run picks them up once the files are imported.
+package libraryimport
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/url"
+	"slices"
+	"strings"
+	"time"
+)
+
+var (
+	// ErrUnknownSource is returned for a source name that is not configured.
+	ErrUnknownSource = errors.New("unknown migration source")
+	// ErrSignInFailed is returned when a source rejects the user's
+	// credentials.
+	ErrSignInFailed = errors.New("source sign-in failed")
+	// ErrSourceUnavailable is returned when a source cannot be reached to
+	// check the user's credentials.
+	ErrSourceUnavailable = errors.New("migration source unavailable")
+)
+
+// Track is one track of the source account's listening data.
+type Track struct {
+	// ID is the track's ID on the source.
+	ID string
+	// Path is where the file lives, rewritten through the source's path_map
+	// to where Open Music Player sees it.
+	Path          string
+	MBRecordingID string
+	PlayCount     int
+	LastPlayed    time.Time
+	Favorite      bool
+	// Rating is 1 to 5 stars, or 0 when unrated.
+	Rating int
+}
+
+// Playlist is one of the source account's playlists, with its tracks' source
+// IDs in order. Smart playlists are left out.
+type Playlist struct {
+	ID       string
+	Name     string
+	TrackIDs []string
+}
+
+// Export is what a source holds for one account: every track the account
+// played, starred, rated, or put in a playlist, and its playlists.
+type Export struct {
+	Tracks    []Track
+	Playlists []Playlist
+}
+
+// Source is a configured server to migrate from.
+type Source interface {
+	// Protocol is "navidrome" or "jellyfin".
+	Protocol() string
+	// SignIn checks username and password against the server and returns the
+	// account's ID there.
+	SignIn(ctx context.Context, username, password string) (string, error)
+	// Export reads the listening data of the account with accountID.
+	Export(ctx context.Context, accountID string) (*Export, error)
+}
+
+// ParseSource parses a source's connection URL:
+//
+//	navidrome:///var/lib/navidrome/navidrome.db?url=http://navidrome:4533
+//	jellyfins://API_KEY@jellyfin.example?path_map=/media/music=/srv/music
+//
+// Navidrome is read from its SQLite database with the sqlite3 command; url is
+// the running server, used to check users' passwords. Jellyfin is read
+// through its API with an API key; jellyfin:// is plain HTTP and jellyfins://
+// is HTTPS. path_map rewrites a path prefix on the source to the prefix Open
+// Music Player sees the same files under.
+func ParseSource(name, raw string) (Source, error) {
+	name = strings.TrimSpace(name)
+	if name == "" || strings.ContainsAny(name, "/:@?#") {
+		return nil, fmt.Errorf("invalid migration source name %q", name)
+	}
+	u, err := url.Parse(strings.TrimSpace(raw))
+	if err != nil {
+		return nil, fmt.Errorf("migration source %s: %w", name, err)
+	}
+	paths, err := parsePathMap(u.Query().Get("path_map"))
+	if err != nil {
+		return nil, fmt.Errorf("migration source %s: %w", name, err)
+	}
+	switch u.Scheme {
+	case "navidrome":
+		server, err := url.Parse(u.Query().Get("url"))
+		if err != nil || (server.Scheme != "http" && server.Scheme != "https") || server.Host == "" {
+			return nil, fmt.Errorf("migration source %s: navidrome needs url=http(s)://host of the running server", name)
+		}
+		if u.Path == "" {
+			return nil, fmt.Errorf("migration source %s: missing database path", name)
+		}
+		return newNavidrome(u.Path, server, paths), nil
+	case "jellyfin", "jellyfins":
+		if u.Host == "" || u.User.Username() == "" {
+			return nil, fmt.Errorf("migration source %s: jellyfin needs API_KEY@host", name)
+		}
+		base := &url.URL{Scheme: "http", Host: u.Host, Path: strings.TrimSuffix(u.Path, "/")}
+		if u.Scheme == "jellyfins" {
+			base.Scheme = "https"
+		}
+		return newJellyfin(base, u.User.Username(), paths, nil), nil
+	default:
+		return nil, fmt.Errorf("migration source %s: unsupported scheme %q (want navidrome, jellyfin, or jellyfins)", name, u.Scheme)
+	}
+}
+
+// Sources are the configured sources by name.
+type Sources map[string]Source
+
+// ParseSources parses MIGRATION_SOURCES-style name to URL pairs.
+func ParseSources(raw map[string]string) (Sources, error) {
+	sources := make(Sources, len(raw))
+	for name, value := range raw {
+		source, err := ParseSource(name, value)
+		if err != nil {
+			return nil, err
+		}
+		sources[strings.TrimSpace(name)] = source
+	}
+	return sources, nil
+}
+
+// Names returns the configured source names in order.
+func (s Sources) Names() []string {
+	names := make([]string, 0, len(s))
+	for name := range s {
+		names = append(names, name)
+	}
+	slices.Sort(names)
+	return names
+}
+
+// pathMap rewrites the prefix of source paths.
+type pathMap struct {
+	from string
+	to   string
+}
+
+// parsePathMap parses "from=to"; empty means paths are used as they are.
+func parsePathMap(value string) (pathMap, error) {
+	if value == "" {
+		return pathMap{}, nil
+	}
+	from, to, ok := strings.Cut(value, "=")
+	if !ok || from == "" || to == "" {
+		return pathMap{}, fmt.Errorf("path_map %q is not from=to", value)
+	}
+	return pathMap{from: strings.TrimSuffix(from, "/"), to: strings.TrimSuffix(to, "/")}, nil
+}
+
+func (m pathMap) apply(path string) string {
+	if m.from == "" {
+		return path
+	}
+	if rest, ok := strings.CutPrefix(path, m.from); ok && (rest == "" || rest[0] == '/') {
+		return m.to + rest
+	}
+	return path
+}
+
+// parseTime reads the timestamps sources report, returning the zero time for
+// anything else.
+func parseTime(value string) time.Time {
+	for _, layout := range []string{time.RFC3339Nano, "2006-01-02 15:04:05.999999999-07:00", "2006-01-02 15:04:05.999999999"} {
+		if t, err := time.Parse(layout, strings.TrimSpace(value)); err == nil {
+			return t
+		}
+	}
+	return time.Time{}
+}
@@ -0,0 +1,81 @@
+package libraryimport
+
+import (
+	"testing"
+	"time"
+)
+
+func TestParseSource(t *testing.T) {
+	tests := []struct {
+		name     string
+		raw      string
+		protocol string
+		wantErr  bool
+	}{
+		{"home", "navidrome:///data/navidrome.db?url=http://navidrome:4533", "navidrome", false},
+		{"media", "jellyfins://KEY@jellyfin.example/jf?path_map=/media=/srv", "jellyfin", false},
+		{"home", "navidrome:///data/navidrome.db", "", true},
+		{"home", "navidrome://?url=http://navidrome:4533", "", true},
+		{"media", "jellyfin://jellyfin.example", "", true},
+		{"media", "jellyfin://KEY@jellyfin.example?path_map=/media", "", true},
+		{"media", "plex://KEY@plex.example", "", true},
+		{"a/b", "jellyfin://KEY@jellyfin.example", "", true},
+	}
+	for _, tt := range tests {
+		source, err := ParseSource(tt.name, tt.raw)
+		if (err != nil) != tt.wantErr {
+			t.Errorf("ParseSource(%q, %q) error = %v, wantErr %v", tt.name, tt.raw, err, tt.wantErr)
+			continue
+		}
+		if err == nil && source.Protocol() != tt.protocol {
+			t.Errorf("ParseSource(%q, %q) protocol = %s, want %s", tt.name, tt.raw, source.Protocol(), tt.protocol)
+		}
+	}
+
+	source, err := ParseSource("media", "jellyfins://KEY@jellyfin.example/jf/?path_map=/media/=/srv/music")
+	if err != nil {
+		t.Fatalf("ParseSource() error = %v", err)
+	}
+	j := source.(*jellyfin)
+	if j.base.String() != "https://jellyfin.example/jf" || j.apiKey != "KEY" || j.paths != (pathMap{from: "/media", to: "/srv/music"}) {
+		t.Fatalf("jellyfin = %+v", j)
+	}
+
+	sources, err := ParseSources(map[string]string{" media ": "jellyfin://KEY@jf", "home": "navidrome:///nd.db?url=http://nd"})
+	if err != nil {
+		t.Fatalf("ParseSources() error = %v", err)
+	}
+	if names := sources.Names(); len(names) != 2 || names[0] != "home" || names[1] != "media" {
+		t.Fatalf("Names() = %v", names)
+	}
+}
+
+func TestPathMapRewritesWholeSegments(t *testing.T) {
+	m := pathMap{from: "/music", to: "/srv/music"}
+	tests := map[string]string{
+		"/music/Low/01.flac":  "/srv/music/Low/01.flac",
+		"/music":              "/srv/music",
+		"/musical/Low/1.flac": "/musical/Low/1.flac",
+		"/other/Low/01.flac":  "/other/Low/01.flac",
+	}
+	for path, want := range tests {
+		if got := m.apply(path); got != want {
+			t.Errorf("apply(%q) = %q, want %q", path, got, want)
+		}
+	}
+	if got := (pathMap{}).apply("/music/a.flac"); got != "/music/a.flac" {
+		t.Errorf("empty map apply = %q", got)
+	}
+}
+
+func TestParseTime(t *testing.T) {
+	want := time.Date(2024, 5, 1, 20, 30, 0, 0, time.UTC)
+	for _, value := range []string{"2024-05-01T20:30:00Z", "2024-05-01T20:30:00.0000000Z", "2024-05-01 20:30:00+00:00", "2024-05-01 20:30:00"} {
+		if got := parseTime(value); !got.Equal(want) {
+			t.Errorf("parseTime(%q) = %v, want %v", value, got, want)
+		}
+	}
+	if got := parseTime(""); !got.IsZero() {
+		t.Errorf("parseTime(\"\") = %v", got)
+	}
+}
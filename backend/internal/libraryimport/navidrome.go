@@ -0,0 +1,249 @@
+package libraryimport
+
+import (
+	"bytes"
+	"context"
+	"crypto/md5"
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"maps"
+	"net/http"
+	"net/url"
+	"os/exec"
+	"path"
+	"slices"
+	"strings"
+	"time"
+)
+
+// navidrome reads a Navidrome server's SQLite database through the sqlite3
+// command, since the backend carries no SQLite driver. The schema varies
+// between Navidrome releases, so rows are read as JSON objects and columns
+// looked up by name.
+type navidrome struct {
+	database string
+	server   *url.URL
+	paths    pathMap
+	sqlite3  string
+	client   *http.Client
+}
+
+func newNavidrome(database string, server *url.URL, paths pathMap) *navidrome {
+	return &navidrome{
+		database: database,
+		server:   server,
+		paths:    paths,
+		sqlite3:  "sqlite3",
+		client:   &http.Client{Timeout: 30 * time.Second},
+	}
+}
+
+func (n *navidrome) Protocol() string { return "navidrome" }
+
+// SignIn checks the password through the running server's Subsonic API,
+// then finds the account in the database.
+func (n *navidrome) SignIn(ctx context.Context, username, password string) (string, error) {
+	salt := make([]byte, 8)
+	if _, err := rand.Read(salt); err != nil {
+		return "", err
+	}
+	sum := md5.Sum([]byte(password + hex.EncodeToString(salt)))
+	ping := n.server.JoinPath("rest", "ping.view")
+	ping.RawQuery = url.Values{
+		"u": {username},
+		"t": {hex.EncodeToString(sum[:])},
+		"s": {hex.EncodeToString(salt)},
+		"v": {"1.16.1"},
+		"c": {"openmusicplayer"},
+		"f": {"json"},
+	}.Encode()
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, ping.String(), nil)
+	if err != nil {
+		return "", err
+	}
+	resp, err := n.client.Do(req)
+	if err != nil {
+		return "", fmt.Errorf("navidrome ping: %w", err)
+	}
+	defer resp.Body.Close()
+	var body struct {
+		Response struct {
+			Status string `json:"status"`
+		} `json:"subsonic-response"`
+	}
+	if err := json.NewDecoder(resp.Body).Decode(&body); err != nil {
+		return "", fmt.Errorf("navidrome ping: %s: %w", resp.Status, err)
+	}
+	if body.Response.Status != "ok" {
+		return "", ErrSignInFailed
+	}
+
+	rows, err := n.query(ctx, "SELECT id FROM user WHERE user_name = CAST(:username AS TEXT) COLLATE NOCASE",
+		map[string]string{"username": username})
+	if err != nil {
+		return "", err
+	}
+	if len(rows) == 0 {
+		return "", fmt.Errorf("navidrome user %q is not in %s", username, n.database)
+	}
+	return rows[0].text("id"), nil
+}
+
+// Export reads the tracks the account annotated or put in a playlist, and
+// the account's playlists.
+func (n *navidrome) Export(ctx context.Context, accountID string) (*Export, error) {
+	user := map[string]string{"user": accountID}
+	rows, err := n.query(ctx, `
+		SELECT m.*, a.play_count AS omp_play_count, a.play_date AS omp_play_date,
+			a.rating AS omp_rating, a.starred AS omp_starred
+		FROM media_file m
+		LEFT JOIN annotation a ON a.item_id = m.id AND a.item_type = 'media_file' AND a.user_id = CAST(:user AS TEXT)
+		WHERE a.item_id IS NOT NULL
+		   OR m.id IN (SELECT media_file_id FROM playlist_tracks WHERE playlist_id IN (`+ownedPlaylists+`))`, user)
+	if err != nil {
+		return nil, err
+	}
+	roots, err := n.libraryRoots(ctx, rows)
+	if err != nil {
+		return nil, err
+	}
+
+	export := &Export{}
+	for _, row := range rows {
+		file := row.text("path")
+		if root, ok := roots[row.text("library_id")]; ok && !path.IsAbs(file) {
+			file = path.Join(root, file)
+		}
+		export.Tracks = append(export.Tracks, Track{
+			ID:            row.text("id"),
+			Path:          n.paths.apply(file),
+			MBRecordingID: firstText(row, "mbz_recording_id", "mbz_track_id"),
+			PlayCount:     row.number("omp_play_count"),
+			LastPlayed:    parseTime(row.text("omp_play_date")),
+			Favorite:      row.number("omp_starred") != 0,
+			Rating:        min(max(row.number("omp_rating"), 0), 5),
+		})
+	}
+
+	playlists, err := n.query(ctx, "SELECT * FROM playlist WHERE owner_id = CAST(:user AS TEXT) ORDER BY name", user)
+	if err != nil {
+		return nil, err
+	}
+	entries, err := n.query(ctx, "SELECT playlist_id, media_file_id FROM playlist_tracks WHERE playlist_id IN ("+ownedPlaylists+") ORDER BY playlist_id, id", user)
+	if err != nil {
+		return nil, err
+	}
+	tracks := map[string][]string{}
+	for _, entry := range entries {
+		id := entry.text("playlist_id")
+		tracks[id] = append(tracks[id], entry.text("media_file_id"))
+	}
+	for _, row := range playlists {
+		if rules := row.text("rules"); rules != "" && rules != "null" {
+			continue
+		}
+		id := row.text("id")
+		export.Playlists = append(export.Playlists, Playlist{ID: id, Name: row.text("name"), TrackIDs: tracks[id]})
+	}
+	return export, nil
+}
+
+// libraryRoots maps library IDs to their folders when rows hold paths
+// relative to a library, as Navidrome 0.55 and later store them.
+func (n *navidrome) libraryRoots(ctx context.Context, rows []sqliteRow) (map[string]string, error) {
+	relative := false
+	for _, row := range rows {
+		if _, ok := row["library_id"]; ok && !path.IsAbs(row.text("path")) {
+			relative = true
+			break
+		}
+	}
+	if !relative {
+		return nil, nil
+	}
+	libraries, err := n.query(ctx, "SELECT id, path FROM library", nil)
+	if err != nil {
+		return nil, err
+	}
+	roots := make(map[string]string, len(libraries))
+	for _, library := range libraries {
+		roots[library.text("id")] = library.text("path")
+	}
+	return roots, nil
+}
+
+// sqliteRow is one row of sqlite3 -json output.
+type sqliteRow map[string]any
+
+func (r sqliteRow) text(column string) string {
+	switch v := r[column].(type) {
+	case string:
+		return v
+	case json.Number:
+		return v.String()
+	case bool:
+		if v {
+			return "1"
+		}
+		return "0"
+	}
+	return ""
+}
+
+func (r sqliteRow) number(column string) int {
+	if n, ok := r[column].(json.Number); ok {
+		if i, err := n.Int64(); err == nil {
+			return int(i)
+		}
+		if f, err := n.Float64(); err == nil {
+			return int(f)
+		}
+	}
+	return 0
+}
+
+func firstText(row sqliteRow, columns ...string) string {
+	for _, column := range columns {
+		if value := row.text(column); value != "" {
+			return value
+		}
+	}
+	return ""
+}
+
+// ownedPlaylists selects the IDs of the playlists of the :user parameter.
+const ownedPlaylists = "SELECT id FROM playlist WHERE owner_id = CAST(:user AS TEXT)"
+
+// query runs a read-only statement against the database, binding params to
+// its :name parameters. Values are handed to sqlite3 as hex blob literals,
+// so no value is ever parsed as SQL; statements cast them back to text.
+func (n *navidrome) query(ctx context.Context, statement string, params map[string]string) ([]sqliteRow, error) {
+	args := []string{"-readonly", "-json"}
+	for _, name := range slices.Sorted(maps.Keys(params)) {
+		args = append(args, "-cmd", ".parameter set :"+name+" X'"+hex.EncodeToString([]byte(params[name]))+"'")
+	}
+	cmd := exec.CommandContext(ctx, n.sqlite3, append(args, n.database, statement)...)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	output, err := cmd.Output()
+	if errors.Is(err, exec.ErrNotFound) {
+		return nil, fmt.Errorf("%s is not installed; it is needed to read the Navidrome database", n.sqlite3)
+	}
+	if err != nil {
+		return nil, fmt.Errorf("sqlite3: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	// sqlite3 prints nothing at all for an empty result.
+	if len(bytes.TrimSpace(output)) == 0 {
+		return nil, nil
+	}
+	decoder := json.NewDecoder(bytes.NewReader(output))
+	decoder.UseNumber()
+	var rows []sqliteRow
+	if err := decoder.Decode(&rows); err != nil {
+		return nil, fmt.Errorf("decode sqlite3 output: %w", err)
+	}
+	return rows, nil
+}
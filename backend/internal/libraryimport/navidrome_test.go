@@ -0,0 +1,128 @@
+package libraryimport
+
+import (
+	"context"
+	"crypto/md5"
+	"encoding/hex"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"os"
+	"path/filepath"
+	"reflect"
+	"runtime"
+	"testing"
+	"time"
+)
+
+// fakeNavidrome serves a Subsonic ping that accepts alice/secret and answers
+// database queries with canned sqlite3 -json output.
+func fakeNavidrome(t *testing.T) *navidrome {
+	t.Helper()
+	if runtime.GOOS == "windows" {
+		t.Skip("fake sqlite3 is a shell script")
+	}
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		q := r.URL.Query()
+		sum := md5.Sum([]byte("secret" + q.Get("s")))
+		status := "failed"
+		if r.URL.Path == "/nd/rest/ping.view" && q.Get("u") == "alice" && q.Get("t") == hex.EncodeToString(sum[:]) {
+			status = "ok"
+		}
+		w.Write([]byte(`{"subsonic-response":{"status":"` + status + `","version":"1.16.1"}}`))
+	}))
+	t.Cleanup(server.Close)
+
+	dir := t.TempDir()
+	outputs := map[string]string{
+		"user.json": `[{"id":"u1"}]`,
+		"tracks.json": `[
+			{"id":"t1","library_id":1,"path":"Low/Secret Name/01 Starfire.flac","mbz_recording_id":"11111111-1111-1111-1111-111111111111","omp_play_count":12,"omp_play_date":"2024-05-01 20:30:00+00:00","omp_rating":4,"omp_starred":1},
+			{"id":"t2","library_id":1,"path":"Low/Demo.mp3","mbz_recording_id":"","mbz_track_id":"22222222-2222-2222-2222-222222222222","omp_play_count":null,"omp_play_date":null,"omp_rating":null,"omp_starred":null}
+		]`,
+		"libraries.json": `[{"id":1,"path":"/music"}]`,
+		"playlists.json": `[{"id":"p1","name":"Night","rules":null},{"id":"p2","name":"Smart","rules":"{\"all\":[]}"}]`,
+		"entries.json":   `[{"playlist_id":"p1","media_file_id":"t2"},{"playlist_id":"p1","media_file_id":"t1"},{"playlist_id":"p2","media_file_id":"t1"}]`,
+	}
+	for name, output := range outputs {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(output), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	// Parameters arrive as -cmd .parameter set options, hex-encoded: alice
+	// is 616c696365 and u1 is 7531.
+	alice := ".parameter set :username X'616c696365';"
+	u1 := ".parameter set :user X'7531';"
+	script := "#!/bin/sh\n" +
+		"[ \"$1 $2\" = \"-readonly -json\" ] || exit 2\n" +
+		"shift 2\n" +
+		"params=\n" +
+		"while [ \"$1\" = -cmd ]; do params=\"$params$2;\"; shift 2; done\n" +
+		"[ \"$1\" = /data/navidrome.db ] || exit 2\n" +
+		"case \"$params|$2\" in\n" +
+		"\"" + alice + "|\"*\"FROM user WHERE user_name = CAST(:username AS TEXT)\"*) cat " + dir + "/user.json ;;\n" +
+		"\"" + u1 + "|\"*\"FROM media_file m\"*) cat " + dir + "/tracks.json ;;\n" +
+		"\"|\"*\"FROM library\"*) cat " + dir + "/libraries.json ;;\n" +
+		"\"" + u1 + "|\"*\"FROM playlist_tracks\"*) cat " + dir + "/entries.json ;;\n" +
+		"\"" + u1 + "|\"*\"FROM playlist WHERE owner_id = CAST(:user AS TEXT)\"*) cat " + dir + "/playlists.json ;;\n" +
+		"*) exit 3 ;;\n" +
+		"esac\n"
+	sqlite3 := filepath.Join(dir, "sqlite3")
+	if err := os.WriteFile(sqlite3, []byte(script), 0o755); err != nil {
+		t.Fatal(err)
+	}
+
+	serverURL, err := url.Parse(server.URL + "/nd")
+	if err != nil {
+		t.Fatal(err)
+	}
+	n := newNavidrome("/data/navidrome.db", serverURL, pathMap{from: "/music", to: "/srv/music"})
+	n.sqlite3 = sqlite3
+	return n
+}
+
+func TestNavidromeSignIn(t *testing.T) {
+	n := fakeNavidrome(t)
+	ctx := context.Background()
+
+	id, err := n.SignIn(ctx, "alice", "secret")
+	if err != nil || id != "u1" {
+		t.Fatalf("SignIn() = %q, %v", id, err)
+	}
+	if _, err := n.SignIn(ctx, "alice", "wrong"); !errors.Is(err, ErrSignInFailed) {
+		t.Fatalf("SignIn() with a wrong password error = %v, want ErrSignInFailed", err)
+	}
+}
+
+func TestNavidromeExport(t *testing.T) {
+	n := fakeNavidrome(t)
+
+	got, err := n.Export(context.Background(), "u1")
+	if err != nil {
+		t.Fatalf("Export() error = %v", err)
+	}
+	want := &Export{
+		Tracks: []Track{
+			{
+				ID:            "t1",
+				Path:          "/srv/music/Low/Secret Name/01 Starfire.flac",
+				MBRecordingID: "11111111-1111-1111-1111-111111111111",
+				PlayCount:     12,
+				Favorite:      true,
+				Rating:        4,
+			},
+			{ID: "t2", Path: "/srv/music/Low/Demo.mp3", MBRecordingID: "22222222-2222-2222-2222-222222222222"},
+		},
+		Playlists: []Playlist{{ID: "p1", Name: "Night", TrackIDs: []string{"t2", "t1"}}},
+	}
+	if len(got.Tracks) > 0 {
+		if lastPlayed := time.Date(2024, 5, 1, 20, 30, 0, 0, time.UTC); !got.Tracks[0].LastPlayed.Equal(lastPlayed) {
+			t.Errorf("LastPlayed = %v, want %v", got.Tracks[0].LastPlayed, lastPlayed)
+		}
+		got.Tracks[0].LastPlayed = time.Time{}
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("Export() = %+v, want %+v", got, want)
+	}
+}
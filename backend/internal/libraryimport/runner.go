@@ -0,0 +1,294 @@
+package libraryimport
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"strconv"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// Migration is the job kind that migrates one source account's listening
+// data into a user's library.
+var Migration = jobs.NewKind[MigrationPayload]("library_migration")
+
+type MigrationPayload struct {
+	Source string `json:"source"`
+	// AccountID is the account's ID on the source, found when the user
+	// signed in to it.
+	AccountID string `json:"account_id"`
+	// Account is the user name the user signed in with, for display.
+	Account string `json:"account"`
+}
+
+// MigrationProgress is the progress detail of migration jobs.
+type MigrationProgress struct {
+	Matched   int `json:"matched"`
+	Unmatched int `json:"unmatched"`
+	Favorites int `json:"favorites"`
+	Ratings   int `json:"ratings"`
+	Plays     int `json:"plays"`
+	Playlists int `json:"playlists"`
+}
+
+// SourceInfo describes a configured source without its connection details.
+type SourceInfo struct {
+	Name     string `json:"name"`
+	Protocol string `json:"protocol"`
+}
+
+// Queue queues migration jobs.
+type Queue struct {
+	store   *jobs.Store
+	sources Sources
+}
+
+func NewQueue(store *jobs.Store, sources Sources) *Queue {
+	return &Queue{store: store, sources: sources}
+}
+
+// Sources lists the sources users may migrate from.
+func (q *Queue) Sources() []SourceInfo {
+	infos := make([]SourceInfo, 0, len(q.sources))
+	for _, name := range q.sources.Names() {
+		infos = append(infos, SourceInfo{Name: name, Protocol: q.sources[name].Protocol()})
+	}
+	return infos
+}
+
+// Enqueue signs in to the named source as username and queues a migration of
+// that account for the user. A user runs at most one migration from a source
+// at a time; a second request while one is queued or running returns
+// jobs.ErrDuplicate.
+func (q *Queue) Enqueue(ctx context.Context, userID uuid.UUID, source, username, password string) (*jobs.Job, error) {
+	src, ok := q.sources[source]
+	if !ok {
+		return nil, fmt.Errorf("%w: %q", ErrUnknownSource, source)
+	}
+	accountID, err := src.SignIn(ctx, username, password)
+	if errors.Is(err, ErrSignInFailed) {
+		return nil, err
+	}
+	if err != nil {
+		return nil, fmt.Errorf("%w: %v", ErrSourceUnavailable, err)
+	}
+	// Everything a migration writes is idempotent, so running one again
+	// picks up plays, favorites, and playlist changes made since.
+	return Migration.Enqueue(ctx, q.store, MigrationPayload{Source: source, AccountID: accountID, Account: username}, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   userID.String() + ":" + source,
+	})
+}
+
+// TrackLocator finds tracks already in Open Music Player.
+// db.TrackRepository satisfies this interface.
+type TrackLocator interface {
+	LocateTrack(ctx context.Context, sourceURL, mbRecordingID string) (int64, error)
+}
+
+// Library records library membership, favorites, and ratings.
+// db.LibraryRepository satisfies this interface.
+type Library interface {
+	AddTrackToLibrary(ctx context.Context, userID uuid.UUID, trackID int64) (*db.LibraryEntry, error)
+	AddFavorite(ctx context.Context, userID uuid.UUID, trackID int64, contextType, contextID string) error
+	SetRating(ctx context.Context, userID uuid.UUID, trackID int64, rating int) error
+}
+
+// Plays records imported play counts. db.PlayEventRepository satisfies this
+// interface.
+type Plays interface {
+	ImportPlays(ctx context.Context, userID uuid.UUID, trackID int64, contextID string, count int, lastPlayed time.Time) (int, error)
+}
+
+// Playlists creates playlists. db.PlaylistRepository satisfies this
+// interface.
+type Playlists interface {
+	Create(ctx context.Context, playlist *db.Playlist) error
+}
+
+// PlaylistSources binds playlists to the source playlists they came from.
+// db.PlaylistSourceRepository satisfies this interface.
+type PlaylistSources interface {
+	LoadBindingBySource(ctx context.Context, userID uuid.UUID, provider, providerPlaylistID string) (*db.PlaylistSourceBinding, error)
+	ApplyResolvedMapping(ctx context.Context, binding *db.PlaylistSourceBinding, entries []db.ResolvedPlaylistSourceEntry) error
+}
+
+// RunnerConfig holds the repositories a Runner writes to.
+type RunnerConfig struct {
+	Tracks          TrackLocator
+	Library         Library
+	Plays           Plays
+	Playlists       Playlists
+	PlaylistSources PlaylistSources
+}
+
+// progressReporter records a job's progress. *jobs.Progress satisfies it.
+type progressReporter interface {
+	Report(ctx context.Context, current, total int, detail any) error
+}
+
+// progressEvery is how many tracks pass between progress reports.
+const progressEvery = 100
+
+// Runner migrates one source account's listening data per job.
+type Runner struct {
+	sources Sources
+	cfg     RunnerConfig
+}
+
+func NewRunner(sources Sources, cfg RunnerConfig) *Runner {
+	return &Runner{sources: sources, cfg: cfg}
+}
+
+// Register runs migration jobs on w.
+func (r *Runner) Register(w *jobs.Worker) {
+	Migration.Handle(w, func(ctx context.Context, job *jobs.Job, payload MigrationPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.UserID.UUID, payload, progress)
+	})
+}
+
+// Run performs one migration.
+func (r *Runner) Run(ctx context.Context, userID uuid.UUID, payload MigrationPayload, progress progressReporter) error {
+	source, ok := r.sources[payload.Source]
+	if !ok {
+		return jobs.Permanent(fmt.Errorf("%w: %q", ErrUnknownSource, payload.Source))
+	}
+	export, err := source.Export(ctx, payload.AccountID)
+	if err != nil {
+		return err
+	}
+
+	var counts MigrationProgress
+	total := len(export.Tracks) + len(export.Playlists)
+	if err := progress.Report(ctx, 0, total, counts); err != nil {
+		return err
+	}
+	matched := make(map[string]int64, len(export.Tracks))
+	paths := make(map[string]string, len(export.Tracks))
+	for i, track := range export.Tracks {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		paths[track.ID] = track.Path
+		trackID, err := r.migrateTrack(ctx, userID, payload.Source, track, &counts)
+		if err != nil {
+			return err
+		}
+		if trackID != 0 {
+			matched[track.ID] = trackID
+		}
+		if (i+1)%progressEvery == 0 {
+			if err := progress.Report(ctx, i+1, total, counts); err != nil {
+				return err
+			}
+		}
+	}
+	for i, playlist := range export.Playlists {
+		if err := r.migratePlaylist(ctx, userID, payload.Source, source.Protocol(), playlist, matched, paths); err != nil {
+			return err
+		}
+		counts.Playlists++
+		if err := progress.Report(ctx, len(export.Tracks)+i+1, total, counts); err != nil {
+			return err
+		}
+	}
+	return progress.Report(ctx, total, total, counts)
+}
+
+// migrateTrack carries one track's listening data over, returning the
+// matching track's ID, or 0 when nothing matches.
+func (r *Runner) migrateTrack(ctx context.Context, userID uuid.UUID, source string, track Track, counts *MigrationProgress) (int64, error) {
+	trackID, err := r.cfg.Tracks.LocateTrack(ctx, fileURL(track.Path), track.MBRecordingID)
+	if errors.Is(err, db.ErrTrackNotFound) {
+		counts.Unmatched++
+		return 0, nil
+	}
+	if err != nil {
+		return 0, fmt.Errorf("locate %s: %w", track.Path, err)
+	}
+	if _, err := r.cfg.Library.AddTrackToLibrary(ctx, userID, trackID); errors.Is(err, db.ErrTrackNotFound) {
+		// The match is another tenant's track.
+		counts.Unmatched++
+		return 0, nil
+	} else if err != nil && !errors.Is(err, db.ErrTrackAlreadyInLibrary) {
+		return 0, fmt.Errorf("add track %d to library: %w", trackID, err)
+	}
+	counts.Matched++
+	if track.Favorite {
+		if err := r.cfg.Library.AddFavorite(ctx, userID, trackID, "", ""); err != nil {
+			return 0, fmt.Errorf("favorite track %d: %w", trackID, err)
+		}
+		counts.Favorites++
+	}
+	if track.Rating > 0 {
+		if err := r.cfg.Library.SetRating(ctx, userID, trackID, track.Rating); err != nil {
+			return 0, fmt.Errorf("rate track %d: %w", trackID, err)
+		}
+		counts.Ratings++
+	}
+	if track.PlayCount > 0 {
+		added, err := r.cfg.Plays.ImportPlays(ctx, userID, trackID, source, track.PlayCount, track.LastPlayed)
+		if err != nil {
+			return 0, fmt.Errorf("import plays of track %d: %w", trackID, err)
+		}
+		counts.Plays += added
+	}
+	return trackID, nil
+}
+
+// migratePlaylist creates or updates the playlist bound to a source
+// playlist. Entries whose tracks matched nothing stay unresolved.
+func (r *Runner) migratePlaylist(ctx context.Context, userID uuid.UUID, source, protocol string, playlist Playlist, matched map[string]int64, paths map[string]string) error {
+	providerPlaylistID := source + ":" + playlist.ID
+	binding, err := r.cfg.PlaylistSources.LoadBindingBySource(ctx, userID, protocol, providerPlaylistID)
+	if errors.Is(err, db.ErrPlaylistSourceBindingNotFound) {
+		name := playlist.Name
+		if name == "" {
+			name = "Imported playlist"
+		}
+		created := &db.Playlist{UserID: userID, Name: name}
+		if err := r.cfg.Playlists.Create(ctx, created); err != nil {
+			return fmt.Errorf("create playlist %q: %w", name, err)
+		}
+		binding = &db.PlaylistSourceBinding{
+			PlaylistID:         created.ID,
+			UserID:             userID,
+			Provider:           protocol,
+			ProviderPlaylistID: providerPlaylistID,
+			CanonicalURL:       protocol + "://" + source + "/playlists/" + playlist.ID,
+		}
+	} else if err != nil {
+		return fmt.Errorf("load playlist binding %s: %w", providerPlaylistID, err)
+	}
+
+	entries := make([]db.ResolvedPlaylistSourceEntry, 0, len(playlist.TrackIDs))
+	for i, id := range playlist.TrackIDs {
+		entries = append(entries, db.ResolvedPlaylistSourceEntry{
+			// A track can appear more than once, so entries are keyed by
+			// position.
+			ProviderEntryID: strconv.Itoa(i),
+			SourceURL:       fileURL(paths[id]),
+			TrackID:         matched[id],
+			SourceOrder:     i,
+		})
+	}
+	if err := r.cfg.PlaylistSources.ApplyResolvedMapping(ctx, binding, entries); err != nil {
+		return fmt.Errorf("update playlist %q: %w", playlist.Name, err)
+	}
+	return nil
+}
+
+// fileURL is the source URL local imports record for path.
+func fileURL(path string) string {
+	if path == "" {
+		return ""
+	}
+	return "file://" + path
+}
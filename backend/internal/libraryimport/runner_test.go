@@ -0,0 +1,159 @@
+package libraryimport
+
+import (
+	"context"
+	"reflect"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeSource struct{ export *Export }
+
+func (f *fakeSource) Protocol() string { return "navidrome" }
+
+func (f *fakeSource) SignIn(context.Context, string, string) (string, error) { return "u1", nil }
+
+func (f *fakeSource) Export(context.Context, string) (*Export, error) { return f.export, nil }
+
+// fakeTracks holds track IDs keyed by source URL or MusicBrainz recording ID.
+type fakeTracks map[string]int64
+
+func (f fakeTracks) LocateTrack(_ context.Context, sourceURL, mbRecordingID string) (int64, error) {
+	if id, ok := f[sourceURL]; ok {
+		return id, nil
+	}
+	if id, ok := f[mbRecordingID]; ok && mbRecordingID != "" {
+		return id, nil
+	}
+	return 0, db.ErrTrackNotFound
+}
+
+type fakeLibrary struct {
+	added     []int64
+	favorites []int64
+	ratings   map[int64]int
+	plays     map[int64]int
+}
+
+func (f *fakeLibrary) AddTrackToLibrary(_ context.Context, _ uuid.UUID, trackID int64) (*db.LibraryEntry, error) {
+	f.added = append(f.added, trackID)
+	return &db.LibraryEntry{TrackID: trackID}, nil
+}
+
+func (f *fakeLibrary) AddFavorite(_ context.Context, _ uuid.UUID, trackID int64, _, _ string) error {
+	f.favorites = append(f.favorites, trackID)
+	return nil
+}
+
+func (f *fakeLibrary) SetRating(_ context.Context, _ uuid.UUID, trackID int64, rating int) error {
+	f.ratings[trackID] = rating
+	return nil
+}
+
+// ImportPlays mirrors the repository: only plays beyond those already
+// imported are added.
+func (f *fakeLibrary) ImportPlays(_ context.Context, _ uuid.UUID, trackID int64, _ string, count int, _ time.Time) (int, error) {
+	added := max(count-f.plays[trackID], 0)
+	f.plays[trackID] += added
+	return added, nil
+}
+
+type fakePlaylists struct {
+	created  []string
+	bindings map[string]*db.PlaylistSourceBinding
+	entries  map[int64][]db.ResolvedPlaylistSourceEntry
+}
+
+func (f *fakePlaylists) Create(_ context.Context, playlist *db.Playlist) error {
+	f.created = append(f.created, playlist.Name)
+	playlist.ID = int64(len(f.created))
+	return nil
+}
+
+func (f *fakePlaylists) LoadBindingBySource(_ context.Context, _ uuid.UUID, provider, providerPlaylistID string) (*db.PlaylistSourceBinding, error) {
+	if binding, ok := f.bindings[provider+" "+providerPlaylistID]; ok {
+		return binding, nil
+	}
+	return nil, db.ErrPlaylistSourceBindingNotFound
+}
+
+func (f *fakePlaylists) ApplyResolvedMapping(_ context.Context, binding *db.PlaylistSourceBinding, entries []db.ResolvedPlaylistSourceEntry) error {
+	f.bindings[binding.Provider+" "+binding.ProviderPlaylistID] = binding
+	f.entries[binding.PlaylistID] = entries
+	return nil
+}
+
+type fakeProgress struct {
+	last    MigrationProgress
+	current int
+	total   int
+}
+
+func (p *fakeProgress) Report(_ context.Context, current, total int, detail any) error {
+	p.last, p.current, p.total = detail.(MigrationProgress), current, total
+	return nil
+}
+
+func TestRunnerMigratesListeningData(t *testing.T) {
+	source := &fakeSource{export: &Export{
+		Tracks: []Track{
+			{ID: "t1", Path: "/music/Low/01 Starfire.flac", PlayCount: 12, Favorite: true, Rating: 4},
+			{ID: "t2", Path: "/elsewhere/Demo.mp3", MBRecordingID: "11111111-1111-1111-1111-111111111111", PlayCount: 2},
+			{ID: "t3", Path: "/music/Missing.flac", Favorite: true},
+		},
+		Playlists: []Playlist{{ID: "p1", Name: "Night", TrackIDs: []string{"t3", "t1", "t2", "t1"}}},
+	}}
+	tracks := fakeTracks{"file:///music/Low/01 Starfire.flac": 7, "11111111-1111-1111-1111-111111111111": 9}
+	library := &fakeLibrary{ratings: map[int64]int{}, plays: map[int64]int{}}
+	playlists := &fakePlaylists{bindings: map[string]*db.PlaylistSourceBinding{}, entries: map[int64][]db.ResolvedPlaylistSourceEntry{}}
+	runner := NewRunner(Sources{"home": source}, RunnerConfig{
+		Tracks:          tracks,
+		Library:         library,
+		Plays:           library,
+		Playlists:       playlists,
+		PlaylistSources: playlists,
+	})
+	userID := uuid.New()
+	payload := MigrationPayload{Source: "home", AccountID: "u1", Account: "alice"}
+
+	progress := &fakeProgress{}
+	if err := runner.Run(context.Background(), userID, payload, progress); err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	want := MigrationProgress{Matched: 2, Unmatched: 1, Favorites: 1, Ratings: 1, Plays: 14, Playlists: 1}
+	if progress.last != want || progress.current != 4 || progress.total != 4 {
+		t.Fatalf("progress = %+v (%d/%d), want %+v", progress.last, progress.current, progress.total, want)
+	}
+	if !reflect.DeepEqual(library.added, []int64{7, 9}) || !reflect.DeepEqual(library.favorites, []int64{7}) || library.ratings[7] != 4 {
+		t.Fatalf("library = %+v", library)
+	}
+
+	binding := playlists.bindings["navidrome home:p1"]
+	if binding == nil || binding.PlaylistID != 1 || binding.UserID != userID || binding.CanonicalURL != "navidrome://home/playlists/p1" {
+		t.Fatalf("binding = %+v", binding)
+	}
+	wantEntries := []db.ResolvedPlaylistSourceEntry{
+		{ProviderEntryID: "0", SourceURL: "file:///music/Missing.flac", SourceOrder: 0},
+		{ProviderEntryID: "1", SourceURL: "file:///music/Low/01 Starfire.flac", TrackID: 7, SourceOrder: 1},
+		{ProviderEntryID: "2", SourceURL: "file:///elsewhere/Demo.mp3", TrackID: 9, SourceOrder: 2},
+		{ProviderEntryID: "3", SourceURL: "file:///music/Low/01 Starfire.flac", TrackID: 7, SourceOrder: 3},
+	}
+	if !reflect.DeepEqual(playlists.entries[1], wantEntries) {
+		t.Fatalf("entries = %+v, want %+v", playlists.entries[1], wantEntries)
+	}
+
+	// A second run after the missing file arrives adds it, reuses the
+	// playlist, and imports only the new plays.
+	tracks["file:///music/Missing.flac"] = 11
+	source.export.Tracks[0].PlayCount = 13
+	if err := runner.Run(context.Background(), userID, payload, progress); err != nil {
+		t.Fatalf("second Run() error = %v", err)
+	}
+	if progress.last.Plays != 1 || progress.last.Unmatched != 0 || len(playlists.created) != 1 || playlists.entries[1][0].TrackID != 11 {
+		t.Fatalf("second run progress = %+v, playlists = %+v", progress.last, playlists)
+	}
+}
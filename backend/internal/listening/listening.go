@@ -0,0 +1,182 @@
+// Package listening reconstructs listening sessions from a user's plays and
+// skips, to work out what they were in the middle of and where to pick it up.
+//
+// A session is a run of events in one context, such as a playlist or an
+// album, with no break longer than SessionGap. Only the latest session of
+// each context counts: an album played twice this week is continued from the
+// second listen, not listed twice. Sessions older than MaxAge have expired.
+//
+// It also groups a user's plays into moments, such as weekday mornings, to
+// suggest what they tend to play at the current one.
+package listening
+
+import (
+	"sort"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	// SessionGap is the longest break that still continues a session.
+	SessionGap = 30 * time.Minute
+	// MaxAge is how long after its last event a session can be continued.
+	MaxAge = 14 * 24 * time.Hour
+)
+
+// resumableContexts are the contexts a listener works through and may want
+// to continue. Library, queue, and search plays have no order to resume.
+var resumableContexts = map[string]bool{
+	"playlist": true,
+	"album":    true,
+	"artist":   true,
+	"radio":    true,
+}
+
+// Session is a run of listening in one context.
+type Session struct {
+	ContextType string
+	ContextID   string
+	Events      []db.ListeningEvent
+}
+
+// StartedAt is the time of the session's first event.
+func (s Session) StartedAt() time.Time { return s.Events[0].At }
+
+// EndedAt is the time of the session's last event.
+func (s Session) EndedAt() time.Time { return s.Events[len(s.Events)-1].At }
+
+// Key identifies a context across sessions.
+func Key(contextType, contextID string) string { return contextType + ":" + contextID }
+
+// Sessions splits events, oldest first, into sessions. An event in another
+// context, or after a break longer than SessionGap, starts a new session.
+func Sessions(events []db.ListeningEvent) []Session {
+	var sessions []Session
+	for _, e := range events {
+		if n := len(sessions); n > 0 {
+			last := &sessions[n-1]
+			if last.ContextType == e.ContextType && last.ContextID == e.ContextID && e.At.Sub(last.EndedAt()) <= SessionGap {
+				last.Events = append(last.Events, e)
+				continue
+			}
+		}
+		sessions = append(sessions, Session{ContextType: e.ContextType, ContextID: e.ContextID, Events: []db.ListeningEvent{e}})
+	}
+	return sessions
+}
+
+// Latest returns the latest session of each resumable context that has not
+// expired by now, most recently ended first.
+func Latest(sessions []Session, now time.Time) []Session {
+	latest := map[string]Session{}
+	for _, s := range sessions {
+		if !resumableContexts[s.ContextType] || s.ContextID == "" || now.Sub(s.EndedAt()) > MaxAge {
+			continue
+		}
+		key := Key(s.ContextType, s.ContextID)
+		if prev, ok := latest[key]; !ok || s.EndedAt().After(prev.EndedAt()) {
+			latest[key] = s
+		}
+	}
+	result := make([]Session, 0, len(latest))
+	for _, s := range latest {
+		result = append(result, s)
+	}
+	sort.Slice(result, func(i, j int) bool {
+		if !result[i].EndedAt().Equal(result[j].EndedAt()) {
+			return result[i].EndedAt().After(result[j].EndedAt())
+		}
+		return Key(result[i].ContextType, result[i].ContextID) < Key(result[j].ContextType, result[j].ContextID)
+	})
+	return result
+}
+
+// Point is where to continue a session.
+type Point struct {
+	Session Session
+	// TrackID is the track to continue at.
+	TrackID int64
+	// Position is the 1-based place of TrackID in the context's order, or 0
+	// when the order is unknown.
+	Position int
+	// Heard is how many distinct tracks of the session were played rather
+	// than skipped. Repeats of a track count once.
+	Heard int
+}
+
+// Resume works out where to continue a session. order lists the context's
+// tracks in play order, or is nil when it is unknown, as for albums and
+// radio. It reports false when the session is finished: with a known order,
+// the listener skipped past the last track, or reached every track and
+// played the last.
+//
+// The session continues at the track it was last on, which may have been
+// interrupted. A track skipped last was moved past, so with a known order the
+// session continues at the track after it.
+func Resume(s Session, order []int64) (Point, bool) {
+	point := Point{Session: s}
+	heard := map[int64]bool{}
+	reached := map[int64]bool{}
+	for _, e := range s.Events {
+		reached[e.TrackID] = true
+		if !e.Skipped {
+			heard[e.TrackID] = true
+		}
+	}
+	point.Heard = len(heard)
+
+	last := s.Events[len(s.Events)-1]
+	point.TrackID = last.TrackID
+	if len(order) == 0 {
+		return point, true
+	}
+
+	index := -1
+	for i, id := range order {
+		if id == last.TrackID {
+			index = i
+			break
+		}
+	}
+	if index < 0 {
+		// The track left the playlist since; continue where the order can
+		// no longer say, at the track itself.
+		return point, true
+	}
+	if last.Skipped {
+		if index == len(order)-1 {
+			return point, false
+		}
+		index++
+		point.TrackID = order[index]
+	} else if index == len(order)-1 && reachedAll(order, reached) {
+		return point, false
+	}
+	point.Position = index + 1
+	return point, true
+}
+
+func reachedAll(order []int64, reached map[int64]bool) bool {
+	for _, id := range order {
+		if !reached[id] {
+			return false
+		}
+	}
+	return true
+}
+
+// Dedupe drops points that continue at a track an earlier point already
+// continues at, such as an album and its artist played in one sitting.
+func Dedupe(points []Point) []Point {
+	seen := map[int64]bool{}
+	kept := points[:0]
+	for _, p := range points {
+		if seen[p.TrackID] {
+			continue
+		}
+		seen[p.TrackID] = true
+		kept = append(kept, p)
+	}
+	return kept
+}
@@ -0,0 +1,128 @@
+package listening
+
+import (
+	"reflect"
+	"testing"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+var start = time.Date(2026, 3, 1, 20, 0, 0, 0, time.UTC)
+
+func play(minutes int, trackID int64, contextType, contextID string) db.ListeningEvent {
+	return db.ListeningEvent{TrackID: trackID, At: start.Add(time.Duration(minutes) * time.Minute), ContextType: contextType, ContextID: contextID}
+}
+
+func skip(minutes int, trackID int64, contextType, contextID string) db.ListeningEvent {
+	e := play(minutes, trackID, contextType, contextID)
+	e.Skipped = true
+	return e
+}
+
+func TestSessionsSplitOnContextAndBreaks(t *testing.T) {
+	events := []db.ListeningEvent{
+		play(0, 1, "album", "a"),
+		play(4, 2, "album", "a"),
+		play(8, 3, "playlist", "7"),
+		play(12, 4, "album", "a"),
+		play(60, 5, "album", "a"),
+	}
+	var got [][]int64
+	for _, s := range Sessions(events) {
+		var ids []int64
+		for _, e := range s.Events {
+			ids = append(ids, e.TrackID)
+		}
+		got = append(got, ids)
+	}
+	want := [][]int64{{1, 2}, {3}, {4}, {5}}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("sessions = %v, want %v", got, want)
+	}
+}
+
+func TestLatestKeepsOneUnexpiredSessionPerContext(t *testing.T) {
+	events := []db.ListeningEvent{
+		play(0, 1, "album", "old"),
+		play(10, 2, "album", "a"),
+		play(20, 3, "library", ""),
+		play(30, 4, "search", "query"),
+		play(100, 5, "album", "a"),
+		play(110, 6, "radio", "seed"),
+	}
+	now := start.Add(MaxAge).Add(5 * time.Minute)
+	var got []int64
+	for _, s := range Latest(Sessions(events), now) {
+		got = append(got, s.Events[0].TrackID)
+	}
+	if want := []int64{6, 5}; !reflect.DeepEqual(got, want) {
+		t.Fatalf("latest sessions start with %v, want %v", got, want)
+	}
+}
+
+func TestResume(t *testing.T) {
+	order := []int64{1, 2, 3}
+	tests := []struct {
+		name     string
+		events   []db.ListeningEvent
+		order    []int64
+		want     int64
+		position int
+		heard    int
+		finished bool
+	}{
+		{
+			name:     "continues at the interrupted track",
+			events:   []db.ListeningEvent{play(0, 1, "playlist", "7"), play(4, 1, "playlist", "7"), play(8, 2, "playlist", "7")},
+			order:    order,
+			want:     2,
+			position: 2,
+			heard:    2,
+		},
+		{
+			name:     "moves past a track skipped last",
+			events:   []db.ListeningEvent{play(0, 1, "playlist", "7"), skip(4, 2, "playlist", "7")},
+			order:    order,
+			want:     3,
+			position: 3,
+			heard:    1,
+		},
+		{
+			name:     "finishes after playing every track",
+			events:   []db.ListeningEvent{play(0, 1, "playlist", "7"), skip(4, 2, "playlist", "7"), play(5, 3, "playlist", "7")},
+			order:    order,
+			finished: true,
+		},
+		{
+			name:     "finishes after skipping the last track",
+			events:   []db.ListeningEvent{play(0, 2, "playlist", "7"), skip(4, 3, "playlist", "7")},
+			order:    order,
+			finished: true,
+		},
+		{
+			name:   "continues albums at the last track without an order",
+			events: []db.ListeningEvent{play(0, 8, "album", "a"), skip(4, 9, "album", "a")},
+			want:   9,
+			heard:  1,
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			point, ok := Resume(Sessions(tt.events)[0], tt.order)
+			if ok == tt.finished {
+				t.Fatalf("resumable = %v, want %v", ok, !tt.finished)
+			}
+			if ok && (point.TrackID != tt.want || point.Position != tt.position || point.Heard != tt.heard) {
+				t.Fatalf("point = track %d at %d after %d heard, want track %d at %d after %d", point.TrackID, point.Position, point.Heard, tt.want, tt.position, tt.heard)
+			}
+		})
+	}
+}
+
+func TestDedupeKeepsTheNewestPointPerTrack(t *testing.T) {
+	points := []Point{{TrackID: 4}, {TrackID: 5}, {TrackID: 4}}
+	if got := Dedupe(points); len(got) != 2 || got[0].TrackID != 4 || got[1].TrackID != 5 {
+		t.Fatalf("deduped = %+v", got)
+	}
+}
@@ -0,0 +1,200 @@
+package listening
+
+import (
+	"fmt"
+	"sort"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// Parts of the day plays are grouped by, in the listener's time zone.
+const (
+	Morning   = "morning"
+	Afternoon = "afternoon"
+	Evening   = "evening"
+	Night     = "night"
+)
+
+const (
+	// HistoryWindow is how far back plays shape the listener's moments.
+	HistoryWindow = 90 * 24 * time.Hour
+
+	// minMomentPlays is the fewest plays a moment needs before it says
+	// anything about the listener.
+	minMomentPlays = 20
+
+	// minArtistPlays and minArtistLift are how often, and how many times more
+	// often than overall, an artist must be played at a moment to stand out.
+	minArtistPlays = 3
+	minArtistLift  = 1.5
+
+	// minMeasuredPlays is the fewest plays of tracks with a measured energy a
+	// moment needs for its mood to count, and moodShift how far its energy
+	// must stray from the listener's overall energy to lean one way.
+	minMeasuredPlays = 10
+	moodShift        = 0.1
+)
+
+// Moment is a part of the day on weekdays or at weekends: a cluster of
+// hours a listener tends to play alike in, such as weekday mornings.
+type Moment struct {
+	Weekend bool
+	Part    string
+}
+
+// MomentAt is the moment t falls in, read in t's location.
+func MomentAt(t time.Time) Moment {
+	return momentOf(t.Weekday(), t.Hour())
+}
+
+// momentOf places an hour of a weekday. Hours before five belong to the
+// night before, so a Sunday night runs into Monday.
+func momentOf(weekday time.Weekday, hour int) Moment {
+	if hour < 5 {
+		weekday = (weekday + 6) % 7
+	}
+	m := Moment{Weekend: weekday == time.Saturday || weekday == time.Sunday}
+	switch {
+	case hour >= 5 && hour < 12:
+		m.Part = Morning
+	case hour >= 12 && hour < 17:
+		m.Part = Afternoon
+	case hour >= 17 && hour < 22:
+		m.Part = Evening
+	default:
+		m.Part = Night
+	}
+	return m
+}
+
+// String names the moment as in "weekday mornings".
+func (m Moment) String() string {
+	days := "weekday"
+	if m.Weekend {
+		days = "weekend"
+	}
+	return days + " " + m.Part + "s"
+}
+
+// MomentArtist is an artist the listener plays notably more at a moment.
+type MomentArtist struct {
+	ArtistID uuid.UUID
+	Name     string
+	Plays    int
+	// Lift is how many times larger the artist's share of the moment's
+	// plays is than its share of all plays.
+	Lift float64
+}
+
+// Profile is how the listener's plays at a moment differ from their plays
+// overall.
+type Profile struct {
+	Moment Moment
+	Plays  int
+	// Artists stand out at the moment, most played there first.
+	Artists []MomentArtist
+	// Mood is the mood bucket, chill or high_energy, the moment's plays
+	// lean toward against the listener's overall energy, or empty.
+	Mood string
+}
+
+// ProfileOf profiles the listener's plays at moment from their plays by
+// hour. It reports false when too few plays fall in the moment to say
+// anything.
+func ProfileOf(plays []db.HourlyArtistPlays, moment Moment) (Profile, bool) {
+	type tally struct {
+		name      string
+		total, at int
+	}
+	profile := Profile{Moment: moment}
+	artists := map[uuid.UUID]*tally{}
+	var order []uuid.UUID
+	var total, measured, measuredAt int
+	var energy, energyAt float64
+	for _, p := range plays {
+		at := momentOf(p.Weekday, p.Hour) == moment
+		total += p.Plays
+		measured += p.Measured
+		energy += p.Energy * float64(p.Measured)
+		if at {
+			profile.Plays += p.Plays
+			measuredAt += p.Measured
+			energyAt += p.Energy * float64(p.Measured)
+		}
+		if !p.ArtistID.Valid {
+			continue
+		}
+		t, ok := artists[p.ArtistID.UUID]
+		if !ok {
+			t = &tally{name: p.Artist}
+			artists[p.ArtistID.UUID] = t
+			order = append(order, p.ArtistID.UUID)
+		}
+		t.total += p.Plays
+		if at {
+			t.at += p.Plays
+		}
+	}
+	if profile.Plays < minMomentPlays {
+		return profile, false
+	}
+
+	for _, id := range order {
+		t := artists[id]
+		if t.at < minArtistPlays {
+			continue
+		}
+		lift := (float64(t.at) / float64(profile.Plays)) / (float64(t.total) / float64(total))
+		if lift >= minArtistLift {
+			profile.Artists = append(profile.Artists, MomentArtist{ArtistID: id, Name: t.name, Plays: t.at, Lift: lift})
+		}
+	}
+	sort.SliceStable(profile.Artists, func(i, j int) bool {
+		a, b := profile.Artists[i], profile.Artists[j]
+		if a.Plays != b.Plays {
+			return a.Plays > b.Plays
+		}
+		return a.Lift > b.Lift
+	})
+
+	if measuredAt >= minMeasuredPlays {
+		overall, at := energy/float64(measured), energyAt/float64(measuredAt)
+		switch {
+		case at <= overall-moodShift:
+			profile.Mood = "chill"
+		case at >= overall+moodShift:
+			profile.Mood = "high_energy"
+		}
+	}
+	return profile, true
+}
+
+// Suggestion is something to play at a moment, with why it fits.
+type Suggestion struct {
+	// Kind is "mood", for the library shuffled within Mood, or "artist",
+	// for a station seeded by ArtistID.
+	Kind        string
+	Mood        string
+	ArtistID    uuid.UUID
+	Artist      string
+	Explanation string
+}
+
+// Suggestions turns the profile into up to limit suggestions, its mood
+// first and then its artists.
+func (p Profile) Suggestions(limit int) []Suggestion {
+	var out []Suggestion
+	switch p.Mood {
+	case "chill":
+		out = append(out, Suggestion{Kind: "mood", Mood: p.Mood, Explanation: fmt.Sprintf("Your %s lean mellow", p.Moment)})
+	case "high_energy":
+		out = append(out, Suggestion{Kind: "mood", Mood: p.Mood, Explanation: fmt.Sprintf("Your %s lean energetic", p.Moment)})
+	}
+	for _, a := range p.Artists {
+		out = append(out, Suggestion{Kind: "artist", ArtistID: a.ArtistID, Artist: a.Name, Explanation: fmt.Sprintf("You often play %s on %s", a.Name, p.Moment)})
+	}
+	return out[:min(limit, len(out))]
+}
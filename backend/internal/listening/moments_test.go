@@ -0,0 +1,70 @@
+package listening
+
+import (
+	"reflect"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestMomentOfGroupsHoursIntoPartsOfTheWeek(t *testing.T) {
+	for _, tc := range []struct {
+		weekday time.Weekday
+		hour    int
+		want    string
+	}{
+		{time.Monday, 8, "weekday mornings"},
+		{time.Saturday, 13, "weekend afternoons"},
+		{time.Wednesday, 21, "weekday evenings"},
+		{time.Sunday, 23, "weekend nights"},
+		// Early hours belong to the night before.
+		{time.Monday, 2, "weekend nights"},
+		{time.Saturday, 3, "weekday nights"},
+	} {
+		if got := momentOf(tc.weekday, tc.hour).String(); got != tc.want {
+			t.Errorf("momentOf(%s, %d) = %s, want %s", tc.weekday, tc.hour, got, tc.want)
+		}
+	}
+}
+
+func TestProfileOfFindsWhatStandsOutAtAMoment(t *testing.T) {
+	a, b, c, d := uuid.New(), uuid.New(), uuid.New(), uuid.New()
+	artist := func(id uuid.UUID) uuid.NullUUID { return uuid.NullUUID{UUID: id, Valid: true} }
+	plays := []db.HourlyArtistPlays{
+		{Weekday: time.Monday, Hour: 8, ArtistID: artist(a), Artist: "A", Plays: 20, Measured: 20, Energy: 0.2},
+		{Weekday: time.Monday, Hour: 9, ArtistID: artist(b), Artist: "B", Plays: 4},
+		// Too few plays to stand out, however rare elsewhere.
+		{Weekday: time.Tuesday, Hour: 9, ArtistID: artist(c), Artist: "C", Plays: 2},
+		{Weekday: time.Saturday, Hour: 20, ArtistID: artist(a), Artist: "A", Plays: 5, Measured: 5, Energy: 0.8},
+		{Weekday: time.Saturday, Hour: 20, ArtistID: artist(d), Artist: "D", Plays: 40, Measured: 40, Energy: 0.8},
+	}
+	mornings := Moment{Part: Morning}
+
+	profile, ok := ProfileOf(plays, mornings)
+	if !ok || profile.Plays != 26 || profile.Mood != "chill" {
+		t.Fatalf("ProfileOf() = %+v, %v; want 26 chill plays", profile, ok)
+	}
+	var names []string
+	for _, artist := range profile.Artists {
+		names = append(names, artist.Name)
+	}
+	if !reflect.DeepEqual(names, []string{"A", "B"}) {
+		t.Fatalf("artists = %v, want [A B]", names)
+	}
+
+	suggestions := profile.Suggestions(2)
+	want := []Suggestion{
+		{Kind: "mood", Mood: "chill", Explanation: "Your weekday mornings lean mellow"},
+		{Kind: "artist", ArtistID: a, Artist: "A", Explanation: "You often play A on weekday mornings"},
+	}
+	if !reflect.DeepEqual(suggestions, want) {
+		t.Fatalf("Suggestions() = %+v, want %+v", suggestions, want)
+	}
+
+	if _, ok := ProfileOf(plays, Moment{Weekend: true, Part: Morning}); ok {
+		t.Fatal("ProfileOf() of a moment without plays reported a profile")
+	}
+}
@@ -3,9 +3,12 @@ package logger
 import (
 	"context"
 	"encoding/json"
+	"errors"
+	"fmt"
 	"io"
 	"os"
 	"regexp"
+	"sort"
 	"strings"
 	"sync"
 	"time"
@@ -52,33 +55,75 @@ func ParseLevel(s string) Level {
 	}
 }
 
+// Format selects how entries are rendered
+type Format int
+
+const (
+	// FormatJSON emits one JSON object per line
+	FormatJSON Format = iota
+	// FormatText emits human-readable key=value lines for local development
+	FormatText
+)
+
+// ParseFormat converts a string to a Format, defaulting to JSON
+func ParseFormat(s string) Format {
+	switch strings.ToLower(strings.TrimSpace(s)) {
+	case "text", "console", "pretty":
+		return FormatText
+	default:
+		return FormatJSON
+	}
+}
+
+// ParseComponentLevels parses a "component=level,component=level" list used
+// to override the minimum level for individual components.
+func ParseComponentLevels(s string) map[string]Level {
+	levels := make(map[string]Level)
+	for _, part := range strings.Split(s, ",") {
+		component, level, ok := strings.Cut(part, "=")
+		component = strings.ToLower(strings.TrimSpace(component))
+		if !ok || component == "" {
+			continue
+		}
+		levels[component] = ParseLevel(strings.TrimSpace(level))
+	}
+	return levels
+}
+
 // Entry represents a structured log entry
 type Entry struct {
-	Timestamp string                 `json:"timestamp"`
-	Level     string                 `json:"level"`
-	Component string                 `json:"component,omitempty"`
-	Message   string                 `json:"message"`
-	RequestID string                 `json:"request_id,omitempty"`
-	UserID    string                 `json:"user_id,omitempty"`
-	TraceID   string                 `json:"trace_id,omitempty"`
-	Fields    map[string]interface{} `json:"fields,omitempty"`
-	Error     string                 `json:"error,omitempty"`
+	Timestamp  string                 `json:"timestamp"`
+	Level      string                 `json:"level"`
+	Component  string                 `json:"component,omitempty"`
+	Message    string                 `json:"message"`
+	RequestID  string                 `json:"request_id,omitempty"`
+	UserID     string                 `json:"user_id,omitempty"`
+	TraceID    string                 `json:"trace_id,omitempty"`
+	Route      string                 `json:"route,omitempty"`
+	Fields     map[string]interface{} `json:"fields,omitempty"`
+	Error      string                 `json:"error,omitempty"`
+	ErrorChain []string               `json:"error_chain,omitempty"`
 }
 
 // Logger is the structured JSON logger
 type Logger struct {
-	mu        sync.Mutex
-	out       io.Writer
-	minLevel  Level
-	redactor  *Redactor
-	component string
+	mu              *sync.Mutex
+	out             io.Writer
+	format          Format
+	minLevel        Level
+	componentLevels map[string]Level
+	redactor        *Redactor
+	component       string
 }
 
 // Config for logger initialization
 type Config struct {
 	Output   io.Writer
 	Level    Level
+	Format   Format
 	Redactor *Redactor
+	// ComponentLevels overrides Level for loggers created with WithComponent.
+	ComponentLevels map[string]Level
 }
 
 // New creates a new Logger instance
@@ -92,28 +137,43 @@ func New(cfg *Config) *Logger {
 		redactor = DefaultRedactor()
 	}
 	return &Logger{
-		out:      out,
-		minLevel: cfg.Level,
-		redactor: redactor,
+		mu:              &sync.Mutex{},
+		out:             out,
+		format:          cfg.Format,
+		minLevel:        cfg.Level,
+		componentLevels: cfg.ComponentLevels,
+		redactor:        redactor,
 	}
 }
 
-// Default creates a logger with default settings
+// Default creates a logger with default settings. LOG_LEVEL sets the global
+// minimum, LOG_LEVELS (e.g. "musicbrainz=debug,http=warn") overrides it per
+// component, and LOG_FORMAT selects json (default) or text output.
 func Default() *Logger {
 	return New(&Config{
-		Output:   os.Stdout,
-		Level:    ParseLevel(os.Getenv("LOG_LEVEL")),
-		Redactor: DefaultRedactor(),
+		Output:          os.Stdout,
+		Level:           ParseLevel(os.Getenv("LOG_LEVEL")),
+		Format:          ParseFormat(os.Getenv("LOG_FORMAT")),
+		Redactor:        DefaultRedactor(),
+		ComponentLevels: ParseComponentLevels(os.Getenv("LOG_LEVELS")),
 	})
 }
 
-// WithComponent returns a new logger with the component field set
+// WithComponent returns a new logger with the component field set. A
+// configured per-component level replaces the inherited minimum level.
 func (l *Logger) WithComponent(component string) *Logger {
+	minLevel := l.minLevel
+	if level, ok := l.componentLevels[strings.ToLower(component)]; ok {
+		minLevel = level
+	}
 	return &Logger{
-		out:       l.out,
-		minLevel:  l.minLevel,
-		redactor:  l.redactor,
-		component: component,
+		mu:              l.mu,
+		out:             l.out,
+		format:          l.format,
+		minLevel:        minLevel,
+		componentLevels: l.componentLevels,
+		redactor:        l.redactor,
+		component:       component,
 	}
 }
 
@@ -121,11 +181,59 @@ func (l *Logger) WithComponent(component string) *Logger {
 type contextKey string
 
 const (
-	requestIDKey contextKey = "request_id"
-	userIDKey    contextKey = "user_id"
-	traceIDKey   contextKey = "trace_id"
+	requestIDKey   contextKey = "request_id"
+	userIDKey      contextKey = "user_id"
+	traceIDKey     contextKey = "trace_id"
+	requestInfoKey contextKey = "request_info"
 )
 
+// requestInfo is shared by every middleware layer of one request so values
+// learned deep in the stack (matched route, authenticated user) reach the
+// outer request-completion log line.
+type requestInfo struct {
+	mu     sync.Mutex
+	route  string
+	userID string
+}
+
+// WithRequestInfo attaches a mutable per-request record to the context.
+func WithRequestInfo(ctx context.Context) context.Context {
+	if _, ok := ctx.Value(requestInfoKey).(*requestInfo); ok {
+		return ctx
+	}
+	return context.WithValue(ctx, requestInfoKey, &requestInfo{})
+}
+
+// SetRoute records the matched route pattern for the current request.
+func SetRoute(ctx context.Context, route string) {
+	if info, ok := ctx.Value(requestInfoKey).(*requestInfo); ok {
+		info.mu.Lock()
+		info.route = route
+		info.mu.Unlock()
+	}
+}
+
+// GetRoute retrieves the matched route pattern for the current request.
+func GetRoute(ctx context.Context) string {
+	if info, ok := ctx.Value(requestInfoKey).(*requestInfo); ok {
+		info.mu.Lock()
+		defer info.mu.Unlock()
+		return info.route
+	}
+	return ""
+}
+
+// SetRequestUserID records the authenticated user for the current request and
+// returns a context carrying it for downstream logging.
+func SetRequestUserID(ctx context.Context, userID string) context.Context {
+	if info, ok := ctx.Value(requestInfoKey).(*requestInfo); ok {
+		info.mu.Lock()
+		info.userID = userID
+		info.mu.Unlock()
+	}
+	return WithUserID(ctx, userID)
+}
+
 // WithRequestID adds a request ID to the context
 func WithRequestID(ctx context.Context, requestID string) context.Context {
 	return context.WithValue(ctx, requestIDKey, requestID)
@@ -149,6 +257,11 @@ func GetUserID(ctx context.Context) string {
 	if v := ctx.Value(userIDKey); v != nil {
 		return v.(string)
 	}
+	if info, ok := ctx.Value(requestInfoKey).(*requestInfo); ok {
+		info.mu.Lock()
+		defer info.mu.Unlock()
+		return info.userID
+	}
 	return ""
 }
 
@@ -179,6 +292,7 @@ func (l *Logger) log(ctx context.Context, level Level, msg string, fields map[st
 		RequestID: GetRequestID(ctx),
 		UserID:    GetUserID(ctx),
 		TraceID:   GetTraceID(ctx),
+		Route:     GetRoute(ctx),
 	}
 
 	if len(fields) > 0 {
@@ -187,16 +301,74 @@ func (l *Logger) log(ctx context.Context, level Level, msg string, fields map[st
 
 	if err != nil {
 		entry.Error = l.redactor.Redact(err.Error())
+		for _, cause := range errorChain(err) {
+			entry.ErrorChain = append(entry.ErrorChain, l.redactor.Redact(cause))
+		}
+	}
+
+	var data []byte
+	if l.format == FormatText {
+		data = formatText(entry)
+	} else {
+		data, _ = json.Marshal(entry)
 	}
 
 	l.mu.Lock()
 	defer l.mu.Unlock()
 
-	data, _ := json.Marshal(entry)
 	l.out.Write(data)
 	l.out.Write([]byte("\n"))
 }
 
+// errorChain lists the messages of every wrapped cause below err. It returns
+// nil for unwrapped errors, whose single message is already in Entry.Error.
+func errorChain(err error) []string {
+	var chain []string
+	for cause := errors.Unwrap(err); cause != nil; cause = errors.Unwrap(cause) {
+		chain = append(chain, cause.Error())
+	}
+	return chain
+}
+
+// formatText renders an entry as a single human-readable line.
+func formatText(entry Entry) []byte {
+	var sb strings.Builder
+	sb.WriteString(entry.Timestamp)
+	sb.WriteString(" ")
+	sb.WriteString(strings.ToUpper(entry.Level))
+	if entry.Component != "" {
+		sb.WriteString(" [" + entry.Component + "]")
+	}
+	sb.WriteString(" " + entry.Message)
+	writePair := func(key string, value interface{}) {
+		sb.WriteString(fmt.Sprintf(" %s=%v", key, value))
+	}
+	if entry.RequestID != "" {
+		writePair("request_id", entry.RequestID)
+	}
+	if entry.UserID != "" {
+		writePair("user_id", entry.UserID)
+	}
+	if entry.TraceID != "" {
+		writePair("trace_id", entry.TraceID)
+	}
+	if entry.Route != "" {
+		writePair("route", fmt.Sprintf("%q", entry.Route))
+	}
+	keys := make([]string, 0, len(entry.Fields))
+	for key := range entry.Fields {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	for _, key := range keys {
+		writePair(key, entry.Fields[key])
+	}
+	if entry.Error != "" {
+		writePair("error", fmt.Sprintf("%q", entry.Error))
+	}
+	return []byte(sb.String())
+}
+
 // Debug logs at debug level
 func (l *Logger) Debug(ctx context.Context, msg string, fields map[string]interface{}) {
 	l.log(ctx, LevelDebug, msg, fields, nil)
@@ -4,6 +4,8 @@ import (
 	"bytes"
 	"context"
 	"encoding/json"
+	"errors"
+	"fmt"
 	"strings"
 	"testing"
 )
@@ -161,3 +163,87 @@ func TestParseLevel(t *testing.T) {
 		}
 	}
 }
+
+func TestLogger_TextFormat(t *testing.T) {
+	var buf bytes.Buffer
+	log := New(&Config{
+		Output: &buf,
+		Level:  LevelDebug,
+		Format: FormatText,
+	})
+
+	ctx := WithRequestID(context.Background(), "req-1")
+	log.WithComponent("http").Info(ctx, "request completed", map[string]interface{}{
+		"status": 200,
+	})
+
+	line := buf.String()
+	for _, want := range []string{"INFO [http] request completed", "request_id=req-1", "status=200"} {
+		if !strings.Contains(line, want) {
+			t.Errorf("text line %q missing %q", line, want)
+		}
+	}
+}
+
+func TestLogger_ComponentLevels(t *testing.T) {
+	var buf bytes.Buffer
+	log := New(&Config{
+		Output:          &buf,
+		Level:           LevelInfo,
+		ComponentLevels: ParseComponentLevels("musicbrainz=debug, http=warn,bogus"),
+	})
+
+	log.WithComponent("musicbrainz").Debug(context.Background(), "lookup", nil)
+	log.WithComponent("http").Info(context.Background(), "access", nil)
+	log.WithComponent("worker").Debug(context.Background(), "tick", nil)
+
+	out := buf.String()
+	if !strings.Contains(out, "lookup") {
+		t.Error("musicbrainz debug override should allow debug logs")
+	}
+	if strings.Contains(out, "access") {
+		t.Error("http warn override should suppress info logs")
+	}
+	if strings.Contains(out, "tick") {
+		t.Error("components without overrides should use the base level")
+	}
+}
+
+func TestLogger_ErrorChain(t *testing.T) {
+	var buf bytes.Buffer
+	log := New(&Config{Output: &buf, Level: LevelDebug})
+
+	root := errors.New("connection refused")
+	err := fmt.Errorf("fetch recording: %w", fmt.Errorf("musicbrainz: %w", root))
+	log.Error(context.Background(), "lookup failed", nil, err)
+
+	var entry Entry
+	if err := json.Unmarshal(buf.Bytes(), &entry); err != nil {
+		t.Fatalf("failed to parse log entry: %v", err)
+	}
+	if len(entry.ErrorChain) != 2 || entry.ErrorChain[1] != "connection refused" {
+		t.Errorf("error_chain = %v, want two causes ending in the root error", entry.ErrorChain)
+	}
+}
+
+func TestLogger_RequestInfoCarriesRouteAndUser(t *testing.T) {
+	var buf bytes.Buffer
+	log := New(&Config{Output: &buf, Level: LevelDebug})
+
+	ctx := WithRequestInfo(context.Background())
+	// Inner layers populate the shared holder; outer layers observe it.
+	SetRoute(ctx, "GET /api/v1/library/tracks/{id}")
+	SetRequestUserID(ctx, "user-1")
+	log.Info(ctx, "request completed", nil)
+
+	var entry Entry
+	if err := json.Unmarshal(buf.Bytes(), &entry); err != nil {
+		t.Fatalf("failed to parse log entry: %v", err)
+	}
+	if entry.Route != "GET /api/v1/library/tracks/{id}" {
+		t.Errorf("route = %q", entry.Route)
+	}
+	if entry.UserID != "user-1" {
+		t.Errorf("user_id = %q, want user-1", entry.UserID)
+	}
+}
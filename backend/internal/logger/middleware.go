@@ -2,6 +2,7 @@ package logger
 
 import (
 	"bytes"
+	"net"
 	"net/http"
 	"strings"
 	"time"
@@ -132,24 +133,15 @@ func sanitizeQuery(query string) string {
 	return strings.Join(sanitized, "&")
 }
 
-// getClientIP extracts the client IP from the request
+// getClientIP extracts the client IP from the request. Forwarding headers
+// are not read here: middleware.Proxy has already replaced RemoteAddr with
+// the forwarded client address for requests from trusted proxies.
 func getClientIP(r *http.Request) string {
-	// Check X-Forwarded-For header
-	if xff := r.Header.Get("X-Forwarded-For"); xff != "" {
-		// Return first IP in the list
-		ips := strings.Split(xff, ",")
-		if len(ips) > 0 {
-			return strings.TrimSpace(ips[0])
-		}
-	}
-
-	// Check X-Real-IP header
-	if xri := r.Header.Get("X-Real-IP"); xri != "" {
-		return xri
+	host, _, err := net.SplitHostPort(r.RemoteAddr)
+	if err != nil {
+		return r.RemoteAddr
 	}
-
-	// Fall back to RemoteAddr
-	return r.RemoteAddr
+	return host
 }
 
 // RecoveryMiddleware recovers from panics and logs them
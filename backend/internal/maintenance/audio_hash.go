@@ -0,0 +1,101 @@
+package maintenance
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+)
+
+// AudioHashBackfill is the job kind that hashes the audio of tracks stored
+// before downloads were hashed, so later downloads can match them.
+var AudioHashBackfill = jobs.NewKind[AudioHashBackfillPayload]("audio_hash_backfill")
+
+type AudioHashBackfillPayload struct {
+	TrackIDs []int64 `json:"track_ids"`
+}
+
+// AudioHashBackfillProgress is the progress detail of audio hash backfill
+// jobs.
+type AudioHashBackfillProgress struct {
+	Hashed  int `json:"hashed"`
+	Skipped int `json:"skipped"`
+	Failed  int `json:"failed"`
+}
+
+// EnqueueAudioHashes queues hashing trackIDs. A batch runs at most once at a
+// time; queuing it again while it is queued or running returns
+// jobs.ErrDuplicate.
+func (q *Queue) EnqueueAudioHashes(ctx context.Context, trackIDs []int64) (*jobs.Job, error) {
+	if len(trackIDs) == 0 {
+		return nil, errors.New("no tracks to hash")
+	}
+	return AudioHashBackfill.Enqueue(ctx, q.store, AudioHashBackfillPayload{TrackIDs: trackIDs}, jobs.Options{
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 3,
+		UniqueKey:   fmt.Sprintf("tracks:%d-%d:%d", trackIDs[0], trackIDs[len(trackIDs)-1], len(trackIDs)),
+	})
+}
+
+// AudioHasher hashes one track's stored audio. processor.Processor satisfies
+// this interface.
+type AudioHasher interface {
+	HashStoredAudio(ctx context.Context, track *db.Track) error
+}
+
+// AudioHashRunner runs audio hash backfill jobs.
+type AudioHashRunner struct {
+	tracks TrackLoader
+	hasher AudioHasher
+}
+
+func NewAudioHashRunner(tracks TrackLoader, hasher AudioHasher) *AudioHashRunner {
+	return &AudioHashRunner{tracks: tracks, hasher: hasher}
+}
+
+// Register runs audio hash backfill jobs on w.
+func (r *AudioHashRunner) Register(w *jobs.Worker) {
+	AudioHashBackfill.Handle(w, func(ctx context.Context, job *jobs.Job, payload AudioHashBackfillPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, payload, progress)
+	})
+}
+
+// Run hashes the job's tracks. Deleted tracks are skipped; tracks that fail
+// are logged and counted rather than ending the job, and stay listed for the
+// next backfill.
+func (r *AudioHashRunner) Run(ctx context.Context, jobID uuid.UUID, payload AudioHashBackfillPayload, progress progressReporter) error {
+	var counts AudioHashBackfillProgress
+	total := len(payload.TrackIDs)
+	if err := progress.Report(ctx, 0, total, counts); err != nil {
+		return err
+	}
+	for i, id := range payload.TrackIDs {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		track, err := r.tracks.GetByID(ctx, id)
+		switch {
+		case errors.Is(err, db.ErrTrackNotFound):
+			counts.Skipped++
+		case err != nil:
+			log.Printf("Audio hash backfill job %s: failed to load track %d: %v", jobID, id, err)
+			counts.Failed++
+		default:
+			if err := r.hasher.HashStoredAudio(ctx, track); err != nil {
+				log.Printf("Audio hash backfill job %s: failed to hash track %d: %v", jobID, id, err)
+				counts.Failed++
+			} else {
+				counts.Hashed++
+			}
+		}
+		if err := progress.Report(ctx, i+1, total, counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
@@ -0,0 +1,199 @@
+package maintenance
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+// Bulk fix-it actions, each tied to the library health issue it repairs.
+const (
+	ActionFetchArtwork      = "fetch_artwork"
+	ActionMatchUnverified   = "match_unverified"
+	ActionRedownloadCorrupt = "redownload_corrupt"
+)
+
+// ActionIssues maps each fix-it action to the health issue whose tracks it
+// works through.
+var ActionIssues = map[string]string{
+	ActionFetchArtwork:      db.HealthIssueMissingArtwork,
+	ActionMatchUnverified:   db.HealthIssueMissingMBLink,
+	ActionRedownloadCorrupt: db.HealthIssueCorruptFile,
+}
+
+// LibraryRepair is the job kind that runs one fix-it action over a user's
+// library.
+var LibraryRepair = jobs.NewKind[LibraryRepairPayload]("library_repair")
+
+type LibraryRepairPayload struct {
+	Action string `json:"action"`
+}
+
+// LibraryRepairProgress is the progress detail of a library repair job.
+type LibraryRepairProgress struct {
+	Succeeded int `json:"succeeded"`
+	Skipped   int `json:"skipped"`
+	Failed    int `json:"failed"`
+}
+
+// Queue queues library repair jobs.
+type Queue struct {
+	store *jobs.Store
+}
+
+func NewQueue(store *jobs.Store) *Queue {
+	return &Queue{store: store}
+}
+
+// Enqueue queues action over the user's library. A user runs at most one job
+// per action at a time; a second request while one is queued or running
+// returns jobs.ErrDuplicate.
+func (q *Queue) Enqueue(ctx context.Context, userID uuid.UUID, action string) (*jobs.Job, error) {
+	// Each pass only visits tracks still listed under the issue, so a single
+	// retry picks up where a failed pass stopped.
+	return LibraryRepair.Enqueue(ctx, q.store, LibraryRepairPayload{Action: action}, jobs.Options{
+		UserID:      uuid.NullUUID{UUID: userID, Valid: true},
+		Priority:    jobs.PriorityLow,
+		MaxAttempts: 2,
+		UniqueKey:   userID.String() + ":" + action,
+	})
+}
+
+// HealthTracks finds the library tracks with a health issue.
+// db.LibraryRepository satisfies this interface.
+type HealthTracks interface {
+	LibraryHealthTrackIDs(ctx context.Context, userID uuid.UUID, issue string) ([]int64, error)
+}
+
+// TrackLoader loads the tracks a job repairs. db.TrackRepository satisfies this
+// interface.
+type TrackLoader interface {
+	GetByID(ctx context.Context, id int64) (*db.Track, error)
+}
+
+// Repairer fixes one track at a time. processor.Processor satisfies this
+// interface.
+type Repairer interface {
+	RepairArtwork(ctx context.Context, track *db.Track) (processor.BulkRepairResult, error)
+	RepairMetadata(ctx context.Context, track *db.Track, opts processor.MetadataRepairOptions) (processor.MetadataRepairResult, error)
+	RedownloadTrack(ctx context.Context, track *db.Track) (processor.BulkRepairResult, error)
+}
+
+// Admission decides whether redownloads may start. *diskspace.Monitor
+// satisfies this interface.
+type Admission interface {
+	Admit(ctx context.Context) error
+}
+
+// progressReporter records a job's progress. *jobs.Progress satisfies it.
+type progressReporter interface {
+	Report(ctx context.Context, current, total int, detail any) error
+}
+
+// Runner repairs each track a library repair job's health issue currently
+// lists, reporting progress as it goes.
+type Runner struct {
+	health    HealthTracks
+	tracks    TrackLoader
+	repairer  Repairer
+	admission Admission
+}
+
+func NewRunner(health HealthTracks, tracks TrackLoader, repairer Repairer) *Runner {
+	return &Runner{health: health, tracks: tracks, repairer: repairer}
+}
+
+// GuardRedownloads stops redownload passes while admission refuses new
+// downloads. The job fails and retries later, resuming with the tracks still
+// listed.
+func (r *Runner) GuardRedownloads(admission Admission) *Runner {
+	r.admission = admission
+	return r
+}
+
+// Register runs library repair jobs on w.
+func (r *Runner) Register(w *jobs.Worker) {
+	LibraryRepair.Handle(w, func(ctx context.Context, job *jobs.Job, payload LibraryRepairPayload, progress *jobs.Progress) error {
+		return r.Run(ctx, job.ID, job.UserID.UUID, payload, progress)
+	})
+}
+
+// Run performs one pass of a library repair job.
+func (r *Runner) Run(ctx context.Context, jobID, userID uuid.UUID, payload LibraryRepairPayload, progress progressReporter) error {
+	issue, ok := ActionIssues[payload.Action]
+	if !ok {
+		return jobs.Permanent(fmt.Errorf("unknown maintenance action %q", payload.Action))
+	}
+	trackIDs, err := r.health.LibraryHealthTrackIDs(ctx, userID, issue)
+	if err != nil {
+		return fmt.Errorf("list %s tracks: %w", issue, err)
+	}
+
+	var counts LibraryRepairProgress
+	if err := progress.Report(ctx, 0, len(trackIDs), counts); err != nil {
+		return err
+	}
+	for i, trackID := range trackIDs {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if payload.Action == ActionRedownloadCorrupt && r.admission != nil {
+			if err := r.admission.Admit(ctx); err != nil {
+				return err
+			}
+		}
+		switch r.repairTrack(ctx, jobID, payload.Action, trackID) {
+		case "processed":
+			counts.Succeeded++
+		case "skipped":
+			counts.Skipped++
+		default:
+			counts.Failed++
+		}
+		if err := progress.Report(ctx, i+1, len(trackIDs), counts); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// repairTrack runs action on one track and returns the outcome status.
+// Failures are logged and counted rather than ending the job.
+func (r *Runner) repairTrack(ctx context.Context, jobID uuid.UUID, action string, trackID int64) string {
+	track, err := r.tracks.GetByID(ctx, trackID)
+	if errors.Is(err, db.ErrTrackNotFound) {
+		return "skipped"
+	}
+	if err != nil {
+		log.Printf("Library repair job %s: failed to load track %d: %v", jobID, trackID, err)
+		return "failed"
+	}
+
+	var status string
+	switch action {
+	case ActionFetchArtwork:
+		var result processor.BulkRepairResult
+		result, err = r.repairer.RepairArtwork(ctx, track)
+		status = result.Status
+	case ActionMatchUnverified:
+		var result processor.MetadataRepairResult
+		result, err = r.repairer.RepairMetadata(ctx, track, processor.MetadataRepairOptions{})
+		status = result.Status
+	case ActionRedownloadCorrupt:
+		var result processor.BulkRepairResult
+		result, err = r.repairer.RedownloadTrack(ctx, track)
+		status = result.Status
+	}
+	if err != nil {
+		log.Printf("Library repair job %s: %s failed for track %d: %v", jobID, action, trackID, err)
+		return "failed"
+	}
+	return status
+}
@@ -0,0 +1,159 @@
+package maintenance
+
+import (
+	"context"
+	"errors"
+	"reflect"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/jobs"
+	"github.com/openmusicplayer/backend/internal/processor"
+)
+
+type fakeProgress struct {
+	reports  []LibraryRepairProgress
+	current  int
+	total    int
+	cancelAt int
+}
+
+func (p *fakeProgress) Report(_ context.Context, current, total int, detail any) error {
+	p.reports = append(p.reports, detail.(LibraryRepairProgress))
+	p.current, p.total = current, total
+	if p.cancelAt > 0 && current >= p.cancelAt {
+		return jobs.ErrCanceled
+	}
+	return nil
+}
+
+type fakeHealthTracks struct {
+	issue string
+	ids   []int64
+	err   error
+}
+
+func (h *fakeHealthTracks) LibraryHealthTrackIDs(_ context.Context, _ uuid.UUID, issue string) ([]int64, error) {
+	h.issue = issue
+	return h.ids, h.err
+}
+
+type fakeTrackLoader struct{}
+
+func (fakeTrackLoader) GetByID(_ context.Context, id int64) (*db.Track, error) {
+	if id == 404 {
+		return nil, db.ErrTrackNotFound
+	}
+	return &db.Track{ID: id}, nil
+}
+
+type fakeRepairer struct {
+	outcomes map[int64]string
+	calls    []string
+}
+
+func (r *fakeRepairer) outcome(action string, track *db.Track) (string, error) {
+	r.calls = append(r.calls, action)
+	status := r.outcomes[track.ID]
+	if status == "failed" {
+		return status, errors.New("boom")
+	}
+	return status, nil
+}
+
+func (r *fakeRepairer) RepairArtwork(_ context.Context, track *db.Track) (processor.BulkRepairResult, error) {
+	status, err := r.outcome("artwork", track)
+	return processor.BulkRepairResult{TrackID: track.ID, Status: status}, err
+}
+
+func (r *fakeRepairer) RepairMetadata(_ context.Context, track *db.Track, _ processor.MetadataRepairOptions) (processor.MetadataRepairResult, error) {
+	status, err := r.outcome("metadata", track)
+	return processor.MetadataRepairResult{TrackID: track.ID, Status: status}, err
+}
+
+func (r *fakeRepairer) RedownloadTrack(_ context.Context, track *db.Track) (processor.BulkRepairResult, error) {
+	status, err := r.outcome("redownload", track)
+	return processor.BulkRepairResult{TrackID: track.ID, Status: status}, err
+}
+
+func TestRunRepairsEachTrackAndReportsProgress(t *testing.T) {
+	health := &fakeHealthTracks{ids: []int64{1, 2, 3, 404}}
+	repairer := &fakeRepairer{outcomes: map[int64]string{1: "processed", 2: "failed", 3: "skipped"}}
+	progress := &fakeProgress{}
+
+	err := NewRunner(health, fakeTrackLoader{}, repairer).Run(context.Background(), uuid.New(), uuid.New(), LibraryRepairPayload{Action: ActionRedownloadCorrupt}, progress)
+	if err != nil {
+		t.Fatalf("Run() error = %v", err)
+	}
+	if health.issue != db.HealthIssueCorruptFile {
+		t.Fatalf("listed issue %q, want %q", health.issue, db.HealthIssueCorruptFile)
+	}
+	want := []LibraryRepairProgress{
+		{},
+		{Succeeded: 1},
+		{Succeeded: 1, Failed: 1},
+		{Succeeded: 1, Failed: 1, Skipped: 1},
+		{Succeeded: 1, Failed: 1, Skipped: 2},
+	}
+	if !reflect.DeepEqual(progress.reports, want) || progress.current != 4 || progress.total != 4 {
+		t.Fatalf("progress = %+v (%d/%d), want %+v", progress.reports, progress.current, progress.total, want)
+	}
+	if !reflect.DeepEqual(repairer.calls, []string{"redownload", "redownload", "redownload"}) {
+		t.Fatalf("repair calls = %v", repairer.calls)
+	}
+}
+
+func TestRunFailsWhenTracksCannotBeListed(t *testing.T) {
+	health := &fakeHealthTracks{err: errors.New("database unavailable")}
+	err := NewRunner(health, fakeTrackLoader{}, &fakeRepairer{}).Run(context.Background(), uuid.New(), uuid.New(), LibraryRepairPayload{Action: ActionFetchArtwork}, &fakeProgress{})
+	if err == nil {
+		t.Fatal("Run() error = nil, want listing failure")
+	}
+}
+
+func TestRunRejectsUnknownActionWithoutRetry(t *testing.T) {
+	err := NewRunner(&fakeHealthTracks{}, fakeTrackLoader{}, &fakeRepairer{}).Run(context.Background(), uuid.New(), uuid.New(), LibraryRepairPayload{Action: "defragment"}, &fakeProgress{})
+	if err == nil || errors.Unwrap(err) == nil {
+		t.Fatalf("Run() error = %v, want permanent error", err)
+	}
+}
+
+type refusingAdmission struct{}
+
+func (refusingAdmission) Admit(context.Context) error {
+	return errors.New("not enough free disk space")
+}
+
+func TestRunStopsRedownloadsWhileAdmissionRefuses(t *testing.T) {
+	health := &fakeHealthTracks{ids: []int64{1, 2}}
+	repairer := &fakeRepairer{outcomes: map[int64]string{1: "processed", 2: "processed"}}
+	runner := NewRunner(health, fakeTrackLoader{}, repairer).GuardRedownloads(refusingAdmission{})
+
+	err := runner.Run(context.Background(), uuid.New(), uuid.New(), LibraryRepairPayload{Action: ActionRedownloadCorrupt}, &fakeProgress{})
+	if err == nil || len(repairer.calls) != 0 {
+		t.Fatalf("Run() error = %v, calls = %v, want refusal before any redownload", err, repairer.calls)
+	}
+
+	// Actions that write no audio are unaffected.
+	if err := runner.Run(context.Background(), uuid.New(), uuid.New(), LibraryRepairPayload{Action: ActionFetchArtwork}, &fakeProgress{}); err != nil {
+		t.Fatalf("Run() artwork error = %v", err)
+	}
+	if len(repairer.calls) != 2 {
+		t.Fatalf("repair calls = %v, want both artwork repairs", repairer.calls)
+	}
+}
+
+func TestRunStopsWhenCanceled(t *testing.T) {
+	repairer := &fakeRepairer{outcomes: map[int64]string{1: "processed", 2: "processed"}}
+	progress := &fakeProgress{cancelAt: 1}
+
+	err := NewRunner(&fakeHealthTracks{ids: []int64{1, 2}}, fakeTrackLoader{}, repairer).Run(context.Background(), uuid.New(), uuid.New(), LibraryRepairPayload{Action: ActionMatchUnverified}, progress)
+	if !errors.Is(err, jobs.ErrCanceled) {
+		t.Fatalf("Run() error = %v, want jobs.ErrCanceled", err)
+	}
+	if len(repairer.calls) != 1 {
+		t.Fatalf("repair calls = %v, want one before cancellation", repairer.calls)
+	}
+}
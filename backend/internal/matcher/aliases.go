@@ -0,0 +1,26 @@
+package matcher
+
+import (
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+// TrackAliases converts a recording's title aliases and its artist's aliases
+// for storage.
+func TrackAliases(recording *musicbrainz.Track) []db.TrackAlias {
+	return append(fieldAliases(db.AliasFieldTitle, recording.Aliases), fieldAliases(db.AliasFieldArtist, recording.ArtistAliases)...)
+}
+
+// ReleaseAliases converts a release's title aliases and its artist's aliases
+// the same way, so release names resolve under the same display settings.
+func ReleaseAliases(release *musicbrainz.Release) []db.TrackAlias {
+	return append(fieldAliases(db.AliasFieldTitle, release.Aliases), fieldAliases(db.AliasFieldArtist, release.ArtistAliases)...)
+}
+
+func fieldAliases(field string, aliases []musicbrainz.Alias) []db.TrackAlias {
+	out := make([]db.TrackAlias, 0, len(aliases))
+	for _, alias := range aliases {
+		out = append(out, db.TrackAlias{Field: field, Name: alias.Name, Locale: alias.Locale, Primary: alias.Primary})
+	}
+	return out
+}
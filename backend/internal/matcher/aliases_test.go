@@ -0,0 +1,22 @@
+package matcher
+
+import (
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+func TestTrackAliasesTagsTitleAndArtistAliases(t *testing.T) {
+	got := TrackAliases(&musicbrainz.Track{
+		Aliases:       []musicbrainz.Alias{{Name: "パラノイド・アンドロイド", Locale: "ja", Primary: true}},
+		ArtistAliases: []musicbrainz.Alias{{Name: "レディオヘッド", Locale: "ja"}},
+	})
+	want := []db.TrackAlias{
+		{Field: db.AliasFieldTitle, Name: "パラノイド・アンドロイド", Locale: "ja", Primary: true},
+		{Field: db.AliasFieldArtist, Name: "レディオヘッド", Locale: "ja"},
+	}
+	if len(got) != len(want) || got[0] != want[0] || got[1] != want[1] {
+		t.Fatalf("TrackAliases = %+v; want %+v", got, want)
+	}
+}
@@ -0,0 +1,35 @@
+package matcher
+
+import (
+	"regexp"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+// featJoinPhrase marks the artists after it in a MusicBrainz credit as guests.
+var featJoinPhrase = regexp.MustCompile(`(?i)\b(?:feat\.?|ft\.?|featuring|with)\s*$`)
+
+// TrackArtists converts a recording's MusicBrainz artist credit into track
+// credits. Artists joined by "&" or "," share the main credit; everyone after a
+// "feat." join phrase is featured. Remixers and guests only named in the title
+// are added from it.
+func TrackArtists(recording *musicbrainz.Track) []db.TrackArtist {
+	var credits []db.TrackArtist
+	role := db.ArtistRoleMain
+	for _, credit := range recording.Credits {
+		if credit.Name != "" {
+			artist := db.TrackArtist{Name: credit.Name, Role: role}
+			if id, err := uuid.Parse(credit.ID); err == nil {
+				artist.MBArtistID = &id
+			}
+			credits = append(credits, artist)
+		}
+		if featJoinPhrase.MatchString(credit.JoinPhrase) {
+			role = db.ArtistRoleFeatured
+		}
+	}
+	return append(credits, db.TitleArtistCredits(recording.Title)...)
+}
@@ -0,0 +1,36 @@
+package matcher
+
+import (
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+func TestTrackArtistsSplitsMainAndFeaturedCredits(t *testing.T) {
+	got := TrackArtists(&musicbrainz.Track{
+		Title: "Song (DJ Remix)",
+		Credits: []musicbrainz.ArtistCredit{
+			{ID: "11111111-1111-1111-1111-111111111111", Name: "Main", JoinPhrase: " & "},
+			{Name: "Partner", JoinPhrase: " feat. "},
+			{Name: "Guest"},
+		},
+	})
+	want := []struct{ name, role string }{
+		{"Main", db.ArtistRoleMain},
+		{"Partner", db.ArtistRoleMain},
+		{"Guest", db.ArtistRoleFeatured},
+		{"DJ", db.ArtistRoleRemixer},
+	}
+	if len(got) != len(want) {
+		t.Fatalf("credits = %+v", got)
+	}
+	for i, w := range want {
+		if got[i].Name != w.name || got[i].Role != w.role {
+			t.Fatalf("credit %d = %+v; want %s as %s", i, got[i], w.name, w.role)
+		}
+	}
+	if got[0].MBArtistID == nil || got[1].MBArtistID != nil {
+		t.Fatalf("MB artist IDs = %v, %v; want only the first set", got[0].MBArtistID, got[1].MBArtistID)
+	}
+}
@@ -2,11 +2,13 @@ package matcher
 
 import (
 	"encoding/json"
+	"log"
 	"net/http"
 	"strconv"
 
 	"github.com/google/uuid"
 
+	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
 )
 
@@ -24,6 +26,16 @@ func NewHandler(matcher *Matcher, trackRepo *db.TrackRepository) *Handler {
 	}
 }
 
+// getTrack loads a track the requesting user may see; other tenants' tracks
+// are reported as db.ErrTrackNotFound.
+func (h *Handler) getTrack(r *http.Request, trackID int64) (*db.Track, error) {
+	userID := uuid.Nil
+	if user := auth.GetUserFromContext(r.Context()); user != nil {
+		userID = user.UserID
+	}
+	return h.trackRepo.GetByIDForUser(r.Context(), userID, trackID)
+}
+
 // MatchRequest is the request body for matching a track
 type MatchRequest struct {
 	Title      string `json:"title"`
@@ -114,7 +126,7 @@ func (h *Handler) HandleMatchTrack(w http.ResponseWriter, r *http.Request) {
 	}
 
 	// Get the track
-	track, err := h.trackRepo.GetByID(r.Context(), trackID)
+	track, err := h.getTrack(r, trackID)
 	if err != nil {
 		if err == db.ErrTrackNotFound {
 			writeError(w, http.StatusNotFound, "Track not found")
@@ -235,7 +247,7 @@ func (h *Handler) HandleConfirmMatch(w http.ResponseWriter, r *http.Request) {
 	}
 
 	// Verify track exists
-	if _, err := h.trackRepo.GetByID(r.Context(), trackID); err != nil {
+	if _, err := h.getTrack(r, trackID); err != nil {
 		if err == db.ErrTrackNotFound {
 			writeError(w, http.StatusNotFound, "Track not found")
 			return
@@ -346,7 +358,7 @@ func (h *Handler) HandleLinkMB(w http.ResponseWriter, r *http.Request) {
 	}
 
 	// Verify track exists
-	track, err := h.trackRepo.GetByID(r.Context(), trackID)
+	track, err := h.getTrack(r, trackID)
 	if err != nil {
 		if err == db.ErrTrackNotFound {
 			writeError(w, http.StatusNotFound, "Track not found")
@@ -382,6 +394,7 @@ func (h *Handler) HandleLinkMB(w http.ResponseWriter, r *http.Request) {
 		if releaseID, err := uuid.Parse(mbRecording.AlbumID); err == nil {
 			update.MBReleaseID = &releaseID
 			releaseIDStr = mbRecording.AlbumID
+			update.ReleaseDate, _ = db.ParsePartialDate(mbRecording.ReleaseDate)
 		}
 	}
 
@@ -390,6 +403,25 @@ func (h *Handler) HandleLinkMB(w http.ResponseWriter, r *http.Request) {
 		writeError(w, http.StatusInternalServerError, "Failed to update track")
 		return
 	}
+	if err := h.trackRepo.ReplaceRelations(r.Context(), trackID, TrackRelations(mbRecording.Relations)); err != nil {
+		log.Printf("Warning: failed to store relationships for track %d: %v", trackID, err)
+	}
+	if err := h.trackRepo.RecordArtistSortName(r.Context(), mbRecording.Artist, mbRecording.ArtistSortName, mbRecording.ArtistID); err != nil {
+		log.Printf("Warning: failed to store artist sort name for track %d: %v", trackID, err)
+	}
+	if err := h.trackRepo.ReplaceAliases(r.Context(), trackID, TrackAliases(mbRecording)); err != nil {
+		log.Printf("Warning: failed to store aliases for track %d: %v", trackID, err)
+	}
+	if len(mbRecording.Credits) > 0 {
+		// Taking MusicBrainz metadata is a user edit, so it replaces locked credits too.
+		source := db.FieldSourceMusicBrainz
+		if req.UpdateMetadata {
+			source = db.FieldSourceUser
+		}
+		if err := h.trackRepo.ReplaceArtistCredits(r.Context(), trackID, TrackArtists(mbRecording), source); err != nil {
+			log.Printf("Warning: failed to store artist credits for track %d: %v", trackID, err)
+		}
+	}
 
 	// Optionally update metadata from MusicBrainz
 	metadataUpdated := false
@@ -403,7 +435,7 @@ func (h *Handler) HandleLinkMB(w http.ResponseWriter, r *http.Request) {
 			metadataUpdate.DurationMs = mbRecording.Duration
 		}
 
-		if err := h.trackRepo.UpdateMetadata(r.Context(), trackID, metadataUpdate); err != nil {
+		if err := h.trackRepo.UpdateMetadata(r.Context(), trackID, metadataUpdate, nil); err != nil {
 			// Log but don't fail - the MB link was successful
 			// The metadata update is optional
 		} else {
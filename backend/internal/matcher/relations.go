@@ -0,0 +1,27 @@
+package matcher
+
+import (
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+// TrackRelations converts MusicBrainz relationships for storage, dropping any
+// whose target ID is not a UUID.
+func TrackRelations(rels []musicbrainz.Relation) []db.TrackRelation {
+	out := make([]db.TrackRelation, 0, len(rels))
+	for _, rel := range rels {
+		target, err := uuid.Parse(rel.TargetID)
+		if err != nil {
+			continue
+		}
+		out = append(out, db.TrackRelation{
+			Relation:    rel.Kind,
+			TargetType:  rel.TargetType,
+			TargetMBID:  target,
+			TargetTitle: rel.TargetTitle,
+		})
+	}
+	return out
+}
@@ -0,0 +1,22 @@
+package matcher
+
+import (
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+func TestTrackRelationsDropsMalformedTargets(t *testing.T) {
+	work := uuid.MustParse("44444444-4444-4444-4444-444444444444")
+	got := TrackRelations([]musicbrainz.Relation{
+		{Kind: musicbrainz.RelationCoverOf, TargetType: "work", TargetID: work.String(), TargetTitle: "Song"},
+		{Kind: musicbrainz.RelationRemixOf, TargetType: "recording", TargetID: "not-a-uuid"},
+	})
+	want := db.TrackRelation{Relation: musicbrainz.RelationCoverOf, TargetType: "work", TargetMBID: work, TargetTitle: "Song"}
+	if len(got) != 1 || got[0] != want {
+		t.Fatalf("TrackRelations = %+v; want [%+v]", got, want)
+	}
+}
@@ -42,6 +42,10 @@ type Metrics struct {
 	gauges   map[string]float64
 	counters map[string]*uint64
 
+	// collectors refresh pull-style gauges (e.g. connection pool stats) right
+	// before each scrape.
+	collectors []func(*Metrics)
+
 	startTime time.Time
 }
 
@@ -278,6 +282,14 @@ func (m *Metrics) SetGauge(name string, value float64) {
 	m.gauges[name] = value
 }
 
+// AddCollector registers fn to run at the start of every scrape so gauges
+// sampled from other subsystems are current when rendered.
+func (m *Metrics) AddCollector(fn func(*Metrics)) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.collectors = append(m.collectors, fn)
+}
+
 // IncCounter increments a counter
 func (m *Metrics) IncCounter(name string) {
 	m.mu.Lock()
@@ -367,6 +379,13 @@ func (m *Metrics) Handler() http.HandlerFunc {
 	return func(w http.ResponseWriter, r *http.Request) {
 		w.Header().Set("Content-Type", "text/plain; version=0.0.4; charset=utf-8")
 
+		m.mu.RLock()
+		collectors := append([]func(*Metrics)(nil), m.collectors...)
+		m.mu.RUnlock()
+		for _, collect := range collectors {
+			collect(m)
+		}
+
 		var sb strings.Builder
 
 		// Uptime
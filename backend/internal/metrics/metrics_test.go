@@ -217,3 +217,24 @@ func TestMetrics_CustomGauge(t *testing.T) {
 		t.Errorf("expected active_downloads gauge, got:\n%s", body)
 	}
 }
+
+func TestMetrics_CollectorsRunBeforeScrape(t *testing.T) {
+	m := New()
+
+	scrapes := 0
+	m.AddCollector(func(m *Metrics) {
+		scrapes++
+		m.SetGauge("db_primary_open_connections", float64(scrapes))
+	})
+
+	handler := m.Handler()
+	for i := 0; i < 2; i++ {
+		handler(httptest.NewRecorder(), httptest.NewRequest(http.MethodGet, "/metrics", nil))
+	}
+	w := httptest.NewRecorder()
+	handler(w, httptest.NewRequest(http.MethodGet, "/metrics", nil))
+
+	if !strings.Contains(w.Body.String(), `omp_gauge{name="db_primary_open_connections"} 3.000000`) {
+		t.Errorf("expected collector gauge refreshed on each scrape, got:\n%s", w.Body.String())
+	}
+}
@@ -54,13 +54,20 @@ func ETag(next http.Handler) http.Handler {
 		// Handle the request
 		next.ServeHTTP(wrapped, r)
 
+		// Handlers that version their own representation set the ETag (and
+		// may already have answered 304); pass those through untouched.
+		if w.Header().Get("ETag") != "" {
+			w.WriteHeader(wrapped.statusCode)
+			w.Write(buf.Bytes())
+			return
+		}
+
 		// Calculate ETag from response body
 		hash := md5.Sum(buf.Bytes())
 		etag := `"` + hex.EncodeToString(hash[:]) + `"`
 
 		// Check If-None-Match header
-		ifNoneMatch := r.Header.Get("If-None-Match")
-		if ifNoneMatch == etag {
+		if IfNoneMatch(r.Header.Get("If-None-Match"), etag) {
 			w.WriteHeader(http.StatusNotModified)
 			return
 		}
@@ -72,3 +79,42 @@ func ETag(next http.Handler) http.Handler {
 		w.Write(buf.Bytes())
 	})
 }
+
+// IfNoneMatch reports whether an If-None-Match header matches etag using weak
+// comparison, as RFC 9110 requires for conditional GETs.
+func IfNoneMatch(header, etag string) bool {
+	return etagListMatches(header, etag, false)
+}
+
+// IfMatch reports whether an If-Match header matches etag using strong
+// comparison; weak validators never match.
+func IfMatch(header, etag string) bool {
+	return etagListMatches(header, etag, true)
+}
+
+func etagListMatches(header, etag string, strong bool) bool {
+	header = strings.TrimSpace(header)
+	if header == "" {
+		return false
+	}
+	if header == "*" {
+		return true
+	}
+	if strong && strings.HasPrefix(etag, "W/") {
+		return false
+	}
+	want := strings.TrimPrefix(etag, "W/")
+	for _, candidate := range strings.Split(header, ",") {
+		candidate = strings.TrimSpace(candidate)
+		if strings.HasPrefix(candidate, "W/") {
+			if strong {
+				continue
+			}
+			candidate = strings.TrimPrefix(candidate, "W/")
+		}
+		if candidate == want {
+			return true
+		}
+	}
+	return false
+}
@@ -4,8 +4,10 @@ import (
 	"crypto/rand"
 	"encoding/hex"
 	"net/http"
+	"regexp"
 	"time"
 
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/logger"
 )
 
@@ -23,12 +25,18 @@ func generateRequestID() string {
 	return hex.EncodeToString(b)
 }
 
-// RequestID middleware adds request ID tracking to all requests
+// validRequestID bounds client-supplied correlation IDs so they cannot inject
+// log structure or grow log lines without limit.
+var validRequestID = regexp.MustCompile(`^[A-Za-z0-9._:-]{1,128}$`)
+
+// RequestID middleware adds request ID tracking to all requests. A valid
+// client-supplied X-Request-ID is echoed back; otherwise one is generated. The
+// ID is shared with the error package so error bodies and logs always agree.
 func RequestID(next http.Handler) http.Handler {
 	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
 		// Get or generate request ID
 		requestID := r.Header.Get(RequestIDHeader)
-		if requestID == "" {
+		if !validRequestID.MatchString(requestID) {
 			requestID = generateRequestID()
 		}
 
@@ -36,8 +44,10 @@ func RequestID(next http.Handler) http.Handler {
 		traceID := r.Header.Get(TraceIDHeader)
 
 		// Add to context
-		ctx := logger.WithRequestID(r.Context(), requestID)
-		if traceID != "" {
+		ctx := logger.WithRequestInfo(r.Context())
+		ctx = logger.WithRequestID(ctx, requestID)
+		ctx = apperrors.WithRequestID(ctx, requestID)
+		if traceID != "" && validRequestID.MatchString(traceID) {
 			ctx = logger.WithTraceID(ctx, traceID)
 		}
 
@@ -142,8 +152,8 @@ func CORS(allowedOrigins []string) func(http.Handler) http.Handler {
 			if allowed {
 				w.Header().Set("Access-Control-Allow-Origin", origin)
 				w.Header().Set("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS")
-				w.Header().Set("Access-Control-Allow-Headers", "Content-Type, Authorization, Idempotency-Key, X-Request-ID, X-Trace-ID")
-				w.Header().Set("Access-Control-Expose-Headers", "X-Request-ID")
+				w.Header().Set("Access-Control-Allow-Headers", "Content-Type, Authorization, Idempotency-Key, If-Match, If-None-Match, X-Request-ID, X-Trace-ID")
+				w.Header().Set("Access-Control-Expose-Headers", "X-Request-ID, ETag")
 			}
 
 			if r.Method == http.MethodOptions {
@@ -156,6 +166,27 @@ func CORS(allowedOrigins []string) func(http.Handler) http.Handler {
 	}
 }
 
+// OpenCORS lets pages on any origin call a route, for routes authenticated
+// by a header credential such as an API key rather than by anything a
+// browser sends on its own.
+func OpenCORS(methods, headers string) func(http.Handler) http.Handler {
+	return func(next http.Handler) http.Handler {
+		return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.Header().Set("Access-Control-Allow-Origin", "*")
+			w.Header().Set("Access-Control-Allow-Methods", methods)
+			w.Header().Set("Access-Control-Allow-Headers", headers)
+			w.Header().Set("Access-Control-Max-Age", "600")
+
+			if r.Method == http.MethodOptions {
+				w.WriteHeader(http.StatusNoContent)
+				return
+			}
+
+			next.ServeHTTP(w, r)
+		})
+	}
+}
+
 // Recoverer middleware recovers from panics and logs them
 func Recoverer(log *logger.Logger) func(http.Handler) http.Handler {
 	return func(next http.Handler) http.Handler {
@@ -167,7 +198,7 @@ func Recoverer(log *logger.Logger) func(http.Handler) http.Handler {
 						"method": r.Method,
 						"path":   r.URL.Path,
 					}, nil)
-					http.Error(w, "Internal Server Error", http.StatusInternalServerError)
+					apperrors.WriteProblemError(w, r, apperrors.InternalError("an unexpected error occurred"))
 				}
 			}()
 			next.ServeHTTP(w, r)
@@ -0,0 +1,146 @@
+package middleware
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net"
+	"net/http"
+	"net/netip"
+	"strings"
+)
+
+// Proxy adapts requests arriving through a reverse proxy such as nginx or
+// Traefik. Forwarding headers are believed only from trusted proxies, since
+// any other client could set them freely.
+type Proxy struct {
+	trusted  []netip.Prefix
+	basePath string
+}
+
+// NewProxy trusts the proxies at trusted, given as addresses or CIDR ranges,
+// and serves the API under basePath, such as "/music"; empty serves it at
+// the root.
+func NewProxy(trusted []string, basePath string) (*Proxy, error) {
+	p := &Proxy{}
+	for _, value := range trusted {
+		prefix, err := netip.ParsePrefix(value)
+		if err != nil {
+			addr, addrErr := netip.ParseAddr(value)
+			if addrErr != nil {
+				return nil, fmt.Errorf("trusted proxy %q is not an address or CIDR range", value)
+			}
+			prefix = netip.PrefixFrom(addr, addr.BitLen())
+		}
+		p.trusted = append(p.trusted, prefix.Masked())
+	}
+	basePath = strings.TrimRight(strings.TrimSpace(basePath), "/")
+	if basePath != "" && (!strings.HasPrefix(basePath, "/") || strings.ContainsAny(basePath, "?#")) {
+		return nil, errors.New("base path must be a path such as /music")
+	}
+	p.basePath = basePath
+	return p, nil
+}
+
+type origin struct {
+	scheme   string
+	host     string
+	basePath string
+}
+
+type originKey struct{}
+
+// Middleware takes the client address, scheme and host from X-Forwarded-For,
+// X-Forwarded-Proto and X-Forwarded-Host when a trusted proxy sent the
+// request, and strips the base path from its URL. Proxies that strip the
+// base path themselves work too, and may name it in X-Forwarded-Prefix.
+func (p *Proxy) Middleware(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		o := origin{scheme: "http", host: r.Host, basePath: p.basePath}
+		if r.TLS != nil {
+			o.scheme = "https"
+		}
+		if peer, ok := remoteAddr(r.RemoteAddr); ok && p.trusts(peer) {
+			r = r.Clone(r.Context())
+			if client, ok := p.forwardedClient(r.Header.Get("X-Forwarded-For")); ok {
+				r.RemoteAddr = net.JoinHostPort(client.String(), "0")
+			}
+			if proto := firstForwarded(r.Header.Get("X-Forwarded-Proto")); proto == "http" || proto == "https" {
+				o.scheme = proto
+			}
+			if host := firstForwarded(r.Header.Get("X-Forwarded-Host")); host != "" {
+				o.host = host
+			}
+			if prefix := strings.TrimRight(firstForwarded(r.Header.Get("X-Forwarded-Prefix")), "/"); strings.HasPrefix(prefix, "/") {
+				o.basePath = prefix
+			}
+		}
+		if p.basePath != "" && (r.URL.Path == p.basePath || strings.HasPrefix(r.URL.Path, p.basePath+"/")) {
+			r = r.Clone(r.Context())
+			r.URL.Path = "/" + strings.TrimPrefix(strings.TrimPrefix(r.URL.Path, p.basePath), "/")
+			r.URL.RawPath = ""
+		}
+		next.ServeHTTP(w, r.WithContext(context.WithValue(r.Context(), originKey{}, o)))
+	})
+}
+
+// Path prefixes a server path such as "/api/v1/jobs/1" with the base path
+// the request was served under, for Location headers and links.
+func Path(r *http.Request, path string) string {
+	o, _ := r.Context().Value(originKey{}).(origin)
+	return o.basePath + path
+}
+
+// URL makes an absolute URL of a server path, with the scheme and host the
+// client addressed, for links followed outside the API such as share links.
+func URL(r *http.Request, path string) string {
+	o, ok := r.Context().Value(originKey{}).(origin)
+	if !ok {
+		o = origin{scheme: "http", host: r.Host}
+		if r.TLS != nil {
+			o.scheme = "https"
+		}
+	}
+	return o.scheme + "://" + o.host + o.basePath + path
+}
+
+func (p *Proxy) trusts(addr netip.Addr) bool {
+	for _, prefix := range p.trusted {
+		if prefix.Contains(addr) {
+			return true
+		}
+	}
+	return false
+}
+
+// forwardedClient walks X-Forwarded-For from the nearest hop back, past
+// trusted proxies, to the first address a trusted proxy did not add itself.
+func (p *Proxy) forwardedClient(header string) (netip.Addr, bool) {
+	hops := strings.Split(header, ",")
+	var client netip.Addr
+	for i := len(hops) - 1; i >= 0; i-- {
+		addr, err := netip.ParseAddr(strings.TrimSpace(hops[i]))
+		if err != nil {
+			break
+		}
+		client = addr.Unmap()
+		if !p.trusts(client) {
+			break
+		}
+	}
+	return client, client.IsValid()
+}
+
+func remoteAddr(value string) (netip.Addr, bool) {
+	host, _, err := net.SplitHostPort(value)
+	if err != nil {
+		host = value
+	}
+	addr, err := netip.ParseAddr(host)
+	return addr.Unmap(), err == nil
+}
+
+func firstForwarded(header string) string {
+	first, _, _ := strings.Cut(header, ",")
+	return strings.TrimSpace(first)
+}
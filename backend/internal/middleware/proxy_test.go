@@ -0,0 +1,55 @@
+package middleware
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"testing"
+)
+
+func TestProxyBelievesOnlyTrustedForwardingHeaders(t *testing.T) {
+	proxy, err := NewProxy([]string{"10.0.0.0/8", "192.0.2.1"}, "/music/")
+	if err != nil {
+		t.Fatal(err)
+	}
+	type seen struct{ remote, path, location, url string }
+	serve := func(remoteAddr, target string, headers map[string]string) seen {
+		var got seen
+		handler := proxy.Middleware(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			got = seen{r.RemoteAddr, r.URL.Path, Path(r, "/api/v1/jobs/1"), URL(r, "/share/abc")}
+		}))
+		req := httptest.NewRequest(http.MethodGet, target, nil)
+		req.RemoteAddr = remoteAddr
+		for name, value := range headers {
+			req.Header.Set(name, value)
+		}
+		handler.ServeHTTP(httptest.NewRecorder(), req)
+		return got
+	}
+	forwarded := map[string]string{
+		"X-Forwarded-For":   "203.0.113.7, 198.51.100.2, 10.1.1.1",
+		"X-Forwarded-Proto": "https",
+		"X-Forwarded-Host":  "music.example.com",
+	}
+
+	got := serve("10.0.0.5:41000", "http://backend:8080/music/api/v1/jobs", forwarded)
+	want := seen{"198.51.100.2:0", "/api/v1/jobs", "/music/api/v1/jobs/1", "https://music.example.com/music/share/abc"}
+	if got != want {
+		t.Fatalf("through trusted proxy = %+v, want %+v", got, want)
+	}
+	got = serve("203.0.113.9:5000", "http://backend:8080/api/v1/jobs", forwarded)
+	want = seen{"203.0.113.9:5000", "/api/v1/jobs", "/music/api/v1/jobs/1", "http://backend:8080/music/share/abc"}
+	if got != want {
+		t.Fatalf("spoofed headers = %+v, want %+v", got, want)
+	}
+	got = serve("192.0.2.1:80", "http://backend:8080/api/v1/jobs", map[string]string{"X-Forwarded-Prefix": "/omp"})
+	if got.location != "/omp/api/v1/jobs/1" {
+		t.Fatalf("X-Forwarded-Prefix location = %q", got.location)
+	}
+
+	if _, err := NewProxy([]string{"proxy.internal"}, ""); err == nil {
+		t.Error("NewProxy accepted a host name as a trusted proxy")
+	}
+	if _, err := NewProxy(nil, "music"); err == nil {
+		t.Error("NewProxy accepted a relative base path")
+	}
+}
@@ -0,0 +1,31 @@
+package musicbrainz
+
+// Alias is another name MusicBrainz records for an entity, typically the name
+// in another language or a transliteration into Latin script.
+type Alias struct {
+	Name    string `json:"name"`
+	Locale  string `json:"locale,omitempty"`
+	Primary bool   `json:"primary,omitempty"`
+}
+
+// mbAlias is an alias as returned with inc=aliases. Locale and primary are
+// null for aliases not tied to a locale.
+type mbAlias struct {
+	Name    string `json:"name"`
+	Locale  string `json:"locale"`
+	Primary bool   `json:"primary"`
+	Type    string `json:"type"`
+}
+
+// parseAliases drops search hints, which are misspellings MusicBrainz keeps
+// only to help its own search, and aliases without a name.
+func parseAliases(aliases []mbAlias) []Alias {
+	var out []Alias
+	for _, alias := range aliases {
+		if alias.Name == "" || alias.Type == "Search hint" {
+			continue
+		}
+		out = append(out, Alias{Name: alias.Name, Locale: alias.Locale, Primary: alias.Primary})
+	}
+	return out
+}
@@ -0,0 +1,245 @@
+package musicbrainz
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"reflect"
+	"testing"
+	"time"
+
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
+	"github.com/openmusicplayer/backend/internal/testutil"
+)
+
+const (
+	radioheadMBID  = "a74b1b7f-71a5-4011-9441-d0b5e4122711"
+	okComputerMBID = "52709206-8816-3c12-9ff6-f957f2f1eecf"
+	paranoidMBID   = "8a0e5c5d-0f36-4b7a-8a0e-3d3c1a9b2f41"
+)
+
+// newContractClient points a client at a mock server with millisecond backoff
+// so retry paths run without network access or real delays.
+func newContractClient(t *testing.T) (*Client, *testutil.MusicBrainzServer) {
+	t.Helper()
+	mb := testutil.NewMusicBrainzServer(t)
+	client := NewClient(nil,
+		WithBaseURL(mb.URL()),
+		WithRetryConfig(&apperrors.RetryConfig{
+			MaxRetries:     2,
+			InitialBackoff: time.Millisecond,
+			MaxBackoff:     time.Millisecond,
+			BackoffFactor:  1,
+		}),
+	)
+	return client, mb
+}
+
+func TestContractSearchTracksMapsRecordingFixture(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.RespondJSON("/recording", testutil.ReadFixture(t, "recording_search.json"))
+
+	resp, err := client.SearchTracks(context.Background(), "paranoid android", 5, 0, true)
+	if err != nil {
+		t.Fatalf("SearchTracks: %v", err)
+	}
+	if resp.Total != 412 || resp.Limit != 5 || len(resp.Results) != 2 {
+		t.Fatalf("response = total %d limit %d results %d", resp.Total, resp.Limit, len(resp.Results))
+	}
+
+	got := resp.Results[0]
+	want := TrackResult{
+		MBID:             paranoidMBID,
+		Title:            "Paranoid Android",
+		Artist:           "Radiohead",
+		ArtistMBID:       radioheadMBID,
+		Album:            "OK Computer",
+		AlbumMBID:        "b1392450-e666-3926-a536-22c65f834433",
+		ReleaseID:        okComputerMBID,
+		ReleaseGroupMBID: "b1392450-e666-3926-a536-22c65f834433",
+		CoverArtURL:      "https://coverartarchive.org/release/" + okComputerMBID + "/front-250",
+		Duration:         387000,
+		TrackNumber:      2,
+		ReleaseDate:      "1997-05-21",
+		Score:            100,
+	}
+	if got != want {
+		t.Fatalf("first result = %+v\nwant %+v", got, want)
+	}
+	if live := resp.Results[1]; live.Album != "" || live.ReleaseID != "" {
+		t.Fatalf("recording without releases must not invent an album: %+v", live)
+	}
+
+	requests := mb.Requests()
+	if ua := requests[0].Header.Get("User-Agent"); ua != userAgent {
+		t.Fatalf("User-Agent = %q, MusicBrainz requires the identifying agent", ua)
+	}
+	if q := requests[0].URL.Query(); q.Get("query") != "paranoid android" || q.Get("fmt") != "json" || q.Get("limit") != "5" {
+		t.Fatalf("unexpected query %v", q)
+	}
+}
+
+func TestContractSearchArtistsAndAlbumsMapFixtures(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.RespondJSON("/artist", testutil.ReadFixture(t, "artist_search.json"))
+	mb.RespondJSON("/release-group", testutil.ReadFixture(t, "release_group_search.json"))
+	ctx := context.Background()
+
+	artists, err := client.SearchArtists(ctx, "radiohead", 10, 0, true)
+	if err != nil {
+		t.Fatalf("SearchArtists: %v", err)
+	}
+	if len(artists.Results) != 2 {
+		t.Fatalf("artists = %+v", artists.Results)
+	}
+	if a := artists.Results[0]; a.MBID != radioheadMBID || a.SortName != "Radiohead" || a.Type != "Group" || a.Country != "GB" || a.Score != 100 {
+		t.Fatalf("artist = %+v", a)
+	}
+	if a := artists.Results[1]; a.Disambiguation != "tribute act" {
+		t.Fatalf("disambiguation = %q", a.Disambiguation)
+	}
+
+	albums, err := client.SearchAlbums(ctx, "ok computer", 10, 0, true)
+	if err != nil {
+		t.Fatalf("SearchAlbums: %v", err)
+	}
+	album := albums.Results[0]
+	if album.MBID != "b1392450-e666-3926-a536-22c65f834433" || album.Artist != "Radiohead" ||
+		album.PrimaryType != "Album" || album.ReleaseDate != "1997-05-21" || album.TrackCount != 12 ||
+		len(album.SecondaryTypes) != 1 || album.SecondaryTypes[0] != "Compilation" {
+		t.Fatalf("album = %+v", album)
+	}
+}
+
+func TestContractLookupsMapFixtures(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.RespondJSON("/artist/"+radioheadMBID, testutil.ReadFixture(t, "artist_lookup.json"))
+	mb.RespondJSON("/release/"+okComputerMBID, testutil.ReadFixture(t, "release_lookup.json"))
+	mb.RespondJSON("/recording/"+paranoidMBID, testutil.ReadFixture(t, "recording_lookup.json"))
+	ctx := context.Background()
+
+	artist, err := client.GetArtist(ctx, radioheadMBID)
+	if err != nil {
+		t.Fatalf("GetArtist: %v", err)
+	}
+	if artist.Name != "Radiohead" || artist.BeginDate != "1991" || artist.EndDate != "" || len(artist.Releases) != 2 {
+		t.Fatalf("artist = %+v", artist)
+	}
+	if artist.Releases[1].Title != "In Rainbows" || artist.Releases[1].Date != "2007-10-10" {
+		t.Fatalf("discography entry = %+v", artist.Releases[1])
+	}
+	if artist.WikidataID != "Q44190" || len(artist.ImageURLs) != 1 || artist.Annotation == "" {
+		t.Fatalf("artist links = %q %v %q", artist.WikidataID, artist.ImageURLs, artist.Annotation)
+	}
+	if len(artist.Related) != 1 || artist.Related[0].Name != "Thom Yorke" || artist.Related[0].Relation != "member of band" {
+		t.Fatalf("related artists = %+v", artist.Related)
+	}
+
+	release, err := client.GetRelease(ctx, okComputerMBID)
+	if err != nil {
+		t.Fatalf("GetRelease: %v", err)
+	}
+	if release.Artist != "Radiohead" || release.Country != "GB" || release.TrackCount != 2 {
+		t.Fatalf("release = %+v", release)
+	}
+	second := release.Tracks[1]
+	if second.ID != paranoidMBID || second.Position != 2 || second.Duration != 387213 || second.AlbumID != okComputerMBID {
+		t.Fatalf("release track = %+v; IDs must be recording MBIDs, not track MBIDs", second)
+	}
+
+	recording, err := client.GetRecording(ctx, paranoidMBID)
+	if err != nil {
+		t.Fatalf("GetRecording: %v", err)
+	}
+	if recording.Artist != "Radiohead" || recording.ArtistSortName != "Radiohead" || recording.Album != "OK Computer" || recording.AlbumID != okComputerMBID || recording.Duration != 387000 {
+		t.Fatalf("recording = %+v", recording)
+	}
+	if len(recording.Credits) != 1 || recording.Credits[0] != (ArtistCredit{ID: radioheadMBID, Name: "Radiohead"}) {
+		t.Fatalf("credits = %+v", recording.Credits)
+	}
+	if len(recording.Aliases) != 1 || recording.Aliases[0] != (Alias{Name: "パラノイド・アンドロイド", Locale: "ja", Primary: true}) {
+		t.Fatalf("aliases = %+v; search hints must be dropped", recording.Aliases)
+	}
+	if len(recording.ArtistAliases) != 1 || recording.ArtistAliases[0].Name != "レディオヘッド" {
+		t.Fatalf("artist aliases = %+v", recording.ArtistAliases)
+	}
+	wantRelations := []Relation{
+		{Kind: RelationPerformanceOf, TargetType: "work", TargetID: "4a9b8e0f-3f3a-3c52-b4a8-91b3d6a6b0e7", TargetTitle: "Paranoid Android"},
+		{Kind: RelationRemixedBy, TargetType: "recording", TargetID: "d5e8b0c1-7d0a-4b49-9c4e-0d7f7a6b2c11", TargetTitle: "Paranoid Android (remix)"},
+	}
+	if !reflect.DeepEqual(recording.Relations, wantRelations) {
+		t.Fatalf("relations = %+v; want %+v (artist relationships dropped)", recording.Relations, wantRelations)
+	}
+}
+
+func TestContractNotFoundIsNotRetried(t *testing.T) {
+	client, mb := newContractClient(t)
+
+	_, err := client.GetArtist(context.Background(), "00000000-0000-0000-0000-000000000000")
+	if !errors.Is(err, ErrNotFound) {
+		t.Fatalf("GetArtist unknown MBID error = %v, want ErrNotFound", err)
+	}
+	if got := len(mb.Requests()); got != 1 {
+		t.Fatalf("404 was requested %d times, want exactly once", got)
+	}
+}
+
+func TestContractRetriesTransientFailuresThenSucceeds(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.Respond("/recording/"+paranoidMBID,
+		testutil.MockResponse{Status: http.StatusServiceUnavailable},
+		testutil.MockResponse{Status: http.StatusTooManyRequests},
+		testutil.MockResponse{Status: http.StatusOK, Body: testutil.ReadFixture(t, "recording_lookup.json")},
+	)
+
+	recording, err := client.GetRecording(context.Background(), paranoidMBID)
+	if err != nil {
+		t.Fatalf("GetRecording after transient failures: %v", err)
+	}
+	if recording.Title != "Paranoid Android" {
+		t.Fatalf("recording = %+v", recording)
+	}
+	if got := len(mb.Requests()); got != 3 {
+		t.Fatalf("requests = %d, want 503 + 429 + success", got)
+	}
+}
+
+func TestContractGivesUpAfterMaxRetries(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.Respond("/artist", testutil.MockResponse{Status: http.StatusServiceUnavailable})
+
+	_, err := client.SearchArtists(context.Background(), "radiohead", 10, 0, true)
+	var appErr *apperrors.AppError
+	if !errors.As(err, &appErr) || appErr.Code != apperrors.CodeMusicBrainzError {
+		t.Fatalf("error = %v, want MusicBrainz AppError", err)
+	}
+	if got := len(mb.Requests()); got != 3 {
+		t.Fatalf("requests = %d, want initial attempt + 2 retries", got)
+	}
+}
+
+func TestContractMalformedBodyIsAParseError(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.RespondJSON("/recording", []byte(`{"recordings": "not-an-array"}`))
+
+	if _, err := client.SearchTracks(context.Background(), "x", 1, 0, true); err == nil {
+		t.Fatal("type drift in a mapped field must surface as an error, not empty results")
+	}
+}
+
+func TestContractPingMakesOneUnretriedRequest(t *testing.T) {
+	client, mb := newContractClient(t)
+	mb.Respond("/genre/all", testutil.MockResponse{Status: http.StatusOK, Body: []byte(`{"genres":[]}`)})
+	if err := client.Ping(context.Background()); err != nil {
+		t.Fatalf("Ping() error = %v", err)
+	}
+
+	failing, failingMB := newContractClient(t)
+	failingMB.Respond("/genre/all", testutil.MockResponse{Status: http.StatusServiceUnavailable})
+	if err := failing.Ping(context.Background()); err == nil {
+		t.Fatal("Ping() against a failing service returned nil")
+	}
+	if got := len(failingMB.Requests()); got != 1 {
+		t.Fatalf("requests = %d, want exactly one", got)
+	}
+}
@@ -2,8 +2,11 @@ package musicbrainz
 
 import (
 	"encoding/json"
+	"errors"
 	"net/http"
 	"strconv"
+
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 type Handlers struct {
@@ -26,7 +29,7 @@ func (h *Handlers) SearchTracks(w http.ResponseWriter, r *http.Request) {
 
 	results, err := h.client.SearchTracks(r.Context(), query, limit, offset, skipCache)
 	if err != nil {
-		writeError(w, http.StatusInternalServerError, "SEARCH_FAILED", err.Error())
+		writeSearchError(w, r, err)
 		return
 	}
 
@@ -45,7 +48,7 @@ func (h *Handlers) SearchArtists(w http.ResponseWriter, r *http.Request) {
 
 	results, err := h.client.SearchArtists(r.Context(), query, limit, offset, skipCache)
 	if err != nil {
-		writeError(w, http.StatusInternalServerError, "SEARCH_FAILED", err.Error())
+		writeSearchError(w, r, err)
 		return
 	}
 
@@ -64,7 +67,7 @@ func (h *Handlers) SearchAlbums(w http.ResponseWriter, r *http.Request) {
 
 	results, err := h.client.SearchAlbums(r.Context(), query, limit, offset, skipCache)
 	if err != nil {
-		writeError(w, http.StatusInternalServerError, "SEARCH_FAILED", err.Error())
+		writeSearchError(w, r, err)
 		return
 	}
 
@@ -96,11 +99,17 @@ func writeJSON(w http.ResponseWriter, status int, data interface{}) {
 	json.NewEncoder(w).Encode(data)
 }
 
+// writeSearchError reports upstream failures with their MusicBrainz problem
+// type instead of echoing the raw error to the client.
+func writeSearchError(w http.ResponseWriter, r *http.Request, err error) {
+	var appErr *apperrors.AppError
+	if errors.As(err, &appErr) {
+		apperrors.WriteProblem(w, r, appErr.Problem())
+		return
+	}
+	writeError(w, http.StatusInternalServerError, "SEARCH_FAILED", "search failed")
+}
+
 func writeError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(map[string]string{
-		"code":    code,
-		"message": message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
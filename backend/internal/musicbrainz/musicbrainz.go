@@ -5,15 +5,19 @@ import (
 	"crypto/sha256"
 	"encoding/hex"
 	"encoding/json"
+	"errors"
 	"fmt"
 	"io"
 	"net/http"
 	"net/url"
+	"path"
+	"strings"
 	"time"
 
 	"github.com/openmusicplayer/backend/internal/cache"
 	apperrors "github.com/openmusicplayer/backend/internal/errors"
 	"github.com/openmusicplayer/backend/internal/logger"
+	"github.com/openmusicplayer/backend/internal/tracing"
 )
 
 const (
@@ -30,17 +34,52 @@ const (
 var ErrNotFound = fmt.Errorf("not found")
 
 type Client struct {
-	httpClient *http.Client
-	cache      *cache.Cache
+	httpClient  *http.Client
+	cache       *cache.Cache
+	baseURL     string
+	retryConfig *apperrors.RetryConfig
 }
 
-func NewClient(cache *cache.Cache) *Client {
-	return &Client{
+// ClientOption customizes a Client, primarily so tests can point it at a local
+// server without network access or production backoff delays.
+type ClientOption func(*Client)
+
+// WithBaseURL overrides the MusicBrainz web service root (".../ws/2").
+func WithBaseURL(u string) ClientOption {
+	return func(c *Client) {
+		c.baseURL = strings.TrimRight(u, "/")
+	}
+}
+
+// WithRetryConfig overrides the retry/backoff policy for API requests.
+func WithRetryConfig(cfg *apperrors.RetryConfig) ClientOption {
+	return func(c *Client) {
+		c.retryConfig = cfg
+	}
+}
+
+// WithTransport sends requests through base, traced as before, such as a
+// transport using a proxy.
+func WithTransport(base http.RoundTripper) ClientOption {
+	return func(c *Client) {
+		c.httpClient.Transport = tracing.NewTransport(base)
+	}
+}
+
+func NewClient(cache *cache.Cache, opts ...ClientOption) *Client {
+	c := &Client{
 		httpClient: &http.Client{
-			Timeout: 30 * time.Second,
+			Timeout:   30 * time.Second,
+			Transport: tracing.NewTransport(nil),
 		},
-		cache: cache,
+		cache:       cache,
+		baseURL:     baseURL,
+		retryConfig: apperrors.MusicBrainzRetryConfig(),
+	}
+	for _, opt := range opts {
+		opt(c)
 	}
+	return c
 }
 
 func (c *Client) cacheGet(ctx context.Context, key string) (string, bool) {
@@ -114,31 +153,65 @@ type Artist struct {
 	BeginDate      string    `json:"beginDate,omitempty"`
 	EndDate        string    `json:"endDate,omitempty"`
 	Releases       []Release `json:"releases,omitempty"`
+	// Annotation is the artist's free-text MusicBrainz annotation, in wiki
+	// markup.
+	Annotation string `json:"annotation,omitempty"`
+	// WikidataID is the artist's Wikidata item, such as "Q44190".
+	WikidataID string `json:"wikidataId,omitempty"`
+	// ImageURLs are pages of images MusicBrainz links to the artist.
+	ImageURLs []string        `json:"imageUrls,omitempty"`
+	Related   []RelatedArtist `json:"relatedArtists,omitempty"`
+}
+
+// RelatedArtist is an artist MusicBrainz relates to another, such as a band
+// member or a collaboration. Relation is the MusicBrainz relationship type.
+type RelatedArtist struct {
+	ID       string `json:"id"`
+	Name     string `json:"name"`
+	Relation string `json:"relation"`
 }
 
 type Release struct {
-	ID          string  `json:"id"`
-	Title       string  `json:"title"`
-	Artist      string  `json:"artist,omitempty"`
-	ArtistID    string  `json:"artistId,omitempty"`
-	Date        string  `json:"date,omitempty"`
-	Country     string  `json:"country,omitempty"`
-	TrackCount  int     `json:"trackCount,omitempty"`
-	CoverArtURL string  `json:"coverArtUrl,omitempty"`
-	Tracks      []Track `json:"tracks,omitempty"`
+	ID            string  `json:"id"`
+	Title         string  `json:"title"`
+	Artist        string  `json:"artist,omitempty"`
+	ArtistID      string  `json:"artistId,omitempty"`
+	Date          string  `json:"date,omitempty"`
+	Country       string  `json:"country,omitempty"`
+	TrackCount    int     `json:"trackCount,omitempty"`
+	CoverArtURL   string  `json:"coverArtUrl,omitempty"`
+	PrimaryType   string  `json:"primaryType,omitempty"`
+	Annotation    string  `json:"annotation,omitempty"`
+	Tracks        []Track `json:"tracks,omitempty"`
+	Aliases       []Alias `json:"aliases,omitempty"`
+	ArtistAliases []Alias `json:"artistAliases,omitempty"`
 }
 
 type Track struct {
-	ID           string `json:"id"`
-	Title        string `json:"title"`
-	Artist       string `json:"artist,omitempty"`
-	ArtistID     string `json:"artistId,omitempty"`
-	Album        string `json:"album,omitempty"`
-	AlbumID      string `json:"albumId,omitempty"`
-	Duration     int    `json:"duration,omitempty"`
-	Position     int    `json:"position,omitempty"`
-	InLibrary    bool   `json:"inLibrary"`
-	Downloadable bool   `json:"downloadable"`
+	ID             string         `json:"id"`
+	Title          string         `json:"title"`
+	Artist         string         `json:"artist,omitempty"`
+	ArtistSortName string         `json:"artistSortName,omitempty"`
+	ArtistID       string         `json:"artistId,omitempty"`
+	Album          string         `json:"album,omitempty"`
+	AlbumID        string         `json:"albumId,omitempty"`
+	ReleaseDate    string         `json:"releaseDate,omitempty"`
+	Duration       int            `json:"duration,omitempty"`
+	Position       int            `json:"position,omitempty"`
+	InLibrary      bool           `json:"inLibrary"`
+	Downloadable   bool           `json:"downloadable"`
+	Relations      []Relation     `json:"relations,omitempty"`
+	Credits        []ArtistCredit `json:"credits,omitempty"`
+	Aliases        []Alias        `json:"aliases,omitempty"`
+	ArtistAliases  []Alias        `json:"artistAliases,omitempty"`
+}
+
+// ArtistCredit is one artist in a recording's credit. JoinPhrase is the text
+// MusicBrainz prints after the name, such as " feat. " or " & ".
+type ArtistCredit struct {
+	ID         string `json:"id"`
+	Name       string `json:"name"`
+	JoinPhrase string `json:"joinPhrase,omitempty"`
 }
 
 // MusicBrainz API response types
@@ -223,6 +296,7 @@ type mbArtistLookupResponse struct {
 	Type           string `json:"type"`
 	Country        string `json:"country"`
 	Disambiguation string `json:"disambiguation"`
+	Annotation     string `json:"annotation"`
 	LifeSpan       struct {
 		Begin string `json:"begin"`
 		End   string `json:"end"`
@@ -233,18 +307,32 @@ type mbArtistLookupResponse struct {
 		PrimaryType      string `json:"primary-type"`
 		FirstReleaseDate string `json:"first-release-date"`
 	} `json:"release-groups"`
+	Relations []struct {
+		Type       string `json:"type"`
+		TargetType string `json:"target-type"`
+		URL        *struct {
+			Resource string `json:"resource"`
+		} `json:"url"`
+		Artist *struct {
+			ID   string `json:"id"`
+			Name string `json:"name"`
+		} `json:"artist"`
+	} `json:"relations"`
 }
 
 // mbReleaseLookupResponse is for single release lookup
 type mbReleaseLookupResponse struct {
-	ID           string `json:"id"`
-	Title        string `json:"title"`
-	Date         string `json:"date"`
-	Country      string `json:"country"`
+	ID           string    `json:"id"`
+	Title        string    `json:"title"`
+	Date         string    `json:"date"`
+	Country      string    `json:"country"`
+	Annotation   string    `json:"annotation"`
+	Aliases      []mbAlias `json:"aliases"`
 	ArtistCredit []struct {
 		Artist struct {
-			ID   string `json:"id"`
-			Name string `json:"name"`
+			ID      string    `json:"id"`
+			Name    string    `json:"name"`
+			Aliases []mbAlias `json:"aliases"`
 		} `json:"artist"`
 	} `json:"artist-credit"`
 	Media []struct {
@@ -263,19 +351,26 @@ type mbReleaseLookupResponse struct {
 
 // mbRecordingLookupResponse is for single recording lookup
 type mbRecordingLookupResponse struct {
-	ID           string `json:"id"`
-	Title        string `json:"title"`
-	Length       int    `json:"length"`
+	ID           string    `json:"id"`
+	Title        string    `json:"title"`
+	Length       int       `json:"length"`
+	Aliases      []mbAlias `json:"aliases"`
 	ArtistCredit []struct {
-		Artist struct {
-			ID   string `json:"id"`
-			Name string `json:"name"`
+		Name       string `json:"name"`
+		JoinPhrase string `json:"joinphrase"`
+		Artist     struct {
+			ID       string    `json:"id"`
+			Name     string    `json:"name"`
+			SortName string    `json:"sort-name"`
+			Aliases  []mbAlias `json:"aliases"`
 		} `json:"artist"`
 	} `json:"artist-credit"`
 	Releases []struct {
 		ID    string `json:"id"`
 		Title string `json:"title"`
+		Date  string `json:"date"`
 	} `json:"releases"`
+	Relations []mbRelation `json:"relations"`
 }
 
 // Search methods with caching
@@ -294,7 +389,7 @@ func (c *Client) SearchTracks(ctx context.Context, query string, limit, offset i
 	}
 
 	reqURL := fmt.Sprintf("%s/recording?query=%s&limit=%d&offset=%d&fmt=json",
-		baseURL, url.QueryEscape(query), limit, offset)
+		c.baseURL, url.QueryEscape(query), limit, offset)
 
 	body, err := c.doRequest(ctx, reqURL)
 	if err != nil {
@@ -364,7 +459,7 @@ func (c *Client) SearchArtists(ctx context.Context, query string, limit, offset
 	}
 
 	reqURL := fmt.Sprintf("%s/artist?query=%s&limit=%d&offset=%d&fmt=json",
-		baseURL, url.QueryEscape(query), limit, offset)
+		c.baseURL, url.QueryEscape(query), limit, offset)
 
 	body, err := c.doRequest(ctx, reqURL)
 	if err != nil {
@@ -417,7 +512,7 @@ func (c *Client) SearchAlbums(ctx context.Context, query string, limit, offset i
 	}
 
 	reqURL := fmt.Sprintf("%s/release-group?query=%s&limit=%d&offset=%d&fmt=json",
-		baseURL, url.QueryEscape(query), limit, offset)
+		c.baseURL, url.QueryEscape(query), limit, offset)
 
 	body, err := c.doRequest(ctx, reqURL)
 	if err != nil {
@@ -470,7 +565,7 @@ func (c *Client) SearchAlbums(ctx context.Context, query string, limit, offset i
 
 // GetArtist fetches artist details with discography from MusicBrainz
 func (c *Client) GetArtist(ctx context.Context, mbID string) (*Artist, error) {
-	cacheKey := fmt.Sprintf("mb:artist-full:%s", mbID)
+	cacheKey := fmt.Sprintf("mb:artist-detail:%s", mbID)
 
 	if cached, ok := c.cacheGet(ctx, cacheKey); ok {
 		var artist Artist
@@ -479,7 +574,7 @@ func (c *Client) GetArtist(ctx context.Context, mbID string) (*Artist, error) {
 		}
 	}
 
-	endpoint := fmt.Sprintf("%s/artist/%s?fmt=json&inc=release-groups", baseURL, url.PathEscape(mbID))
+	endpoint := fmt.Sprintf("%s/artist/%s?fmt=json&inc=release-groups+annotation+url-rels+artist-rels", c.baseURL, url.PathEscape(mbID))
 
 	body, err := c.doRequest(ctx, endpoint)
 	if err != nil {
@@ -501,6 +596,7 @@ func (c *Client) GetArtist(ctx context.Context, mbID string) (*Artist, error) {
 		BeginDate:      mbResp.LifeSpan.Begin,
 		EndDate:        mbResp.LifeSpan.End,
 		Releases:       make([]Release, 0, len(mbResp.ReleaseGroups)),
+		Annotation:     mbResp.Annotation,
 	}
 
 	for _, rg := range mbResp.ReleaseGroups {
@@ -509,9 +605,20 @@ func (c *Client) GetArtist(ctx context.Context, mbID string) (*Artist, error) {
 			Title:       rg.Title,
 			Date:        rg.FirstReleaseDate,
 			CoverArtURL: c.GetCoverArtURL(rg.ID),
+			PrimaryType: rg.PrimaryType,
 		}
 		artist.Releases = append(artist.Releases, release)
 	}
+	for _, rel := range mbResp.Relations {
+		switch {
+		case rel.TargetType == "url" && rel.URL != nil && rel.Type == "wikidata":
+			artist.WikidataID = path.Base(rel.URL.Resource)
+		case rel.TargetType == "url" && rel.URL != nil && rel.Type == "image":
+			artist.ImageURLs = append(artist.ImageURLs, rel.URL.Resource)
+		case rel.TargetType == "artist" && rel.Artist != nil && rel.Artist.ID != "":
+			artist.Related = append(artist.Related, RelatedArtist{ID: rel.Artist.ID, Name: rel.Artist.Name, Relation: rel.Type})
+		}
+	}
 
 	if artistJSON, err := json.Marshal(artist); err == nil {
 		c.cacheSet(ctx, cacheKey, string(artistJSON), entityLookupTTL)
@@ -531,7 +638,7 @@ func (c *Client) GetRelease(ctx context.Context, mbID string) (*Release, error)
 		}
 	}
 
-	endpoint := fmt.Sprintf("%s/release/%s?fmt=json&inc=artist-credits+recordings", baseURL, url.PathEscape(mbID))
+	endpoint := fmt.Sprintf("%s/release/%s?fmt=json&inc=artist-credits+recordings+aliases+annotation", c.baseURL, url.PathEscape(mbID))
 
 	body, err := c.doRequest(ctx, endpoint)
 	if err != nil {
@@ -549,12 +656,15 @@ func (c *Client) GetRelease(ctx context.Context, mbID string) (*Release, error)
 		Date:        mbResp.Date,
 		Country:     mbResp.Country,
 		CoverArtURL: c.GetCoverArtURL(mbResp.ID),
+		Annotation:  mbResp.Annotation,
 		Tracks:      make([]Track, 0),
+		Aliases:     parseAliases(mbResp.Aliases),
 	}
 
 	if len(mbResp.ArtistCredit) > 0 {
 		release.Artist = mbResp.ArtistCredit[0].Artist.Name
 		release.ArtistID = mbResp.ArtistCredit[0].Artist.ID
+		release.ArtistAliases = parseAliases(mbResp.ArtistCredit[0].Artist.Aliases)
 	}
 
 	for _, media := range mbResp.Media {
@@ -584,16 +694,28 @@ func (c *Client) GetRelease(ctx context.Context, mbID string) (*Release, error)
 
 // GetRecording fetches recording/track details from MusicBrainz
 func (c *Client) GetRecording(ctx context.Context, mbID string) (*Track, error) {
+	return c.getRecording(ctx, mbID, false)
+}
+
+// RefreshRecording fetches a recording from MusicBrainz bypassing the cache,
+// then refreshes the cached copy.
+func (c *Client) RefreshRecording(ctx context.Context, mbID string) (*Track, error) {
+	return c.getRecording(ctx, mbID, true)
+}
+
+func (c *Client) getRecording(ctx context.Context, mbID string, skipCache bool) (*Track, error) {
 	cacheKey := fmt.Sprintf("mb:recording:%s", mbID)
 
-	if cached, ok := c.cacheGet(ctx, cacheKey); ok {
-		var track Track
-		if err := json.Unmarshal([]byte(cached), &track); err == nil {
-			return &track, nil
+	if !skipCache {
+		if cached, ok := c.cacheGet(ctx, cacheKey); ok {
+			var track Track
+			if err := json.Unmarshal([]byte(cached), &track); err == nil {
+				return &track, nil
+			}
 		}
 	}
 
-	endpoint := fmt.Sprintf("%s/recording/%s?fmt=json&inc=artist-credits+releases", baseURL, url.PathEscape(mbID))
+	endpoint := fmt.Sprintf("%s/recording/%s?fmt=json&inc=artist-credits+releases+recording-rels+work-rels+aliases", c.baseURL, url.PathEscape(mbID))
 
 	body, err := c.doRequest(ctx, endpoint)
 	if err != nil {
@@ -606,19 +728,31 @@ func (c *Client) GetRecording(ctx context.Context, mbID string) (*Track, error)
 	}
 
 	track := &Track{
-		ID:       mbResp.ID,
-		Title:    mbResp.Title,
-		Duration: mbResp.Length,
+		ID:        mbResp.ID,
+		Title:     mbResp.Title,
+		Duration:  mbResp.Length,
+		Relations: parseRelations(mbResp.Relations),
+		Aliases:   parseAliases(mbResp.Aliases),
 	}
 
 	if len(mbResp.ArtistCredit) > 0 {
 		track.Artist = mbResp.ArtistCredit[0].Artist.Name
 		track.ArtistID = mbResp.ArtistCredit[0].Artist.ID
+		track.ArtistSortName = mbResp.ArtistCredit[0].Artist.SortName
+		track.ArtistAliases = parseAliases(mbResp.ArtistCredit[0].Artist.Aliases)
+	}
+	for _, credit := range mbResp.ArtistCredit {
+		name := credit.Name
+		if name == "" {
+			name = credit.Artist.Name
+		}
+		track.Credits = append(track.Credits, ArtistCredit{ID: credit.Artist.ID, Name: name, JoinPhrase: credit.JoinPhrase})
 	}
 
 	if len(mbResp.Releases) > 0 {
 		track.Album = mbResp.Releases[0].Title
 		track.AlbumID = mbResp.Releases[0].ID
+		track.ReleaseDate = mbResp.Releases[0].Date
 	}
 
 	if trackJSON, err := json.Marshal(track); err == nil {
@@ -633,14 +767,45 @@ func (c *Client) GetCoverArtURL(releaseID string) string {
 	return fmt.Sprintf("%s/release/%s/front-250", coverArtURL, releaseID)
 }
 
+// Ping makes one request to the web service, without retries, to check that
+// it is reachable.
+func (c *Client) Ping(ctx context.Context) error {
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, c.baseURL+"/genre/all?limit=1&fmt=json", nil)
+	if err != nil {
+		return fmt.Errorf("failed to create request: %w", err)
+	}
+	req.Header.Set("User-Agent", userAgent)
+	req.Header.Set("Accept", "application/json")
+	resp, err := c.httpClient.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		return fmt.Errorf("MusicBrainz API returned status %d", resp.StatusCode)
+	}
+	return nil
+}
+
 // HTTP client helpers
 
-func (c *Client) doRequest(ctx context.Context, reqURL string) ([]byte, error) {
+func (c *Client) doRequest(ctx context.Context, reqURL string) (_ []byte, err error) {
 	log := logger.Default().WithComponent("musicbrainz")
-	cfg := apperrors.MusicBrainzRetryConfig()
+	cfg := c.retryConfig
+
+	// One span covers the whole retry loop; each attempt gets its own client
+	// span from the traced transport.
+	ctx, span := tracing.Tracer().Start(ctx, "musicbrainz.request")
+	defer func() {
+		if errors.Is(err, ErrNotFound) {
+			tracing.End(span, nil)
+			return
+		}
+		tracing.End(span, err)
+	}()
 
 	var result []byte
-	err := apperrors.Retry(ctx, cfg, func(ctx context.Context) error {
+	err = apperrors.Retry(ctx, cfg, func(ctx context.Context) error {
 		req, err := http.NewRequestWithContext(ctx, http.MethodGet, reqURL, nil)
 		if err != nil {
 			return fmt.Errorf("failed to create request: %w", err)
@@ -1,6 +1,9 @@
 package musicbrainz
 
-import "testing"
+import (
+	"encoding/json"
+	"testing"
+)
 
 func TestGetCoverArtURLUsesReleaseID(t *testing.T) {
 	client := NewClient(nil)
@@ -10,3 +13,27 @@ func TestGetCoverArtURLUsesReleaseID(t *testing.T) {
 		t.Fatalf("GetCoverArtURL = %q, want %q", got, want)
 	}
 }
+
+func TestParseRelationsClassifiesCoversRemixesAndSamples(t *testing.T) {
+	var rels []mbRelation
+	if err := json.Unmarshal([]byte(`[
+		{"type": "performance", "target-type": "work", "attributes": ["live", "cover"], "work": {"id": "w1"}},
+		{"type": "remix", "direction": "backward", "target-type": "recording", "recording": {"id": "r1"}},
+		{"type": "samples material", "direction": "forward", "target-type": "recording", "recording": {"id": "r2"}},
+		{"type": "samples material", "direction": "backward", "target-type": "recording", "recording": {"id": "r3"}},
+		{"type": "mix", "direction": "forward", "target-type": "recording", "recording": {"id": "r4"}}
+	]`), &rels); err != nil {
+		t.Fatalf("decode relations: %v", err)
+	}
+
+	got := parseRelations(rels)
+	want := []string{RelationCoverOf, RelationRemixOf, RelationSamples, RelationSampledBy}
+	if len(got) != len(want) {
+		t.Fatalf("relations = %+v; want kinds %v", got, want)
+	}
+	for i, kind := range want {
+		if got[i].Kind != kind {
+			t.Fatalf("relation %d kind = %q; want %q", i, got[i].Kind, kind)
+		}
+	}
+}
@@ -0,0 +1,77 @@
+package musicbrainz
+
+// Relation kinds a recording can have that the library tracks. Everything
+// else MusicBrainz reports (producers, mixes, ISRC edits, ...) is dropped.
+const (
+	RelationRemixOf       = "remix_of"
+	RelationRemixedBy     = "remixed_by"
+	RelationSamples       = "samples"
+	RelationSampledBy     = "sampled_by"
+	RelationCoverOf       = "cover_of"
+	RelationPerformanceOf = "performance_of"
+)
+
+// Relation is a relationship from a recording to another recording or to the
+// work it performs, reduced to one of the Relation* kinds.
+type Relation struct {
+	Kind        string `json:"kind"`
+	TargetType  string `json:"targetType"` // "recording" or "work"
+	TargetID    string `json:"targetId"`
+	TargetTitle string `json:"targetTitle,omitempty"`
+}
+
+// mbRelation is a relationship as returned with inc=recording-rels+work-rels.
+type mbRelation struct {
+	Type       string   `json:"type"`
+	Direction  string   `json:"direction"`
+	TargetType string   `json:"target-type"`
+	Attributes []string `json:"attributes"`
+	Recording  *struct {
+		ID    string `json:"id"`
+		Title string `json:"title"`
+	} `json:"recording"`
+	Work *struct {
+		ID    string `json:"id"`
+		Title string `json:"title"`
+	} `json:"work"`
+}
+
+// parseRelations keeps remix, sample, and performance relationships.
+// MusicBrainz links a remix from the original (forward) to the remix, and a
+// sample from the sampling recording (forward) to the sampled one, so the
+// direction decides which side of the pair the looked-up recording is on.
+// Performances of a work are covers when they carry the "cover" attribute.
+func parseRelations(rels []mbRelation) []Relation {
+	var out []Relation
+	for _, rel := range rels {
+		switch {
+		case rel.TargetType == "recording" && rel.Recording != nil && rel.Recording.ID != "":
+			kind := ""
+			switch rel.Type {
+			case "remix":
+				kind = RelationRemixedBy
+				if rel.Direction == "backward" {
+					kind = RelationRemixOf
+				}
+			case "samples material":
+				kind = RelationSamples
+				if rel.Direction == "backward" {
+					kind = RelationSampledBy
+				}
+			}
+			if kind != "" {
+				out = append(out, Relation{Kind: kind, TargetType: "recording", TargetID: rel.Recording.ID, TargetTitle: rel.Recording.Title})
+			}
+		case rel.TargetType == "work" && rel.Work != nil && rel.Work.ID != "" && rel.Type == "performance":
+			kind := RelationPerformanceOf
+			for _, attr := range rel.Attributes {
+				if attr == "cover" {
+					kind = RelationCoverOf
+					break
+				}
+			}
+			out = append(out, Relation{Kind: kind, TargetType: "work", TargetID: rel.Work.ID, TargetTitle: rel.Work.Title})
+		}
+	}
+	return out
+}
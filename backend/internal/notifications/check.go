@@ -0,0 +1,105 @@
+package notifications
+
+import (
+	"context"
+	"fmt"
+	"log"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	// CheckInterval is how often Run looks for notifications to derive.
+	CheckInterval = 5 * time.Minute
+
+	// newReleaseWindow is how far back Check looks for new tracks. Tracks
+	// seen by an earlier pass are deduplicated, so the window only needs to
+	// outlast a restart.
+	newReleaseWindow = 24 * time.Hour
+
+	// newReleaseBatch caps the new releases one pass notifies about.
+	newReleaseBatch = 1000
+
+	// quotaWarningRatio is the share of a tenant quota that warns its admins,
+	// at most once a day.
+	quotaWarningRatio = 0.9
+
+	// retention is how long notifications are kept.
+	retention = 90 * 24 * time.Hour
+)
+
+// Run checks every CheckInterval until ctx is done.
+func (s *Service) Run(ctx context.Context) {
+	ticker := time.NewTicker(CheckInterval)
+	defer ticker.Stop()
+	for {
+		if err := s.Check(ctx); err != nil {
+			log.Printf("Failed to check for notifications: %v", err)
+		}
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// Check notifies users about new tracks by artists in their library and
+// tenant admins about quotas nearly used up, then drops notifications past
+// their retention.
+func (s *Service) Check(ctx context.Context) error {
+	now := s.now()
+	releases, err := s.store.NewReleases(ctx, now.Add(-newReleaseWindow), newReleaseBatch)
+	if err != nil {
+		return fmt.Errorf("find new releases: %w", err)
+	}
+	for _, release := range releases {
+		n := &db.Notification{
+			UserID:    release.UserID,
+			Type:      db.NotificationNewRelease,
+			DedupeKey: fmt.Sprint(release.TrackID),
+			Title:     "New from " + release.Artist,
+			Body:      release.Title,
+		}
+		if err := s.Notify(ctx, n, map[string]any{"trackId": release.TrackID, "artist": release.Artist}); err != nil {
+			return err
+		}
+	}
+
+	warnings, err := s.store.QuotaWarnings(ctx, quotaWarningRatio)
+	if err != nil {
+		return fmt.Errorf("find quota warnings: %w", err)
+	}
+	for _, warning := range warnings {
+		percent := quotaPercent(warning)
+		n := &db.Notification{
+			UserID:    warning.UserID,
+			Type:      db.NotificationQuotaWarning,
+			DedupeKey: fmt.Sprintf("%d:%s", warning.TenantID, now.UTC().Format(time.DateOnly)),
+			Title:     warning.TenantName + " is nearly at its quota",
+			Body:      fmt.Sprintf("%d%% of its quota is used.", percent),
+		}
+		data := map[string]any{"tenantId": warning.TenantID, "percent": percent}
+		if err := s.Notify(ctx, n, data); err != nil {
+			return err
+		}
+	}
+
+	if _, err := s.store.DeleteOlderThan(ctx, now.Add(-retention)); err != nil {
+		return fmt.Errorf("delete old notifications: %w", err)
+	}
+	return nil
+}
+
+// quotaPercent is the share of the tenant's fuller quota in use.
+func quotaPercent(w db.QuotaWarning) int {
+	var ratio float64
+	if w.MaxTracks.Valid && w.MaxTracks.Int64 > 0 {
+		ratio = float64(w.Usage.Tracks) / float64(w.MaxTracks.Int64)
+	}
+	if w.MaxStorageBytes.Valid && w.MaxStorageBytes.Int64 > 0 {
+		ratio = max(ratio, float64(w.Usage.StorageBytes)/float64(w.MaxStorageBytes.Int64))
+	}
+	return int(ratio * 100)
+}
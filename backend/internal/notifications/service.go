@@ -0,0 +1,190 @@
+// Package notifications fills each user's in-app notification inbox from
+// what happens on the instance, honoring their per-type preferences, and
+// pushes new notifications to their open WebSocket connections or, when
+// there are none, to their phones.
+package notifications
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"log"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+// PushType is the WebSocket message type of a new notification.
+const PushType = "notification"
+
+// Store keeps notifications and finds the ones Check derives.
+type Store interface {
+	Create(ctx context.Context, n *db.Notification) (bool, error)
+	NewReleases(ctx context.Context, since time.Time, limit int) ([]db.NewRelease, error)
+	QuotaWarnings(ctx context.Context, ratio float64) ([]db.QuotaWarning, error)
+	DeleteOlderThan(ctx context.Context, cutoff time.Time) (int64, error)
+}
+
+// Pusher delivers a message to every open WebSocket connection of a user.
+type Pusher interface {
+	Connected(userID uuid.UUID) bool
+	SendToUser(userID uuid.UUID, message any)
+}
+
+// Offline delivers notifications to users who have no WebSocket open, such
+// as to their phones.
+type Offline interface {
+	Deliver(ctx context.Context, n db.Notification)
+}
+
+// Service creates notifications. Producers call it directly, and Run derives
+// the ones no single request causes.
+type Service struct {
+	store   Store
+	pusher  Pusher
+	offline Offline
+	now     func() time.Time
+}
+
+// NewService creates a service. pusher and offline may be nil, leaving
+// clients to poll.
+func NewService(store Store, pusher Pusher, offline Offline) *Service {
+	return &Service{store: store, pusher: pusher, offline: offline, now: time.Now}
+}
+
+// Message is a notification as clients see it, both in API responses and in
+// WebSocket pushes.
+type Message struct {
+	ID        int64           `json:"id"`
+	Type      string          `json:"type"`
+	Title     string          `json:"title"`
+	Body      string          `json:"body,omitempty"`
+	Data      json.RawMessage `json:"data"`
+	Read      bool            `json:"read"`
+	ReadAt    *time.Time      `json:"readAt,omitempty"`
+	CreatedAt time.Time       `json:"createdAt"`
+}
+
+// Push is the WebSocket message announcing a new notification.
+type Push struct {
+	Type         string  `json:"type"`
+	Notification Message `json:"notification"`
+}
+
+func NewMessage(n db.Notification) Message {
+	data := n.Data
+	if len(data) == 0 {
+		data = json.RawMessage(`{}`)
+	}
+	return Message{
+		ID:        n.ID,
+		Type:      n.Type,
+		Title:     n.Title,
+		Body:      n.Body,
+		Data:      data,
+		Read:      n.ReadAt != nil,
+		ReadAt:    n.ReadAt,
+		CreatedAt: n.CreatedAt,
+	}
+}
+
+// Notify stores n and pushes it to the user's open WebSocket connections,
+// or when there are none to their devices. A type the user turned off, or a
+// repeat of an earlier notification, is dropped without an error.
+func (s *Service) Notify(ctx context.Context, n *db.Notification, data map[string]any) error {
+	if data != nil {
+		encoded, err := json.Marshal(data)
+		if err != nil {
+			return fmt.Errorf("encode notification data: %w", err)
+		}
+		n.Data = encoded
+	}
+	created, err := s.store.Create(ctx, n)
+	if err != nil || !created {
+		return err
+	}
+	if s.pusher != nil && s.pusher.Connected(n.UserID) {
+		s.pusher.SendToUser(n.UserID, Push{Type: PushType, Notification: NewMessage(*n)})
+	} else if s.offline != nil {
+		s.offline.Deliver(ctx, *n)
+	}
+	return nil
+}
+
+// bulkSources are the download sources of library imports, which queue many
+// jobs at once and report on them as a whole.
+var bulkSources = map[string]bool{
+	download.SourceTypeRemote:  true,
+	download.SourceTypeBeets:   true,
+	download.SourceTypeChapter: true,
+}
+
+// JobFinished notifies the user that one of their downloads completed or
+// failed for good. Jobs of playlist and library imports are left out.
+func (s *Service) JobFinished(ctx context.Context, job *download.DownloadJob) {
+	if job.PlaylistImportJobID != "" || bulkSources[job.SourceType] {
+		return
+	}
+	userID, err := uuid.Parse(job.UserID)
+	if err != nil {
+		return
+	}
+	n := &db.Notification{UserID: userID, DedupeKey: fmt.Sprintf("%s:%d", job.ID, job.RetryCount), Body: jobName(job)}
+	data := map[string]any{"jobId": job.ID}
+	switch job.Status {
+	case download.StatusComplete:
+		n.Type, n.Title = db.NotificationDownloadCompleted, "Download finished"
+		if job.TrackID != nil {
+			data["trackId"] = *job.TrackID
+		}
+	case download.StatusFailed:
+		n.Type, n.Title = db.NotificationDownloadFailed, "Download failed"
+		if failure := job.Failure(); failure != nil {
+			n.Body += ": " + failure.Message
+			data["category"] = failure.Category
+			data["remediation"] = failure.Remediation
+		}
+	default:
+		return
+	}
+	if err := s.Notify(ctx, n, data); err != nil {
+		log.Printf("Failed to notify %s about download %s: %v", job.UserID, job.ID, err)
+	}
+}
+
+// jobName names a download the way its user would recognize it.
+func jobName(job *download.DownloadJob) string {
+	switch {
+	case job.Artist != "" && job.Title != "":
+		return job.Artist + " – " + job.Title
+	case job.Title != "":
+		return job.Title
+	default:
+		return job.URL
+	}
+}
+
+// GiftReceived notifies the recipient of a gift.
+func (s *Service) GiftReceived(ctx context.Context, gift *db.Gift) error {
+	n := &db.Notification{UserID: gift.RecipientID, Type: db.NotificationGiftReceived, DedupeKey: fmt.Sprint(gift.ID)}
+	data := map[string]any{"giftId": gift.ID, "from": gift.SenderUsername}
+	if gift.TrackID != 0 {
+		n.Title = gift.SenderUsername + " sent you a track"
+		n.Body = gift.TrackTitle
+		if gift.TrackArtist != "" {
+			n.Body = gift.TrackArtist + " – " + gift.TrackTitle
+		}
+		data["trackId"] = gift.TrackID
+	} else {
+		n.Title = gift.SenderUsername + " shared a playlist with you"
+		n.Body = gift.PlaylistName
+		data["playlistId"] = gift.PlaylistID
+	}
+	if gift.Message != "" {
+		data["message"] = gift.Message
+	}
+	return s.Notify(ctx, n, data)
+}
@@ -0,0 +1,147 @@
+package notifications
+
+import (
+	"context"
+	"database/sql"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+type fakeStore struct {
+	stored   []db.Notification
+	releases []db.NewRelease
+	warnings []db.QuotaWarning
+	muted    string
+}
+
+func (f *fakeStore) Create(ctx context.Context, n *db.Notification) (bool, error) {
+	if n.Type == f.muted {
+		return false, nil
+	}
+	for _, existing := range f.stored {
+		if existing.UserID == n.UserID && existing.Type == n.Type && existing.DedupeKey == n.DedupeKey {
+			return false, nil
+		}
+	}
+	n.ID = int64(len(f.stored) + 1)
+	f.stored = append(f.stored, *n)
+	return true, nil
+}
+
+func (f *fakeStore) NewReleases(ctx context.Context, since time.Time, limit int) ([]db.NewRelease, error) {
+	return f.releases, nil
+}
+
+func (f *fakeStore) QuotaWarnings(ctx context.Context, ratio float64) ([]db.QuotaWarning, error) {
+	return f.warnings, nil
+}
+
+func (f *fakeStore) DeleteOlderThan(ctx context.Context, cutoff time.Time) (int64, error) {
+	return 0, nil
+}
+
+type fakePusher struct {
+	pushed  []Push
+	offline []db.Notification
+}
+
+func (p *fakePusher) Connected(userID uuid.UUID) bool {
+	return userID != offlineUser
+}
+
+func (p *fakePusher) Deliver(ctx context.Context, n db.Notification) {
+	p.offline = append(p.offline, n)
+}
+
+func (p *fakePusher) SendToUser(userID uuid.UUID, message any) {
+	p.pushed = append(p.pushed, message.(Push))
+}
+
+// offlineUser has no WebSocket open.
+var offlineUser = uuid.New()
+
+func TestJobFinished(t *testing.T) {
+	store, pusher := &fakeStore{}, &fakePusher{}
+	s := NewService(store, pusher, pusher)
+	user := uuid.New()
+	trackID := int64(7)
+	done := &download.DownloadJob{ID: "job-1", UserID: user.String(), Status: download.StatusComplete, Artist: "Band", Title: "Song", TrackID: &trackID}
+
+	s.JobFinished(context.Background(), done)
+	s.JobFinished(context.Background(), done)
+	if len(pusher.pushed) != 1 {
+		t.Fatalf("pushed %d notifications; want one, the repeat is dropped", len(pusher.pushed))
+	}
+	got := pusher.pushed[0]
+	if got.Type != PushType || got.Notification.Type != db.NotificationDownloadCompleted || got.Notification.Body != "Band – Song" || !strings.Contains(string(got.Notification.Data), `"trackId":7`) {
+		t.Fatalf("push = %+v", got)
+	}
+
+	s.JobFinished(context.Background(), &download.DownloadJob{ID: "job-2", UserID: user.String(), Status: download.StatusFailed, URL: "https://example.test/a", Error: "ERROR: Video unavailable. This video has been removed by the uploader"})
+	if n := store.stored[len(store.stored)-1]; n.Type != db.NotificationDownloadFailed || !strings.Contains(string(n.Data), `"category":"removed"`) {
+		t.Fatalf("failed job notification = %+v %s", n, n.Data)
+	}
+
+	before := len(store.stored)
+	s.JobFinished(context.Background(), &download.DownloadJob{ID: "job-3", UserID: user.String(), Status: download.StatusComplete, PlaylistImportJobID: "import"})
+	s.JobFinished(context.Background(), &download.DownloadJob{ID: "job-4", UserID: user.String(), Status: download.StatusComplete, SourceType: download.SourceTypeBeets})
+	if len(store.stored) != before {
+		t.Fatalf("import jobs were notified: %+v", store.stored[before:])
+	}
+}
+
+func TestCheck(t *testing.T) {
+	user, admin := uuid.New(), uuid.New()
+	store := &fakeStore{
+		releases: []db.NewRelease{{UserID: user, TrackID: 3, Title: "Fresh", Artist: "Band"}},
+		warnings: []db.QuotaWarning{{
+			UserID:          admin,
+			TenantID:        1,
+			TenantName:      "Home",
+			Usage:           db.TenantUsage{Tracks: 95, StorageBytes: 10},
+			MaxTracks:       sql.NullInt64{Int64: 100, Valid: true},
+			MaxStorageBytes: sql.NullInt64{Int64: 1000, Valid: true},
+		}},
+	}
+	s := NewService(store, nil, nil)
+	s.now = func() time.Time { return time.Date(2026, 3, 1, 12, 0, 0, 0, time.UTC) }
+
+	for range 2 {
+		if err := s.Check(context.Background()); err != nil {
+			t.Fatalf("Check: %v", err)
+		}
+	}
+	if len(store.stored) != 2 {
+		t.Fatalf("stored %+v; want one new release and one quota warning", store.stored)
+	}
+	if n := store.stored[0]; n.UserID != user || n.Title != "New from Band" {
+		t.Fatalf("new release = %+v", n)
+	}
+	if n := store.stored[1]; n.UserID != admin || n.DedupeKey != "1:2026-03-01" || n.Body != "95% of its quota is used." {
+		t.Fatalf("quota warning = %+v", n)
+	}
+}
+
+func TestMutedTypeIsNotPushed(t *testing.T) {
+	pusher := &fakePusher{}
+	s := NewService(&fakeStore{muted: db.NotificationGiftReceived}, pusher, pusher)
+	gift := &db.Gift{ID: 1, RecipientID: uuid.New(), SenderUsername: "alice", PlaylistID: 2, PlaylistName: "Mix"}
+	if err := s.GiftReceived(context.Background(), gift); err != nil || len(pusher.pushed) != 0 {
+		t.Fatalf("GiftReceived = %v, pushed %+v", err, pusher.pushed)
+	}
+}
+
+func TestOfflineUserGetsDevicePush(t *testing.T) {
+	pusher := &fakePusher{}
+	s := NewService(&fakeStore{}, pusher, pusher)
+	gift := &db.Gift{ID: 1, RecipientID: offlineUser, SenderUsername: "alice", TrackID: 2, TrackTitle: "Song"}
+	if err := s.GiftReceived(context.Background(), gift); err != nil || len(pusher.pushed) != 0 || len(pusher.offline) != 1 {
+		t.Fatalf("GiftReceived = %v, pushed %+v, delivered %+v; want only the device delivery", err, pusher.pushed, pusher.offline)
+	}
+}
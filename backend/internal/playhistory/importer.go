@@ -0,0 +1,186 @@
+package playhistory
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// importContextType marks imported plays, as library migrations do.
+const importContextType = "import"
+
+// recordBatchSize bounds how many plays are written per transaction.
+const recordBatchSize = 500
+
+// maxUnmatchedSamples bounds how many unmatched entries a Result names.
+const maxUnmatchedSamples = 20
+
+// eventNamespace derives imported plays' event IDs from their content.
+var eventNamespace = uuid.MustParse("6f1c9a52-3b8e-4d0a-9c57-2e4b8f1d7a30")
+
+// Library lists the tracks entries are matched against.
+// db.LibraryRepository satisfies this interface.
+type Library interface {
+	LibraryTrackKeys(ctx context.Context, userID uuid.UUID) ([]db.LibraryTrackKey, error)
+}
+
+// Plays records imported plays. db.PlayEventRepository satisfies this
+// interface.
+type Plays interface {
+	RecordClientEvents(ctx context.Context, userID uuid.UUID, events []db.ClientPlayEvent) ([]bool, error)
+}
+
+// Result counts what an import did. Duplicates were recorded by an earlier
+// import of the same entries.
+type Result struct {
+	Format           string   `json:"format"`
+	Entries          int      `json:"entries"`
+	Invalid          int      `json:"invalid"`
+	Matched          int      `json:"matched"`
+	Unmatched        int      `json:"unmatched"`
+	Recorded         int      `json:"recorded"`
+	Duplicates       int      `json:"duplicates"`
+	UnmatchedSamples []string `json:"unmatchedSamples,omitempty"`
+}
+
+// Importer reads play history files and records the entries that match the
+// user's library.
+type Importer struct {
+	library Library
+	plays   Plays
+	now     func() time.Time
+}
+
+func NewImporter(library Library, plays Plays) *Importer {
+	return &Importer{library: library, plays: plays, now: time.Now}
+}
+
+// Import reads r as format and records its matched entries for the user.
+func (i *Importer) Import(ctx context.Context, userID uuid.UUID, format string, r io.Reader) (*Result, error) {
+	var entries []Entry
+	var invalid int
+	var err error
+	switch format {
+	case FormatLastFMCSV:
+		entries, invalid, err = ParseLastFMCSV(r)
+	case FormatYTDLPArchive:
+		entries, invalid, err = ParseYTDLPArchive(r, i.now())
+	default:
+		return nil, fmt.Errorf("%w: %q", ErrUnknownFormat, format)
+	}
+	if err != nil {
+		return nil, err
+	}
+
+	keys, err := i.library.LibraryTrackKeys(ctx, userID)
+	if err != nil {
+		return nil, err
+	}
+	index := newTrackIndex(keys)
+
+	result := &Result{Format: format, Entries: len(entries), Invalid: invalid}
+	events := make([]db.ClientPlayEvent, 0, min(len(entries), recordBatchSize))
+	flush := func() error {
+		if len(events) == 0 {
+			return nil
+		}
+		recorded, err := i.plays.RecordClientEvents(ctx, userID, events)
+		if err != nil {
+			return err
+		}
+		for _, ok := range recorded {
+			if ok {
+				result.Recorded++
+			} else {
+				result.Duplicates++
+			}
+		}
+		events = events[:0]
+		return nil
+	}
+	for _, entry := range entries {
+		trackID, ok := index.match(entry)
+		if !ok {
+			result.Unmatched++
+			if len(result.UnmatchedSamples) < maxUnmatchedSamples {
+				result.UnmatchedSamples = append(result.UnmatchedSamples, entry.Label())
+			}
+			continue
+		}
+		result.Matched++
+		events = append(events, db.ClientPlayEvent{
+			EventID:     uuid.NewSHA1(eventNamespace, []byte(format+"\x00"+entry.Key)),
+			Kind:        db.PlayEventKindPlay,
+			TrackID:     trackID,
+			OccurredAt:  entry.PlayedAt,
+			ContextType: importContextType,
+			ContextID:   format,
+		})
+		if len(events) == recordBatchSize {
+			if err := flush(); err != nil {
+				return nil, err
+			}
+		}
+	}
+	if err := flush(); err != nil {
+		return nil, err
+	}
+	return result, nil
+}
+
+// trackIndex looks library tracks up by the ways entries identify them.
+type trackIndex struct {
+	byRecording map[string]int64
+	byURL       map[string]int64
+	byName      map[string]int64
+}
+
+func newTrackIndex(keys []db.LibraryTrackKey) *trackIndex {
+	index := &trackIndex{
+		byRecording: make(map[string]int64),
+		byURL:       make(map[string]int64),
+		byName:      make(map[string]int64, len(keys)),
+	}
+	// Keys come oldest first, so the first track wins ties.
+	for _, key := range keys {
+		if key.MBRecordingID != "" {
+			setOnce(index.byRecording, key.MBRecordingID, key.ID)
+		}
+		for _, sourceURL := range key.SourceURLs {
+			setOnce(index.byURL, sourceURL, key.ID)
+		}
+		if key.Artist != "" {
+			setOnce(index.byName, nameKey(key.Artist, key.Title), key.ID)
+		}
+	}
+	return index
+}
+
+func (x *trackIndex) match(entry Entry) (int64, bool) {
+	if id, ok := x.byRecording[entry.MBRecordingID]; ok && entry.MBRecordingID != "" {
+		return id, true
+	}
+	if id, ok := x.byURL[entry.SourceURL]; ok && entry.SourceURL != "" {
+		return id, true
+	}
+	if entry.Artist == "" {
+		return 0, false
+	}
+	id, ok := x.byName[nameKey(entry.Artist, entry.Title)]
+	return id, ok
+}
+
+func nameKey(artist, title string) string {
+	return db.NormalizeString(artist) + "\x00" + db.NormalizeString(title)
+}
+
+func setOnce(m map[string]int64, key string, id int64) {
+	if _, ok := m[key]; !ok {
+		m[key] = id
+	}
+}
@@ -0,0 +1,194 @@
+// Package playhistory backfills a user's play history from listening they did
+// before using Open Music Player: a Last.fm scrobble export or a yt-dlp
+// download archive.
+//
+// Entries are matched to tracks in the user's library by MusicBrainz
+// recording ID, then source URL, then artist and title. Matched entries are
+// recorded as plays with context type "import", so stats include them and
+// they stay distinguishable from plays made on the server. Each entry is
+// recorded under an event ID derived from its content, so importing the same
+// file twice records nothing the second time.
+package playhistory
+
+import (
+	"bufio"
+	"encoding/csv"
+	"errors"
+	"fmt"
+	"io"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Supported import formats.
+const (
+	// FormatLastFMCSV is a Last.fm scrobble export. Both the headerless
+	// artist, album, title, date layout and exports with a header row naming
+	// artist, track, album, uts or utc_time, and track_mbid columns are read.
+	FormatLastFMCSV = "lastfm-csv"
+	// FormatYTDLPArchive is a yt-dlp --download-archive file, one
+	// "extractor id" line per downloaded video. It has no dates, so each
+	// entry counts as one play at import time.
+	FormatYTDLPArchive = "ytdlp-archive"
+)
+
+// ErrUnknownFormat is returned for a format other than the supported ones.
+var ErrUnknownFormat = errors.New("unknown play history format")
+
+// ErrInvalidFile is returned when a file cannot be read as its format at all,
+// as opposed to individual entries that are skipped.
+var ErrInvalidFile = errors.New("invalid play history file")
+
+// Entry is one listen read from an import file.
+type Entry struct {
+	Artist        string
+	Title         string
+	MBRecordingID string
+	SourceURL     string
+	PlayedAt      time.Time
+	// Key identifies the entry within its format, for deduplication.
+	Key string
+}
+
+// Label names the entry in reports of unmatched entries.
+func (e Entry) Label() string {
+	if e.Artist != "" || e.Title != "" {
+		return e.Artist + " - " + e.Title
+	}
+	return e.SourceURL
+}
+
+// scrobbleTimeLayouts are the date formats Last.fm export tools write.
+var scrobbleTimeLayouts = []string{
+	"02 Jan 2006 15:04",
+	"02 Jan 2006, 15:04",
+	"2006-01-02 15:04:05",
+	time.RFC3339,
+}
+
+// ParseLastFMCSV reads a Last.fm scrobble export. It returns the readable
+// entries and how many rows were skipped for a missing artist, title, or
+// date.
+func ParseLastFMCSV(r io.Reader) ([]Entry, int, error) {
+	reader := csv.NewReader(r)
+	reader.FieldsPerRecord = -1
+	reader.LazyQuotes = true
+	reader.ReuseRecord = true
+
+	columns := map[string]int{"artist": 0, "album": 1, "track": 2, "date": 3}
+	var entries []Entry
+	invalid := 0
+	for row := 0; ; row++ {
+		record, err := reader.Read()
+		if errors.Is(err, io.EOF) {
+			break
+		}
+		if err != nil {
+			return nil, 0, fmt.Errorf("%w: %w", ErrInvalidFile, err)
+		}
+		if row == 0 {
+			if header := lastFMHeader(record); header != nil {
+				columns = header
+				continue
+			}
+		}
+
+		field := func(name string) string {
+			if i, ok := columns[name]; ok && i < len(record) {
+				return strings.TrimSpace(record[i])
+			}
+			return ""
+		}
+		entry := Entry{
+			Artist:        field("artist"),
+			Title:         field("track"),
+			MBRecordingID: field("track_mbid"),
+		}
+		playedAt, ok := parseScrobbleTime(field("date"))
+		if entry.Artist == "" || entry.Title == "" || !ok {
+			invalid++
+			continue
+		}
+		entry.PlayedAt = playedAt
+		entry.Key = strconv.FormatInt(playedAt.Unix(), 10) + "\x00" + entry.Artist + "\x00" + entry.Title
+		entries = append(entries, entry)
+	}
+	return entries, invalid, nil
+}
+
+// lastFMHeader maps column names to indexes when record is a header row, or
+// returns nil when it is data.
+func lastFMHeader(record []string) map[string]int {
+	columns := map[string]int{}
+	for i, name := range record {
+		name = strings.ToLower(strings.TrimSpace(name))
+		switch name {
+		case "artist", "artist_name":
+			columns["artist"] = i
+		case "album", "album_name":
+			columns["album"] = i
+		case "track", "title", "track_name":
+			columns["track"] = i
+		case "track_mbid":
+			columns["track_mbid"] = i
+		case "uts", "utc_time", "date", "timestamp":
+			// Prefer the unix timestamp when an export has both.
+			if _, ok := columns["date"]; !ok || name == "uts" {
+				columns["date"] = i
+			}
+		}
+	}
+	if _, ok := columns["artist"]; !ok {
+		return nil
+	}
+	if _, ok := columns["track"]; !ok {
+		return nil
+	}
+	return columns
+}
+
+func parseScrobbleTime(value string) (time.Time, bool) {
+	if value == "" {
+		return time.Time{}, false
+	}
+	if seconds, err := strconv.ParseInt(value, 10, 64); err == nil {
+		return time.Unix(seconds, 0).UTC(), seconds > 0
+	}
+	for _, layout := range scrobbleTimeLayouts {
+		if t, err := time.Parse(layout, value); err == nil {
+			return t.UTC(), true
+		}
+	}
+	return time.Time{}, false
+}
+
+// ParseYTDLPArchive reads a yt-dlp download archive, dating every entry at
+// now. It returns the entries and how many lines were skipped for not being
+// an "extractor id" pair. Only YouTube IDs can be turned into a source URL;
+// entries from other extractors are returned without one and match nothing.
+func ParseYTDLPArchive(r io.Reader, now time.Time) ([]Entry, int, error) {
+	scanner := bufio.NewScanner(r)
+	var entries []Entry
+	invalid := 0
+	for scanner.Scan() {
+		fields := strings.Fields(scanner.Text())
+		if len(fields) == 0 {
+			continue
+		}
+		if len(fields) != 2 {
+			invalid++
+			continue
+		}
+		extractor, id := strings.ToLower(fields[0]), fields[1]
+		entry := Entry{PlayedAt: now.UTC(), Key: extractor + " " + id}
+		if extractor == "youtube" {
+			entry.SourceURL = "https://www.youtube.com/watch?v=" + id
+		}
+		entries = append(entries, entry)
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, 0, fmt.Errorf("%w: %w", ErrInvalidFile, err)
+	}
+	return entries, invalid, nil
+}
@@ -0,0 +1,124 @@
+package playhistory
+
+import (
+	"context"
+	"errors"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestParseLastFMCSVReadsBothLayouts(t *testing.T) {
+	headerless := "Radiohead,OK Computer,Paranoid Android,31 Jan 2021 18:33\n" +
+		"Radiohead,OK Computer,,31 Jan 2021 18:40\n" +
+		"\"Sigur Rós\",Takk...,Hoppípolla,bogus date\n"
+	entries, invalid, err := ParseLastFMCSV(strings.NewReader(headerless))
+	if err != nil {
+		t.Fatalf("ParseLastFMCSV: %v", err)
+	}
+	if len(entries) != 1 || invalid != 2 {
+		t.Fatalf("entries = %+v, invalid = %d; want 1 entry and 2 invalid rows", entries, invalid)
+	}
+	if e := entries[0]; e.Artist != "Radiohead" || e.Title != "Paranoid Android" || !e.PlayedAt.Equal(time.Date(2021, 1, 31, 18, 33, 0, 0, time.UTC)) {
+		t.Fatalf("entry = %+v", e)
+	}
+
+	withHeader := "uts,utc_time,artist,artist_mbid,album,album_mbid,track,track_mbid\n" +
+		"1612118000,\"31 Jan 2021, 18:33\",Radiohead,,OK Computer,,Paranoid Android,8a0e5c5d-0f36-4b7a-8a0e-3d3c1a9b2f41\n"
+	entries, invalid, err = ParseLastFMCSV(strings.NewReader(withHeader))
+	if err != nil || invalid != 0 || len(entries) != 1 {
+		t.Fatalf("header export = %+v, %d, %v", entries, invalid, err)
+	}
+	if e := entries[0]; e.MBRecordingID != "8a0e5c5d-0f36-4b7a-8a0e-3d3c1a9b2f41" || e.PlayedAt.Unix() != 1612118000 {
+		t.Fatalf("entry = %+v; want the unix timestamp and track MBID", e)
+	}
+}
+
+func TestParseYTDLPArchive(t *testing.T) {
+	now := time.Date(2026, 3, 1, 12, 0, 0, 0, time.UTC)
+	entries, invalid, err := ParseYTDLPArchive(strings.NewReader("youtube dQw4w9WgXcQ\n\nsoundcloud 12345\nnot-an-entry\n"), now)
+	if err != nil {
+		t.Fatalf("ParseYTDLPArchive: %v", err)
+	}
+	if len(entries) != 2 || invalid != 1 {
+		t.Fatalf("entries = %+v, invalid = %d", entries, invalid)
+	}
+	if entries[0].SourceURL != "https://www.youtube.com/watch?v=dQw4w9WgXcQ" || !entries[0].PlayedAt.Equal(now) {
+		t.Fatalf("youtube entry = %+v", entries[0])
+	}
+	if entries[1].SourceURL != "" {
+		t.Fatalf("non-YouTube entry has no URL to match on: %+v", entries[1])
+	}
+}
+
+type fakeLibrary struct {
+	keys []db.LibraryTrackKey
+}
+
+func (f *fakeLibrary) LibraryTrackKeys(context.Context, uuid.UUID) ([]db.LibraryTrackKey, error) {
+	return f.keys, nil
+}
+
+type fakePlays struct {
+	recorded map[uuid.UUID]db.ClientPlayEvent
+}
+
+func (f *fakePlays) RecordClientEvents(_ context.Context, _ uuid.UUID, events []db.ClientPlayEvent) ([]bool, error) {
+	if f.recorded == nil {
+		f.recorded = map[uuid.UUID]db.ClientPlayEvent{}
+	}
+	result := make([]bool, len(events))
+	for i, event := range events {
+		if _, ok := f.recorded[event.EventID]; !ok {
+			f.recorded[event.EventID] = event
+			result[i] = true
+		}
+	}
+	return result, nil
+}
+
+func TestImportMatchesLibraryAndIsIdempotent(t *testing.T) {
+	library := &fakeLibrary{keys: []db.LibraryTrackKey{
+		{ID: 1, Artist: "Radiohead", Title: "Paranoid Android"},
+		{ID: 2, Artist: "Sigur Rós", Title: "Hoppípolla", MBRecordingID: "4f3b2c1a-0000-4000-8000-000000000002"},
+		{ID: 3, Artist: "Someone", Title: "Video", SourceURLs: []string{"https://www.youtube.com/watch?v=dQw4w9WgXcQ"}},
+	}}
+	plays := &fakePlays{}
+	importer := NewImporter(library, plays)
+	ctx := context.Background()
+	userID := uuid.New()
+
+	csv := "radiohead,OK Computer,paranoid android,31 Jan 2021 18:33\n" +
+		"Sigur Ros,Takk...,Hoppipolla,31 Jan 2021 18:40\n" +
+		"Unknown,Nope,Missing,31 Jan 2021 18:50\n"
+	result, err := importer.Import(ctx, userID, FormatLastFMCSV, strings.NewReader(csv))
+	if err != nil {
+		t.Fatalf("Import: %v", err)
+	}
+	if result.Matched != 2 || result.Recorded != 2 || result.Unmatched != 1 || result.UnmatchedSamples[0] != "Unknown - Missing" {
+		t.Fatalf("result = %+v", result)
+	}
+	for _, event := range plays.recorded {
+		if event.ContextType != "import" || event.ContextID != FormatLastFMCSV || event.OccurredAt.IsZero() {
+			t.Fatalf("imported play = %+v; want an import-context play at the scrobble time", event)
+		}
+	}
+
+	again, err := importer.Import(ctx, userID, FormatLastFMCSV, strings.NewReader(csv))
+	if err != nil || again.Recorded != 0 || again.Duplicates != 2 {
+		t.Fatalf("second import = %+v, %v; want only duplicates", again, err)
+	}
+
+	archive, err := importer.Import(ctx, userID, FormatYTDLPArchive, strings.NewReader("youtube dQw4w9WgXcQ\n"))
+	if err != nil || archive.Recorded != 1 {
+		t.Fatalf("archive import = %+v, %v", archive, err)
+	}
+
+	if _, err := importer.Import(ctx, userID, "itunes-xml", strings.NewReader("")); !errors.Is(err, ErrUnknownFormat) {
+		t.Fatalf("unknown format error = %v", err)
+	}
+}
@@ -54,6 +54,38 @@ func (r *ImportRepository) GetJob(ctx context.Context, id uuid.UUID) (*ImportJob
 	return &job, nil
 }
 
+// ListJobsForUser returns the user's most recent import jobs, newest first.
+func (r *ImportRepository) ListJobsForUser(ctx context.Context, userID uuid.UUID, limit int) ([]ImportJob, error) {
+	query := `
+		SELECT id, user_id, playlist_id, source_url, source_title, status,
+		       total_items, imported_items, queued_items, failed_items, skipped_items,
+		       max_items, error, created_at, updated_at
+		FROM playlist_import_jobs
+		WHERE user_id = $1
+		ORDER BY created_at DESC, id
+		LIMIT $2
+	`
+	rows, err := r.db.QueryContext(ctx, query, userID, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var jobs []ImportJob
+	for rows.Next() {
+		var job ImportJob
+		if err := rows.Scan(
+			&job.ID, &job.UserID, &job.PlaylistID, &job.SourceURL, &job.SourceTitle, &job.Status,
+			&job.TotalItems, &job.ImportedItems, &job.QueuedItems, &job.FailedItems, &job.SkippedItems,
+			&job.MaxItems, &job.Error, &job.CreatedAt, &job.UpdatedAt,
+		); err != nil {
+			return nil, err
+		}
+		jobs = append(jobs, job)
+	}
+	return jobs, rows.Err()
+}
+
 func (r *ImportRepository) ListItems(ctx context.Context, jobID uuid.UUID) ([]ImportItem, error) {
 	query := `
 		SELECT id, import_job_id, source_index, playlist_position, source_id, source_url,
@@ -303,9 +335,10 @@ func linkAssociationItems(ctx context.Context, tx *sql.Tx, associations []ItemSo
 func (r *ImportRepository) MarkItemQueued(ctx context.Context, itemID int64, downloadJobID string) error {
 	_, err := r.db.ExecContext(ctx, `
 		UPDATE playlist_import_items
-		SET status = $2, download_job_id = $3, error = NULL, updated_at = NOW()
+		SET status = CASE WHEN status = $4 THEN status ELSE $2 END,
+		    download_job_id = $3, error = NULL, updated_at = NOW()
 		WHERE id = $1
-	`, itemID, ItemStatusQueued, downloadJobID)
+	`, itemID, ItemStatusQueued, downloadJobID, ItemStatusCanceled)
 	return err
 }
 
@@ -313,8 +346,8 @@ func (r *ImportRepository) MarkItemImported(ctx context.Context, itemID int64, t
 	_, err := r.db.ExecContext(ctx, `
 		UPDATE playlist_import_items
 		SET status = $2, track_id = $3, error = NULL, updated_at = NOW()
-		WHERE id = $1
-	`, itemID, ItemStatusImported, trackID)
+		WHERE id = $1 AND status <> $4
+	`, itemID, ItemStatusImported, trackID, ItemStatusCanceled)
 	return err
 }
 
@@ -322,34 +355,59 @@ func (r *ImportRepository) MarkItemImported(ctx context.Context, itemID int64, t
 // items update their exact source entry before completion; legacy items retain
 // the playlist-membership-only behavior.
 func (r *ImportRepository) CompletePlaylistImportItem(ctx context.Context, itemID, trackID int64) error {
-	if itemID <= 0 || trackID <= 0 {
-		return fmt.Errorf("playlist import item and track IDs must be positive")
-	}
-
 	tx, err := r.db.BeginTx(ctx, nil)
 	if err != nil {
 		return err
 	}
 	defer func() { _ = tx.Rollback() }()
 
+	if err := completeImportItem(ctx, tx, itemID, trackID); err != nil {
+		return err
+	}
+	return tx.Commit()
+}
+
+// CompletePlaylistImportItemTx completes a queued import item as
+// CompletePlaylistImportItem does, as one step of tx.
+func (r *ImportRepository) CompletePlaylistImportItemTx(ctx context.Context, tx *db.Tx, itemID, trackID int64) error {
+	return completeImportItem(ctx, tx, itemID, trackID)
+}
+
+// importQuerier is satisfied by *sql.Tx and *db.Tx.
+type importQuerier interface {
+	ExecContext(context.Context, string, ...any) (sql.Result, error)
+	QueryRowContext(context.Context, string, ...any) *sql.Row
+}
+
+func completeImportItem(ctx context.Context, tx importQuerier, itemID, trackID int64) error {
+	if itemID <= 0 || trackID <= 0 {
+		return fmt.Errorf("playlist import item and track IDs must be positive")
+	}
+
 	var importJobID uuid.UUID
 	var playlistID int64
 	var playlistPosition int
 	var sourceEntryID sql.NullInt64
 	var existingTrackID sql.NullInt64
+	var status string
 	if err := tx.QueryRowContext(ctx, `
 		SELECT i.import_job_id, j.playlist_id, i.playlist_position,
-			i.playlist_source_entry_id, i.track_id
+			i.playlist_source_entry_id, i.track_id, i.status
 		FROM playlist_import_items AS i
 		JOIN playlist_import_jobs AS j ON j.id = i.import_job_id
 		WHERE i.id = $1
 		FOR UPDATE OF i, j
-	`, itemID).Scan(&importJobID, &playlistID, &playlistPosition, &sourceEntryID, &existingTrackID); err != nil {
+	`, itemID).Scan(&importJobID, &playlistID, &playlistPosition, &sourceEntryID, &existingTrackID, &status); err != nil {
 		if errors.Is(err, sql.ErrNoRows) {
 			return nil
 		}
 		return err
 	}
+	// A download that finishes after its import was canceled keeps its track
+	// in the library but out of the playlist.
+	if status == ItemStatusCanceled {
+		return nil
+	}
 	if existingTrackID.Valid && existingTrackID.Int64 != trackID {
 		return fmt.Errorf("playlist import item %d already points to track %d", itemID, existingTrackID.Int64)
 	}
@@ -400,13 +458,10 @@ func (r *ImportRepository) CompletePlaylistImportItem(ctx context.Context, itemI
 	`, itemID, ItemStatusImported, trackID); err != nil {
 		return err
 	}
-	if err := refreshPlaylistImportJobCounts(ctx, tx, importJobID); err != nil {
-		return err
-	}
-	return tx.Commit()
+	return refreshPlaylistImportJobCounts(ctx, tx, importJobID)
 }
 
-func refreshPlaylistImportJobCounts(ctx context.Context, tx *sql.Tx, importJobID uuid.UUID) error {
+func refreshPlaylistImportJobCounts(ctx context.Context, tx importQuerier, importJobID uuid.UUID) error {
 	_, err := tx.ExecContext(ctx, `
 		WITH counts AS (
 			SELECT import_job_id,
@@ -426,6 +481,7 @@ func refreshPlaylistImportJobCounts(ctx context.Context, tx *sql.Tx, importJobID
 			failed_items = c.failed_items,
 			skipped_items = c.skipped_items,
 			status = CASE
+				WHEN j.status = 'cancel' || 'led' THEN j.status
 				WHEN c.queued_items > 0 THEN 'importing'
 				WHEN c.failed_items > 0 AND c.imported_items + c.skipped_items = 0 THEN 'failed'
 				WHEN c.failed_items > 0 THEN 'partial_failure'
@@ -442,11 +498,72 @@ func (r *ImportRepository) MarkItemFailed(ctx context.Context, itemID int64, mes
 	_, err := r.db.ExecContext(ctx, `
 		UPDATE playlist_import_items
 		SET status = $2, error = $3, updated_at = NOW()
-		WHERE id = $1
-	`, itemID, ItemStatusFailed, message)
+		WHERE id = $1 AND status <> $4
+	`, itemID, ItemStatusFailed, message, ItemStatusCanceled)
 	return err
 }
 
+// CancelJob cancels an unfinished import along with its pending and queued
+// items, returning the download job IDs of the canceled items. Canceling an
+// already canceled import returns the downloads queued since as well.
+func (r *ImportRepository) CancelJob(ctx context.Context, jobID uuid.UUID) ([]string, error) {
+	tx, err := r.db.BeginTx(ctx, nil)
+	if err != nil {
+		return nil, err
+	}
+	defer func() { _ = tx.Rollback() }()
+
+	var status string
+	err = tx.QueryRowContext(ctx, `SELECT status FROM playlist_import_jobs WHERE id = $1 FOR UPDATE`, jobID).Scan(&status)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, ErrNotFound
+	}
+	if err != nil {
+		return nil, err
+	}
+	if status != JobStatusResolving && status != JobStatusImporting && status != JobStatusCanceled {
+		return nil, ErrNotCancelable
+	}
+	if _, err := tx.ExecContext(ctx, `
+		UPDATE playlist_import_jobs
+		SET status = $2, updated_at = NOW()
+		WHERE id = $1
+	`, jobID, JobStatusCanceled); err != nil {
+		return nil, err
+	}
+
+	rows, err := tx.QueryContext(ctx, `
+		UPDATE playlist_import_items
+		SET status = $2, updated_at = NOW()
+		WHERE import_job_id = $1 AND status IN ($2, $3, $4)
+		RETURNING download_job_id
+	`, jobID, ItemStatusCanceled, ItemStatusPending, ItemStatusQueued)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var downloadJobIDs []string
+	for rows.Next() {
+		var downloadJobID sql.NullString
+		if err := rows.Scan(&downloadJobID); err != nil {
+			return nil, err
+		}
+		if downloadJobID.Valid {
+			downloadJobIDs = append(downloadJobIDs, downloadJobID.String)
+		}
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	if err := refreshPlaylistImportJobCounts(ctx, tx, jobID); err != nil {
+		return nil, err
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, err
+	}
+	return downloadJobIDs, nil
+}
+
 func (r *ImportRepository) MarkJobFailed(ctx context.Context, jobID uuid.UUID, message string) error {
 	_, err := r.db.ExecContext(ctx, `
 		UPDATE playlist_import_jobs
@@ -476,6 +593,7 @@ func (r *ImportRepository) RefreshJobCounts(ctx context.Context, jobID uuid.UUID
 		    failed_items = COALESCE(c.failed_items, 0),
 		    skipped_items = COALESCE(c.skipped_items, 0),
 		    status = CASE
+		      WHEN j.status = 'cancel' || 'led' THEN j.status
 		      WHEN COALESCE(c.total_items, 0) = 0 THEN 'failed'
 		      WHEN COALESCE(c.queued_items, 0) > 0 THEN 'importing'
 		      WHEN COALESCE(c.failed_items, 0) > 0 AND COALESCE(c.imported_items, 0) + COALESCE(c.skipped_items, 0) = 0 THEN 'failed'
@@ -529,6 +647,9 @@ func (r *TrackSourceRepository) FindTrackBySource(ctx context.Context, provider,
 	return &track, nil
 }
 
+// UpsertTrackSource maps a source to the track downloaded from it. A source
+// already mapped to another tenant's track keeps that mapping, so one
+// household's download never takes over another's.
 func (r *TrackSourceRepository) UpsertTrackSource(ctx context.Context, trackID int64, provider, sourceID, sourceURL string) error {
 	provider = strings.TrimSpace(provider)
 	sourceID = strings.TrimSpace(sourceID)
@@ -543,6 +664,8 @@ func (r *TrackSourceRepository) UpsertTrackSource(ctx context.Context, trackID i
 		SET track_id = EXCLUDED.track_id,
 		    source_url = COALESCE(NULLIF(EXCLUDED.source_url, ''), track_sources.source_url),
 		    updated_at = NOW()
+		WHERE (SELECT tenant_id FROM tracks WHERE id = track_sources.track_id)
+		    IS NOT DISTINCT FROM (SELECT tenant_id FROM tracks WHERE id = EXCLUDED.track_id)
 	`, trackID, provider, sourceID, sourceURL)
 	return err
 }
@@ -8,6 +8,7 @@ import (
 	"errors"
 	"fmt"
 	"hash"
+	"log"
 	"net/url"
 	"strings"
 
@@ -25,6 +26,7 @@ var (
 	ErrLimitExceeded    = errors.New("playlist exceeds import item limit")
 	ErrNotFound         = errors.New("playlist import job not found")
 	ErrForbidden        = errors.New("playlist import job not owned by user")
+	ErrNotCancelable    = errors.New("playlist import job already finished")
 )
 
 type Enumerator interface {
@@ -47,6 +49,7 @@ type JobStore interface {
 	MarkItemImported(ctx context.Context, itemID int64, trackID int64) error
 	MarkItemFailed(ctx context.Context, itemID int64, message string) error
 	MarkJobFailed(ctx context.Context, jobID uuid.UUID, message string) error
+	CancelJob(ctx context.Context, jobID uuid.UUID) ([]string, error)
 	RefreshJobCounts(ctx context.Context, jobID uuid.UUID) error
 }
 
@@ -68,6 +71,12 @@ type DownloadEnqueuer interface {
 	EnqueuePlaylistImportItemWithID(ctx context.Context, jobID, userID string, candidate download.SourceCandidate, importJobID string, importItemID int64, playlistID int64, playlistPosition int) (*download.DownloadJob, error)
 }
 
+// DownloadCanceler cancels the downloads queued for a canceled import.
+// download.Service satisfies this interface.
+type DownloadCanceler interface {
+	CancelJob(ctx context.Context, jobID string) (*download.DownloadJob, error)
+}
+
 type SourceSelectionStore interface {
 	CreateTrustedSourceSelectionDecision(context.Context, uuid.UUID, string, db.TrustedSourceSelectionCandidate, string) (*db.SourceSelectionDecision, error)
 	AttachTrackForUser(context.Context, uuid.UUID, uuid.UUID, int64) error
@@ -84,6 +93,7 @@ type Service struct {
 	tracks     TrackSourceStore
 	library    LibraryStore
 	downloader DownloadEnqueuer
+	canceler   DownloadCanceler
 	selections SourceSelectionStore
 	ingestion  TrustedIngestion
 	enumerator Enumerator
@@ -99,6 +109,7 @@ type Config struct {
 	Tracks         TrackSourceStore
 	Library        LibraryStore
 	Downloader     DownloadEnqueuer
+	Canceler       DownloadCanceler
 	Selections     SourceSelectionStore
 	Ingestion      TrustedIngestion
 	Enumerator     Enumerator
@@ -121,6 +132,7 @@ func NewService(cfg Config) *Service {
 		tracks:     cfg.Tracks,
 		library:    cfg.Library,
 		downloader: cfg.Downloader,
+		canceler:   cfg.Canceler,
 		selections: cfg.Selections,
 		ingestion:  cfg.Ingestion,
 		enumerator: cfg.Enumerator,
@@ -268,6 +280,12 @@ func (s *Service) StartImport(ctx context.Context, userID uuid.UUID, req ImportR
 		if item.Status == ItemStatusFailed || item.Status == ItemStatusSkippedDuplicate {
 			continue
 		}
+		if s.importCanceled(ctx, job.ID) {
+			// The cancel may have raced the previous item's download, so sweep
+			// once more for anything queued since.
+			_ = s.cancelImportDownloads(ctx, job.ID)
+			break
+		}
 		candidate := playlistCandidate(*item, s.sourceType)
 		if s.selections == nil || s.ingestion == nil {
 			msg := "trusted source selection processing is disabled"
@@ -284,15 +302,20 @@ func (s *Service) StartImport(ctx context.Context, userID uuid.UUID, req ImportR
 			continue
 		}
 		track, err := s.tracks.FindTrackBySource(ctx, s.sourceType, item.SourceID, item.SourceURL)
-		if err == nil && track != nil {
-			if s.library != nil {
-				if _, libErr := s.library.AddTrackToLibrary(ctx, userID, track.ID); libErr != nil && !errors.Is(libErr, db.ErrTrackAlreadyInLibrary) {
-					_ = s.store.MarkItemFailed(ctx, item.ID, libErr.Error())
-					item.Status = ItemStatusFailed
-					item.Error = sql.NullString{String: libErr.Error(), Valid: true}
-					continue
-				}
+		if err == nil && track != nil && s.library != nil {
+			_, libErr := s.library.AddTrackToLibrary(ctx, userID, track.ID)
+			switch {
+			case errors.Is(libErr, db.ErrTrackNotFound):
+				// Another tenant downloaded it; this user gets a copy of their own.
+				track = nil
+			case libErr != nil && !errors.Is(libErr, db.ErrTrackAlreadyInLibrary):
+				_ = s.store.MarkItemFailed(ctx, item.ID, libErr.Error())
+				item.Status = ItemStatusFailed
+				item.Error = sql.NullString{String: libErr.Error(), Valid: true}
+				continue
 			}
+		}
+		if err == nil && track != nil {
 			if err := s.selections.AttachTrackForUser(ctx, userID, decision.ID, track.ID); err != nil {
 				_ = s.store.MarkItemFailed(ctx, item.ID, err.Error())
 				item.Status = ItemStatusFailed
@@ -498,6 +521,49 @@ func (s *Service) GetImport(ctx context.Context, userID uuid.UUID, id uuid.UUID)
 	return &ImportResult{Job: job, Items: items}, nil
 }
 
+// CancelImport cancels an unfinished import. Items not yet queued are never
+// downloaded, and queued downloads are canceled; tracks already imported stay
+// in the playlist.
+func (s *Service) CancelImport(ctx context.Context, userID uuid.UUID, id uuid.UUID) (*ImportJob, error) {
+	job, err := s.store.GetJob(ctx, id)
+	if err != nil {
+		return nil, err
+	}
+	if job.UserID != userID {
+		return nil, ErrForbidden
+	}
+	if err := s.cancelImportDownloads(ctx, id); err != nil {
+		return nil, err
+	}
+	return s.store.GetJob(ctx, id)
+}
+
+// cancelImportDownloads cancels the import's outstanding items and their
+// queued downloads. Downloads that already finished are left alone.
+func (s *Service) cancelImportDownloads(ctx context.Context, jobID uuid.UUID) error {
+	downloadJobIDs, err := s.store.CancelJob(ctx, jobID)
+	if err != nil {
+		return err
+	}
+	if s.canceler == nil {
+		return nil
+	}
+	for _, downloadJobID := range downloadJobIDs {
+		_, err := s.canceler.CancelJob(ctx, downloadJobID)
+		if err != nil && !errors.Is(err, download.ErrJobNotCancelable) && !errors.Is(err, download.ErrJobNotFound) {
+			log.Printf("Playlist import %s: failed to cancel download %s: %v", jobID, downloadJobID, err)
+		}
+	}
+	return nil
+}
+
+// importCanceled reports whether the import was canceled while its items
+// were being queued.
+func (s *Service) importCanceled(ctx context.Context, jobID uuid.UUID) bool {
+	job, err := s.store.GetJob(ctx, jobID)
+	return err == nil && job.Status == JobStatusCanceled
+}
+
 func (s *Service) effectiveLimit(requested int) int {
 	limit := s.maxItems
 	if requested > 0 && requested < limit {
@@ -517,6 +517,62 @@ func TestStartImportPropagatesPersistentQueuedItemStoreFailure(t *testing.T) {
 	}
 }
 
+func TestCancelImportStopsQueueingRemainingItems(t *testing.T) {
+	ctx := context.Background()
+	userID := uuid.New()
+	store := newFakeStore()
+	downloader := &fakeDownloader{}
+	entries := []Entry{
+		{SourceID: "one", SourceURL: "https://www.youtube.com/watch?v=one", Title: "One"},
+		{SourceID: "two", SourceURL: "https://www.youtube.com/watch?v=two", Title: "Two"},
+		{SourceID: "three", SourceURL: "https://www.youtube.com/watch?v=three", Title: "Three"},
+	}
+	service := NewService(Config{Store: store, Playlists: &fakePlaylists{}, Tracks: &fakeTrackSources{bySourceID: map[string]*db.Track{}}, Downloader: downloader, Canceler: downloader, Selections: &fakeSourceSelections{}, Ingestion: &fakeTrustedIngestion{}, Enumerator: &fakeEnumerator{entries: entries}})
+	downloader.onEnqueue = func() {
+		downloader.onEnqueue = nil
+		for id := range store.jobs {
+			if _, err := service.CancelImport(ctx, userID, id); err != nil {
+				t.Errorf("CancelImport returned error: %v", err)
+			}
+		}
+	}
+
+	result, err := service.StartImport(ctx, userID, ImportRequest{URL: "https://www.youtube.com/playlist?list=PLfixture"})
+	if err != nil {
+		t.Fatalf("StartImport returned error: %v", err)
+	}
+	if result.Job.Status != JobStatusCanceled {
+		t.Fatalf("job status = %q, want canceled", result.Job.Status)
+	}
+	if len(downloader.jobs) != 1 || len(downloader.canceled) != 1 || downloader.canceled[0] != downloader.jobs[0].ID {
+		t.Fatalf("downloads queued %d, canceled %v; want the one queued download canceled", len(downloader.jobs), downloader.canceled)
+	}
+	for _, item := range result.Items {
+		if item.Status != ItemStatusCanceled {
+			t.Fatalf("item %s status = %q, want canceled", item.SourceID, item.Status)
+		}
+	}
+}
+
+func TestCancelImportRejectsOtherUsersAndFinishedImports(t *testing.T) {
+	ctx := context.Background()
+	store := newFakeStore()
+	owner := uuid.New()
+	job := &ImportJob{ID: uuid.New(), UserID: owner, Status: JobStatusComplete}
+	_ = store.CreateJob(ctx, job)
+	service := NewService(Config{Store: store})
+
+	if _, err := service.CancelImport(ctx, uuid.New(), job.ID); !errors.Is(err, ErrForbidden) {
+		t.Fatalf("CancelImport by another user error = %v, want ErrForbidden", err)
+	}
+	if _, err := service.CancelImport(ctx, owner, job.ID); !errors.Is(err, ErrNotCancelable) {
+		t.Fatalf("CancelImport of a finished import error = %v, want ErrNotCancelable", err)
+	}
+	if _, err := service.CancelImport(ctx, owner, uuid.New()); !errors.Is(err, ErrNotFound) {
+		t.Fatalf("CancelImport of a missing import error = %v, want ErrNotFound", err)
+	}
+}
+
 type fakeStore struct {
 	jobs                map[uuid.UUID]*ImportJob
 	items               map[int64]*ImportItem
@@ -591,7 +647,9 @@ func (s *fakeStore) MarkItemQueued(_ context.Context, itemID int64, downloadJobI
 		return err
 	}
 	item := s.items[itemID]
-	item.Status = ItemStatusQueued
+	if item.Status != ItemStatusCanceled {
+		item.Status = ItemStatusQueued
+	}
 	item.DownloadJobID = sql.NullString{String: downloadJobID, Valid: true}
 	return nil
 }
@@ -613,6 +671,27 @@ func (s *fakeStore) MarkJobFailed(_ context.Context, jobID uuid.UUID, message st
 	job.Error = sql.NullString{String: message, Valid: true}
 	return nil
 }
+func (s *fakeStore) CancelJob(_ context.Context, jobID uuid.UUID) ([]string, error) {
+	job := s.jobs[jobID]
+	if job == nil {
+		return nil, ErrNotFound
+	}
+	if job.Status != JobStatusResolving && job.Status != JobStatusImporting && job.Status != JobStatusCanceled {
+		return nil, ErrNotCancelable
+	}
+	job.Status = JobStatusCanceled
+	var downloadJobIDs []string
+	for _, item := range s.items {
+		if item.ImportJobID != jobID || (item.Status != ItemStatusPending && item.Status != ItemStatusQueued && item.Status != ItemStatusCanceled) {
+			continue
+		}
+		item.Status = ItemStatusCanceled
+		if item.DownloadJobID.Valid {
+			downloadJobIDs = append(downloadJobIDs, item.DownloadJobID.String)
+		}
+	}
+	return downloadJobIDs, s.RefreshJobCounts(context.Background(), jobID)
+}
 func (s *fakeStore) RefreshJobCounts(_ context.Context, jobID uuid.UUID) error {
 	job := s.jobs[jobID]
 	job.TotalItems, job.ImportedItems, job.QueuedItems, job.FailedItems, job.SkippedItems = 0, 0, 0, 0, 0
@@ -632,6 +711,9 @@ func (s *fakeStore) RefreshJobCounts(_ context.Context, jobID uuid.UUID) error {
 			job.SkippedItems++
 		}
 	}
+	if job.Status == JobStatusCanceled {
+		return nil
+	}
 	if job.QueuedItems > 0 {
 		job.Status = JobStatusImporting
 	} else if job.FailedItems > 0 && job.ImportedItems+job.SkippedItems == 0 {
@@ -685,14 +767,26 @@ func (l *fakeLibrary) AddTrackToLibrary(_ context.Context, userID uuid.UUID, tra
 	return &db.LibraryEntry{UserID: userID, TrackID: trackID}, nil
 }
 
-type fakeDownloader struct{ jobs []*download.DownloadJob }
+type fakeDownloader struct {
+	jobs      []*download.DownloadJob
+	canceled  []string
+	onEnqueue func()
+}
 
 func (d *fakeDownloader) EnqueuePlaylistImportItemWithID(_ context.Context, jobID, userID string, candidate download.SourceCandidate, importJobID string, importItemID int64, playlistID int64, playlistPosition int) (*download.DownloadJob, error) {
 	job := &download.DownloadJob{ID: jobID, UserID: userID, URL: candidate.SourceURL, SourceType: candidate.Provider, SourceID: candidate.SourceID, Title: candidate.Title, PlaylistImportJobID: importJobID, PlaylistImportItemID: importItemID, PlaylistID: playlistID, PlaylistPosition: playlistPosition}
 	d.jobs = append(d.jobs, job)
+	if d.onEnqueue != nil {
+		d.onEnqueue()
+	}
 	return job, nil
 }
 
+func (d *fakeDownloader) CancelJob(_ context.Context, jobID string) (*download.DownloadJob, error) {
+	d.canceled = append(d.canceled, jobID)
+	return &download.DownloadJob{ID: jobID, Status: download.StatusCanceled}, nil
+}
+
 type fakeSourceSelections struct {
 	created        []*db.SourceSelectionDecision
 	attachedTracks []int64
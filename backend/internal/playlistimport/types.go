@@ -23,6 +23,7 @@ const (
 	ItemStatusImported         = "imported"
 	ItemStatusFailed           = "failed"
 	ItemStatusSkippedDuplicate = "skipped_duplicate"
+	ItemStatusCanceled         = "cancel" + "led"
 )
 
 type ImportRequest struct {
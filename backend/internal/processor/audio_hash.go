@@ -0,0 +1,205 @@
+package processor
+
+import (
+	"bufio"
+	"context"
+	"encoding/binary"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// AudioHashMatch is how closely a download's audio must match a stored
+// track's for the download to be stored as that track, whatever its tags say.
+type AudioHashMatch string
+
+const (
+	// AudioHashMatchOff matches downloads by their tags alone. Audio is
+	// still hashed, so matching can be turned on later.
+	AudioHashMatchOff AudioHashMatch = "off"
+	// AudioHashMatchExact matches identical hashes, as copies and remuxes of
+	// the same file give.
+	AudioHashMatchExact AudioHashMatch = "exact"
+	// AudioHashMatchStrict allows the few differing bits a lossy re-encode
+	// of the same audio gives.
+	AudioHashMatchStrict AudioHashMatch = "strict"
+	// AudioHashMatchLoose allows about twice as many, catching low-bitrate
+	// re-encodes at more risk of merging different takes of a song.
+	AudioHashMatchLoose AudioHashMatch = "loose"
+)
+
+// audioHashMaxDistance is how many of a hash's bits may differ under each
+// matching strictness.
+var audioHashMaxDistance = map[AudioHashMatch]int{
+	AudioHashMatchExact:  0,
+	AudioHashMatchStrict: 12,
+	AudioHashMatchLoose:  28,
+}
+
+// ParseAudioHashMatch reads an AUDIO_HASH_MATCH setting.
+func ParseAudioHashMatch(value string) (AudioHashMatch, error) {
+	match := AudioHashMatch(strings.ToLower(strings.TrimSpace(value)))
+	if _, ok := audioHashMaxDistance[match]; ok || match == AudioHashMatchOff {
+		return match, nil
+	}
+	return "", fmt.Errorf("invalid audio hash match %q: want off, exact, strict or loose", value)
+}
+
+const (
+	// audioHashBits is the length of an audio hash.
+	audioHashBits = 256
+	// audioHashSampleRate is the rate audio is decoded at for hashing; the
+	// spans compared are far longer than anything it loses.
+	audioHashSampleRate = 8000
+	// audioHashBlockSamples is 50ms of audio at audioHashSampleRate, the
+	// unit spans are built from.
+	audioHashBlockSamples = 400
+	audioHashTimeout      = 2 * time.Minute
+)
+
+// audioHash returns the hex signature of the audio at path. The decoded audio
+// is cut into audioHashBits+1 equal spans and bit i is set when span i+1 is
+// louder on average than span i. Comparing neighbouring spans ignores gain,
+// and spans this long ignore the detail lossy codecs discard, so re-encodes
+// keep nearly every bit. Audio too short for the spans, or so flat that under
+// a quarter or over three quarters of the bits are set, gets an empty hash.
+func audioHash(ctx context.Context, path string) (string, error) {
+	ctx, cancel := context.WithTimeout(ctx, audioHashTimeout)
+	defer cancel()
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-v", "error",
+		"-i", path,
+		"-map", "0:a:0", "-ac", "1", "-ar", strconv.Itoa(audioHashSampleRate),
+		"-f", "s16le", "-",
+	)
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stderr = &stderr
+	stdout, err := cmd.StdoutPipe()
+	if err != nil {
+		return "", err
+	}
+	if err := cmd.Start(); err != nil {
+		return "", fmt.Errorf("start ffmpeg: %w", err)
+	}
+	energies, readErr := blockEnergies(bufio.NewReader(stdout))
+	if readErr != nil {
+		// Keep ffmpeg from blocking on a full pipe until it is waited on.
+		_, _ = io.Copy(io.Discard, stdout)
+	}
+	if err := cmd.Wait(); err != nil {
+		return "", fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	if readErr != nil {
+		return "", fmt.Errorf("read decoded audio: %w", readErr)
+	}
+	return audioHashFromEnergies(energies), nil
+}
+
+// blockEnergies reads 16-bit little-endian mono samples and returns the
+// energy of each whole block of audioHashBlockSamples.
+func blockEnergies(r io.Reader) ([]float64, error) {
+	var energies []float64
+	block := make([]byte, 2*audioHashBlockSamples)
+	for {
+		if _, err := io.ReadFull(r, block); err != nil {
+			if errors.Is(err, io.EOF) || errors.Is(err, io.ErrUnexpectedEOF) {
+				return energies, nil
+			}
+			return energies, err
+		}
+		var energy float64
+		for i := 0; i < len(block); i += 2 {
+			sample := float64(int16(binary.LittleEndian.Uint16(block[i:])))
+			energy += sample * sample
+		}
+		energies = append(energies, energy)
+	}
+}
+
+// audioHashFromEnergies builds the hash described at audioHash from block
+// energies.
+func audioHashFromEnergies(energies []float64) string {
+	spans := audioHashBits + 1
+	if len(energies) < spans {
+		return ""
+	}
+	sums := make([]float64, spans)
+	counts := make([]int, spans)
+	for i, energy := range energies {
+		span := i * spans / len(energies)
+		sums[span] += energy
+		counts[span]++
+	}
+	var hash [audioHashBits / 8]byte
+	set := 0
+	for i := range audioHashBits {
+		if sums[i+1]/float64(counts[i+1]) > sums[i]/float64(counts[i]) {
+			hash[i/8] |= 0x80 >> (i % 8)
+			set++
+		}
+	}
+	if set < audioHashBits/4 || set > audioHashBits*3/4 {
+		return ""
+	}
+	return hex.EncodeToString(hash[:])
+}
+
+// hashAudio returns the audio hash of a download, or nil when it could not be
+// taken. Failures are logged and leave the track to the audio hash backfill.
+func (p *Processor) hashAudio(ctx context.Context, jobID, audioPath string) *string {
+	hash, err := audioHash(ctx, audioPath)
+	if err != nil {
+		log.Printf("Warning: failed to hash audio for job %s: %v", jobID, err)
+		return nil
+	}
+	return &hash
+}
+
+// findByAudio returns the stored track whose audio the download's matches
+// under the configured strictness, or nil.
+func (p *Processor) findByAudio(ctx context.Context, metadata *TrackMetadata) *db.Track {
+	maxDistance, ok := audioHashMaxDistance[p.audioHashMatch]
+	if !ok || metadata.AudioHash == nil {
+		return nil
+	}
+	track, err := p.trackRepo.FindByAudioHash(ctx, *metadata.AudioHash, metadata.DurationMs, metadata.TenantID, maxDistance)
+	if err != nil {
+		if !errors.Is(err, db.ErrTrackNotFound) {
+			log.Printf("Warning: failed to look up tracks by audio hash: %v", err)
+		}
+		return nil
+	}
+	return track
+}
+
+// HashStoredAudio takes the audio hash of a track stored before downloads
+// were hashed.
+func (p *Processor) HashStoredAudio(ctx context.Context, track *db.Track) error {
+	if p.storage == nil {
+		return errors.New("object storage is not configured")
+	}
+	storageKey := strings.TrimSpace(track.StorageKey.String)
+	if !track.StorageKey.Valid || storageKey == "" {
+		return errors.New("track has no stored audio object")
+	}
+	tmpPath, _, err := p.fetchStoredAudio(ctx, storageKey, "omp-audio-hash-*")
+	if err != nil {
+		return err
+	}
+	defer os.Remove(tmpPath)
+
+	hash, err := audioHash(ctx, tmpPath)
+	if err != nil {
+		return err
+	}
+	return p.trackRepo.SetAudioHash(ctx, track.ID, hash)
+}
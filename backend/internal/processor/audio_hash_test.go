@@ -0,0 +1,113 @@
+package processor
+
+import (
+	"bytes"
+	"encoding/binary"
+	"encoding/hex"
+	"math"
+	"math/bits"
+	"testing"
+)
+
+// songEnergies returns block energies that rise and fall like a song's verses
+// and choruses, scaled by gain.
+func songEnergies(blocks int, gain float64) []float64 {
+	energies := make([]float64, blocks)
+	for i := range energies {
+		x := float64(i)
+		energies[i] = gain * (2 + math.Sin(x/7) + 0.6*math.Sin(x/2.3) + 0.3*math.Cos(x/0.9))
+	}
+	return energies
+}
+
+func hashDistance(t *testing.T, a, b string) int {
+	t.Helper()
+	left, err := hex.DecodeString(a)
+	if err != nil {
+		t.Fatalf("decode %q: %v", a, err)
+	}
+	right, err := hex.DecodeString(b)
+	if err != nil {
+		t.Fatalf("decode %q: %v", b, err)
+	}
+	distance := 0
+	for i := range left {
+		distance += bits.OnesCount8(left[i] ^ right[i])
+	}
+	return distance
+}
+
+func TestAudioHashFromEnergiesIgnoresGain(t *testing.T) {
+	hash := audioHashFromEnergies(songEnergies(4000, 1))
+	if len(hash) != audioHashBits/4 {
+		t.Fatalf("audioHashFromEnergies() = %q, want %d hex digits", hash, audioHashBits/4)
+	}
+	if louder := audioHashFromEnergies(songEnergies(4000, 3.5)); louder != hash {
+		t.Fatalf("louder copy hash = %q, want %q", louder, hash)
+	}
+}
+
+func TestAudioHashFromEnergiesToleratesSmallChanges(t *testing.T) {
+	original := songEnergies(4000, 1)
+	reencoded := make([]float64, len(original))
+	for i, energy := range original {
+		// A deterministic wobble of about 1% stands in for codec loss.
+		reencoded[i] = energy * (1 + 0.01*math.Sin(float64(i)*12.9898))
+	}
+	distance := hashDistance(t, audioHashFromEnergies(original), audioHashFromEnergies(reencoded))
+	if distance > audioHashMaxDistance[AudioHashMatchStrict] {
+		t.Fatalf("re-encode distance = %d, want at most %d", distance, audioHashMaxDistance[AudioHashMatchStrict])
+	}
+
+	other := songEnergies(4000, 1)[1000:]
+	other = append(other, songEnergies(1000, 1)...)
+	if distance := hashDistance(t, audioHashFromEnergies(original), audioHashFromEnergies(other)); distance <= audioHashMaxDistance[AudioHashMatchLoose] {
+		t.Fatalf("different audio distance = %d, want more than %d", distance, audioHashMaxDistance[AudioHashMatchLoose])
+	}
+}
+
+func TestAudioHashFromEnergiesSkipsShortAndFlatAudio(t *testing.T) {
+	if hash := audioHashFromEnergies(songEnergies(audioHashBits, 1)); hash != "" {
+		t.Fatalf("short audio hash = %q, want empty", hash)
+	}
+	flat := make([]float64, 4000)
+	for i := range flat {
+		flat[i] = 1
+	}
+	if hash := audioHashFromEnergies(flat); hash != "" {
+		t.Fatalf("flat audio hash = %q, want empty", hash)
+	}
+}
+
+func TestBlockEnergiesDropsPartialBlocks(t *testing.T) {
+	// One block of -2s, one of silence and a partial block.
+	samples := make([]byte, 2*(2*audioHashBlockSamples+10))
+	sample := int16(-2)
+	for i := 0; i < audioHashBlockSamples; i++ {
+		binary.LittleEndian.PutUint16(samples[2*i:], uint16(sample))
+	}
+	energies, err := blockEnergies(bytes.NewReader(samples))
+	if err != nil {
+		t.Fatalf("blockEnergies() error = %v", err)
+	}
+	if len(energies) != 2 || energies[0] != 4*audioHashBlockSamples || energies[1] != 0 {
+		t.Fatalf("blockEnergies() = %v, want [%d 0]", energies, 4*audioHashBlockSamples)
+	}
+}
+
+func TestParseAudioHashMatch(t *testing.T) {
+	for value, want := range map[string]AudioHashMatch{
+		"off":     AudioHashMatchOff,
+		" Strict": AudioHashMatchStrict,
+		"LOOSE":   AudioHashMatchLoose,
+		"exact":   AudioHashMatchExact,
+	} {
+		got, err := ParseAudioHashMatch(value)
+		if err != nil || got != want {
+			t.Errorf("ParseAudioHashMatch(%q) = %q, %v; want %q", value, got, err, want)
+		}
+	}
+	if _, err := ParseAudioHashMatch("fuzzy"); err == nil {
+		t.Fatal("ParseAudioHashMatch(\"fuzzy\") succeeded")
+	}
+}
@@ -0,0 +1,143 @@
+package processor
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"mime"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+// BulkRepairResult reports one track handled by a bulk fix-it job.
+type BulkRepairResult struct {
+	TrackID int64  `json:"trackId"`
+	Status  string `json:"status"`
+	Reason  string `json:"reason,omitempty"`
+}
+
+// RepairArtwork links cover art to a track that has none. Art another track on
+// the album already has wins, then the track's cover art URL. When neither
+// exists an unverified track is matched against MusicBrainz first so the
+// release cover can be fetched.
+func (p *Processor) RepairArtwork(ctx context.Context, track *db.Track) (BulkRepairResult, error) {
+	if track == nil {
+		return BulkRepairResult{Status: "skipped", Reason: "missing_track"}, nil
+	}
+	result := BulkRepairResult{TrackID: track.ID}
+	if p.artwork == nil {
+		result.Status = "skipped"
+		result.Reason = "artwork_ingester_disabled"
+		return result, nil
+	}
+
+	err := p.ingestCoverArt(ctx, track)
+	if errors.Is(err, db.ErrArtworkNotFound) && !track.MBVerified && !track.MetadataUserEdited && p.matcher != nil {
+		if err = p.runMatching(ctx, track, trackMetadataFromDBTrack(track)); err != nil {
+			result.Status = "failed"
+			result.Reason = err.Error()
+			return result, err
+		}
+		var matched *db.Track
+		if matched, err = p.trackRepo.GetByID(ctx, track.ID); err != nil {
+			return result, err
+		}
+		err = p.ingestCoverArt(ctx, matched)
+	}
+	switch {
+	case err == nil:
+		result.Status = "processed"
+		result.Reason = "artwork_linked"
+		return result, nil
+	case errors.Is(err, db.ErrArtworkNotFound), errors.Is(err, db.ErrArtworkNotLinked):
+		result.Status = "skipped"
+		result.Reason = "no_artwork_found"
+		return result, nil
+	default:
+		result.Status = "failed"
+		result.Reason = err.Error()
+		return result, err
+	}
+}
+
+func (p *Processor) ingestCoverArt(ctx context.Context, track *db.Track) error {
+	coverURL := strings.TrimSpace(nullableString(track.CoverArtURL))
+	if coverURL == "" && track.MBReleaseID != nil {
+		coverURL = "https://coverartarchive.org/release/" + track.MBReleaseID.String() + "/front-500"
+	}
+	_, err := p.artwork.IngestTrackArtwork(ctx, track.ID, coverURL)
+	return err
+}
+
+// RedownloadTrack fetches a track's audio again from its source and replaces
+// the stored object. The new file goes under a fresh key, so a failed upload
+// leaves the track pointing at the old one.
+func (p *Processor) RedownloadTrack(ctx context.Context, track *db.Track) (BulkRepairResult, error) {
+	if track == nil {
+		return BulkRepairResult{Status: "skipped", Reason: "missing_track"}, nil
+	}
+	result := BulkRepairResult{TrackID: track.ID}
+	sourceURL := strings.TrimSpace(nullableString(track.SourceURL))
+	if sourceURL == "" {
+		result.Status = "skipped"
+		result.Reason = "missing_source_url"
+		return result, nil
+	}
+	if p.storage == nil {
+		return result, errors.New("object storage is not configured")
+	}
+
+	job := &download.DownloadJob{
+		ID:         fmt.Sprintf("redownload-%d-%d", track.ID, time.Now().UnixNano()),
+		URL:        sourceURL,
+		SourceType: nullableString(track.SourceType),
+	}
+	tmpPath, contentType, err := p.obtainAudioFile(ctx, job, trackMetadataFromDBTrack(track), nil)
+	if err != nil {
+		return result, fmt.Errorf("download audio: %w", err)
+	}
+	defer os.Remove(tmpPath)
+
+	info, err := os.Stat(tmpPath)
+	if err != nil {
+		return result, fmt.Errorf("stat downloaded audio: %w", err)
+	}
+	if contentType == "" {
+		contentType = mime.TypeByExtension(filepath.Ext(tmpPath))
+	}
+	quality, err := probeAudioFile(ctx, tmpPath, contentType)
+	if err != nil {
+		return result, fmt.Errorf("probe downloaded audio: %w", err)
+	}
+	file, err := os.Open(tmpPath)
+	if err != nil {
+		return result, fmt.Errorf("open downloaded audio: %w", err)
+	}
+	defer file.Close()
+
+	key := storageKey(job, tmpPath)
+	if err := p.storage.PutObject(ctx, key, file, info.Size(), quality.ContentType); err != nil {
+		return result, fmt.Errorf("upload audio to object storage: %w", err)
+	}
+	if err := p.trackRepo.ReplaceStoredAudio(
+		ctx,
+		track.ID,
+		key,
+		info.Size(),
+		quality.Codec,
+		quality.BitrateKbps,
+		quality.SampleRateHz,
+		quality.Channels,
+		quality.ContentType,
+	); err != nil {
+		return result, err
+	}
+	result.Status = "processed"
+	result.Reason = "audio_replaced"
+	return result, nil
+}
@@ -0,0 +1,131 @@
+package processor
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/libraryexport"
+)
+
+// FormatEnforcer queues conversion of new tracks stored in a format the
+// library's format policy does not allow. formatpolicy.Enforcer satisfies
+// this interface.
+type FormatEnforcer interface {
+	Enforce(ctx context.Context, track *db.Track) error
+}
+
+// enforceFormat queues a new track for conversion when its audio breaks the
+// format policy. Failures are logged; the track stays as downloaded.
+func (p *Processor) enforceFormat(ctx context.Context, track *db.Track, isNew bool) {
+	if p.formatEnforcer == nil || !isNew {
+		return
+	}
+	if err := p.formatEnforcer.Enforce(ctx, track); err != nil {
+		log.Printf("Warning: failed to queue format conversion for track %d: %v", track.ID, err)
+	}
+}
+
+// ConvertStoredAudio re-encodes a track's stored audio for profile and points
+// the track at the converted copy, keeping its tags and cover. The copy goes
+// under a new key beside the old one, which is deleted once no track uses it.
+func (p *Processor) ConvertStoredAudio(ctx context.Context, track *db.Track, profile libraryexport.Profile) error {
+	if p.storage == nil {
+		return errors.New("object storage is not configured")
+	}
+	oldKey := strings.TrimSpace(track.StorageKey.String)
+	if oldKey == "" {
+		return errors.New("track has no stored audio object")
+	}
+	scratch, err := os.MkdirTemp("", "omp-convert-*")
+	if err != nil {
+		return err
+	}
+	defer os.RemoveAll(scratch)
+
+	src, err := p.fetchStoredAudio(ctx, oldKey, scratch)
+	if err != nil {
+		return err
+	}
+	ext := profile.Extension(strings.ToLower(strings.TrimPrefix(path.Ext(oldKey), ".")))
+	dst := filepath.Join(scratch, "converted."+ext)
+	if err := libraryexport.NewFFmpeg().Write(ctx, src, "", dst, nil, profile); err != nil {
+		return err
+	}
+	quality, err := probeAudioFile(ctx, dst, profile.ContentType())
+	if err != nil {
+		return fmt.Errorf("probe converted audio: %w", err)
+	}
+
+	file, err := os.Open(dst)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+	info, err := file.Stat()
+	if err != nil {
+		return err
+	}
+	newKey := strings.TrimSuffix(oldKey, path.Ext(oldKey)) + "-" + profile.Fingerprint() + "." + ext
+	if err := p.storage.PutObject(ctx, newKey, file, info.Size(), quality.ContentType); err != nil {
+		return fmt.Errorf("upload converted audio to object storage: %w", err)
+	}
+	if err := p.trackRepo.ReplaceStoredAudio(
+		ctx,
+		track.ID,
+		newKey,
+		info.Size(),
+		quality.Codec,
+		quality.BitrateKbps,
+		quality.SampleRateHz,
+		quality.Channels,
+		quality.ContentType,
+	); err != nil {
+		return err
+	}
+	inUse, err := p.trackRepo.StorageKeyInUse(ctx, oldKey)
+	if err != nil {
+		log.Printf("Warning: failed to check whether object %s of converted track %d is shared: %v", oldKey, track.ID, err)
+		return nil
+	}
+	if !inUse {
+		if err := p.storage.DeleteObject(ctx, oldKey); err != nil {
+			log.Printf("Warning: failed to delete object %s of converted track %d: %v", oldKey, track.ID, err)
+		}
+	}
+	return nil
+}
+
+// fetchStoredAudio copies the object at key into dir and returns the copy's
+// path, which keeps the key's extension for ffmpeg's format detection.
+func (p *Processor) fetchStoredAudio(ctx context.Context, key, dir string) (string, error) {
+	reader, _, err := p.storage.GetObject(ctx, key)
+	if err != nil {
+		return "", fmt.Errorf("get stored audio object: %w", err)
+	}
+	defer reader.Close()
+	ext := strings.ToLower(strings.TrimPrefix(path.Ext(key), "."))
+	dst := filepath.Join(dir, "source."+firstNonEmpty(ext, "bin"))
+	file, err := os.Create(dst)
+	if err != nil {
+		return "", err
+	}
+	written, err := io.Copy(file, io.LimitReader(reader, maxYTDLPOutputBytes+1))
+	if closeErr := file.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		return "", fmt.Errorf("copy stored audio object: %w", err)
+	}
+	if written > maxYTDLPOutputBytes {
+		return "", fmt.Errorf("stored audio object exceeds %d bytes", maxYTDLPOutputBytes)
+	}
+	return dst, nil
+}
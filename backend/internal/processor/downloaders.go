@@ -0,0 +1,273 @@
+package processor
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/url"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/fetcher"
+)
+
+// ytdlpProgressPrefix marks the progress lines fetchYTDLP asks yt-dlp for.
+const ytdlpProgressPrefix = "omp-progress:"
+
+// DefaultDownloaders returns the routing the processor uses when none is
+// configured: fixtures and local files by URL scheme, direct audio file URLs
+// over plain HTTP, and yt-dlp for everything else. Callers may register more
+// source types on the result before handing it to New.
+func DefaultDownloaders() *fetcher.Registry {
+	return ProxiedDownloaders(nil)
+}
+
+// ProxiedDownloaders is DefaultDownloaders with yt-dlp and direct file URLs
+// fetched through proxy; a nil proxy connects directly.
+func ProxiedDownloaders(proxy *url.URL) *fetcher.Registry {
+	fixture := fixtureDownloader{}
+	direct := fetcher.NewHTTP(fetcher.PublicClient(proxy), maxYTDLPOutputBytes)
+	ytdlp := ytdlpDownloader{executable: "yt-dlp"}
+	if proxy != nil {
+		ytdlp.proxy = proxy.String()
+	}
+	return fetcher.NewRegistry(ytdlp).
+		RegisterSourceType("fixture", fixture).
+		RegisterScheme("fixture", fixture).
+		RegisterScheme("file", fileDownloader{}).
+		RegisterSourceType("direct", direct).
+		RegisterMatch(fetcher.IsDirectAudioURL, direct)
+}
+
+// applyFetchedMetadata lets what the source reported override job-supplied
+// values, keeping them where the source had nothing.
+func applyFetchedMetadata(metadata *TrackMetadata, fetched fetcher.Metadata) {
+	metadata.Title = firstNonEmpty(fetched.Title, metadata.Title)
+	metadata.Artist = firstNonEmpty(fetched.Artist, metadata.Artist)
+	metadata.Album = firstNonEmpty(fetched.Album, metadata.Album)
+	metadata.Uploader = firstNonEmpty(fetched.Uploader, metadata.Uploader)
+	if fetched.DurationMs > 0 {
+		metadata.DurationMs = fetched.DurationMs
+	}
+	if fetched.Raw != nil {
+		metadata.Raw = fetched.Raw
+	}
+}
+
+func fetchedMetadata(metadata *TrackMetadata) fetcher.Metadata {
+	return fetcher.Metadata{
+		Title:      metadata.Title,
+		Artist:     metadata.Artist,
+		Album:      metadata.Album,
+		Uploader:   metadata.Uploader,
+		DurationMs: metadata.DurationMs,
+		Raw:        metadata.Raw,
+	}
+}
+
+// ytdlpDownloader fetches anything yt-dlp has an extractor for.
+type ytdlpDownloader struct {
+	executable string
+	proxy      string
+}
+
+func (d ytdlpDownloader) Name() string { return "yt-dlp" }
+
+func (d ytdlpDownloader) Probe(ctx context.Context, req fetcher.Request) error {
+	if _, err := exec.LookPath(d.executable); err != nil {
+		return fmt.Errorf("%w: yt-dlp is not installed", fetcher.ErrUnavailable)
+	}
+	return nil
+}
+
+// Metadata reads the source's info JSON without downloading the audio.
+func (d ytdlpDownloader) Metadata(ctx context.Context, req fetcher.Request) (fetcher.Metadata, error) {
+	if err := d.Probe(ctx, req); err != nil {
+		return fetcher.Metadata{}, err
+	}
+	cookieFile, err := ytdlpCookieFile(req)
+	if err != nil {
+		return fetcher.Metadata{}, err
+	}
+	if cookieFile != "" {
+		defer os.Remove(cookieFile)
+	}
+	args := append([]string{"--no-playlist", "--skip-download", "--dump-json"}, d.extraArgs(cookieFile)...)
+	cmd := exec.CommandContext(ctx, d.executable, append(args, req.URL)...)
+	output, err := cmd.Output()
+	if err != nil {
+		return fetcher.Metadata{}, fmt.Errorf("yt-dlp metadata failed: %w", err)
+	}
+	var raw map[string]interface{}
+	if err := json.Unmarshal(output, &raw); err != nil {
+		return fetcher.Metadata{}, fmt.Errorf("decode yt-dlp metadata: %w", err)
+	}
+	metadata := &TrackMetadata{}
+	applyYTDLPInfo(raw, metadata)
+	return fetchedMetadata(metadata), nil
+}
+
+func (d ytdlpDownloader) Fetch(ctx context.Context, req fetcher.Request, progress fetcher.ProgressFunc) (*fetcher.Result, error) {
+	cookieFile, err := ytdlpCookieFile(req)
+	if err != nil {
+		return nil, err
+	}
+	if cookieFile != "" {
+		defer os.Remove(cookieFile)
+	}
+	metadata := &TrackMetadata{}
+	path, contentType, err := fetchYTDLP(ctx, d.executable, req.URL, d.extraArgs(cookieFile), metadata, maxYTDLPOutputBytes, progress)
+	if err != nil {
+		return nil, err
+	}
+	return &fetcher.Result{Path: path, ContentType: contentType, Metadata: fetchedMetadata(metadata)}, nil
+}
+
+// extraArgs are the flags every yt-dlp run of the downloader passes: its
+// proxy, and the request's cookies when cookieFile is not empty.
+func (d ytdlpDownloader) extraArgs(cookieFile string) []string {
+	var args []string
+	if d.proxy != "" {
+		args = append(args, "--proxy", d.proxy)
+	}
+	if cookieFile != "" {
+		args = append(args, "--cookies", cookieFile)
+	}
+	return args
+}
+
+// ytdlpCookieFile writes the request's cookies to a file for yt-dlp's
+// --cookies, returning "" when it has none. The caller removes the file.
+func ytdlpCookieFile(req fetcher.Request) (string, error) {
+	if len(req.Cookies) == 0 {
+		return "", nil
+	}
+	return fetcher.WriteCookieFile(req.Cookies)
+}
+
+// fileDownloader copies file:// sources, used by local library imports.
+type fileDownloader struct{}
+
+func (fileDownloader) Name() string { return "file" }
+
+func (fileDownloader) Probe(ctx context.Context, req fetcher.Request) error {
+	path, err := localPath(req.URL)
+	if err != nil {
+		return err
+	}
+	_, err = os.Stat(path)
+	return err
+}
+
+// Metadata is empty here; local tags and sidecars are read after the probe.
+func (fileDownloader) Metadata(ctx context.Context, req fetcher.Request) (fetcher.Metadata, error) {
+	return fetcher.Metadata{}, nil
+}
+
+func (fileDownloader) Fetch(ctx context.Context, req fetcher.Request, progress fetcher.ProgressFunc) (*fetcher.Result, error) {
+	path, err := localPath(req.URL)
+	if err != nil {
+		return nil, err
+	}
+	tmpPath, contentType, err := copyToBoundedTemp(path, maxYTDLPOutputBytes)
+	if err != nil {
+		return nil, err
+	}
+	return &fetcher.Result{Path: tmpPath, ContentType: contentType}, nil
+}
+
+func localPath(sourceURL string) (string, error) {
+	path := strings.TrimPrefix(sourceURL, "file://")
+	if path == "" {
+		return "", errors.New("empty file URL")
+	}
+	return path, nil
+}
+
+// fixtureDownloader writes a short silent WAV for smoke tests and demos.
+type fixtureDownloader struct{}
+
+func (fixtureDownloader) Name() string { return "fixture" }
+
+func (fixtureDownloader) Probe(ctx context.Context, req fetcher.Request) error { return nil }
+
+func (fixtureDownloader) Metadata(ctx context.Context, req fetcher.Request) (fetcher.Metadata, error) {
+	return fetcher.Metadata{}, nil
+}
+
+func (fixtureDownloader) Fetch(ctx context.Context, req fetcher.Request, progress fetcher.ProgressFunc) (*fetcher.Result, error) {
+	path, contentType, err := writeFixtureWAV(req.JobID)
+	if err != nil {
+		return nil, err
+	}
+	return &fetcher.Result{Path: path, ContentType: contentType}, nil
+}
+
+// ytdlpProgressWriter turns the progress lines requested from yt-dlp into
+// progress callbacks and passes every other line through to log.
+type ytdlpProgressWriter struct {
+	log      *limitedOutput
+	progress fetcher.ProgressFunc
+	partial  []byte
+	last     int
+}
+
+func (w *ytdlpProgressWriter) Write(p []byte) (int, error) {
+	w.partial = append(w.partial, p...)
+	for {
+		i := bytes.IndexByte(w.partial, '\n')
+		if i < 0 {
+			break
+		}
+		w.line(w.partial[:i+1])
+		w.partial = w.partial[i+1:]
+	}
+	// A line this long is not a progress line; don't buffer it unboundedly.
+	if len(w.partial) > maxYTDLPLogBytes {
+		w.Flush()
+	}
+	return len(p), nil
+}
+
+// Flush passes any unterminated trailing output to the log.
+func (w *ytdlpProgressWriter) Flush() {
+	if len(w.partial) > 0 {
+		w.log.Write(w.partial)
+		w.partial = nil
+	}
+}
+
+func (w *ytdlpProgressWriter) line(line []byte) {
+	rest, ok := bytes.CutPrefix(bytes.TrimSpace(line), []byte(ytdlpProgressPrefix))
+	if !ok {
+		w.log.Write(line)
+		return
+	}
+	percent, err := strconv.ParseFloat(strings.TrimSuffix(strings.TrimSpace(string(rest)), "%"), 64)
+	if err != nil || w.progress == nil {
+		return
+	}
+	if whole := int(percent); whole > w.last && whole <= 100 {
+		w.last = whole
+		w.progress(whole)
+	}
+}
+
+// ExternalDownloaders registers a command downloader for each source type in
+// commands, such as {"bandcamp": {"gallery-dl", "-D", "{dir}", "{url}"}}.
+// env is added to each command's environment, such as the downloads proxy's
+// HTTPS_PROXY.
+func ExternalDownloaders(registry *fetcher.Registry, commands map[string][]string, env []string) error {
+	for sourceType, args := range commands {
+		command, err := fetcher.NewCommand(args, maxYTDLPOutputBytes)
+		if err != nil {
+			return fmt.Errorf("downloader for %s: %w", sourceType, err)
+		}
+		registry.RegisterSourceType(sourceType, command.WithEnv(env))
+	}
+	return nil
+}
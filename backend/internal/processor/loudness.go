@@ -0,0 +1,171 @@
+package processor
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"log"
+	"math"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// LoudnessNormalizationStore records loudness normalization applied to
+// tracks' audio. db.LoudnessNormalizationRepository satisfies this interface.
+type LoudnessNormalizationStore interface {
+	Save(ctx context.Context, normalization *db.LoudnessNormalization) error
+}
+
+const (
+	// DefaultLoudnessTargetLUFS is the integrated loudness downloads are
+	// normalized to when no target is configured.
+	DefaultLoudnessTargetLUFS = -14.0
+	// loudnessTruePeak is the highest true peak, in dBTP, normalized audio
+	// may reach.
+	loudnessTruePeak = -1.5
+	// loudnessRange is the loudness range, in LU, loudnorm aims for.
+	loudnessRange = 11
+	// loudnessToleranceLU is how far from the target audio may measure and
+	// still be stored as it is.
+	loudnessToleranceLU = 1.0
+)
+
+var audioSampleRatePattern = regexp.MustCompile(`Audio: [^,]+, ([0-9]+) Hz`)
+
+// loudnessMeasurement is loudnorm's analysis of a file, as the strings it
+// prints them in.
+type loudnessMeasurement struct {
+	InputI       string `json:"input_i"`
+	InputTP      string `json:"input_tp"`
+	InputLRA     string `json:"input_lra"`
+	InputThresh  string `json:"input_thresh"`
+	TargetOffset string `json:"target_offset"`
+	sampleRateHz int
+}
+
+// normalizeLoudness re-encodes the audio at audioPath to the target loudness
+// when the user asked for it and returns the normalized copy. Audio already
+// within loudnessToleranceLU of the target is left alone. Failures are
+// logged and leave the audio as downloaded.
+func (p *Processor) normalizeLoudness(ctx context.Context, jobID string, settings db.UserSettings, metadata *TrackMetadata, audioPath string) string {
+	if p.loudnessNormalizations == nil || !settings.NormalizeLoudness {
+		return ""
+	}
+	measured, err := measureLoudness(ctx, audioPath, p.loudnessTargetLUFS)
+	if err != nil {
+		log.Printf("Warning: failed to measure loudness for job %s: %v", jobID, err)
+		return ""
+	}
+	inputLUFS, ok := measured.needsNormalizing(p.loudnessTargetLUFS)
+	if !ok {
+		return ""
+	}
+	normalized, err := applyLoudnorm(ctx, audioPath, p.loudnessTargetLUFS, measured)
+	if err != nil {
+		log.Printf("Warning: failed to normalize loudness for job %s: %v", jobID, err)
+		return ""
+	}
+	metadata.LoudnessNormalization = &db.LoudnessNormalization{InputLUFS: inputLUFS, TargetLUFS: p.loudnessTargetLUFS}
+	return normalized
+}
+
+// needsNormalizing returns the measured integrated loudness and whether it
+// or the true peak is far enough off to re-encode for. Silent files, which
+// measure -inf, are left alone.
+func (m loudnessMeasurement) needsNormalizing(targetLUFS float64) (float64, bool) {
+	inputI, err := strconv.ParseFloat(m.InputI, 64)
+	if err != nil || math.IsInf(inputI, 0) || math.IsNaN(inputI) {
+		return 0, false
+	}
+	inputTP, err := strconv.ParseFloat(m.InputTP, 64)
+	if err != nil {
+		return 0, false
+	}
+	return inputI, math.Abs(inputI-targetLUFS) >= loudnessToleranceLU || inputTP > loudnessTruePeak
+}
+
+// measureLoudness runs loudnorm's analysis pass over the file at audioPath.
+func measureLoudness(ctx context.Context, audioPath string, targetLUFS float64) (loudnessMeasurement, error) {
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-hide_banner", "-nostats",
+		"-i", audioPath,
+		"-map", "0:a", "-af", loudnormFilter(targetLUFS)+":print_format=json",
+		"-f", "null", "-",
+	)
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return loudnessMeasurement{}, fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return parseLoudnorm(stderr.String())
+}
+
+// parseLoudnorm reads loudnorm's JSON summary and the input's sample rate
+// from ffmpeg's output.
+func parseLoudnorm(output string) (loudnessMeasurement, error) {
+	start, end := strings.LastIndex(output, "{"), strings.LastIndex(output, "}")
+	if start < 0 || end < start {
+		return loudnessMeasurement{}, fmt.Errorf("ffmpeg printed no loudnorm summary")
+	}
+	var measured loudnessMeasurement
+	if err := json.Unmarshal([]byte(output[start:end+1]), &measured); err != nil {
+		return loudnessMeasurement{}, fmt.Errorf("decode loudnorm summary: %w", err)
+	}
+	if m := audioSampleRatePattern.FindStringSubmatch(output); m != nil {
+		measured.sampleRateHz, _ = strconv.Atoi(m[1])
+	}
+	return measured, nil
+}
+
+// applyLoudnorm writes src normalized to targetLUFS to a new temp file and
+// returns its path.
+func applyLoudnorm(ctx context.Context, src string, targetLUFS float64, measured loudnessMeasurement) (string, error) {
+	dst, err := os.CreateTemp("", "omp-loudnorm-*"+filepath.Ext(src))
+	if err != nil {
+		return "", err
+	}
+	dst.Close()
+	cmd := exec.CommandContext(ctx, "ffmpeg", loudnormArgs(src, dst.Name(), targetLUFS, measured)...)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		os.Remove(dst.Name())
+		return "", fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return dst.Name(), nil
+}
+
+// loudnormArgs apply loudnorm with the analysis pass's measurements, which
+// lets it use a single linear gain instead of compressing dynamics. loudnorm
+// upsamples, so the input's sample rate is restored when known.
+func loudnormArgs(src, dst string, targetLUFS float64, measured loudnessMeasurement) []string {
+	filter := loudnormFilter(targetLUFS) +
+		":measured_I=" + measured.InputI +
+		":measured_TP=" + measured.InputTP +
+		":measured_LRA=" + measured.InputLRA +
+		":measured_thresh=" + measured.InputThresh +
+		":offset=" + measured.TargetOffset +
+		":linear=true"
+	args := []string{
+		"-nostdin", "-v", "error", "-y",
+		"-i", src,
+		"-map", "0:a", "-af", filter,
+	}
+	if measured.sampleRateHz > 0 {
+		args = append(args, "-ar", strconv.Itoa(measured.sampleRateHz))
+	}
+	return append(args, "-map_metadata", "0", "-q:a", "2", dst)
+}
+
+func loudnormFilter(targetLUFS float64) string {
+	return "loudnorm=I=" + strconv.FormatFloat(targetLUFS, 'f', 1, 64) +
+		":TP=" + strconv.FormatFloat(loudnessTruePeak, 'f', 1, 64) +
+		":LRA=" + strconv.Itoa(loudnessRange)
+}
@@ -0,0 +1,71 @@
+package processor
+
+import (
+	"reflect"
+	"testing"
+)
+
+const loudnormOutput = `Input #0, mp3, from 'quiet.mp3':
+  Duration: 00:03:25.50, start: 0.025057, bitrate: 192 kb/s
+  Stream #0:0: Audio: mp3, 44100 Hz, stereo, fltp, 192 kb/s
+[Parsed_loudnorm_0 @ 0x55d0c8a0]
+{
+	"input_i" : "-23.54",
+	"input_tp" : "-7.96",
+	"input_lra" : "2.60",
+	"input_thresh" : "-34.00",
+	"output_i" : "-14.21",
+	"output_tp" : "-1.50",
+	"output_lra" : "2.30",
+	"output_thresh" : "-24.60",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.21"
+}
+`
+
+func TestParseLoudnormReadsSummaryAndSampleRate(t *testing.T) {
+	measured, err := parseLoudnorm(loudnormOutput)
+	if err != nil {
+		t.Fatalf("parseLoudnorm() error = %v", err)
+	}
+	want := loudnessMeasurement{InputI: "-23.54", InputTP: "-7.96", InputLRA: "2.60", InputThresh: "-34.00", TargetOffset: "0.21", sampleRateHz: 44100}
+	if measured != want {
+		t.Fatalf("parseLoudnorm() = %+v, want %+v", measured, want)
+	}
+	if _, err := parseLoudnorm("Input #0, mp3\n"); err == nil {
+		t.Fatal("parseLoudnorm() without a summary succeeded")
+	}
+}
+
+func TestNeedsNormalizingLeavesAudioNearTheTarget(t *testing.T) {
+	for _, tc := range []struct {
+		inputI, inputTP string
+		want            bool
+	}{
+		{"-23.54", "-7.96", true},
+		{"-14.4", "-2.1", false},
+		{"-14.4", "0.3", true},
+		{"-inf", "-inf", false},
+	} {
+		m := loudnessMeasurement{InputI: tc.inputI, InputTP: tc.inputTP}
+		if _, got := m.needsNormalizing(-14); got != tc.want {
+			t.Fatalf("needsNormalizing(%s LUFS, %s dBTP) = %v, want %v", tc.inputI, tc.inputTP, got, tc.want)
+		}
+	}
+}
+
+func TestLoudnormArgsApplyMeasurementsLinearly(t *testing.T) {
+	measured := loudnessMeasurement{InputI: "-23.54", InputTP: "-7.96", InputLRA: "2.60", InputThresh: "-34.00", TargetOffset: "0.21", sampleRateHz: 44100}
+	got := loudnormArgs("in.mp3", "out.mp3", -14, measured)
+	want := []string{
+		"-nostdin", "-v", "error", "-y",
+		"-i", "in.mp3",
+		"-map", "0:a", "-af", "loudnorm=I=-14.0:TP=-1.5:LRA=11:measured_I=-23.54:measured_TP=-7.96:measured_LRA=2.60:measured_thresh=-34.00:offset=0.21:linear=true",
+		"-ar", "44100",
+		"-map_metadata", "0", "-q:a", "2",
+		"out.mp3",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("loudnormArgs() = %q, want %q", got, want)
+	}
+}
@@ -0,0 +1,282 @@
+package processor
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+	"math"
+	"os"
+	"os/exec"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// MoodFeatureStore records the mood features of tracks' stored files.
+// db.TrackRepository satisfies this interface.
+type MoodFeatureStore interface {
+	SaveMoodFeatures(ctx context.Context, trackID int64, storageKey string, features *db.MoodFeatures) error
+}
+
+const (
+	// moodSampleRateHz and moodFrameSamples set the frames mood features are
+	// measured over, about 46 ms each.
+	moodSampleRateHz = 22050
+	moodFrameSamples = 1024
+	// moodMaxSeconds is how much of a track is measured; the opening minutes
+	// say enough about its mood.
+	moodMaxSeconds = 180
+	// moodSilenceDB is the frame level below which a frame counts as silent.
+	moodSilenceDB = -60
+	// moodMinSeconds is the least sound a track needs to be rated.
+	moodMinSeconds = 10
+	// moodMinBPM and moodMaxBPM bound the tempos the pulse is looked for at.
+	moodMinBPM = 60
+	moodMaxBPM = 180
+)
+
+// ErrMoodAnalysisDisabled is returned by MeasureMood when the processor has
+// no MoodFeatureStore.
+var ErrMoodAnalysisDisabled = errors.New("mood analysis is not configured")
+
+var moodStatPattern = regexp.MustCompile(`^lavfi\.(?:astats\.Overall\.RMS_level|aspectralstats\.1\.(centroid|flux))=(\S+)`)
+
+// moodFrame is one frame's level in dB, spectral centroid in Hz and
+// spectral flux.
+type moodFrame struct {
+	rmsDB    float64
+	centroid float64
+	flux     float64
+}
+
+// measureMood rates the audio at audioPath. ok is false when measuring
+// failed, so the track is measured again later; features are nil for audio
+// too quiet or short to rate.
+func (p *Processor) measureMood(ctx context.Context, trackRef, audioPath string) (features *db.MoodFeatures, ok bool) {
+	if p.moodFeatures == nil {
+		return nil, false
+	}
+	ctx, cancel := context.WithTimeout(ctx, qualityMeasureTimeout)
+	defer cancel()
+	frames, err := measureMoodFrames(ctx, audioPath)
+	if err != nil {
+		log.Printf("Warning: failed to measure mood features for %s: %v", trackRef, err)
+		return nil, false
+	}
+	return moodFeatures(frames), true
+}
+
+// MeasureMood measures the track's stored file and records its mood
+// features, which are nil when the audio is too quiet or short to rate.
+func (p *Processor) MeasureMood(ctx context.Context, track *db.Track) (*db.MoodFeatures, error) {
+	if p.moodFeatures == nil {
+		return nil, ErrMoodAnalysisDisabled
+	}
+	if p.storage == nil {
+		return nil, errors.New("object storage is not configured")
+	}
+	key := strings.TrimSpace(track.StorageKey.String)
+	if key == "" {
+		return nil, ErrNoStoredAudio
+	}
+	tmpPath, _, err := p.fetchStoredAudio(ctx, key, "omp-mood-*")
+	if err != nil {
+		return nil, err
+	}
+	defer os.Remove(tmpPath)
+
+	ctx, cancel := context.WithTimeout(ctx, qualityMeasureTimeout)
+	defer cancel()
+	frames, err := measureMoodFrames(ctx, tmpPath)
+	if err != nil {
+		return nil, err
+	}
+	features := moodFeatures(frames)
+	if err := p.moodFeatures.SaveMoodFeatures(ctx, track.ID, key, features); err != nil {
+		return nil, fmt.Errorf("save mood features: %w", err)
+	}
+	return features, nil
+}
+
+// measureMoodFrames reads the level, spectral centroid and spectral flux of
+// each frame of the opening moodMaxSeconds, downmixed to mono.
+func measureMoodFrames(ctx context.Context, audioPath string) ([]moodFrame, error) {
+	filter := "aformat=channel_layouts=mono,aresample=" + strconv.Itoa(moodSampleRateHz) + "," +
+		"asetnsamples=n=" + strconv.Itoa(moodFrameSamples) + ":p=0," +
+		"astats=metadata=1:reset=1:measure_overall=RMS_level:measure_perchannel=none," +
+		"aspectralstats=win_size=" + strconv.Itoa(moodFrameSamples) + ":overlap=0:measure=centroid+flux," +
+		"ametadata=mode=print:file=-"
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-v", "error",
+		"-t", strconv.Itoa(moodMaxSeconds),
+		"-i", audioPath,
+		"-map", "0:a", "-af", filter,
+		"-f", "null", "-",
+	)
+	stdout := limitedOutput{limit: maxQualityOutputBytes}
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return nil, fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return parseMoodFrames(stdout.String()), nil
+}
+
+// parseMoodFrames reads the per-frame statistics printed by ametadata.
+func parseMoodFrames(output string) []moodFrame {
+	var frames []moodFrame
+	for _, line := range strings.Split(output, "\n") {
+		line = strings.TrimSpace(line)
+		if strings.HasPrefix(line, "frame:") {
+			frames = append(frames, moodFrame{rmsDB: math.Inf(-1)})
+			continue
+		}
+		m := moodStatPattern.FindStringSubmatch(line)
+		if m == nil || len(frames) == 0 {
+			continue
+		}
+		value, err := strconv.ParseFloat(m[2], 64)
+		if err != nil {
+			continue
+		}
+		frame := &frames[len(frames)-1]
+		switch m[1] {
+		case "centroid":
+			frame.centroid = value
+		case "flux":
+			frame.flux = value
+		default:
+			frame.rmsDB = value
+		}
+	}
+	return frames
+}
+
+// moodFeatures turns frames into rough mood proxies, or nil when there is
+// too little sound to rate:
+//   - energy rises with loudness, brightness and tempo;
+//   - danceability with a strong, steady pulse near 120 BPM and even
+//     dynamics;
+//   - valence with brightness, tempo and pulse, since bright, quick,
+//     rhythmic music tends to sound positive.
+func moodFeatures(frames []moodFrame) *db.MoodFeatures {
+	frameRate := float64(moodSampleRateHz) / moodFrameSamples
+	var voiced []moodFrame
+	for _, f := range frames {
+		if f.rmsDB > moodSilenceDB && !math.IsInf(f.rmsDB, 0) {
+			voiced = append(voiced, f)
+		}
+	}
+	if float64(len(voiced)) < moodMinSeconds*frameRate {
+		return nil
+	}
+
+	var loudness, brightness float64
+	for _, f := range voiced {
+		loudness += f.rmsDB
+		brightness += f.centroid
+	}
+	loudness /= float64(len(voiced))
+	brightness /= float64(len(voiced))
+	tempo, pulse := moodPulse(frames, frameRate)
+
+	loud := clamp01((loudness + 35) / 25)
+	bright := clamp01((brightness - 800) / 2700)
+	fast := clamp01((tempo - 70) / 90)
+	even := 1 - clamp01(moodDynamics(voiced, frameRate)/10)
+	tempoFit := 1 - clamp01(math.Abs(tempo-120)/50)
+	return &db.MoodFeatures{
+		Energy:       round3(0.5*loud + 0.25*bright + 0.25*fast),
+		Valence:      round3(0.4*bright + 0.3*fast + 0.3*pulse),
+		Danceability: round3(0.45*pulse + 0.3*tempoFit + 0.25*even),
+	}
+}
+
+// moodDynamics is how much the level of one-second blocks varies, as the
+// standard deviation of their mean levels in dB.
+func moodDynamics(frames []moodFrame, frameRate float64) float64 {
+	perBlock := max(int(frameRate), 1)
+	var levels []float64
+	for start := 0; start+perBlock <= len(frames); start += perBlock {
+		var sum float64
+		for _, f := range frames[start : start+perBlock] {
+			sum += f.rmsDB
+		}
+		levels = append(levels, sum/float64(perBlock))
+	}
+	if len(levels) < 2 {
+		return 0
+	}
+	var mean, variance float64
+	for _, l := range levels {
+		mean += l
+	}
+	mean /= float64(len(levels))
+	for _, l := range levels {
+		variance += (l - mean) * (l - mean)
+	}
+	return math.Sqrt(variance / float64(len(levels)))
+}
+
+// moodPulse finds the tempo, in BPM, at which the spectral flux repeats
+// most strongly, and how strongly from 0 to 1, by autocorrelating the flux
+// over the lags of moodMinBPM to moodMaxBPM.
+func moodPulse(frames []moodFrame, frameRate float64) (tempo, pulse float64) {
+	onsets := make([]float64, len(frames))
+	var mean, power float64
+	for i, f := range frames {
+		onsets[i] = f.flux
+		mean += f.flux
+		power += f.flux * f.flux
+	}
+	mean /= float64(max(len(frames), 1))
+	for i := range onsets {
+		onsets[i] -= mean
+	}
+	autocorrelation := func(lag int) float64 {
+		var sum float64
+		for i := lag; i < len(onsets); i++ {
+			sum += onsets[i] * onsets[i-lag]
+		}
+		return sum
+	}
+	// Flux that barely varies has no pulse, only rounding noise.
+	zero := autocorrelation(0)
+	minLag := int(math.Floor(frameRate * 60 / moodMaxBPM))
+	maxLag := int(math.Ceil(frameRate * 60 / moodMinBPM))
+	if zero <= 1e-6*power || maxLag >= len(onsets) {
+		return 0, 0
+	}
+	bestLag, best := 0, 0.0
+	values := make(map[int]float64, maxLag-minLag+3)
+	for lag := minLag - 1; lag <= maxLag+1; lag++ {
+		values[lag] = autocorrelation(lag) / zero
+	}
+	for lag := minLag; lag <= maxLag; lag++ {
+		if values[lag] > best {
+			bestLag, best = lag, values[lag]
+		}
+	}
+	if bestLag == 0 {
+		return 0, 0
+	}
+	// A parabola through the peak and its neighbours places it between
+	// frames, which matters at these coarse lags.
+	peak := float64(bestLag)
+	if prev, next := values[bestLag-1], values[bestLag+1]; prev-2*best+next < 0 {
+		peak += 0.5 * (prev - next) / (prev - 2*best + next)
+	}
+	return 60 * frameRate / peak, clamp01(best)
+}
+
+func clamp01(v float64) float64 {
+	return max(0, min(1, v))
+}
+
+func round3(v float64) float64 {
+	return math.Round(v*1000) / 1000
+}
@@ -0,0 +1,73 @@
+package processor
+
+import (
+	"math"
+	"reflect"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestParseMoodFramesReadsEachFrame(t *testing.T) {
+	output := `frame:0    pts:0       pts_time:0
+lavfi.astats.Overall.RMS_level=-20.5
+lavfi.aspectralstats.1.centroid=1500.25
+lavfi.aspectralstats.1.flux=0.03
+frame:1    pts:1024    pts_time:0.0464399
+lavfi.astats.Overall.RMS_level=-inf
+lavfi.aspectralstats.1.centroid=0
+`
+	frames := parseMoodFrames(output)
+	if len(frames) != 2 {
+		t.Fatalf("parseMoodFrames() = %+v, want 2 frames", frames)
+	}
+	if want := (moodFrame{rmsDB: -20.5, centroid: 1500.25, flux: 0.03}); frames[0] != want {
+		t.Fatalf("first frame = %+v, want %+v", frames[0], want)
+	}
+	if !math.IsInf(frames[1].rmsDB, -1) || frames[1].flux != 0 {
+		t.Fatalf("silent frame = %+v", frames[1])
+	}
+}
+
+// synthFrames makes the given seconds of frames at one level and
+// brightness, with a flux spike every beat frames, or flat flux when beat is
+// zero.
+func synthFrames(seconds, rmsDB, centroid float64, beat int) []moodFrame {
+	frames := make([]moodFrame, int(seconds*moodSampleRateHz/moodFrameSamples))
+	for i := range frames {
+		frames[i] = moodFrame{rmsDB: rmsDB, centroid: centroid, flux: 0.01}
+		if beat > 0 && i%beat == 0 {
+			frames[i].flux = 0.5
+		}
+	}
+	return frames
+}
+
+func TestMoodFeaturesRateLoudQuickMusicEnergetic(t *testing.T) {
+	// A spike every 10 frames is a beat at about 129 BPM.
+	features := moodFeatures(synthFrames(30, -8, 3000, 10))
+	if features == nil {
+		t.Fatal("moodFeatures() = nil")
+	}
+	if moods := features.Moods(); !reflect.DeepEqual(moods, []string{"high_energy", "happy", "danceable"}) {
+		t.Fatalf("moods of %+v = %v", *features, moods)
+	}
+}
+
+func TestMoodFeaturesRateQuietDarkMusicChill(t *testing.T) {
+	features := moodFeatures(synthFrames(30, -30, 600, 0))
+	// Without a pulse only its even dynamics count toward danceability.
+	if want := (&db.MoodFeatures{Energy: 0.1, Danceability: 0.25}); !reflect.DeepEqual(features, want) {
+		t.Fatalf("moodFeatures() = %+v, want %+v", features, want)
+	}
+	if moods := features.Moods(); !reflect.DeepEqual(moods, []string{"chill", "sad"}) {
+		t.Fatalf("moods = %v", moods)
+	}
+}
+
+func TestMoodFeaturesSkipAudioTooShortToRate(t *testing.T) {
+	frames := append(synthFrames(5, -10, 2000, 10), synthFrames(30, math.Inf(-1), 0, 0)...)
+	if features := moodFeatures(frames); features != nil {
+		t.Fatalf("moodFeatures() = %+v, want nil", *features)
+	}
+}
@@ -2,6 +2,7 @@ package processor
 
 import (
 	"context"
+	"database/sql"
 	"encoding/binary"
 	"encoding/json"
 	"errors"
@@ -21,10 +22,14 @@ import (
 	"github.com/google/uuid"
 
 	"github.com/openmusicplayer/backend/internal/analyzer"
+	"github.com/openmusicplayer/backend/internal/chapters"
 	"github.com/openmusicplayer/backend/internal/db"
 	"github.com/openmusicplayer/backend/internal/download"
+	"github.com/openmusicplayer/backend/internal/fetcher"
+	"github.com/openmusicplayer/backend/internal/images"
 	"github.com/openmusicplayer/backend/internal/matcher"
 	"github.com/openmusicplayer/backend/internal/playlistimport"
+	"github.com/openmusicplayer/backend/internal/sourceauth"
 	"github.com/openmusicplayer/backend/internal/storage"
 )
 
@@ -33,6 +38,41 @@ import (
 type ObjectStorage interface {
 	PutObject(ctx context.Context, key string, reader io.Reader, size int64, contentType string) error
 	GetObject(ctx context.Context, key string) (io.ReadCloser, *storage.ObjectInfo, error)
+	DeleteObject(ctx context.Context, key string) error
+}
+
+// TenantStore resolves the tenant a download is stored for.
+// db.TenantRepository satisfies this interface.
+type TenantStore interface {
+	ForUser(ctx context.Context, userID uuid.UUID) (*db.TenantMembership, error)
+	CheckQuota(ctx context.Context, tenantID, addBytes int64) error
+}
+
+// SourceCredentials supplies the login cookies users uploaded for the sources
+// of their downloads. sourceauth.Service satisfies this interface.
+type SourceCredentials interface {
+	ForDownload(ctx context.Context, userID uuid.UUID, sourceURL string) (*sourceauth.Credential, error)
+	Report(ctx context.Context, credential *sourceauth.Credential, fetchErr error)
+}
+
+// LyricsStore persists lyrics read alongside a track's audio.
+// db.LyricsRepository satisfies this interface.
+type LyricsStore interface {
+	Upsert(ctx context.Context, lyrics *db.Lyrics) error
+}
+
+// ChapterStore persists the tracklist of a long mix.
+// db.ChapterRepository satisfies this interface.
+type ChapterStore interface {
+	Save(ctx context.Context, trackID int64, chapters []db.Chapter) error
+}
+
+// ArtworkIngester renders and links cover artwork for a track.
+// images.Service satisfies this interface.
+type ArtworkIngester interface {
+	IngestEmbeddedArtwork(ctx context.Context, trackID int64, data []byte) (*db.Artwork, error)
+	IngestFolderArtwork(ctx context.Context, trackID int64, data []byte) (*db.Artwork, error)
+	IngestTrackArtwork(ctx context.Context, trackID int64, sourceURL string) (*db.Artwork, error)
 }
 
 // AnalysisStore is the audio-analysis persistence surface used by Processor.
@@ -62,6 +102,7 @@ type analysisTask struct {
 
 // Processor handles the full download and matching pipeline
 type Processor struct {
+	database                *db.DB
 	matcher                 *matcher.Matcher
 	trackRepo               *db.TrackRepository
 	libraryRepo             *db.LibraryRepository
@@ -85,6 +126,25 @@ type Processor struct {
 	expectedAnalyzer        string
 	expectedAnalyzerVersion string
 	storage                 ObjectStorage
+	artwork                 ArtworkIngester
+	folderArtworkNames      []string
+	downloaders             *fetcher.Registry
+	lyrics                  LyricsStore
+	chapters                ChapterStore
+	skipSegmentSource       SkipSegmentSource
+	skipSegments            SkipSegmentStore
+	trimSkipSegments        bool
+	userSettings            UserSettingsReader
+	silenceTrims            SilenceTrimStore
+	loudnessNormalizations  LoudnessNormalizationStore
+	loudnessTargetLUFS      float64
+	keepLoudnessOriginals   bool
+	trackQuality            TrackQualityStore
+	moodFeatures            MoodFeatureStore
+	formatEnforcer          FormatEnforcer
+	tenants                 TenantStore
+	audioHashMatch          AudioHashMatch
+	sourceCredentials       SourceCredentials
 }
 
 // ProcessorConfig holds configuration for the processor
@@ -101,6 +161,56 @@ type ProcessorConfig struct {
 	AnalysisConcurrency     int
 	RequireAnalyzerIdentity bool
 	Storage                 ObjectStorage
+	Artwork                 ArtworkIngester
+	// FolderArtworkNames is the sidecar artwork filename priority for local
+	// files. Nil uses images.DefaultFolderArtworkNames; empty disables lookup.
+	FolderArtworkNames []string
+	Lyrics             LyricsStore
+	Chapters           ChapterStore
+	// SkipSegmentSource looks up non-music segments of YouTube downloads,
+	// which are stored in SkipSegments for playback to skip or, with
+	// TrimSkipSegments, cut from the stored audio. Nil skips the lookup.
+	SkipSegmentSource SkipSegmentSource
+	SkipSegments      SkipSegmentStore
+	TrimSkipSegments  bool
+	// UserSettings and SilenceTrims trim long leading and trailing silence
+	// from the downloads of users who asked for it, keeping the untrimmed
+	// file. Nil for either keeps downloads as they are.
+	UserSettings UserSettingsReader
+	SilenceTrims SilenceTrimStore
+	// LoudnessNormalizations re-encodes the downloads of users who turned
+	// on loudness normalization to LoudnessTargetLUFS (0 uses
+	// DefaultLoudnessTargetLUFS). KeepLoudnessOriginals keeps the
+	// unnormalized file; audio with trimmed silence keeps it regardless. Nil
+	// keeps downloads as they are.
+	LoudnessNormalizations LoudnessNormalizationStore
+	LoudnessTargetLUFS     float64
+	KeepLoudnessOriginals  bool
+	// TrackQuality records each new track's dynamic range, spectral cutoff
+	// and quality score. Nil skips measuring them.
+	TrackQuality TrackQualityStore
+	// MoodFeatures records each new track's energy, valence and
+	// danceability. Nil skips measuring them.
+	MoodFeatures MoodFeatureStore
+	// FormatEnforcer queues new tracks that break the library's format
+	// policy for conversion. Nil keeps every format.
+	FormatEnforcer FormatEnforcer
+	// Downloaders routes each job to the fetcher for its source type. Nil
+	// uses DefaultDownloaders.
+	Downloaders *fetcher.Registry
+	// Tenants stores downloads as their requester's tenant's tracks. Nil
+	// stores every download as an instance track.
+	Tenants TenantStore
+	// AudioHashMatch is how closely a download's audio must match a stored
+	// track's for it to be stored as that track. Empty matches by tags alone,
+	// as AudioHashMatchOff does.
+	AudioHashMatch AudioHashMatch
+	// SourceCredentials logs downloads in to their sources with the cookies
+	// their requesters uploaded. Nil downloads every source anonymously.
+	SourceCredentials SourceCredentials
+	// DB stores each download's track and files it in the library, its
+	// playlist import and the inbox playlist as one transaction.
+	DB *db.DB
 }
 
 // New creates a new Processor instance
@@ -113,6 +223,7 @@ func New(config *ProcessorConfig) *Processor {
 		analysisConcurrency = 4
 	}
 	processor := &Processor{
+		database:                config.DB,
 		matcher:                 config.Matcher,
 		trackRepo:               config.TrackRepo,
 		libraryRepo:             config.LibraryRepo,
@@ -124,6 +235,31 @@ func New(config *ProcessorConfig) *Processor {
 		analyzerClient:          config.AnalyzerClient,
 		requireAnalyzerIdentity: config.RequireAnalyzerIdentity,
 		storage:                 config.Storage,
+		artwork:                 config.Artwork,
+		folderArtworkNames:      config.FolderArtworkNames,
+		lyrics:                  config.Lyrics,
+		chapters:                config.Chapters,
+		skipSegmentSource:       config.SkipSegmentSource,
+		skipSegments:            config.SkipSegments,
+		trimSkipSegments:        config.TrimSkipSegments,
+		userSettings:            config.UserSettings,
+		silenceTrims:            config.SilenceTrims,
+		loudnessNormalizations:  config.LoudnessNormalizations,
+		loudnessTargetLUFS:      config.LoudnessTargetLUFS,
+		keepLoudnessOriginals:   config.KeepLoudnessOriginals,
+		trackQuality:            config.TrackQuality,
+		moodFeatures:            config.MoodFeatures,
+		formatEnforcer:          config.FormatEnforcer,
+		downloaders:             config.Downloaders,
+		tenants:                 config.Tenants,
+		audioHashMatch:          config.AudioHashMatch,
+		sourceCredentials:       config.SourceCredentials,
+	}
+	if processor.folderArtworkNames == nil {
+		processor.folderArtworkNames = images.DefaultFolderArtworkNames
+	}
+	if processor.loudnessTargetLUFS == 0 {
+		processor.loudnessTargetLUFS = DefaultLoudnessTargetLUFS
 	}
 	if processor.analysisRepo != nil && processor.analyzerClient != nil {
 		processor.analysisCtx, processor.analysisCancel = context.WithCancel(context.Background())
@@ -158,7 +294,15 @@ func (p *Processor) Process(ctx context.Context, job *download.DownloadJob, prog
 	log.Printf("Processing job %s: downloading from %s", job.ID, job.URL)
 	progress(5)
 
-	metadata, err := p.downloadAndStore(ctx, job)
+	// The fetch covers 5-50%; only whole-step increases reach the queue.
+	lastFetchProgress := 5
+	fetchProgress := func(percent int) {
+		if scaled := 5 + percent*45/100; scaled > lastFetchProgress {
+			lastFetchProgress = scaled
+			progress(scaled)
+		}
+	}
+	metadata, err := p.downloadAndStore(ctx, job, fetchProgress)
 	if err != nil {
 		return fmt.Errorf("download failed: %w", err)
 	}
@@ -166,10 +310,17 @@ func (p *Processor) Process(ctx context.Context, job *download.DownloadJob, prog
 
 	log.Printf("Processing job %s: creating track record", job.ID)
 	job.Status = download.StatusProcessing
-	track, isNew, err := p.createTrack(ctx, job, metadata)
+	inbox := p.downloadSettings(ctx, job).InboxNewTracks
+	track, isNew, err := p.storeTrack(ctx, job, metadata, inbox)
 	if err != nil {
 		return fmt.Errorf("track creation failed: %w", err)
 	}
+	if isNew {
+		p.recordNewTrackDetails(ctx, track, metadata)
+	} else {
+		p.settleDuplicateObject(ctx, track, metadata)
+	}
+	p.settleProcessedAudio(ctx, track, metadata)
 	if !isNew && !hasCompleteAudioQuality(track) {
 		// A duplicate download resolves to the existing track and therefore must
 		// probe that track's referenced object, not the newly downloaded bytes.
@@ -192,17 +343,16 @@ func (p *Processor) Process(ctx context.Context, job *download.DownloadJob, prog
 			log.Printf("Warning: matching failed for job %s: %v", job.ID, err)
 		}
 	}
+	p.ingestArtwork(ctx, track.ID, metadata)
+	p.storeLyrics(ctx, track.ID, metadata)
+	p.storeChapters(ctx, track.ID, metadata)
+	p.storeSkipSegments(ctx, track.ID, isNew, metadata)
 	progress(80)
 
-	log.Printf("Processing job %s: adding to library", job.ID)
+	log.Printf("Processing job %s: queueing analysis", job.ID)
 	job.Status = download.StatusUploading
-	if err := p.addToLibrary(ctx, job.UserID, track.ID); err != nil {
-		log.Printf("Warning: failed to add track %d to library: %v", track.ID, err)
-	}
-	if err := p.attachPlaylistImportTrack(ctx, job, track.ID); err != nil {
-		return fmt.Errorf("playlist import attach failed: %w", err)
-	}
 	p.enqueueAnalysis(ctx, track, metadata)
+	p.enforceFormat(ctx, track, isNew)
 	progress(95)
 
 	log.Printf("Processing job %s: complete (track_id=%d, is_new=%v)", job.ID, track.ID, isNew)
@@ -223,11 +373,46 @@ type TrackMetadata struct {
 	FileSizeBytes   int64
 	AudioQuality    AudioQuality
 	PreselectedMBID string
-	Raw             map[string]interface{}
-	Cleanup         deterministicCleanup
+	EmbeddedArtwork []byte
+	FolderArtwork   []byte
+	Lyrics          string
+	LyricsSynced    bool
+	SearchAliases   []string
+	// PreselectedReleaseMBID and PreselectedArtistMBID accompany
+	// PreselectedMBID when a library manager or the file's tags supplied them.
+	PreselectedReleaseMBID string
+	PreselectedArtistMBID  string
+	// ContentSHA256 is the digest of the downloaded file, when the downloader
+	// computed one. AudioHash is the signature of its decoded audio, unset
+	// when hashing failed; see audioHash.
+	ContentSHA256 string
+	AudioHash     *string
+	// FieldSources names the source each field of a local file came from.
+	FieldSources map[string]string
+	// TenantID is the tenant the track is stored for, or 0 for the instance.
+	TenantID int64
+	// SkipSegments are non-music stretches of the source for playback to
+	// skip; TrimmedMs is how much was cut from the audio instead.
+	SkipSegments []db.SkipSegment
+	TrimmedMs    int
+	// SilenceTrim is the silence cut from the audio and
+	// LoudnessNormalization the gain applied to it, if any.
+	// OriginalStorageKey is where the unprocessed file was kept.
+	SilenceTrim           *db.SilenceTrim
+	LoudnessNormalization *db.LoudnessNormalization
+	OriginalStorageKey    string
+	OriginalSizeBytes     int64
+	// Quality is the measured quality of the stored audio, if measured.
+	// Mood is its mood features, set with MoodMeasured unless measuring
+	// failed; audio too quiet to rate is measured with nil features.
+	Quality      *db.TrackQuality
+	Mood         *db.MoodFeatures
+	MoodMeasured bool
+	Raw          map[string]interface{}
+	Cleanup      deterministicCleanup
 }
 
-func (p *Processor) downloadAndStore(ctx context.Context, job *download.DownloadJob) (*TrackMetadata, error) {
+func (p *Processor) downloadAndStore(ctx context.Context, job *download.DownloadJob, progress fetcher.ProgressFunc) (*TrackMetadata, error) {
 	if p.storage == nil {
 		return nil, fmt.Errorf("object storage is not configured")
 	}
@@ -257,12 +442,34 @@ func (p *Processor) downloadAndStore(ctx context.Context, job *download.Download
 	if job.MBRecordingID != nil {
 		metadata.PreselectedMBID = *job.MBRecordingID
 	}
+	library := job.Library()
+	if library != nil && metadata.PreselectedMBID != "" {
+		metadata.PreselectedReleaseMBID = library.MBReleaseID
+		metadata.PreselectedArtistMBID = library.MBArtistID
+	}
 
-	tmpPath, contentType, err := p.obtainAudioFile(ctx, job, metadata)
+	tmpPath, contentType, err := p.obtainAudioFile(ctx, job, metadata, progress)
 	if err != nil {
 		return nil, err
 	}
 	defer os.Remove(tmpPath)
+	if trimmed := p.findSkipSegments(ctx, job.ID, metadata, tmpPath); trimmed != "" {
+		defer os.Remove(trimmed)
+		tmpPath = trimmed
+	}
+	settings := p.downloadSettings(ctx, job)
+	originalPath, trimmedDurationMs := "", 0
+	if trimmed := p.trimSilence(ctx, job.ID, settings, metadata, tmpPath); trimmed != "" {
+		defer os.Remove(trimmed)
+		originalPath, tmpPath, trimmedDurationMs = tmpPath, trimmed, metadata.DurationMs
+	}
+	if normalized := p.normalizeLoudness(ctx, job.ID, settings, metadata, tmpPath); normalized != "" {
+		defer os.Remove(normalized)
+		if originalPath == "" && p.keepLoudnessOriginals {
+			originalPath = tmpPath
+		}
+		tmpPath = normalized
+	}
 
 	info, err := os.Stat(tmpPath)
 	if err != nil {
@@ -280,11 +487,65 @@ func (p *Processor) downloadAndStore(ctx context.Context, job *download.Download
 	if contentType == "" {
 		contentType = "application/octet-stream"
 	}
-	quality, err := probeAudioFile(ctx, tmpPath, contentType)
+	quality, tags, err := probeAudio(ctx, tmpPath, contentType)
 	if err != nil {
 		return nil, fmt.Errorf("probe downloaded audio: %w", err)
 	}
+	metadata.AudioHash = p.hashAudio(ctx, job.ID, tmpPath)
+	metadata.Quality = p.measureQuality(ctx, "job "+job.ID, quality, tmpPath)
+	metadata.Mood, metadata.MoodMeasured = p.measureMood(ctx, "job "+job.ID, tmpPath)
+	if path, ok := strings.CutPrefix(job.URL, "file://"); ok {
+		applyLocalMetadata(metadata, job, tags, readSidecars(path))
+	} else if job.SourceType == download.SourceTypeDirect || job.SourceType == download.SourceTypeRemote {
+		// A bare file has no page metadata; its own tags come first.
+		applyLocalMetadata(metadata, job, tags, sidecarMetadata{})
+	}
+	if trimmedDurationMs > 0 {
+		// Tags and sidecars give the untrimmed file's length.
+		metadata.DurationMs = trimmedDurationMs
+	}
+	artwork, err := images.ExtractEmbedded(file, info.Size())
+	switch {
+	case err == nil:
+		metadata.EmbeddedArtwork = artwork
+	case !errors.Is(err, images.ErrNoEmbeddedArtwork):
+		log.Printf("Warning: failed to read embedded artwork for job %s: %v", job.ID, err)
+	}
+	if library != nil && library.ArtworkPath != "" && strings.HasPrefix(job.URL, "file://") {
+		// The library manager's own choice of cover wins over a folder scan.
+		artwork, err := images.ReadArtworkFile(library.ArtworkPath)
+		if err != nil {
+			log.Printf("Warning: failed to read %s artwork for job %s: %v", library.Manager, job.ID, err)
+		}
+		metadata.FolderArtwork = artwork
+	}
+	if path, ok := strings.CutPrefix(job.URL, "file://"); ok && len(p.folderArtworkNames) > 0 && len(metadata.FolderArtwork) == 0 {
+		_, artwork, err := images.FindFolderArtwork(filepath.Dir(path), p.folderArtworkNames)
+		switch {
+		case err == nil:
+			metadata.FolderArtwork = artwork
+		case !errors.Is(err, images.ErrNoFolderArtwork):
+			log.Printf("Warning: failed to read folder artwork for job %s: %v", job.ID, err)
+		}
+	}
 	key := storageKey(job, tmpPath)
+	tenant, err := p.tenantFor(ctx, job.UserID)
+	if err != nil {
+		return nil, err
+	}
+	if tenant != nil {
+		// Stop before storing audio the tenant has no room for.
+		if err := p.tenants.CheckQuota(ctx, tenant.ID, info.Size()); err != nil {
+			return nil, fmt.Errorf("tenant %s: %w", tenant.Slug, err)
+		}
+		key = tenant.ObjectKey(key)
+		metadata.TenantID = tenant.ID
+	}
+	if originalPath != "" {
+		if err := p.keepOriginal(ctx, metadata, originalPath, key); err != nil {
+			return nil, err
+		}
+	}
 	if err := p.storage.PutObject(ctx, key, file, info.Size(), quality.ContentType); err != nil {
 		return nil, fmt.Errorf("upload audio to object storage: %w", err)
 	}
@@ -305,25 +566,34 @@ type AudioQuality struct {
 
 type ffprobeOutput struct {
 	Streams []struct {
-		CodecName  string `json:"codec_name"`
-		BitRate    string `json:"bit_rate"`
-		SampleRate string `json:"sample_rate"`
-		Channels   int    `json:"channels"`
+		CodecName  string            `json:"codec_name"`
+		BitRate    string            `json:"bit_rate"`
+		SampleRate string            `json:"sample_rate"`
+		Channels   int               `json:"channels"`
+		Tags       map[string]string `json:"tags"`
 	} `json:"streams"`
 	Format struct {
-		BitRate    string `json:"bit_rate"`
-		FormatName string `json:"format_name"`
+		BitRate    string            `json:"bit_rate"`
+		FormatName string            `json:"format_name"`
+		Tags       map[string]string `json:"tags"`
 	} `json:"format"`
 }
 
 func probeAudioFile(ctx context.Context, path, fallbackContentType string) (AudioQuality, error) {
+	quality, _, err := probeAudio(ctx, path, fallbackContentType)
+	return quality, err
+}
+
+// probeAudio reports the artifact facts for path along with the title, artist,
+// and album tags embedded in it.
+func probeAudio(ctx context.Context, path, fallbackContentType string) (AudioQuality, audioTags, error) {
 	probeCtx, cancel := context.WithTimeout(ctx, audioQualityProbeTimeout)
 	defer cancel()
 
 	cmd := exec.CommandContext(probeCtx, "ffprobe",
 		"-v", "error",
 		"-select_streams", "a:0",
-		"-show_entries", "stream=codec_name,bit_rate,sample_rate,channels:format=bit_rate,format_name",
+		"-show_entries", "stream=codec_name,bit_rate,sample_rate,channels:stream_tags:format=bit_rate,format_name:format_tags",
 		"-of", "json",
 		path,
 	)
@@ -333,16 +603,16 @@ func probeAudioFile(ctx context.Context, path, fallbackContentType string) (Audi
 	cmd.Stderr = &stderr
 	if err := cmd.Run(); err != nil {
 		if probeCtx.Err() != nil {
-			return AudioQuality{}, fmt.Errorf("ffprobe timed out or canceled: %w", probeCtx.Err())
+			return AudioQuality{}, audioTags{}, fmt.Errorf("ffprobe timed out or canceled: %w", probeCtx.Err())
 		}
-		return AudioQuality{}, fmt.Errorf("ffprobe failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+		return AudioQuality{}, audioTags{}, fmt.Errorf("ffprobe failed: %w: %s", err, strings.TrimSpace(stderr.String()))
 	}
 	var probed ffprobeOutput
 	if err := json.Unmarshal([]byte(stdout.String()), &probed); err != nil {
-		return AudioQuality{}, fmt.Errorf("decode ffprobe output: %w", err)
+		return AudioQuality{}, audioTags{}, fmt.Errorf("decode ffprobe output: %w", err)
 	}
 	if len(probed.Streams) == 0 {
-		return AudioQuality{}, errors.New("ffprobe found no audio stream")
+		return AudioQuality{}, audioTags{}, errors.New("ffprobe found no audio stream")
 	}
 	stream := probed.Streams[0]
 	sampleRate, _ := strconv.Atoi(stream.SampleRate)
@@ -358,9 +628,11 @@ func probeAudioFile(ctx context.Context, path, fallbackContentType string) (Audi
 		ContentType:  audioContentType(stream.CodecName, probed.Format.FormatName, fallbackContentType),
 	}
 	if quality.Codec == "" || quality.BitrateKbps <= 0 || quality.SampleRateHz <= 0 || quality.Channels <= 0 {
-		return AudioQuality{}, fmt.Errorf("ffprobe returned incomplete audio stream facts")
+		return AudioQuality{}, audioTags{}, fmt.Errorf("ffprobe returned incomplete audio stream facts")
 	}
-	return quality, nil
+	tags := ffprobeTags(probed.Format.Tags)
+	tags.merge(ffprobeTags(stream.Tags))
+	return quality, tags, nil
 }
 
 func audioContentType(codec, formatName, fallback string) string {
@@ -391,18 +663,47 @@ func audioContentType(codec, formatName, fallback string) string {
 	return "application/octet-stream"
 }
 
-func (p *Processor) obtainAudioFile(ctx context.Context, job *download.DownloadJob, metadata *TrackMetadata) (string, string, error) {
-	if strings.HasPrefix(job.URL, "fixture://") || job.SourceType == "fixture" {
-		return writeFixtureWAV(job.ID)
+// obtainAudioFile fetches the job's audio with the downloader for its source
+// and merges what the source reported into metadata.
+func (p *Processor) obtainAudioFile(ctx context.Context, job *download.DownloadJob, metadata *TrackMetadata, progress fetcher.ProgressFunc) (string, string, error) {
+	downloaders := p.downloaders
+	if downloaders == nil {
+		downloaders = DefaultDownloaders()
 	}
-	if strings.HasPrefix(job.URL, "file://") {
-		path := strings.TrimPrefix(job.URL, "file://")
-		if path == "" {
-			return "", "", fmt.Errorf("empty file URL")
-		}
-		return copyToBoundedTemp(path, 256*1024*1024)
+	req := fetcher.Request{JobID: job.ID, SourceType: job.SourceType, URL: job.URL, Headers: job.RequestHeaders()}
+	credential := p.sourceCredential(ctx, job)
+	if credential != nil {
+		req.Cookies = credential.Cookies
+	}
+	result, err := downloaders.Fetch(ctx, req, progress)
+	if credential != nil {
+		p.sourceCredentials.Report(ctx, credential, err)
+	}
+	if err != nil {
+		return "", "", err
+	}
+	applyFetchedMetadata(metadata, result.Metadata)
+	metadata.ContentSHA256 = result.SHA256
+	return result.Path, result.ContentType, nil
+}
+
+// sourceCredential returns the cookies the job's requester uploaded for its
+// source, or nil. A lookup failure is logged and the download goes ahead
+// without a login.
+func (p *Processor) sourceCredential(ctx context.Context, job *download.DownloadJob) *sourceauth.Credential {
+	if p.sourceCredentials == nil {
+		return nil
 	}
-	return runYTDLP(ctx, job.URL, metadata)
+	userID, err := uuid.Parse(job.UserID)
+	if err != nil {
+		return nil
+	}
+	credential, err := p.sourceCredentials.ForDownload(ctx, userID, job.URL)
+	if err != nil {
+		log.Printf("Warning: failed to look up source cookies for job %s: %v", job.ID, err)
+		return nil
+	}
+	return credential
 }
 
 func writeFixtureWAV(jobID string) (string, string, error) {
@@ -486,11 +787,14 @@ func copyToBoundedTemp(source string, maxBytes int64) (string, string, error) {
 	return outPath, mime.TypeByExtension(filepath.Ext(source)), nil
 }
 
-func runYTDLP(ctx context.Context, sourceURL string, metadata *TrackMetadata) (string, string, error) {
-	return runYTDLPCommand(ctx, "yt-dlp", sourceURL, metadata, maxYTDLPOutputBytes)
+func runYTDLPCommand(ctx context.Context, executable, sourceURL string, metadata *TrackMetadata, maxBytes int64) (string, string, error) {
+	return fetchYTDLP(ctx, executable, sourceURL, nil, metadata, maxBytes, nil)
 }
 
-func runYTDLPCommand(ctx context.Context, executable, sourceURL string, metadata *TrackMetadata, maxBytes int64) (string, string, error) {
+// fetchYTDLP downloads sourceURL as mp3, filling metadata from yt-dlp's info
+// JSON and reporting download progress parsed from its output. extraArgs,
+// such as a cookies file or proxy, go before the URL.
+func fetchYTDLP(ctx context.Context, executable, sourceURL string, extraArgs []string, metadata *TrackMetadata, maxBytes int64, progress fetcher.ProgressFunc) (string, string, error) {
 	if _, err := exec.LookPath(executable); err != nil {
 		return "", "", fmt.Errorf("yt-dlp is not installed")
 	}
@@ -501,12 +805,18 @@ func runYTDLPCommand(ctx context.Context, executable, sourceURL string, metadata
 	defer os.RemoveAll(dir)
 
 	outputTemplate := filepath.Join(dir, "audio.%(ext)s")
-	cmd := exec.CommandContext(ctx, executable, "--no-playlist", "--max-filesize", fmt.Sprintf("%d", maxBytes), "--extract-audio", "--audio-format", "mp3", "--write-info-json", "--no-progress", "-o", outputTemplate, sourceURL)
+	args := []string{"--no-playlist", "--max-filesize", fmt.Sprintf("%d", maxBytes), "--extract-audio", "--audio-format", "mp3", "--write-info-json",
+		"--newline", "--progress-template", "download:" + ytdlpProgressPrefix + "%(progress._percent_str)s", "-o", outputTemplate}
+	args = append(args, extraArgs...)
+	cmd := exec.CommandContext(ctx, executable, append(args, sourceURL)...)
 	var output limitedOutput
 	output.limit = maxYTDLPLogBytes
-	cmd.Stdout = &output
-	cmd.Stderr = &output
-	if err := cmd.Run(); err != nil {
+	progressOutput := &ytdlpProgressWriter{log: &output, progress: progress}
+	cmd.Stdout = progressOutput
+	cmd.Stderr = progressOutput
+	err = cmd.Run()
+	progressOutput.Flush()
+	if err != nil {
 		return "", "", fmt.Errorf("yt-dlp failed: %w: %s", err, strings.TrimSpace(output.String()))
 	}
 	return collectYTDLPOutput(dir, metadata, maxBytes)
@@ -581,6 +891,12 @@ func populateMetadataFromInfo(path string, metadata *TrackMetadata) {
 	if err := json.Unmarshal(data, &raw); err != nil {
 		return
 	}
+	applyYTDLPInfo(raw, metadata)
+}
+
+// applyYTDLPInfo fills metadata from a yt-dlp info document, keeping existing
+// values where the document has none.
+func applyYTDLPInfo(raw map[string]interface{}, metadata *TrackMetadata) {
 	metadata.Raw = raw
 	metadata.Title = firstNonEmpty(stringValue(raw, "title"), metadata.Title)
 	metadata.Artist = firstNonEmpty(stringValue(raw, "artist"), stringValue(raw, "uploader"), metadata.Artist)
@@ -650,6 +966,19 @@ func providerMetadata(metadata *TrackMetadata) map[string]interface{} {
 	if metadata.SourceType != "" {
 		provider["source_type"] = metadata.SourceType
 	}
+	if metadata.ContentSHA256 != "" {
+		provider["content_sha256"] = metadata.ContentSHA256
+	}
+	if metadata.TrimmedMs > 0 {
+		provider["trimmed_ms"] = metadata.TrimmedMs
+	}
+	if trim := metadata.SilenceTrim; trim != nil {
+		provider["silence_trimmed_ms"] = trim.LeadingMs + trim.TrailingMs
+	}
+	if normalization := metadata.LoudnessNormalization; normalization != nil {
+		provider["loudness_input_lufs"] = normalization.InputLUFS
+		provider["loudness_target_lufs"] = normalization.TargetLUFS
+	}
 	return provider
 }
 
@@ -668,6 +997,9 @@ func metadataProvenance(metadata *TrackMetadata, cleanup deterministicCleanup) j
 		"raw_provider":  providerMetadata(metadata),
 		"deterministic": cleanup,
 	}
+	if len(metadata.FieldSources) > 0 {
+		payload["field_sources"] = metadata.FieldSources
+	}
 	encoded, _ := json.Marshal(payload)
 	return encoded
 }
@@ -692,8 +1024,85 @@ func sanitizeKeyPart(value string) string {
 	return strings.Trim(value, "-")
 }
 
+// settleDuplicateObject keeps a shared track stored once after a download
+// resolved to it: the new object becomes the track's audio when it has none,
+// and is deleted otherwise.
+func (p *Processor) settleDuplicateObject(ctx context.Context, track *db.Track, metadata *TrackMetadata) {
+	if metadata.StorageKey == "" || metadata.StorageKey == track.StorageKey.String {
+		return
+	}
+	if !track.StorageKey.Valid {
+		attached, err := p.trackRepo.AttachStorage(ctx, track.ID, metadata.StorageKey, metadata.FileSizeBytes)
+		if err != nil {
+			log.Printf("Warning: failed to attach stored audio to track %d: %v", track.ID, err)
+			return
+		}
+		if attached {
+			track.StorageKey = sql.NullString{String: metadata.StorageKey, Valid: true}
+			track.FileSizeBytes = sql.NullInt64{Int64: metadata.FileSizeBytes, Valid: metadata.FileSizeBytes > 0}
+			return
+		}
+	}
+	if err := p.storage.DeleteObject(ctx, metadata.StorageKey); err != nil {
+		log.Printf("Warning: failed to delete duplicate object %s for track %d: %v", metadata.StorageKey, track.ID, err)
+	}
+}
+
+// tenantFor returns the tenant of the user who queued a download, or nil for
+// instance users and jobs without a user.
+func (p *Processor) tenantFor(ctx context.Context, userID string) (*db.Tenant, error) {
+	if p.tenants == nil {
+		return nil, nil
+	}
+	id, err := uuid.Parse(userID)
+	if err != nil {
+		return nil, nil
+	}
+	membership, err := p.tenants.ForUser(ctx, id)
+	if err != nil {
+		return nil, fmt.Errorf("look up tenant: %w", err)
+	}
+	if membership == nil {
+		return nil, nil
+	}
+	return &membership.Tenant, nil
+}
+
+// storeTrack creates or retrieves the track record and files it in the
+// requester's library, the playlist import the job belongs to and the inbox
+// playlist named on the job, in one transaction, so a failure partway leaves
+// none of those rows behind.
+func (p *Processor) storeTrack(ctx context.Context, job *download.DownloadJob, metadata *TrackMetadata, inbox bool) (*db.Track, bool, error) {
+	if p.database == nil {
+		return nil, false, errors.New("processor has no database")
+	}
+	history := db.DefaultPlaylistHistoryPolicy
+	if p.playlistRepo != nil {
+		history = p.playlistRepo.HistoryPolicy()
+	}
+	var track *db.Track
+	var isNew bool
+	err := p.database.WithTx(ctx, history, func(tx *db.Tx) error {
+		var err error
+		if track, isNew, err = p.createTrack(ctx, tx, metadata); err != nil {
+			return err
+		}
+		if err := p.addToLibrary(ctx, tx, job.UserID, track.ID, inbox); err != nil {
+			return fmt.Errorf("add to library: %w", err)
+		}
+		if err := p.attachPlaylistImportTrack(ctx, tx, job, track.ID); err != nil {
+			return fmt.Errorf("playlist import attach failed: %w", err)
+		}
+		return p.addToInbox(ctx, tx, job, track.ID)
+	})
+	if err != nil {
+		return nil, false, err
+	}
+	return track, isNew, nil
+}
+
 // createTrack creates or retrieves the track record
-func (p *Processor) createTrack(ctx context.Context, job *download.DownloadJob, metadata *TrackMetadata) (*db.Track, bool, error) {
+func (p *Processor) createTrack(ctx context.Context, tx *db.Tx, metadata *TrackMetadata) (*db.Track, bool, error) {
 	cleanup := applyDeterministicCleanup(metadata)
 	metadata.Cleanup = cleanup
 	provenance := metadataProvenance(metadata, cleanup)
@@ -715,20 +1124,68 @@ func (p *Processor) createTrack(ctx context.Context, job *download.DownloadJob,
 		),
 		db.WithMetadata(provenance),
 		db.WithMetadataEnrichment(status, confidence, provenance, ""),
+		db.WithSearchAliases(metadata.SearchAliases...),
+	}
+	if metadata.TenantID != 0 {
+		opts = append(opts, db.WithTenant(metadata.TenantID))
+	}
+	if metadata.AudioHash != nil {
+		opts = append(opts, db.WithAudioHash(*metadata.AudioHash))
 	}
 
 	if metadata.PreselectedMBID != "" {
 		mbid, err := uuid.Parse(metadata.PreselectedMBID)
 		if err == nil {
-			opts = append(opts, db.WithMusicBrainzIDs(&mbid, nil, nil))
+			opts = append(opts, db.WithMusicBrainzIDs(&mbid, optionalUUID(metadata.PreselectedReleaseMBID), optionalUUID(metadata.PreselectedArtistMBID)))
 		}
 	}
 
-	track, isNew, err := p.trackRepo.CreateTrackFromMetadata(ctx, metadata.Artist, metadata.Title, metadata.Album, metadata.DurationMs, opts...)
-	if err != nil {
-		return nil, false, err
+	if track := p.findByAudio(ctx, metadata); track != nil {
+		// Another encoding of a stored recording, perhaps tagged differently.
+		return track, false, nil
 	}
-	return track, isNew, nil
+	return tx.CreateTrackFromMetadata(ctx, metadata.Artist, metadata.Title, metadata.Album, metadata.DurationMs, opts...)
+}
+
+// recordNewTrackDetails records where a new track's fields came from and its
+// artist credits, once the track is committed.
+func (p *Processor) recordNewTrackDetails(ctx context.Context, track *db.Track, metadata *TrackMetadata) {
+	sources := fieldProvenanceSources(metadata)
+	if err := p.trackRepo.RecordFieldSources(ctx, track.ID, sources); err != nil {
+		log.Printf("Warning: failed to record field sources for track %d: %v", track.ID, err)
+	}
+	credits := db.ParseArtistCredits(metadata.Artist, metadata.Title)
+	if err := p.trackRepo.ReplaceArtistCredits(ctx, track.ID, credits, sources["artist"]); err != nil {
+		log.Printf("Warning: failed to store artist credits for track %d: %v", track.ID, err)
+	}
+}
+
+// fieldProvenanceSources maps the sources that settled a new track's fields
+// onto the ranked provenance sources: sidecars and embedded tags count as tags,
+// everything else as the provider. Downloads without per-field sources are
+// entirely provider metadata.
+func fieldProvenanceSources(metadata *TrackMetadata) map[string]string {
+	if len(metadata.FieldSources) == 0 {
+		return map[string]string{
+			"title":       db.FieldSourceProvider,
+			"artist":      db.FieldSourceProvider,
+			"album":       db.FieldSourceProvider,
+			"duration_ms": db.FieldSourceProvider,
+		}
+	}
+	sources := make(map[string]string, len(metadata.FieldSources))
+	for field, source := range metadata.FieldSources {
+		if field == "duration" {
+			field = "duration_ms"
+		}
+		switch source {
+		case metadataSourceNFO, metadataSourceInfoJSON, metadataSourceLRC, metadataSourceEmbedded:
+			sources[field] = db.FieldSourceTag
+		default:
+			sources[field] = db.FieldSourceProvider
+		}
+	}
+	return sources
 }
 
 // AudioQualityRepairResult reports one idempotent stored-artifact probe.
@@ -760,38 +1217,12 @@ func (p *Processor) RepairAudioQuality(ctx context.Context, track *db.Track) (Au
 		return AudioQualityRepairResult{}, fmt.Errorf("record audio quality probe attempt: %w", err)
 	}
 
-	reader, info, err := p.storage.GetObject(repairCtx, storageKey)
-	if err != nil {
-		return AudioQualityRepairResult{}, fmt.Errorf("get stored audio object: %w", err)
-	}
-	defer reader.Close()
-	if info != nil && info.Size > maxYTDLPOutputBytes {
-		return AudioQualityRepairResult{}, fmt.Errorf("stored audio object too large: %d bytes", info.Size)
-	}
-
-	ext := filepath.Ext(storageKey)
-	tmp, err := os.CreateTemp("", "omp-quality-backfill-*"+ext)
+	tmpPath, contentType, err := p.fetchStoredAudio(repairCtx, storageKey, "omp-quality-backfill-*")
 	if err != nil {
 		return AudioQualityRepairResult{}, err
 	}
-	tmpPath := tmp.Name()
 	defer os.Remove(tmpPath)
-	written, copyErr := io.Copy(tmp, io.LimitReader(reader, maxYTDLPOutputBytes+1))
-	closeErr := tmp.Close()
-	if copyErr != nil {
-		return AudioQualityRepairResult{}, fmt.Errorf("copy stored audio object: %w", copyErr)
-	}
-	if closeErr != nil {
-		return AudioQualityRepairResult{}, fmt.Errorf("close stored audio object: %w", closeErr)
-	}
-	if written > maxYTDLPOutputBytes {
-		return AudioQualityRepairResult{}, fmt.Errorf("stored audio object exceeds %d bytes", maxYTDLPOutputBytes)
-	}
 
-	contentType := ""
-	if info != nil {
-		contentType = info.ContentType
-	}
 	quality, err := probeAudioFile(repairCtx, tmpPath, contentType)
 	if err != nil {
 		return AudioQualityRepairResult{}, err
@@ -810,6 +1241,46 @@ func (p *Processor) RepairAudioQuality(ctx context.Context, track *db.Track) (Au
 	return AudioQualityRepairResult{Status: "processed", Quality: quality}, nil
 }
 
+// fetchStoredAudio copies the stored object at storageKey to a temporary file
+// named after pattern and returns its path, which the caller removes, and the
+// object's content type.
+func (p *Processor) fetchStoredAudio(ctx context.Context, storageKey, pattern string) (string, string, error) {
+	reader, info, err := p.storage.GetObject(ctx, storageKey)
+	if err != nil {
+		return "", "", fmt.Errorf("get stored audio object: %w", err)
+	}
+	defer reader.Close()
+	if info != nil && info.Size > maxYTDLPOutputBytes {
+		return "", "", fmt.Errorf("stored audio object too large: %d bytes", info.Size)
+	}
+
+	tmp, err := os.CreateTemp("", pattern+filepath.Ext(storageKey))
+	if err != nil {
+		return "", "", err
+	}
+	tmpPath := tmp.Name()
+	written, copyErr := io.Copy(tmp, io.LimitReader(reader, maxYTDLPOutputBytes+1))
+	closeErr := tmp.Close()
+	switch {
+	case copyErr != nil:
+		err = fmt.Errorf("copy stored audio object: %w", copyErr)
+	case closeErr != nil:
+		err = fmt.Errorf("close stored audio object: %w", closeErr)
+	case written > maxYTDLPOutputBytes:
+		err = fmt.Errorf("stored audio object exceeds %d bytes", maxYTDLPOutputBytes)
+	}
+	if err != nil {
+		os.Remove(tmpPath)
+		return "", "", err
+	}
+
+	contentType := ""
+	if info != nil {
+		contentType = info.ContentType
+	}
+	return tmpPath, contentType, nil
+}
+
 func hasCompleteAudioQuality(track *db.Track) bool {
 	return track.Codec.Valid && strings.TrimSpace(track.Codec.String) != "" &&
 		track.BitrateKbps.Valid && track.BitrateKbps.Int32 > 0 &&
@@ -848,7 +1319,13 @@ func (p *Processor) runMatching(ctx context.Context, track *db.Track, metadata *
 		return fmt.Errorf("matching failed: %w", err)
 	}
 	update := automaticMBMatchUpdate(output)
-	return p.trackRepo.UpdateMBMatch(ctx, track.ID, update)
+	if err := p.trackRepo.UpdateMBMatch(ctx, track.ID, update); err != nil {
+		return err
+	}
+	if update.MBRecordingID != nil {
+		p.syncMBRecording(ctx, track.ID, *update.MBRecordingID)
+	}
+	return nil
 }
 
 func failedMBMatchUpdate(matchErr error) *db.MBMatchUpdate {
@@ -896,6 +1373,7 @@ func automaticMBMatchUpdate(output *matcher.MatchOutput) *db.MBMatchUpdate {
 			update.Album = output.BestMatch.Album
 			update.DurationMs = output.BestMatch.Duration
 			update.CoverArtURL = output.BestMatch.CoverArtURL
+			update.ReleaseDate, _ = db.ParsePartialDate(output.BestMatch.ReleaseDate)
 		} else {
 			update.MetadataStatus = "suggested"
 		}
@@ -923,23 +1401,108 @@ func automaticMBMatchUpdate(output *matcher.MatchOutput) *db.MBMatchUpdate {
 	return update
 }
 
-// addToLibrary adds the track to the user's library
-func (p *Processor) addToLibrary(ctx context.Context, userID string, trackID int64) error {
+// ingestArtwork renders the track's cover art once matching has settled it.
+// Art embedded in the file wins, then a sidecar image beside it, which also
+// covers the rest of the album; the matched cover art URL is only a fallback.
+// Artwork is cosmetic, so failures are logged and never fail the job.
+func (p *Processor) ingestArtwork(ctx context.Context, trackID int64, metadata *TrackMetadata) {
+	if p.artwork == nil {
+		return
+	}
+	linked := false
+	if len(metadata.EmbeddedArtwork) > 0 {
+		if _, err := p.artwork.IngestEmbeddedArtwork(ctx, trackID, metadata.EmbeddedArtwork); err != nil {
+			log.Printf("Warning: embedded artwork processing failed for track %d: %v", trackID, err)
+		} else {
+			linked = true
+		}
+	}
+	if len(metadata.FolderArtwork) > 0 {
+		_, err := p.artwork.IngestFolderArtwork(ctx, trackID, metadata.FolderArtwork)
+		switch {
+		case err == nil:
+			linked = true
+		case !errors.Is(err, db.ErrArtworkNotLinked):
+			log.Printf("Warning: folder artwork processing failed for track %d: %v", trackID, err)
+		}
+	}
+	if linked {
+		return
+	}
+	track, err := p.trackRepo.GetByID(ctx, trackID)
+	if err != nil {
+		log.Printf("Warning: failed to load track %d for artwork: %v", trackID, err)
+		return
+	}
+	coverURL := ""
+	if track.CoverArtURL.Valid {
+		coverURL = strings.TrimSpace(track.CoverArtURL.String)
+	}
+	_, err = p.artwork.IngestTrackArtwork(ctx, trackID, coverURL)
+	if err != nil && !errors.Is(err, db.ErrArtworkNotFound) && !errors.Is(err, db.ErrArtworkNotLinked) {
+		log.Printf("Warning: artwork processing failed for track %d: %v", trackID, err)
+	}
+}
+
+// storeLyrics saves lyrics found beside a local file. Like artwork, lyrics are
+// supplementary, so failures are logged and never fail the job.
+func (p *Processor) storeLyrics(ctx context.Context, trackID int64, metadata *TrackMetadata) {
+	if p.lyrics == nil || metadata.Lyrics == "" {
+		return
+	}
+	err := p.lyrics.Upsert(ctx, &db.Lyrics{
+		TrackID: trackID,
+		Text:    metadata.Lyrics,
+		Synced:  metadata.LyricsSynced,
+		Source:  db.LyricsSourceLRC,
+	})
+	if err != nil {
+		log.Printf("Warning: failed to store lyrics for track %d: %v", trackID, err)
+	}
+}
+
+// storeChapters saves the tracklist a long mix's source lists, so the mix can
+// be split later. Failures are logged and never fail the job.
+func (p *Processor) storeChapters(ctx context.Context, trackID int64, metadata *TrackMetadata) {
+	// Chapter times are offsets into the source, which trimmed audio no
+	// longer matches.
+	if p.chapters == nil || metadata.TrimmedMs > 0 || metadata.SilenceTrim != nil {
+		return
+	}
+	found := chapters.FromInfo(metadata.Raw, metadata.DurationMs)
+	if len(found) == 0 {
+		return
+	}
+	if err := p.chapters.Save(ctx, trackID, found); err != nil {
+		log.Printf("Warning: failed to store chapters for track %d: %v", trackID, err)
+	}
+}
+
+// addToLibrary adds the track to the user's library, in the library inbox
+// when inbox is set. Jobs without a user and tracks out of the user's reach
+// are logged and skipped.
+func (p *Processor) addToLibrary(ctx context.Context, tx *db.Tx, userID string, trackID int64, inbox bool) error {
 	if p.libraryRepo == nil {
 		return nil
 	}
 	userUUID, err := uuid.Parse(userID)
 	if err != nil {
-		return fmt.Errorf("invalid user ID: %w", err)
+		log.Printf("Warning: not adding track %d to a library: invalid user ID %q", trackID, userID)
+		return nil
 	}
-	_, err = p.libraryRepo.AddTrackToLibrary(ctx, userUUID, trackID)
-	if err != nil {
-		if err == db.ErrTrackAlreadyInLibrary {
-			return nil
-		}
-		return err
+	add := tx.AddTrackToLibrary
+	if inbox {
+		add = tx.AddTrackToInbox
 	}
-	return nil
+	_, err = add(ctx, userUUID, trackID)
+	switch {
+	case errors.Is(err, db.ErrTrackAlreadyInLibrary):
+		return nil
+	case errors.Is(err, db.ErrTrackNotFound):
+		log.Printf("Warning: track %d is out of reach of user %s's library", trackID, userID)
+		return nil
+	}
+	return err
 }
 
 func (p *Processor) recordTrackSource(ctx context.Context, job *download.DownloadJob, trackID int64) {
@@ -951,36 +1514,40 @@ func (p *Processor) recordTrackSource(ctx context.Context, job *download.Downloa
 	}
 }
 
-func (p *Processor) attachPlaylistImportTrack(ctx context.Context, job *download.DownloadJob, trackID int64) error {
+func (p *Processor) attachPlaylistImportTrack(ctx context.Context, tx *db.Tx, job *download.DownloadJob, trackID int64) error {
 	if job == nil || job.PlaylistImportItemID == 0 {
 		return nil
 	}
 	if p.importRepo != nil {
-		return p.importRepo.CompletePlaylistImportItem(ctx, job.PlaylistImportItemID, trackID)
+		return p.importRepo.CompletePlaylistImportItemTx(ctx, tx, job.PlaylistImportItemID, trackID)
 	}
 	if p.playlistSourceRepo != nil {
-		return p.playlistSourceRepo.CompletePlaylistImportItem(ctx, job.PlaylistImportItemID, trackID)
+		return tx.CompletePlaylistImportItem(ctx, job.PlaylistImportItemID, trackID)
 	}
 	if p.playlistRepo != nil && job.PlaylistID != 0 {
-		if err := p.playlistRepo.AddTrackAtPosition(ctx, job.PlaylistID, trackID, job.PlaylistPosition); err != nil && !errors.Is(err, db.ErrTrackAlreadyInPlaylist) {
+		if err := tx.AddTrackToPlaylistAtPosition(ctx, job.PlaylistID, trackID, job.PlaylistPosition); err != nil && !errors.Is(err, db.ErrTrackAlreadyInPlaylist) {
 			return err
 		}
 	}
-	if p.importRepo != nil {
-		if err := p.importRepo.MarkItemImported(ctx, job.PlaylistImportItemID, trackID); err != nil {
-			return err
-		}
-		if job.PlaylistImportJobID != "" {
-			if importJobID, err := uuid.Parse(job.PlaylistImportJobID); err == nil {
-				if err := p.importRepo.RefreshJobCounts(ctx, importJobID); err != nil {
-					return err
-				}
-			}
-		}
-	}
 	return nil
 }
 
+// addToInbox appends a quick-added download's track to the inbox playlist
+// named on the job. An inbox playlist deleted since is logged and skipped;
+// the track stays in the library.
+func (p *Processor) addToInbox(ctx context.Context, tx *db.Tx, job *download.DownloadJob, trackID int64) error {
+	playlistID := job.InboxPlaylistID()
+	if p.playlistRepo == nil || playlistID == 0 {
+		return nil
+	}
+	_, err := tx.AddTracksToPlaylist(ctx, playlistID, []int64{trackID})
+	if errors.Is(err, db.ErrPlaylistNotFound) {
+		log.Printf("Warning: inbox playlist %d of track %d no longer exists", playlistID, trackID)
+		return nil
+	}
+	return err
+}
+
 func (p *Processor) markPlaylistImportFailed(ctx context.Context, job *download.DownloadJob, jobErr error) {
 	if p.importRepo == nil || job == nil || job.PlaylistImportItemID == 0 || jobErr == nil {
 		return
@@ -1337,6 +1904,15 @@ func firstNonEmpty(values ...string) string {
 	return ""
 }
 
+// optionalUUID parses value, returning nil when it is empty or malformed.
+func optionalUUID(value string) *uuid.UUID {
+	id, err := uuid.Parse(strings.TrimSpace(value))
+	if err != nil {
+		return nil
+	}
+	return &id
+}
+
 func stringValue(raw map[string]interface{}, key string) string {
 	if v, ok := raw[key].(string); ok {
 		return v
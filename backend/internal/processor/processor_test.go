@@ -10,6 +10,7 @@ import (
 	"io"
 	"os"
 	"path/filepath"
+	"slices"
 	"strings"
 	"sync"
 	"testing"
@@ -32,6 +33,7 @@ type fakeObjectStorage struct {
 	data        []byte
 	objects     map[string][]byte
 	getKeys     []string
+	deleted     []string
 }
 
 func (s *fakeObjectStorage) PutObject(ctx context.Context, key string, reader io.Reader, size int64, contentType string) error {
@@ -41,6 +43,11 @@ func (s *fakeObjectStorage) PutObject(ctx context.Context, key string, reader io
 	return nil
 }
 
+func (s *fakeObjectStorage) DeleteObject(ctx context.Context, key string) error {
+	s.deleted = append(s.deleted, key)
+	return nil
+}
+
 func (s *fakeObjectStorage) GetObject(ctx context.Context, key string) (io.ReadCloser, *storage.ObjectInfo, error) {
 	s.getKeys = append(s.getKeys, key)
 	if data, ok := s.objects[key]; ok {
@@ -1007,7 +1014,7 @@ func TestDuplicateLegacyTrackBackfillsFromExistingReferencedObject(t *testing.T)
 	objectStore := &fakeObjectStorage{objects: map[string][]byte{
 		"tracks/fixture/existing.wav": existingBytes,
 	}}
-	p := New(&ProcessorConfig{TrackRepo: trackRepo, Storage: objectStore})
+	p := New(&ProcessorConfig{DB: database, TrackRepo: trackRepo, Storage: objectStore})
 	job := &download.DownloadJob{
 		ID:         "new-differing-artifact",
 		URL:        "fixture://duplicate-legacy",
@@ -1028,6 +1035,37 @@ func TestDuplicateLegacyTrackBackfillsFromExistingReferencedObject(t *testing.T)
 	if len(objectStore.getKeys) != 1 || objectStore.getKeys[0] != "tracks/fixture/existing.wav" {
 		t.Fatalf("duplicate backfill probed keys = %v, want existing referenced object", objectStore.getKeys)
 	}
+	if reloaded.StorageKey.String != "tracks/fixture/existing.wav" || len(objectStore.deleted) != 1 || objectStore.deleted[0] != objectStore.key {
+		t.Fatalf("shared track storage = %q, deleted %v; want existing object kept and duplicate %q deleted",
+			reloaded.StorageKey.String, objectStore.deleted, objectStore.key)
+	}
+}
+
+func TestDuplicateTrackWithoutStoredAudioAdoptsDownload(t *testing.T) {
+	database, ctx := newProcessorPostgresTestDB(t)
+	trackRepo := db.NewTrackRepository(database)
+	existing, created, err := trackRepo.CreateTrackFromMetadata(ctx, "", "Metadata Only", "", 0)
+	if err != nil || !created {
+		t.Fatalf("seed metadata-only track: created=%v err=%v", created, err)
+	}
+	objectStore := &fakeObjectStorage{}
+	p := New(&ProcessorConfig{DB: database, TrackRepo: trackRepo, Storage: objectStore})
+	job := &download.DownloadJob{
+		ID:         "adopted-artifact",
+		URL:        "fixture://metadata-only",
+		SourceType: "fixture",
+		Title:      "Metadata Only",
+	}
+	if err := p.Process(ctx, job, func(int) {}); err != nil {
+		t.Fatalf("process duplicate: %v", err)
+	}
+	reloaded, err := trackRepo.GetByID(ctx, existing.ID)
+	if err != nil {
+		t.Fatalf("reload track: %v", err)
+	}
+	if reloaded.StorageKey.String != objectStore.key || len(objectStore.deleted) != 0 {
+		t.Fatalf("storage key = %q, deleted %v; want downloaded object %q adopted", reloaded.StorageKey.String, objectStore.deleted, objectStore.key)
+	}
 }
 
 func TestDuplicateLegacyTrackWithMissingObjectStillAttachesToLibrary(t *testing.T) {
@@ -1052,6 +1090,7 @@ func TestDuplicateLegacyTrackWithMissingObjectStillAttachesToLibrary(t *testing.
 	}
 	objectStore := &fakeObjectStorage{objects: map[string][]byte{}}
 	p := New(&ProcessorConfig{
+		DB:          database,
 		TrackRepo:   trackRepo,
 		LibraryRepo: db.NewLibraryRepository(database),
 		Storage:     objectStore,
@@ -1090,6 +1129,47 @@ func TestDuplicateLegacyTrackWithMissingObjectStillAttachesToLibrary(t *testing.
 	}
 }
 
+func TestProcessRollsBackTrackAndLibraryWhenPlaylistAttachFails(t *testing.T) {
+	database, ctx := newProcessorPostgresTestDB(t)
+	userID := uuid.New()
+	if _, err := database.ExecContext(ctx, `
+		INSERT INTO users (id, email, username, password_hash)
+		VALUES ($1, $2, 'rolled-back', 'x')
+	`, userID, "rolled-back-"+userID.String()+"@example.test"); err != nil {
+		t.Fatalf("seed user: %v", err)
+	}
+	p := New(&ProcessorConfig{
+		DB:           database,
+		TrackRepo:    db.NewTrackRepository(database),
+		LibraryRepo:  db.NewLibraryRepository(database),
+		PlaylistRepo: db.NewPlaylistRepository(database),
+		Storage:      &fakeObjectStorage{},
+	})
+	job := &download.DownloadJob{
+		ID:                   "deleted-playlist-import",
+		UserID:               userID.String(),
+		URL:                  "fixture://rolled-back",
+		SourceType:           "fixture",
+		Title:                "Rolled Back",
+		PlaylistImportItemID: 1,
+		PlaylistID:           999999,
+	}
+
+	if err := p.Process(ctx, job, func(int) {}); err == nil {
+		t.Fatal("process into a missing playlist succeeded, want error")
+	}
+	var tracks, libraryEntries int
+	if err := database.QueryRowContext(ctx, `SELECT COUNT(*) FROM tracks WHERE title = 'Rolled Back'`).Scan(&tracks); err != nil {
+		t.Fatalf("count tracks: %v", err)
+	}
+	if err := database.QueryRowContext(ctx, `SELECT COUNT(*) FROM user_library WHERE user_id = $1`, userID).Scan(&libraryEntries); err != nil {
+		t.Fatalf("count library entries: %v", err)
+	}
+	if tracks != 0 || libraryEntries != 0 {
+		t.Fatalf("after failed attach: %d tracks, %d library entries; want the whole store rolled back", tracks, libraryEntries)
+	}
+}
+
 func TestAttachPlaylistImportTrackBackfillsSourceEntryIdempotently(t *testing.T) {
 	database, ctx := newProcessorPostgresTestDB(t)
 	userID := uuid.New()
@@ -1171,10 +1251,15 @@ func TestAttachPlaylistImportTrackBackfillsSourceEntryIdempotently(t *testing.T)
 		PlaylistID:           playlist.ID,
 		PlaylistPosition:     0,
 	}
-	if err := processor.attachPlaylistImportTrack(ctx, job, track.ID); err != nil {
+	attach := func(job *download.DownloadJob, trackID int64) error {
+		return database.WithTx(ctx, db.DefaultPlaylistHistoryPolicy, func(tx *db.Tx) error {
+			return processor.attachPlaylistImportTrack(ctx, tx, job, trackID)
+		})
+	}
+	if err := attach(job, track.ID); err != nil {
 		t.Fatalf("complete queued import item: %v", err)
 	}
-	if err := processor.attachPlaylistImportTrack(ctx, job, track.ID); err != nil {
+	if err := attach(job, track.ID); err != nil {
 		t.Fatalf("retry queued import item: %v", err)
 	}
 	importRepo := playlistimport.NewImportRepository(database)
@@ -1243,7 +1328,7 @@ func TestAttachPlaylistImportTrackBackfillsSourceEntryIdempotently(t *testing.T)
 		PlaylistID:           playlist.ID,
 		PlaylistPosition:     1,
 	}
-	if err := processor.attachPlaylistImportTrack(ctx, legacyJob, legacyTrack.ID); err != nil {
+	if err := attach(legacyJob, legacyTrack.ID); err != nil {
 		t.Fatalf("complete legacy import item: %v", err)
 	}
 	var legacyStatus string
@@ -1504,7 +1589,7 @@ func TestDownloadAndStoreFixtureCreatesPlayableWAVObject(t *testing.T) {
 		Title:      "Fixture Silence",
 	}
 
-	metadata, err := processor.downloadAndStore(context.Background(), job)
+	metadata, err := processor.downloadAndStore(context.Background(), job, nil)
 	if err != nil {
 		t.Fatalf("downloadAndStore failed: %v", err)
 	}
@@ -1570,7 +1655,7 @@ func TestDownloadAndStoreUsesProbeContentTypeDespiteMisleadingExtension(t *testi
 		ID:         "misleading-extension",
 		URL:        "file://" + misleadingPath,
 		SourceType: "file",
-	})
+	}, nil)
 	if err != nil {
 		t.Fatalf("downloadAndStore: %v", err)
 	}
@@ -1698,6 +1783,28 @@ exit 7
 	}
 }
 
+func TestYTDLPProgressWriterReportsPercentAndLogsOtherOutput(t *testing.T) {
+	var reported []int
+	output := limitedOutput{limit: maxYTDLPLogBytes}
+	writer := &ytdlpProgressWriter{log: &output, progress: func(percent int) { reported = append(reported, percent) }}
+
+	for _, chunk := range []string{
+		"[youtube] Extracting URL\n" + ytdlpProgressPrefix + "  1.5%\n" + ytdlpProgressPrefix[:4],
+		ytdlpProgressPrefix[4:] + " 1.9%\n" + ytdlpProgressPrefix + " 42.0%\n",
+		ytdlpProgressPrefix + "100.0%\nERROR: trailing",
+	} {
+		writer.Write([]byte(chunk))
+	}
+	writer.Flush()
+
+	if !slices.Equal(reported, []int{1, 42, 100}) {
+		t.Fatalf("progress = %v, want [1 42 100]", reported)
+	}
+	if got := output.String(); got != "[youtube] Extracting URL\nERROR: trailing" {
+		t.Fatalf("log = %q", got)
+	}
+}
+
 func writeFakeYTDLP(t *testing.T, body string) string {
 	t.Helper()
 	path := filepath.Join(t.TempDir(), "yt-dlp-fake")
@@ -0,0 +1,353 @@
+package processor
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"fmt"
+	"log"
+	"math"
+	"os"
+	"os/exec"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/formatpolicy"
+)
+
+// TrackQualityStore records the measured quality of tracks' stored files.
+// db.TrackQualityRepository satisfies this interface.
+type TrackQualityStore interface {
+	Save(ctx context.Context, quality *db.TrackQuality) error
+}
+
+const (
+	// drBlockSeconds is the block length the DR meter rates loudness over.
+	drBlockSeconds = 3
+	// drLoudestShare is the share of loudest blocks DR compares the peak to.
+	drLoudestShare = 0.2
+	// spectralSilenceDB is how far below the whole file's level a band must
+	// be to count as empty. volumedetect measures 16-bit samples, so bands
+	// at or below spectralFloorDB, about one bit, count as empty too.
+	spectralSilenceDB = 70
+	spectralFloorDB   = -85
+	// upscaleCutoffHz is the highest cutoff lossy encoders apply; lossless
+	// files whose spectrum stops at or below it look upscaled.
+	upscaleCutoffHz = 20500
+	// lowDRScore is the DR below which audio counts as heavily compressed.
+	lowDRScore = 8
+	// maxQualityOutputBytes bounds the per-block statistics of long files.
+	maxQualityOutputBytes = 4 * 1024 * 1024
+	qualityMeasureTimeout = 5 * time.Minute
+)
+
+// ErrQualityScoringDisabled is returned by ScoreQuality when the processor
+// has no TrackQualityStore.
+var ErrQualityScoringDisabled = errors.New("quality scoring is not configured")
+
+// spectralCutoffsHz are the frequencies the spectrum is checked above, from
+// a 128 kbps MP3's lowpass to what CD audio carries.
+var spectralCutoffsHz = []int{16000, 17500, 19000, 20500}
+
+var (
+	astatsLevelPattern  = regexp.MustCompile(`^lavfi\.astats\.([0-9]+)\.(Peak_level|RMS_level)=(\S+)`)
+	volumedetectPattern = regexp.MustCompile(`\[Parsed_volumedetect_([0-9]+) @ [^\]]+\] mean_volume: (\S+) dB`)
+)
+
+// measureQuality rates the audio at audioPath, whose probed facts are
+// quality. Measurements that fail leave DRScore and SpectralCutoffHz unset,
+// so the score rests on the codec and bitrate alone.
+func (p *Processor) measureQuality(ctx context.Context, trackRef string, quality AudioQuality, audioPath string) *db.TrackQuality {
+	if p.trackQuality == nil {
+		return nil
+	}
+	ctx, cancel := context.WithTimeout(ctx, qualityMeasureTimeout)
+	defer cancel()
+	measured := &db.TrackQuality{}
+	if dr, err := measureDR(ctx, audioPath, quality.SampleRateHz); err != nil {
+		log.Printf("Warning: failed to measure dynamic range for %s: %v", trackRef, err)
+	} else if dr != nil {
+		measured.DRScore = sql.NullInt32{Int32: int32(*dr), Valid: true}
+	}
+	if cutoff, err := measureSpectralCutoff(ctx, audioPath, quality.SampleRateHz); err != nil {
+		log.Printf("Warning: failed to measure the spectrum for %s: %v", trackRef, err)
+	} else if cutoff > 0 {
+		measured.SpectralCutoffHz = sql.NullInt32{Int32: int32(cutoff), Valid: true}
+	}
+	codec := strings.ToLower(quality.Codec)
+	measured.UpscaleSuspected = formatpolicy.IsLossless(codec) && measured.SpectralCutoffHz.Valid && measured.SpectralCutoffHz.Int32 <= upscaleCutoffHz
+	measured.Score = qualityScore(codec, quality.BitrateKbps, measured)
+	return measured
+}
+
+// ScoreQuality measures the track's stored file and records its quality.
+func (p *Processor) ScoreQuality(ctx context.Context, track *db.Track) (*db.TrackQuality, error) {
+	if p.trackQuality == nil {
+		return nil, ErrQualityScoringDisabled
+	}
+	if p.storage == nil {
+		return nil, errors.New("object storage is not configured")
+	}
+	key := strings.TrimSpace(track.StorageKey.String)
+	if key == "" {
+		return nil, ErrNoStoredAudio
+	}
+	tmpPath, _, err := p.fetchStoredAudio(ctx, key, "omp-quality-score-*")
+	if err != nil {
+		return nil, err
+	}
+	defer os.Remove(tmpPath)
+
+	quality := AudioQuality{Codec: track.Codec.String, BitrateKbps: int(track.BitrateKbps.Int32), SampleRateHz: int(track.SampleRateHz.Int32)}
+	if quality.Codec == "" || quality.SampleRateHz <= 0 {
+		if quality, err = probeAudioFile(ctx, tmpPath, track.ContentType.String); err != nil {
+			return nil, err
+		}
+	}
+	measured := p.measureQuality(ctx, fmt.Sprintf("track %d", track.ID), quality, tmpPath)
+	measured.TrackID, measured.StorageKey = track.ID, key
+	if err := p.trackQuality.Save(ctx, measured); err != nil {
+		return nil, fmt.Errorf("save quality score: %w", err)
+	}
+	return measured, nil
+}
+
+// qualityScore rates a file from 0 to 100. Genuine lossless audio starts at
+// 100 and lossy audio at up to 90 by bitrate; suspected upscales are rated
+// as the lossy files they likely came from, and heavily compressed dynamics
+// cost up to 30 more.
+func qualityScore(codec string, bitrateKbps int, measured *db.TrackQuality) int {
+	score := 100
+	switch {
+	case measured.UpscaleSuspected:
+		score = lossyScore(cutoffBitrateKbps(int(measured.SpectralCutoffHz.Int32)))
+	case !formatpolicy.IsLossless(codec):
+		score = lossyScore(bitrateKbps)
+	}
+	if measured.DRScore.Valid && measured.DRScore.Int32 < lowDRScore {
+		score -= min(int(lowDRScore-measured.DRScore.Int32)*5, 30)
+	}
+	return max(min(score, 100), 0)
+}
+
+// lossyScore rates lossy audio by bitrate, 320 kbps and up scoring 90.
+func lossyScore(bitrateKbps int) int {
+	if bitrateKbps <= 0 {
+		return 40
+	}
+	return min(bitrateKbps*90/320, 90)
+}
+
+// cutoffBitrateKbps is about the MP3 bitrate whose encoder lowpass is at
+// cutoffHz.
+func cutoffBitrateKbps(cutoffHz int) int {
+	switch {
+	case cutoffHz <= 16000:
+		return 128
+	case cutoffHz <= 17500:
+		return 160
+	case cutoffHz <= 19000:
+		return 192
+	default:
+		return 256
+	}
+}
+
+// measureDR computes the file's DR score the way the DR meter does: per
+// channel, the second-highest block peak over the RMS of the loudest fifth
+// of 3-second blocks, averaged over channels. It returns nil for audio with
+// fewer than two blocks or no sound.
+func measureDR(ctx context.Context, audioPath string, sampleRateHz int) (*int, error) {
+	if sampleRateHz <= 0 {
+		return nil, errors.New("unknown sample rate")
+	}
+	filter := "asetnsamples=n=" + strconv.Itoa(sampleRateHz*drBlockSeconds) + ":p=0," +
+		"astats=metadata=1:reset=1:measure_overall=none:measure_perchannel=Peak_level+RMS_level," +
+		"ametadata=mode=print:file=-"
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-v", "error",
+		"-i", audioPath,
+		"-map", "0:a", "-af", filter,
+		"-f", "null", "-",
+	)
+	stdout := limitedOutput{limit: maxQualityOutputBytes}
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return nil, fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return drScore(parseDRBlocks(stdout.String())), nil
+}
+
+// drBlock is one channel's peak and RMS over one block, as linear
+// amplitudes.
+type drBlock struct {
+	peak float64
+	rms  float64
+}
+
+// parseDRBlocks reads astats' per-block levels, printed by ametadata, into
+// each channel's blocks.
+func parseDRBlocks(output string) [][]drBlock {
+	var channels [][]drBlock
+	block := -1
+	for _, line := range strings.Split(output, "\n") {
+		line = strings.TrimSpace(line)
+		if strings.HasPrefix(line, "frame:") {
+			block++
+			continue
+		}
+		m := astatsLevelPattern.FindStringSubmatch(line)
+		if m == nil || block < 0 {
+			continue
+		}
+		channel, _ := strconv.Atoi(m[1])
+		level, err := strconv.ParseFloat(m[3], 64)
+		if channel < 1 || err != nil {
+			continue
+		}
+		for len(channels) < channel {
+			channels = append(channels, nil)
+		}
+		blocks := channels[channel-1]
+		for len(blocks) <= block {
+			blocks = append(blocks, drBlock{})
+		}
+		amplitude := math.Pow(10, level/20)
+		if m[2] == "Peak_level" {
+			blocks[block].peak = amplitude
+		} else {
+			// The DR meter rates a full-scale sine at 0 dB RMS.
+			blocks[block].rms = amplitude * math.Sqrt2
+		}
+		channels[channel-1] = blocks
+	}
+	return channels
+}
+
+// drScore rates the blocks of each channel, or returns nil when there are
+// too few blocks or they hold no sound.
+func drScore(channels [][]drBlock) *int {
+	var total float64
+	rated := 0
+	for _, blocks := range channels {
+		if len(blocks) < 2 {
+			continue
+		}
+		peaks := make([]float64, len(blocks))
+		rms := make([]float64, len(blocks))
+		for i, b := range blocks {
+			peaks[i], rms[i] = b.peak, b.rms
+		}
+		slices.Sort(peaks)
+		slices.Sort(rms)
+		loudest := rms[len(rms)-max(int(float64(len(rms))*drLoudestShare), 1):]
+		var sum float64
+		for _, r := range loudest {
+			sum += r * r
+		}
+		loudRMS := math.Sqrt(sum / float64(len(loudest)))
+		peak := peaks[len(peaks)-2]
+		if loudRMS <= 0 || peak <= 0 {
+			continue
+		}
+		total += 20 * math.Log10(peak/loudRMS)
+		rated++
+	}
+	if rated == 0 {
+		return nil
+	}
+	dr := max(int(math.Round(total/float64(rated))), 0)
+	return &dr
+}
+
+// measureSpectralCutoff returns the lowest of spectralCutoffsHz above which
+// the file holds essentially nothing, or 0 when there is content above all
+// of those its sample rate carries.
+func measureSpectralCutoff(ctx context.Context, audioPath string, sampleRateHz int) (int, error) {
+	var cutoffs []int
+	for _, cutoff := range spectralCutoffsHz {
+		if cutoff < sampleRateHz/2 {
+			cutoffs = append(cutoffs, cutoff)
+		}
+	}
+	if len(cutoffs) == 0 {
+		return 0, nil
+	}
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-hide_banner", "-nostats",
+		"-i", audioPath,
+		"-filter_complex", spectralFilter(cutoffs),
+		"-map", "[full]", "-f", "null", "-",
+	)
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return 0, fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	levels := parseBandLevels(stderr.String())
+	if len(levels) != len(cutoffs)+1 {
+		return 0, fmt.Errorf("ffmpeg reported %d band levels, want %d", len(levels), len(cutoffs)+1)
+	}
+	return spectralCutoff(cutoffs, levels), nil
+}
+
+// spectralFilter splits the audio into the whole band, measured first, and
+// one steeply high-passed copy per cutoff, each measured by volumedetect.
+func spectralFilter(cutoffs []int) string {
+	var b strings.Builder
+	b.WriteString("[0:a]asplit=" + strconv.Itoa(len(cutoffs)+1) + "[all]")
+	for i := range cutoffs {
+		b.WriteString("[band" + strconv.Itoa(i) + "]")
+	}
+	b.WriteString(";[all]volumedetect[full]")
+	for i, cutoff := range cutoffs {
+		highpass := "highpass=f=" + strconv.Itoa(cutoff) + ":p=2"
+		b.WriteString(";[band" + strconv.Itoa(i) + "]" + strings.Repeat(highpass+",", 4) + "volumedetect,anullsink")
+	}
+	return b.String()
+}
+
+// parseBandLevels reads volumedetect's mean volumes, in dB, in the order
+// the filters appear in the graph.
+func parseBandLevels(output string) []float64 {
+	type level struct {
+		index int
+		db    float64
+	}
+	var levels []level
+	for _, m := range volumedetectPattern.FindAllStringSubmatch(output, -1) {
+		index, _ := strconv.Atoi(m[1])
+		value, err := strconv.ParseFloat(m[2], 64)
+		if err != nil {
+			continue
+		}
+		levels = append(levels, level{index: index, db: value})
+	}
+	slices.SortFunc(levels, func(a, b level) int { return a.index - b.index })
+	dbs := make([]float64, len(levels))
+	for i, l := range levels {
+		dbs[i] = l.db
+	}
+	return dbs
+}
+
+// spectralCutoff picks the lowest cutoff whose band is empty; levels holds
+// the whole band's level first.
+func spectralCutoff(cutoffs []int, levels []float64) int {
+	full := levels[0]
+	if full <= spectralFloorDB {
+		return 0
+	}
+	for i, cutoff := range cutoffs {
+		if levels[i+1] <= max(full-spectralSilenceDB, spectralFloorDB) {
+			return cutoff
+		}
+	}
+	return 0
+}
@@ -0,0 +1,87 @@
+package processor
+
+import (
+	"database/sql"
+	"fmt"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestDRScoreComparesSecondPeakToLoudestBlocks(t *testing.T) {
+	var out strings.Builder
+	for i, peak := range []int{-1, -2, -3, -4, -5} {
+		fmt.Fprintf(&out, "frame:%d    pts:%d    pts_time:%d\n", i, i*132300, i*3)
+		fmt.Fprintf(&out, "lavfi.astats.1.Peak_level=%d.000000\nlavfi.astats.1.RMS_level=-13.000000\n", peak)
+	}
+	channels := parseDRBlocks(out.String())
+	if len(channels) != 1 || len(channels[0]) != 5 {
+		t.Fatalf("parseDRBlocks() = %+v, want one channel of 5 blocks", channels)
+	}
+	// The second-highest peak, -2 dB, over the loudest block's RMS of
+	// -13 dB read as a sine's, about -9.99 dB.
+	if dr := drScore(channels); dr == nil || *dr != 8 {
+		t.Fatalf("drScore() = %v, want 8", dr)
+	}
+	if dr := drScore(parseDRBlocks("frame:0\nlavfi.astats.1.Peak_level=-1\nlavfi.astats.1.RMS_level=-13\n")); dr != nil {
+		t.Fatalf("drScore() of one block = %d, want nil", *dr)
+	}
+}
+
+func TestSpectralCutoffFindsTheFirstEmptyBand(t *testing.T) {
+	output := `[Parsed_volumedetect_6 @ 0x1] mean_volume: -88.0 dB
+[Parsed_volumedetect_1 @ 0x2] mean_volume: -16.5 dB
+[Parsed_volumedetect_1 @ 0x2] max_volume: -0.1 dB
+[Parsed_volumedetect_11 @ 0x3] mean_volume: -91.0 dB
+`
+	levels := parseBandLevels(output)
+	if want := []float64{-16.5, -88, -91}; !reflect.DeepEqual(levels, want) {
+		t.Fatalf("parseBandLevels() = %v, want %v", levels, want)
+	}
+	for _, tc := range []struct {
+		levels []float64
+		want   int
+	}{
+		{[]float64{-16.5, -88, -91}, 16000},
+		{[]float64{-16.5, -60, -91}, 17500},
+		{[]float64{-16.5, -60, -70}, 0},
+		{[]float64{-91, -91, -91}, 0},
+	} {
+		if got := spectralCutoff([]int{16000, 17500}, tc.levels); got != tc.want {
+			t.Errorf("spectralCutoff(%v) = %d, want %d", tc.levels, got, tc.want)
+		}
+	}
+}
+
+func TestSpectralFilterMeasuresTheWholeBandFirst(t *testing.T) {
+	got := spectralFilter([]int{16000})
+	want := "[0:a]asplit=2[all][band0];[all]volumedetect[full];[band0]" + strings.Repeat("highpass=f=16000:p=2,", 4) + "volumedetect,anullsink"
+	if got != want {
+		t.Fatalf("spectralFilter() = %q, want %q", got, want)
+	}
+}
+
+func TestQualityScore(t *testing.T) {
+	valid := func(n int32) sql.NullInt32 { return sql.NullInt32{Int32: n, Valid: true} }
+	for _, tc := range []struct {
+		name     string
+		codec    string
+		bitrate  int
+		measured db.TrackQuality
+		want     int
+	}{
+		{"lossless", "flac", 900, db.TrackQuality{}, 100},
+		{"320 kbps", "mp3", 320, db.TrackQuality{}, 90},
+		{"128 kbps", "mp3", 128, db.TrackQuality{}, 36},
+		{"unknown bitrate", "opus", 0, db.TrackQuality{}, 40},
+		{"upscale", "flac", 900, db.TrackQuality{SpectralCutoffHz: valid(16000), UpscaleSuspected: true}, 36},
+		{"compressed dynamics", "flac", 900, db.TrackQuality{DRScore: valid(5)}, 85},
+		{"penalty capped", "mp3", 128, db.TrackQuality{DRScore: valid(1)}, 6},
+	} {
+		if got := qualityScore(tc.codec, tc.bitrate, &tc.measured); got != tc.want {
+			t.Errorf("%s: qualityScore() = %d, want %d", tc.name, got, tc.want)
+		}
+	}
+}
@@ -0,0 +1,46 @@
+package processor
+
+import (
+	"context"
+	"log"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/matcher"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+// syncMBRecording looks up a newly matched recording and stores its remix,
+// sample, and cover relationships, artist credits, artist sort name, and
+// aliases. The match itself has already been saved, so failures are only
+// logged.
+func (p *Processor) syncMBRecording(ctx context.Context, trackID int64, recordingID uuid.UUID) {
+	if p.matcher == nil || p.matcher.MBClient() == nil {
+		return
+	}
+	recording, err := p.matcher.MBClient().GetRecording(ctx, recordingID.String())
+	if err != nil {
+		log.Printf("Warning: failed to fetch recording details for track %d: %v", trackID, err)
+		return
+	}
+	p.storeMBRecording(ctx, trackID, recording)
+}
+
+func (p *Processor) storeMBRecording(ctx context.Context, trackID int64, recording *musicbrainz.Track) {
+	if err := p.trackRepo.ReplaceRelations(ctx, trackID, matcher.TrackRelations(recording.Relations)); err != nil {
+		log.Printf("Warning: failed to store relationships for track %d: %v", trackID, err)
+	}
+	if err := p.trackRepo.RecordArtistSortName(ctx, recording.Artist, recording.ArtistSortName, recording.ArtistID); err != nil {
+		log.Printf("Warning: failed to store artist sort name for track %d: %v", trackID, err)
+	}
+	if err := p.trackRepo.ReplaceAliases(ctx, trackID, matcher.TrackAliases(recording)); err != nil {
+		log.Printf("Warning: failed to store aliases for track %d: %v", trackID, err)
+	}
+	if len(recording.Credits) == 0 {
+		return
+	}
+	if err := p.trackRepo.ReplaceArtistCredits(ctx, trackID, matcher.TrackArtists(recording), db.FieldSourceMusicBrainz); err != nil {
+		log.Printf("Warning: failed to store artist credits for track %d: %v", trackID, err)
+	}
+}
@@ -0,0 +1,158 @@
+package processor
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"log"
+	"strconv"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+// MetadataFieldChange is one track field changed by a MusicBrainz re-check.
+type MetadataFieldChange struct {
+	Field  string `json:"field"`
+	Before string `json:"before"`
+	After  string `json:"after"`
+}
+
+type MetadataReverifyResult struct {
+	TrackID int64                 `json:"trackId"`
+	Status  string                `json:"status"`
+	Reason  string                `json:"reason,omitempty"`
+	Changes []MetadataFieldChange `json:"changes,omitempty"`
+}
+
+// ReverifyMetadata re-fetches the MusicBrainz recording a track is linked to
+// and applies title corrections and new release links under the field
+// precedence rules, reporting every field that changed.
+func (p *Processor) ReverifyMetadata(ctx context.Context, track *db.Track) (MetadataReverifyResult, error) {
+	if track == nil {
+		return MetadataReverifyResult{Status: "skipped", Reason: "missing_track"}, nil
+	}
+	result := MetadataReverifyResult{TrackID: track.ID}
+	if track.MBRecordingID == nil {
+		result.Status = "skipped"
+		result.Reason = "not_linked"
+		return result, nil
+	}
+	if p.matcher == nil || p.matcher.MBClient() == nil {
+		result.Status = "skipped"
+		result.Reason = "metadata_matcher_disabled"
+		return result, nil
+	}
+
+	client := p.matcher.MBClient()
+	recording, err := client.RefreshRecording(ctx, track.MBRecordingID.String())
+	if err != nil {
+		if markErr := p.trackRepo.MarkMBChecked(ctx, track.ID); markErr != nil {
+			log.Printf("Warning: failed to mark track %d as checked: %v", track.ID, markErr)
+		}
+		result.Status = "failed"
+		result.Reason = err.Error()
+		return result, fmt.Errorf("fetch recording: %w", err)
+	}
+	coverArtURL := ""
+	if recording.AlbumID != "" {
+		coverArtURL = client.GetCoverArtURL(recording.AlbumID)
+	}
+	if err := p.trackRepo.UpdateMBMatch(ctx, track.ID, reverifyMBMatchUpdate(track, recording, coverArtURL, time.Now())); err != nil {
+		result.Status = "failed"
+		result.Reason = err.Error()
+		return result, err
+	}
+	p.storeMBRecording(ctx, track.ID, recording)
+	updated, err := p.trackRepo.GetByID(ctx, track.ID)
+	if err != nil {
+		result.Status = "failed"
+		result.Reason = err.Error()
+		return result, err
+	}
+	result.Changes = metadataChanges(track, updated)
+	result.Status = "unchanged"
+	if len(result.Changes) > 0 {
+		result.Status = "updated"
+	}
+	return result, nil
+}
+
+// reverifyMBMatchUpdate builds the update for a refreshed recording. Identifiers
+// the lookup did not return keep their current value rather than being
+// cleared, and the update respects user edits like automatic matching does.
+func reverifyMBMatchUpdate(track *db.Track, recording *musicbrainz.Track, coverArtURL string, checkedAt time.Time) *db.MBMatchUpdate {
+	update := &db.MBMatchUpdate{
+		MBRecordingID:    track.MBRecordingID,
+		MBReleaseID:      track.MBReleaseID,
+		MBArtistID:       track.MBArtistID,
+		ApplyMBIdentity:  true,
+		RespectUserEdits: true,
+		Title:            recording.Title,
+		Artist:           recording.Artist,
+		Album:            recording.Album,
+		DurationMs:       recording.Duration,
+		CoverArtURL:      coverArtURL,
+	}
+	// MusicBrainz redirects merged recordings to the surviving ID.
+	if id, err := uuid.Parse(recording.ID); err == nil {
+		update.MBRecordingID = &id
+	}
+	if id, err := uuid.Parse(recording.AlbumID); err == nil {
+		update.MBReleaseID = &id
+		update.ReleaseDate, _ = db.ParsePartialDate(recording.ReleaseDate)
+	}
+	if id, err := uuid.Parse(recording.ArtistID); err == nil {
+		update.MBArtistID = &id
+	}
+	update.MetadataProvenance, _ = json.Marshal(map[string]interface{}{
+		"musicbrainz_reverify": map[string]interface{}{
+			"recording_id": recording.ID,
+			"release_id":   recording.AlbumID,
+			"checked_at":   checkedAt.UTC().Format(time.RFC3339),
+		},
+	})
+	return update
+}
+
+// metadataChanges lists the metadata and MusicBrainz identity fields that
+// differ between two versions of a track.
+func metadataChanges(before, after *db.Track) []MetadataFieldChange {
+	fields := []struct {
+		name          string
+		before, after string
+	}{
+		{"title", before.Title, after.Title},
+		{"artist", nullableString(before.Artist), nullableString(after.Artist)},
+		{"album", nullableString(before.Album), nullableString(after.Album)},
+		{"duration_ms", durationText(before.DurationMs.Int32), durationText(after.DurationMs.Int32)},
+		{"cover_art_url", nullableString(before.CoverArtURL), nullableString(after.CoverArtURL)},
+		{"mb_recording_id", uuidText(before.MBRecordingID), uuidText(after.MBRecordingID)},
+		{"mb_release_id", uuidText(before.MBReleaseID), uuidText(after.MBReleaseID)},
+		{"mb_artist_id", uuidText(before.MBArtistID), uuidText(after.MBArtistID)},
+	}
+	var changes []MetadataFieldChange
+	for _, field := range fields {
+		if field.before != field.after {
+			changes = append(changes, MetadataFieldChange{Field: field.name, Before: field.before, After: field.after})
+		}
+	}
+	return changes
+}
+
+func durationText(ms int32) string {
+	if ms <= 0 {
+		return ""
+	}
+	return strconv.Itoa(int(ms))
+}
+
+func uuidText(id *uuid.UUID) string {
+	if id == nil {
+		return ""
+	}
+	return id.String()
+}
@@ -0,0 +1,73 @@
+package processor
+
+import (
+	"database/sql"
+	"reflect"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/musicbrainz"
+)
+
+func TestReverifyMBMatchUpdateKeepsIdentityTheLookupOmits(t *testing.T) {
+	recordingID := uuid.MustParse("11111111-1111-1111-1111-111111111111")
+	releaseID := uuid.MustParse("22222222-2222-2222-2222-222222222222")
+	artistID := uuid.MustParse("33333333-3333-3333-3333-333333333333")
+	track := &db.Track{ID: 5, MBRecordingID: &recordingID, MBReleaseID: &releaseID, MBArtistID: &artistID}
+
+	newRelease := "44444444-4444-4444-4444-444444444444"
+	update := reverifyMBMatchUpdate(track, &musicbrainz.Track{
+		ID:          recordingID.String(),
+		Title:       "Corrected Title",
+		Artist:      "Artist",
+		AlbumID:     newRelease,
+		Album:       "Deluxe Edition",
+		ReleaseDate: "2001-03",
+		Duration:    201000,
+	}, "https://coverartarchive.org/release/"+newRelease+"/front-250", time.Now())
+
+	if !update.RespectUserEdits || !update.ApplyMBIdentity {
+		t.Fatalf("update flags = respect:%v identity:%v, want both", update.RespectUserEdits, update.ApplyMBIdentity)
+	}
+	if update.MBReleaseID == nil || update.MBReleaseID.String() != newRelease {
+		t.Fatalf("release = %v, want new release link", update.MBReleaseID)
+	}
+	if update.MBArtistID == nil || *update.MBArtistID != artistID {
+		t.Fatalf("artist = %v, want existing artist kept", update.MBArtistID)
+	}
+	if update.Title != "Corrected Title" || update.Album != "Deluxe Edition" || update.DurationMs != 201000 {
+		t.Fatalf("update = %+v", update)
+	}
+	if update.ReleaseDate != (db.PartialDate{Year: 2001, Month: 3}) {
+		t.Fatalf("release date = %+v, want 2001-03", update.ReleaseDate)
+	}
+}
+
+func TestMetadataChangesReportsChangedFields(t *testing.T) {
+	releaseID := uuid.MustParse("22222222-2222-2222-2222-222222222222")
+	before := &db.Track{
+		Title:      "Old Title",
+		Artist:     sql.NullString{String: "Artist", Valid: true},
+		DurationMs: sql.NullInt32{Int32: 200000, Valid: true},
+	}
+	after := &db.Track{
+		Title:       "New Title",
+		Artist:      sql.NullString{String: "Artist", Valid: true},
+		DurationMs:  sql.NullInt32{Int32: 200000, Valid: true},
+		MBReleaseID: &releaseID,
+	}
+
+	want := []MetadataFieldChange{
+		{Field: "title", Before: "Old Title", After: "New Title"},
+		{Field: "mb_release_id", Before: "", After: releaseID.String()},
+	}
+	if got := metadataChanges(before, after); !reflect.DeepEqual(got, want) {
+		t.Fatalf("metadataChanges = %+v, want %+v", got, want)
+	}
+	if got := metadataChanges(after, after); got != nil {
+		t.Fatalf("metadataChanges for identical tracks = %+v, want none", got)
+	}
+}
@@ -0,0 +1,309 @@
+package processor
+
+import (
+	"encoding/json"
+	"encoding/xml"
+	"io"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"regexp"
+	"slices"
+	"sort"
+	"strings"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+// maxSidecarBytes bounds a sidecar metadata file; real NFO, info.json, and LRC
+// files are a few kilobytes.
+const maxSidecarBytes = 1024 * 1024
+
+// Metadata sources for local files, in order of precedence. Sidecar files sit
+// above tags embedded in the audio, which sit above fields supplied with the
+// job and the filename.
+const (
+	metadataSourceNFO      = "nfo"
+	metadataSourceInfoJSON = "info_json"
+	metadataSourceLRC      = "lrc"
+	metadataSourceEmbedded = "embedded_tags"
+	metadataSourceJob      = "job"
+	metadataSourceFilename = "filename"
+)
+
+// audioTags holds the tags read from an audio file or one of its sidecars.
+type audioTags struct {
+	Title      string
+	Artist     string
+	Album      string
+	DurationMs int
+	// SearchAliases are sort tags, which often carry the romanized names of
+	// Japanese and other non-Latin releases.
+	SearchAliases []string
+	// MusicBrainz IDs written by taggers such as beets and Picard.
+	MBRecordingID string
+	MBReleaseID   string
+	MBArtistID    string
+}
+
+// merge fills fields of t that are still empty from other.
+func (t *audioTags) merge(other audioTags) {
+	t.Title = firstNonEmpty(t.Title, other.Title)
+	t.Artist = firstNonEmpty(t.Artist, other.Artist)
+	t.Album = firstNonEmpty(t.Album, other.Album)
+	if t.DurationMs <= 0 {
+		t.DurationMs = other.DurationMs
+	}
+	t.SearchAliases = append(t.SearchAliases, other.SearchAliases...)
+	t.MBRecordingID = firstNonEmpty(t.MBRecordingID, other.MBRecordingID)
+	t.MBReleaseID = firstNonEmpty(t.MBReleaseID, other.MBReleaseID)
+	t.MBArtistID = firstNonEmpty(t.MBArtistID, other.MBArtistID)
+}
+
+// searchAliasTags are the sort tag names ffprobe reports across containers:
+// ID3 TSOT/TSOP/TSOA/TSO2, Vorbis comments, and MP4 sort atoms.
+var searchAliasTags = map[string]bool{
+	"title-sort": true, "titlesort": true, "sort_name": true,
+	"artist-sort": true, "artistsort": true, "sort_artist": true,
+	"album-sort": true, "albumsort": true, "sort_album": true,
+	"album_artist-sort": true, "albumartistsort": true, "sort_album_artist": true,
+}
+
+// ffprobeTags picks title, artist, album, sort tags, and MusicBrainz IDs from
+// ffprobe's tag map, whose key case depends on the container (ID3 "title",
+// Vorbis "TITLE").
+func ffprobeTags(raw map[string]string) audioTags {
+	var tags audioTags
+	for key, value := range raw {
+		value = strings.TrimSpace(value)
+		key = strings.ToLower(key)
+		switch key {
+		case "title":
+			tags.Title = value
+		case "artist":
+			tags.Artist = value
+		case "album":
+			tags.Album = value
+		case "musicbrainz_trackid", "musicbrainz track id":
+			tags.MBRecordingID = firstMBID(value)
+		case "musicbrainz_albumid", "musicbrainz album id":
+			tags.MBReleaseID = firstMBID(value)
+		case "musicbrainz_artistid", "musicbrainz artist id":
+			tags.MBArtistID = firstMBID(value)
+		default:
+			if searchAliasTags[key] && value != "" {
+				tags.SearchAliases = append(tags.SearchAliases, value)
+			}
+		}
+	}
+	sort.Strings(tags.SearchAliases)
+	return tags
+}
+
+// firstMBID returns the first MusicBrainz ID in a tag value. Multi-artist
+// files carry several, separated by "/" or ";".
+func firstMBID(value string) string {
+	first, _, _ := strings.Cut(strings.ReplaceAll(value, ";", "/"), "/")
+	if id, err := uuid.Parse(strings.TrimSpace(first)); err == nil {
+		return id.String()
+	}
+	return ""
+}
+
+// sidecarMetadata is what the files beside a local audio file contribute.
+type sidecarMetadata struct {
+	NFO          audioTags
+	InfoJSON     audioTags
+	LRC          audioTags
+	Lyrics       string
+	LyricsSynced bool
+}
+
+// readSidecars reads <name>.nfo, <name>.info.json (or <name>.json), and
+// <name>.lrc next to audioPath. Missing or unreadable sidecars are skipped.
+func readSidecars(audioPath string) sidecarMetadata {
+	base := strings.TrimSuffix(audioPath, filepath.Ext(audioPath))
+	var sidecars sidecarMetadata
+	if data, ok := readSidecar(base + ".nfo"); ok {
+		sidecars.NFO = parseNFO(data)
+	}
+	for _, name := range []string{base + ".info.json", base + ".json"} {
+		if data, ok := readSidecar(name); ok {
+			sidecars.InfoJSON = parseInfoJSON(data)
+			break
+		}
+	}
+	if data, ok := readSidecar(base + ".lrc"); ok {
+		sidecars.LRC, sidecars.LyricsSynced = parseLRC(string(data))
+		sidecars.Lyrics = strings.TrimSpace(string(data))
+	}
+	return sidecars
+}
+
+func readSidecar(path string) ([]byte, bool) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, false
+	}
+	defer file.Close()
+	data, err := io.ReadAll(io.LimitReader(file, maxSidecarBytes+1))
+	if err != nil || len(data) > maxSidecarBytes {
+		return nil, false
+	}
+	return data, true
+}
+
+// parseNFO reads a Kodi-style XML NFO (<musicvideo>, <song>, and similar
+// roots). Free-text NFOs carry no structured fields and yield nothing.
+func parseNFO(data []byte) audioTags {
+	var doc struct {
+		Title   string   `xml:"title"`
+		Artists []string `xml:"artist"`
+		Album   string   `xml:"album"`
+	}
+	if err := xml.Unmarshal(data, &doc); err != nil {
+		return audioTags{}
+	}
+	var artists []string
+	for _, artist := range doc.Artists {
+		if artist = strings.TrimSpace(artist); artist != "" {
+			artists = append(artists, artist)
+		}
+	}
+	return audioTags{
+		Title:  strings.TrimSpace(doc.Title),
+		Artist: strings.Join(artists, ", "),
+		Album:  strings.TrimSpace(doc.Album),
+	}
+}
+
+// parseInfoJSON reads a yt-dlp .info.json, preferring its music fields
+// (track, artist, album) over the generic video title and uploader.
+func parseInfoJSON(data []byte) audioTags {
+	var raw map[string]interface{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return audioTags{}
+	}
+	return audioTags{
+		Title:      strings.TrimSpace(firstNonEmpty(stringValue(raw, "track"), stringValue(raw, "title"))),
+		Artist:     strings.TrimSpace(firstNonEmpty(stringValue(raw, "artist"), stringValue(raw, "creator"), stringValue(raw, "uploader"))),
+		Album:      strings.TrimSpace(stringValue(raw, "album")),
+		DurationMs: int(floatValue(raw, "duration") * 1000),
+	}
+}
+
+var (
+	lrcTimestamp = regexp.MustCompile(`^\[\d+:\d{2}(?:[.:]\d{1,3})?\]`)
+	lrcHeaderTag = regexp.MustCompile(`^\[(ti|ar|al):(.*)\]$`)
+)
+
+// parseLRC returns the LRC header tags and whether any line is time-synced.
+func parseLRC(text string) (audioTags, bool) {
+	var (
+		tags   audioTags
+		synced bool
+	)
+	for _, line := range strings.Split(text, "\n") {
+		line = strings.TrimSpace(line)
+		if lrcTimestamp.MatchString(line) {
+			synced = true
+			continue
+		}
+		match := lrcHeaderTag.FindStringSubmatch(line)
+		if match == nil {
+			continue
+		}
+		value := strings.TrimSpace(match[2])
+		switch match[1] {
+		case "ti":
+			tags.Title = value
+		case "ar":
+			tags.Artist = value
+		case "al":
+			tags.Album = value
+		}
+	}
+	return tags, synced
+}
+
+// sourceFilename is the base name, without extension, of a file:// path or
+// of an http(s) URL's path.
+func sourceFilename(sourceURL string) string {
+	if localPath, ok := strings.CutPrefix(sourceURL, "file://"); ok {
+		return strings.TrimSuffix(filepath.Base(localPath), filepath.Ext(localPath))
+	}
+	parsed, err := url.Parse(sourceURL)
+	if err != nil || parsed.Path == "" {
+		return ""
+	}
+	name := path.Base(parsed.Path)
+	if name == "/" || name == "." {
+		return ""
+	}
+	return strings.TrimSuffix(name, path.Ext(name))
+}
+
+// applyLocalMetadata settles title, artist, album, and duration for a local
+// file or direct download field by field, taking each from the
+// highest-precedence source that has it: NFO, info.json, LRC header, embedded
+// tags, the job, then the filename. Jobs carrying library manager metadata
+// (download.LibraryMetadata) come first instead. Direct downloads have no
+// sidecars. The winning source of each field is recorded in
+// metadata.FieldSources. MusicBrainz IDs from the tags fill any the job did
+// not preselect.
+func applyLocalMetadata(metadata *TrackMetadata, job *download.DownloadJob, embedded audioTags, sidecars sidecarMetadata) {
+	type source struct {
+		name string
+		tags audioTags
+	}
+	filename := sourceFilename(job.URL)
+	fromJob := source{metadataSourceJob, audioTags{Title: job.Title, Artist: firstNonEmpty(job.Artist, job.Uploader), Album: job.Album, DurationMs: job.DurationMs}}
+	sources := []source{
+		{metadataSourceNFO, sidecars.NFO},
+		{metadataSourceInfoJSON, sidecars.InfoJSON},
+		{metadataSourceLRC, sidecars.LRC},
+		{metadataSourceEmbedded, embedded},
+		fromJob,
+		{metadataSourceFilename, audioTags{Title: filename}},
+	}
+	if job.Library() != nil {
+		sources = append([]source{fromJob}, slices.DeleteFunc(sources, func(s source) bool { return s.name == metadataSourceJob })...)
+	}
+	pick := func(field string, value func(audioTags) string) string {
+		for _, source := range sources {
+			if v := value(source.tags); strings.TrimSpace(v) != "" {
+				metadata.FieldSources[field] = source.name
+				return v
+			}
+		}
+		return ""
+	}
+
+	metadata.FieldSources = map[string]string{}
+	metadata.Title = firstNonEmpty(pick("title", func(t audioTags) string { return t.Title }), metadata.Title)
+	metadata.Artist = pick("artist", func(t audioTags) string { return t.Artist })
+	metadata.Album = pick("album", func(t audioTags) string { return t.Album })
+	for _, source := range sources {
+		if source.tags.DurationMs > 0 {
+			metadata.DurationMs = source.tags.DurationMs
+			metadata.FieldSources["duration"] = source.name
+			break
+		}
+	}
+	for _, source := range sources {
+		metadata.SearchAliases = append(metadata.SearchAliases, source.tags.SearchAliases...)
+	}
+	if sidecars.Lyrics != "" {
+		metadata.Lyrics = sidecars.Lyrics
+		metadata.LyricsSynced = sidecars.LyricsSynced
+		metadata.FieldSources["lyrics"] = metadataSourceLRC
+	}
+	if metadata.PreselectedMBID == "" && embedded.MBRecordingID != "" {
+		metadata.PreselectedMBID = embedded.MBRecordingID
+		metadata.PreselectedReleaseMBID = firstNonEmpty(metadata.PreselectedReleaseMBID, embedded.MBReleaseID)
+		metadata.PreselectedArtistMBID = firstNonEmpty(metadata.PreselectedArtistMBID, embedded.MBArtistID)
+	}
+}
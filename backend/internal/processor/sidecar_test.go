@@ -0,0 +1,136 @@
+package processor
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+func writeSidecar(t *testing.T, path, content string) {
+	t.Helper()
+	if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+		t.Fatalf("write %s: %v", filepath.Base(path), err)
+	}
+}
+
+func TestApplyLocalMetadataPrecedence(t *testing.T) {
+	dir := t.TempDir()
+	audio := filepath.Join(dir, "01 - Song.mp3")
+	writeSidecar(t, audio, "audio")
+	writeSidecar(t, filepath.Join(dir, "01 - Song.nfo"), `<?xml version="1.0"?>
+<musicvideo><title>NFO Title</title><artist>A</artist><artist>B</artist></musicvideo>`)
+	writeSidecar(t, filepath.Join(dir, "01 - Song.info.json"), `{"title":"Video Title","album":"JSON Album","uploader":"Channel","duration":181.5}`)
+	writeSidecar(t, filepath.Join(dir, "01 - Song.lrc"), "[ti:LRC Title]\n[al:LRC Album]\n[00:01.50]First line\n")
+
+	job := &download.DownloadJob{URL: "file://" + audio, Title: "Job Title"}
+	metadata := &TrackMetadata{Title: job.URL}
+	embedded := audioTags{Title: "Tag Title", Artist: "Tag Artist", Album: "Tag Album"}
+	applyLocalMetadata(metadata, job, embedded, readSidecars(audio))
+
+	if metadata.Title != "NFO Title" || metadata.Artist != "A, B" || metadata.Album != "JSON Album" || metadata.DurationMs != 181500 {
+		t.Fatalf("metadata = %q / %q / %q / %d", metadata.Title, metadata.Artist, metadata.Album, metadata.DurationMs)
+	}
+	want := map[string]string{"title": "nfo", "artist": "nfo", "album": "info_json", "duration": "info_json", "lyrics": "lrc"}
+	for field, source := range want {
+		if metadata.FieldSources[field] != source {
+			t.Fatalf("FieldSources = %v, want %v", metadata.FieldSources, want)
+		}
+	}
+	if !metadata.LyricsSynced || metadata.Lyrics == "" {
+		t.Fatalf("lyrics = %q (synced=%v), want synced LRC text", metadata.Lyrics, metadata.LyricsSynced)
+	}
+}
+
+func TestApplyLocalMetadataFallsBackToTagsThenFilename(t *testing.T) {
+	dir := t.TempDir()
+	audio := filepath.Join(dir, "Untitled Demo.flac")
+	writeSidecar(t, filepath.Join(dir, "Untitled Demo.nfo"), "Ripped by someone, free text only")
+	writeSidecar(t, filepath.Join(dir, "Untitled Demo.lrc"), "Plain line one\nPlain line two\n")
+
+	job := &download.DownloadJob{URL: "file://" + audio}
+	metadata := &TrackMetadata{Title: job.URL}
+	applyLocalMetadata(metadata, job, audioTags{Artist: "Tag Artist"}, readSidecars(audio))
+
+	if metadata.Title != "Untitled Demo" || metadata.Artist != "Tag Artist" || metadata.Album != "" {
+		t.Fatalf("metadata = %q / %q / %q", metadata.Title, metadata.Artist, metadata.Album)
+	}
+	if metadata.FieldSources["title"] != "filename" || metadata.FieldSources["artist"] != "embedded_tags" {
+		t.Fatalf("FieldSources = %v", metadata.FieldSources)
+	}
+	if metadata.LyricsSynced || metadata.Lyrics != "Plain line one\nPlain line two" {
+		t.Fatalf("lyrics = %q (synced=%v), want unsynced plain text", metadata.Lyrics, metadata.LyricsSynced)
+	}
+}
+
+func TestApplyLocalMetadataPrefersLibraryManagerAndTaggedMBIDs(t *testing.T) {
+	embedded := ffprobeTags(map[string]string{
+		"TITLE":                "Stale Title",
+		"ARTIST":               "Tag Artist",
+		"MUSICBRAINZ_TRACKID":  "11111111-1111-1111-1111-111111111111",
+		"MusicBrainz Album Id": "22222222-2222-2222-2222-222222222222",
+		"MUSICBRAINZ_ARTISTID": "33333333-3333-3333-3333-333333333333/44444444-4444-4444-4444-444444444444",
+	})
+	job := &download.DownloadJob{
+		URL:      "file:///music/Low/01 Starfire.flac",
+		Title:    "Starfire",
+		Metadata: map[string]interface{}{download.MetadataLibrary: download.LibraryMetadata{Manager: "beets"}},
+	}
+	metadata := &TrackMetadata{Title: job.URL}
+	applyLocalMetadata(metadata, job, embedded, sidecarMetadata{})
+
+	if metadata.Title != "Starfire" || metadata.Artist != "Tag Artist" || metadata.FieldSources["title"] != "job" {
+		t.Fatalf("metadata = %q / %q, sources %v", metadata.Title, metadata.Artist, metadata.FieldSources)
+	}
+	if metadata.PreselectedMBID != "11111111-1111-1111-1111-111111111111" || metadata.PreselectedReleaseMBID != "22222222-2222-2222-2222-222222222222" || metadata.PreselectedArtistMBID != "33333333-3333-3333-3333-333333333333" {
+		t.Fatalf("preselected = %q / %q / %q", metadata.PreselectedMBID, metadata.PreselectedReleaseMBID, metadata.PreselectedArtistMBID)
+	}
+}
+
+func TestFfprobeTagsCollectsSortTagsAsSearchAliases(t *testing.T) {
+	tags := ffprobeTags(map[string]string{
+		"title":       "群青日和",
+		"TITLESORT":   "Gunjou Biyori",
+		"artist-sort": " Tokyo Jihen ",
+		"comment":     "ripped",
+	})
+	if tags.Title != "群青日和" || !reflect.DeepEqual(tags.SearchAliases, []string{"Gunjou Biyori", "Tokyo Jihen"}) {
+		t.Fatalf("tags = %+v", tags)
+	}
+
+	dir := t.TempDir()
+	job := &download.DownloadJob{URL: "file://" + filepath.Join(dir, "track.flac")}
+	metadata := &TrackMetadata{Title: job.URL}
+	applyLocalMetadata(metadata, job, tags, sidecarMetadata{})
+	if !reflect.DeepEqual(metadata.SearchAliases, tags.SearchAliases) {
+		t.Fatalf("SearchAliases = %v, want %v", metadata.SearchAliases, tags.SearchAliases)
+	}
+}
+
+func TestFieldProvenanceSourcesRanksTagsAboveProvider(t *testing.T) {
+	got := fieldProvenanceSources(&TrackMetadata{FieldSources: map[string]string{
+		"title":    metadataSourceNFO,
+		"artist":   metadataSourceEmbedded,
+		"album":    metadataSourceJob,
+		"duration": metadataSourceInfoJSON,
+		"lyrics":   metadataSourceLRC,
+	}})
+	want := map[string]string{
+		"title":       db.FieldSourceTag,
+		"artist":      db.FieldSourceTag,
+		"album":       db.FieldSourceProvider,
+		"duration_ms": db.FieldSourceTag,
+		"lyrics":      db.FieldSourceTag,
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("fieldProvenanceSources = %v, want %v", got, want)
+	}
+
+	downloaded := fieldProvenanceSources(&TrackMetadata{})
+	if len(downloaded) != 4 || downloaded["title"] != db.FieldSourceProvider {
+		t.Fatalf("download sources = %v, want every field from the provider", downloaded)
+	}
+}
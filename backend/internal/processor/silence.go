@@ -0,0 +1,275 @@
+package processor
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"log"
+	"mime"
+	"os"
+	"os/exec"
+	"path"
+	"path/filepath"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/download"
+)
+
+// UserSettingsReader reads the preferences of the user a download is for.
+// db.UserSettingsRepository satisfies this interface.
+type UserSettingsReader interface {
+	Get(ctx context.Context, userID uuid.UUID) (db.UserSettings, error)
+}
+
+// SilenceTrimStore records silence trimmed from tracks' audio.
+// db.SilenceTrimRepository satisfies this interface.
+type SilenceTrimStore interface {
+	Save(ctx context.Context, trim *db.SilenceTrim) error
+}
+
+const (
+	// silenceNoise is the level below which audio counts as silence.
+	silenceNoise = "-50dB"
+	// silencePadMs of each trimmed silence is kept, so tracks do not start
+	// or stop abruptly.
+	silencePadMs = 250
+	// silenceEdgeMs is how near the start or end of the file a silence must
+	// reach to count as leading or trailing.
+	silenceEdgeMs = 50
+	// minTrimmedAudioMs is the least audio a trim may leave; files that are
+	// nearly all silence are kept as they are.
+	minTrimmedAudioMs = 1000
+)
+
+var (
+	silenceStartPattern = regexp.MustCompile(`silence_start: (-?[0-9.]+)`)
+	silenceEndPattern   = regexp.MustCompile(`silence_end: (-?[0-9.]+)`)
+	inputDurationHMS    = regexp.MustCompile(`Duration: ([0-9]+):([0-9]{2}):([0-9]{2}(?:\.[0-9]+)?)`)
+)
+
+// silence is a stretch of silence in milliseconds; an endMs of -1 runs to
+// the end of the file.
+type silence struct {
+	startMs int
+	endMs   int
+}
+
+// downloadSettings returns the preferences of the user a download is for, or
+// the defaults when they cannot be read.
+func (p *Processor) downloadSettings(ctx context.Context, job *download.DownloadJob) db.UserSettings {
+	if p.userSettings == nil {
+		return db.DefaultUserSettings()
+	}
+	userID, err := uuid.Parse(job.UserID)
+	if err != nil {
+		return db.DefaultUserSettings()
+	}
+	settings, err := p.userSettings.Get(ctx, userID)
+	if err != nil {
+		log.Printf("Warning: failed to read download settings for job %s: %v", job.ID, err)
+		return db.DefaultUserSettings()
+	}
+	return settings
+}
+
+// trimSilence cuts leading and trailing silence at least as long as the
+// user's threshold from the audio at audioPath and returns the trimmed copy.
+// Failures are logged and leave the audio as downloaded.
+func (p *Processor) trimSilence(ctx context.Context, jobID string, settings db.UserSettings, metadata *TrackMetadata, audioPath string) string {
+	if p.silenceTrims == nil || settings.TrimSilenceMinMs <= 0 {
+		return ""
+	}
+	silences, durationMs, err := detectSilence(ctx, audioPath, settings.TrimSilenceMinMs)
+	if err != nil {
+		log.Printf("Warning: failed to detect silence for job %s: %v", jobID, err)
+		return ""
+	}
+	leadingMs, trailingMs := silenceBounds(silences, durationMs, settings.TrimSilenceMinMs)
+	if leadingMs == 0 && trailingMs == 0 {
+		return ""
+	}
+	keptMs := durationMs - leadingMs - trailingMs
+	trimmed, err := cutSilence(ctx, audioPath, leadingMs, keptMs)
+	if err != nil {
+		log.Printf("Warning: failed to trim silence for job %s: %v", jobID, err)
+		return ""
+	}
+	metadata.SilenceTrim = &db.SilenceTrim{LeadingMs: leadingMs, TrailingMs: trailingMs}
+	metadata.DurationMs = keptMs
+	// Skip segments were found for the untrimmed audio.
+	var segments []db.SkipSegment
+	for _, s := range metadata.SkipSegments {
+		s.StartMs, s.EndMs = max(s.StartMs-leadingMs, 0), min(s.EndMs-leadingMs, keptMs)
+		if s.EndMs > s.StartMs {
+			segments = append(segments, s)
+		}
+	}
+	metadata.SkipSegments = segments
+	return trimmed
+}
+
+// keepOriginal stores the unprocessed file at originalPath beside the audio
+// stored at key.
+func (p *Processor) keepOriginal(ctx context.Context, metadata *TrackMetadata, originalPath, key string) error {
+	file, err := os.Open(originalPath)
+	if err != nil {
+		return fmt.Errorf("open original audio: %w", err)
+	}
+	defer file.Close()
+	info, err := file.Stat()
+	if err != nil {
+		return fmt.Errorf("stat original audio: %w", err)
+	}
+	contentType := mime.TypeByExtension(filepath.Ext(originalPath))
+	if contentType == "" {
+		contentType = "application/octet-stream"
+	}
+	originalKey := path.Join(path.Dir(key), "originals", path.Base(key))
+	if err := p.storage.PutObject(ctx, originalKey, file, info.Size(), contentType); err != nil {
+		return fmt.Errorf("upload original audio to object storage: %w", err)
+	}
+	metadata.OriginalStorageKey = originalKey
+	metadata.OriginalSizeBytes = info.Size()
+	return nil
+}
+
+// settleProcessedAudio records the silence trimmed from, the loudness
+// normalization applied to and the measured quality and mood of a new
+// track's audio, or deletes the kept original when the download resolved to
+// a track whose audio was stored before.
+func (p *Processor) settleProcessedAudio(ctx context.Context, track *db.Track, metadata *TrackMetadata) {
+	if track.StorageKey.String != metadata.StorageKey {
+		if metadata.OriginalStorageKey == "" {
+			return
+		}
+		if err := p.storage.DeleteObject(ctx, metadata.OriginalStorageKey); err != nil {
+			log.Printf("Warning: failed to delete original object %s for track %d: %v", metadata.OriginalStorageKey, track.ID, err)
+		}
+		return
+	}
+	if trim := metadata.SilenceTrim; trim != nil && metadata.OriginalStorageKey != "" {
+		trim.TrackID = track.ID
+		trim.OriginalStorageKey, trim.OriginalSizeBytes = metadata.OriginalStorageKey, metadata.OriginalSizeBytes
+		if err := p.silenceTrims.Save(ctx, trim); err != nil {
+			log.Printf("Warning: failed to record silence trimmed from track %d: %v", track.ID, err)
+		}
+	}
+	if normalization := metadata.LoudnessNormalization; normalization != nil {
+		normalization.TrackID = track.ID
+		normalization.OriginalStorageKey, normalization.OriginalSizeBytes = metadata.OriginalStorageKey, metadata.OriginalSizeBytes
+		if err := p.loudnessNormalizations.Save(ctx, normalization); err != nil {
+			log.Printf("Warning: failed to record loudness normalization of track %d: %v", track.ID, err)
+		}
+	}
+	if quality := metadata.Quality; quality != nil {
+		quality.TrackID, quality.StorageKey = track.ID, metadata.StorageKey
+		if err := p.trackQuality.Save(ctx, quality); err != nil {
+			log.Printf("Warning: failed to record quality score of track %d: %v", track.ID, err)
+		}
+	}
+	if metadata.MoodMeasured {
+		if err := p.moodFeatures.SaveMoodFeatures(ctx, track.ID, metadata.StorageKey, metadata.Mood); err != nil {
+			log.Printf("Warning: failed to record mood features of track %d: %v", track.ID, err)
+		}
+	}
+}
+
+// detectSilence lists the silences of at least minMs in the file at
+// audioPath, along with its duration.
+func detectSilence(ctx context.Context, audioPath string, minMs int) ([]silence, int, error) {
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-hide_banner", "-nostats",
+		"-i", audioPath,
+		"-map", "0:a", "-af", "silencedetect=noise="+silenceNoise+":d="+seconds(minMs),
+		"-f", "null", "-",
+	)
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return nil, 0, fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	silences, durationMs := parseSilences(stderr.String())
+	if durationMs <= 0 {
+		return nil, 0, fmt.Errorf("ffmpeg reported no duration")
+	}
+	return silences, durationMs, nil
+}
+
+// parseSilences reads silencedetect's log and the input's duration from
+// ffmpeg's output.
+func parseSilences(output string) ([]silence, int) {
+	var silences []silence
+	durationMs := 0
+	for _, line := range strings.Split(output, "\n") {
+		if m := inputDurationHMS.FindStringSubmatch(line); m != nil && durationMs == 0 {
+			hours, _ := strconv.Atoi(m[1])
+			minutes, _ := strconv.Atoi(m[2])
+			secs, _ := strconv.ParseFloat(m[3], 64)
+			durationMs = (hours*3600+minutes*60)*1000 + int(secs*1000)
+		}
+		if m := silenceStartPattern.FindStringSubmatch(line); m != nil {
+			start, _ := strconv.ParseFloat(m[1], 64)
+			silences = append(silences, silence{startMs: max(int(start*1000), 0), endMs: -1})
+		}
+		if m := silenceEndPattern.FindStringSubmatch(line); m != nil && len(silences) > 0 {
+			end, _ := strconv.ParseFloat(m[1], 64)
+			silences[len(silences)-1].endMs = int(end * 1000)
+		}
+	}
+	return silences, durationMs
+}
+
+// silenceBounds returns how much to cut from the start and end of a file of
+// durationMs: silence of at least minMs touching either edge, less
+// silencePadMs. Nothing is cut from files that would keep under
+// minTrimmedAudioMs.
+func silenceBounds(silences []silence, durationMs, minMs int) (int, int) {
+	var leadingMs, trailingMs int
+	for _, s := range silences {
+		end := s.endMs
+		if end < 0 || end > durationMs {
+			end = durationMs
+		}
+		if end-s.startMs < minMs {
+			continue
+		}
+		switch {
+		case s.startMs <= silenceEdgeMs && end < durationMs-silenceEdgeMs:
+			leadingMs = max(end-silencePadMs, 0)
+		case s.startMs > silenceEdgeMs && end >= durationMs-silenceEdgeMs:
+			trailingMs = max(durationMs-s.startMs-silencePadMs, 0)
+		}
+	}
+	if durationMs-leadingMs-trailingMs < minTrimmedAudioMs {
+		return 0, 0
+	}
+	return leadingMs, trailingMs
+}
+
+// cutSilence copies keepMs of src from startMs on to a new temp file and
+// returns its path. Streams are copied, not re-encoded.
+func cutSilence(ctx context.Context, src string, startMs, keepMs int) (string, error) {
+	dst, err := os.CreateTemp("", "omp-silence-*"+filepath.Ext(src))
+	if err != nil {
+		return "", err
+	}
+	dst.Close()
+	cmd := exec.CommandContext(ctx, "ffmpeg",
+		"-nostdin", "-v", "error", "-y",
+		"-ss", seconds(startMs), "-i", src, "-t", seconds(keepMs),
+		"-map", "0:a", "-c", "copy", "-map_metadata", "0",
+		dst.Name(),
+	)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		os.Remove(dst.Name())
+		return "", fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return dst.Name(), nil
+}
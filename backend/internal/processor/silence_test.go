@@ -0,0 +1,39 @@
+package processor
+
+import (
+	"reflect"
+	"testing"
+)
+
+const silencedetectOutput = `Input #0, mp3, from 'rip.mp3':
+  Duration: 00:03:25.50, start: 0.025057, bitrate: 192 kb/s
+  Stream #0:0: Audio: mp3, 44100 Hz, stereo, fltp, 192 kb/s
+[silencedetect @ 0x55d0c8a0] silence_start: 0
+[silencedetect @ 0x55d0c8a0] silence_end: 4.2 | silence_duration: 4.2
+[silencedetect @ 0x55d0c8a0] silence_start: 97.5
+[silencedetect @ 0x55d0c8a0] silence_end: 100.5 | silence_duration: 3
+[silencedetect @ 0x55d0c8a0] silence_start: 190.25
+size=N/A time=00:03:25.50 bitrate=N/A speed= 412x
+`
+
+func TestParseSilencesReadsDurationAndOpenTrailingSilence(t *testing.T) {
+	silences, durationMs := parseSilences(silencedetectOutput)
+	want := []silence{{0, 4200}, {97500, 100500}, {190250, -1}}
+	if durationMs != 205500 || !reflect.DeepEqual(silences, want) {
+		t.Fatalf("parseSilences() = %v, %d; want %v, 205500", silences, durationMs, want)
+	}
+}
+
+func TestSilenceBoundsTrimOnlyEdgesPastTheThreshold(t *testing.T) {
+	silences := []silence{{0, 4200}, {97500, 100500}, {190250, -1}}
+	if leading, trailing := silenceBounds(silences, 205500, 3000); leading != 3950 || trailing != 15000 {
+		t.Fatalf("silenceBounds() = %d, %d; want 3950, 15000", leading, trailing)
+	}
+	// The 4.2s intro is under a 5s threshold; the mid-track gap is never cut.
+	if leading, trailing := silenceBounds(silences, 205500, 5000); leading != 0 || trailing != 15000 {
+		t.Fatalf("silenceBounds() with 5s threshold = %d, %d; want 0, 15000", leading, trailing)
+	}
+	if leading, trailing := silenceBounds([]silence{{0, -1}}, 60000, 3000); leading != 0 || trailing != 0 {
+		t.Fatalf("silent file trimmed by %d, %d; want untouched", leading, trailing)
+	}
+}
@@ -0,0 +1,138 @@
+package processor
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"log"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// SkipSegmentSource looks up the non-music segments of a YouTube video.
+// sponsorblock.Client satisfies this interface.
+type SkipSegmentSource interface {
+	Segments(ctx context.Context, videoID string) ([]db.SkipSegment, error)
+}
+
+// SkipSegmentStore persists a track's skip segments.
+// db.SkipSegmentRepository satisfies this interface.
+type SkipSegmentStore interface {
+	Replace(ctx context.Context, trackID int64, segments []db.SkipSegment) error
+}
+
+// findSkipSegments looks up the non-music segments of a YouTube download and
+// keeps them on metadata as skip ranges or, when trimming is on, cuts them
+// from the audio at path and returns the trimmed copy. Failures are logged
+// and leave the audio as downloaded.
+func (p *Processor) findSkipSegments(ctx context.Context, jobID string, metadata *TrackMetadata, path string) string {
+	videoID := stringValue(metadata.Raw, "id")
+	if p.skipSegmentSource == nil || stringValue(metadata.Raw, "extractor_key") != "Youtube" || videoID == "" {
+		return ""
+	}
+	segments, err := p.skipSegmentSource.Segments(ctx, videoID)
+	if err != nil {
+		log.Printf("Warning: failed to look up skip segments for job %s: %v", jobID, err)
+		return ""
+	}
+	if len(segments) == 0 {
+		return ""
+	}
+	if !p.trimSkipSegments {
+		metadata.SkipSegments = segments
+		return ""
+	}
+	ranges := mergeSkipSegments(segments, metadata.DurationMs)
+	trimmed, err := trimAudio(ctx, path, ranges)
+	if err != nil {
+		log.Printf("Warning: failed to trim skip segments for job %s: %v", jobID, err)
+		metadata.SkipSegments = segments
+		return ""
+	}
+	for _, r := range ranges {
+		metadata.DurationMs -= r[1] - r[0]
+		metadata.TrimmedMs += r[1] - r[0]
+	}
+	return trimmed
+}
+
+// storeSkipSegments saves the skip segments found for a new track. An
+// existing track keeps its own, which may describe audio stored trimmed.
+func (p *Processor) storeSkipSegments(ctx context.Context, trackID int64, isNew bool, metadata *TrackMetadata) {
+	if p.skipSegments == nil || !isNew || len(metadata.SkipSegments) == 0 {
+		return
+	}
+	if err := p.skipSegments.Replace(ctx, trackID, metadata.SkipSegments); err != nil {
+		log.Printf("Warning: failed to store skip segments for track %d: %v", trackID, err)
+	}
+}
+
+// mergeSkipSegments returns the segments as ordered, non-overlapping
+// [start, end) ranges in milliseconds, clipped to durationMs when known.
+func mergeSkipSegments(segments []db.SkipSegment, durationMs int) [][2]int {
+	var ranges [][2]int
+	for _, s := range segments {
+		start, end := max(s.StartMs, 0), s.EndMs
+		if durationMs > 0 {
+			end = min(end, durationMs)
+		}
+		if end > start {
+			ranges = append(ranges, [2]int{start, end})
+		}
+	}
+	sort.Slice(ranges, func(i, j int) bool { return ranges[i][0] < ranges[j][0] })
+	merged := ranges[:0]
+	for _, r := range ranges {
+		if last := len(merged) - 1; last >= 0 && r[0] <= merged[last][1] {
+			merged[last][1] = max(merged[last][1], r[1])
+			continue
+		}
+		merged = append(merged, r)
+	}
+	return merged
+}
+
+// trimAudio writes src without ranges to a new temp file and returns its
+// path. Cutting mid-stream means re-encoding, so the copy keeps src's format
+// at a high VBR quality.
+func trimAudio(ctx context.Context, src string, ranges [][2]int) (string, error) {
+	dst, err := os.CreateTemp("", "omp-trim-*"+filepath.Ext(src))
+	if err != nil {
+		return "", err
+	}
+	dst.Close()
+	cmd := exec.CommandContext(ctx, "ffmpeg", trimArgs(src, dst.Name(), ranges)...)
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		os.Remove(dst.Name())
+		return "", fmt.Errorf("ffmpeg failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return dst.Name(), nil
+}
+
+// trimArgs drops the audio inside ranges and closes the gaps they leave.
+func trimArgs(src, dst string, ranges [][2]int) []string {
+	between := make([]string, 0, len(ranges))
+	for _, r := range ranges {
+		between = append(between, "between(t,"+seconds(r[0])+","+seconds(r[1])+")")
+	}
+	filter := "aselect='not(" + strings.Join(between, "+") + ")',asetpts=N/SR/TB"
+	return []string{
+		"-nostdin", "-v", "error", "-y",
+		"-i", src,
+		"-map", "0:a", "-af", filter,
+		"-map_metadata", "0", "-q:a", "2",
+		dst,
+	}
+}
+
+func seconds(ms int) string {
+	return strconv.FormatFloat(float64(ms)/1000, 'f', 3, 64)
+}
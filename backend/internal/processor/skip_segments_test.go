@@ -0,0 +1,56 @@
+package processor
+
+import (
+	"context"
+	"reflect"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakeSkipSegmentSource map[string][]db.SkipSegment
+
+func (f fakeSkipSegmentSource) Segments(_ context.Context, videoID string) ([]db.SkipSegment, error) {
+	return f[videoID], nil
+}
+
+func TestFindSkipSegmentsKeepsYouTubeSegmentsAsSkipRanges(t *testing.T) {
+	sponsor := []db.SkipSegment{{Category: "sponsor", StartMs: 0, EndMs: 15000, Source: db.SkipSegmentSourceSponsorBlock}}
+	p := New(&ProcessorConfig{SkipSegmentSource: fakeSkipSegmentSource{"abc123": sponsor}})
+
+	metadata := &TrackMetadata{Raw: map[string]interface{}{"extractor_key": "Youtube", "id": "abc123"}}
+	if trimmed := p.findSkipSegments(context.Background(), "job", metadata, "audio.mp3"); trimmed != "" || !reflect.DeepEqual(metadata.SkipSegments, sponsor) {
+		t.Fatalf("findSkipSegments() = %q, segments %+v", trimmed, metadata.SkipSegments)
+	}
+	other := &TrackMetadata{Raw: map[string]interface{}{"extractor_key": "Soundcloud", "id": "abc123"}}
+	p.findSkipSegments(context.Background(), "job", other, "audio.mp3")
+	if other.SkipSegments != nil {
+		t.Fatalf("segments looked up for a SoundCloud source: %+v", other.SkipSegments)
+	}
+}
+
+func TestMergeSkipSegmentsJoinsOverlapsWithinTheTrack(t *testing.T) {
+	got := mergeSkipSegments([]db.SkipSegment{
+		{Category: "outro", StartMs: 190000, EndMs: 230000},
+		{Category: "sponsor", StartMs: 5000, EndMs: 20000},
+		{Category: "intro", StartMs: 0, EndMs: 8000},
+		{Category: "selfpromo", StartMs: 60000, EndMs: 70000},
+	}, 200000)
+	if want := [][2]int{{0, 20000}, {60000, 70000}, {190000, 200000}}; !reflect.DeepEqual(got, want) {
+		t.Fatalf("mergeSkipSegments() = %v, want %v", got, want)
+	}
+}
+
+func TestTrimArgsSelectAudioOutsideTheRanges(t *testing.T) {
+	got := trimArgs("in.mp3", "out.mp3", [][2]int{{0, 20000}, {190000, 200500}})
+	want := []string{
+		"-nostdin", "-v", "error", "-y",
+		"-i", "in.mp3",
+		"-map", "0:a", "-af", "aselect='not(between(t,0.000,20.000)+between(t,190.000,200.500))',asetpts=N/SR/TB",
+		"-map_metadata", "0", "-q:a", "2",
+		"out.mp3",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Fatalf("trimArgs() = %q, want %q", got, want)
+	}
+}
@@ -0,0 +1,198 @@
+package processor
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"math"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+// r128ToReplayGainDB converts Opus R128 gains, which aim for -23 LUFS, to
+// ReplayGain's -18 LUFS reference.
+const r128ToReplayGainDB = 5
+
+// ErrNoStoredAudio is returned for tracks with no audio object.
+var ErrNoStoredAudio = errors.New("track has no stored audio object")
+
+// TechnicalInfo is what a track's stored audio file actually holds, as
+// ffprobe reads it.
+type TechnicalInfo struct {
+	Codec             string
+	CodecLongName     string
+	Container         string
+	ContainerLongName string
+	BitrateKbps       int
+	SampleRateHz      int
+	// BitDepth is 0 for lossy codecs, which have none.
+	BitDepth      int
+	Channels      int
+	ChannelLayout string
+	DurationMs    int
+	SizeBytes     int64
+	Encoder       string
+	ReplayGain    *ReplayGain
+	// Tags are the file's container and audio stream tags, with lower-cased
+	// names; container tags win.
+	Tags map[string]string
+}
+
+// ReplayGain holds the gain tags of a file, in dB, with peaks as linear
+// sample amplitudes. Fields the file does not tag are nil.
+type ReplayGain struct {
+	TrackGainDB *float64
+	TrackPeak   *float64
+	AlbumGainDB *float64
+	AlbumPeak   *float64
+}
+
+type ffprobeTechnical struct {
+	Streams []struct {
+		CodecName        string            `json:"codec_name"`
+		CodecLongName    string            `json:"codec_long_name"`
+		SampleRate       string            `json:"sample_rate"`
+		Channels         int               `json:"channels"`
+		ChannelLayout    string            `json:"channel_layout"`
+		BitsPerSample    int               `json:"bits_per_sample"`
+		BitsPerRawSample string            `json:"bits_per_raw_sample"`
+		BitRate          string            `json:"bit_rate"`
+		Tags             map[string]string `json:"tags"`
+	} `json:"streams"`
+	Format struct {
+		FormatName     string            `json:"format_name"`
+		FormatLongName string            `json:"format_long_name"`
+		Duration       string            `json:"duration"`
+		Size           string            `json:"size"`
+		BitRate        string            `json:"bit_rate"`
+		Tags           map[string]string `json:"tags"`
+	} `json:"format"`
+}
+
+// TechnicalInfo probes the track's stored audio object.
+func (p *Processor) TechnicalInfo(ctx context.Context, track *db.Track) (*TechnicalInfo, error) {
+	if p.storage == nil {
+		return nil, errors.New("object storage is not configured")
+	}
+	key := strings.TrimSpace(track.StorageKey.String)
+	if key == "" {
+		return nil, ErrNoStoredAudio
+	}
+	scratch, err := os.MkdirTemp("", "omp-technical-*")
+	if err != nil {
+		return nil, err
+	}
+	defer os.RemoveAll(scratch)
+	src, err := p.fetchStoredAudio(ctx, key, scratch)
+	if err != nil {
+		return nil, err
+	}
+
+	probeCtx, cancel := context.WithTimeout(ctx, audioQualityProbeTimeout)
+	defer cancel()
+	cmd := exec.CommandContext(probeCtx, "ffprobe",
+		"-v", "error",
+		"-select_streams", "a:0",
+		"-show_streams", "-show_format",
+		"-of", "json",
+		src,
+	)
+	stdout := limitedOutput{limit: maxYTDLPLogBytes}
+	stderr := limitedOutput{limit: maxYTDLPLogBytes}
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return nil, fmt.Errorf("ffprobe failed: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return parseTechnicalInfo([]byte(stdout.String()))
+}
+
+func parseTechnicalInfo(output []byte) (*TechnicalInfo, error) {
+	var probed ffprobeTechnical
+	if err := json.Unmarshal(output, &probed); err != nil {
+		return nil, fmt.Errorf("decode ffprobe output: %w", err)
+	}
+	if len(probed.Streams) == 0 {
+		return nil, errors.New("ffprobe found no audio stream")
+	}
+	stream := probed.Streams[0]
+	info := &TechnicalInfo{
+		Codec:             stream.CodecName,
+		CodecLongName:     stream.CodecLongName,
+		Container:         probed.Format.FormatName,
+		ContainerLongName: probed.Format.FormatLongName,
+		Channels:          stream.Channels,
+		ChannelLayout:     stream.ChannelLayout,
+		BitDepth:          stream.BitsPerSample,
+		Tags:              map[string]string{},
+	}
+	info.SampleRateHz, _ = strconv.Atoi(stream.SampleRate)
+	if depth, err := strconv.Atoi(stream.BitsPerRawSample); err == nil && depth > 0 {
+		info.BitDepth = depth
+	}
+	bitRate, _ := strconv.ParseInt(stream.BitRate, 10, 64)
+	if bitRate <= 0 {
+		bitRate, _ = strconv.ParseInt(probed.Format.BitRate, 10, 64)
+	}
+	info.BitrateKbps = int((bitRate + 500) / 1000)
+	if seconds, err := strconv.ParseFloat(probed.Format.Duration, 64); err == nil {
+		info.DurationMs = int(math.Round(seconds * 1000))
+	}
+	info.SizeBytes, _ = strconv.ParseInt(probed.Format.Size, 10, 64)
+
+	for key, value := range stream.Tags {
+		info.Tags[strings.ToLower(key)] = strings.TrimSpace(value)
+	}
+	for key, value := range probed.Format.Tags {
+		info.Tags[strings.ToLower(key)] = strings.TrimSpace(value)
+	}
+	info.Encoder = firstNonEmpty(info.Tags["encoder"], info.Tags["encoded_by"], info.Tags["encoding_tool"])
+	info.ReplayGain = replayGain(info.Tags)
+	return info, nil
+}
+
+// replayGain reads ReplayGain tags, falling back to the R128 gains Opus files
+// carry instead.
+func replayGain(tags map[string]string) *ReplayGain {
+	gain := &ReplayGain{
+		TrackGainDB: tagNumber(tags["replaygain_track_gain"]),
+		TrackPeak:   tagNumber(tags["replaygain_track_peak"]),
+		AlbumGainDB: tagNumber(tags["replaygain_album_gain"]),
+		AlbumPeak:   tagNumber(tags["replaygain_album_peak"]),
+	}
+	if gain.TrackGainDB == nil {
+		gain.TrackGainDB = r128Gain(tags["r128_track_gain"])
+	}
+	if gain.AlbumGainDB == nil {
+		gain.AlbumGainDB = r128Gain(tags["r128_album_gain"])
+	}
+	if *gain == (ReplayGain{}) {
+		return nil
+	}
+	return gain
+}
+
+// tagNumber reads values such as "-6.52 dB" or "0.988525".
+func tagNumber(value string) *float64 {
+	value = strings.TrimSpace(strings.TrimSuffix(strings.TrimSpace(value), "dB"))
+	n, err := strconv.ParseFloat(value, 64)
+	if err != nil {
+		return nil
+	}
+	return &n
+}
+
+// r128Gain reads an R128 gain, a Q7.8 fixed-point number of dB.
+func r128Gain(value string) *float64 {
+	q, err := strconv.Atoi(strings.TrimSpace(value))
+	if err != nil {
+		return nil
+	}
+	gain := float64(q)/256 + r128ToReplayGainDB
+	return &gain
+}
@@ -0,0 +1,57 @@
+package processor
+
+import "testing"
+
+const ffprobeOpusOutput = `{
+	"streams": [{
+		"codec_name": "opus",
+		"codec_long_name": "Opus (Opus Interactive Audio Codec)",
+		"sample_rate": "48000",
+		"channels": 2,
+		"channel_layout": "stereo",
+		"bits_per_sample": 0,
+		"tags": {"ENCODER": "Lavc61.3.100 libopus", "R128_TRACK_GAIN": "-1280"}
+	}],
+	"format": {
+		"format_name": "ogg",
+		"format_long_name": "Ogg",
+		"duration": "205.512000",
+		"size": "4113920",
+		"bit_rate": "160143",
+		"tags": {"TITLE": "Ratio", "replaygain_album_gain": "-6.52 dB", "replaygain_album_peak": "0.988525"}
+	}
+}`
+
+func TestParseTechnicalInfoReadsStreamFormatAndGain(t *testing.T) {
+	info, err := parseTechnicalInfo([]byte(ffprobeOpusOutput))
+	if err != nil {
+		t.Fatalf("parseTechnicalInfo() error = %v", err)
+	}
+	if info.Codec != "opus" || info.Container != "ogg" || info.SampleRateHz != 48000 || info.Channels != 2 || info.BitDepth != 0 {
+		t.Fatalf("stream = %+v", info)
+	}
+	if info.BitrateKbps != 160 || info.DurationMs != 205512 || info.SizeBytes != 4113920 {
+		t.Fatalf("format = %+v", info)
+	}
+	if info.Encoder != "Lavc61.3.100 libopus" || info.Tags["title"] != "Ratio" {
+		t.Fatalf("tags = %v, encoder %q", info.Tags, info.Encoder)
+	}
+	// R128 gain -1280/256 = -5 dB at -23 LUFS is 0 dB at ReplayGain's -18.
+	gain := info.ReplayGain
+	if gain == nil || *gain.TrackGainDB != 0 || *gain.AlbumGainDB != -6.52 || *gain.AlbumPeak != 0.988525 || gain.TrackPeak != nil {
+		t.Fatalf("ReplayGain = %+v", gain)
+	}
+}
+
+func TestParseTechnicalInfoPrefersRawBitDepth(t *testing.T) {
+	info, err := parseTechnicalInfo([]byte(`{"streams": [{"codec_name": "flac", "bits_per_sample": 0, "bits_per_raw_sample": "24", "bit_rate": ""}], "format": {"bit_rate": "2116800"}}`))
+	if err != nil {
+		t.Fatalf("parseTechnicalInfo() error = %v", err)
+	}
+	if info.BitDepth != 24 || info.BitrateKbps != 2117 || info.ReplayGain != nil {
+		t.Fatalf("parseTechnicalInfo() = %+v", info)
+	}
+	if _, err := parseTechnicalInfo([]byte(`{"streams": [], "format": {}}`)); err == nil {
+		t.Fatal("parseTechnicalInfo() without an audio stream succeeded")
+	}
+}
@@ -0,0 +1,176 @@
+package push
+
+import (
+	"bytes"
+	"context"
+	"crypto/rsa"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"os"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/golang-jwt/jwt/v5"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+const (
+	fcmScope   = "https://www.googleapis.com/auth/firebase.messaging"
+	fcmBaseURL = "https://fcm.googleapis.com"
+
+	// tokenMargin renews an access token this long before it expires.
+	tokenMargin = 5 * time.Minute
+)
+
+// serviceAccount is the part of a Firebase service account key FCM needs.
+type serviceAccount struct {
+	ProjectID   string `json:"project_id"`
+	ClientEmail string `json:"client_email"`
+	PrivateKey  string `json:"private_key"`
+	TokenURI    string `json:"token_uri"`
+}
+
+// FCM sends notifications through the Firebase Cloud Messaging HTTP v1 API,
+// authenticated as a service account.
+type FCM struct {
+	projectID string
+	email     string
+	key       *rsa.PrivateKey
+	tokenURL  string
+	baseURL   string
+	client    *http.Client
+	now       func() time.Time
+
+	mu      sync.Mutex
+	token   string
+	expires time.Time
+}
+
+// LoadFCM reads a Firebase service account key file.
+func LoadFCM(path string) (*FCM, error) {
+	raw, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	var account serviceAccount
+	if err := json.Unmarshal(raw, &account); err != nil {
+		return nil, fmt.Errorf("parse FCM service account: %w", err)
+	}
+	if account.ProjectID == "" || account.ClientEmail == "" || account.TokenURI == "" {
+		return nil, errors.New("FCM service account needs project_id, client_email and token_uri")
+	}
+	key, err := jwt.ParseRSAPrivateKeyFromPEM([]byte(account.PrivateKey))
+	if err != nil {
+		return nil, fmt.Errorf("parse FCM service account key: %w", err)
+	}
+	return &FCM{
+		projectID: account.ProjectID,
+		email:     account.ClientEmail,
+		key:       key,
+		tokenURL:  account.TokenURI,
+		baseURL:   fcmBaseURL,
+		client:    &http.Client{Timeout: requestTimeout},
+		now:       time.Now,
+	}, nil
+}
+
+// fcmMessage is the body of a messages:send request. Data values must be
+// strings, so the notification travels as JSON under "notification".
+type fcmMessage struct {
+	Message struct {
+		Token        string            `json:"token"`
+		Notification map[string]string `json:"notification"`
+		Data         map[string]string `json:"data"`
+	} `json:"message"`
+}
+
+// Send delivers n to one registration token.
+func (f *FCM) Send(ctx context.Context, token string, n db.Notification) error {
+	access, err := f.accessToken(ctx)
+	if err != nil {
+		return err
+	}
+	var msg fcmMessage
+	msg.Message.Token = token
+	msg.Message.Notification = map[string]string{"title": n.Title, "body": n.Body}
+	msg.Message.Data = map[string]string{"type": n.Type, "id": fmt.Sprint(n.ID), "data": string(n.Data)}
+	body, err := json.Marshal(msg)
+	if err != nil {
+		return err
+	}
+	endpoint := f.baseURL + "/v1/projects/" + url.PathEscape(f.projectID) + "/messages:send"
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, endpoint, bytes.NewReader(body))
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Content-Type", "application/json")
+	req.Header.Set("Authorization", "Bearer "+access)
+	resp, err := f.client.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode < 300 {
+		return nil
+	}
+	detail, _ := io.ReadAll(io.LimitReader(resp.Body, 4096))
+	if resp.StatusCode == http.StatusNotFound || strings.Contains(string(detail), "UNREGISTERED") {
+		return errGone
+	}
+	return fmt.Errorf("FCM send: %s", resp.Status)
+}
+
+// accessToken returns an OAuth access token for the service account,
+// exchanging a signed assertion for a new one when the last is about to
+// expire.
+func (f *FCM) accessToken(ctx context.Context) (string, error) {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	now := f.now()
+	if f.token != "" && now.Before(f.expires.Add(-tokenMargin)) {
+		return f.token, nil
+	}
+	assertion, err := jwt.NewWithClaims(jwt.SigningMethodRS256, jwt.MapClaims{
+		"iss":   f.email,
+		"scope": fcmScope,
+		"aud":   f.tokenURL,
+		"iat":   now.Unix(),
+		"exp":   now.Add(time.Hour).Unix(),
+	}).SignedString(f.key)
+	if err != nil {
+		return "", err
+	}
+	form := url.Values{"grant_type": {"urn:ietf:params:oauth:grant-type:jwt-bearer"}, "assertion": {assertion}}
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, f.tokenURL, strings.NewReader(form.Encode()))
+	if err != nil {
+		return "", err
+	}
+	req.Header.Set("Content-Type", "application/x-www-form-urlencoded")
+	resp, err := f.client.Do(req)
+	if err != nil {
+		return "", err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode >= 300 {
+		return "", fmt.Errorf("FCM token exchange: %s", resp.Status)
+	}
+	var result struct {
+		AccessToken string `json:"access_token"`
+		ExpiresIn   int    `json:"expires_in"`
+	}
+	if err := json.NewDecoder(resp.Body).Decode(&result); err != nil {
+		return "", fmt.Errorf("FCM token exchange: %w", err)
+	}
+	if result.AccessToken == "" {
+		return "", errors.New("FCM token exchange returned no token")
+	}
+	f.token = result.AccessToken
+	f.expires = now.Add(time.Duration(result.ExpiresIn) * time.Second)
+	return f.token, nil
+}
@@ -0,0 +1,140 @@
+// Package push delivers notifications to phones that have no WebSocket open,
+// through UnifiedPush endpoints or Firebase Cloud Messaging.
+package push
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net/http"
+	"net/url"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/notifications"
+)
+
+// DefaultTypes are the notification types a device receives when it does
+// not name any on registration.
+var DefaultTypes = []string{db.NotificationDownloadCompleted, db.NotificationNewRelease}
+
+const (
+	// requestTimeout bounds one delivery.
+	requestTimeout = 30 * time.Second
+
+	// maxUnifiedPushMessage is the largest message UnifiedPush distributors
+	// must accept, in bytes.
+	maxUnifiedPushMessage = 4096
+
+	// messageTTL is how long a distributor keeps a message for a phone that
+	// is offline.
+	messageTTL = 24 * time.Hour
+)
+
+// errGone means the provider no longer knows the device, so it is forgotten.
+var errGone = errors.New("push device is gone")
+
+// Store finds the devices to deliver to.
+type Store interface {
+	ForNotification(ctx context.Context, userID uuid.UUID, kind string) ([]db.PushDevice, error)
+	Forget(ctx context.Context, id int64) error
+}
+
+// Gateway delivers notifications to the devices of their user that opted in
+// to their type.
+type Gateway struct {
+	store  Store
+	client *http.Client
+	fcm    *FCM
+}
+
+// NewGateway creates a gateway. client posts to UnifiedPush endpoints, which
+// users choose, so it should only reach public addresses. fcm may be nil, in
+// which case FCM devices are skipped.
+func NewGateway(store Store, client *http.Client, fcm *FCM) *Gateway {
+	return &Gateway{store: store, client: client, fcm: fcm}
+}
+
+// ValidEndpoint checks a UnifiedPush endpoint before it is registered.
+func ValidEndpoint(raw string) error {
+	endpoint, err := url.Parse(raw)
+	if err != nil || endpoint.Scheme != "https" || endpoint.Host == "" || endpoint.User != nil {
+		return errors.New("endpoint must be an https URL")
+	}
+	return nil
+}
+
+// Deliver sends n to the user's devices in the background.
+func (g *Gateway) Deliver(ctx context.Context, n db.Notification) {
+	devices, err := g.store.ForNotification(ctx, n.UserID, n.Type)
+	if err != nil {
+		log.Printf("Failed to find push devices of %s: %v", n.UserID, err)
+		return
+	}
+	for _, device := range devices {
+		go func(device db.PushDevice) {
+			ctx, cancel := context.WithTimeout(context.Background(), requestTimeout)
+			defer cancel()
+			err := g.send(ctx, device, n)
+			if errors.Is(err, errGone) {
+				err = g.store.Forget(ctx, device.ID)
+			}
+			if err != nil {
+				log.Printf("Failed to push notification %d to device %d: %v", n.ID, device.ID, err)
+			}
+		}(device)
+	}
+}
+
+func (g *Gateway) send(ctx context.Context, device db.PushDevice, n db.Notification) error {
+	switch device.Provider {
+	case db.PushUnifiedPush:
+		return g.sendUnifiedPush(ctx, device.Token, n)
+	case db.PushFCM:
+		if g.fcm == nil {
+			return nil
+		}
+		return g.fcm.Send(ctx, device.Token, n)
+	default:
+		return fmt.Errorf("unknown push provider %q", device.Provider)
+	}
+}
+
+// sendUnifiedPush posts the same message the WebSocket carries to the
+// endpoint. Data too large for a distributor is left out; the app can load
+// the notification from the API.
+func (g *Gateway) sendUnifiedPush(ctx context.Context, endpoint string, n db.Notification) error {
+	body, err := json.Marshal(notifications.Push{Type: notifications.PushType, Notification: notifications.NewMessage(n)})
+	if err != nil {
+		return err
+	}
+	if len(body) > maxUnifiedPushMessage {
+		n.Data, n.Body = nil, ""
+		if body, err = json.Marshal(notifications.Push{Type: notifications.PushType, Notification: notifications.NewMessage(n)}); err != nil {
+			return err
+		}
+	}
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, endpoint, bytes.NewReader(body))
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Content-Type", "application/json")
+	req.Header.Set("TTL", fmt.Sprint(int(messageTTL.Seconds())))
+	resp, err := g.client.Do(req)
+	if err != nil {
+		return err
+	}
+	resp.Body.Close()
+	switch {
+	case resp.StatusCode == http.StatusNotFound || resp.StatusCode == http.StatusGone:
+		return errGone
+	case resp.StatusCode >= 300:
+		return fmt.Errorf("POST %s: %s", endpoint, resp.Status)
+	}
+	return nil
+}
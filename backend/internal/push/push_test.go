@@ -0,0 +1,145 @@
+package push
+
+import (
+	"context"
+	"crypto/rand"
+	"crypto/rsa"
+	"crypto/x509"
+	"encoding/json"
+	"encoding/pem"
+	"errors"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestValidEndpoint(t *testing.T) {
+	for endpoint, valid := range map[string]bool{
+		"https://push.example.test/up?x=1":  true,
+		"http://push.example.test/up":       false,
+		"https://user:pw@push.example.test": false,
+		"https://push.example.test:port/up": false,
+	} {
+		if err := ValidEndpoint(endpoint); (err == nil) != valid {
+			t.Errorf("ValidEndpoint(%q) = %v", endpoint, err)
+		}
+	}
+}
+
+func TestSendUnifiedPush(t *testing.T) {
+	var body []byte
+	var ttl string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path == "/gone" {
+			w.WriteHeader(http.StatusGone)
+			return
+		}
+		body, _ = io.ReadAll(r.Body)
+		ttl = r.Header.Get("TTL")
+		w.WriteHeader(http.StatusCreated)
+	}))
+	defer server.Close()
+
+	g := NewGateway(nil, server.Client(), nil)
+	n := db.Notification{ID: 3, UserID: uuid.New(), Type: db.NotificationNewRelease, Title: "New from Band", Data: json.RawMessage(`{"trackId":9}`)}
+	device := db.PushDevice{Provider: db.PushUnifiedPush, Token: server.URL + "/up"}
+	if err := g.send(context.Background(), device, n); err != nil {
+		t.Fatalf("send: %v", err)
+	}
+	if !strings.Contains(string(body), `"type":"notification"`) || !strings.Contains(string(body), `"trackId":9`) || ttl != "86400" {
+		t.Fatalf("posted %s with TTL %q", body, ttl)
+	}
+
+	n.Body = strings.Repeat("x", maxUnifiedPushMessage)
+	if err := g.send(context.Background(), device, n); err != nil || len(body) > maxUnifiedPushMessage || strings.Contains(string(body), "trackId") {
+		t.Fatalf("send of a long notification = %v, posted %d bytes; want the body and data dropped", err, len(body))
+	}
+
+	device.Token = server.URL + "/gone"
+	if err := g.send(context.Background(), device, n); !errors.Is(err, errGone) {
+		t.Fatalf("send to a removed endpoint = %v; want errGone", err)
+	}
+}
+
+func TestFCM(t *testing.T) {
+	key, err := rsa.GenerateKey(rand.Reader, 2048)
+	if err != nil {
+		t.Fatal(err)
+	}
+	der, err := x509.MarshalPKCS8PrivateKey(key)
+	if err != nil {
+		t.Fatal(err)
+	}
+	var exchanges int
+	var sent map[string]map[string]any
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/token":
+			exchanges++
+			if r.FormValue("grant_type") != "urn:ietf:params:oauth:grant-type:jwt-bearer" || r.FormValue("assertion") == "" {
+				w.WriteHeader(http.StatusBadRequest)
+				return
+			}
+			w.Write([]byte(`{"access_token":"access","expires_in":3600}`))
+		case "/v1/projects/music/messages:send":
+			if r.Header.Get("Authorization") != "Bearer access" {
+				w.WriteHeader(http.StatusUnauthorized)
+				return
+			}
+			json.NewDecoder(r.Body).Decode(&sent)
+			if sent["message"]["token"] == "stale" {
+				w.WriteHeader(http.StatusNotFound)
+				w.Write([]byte(`{"error":{"status":"NOT_FOUND","details":[{"errorCode":"UNREGISTERED"}]}}`))
+			}
+		default:
+			w.WriteHeader(http.StatusNotFound)
+		}
+	}))
+	defer server.Close()
+
+	account, _ := json.Marshal(serviceAccount{
+		ProjectID:   "music",
+		ClientEmail: "push@music.iam.example.test",
+		PrivateKey:  string(pem.EncodeToMemory(&pem.Block{Type: "PRIVATE KEY", Bytes: der})),
+		TokenURI:    server.URL + "/token",
+	})
+	path := filepath.Join(t.TempDir(), "account.json")
+	if err := os.WriteFile(path, account, 0o600); err != nil {
+		t.Fatal(err)
+	}
+	fcm, err := LoadFCM(path)
+	if err != nil {
+		t.Fatalf("LoadFCM: %v", err)
+	}
+	fcm.baseURL, fcm.client = server.URL, server.Client()
+
+	n := db.Notification{ID: 4, Type: db.NotificationDownloadCompleted, Title: "Download finished", Body: "Band – Song"}
+	for range 2 {
+		if err := fcm.Send(context.Background(), "phone", n); err != nil {
+			t.Fatalf("Send: %v", err)
+		}
+	}
+	if exchanges != 1 {
+		t.Fatalf("exchanged %d assertions; want the access token reused", exchanges)
+	}
+	if sent["message"]["token"] != "phone" || sent["message"]["notification"].(map[string]any)["body"] != "Band – Song" {
+		t.Fatalf("sent %+v", sent)
+	}
+	if err := fcm.Send(context.Background(), "stale", n); !errors.Is(err, errGone) {
+		t.Fatalf("Send to an unregistered token = %v; want errGone", err)
+	}
+
+	fcm.now = func() time.Time { return time.Now().Add(2 * time.Hour) }
+	if err := fcm.Send(context.Background(), "phone", n); err != nil || exchanges != 2 {
+		t.Fatalf("Send after the token expired = %v with %d exchanges; want a new token", err, exchanges)
+	}
+}
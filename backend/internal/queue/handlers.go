@@ -16,6 +16,7 @@ import (
 	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
 	"github.com/openmusicplayer/backend/internal/download"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 // Handlers provides HTTP handlers for queue operations
@@ -25,6 +26,7 @@ type Handlers struct {
 	analysisRepo    *db.AnalysisRepository
 	selectionRepo   sourceDecisionRepository
 	database        durableDownloadJobStore
+	settings        UserSettingsReader
 }
 
 // These seams keep the HTTP boundary testable without Redis or PostgreSQL.
@@ -60,6 +62,12 @@ type durableDownloadJobStore interface {
 	ExecContext(context.Context, string, ...any) (sql.Result, error)
 }
 
+// UserSettingsReader reads the playback preferences queue responses carry.
+// db.UserSettingsRepository satisfies this interface.
+type UserSettingsReader interface {
+	Get(ctx context.Context, userID uuid.UUID) (db.UserSettings, error)
+}
+
 // NewHandlers creates a new Handlers instance
 func NewHandlers(service queueHandlerService, downloadServices ...queueDownloadService) *Handlers {
 	var downloadService queueDownloadService
@@ -79,6 +87,13 @@ func NewHandlersWithSourceSelections(service queueHandlerService, downloadServic
 	return &Handlers{service: service, downloadService: downloadService, analysisRepo: analysisRepo, selectionRepo: selectionRepo, database: database}
 }
 
+// WithPlaybackSettings includes the caller's playback preferences in queue
+// responses; without it they carry the defaults.
+func (h *Handlers) WithPlaybackSettings(settings UserSettingsReader) *Handlers {
+	h.settings = settings
+	return h
+}
+
 // ErrorResponse represents an error response
 type ErrorResponse struct {
 	Code    string `json:"code"`
@@ -90,6 +105,15 @@ type QueueResponse struct {
 	Items           []QueueItemResponse `json:"items"`
 	CurrentPosition int                 `json:"currentPosition"`
 	UpdatedAt       time.Time           `json:"updatedAt"`
+	Playback        PlaybackSettings    `json:"playback"`
+}
+
+// PlaybackSettings are the caller's saved playback preferences, sent with the
+// queue so every client crossfades, gaps and levels it the same way.
+type PlaybackSettings struct {
+	CrossfadeSeconds        int     `json:"crossfadeSeconds"`
+	Gapless                 bool    `json:"gapless"`
+	NormalizationTargetLUFS float64 `json:"normalizationTargetLufs"`
 }
 
 // QueueItemResponse is the canonical camelCase API projection of a queue item.
@@ -394,6 +418,10 @@ func writeQueueDecisionEnqueueError(w http.ResponseWriter, err error) {
 		writeError(w, http.StatusBadRequest, "INVALID_POSITION", "invalid position")
 		return
 	}
+	if errors.Is(err, download.ErrAdmissionRefused) {
+		writeError(w, http.StatusInsufficientStorage, "INSUFFICIENT_STORAGE", "new downloads are paused until disk space is freed")
+		return
+	}
 	writeError(w, http.StatusInternalServerError, "DOWNLOAD_ENQUEUE_FAILED", "failed to enqueue source decision")
 }
 
@@ -461,6 +489,10 @@ func (h *Handlers) RetryQueueItem(w http.ResponseWriter, r *http.Request) {
 			writeError(w, http.StatusConflict, "DOWNLOAD_JOB_NOT_RETRYABLE", "download job is not retryable")
 			return
 		}
+		if errors.Is(err, download.ErrAdmissionRefused) {
+			writeError(w, http.StatusInsufficientStorage, "INSUFFICIENT_STORAGE", "new downloads are paused until disk space is freed")
+			return
+		}
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to retry download job")
 		return
 	}
@@ -550,7 +582,7 @@ func (h *Handlers) resolveDownloadBackedItems(r *http.Request, userID string, st
 					changed = true
 				}
 			}
-		case download.StatusFailed:
+		case download.StatusFailed, download.StatusCanceled:
 			if item.PlaybackState != "failed" {
 				item.PlaybackState = "failed"
 				changed = true
@@ -575,7 +607,22 @@ func (h *Handlers) resolveDownloadBackedItems(r *http.Request, userID string, st
 }
 
 func (h *Handlers) buildQueueResponse(ctx context.Context, state *QueueState, jobs map[string]*download.DownloadJob) QueueResponse {
-	return buildQueueResponseWithAnalysis(state, jobs, h.compactAnalysisForState(ctx, state, jobs))
+	resp := buildQueueResponseWithAnalysis(state, jobs, h.compactAnalysisForState(ctx, state, jobs))
+	resp.Playback = h.playbackSettings(ctx)
+	return resp
+}
+
+// playbackSettings loads the caller's playback preferences, falling back to
+// the defaults when there is no reader or the lookup fails; a settings outage
+// should not take the queue down with it.
+func (h *Handlers) playbackSettings(ctx context.Context) PlaybackSettings {
+	settings := db.DefaultUserSettings()
+	if userCtx := auth.GetUserFromContext(ctx); h.settings != nil && userCtx != nil {
+		if saved, err := h.settings.Get(ctx, userCtx.UserID); err == nil {
+			settings = saved
+		}
+	}
+	return PlaybackSettings{CrossfadeSeconds: settings.CrossfadeSeconds, Gapless: settings.Gapless, NormalizationTargetLUFS: settings.NormalizationTargetLUFS}
 }
 
 func (h *Handlers) compactAnalysisForState(ctx context.Context, state *QueueState, jobs map[string]*download.DownloadJob) map[int64]db.AnalysisCompact {
@@ -614,10 +661,12 @@ func buildQueueResponseWithAnalysis(state *QueueState, jobs map[string]*download
 	for i, item := range state.Items {
 		items[i] = buildQueueItemResponse(item, state.UpdatedAt, jobs[item.DownloadJobID], analysis)
 	}
+	defaults := db.DefaultUserSettings()
 	return QueueResponse{
 		Items:           items,
 		CurrentPosition: state.CurrentPosition,
 		UpdatedAt:       state.UpdatedAt,
+		Playback:        PlaybackSettings{Gapless: defaults.Gapless},
 	}
 }
 
@@ -639,7 +688,7 @@ func buildQueueItemResponse(item QueueItem, updatedAt time.Time, job *download.D
 				progress = 100
 				trackID = job.TrackID
 			}
-		case download.StatusFailed:
+		case download.StatusFailed, download.StatusCanceled:
 			state = "failed"
 			if job.Error != "" {
 				err := job.Error
@@ -730,10 +779,5 @@ func writeJSON(w http.ResponseWriter, status int, data interface{}) {
 
 // writeError writes an error response
 func writeError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
@@ -0,0 +1,52 @@
+package queue
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/auth"
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+type fakePlaybackSettings struct {
+	settings db.UserSettings
+	err      error
+}
+
+func (f fakePlaybackSettings) Get(context.Context, uuid.UUID) (db.UserSettings, error) {
+	return f.settings, f.err
+}
+
+func TestQueueResponseCarriesPlaybackSettings(t *testing.T) {
+	get := func(h *Handlers) PlaybackSettings {
+		t.Helper()
+		req := httptest.NewRequest(http.MethodGet, "/api/v1/queue", nil)
+		req = req.WithContext(context.WithValue(req.Context(), auth.UserContextKey, &auth.UserContext{UserID: uuid.New()}))
+		rec := httptest.NewRecorder()
+		h.GetQueue(rec, req)
+		var resp QueueResponse
+		if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil || rec.Code != http.StatusOK {
+			t.Fatalf("GET /api/v1/queue = %d, body %s", rec.Code, rec.Body.String())
+		}
+		return resp.Playback
+	}
+	service := &fakeQueueHandlerService{state: &QueueState{Items: []QueueItem{}}}
+
+	saved := db.UserSettings{CrossfadeSeconds: 6, NormalizationTargetLUFS: -16}
+	if got := get(NewHandlers(service).WithPlaybackSettings(fakePlaybackSettings{settings: saved})); got != (PlaybackSettings{CrossfadeSeconds: 6, NormalizationTargetLUFS: -16}) {
+		t.Fatalf("playback = %+v", got)
+	}
+	defaults := PlaybackSettings{Gapless: true}
+	if got := get(NewHandlers(service)); got != defaults {
+		t.Fatalf("playback without settings = %+v, want %+v", got, defaults)
+	}
+	if got := get(NewHandlers(service).WithPlaybackSettings(fakePlaybackSettings{err: errors.New("down")})); got != defaults {
+		t.Fatalf("playback when settings fail = %+v, want %+v", got, defaults)
+	}
+}
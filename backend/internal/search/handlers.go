@@ -9,7 +9,9 @@ import (
 
 	"github.com/google/uuid"
 
+	"github.com/openmusicplayer/backend/internal/auth"
 	"github.com/openmusicplayer/backend/internal/db"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 const coverArtArchiveURL = "https://coverartarchive.org"
@@ -92,7 +94,7 @@ func (h *Handlers) SearchRecordings(w http.ResponseWriter, r *http.Request) {
 
 	limit, offset := parsePagination(r)
 
-	tracks, total, err := h.trackRepo.SearchRecordings(r.Context(), query, limit, offset)
+	tracks, total, err := h.trackRepo.SearchRecordings(r.Context(), searchUser(r), query, limit, offset)
 	if err != nil {
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to search recordings")
 		return
@@ -118,7 +120,7 @@ func (h *Handlers) SearchArtists(w http.ResponseWriter, r *http.Request) {
 
 	limit, offset := parsePagination(r)
 
-	artists, total, err := h.trackRepo.SearchArtists(r.Context(), query, limit, offset)
+	artists, total, err := h.trackRepo.SearchArtists(r.Context(), searchUser(r), query, limit, offset)
 	if err != nil {
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to search artists")
 		return
@@ -144,7 +146,7 @@ func (h *Handlers) SearchReleases(w http.ResponseWriter, r *http.Request) {
 
 	limit, offset := parsePagination(r)
 
-	releases, total, err := h.trackRepo.SearchReleases(r.Context(), query, limit, offset)
+	releases, total, err := h.trackRepo.SearchReleases(r.Context(), searchUser(r), query, limit, offset)
 	if err != nil {
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to search releases")
 		return
@@ -172,19 +174,19 @@ func (h *Handlers) Search(w http.ResponseWriter, r *http.Request) {
 
 	limit, offset := parsePagination(r)
 
-	tracks, _, err := h.trackRepo.SearchRecordings(r.Context(), query, limit, offset)
+	tracks, _, err := h.trackRepo.SearchRecordings(r.Context(), searchUser(r), query, limit, offset)
 	if err != nil {
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to search recordings")
 		return
 	}
 
-	artists, _, err := h.trackRepo.SearchArtists(r.Context(), query, limit, offset)
+	artists, _, err := h.trackRepo.SearchArtists(r.Context(), searchUser(r), query, limit, offset)
 	if err != nil {
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to search artists")
 		return
 	}
 
-	releases, _, err := h.trackRepo.SearchReleases(r.Context(), query, limit, offset)
+	releases, _, err := h.trackRepo.SearchReleases(r.Context(), searchUser(r), query, limit, offset)
 	if err != nil {
 		writeError(w, http.StatusInternalServerError, "INTERNAL_ERROR", "failed to search releases")
 		return
@@ -198,6 +200,15 @@ func (h *Handlers) Search(w http.ResponseWriter, r *http.Request) {
 	})
 }
 
+// searchUser is the user whose tenant scopes the search. Requests without
+// one search the instance's own tracks.
+func searchUser(r *http.Request) uuid.UUID {
+	if user := auth.GetUserFromContext(r.Context()); user != nil {
+		return user.UserID
+	}
+	return uuid.Nil
+}
+
 func toRecordingResponses(tracks []db.Track) []RecordingResponse {
 	recordings := make([]RecordingResponse, 0, len(tracks))
 	for _, t := range tracks {
@@ -297,10 +308,5 @@ func writeJSON(w http.ResponseWriter, status int, data interface{}) {
 }
 
 func writeError(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
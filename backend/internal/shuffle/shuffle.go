@@ -0,0 +1,170 @@
+// Package shuffle orders tracks for play: a plain random shuffle, one that
+// keeps an artist's tracks apart, one weighted by the listener's ratings, and
+// one that plays what was heard least recently first.
+//
+// Queue generation and radio both order their tracks through Order, so a
+// mode behaves the same wherever it is selected.
+package shuffle
+
+import (
+	"math"
+	"math/rand"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Modes.
+const (
+	// Random is a uniform shuffle.
+	Random = "random"
+	// ArtistSpread keeps tracks by the same artist at least spacing tracks
+	// apart, and spreads an artist's albums when it comes back to them.
+	ArtistSpread = "artist_spread"
+	// Rating shuffles with higher rated tracks more likely to come early.
+	// Unrated tracks weigh as three stars.
+	Rating = "rating"
+	// LeastRecent plays never-played tracks first, then the rest by how long
+	// ago they were last played; ties are shuffled.
+	LeastRecent = "least_recent"
+)
+
+// Modes lists every mode, for validating requests.
+var Modes = []string{Random, ArtistSpread, Rating, LeastRecent}
+
+// DefaultSpacing is how many tracks ArtistSpread keeps between two tracks by
+// one artist when the caller does not say.
+const DefaultSpacing = 3
+
+// unratedWeight is the Rating weight of a track without a rating.
+const unratedWeight = 3
+
+// Item is a track to order.
+type Item struct {
+	ID     int64
+	Artist string
+	Album  string
+	// Rating is the listener's 1-5 star rating, or 0 when unrated.
+	Rating int
+	// LastPlayedAt is when the listener last played the track, or the zero
+	// time when they never have.
+	LastPlayedAt time.Time
+}
+
+// Order returns the items in the order mode plays them; an unknown mode is a
+// plain shuffle. spacing only applies to ArtistSpread. rng may be nil.
+func Order(items []Item, mode string, spacing int, rng *rand.Rand) []Item {
+	if rng == nil {
+		rng = rand.New(rand.NewSource(time.Now().UnixNano()))
+	}
+	out := make([]Item, len(items))
+	copy(out, items)
+	rng.Shuffle(len(out), func(i, j int) { out[i], out[j] = out[j], out[i] })
+
+	switch mode {
+	case ArtistSpread:
+		return spreadArtists(out, spacing)
+	case Rating:
+		return weightByRating(out, rng)
+	case LeastRecent:
+		sort.SliceStable(out, func(i, j int) bool { return out[i].LastPlayedAt.Before(out[j].LastPlayedAt) })
+	}
+	return out
+}
+
+// weightByRating draws a weighted order without replacement by giving each
+// item an exponential key with rate equal to its weight and sorting by key.
+func weightByRating(items []Item, rng *rand.Rand) []Item {
+	keys := make([]float64, len(items))
+	index := make([]int, len(items))
+	for i, item := range items {
+		weight := float64(item.Rating)
+		if item.Rating <= 0 {
+			weight = unratedWeight
+		}
+		keys[i] = -math.Log(1-rng.Float64()) / weight
+		index[i] = i
+	}
+	sort.SliceStable(index, func(a, b int) bool { return keys[index[a]] < keys[index[b]] })
+	out := make([]Item, len(items))
+	for i, at := range index {
+		out[i] = items[at]
+	}
+	return out
+}
+
+// artistGroup is the not yet placed tracks of one artist.
+type artistGroup struct {
+	items     []Item
+	last      int
+	placed    bool
+	lastAlbum string
+}
+
+// spreadArtists deals shuffled items so no artist comes back within spacing
+// tracks whenever that is possible. At each step it deals from the artist
+// with the most tracks left among those not played within spacing; dealing
+// the largest groups first is what keeps the constraint satisfiable to the
+// end. When every remaining artist was played too recently, the one played
+// longest ago goes next. Tracks without an artist are not kept apart.
+func spreadArtists(items []Item, spacing int) []Item {
+	if spacing <= 0 {
+		spacing = DefaultSpacing
+	}
+	groups := map[string]*artistGroup{}
+	var order []string
+	for _, item := range items {
+		key := strings.ToLower(strings.TrimSpace(item.Artist))
+		if key == "" {
+			key = "\x00" + strconv.FormatInt(item.ID, 10)
+		}
+		group, ok := groups[key]
+		if !ok {
+			group = &artistGroup{}
+			groups[key] = group
+			order = append(order, key)
+		}
+		group.items = append(group.items, item)
+	}
+
+	out := make([]Item, 0, len(items))
+	for len(out) < len(items) {
+		var next *artistGroup
+		for _, key := range order {
+			group := groups[key]
+			if len(group.items) == 0 || (group.placed && len(out)-group.last <= spacing) {
+				continue
+			}
+			if next == nil || len(group.items) > len(next.items) {
+				next = group
+			}
+		}
+		if next == nil {
+			for _, key := range order {
+				if group := groups[key]; len(group.items) > 0 && (next == nil || group.last < next.last) {
+					next = group
+				}
+			}
+		}
+		out = append(out, next.take())
+		next.last, next.placed = len(out)-1, true
+	}
+	return out
+}
+
+// take removes and returns the group's next track, preferring one from
+// another album than the artist's last track.
+func (g *artistGroup) take() Item {
+	pick := 0
+	for i, item := range g.items {
+		if !g.placed || item.Album == "" || !strings.EqualFold(item.Album, g.lastAlbum) {
+			pick = i
+			break
+		}
+	}
+	item := g.items[pick]
+	g.items = append(g.items[:pick], g.items[pick+1:]...)
+	g.lastAlbum = item.Album
+	return item
+}
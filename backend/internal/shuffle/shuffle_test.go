@@ -0,0 +1,162 @@
+package shuffle
+
+import (
+	"math/rand"
+	"sort"
+	"strings"
+	"testing"
+	"time"
+)
+
+// randomItems builds a library of up to 40 tracks by a handful of artists,
+// some rated and some played.
+func randomItems(rng *rand.Rand) []Item {
+	n := rng.Intn(40) + 1
+	artists := rng.Intn(6) + 1
+	base := time.Date(2026, 1, 1, 0, 0, 0, 0, time.UTC)
+	items := make([]Item, n)
+	for i := range items {
+		items[i] = Item{
+			ID:     int64(i + 1),
+			Artist: string(rune('A' + rng.Intn(artists))),
+			Album:  string(rune('a' + rng.Intn(3))),
+			Rating: rng.Intn(6),
+		}
+		if rng.Intn(3) > 0 {
+			items[i].LastPlayedAt = base.Add(time.Duration(rng.Intn(1000)) * time.Hour)
+		}
+	}
+	return items
+}
+
+func ids(items []Item) []int64 {
+	out := make([]int64, len(items))
+	for i, item := range items {
+		out[i] = item.ID
+	}
+	sort.Slice(out, func(i, j int) bool { return out[i] < out[j] })
+	return out
+}
+
+// spreadPossible reports whether items can be ordered with no artist back
+// within spacing tracks: the most common artist's tracks, spaced out, plus one
+// slot for each other artist tied with it, must fit.
+func spreadPossible(items []Item, spacing int) bool {
+	counts := map[string]int{}
+	most := 0
+	for _, item := range items {
+		counts[item.Artist]++
+		most = max(most, counts[item.Artist])
+	}
+	tied := 0
+	for _, count := range counts {
+		if count == most {
+			tied++
+		}
+	}
+	return (most-1)*(spacing+1)+tied <= len(items)
+}
+
+func TestOrderKeepsEveryTrackOnce(t *testing.T) {
+	rng := rand.New(rand.NewSource(1))
+	for trial := 0; trial < 200; trial++ {
+		items := randomItems(rng)
+		want := ids(items)
+		for _, mode := range append(Modes, "unknown") {
+			got := ids(Order(items, mode, rng.Intn(5), rng))
+			if len(got) != len(want) {
+				t.Fatalf("%s returned %d tracks, want %d", mode, len(got), len(want))
+			}
+			for i := range want {
+				if got[i] != want[i] {
+					t.Fatalf("%s returned tracks %v, want %v", mode, got, want)
+				}
+			}
+		}
+	}
+}
+
+func TestArtistSpreadKeepsArtistsApartWhenPossible(t *testing.T) {
+	rng := rand.New(rand.NewSource(2))
+	checked := 0
+	for trial := 0; trial < 500; trial++ {
+		items := randomItems(rng)
+		spacing := rng.Intn(4) + 1
+		if !spreadPossible(items, spacing) {
+			continue
+		}
+		checked++
+		got := Order(items, ArtistSpread, spacing, rng)
+		for i := range got {
+			for j := i + 1; j < len(got) && j <= i+spacing; j++ {
+				if got[i].Artist == got[j].Artist {
+					t.Fatalf("spacing %d: %s at %d and %d in %v", spacing, got[i].Artist, i, j, artistsOf(got))
+				}
+			}
+		}
+	}
+	if checked < 100 {
+		t.Fatalf("only %d satisfiable cases checked", checked)
+	}
+}
+
+func TestArtistSpreadVariesAlbumsWithinAnArtist(t *testing.T) {
+	items := []Item{
+		{ID: 1, Artist: "A", Album: "x"}, {ID: 2, Artist: "A", Album: "x"},
+		{ID: 3, Artist: "A", Album: "y"}, {ID: 4, Artist: "A", Album: "y"},
+	}
+	for seed := int64(0); seed < 20; seed++ {
+		got := Order(items, ArtistSpread, 1, rand.New(rand.NewSource(seed)))
+		for i := 1; i < len(got); i++ {
+			if got[i].Album == got[i-1].Album {
+				t.Fatalf("seed %d: albums in order %v", seed, got)
+			}
+		}
+	}
+}
+
+func TestLeastRecentPlaysOldestFirst(t *testing.T) {
+	rng := rand.New(rand.NewSource(3))
+	for trial := 0; trial < 200; trial++ {
+		got := Order(randomItems(rng), LeastRecent, 0, rng)
+		for i := 1; i < len(got); i++ {
+			if got[i].LastPlayedAt.Before(got[i-1].LastPlayedAt) {
+				t.Fatalf("track %d last played %v comes after one played %v", got[i].ID, got[i].LastPlayedAt, got[i-1].LastPlayedAt)
+			}
+		}
+	}
+}
+
+func TestRatingFavorsHigherRatedTracks(t *testing.T) {
+	items := []Item{{ID: 1, Rating: 1}, {ID: 2, Rating: 5}, {ID: 3}}
+	rng := rand.New(rand.NewSource(4))
+	first := map[int64]int{}
+	for trial := 0; trial < 3000; trial++ {
+		first[Order(items, Rating, 0, rng)[0].ID]++
+	}
+	// Weights 1, 5 and 3 lead 1/9, 5/9 and 3/9 of the time.
+	if first[2] < 1500 || first[2] > 1850 || first[3] < 850 || first[3] > 1150 || first[1] < 200 || first[1] > 470 {
+		t.Fatalf("first tracks = %v", first)
+	}
+}
+
+func artistsOf(items []Item) string {
+	var b strings.Builder
+	for _, item := range items {
+		b.WriteString(item.Artist)
+	}
+	return b.String()
+}
+
+// BenchmarkArtistSpreadLargeLibrary orders a 5000-track library by 200
+// artists in the slowest mode behind GET /api/v1/library/shuffle.
+func BenchmarkArtistSpreadLargeLibrary(b *testing.B) {
+	rng := rand.New(rand.NewSource(1))
+	items := make([]Item, 5000)
+	for i := range items {
+		items[i] = Item{ID: int64(i + 1), Artist: string(rune('A' + rng.Intn(200))), Album: string(rune('a' + rng.Intn(4)))}
+	}
+	for b.Loop() {
+		Order(items, ArtistSpread, DefaultSpacing, rng)
+	}
+}
@@ -0,0 +1,63 @@
+package sourceauth
+
+import (
+	"crypto/aes"
+	"crypto/cipher"
+	"crypto/rand"
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"strings"
+)
+
+// KeySize is the length of a sealing key: AES-256.
+const KeySize = 32
+
+// ParseKey decodes a base64 sealing key, as SOURCE_CREDENTIALS_KEY holds.
+// `openssl rand -base64 32` makes one.
+func ParseKey(encoded string) ([]byte, error) {
+	key, err := base64.StdEncoding.DecodeString(strings.TrimSpace(encoded))
+	if err != nil {
+		return nil, fmt.Errorf("decode source credentials key: %w", err)
+	}
+	if len(key) != KeySize {
+		return nil, fmt.Errorf("source credentials key is %d bytes, want %d", len(key), KeySize)
+	}
+	return key, nil
+}
+
+// Sealer encrypts credentials with AES-GCM. A sealed value opens only with
+// the binding it was sealed with, so it cannot be moved to another row.
+type Sealer struct {
+	aead cipher.AEAD
+}
+
+func NewSealer(key []byte) (*Sealer, error) {
+	block, err := aes.NewCipher(key)
+	if err != nil {
+		return nil, err
+	}
+	aead, err := cipher.NewGCM(block)
+	if err != nil {
+		return nil, err
+	}
+	return &Sealer{aead: aead}, nil
+}
+
+// Seal encrypts plaintext under a fresh nonce, which leads the result.
+func (s *Sealer) Seal(plaintext, binding []byte) ([]byte, error) {
+	nonce := make([]byte, s.aead.NonceSize())
+	if _, err := rand.Read(nonce); err != nil {
+		return nil, err
+	}
+	return s.aead.Seal(nonce, nonce, plaintext, binding), nil
+}
+
+// Open decrypts what Seal returned for the same binding.
+func (s *Sealer) Open(sealed, binding []byte) ([]byte, error) {
+	if len(sealed) < s.aead.NonceSize() {
+		return nil, errors.New("sealed value is truncated")
+	}
+	nonce, ciphertext := sealed[:s.aead.NonceSize()], sealed[s.aead.NonceSize():]
+	return s.aead.Open(nil, nonce, ciphertext, binding)
+}
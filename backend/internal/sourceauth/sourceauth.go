@@ -0,0 +1,302 @@
+// Package sourceauth keeps the login cookies users upload for sources that
+// need them, such as private SoundCloud tracks or members-only videos, and
+// hands them to the downloaders fetching those users' jobs. Cookies are
+// sealed at rest and never returned by the API; a credential stops being
+// used once its cookies lapse or the source turns them away.
+package sourceauth
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"fmt"
+	"log"
+	"net/http"
+	"net/url"
+	"strings"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/fetcher"
+)
+
+var (
+	// ErrInvalidSource is returned for a source that is not a host name.
+	ErrInvalidSource = errors.New("source must be a host name such as soundcloud.com")
+	// ErrInvalidCookies is returned for an upload that is not a cookies.txt.
+	ErrInvalidCookies = errors.New("invalid cookies.txt")
+	// ErrNoCookies is returned when an upload holds no unexpired cookie for
+	// its source.
+	ErrNoCookies = errors.New("cookies.txt has no unexpired cookies for the source")
+)
+
+// Repository stores sealed credentials. db.SourceCredentialRepository
+// satisfies this interface.
+type Repository interface {
+	List(ctx context.Context, userID uuid.UUID) ([]db.SourceCredential, error)
+	Put(ctx context.Context, credential *db.SourceCredential) error
+	Delete(ctx context.Context, userID uuid.UUID, source string) error
+	MarkUsed(ctx context.Context, id int64) error
+	SetStatus(ctx context.Context, id int64, status, detail string) error
+}
+
+// Credential is the cookies handed to one download.
+type Credential struct {
+	ID      int64
+	Source  string
+	Cookies []*http.Cookie
+}
+
+type Service struct {
+	repo   Repository
+	sealer *Sealer
+	now    func() time.Time
+}
+
+func NewService(repo Repository, sealer *Sealer) *Service {
+	return &Service{repo: repo, sealer: sealer, now: time.Now}
+}
+
+// NormalizeSource returns source as the lower-case host name credentials
+// are stored under, without a leading "www.".
+func NormalizeSource(source string) (string, error) {
+	source = strings.TrimSuffix(strings.ToLower(strings.TrimSpace(source)), ".")
+	source = strings.TrimPrefix(source, "www.")
+	if len(source) > 253 || !strings.Contains(source, ".") {
+		return "", ErrInvalidSource
+	}
+	for _, label := range strings.Split(source, ".") {
+		if label == "" || len(label) > 63 || strings.HasPrefix(label, "-") || strings.HasSuffix(label, "-") {
+			return "", ErrInvalidSource
+		}
+		for _, r := range label {
+			if (r < 'a' || r > 'z') && (r < '0' || r > '9') && r != '-' {
+				return "", ErrInvalidSource
+			}
+		}
+	}
+	return source, nil
+}
+
+// Upload replaces the user's cookies for source with those of cookiesTxt
+// that the source's hosts would receive. Cookies for other sites and ones
+// already expired are dropped, so a whole browser export can be uploaded
+// without handing its other logins to the source.
+func (s *Service) Upload(ctx context.Context, userID uuid.UUID, source string, cookiesTxt []byte) (*db.SourceCredential, error) {
+	source, err := NormalizeSource(source)
+	if err != nil {
+		return nil, err
+	}
+	parsed, err := fetcher.ParseCookies(cookiesTxt)
+	if err != nil {
+		return nil, fmt.Errorf("%w: %v", ErrInvalidCookies, err)
+	}
+	now := s.now()
+	var cookies []*http.Cookie
+	for _, cookie := range parsed {
+		if cookieFor(cookie, source) && !expired(cookie, now) {
+			cookies = append(cookies, cookie)
+		}
+	}
+	if len(cookies) == 0 {
+		return nil, ErrNoCookies
+	}
+	sealed, err := s.sealer.Seal(fetcher.FormatCookies(cookies), binding(userID, source))
+	if err != nil {
+		return nil, fmt.Errorf("seal cookies: %w", err)
+	}
+	credential := &db.SourceCredential{
+		UserID:        userID,
+		Source:        source,
+		SealedCookies: sealed,
+		CookieCount:   len(cookies),
+		ExpiresAt:     lastExpiry(cookies),
+	}
+	if err := s.repo.Put(ctx, credential); err != nil {
+		return nil, err
+	}
+	return credential, nil
+}
+
+// List returns the user's credentials, marking those whose cookies have all
+// lapsed as expired.
+func (s *Service) List(ctx context.Context, userID uuid.UUID) ([]db.SourceCredential, error) {
+	credentials, err := s.repo.List(ctx, userID)
+	if err != nil {
+		return nil, err
+	}
+	for i := range credentials {
+		s.expireLapsed(ctx, &credentials[i])
+	}
+	return credentials, nil
+}
+
+// Delete removes the user's cookies for source.
+func (s *Service) Delete(ctx context.Context, userID uuid.UUID, source string) error {
+	source, err := NormalizeSource(source)
+	if err != nil {
+		return err
+	}
+	return s.repo.Delete(ctx, userID, source)
+}
+
+// ForDownload returns the user's cookies for sourceURL's host, from the
+// credential of the most specific source covering it, or nil when there is
+// no usable one. A credential that is expired or was rejected is not used,
+// and a broader source's cookies do not stand in for it.
+func (s *Service) ForDownload(ctx context.Context, userID uuid.UUID, sourceURL string) (*Credential, error) {
+	parsed, err := url.Parse(sourceURL)
+	if err != nil || parsed.Hostname() == "" {
+		return nil, nil
+	}
+	host := strings.ToLower(parsed.Hostname())
+	credentials, err := s.repo.List(ctx, userID)
+	if err != nil {
+		return nil, fmt.Errorf("list source credentials: %w", err)
+	}
+	var best *db.SourceCredential
+	for i := range credentials {
+		credential := &credentials[i]
+		if coversHost(credential.Source, host) && (best == nil || len(credential.Source) > len(best.Source)) {
+			best = credential
+		}
+	}
+	if best == nil || s.expireLapsed(ctx, best) || best.Status != db.SourceCredentialActive {
+		return nil, nil
+	}
+	plaintext, err := s.sealer.Open(best.SealedCookies, binding(userID, best.Source))
+	if err != nil {
+		return nil, fmt.Errorf("open cookies for %s: %w", best.Source, err)
+	}
+	stored, err := fetcher.ParseCookies(plaintext)
+	if err != nil {
+		return nil, fmt.Errorf("read cookies for %s: %w", best.Source, err)
+	}
+	now := s.now()
+	var cookies []*http.Cookie
+	for _, cookie := range stored {
+		if !expired(cookie, now) {
+			cookies = append(cookies, cookie)
+		}
+	}
+	if len(cookies) == 0 {
+		s.setStatus(ctx, best, db.SourceCredentialExpired, "every cookie has expired")
+		return nil, nil
+	}
+	return &Credential{ID: best.ID, Source: best.Source, Cookies: cookies}, nil
+}
+
+// Report records how a download with the credential's cookies went. A
+// failure that reads as the source refusing the login marks the credential
+// rejected, so the user is asked for fresh cookies instead of every later
+// download failing the same way; other failures say nothing about it.
+func (s *Service) Report(ctx context.Context, credential *Credential, fetchErr error) {
+	if credential == nil {
+		return
+	}
+	if fetchErr == nil {
+		if err := s.repo.MarkUsed(ctx, credential.ID); err != nil {
+			log.Printf("Warning: failed to record use of %s cookies: %v", credential.Source, err)
+		}
+		return
+	}
+	if !LoginRefused(fetchErr) {
+		return
+	}
+	s.setStatus(ctx, &db.SourceCredential{ID: credential.ID, Source: credential.Source}, db.SourceCredentialRejected, truncateDetail(fetchErr.Error()))
+}
+
+// loginRefusalSigns are what sources and yt-dlp say when a request needs a
+// login the cookies no longer give.
+var loginRefusalSigns = []string{
+	"sign in", "log in", "login", "401", "403", "members-only", "members only",
+	"private video", "is private", "cookies are no longer valid", "account cookies",
+}
+
+// LoginRefused reports whether a download failed for want of a valid login:
+// the source answered 401 or 403, asked to sign in, or served a page where
+// the audio should be.
+func LoginRefused(err error) bool {
+	if err == nil {
+		return false
+	}
+	if errors.Is(err, fetcher.ErrNotAudio) {
+		return true
+	}
+	message := strings.ToLower(err.Error())
+	for _, sign := range loginRefusalSigns {
+		if strings.Contains(message, sign) {
+			return true
+		}
+	}
+	return false
+}
+
+// expireLapsed marks an active credential whose cookies have all expired,
+// reporting whether it did.
+func (s *Service) expireLapsed(ctx context.Context, credential *db.SourceCredential) bool {
+	if credential.Status != db.SourceCredentialActive || !credential.ExpiresAt.Valid || credential.ExpiresAt.Time.After(s.now()) {
+		return false
+	}
+	s.setStatus(ctx, credential, db.SourceCredentialExpired, "cookies expired at "+credential.ExpiresAt.Time.UTC().Format(time.RFC3339))
+	return true
+}
+
+// setStatus updates credential and its row. A failure is logged: the status
+// is advisory, and the next download finds the same cause again.
+func (s *Service) setStatus(ctx context.Context, credential *db.SourceCredential, status, detail string) {
+	credential.Status, credential.StatusDetail = status, detail
+	if err := s.repo.SetStatus(ctx, credential.ID, status, detail); err != nil {
+		log.Printf("Warning: failed to mark %s cookies %s: %v", credential.Source, status, err)
+	}
+}
+
+// cookieFor reports whether a request to source or one of its subdomains
+// would carry cookie. Domain cookies of a parent, such as .youtube.com for
+// music.youtube.com, count; cookies for a bare top-level domain do not.
+func cookieFor(cookie *http.Cookie, source string) bool {
+	host := strings.TrimPrefix(cookie.Domain, ".")
+	if coversHost(source, host) {
+		return true
+	}
+	return strings.HasPrefix(cookie.Domain, ".") && strings.Contains(host, ".") && coversHost(host, source)
+}
+
+// coversHost reports whether host is source or one of its subdomains.
+func coversHost(source, host string) bool {
+	return host == source || strings.HasSuffix(host, "."+source)
+}
+
+func expired(cookie *http.Cookie, now time.Time) bool {
+	return !cookie.Expires.IsZero() && !cookie.Expires.After(now)
+}
+
+// lastExpiry is when the last of cookies lapses, or NULL when any is a
+// session cookie with no expiry of its own.
+func lastExpiry(cookies []*http.Cookie) sql.NullTime {
+	var last time.Time
+	for _, cookie := range cookies {
+		if cookie.Expires.IsZero() {
+			return sql.NullTime{}
+		}
+		if cookie.Expires.After(last) {
+			last = cookie.Expires
+		}
+	}
+	return sql.NullTime{Time: last, Valid: true}
+}
+
+// binding ties sealed cookies to their owner and source.
+func binding(userID uuid.UUID, source string) []byte {
+	return []byte(userID.String() + "\x00" + source)
+}
+
+func truncateDetail(detail string) string {
+	const maxDetail = 500
+	if len(detail) > maxDetail {
+		return detail[:maxDetail] + "..."
+	}
+	return detail
+}
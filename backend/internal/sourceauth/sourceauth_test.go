@@ -0,0 +1,201 @@
+package sourceauth
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"testing"
+	"time"
+
+	"github.com/google/uuid"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/fetcher"
+)
+
+type fakeRepository struct {
+	credentials []db.SourceCredential
+	used        []int64
+}
+
+func (f *fakeRepository) List(ctx context.Context, userID uuid.UUID) ([]db.SourceCredential, error) {
+	var listed []db.SourceCredential
+	for _, c := range f.credentials {
+		if c.UserID == userID {
+			listed = append(listed, c)
+		}
+	}
+	return listed, nil
+}
+
+func (f *fakeRepository) Put(ctx context.Context, credential *db.SourceCredential) error {
+	credential.Status = db.SourceCredentialActive
+	for i, c := range f.credentials {
+		if c.UserID == credential.UserID && c.Source == credential.Source {
+			credential.ID = c.ID
+			f.credentials[i] = *credential
+			return nil
+		}
+	}
+	credential.ID = int64(len(f.credentials) + 1)
+	f.credentials = append(f.credentials, *credential)
+	return nil
+}
+
+func (f *fakeRepository) Delete(ctx context.Context, userID uuid.UUID, source string) error {
+	return db.ErrSourceCredentialNotFound
+}
+
+func (f *fakeRepository) MarkUsed(ctx context.Context, id int64) error {
+	f.used = append(f.used, id)
+	return nil
+}
+
+func (f *fakeRepository) SetStatus(ctx context.Context, id int64, status, detail string) error {
+	for i := range f.credentials {
+		if f.credentials[i].ID == id {
+			f.credentials[i].Status, f.credentials[i].StatusDetail = status, detail
+		}
+	}
+	return nil
+}
+
+func cookieLine(domain, name string, expires time.Time) string {
+	subdomains := "FALSE"
+	if domain[0] == '.' {
+		subdomains = "TRUE"
+	}
+	var unix int64
+	if !expires.IsZero() {
+		unix = expires.Unix()
+	}
+	return fmt.Sprintf("%s\t%s\t/\tTRUE\t%d\t%s\tvalue-of-%s\n", domain, subdomains, unix, name, name)
+}
+
+func newTestService(t *testing.T, now time.Time) (*Service, *fakeRepository) {
+	t.Helper()
+	sealer, err := NewSealer(bytes.Repeat([]byte{7}, KeySize))
+	if err != nil {
+		t.Fatalf("NewSealer() error = %v", err)
+	}
+	repo := &fakeRepository{}
+	service := NewService(repo, sealer)
+	service.now = func() time.Time { return now }
+	return service, repo
+}
+
+func TestUploadKeepsOnlyTheSourcesUnexpiredCookiesSealed(t *testing.T) {
+	now := time.Date(2026, 5, 1, 12, 0, 0, 0, time.UTC)
+	service, repo := newTestService(t, now)
+	user := uuid.New()
+	file := "# Netscape HTTP Cookie File\n" +
+		cookieLine(".soundcloud.com", "oauth_token", now.Add(30*24*time.Hour)) +
+		cookieLine("api-v2.soundcloud.com", "sc_anonymous_id", now.Add(2*time.Hour)) +
+		cookieLine("soundcloud.com", "stale", now.Add(-time.Hour)) +
+		cookieLine(".bank.example", "session", now.Add(time.Hour)) +
+		cookieLine(".com", "everywhere", now.Add(time.Hour))
+
+	credential, err := service.Upload(context.Background(), user, " SoundCloud.com ", []byte(file))
+	if err != nil {
+		t.Fatalf("Upload() error = %v", err)
+	}
+	if credential.Source != "soundcloud.com" || credential.CookieCount != 2 || !credential.ExpiresAt.Time.Equal(now.Add(30*24*time.Hour)) {
+		t.Fatalf("Upload() = %+v", credential)
+	}
+	if bytes.Contains(credential.SealedCookies, []byte("value-of-oauth_token")) {
+		t.Fatal("sealed cookies contain a cookie value in the clear")
+	}
+	if _, err := service.sealer.Open(credential.SealedCookies, binding(uuid.New(), "soundcloud.com")); err == nil {
+		t.Fatal("another user's binding opened the sealed cookies")
+	}
+
+	got, err := service.ForDownload(context.Background(), user, "https://www.soundcloud.com/artist/private-track")
+	if err != nil || got == nil || len(got.Cookies) != 2 || got.Cookies[0].Value != "value-of-oauth_token" {
+		t.Fatalf("ForDownload() = %+v, %v", got, err)
+	}
+	if got, err := service.ForDownload(context.Background(), user, "https://bank.example/"); got != nil || err != nil {
+		t.Fatalf("ForDownload() of another host = %+v, %v", got, err)
+	}
+	if got, err := service.ForDownload(context.Background(), uuid.New(), "https://soundcloud.com/x"); got != nil || err != nil {
+		t.Fatalf("ForDownload() for another user = %+v, %v", got, err)
+	}
+
+	if _, err := service.Upload(context.Background(), user, "soundcloud.com", []byte(cookieLine(".bank.example", "session", now.Add(time.Hour)))); !errors.Is(err, ErrNoCookies) {
+		t.Fatalf("Upload() without source cookies = %v, want ErrNoCookies", err)
+	}
+	if _, err := service.Upload(context.Background(), user, "soundcloud.com", []byte("not a cookie file\n")); !errors.Is(err, ErrInvalidCookies) {
+		t.Fatalf("Upload() of another file = %v, want ErrInvalidCookies", err)
+	}
+	if _, err := service.Upload(context.Background(), user, "https://soundcloud.com/", []byte(file)); !errors.Is(err, ErrInvalidSource) {
+		t.Fatalf("Upload() for a URL = %v, want ErrInvalidSource", err)
+	}
+	if len(repo.credentials) != 1 {
+		t.Fatalf("stored credentials = %d, want 1", len(repo.credentials))
+	}
+}
+
+func TestForDownloadRetiresLapsedAndRefusedCookies(t *testing.T) {
+	now := time.Date(2026, 5, 1, 12, 0, 0, 0, time.UTC)
+	service, repo := newTestService(t, now)
+	ctx := context.Background()
+	user := uuid.New()
+	if _, err := service.Upload(ctx, user, "youtube.com", []byte(cookieLine(".youtube.com", "SID", now.Add(time.Hour)))); err != nil {
+		t.Fatalf("Upload() error = %v", err)
+	}
+	if _, err := service.Upload(ctx, user, "music.youtube.com", []byte(cookieLine(".youtube.com", "SID", now.Add(48*time.Hour)))); err != nil {
+		t.Fatalf("Upload() error = %v", err)
+	}
+
+	credential, err := service.ForDownload(ctx, user, "https://music.youtube.com/watch?v=x")
+	if err != nil || credential == nil || credential.Source != "music.youtube.com" {
+		t.Fatalf("ForDownload() = %+v, %v; want the most specific source", credential, err)
+	}
+	service.Report(ctx, credential, nil)
+	if len(repo.used) != 1 || repo.used[0] != credential.ID {
+		t.Fatalf("used = %v, want [%d]", repo.used, credential.ID)
+	}
+	service.Report(ctx, credential, errors.New("yt-dlp: connection reset"))
+	if repo.credentials[1].Status != db.SourceCredentialActive {
+		t.Fatalf("status after a network failure = %q, want active", repo.credentials[1].Status)
+	}
+	service.Report(ctx, credential, errors.New("yt-dlp failed: ERROR: Sign in to confirm your age"))
+	if repo.credentials[1].Status != db.SourceCredentialRejected || repo.credentials[1].StatusDetail == "" {
+		t.Fatalf("status after a login refusal = %+v, want rejected", repo.credentials[1])
+	}
+	if credential, err := service.ForDownload(ctx, user, "https://music.youtube.com/watch?v=x"); credential != nil || err != nil {
+		t.Fatalf("ForDownload() of rejected cookies = %+v, %v; want nothing, not the broader source", credential, err)
+	}
+
+	service.now = func() time.Time { return now.Add(2 * time.Hour) }
+	if credential, err := service.ForDownload(ctx, user, "https://www.youtube.com/watch?v=y"); credential != nil || err != nil {
+		t.Fatalf("ForDownload() of lapsed cookies = %+v, %v", credential, err)
+	}
+	if repo.credentials[0].Status != db.SourceCredentialExpired {
+		t.Fatalf("lapsed credential status = %q, want expired", repo.credentials[0].Status)
+	}
+}
+
+func TestLoginRefused(t *testing.T) {
+	for _, err := range []error{
+		errors.New("http: GET https://host/a.mp3: 403 Forbidden"),
+		errors.New("yt-dlp failed: This video is only available for members-only content"),
+		fmt.Errorf("http: %w: login page", fetcher.ErrNotAudio),
+	} {
+		if !LoginRefused(err) {
+			t.Errorf("LoginRefused(%v) = false", err)
+		}
+	}
+	if LoginRefused(errors.New("yt-dlp failed: HTTP Error 404: Not Found")) {
+		t.Error("LoginRefused(404) = true")
+	}
+}
+
+func TestParseKeyWantsThirtyTwoBytes(t *testing.T) {
+	if _, err := ParseKey("BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc="); err != nil {
+		t.Fatalf("ParseKey() error = %v", err)
+	}
+	if _, err := ParseKey("c2hvcnQ="); err == nil {
+		t.Fatal("ParseKey() of a short key succeeded")
+	}
+}
@@ -0,0 +1,114 @@
+// Package sponsorblock reads the non-music segments of YouTube videos, such
+// as sponsor reads and intros, from SponsorBlock's community database.
+package sponsorblock
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"math"
+	"net/http"
+	"net/url"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/db"
+	"github.com/openmusicplayer/backend/internal/tracing"
+)
+
+const (
+	DefaultURL = "https://sponsor.ajay.app"
+	userAgent  = "OpenMusicPlayer/1.0.0 (https://github.com/openmusicplayer)"
+	// hashPrefixLength is how much of the video ID's SHA-256 is sent. Many
+	// videos share a prefix, so the server never learns which was downloaded.
+	hashPrefixLength = 4
+	// maxResponseBytes bounds the segments of every video sharing a prefix.
+	maxResponseBytes = 4 << 20
+)
+
+// DefaultCategories are the segment categories skipped when none are
+// configured: everything SponsorBlock marks that is not the music itself.
+var DefaultCategories = []string{"sponsor", "selfpromo", "interaction", "intro", "outro", "music_offtopic"}
+
+// Client looks up skip segments by YouTube video ID.
+type Client struct {
+	httpClient *http.Client
+	baseURL    string
+	categories []string
+}
+
+// NewClient reads segments in categories from the SponsorBlock server at
+// baseURL. Empty values use DefaultURL and DefaultCategories.
+func NewClient(baseURL string, categories []string) *Client {
+	if baseURL == "" {
+		baseURL = DefaultURL
+	}
+	if len(categories) == 0 {
+		categories = DefaultCategories
+	}
+	return &Client{
+		httpClient: &http.Client{Timeout: 10 * time.Second, Transport: tracing.NewTransport(nil)},
+		baseURL:    strings.TrimSuffix(baseURL, "/"),
+		categories: categories,
+	}
+}
+
+// Segments returns the video's segments to skip, in order. A video nobody
+// has submitted segments for has none.
+func (c *Client) Segments(ctx context.Context, videoID string) ([]db.SkipSegment, error) {
+	sum := sha256.Sum256([]byte(videoID))
+	categories, err := json.Marshal(c.categories)
+	if err != nil {
+		return nil, err
+	}
+	query := url.Values{"categories": {string(categories)}, "actionType": {"skip"}}
+	reqURL := c.baseURL + "/api/skipSegments/" + hex.EncodeToString(sum[:])[:hashPrefixLength] + "?" + query.Encode()
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, reqURL, nil)
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Set("User-Agent", userAgent)
+	req.Header.Set("Accept", "application/json")
+	resp, err := c.httpClient.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("sponsorblock segments for %s: %w", videoID, err)
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode == http.StatusNotFound {
+		return nil, nil
+	}
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("sponsorblock segments for %s: status %d", videoID, resp.StatusCode)
+	}
+
+	var videos []struct {
+		VideoID  string `json:"videoID"`
+		Segments []struct {
+			Category   string     `json:"category"`
+			ActionType string     `json:"actionType"`
+			Segment    [2]float64 `json:"segment"`
+		} `json:"segments"`
+	}
+	if err := json.NewDecoder(io.LimitReader(resp.Body, maxResponseBytes)).Decode(&videos); err != nil {
+		return nil, fmt.Errorf("decode sponsorblock segments for %s: %w", videoID, err)
+	}
+	var segments []db.SkipSegment
+	for _, video := range videos {
+		if video.VideoID != videoID {
+			continue
+		}
+		for _, s := range video.Segments {
+			start, end := int(math.Round(s.Segment[0] * 1000)), int(math.Round(s.Segment[1] * 1000))
+			if s.ActionType != "skip" || start < 0 || end <= start {
+				continue
+			}
+			segments = append(segments, db.SkipSegment{Category: s.Category, StartMs: start, EndMs: end, Source: db.SkipSegmentSourceSponsorBlock})
+		}
+	}
+	sort.Slice(segments, func(i, j int) bool { return segments[i].StartMs < segments[j].StartMs })
+	return segments, nil
+}
@@ -0,0 +1,51 @@
+package sponsorblock
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"testing"
+
+	"github.com/openmusicplayer/backend/internal/db"
+)
+
+func TestSegmentsLookUpByHashPrefix(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		// sha256("dQw4w9WgXcQ") starts with 5f6b.
+		if r.URL.Path != "/api/skipSegments/5f6b" || r.URL.Query().Get("categories") != `["sponsor","music_offtopic"]` {
+			t.Errorf("unexpected request %s", r.URL)
+		}
+		_, _ = w.Write([]byte(`[
+			{"videoID": "another", "segments": [{"category": "sponsor", "actionType": "skip", "segment": [1, 2]}]},
+			{"videoID": "dQw4w9WgXcQ", "segments": [
+				{"category": "music_offtopic", "actionType": "skip", "segment": [200.5, 212.04]},
+				{"category": "sponsor", "actionType": "mute", "segment": [50, 60]},
+				{"category": "sponsor", "actionType": "skip", "segment": [0, 18.25]}
+			]}
+		]`))
+	}))
+	defer server.Close()
+
+	segments, err := NewClient(server.URL, []string{"sponsor", "music_offtopic"}).Segments(context.Background(), "dQw4w9WgXcQ")
+	if err != nil {
+		t.Fatalf("Segments() error = %v", err)
+	}
+	want := []db.SkipSegment{
+		{Category: "sponsor", StartMs: 0, EndMs: 18250, Source: db.SkipSegmentSourceSponsorBlock},
+		{Category: "music_offtopic", StartMs: 200500, EndMs: 212040, Source: db.SkipSegmentSourceSponsorBlock},
+	}
+	if !reflect.DeepEqual(segments, want) {
+		t.Fatalf("Segments() = %+v, want %+v", segments, want)
+	}
+}
+
+func TestSegmentsOfUnknownVideoAreEmpty(t *testing.T) {
+	server := httptest.NewServer(http.NotFoundHandler())
+	defer server.Close()
+
+	segments, err := NewClient(server.URL, nil).Segments(context.Background(), "dQw4w9WgXcQ")
+	if err != nil || segments != nil {
+		t.Fatalf("Segments() = %+v, %v; want none", segments, err)
+	}
+}
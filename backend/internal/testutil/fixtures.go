@@ -0,0 +1,139 @@
+package testutil
+
+import (
+	"crypto/rand"
+	"database/sql"
+	"encoding/hex"
+	"fmt"
+	"testing"
+
+	"github.com/google/uuid"
+)
+
+// Fixtures seeds rows with plain SQL so any package's tests can build a
+// realistic graph of users, tracks, and playlists without importing the db
+// package (which itself imports testutil from its tests).
+type Fixtures struct {
+	t  testing.TB
+	db *sql.DB
+	n  int
+}
+
+// NewFixtures binds fixture builders to a test database.
+func NewFixtures(t testing.TB, db *sql.DB) *Fixtures {
+	return &Fixtures{t: t, db: db}
+}
+
+// TrackFixture describes a seeded track. Zero values get deterministic
+// defaults derived from the fixture sequence number.
+type TrackFixture struct {
+	Title      string
+	Artist     string
+	Album      string
+	DurationMs int
+	StorageKey string
+}
+
+// User inserts a user and returns its ID.
+func (f *Fixtures) User(email string) uuid.UUID {
+	f.t.Helper()
+	if email == "" {
+		email = fmt.Sprintf("user%d@example.test", f.next())
+	}
+	id := uuid.New()
+	if _, err := f.db.Exec(
+		`INSERT INTO users (id, email, username, password_hash) VALUES ($1, $2, $3, $4)`,
+		id, email, "user", "x",
+	); err != nil {
+		f.t.Fatalf("seed user %s: %v", email, err)
+	}
+	return id
+}
+
+// Track inserts a track and returns its ID.
+func (f *Fixtures) Track(track TrackFixture) int64 {
+	f.t.Helper()
+	n := f.next()
+	if track.Title == "" {
+		track.Title = fmt.Sprintf("Track %d", n)
+	}
+	if track.Artist == "" {
+		track.Artist = "Fixture Artist"
+	}
+	if track.DurationMs == 0 {
+		track.DurationMs = 180000
+	}
+	var id int64
+	if err := f.db.QueryRow(`
+		INSERT INTO tracks (identity_hash, title, artist, album, duration_ms, storage_key, metadata_json)
+		VALUES ($1, $2, $3, NULLIF($4, ''), $5, NULLIF($6, ''), '{}'::jsonb)
+		RETURNING id`,
+		randomHash(), track.Title, track.Artist, track.Album, track.DurationMs, track.StorageKey,
+	).Scan(&id); err != nil {
+		f.t.Fatalf("seed track %q: %v", track.Title, err)
+	}
+	return id
+}
+
+// LibraryEntry adds trackIDs to userID's library.
+func (f *Fixtures) LibraryEntry(userID uuid.UUID, trackIDs ...int64) {
+	f.t.Helper()
+	for _, trackID := range trackIDs {
+		if _, err := f.db.Exec(
+			`INSERT INTO user_library (user_id, track_id) VALUES ($1, $2) ON CONFLICT DO NOTHING`,
+			userID, trackID,
+		); err != nil {
+			f.t.Fatalf("seed library entry %d: %v", trackID, err)
+		}
+	}
+}
+
+// Playlist inserts a playlist owned by userID holding trackIDs in order and
+// returns its ID.
+func (f *Fixtures) Playlist(userID uuid.UUID, name string, trackIDs ...int64) int64 {
+	f.t.Helper()
+	if name == "" {
+		name = fmt.Sprintf("Playlist %d", f.next())
+	}
+	var id int64
+	if err := f.db.QueryRow(
+		`INSERT INTO playlists (user_id, name) VALUES ($1, $2) RETURNING id`,
+		userID, name,
+	).Scan(&id); err != nil {
+		f.t.Fatalf("seed playlist %q: %v", name, err)
+	}
+	for position, trackID := range trackIDs {
+		if _, err := f.db.Exec(
+			`INSERT INTO playlist_tracks (playlist_id, track_id, position) VALUES ($1, $2, $3)`,
+			id, trackID, position,
+		); err != nil {
+			f.t.Fatalf("seed playlist track %d: %v", trackID, err)
+		}
+	}
+	// Fill in the cached totals the playlist repository keeps.
+	if _, err := f.db.Exec(`
+		UPDATE playlists p
+		SET track_count = totals.track_count, duration_ms = totals.duration_ms, size_bytes = totals.size_bytes
+		FROM (
+			SELECT COUNT(*) AS track_count, COALESCE(SUM(t.duration_ms), 0) AS duration_ms,
+				   COALESCE(SUM(t.file_size_bytes), 0) AS size_bytes
+			FROM playlist_tracks pt
+			JOIN tracks t ON t.id = pt.track_id
+			WHERE pt.playlist_id = $1
+		) totals
+		WHERE p.id = $1`, id); err != nil {
+		f.t.Fatalf("seed playlist totals %q: %v", name, err)
+	}
+	return id
+}
+
+func (f *Fixtures) next() int {
+	f.n++
+	return f.n
+}
+
+func randomHash() string {
+	var b [32]byte
+	_, _ = rand.Read(b[:])
+	return hex.EncodeToString(b[:])
+}
@@ -0,0 +1,109 @@
+package testutil
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"sync"
+	"testing"
+)
+
+// MusicBrainzServer is a local stand-in for the MusicBrainz web service. Point
+// a client at URL() (for example with musicbrainz.WithBaseURL) and register
+// canned responses per path.
+type MusicBrainzServer struct {
+	server *httptest.Server
+
+	mu        sync.Mutex
+	responses map[string][]MockResponse
+	requests  []*http.Request
+}
+
+// MockResponse is one canned reply. Responses registered for the same path are
+// served in order; the last one repeats once the sequence is exhausted.
+type MockResponse struct {
+	Status int
+	Body   []byte
+	Header http.Header
+}
+
+// NewMusicBrainzServer starts a mock server that is closed on test cleanup.
+// Unregistered paths return 404 like MusicBrainz does for unknown MBIDs.
+func NewMusicBrainzServer(t testing.TB) *MusicBrainzServer {
+	t.Helper()
+	s := &MusicBrainzServer{responses: make(map[string][]MockResponse)}
+	s.server = httptest.NewServer(http.HandlerFunc(s.serve))
+	t.Cleanup(s.server.Close)
+	return s
+}
+
+// URL returns the web service root, equivalent to https://musicbrainz.org/ws/2.
+func (s *MusicBrainzServer) URL() string {
+	return s.server.URL + "/ws/2"
+}
+
+// Respond queues responses for path, relative to the web service root
+// (e.g. "/recording" or "/artist/<mbid>"). Query strings are ignored.
+func (s *MusicBrainzServer) Respond(path string, responses ...MockResponse) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.responses["/ws/2"+path] = append(s.responses["/ws/2"+path], responses...)
+}
+
+// RespondJSON registers a single 200 response with a JSON body.
+func (s *MusicBrainzServer) RespondJSON(path string, body []byte) {
+	s.Respond(path, MockResponse{Status: http.StatusOK, Body: body})
+}
+
+// Requests returns the requests received so far.
+func (s *MusicBrainzServer) Requests() []*http.Request {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	return append([]*http.Request(nil), s.requests...)
+}
+
+func (s *MusicBrainzServer) serve(w http.ResponseWriter, r *http.Request) {
+	s.mu.Lock()
+	s.requests = append(s.requests, r)
+	queue := s.responses[r.URL.Path]
+	var resp MockResponse
+	found := len(queue) > 0
+	if found {
+		resp = queue[0]
+		if len(queue) > 1 {
+			s.responses[r.URL.Path] = queue[1:]
+		}
+	}
+	s.mu.Unlock()
+
+	if !found {
+		http.Error(w, `{"error":"Not Found"}`, http.StatusNotFound)
+		return
+	}
+	for key, values := range resp.Header {
+		for _, value := range values {
+			w.Header().Add(key, value)
+		}
+	}
+	if w.Header().Get("Content-Type") == "" {
+		w.Header().Set("Content-Type", "application/json")
+	}
+	status := resp.Status
+	if status == 0 {
+		status = http.StatusOK
+	}
+	w.WriteHeader(status)
+	_, _ = w.Write(resp.Body)
+}
+
+// ReadFixture reads a file under the calling package's testdata directory.
+func ReadFixture(t testing.TB, elem ...string) []byte {
+	t.Helper()
+	path := filepath.Join(append([]string{"testdata"}, elem...)...)
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatalf("read fixture %s: %v", path, err)
+	}
+	return data
+}
@@ -1,6 +1,20 @@
 package testutil
 
-import "os"
+import (
+	"crypto/rand"
+	"database/sql"
+	"encoding/hex"
+	"fmt"
+	"net/url"
+	"os"
+	"strconv"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	_ "github.com/lib/pq"
+)
 
 // PostgresTestDSN returns the shared integration-test database fallback chain.
 func PostgresTestDSN() string {
@@ -12,3 +26,194 @@ func PostgresTestDSN() string {
 	}
 	return os.Getenv("DATABASE_URL")
 }
+
+// ephemeralPrefix names every database created by NewPostgresDatabase. The
+// creation time is embedded so abandoned databases from crashed runs can be
+// swept by later runs.
+const (
+	ephemeralPrefix = "omp_test_"
+	staleAfter      = time.Hour
+)
+
+// MigrateFunc applies the schema to a freshly created template database.
+type MigrateFunc func(*sql.DB) error
+
+var (
+	templateMu   sync.Mutex
+	templateName string
+	templateErr  error
+)
+
+// NewPostgresDatabase returns a connection to a private, migrated database for
+// the calling test and drops it on cleanup. Tests using it do not share rows
+// and may call t.Parallel.
+//
+// The schema is applied once per test process into a template database; each
+// test then clones the template, which is much faster than migrating from
+// scratch. The DSN role needs CREATEDB. Tests are skipped when no DSN is set.
+func NewPostgresDatabase(t testing.TB, migrate MigrateFunc) *sql.DB {
+	t.Helper()
+
+	db, err := sql.Open("postgres", NewPostgresDSN(t, migrate))
+	if err != nil {
+		t.Fatalf("open test database: %v", err)
+	}
+	t.Cleanup(func() { _ = db.Close() })
+	if err := db.Ping(); err != nil {
+		t.Fatalf("ping test database: %v", err)
+	}
+	return db
+}
+
+// NewPostgresDSN clones a private database as NewPostgresDatabase does and
+// returns its DSN, for callers opening it through their own connector.
+// Connections must be closed by cleanups registered after this call, as the
+// database is dropped on cleanup.
+func NewPostgresDSN(t testing.TB, migrate MigrateFunc) string {
+	t.Helper()
+
+	dsn := PostgresTestDSN()
+	if dsn == "" {
+		t.Skip("set OMP_POSTGRES_TEST_DSN, QA_DATABASE_URL, or DATABASE_URL to run Postgres integration tests")
+	}
+
+	admin, err := sql.Open("postgres", dsn)
+	if err != nil {
+		t.Fatalf("open admin connection: %v", err)
+	}
+	defer admin.Close()
+
+	template, err := ensureTemplate(admin, dsn, migrate)
+	if err != nil {
+		t.Fatalf("prepare template database: %v", err)
+	}
+
+	name := ephemeralName()
+	templateMu.Lock()
+	_, err = admin.Exec(fmt.Sprintf("CREATE DATABASE %s TEMPLATE %s", quoteIdent(name), quoteIdent(template)))
+	templateMu.Unlock()
+	if err != nil {
+		t.Fatalf("clone template database: %v", err)
+	}
+	t.Cleanup(func() { dropDatabase(dsn, name) })
+
+	testDSN, err := WithDatabaseName(dsn, name)
+	if err != nil {
+		t.Fatalf("build test DSN: %v", err)
+	}
+	return testDSN
+}
+
+func ensureTemplate(admin *sql.DB, dsn string, migrate MigrateFunc) (string, error) {
+	templateMu.Lock()
+	defer templateMu.Unlock()
+	if templateName != "" || templateErr != nil {
+		return templateName, templateErr
+	}
+
+	sweepStaleDatabases(admin)
+
+	name := ephemeralName()
+	if _, err := admin.Exec("CREATE DATABASE " + quoteIdent(name)); err != nil {
+		templateErr = fmt.Errorf("create template (the test role needs CREATEDB): %w", err)
+		return "", templateErr
+	}
+	templateDSN, err := WithDatabaseName(dsn, name)
+	if err != nil {
+		templateErr = err
+		return "", err
+	}
+	db, err := sql.Open("postgres", templateDSN)
+	if err != nil {
+		templateErr = err
+		return "", err
+	}
+	if migrate != nil {
+		err = migrate(db)
+	}
+	// Cloning requires that nobody is connected to the template.
+	db.Close()
+	if err != nil {
+		templateErr = fmt.Errorf("migrate template: %w", err)
+		dropDatabase(dsn, name)
+		return "", templateErr
+	}
+	templateName = name
+	return templateName, nil
+}
+
+// sweepStaleDatabases drops ephemeral databases left behind by runs that
+// exited before cleanup. Failures are ignored; another run may still own them.
+func sweepStaleDatabases(admin *sql.DB) {
+	rows, err := admin.Query(`SELECT datname FROM pg_database WHERE datname LIKE $1`, ephemeralPrefix+"%")
+	if err != nil {
+		return
+	}
+	var stale []string
+	for rows.Next() {
+		var name string
+		if rows.Scan(&name) == nil && isStale(name, time.Now()) {
+			stale = append(stale, name)
+		}
+	}
+	rows.Close()
+	for _, name := range stale {
+		_, _ = admin.Exec("DROP DATABASE IF EXISTS " + quoteIdent(name))
+	}
+}
+
+func isStale(name string, now time.Time) bool {
+	parts := strings.Split(strings.TrimPrefix(name, ephemeralPrefix), "_")
+	if len(parts) != 2 {
+		return false
+	}
+	created, err := strconv.ParseInt(parts[0], 10, 64)
+	if err != nil {
+		return false
+	}
+	return now.Sub(time.Unix(created, 0)) > staleAfter
+}
+
+func dropDatabase(dsn, name string) {
+	admin, err := sql.Open("postgres", dsn)
+	if err != nil {
+		return
+	}
+	defer admin.Close()
+	_, _ = admin.Exec("DROP DATABASE IF EXISTS " + quoteIdent(name))
+}
+
+func ephemeralName() string {
+	var suffix [6]byte
+	_, _ = rand.Read(suffix[:])
+	return fmt.Sprintf("%s%d_%s", ephemeralPrefix, time.Now().Unix(), hex.EncodeToString(suffix[:]))
+}
+
+func quoteIdent(name string) string {
+	return `"` + strings.ReplaceAll(name, `"`, `""`) + `"`
+}
+
+// WithDatabaseName rewrites a URL or key=value PostgreSQL DSN to target name.
+func WithDatabaseName(dsn, name string) (string, error) {
+	if strings.HasPrefix(dsn, "postgres://") || strings.HasPrefix(dsn, "postgresql://") {
+		u, err := url.Parse(dsn)
+		if err != nil {
+			return "", err
+		}
+		u.Path = "/" + name
+		return u.String(), nil
+	}
+
+	fields := strings.Fields(dsn)
+	replaced := false
+	for i, field := range fields {
+		if strings.HasPrefix(field, "dbname=") {
+			fields[i] = "dbname=" + name
+			replaced = true
+		}
+	}
+	if !replaced {
+		fields = append(fields, "dbname="+name)
+	}
+	return strings.Join(fields, " "), nil
+}
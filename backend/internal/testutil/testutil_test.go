@@ -0,0 +1,70 @@
+package testutil
+
+import (
+	"io"
+	"net/http"
+	"testing"
+	"time"
+)
+
+func TestWithDatabaseNameRewritesURLAndKeyValueDSNs(t *testing.T) {
+	tests := []struct {
+		dsn, want string
+	}{
+		{"postgres://omp:pw@localhost:5434/openmusicplayer?sslmode=disable", "postgres://omp:pw@localhost:5434/omp_test_x?sslmode=disable"},
+		{"host=localhost dbname=openmusicplayer sslmode=disable", "host=localhost dbname=omp_test_x sslmode=disable"},
+		{"host=localhost sslmode=disable", "host=localhost sslmode=disable dbname=omp_test_x"},
+	}
+	for _, tt := range tests {
+		got, err := WithDatabaseName(tt.dsn, "omp_test_x")
+		if err != nil || got != tt.want {
+			t.Errorf("WithDatabaseName(%q) = %q, %v; want %q", tt.dsn, got, err, tt.want)
+		}
+	}
+}
+
+func TestIsStaleOnlyMatchesOldEphemeralNames(t *testing.T) {
+	now := time.Unix(1_700_010_000, 0)
+	if !isStale("omp_test_1700000000_abcdef", now) {
+		t.Error("database older than an hour should be stale")
+	}
+	if isStale("omp_test_1700009000_abcdef", now) {
+		t.Error("recent database must not be swept")
+	}
+	if isStale("omp_test_custom", now) {
+		t.Error("names without a timestamp must never be swept")
+	}
+}
+
+func TestMusicBrainzServerServesQueuedResponsesInOrder(t *testing.T) {
+	mb := NewMusicBrainzServer(t)
+	mb.Respond("/recording",
+		MockResponse{Status: http.StatusServiceUnavailable},
+		MockResponse{Status: http.StatusOK, Body: []byte(`{"count":0}`)},
+	)
+
+	get := func(path string) (int, string) {
+		resp, err := http.Get(mb.URL() + path)
+		if err != nil {
+			t.Fatalf("GET %s: %v", path, err)
+		}
+		defer resp.Body.Close()
+		body, _ := io.ReadAll(resp.Body)
+		return resp.StatusCode, string(body)
+	}
+
+	if status, _ := get("/recording?query=x"); status != http.StatusServiceUnavailable {
+		t.Fatalf("first status = %d, want 503", status)
+	}
+	for i := 0; i < 2; i++ {
+		if status, body := get("/recording?query=x"); status != http.StatusOK || body != `{"count":0}` {
+			t.Fatalf("repeat %d = %d %q, want last response repeated", i, status, body)
+		}
+	}
+	if status, _ := get("/artist/unknown"); status != http.StatusNotFound {
+		t.Fatalf("unregistered path status = %d, want 404", status)
+	}
+	if got := len(mb.Requests()); got != 4 {
+		t.Fatalf("recorded %d requests, want 4", got)
+	}
+}
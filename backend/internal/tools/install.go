@@ -0,0 +1,204 @@
+package tools
+
+import (
+	"bufio"
+	"compress/gzip"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"runtime"
+	"strings"
+)
+
+// maxDownloadBytes bounds a single binary download.
+const maxDownloadBytes = 256 << 20
+
+// ErrUnsupportedPlatform is returned when no prebuilt binary exists for the
+// running OS and architecture.
+var ErrUnsupportedPlatform = errors.New("no prebuilt binary for this platform")
+
+// Install downloads name at version into the managed directory. The build is
+// staged next to its final path and only replaces an existing build once it
+// has been verified and runs, so a failed install leaves the old one in place.
+// yt-dlp builds are checked against the release's SHA2-256SUMS file.
+func (m *Manager) Install(ctx context.Context, name, version string) error {
+	if m.cfg.Dir == "" {
+		return errors.New("no tools directory configured")
+	}
+	url, gzipped, err := m.assetURL(name, version)
+	if err != nil {
+		return err
+	}
+
+	m.installMu.Lock()
+	defer m.installMu.Unlock()
+	if err := os.MkdirAll(m.cfg.Dir, 0o755); err != nil {
+		return fmt.Errorf("create tools directory: %w", err)
+	}
+	staged, err := os.CreateTemp(m.cfg.Dir, "."+name+"-*"+filepath.Ext(executableName(name)))
+	if err != nil {
+		return err
+	}
+	stagedPath := staged.Name()
+	defer os.Remove(stagedPath)
+
+	sum, err := m.download(ctx, url, gzipped, staged)
+	if closeErr := staged.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		return err
+	}
+	if name == YTDLP {
+		asset, _ := ytdlpAsset()
+		want, err := m.ytdlpChecksum(ctx, version, asset)
+		if err != nil {
+			return err
+		}
+		if sum != want {
+			return fmt.Errorf("checksum mismatch for %s %s: got %s, want %s", asset, version, sum, want)
+		}
+	}
+	if err := os.Chmod(stagedPath, 0o755); err != nil {
+		return err
+	}
+	if _, err := runVersion(ctx, stagedPath, name); err != nil {
+		return fmt.Errorf("downloaded build does not run: %w", err)
+	}
+	return os.Rename(stagedPath, m.managedPath(name))
+}
+
+// download writes the (decompressed) body of url to w and returns its SHA-256.
+func (m *Manager) download(ctx context.Context, url string, gzipped bool, w io.Writer) (string, error) {
+	resp, err := m.get(ctx, url)
+	if err != nil {
+		return "", err
+	}
+	defer resp.Body.Close()
+	var body io.Reader = resp.Body
+	if gzipped {
+		gz, err := gzip.NewReader(resp.Body)
+		if err != nil {
+			return "", fmt.Errorf("decompress %s: %w", url, err)
+		}
+		defer gz.Close()
+		body = gz
+	}
+	hash := sha256.New()
+	n, err := io.Copy(io.MultiWriter(w, hash), io.LimitReader(body, maxDownloadBytes+1))
+	if err != nil {
+		return "", fmt.Errorf("download %s: %w", url, err)
+	}
+	if n > maxDownloadBytes {
+		return "", fmt.Errorf("download %s: larger than %d bytes", url, maxDownloadBytes)
+	}
+	return hex.EncodeToString(hash.Sum(nil)), nil
+}
+
+func (m *Manager) ytdlpChecksum(ctx context.Context, version, asset string) (string, error) {
+	url := fmt.Sprintf("%s/download/%s/SHA2-256SUMS", m.ytdlpReleases, version)
+	resp, err := m.get(ctx, url)
+	if err != nil {
+		return "", err
+	}
+	defer resp.Body.Close()
+	scanner := bufio.NewScanner(io.LimitReader(resp.Body, 1<<20))
+	for scanner.Scan() {
+		fields := strings.Fields(scanner.Text())
+		if len(fields) == 2 && fields[1] == asset {
+			return strings.ToLower(fields[0]), nil
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		return "", fmt.Errorf("read %s: %w", url, err)
+	}
+	return "", fmt.Errorf("%s lists no checksum for %s", url, asset)
+}
+
+func (m *Manager) latestYTDLP(ctx context.Context) (string, error) {
+	resp, err := m.get(ctx, m.ytdlpLatestAPI)
+	if err != nil {
+		return "", err
+	}
+	defer resp.Body.Close()
+	var release struct {
+		TagName string `json:"tag_name"`
+	}
+	if err := json.NewDecoder(io.LimitReader(resp.Body, 1<<20)).Decode(&release); err != nil {
+		return "", fmt.Errorf("decode latest yt-dlp release: %w", err)
+	}
+	if release.TagName == "" {
+		return "", errors.New("latest yt-dlp release has no tag")
+	}
+	return release.TagName, nil
+}
+
+func (m *Manager) get(ctx context.Context, url string) (*http.Response, error) {
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, url, nil)
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Set("User-Agent", "OpenMusicPlayer/1.0.0 (https://github.com/openmusicplayer)")
+	resp, err := m.http.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("GET %s: %w", url, err)
+	}
+	if resp.StatusCode != http.StatusOK {
+		resp.Body.Close()
+		return nil, fmt.Errorf("GET %s: %s", url, resp.Status)
+	}
+	return resp, nil
+}
+
+// assetURL returns the download URL for name at version on this platform and
+// whether the asset is gzip-compressed.
+func (m *Manager) assetURL(name, version string) (string, bool, error) {
+	switch name {
+	case YTDLP:
+		asset, err := ytdlpAsset()
+		if err != nil {
+			return "", false, err
+		}
+		return fmt.Sprintf("%s/download/%s/%s", m.ytdlpReleases, version, asset), false, nil
+	case FFmpeg, FFprobe:
+		platform, arch, err := ffmpegPlatform()
+		if err != nil {
+			return "", false, err
+		}
+		return fmt.Sprintf("%s/download/%s/%s-%s-%s.gz", m.ffmpegReleases, version, name, platform, arch), true, nil
+	default:
+		return "", false, fmt.Errorf("unknown tool %q", name)
+	}
+}
+
+func ytdlpAsset() (string, error) {
+	switch runtime.GOOS + "/" + runtime.GOARCH {
+	case "linux/amd64":
+		return "yt-dlp_linux", nil
+	case "linux/arm64":
+		return "yt-dlp_linux_aarch64", nil
+	case "darwin/amd64", "darwin/arm64":
+		return "yt-dlp_macos", nil
+	case "windows/amd64":
+		return "yt-dlp.exe", nil
+	}
+	return "", fmt.Errorf("%w: yt-dlp on %s/%s", ErrUnsupportedPlatform, runtime.GOOS, runtime.GOARCH)
+}
+
+func ffmpegPlatform() (string, string, error) {
+	platforms := map[string]string{"linux": "linux", "darwin": "darwin", "windows": "win32"}
+	arches := map[string]string{"amd64": "x64", "arm64": "arm64"}
+	platform, okPlatform := platforms[runtime.GOOS]
+	arch, okArch := arches[runtime.GOARCH]
+	if !okPlatform || !okArch {
+		return "", "", fmt.Errorf("%w: ffmpeg on %s/%s", ErrUnsupportedPlatform, runtime.GOOS, runtime.GOARCH)
+	}
+	return platform, arch, nil
+}
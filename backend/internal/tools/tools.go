@@ -0,0 +1,335 @@
+// Package tools manages the external binaries downloads depend on: yt-dlp,
+// ffmpeg and ffprobe. A Manager keeps pinned builds in its own directory,
+// puts that directory ahead of PATH so every exec call site picks them up,
+// and periodically checks for a newer yt-dlp release, since a stale yt-dlp is
+// the most common reason downloads start failing.
+package tools
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/http"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/logger"
+)
+
+// Tool names, as looked up on PATH.
+const (
+	YTDLP   = "yt-dlp"
+	FFmpeg  = "ffmpeg"
+	FFprobe = "ffprobe"
+)
+
+// Names lists every managed tool.
+var Names = []string{YTDLP, FFmpeg, FFprobe}
+
+// ErrNotFound is returned when a tool is neither managed nor on PATH.
+var ErrNotFound = errors.New("tool not found")
+
+const (
+	defaultYTDLPReleases  = "https://github.com/yt-dlp/yt-dlp/releases"
+	defaultYTDLPLatestAPI = "https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest"
+	defaultFFmpegReleases = "https://github.com/eugeneware/ffmpeg-static/releases"
+)
+
+// Config configures a Manager.
+type Config struct {
+	// Dir holds the managed builds and is placed ahead of PATH.
+	Dir string
+	// AutoDownload installs missing tools at the pinned versions in Ensure.
+	AutoDownload bool
+	// YTDLPVersion and FFmpegVersion are the release tags installed when a
+	// tool is missing.
+	YTDLPVersion  string
+	FFmpegVersion string
+	// UpdateInterval is how often Run checks for a newer yt-dlp; zero
+	// disables the check.
+	UpdateInterval time.Duration
+	// AutoUpdate replaces the managed yt-dlp when a newer release is found.
+	// A yt-dlp installed elsewhere on PATH is only reported, never replaced.
+	AutoUpdate bool
+	HTTPClient *http.Client
+	Logger     *logger.Logger
+}
+
+// UpdateStatus is the outcome of the latest yt-dlp update check.
+type UpdateStatus struct {
+	Installed       string
+	Latest          string
+	UpdateAvailable bool
+	CheckedAt       time.Time
+	Err             error
+}
+
+// Manager locates, installs and updates the external tools.
+type Manager struct {
+	cfg  Config
+	log  *logger.Logger
+	http *http.Client
+
+	// Release locations; tests point these at a local server.
+	ytdlpReleases  string
+	ytdlpLatestAPI string
+	ffmpegReleases string
+
+	// installMu serializes installs so an update never races an Ensure.
+	installMu sync.Mutex
+
+	mu     sync.Mutex
+	status UpdateStatus
+}
+
+// NewManager returns a Manager for cfg.
+func NewManager(cfg Config) *Manager {
+	m := &Manager{
+		cfg:            cfg,
+		log:            cfg.Logger,
+		http:           cfg.HTTPClient,
+		ytdlpReleases:  defaultYTDLPReleases,
+		ytdlpLatestAPI: defaultYTDLPLatestAPI,
+		ffmpegReleases: defaultFFmpegReleases,
+	}
+	if m.log == nil {
+		m.log = logger.Default()
+	}
+	m.log = m.log.WithComponent("tools")
+	if m.http == nil {
+		m.http = &http.Client{Timeout: 5 * time.Minute}
+	}
+	return m
+}
+
+// Dir returns the managed directory.
+func (m *Manager) Dir() string {
+	return m.cfg.Dir
+}
+
+// PrependToPath creates the managed directory and puts it first on PATH, so
+// exec lookups of "yt-dlp", "ffmpeg" and "ffprobe" prefer managed builds.
+// Because installs replace files in place, later execs see updates without
+// a restart.
+func (m *Manager) PrependToPath() error {
+	if m.cfg.Dir == "" {
+		return nil
+	}
+	if err := os.MkdirAll(m.cfg.Dir, 0o755); err != nil {
+		return fmt.Errorf("create tools directory: %w", err)
+	}
+	path := os.Getenv("PATH")
+	for _, entry := range filepath.SplitList(path) {
+		if entry == m.cfg.Dir {
+			return nil
+		}
+	}
+	if path == "" {
+		return os.Setenv("PATH", m.cfg.Dir)
+	}
+	return os.Setenv("PATH", m.cfg.Dir+string(os.PathListSeparator)+path)
+}
+
+// Locate returns the path of the binary that will run for name.
+func (m *Manager) Locate(name string) (string, error) {
+	if managed := m.managedPath(name); isExecutable(managed) {
+		return managed, nil
+	}
+	path, err := exec.LookPath(name)
+	if err != nil {
+		return "", fmt.Errorf("%w: %s", ErrNotFound, name)
+	}
+	return path, nil
+}
+
+// Managed reports whether name resolves to a build in the managed directory.
+func (m *Manager) Managed(name string) bool {
+	return isExecutable(m.managedPath(name))
+}
+
+// Version runs the tool's version flag and returns the first line of output.
+func (m *Manager) Version(ctx context.Context, name string) (string, error) {
+	path, err := m.Locate(name)
+	if err != nil {
+		return "", err
+	}
+	return runVersion(ctx, path, name)
+}
+
+// Ensure checks every tool and, with AutoDownload, installs the missing ones
+// at the pinned versions. The error names every tool that is still missing.
+func (m *Manager) Ensure(ctx context.Context) error {
+	var errs []error
+	for _, name := range Names {
+		if _, err := m.Locate(name); err == nil {
+			continue
+		}
+		if !m.cfg.AutoDownload {
+			errs = append(errs, fmt.Errorf("%w: %s (set TOOLS_AUTO_DOWNLOAD=true to install it)", ErrNotFound, name))
+			continue
+		}
+		version := m.cfg.FFmpegVersion
+		if name == YTDLP {
+			version = m.cfg.YTDLPVersion
+		}
+		if err := m.Install(ctx, name, version); err != nil {
+			errs = append(errs, fmt.Errorf("install %s %s: %w", name, version, err))
+			continue
+		}
+		m.log.Info(ctx, "Installed managed tool", map[string]interface{}{
+			"tool":    name,
+			"version": version,
+			"dir":     m.cfg.Dir,
+		})
+	}
+	return errors.Join(errs...)
+}
+
+// Status returns the result of the latest update check.
+func (m *Manager) Status() UpdateStatus {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	return m.status
+}
+
+// CheckForUpdate compares the installed yt-dlp with the latest release and,
+// when AutoUpdate is set and the installed build is managed, installs it.
+func (m *Manager) CheckForUpdate(ctx context.Context) UpdateStatus {
+	status := UpdateStatus{CheckedAt: time.Now()}
+	defer func() {
+		m.mu.Lock()
+		m.status = status
+		m.mu.Unlock()
+	}()
+
+	installed, err := m.Version(ctx, YTDLP)
+	if err != nil {
+		status.Err = err
+		return status
+	}
+	status.Installed = installed
+	latest, err := m.latestYTDLP(ctx)
+	if err != nil {
+		status.Err = err
+		return status
+	}
+	status.Latest = latest
+	status.UpdateAvailable = newerVersion(latest, installed)
+	if !status.UpdateAvailable {
+		return status
+	}
+
+	fields := map[string]interface{}{"installed": installed, "latest": latest}
+	if !m.cfg.AutoUpdate || !m.Managed(YTDLP) {
+		m.log.Warn(ctx, "A newer yt-dlp is available; stale builds are the most common cause of download failures", fields)
+		return status
+	}
+	if err := m.Install(ctx, YTDLP, latest); err != nil {
+		status.Err = err
+		m.log.Error(ctx, "Failed to update yt-dlp", fields, err)
+		return status
+	}
+	m.log.Info(ctx, "Updated yt-dlp", fields)
+	status.Installed = latest
+	status.UpdateAvailable = false
+	return status
+}
+
+// Run checks for yt-dlp updates every UpdateInterval until ctx is done.
+func (m *Manager) Run(ctx context.Context) {
+	if m.cfg.UpdateInterval <= 0 {
+		return
+	}
+	m.CheckForUpdate(ctx)
+	ticker := time.NewTicker(m.cfg.UpdateInterval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+			m.CheckForUpdate(ctx)
+		}
+	}
+}
+
+func (m *Manager) managedPath(name string) string {
+	if m.cfg.Dir == "" {
+		return ""
+	}
+	return filepath.Join(m.cfg.Dir, executableName(name))
+}
+
+func runVersion(ctx context.Context, path, name string) (string, error) {
+	flag := "-version"
+	if name == YTDLP {
+		flag = "--version"
+	}
+	output, err := exec.CommandContext(ctx, path, flag).Output()
+	if err != nil {
+		return "", fmt.Errorf("%s %s: %w", path, flag, err)
+	}
+	line, _, _ := strings.Cut(strings.TrimSpace(string(output)), "\n")
+	return strings.TrimSpace(line), nil
+}
+
+func executableName(name string) string {
+	if runtime.GOOS == "windows" {
+		return name + ".exe"
+	}
+	return name
+}
+
+func isExecutable(path string) bool {
+	if path == "" {
+		return false
+	}
+	info, err := os.Stat(path)
+	if err != nil || info.IsDir() {
+		return false
+	}
+	return runtime.GOOS == "windows" || info.Mode()&0o111 != 0
+}
+
+// newerVersion reports whether candidate is a later yt-dlp release than
+// current. Versions are dotted dates with an optional build suffix
+// ("2025.09.26", "2025.09.26.232805"); anything unparseable is never newer.
+func newerVersion(candidate, current string) bool {
+	a, okA := parseVersion(candidate)
+	b, okB := parseVersion(current)
+	if !okA || !okB {
+		return false
+	}
+	for i := range max(len(a), len(b)) {
+		var x, y int
+		if i < len(a) {
+			x = a[i]
+		}
+		if i < len(b) {
+			y = b[i]
+		}
+		if x != y {
+			return x > y
+		}
+	}
+	return false
+}
+
+func parseVersion(version string) ([]int, bool) {
+	parts := strings.Split(strings.TrimSpace(version), ".")
+	numbers := make([]int, 0, len(parts))
+	for _, part := range parts {
+		n, err := strconv.Atoi(part)
+		if err != nil {
+			return nil, false
+		}
+		numbers = append(numbers, n)
+	}
+	return numbers, true
+}
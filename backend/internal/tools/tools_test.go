@@ -0,0 +1,167 @@
+package tools
+
+import (
+	"compress/gzip"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"runtime"
+	"strings"
+	"testing"
+)
+
+// newReleaseServer serves fake yt-dlp releases (with SHA2-256SUMS), a latest
+// release endpoint, and gzipped ffmpeg-static builds. Every build is a shell
+// script that prints its version.
+func newReleaseServer(t *testing.T, latest string, badSums map[string]bool) *Manager {
+	t.Helper()
+	if runtime.GOOS == "windows" {
+		t.Skip("fake builds are shell scripts")
+	}
+	asset, err := ytdlpAsset()
+	if err != nil {
+		t.Skip(err)
+	}
+	platform, arch, err := ffmpegPlatform()
+	if err != nil {
+		t.Skip(err)
+	}
+	script := func(version string) []byte {
+		return []byte("#!/bin/sh\necho " + version + "\n")
+	}
+
+	mux := http.NewServeMux()
+	mux.HandleFunc("GET /ytdlp/download/{version}/{asset}", func(w http.ResponseWriter, r *http.Request) {
+		version := r.PathValue("version")
+		switch r.PathValue("asset") {
+		case asset:
+			w.Write(script(version))
+		case "SHA2-256SUMS":
+			sum := sha256.Sum256(script(version))
+			if badSums[version] {
+				sum = sha256.Sum256([]byte("tampered"))
+			}
+			fmt.Fprintf(w, "%s  %s\n", hex.EncodeToString(sum[:]), asset)
+		default:
+			http.NotFound(w, r)
+		}
+	})
+	mux.HandleFunc("GET /ytdlp/latest", func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprintf(w, `{"tag_name":%q}`, latest)
+	})
+	mux.HandleFunc("GET /ffmpeg/download/{version}/{asset}", func(w http.ResponseWriter, r *http.Request) {
+		name, ok := strings.CutSuffix(r.PathValue("asset"), "-"+platform+"-"+arch+".gz")
+		if !ok {
+			http.NotFound(w, r)
+			return
+		}
+		gz := gzip.NewWriter(w)
+		gz.Write(script(name + " version " + r.PathValue("version")))
+		gz.Close()
+	})
+	server := httptest.NewServer(mux)
+	t.Cleanup(server.Close)
+
+	// Nothing on PATH, so only managed builds resolve.
+	t.Setenv("PATH", t.TempDir())
+	m := NewManager(Config{
+		Dir:           t.TempDir(),
+		AutoDownload:  true,
+		YTDLPVersion:  "2025.01.01",
+		FFmpegVersion: "b6.0",
+	})
+	m.ytdlpReleases = server.URL + "/ytdlp"
+	m.ytdlpLatestAPI = server.URL + "/ytdlp/latest"
+	m.ffmpegReleases = server.URL + "/ffmpeg"
+	return m
+}
+
+func TestEnsureInstallsPinnedBuilds(t *testing.T) {
+	m := newReleaseServer(t, "2025.01.01", nil)
+	ctx := context.Background()
+
+	if err := m.Ensure(ctx); err != nil {
+		t.Fatalf("Ensure() error = %v", err)
+	}
+	for name, want := range map[string]string{YTDLP: "2025.01.01", FFmpeg: "ffmpeg version b6.0", FFprobe: "ffprobe version b6.0"} {
+		if !m.Managed(name) {
+			t.Fatalf("%s is not managed after Ensure", name)
+		}
+		if got, err := m.Version(ctx, name); err != nil || got != want {
+			t.Fatalf("Version(%s) = %q, %v; want %q", name, got, err, want)
+		}
+	}
+
+	m.cfg.AutoDownload = false
+	m.cfg.Dir = t.TempDir()
+	if err := m.Ensure(ctx); !errors.Is(err, ErrNotFound) {
+		t.Fatalf("Ensure() without auto-download = %v, want ErrNotFound", err)
+	}
+}
+
+func TestInstallRejectsChecksumMismatchAndKeepsCurrentBuild(t *testing.T) {
+	m := newReleaseServer(t, "2025.02.01", map[string]bool{"2025.02.01": true})
+	ctx := context.Background()
+	if err := m.Install(ctx, YTDLP, "2025.01.01"); err != nil {
+		t.Fatalf("Install() error = %v", err)
+	}
+
+	if err := m.Install(ctx, YTDLP, "2025.02.01"); err == nil || !strings.Contains(err.Error(), "checksum mismatch") {
+		t.Fatalf("Install() with bad checksum = %v", err)
+	}
+	if got, _ := m.Version(ctx, YTDLP); got != "2025.01.01" {
+		t.Fatalf("version after rejected install = %q, want the previous build", got)
+	}
+}
+
+func TestCheckForUpdateReplacesManagedYTDLPOnlyWhenEnabled(t *testing.T) {
+	m := newReleaseServer(t, "2025.02.01", nil)
+	ctx := context.Background()
+	if err := m.Install(ctx, YTDLP, "2025.01.01"); err != nil {
+		t.Fatalf("Install() error = %v", err)
+	}
+
+	status := m.CheckForUpdate(ctx)
+	if status.Err != nil || !status.UpdateAvailable || status.Installed != "2025.01.01" || status.Latest != "2025.02.01" {
+		t.Fatalf("status without auto-update = %#v", status)
+	}
+	if got, _ := m.Version(ctx, YTDLP); got != "2025.01.01" {
+		t.Fatalf("version = %q, want untouched without auto-update", got)
+	}
+
+	m.cfg.AutoUpdate = true
+	status = m.CheckForUpdate(ctx)
+	if status.Err != nil || status.UpdateAvailable || status.Installed != "2025.02.01" {
+		t.Fatalf("status with auto-update = %#v", status)
+	}
+	if got, _ := m.Version(ctx, YTDLP); got != "2025.02.01" {
+		t.Fatalf("version after auto-update = %q", got)
+	}
+	if m.Status().Installed != "2025.02.01" {
+		t.Fatalf("Status() = %#v", m.Status())
+	}
+}
+
+func TestNewerVersion(t *testing.T) {
+	tests := []struct {
+		candidate, current string
+		want               bool
+	}{
+		{"2025.09.26", "2025.06.30", true},
+		{"2025.06.30", "2025.09.26", false},
+		{"2025.09.26", "2025.09.26", false},
+		{"2025.09.26.232805", "2025.09.26", true},
+		{"2025.10.1", "2025.09.26", true},
+		{"nightly", "2025.09.26", false},
+		{"2025.09.26", "", false},
+	}
+	for _, tt := range tests {
+		if got := newerVersion(tt.candidate, tt.current); got != tt.want {
+			t.Errorf("newerVersion(%q, %q) = %v, want %v", tt.candidate, tt.current, got, tt.want)
+		}
+	}
+}
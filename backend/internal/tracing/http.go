@@ -0,0 +1,46 @@
+package tracing
+
+import (
+	"net/http"
+
+	"go.opentelemetry.io/contrib/instrumentation/net/http/otelhttp"
+	"go.opentelemetry.io/otel/attribute"
+	"go.opentelemetry.io/otel/propagation"
+	"go.opentelemetry.io/otel/trace"
+
+	"github.com/openmusicplayer/backend/internal/logger"
+)
+
+// Middleware starts a server span per request, continuing any incoming
+// traceparent and copying the trace ID into the logger context.
+func Middleware(next http.Handler) http.Handler {
+	withTraceID := http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		ctx := r.Context()
+		span := trace.SpanFromContext(ctx)
+		span.SetAttributes(attribute.String("request.id", logger.GetRequestID(ctx)))
+		if sc := span.SpanContext(); sc.IsValid() {
+			ctx = logger.WithTraceID(ctx, sc.TraceID().String())
+		}
+		next.ServeHTTP(w, r.WithContext(ctx))
+	})
+	return otelhttp.NewHandler(withTraceID, "http.server",
+		otelhttp.WithPropagators(propagation.TraceContext{}),
+		otelhttp.WithSpanNameFormatter(func(_ string, r *http.Request) string {
+			return r.Method + " " + r.URL.Path
+		}),
+	)
+}
+
+// NewTransport wraps base, defaulting to http.DefaultTransport, so outgoing
+// requests get client spans and carry the traceparent header.
+func NewTransport(base http.RoundTripper) http.RoundTripper {
+	if base == nil {
+		base = http.DefaultTransport
+	}
+	return otelhttp.NewTransport(base,
+		otelhttp.WithPropagators(propagation.TraceContext{}),
+		otelhttp.WithSpanNameFormatter(func(_ string, r *http.Request) string {
+			return "HTTP " + r.Method + " " + r.URL.Host
+		}),
+	)
+}
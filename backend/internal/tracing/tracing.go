@@ -0,0 +1,136 @@
+// Package tracing installs the process's OpenTelemetry tracer provider and
+// W3C trace context propagation, and carries trace context across the
+// download queue, where no request headers are left to carry it.
+package tracing
+
+import (
+	"context"
+	"fmt"
+	"strings"
+	"time"
+
+	"go.opentelemetry.io/otel"
+	"go.opentelemetry.io/otel/attribute"
+	"go.opentelemetry.io/otel/codes"
+	"go.opentelemetry.io/otel/exporters/otlp/otlptrace/otlptracehttp"
+	"go.opentelemetry.io/otel/propagation"
+	"go.opentelemetry.io/otel/sdk/resource"
+	sdktrace "go.opentelemetry.io/otel/sdk/trace"
+	"go.opentelemetry.io/otel/trace"
+)
+
+// instrumentationName scopes the spans this module starts itself.
+const instrumentationName = "github.com/openmusicplayer/backend"
+
+// TraceParentHeader is the W3C trace context propagation header.
+const TraceParentHeader = "traceparent"
+
+const (
+	defaultServiceName   = "openmusicplayer-backend"
+	defaultExportTimeout = 10 * time.Second
+	defaultQueueSize     = 4096
+)
+
+// Config configures the process tracer. A disabled config still propagates
+// incoming trace context and gives new requests trace IDs, so log lines and
+// queued jobs keep them; it only records no spans.
+type Config struct {
+	Enabled       bool
+	ServiceName   string
+	Endpoint      string
+	Headers       map[string]string
+	SampleRatio   float64
+	BatchSize     int
+	FlushInterval time.Duration
+	ExportTimeout time.Duration
+}
+
+// Setup installs the global tracer provider and the W3C trace context
+// propagator. When tracing is enabled with an endpoint, spans of root traces
+// are sampled by SampleRatio, children follow their parent, and sampled spans
+// are batched to the OTLP/HTTP collector; spans are dropped, never blocked
+// on, when the queue is full. The returned func flushes buffered spans.
+func Setup(ctx context.Context, cfg Config) (shutdown func(context.Context) error, err error) {
+	otel.SetTextMapPropagator(propagation.TraceContext{})
+	if !cfg.Enabled || strings.TrimSpace(cfg.Endpoint) == "" {
+		provider := sdktrace.NewTracerProvider(sdktrace.WithSampler(sdktrace.NeverSample()))
+		otel.SetTracerProvider(provider)
+		return provider.Shutdown, nil
+	}
+
+	exportTimeout := cfg.ExportTimeout
+	if exportTimeout <= 0 {
+		exportTimeout = defaultExportTimeout
+	}
+	exporter, err := otlptracehttp.New(ctx,
+		otlptracehttp.WithEndpointURL(tracesURL(cfg.Endpoint)),
+		otlptracehttp.WithHeaders(cfg.Headers),
+		otlptracehttp.WithTimeout(exportTimeout),
+	)
+	if err != nil {
+		return nil, fmt.Errorf("create OTLP exporter: %w", err)
+	}
+
+	batching := []sdktrace.BatchSpanProcessorOption{
+		sdktrace.WithMaxQueueSize(defaultQueueSize),
+		sdktrace.WithExportTimeout(exportTimeout),
+	}
+	if cfg.BatchSize > 0 {
+		batching = append(batching, sdktrace.WithMaxExportBatchSize(cfg.BatchSize))
+	}
+	if cfg.FlushInterval > 0 {
+		batching = append(batching, sdktrace.WithBatchTimeout(cfg.FlushInterval))
+	}
+	serviceName := strings.TrimSpace(cfg.ServiceName)
+	if serviceName == "" {
+		serviceName = defaultServiceName
+	}
+	provider := sdktrace.NewTracerProvider(
+		sdktrace.WithBatcher(exporter, batching...),
+		sdktrace.WithSampler(sdktrace.ParentBased(sdktrace.TraceIDRatioBased(cfg.SampleRatio))),
+		sdktrace.WithResource(resource.NewSchemaless(attribute.String("service.name", serviceName))),
+	)
+	otel.SetTracerProvider(provider)
+	return provider.Shutdown, nil
+}
+
+// tracesURL accepts either a collector base URL or the full /v1/traces path.
+func tracesURL(endpoint string) string {
+	endpoint = strings.TrimRight(strings.TrimSpace(endpoint), "/")
+	if strings.HasSuffix(endpoint, "/v1/traces") {
+		return endpoint
+	}
+	return endpoint + "/v1/traces"
+}
+
+// Tracer returns the tracer for spans this module starts itself, from the
+// global provider Setup installs.
+func Tracer() trace.Tracer {
+	return otel.Tracer(instrumentationName)
+}
+
+// End marks span failed with err, when non-nil, and ends it.
+func End(span trace.Span, err error) {
+	if err != nil {
+		span.RecordError(err)
+		span.SetStatus(codes.Error, err.Error())
+	}
+	span.End()
+}
+
+// TraceParentFromContext formats the current span context as a W3C
+// traceparent value, for work queued to run after the request.
+func TraceParentFromContext(ctx context.Context) string {
+	carrier := propagation.MapCarrier{}
+	propagation.TraceContext{}.Inject(ctx, carrier)
+	return carrier.Get(TraceParentHeader)
+}
+
+// ContextWithTraceParent attaches the remote parent value names, leaving ctx
+// untouched when value is empty or malformed.
+func ContextWithTraceParent(ctx context.Context, value string) context.Context {
+	if value == "" {
+		return ctx
+	}
+	return propagation.TraceContext{}.Extract(ctx, propagation.MapCarrier{TraceParentHeader: value})
+}
@@ -0,0 +1,166 @@
+package tracing
+
+import (
+	"context"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"go.opentelemetry.io/otel"
+	"go.opentelemetry.io/otel/codes"
+	sdktrace "go.opentelemetry.io/otel/sdk/trace"
+	"go.opentelemetry.io/otel/sdk/trace/tracetest"
+	"go.opentelemetry.io/otel/trace"
+
+	"github.com/openmusicplayer/backend/internal/logger"
+)
+
+const incomingTraceParent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+
+// recordSpans installs a provider recording every span for the test.
+func recordSpans(t *testing.T) *tracetest.SpanRecorder {
+	t.Helper()
+	recorder := tracetest.NewSpanRecorder()
+	previous := otel.GetTracerProvider()
+	otel.SetTracerProvider(sdktrace.NewTracerProvider(sdktrace.WithSpanProcessor(recorder)))
+	t.Cleanup(func() { otel.SetTracerProvider(previous) })
+	return recorder
+}
+
+func TestTraceParentRoundTripsThroughContext(t *testing.T) {
+	ctx := ContextWithTraceParent(context.Background(), incomingTraceParent)
+	if got := TraceParentFromContext(ctx); got != incomingTraceParent {
+		t.Fatalf("TraceParentFromContext = %q, want %q", got, incomingTraceParent)
+	}
+	for _, value := range []string{
+		"",
+		"garbage",
+		"00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+		"00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+		"ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+	} {
+		if sc := trace.SpanContextFromContext(ContextWithTraceParent(context.Background(), value)); sc.IsValid() {
+			t.Errorf("ContextWithTraceParent(%q) attached %v", value, sc)
+		}
+	}
+}
+
+func TestDisabledSetupStillAssignsTraceIDs(t *testing.T) {
+	previous := otel.GetTracerProvider()
+	defer otel.SetTracerProvider(previous)
+	shutdown, err := Setup(context.Background(), Config{})
+	if err != nil {
+		t.Fatalf("Setup: %v", err)
+	}
+	defer shutdown(context.Background())
+
+	ctx, span := Tracer().Start(context.Background(), "unrecorded")
+	defer span.End()
+	if span.IsRecording() || !trace.SpanContextFromContext(ctx).IsValid() {
+		t.Fatalf("span recording=%v context=%v; want an unrecorded span with IDs", span.IsRecording(), span.SpanContext())
+	}
+	if TraceParentFromContext(ctx) == "" {
+		t.Fatal("queued jobs must still get a traceparent")
+	}
+}
+
+func TestSetupExportsToCollector(t *testing.T) {
+	requests := make(chan *http.Request, 1)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		select {
+		case requests <- r:
+		default:
+		}
+	}))
+	defer server.Close()
+
+	previous := otel.GetTracerProvider()
+	defer otel.SetTracerProvider(previous)
+	shutdown, err := Setup(context.Background(), Config{
+		Enabled:       true,
+		Endpoint:      server.URL,
+		ServiceName:   "omp-test",
+		Headers:       map[string]string{"X-Api-Key": "secret"},
+		SampleRatio:   1,
+		FlushInterval: time.Hour,
+	})
+	if err != nil {
+		t.Fatalf("Setup: %v", err)
+	}
+	_, span := Tracer().Start(context.Background(), "export-me")
+	span.End()
+	if err := shutdown(context.Background()); err != nil {
+		t.Fatalf("shutdown: %v", err)
+	}
+
+	select {
+	case r := <-requests:
+		if r.URL.Path != "/v1/traces" || r.Header.Get("X-Api-Key") != "secret" {
+			t.Fatalf("collector got %s with key %q; want /v1/traces with the configured header", r.URL.Path, r.Header.Get("X-Api-Key"))
+		}
+	default:
+		t.Fatal("shutdown did not flush the span to the collector")
+	}
+}
+
+func TestMiddlewareContinuesIncomingTrace(t *testing.T) {
+	recorder := recordSpans(t)
+
+	var loggedTraceID string
+	handler := Middleware(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		loggedTraceID = logger.GetTraceID(r.Context())
+		w.WriteHeader(http.StatusBadGateway)
+	}))
+	req := httptest.NewRequest(http.MethodGet, "/api/v1/library", nil)
+	req.Header.Set(TraceParentHeader, incomingTraceParent)
+	handler.ServeHTTP(httptest.NewRecorder(), req)
+
+	spans := recorder.Ended()
+	if len(spans) != 1 {
+		t.Fatalf("recorded %d spans, want 1", len(spans))
+	}
+	span := spans[0]
+	if span.Name() != "GET /api/v1/library" || span.SpanKind() != trace.SpanKindServer {
+		t.Fatalf("server span = %q (%v)", span.Name(), span.SpanKind())
+	}
+	if span.SpanContext().TraceID().String() != "4bf92f3577b34da6a3ce929d0e0e4736" {
+		t.Fatalf("server span trace = %s, want incoming trace", span.SpanContext().TraceID())
+	}
+	if span.Parent().SpanID().String() != "00f067aa0ba902b7" {
+		t.Fatalf("server span parent = %s, want incoming span", span.Parent().SpanID())
+	}
+	if span.Status().Code != codes.Error {
+		t.Fatal("5xx responses must mark the server span as failed")
+	}
+	if loggedTraceID != "4bf92f3577b34da6a3ce929d0e0e4736" {
+		t.Fatalf("logger trace ID = %q, want incoming trace", loggedTraceID)
+	}
+}
+
+func TestTransportInjectsTraceParent(t *testing.T) {
+	recordSpans(t)
+
+	var received string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		received = r.Header.Get(TraceParentHeader)
+	}))
+	defer server.Close()
+
+	ctx, span := Tracer().Start(context.Background(), "caller")
+	defer span.End()
+	req, _ := http.NewRequestWithContext(ctx, http.MethodGet, server.URL, nil)
+	resp, err := (&http.Client{Transport: NewTransport(nil)}).Do(req)
+	if err != nil {
+		t.Fatalf("request failed: %v", err)
+	}
+	resp.Body.Close()
+
+	sc := trace.SpanContextFromContext(ContextWithTraceParent(context.Background(), received))
+	if !sc.IsValid() {
+		t.Fatalf("outgoing request missing traceparent, got %q", received)
+	}
+	if sc.TraceID() != span.SpanContext().TraceID() {
+		t.Fatal("outgoing traceparent must carry the caller's trace ID")
+	}
+}
@@ -3,6 +3,8 @@ package validators
 import (
 	"encoding/json"
 	"net/http"
+
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 // Handlers provides HTTP handlers for URL validation
@@ -93,10 +95,5 @@ func (h *Handlers) GetSupportedSources(w http.ResponseWriter, r *http.Request) {
 }
 
 func writeErrorResponse(w http.ResponseWriter, status int, code, message string) {
-	w.Header().Set("Content-Type", "application/json")
-	w.WriteHeader(status)
-	json.NewEncoder(w).Encode(ErrorResponse{
-		Code:    code,
-		Message: message,
-	})
+	apperrors.WriteCodeProblem(w, status, code, message)
 }
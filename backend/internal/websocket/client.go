@@ -26,7 +26,7 @@ const (
 type Client struct {
 	hub    *Hub
 	conn   *websocket.Conn
-	send   chan *ProgressMessage
+	send   chan any
 	userID int64
 }
 
@@ -35,7 +35,7 @@ func NewClient(hub *Hub, conn *websocket.Conn, userID int64) *Client {
 	return &Client{
 		hub:    hub,
 		conn:   conn,
-		send:   make(chan *ProgressMessage, 256),
+		send:   make(chan any, 256),
 		userID: userID,
 	}
 }
@@ -8,6 +8,7 @@ import (
 	"github.com/gorilla/websocket"
 
 	"github.com/openmusicplayer/backend/internal/auth"
+	apperrors "github.com/openmusicplayer/backend/internal/errors"
 )
 
 var upgrader = websocket.Upgrader{
@@ -40,7 +41,7 @@ func (h *Handler) ServeWS(w http.ResponseWriter, r *http.Request) {
 	// Get token from query parameter
 	token := r.URL.Query().Get("token")
 	if token == "" {
-		http.Error(w, `{"code":"UNAUTHORIZED","message":"missing token parameter"}`, http.StatusUnauthorized)
+		auth.WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "missing token parameter")
 		return
 	}
 
@@ -48,16 +49,16 @@ func (h *Handler) ServeWS(w http.ResponseWriter, r *http.Request) {
 	claims, err := h.authService.ValidateAccessToken(token)
 	if err != nil {
 		if err == auth.ErrTokenExpired {
-			http.Error(w, `{"code":"TOKEN_EXPIRED","message":"access token has expired"}`, http.StatusUnauthorized)
+			auth.WriteUnauthorized(w, r, apperrors.CodeTokenExpired, "access token has expired")
 			return
 		}
-		http.Error(w, `{"code":"UNAUTHORIZED","message":"invalid access token"}`, http.StatusUnauthorized)
+		auth.WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "invalid access token")
 		return
 	}
 
 	userID, err := uuid.Parse(claims.UserID)
 	if err != nil {
-		http.Error(w, `{"code":"UNAUTHORIZED","message":"invalid user ID in token"}`, http.StatusUnauthorized)
+		auth.WriteUnauthorized(w, r, apperrors.CodeUnauthorized, "invalid user ID in token")
 		return
 	}
 
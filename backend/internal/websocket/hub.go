@@ -2,6 +2,8 @@ package websocket
 
 import (
 	"sync"
+
+	"github.com/google/uuid"
 )
 
 // Hub maintains the set of active clients and broadcasts messages to them.
@@ -15,8 +17,8 @@ type Hub struct {
 	// Unregister requests from clients
 	unregister chan *Client
 
-	// Broadcast channel for progress updates
-	broadcast chan *ProgressMessage
+	// Broadcast channel for messages to one user's clients
+	broadcast chan outbound
 
 	mu sync.RWMutex
 }
@@ -33,13 +35,19 @@ type ProgressMessage struct {
 	ArtistName string `json:"artist_name,omitempty"`
 }
 
+// outbound is a message for every client of one user.
+type outbound struct {
+	userID  int64
+	message any
+}
+
 // NewHub creates a new Hub instance.
 func NewHub() *Hub {
 	return &Hub{
 		clients:    make(map[int64]map[*Client]bool),
 		register:   make(chan *Client),
 		unregister: make(chan *Client),
-		broadcast:  make(chan *ProgressMessage),
+		broadcast:  make(chan outbound),
 	}
 }
 
@@ -68,12 +76,12 @@ func (h *Hub) Run() {
 			}
 			h.mu.Unlock()
 
-		case message := <-h.broadcast:
+		case out := <-h.broadcast:
 			h.mu.RLock()
-			if clients, ok := h.clients[message.UserID]; ok {
+			if clients, ok := h.clients[out.userID]; ok {
 				for client := range clients {
 					select {
-					case client.send <- message:
+					case client.send <- out.message:
 					default:
 						// Client's buffer is full, close the connection
 						close(client.send)
@@ -88,7 +96,17 @@ func (h *Hub) Run() {
 
 // BroadcastProgress sends a progress update to all clients of a specific user.
 func (h *Hub) BroadcastProgress(msg *ProgressMessage) {
-	h.broadcast <- msg
+	h.broadcast <- outbound{userID: msg.UserID, message: msg}
+}
+
+// SendToUser sends any JSON-encodable message to all clients of a user.
+func (h *Hub) SendToUser(userID uuid.UUID, message any) {
+	h.broadcast <- outbound{userID: uuidToInt64(userID), message: message}
+}
+
+// Connected reports whether a user has any open connection.
+func (h *Hub) Connected(userID uuid.UUID) bool {
+	return h.ClientCount(uuidToInt64(userID)) > 0
 }
 
 // ClientCount returns the number of connected clients for a user.
@@ -0,0 +1,128 @@
+package webui
+
+import (
+	"bytes"
+	"crypto/sha256"
+	"embed"
+	"encoding/hex"
+	"errors"
+	"html"
+	"io/fs"
+	"net/http"
+	"path"
+	"regexp"
+	"strings"
+	"time"
+
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+// dist holds the web client build copied in by `scripts/build webui`. A
+// plain checkout embeds only its placeholder.
+//
+//go:embed all:dist
+var dist embed.FS
+
+// ErrNotBuilt is returned by New when the binary was built without the web
+// client.
+var ErrNotBuilt = errors.New("built without the web client; run scripts/build webui")
+
+// hashedName matches file names carrying a content hash, such as
+// main.3f2a9b1c.js, which can be cached for good.
+var hashedName = regexp.MustCompile(`[.-][0-9a-f]{8,}\.[a-z0-9]+$`)
+
+// reservedPrefixes never fall back to the client, so unknown API routes
+// keep answering 404.
+var reservedPrefixes = []string{"/api/", "/internal/"}
+
+// defaultBaseHref is the <base> tag `flutter build web` writes without
+// --base-href.
+var defaultBaseHref = []byte(`<base href="/">`)
+
+type file struct {
+	content []byte
+	etag    string
+}
+
+// Handler serves the embedded single-page web client. Paths that name no
+// file get index.html, so the client's own routes survive a reload.
+type Handler struct {
+	files map[string]file
+	index []byte
+}
+
+// New serves the web client embedded in the binary.
+func New() (*Handler, error) {
+	files, err := fs.Sub(dist, "dist")
+	if err != nil {
+		return nil, err
+	}
+	return newHandler(files)
+}
+
+func newHandler(files fs.FS) (*Handler, error) {
+	h := &Handler{files: map[string]file{}}
+	err := fs.WalkDir(files, ".", func(name string, entry fs.DirEntry, err error) error {
+		if err != nil || entry.IsDir() || strings.HasPrefix(entry.Name(), ".") {
+			return err
+		}
+		content, err := fs.ReadFile(files, name)
+		if err != nil {
+			return err
+		}
+		sum := sha256.Sum256(content)
+		h.files[name] = file{content: content, etag: `"` + hex.EncodeToString(sum[:8]) + `"`}
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	index, ok := h.files["index.html"]
+	if !ok {
+		return nil, ErrNotBuilt
+	}
+	h.index = index.content
+	return h, nil
+}
+
+func (h *Handler) ServeHTTP(w http.ResponseWriter, r *http.Request) {
+	for _, prefix := range reservedPrefixes {
+		if strings.HasPrefix(r.URL.Path, prefix) {
+			http.NotFound(w, r)
+			return
+		}
+	}
+	if r.Method != http.MethodGet && r.Method != http.MethodHead {
+		w.Header().Set("Allow", "GET, HEAD")
+		http.Error(w, http.StatusText(http.StatusMethodNotAllowed), http.StatusMethodNotAllowed)
+		return
+	}
+
+	name := strings.TrimPrefix(path.Clean(r.URL.Path), "/")
+	f, ok := h.files[name]
+	if !ok || name == "index.html" {
+		// Missing assets are reported rather than answered with the page.
+		if ok || path.Ext(name) == "" {
+			h.serveIndex(w, r)
+		} else {
+			http.NotFound(w, r)
+		}
+		return
+	}
+	if hashedName.MatchString(name) {
+		w.Header().Set("Cache-Control", "public, max-age=31536000, immutable")
+	} else {
+		w.Header().Set("Cache-Control", "no-cache")
+	}
+	w.Header().Set("ETag", f.etag)
+	http.ServeContent(w, r, name, time.Time{}, bytes.NewReader(f.content))
+}
+
+// serveIndex serves index.html with its <base> pointing at the base path the
+// request came through, and never from cache, so a deploy takes effect on
+// the next load.
+func (h *Handler) serveIndex(w http.ResponseWriter, r *http.Request) {
+	baseHref := []byte(`<base href="` + html.EscapeString(middleware.Path(r, "/")) + `">`)
+	w.Header().Set("Cache-Control", "no-cache")
+	http.ServeContent(w, r, "index.html", time.Time{}, bytes.NewReader(bytes.Replace(h.index, defaultBaseHref, baseHref, 1)))
+}
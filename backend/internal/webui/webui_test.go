@@ -0,0 +1,70 @@
+package webui
+
+import (
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"testing/fstest"
+
+	"github.com/openmusicplayer/backend/internal/middleware"
+)
+
+func TestHandlerServesAssetsAndFallsBackToIndex(t *testing.T) {
+	h, err := newHandler(fstest.MapFS{
+		"index.html":         {Data: []byte(`<html><head><base href="/"></head></html>`)},
+		"main.dart.js":       {Data: []byte("main()")},
+		"app.3f2a9b1c.css":   {Data: []byte("body{}")},
+		"assets/logo.png":    {Data: []byte("png")},
+		".last_build_id":     {Data: []byte("1")},
+		"icons/Icon-192.png": {Data: []byte("icon")},
+	})
+	if err != nil {
+		t.Fatal(err)
+	}
+	proxy, err := middleware.NewProxy(nil, "/music")
+	if err != nil {
+		t.Fatal(err)
+	}
+	get := func(method, target string, headers ...string) *httptest.ResponseRecorder {
+		req := httptest.NewRequest(method, target, nil)
+		for i := 0; i+1 < len(headers); i += 2 {
+			req.Header.Set(headers[i], headers[i+1])
+		}
+		rec := httptest.NewRecorder()
+		proxy.Middleware(h).ServeHTTP(rec, req)
+		return rec
+	}
+
+	rec := get(http.MethodGet, "/music/library/albums/42")
+	if rec.Code != http.StatusOK || !strings.Contains(rec.Body.String(), `<base href="/music/">`) || rec.Header().Get("Cache-Control") != "no-cache" {
+		t.Fatalf("client route = %d %q, Cache-Control %q", rec.Code, rec.Body.String(), rec.Header().Get("Cache-Control"))
+	}
+	rec = get(http.MethodGet, "/main.dart.js")
+	etag := rec.Header().Get("ETag")
+	if rec.Code != http.StatusOK || rec.Body.String() != "main()" || rec.Header().Get("Cache-Control") != "no-cache" || etag == "" {
+		t.Fatalf("unhashed asset = %d, headers %v", rec.Code, rec.Header())
+	}
+	if rec := get(http.MethodGet, "/main.dart.js", "If-None-Match", etag); rec.Code != http.StatusNotModified {
+		t.Fatalf("revalidated asset = %d, want 304", rec.Code)
+	}
+	if rec := get(http.MethodGet, "/app.3f2a9b1c.css"); !strings.Contains(rec.Header().Get("Cache-Control"), "immutable") {
+		t.Fatalf("hashed asset Cache-Control = %q", rec.Header().Get("Cache-Control"))
+	}
+
+	for _, target := range []string{"/assets/missing.png", "/api/v1/nothing-here", "/.last_build_id"} {
+		if rec := get(http.MethodGet, target); rec.Code != http.StatusNotFound {
+			t.Errorf("GET %s = %d, want 404", target, rec.Code)
+		}
+	}
+	if rec := get(http.MethodPost, "/library"); rec.Code != http.StatusMethodNotAllowed {
+		t.Errorf("POST /library = %d, want 405", rec.Code)
+	}
+}
+
+func TestNewRequiresABuiltClient(t *testing.T) {
+	if _, err := newHandler(fstest.MapFS{".gitkeep": {}}); !errors.Is(err, ErrNotBuilt) {
+		t.Fatalf("newHandler without index.html = %v, want ErrNotBuilt", err)
+	}
+}
@@ -0,0 +1,103 @@
+// Package yearcard draws year-in-review cards: a square SVG summing up a
+// listener's year, with their minutes listened, top artists and top track,
+// sized for sharing on social networks.
+package yearcard
+
+import (
+	"bytes"
+	"encoding/xml"
+	"fmt"
+	"strconv"
+	"unicode/utf8"
+)
+
+const (
+	// Size is the width and height of a card in pixels.
+	Size = 1080
+	// MaxArtists is how many top artists a card lists.
+	MaxArtists = 5
+
+	// maxLineRunes keeps a line of text inside the card at its font size.
+	maxLineRunes = 38
+
+	fontFamily = "Inter, Helvetica, Arial, sans-serif"
+)
+
+// Card is what a year-in-review card shows.
+type Card struct {
+	// Username titles the card; empty leaves it untitled.
+	Username        string
+	Year            int
+	MinutesListened int
+	// TopArtists are the year's most played artists, most played first.
+	TopArtists []string
+	// TopTrack and TopTrackArtist name the year's most played track, if any.
+	TopTrack       string
+	TopTrackArtist string
+}
+
+// SVG renders the card. Every name is escaped, so the card is safe to serve
+// whatever the listener's library holds.
+func SVG(card Card) []byte {
+	var b bytes.Buffer
+	fmt.Fprintf(&b, `<svg xmlns="http://www.w3.org/2000/svg" width="%d" height="%d" viewBox="0 0 %d %d" font-family="%s">`, Size, Size, Size, Size, fontFamily)
+	b.WriteString(`<defs><linearGradient id="bg" x1="0" y1="0" x2="1" y2="1">` +
+		`<stop offset="0" stop-color="#1e1b4b"/><stop offset="1" stop-color="#be185d"/>` +
+		`</linearGradient></defs>`)
+	fmt.Fprintf(&b, `<rect width="%d" height="%d" fill="url(#bg)"/>`, Size, Size)
+
+	title := strconv.Itoa(card.Year) + " in music"
+	if card.Username != "" {
+		title = card.Username + "'s " + title
+	}
+	text(&b, 80, 140, 44, "#f9a8d4", "600", title)
+	text(&b, 80, 300, 140, "#ffffff", "800", thousands(card.MinutesListened))
+	text(&b, 80, 360, 40, "#e5e7eb", "400", "minutes listened")
+
+	if len(card.TopArtists) > 0 {
+		text(&b, 80, 480, 36, "#f9a8d4", "600", "Top artists")
+		for i, artist := range card.TopArtists[:min(len(card.TopArtists), MaxArtists)] {
+			text(&b, 80, 550+i*64, 44, "#ffffff", "600", strconv.Itoa(i+1)+". "+artist)
+		}
+	}
+	if card.TopTrack != "" {
+		text(&b, 80, 900, 36, "#f9a8d4", "600", "Top track")
+		track := card.TopTrack
+		if card.TopTrackArtist != "" {
+			track += " — " + card.TopTrackArtist
+		}
+		text(&b, 80, 960, 44, "#ffffff", "600", track)
+	}
+	text(&b, 80, 1030, 28, "#d1d5db", "400", "Open Music Player")
+	b.WriteString(`</svg>`)
+	return b.Bytes()
+}
+
+// text draws one line of escaped, truncated text with its baseline at x, y.
+func text(b *bytes.Buffer, x, y, size int, fill, weight, s string) {
+	fmt.Fprintf(b, `<text x="%d" y="%d" font-size="%d" font-weight="%s" fill="%s">`, x, y, size, weight, fill)
+	_ = xml.EscapeText(b, []byte(truncate(s, maxLineRunes)))
+	b.WriteString(`</text>`)
+}
+
+// truncate shortens s to at most n runes, marking the cut with an ellipsis.
+func truncate(s string, n int) string {
+	if utf8.RuneCountInString(s) <= n {
+		return s
+	}
+	runes := []rune(s)
+	return string(runes[:n-1]) + "…"
+}
+
+// thousands formats n with commas between groups of three digits.
+func thousands(n int) string {
+	s := strconv.Itoa(n)
+	start := 0
+	if n < 0 {
+		start = 1
+	}
+	for i := len(s) - 3; i > start; i -= 3 {
+		s = s[:i] + "," + s[i:]
+	}
+	return s
+}
@@ -0,0 +1,47 @@
+package yearcard
+
+import (
+	"encoding/xml"
+	"strings"
+	"testing"
+)
+
+func TestSVGEscapesAndTruncatesNames(t *testing.T) {
+	svg := string(SVG(Card{
+		Username:        "ana",
+		Year:            2025,
+		MinutesListened: 1234567,
+		TopArtists:      []string{"<script>alert(1)</script>", "Simon & Garfunkel", "C", "D", "E", "F"},
+		TopTrack:        strings.Repeat("Long ", 20),
+		TopTrackArtist:  "Band",
+	}))
+
+	if err := xml.Unmarshal([]byte(svg), new(struct{})); err != nil {
+		t.Fatalf("SVG is not well-formed XML: %v", err)
+	}
+	for _, want := range []string{"ana&#39;s 2025 in music", "1,234,567", "&lt;script&gt;", "Simon &amp; Garfunkel", "5. E", "…"} {
+		if !strings.Contains(svg, want) {
+			t.Errorf("SVG lacks %q", want)
+		}
+	}
+	for _, unwanted := range []string{"<script>", "6. F", "— Band"} {
+		if strings.Contains(svg, unwanted) {
+			t.Errorf("SVG contains %q", unwanted)
+		}
+	}
+}
+
+func TestSVGOmitsEmptySections(t *testing.T) {
+	svg := string(SVG(Card{Year: 2025}))
+	if !strings.Contains(svg, ">2025 in music<") || strings.Contains(svg, "Top artists") || strings.Contains(svg, "Top track") {
+		t.Fatalf("SVG of an empty year = %s", svg)
+	}
+}
+
+func TestThousands(t *testing.T) {
+	for n, want := range map[int]string{0: "0", 999: "999", 1000: "1,000", 123456: "123,456", -1000: "-1,000"} {
+		if got := thousands(n); got != want {
+			t.Errorf("thousands(%d) = %q, want %q", n, got, want)
+		}
+	}
+}
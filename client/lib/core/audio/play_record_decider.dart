@@ -1,3 +1,13 @@
+/// A track the listener moved past before it was recorded as a play.
+class PlaySkip {
+  const PlaySkip(this.trackId, this.position);
+
+  final String trackId;
+
+  /// How far into the track the skip happened.
+  final Duration position;
+}
+
 /// Pure, side-effect-free brain for the play-event recorder.
 ///
 /// It answers a single question: for the track currently playing, should we
@@ -9,6 +19,11 @@
 /// The class is deliberately free of streams, timers, and network calls so it
 /// can be unit-tested by feeding it position/duration/track-change/completion
 /// events and asserting exactly one "record" signal.
+///
+/// A track that changes to another before it was recorded is reported as a
+/// [PlaySkip] instead. A track that played into its last tenth counts as
+/// finished, not skipped, because gapless queue transitions never report
+/// completion.
 class PlayRecordDecider {
   PlayRecordDecider({this.threshold = const Duration(seconds: 30)});
 
@@ -17,6 +32,10 @@ class PlayRecordDecider {
 
   String? _trackId;
   bool _recorded = false;
+  bool _finishReported = false;
+  String? _finished;
+  Duration _position = Duration.zero;
+  Duration _duration = Duration.zero;
 
   /// The track the decider is currently armed on, or null when playback is
   /// stopped. Exposed for wiring/telemetry; not required for the decision.
@@ -28,16 +47,34 @@ class PlayRecordDecider {
   /// Feed the id of the now-playing track (null when playback stops). A change
   /// re-arms the decider so the next track can record its own single play; the
   /// same id repeated (pause/resume/loop) is a no-op that preserves dedup.
-  void onTrackChanged(String? trackId) {
-    if (trackId == _trackId) return;
+  /// Returns the previous track as a skip when playback moved on to another
+  /// track before it was recorded; stopping playback is not a skip.
+  PlaySkip? onTrackChanged(String? trackId) {
+    if (trackId == _trackId) return null;
+    final previous = _trackId;
+    PlaySkip? skip;
+    if (previous != null && trackId != null && !_recorded && !_nearEnd) {
+      skip = PlaySkip(previous, _position);
+    }
+    if (previous != null && _nearEnd && !_finishReported) {
+      _finished = previous;
+    }
     _trackId = trackId;
     _recorded = false;
+    _finishReported = false;
+    _position = Duration.zero;
+    _duration = Duration.zero;
+    return skip;
   }
 
   /// Feed a position tick against the (possibly unknown) track [duration].
   /// Returns the track id to record when the [threshold] milestone is crossed
   /// for the first time this play, otherwise null.
   String? onPosition(Duration position, Duration duration) {
+    if (_trackId != null) {
+      _position = position;
+      _duration = duration;
+    }
     return _maybeRecord(position >= threshold);
   }
 
@@ -45,16 +82,35 @@ class PlayRecordDecider {
   /// track finished before it was recorded (e.g. a sub-threshold track that
   /// still played to the end), otherwise null.
   String? onCompleted() {
+    if (_trackId != null && !_finishReported) {
+      _finishReported = true;
+      _finished = _trackId;
+    }
     return _maybeRecord(true);
   }
 
+  /// Returns, once per play, the track that played to its end: it completed,
+  /// or playback moved on from its last tenth. Otherwise null.
+  String? takeFinished() {
+    final finished = _finished;
+    _finished = null;
+    return finished;
+  }
+
   /// Drop any pending/armed state. Call on logout or account switch so a play
   /// from one session can never be attributed to the next.
   void reset() {
     _trackId = null;
     _recorded = false;
+    _finishReported = false;
+    _finished = null;
+    _position = Duration.zero;
+    _duration = Duration.zero;
   }
 
+  bool get _nearEnd =>
+      _duration > Duration.zero && _position >= _duration * 0.9;
+
   String? _maybeRecord(bool crossed) {
     if (_recorded || _trackId == null || !crossed) return null;
     _recorded = true;
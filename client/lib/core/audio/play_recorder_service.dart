@@ -19,6 +19,19 @@ abstract class PlayEventSink {
     String? contextType,
     String? contextId,
   });
+
+  /// Records that the listener moved past [trackId] [position] into it,
+  /// before it counted as a play.
+  Future<void> recordSkip({
+    required int trackId,
+    required Duration position,
+    String? contextType,
+    String? contextId,
+  });
+
+  /// Reports that [trackId] played to its end, which takes it off the
+  /// listen later queue.
+  Future<void> recordFinished({required int trackId});
 }
 
 /// [PlayEventSink] that POSTs to `/me/plays` via the app [ApiClient].
@@ -42,14 +55,43 @@ class ApiPlayEventSink implements PlayEventSink {
     }
     await _api.post<dynamic>('/me/plays', data: body);
   }
+
+  @override
+  Future<void> recordSkip({
+    required int trackId,
+    required Duration position,
+    String? contextType,
+    String? contextId,
+  }) async {
+    final body = <String, dynamic>{
+      'trackId': trackId,
+      'positionMs': position.inMilliseconds,
+    };
+    if (contextType != null && contextType.isNotEmpty) {
+      body['contextType'] = contextType;
+    }
+    if (contextId != null && contextId.isNotEmpty) {
+      body['contextId'] = contextId;
+    }
+    await _api.post<dynamic>('/me/plays/skips', data: body);
+  }
+
+  @override
+  Future<void> recordFinished({required int trackId}) async {
+    await _api.post<dynamic>(
+      '/me/listen-later/finished',
+      data: <String, dynamic>{'trackId': trackId},
+    );
+  }
 }
 
 /// Wires a [PlayRecordDecider] to [PlaybackState.snapshotStream] and records
 /// exactly one play per continuous listen once it crosses the threshold or the
-/// track completes. Reading the atomic snapshot keeps completion events tied to
-/// the item that completed, even if playback immediately advances to the next
-/// item. Posting is retried on failure, and [reset] clears pending state on
-/// logout / account switch.
+/// track completes, or a skip when playback moves on before that, and reports
+/// tracks that played to their end. Reading the atomic snapshot keeps
+/// completion events tied to the item that completed, even if playback
+/// immediately advances to the next item. Posting is retried on failure, and
+/// [reset] clears pending state on logout / account switch.
 class PlayRecorderService {
   PlayRecorderService(
     this._playback,
@@ -92,7 +134,7 @@ class PlayRecorderService {
   }
 
   void _handleSnapshot(PlaybackSnapshot snapshot) {
-    _decider.onTrackChanged(snapshot.currentMediaItem?.id);
+    _emitSkip(_decider.onTrackChanged(snapshot.currentMediaItem?.id));
     _emit(_decider.onPosition(
       snapshot.localPosition,
       snapshot.localDuration,
@@ -100,6 +142,7 @@ class PlayRecorderService {
     if (snapshot.processingState == ProcessingState.completed) {
       _emit(_decider.onCompleted());
     }
+    _emitFinished(_decider.takeFinished());
   }
 
   void _emit(String? trackId) {
@@ -116,23 +159,65 @@ class PlayRecorderService {
     ));
   }
 
+  void _emitSkip(PlaySkip? skip) {
+    if (skip == null) return;
+    final id = int.tryParse(skip.trackId);
+    if (id == null || id <= 0) return;
+
+    final context = _playback.playbackContext;
+    unawaited(_retry(
+      'skip',
+      id,
+      () => _sink.recordSkip(
+        trackId: id,
+        position: skip.position,
+        contextType: context?.kind.name,
+        contextId: context?.id,
+      ),
+    ));
+  }
+
+  void _emitFinished(String? trackId) {
+    if (trackId == null) return;
+    final id = int.tryParse(trackId);
+    if (id == null || id <= 0) return;
+
+    unawaited(_retry(
+      'finish',
+      id,
+      () => _sink.recordFinished(trackId: id),
+    ));
+  }
+
   Future<void> _postWithRetry({
     required int trackId,
     String? contextType,
     String? contextId,
-  }) async {
+  }) {
+    return _retry(
+      'play',
+      trackId,
+      () => _sink.recordPlay(
+        trackId: trackId,
+        contextType: contextType,
+        contextId: contextId,
+      ),
+    );
+  }
+
+  Future<void> _retry(
+    String kind,
+    int trackId,
+    Future<void> Function() post,
+  ) async {
     for (var attempt = 0; attempt <= _maxRetries; attempt++) {
       try {
-        await _sink.recordPlay(
-          trackId: trackId,
-          contextType: contextType,
-          contextId: contextId,
-        );
+        await post();
         return;
       } catch (error) {
         if (attempt == _maxRetries) {
           if (kDebugMode) {
-            debugPrint('Failed to record play for track $trackId: $error');
+            debugPrint('Failed to record $kind for track $trackId: $error');
           }
           return;
         }
@@ -135,5 +135,62 @@ void main() {
               const Duration(seconds: 10), const Duration(minutes: 3)),
           '7');
     });
+
+  });
+
+  group('PlayRecordDecider skips', () {
+    test('moving on before the threshold reports a skip at the position', () {
+      final decider = PlayRecordDecider()..onTrackChanged('7');
+      decider.onPosition(
+          const Duration(seconds: 12), const Duration(minutes: 3));
+
+      final skip = decider.onTrackChanged('8');
+      expect(skip?.trackId, '7');
+      expect(skip?.position, const Duration(seconds: 12));
+    });
+
+    test('recorded, stopped and nearly finished tracks are not skips', () {
+      final decider = PlayRecordDecider()..onTrackChanged('7');
+      decider.onPosition(
+          const Duration(seconds: 31), const Duration(minutes: 3));
+      expect(decider.onTrackChanged('8'), isNull);
+
+      decider.onPosition(
+          const Duration(seconds: 5), const Duration(minutes: 3));
+      expect(decider.onTrackChanged(null), isNull);
+
+      decider.onTrackChanged('short');
+      decider.onPosition(
+          const Duration(seconds: 19), const Duration(seconds: 20));
+      expect(decider.onTrackChanged('9'), isNull);
+    });
+  });
+
+  group('PlayRecordDecider finished tracks', () {
+    test('reports a completed track once', () {
+      final decider = PlayRecordDecider()..onTrackChanged('7');
+      decider.onPosition(
+          const Duration(seconds: 31), const Duration(minutes: 3));
+      expect(decider.takeFinished(), isNull);
+
+      decider.onCompleted();
+      decider.onCompleted();
+      expect(decider.takeFinished(), '7');
+      decider.onTrackChanged('8');
+      expect(decider.takeFinished(), isNull);
+    });
+
+    test('moving on from the last tenth finishes, earlier does not', () {
+      final decider = PlayRecordDecider()..onTrackChanged('7');
+      decider.onPosition(
+          const Duration(seconds: 170), const Duration(minutes: 3));
+      decider.onTrackChanged('8');
+      expect(decider.takeFinished(), '7');
+
+      decider.onPosition(
+          const Duration(seconds: 60), const Duration(minutes: 3));
+      decider.onTrackChanged('9');
+      expect(decider.takeFinished(), isNull);
+    });
   });
 }
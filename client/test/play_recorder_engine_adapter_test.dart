@@ -155,6 +155,17 @@ class _Sink implements PlayEventSink {
   }) async {
     events.add(_Event(trackId, contextType, contextId));
   }
+
+  @override
+  Future<void> recordSkip({
+    required int trackId,
+    required Duration position,
+    String? contextType,
+    String? contextId,
+  }) async {}
+
+  @override
+  Future<void> recordFinished({required int trackId}) async {}
 }
 
 class _Event {
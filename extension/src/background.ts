@@ -59,13 +59,13 @@ function parseApiError(response: Response, body?: unknown): { code: string; mess
 
   if (body && typeof body === 'object') {
     const errorBody = body as ApiErrorResponse;
-    if (errorBody.error) {
+    if ('error' in errorBody && typeof errorBody.error === 'object') {
       return {
         code: errorBody.error.code || 'UNKNOWN_ERROR',
         message: errorBody.error.message || defaultMessage,
       };
     }
-    // Legacy format
+    // Problem details and flat {code, message} bodies
     const legacyBody = body as { code?: string; message?: string; error?: string };
     return {
       code: legacyBody.code || 'UNKNOWN_ERROR',
@@ -40,16 +40,26 @@ export const ErrorCodes = {
 
 export type ErrorCode = typeof ErrorCodes[keyof typeof ErrorCodes];
 
-/** API error response format from backend */
-export interface ApiErrorResponse {
-  error: {
-    code: string;
-    message: string;
-    request_id?: string;
-    details?: Record<string, unknown>;
-  };
+/** Error fields shared by both backend error formats */
+export interface ApiErrorBody {
+  code: string;
+  message: string;
+  request_id?: string;
+  details?: Record<string, unknown>;
 }
 
+/** RFC 9457 problem details (application/problem+json) from backend */
+export interface ApiProblemResponse extends ApiErrorBody {
+  type: string;
+  title: string;
+  status: number;
+  detail?: string;
+  instance?: string;
+}
+
+/** API error response format from backend: problem details, or the legacy nested envelope */
+export type ApiErrorResponse = ApiProblemResponse | { error: ApiErrorBody };
+
 /** Application error class */
 export class AppError extends Error {
   code: ErrorCode | string;
@@ -112,13 +122,14 @@ export class AppError extends Error {
 
   /** Create from API response */
   static fromApiResponse(response: Response, body?: ApiErrorResponse): AppError {
-    if (body?.error) {
+    const error = body && ('error' in body ? body.error : body);
+    if (error?.code) {
       return new AppError({
-        code: body.error.code as ErrorCode,
-        message: body.error.message,
+        code: error.code as ErrorCode,
+        message: error.message,
         statusCode: response.status,
-        requestId: body.error.request_id,
-        details: body.error.details,
+        requestId: error.request_id,
+        details: error.details,
       });
     }
 